@@ -0,0 +1,28 @@
+# Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+# SPDX-License-Identifier: Apache-2.0
+
+# Sends a message to the echo server in `examples/echo` and prints the response
+
+import asyncio
+import pathlib
+
+import s2n_quic_py
+
+CERT_PEM = pathlib.Path(__file__).parent.joinpath("../../quic/s2n-quic-core/certs/cert.pem").read_text()
+
+
+async def main():
+    client = s2n_quic_py.Client(CERT_PEM)
+    connection = await client.connect("127.0.0.1:4433", "localhost")
+    print("connected to", connection.remote_addr())
+
+    stream = await connection.open_bidirectional_stream()
+    await stream.send(b"hello from python")
+
+    response = await stream.receive()
+    print("received", response)
+
+    connection.close()
+
+
+asyncio.run(main())
@@ -0,0 +1,262 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Python bindings for an `s2n_quic::Client`, which integrate with `asyncio`
+//!
+//! The endpoint runs on the Tokio runtime managed by `pyo3-asyncio`. Each `async` method returns
+//! an awaitable, which completes on the Python event loop once the Tokio task finishes.
+
+use bytes::Bytes;
+use core::task::Poll;
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    prelude::*,
+    types::PyBytes,
+};
+use s2n_quic::{
+    client::Connect,
+    connection,
+    provider::datagram::default::{Endpoint as Datagrams, Receiver, Sender},
+    stream::{ReceiveStream, SendStream},
+};
+use std::{fmt::Display, net::SocketAddr, sync::Arc};
+use tokio::sync::Mutex;
+
+/// Converts any of the s2n-quic errors into a Python `RuntimeError`
+fn to_err<E: Display>(error: E) -> PyErr {
+    PyRuntimeError::new_err(error.to_string())
+}
+
+fn to_bytes(py: Python, data: &[u8]) -> PyObject {
+    PyBytes::new(py, data).into_py(py)
+}
+
+/// A QUIC client endpoint
+#[pyclass]
+struct Client {
+    client: s2n_quic::Client,
+}
+
+#[pymethods]
+impl Client {
+    /// Creates a client bound to `address`, which trusts the PEM encoded certificate `ca_pem`
+    ///
+    /// Datagrams are only sent if `datagram_capacity` is greater than `0`.
+    #[new]
+    #[pyo3(signature = (ca_pem, address = "0.0.0.0:0", datagram_capacity = 0))]
+    fn new(ca_pem: &str, address: &str, datagram_capacity: usize) -> PyResult<Self> {
+        let mut datagrams = Datagrams::builder();
+        if datagram_capacity > 0 {
+            datagrams = datagrams
+                .with_send_capacity(datagram_capacity)
+                .map_err(to_err)?
+                .with_recv_capacity(datagram_capacity)
+                .map_err(to_err)?;
+        }
+        let datagrams = datagrams.build().map_err(to_err)?;
+
+        // the IO provider spawns its task on the runtime of the current context
+        let _guard = pyo3_asyncio::tokio::get_runtime().enter();
+
+        let client = s2n_quic::Client::builder()
+            .with_tls(ca_pem)
+            .map_err(to_err)?
+            .with_io(address)
+            .map_err(to_err)?
+            .with_datagram(datagrams)
+            .map_err(to_err)?
+            .start()
+            .map_err(to_err)?;
+
+        Ok(Self { client })
+    }
+
+    /// Connects to the server at `address`, which is authenticated with `server_name`
+    fn connect<'py>(
+        &self,
+        py: Python<'py>,
+        address: &str,
+        server_name: &str,
+    ) -> PyResult<&'py PyAny> {
+        let address: SocketAddr = address.parse().map_err(to_err)?;
+        let connect = Connect::new(address).with_server_name(server_name);
+        let attempt = self.client.connect(connect);
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let connection = attempt.await.map_err(to_err)?;
+            Ok(Connection::new(connection))
+        })
+    }
+
+    /// Returns the local address of the endpoint
+    fn local_addr(&self) -> PyResult<String> {
+        Ok(self.client.local_addr().map_err(to_err)?.to_string())
+    }
+}
+
+/// A connection to a server
+#[pyclass]
+struct Connection {
+    handle: connection::Handle,
+    acceptor: Arc<Mutex<connection::StreamAcceptor>>,
+}
+
+impl Connection {
+    fn new(connection: s2n_quic::Connection) -> Self {
+        let (handle, acceptor) = connection.split();
+        Self {
+            handle,
+            acceptor: Arc::new(Mutex::new(acceptor)),
+        }
+    }
+}
+
+#[pymethods]
+impl Connection {
+    /// The endpoint-unique identifier of the connection
+    #[getter]
+    fn id(&self) -> u64 {
+        self.handle.id()
+    }
+
+    /// Returns the address of the server
+    fn remote_addr(&self) -> PyResult<String> {
+        Ok(self.handle.remote_addr().map_err(to_err)?.to_string())
+    }
+
+    /// Opens a bidirectional stream
+    fn open_bidirectional_stream<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
+        let mut handle = self.handle.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let stream = handle.open_bidirectional_stream().await.map_err(to_err)?;
+            Ok(Stream::new(stream))
+        })
+    }
+
+    /// Accepts a bidirectional stream opened by the server
+    ///
+    /// Completes with `None` once the connection is closed.
+    fn accept_bidirectional_stream<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
+        let acceptor = self.acceptor.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let stream = acceptor
+                .lock()
+                .await
+                .accept_bidirectional_stream()
+                .await
+                .map_err(to_err)?;
+            Ok(stream.map(Stream::new))
+        })
+    }
+
+    /// Queues an unreliable datagram to be sent to the server
+    ///
+    /// Raises an exception if the server doesn't support datagrams or the send queue is full.
+    fn send_datagram(&self, data: &[u8]) -> PyResult<()> {
+        let data = Bytes::copy_from_slice(data);
+
+        self.handle
+            .clone()
+            .datagram_mut(|sender: &mut Sender| sender.send_datagram(data))
+            .map_err(to_err)?
+            .map_err(to_err)
+    }
+
+    /// Receives an unreliable datagram from the server
+    fn receive_datagram<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
+        let mut handle = self.handle.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let datagram = futures::future::poll_fn(|cx| {
+                match handle.datagram_mut(|receiver: &mut Receiver| receiver.poll_recv_datagram(cx))
+                {
+                    Ok(Poll::Ready(datagram)) => Poll::Ready(datagram.map_err(to_err)),
+                    Ok(Poll::Pending) => Poll::Pending,
+                    Err(error) => Poll::Ready(Err(to_err(error))),
+                }
+            })
+            .await?;
+
+            Ok(Python::with_gil(|py| to_bytes(py, &datagram)))
+        })
+    }
+
+    /// Closes the connection with an application error code
+    #[pyo3(signature = (error_code = 0))]
+    fn close(&self, error_code: u64) -> PyResult<()> {
+        let error = s2n_quic::application::Error::new(error_code)
+            .map_err(|_| PyValueError::new_err("the error code exceeds the maximum QUIC varint"))?;
+        self.handle.close(error);
+        Ok(())
+    }
+}
+
+/// A bidirectional stream
+#[pyclass]
+struct Stream {
+    id: u64,
+    receive: Arc<Mutex<ReceiveStream>>,
+    send: Arc<Mutex<SendStream>>,
+}
+
+impl Stream {
+    fn new(stream: s2n_quic::stream::BidirectionalStream) -> Self {
+        let id = stream.id();
+        let (receive, send) = stream.split();
+        Self {
+            id,
+            receive: Arc::new(Mutex::new(receive)),
+            send: Arc::new(Mutex::new(send)),
+        }
+    }
+}
+
+#[pymethods]
+impl Stream {
+    /// The identifier of the stream
+    #[getter]
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Sends `data` on the stream, once the peer has capacity to receive it
+    fn send<'py>(&self, py: Python<'py>, data: &[u8]) -> PyResult<&'py PyAny> {
+        let data = Bytes::copy_from_slice(data);
+        let send = self.send.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            send.lock().await.send(data).await.map_err(to_err)
+        })
+    }
+
+    /// Finishes the stream and waits for the peer to acknowledge all of the data
+    fn finish<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
+        let send = self.send.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            send.lock().await.close().await.map_err(to_err)
+        })
+    }
+
+    /// Receives the next chunk of data
+    ///
+    /// Completes with `None` once the peer has finished the stream.
+    fn receive<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
+        let receive = self.receive.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let chunk = receive.lock().await.receive().await.map_err(to_err)?;
+            Ok(chunk.map(|chunk| Python::with_gil(|py| to_bytes(py, &chunk))))
+        })
+    }
+}
+
+#[pymodule]
+fn s2n_quic_py(_py: Python, module: &PyModule) -> PyResult<()> {
+    module.add_class::<Client>()?;
+    module.add_class::<Connection>()?;
+    module.add_class::<Stream>()?;
+    Ok(())
+}
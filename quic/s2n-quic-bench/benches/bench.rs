@@ -3,5 +3,9 @@
 
 use criterion::{criterion_group, criterion_main};
 
-criterion_group!(benches, s2n_quic_bench::benchmarks);
+criterion_group! {
+    name = benches;
+    config = s2n_quic_bench::config();
+    targets = s2n_quic_bench::benchmarks
+}
 criterion_main!(benches);
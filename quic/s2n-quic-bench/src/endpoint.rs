@@ -0,0 +1,268 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! End-to-end benchmarks of a client and a server
+//!
+//! The benchmarks run in two modes:
+//!
+//! * `loopback` - the endpoints exchange datagrams over UDP sockets bound to localhost, so the
+//!   results include the cost of the system calls.
+//! * `simulated` - the endpoints exchange datagrams over the network of the testing IO provider.
+//!   The results are deterministic and only include the cost of the library, along with the setup
+//!   of a new client and server in each iteration.
+//!
+//! Each benchmark runs with every TLS provider which is available on the platform.
+
+use bytes::Bytes;
+use core::{future::Future, pin::Pin};
+use criterion::{BenchmarkId, Criterion, Throughput};
+use s2n_quic::{
+    client::Connect,
+    connection::{self, Connection},
+    provider::{
+        io::testing::{self, primary, Model},
+        tls,
+    },
+    stream::BidirectionalStream,
+    Client, Server,
+};
+use s2n_quic_core::crypto::tls::testing::certificates::{CERT_PEM, KEY_PEM};
+use std::net::SocketAddr;
+
+/// The first byte of a stream which requests the server to echo the data
+const ECHO: u8 = 0;
+/// The first byte of a stream which requests the server to discard the data
+const DISCARD: u8 = 1;
+
+const REQUEST_LEN: usize = 100;
+static REQUEST: [u8; REQUEST_LEN] = [ECHO; REQUEST_LEN];
+
+const CHUNK_LEN: usize = 1 << 16;
+const CHUNKS: usize = 16;
+static CHUNK: [u8; CHUNK_LEN] = [DISCARD; CHUNK_LEN];
+
+pub fn benchmarks(c: &mut Criterion) {
+    loopback(c);
+    simulated(c);
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Tls {
+    #[cfg(unix)]
+    S2nTls,
+    Rustls,
+}
+
+impl Tls {
+    #[cfg(unix)]
+    const ALL: &'static [Self] = &[Self::S2nTls, Self::Rustls];
+    #[cfg(not(unix))]
+    const ALL: &'static [Self] = &[Self::Rustls];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            #[cfg(unix)]
+            Self::S2nTls => "s2n-tls",
+            Self::Rustls => "rustls",
+        }
+    }
+}
+
+macro_rules! server {
+    ($io:expr, $tls:expr) => {{
+        let builder = Server::builder().with_io($io).unwrap();
+        match $tls {
+            #[cfg(unix)]
+            Tls::S2nTls => {
+                let tls = tls::s2n_tls::Server::builder()
+                    .with_certificate(CERT_PEM, KEY_PEM)
+                    .unwrap()
+                    .build()
+                    .unwrap();
+                builder.with_tls(tls).unwrap().start().unwrap()
+            }
+            Tls::Rustls => {
+                let tls = tls::rustls::Server::builder()
+                    .with_certificate(CERT_PEM, KEY_PEM)
+                    .unwrap()
+                    .build()
+                    .unwrap();
+                builder.with_tls(tls).unwrap().start().unwrap()
+            }
+        }
+    }};
+}
+
+macro_rules! client {
+    ($io:expr, $tls:expr) => {{
+        let builder = Client::builder().with_io($io).unwrap();
+        match $tls {
+            #[cfg(unix)]
+            Tls::S2nTls => {
+                let tls = tls::s2n_tls::Client::builder()
+                    .with_certificate(CERT_PEM)
+                    .unwrap()
+                    .build()
+                    .unwrap();
+                builder.with_tls(tls).unwrap().start().unwrap()
+            }
+            Tls::Rustls => {
+                let tls = tls::rustls::Client::builder()
+                    .with_certificate(CERT_PEM)
+                    .unwrap()
+                    .build()
+                    .unwrap();
+                builder.with_tls(tls).unwrap().start().unwrap()
+            }
+        }
+    }};
+}
+
+fn loopback(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("endpoint/loopback");
+
+    for tls in Tls::ALL.iter().copied() {
+        let (client, addr, connection) = runtime.block_on(async {
+            let server = server!("127.0.0.1:0", tls);
+            let addr = server.local_addr().unwrap();
+            tokio::spawn(serve(server, |task| {
+                tokio::spawn(task);
+            }));
+
+            let client = client!("127.0.0.1:0", tls);
+            let connection = connect(&client, addr).await;
+            (client, addr, connection)
+        });
+
+        group.throughput(Throughput::Elements(1));
+        group.bench_function(BenchmarkId::new("handshake", tls.as_str()), |b| {
+            let client = &client;
+            b.to_async(&runtime).iter(|| async move {
+                connect(client, addr).await;
+            });
+        });
+
+        group.throughput(Throughput::Elements(1));
+        group.bench_function(BenchmarkId::new("request_response", tls.as_str()), |b| {
+            b.to_async(&runtime)
+                .iter(|| request_response(connection.handle()));
+        });
+
+        group.throughput(Throughput::Bytes((CHUNK_LEN * CHUNKS) as _));
+        group.bench_function(BenchmarkId::new("transfer", tls.as_str()), |b| {
+            b.to_async(&runtime).iter(|| transfer(connection.handle()));
+        });
+    }
+
+    group.finish();
+}
+
+fn simulated(c: &mut Criterion) {
+    let mut group = c.benchmark_group("endpoint/simulated");
+    // each iteration simulates a new client and server
+    group.sample_size(10);
+
+    for tls in Tls::ALL.iter().copied() {
+        group.throughput(Throughput::Elements(1));
+        group.bench_function(BenchmarkId::new("handshake", tls.as_str()), |b| {
+            b.iter(|| simulate(tls, |_connection| async {}));
+        });
+
+        group.throughput(Throughput::Elements(1));
+        group.bench_function(BenchmarkId::new("request_response", tls.as_str()), |b| {
+            b.iter(|| simulate(tls, request_response));
+        });
+
+        group.throughput(Throughput::Bytes((CHUNK_LEN * CHUNKS) as _));
+        group.bench_function(BenchmarkId::new("transfer", tls.as_str()), |b| {
+            b.iter(|| simulate(tls, transfer));
+        });
+    }
+
+    group.finish();
+}
+
+/// Connects a client to a server on the simulated network and runs the scenario on the
+/// connection
+fn simulate<F, Fut>(tls: Tls, scenario: F)
+where
+    F: 'static + Send + FnOnce(connection::Handle) -> Fut,
+    Fut: 'static + Send + Future<Output = ()>,
+{
+    testing::test(Model::default(), |handle| {
+        let server = server!(handle.builder().build().unwrap(), tls);
+        let addr = server.local_addr()?;
+        testing::spawn(serve(server, |task| {
+            testing::spawn(task);
+        }));
+
+        let client = client!(handle.builder().build().unwrap(), tls);
+        primary::spawn(async move {
+            let connection = connect(&client, addr).await;
+            scenario(connection.handle()).await;
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+type Task = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Accepts connections and serves their streams
+///
+/// Streams which start with [`ECHO`] are echoed back to the client. Streams which start with
+/// [`DISCARD`] are read to the end. The server finishes each stream after the client finished
+/// it.
+async fn serve(mut server: Server, spawn: fn(Task)) {
+    while let Some(connection) = server.accept().await {
+        spawn(Box::pin(serve_connection(connection, spawn)));
+    }
+}
+
+async fn serve_connection(mut connection: Connection, spawn: fn(Task)) {
+    while let Ok(Some(stream)) = connection.accept_bidirectional_stream().await {
+        spawn(Box::pin(serve_stream(stream)));
+    }
+}
+
+async fn serve_stream(mut stream: BidirectionalStream) {
+    let mut echo = None;
+    while let Ok(Some(chunk)) = stream.receive().await {
+        let echo = *echo.get_or_insert(chunk.first() == Some(&ECHO));
+        if echo && stream.send(chunk).await.is_err() {
+            return;
+        }
+    }
+    let _ = stream.finish();
+}
+
+async fn connect(client: &Client, addr: SocketAddr) -> Connection {
+    let connect = Connect::new(addr).with_server_name("localhost");
+    client.connect(connect).await.unwrap()
+}
+
+/// Sends a request on a new stream and waits for the response
+async fn request_response(mut connection: connection::Handle) {
+    let mut stream = connection.open_bidirectional_stream().await.unwrap();
+    stream.send(Bytes::from_static(&REQUEST)).await.unwrap();
+    stream.finish().unwrap();
+
+    let mut len = 0;
+    while let Some(chunk) = stream.receive().await.unwrap() {
+        len += chunk.len();
+    }
+    assert_eq!(len, REQUEST_LEN);
+}
+
+/// Sends data on a new stream and waits until the server read all of it
+async fn transfer(mut connection: connection::Handle) {
+    let mut stream = connection.open_bidirectional_stream().await.unwrap();
+    for _ in 0..CHUNKS {
+        stream.send(Bytes::from_static(&CHUNK)).await.unwrap();
+    }
+    stream.finish().unwrap();
+
+    assert!(stream.receive().await.unwrap().is_none());
+}
@@ -4,12 +4,30 @@
 use criterion::Criterion;
 
 mod crypto;
+mod endpoint;
 mod frame;
 mod packet;
 mod varint;
 
+/// Returns the configuration of the benchmarks
+///
+/// On unix platforms, a flamegraph of each benchmark is written to its report directory when the
+/// benchmarks are run with `--profile-time <seconds>`.
+pub fn config() -> Criterion {
+    let config = Criterion::default();
+
+    #[cfg(unix)]
+    let config = config.with_profiler(pprof::criterion::PProfProfiler::new(
+        100,
+        pprof::criterion::Output::Flamegraph(None),
+    ));
+
+    config
+}
+
 pub fn benchmarks(c: &mut Criterion) {
     crypto::benchmarks(c);
+    endpoint::benchmarks(c);
     frame::benchmarks(c);
     packet::benchmarks(c);
     varint::benchmarks(c);
@@ -3,10 +3,16 @@
 
 use criterion::{black_box, BenchmarkId, Criterion, Throughput};
 use s2n_codec::DecoderBufferMut;
-use s2n_quic_core::{connection::id::ConnectionInfo, inet::SocketAddress, packet::ProtectedPacket};
+use s2n_quic_core::{
+    connection::id::ConnectionInfo,
+    inet::SocketAddress,
+    packet::{number::PacketNumberSpace, ProtectedPacket},
+    varint::VarInt,
+};
 
 pub fn benchmarks(c: &mut Criterion) {
     codec(c);
+    packet_number(c);
 }
 
 struct Input {
@@ -62,3 +68,35 @@ fn codec(c: &mut Criterion) {
 
     group.finish();
 }
+
+fn packet_number(c: &mut Criterion) {
+    let mut group = c.benchmark_group("packet_number");
+
+    let space = PacketNumberSpace::ApplicationData;
+
+    // packet numbers which are truncated to each of the packet number lengths
+    let inputs: Vec<_> = (0..256u32)
+        .map(|i| {
+            let largest = space.new_packet_number(VarInt::from_u32(1_000_000 + i * 97));
+            let packet_number = space
+                .new_packet_number(VarInt::from_u32(1_000_000 + i * 97 + (1 << ((i % 4) * 7))));
+            let truncated = packet_number.truncate(largest).unwrap();
+            (largest, truncated)
+        })
+        .collect();
+
+    group.throughput(Throughput::Elements(inputs.len() as _));
+    group.bench_with_input(
+        BenchmarkId::new("expand", inputs.len()),
+        &inputs,
+        |b, inputs| {
+            b.iter(|| {
+                for (largest, truncated) in inputs {
+                    black_box(truncated.expand(*largest));
+                }
+            });
+        },
+    );
+
+    group.finish();
+}
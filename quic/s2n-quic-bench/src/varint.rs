@@ -1,12 +1,13 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use criterion::{black_box, BenchmarkId, Criterion};
-use s2n_codec::{DecoderBuffer, EncoderBuffer, EncoderValue};
+use criterion::{black_box, BenchmarkId, Criterion, Throughput};
+use s2n_codec::{DecoderBuffer, Encoder, EncoderBuffer, EncoderValue};
 use s2n_quic_core::varint::VarInt;
 
 pub fn benchmarks(c: &mut Criterion) {
     round_trip(c);
+    decode_batch(c);
 }
 
 fn round_trip(c: &mut Criterion) {
@@ -25,3 +26,45 @@ fn round_trip(c: &mut Criterion) {
     }
     group.finish();
 }
+
+fn decode_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("varint");
+
+    // a pseudo-random mix of all of the encoding sizes
+    let values: Vec<_> = (0..256u64)
+        .map(|i| {
+            let r = i
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            let r = r ^ (r >> 29);
+            VarInt::new((r >> 2) >> ((r % 4) * 16 + 1)).unwrap()
+        })
+        .collect();
+    let len = values.iter().map(|value| value.encoding_size()).sum();
+    let mut buffer = vec![0; len];
+    let mut encoder = EncoderBuffer::new(&mut buffer);
+    for value in &values {
+        encoder.encode(value);
+    }
+
+    group.throughput(Throughput::Elements(values.len() as _));
+
+    group.bench_with_input(BenchmarkId::new("decode", "scalar"), &buffer, |b, input| {
+        b.iter(|| {
+            let mut buffer = DecoderBuffer::new(input);
+            while let Ok((value, remaining)) = buffer.decode::<VarInt>() {
+                black_box(value);
+                buffer = remaining;
+            }
+        });
+    });
+
+    group.bench_with_input(BenchmarkId::new("decode", "batch"), &buffer, |b, input| {
+        let mut output = vec![VarInt::default(); values.len()];
+        b.iter(|| {
+            let _ = black_box(VarInt::decode_batch(DecoderBuffer::new(input), &mut output));
+        });
+    });
+
+    group.finish();
+}
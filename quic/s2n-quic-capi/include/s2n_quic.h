@@ -0,0 +1,152 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+#pragma once
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Unless stated otherwise, functions returning `int` return `S2N_QUIC_SUCCESS` on success and
+ * `S2N_QUIC_FAILURE` on failure.
+ *
+ * All of the functions must be called from the thread that created the endpoint.
+ */
+#define S2N_QUIC_SUCCESS 0
+#define S2N_QUIC_FAILURE -1
+
+struct s2n_quic_endpoint;
+struct s2n_quic_connection;
+struct s2n_quic_stream;
+
+/*
+ * Functions invoked by an endpoint from within `s2n_quic_endpoint_poll`
+ *
+ * Any of the functions may be `NULL`. `context` is passed to each function as the first argument.
+ */
+struct s2n_quic_callbacks {
+    void *context;
+
+    /*
+     * Called when a server accepts a connection or a client connection is established.
+     *
+     * The application owns the connection and must release it with `s2n_quic_connection_free`.
+     * `connection` is `NULL` if a connection attempt made with `s2n_quic_client_connect` failed.
+     */
+    void (*on_connection)(void *context, struct s2n_quic_connection *connection);
+
+    /*
+     * Called when the peer opens a bidirectional stream or a stream requested with
+     * `s2n_quic_connection_open_stream` is opened.
+     *
+     * The application owns the stream and must release it with `s2n_quic_stream_free`.
+     * `stream` is `NULL` if a stream could not be opened.
+     */
+    void (*on_stream)(void *context, struct s2n_quic_stream *stream);
+
+    /*
+     * Called when data is received on a stream.
+     *
+     * `data` is only valid for the duration of the call.
+     */
+    void (*on_stream_data)(void *context, struct s2n_quic_stream *stream, const uint8_t *data, size_t len);
+
+    /*
+     * Called when the peer has finished sending on a stream.
+     *
+     * `status` is `S2N_QUIC_SUCCESS` if all of the data was received and `S2N_QUIC_FAILURE` if the
+     * stream or its connection was closed with an error.
+     */
+    void (*on_stream_end)(void *context, struct s2n_quic_stream *stream, int status);
+};
+
+/*
+ * Creates a server bound to `address`, for example "0.0.0.0:443"
+ *
+ * `cert_pem` and `key_pem` contain the PEM encoded certificate chain and private key.
+ * Returns `NULL` on failure.
+ */
+struct s2n_quic_endpoint *s2n_quic_server_new(const char *address, const char *cert_pem, const char *key_pem,
+                                              const struct s2n_quic_callbacks *callbacks);
+
+/*
+ * Creates a client bound to `address`, for example "0.0.0.0:0"
+ *
+ * `ca_pem` contains the PEM encoded certificate used to authenticate servers.
+ * Returns `NULL` on failure.
+ */
+struct s2n_quic_endpoint *s2n_quic_client_new(const char *address, const char *ca_pem,
+                                              const struct s2n_quic_callbacks *callbacks);
+
+/*
+ * Makes progress on all of the connections owned by the endpoint and invokes the callbacks
+ *
+ * Blocks for up to `timeout_ms` milliseconds. A timeout of `0` makes progress without blocking, so
+ * the endpoint can be driven from an existing event loop. Must not be called from a callback.
+ */
+int s2n_quic_endpoint_poll(struct s2n_quic_endpoint *endpoint, uint64_t timeout_ms);
+
+/* Writes the port the endpoint is bound to into `port` */
+int s2n_quic_endpoint_local_port(const struct s2n_quic_endpoint *endpoint, uint16_t *port);
+
+/*
+ * Releases the endpoint and closes all of its connections
+ *
+ * All of the connections and streams of the endpoint must be released first.
+ */
+void s2n_quic_endpoint_free(struct s2n_quic_endpoint *endpoint);
+
+/*
+ * Starts connecting to the server at `address`, for example "127.0.0.1:443"
+ *
+ * `server_name` is used for SNI and to authenticate the server. The result is reported with the
+ * `on_connection` callback. Fails if the endpoint is a server.
+ */
+int s2n_quic_client_connect(struct s2n_quic_endpoint *endpoint, const char *address, const char *server_name);
+
+/* Returns the endpoint-unique identifier of the connection */
+uint64_t s2n_quic_connection_id(const struct s2n_quic_connection *connection);
+
+/* Starts opening a bidirectional stream, which is reported with the `on_stream` callback */
+int s2n_quic_connection_open_stream(struct s2n_quic_connection *connection);
+
+/* Closes the connection with an application error code */
+int s2n_quic_connection_close(struct s2n_quic_connection *connection, uint64_t error_code);
+
+/*
+ * Releases the connection
+ *
+ * No more streams are reported for the connection. The connection is closed once all of its
+ * streams have been released.
+ */
+void s2n_quic_connection_free(struct s2n_quic_connection *connection);
+
+/* Returns the identifier of the stream */
+uint64_t s2n_quic_stream_id(const struct s2n_quic_stream *stream);
+
+/*
+ * Queues `len` bytes of `data` to be sent on the stream
+ *
+ * The data is copied, so the buffer can be reused once the call returns. If `fin` is `true`, the
+ * stream is finished after the data is sent. Fails if the stream is finished or was reset.
+ */
+int s2n_quic_stream_write(struct s2n_quic_stream *stream, const uint8_t *data, size_t len, bool fin);
+
+/*
+ * Releases the stream
+ *
+ * No more callbacks are invoked for the stream. Data queued with `s2n_quic_stream_write` is still
+ * sent.
+ */
+void s2n_quic_stream_free(struct s2n_quic_stream *stream);
+
+#ifdef __cplusplus
+}
+#endif
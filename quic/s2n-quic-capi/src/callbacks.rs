@@ -0,0 +1,71 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{connection::Connection, stream::Stream};
+use core::{ffi::c_void, ptr};
+use std::os::raw::c_int;
+
+/// Functions invoked by an endpoint from within `s2n_quic_endpoint_poll`
+///
+/// This mirrors `struct s2n_quic_callbacks` in `s2n_quic.h`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Callbacks {
+    pub context: *mut c_void,
+    pub on_connection: Option<unsafe extern "C" fn(*mut c_void, *mut Connection)>,
+    pub on_stream: Option<unsafe extern "C" fn(*mut c_void, *mut Stream)>,
+    pub on_stream_data: Option<unsafe extern "C" fn(*mut c_void, *mut Stream, *const u8, usize)>,
+    pub on_stream_end: Option<unsafe extern "C" fn(*mut c_void, *mut Stream, c_int)>,
+}
+
+impl Callbacks {
+    /// Returns `true` if the application wants to be notified of new connections
+    #[inline]
+    pub(crate) fn has_on_connection(&self) -> bool {
+        self.on_connection.is_some()
+    }
+
+    /// Returns `true` if the application wants to be notified of new streams
+    #[inline]
+    pub(crate) fn has_on_stream(&self) -> bool {
+        self.on_stream.is_some()
+    }
+
+    #[inline]
+    pub(crate) fn on_connection(&self, connection: *mut Connection) {
+        if let Some(f) = self.on_connection {
+            unsafe { f(self.context, connection) }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn on_connection_failed(&self) {
+        self.on_connection(ptr::null_mut())
+    }
+
+    #[inline]
+    pub(crate) fn on_stream(&self, stream: *mut Stream) {
+        if let Some(f) = self.on_stream {
+            unsafe { f(self.context, stream) }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn on_stream_failed(&self) {
+        self.on_stream(ptr::null_mut())
+    }
+
+    #[inline]
+    pub(crate) fn on_stream_data(&self, stream: *mut Stream, data: &[u8]) {
+        if let Some(f) = self.on_stream_data {
+            unsafe { f(self.context, stream, data.as_ptr(), data.len()) }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn on_stream_end(&self, stream: *mut Stream, status: c_int) {
+        if let Some(f) = self.on_stream_end {
+            unsafe { f(self.context, stream, status) }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{callbacks::Callbacks, stream::Stream, FAILURE, SUCCESS};
+use core::cell::Cell;
+use s2n_quic::{application, connection};
+use std::{
+    os::raw::c_int,
+    rc::{Rc, Weak},
+};
+use tokio::task::{self, JoinHandle, LocalSet};
+
+/// A connection, declared as `struct s2n_quic_connection` in `s2n_quic.h`
+pub struct Connection {
+    handle: connection::Handle,
+    callbacks: Callbacks,
+    /// The tasks of the endpoint, which are released along with the endpoint
+    local: Weak<LocalSet>,
+    /// The task accepting streams opened by the peer
+    acceptor: Cell<Option<JoinHandle<()>>>,
+    /// Set once the application has released the connection
+    is_freed: Rc<Cell<bool>>,
+}
+
+impl Connection {
+    /// Spawns the task accepting streams and hands the connection to the application
+    ///
+    /// Must be called from within the `LocalSet` of the endpoint.
+    pub(crate) fn open(
+        connection: s2n_quic::Connection,
+        callbacks: Callbacks,
+        local: Weak<LocalSet>,
+    ) {
+        // dropping the connection closes it, since nobody would be able to use it
+        if !callbacks.has_on_connection() {
+            return;
+        }
+
+        let (handle, mut acceptor) = connection.split();
+        let is_freed = Rc::new(Cell::new(false));
+
+        let acceptor = {
+            let is_freed = is_freed.clone();
+            task::spawn_local(async move {
+                while let Ok(Some(stream)) = acceptor.accept_bidirectional_stream().await {
+                    Stream::open(stream, callbacks);
+
+                    // the application released the connection from within the callback
+                    if is_freed.get() {
+                        return;
+                    }
+                }
+            })
+        };
+
+        let connection = Box::new(Self {
+            handle,
+            callbacks,
+            local,
+            acceptor: Cell::new(Some(acceptor)),
+            is_freed,
+        });
+
+        callbacks.on_connection(Box::into_raw(connection));
+    }
+}
+
+/// Returns the endpoint-unique identifier of the connection
+///
+/// # Safety
+///
+/// `connection` must be a connection passed to the `on_connection` callback, which has not been
+/// released.
+#[no_mangle]
+pub unsafe extern "C" fn s2n_quic_connection_id(connection: *const Connection) -> u64 {
+    (*connection).handle.id()
+}
+
+/// Starts opening a bidirectional stream, which is reported with the `on_stream` callback
+///
+/// # Safety
+///
+/// `connection` must be a connection passed to the `on_connection` callback, which has not been
+/// released.
+#[no_mangle]
+pub unsafe extern "C" fn s2n_quic_connection_open_stream(connection: *mut Connection) -> c_int {
+    let connection = match connection.as_ref() {
+        Some(connection) => connection,
+        None => return FAILURE,
+    };
+
+    let local = match connection.local.upgrade() {
+        Some(local) => local,
+        None => return FAILURE,
+    };
+
+    let mut handle = connection.handle.clone();
+    let callbacks = connection.callbacks;
+    let is_freed = connection.is_freed.clone();
+
+    local.spawn_local(async move {
+        let stream = handle.open_bidirectional_stream().await;
+
+        if is_freed.get() {
+            return;
+        }
+
+        match stream {
+            Ok(stream) => Stream::open(stream, callbacks),
+            Err(_) => callbacks.on_stream_failed(),
+        }
+    });
+
+    SUCCESS
+}
+
+/// Closes the connection with an application error code
+///
+/// # Safety
+///
+/// `connection` must be a connection passed to the `on_connection` callback, which has not been
+/// released.
+#[no_mangle]
+pub unsafe extern "C" fn s2n_quic_connection_close(
+    connection: *mut Connection,
+    error_code: u64,
+) -> c_int {
+    let connection = match connection.as_ref() {
+        Some(connection) => connection,
+        None => return FAILURE,
+    };
+
+    let error = match application::Error::new(error_code) {
+        Ok(error) => error,
+        Err(_) => return FAILURE,
+    };
+
+    connection.handle.close(error);
+
+    SUCCESS
+}
+
+/// Releases the connection
+///
+/// # Safety
+///
+/// `connection` must be `NULL` or a connection passed to the `on_connection` callback, which has
+/// not been released.
+#[no_mangle]
+pub unsafe extern "C" fn s2n_quic_connection_free(connection: *mut Connection) {
+    if connection.is_null() {
+        return;
+    }
+
+    let connection = Box::from_raw(connection);
+    connection.is_freed.set(true);
+
+    if let Some(acceptor) = connection.acceptor.take() {
+        acceptor.abort();
+    }
+}
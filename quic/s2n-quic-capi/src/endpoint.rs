@@ -0,0 +1,258 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{callbacks::Callbacks, connection::Connection, FAILURE, SUCCESS};
+use core::time::Duration;
+use s2n_quic::{client::Connect, Client, Server};
+use std::{
+    error::Error,
+    ffi::CStr,
+    net::SocketAddr,
+    os::raw::{c_char, c_int},
+    rc::Rc,
+};
+use tokio::{
+    runtime::{self, Runtime},
+    task::LocalSet,
+};
+
+/// A server or client endpoint, declared as `struct s2n_quic_endpoint` in `s2n_quic.h`
+pub struct Endpoint {
+    /// The tasks driving the connections and streams
+    ///
+    /// This is declared first, so the tasks are dropped before the runtime.
+    local: Rc<LocalSet>,
+    client: Option<Client>,
+    callbacks: Callbacks,
+    local_addr: SocketAddr,
+    runtime: Runtime,
+}
+
+impl Endpoint {
+    fn new<F>(callbacks: *const Callbacks, start: F) -> Result<Box<Self>, Box<dyn Error>>
+    where
+        F: FnOnce() -> Result<Kind, Box<dyn Error>>,
+    {
+        let callbacks = unsafe { callbacks.as_ref() }
+            .copied()
+            .ok_or("missing callbacks")?;
+
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        // the IO provider registers its sockets with the runtime of the current context
+        let kind = {
+            let _guard = runtime.enter();
+            start()?
+        };
+
+        let local = Rc::new(LocalSet::new());
+
+        let (client, local_addr) = match kind {
+            Kind::Server(mut server) => {
+                let local_addr = server.local_addr()?;
+                let tasks = Rc::downgrade(&local);
+                local.spawn_local(async move {
+                    while let Some(connection) = server.accept().await {
+                        Connection::open(connection, callbacks, tasks.clone());
+                    }
+                });
+                (None, local_addr)
+            }
+            Kind::Client(client) => {
+                let local_addr = client.local_addr()?;
+                (Some(client), local_addr)
+            }
+        };
+
+        Ok(Box::new(Self {
+            local,
+            client,
+            callbacks,
+            local_addr,
+            runtime,
+        }))
+    }
+}
+
+enum Kind {
+    Server(Server),
+    Client(Client),
+}
+
+unsafe fn to_str<'a>(value: *const c_char) -> Result<&'a str, Box<dyn Error>> {
+    if value.is_null() {
+        return Err("unexpected NULL string".into());
+    }
+
+    Ok(CStr::from_ptr(value).to_str()?)
+}
+
+/// Creates a server bound to `address`
+///
+/// Returns `NULL` on failure.
+///
+/// # Safety
+///
+/// `address`, `cert_pem` and `key_pem` must be NUL-terminated strings. `callbacks` must point to
+/// a valid `s2n_quic_callbacks`, which is copied.
+#[no_mangle]
+pub unsafe extern "C" fn s2n_quic_server_new(
+    address: *const c_char,
+    cert_pem: *const c_char,
+    key_pem: *const c_char,
+    callbacks: *const Callbacks,
+) -> *mut Endpoint {
+    let endpoint = Endpoint::new(callbacks, || {
+        let address = to_str(address)?;
+        let cert_pem = to_str(cert_pem)?;
+        let key_pem = to_str(key_pem)?;
+
+        let server = Server::builder()
+            .with_tls((cert_pem, key_pem))?
+            .with_io(address)?
+            .start()?;
+
+        Ok(Kind::Server(server))
+    });
+
+    endpoint.map_or(core::ptr::null_mut(), Box::into_raw)
+}
+
+/// Creates a client bound to `address`
+///
+/// Returns `NULL` on failure.
+///
+/// # Safety
+///
+/// `address` and `ca_pem` must be NUL-terminated strings. `callbacks` must point to a valid
+/// `s2n_quic_callbacks`, which is copied.
+#[no_mangle]
+pub unsafe extern "C" fn s2n_quic_client_new(
+    address: *const c_char,
+    ca_pem: *const c_char,
+    callbacks: *const Callbacks,
+) -> *mut Endpoint {
+    let endpoint = Endpoint::new(callbacks, || {
+        let address = to_str(address)?;
+        let ca_pem = to_str(ca_pem)?;
+
+        let client = Client::builder()
+            .with_tls(ca_pem)?
+            .with_io(address)?
+            .start()?;
+
+        Ok(Kind::Client(client))
+    });
+
+    endpoint.map_or(core::ptr::null_mut(), Box::into_raw)
+}
+
+/// Makes progress on all of the connections owned by the endpoint and invokes the callbacks
+///
+/// Blocks for up to `timeout_ms` milliseconds. A timeout of `0` makes progress without
+/// blocking.
+///
+/// # Safety
+///
+/// `endpoint` must be an endpoint which has not been released. The function must not be called
+/// from within a callback.
+#[no_mangle]
+pub unsafe extern "C" fn s2n_quic_endpoint_poll(endpoint: *mut Endpoint, timeout_ms: u64) -> c_int {
+    let endpoint = match endpoint.as_ref() {
+        Some(endpoint) => endpoint,
+        None => return FAILURE,
+    };
+
+    let local = &endpoint.local;
+    let runtime = &endpoint.runtime;
+
+    if timeout_ms == 0 {
+        runtime.block_on(local.run_until(tokio::task::yield_now()));
+    } else {
+        // the timer needs to be registered with the runtime of the endpoint
+        let _guard = runtime.enter();
+        let timeout = tokio::time::sleep(Duration::from_millis(timeout_ms));
+        runtime.block_on(local.run_until(timeout));
+    }
+
+    SUCCESS
+}
+
+/// Writes the port the endpoint is bound to into `port`
+///
+/// # Safety
+///
+/// `endpoint` must be an endpoint which has not been released. `port` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn s2n_quic_endpoint_local_port(
+    endpoint: *const Endpoint,
+    port: *mut u16,
+) -> c_int {
+    match (endpoint.as_ref(), port.as_mut()) {
+        (Some(endpoint), Some(port)) => {
+            *port = endpoint.local_addr.port();
+            SUCCESS
+        }
+        _ => FAILURE,
+    }
+}
+
+/// Releases the endpoint
+///
+/// # Safety
+///
+/// `endpoint` must be `NULL` or an endpoint which has not been released.
+#[no_mangle]
+pub unsafe extern "C" fn s2n_quic_endpoint_free(endpoint: *mut Endpoint) {
+    if !endpoint.is_null() {
+        drop(Box::from_raw(endpoint));
+    }
+}
+
+/// Starts connecting to the server at `address`
+///
+/// The result is reported with the `on_connection` callback.
+///
+/// # Safety
+///
+/// `endpoint` must be an endpoint which has not been released. `address` and `server_name` must
+/// be NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn s2n_quic_client_connect(
+    endpoint: *mut Endpoint,
+    address: *const c_char,
+    server_name: *const c_char,
+) -> c_int {
+    let endpoint = match endpoint.as_ref() {
+        Some(endpoint) => endpoint,
+        None => return FAILURE,
+    };
+
+    let client = match endpoint.client.as_ref() {
+        Some(client) => client,
+        None => return FAILURE,
+    };
+
+    let connect = match (to_str(address), to_str(server_name)) {
+        (Ok(address), Ok(server_name)) => match address.parse::<SocketAddr>() {
+            Ok(address) => Connect::new(address).with_server_name(server_name),
+            Err(_) => return FAILURE,
+        },
+        _ => return FAILURE,
+    };
+
+    let attempt = client.connect(connect);
+    let callbacks = endpoint.callbacks;
+    let tasks = Rc::downgrade(&endpoint.local);
+
+    endpoint.local.spawn_local(async move {
+        match attempt.await {
+            Ok(connection) => Connection::open(connection, callbacks, tasks),
+            Err(_) => callbacks.on_connection_failed(),
+        }
+    });
+
+    SUCCESS
+}
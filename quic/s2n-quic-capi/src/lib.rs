@@ -0,0 +1,33 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! C bindings for s2n-quic
+//!
+//! The declarations of the exported functions are in `include/s2n_quic.h`.
+//!
+//! Each endpoint owns a single-threaded Tokio runtime, which only makes progress while the
+//! application calls [`s2n_quic_endpoint_poll`]. The callbacks are invoked from within the poll
+//! call, so applications don't need to synchronize with any background threads. This also means
+//! that all of the functions must be called from the thread that created the endpoint.
+//!
+//! The application owns the connections and streams passed to the callbacks, and releases them
+//! with [`s2n_quic_connection_free`] and [`s2n_quic_stream_free`]. The tasks driving a connection
+//! or stream don't depend on the application's pointer, so it stays valid until it is released.
+
+mod callbacks;
+mod connection;
+mod endpoint;
+mod stream;
+
+#[cfg(test)]
+mod tests;
+
+pub use callbacks::Callbacks;
+pub use connection::*;
+pub use endpoint::*;
+pub use stream::*;
+
+use std::os::raw::c_int;
+
+const SUCCESS: c_int = 0;
+const FAILURE: c_int = -1;
@@ -0,0 +1,173 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{callbacks::Callbacks, FAILURE, SUCCESS};
+use bytes::Bytes;
+use core::{cell::Cell, slice};
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    StreamExt,
+};
+use s2n_quic::stream::{BidirectionalStream, ReceiveStream, SendStream};
+use std::{os::raw::c_int, rc::Rc};
+use tokio::task::{self, JoinHandle};
+
+/// A bidirectional stream, declared as `struct s2n_quic_stream` in `s2n_quic.h`
+pub struct Stream {
+    id: u64,
+    callbacks: Callbacks,
+    /// Queues data for the task writing to the send stream
+    writer: UnboundedSender<Write>,
+    /// The task reading from the receive stream
+    reader: Cell<Option<JoinHandle<()>>>,
+    /// Set once the application has released the stream
+    is_freed: Cell<bool>,
+}
+
+enum Write {
+    Data(Bytes),
+    Finish,
+}
+
+impl Stream {
+    /// Spawns the tasks driving the stream and hands it to the application
+    ///
+    /// Must be called from within the `LocalSet` of the endpoint.
+    pub(crate) fn open(stream: BidirectionalStream, callbacks: Callbacks) {
+        // nobody would be able to use the stream
+        if !callbacks.has_on_stream() {
+            return;
+        }
+
+        let id = stream.id();
+        let (receive, send) = stream.split();
+        let (writer, queue) = unbounded();
+
+        let stream = Rc::new(Self {
+            id,
+            callbacks,
+            writer,
+            reader: Cell::new(None),
+            is_freed: Cell::new(false),
+        });
+
+        task::spawn_local(write(send, queue));
+        let reader = task::spawn_local(read(stream.clone(), receive));
+        stream.reader.set(Some(reader));
+
+        // the reader doesn't run until the current task yields, so `on_stream` is always the
+        // first callback for the stream
+        let ptr = Rc::into_raw(stream.clone()) as *mut Self;
+        stream.callbacks.on_stream(ptr);
+    }
+
+    #[inline]
+    fn as_ptr(self: &Rc<Self>) -> *mut Self {
+        Rc::as_ptr(self) as *mut Self
+    }
+}
+
+async fn read(stream: Rc<Stream>, mut receive: ReceiveStream) {
+    loop {
+        let status = match receive.receive().await {
+            Ok(Some(chunk)) => {
+                stream.callbacks.on_stream_data(stream.as_ptr(), &chunk);
+                None
+            }
+            Ok(None) => Some(SUCCESS),
+            Err(_) => Some(FAILURE),
+        };
+
+        // the application released the stream from within the callback
+        if stream.is_freed.get() {
+            return;
+        }
+
+        if let Some(status) = status {
+            stream.callbacks.on_stream_end(stream.as_ptr(), status);
+            return;
+        }
+    }
+}
+
+async fn write(mut send: SendStream, mut queue: UnboundedReceiver<Write>) {
+    while let Some(write) = queue.next().await {
+        let result = match write {
+            Write::Data(data) => send.send(data).await,
+            Write::Finish => send.finish(),
+        };
+
+        if result.is_err() {
+            return;
+        }
+    }
+}
+
+/// Returns the identifier of the stream
+///
+/// # Safety
+///
+/// `stream` must be a stream passed to the `on_stream` callback, which has not been released.
+#[no_mangle]
+pub unsafe extern "C" fn s2n_quic_stream_id(stream: *const Stream) -> u64 {
+    (*stream).id
+}
+
+/// Queues `len` bytes of `data` to be sent on the stream
+///
+/// # Safety
+///
+/// `stream` must be a stream passed to the `on_stream` callback, which has not been released.
+/// `data` must point to `len` readable bytes, unless `len` is `0`.
+#[no_mangle]
+pub unsafe extern "C" fn s2n_quic_stream_write(
+    stream: *mut Stream,
+    data: *const u8,
+    len: usize,
+    fin: bool,
+) -> c_int {
+    let stream = match stream.as_ref() {
+        Some(stream) => stream,
+        None => return FAILURE,
+    };
+
+    if len > 0 {
+        if data.is_null() {
+            return FAILURE;
+        }
+
+        let data = Bytes::copy_from_slice(slice::from_raw_parts(data, len));
+        if stream.writer.unbounded_send(Write::Data(data)).is_err() {
+            return FAILURE;
+        }
+    }
+
+    if fin {
+        if stream.writer.unbounded_send(Write::Finish).is_err() {
+            return FAILURE;
+        }
+        stream.writer.close_channel();
+    }
+
+    SUCCESS
+}
+
+/// Releases the stream
+///
+/// # Safety
+///
+/// `stream` must be `NULL` or a stream passed to the `on_stream` callback, which has not been
+/// released.
+#[no_mangle]
+pub unsafe extern "C" fn s2n_quic_stream_free(stream: *mut Stream) {
+    if stream.is_null() {
+        return;
+    }
+
+    let stream = Rc::from_raw(stream as *const Stream);
+    stream.is_freed.set(true);
+
+    if let Some(reader) = stream.reader.take() {
+        reader.abort();
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use core::{ffi::c_void, ptr, slice};
+use s2n_quic_core::crypto::tls::testing::certificates::{CERT_PEM, KEY_PEM};
+use std::{ffi::CString, os::raw::c_int};
+
+#[derive(Default)]
+struct State {
+    connections: Vec<*mut Connection>,
+    streams: Vec<*mut Stream>,
+    received: Vec<u8>,
+    status: Option<c_int>,
+    is_client: bool,
+}
+
+unsafe fn state<'a>(context: *mut c_void) -> &'a mut State {
+    &mut *(context as *mut State)
+}
+
+unsafe extern "C" fn on_connection(context: *mut c_void, connection: *mut Connection) {
+    let state = state(context);
+    assert!(!connection.is_null());
+    state.connections.push(connection);
+
+    if state.is_client {
+        assert_eq!(s2n_quic_connection_open_stream(connection), 0);
+    }
+}
+
+unsafe extern "C" fn on_stream(context: *mut c_void, stream: *mut Stream) {
+    let state = state(context);
+    assert!(!stream.is_null());
+    assert_eq!(s2n_quic_stream_id(stream), 0);
+    state.streams.push(stream);
+
+    if state.is_client {
+        let data = b"hello";
+        assert_eq!(
+            s2n_quic_stream_write(stream, data.as_ptr(), data.len(), true),
+            0
+        );
+    }
+}
+
+unsafe extern "C" fn on_stream_data(
+    context: *mut c_void,
+    stream: *mut Stream,
+    data: *const u8,
+    len: usize,
+) {
+    let state = state(context);
+    let data = slice::from_raw_parts(data, len);
+    state.received.extend_from_slice(data);
+
+    // the server echoes the data back
+    if !state.is_client {
+        assert_eq!(
+            s2n_quic_stream_write(stream, data.as_ptr(), data.len(), false),
+            0
+        );
+    }
+}
+
+unsafe extern "C" fn on_stream_end(context: *mut c_void, stream: *mut Stream, status: c_int) {
+    let state = state(context);
+    state.status = Some(status);
+
+    if !state.is_client {
+        assert_eq!(s2n_quic_stream_write(stream, ptr::null(), 0, true), 0);
+    }
+}
+
+fn callbacks(state: &mut State) -> Callbacks {
+    Callbacks {
+        context: state as *mut State as *mut c_void,
+        on_connection: Some(on_connection),
+        on_stream: Some(on_stream),
+        on_stream_data: Some(on_stream_data),
+        on_stream_end: Some(on_stream_end),
+    }
+}
+
+#[test]
+fn echo_test() {
+    let mut server_state = State::default();
+    let mut client_state = State {
+        is_client: true,
+        ..Default::default()
+    };
+
+    let address = CString::new("127.0.0.1:0").unwrap();
+    let cert = CString::new(CERT_PEM).unwrap();
+    let key = CString::new(KEY_PEM).unwrap();
+
+    unsafe {
+        let server = s2n_quic_server_new(
+            address.as_ptr(),
+            cert.as_ptr(),
+            key.as_ptr(),
+            &callbacks(&mut server_state),
+        );
+        assert!(!server.is_null());
+
+        let client = s2n_quic_client_new(
+            address.as_ptr(),
+            cert.as_ptr(),
+            &callbacks(&mut client_state),
+        );
+        assert!(!client.is_null());
+
+        // servers can't initiate connections
+        assert_eq!(
+            s2n_quic_client_connect(server, address.as_ptr(), address.as_ptr()),
+            -1
+        );
+
+        let mut port = 0;
+        assert_eq!(s2n_quic_endpoint_local_port(server, &mut port), 0);
+        assert_ne!(port, 0);
+
+        let server_address = CString::new(format!("127.0.0.1:{}", port)).unwrap();
+        let server_name = CString::new("localhost").unwrap();
+        assert_eq!(
+            s2n_quic_client_connect(client, server_address.as_ptr(), server_name.as_ptr()),
+            0
+        );
+
+        for _ in 0..1000 {
+            if client_state.status.is_some() {
+                break;
+            }
+            assert_eq!(s2n_quic_endpoint_poll(server, 0), 0);
+            assert_eq!(s2n_quic_endpoint_poll(client, 1), 0);
+        }
+
+        assert_eq!(server_state.received, b"hello");
+        assert_eq!(server_state.status, Some(0));
+        assert_eq!(client_state.received, b"hello");
+        assert_eq!(client_state.status, Some(0));
+
+        for state in [&mut server_state, &mut client_state] {
+            assert_eq!(state.connections.len(), 1);
+            assert_eq!(state.streams.len(), 1);
+
+            for stream in state.streams.drain(..) {
+                s2n_quic_stream_free(stream);
+            }
+            for connection in state.connections.drain(..) {
+                assert_eq!(s2n_quic_connection_close(connection, 1), 0);
+                s2n_quic_connection_free(connection);
+            }
+        }
+
+        s2n_quic_endpoint_free(client);
+        s2n_quic_endpoint_free(server);
+    }
+}
+
+#[test]
+fn invalid_arguments_test() {
+    let address = CString::new("not an address").unwrap();
+    let cert = CString::new(CERT_PEM).unwrap();
+    let callbacks = Callbacks {
+        context: ptr::null_mut(),
+        on_connection: None,
+        on_stream: None,
+        on_stream_data: None,
+        on_stream_end: None,
+    };
+
+    unsafe {
+        assert!(s2n_quic_client_new(address.as_ptr(), cert.as_ptr(), &callbacks).is_null());
+        assert!(s2n_quic_client_new(ptr::null(), cert.as_ptr(), &callbacks).is_null());
+        assert!(s2n_quic_client_new(address.as_ptr(), cert.as_ptr(), ptr::null()).is_null());
+
+        assert_eq!(s2n_quic_endpoint_poll(ptr::null_mut(), 0), -1);
+        assert_eq!(s2n_quic_connection_open_stream(ptr::null_mut()), -1);
+        assert_eq!(
+            s2n_quic_stream_write(ptr::null_mut(), ptr::null(), 0, true),
+            -1
+        );
+
+        // releasing NULL is a no-op
+        s2n_quic_endpoint_free(ptr::null_mut());
+        s2n_quic_connection_free(ptr::null_mut());
+        s2n_quic_stream_free(ptr::null_mut());
+    }
+}
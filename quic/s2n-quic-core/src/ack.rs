@@ -1,8 +1,12 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod error;
 pub mod set;
 pub mod settings;
+pub mod strategy;
 
+pub use error::Error;
 pub use set::Set;
 pub use settings::Settings;
+pub use strategy::Strategy;
@@ -0,0 +1,42 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::transport;
+
+/// Errors that can occur when validating an ACK received from the peer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The peer acknowledged a packet number that has not been sent yet
+    PacketNotSent,
+    /// The peer acknowledged a packet number that was intentionally skipped
+    ///
+    /// This indicates the peer is optimistically acknowledging packets it
+    /// has not received.
+    SkippedPacketNumber,
+    /// The peer sent an ACK frame with more ranges than the endpoint accepts
+    RangeLimitExceeded,
+}
+
+impl Error {
+    fn message(&self) -> &'static str {
+        match self {
+            Error::PacketNotSent => "received an ACK for a packet that was not sent",
+            Error::SkippedPacketNumber => "received an ACK for a skipped packet number",
+            Error::RangeLimitExceeded => "received an ACK with too many ranges",
+        }
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl From<Error> for transport::Error {
+    #[inline]
+    fn from(error: Error) -> Self {
+        Self::PROTOCOL_VIOLATION.with_reason(error.message())
+    }
+}
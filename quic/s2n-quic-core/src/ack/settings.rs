@@ -16,6 +16,12 @@ const RECOMMENDED_ELICITATION_INTERVAL: u8 = 4;
 /// The recommended number of packet number ranges that an endpoint should store
 const RECOMMENDED_RANGES_LIMIT: u8 = 10;
 
+// TODO update to draft link after published
+// https://github.com/quicwg/base-drafts/pull/3623
+// An ACK frame SHOULD be generated for at least every 10th ack-eliciting packet
+/// The recommended value for the ack_eliciting_threshold setting
+const RECOMMENDED_ACK_ELICITING_THRESHOLD: u8 = 10;
+
 /// Settings for ACK frames
 #[derive(Clone, Copy, Debug)]
 pub struct Settings {
@@ -39,6 +45,22 @@ pub struct Settings {
 
     /// The number of packet number intervals an endpoint is willing to store
     pub ack_ranges_limit: u8,
+
+    /// The number of packet number intervals included in a transmitted ACK frame
+    ///
+    /// Only the intervals with the largest packet numbers are included if more are stored.
+    pub advertised_ack_ranges_limit: u8,
+
+    /// The number of packets received before an ACK is sent without waiting for the
+    /// ACK delay timer
+    pub ack_eliciting_threshold: u8,
+
+    /// If `true`, ack-eliciting packets received out of order are acknowledged immediately
+    pub immediate_ack_on_reorder: bool,
+
+    /// If `true`, packets marked with the ECN Congestion Experienced (CE) codepoint are
+    /// acknowledged immediately
+    pub immediate_ack_on_congestion: bool,
 }
 
 impl Default for Settings {
@@ -72,6 +94,10 @@ impl Settings {
         ack_delay_exponent: AckDelayExponent::RECOMMENDED.as_u8(),
         ack_elicitation_interval: RECOMMENDED_ELICITATION_INTERVAL,
         ack_ranges_limit: RECOMMENDED_RANGES_LIMIT,
+        advertised_ack_ranges_limit: RECOMMENDED_RANGES_LIMIT,
+        ack_eliciting_threshold: RECOMMENDED_ACK_ELICITING_THRESHOLD,
+        immediate_ack_on_reorder: true,
+        immediate_ack_on_congestion: true,
     };
 
     /// Decodes the peer's `Ack Delay` field
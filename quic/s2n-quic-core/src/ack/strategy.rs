@@ -0,0 +1,252 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Configures how each connection delays acknowledgements
+
+use crate::{
+    ack::Settings,
+    transport::parameters::{MaxAckDelay, ValidationError},
+};
+use core::{convert::TryFrom, time::Duration};
+
+pub use crate::connection::limits::ConnectionInfo;
+
+const ACK_ELICITING_THRESHOLD_TOO_SMALL: ValidationError =
+    ValidationError::new("ack eliciting threshold must be at least 1");
+
+/// The policy used to decide when to send acknowledgements
+///
+/// By default, an ACK frame is sent after at most 10 packets or `max_ack_delay`,
+/// whichever comes first, and packets which are received out of order or marked
+/// with ECN-CE are acknowledged immediately.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Strategy {
+    max_ack_delay: MaxAckDelay,
+    ack_eliciting_threshold: u8,
+    immediate_ack_on_reorder: bool,
+    immediate_ack_on_congestion: bool,
+}
+
+impl Default for Strategy {
+    fn default() -> Self {
+        Self::RECOMMENDED
+    }
+}
+
+impl Strategy {
+    /// The strategy recommended by RFC 9000
+    pub const RECOMMENDED: Self = Self {
+        max_ack_delay: MaxAckDelay::RECOMMENDED,
+        ack_eliciting_threshold: Settings::RECOMMENDED.ack_eliciting_threshold,
+        immediate_ack_on_reorder: Settings::RECOMMENDED.immediate_ack_on_reorder,
+        immediate_ack_on_congestion: Settings::RECOMMENDED.immediate_ack_on_congestion,
+    };
+
+    /// Acknowledges every ack-eliciting packet immediately
+    ///
+    /// This minimizes the time it takes the peer to detect losses at the cost of sending
+    /// an ACK frame for every packet received.
+    pub const LOW_LATENCY: Self = Self {
+        max_ack_delay: MaxAckDelay::from_millis_u8(1),
+        ack_eliciting_threshold: 1,
+        immediate_ack_on_reorder: true,
+        immediate_ack_on_congestion: true,
+    };
+
+    /// Acknowledges up to 32 packets with a single ACK frame and does not send an ACK
+    /// immediately when packets are reordered
+    ///
+    /// This reduces the ACK overhead on paths which frequently reorder packets. Packets
+    /// marked with ECN-CE are still acknowledged immediately so the peer can respond to
+    /// congestion.
+    pub const HIGH_THROUGHPUT: Self = Self {
+        max_ack_delay: MaxAckDelay::RECOMMENDED,
+        ack_eliciting_threshold: 32,
+        immediate_ack_on_reorder: false,
+        immediate_ack_on_congestion: true,
+    };
+
+    /// Returns a builder for an ACK `Strategy`, starting from the recommended strategy
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// The maximum amount of time an acknowledgement is delayed
+    ///
+    /// The value is advertised to the peer with the `max_ack_delay` transport parameter.
+    pub fn max_ack_delay(&self) -> Duration {
+        self.max_ack_delay.as_duration()
+    }
+
+    /// The number of packets received before sending an ACK without waiting for the
+    /// ACK delay timer
+    pub fn ack_eliciting_threshold(&self) -> u8 {
+        self.ack_eliciting_threshold
+    }
+
+    /// Returns `true` if ack-eliciting packets received out of order are acknowledged
+    /// immediately
+    pub fn immediate_ack_on_reorder(&self) -> bool {
+        self.immediate_ack_on_reorder
+    }
+
+    /// Returns `true` if packets marked with ECN-CE are acknowledged immediately
+    pub fn immediate_ack_on_congestion(&self) -> bool {
+        self.immediate_ack_on_congestion
+    }
+
+    pub(crate) fn max_ack_delay_parameter(&self) -> MaxAckDelay {
+        self.max_ack_delay
+    }
+}
+
+/// Builds an ACK [`Strategy`]
+#[derive(Debug, Default)]
+pub struct Builder {
+    strategy: Strategy,
+}
+
+impl From<Strategy> for Builder {
+    fn from(strategy: Strategy) -> Self {
+        Self { strategy }
+    }
+}
+
+impl Builder {
+    /// Sets the maximum amount of time an acknowledgement is delayed
+    ///
+    /// Defaults to 25ms. The value is truncated to milliseconds and must not be larger
+    /// than 2^14 milliseconds.
+    pub fn with_max_ack_delay(mut self, max_ack_delay: Duration) -> Result<Self, ValidationError> {
+        self.strategy.max_ack_delay = MaxAckDelay::try_from(max_ack_delay)?;
+        Ok(self)
+    }
+
+    /// Sets the number of packets received before sending an ACK without waiting for the
+    /// ACK delay timer
+    ///
+    /// Defaults to 10. Setting the threshold to 1 acknowledges every ack-eliciting packet
+    /// immediately.
+    pub fn with_ack_eliciting_threshold(mut self, threshold: u8) -> Result<Self, ValidationError> {
+        if threshold == 0 {
+            return Err(ACK_ELICITING_THRESHOLD_TOO_SMALL);
+        }
+        self.strategy.ack_eliciting_threshold = threshold;
+        Ok(self)
+    }
+
+    /// Sets whether ack-eliciting packets received out of order are acknowledged immediately
+    ///
+    /// Defaults to `true`, which assists the peer in detecting losses. Disabling it reduces
+    /// the number of ACK frames sent on paths which frequently reorder packets.
+    pub fn with_immediate_ack_on_reorder(mut self, enabled: bool) -> Self {
+        self.strategy.immediate_ack_on_reorder = enabled;
+        self
+    }
+
+    /// Sets whether packets marked with ECN-CE are acknowledged immediately
+    ///
+    /// Defaults to `true`, which reduces the time it takes the peer to respond to congestion.
+    pub fn with_immediate_ack_on_congestion(mut self, enabled: bool) -> Self {
+        self.strategy.immediate_ack_on_congestion = enabled;
+        self
+    }
+
+    /// Builds the [`Strategy`]
+    pub fn build(self) -> Strategy {
+        self.strategy
+    }
+}
+
+/// Configures the ACK strategy of each connection created by the endpoint
+pub trait Endpoint: 'static + Send {
+    /// Called when a new connection is created
+    ///
+    /// Returning `None` uses the `max_ack_delay` configured on the connection limits and
+    /// the recommended strategy.
+    ///
+    /// ```rust
+    /// # mod s2n_quic { pub mod provider { pub mod ack { pub use s2n_quic_core::ack::strategy::*; } } }
+    /// use s2n_quic::provider::ack::{ConnectionInfo, Endpoint, Strategy};
+    /// use std::net::{IpAddr, SocketAddr};
+    ///
+    /// struct Datacenter;
+    ///
+    /// impl Endpoint for Datacenter {
+    ///     fn on_connection(&mut self, info: &ConnectionInfo) -> Option<Strategy> {
+    ///         // reduce the ACK overhead for peers within 10.0.0.0/8
+    ///         match SocketAddr::from(&info.remote_address).ip() {
+    ///             IpAddr::V4(ip) if ip.octets()[0] == 10 => Some(Strategy::HIGH_THROUGHPUT),
+    ///             _ => None,
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    fn on_connection(&mut self, info: &ConnectionInfo) -> Option<Strategy>;
+}
+
+/// Applies the same ACK strategy to every connection
+impl Endpoint for Strategy {
+    #[inline]
+    fn on_connection(&mut self, _info: &ConnectionInfo) -> Option<Strategy> {
+        Some(*self)
+    }
+}
+
+pub mod default {
+    use super::*;
+
+    /// Uses the `max_ack_delay` configured on the connection limits and the recommended
+    /// strategy for every connection
+    #[derive(Clone, Debug, Default)]
+    pub struct Endpoint;
+
+    impl super::Endpoint for Endpoint {
+        #[inline]
+        fn on_connection(&mut self, _info: &ConnectionInfo) -> Option<Strategy> {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_test() {
+        assert_eq!(Strategy::builder().build(), Strategy::RECOMMENDED);
+
+        let strategy = Strategy::builder()
+            .with_max_ack_delay(Duration::from_millis(100))
+            .unwrap()
+            .with_ack_eliciting_threshold(2)
+            .unwrap()
+            .with_immediate_ack_on_reorder(false)
+            .with_immediate_ack_on_congestion(false)
+            .build();
+        assert_eq!(strategy.max_ack_delay(), Duration::from_millis(100));
+        assert_eq!(strategy.ack_eliciting_threshold(), 2);
+        assert!(!strategy.immediate_ack_on_reorder());
+        assert!(!strategy.immediate_ack_on_congestion());
+
+        let strategy = Builder::from(Strategy::HIGH_THROUGHPUT)
+            .with_immediate_ack_on_reorder(true)
+            .build();
+        assert_eq!(strategy.ack_eliciting_threshold(), 32);
+        assert!(strategy.immediate_ack_on_reorder());
+    }
+
+    #[test]
+    fn builder_validation_test() {
+        assert_eq!(
+            Strategy::builder()
+                .with_ack_eliciting_threshold(0)
+                .unwrap_err(),
+            ACK_ELICITING_THRESHOLD_TOO_SMALL
+        );
+        assert!(Strategy::builder()
+            .with_max_ack_delay(Duration::from_secs(60))
+            .is_err());
+    }
+}
@@ -36,6 +36,132 @@ impl Error {
     pub fn new(value: u64) -> Result<Self, VarIntError> {
         Ok(Self(VarInt::new(value)?))
     }
+
+    /// Creates an error code from a `u8`, which is always in the valid range
+    pub const fn from_u8(value: u8) -> Self {
+        Self(VarInt::from_u8(value))
+    }
+
+    /// Creates an error code from a `u16`, which is always in the valid range
+    pub const fn from_u16(value: u16) -> Self {
+        Self(VarInt::from_u16(value))
+    }
+
+    /// Creates an error code from a `u32`, which is always in the valid range
+    pub const fn from_u32(value: u32) -> Self {
+        Self(VarInt::from_u32(value))
+    }
+
+    /// Returns the error code as a `u64`
+    pub const fn as_u64(self) -> u64 {
+        self.0.as_u64()
+    }
+}
+
+/// An inclusive range of error codes which are defined by an application protocol
+///
+/// Application protocols typically reserve a range of codes for their errors, e.g. HTTP/3 uses
+/// `0x0100..=0x0110`. Ranges allow error codes received from the peer to be attributed to the
+/// protocol and mapped back to the protocol's own error type.
+///
+/// ```rust
+/// use core::convert::TryFrom;
+/// use s2n_quic_core::application::{error::Range, Error};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// enum H3Error {
+///     NoError,
+///     GeneralProtocolError,
+///     InternalError,
+/// }
+///
+/// impl H3Error {
+///     const RANGE: Range = Range::new(Error::from_u16(0x0100), Error::from_u16(0x0110));
+/// }
+///
+/// impl From<H3Error> for Error {
+///     fn from(error: H3Error) -> Self {
+///         H3Error::RANGE.get(error as u64).expect("codes are in range")
+///     }
+/// }
+///
+/// impl TryFrom<Error> for H3Error {
+///     type Error = Error;
+///
+///     fn try_from(error: Error) -> Result<Self, Error> {
+///         match H3Error::RANGE.offset(error) {
+///             Some(0) => Ok(Self::NoError),
+///             Some(1) => Ok(Self::GeneralProtocolError),
+///             Some(2) => Ok(Self::InternalError),
+///             _ => Err(error),
+///         }
+///     }
+/// }
+///
+/// let error: Error = H3Error::InternalError.into();
+/// assert_eq!(*error, 0x0102);
+/// assert_eq!(H3Error::try_from(error), Ok(H3Error::InternalError));
+/// assert!(H3Error::try_from(Error::UNKNOWN).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Range {
+    start: Error,
+    end: Error,
+}
+
+impl Range {
+    /// Creates a range of error codes from `start` to `end`, inclusive
+    ///
+    /// The range is empty if `start` is greater than `end`.
+    pub const fn new(start: Error, end: Error) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns the first error code in the range
+    pub const fn start(&self) -> Error {
+        self.start
+    }
+
+    /// Returns the last error code in the range
+    pub const fn end(&self) -> Error {
+        self.end
+    }
+
+    /// Returns `true` if the range contains the error code
+    pub fn contains(&self, error: Error) -> bool {
+        (*self.start..=*self.end).contains(&*error)
+    }
+
+    /// Returns `true` if any error code is contained in both ranges
+    ///
+    /// This can be used to check that the ranges of multiple application protocols don't
+    /// collide.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        *self.start <= *self.end
+            && *other.start <= *other.end
+            && *self.start <= *other.end
+            && *other.start <= *self.end
+    }
+
+    /// Returns the error code at the given offset from the start of the range
+    pub fn get(&self, offset: u64) -> Option<Error> {
+        let error = Error::new(self.start.checked_add(offset)?).ok()?;
+        if self.contains(error) {
+            Some(error)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the offset of the error code from the start of the range, if it is contained in
+    /// the range
+    pub fn offset(&self, error: Error) -> Option<u64> {
+        if self.contains(error) {
+            Some(*error - *self.start)
+        } else {
+            None
+        }
+    }
 }
 
 impl ops::Deref for Error {
@@ -107,3 +233,39 @@ pub trait TryInto {
     /// Returns the associated [`Error`], if any
     fn application_error(&self) -> Option<Error>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_test() {
+        let range = Range::new(Error::from_u16(0x0100), Error::from_u16(0x0110));
+
+        assert!(range.contains(Error::from_u16(0x0100)));
+        assert!(range.contains(Error::from_u16(0x0110)));
+        assert!(!range.contains(Error::from_u16(0x0111)));
+        assert!(!range.contains(Error::UNKNOWN));
+
+        assert_eq!(range.get(0), Some(range.start()));
+        assert_eq!(range.get(0x10), Some(range.end()));
+        assert_eq!(range.get(0x11), None);
+        assert_eq!(range.get(u64::MAX), None);
+
+        assert_eq!(range.offset(Error::from_u16(0x0102)), Some(2));
+        assert_eq!(range.offset(Error::from_u16(0x00ff)), None);
+
+        let other = Range::new(Error::from_u16(0x0110), Error::from_u16(0x0200));
+        assert!(range.overlaps(&other));
+        assert!(other.overlaps(&range));
+
+        let other = Range::new(Error::from_u16(0x0111), Error::from_u16(0x0200));
+        assert!(!range.overlaps(&other));
+
+        // empty ranges don't contain or overlap anything
+        let empty = Range::new(Error::from_u16(0x0105), Error::from_u16(0x0104));
+        assert!(!empty.contains(Error::from_u16(0x0104)));
+        assert!(!empty.overlaps(&range));
+        assert_eq!(empty.get(0), None);
+    }
+}
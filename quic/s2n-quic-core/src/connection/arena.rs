@@ -0,0 +1,115 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Usage statistics for the per-connection arenas
+//!
+//! Each connection serves its frequent, short-lived allocations from arenas to reduce the
+//! pressure on the global allocator. Stream frame data is carved out of larger chunks with a
+//! bump allocator, while stream state and sent-packet metadata are recycled once they are no
+//! longer needed.
+
+use core::ops::{Add, AddAssign};
+
+/// The usage of all of the arenas of a connection
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Stats {
+    /// The buffers allocated for received stream frame data
+    pub frames: Usage,
+    /// The metadata tracked for sent packets which were declared lost
+    pub sent_packets: Usage,
+    /// The state allocated for each stream
+    pub streams: Usage,
+}
+
+impl Stats {
+    /// Returns the combined usage of all of the arenas
+    #[inline]
+    pub fn total(&self) -> Usage {
+        self.frames + self.sent_packets + self.streams
+    }
+}
+
+/// The usage of a single arena
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Usage {
+    /// The number of values handed out by the arena
+    pub allocations: u64,
+    /// The number of times the arena allocated memory from the global allocator
+    pub heap_allocations: u64,
+    /// The total number of bytes the arena allocated from the global allocator
+    pub heap_bytes: u64,
+}
+
+impl Usage {
+    /// Records a value handed out by the arena
+    ///
+    /// `heap_bytes` is the number of bytes which had to be allocated from the global allocator
+    /// to serve the value, which is `0` if the arena already had memory available.
+    #[inline]
+    pub fn on_allocation(&mut self, heap_bytes: usize) {
+        self.allocations += 1;
+        self.on_heap_allocation(heap_bytes);
+    }
+
+    /// Records memory allocated from the global allocator, without handing out a value
+    #[inline]
+    pub fn on_heap_allocation(&mut self, heap_bytes: usize) {
+        if heap_bytes > 0 {
+            self.heap_allocations += 1;
+            self.heap_bytes += heap_bytes as u64;
+        }
+    }
+}
+
+impl Add for Usage {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl AddAssign for Usage {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        self.allocations += rhs.allocations;
+        self.heap_allocations += rhs.heap_allocations;
+        self.heap_bytes += rhs.heap_bytes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_test() {
+        let mut frames = Usage::default();
+        frames.on_allocation(4096);
+        frames.on_allocation(0);
+        frames.on_allocation(0);
+
+        let mut streams = Usage::default();
+        streams.on_allocation(256);
+        streams.on_heap_allocation(0);
+
+        let stats = Stats {
+            frames,
+            streams,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            stats.total(),
+            Usage {
+                allocations: 4,
+                heap_allocations: 2,
+                heap_bytes: 4096 + 256,
+            }
+        );
+    }
+}
@@ -96,32 +96,178 @@ impl Formatter for Development {
 /// * INTERNAL_ERROR is transformed into PROTOCOL_VIOLATION
 /// * Application codes are hidden in early (initial, handshake) packets
 /// * Crypto (TLS) alerts are transformed into HANDSHAKE_FAILURE
+///
+/// See [`Redacted`] for configuring which details are removed.
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Production;
 
+static PRODUCTION: Redacted = Redacted::new();
+
 impl Formatter for Production {
     fn format_transport_error(
         &self,
-        _context: &Context,
+        context: &Context,
         error: transport::Error,
     ) -> ConnectionClose {
-        // rewrite internal errors as PROTOCOL_VIOLATION
-        if error.code == transport::Error::INTERNAL_ERROR.code {
-            return transport::Error::PROTOCOL_VIOLATION.into();
+        PRODUCTION.format_transport_error(context, error)
+    }
+
+    fn format_application_error(
+        &self,
+        context: &Context,
+        error: application::Error,
+    ) -> ConnectionClose {
+        PRODUCTION.format_application_error(context, error)
+    }
+
+    fn format_early_transport_error(
+        &self,
+        context: &Context,
+        error: transport::Error,
+    ) -> ConnectionClose {
+        PRODUCTION.format_early_transport_error(context, error)
+    }
+
+    fn format_early_application_error(
+        &self,
+        context: &Context,
+        error: application::Error,
+    ) -> ConnectionClose {
+        PRODUCTION.format_early_application_error(context, error)
+    }
+}
+
+/// A formatter that maps transport errors to generic codes before sending them to the peer
+///
+/// The default configuration removes the same information as [`Production`]. Each detail can
+/// be preserved for trusted peers, or transport codes can be made fully generic for untrusted
+/// ones. The redaction only applies to the CONNECTION_CLOSE frame on the wire; the original error
+/// is still reported locally with the `ConnectionClosed` event.
+///
+/// Application codes are always hidden in early (initial, handshake) packets.
+///
+/// ```rust
+/// use s2n_quic_core::connection::close::Redacted;
+///
+/// // only tell the peer that the connection was closed with an error
+/// let formatter = Redacted::new().with_transport_codes(false);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Redacted {
+    reasons: bool,
+    frame_types: bool,
+    transport_codes: bool,
+    internal_errors: bool,
+    crypto_alerts: bool,
+}
+
+impl Default for Redacted {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Redacted {
+    /// Creates a formatter which removes the same information as [`Production`]
+    pub const fn new() -> Self {
+        Self {
+            reasons: false,
+            frame_types: false,
+            transport_codes: true,
+            internal_errors: false,
+            crypto_alerts: false,
+        }
+    }
+
+    /// Sets whether the reasons of transport errors are sent to the peer
+    ///
+    /// Defaults to `false`.
+    #[must_use]
+    pub const fn with_reasons(mut self, enabled: bool) -> Self {
+        self.reasons = enabled;
+        self
+    }
+
+    /// Sets whether the types of the frames which caused transport errors are sent to the peer
+    ///
+    /// Defaults to `false`.
+    #[must_use]
+    pub const fn with_frame_types(mut self, enabled: bool) -> Self {
+        self.frame_types = enabled;
+        self
+    }
+
+    /// Sets whether the codes of transport errors are sent to the peer
+    ///
+    /// If disabled, every transport error other than NO_ERROR is sent as PROTOCOL_VIOLATION,
+    /// or HANDSHAKE_FAILURE for crypto (TLS) alerts. Defaults to `true`.
+    #[must_use]
+    pub const fn with_transport_codes(mut self, enabled: bool) -> Self {
+        self.transport_codes = enabled;
+        self
+    }
+
+    /// Sets whether INTERNAL_ERROR is sent to the peer
+    ///
+    /// If disabled, INTERNAL_ERROR is sent as PROTOCOL_VIOLATION, so peers can't tell internal
+    /// failures apart from protocol errors. Defaults to `false`.
+    #[must_use]
+    pub const fn with_internal_errors(mut self, enabled: bool) -> Self {
+        self.internal_errors = enabled;
+        self
+    }
+
+    /// Sets whether crypto (TLS) alerts are sent to the peer
+    ///
+    /// If disabled, all alerts are sent as HANDSHAKE_FAILURE. Defaults to `false`.
+    #[must_use]
+    pub const fn with_crypto_alerts(mut self, enabled: bool) -> Self {
+        self.crypto_alerts = enabled;
+        self
+    }
+
+    fn redact(&self, error: transport::Error) -> transport::Error {
+        let mut redacted = if error.code == transport::Error::NO_ERROR.code {
+            transport::Error::NO_ERROR
+        } else if error.try_into_crypto_error().is_some() {
+            //= https://www.rfc-editor.org/rfc/rfc9001#section-4.8
+            //# QUIC permits the use of a generic code in place of a specific error
+            //# code; see Section 11 of [QUIC-TRANSPORT].  For TLS alerts, this
+            //# includes replacing any alert with a generic alert, such as
+            //# handshake_failure (0x0128 in QUIC).  Endpoints MAY use a generic
+            //# error code to avoid possibly exposing confidential information.
+            if self.transport_codes && self.crypto_alerts {
+                transport::Error::new(error.code.as_varint())
+            } else {
+                crypto::CryptoError::HANDSHAKE_FAILURE.into()
+            }
+        } else if !self.transport_codes
+            || (!self.internal_errors && error.code == transport::Error::INTERNAL_ERROR.code)
+        {
+            transport::Error::PROTOCOL_VIOLATION
+        } else {
+            transport::Error::new(error.code.as_varint())
+        };
+
+        if self.reasons {
+            redacted = redacted.with_reason(error.reason);
         }
 
-        //= https://www.rfc-editor.org/rfc/rfc9001#section-4.8
-        //# QUIC permits the use of a generic code in place of a specific error
-        //# code; see Section 11 of [QUIC-TRANSPORT].  For TLS alerts, this
-        //# includes replacing any alert with a generic alert, such as
-        //# handshake_failure (0x0128 in QUIC).  Endpoints MAY use a generic
-        //# error code to avoid possibly exposing confidential information.
-        if error.try_into_crypto_error().is_some() {
-            return transport::Error::from(crypto::CryptoError::HANDSHAKE_FAILURE).into();
+        if self.frame_types {
+            redacted = redacted.with_frame_type(error.frame_type);
         }
 
-        // only preserve the error code
-        transport::Error::new(error.code.as_varint()).into()
+        redacted
+    }
+}
+
+impl Formatter for Redacted {
+    fn format_transport_error(
+        &self,
+        _context: &Context,
+        error: transport::Error,
+    ) -> ConnectionClose {
+        self.redact(error).into()
     }
 
     fn format_application_error(
@@ -137,7 +283,7 @@ impl Formatter for Production {
         context: &Context,
         error: transport::Error,
     ) -> ConnectionClose {
-        Self.format_transport_error(context, error)
+        self.format_transport_error(context, error)
     }
 
     fn format_early_application_error(
@@ -158,3 +304,113 @@ impl Formatter for Production {
         transport::Error::APPLICATION_ERROR.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::varint::VarInt;
+
+    const FLOW_CONTROL_ERROR: transport::Error = transport::Error::FLOW_CONTROL_ERROR
+        .with_reason("stream data exceeded the limit")
+        .with_frame_type(VarInt::from_u8(0x08));
+
+    fn format(formatter: &dyn Formatter, error: transport::Error) -> ConnectionClose<'static> {
+        let remote_address = SocketAddress::default();
+        let context = Context::new(&remote_address);
+        let frame = formatter.format_transport_error(&context, error);
+        let early_frame = formatter.format_early_transport_error(&context, error);
+        assert_eq!(frame, early_frame);
+        assert!(frame.reason.is_none());
+        ConnectionClose {
+            error_code: frame.error_code,
+            frame_type: frame.frame_type,
+            reason: None,
+        }
+    }
+
+    fn expected(error: transport::Error) -> ConnectionClose<'static> {
+        transport::Error::new(error.code.as_varint()).into()
+    }
+
+    #[test]
+    fn production_test() {
+        let internal_error = transport::Error::INTERNAL_ERROR.with_reason("invalid state");
+        let crypto_error = transport::Error::from(crypto::CryptoError::BAD_CERTIFICATE);
+
+        for formatter in [&Production as &dyn Formatter, &Redacted::new()] {
+            assert_eq!(
+                format(formatter, internal_error),
+                expected(transport::Error::PROTOCOL_VIOLATION)
+            );
+            assert_eq!(
+                format(formatter, crypto_error),
+                expected(crypto::CryptoError::HANDSHAKE_FAILURE.into())
+            );
+            assert_eq!(
+                format(formatter, FLOW_CONTROL_ERROR),
+                expected(transport::Error::FLOW_CONTROL_ERROR)
+            );
+        }
+    }
+
+    #[test]
+    fn preserve_test() {
+        let formatter = Redacted::new()
+            .with_reasons(true)
+            .with_frame_types(true)
+            .with_internal_errors(true)
+            .with_crypto_alerts(true);
+        let remote_address = SocketAddress::default();
+        let context = Context::new(&remote_address);
+
+        let errors = [
+            transport::Error::INTERNAL_ERROR.with_reason("invalid state"),
+            crypto::CryptoError::BAD_CERTIFICATE.into(),
+            FLOW_CONTROL_ERROR,
+        ];
+
+        for error in errors {
+            assert_eq!(
+                formatter.format_transport_error(&context, error),
+                ConnectionClose::from(error)
+            );
+        }
+    }
+
+    #[test]
+    fn generic_transport_codes_test() {
+        let formatter = Redacted::new()
+            .with_transport_codes(false)
+            .with_crypto_alerts(true);
+
+        assert_eq!(
+            format(&formatter, FLOW_CONTROL_ERROR),
+            expected(transport::Error::PROTOCOL_VIOLATION)
+        );
+        assert_eq!(
+            format(&formatter, crypto::CryptoError::BAD_CERTIFICATE.into()),
+            expected(crypto::CryptoError::HANDSHAKE_FAILURE.into())
+        );
+        assert_eq!(
+            format(&formatter, transport::Error::NO_ERROR),
+            expected(transport::Error::NO_ERROR)
+        );
+    }
+
+    #[test]
+    fn application_error_test() {
+        let formatter = Redacted::new().with_reasons(true);
+        let remote_address = SocketAddress::default();
+        let context = Context::new(&remote_address);
+        let error = application::Error::new(42).unwrap();
+
+        assert_eq!(
+            formatter.format_application_error(&context, error),
+            ConnectionClose::from(error)
+        );
+        assert_eq!(
+            formatter.format_early_application_error(&context, error),
+            ConnectionClose::from(transport::Error::APPLICATION_ERROR)
+        );
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::random;
+use core::fmt;
+use s2n_codec::{decoder_value, Encoder, EncoderValue};
+
+/// Identifies a connection in telemetry
+///
+/// Every event of a connection carries its correlation ID. Unlike the internal connection ID,
+/// which is only unique to an endpoint, the correlation ID is either random or provided by the
+/// application, which allows joining the events of a connection with the traces of the
+/// application. It has the same size and text format as a W3C Trace Context trace-id.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CorrelationId([u8; CorrelationId::LEN]);
+
+impl CorrelationId {
+    /// The length of a correlation ID in bytes
+    pub const LEN: usize = 16;
+
+    /// Creates a correlation ID from its bytes
+    #[inline]
+    pub const fn new(bytes: [u8; Self::LEN]) -> Self {
+        Self(bytes)
+    }
+
+    /// Generates a random correlation ID
+    #[inline]
+    pub fn random<R: random::Generator + ?Sized>(generator: &mut R) -> Self {
+        let mut bytes = [0; Self::LEN];
+        generator.private_random_fill(&mut bytes);
+        Self(bytes)
+    }
+
+    /// Returns the bytes of the correlation ID
+    #[inline]
+    pub const fn as_bytes(&self) -> &[u8; Self::LEN] {
+        &self.0
+    }
+
+    /// Returns the correlation ID as an integer
+    #[inline]
+    pub const fn as_u128(&self) -> u128 {
+        u128::from_be_bytes(self.0)
+    }
+}
+
+impl From<[u8; CorrelationId::LEN]> for CorrelationId {
+    #[inline]
+    fn from(bytes: [u8; CorrelationId::LEN]) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl From<u128> for CorrelationId {
+    #[inline]
+    fn from(value: u128) -> Self {
+        Self::new(value.to_be_bytes())
+    }
+}
+
+decoder_value!(
+    impl<'a> CorrelationId {
+        fn decode(buffer: Buffer) -> Result<Self> {
+            let (value, buffer) = buffer.decode_slice(CorrelationId::LEN)?;
+            let mut bytes = [0; CorrelationId::LEN];
+            bytes.copy_from_slice(value.into_less_safe_slice());
+            Ok((Self(bytes), buffer))
+        }
+    }
+);
+
+impl EncoderValue for CorrelationId {
+    #[inline]
+    fn encoding_size(&self) -> usize {
+        Self::LEN
+    }
+
+    #[inline]
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        encoder.write_slice(&self.0)
+    }
+}
+
+impl fmt::Display for CorrelationId {
+    /// Formats the correlation ID as 32 lowercase hex digits
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:032x}", self.as_u128())
+    }
+}
+
+impl fmt::Debug for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CorrelationId({})", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_test() {
+        let id = CorrelationId::from(0x4bf92f3577b34da6a3ce929d0e0e4736u128);
+        assert_eq!(id.to_string(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(id.as_bytes()[0], 0x4b);
+        assert_eq!(CorrelationId::from(1u128).to_string(), format!("{:032}", 1));
+    }
+
+    #[test]
+    fn random_test() {
+        let mut generator = random::testing::Generator::default();
+        let a = CorrelationId::random(&mut generator);
+        let b = CorrelationId::random(&mut generator);
+        assert_ne!(a, b);
+    }
+}
@@ -52,6 +52,8 @@ pub enum Error {
     /// The connection was closed because the local connection's idle timer expired
     #[non_exhaustive]
     IdleTimerExpired {
+        /// Indicates if any packets were received from the peer before the timer expired
+        peer_responded: bool,
         source: &'static panic::Location<'static>,
     },
 
@@ -71,6 +73,33 @@ pub enum Error {
     #[non_exhaustive]
     MaxHandshakeDurationExceeded {
         max_handshake_duration: Duration,
+        /// Indicates if any packets were received from the peer during the handshake
+        peer_responded: bool,
+        source: &'static panic::Location<'static>,
+    },
+
+    /// The handshake probe timer expired more often than the configured max handshake PTO count
+    #[non_exhaustive]
+    MaxHandshakePtoCountExceeded {
+        max_handshake_pto_count: u8,
+        /// Indicates if any packets were received from the peer during the handshake
+        peer_responded: bool,
+        source: &'static panic::Location<'static>,
+    },
+
+    /// The peer sent more bytes than the configured limit before its address was validated
+    #[non_exhaustive]
+    MaxUnvalidatedHandshakeBytesExceeded {
+        max_unvalidated_handshake_bytes: u64,
+        source: &'static panic::Location<'static>,
+    },
+
+    /// The peer doesn't support any of the QUIC versions supported by the local endpoint
+    ///
+    /// This is returned when a client receives a Version Negotiation packet which doesn't list
+    /// the version it selected.
+    #[non_exhaustive]
+    NoCompatibleVersion {
         source: &'static panic::Location<'static>,
     },
 
@@ -87,6 +116,12 @@ pub enum Error {
         source: &'static panic::Location<'static>,
     },
 
+    /// The connection state was exported and transferred to another endpoint
+    #[non_exhaustive]
+    Transferred {
+        source: &'static panic::Location<'static>,
+    },
+
     /// The connection was closed due to an unspecified reason
     #[non_exhaustive]
     Unspecified {
@@ -145,6 +180,21 @@ impl fmt::Display for Error {
                 "The connection was closed because the handshake took longer than the max handshake \
                 duration of {:?}", max_handshake_duration
             ),
+            Self::MaxHandshakePtoCountExceeded { max_handshake_pto_count, .. } => write!(
+                f,
+                "The connection was closed because the handshake probe timer expired more than \
+                {} times", max_handshake_pto_count
+            ),
+            Self::MaxUnvalidatedHandshakeBytesExceeded { max_unvalidated_handshake_bytes, .. } => write!(
+                f,
+                "The connection was closed because the peer sent more than {} bytes before its \
+                address was validated", max_unvalidated_handshake_bytes
+            ),
+            Self::NoCompatibleVersion { .. } => write!(
+                f,
+                "The connection was closed because the peer doesn't support any of the local QUIC \
+                versions"
+            ),
             Self::ImmediateClose { reason, .. } => write!(
                 f,
                 "The connection was closed due to: {}", reason
@@ -152,6 +202,9 @@ impl fmt::Display for Error {
             Self::EndpointClosing { .. } => {
                 write!(f, "The connection attempt was rejected because the endpoint is closing")
             }
+            Self::Transferred { .. } => {
+                write!(f, "The connection was transferred to another endpoint")
+            }
             Self::Unspecified { .. } => {
                 write!(f, "The connection was closed due to an unspecified reason")
             }
@@ -167,16 +220,111 @@ impl Error {
             Error::Transport { source, .. } => source,
             Error::Application { source, .. } => source,
             Error::StatelessReset { source } => source,
-            Error::IdleTimerExpired { source } => source,
+            Error::IdleTimerExpired { source, .. } => source,
             Error::NoValidPath { source } => source,
             Error::StreamIdExhausted { source } => source,
             Error::MaxHandshakeDurationExceeded { source, .. } => source,
+            Error::MaxHandshakePtoCountExceeded { source, .. } => source,
+            Error::MaxUnvalidatedHandshakeBytesExceeded { source, .. } => source,
+            Error::NoCompatibleVersion { source } => source,
             Error::ImmediateClose { source, .. } => source,
             Error::EndpointClosing { source } => source,
+            Error::Transferred { source } => source,
             Error::Unspecified { source } => source,
         }
     }
 
+    /// Returns the endpoint that closed the connection, if known
+    pub fn initiator(&self) -> Option<endpoint::Location> {
+        match self {
+            Error::Closed { initiator, .. } => Some(*initiator),
+            Error::Transport { initiator, .. } => Some(*initiator),
+            Error::Application { initiator, .. } => Some(*initiator),
+            Error::StatelessReset { .. } => Some(endpoint::Location::Remote),
+            Error::IdleTimerExpired { .. }
+            | Error::NoValidPath { .. }
+            | Error::StreamIdExhausted { .. }
+            | Error::MaxHandshakeDurationExceeded { .. }
+            | Error::MaxHandshakePtoCountExceeded { .. }
+            | Error::MaxUnvalidatedHandshakeBytesExceeded { .. }
+            | Error::NoCompatibleVersion { .. }
+            | Error::ImmediateClose { .. }
+            | Error::EndpointClosing { .. }
+            | Error::Transferred { .. } => Some(endpoint::Location::Local),
+            Error::Unspecified { .. } => None,
+        }
+    }
+
+    /// Returns the transport error code, if the connection was closed on the transport level
+    pub fn transport_error_code(&self) -> Option<transport::error::Code> {
+        if let Error::Transport { code, .. } = self {
+            Some(*code)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the type of the frame that triggered the transport error, if any
+    ///
+    /// A value of `0` indicates the frame type is unknown.
+    pub fn frame_type(&self) -> Option<u64> {
+        if let Error::Transport { frame_type, .. } = self {
+            Some(*frame_type)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the reason for closing the connection, if one was provided locally
+    ///
+    /// Reasons sent by the peer are discarded and are not returned.
+    pub fn reason(&self) -> Option<&'static str> {
+        match self {
+            Error::Transport { reason, .. } | Error::ImmediateClose { reason, .. }
+                if !reason.is_empty() =>
+            {
+                Some(reason)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the TLS alert description, if the connection was closed due to a TLS error
+    ///
+    /// TLS alerts are sent as transport errors in the CRYPTO_ERROR range of `0x100..=0x1ff`.
+    pub fn tls_alert(&self) -> Option<u8> {
+        let code = self.transport_error_code()?.as_u64();
+        if (0x100..=0x1ff).contains(&code) {
+            Some(code as u8)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the reason QUIC is likely blocked on the path to the peer, if the connection
+    /// failed because of it
+    ///
+    /// Applications can use this to fall back to a TCP-based protocol, e.g. HTTP/2, instead of
+    /// waiting for further QUIC connection attempts to fail.
+    pub fn quic_blocked(&self) -> Option<Blocked> {
+        match self {
+            Error::IdleTimerExpired {
+                peer_responded: false,
+                ..
+            }
+            | Error::MaxHandshakeDurationExceeded {
+                peer_responded: false,
+                ..
+            }
+            | Error::MaxHandshakePtoCountExceeded {
+                peer_responded: false,
+                ..
+            } => Some(Blocked::NoResponse),
+            Error::NoCompatibleVersion { .. } => Some(Blocked::NoCompatibleVersion),
+            _ => None,
+        }
+    }
+
     #[track_caller]
     fn from_transport_error(error: transport::Error, initiator: endpoint::Location) -> Self {
         let source = panic::Location::caller();
@@ -217,9 +365,12 @@ impl Error {
     #[inline]
     #[track_caller]
     #[doc(hidden)]
-    pub fn idle_timer_expired() -> Error {
+    pub fn idle_timer_expired(peer_responded: bool) -> Error {
         let source = panic::Location::caller();
-        Error::IdleTimerExpired { source }
+        Error::IdleTimerExpired {
+            peer_responded,
+            source,
+        }
     }
 
     #[inline]
@@ -249,14 +400,52 @@ impl Error {
     #[inline]
     #[track_caller]
     #[doc(hidden)]
-    pub fn max_handshake_duration_exceeded(max_handshake_duration: Duration) -> Error {
+    pub fn max_handshake_duration_exceeded(
+        max_handshake_duration: Duration,
+        peer_responded: bool,
+    ) -> Error {
         let source = panic::Location::caller();
         Error::MaxHandshakeDurationExceeded {
             max_handshake_duration,
+            peer_responded,
             source,
         }
     }
 
+    #[inline]
+    #[track_caller]
+    #[doc(hidden)]
+    pub fn max_handshake_pto_count_exceeded(
+        max_handshake_pto_count: u8,
+        peer_responded: bool,
+    ) -> Error {
+        let source = panic::Location::caller();
+        Error::MaxHandshakePtoCountExceeded {
+            max_handshake_pto_count,
+            peer_responded,
+            source,
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    #[doc(hidden)]
+    pub fn max_unvalidated_handshake_bytes_exceeded(max_unvalidated_handshake_bytes: u64) -> Error {
+        let source = panic::Location::caller();
+        Error::MaxUnvalidatedHandshakeBytesExceeded {
+            max_unvalidated_handshake_bytes,
+            source,
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    #[doc(hidden)]
+    pub fn no_compatible_version() -> Error {
+        let source = panic::Location::caller();
+        Error::NoCompatibleVersion { source }
+    }
+
     #[inline]
     #[track_caller]
     #[doc(hidden)]
@@ -277,6 +466,14 @@ impl Error {
         Error::EndpointClosing { source }
     }
 
+    #[inline]
+    #[track_caller]
+    #[doc(hidden)]
+    pub fn transferred() -> Error {
+        let source = panic::Location::caller();
+        Error::Transferred { source }
+    }
+
     #[inline]
     #[track_caller]
     #[doc(hidden)]
@@ -286,6 +483,21 @@ impl Error {
     }
 }
 
+/// The reason QUIC is likely blocked on the path to the peer
+///
+/// See [`Error::quic_blocked`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Blocked {
+    /// The connection attempt timed out before any packets were received from the peer
+    ///
+    /// This is usually caused by a firewall or middlebox on the path dropping UDP traffic, or
+    /// a peer which isn't listening for QUIC connections.
+    NoResponse,
+    /// The peer doesn't support any of the QUIC versions supported by the local endpoint
+    NoCompatibleVersion,
+}
+
 /// Returns a CONNECTION_CLOSE frame for the given connection Error, if any
 ///
 /// The first item will be a close frame for an early (initial, handshake) packet.
@@ -357,8 +569,16 @@ pub fn as_frame<'a, F: connection::close::Formatter>(
             Some((early, one_rtt))
         }
         Error::MaxHandshakeDurationExceeded { .. } => None,
+        Error::MaxHandshakePtoCountExceeded { .. } => None,
+        // The peer's address hasn't been validated so nothing is sent to it
+        Error::MaxUnvalidatedHandshakeBytesExceeded { .. } => None,
+        // The peer doesn't support the version of the connection so it can't process a
+        // CONNECTION_CLOSE frame
+        Error::NoCompatibleVersion { .. } => None,
         Error::ImmediateClose { .. } => None,
         Error::EndpointClosing { .. } => None,
+        // The connection continues on another endpoint so the peer is not notified
+        Error::Transferred { .. } => None,
         Error::Unspecified { .. } => {
             let error =
                 transport::Error::INTERNAL_ERROR.with_reason("an unspecified error occurred");
@@ -442,8 +662,12 @@ impl From<Error> for std::io::ErrorKind {
             Error::NoValidPath { .. } => ErrorKind::Other,
             Error::StreamIdExhausted { .. } => ErrorKind::Other,
             Error::MaxHandshakeDurationExceeded { .. } => ErrorKind::TimedOut,
+            Error::MaxHandshakePtoCountExceeded { .. } => ErrorKind::TimedOut,
+            Error::MaxUnvalidatedHandshakeBytesExceeded { .. } => ErrorKind::ConnectionRefused,
+            Error::NoCompatibleVersion { .. } => ErrorKind::ConnectionRefused,
             Error::ImmediateClose { .. } => ErrorKind::Other,
             Error::EndpointClosing { .. } => ErrorKind::Other,
+            Error::Transferred { .. } => ErrorKind::Other,
             Error::Unspecified { .. } => ErrorKind::Other,
         }
     }
@@ -484,3 +708,101 @@ impl From<CryptoError> for ProcessingError {
         ProcessingError::CryptoError(inner_error)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::varint::VarInt;
+
+    #[test]
+    fn transport_error_introspection_test() {
+        let error: Error = transport::Error::PROTOCOL_VIOLATION
+            .with_reason("invalid frame")
+            .with_frame_type(VarInt::from_u8(0x19))
+            .into();
+
+        assert_eq!(error.initiator(), Some(endpoint::Location::Local));
+        assert_eq!(
+            error.transport_error_code(),
+            Some(transport::Error::PROTOCOL_VIOLATION.code)
+        );
+        assert_eq!(error.frame_type(), Some(0x19));
+        assert_eq!(error.reason(), Some("invalid frame"));
+        assert_eq!(error.tls_alert(), None);
+    }
+
+    #[test]
+    fn tls_alert_introspection_test() {
+        let error: Error = CryptoError::HANDSHAKE_FAILURE.into();
+
+        assert_eq!(error.tls_alert(), Some(CryptoError::HANDSHAKE_FAILURE.code));
+        assert_eq!(error.transport_error_code().unwrap().as_u64(), 0x100 + 40);
+    }
+
+    #[test]
+    fn remote_error_introspection_test() {
+        let frame = ConnectionClose {
+            error_code: VarInt::from_u8(0x0a),
+            frame_type: Some(VarInt::from_u8(0x06)),
+            reason: Some(b"peer reason"),
+        };
+        let error: Error = frame.into();
+
+        assert_eq!(error.initiator(), Some(endpoint::Location::Remote));
+        assert_eq!(
+            error.transport_error_code(),
+            Some(transport::Error::PROTOCOL_VIOLATION.code)
+        );
+        assert_eq!(error.frame_type(), Some(0x06));
+        // remote reasons are not retained
+        assert_eq!(error.reason(), None);
+
+        let error = Error::application(VarInt::from_u8(1).into());
+        assert_eq!(error.initiator(), Some(endpoint::Location::Local));
+        assert_eq!(error.transport_error_code(), None);
+        assert_eq!(error.frame_type(), None);
+
+        assert_eq!(
+            Error::stateless_reset().initiator(),
+            Some(endpoint::Location::Remote)
+        );
+        assert_eq!(Error::unspecified().initiator(), None);
+    }
+
+    #[test]
+    fn quic_blocked_test() {
+        let duration = Duration::from_secs(10);
+
+        assert_eq!(
+            Error::idle_timer_expired(false).quic_blocked(),
+            Some(Blocked::NoResponse)
+        );
+        assert_eq!(
+            Error::max_handshake_duration_exceeded(duration, false).quic_blocked(),
+            Some(Blocked::NoResponse)
+        );
+        assert_eq!(
+            Error::max_handshake_pto_count_exceeded(3, false).quic_blocked(),
+            Some(Blocked::NoResponse)
+        );
+        assert_eq!(
+            Error::no_compatible_version().quic_blocked(),
+            Some(Blocked::NoCompatibleVersion)
+        );
+
+        // the peer is reachable over QUIC if it responded
+        assert_eq!(Error::idle_timer_expired(true).quic_blocked(), None);
+        assert_eq!(
+            Error::max_handshake_duration_exceeded(duration, true).quic_blocked(),
+            None
+        );
+        assert_eq!(
+            Error::max_handshake_pto_count_exceeded(3, true).quic_blocked(),
+            None
+        );
+        assert_eq!(
+            Error::closed(endpoint::Location::Remote).quic_blocked(),
+            None
+        );
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Application data attached to a connection
+//!
+//! [`Extensions`] stores at most one value of each type, which allows independent parts of an
+//! application, e.g. authentication and routing, to attach their own state to a connection
+//! without maintaining separate maps keyed by the connection.
+
+use alloc::{boxed::Box, collections::BTreeMap};
+use core::{
+    any::{Any, TypeId},
+    fmt,
+};
+
+/// A map of values attached to a connection, keyed by their type
+#[derive(Default)]
+pub struct Extensions {
+    map: BTreeMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.map.len())
+            .finish()
+    }
+}
+
+impl Extensions {
+    /// Creates an empty map
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a value into the map
+    ///
+    /// If a value of the same type was already present, it is returned.
+    #[inline]
+    pub fn insert<T: 'static + Send + Sync>(&mut self, value: T) -> Option<T> {
+        self.map
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(downcast)
+    }
+
+    /// Returns a reference to the value of type `T`, if present
+    #[inline]
+    pub fn get<T: 'static + Send + Sync>(&self) -> Option<&T> {
+        self.map.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    /// Returns a mutable reference to the value of type `T`, if present
+    #[inline]
+    pub fn get_mut<T: 'static + Send + Sync>(&mut self) -> Option<&mut T> {
+        self.map.get_mut(&TypeId::of::<T>())?.downcast_mut()
+    }
+
+    /// Returns a mutable reference to the value of type `T`, inserting the value returned by `f`
+    /// if it isn't present
+    #[inline]
+    pub fn get_or_insert_with<T: 'static + Send + Sync, F: FnOnce() -> T>(
+        &mut self,
+        f: F,
+    ) -> &mut T {
+        self.map
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(f()))
+            .downcast_mut()
+            .expect("values are keyed by their type")
+    }
+
+    /// Removes the value of type `T` from the map and returns it, if present
+    #[inline]
+    pub fn remove<T: 'static + Send + Sync>(&mut self) -> Option<T> {
+        self.map.remove(&TypeId::of::<T>()).and_then(downcast)
+    }
+
+    /// Returns `true` if the map contains a value of type `T`
+    #[inline]
+    pub fn contains<T: 'static + Send + Sync>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Returns the number of values in the map
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the map doesn't contain any values
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Removes all of the values from the map
+    #[inline]
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+}
+
+#[inline]
+fn downcast<T: 'static>(value: Box<dyn Any + Send + Sync>) -> Option<T> {
+    let value: Box<dyn Any> = value;
+    value.downcast().ok().map(|value| *value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::{String, ToString};
+
+    #[derive(Debug, PartialEq)]
+    struct TenantId(u64);
+
+    #[test]
+    fn extensions_test() {
+        let mut extensions = Extensions::new();
+        assert!(extensions.is_empty());
+        assert_eq!(extensions.get::<TenantId>(), None);
+
+        assert_eq!(extensions.insert(TenantId(1)), None);
+        assert_eq!(extensions.insert("user".to_string()), None);
+        assert_eq!(extensions.len(), 2);
+
+        // values are keyed by their type
+        assert_eq!(extensions.get::<TenantId>(), Some(&TenantId(1)));
+        assert_eq!(extensions.get::<String>().unwrap(), "user");
+        assert!(!extensions.contains::<u64>());
+
+        assert_eq!(extensions.insert(TenantId(2)), Some(TenantId(1)));
+        extensions.get_mut::<TenantId>().unwrap().0 += 1;
+        assert_eq!(extensions.get::<TenantId>(), Some(&TenantId(3)));
+
+        *extensions.get_or_insert_with(|| 0u64) += 5;
+        *extensions.get_or_insert_with(|| 0u64) += 5;
+        assert_eq!(extensions.get::<u64>(), Some(&10));
+
+        assert_eq!(extensions.remove::<TenantId>(), Some(TenantId(3)));
+        assert_eq!(extensions.remove::<TenantId>(), None);
+        assert_eq!(extensions.len(), 2);
+
+        extensions.clear();
+        assert!(extensions.is_empty());
+    }
+}
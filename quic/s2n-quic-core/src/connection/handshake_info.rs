@@ -0,0 +1,97 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{application::ServerName, inet::SocketAddress};
+use bytes::Bytes;
+use core::time::Duration;
+
+/// Metadata about the handshake of a connection
+///
+/// Servers receive the metadata along with each accepted connection, so they don't need to query
+/// the values from the connection one at a time.
+#[derive(Clone, Debug)]
+pub struct HandshakeInfo {
+    application_protocol: Bytes,
+    server_name: Option<ServerName>,
+    remote_address: SocketAddress,
+    token: Option<Bytes>,
+    resumed: bool,
+    handshake_duration: Option<Duration>,
+    smoothed_rtt: Duration,
+}
+
+impl HandshakeInfo {
+    #[inline]
+    #[doc(hidden)]
+    pub fn new(
+        application_protocol: Bytes,
+        server_name: Option<ServerName>,
+        remote_address: SocketAddress,
+        token: Option<Bytes>,
+        resumed: bool,
+        handshake_duration: Option<Duration>,
+        smoothed_rtt: Duration,
+    ) -> Self {
+        Self {
+            application_protocol,
+            server_name,
+            remote_address,
+            token,
+            resumed,
+            handshake_duration,
+            smoothed_rtt,
+        }
+    }
+
+    /// Returns the application protocol which was negotiated with ALPN
+    #[inline]
+    pub fn application_protocol(&self) -> &Bytes {
+        &self.application_protocol
+    }
+
+    /// Returns the server name which was sent by the client with SNI, if any
+    #[inline]
+    pub fn server_name(&self) -> Option<&ServerName> {
+        self.server_name.as_ref()
+    }
+
+    /// Returns the address of the peer on the active path
+    #[inline]
+    pub fn remote_address(&self) -> SocketAddress {
+        self.remote_address
+    }
+
+    /// Returns the address validation token the client sent in its first Initial packet, if any
+    ///
+    /// The token was issued by the server in a Retry packet or a NEW_TOKEN frame.
+    #[inline]
+    pub fn token(&self) -> Option<&[u8]> {
+        self.token.as_deref()
+    }
+
+    /// Returns `true` if the TLS session was resumed with 0-RTT keys
+    ///
+    /// TLS providers only report resumptions which install 0-RTT keys, so sessions which were
+    /// resumed without early data return `false`.
+    #[inline]
+    pub fn is_resumed(&self) -> bool {
+        self.resumed
+    }
+
+    /// Returns the time between receiving the first packet of the connection and completing the
+    /// handshake
+    ///
+    /// Connections which were imported from another endpoint completed their handshake on that
+    /// endpoint, so `None` is returned for them.
+    #[inline]
+    pub fn handshake_duration(&self) -> Option<Duration> {
+        self.handshake_duration
+    }
+
+    /// Returns the smoothed round-trip time of the active path at the time the metadata was
+    /// captured
+    #[inline]
+    pub fn smoothed_rtt(&self) -> Duration {
+        self.smoothed_rtt
+    }
+}
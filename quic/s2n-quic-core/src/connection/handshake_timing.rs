@@ -0,0 +1,73 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::event::builder::HandshakePhase;
+use core::time::Duration;
+
+/// The times at which a connection reached each phase of the handshake
+///
+/// Each value is the time elapsed since the connection was created, or `None` if the phase
+/// wasn't reached yet. Connections which were imported from another endpoint don't record any
+/// phases, since the handshake was completed by the exporting endpoint.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct HandshakeTiming {
+    /// The first Initial packet was sent
+    pub initial_sent: Option<Duration>,
+    /// The first Initial packet was received from the peer
+    pub initial_received: Option<Duration>,
+    /// The first Handshake packet was sent
+    pub handshake_sent: Option<Duration>,
+    /// The first Handshake packet was received from the peer
+    pub handshake_received: Option<Duration>,
+    /// The handshake was confirmed
+    pub handshake_confirmed: Option<Duration>,
+    /// The first byte of stream data was received from the peer
+    pub application_data_received: Option<Duration>,
+}
+
+impl HandshakeTiming {
+    /// Records the time at which the phase was reached
+    ///
+    /// Returns `true` if the phase was reached for the first time.
+    #[inline]
+    #[doc(hidden)]
+    pub fn on_phase(&mut self, phase: &HandshakePhase, elapsed: Duration) -> bool {
+        let slot = match phase {
+            HandshakePhase::InitialSent => &mut self.initial_sent,
+            HandshakePhase::InitialReceived => &mut self.initial_received,
+            HandshakePhase::HandshakeSent => &mut self.handshake_sent,
+            HandshakePhase::HandshakeReceived => &mut self.handshake_received,
+            HandshakePhase::HandshakeConfirmed => &mut self.handshake_confirmed,
+            HandshakePhase::ApplicationDataReceived => &mut self.application_data_received,
+        };
+
+        if slot.is_some() {
+            return false;
+        }
+
+        *slot = Some(elapsed);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_phase_test() {
+        let mut timing = HandshakeTiming::default();
+
+        assert!(timing.on_phase(&HandshakePhase::InitialSent, Duration::from_millis(1)));
+        assert!(!timing.on_phase(&HandshakePhase::InitialSent, Duration::from_millis(2)));
+        assert!(timing.on_phase(
+            &HandshakePhase::HandshakeConfirmed,
+            Duration::from_millis(3)
+        ));
+
+        assert_eq!(timing.initial_sent, Some(Duration::from_millis(1)));
+        assert_eq!(timing.handshake_confirmed, Some(Duration::from_millis(3)));
+        assert_eq!(timing.initial_received, None);
+    }
+}
@@ -5,7 +5,7 @@
 
 use crate::{
     event::{api::SocketAddress, IntoEvent},
-    inet, transport,
+    inet, random, transport,
 };
 use core::{
     convert::{TryFrom, TryInto},
@@ -16,6 +16,8 @@ use s2n_codec::{decoder_value, Encoder, EncoderValue};
 #[cfg(any(test, feature = "generator"))]
 use bolero_generator::*;
 
+pub mod shard;
+
 //= https://www.rfc-editor.org/rfc/rfc9000#section-5.1
 //# Each connection possesses a set of connection identifiers, or
 //# connection IDs, each of which can identify the connection.
@@ -25,6 +27,15 @@ use bolero_generator::*;
 /// The maximum size of a connection ID.
 pub const MAX_LEN: usize = crate::packet::long::DESTINATION_CONNECTION_ID_MAX_LEN;
 
+//= https://www.rfc-editor.org/rfc/rfc9000#section-5.1
+//# A zero-length connection ID can be used when a connection ID is not
+//# needed to route to the correct endpoint.
+/// The minimum length of a non-empty connection ID generated by an endpoint.
+///
+/// Only clients may use zero-length connection IDs, since servers rely on connection IDs to route
+/// packets after the peer migrates.
+pub const MIN_NON_EMPTY_LOCAL_LEN: usize = 4;
+
 /// The minimum lifetime of a connection ID.
 pub const MIN_LIFETIME: Duration = Duration::from_secs(60);
 
@@ -185,9 +196,9 @@ macro_rules! id {
 }
 
 // Connection IDs that are generated locally and used to route packets from the peer to the local
-// endpoint. s2n-QUIC does not provide zero-length connection IDs, the minimum allowable LocalId
-// is 4 bytes.
-id!(LocalId, 4);
+// endpoint. Clients may use zero-length connection IDs, in which case packets are routed by the
+// remote address. Otherwise, the minimum allowable LocalId is `MIN_NON_EMPTY_LOCAL_LEN` bytes.
+id!(LocalId, 0);
 
 // Connection IDs used to route packets to the peer. The peer may choose to use zero-length
 // connection IDs.
@@ -256,6 +267,7 @@ impl TryFrom<LocalId> for InitialId {
 pub enum Error {
     InvalidLength,
     InvalidLifetime,
+    Unroutable,
 }
 
 impl Error {
@@ -263,6 +275,7 @@ impl Error {
         match self {
             Error::InvalidLength => "invalid connection id length",
             Error::InvalidLifetime => "invalid connection id lifetime",
+            Error::Unroutable => "connection ids can't be routed to every shard",
         }
     }
 }
@@ -273,6 +286,9 @@ impl core::fmt::Display for Error {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
 impl From<Error> for transport::Error {
     #[inline]
     fn from(error: Error) -> Self {
@@ -344,6 +360,21 @@ pub trait Generator {
     /// otherwise the endpoint may terminate.
     fn generate(&mut self, connection_info: &ConnectionInfo) -> LocalId;
 
+    /// Generates a connection ID with the random generator of the endpoint.
+    ///
+    /// Formats which generate random connection IDs should draw the random
+    /// bytes from `random_generator`, so a seeded random provider also makes
+    /// the connection IDs deterministic. By default, `generate` is called.
+    #[inline]
+    fn generate_with_random(
+        &mut self,
+        connection_info: &ConnectionInfo,
+        random_generator: &mut dyn random::Generator,
+    ) -> LocalId {
+        let _ = random_generator;
+        self.generate(connection_info)
+    }
+
     /// The maximum amount of time each generated connection ID should be
     /// used for. By default there is no maximum, though connection IDs
     /// may be retired due to rotation requirements or peer requests.
@@ -407,8 +438,8 @@ mod tests {
         let connection_id_bytes = [0u8; InitialId::MIN_LEN];
         assert!(InitialId::try_from_bytes(&connection_id_bytes).is_some());
 
-        let connection_id_bytes = [0u8; LocalId::MIN_LEN - 1];
-        assert!(LocalId::try_from_bytes(&connection_id_bytes).is_none());
+        // zero-length connection IDs are routed by remote address
+        assert!(LocalId::try_from_bytes(&[]).is_some());
 
         let connection_id_bytes = [0u8; InitialId::MIN_LEN - 1];
         assert!(InitialId::try_from_bytes(&connection_id_bytes).is_none());
@@ -0,0 +1,302 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Partitions connections across multiple endpoints by connection ID
+//!
+//! Each datagram is assigned to a shard by hashing the first [`KEY_LEN`] bytes of its
+//! destination connection ID. Since the connection IDs chosen by clients in their first Initial
+//! packets are random, new connections are spread evenly across the shards. Each shard then
+//! wraps its connection ID format with [`Format`], which only issues connection IDs that hash to
+//! the shard, so all of the packets of a connection are routed to the same shard, even if the
+//! peer migrates to a new address.
+
+use crate::{
+    connection::id::{self, ConnectionInfo, LocalId},
+    random,
+};
+use core::time::Duration;
+
+/// The number of connection ID bytes used to select a shard
+///
+/// This is the minimum length of a non-empty [`LocalId`], which means the length of the
+/// connection ID doesn't need to be known to route packets with short headers.
+pub const KEY_LEN: usize = id::MIN_NON_EMPTY_LOCAL_LEN;
+
+/// The maximum number of shards
+pub const MAX_SHARDS: usize = 256;
+
+/// The number of connection IDs generated by the inner format before giving up on
+/// finding one for the shard
+const MAX_ATTEMPTS: usize = MAX_SHARDS * 64;
+
+/// Returns the shard for a connection ID
+#[inline]
+pub fn index(connection_id: &[u8], shards: usize) -> usize {
+    debug_assert!((1..=MAX_SHARDS).contains(&shards));
+
+    let key = &connection_id[..connection_id.len().min(KEY_LEN)];
+
+    // FNV-1a
+    let mut hash: u32 = 0x811c_9dc5;
+    for byte in key {
+        hash ^= *byte as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+
+    hash as usize % shards
+}
+
+/// Returns the shard for a datagram based on the destination connection ID of its first packet
+///
+/// Datagrams which are too short to contain a destination connection ID are assigned to the
+/// first shard.
+#[inline]
+pub fn index_for_datagram(payload: &[u8], shards: usize) -> usize {
+    let connection_id = match payload.first() {
+        // long header: the destination connection ID is prefixed with its length
+        Some(tag) if tag & 0x80 != 0 => payload
+            .get(5)
+            .and_then(|len| payload.get(6..6 + *len as usize)),
+        // short header: the destination connection ID immediately follows the tag
+        Some(_) => payload.get(1..),
+        None => None,
+    };
+
+    connection_id.map_or(0, |connection_id| index(connection_id, shards))
+}
+
+/// Checks that the connection IDs of `generator` can be routed to each of the `shards`
+///
+/// Returns [`id::Error::InvalidLength`] if the generated connection IDs are shorter than
+/// [`KEY_LEN`], which includes zero-length connection IDs, and [`id::Error::Unroutable`] if no
+/// connection ID was generated for one of the shards, e.g. because the first [`KEY_LEN`] bytes
+/// of the connection IDs are fixed.
+pub fn validate<G: id::Generator>(generator: &mut G, shards: usize) -> Result<(), id::Error> {
+    debug_assert!((1..=MAX_SHARDS).contains(&shards));
+
+    let remote_address = crate::inet::SocketAddress::default();
+    let connection_info = ConnectionInfo::new(&remote_address);
+
+    let mut routed = [false; MAX_SHARDS];
+    let mut remaining = shards;
+
+    for _ in 0..MAX_ATTEMPTS {
+        let id = generator.generate(&connection_info);
+        if id.len() < KEY_LEN {
+            return Err(id::Error::InvalidLength);
+        }
+
+        let shard = index(id.as_bytes(), shards);
+        if !core::mem::replace(&mut routed[shard], true) {
+            remaining -= 1;
+            if remaining == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    Err(id::Error::Unroutable)
+}
+
+/// A connection ID format which only generates connection IDs for a single shard
+///
+/// The inner format must generate connection IDs with unpredictable first [`KEY_LEN`] bytes,
+/// since new connection IDs are generated until one belongs to the shard.
+#[derive(Clone, Debug)]
+pub struct Format<F> {
+    inner: F,
+    shard: usize,
+    shards: usize,
+}
+
+impl<F> Format<F> {
+    /// Creates a format for the `shard` of `shards`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0 or greater than [`MAX_SHARDS`], or if `shard` is not less than
+    /// `shards`.
+    pub fn new(inner: F, shard: usize, shards: usize) -> Self {
+        assert!(
+            (1..=MAX_SHARDS).contains(&shards),
+            "the number of shards must be between 1 and {}",
+            MAX_SHARDS
+        );
+        assert!(
+            shard < shards,
+            "the shard must be less than the number of shards"
+        );
+
+        Self {
+            inner,
+            shard,
+            shards,
+        }
+    }
+
+    /// Returns the shard the connection IDs are generated for
+    pub fn shard(&self) -> usize {
+        self.shard
+    }
+}
+
+impl<F: id::Validator> id::Validator for Format<F> {
+    #[inline]
+    fn validate(&self, connection_info: &ConnectionInfo, buffer: &[u8]) -> Option<usize> {
+        self.inner.validate(connection_info, buffer)
+    }
+}
+
+impl<F: id::Generator> Format<F> {
+    fn generate_with(&mut self, mut generate: impl FnMut(&mut F) -> LocalId) -> LocalId {
+        for _ in 0..MAX_ATTEMPTS {
+            let id = generate(&mut self.inner);
+            if index(id.as_bytes(), self.shards) == self.shard {
+                return id;
+            }
+        }
+
+        panic!("the connection ID format does not generate connection IDs for every shard");
+    }
+}
+
+impl<F: id::Generator> id::Generator for Format<F> {
+    fn generate(&mut self, connection_info: &ConnectionInfo) -> LocalId {
+        self.generate_with(|inner| inner.generate(connection_info))
+    }
+
+    fn generate_with_random(
+        &mut self,
+        connection_info: &ConnectionInfo,
+        random_generator: &mut dyn random::Generator,
+    ) -> LocalId {
+        self.generate_with(|inner| inner.generate_with_random(connection_info, random_generator))
+    }
+
+    #[inline]
+    fn lifetime(&self) -> Option<Duration> {
+        self.inner.lifetime()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        connection::id::{Generator, Validator},
+        inet::SocketAddress,
+    };
+    use core::convert::TryInto;
+
+    /// Generates connection IDs from a linear congruential generator
+    #[derive(Debug, Default)]
+    struct TestFormat(u64);
+
+    impl Validator for TestFormat {
+        fn validate(&self, _connection_info: &ConnectionInfo, _buffer: &[u8]) -> Option<usize> {
+            Some(core::mem::size_of::<u64>())
+        }
+    }
+
+    impl Generator for TestFormat {
+        fn generate(&mut self, _connection_info: &ConnectionInfo) -> LocalId {
+            self.0 = self
+                .0
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (&self.0.to_be_bytes()[..]).try_into().unwrap()
+        }
+    }
+
+    #[test]
+    fn generate_test() {
+        let shards = 7;
+        let remote_address = SocketAddress::default();
+        let connection_info = ConnectionInfo::new(&remote_address);
+
+        for shard in 0..shards {
+            let mut format = Format::new(TestFormat::default(), shard, shards);
+            for _ in 0..10 {
+                let id = format.generate(&connection_info);
+                assert_eq!(index(id.as_bytes(), shards), shard);
+                assert_eq!(format.validate(&connection_info, id.as_bytes()), Some(8));
+            }
+        }
+    }
+
+    #[test]
+    fn datagram_test() {
+        let shards = 16;
+        let id: LocalId = (&[1, 2, 3, 4, 5, 6, 7, 8][..]).try_into().unwrap();
+        let expected = index(id.as_bytes(), shards);
+
+        // short header
+        let mut short = vec![0x40];
+        short.extend_from_slice(id.as_bytes());
+        short.extend_from_slice(&[0; 20]);
+        assert_eq!(index_for_datagram(&short, shards), expected);
+
+        // long header
+        let mut long = vec![0xc0, 0, 0, 0, 1, id.len() as u8];
+        long.extend_from_slice(id.as_bytes());
+        long.extend_from_slice(&[0; 20]);
+        assert_eq!(index_for_datagram(&long, shards), expected);
+
+        // truncated packets
+        assert_eq!(index_for_datagram(&[], shards), 0);
+        assert_eq!(index_for_datagram(&long[..4], shards), 0);
+        assert_eq!(index_for_datagram(&long[..8], shards), 0);
+    }
+
+    #[test]
+    fn validate_test() {
+        for shards in [1, 7, MAX_SHARDS] {
+            assert_eq!(validate(&mut TestFormat::default(), shards), Ok(()));
+        }
+
+        /// Generates connection IDs with a fixed prefix
+        struct PrefixFormat(TestFormat, usize);
+
+        impl Generator for PrefixFormat {
+            fn generate(&mut self, connection_info: &ConnectionInfo) -> LocalId {
+                let mut id = [0; 8];
+                id[self.1..]
+                    .copy_from_slice(&self.0.generate(connection_info).as_bytes()[self.1..]);
+                (&id[..]).try_into().unwrap()
+            }
+        }
+
+        // the last bytes of the shard key are random
+        assert_eq!(
+            validate(&mut PrefixFormat(TestFormat::default(), 2), 16),
+            Ok(())
+        );
+        // the whole shard key is fixed
+        assert_eq!(
+            validate(&mut PrefixFormat(TestFormat::default(), KEY_LEN), 16),
+            Err(id::Error::Unroutable)
+        );
+        // a single shard can still be used with a fixed key
+        assert_eq!(
+            validate(&mut PrefixFormat(TestFormat::default(), KEY_LEN), 1),
+            Ok(())
+        );
+
+        /// Generates zero-length connection IDs
+        struct EmptyFormat;
+
+        impl Generator for EmptyFormat {
+            fn generate(&mut self, _connection_info: &ConnectionInfo) -> LocalId {
+                LocalId::try_from_bytes(&[]).unwrap()
+            }
+        }
+
+        assert_eq!(validate(&mut EmptyFormat, 1), Err(id::Error::InvalidLength));
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_shard_test() {
+        let _ = Format::new(TestFormat::default(), 2, 2);
+    }
+}
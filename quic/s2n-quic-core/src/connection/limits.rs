@@ -4,20 +4,74 @@
 use crate::{
     ack,
     event::{api::SocketAddress, IntoEvent},
-    inet, stream,
+    inet, path, recovery, stream,
     transport::parameters::{
-        AckDelayExponent, ActiveConnectionIdLimit, InitialFlowControlLimits, InitialMaxData,
-        InitialMaxStreamDataBidiLocal, InitialMaxStreamDataBidiRemote, InitialMaxStreamDataUni,
-        InitialMaxStreamsBidi, InitialMaxStreamsUni, InitialStreamLimits, MaxAckDelay,
-        MaxDatagramFrameSize, MaxIdleTimeout, TransportParameters,
+        AckDelayExponent, ActiveConnectionIdLimit, CustomParameters, InitialFlowControlLimits,
+        InitialMaxData, InitialMaxStreamDataBidiLocal, InitialMaxStreamDataBidiRemote,
+        InitialMaxStreamDataUni, InitialMaxStreamsBidi, InitialMaxStreamsUni, InitialStreamLimits,
+        MaxAckDelay, MaxDatagramFrameSize, MaxIdleTimeout, ServerTransportParameters,
+        TransportParameterId, TransportParameters,
     },
 };
 use core::{convert::TryInto, time::Duration};
 
 pub use crate::transport::parameters::ValidationError;
 
+const CRYPTO_BUFFER_SIZE_TOO_SMALL: ValidationError =
+    ValidationError::new("crypto buffer size must be at least 4096 bytes");
+
+const UDP_PAYLOAD_TOO_SMALL: ValidationError =
+    ValidationError::new("UDP payload size must be at least 1200 bytes");
+
+const ACK_RANGES_LIMIT_TOO_SMALL: ValidationError =
+    ValidationError::new("ACK ranges limit must be at least 1");
+
+const INITIAL_RTT_TOO_SMALL: ValidationError =
+    ValidationError::new("initial round trip time must be at least 1ms");
+
+const HANDSHAKE_PTO_COUNT_TOO_SMALL: ValidationError =
+    ValidationError::new("max handshake PTO count must be at least 1");
+
+const UNVALIDATED_HANDSHAKE_BYTES_TOO_SMALL: ValidationError =
+    ValidationError::new("max unvalidated handshake bytes must be at least 1200 bytes");
+
+const HANDSHAKE_PROBES_OUT_OF_RANGE: ValidationError =
+    ValidationError::new("handshake probes must be either 1 or 2");
+
+const RTT_THRESHOLD_TOO_SMALL: ValidationError =
+    ValidationError::new("RTT threshold must be greater than 0");
+
+const LOSS_RATE_THRESHOLD_OUT_OF_RANGE: ValidationError =
+    ValidationError::new("loss rate threshold must be at least 0 and less than 1");
+
+const LOSS_PERIOD_TOO_SMALL: ValidationError =
+    ValidationError::new("loss rate period must be greater than 0");
+
+const REASSEMBLY_BYTES_TOO_SMALL: ValidationError =
+    ValidationError::new("max reassembly bytes must be at least 4096 bytes");
+
+const REASSEMBLY_GAPS_TOO_SMALL: ValidationError =
+    ValidationError::new("max reassembly gaps must be at least 1");
+
+const CUSTOM_TRANSPORT_PARAMETER_IS_KNOWN: ValidationError =
+    ValidationError::new("custom transport parameters can't use the ID of a known parameter");
+
+/// The size of a stream receive buffer allocation, which is the least amount of memory held by
+/// a single chunk of out-of-order data
+const MIN_REASSEMBLY_BYTES: u64 = 4096;
+
 const MAX_HANDSHAKE_DURATION_DEFAULT: Duration = Duration::from_secs(10);
 
+const IDLE_RECLAMATION_DELAY_DEFAULT: Duration = Duration::from_secs(10);
+
+//= https://www.rfc-editor.org/rfc/rfc9002#section-6.2.4
+//# An endpoint
+//# MAY send up to two full-sized datagrams containing ack-eliciting
+//# packets to avoid an expensive consecutive PTO expiration due to a
+//# single lost datagram or to transmit data from multiple packet number
+//# spaces.
+const MAX_HANDSHAKE_PROBES: u8 = 2;
+
 //= https://www.rfc-editor.org/rfc/rfc9000#section-10.1.2
 //# A connection will time out if no packets are sent or received for a
 //# period longer than the time negotiated using the max_idle_timeout
@@ -28,6 +82,20 @@ const MAX_HANDSHAKE_DURATION_DEFAULT: Duration = Duration::from_secs(10);
 //# middleboxes from losing state for UDP flows [GATEWAY].
 const MAX_KEEP_ALIVE_PERIOD_DEFAULT: Duration = Duration::from_secs(30);
 
+//= https://www.rfc-editor.org/rfc/rfc9000#section-7.5
+//# Implementations MUST support buffering at least 4096 bytes of data
+//# received in out-of-order CRYPTO frames.
+const MIN_CRYPTO_BUFFER_SIZE: u32 = 4096;
+
+//= https://www.rfc-editor.org/rfc/rfc9000#section-7.5
+//# Endpoints MAY choose to
+//# allow more data to be buffered during the handshake.
+const MAX_CRYPTO_BUFFER_SIZE_DEFAULT: u32 = 64 * 1024;
+
+/// Clients that have not validated their address are limited to a smaller buffer to
+/// reduce the amount of memory an attacker can hold with spoofed Initial packets.
+const MAX_UNVALIDATED_CRYPTO_BUFFER_SIZE_DEFAULT: u32 = 16 * 1024;
+
 #[non_exhaustive]
 #[derive(Debug)]
 pub struct ConnectionInfo<'a> {
@@ -44,9 +112,51 @@ impl<'a> ConnectionInfo<'a> {
     }
 }
 
+/// How datagrams carrying Initial packets are padded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InitialPadding {
+    /// Datagrams are padded to the MTU of the path
+    ///
+    /// This confirms the path supports the MTU during the handshake, at the cost of spending
+    /// more of the server's amplification limit on padding.
+    Mtu,
+    /// Datagrams are padded to the minimum of 1200 bytes
+    ///
+    /// The padding is added to the last packet coalesced into the datagram, so the bytes of
+    /// the preceding packets count towards the minimum. This leaves more of the server's
+    /// amplification limit for handshake data, which can save a round trip with large
+    /// certificate chains.
+    Minimum,
+}
+
+impl Default for InitialPadding {
+    fn default() -> Self {
+        Self::Mtu
+    }
+}
+
+impl InitialPadding {
+    /// Returns the minimum length of the packet which pads a datagram
+    ///
+    /// `datagram_len` is the number of bytes which were already written to the datagram and
+    /// `remaining` is the number of bytes left before reaching the MTU.
+    #[inline]
+    #[doc(hidden)]
+    pub fn min_packet_len(&self, datagram_len: usize, remaining: usize) -> usize {
+        match self {
+            Self::Mtu => remaining,
+            Self::Minimum => (path::MINIMUM_MTU as usize)
+                .saturating_sub(datagram_len)
+                .min(remaining),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Limits {
     pub(crate) max_idle_timeout: MaxIdleTimeout,
+    pub(crate) idle_timeout_enabled: bool,
     pub(crate) data_window: InitialMaxData,
     pub(crate) bidirectional_local_data_window: InitialMaxStreamDataBidiLocal,
     pub(crate) bidirectional_remote_data_window: InitialMaxStreamDataBidiRemote,
@@ -60,10 +170,32 @@ pub struct Limits {
     pub(crate) max_active_connection_ids: ActiveConnectionIdLimit,
     pub(crate) ack_elicitation_interval: u8,
     pub(crate) ack_ranges_limit: u8,
+    pub(crate) advertised_ack_ranges_limit: u8,
+    pub(crate) ack_eliciting_threshold: u8,
+    pub(crate) immediate_ack_on_reorder: bool,
+    pub(crate) immediate_ack_on_congestion: bool,
     pub(crate) max_send_buffer_size: stream::limits::MaxSendBufferSize,
     pub(crate) max_handshake_duration: Duration,
+    pub(crate) initial_round_trip_time: Duration,
+    pub(crate) max_handshake_pto_count: Option<u8>,
+    pub(crate) max_handshake_probes: u8,
     pub(crate) max_keep_alive_period: Duration,
+    pub(crate) idle_reclamation_enabled: bool,
+    pub(crate) idle_reclamation_delay: Duration,
     pub(crate) max_datagram_frame_size: MaxDatagramFrameSize,
+    pub(crate) max_crypto_buffer_size: u32,
+    pub(crate) max_unvalidated_crypto_buffer_size: u32,
+    pub(crate) max_unvalidated_handshake_bytes: Option<u64>,
+    pub(crate) stream_reassembly_limit: stream::limits::ReassemblyLimit,
+    pub(crate) connection_reassembly_limit: stream::limits::ReassemblyLimit,
+    pub(crate) base_udp_payload: u16,
+    pub(crate) max_udp_payload: u16,
+    pub(crate) packet_coalescing_enabled: bool,
+    pub(crate) initial_padding: InitialPadding,
+    pub(crate) grease_enabled: bool,
+    pub(crate) grease_quic_bit_enabled: bool,
+    pub(crate) slo_thresholds: recovery::slo::Thresholds,
+    pub(crate) custom_transport_parameters: CustomParameters,
 }
 
 impl Default for Limits {
@@ -85,6 +217,7 @@ impl Limits {
     pub const fn new() -> Self {
         Self {
             max_idle_timeout: MaxIdleTimeout::RECOMMENDED,
+            idle_timeout_enabled: true,
             data_window: InitialMaxData::RECOMMENDED,
             bidirectional_local_data_window: InitialMaxStreamDataBidiLocal::RECOMMENDED,
             bidirectional_remote_data_window: InitialMaxStreamDataBidiRemote::RECOMMENDED,
@@ -98,14 +231,50 @@ impl Limits {
             max_active_connection_ids: ActiveConnectionIdLimit::RECOMMENDED,
             ack_elicitation_interval: ack::Settings::RECOMMENDED.ack_elicitation_interval,
             ack_ranges_limit: ack::Settings::RECOMMENDED.ack_ranges_limit,
+            advertised_ack_ranges_limit: ack::Settings::RECOMMENDED.advertised_ack_ranges_limit,
+            ack_eliciting_threshold: ack::Settings::RECOMMENDED.ack_eliciting_threshold,
+            immediate_ack_on_reorder: ack::Settings::RECOMMENDED.immediate_ack_on_reorder,
+            immediate_ack_on_congestion: ack::Settings::RECOMMENDED.immediate_ack_on_congestion,
             max_send_buffer_size: stream::Limits::RECOMMENDED.max_send_buffer_size,
             max_handshake_duration: MAX_HANDSHAKE_DURATION_DEFAULT,
+            initial_round_trip_time: recovery::DEFAULT_INITIAL_RTT,
+            max_handshake_pto_count: None,
+            max_handshake_probes: MAX_HANDSHAKE_PROBES,
             max_keep_alive_period: MAX_KEEP_ALIVE_PERIOD_DEFAULT,
+            idle_reclamation_enabled: true,
+            idle_reclamation_delay: IDLE_RECLAMATION_DELAY_DEFAULT,
             max_datagram_frame_size: MaxDatagramFrameSize::DEFAULT,
+            max_crypto_buffer_size: MAX_CRYPTO_BUFFER_SIZE_DEFAULT,
+            max_unvalidated_crypto_buffer_size: MAX_UNVALIDATED_CRYPTO_BUFFER_SIZE_DEFAULT,
+            max_unvalidated_handshake_bytes: None,
+            stream_reassembly_limit: stream::limits::ReassemblyLimit::UNLIMITED,
+            connection_reassembly_limit: stream::limits::ReassemblyLimit::UNLIMITED,
+            base_udp_payload: path::MINIMUM_MTU,
+            max_udp_payload: u16::MAX,
+            packet_coalescing_enabled: true,
+            initial_padding: InitialPadding::Mtu,
+            grease_enabled: true,
+            grease_quic_bit_enabled: false,
+            slo_thresholds: recovery::slo::Thresholds::new(),
+            custom_transport_parameters: CustomParameters::new(),
         }
     }
 
     setter!(with_max_idle_timeout, max_idle_timeout, Duration);
+
+    /// Enables or disables the idle timeout
+    ///
+    /// When disabled, the endpoint advertises a `max_idle_timeout` of 0 and never closes the
+    /// connection due to inactivity, even if the peer advertises a non-zero value. Note that
+    /// the peer may still close the connection with its own idle timer; this option is intended
+    /// for links where both endpoints are configured to disable the idle timeout.
+    ///
+    /// The idle timeout can be disabled for individual connections by returning limits with
+    /// the idle timeout disabled from a [`Limiter`].
+    pub fn with_idle_timeout_enabled(mut self, enabled: bool) -> Result<Self, ValidationError> {
+        self.idle_timeout_enabled = enabled;
+        Ok(self)
+    }
     setter!(with_data_window, data_window, u64);
     setter!(
         with_bidirectional_local_data_window,
@@ -175,7 +344,6 @@ impl Limits {
         u64
     );
     setter!(with_ack_elicitation_interval, ack_elicitation_interval, u8);
-    setter!(with_max_ack_ranges, ack_ranges_limit, u8);
     setter!(with_max_send_buffer_size, max_send_buffer_size, u32);
     setter!(
         with_max_handshake_duration,
@@ -184,21 +352,386 @@ impl Limits {
     );
     setter!(with_max_keep_alive_period, max_keep_alive_period, Duration);
 
+    /// Sets how long a connection has to be inactive before its idle resources are reclaimed
+    ///
+    /// Once no packets were received for the given duration after the handshake was confirmed,
+    /// the connection releases the memory it retains for reuse: the spare capacity of the
+    /// stream buffers and the sent packet tracking, the recycled stream and packet metadata,
+    /// and the 0-RTT keys of servers. The memory is allocated again once the connection becomes
+    /// active. Defaults to 10 seconds.
+    pub fn with_idle_reclamation_delay(mut self, value: Duration) -> Result<Self, ValidationError> {
+        self.idle_reclamation_delay = value;
+        Ok(self)
+    }
+
+    /// Enables or disables reclaiming the resources of idle connections
+    ///
+    /// Enabled by default. Disabling it avoids reallocating the released memory for connections
+    /// which are frequently idle for short periods. See
+    /// [`with_idle_reclamation_delay`](Self::with_idle_reclamation_delay) for the resources which
+    /// are reclaimed.
+    pub fn with_idle_reclamation_enabled(mut self, enabled: bool) -> Result<Self, ValidationError> {
+        self.idle_reclamation_enabled = enabled;
+        Ok(self)
+    }
+
+    /// Sets the round trip time assumed before the first RTT sample is taken
+    ///
+    /// The initial probe timeout (PTO) of the handshake is 3 times this value and doubles every
+    /// time the timer expires. Defaults to 333ms, which results in an initial PTO of 1 second.
+    /// Lower values retransmit lost handshake packets sooner on networks with known low
+    /// latency, while higher values avoid spurious retransmissions on high latency links. The
+    /// value must be at least 1ms.
+    pub fn with_initial_round_trip_time(
+        mut self,
+        value: Duration,
+    ) -> Result<Self, ValidationError> {
+        if value < recovery::K_GRANULARITY {
+            return Err(INITIAL_RTT_TOO_SMALL);
+        }
+        self.initial_round_trip_time = value;
+        Ok(self)
+    }
+
+    /// Sets the number of consecutive times the probe timer can expire during the handshake
+    /// before the connection attempt is abandoned
+    ///
+    /// By default, the handshake is only limited by the max handshake duration. Lower values
+    /// give up sooner on peers that aren't responding, as the PTO doubles every time it
+    /// expires. The value must be at least 1.
+    pub fn with_max_handshake_pto_count(mut self, value: u8) -> Result<Self, ValidationError> {
+        if value == 0 {
+            return Err(HANDSHAKE_PTO_COUNT_TOO_SMALL);
+        }
+        self.max_handshake_pto_count = Some(value);
+        Ok(self)
+    }
+
+    /// Sets the number of probe packets sent in the Initial and Handshake packet number spaces
+    /// when the probe timer expires
+    ///
+    /// Defaults to 2, which makes the handshake more resilient to a single lost datagram.
+    /// Setting it to 1 halves the size of the bursts of retransmitted handshake packets, which
+    /// can be useful on constrained networks. The value must be either 1 or 2.
+    pub fn with_max_handshake_probes(mut self, value: u8) -> Result<Self, ValidationError> {
+        if !(1..=MAX_HANDSHAKE_PROBES).contains(&value) {
+            return Err(HANDSHAKE_PROBES_OUT_OF_RANGE);
+        }
+        self.max_handshake_probes = value;
+        Ok(self)
+    }
+
+    /// Sets the maximum number of received packet number ranges stored in each packet number
+    /// space
+    ///
+    /// Once the limit is reached, the ranges with the smallest packet numbers are no longer
+    /// acknowledged, which bounds the memory used when the peer's packets are heavily
+    /// reordered or lost. The value must be at least 1.
+    pub fn with_max_ack_ranges(mut self, value: u8) -> Result<Self, ValidationError> {
+        if value == 0 {
+            return Err(ACK_RANGES_LIMIT_TOO_SMALL);
+        }
+        self.ack_ranges_limit = value;
+        Ok(self)
+    }
+
+    /// Sets the maximum number of packet number ranges included in each transmitted ACK frame
+    ///
+    /// Only the ranges with the largest packet numbers are advertised if more ranges are
+    /// stored, which bounds the size of ACK frames. The value must be at least 1.
+    pub fn with_max_advertised_ack_ranges(mut self, value: u8) -> Result<Self, ValidationError> {
+        if value == 0 {
+            return Err(ACK_RANGES_LIMIT_TOO_SMALL);
+        }
+        self.advertised_ack_ranges_limit = value;
+        Ok(self)
+    }
+
+    /// Sets the maximum amount of CRYPTO data buffered in each packet number space
+    ///
+    /// This bounds how far past the data already consumed by the TLS provider the peer
+    /// is allowed to send. The value must be at least 4096 bytes.
+    pub fn with_max_crypto_buffer_size(mut self, value: u32) -> Result<Self, ValidationError> {
+        if value < MIN_CRYPTO_BUFFER_SIZE {
+            return Err(CRYPTO_BUFFER_SIZE_TOO_SMALL);
+        }
+        self.max_crypto_buffer_size = value;
+        Ok(self)
+    }
+
+    /// Sets the maximum amount of CRYPTO data buffered for a peer that has not validated
+    /// its address
+    ///
+    /// The limit applies to the total amount of data buffered across the Initial and Handshake
+    /// packet number spaces until the peer's address is validated, after which each space is
+    /// only bounded by [`Self::with_max_crypto_buffer_size`]. The value must be at least
+    /// 4096 bytes.
+    pub fn with_max_unvalidated_crypto_buffer_size(
+        mut self,
+        value: u32,
+    ) -> Result<Self, ValidationError> {
+        if value < MIN_CRYPTO_BUFFER_SIZE {
+            return Err(CRYPTO_BUFFER_SIZE_TOO_SMALL);
+        }
+        self.max_unvalidated_crypto_buffer_size = value;
+        Ok(self)
+    }
+
+    /// Sets the maximum number of bytes a server receives from a client during the handshake
+    /// before the client's address is validated
+    ///
+    /// Servers count all of the datagrams attributed to the connection until the client proves
+    /// it can receive packets at its address. Connections exceeding the limit are closed without
+    /// notifying the peer. By default, the received bytes are only limited indirectly by the
+    /// max handshake duration. The value must be at least 1200 bytes, which is the minimum size
+    /// of a datagram carrying a client Initial packet.
+    pub fn with_max_unvalidated_handshake_bytes(
+        mut self,
+        value: u64,
+    ) -> Result<Self, ValidationError> {
+        if value < path::MINIMUM_MTU as u64 {
+            return Err(UNVALIDATED_HANDSHAKE_BYTES_TOO_SMALL);
+        }
+        self.max_unvalidated_handshake_bytes = Some(value);
+        Ok(self)
+    }
+
+    /// Sets the maximum number of bytes each stream buffers for data received out of order
+    ///
+    /// Data received after a missing range is held until the range arrives. The count includes
+    /// the space of the receive buffers which was allocated but not filled yet, so a peer
+    /// sending small chunks far apart reaches the limit quickly. Connections exceeding the limit
+    /// are closed with a `PROTOCOL_VIOLATION` error. By default, the buffered data is only
+    /// limited by the flow control window. The value must be at least 4096 bytes.
+    pub fn with_max_stream_reassembly_bytes(mut self, value: u64) -> Result<Self, ValidationError> {
+        if value < MIN_REASSEMBLY_BYTES {
+            return Err(REASSEMBLY_BYTES_TOO_SMALL);
+        }
+        self.stream_reassembly_limit.max_bytes = Some(value);
+        Ok(self)
+    }
+
+    /// Sets the maximum number of missing ranges each stream tracks before the received data
+    ///
+    /// Connections exceeding the limit are closed with a `PROTOCOL_VIOLATION` error. By default,
+    /// the number of gaps is not limited. The value must be at least 1.
+    pub fn with_max_stream_reassembly_gaps(mut self, value: u64) -> Result<Self, ValidationError> {
+        if value == 0 {
+            return Err(REASSEMBLY_GAPS_TOO_SMALL);
+        }
+        self.stream_reassembly_limit.max_gaps = Some(value);
+        Ok(self)
+    }
+
+    /// Sets the maximum number of bytes buffered for data received out of order across all of
+    /// the streams of a connection
+    ///
+    /// See [`Self::with_max_stream_reassembly_bytes`] for how the bytes are counted. The value
+    /// must be at least 4096 bytes.
+    pub fn with_max_connection_reassembly_bytes(
+        mut self,
+        value: u64,
+    ) -> Result<Self, ValidationError> {
+        if value < MIN_REASSEMBLY_BYTES {
+            return Err(REASSEMBLY_BYTES_TOO_SMALL);
+        }
+        self.connection_reassembly_limit.max_bytes = Some(value);
+        Ok(self)
+    }
+
+    /// Sets the maximum number of missing ranges tracked across all of the streams of a
+    /// connection
+    ///
+    /// The value must be at least 1.
+    pub fn with_max_connection_reassembly_gaps(
+        mut self,
+        value: u64,
+    ) -> Result<Self, ValidationError> {
+        if value == 0 {
+            return Err(REASSEMBLY_GAPS_TOO_SMALL);
+        }
+        self.connection_reassembly_limit.max_gaps = Some(value);
+        Ok(self)
+    }
+
+    /// Sets the maximum UDP payload size of outgoing packets
+    ///
+    /// Path MTU discovery will not probe for payload sizes larger than this value, which is
+    /// useful for networks that are known to drop larger datagrams. The effective value is
+    /// also limited by the max MTU of the IO provider. The value must be at least 1200 bytes.
+    pub fn with_max_udp_payload(mut self, value: u16) -> Result<Self, ValidationError> {
+        if value < path::MINIMUM_MTU {
+            return Err(UDP_PAYLOAD_TOO_SMALL);
+        }
+        self.max_udp_payload = value;
+        self.base_udp_payload = self.base_udp_payload.min(value);
+        Ok(self)
+    }
+
+    /// Sends all packets with a fixed UDP payload size and disables path MTU discovery
+    ///
+    /// This is intended for networks where path MTU discovery is unreliable, such as those
+    /// with broken fragmentation. The value must be at least 1200 bytes and is limited by the
+    /// max MTU of the IO provider.
+    pub fn with_fixed_udp_payload(mut self, value: u16) -> Result<Self, ValidationError> {
+        if value < path::MINIMUM_MTU {
+            return Err(UDP_PAYLOAD_TOO_SMALL);
+        }
+        self.base_udp_payload = value;
+        self.max_udp_payload = value;
+        Ok(self)
+    }
+
+    /// Enables or disables coalescing packets of different packet number spaces
+    ///
+    /// When enabled, which is the default, the Initial, Handshake and 1-RTT packets which are
+    /// ready at the same time are sent in a single datagram, which reduces the number of
+    /// datagrams in each handshake flight. When disabled, each datagram carries a single packet,
+    /// which can be useful for interoperating with peers that mishandle coalesced packets.
+    pub fn with_packet_coalescing_enabled(
+        mut self,
+        enabled: bool,
+    ) -> Result<Self, ValidationError> {
+        self.packet_coalescing_enabled = enabled;
+        Ok(self)
+    }
+
+    /// Sets how datagrams carrying Initial packets are padded
+    ///
+    /// Defaults to [`InitialPadding::Mtu`]. See [`InitialPadding`] for the available strategies.
+    pub fn with_initial_padding(mut self, value: InitialPadding) -> Result<Self, ValidationError> {
+        self.initial_padding = value;
+        Ok(self)
+    }
+
+    /// Enables or disables sending a reserved transport parameter
+    ///
+    /// When enabled, which is the default, a transport parameter with a random reserved ID and
+    /// value is sent to the peer, as described in RFC 9000 Section 18.1. This ensures peers and
+    /// middleboxes keep ignoring unknown transport parameters, so new parameters can be
+    /// deployed. It can be disabled for interoperating with peers that reject unknown
+    /// parameters.
+    pub fn with_grease_enabled(mut self, enabled: bool) -> Result<Self, ValidationError> {
+        self.grease_enabled = enabled;
+        Ok(self)
+    }
+
+    /// Enables or disables greasing the QUIC bit
+    ///
+    /// When enabled, the `grease_quic_bit` transport parameter from RFC 9287 is advertised and
+    /// packets from the peer with the QUIC bit cleared are accepted. If the peer also advertises
+    /// the parameter, the bit is set to a random value on the 1-RTT packets sent to it, which
+    /// keeps middleboxes from relying on it. Disabled by default.
+    pub fn with_grease_quic_bit_enabled(mut self, enabled: bool) -> Result<Self, ValidationError> {
+        self.grease_quic_bit_enabled = enabled;
+        Ok(self)
+    }
+
+    /// Sends a transport parameter which isn't defined by this crate to the peer
+    ///
+    /// This allows for experimenting with private extensions. The parameters with unknown IDs
+    /// received from the peer are included in the `custom_parameters` field of the
+    /// `TransportParametersReceived` event. Returns an error if the ID belongs to a parameter
+    /// defined by this crate, if it was already added, or if the parameters exceed
+    /// [`CustomParameters::CAPACITY`].
+    pub fn with_custom_transport_parameter(
+        mut self,
+        id: TransportParameterId,
+        value: &[u8],
+    ) -> Result<Self, ValidationError> {
+        if ServerTransportParameters::is_known_parameter(id) {
+            return Err(CUSTOM_TRANSPORT_PARAMETER_IS_KNOWN);
+        }
+        self.custom_transport_parameters.insert(id, value)?;
+        Ok(self)
+    }
+
     // internal APIs
 
     #[doc(hidden)]
+    /// Reports when the smoothed round trip time exceeds `max_rtt` for at least `duration`
+    ///
+    /// A `SloUpdated` event is published once the threshold is breached, and again once the
+    /// smoothed RTT is no longer above `max_rtt`. The threshold is evaluated for each RTT
+    /// sample of the active path after the handshake completed. `max_rtt` must be greater than 0.
+    pub fn with_rtt_threshold(
+        mut self,
+        max_rtt: Duration,
+        duration: Duration,
+    ) -> Result<Self, ValidationError> {
+        if max_rtt.is_zero() {
+            return Err(RTT_THRESHOLD_TOO_SMALL);
+        }
+        self.slo_thresholds.rtt = Some(recovery::slo::RttThreshold { max_rtt, duration });
+        Ok(self)
+    }
+
+    /// Reports when more than `max_loss_rate` of the packets acknowledged or declared lost
+    /// during a `period` were lost
+    ///
+    /// The rate is a fraction, so `0.02` corresponds to a loss rate of 2%. A `SloUpdated` event
+    /// is published at the end of the first period which exceeds the rate, and again at the end
+    /// of the first period which no longer does. Only packets sent after the handshake completed
+    /// are taken into account.
+    pub fn with_loss_threshold(
+        mut self,
+        max_loss_rate: f32,
+        period: Duration,
+    ) -> Result<Self, ValidationError> {
+        if !(0.0..1.0).contains(&max_loss_rate) {
+            return Err(LOSS_RATE_THRESHOLD_OUT_OF_RANGE);
+        }
+        if period.is_zero() {
+            return Err(LOSS_PERIOD_TOO_SMALL);
+        }
+        self.slo_thresholds.loss = Some(recovery::slo::LossThreshold {
+            max_loss_rate,
+            period,
+        });
+        Ok(self)
+    }
+
     pub fn load_peer<A, B, C, D>(&mut self, peer_parameters: &TransportParameters<A, B, C, D>) {
+        if !self.idle_timeout_enabled {
+            return;
+        }
+
         self.max_idle_timeout
             .load_peer(&peer_parameters.max_idle_timeout);
     }
 
+    /// Applies the idle timeout which was negotiated by a transferred connection
+    #[doc(hidden)]
+    pub fn load_transferred_idle_timeout(&mut self, max_idle_timeout: Option<Duration>) {
+        match max_idle_timeout.and_then(|timeout| MaxIdleTimeout::try_from(timeout).ok()) {
+            Some(timeout) => {
+                self.max_idle_timeout = timeout;
+                self.idle_timeout_enabled = true;
+            }
+            None => self.idle_timeout_enabled = false,
+        }
+    }
+
+    /// Applies an ACK strategy, overriding the configured `max_ack_delay`
+    #[doc(hidden)]
+    pub fn load_ack_strategy(&mut self, strategy: &ack::Strategy) {
+        self.max_ack_delay = strategy.max_ack_delay_parameter();
+        self.ack_eliciting_threshold = strategy.ack_eliciting_threshold();
+        self.immediate_ack_on_reorder = strategy.immediate_ack_on_reorder();
+        self.immediate_ack_on_congestion = strategy.immediate_ack_on_congestion();
+    }
+
     #[doc(hidden)]
     pub const fn ack_settings(&self) -> ack::Settings {
         ack::Settings {
             ack_delay_exponent: self.ack_delay_exponent.as_u8(),
             max_ack_delay: self.max_ack_delay.as_duration(),
             ack_ranges_limit: self.ack_ranges_limit,
+            advertised_ack_ranges_limit: self.advertised_ack_ranges_limit,
             ack_elicitation_interval: self.ack_elicitation_interval,
+            ack_eliciting_threshold: self.ack_eliciting_threshold,
+            immediate_ack_on_reorder: self.immediate_ack_on_reorder,
+            immediate_ack_on_congestion: self.immediate_ack_on_congestion,
         }
     }
 
@@ -236,6 +769,10 @@ impl Limits {
 
     #[doc(hidden)]
     pub fn max_idle_timeout(&self) -> Option<Duration> {
+        if !self.idle_timeout_enabled {
+            return None;
+        }
+
         self.max_idle_timeout.as_duration()
     }
 
@@ -244,10 +781,94 @@ impl Limits {
         self.max_handshake_duration
     }
 
+    #[doc(hidden)]
+    pub fn initial_round_trip_time(&self) -> Duration {
+        self.initial_round_trip_time
+    }
+
+    #[doc(hidden)]
+    pub fn max_handshake_pto_count(&self) -> Option<u8> {
+        self.max_handshake_pto_count
+    }
+
+    #[doc(hidden)]
+    pub fn max_handshake_probes(&self) -> u8 {
+        self.max_handshake_probes
+    }
+
     #[doc(hidden)]
     pub fn max_keep_alive_period(&self) -> Duration {
         self.max_keep_alive_period
     }
+
+    #[doc(hidden)]
+    pub fn idle_reclamation_delay(&self) -> Option<Duration> {
+        if !self.idle_reclamation_enabled {
+            return None;
+        }
+
+        Some(self.idle_reclamation_delay)
+    }
+
+    #[doc(hidden)]
+    pub fn max_crypto_buffer_size(&self) -> u32 {
+        self.max_crypto_buffer_size
+    }
+
+    #[doc(hidden)]
+    pub fn max_unvalidated_crypto_buffer_size(&self) -> u32 {
+        self.max_unvalidated_crypto_buffer_size
+    }
+
+    #[doc(hidden)]
+    pub fn max_unvalidated_handshake_bytes(&self) -> Option<u64> {
+        self.max_unvalidated_handshake_bytes
+    }
+
+    #[doc(hidden)]
+    pub fn stream_reassembly_limit(&self) -> stream::limits::ReassemblyLimit {
+        self.stream_reassembly_limit
+    }
+
+    #[doc(hidden)]
+    pub fn connection_reassembly_limit(&self) -> stream::limits::ReassemblyLimit {
+        self.connection_reassembly_limit
+    }
+
+    #[doc(hidden)]
+    pub fn base_udp_payload(&self) -> u16 {
+        self.base_udp_payload
+    }
+
+    #[doc(hidden)]
+    pub fn max_udp_payload(&self) -> u16 {
+        self.max_udp_payload
+    }
+
+    #[doc(hidden)]
+    pub fn packet_coalescing_enabled(&self) -> bool {
+        self.packet_coalescing_enabled
+    }
+
+    #[doc(hidden)]
+    pub fn initial_padding(&self) -> InitialPadding {
+        self.initial_padding
+    }
+
+    #[doc(hidden)]
+    pub fn grease_enabled(&self) -> bool {
+        self.grease_enabled
+    }
+
+    #[doc(hidden)]
+    pub fn grease_quic_bit_enabled(&self) -> bool {
+        self.grease_quic_bit_enabled
+    }
+
+    #[doc(hidden)]
+    pub fn slo_thresholds(&self) -> recovery::slo::Thresholds {
+        self.slo_thresholds
+    }
 }
 
 /// Creates limits for a given connection
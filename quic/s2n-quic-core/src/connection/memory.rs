@@ -0,0 +1,80 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Memory usage of a connection
+//!
+//! The usage is an estimate based on the capacity of the buffers and collections held by the
+//! connection. Memory which the TLS provider allocates internally is not included.
+
+use core::ops::{Add, AddAssign};
+
+/// The number of bytes used by each component of a connection
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Usage {
+    /// The data which was written to streams and not acknowledged by the peer yet
+    pub send_buffers: usize,
+    /// The buffers for stream data which was received and not read by the application yet
+    pub receive_buffers: usize,
+    /// The metadata tracked for sent packets until they are acknowledged or declared lost
+    pub sent_packets: usize,
+    /// The packet protection keys and the buffers of the CRYPTO streams
+    pub crypto: usize,
+}
+
+impl Usage {
+    /// Returns the combined usage of all of the components
+    #[inline]
+    pub fn total(&self) -> usize {
+        self.send_buffers + self.receive_buffers + self.sent_packets + self.crypto
+    }
+}
+
+impl Add for Usage {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl AddAssign for Usage {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        self.send_buffers += rhs.send_buffers;
+        self.receive_buffers += rhs.receive_buffers;
+        self.sent_packets += rhs.sent_packets;
+        self.crypto += rhs.crypto;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_test() {
+        let streams = Usage {
+            send_buffers: 100,
+            receive_buffers: 200,
+            ..Default::default()
+        };
+        let recovery = Usage {
+            sent_packets: 300,
+            ..Default::default()
+        };
+        let spaces = Usage {
+            crypto: 400,
+            ..Default::default()
+        };
+
+        let usage = streams + recovery + spaces;
+        assert_eq!(usage.send_buffers, 100);
+        assert_eq!(usage.receive_buffers, 200);
+        assert_eq!(usage.sent_packets, 300);
+        assert_eq!(usage.crypto, 400);
+        assert_eq!(usage.total(), 1000);
+    }
+}
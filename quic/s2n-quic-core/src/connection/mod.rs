@@ -1,11 +1,28 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod arena;
 pub mod close;
+pub mod correlation_id;
 pub mod error;
+#[cfg(feature = "alloc")]
+pub mod extensions;
+pub mod handshake_info;
+pub mod handshake_timing;
 pub mod id;
 pub mod limits;
+pub mod memory;
+pub mod protocol_violation;
+#[cfg(feature = "state-snapshot")]
+pub mod snapshot;
+#[cfg(feature = "alloc")]
+pub mod transfer;
 
+pub use correlation_id::CorrelationId;
 pub use error::{Error, ProcessingError};
+#[cfg(feature = "alloc")]
+pub use extensions::Extensions;
+pub use handshake_info::HandshakeInfo;
+pub use handshake_timing::HandshakeTiming;
 pub use id::{InitialId, LocalId, PeerId, UnboundedId};
 pub use limits::Limits;
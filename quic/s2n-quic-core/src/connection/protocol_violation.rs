@@ -0,0 +1,184 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decides how a connection responds to protocol violations by the peer
+//!
+//! The specification permits some violations to be treated as a connection error, without
+//! requiring it. The policy is consulted for each of these violations to decide whether the
+//! connection should be closed or the offending frame ignored. Violations that the
+//! specification requires to close the connection are not passed to the policy.
+
+use crate::{
+    event::{self, api::SocketAddress, IntoEvent},
+    inet, transport,
+};
+use core::fmt;
+
+/// Information about the connection that the policy is being created for
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct ConnectionInfo<'a> {
+    /// The address of the peer
+    pub remote_address: SocketAddress<'a>,
+}
+
+impl<'a> ConnectionInfo<'a> {
+    #[inline]
+    #[doc(hidden)]
+    pub fn new(remote_address: &'a inet::SocketAddress) -> Self {
+        Self {
+            remote_address: remote_address.into_event(),
+        }
+    }
+}
+
+/// A protocol violation by the peer that may be ignored
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Violation {
+    /// A RETIRE_CONNECTION_ID frame referred to the connection ID the packet containing
+    /// it was sent to
+    ///
+    /// If ignored, the frame has no effect and the connection ID is not retired.
+    ///
+    /// See [Section 19.16](https://www.rfc-editor.org/rfc/rfc9000#section-19.16).
+    RetireCurrentConnectionId,
+
+    /// A NEW_CONNECTION_ID frame reissued a connection ID with a different sequence number or
+    /// stateless reset token, or reused the sequence number or stateless reset token of another
+    /// connection ID
+    ///
+    /// If ignored, the frame has no effect and the connection ID is not registered.
+    ///
+    /// See [Section 19.15](https://www.rfc-editor.org/rfc/rfc9000#section-19.15).
+    InconsistentNewConnectionId,
+
+    /// An ACK frame acknowledged a packet number that was never sent
+    ///
+    /// If ignored, the entire ACK frame has no effect.
+    ///
+    /// See [Section 13.1](https://www.rfc-editor.org/rfc/rfc9000#section-13.1).
+    AckForUnsentPacket,
+}
+
+impl Violation {
+    /// Returns the error the connection is closed with if the violation is not ignored
+    pub fn error(&self) -> transport::Error {
+        match self {
+            Self::RetireCurrentConnectionId => transport::Error::PROTOCOL_VIOLATION
+                .with_reason("RETIRE_CONNECTION_ID referred to the current connection ID"),
+            Self::InconsistentNewConnectionId => transport::Error::PROTOCOL_VIOLATION.with_reason(
+                "The new connection ID had an invalid sequence_number or stateless_reset_token",
+            ),
+            Self::AckForUnsentPacket => crate::ack::Error::PacketNotSent.into(),
+        }
+    }
+}
+
+impl IntoEvent<event::builder::ProtocolViolation> for Violation {
+    #[inline]
+    fn into_event(self) -> event::builder::ProtocolViolation {
+        match self {
+            Self::RetireCurrentConnectionId => {
+                event::builder::ProtocolViolation::RetireCurrentConnectionId
+            }
+            Self::InconsistentNewConnectionId => {
+                event::builder::ProtocolViolation::InconsistentNewConnectionId
+            }
+            Self::AckForUnsentPacket => event::builder::ProtocolViolation::AckForUnsentPacket,
+        }
+    }
+}
+
+/// How the connection responds to a protocol violation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// Closes the connection with the error returned by [`Violation::error`]
+    Close,
+    /// Ignores the frame that caused the violation and continues processing the packet
+    ///
+    /// A `ProtocolViolationIgnored` event is published for each ignored violation.
+    Ignore,
+}
+
+/// Creates a [`Policy`] for each connection
+pub trait Endpoint: 'static + Send {
+    type Policy: Policy;
+
+    /// Called when a connection is created to return the policy for the connection
+    fn new_policy(&mut self, info: &ConnectionInfo) -> Self::Policy;
+}
+
+/// Decides how a connection responds to protocol violations by the peer
+pub trait Policy: 'static + Send + fmt::Debug {
+    /// Called each time the peer commits a [`Violation`]
+    ///
+    /// ```rust
+    /// # mod s2n_quic { pub mod provider { pub mod protocol_violation { pub use s2n_quic_core::connection::protocol_violation::*; } } }
+    /// use s2n_quic::provider::protocol_violation::{Outcome, Policy, Violation};
+    ///
+    /// /// Tolerates peers that retire the current connection ID, up to a limit
+    /// #[derive(Debug)]
+    /// struct Lenient {
+    ///     remaining: usize,
+    /// }
+    ///
+    /// impl Policy for Lenient {
+    ///     fn on_violation(&mut self, violation: &Violation) -> Outcome {
+    ///         match violation {
+    ///             Violation::RetireCurrentConnectionId if self.remaining > 0 => {
+    ///                 self.remaining -= 1;
+    ///                 Outcome::Ignore
+    ///             }
+    ///             _ => Outcome::Close,
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    fn on_violation(&mut self, violation: &Violation) -> Outcome;
+}
+
+/// Applies the same outcome to every violation on every connection
+impl Endpoint for Outcome {
+    type Policy = Self;
+
+    #[inline]
+    fn new_policy(&mut self, _info: &ConnectionInfo) -> Self::Policy {
+        *self
+    }
+}
+
+impl Policy for Outcome {
+    #[inline]
+    fn on_violation(&mut self, _violation: &Violation) -> Outcome {
+        *self
+    }
+}
+
+pub mod default {
+    use super::*;
+
+    /// Closes the connection on every protocol violation
+    #[derive(Clone, Debug, Default)]
+    pub struct Endpoint;
+
+    impl super::Endpoint for Endpoint {
+        type Policy = Policy;
+
+        #[inline]
+        fn new_policy(&mut self, _info: &ConnectionInfo) -> Self::Policy {
+            Policy
+        }
+    }
+
+    /// Closes the connection on every protocol violation
+    #[derive(Debug, Default)]
+    pub struct Policy;
+
+    impl super::Policy for Policy {
+        #[inline]
+        fn on_violation(&mut self, _violation: &Violation) -> Outcome {
+            Outcome::Close
+        }
+    }
+}
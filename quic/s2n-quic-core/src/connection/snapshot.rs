@@ -0,0 +1,119 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Snapshots of the state machines of a connection
+//!
+//! A [`Snapshot`] captures the state of a connection, its packet number spaces, its flow control
+//! and the states of its streams, as they are defined in RFC 9000. The snapshots are meant for
+//! differential testing: they can be serialized and compared to the states of other QUIC
+//! implementations or of a model checker after the same sequence of events.
+//!
+//! Only the state which is visible to the protocol is captured, so snapshots don't change when
+//! the internals of the implementation change.
+
+use alloc::vec::Vec;
+use serde::Serialize;
+
+/// The state of a connection and all of its state machines
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Snapshot {
+    /// The state of the connection
+    pub state: State,
+    /// The packet number spaces of the connection
+    pub spaces: Spaces,
+    /// The connection-level flow control
+    pub flow_control: FlowControl,
+    /// The streams which are currently open, ordered by their stream ID
+    pub streams: Vec<Stream>,
+}
+
+/// The states of a connection
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum State {
+    /// The connection is performing the handshake
+    Handshaking,
+    /// The handshake completed
+    Active,
+    /// The application closed the connection, which still has stream data to transmit
+    Flushing,
+    /// The connection is closing, as described in RFC 9000 Section 10.2.1
+    Closing,
+    /// The connection is draining, as described in RFC 9000 Section 10.2.2
+    Draining,
+    /// The connection was closed and its state is discarded
+    Finished,
+}
+
+/// The packet number spaces of a connection
+///
+/// Spaces are `None` before their keys are available and after they were discarded.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Spaces {
+    pub initial: Option<Space>,
+    pub handshake: Option<Space>,
+    pub application: Option<Space>,
+    /// Whether the handshake is confirmed, as defined in RFC 9001 Section 4.1.2
+    pub handshake_confirmed: bool,
+}
+
+/// The packet numbers of a packet number space
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Space {
+    /// The packet number of the next packet which is sent
+    pub next_packet_number: u64,
+    /// The largest packet number which was sent and acknowledged by the peer, or 0 if no
+    /// packet was acknowledged yet
+    pub largest_acked_packet_number: u64,
+    /// The largest packet number which was received from the peer
+    pub largest_received_packet_number: Option<u64>,
+}
+
+/// The connection-level flow control offsets
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FlowControl {
+    /// The amount of flow control credit which was used by the peer
+    pub received_data: u64,
+    /// The amount of received data which was consumed by the application
+    pub consumed_data: u64,
+    /// The amount of flow control credit which was used by the local endpoint
+    pub sent_data: u64,
+    /// The connection flow control limit of the peer
+    pub peer_max_data: u64,
+}
+
+/// The state of a stream
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Stream {
+    /// The ID of the stream
+    pub id: u64,
+    /// The state of the sending part, if the stream has one
+    pub send: Option<SendState>,
+    /// The state of the receiving part, if the stream has one
+    pub receive: Option<ReceiveState>,
+}
+
+/// The states of the sending part of a stream
+///
+/// See RFC 9000 Section 3.1. Streams in the "Ready" state are reported as [`SendState::Send`],
+/// since streams only track the data which was enqueued by the application.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum SendState {
+    Send,
+    DataSent,
+    DataRecvd,
+    ResetSent,
+    ResetRecvd,
+}
+
+/// The states of the receiving part of a stream
+///
+/// See RFC 9000 Section 3.2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum ReceiveState {
+    Recv,
+    SizeKnown,
+    DataRecvd,
+    DataRead,
+    ResetRecvd,
+    ResetRead,
+}
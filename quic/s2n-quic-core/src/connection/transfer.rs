@@ -0,0 +1,823 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Transferring established connections between endpoints
+//!
+//! A server connection which completed the handshake can be exported into a [`State`], which
+//! contains everything that is needed to continue the connection on another endpoint: the 1-RTT
+//! keys, the connection IDs, the packet numbers and the flow control offsets. The state is
+//! encoded into bytes, which allows passing it to another process, for example during a binary
+//! upgrade.
+//!
+//! Only quiescent connections can be transferred: the streams of the connection must be
+//! closed and a key update must not be in progress. Data which is in flight when the connection
+//! is exported is not transferred; the peer retransmits the frames it sent, and the control
+//! frames of the local endpoint are sent again by the importing endpoint.
+
+use crate::{
+    application::ServerName,
+    connection::{CorrelationId, LocalId, PeerId},
+    crypto::{
+        application::KeySetState,
+        one_rtt::{DirectionalKey, ExportedHeaderKey, ExportedKey, Secret},
+        tls::CipherSuite,
+    },
+    inet::{SocketAddress, SocketAddressV4, SocketAddressV6},
+    packet::KeyPhase,
+    stateless_reset,
+    transport::parameters::{InitialFlowControlLimits, InitialStreamLimits},
+    varint::VarInt,
+};
+use alloc::{vec, vec::Vec};
+use bytes::Bytes;
+use core::{fmt, time::Duration};
+use s2n_codec::{
+    decoder_invariant, decoder_value, DecoderBuffer, DecoderError, Encoder, EncoderBuffer,
+    EncoderValue,
+};
+
+/// The version of the encoding of [`State`]
+const VERSION: u8 = 2;
+
+/// Reasons why a connection could not be transferred
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Only server connections can be transferred
+    Unsupported,
+    /// The handshake of the connection is not yet confirmed
+    HandshakeNotConfirmed,
+    /// The connection has open streams
+    StreamsOpen,
+    /// A key update is in progress
+    KeyUpdateInProgress,
+    /// The TLS provider doesn't support exporting or importing keys
+    KeysNotExportable,
+    /// The connection is closing or was already closed
+    ConnectionClosed,
+    /// The endpoint which should import the connection was closed
+    EndpointClosed,
+    /// A connection ID of the transferred connection is already in use by another connection
+    ConnectionIdInUse,
+    /// The transferred state could not be decoded
+    InvalidState,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            Self::Unsupported => "only server connections can be transferred",
+            Self::HandshakeNotConfirmed => "the handshake is not confirmed",
+            Self::StreamsOpen => "the connection has open streams",
+            Self::KeyUpdateInProgress => "a key update is in progress",
+            Self::KeysNotExportable => "the keys of the connection cannot be exported",
+            Self::ConnectionClosed => "the connection is closed",
+            Self::EndpointClosed => "the endpoint is closed",
+            Self::ConnectionIdInUse => "a connection ID is already in use",
+            Self::InvalidState => "the transferred state is invalid",
+        };
+        f.write_str(message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<DecoderError> for Error {
+    fn from(_error: DecoderError) -> Self {
+        Self::InvalidState
+    }
+}
+
+impl From<crate::connection::Error> for Error {
+    fn from(_error: crate::connection::Error) -> Self {
+        Self::ConnectionClosed
+    }
+}
+
+/// The status of a transferred local connection ID
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LocalIdStatus {
+    /// The peer acknowledged the NEW_CONNECTION_ID frame of the connection ID
+    Active,
+    /// The connection ID still needs to be sent to the peer
+    Pending,
+    /// The connection ID was retired
+    Retired,
+}
+
+/// A connection ID which was issued by the exporting endpoint
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalConnectionId {
+    pub sequence_number: u32,
+    pub id: LocalId,
+    pub stateless_reset_token: stateless_reset::Token,
+    pub status: LocalIdStatus,
+}
+
+/// The status of a transferred peer connection ID
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PeerIdStatus {
+    /// The connection ID wasn't used yet
+    New,
+    /// The connection ID is used on a path
+    InUse,
+    /// The connection ID of the handshake, which is retired once the peer issues a new one
+    InUsePendingNewConnectionId,
+    /// The connection ID is retired, but the RETIRE_CONNECTION_ID frame wasn't acknowledged
+    Retired,
+}
+
+/// A connection ID which was issued by the peer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeerConnectionId {
+    pub sequence_number: u32,
+    pub id: PeerId,
+    pub stateless_reset_token: Option<stateless_reset::Token>,
+    pub status: PeerIdStatus,
+}
+
+/// The stream counts and flow control offsets of a transferred connection
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Streams {
+    /// The number of bidirectional streams which were opened by the local endpoint
+    pub local_bidirectional_opened: VarInt,
+    /// The number of unidirectional streams which were opened by the local endpoint
+    pub local_unidirectional_opened: VarInt,
+    /// The number of bidirectional streams which were opened by the peer
+    pub remote_bidirectional_opened: VarInt,
+    /// The number of unidirectional streams which were opened by the peer
+    pub remote_unidirectional_opened: VarInt,
+    /// The cumulative bidirectional stream limit of the peer
+    pub peer_max_bidirectional_streams: VarInt,
+    /// The cumulative unidirectional stream limit of the peer
+    pub peer_max_unidirectional_streams: VarInt,
+    /// The amount of connection flow control credit which was used by the peer
+    pub received_data: VarInt,
+    /// The amount of received data which was consumed by the application
+    pub consumed_data: VarInt,
+    /// The connection flow control limit of the peer
+    pub peer_max_data: VarInt,
+    /// The amount of connection flow control credit which was used by the local endpoint
+    pub sent_data: VarInt,
+}
+
+/// The state of an established connection, which can be imported by another endpoint
+#[derive(Clone, Debug)]
+pub struct State {
+    /// The QUIC version of the connection
+    pub quic_version: u32,
+    /// The correlation ID of the connection, which is kept by the importing endpoint
+    pub correlation_id: CorrelationId,
+    /// The local address of the active path
+    pub local_address: SocketAddress,
+    /// The remote address of the active path
+    pub remote_address: SocketAddress,
+    /// The server name which was indicated by the client
+    pub server_name: Option<ServerName>,
+    /// The negotiated application protocol
+    pub application_protocol: Bytes,
+    /// The connection ID the peer uses on the active path
+    pub local_connection_id: LocalId,
+    /// The connection IDs which were issued to the peer
+    pub local_connection_ids: Vec<LocalConnectionId>,
+    /// The sequence number of the next connection ID issued to the peer
+    pub next_local_sequence_number: u32,
+    /// The retire prior to value which was sent to the peer
+    pub local_retire_prior_to: u32,
+    /// The connection ID used for sending packets on the active path
+    pub peer_connection_id: PeerId,
+    /// The connection IDs which were issued by the peer
+    pub peer_connection_ids: Vec<PeerConnectionId>,
+    /// The largest retire prior to value which was received from the peer
+    pub peer_retire_prior_to: u32,
+    /// The 1-RTT packet protection keys
+    pub key: ExportedKey,
+    /// The 1-RTT header protection keys
+    pub header_key: ExportedHeaderKey,
+    /// The progress of the key updates
+    pub key_set: KeySetState,
+    /// The packet number of the next 1-RTT packet which is sent
+    pub next_packet_number: VarInt,
+    /// The largest packet number which was received from the peer
+    pub largest_received_packet_number: Option<VarInt>,
+    /// The stream counts and flow control offsets
+    pub streams: Streams,
+    /// The flow control limits which were advertised to the peer
+    pub local_limits: InitialFlowControlLimits,
+    /// The flow control limits which were advertised by the peer
+    pub peer_limits: InitialFlowControlLimits,
+    /// The `active_connection_id_limit` of the peer
+    pub active_connection_id_limit: u64,
+    /// The negotiated idle timeout, or `None` if it is disabled
+    pub max_idle_timeout: Option<Duration>,
+    /// The `max_ack_delay` of the peer
+    pub max_ack_delay: Duration,
+    /// The largest datagram payload the peer accepts
+    pub max_datagram_payload: u64,
+    /// If the peer advertised the `grease_quic_bit` transport parameter
+    pub grease_quic_bit: bool,
+}
+
+impl State {
+    /// Encodes the state into bytes
+    pub fn encode_to_vec(&self) -> Vec<u8> {
+        let mut bytes = vec![0; self.encoding_size()];
+        let mut encoder = EncoderBuffer::new(&mut bytes);
+        encoder.encode(self);
+        bytes
+    }
+
+    /// Decodes a state which was encoded with [`Self::encode_to_vec`]
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let buffer = DecoderBuffer::new(bytes);
+        let (version, buffer) = buffer.decode::<u8>()?;
+        if version != VERSION {
+            return Err(Error::InvalidState);
+        }
+
+        let (quic_version, buffer) = buffer.decode::<u32>()?;
+        let (correlation_id, buffer) = buffer.decode::<CorrelationId>()?;
+        let (local_address, buffer) = decode_address(buffer)?;
+        let (remote_address, buffer) = decode_address(buffer)?;
+        let (server_name, buffer) = buffer.decode_slice_with_len_prefix::<VarInt>()?;
+        let server_name = server_name.into_less_safe_slice();
+        let server_name = if server_name.is_empty() {
+            None
+        } else {
+            let server_name = core::str::from_utf8(server_name).map_err(|_| Error::InvalidState)?;
+            Some(ServerName::from(server_name))
+        };
+        let (application_protocol, buffer) = buffer.decode_slice_with_len_prefix::<VarInt>()?;
+        let application_protocol =
+            Bytes::copy_from_slice(application_protocol.into_less_safe_slice());
+
+        let (local_connection_id, buffer) = buffer.decode_with_len_prefix::<u8, LocalId>()?;
+        let (count, mut buffer) = buffer.decode::<u8>()?;
+        let mut local_connection_ids = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let (id, remaining) = buffer.decode::<LocalConnectionId>()?;
+            local_connection_ids.push(id);
+            buffer = remaining;
+        }
+        let (next_local_sequence_number, buffer) = buffer.decode::<u32>()?;
+        let (local_retire_prior_to, buffer) = buffer.decode::<u32>()?;
+
+        let (peer_connection_id, buffer) = buffer.decode_with_len_prefix::<u8, PeerId>()?;
+        let (count, mut buffer) = buffer.decode::<u8>()?;
+        let mut peer_connection_ids = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let (id, remaining) = buffer.decode::<PeerConnectionId>()?;
+            peer_connection_ids.push(id);
+            buffer = remaining;
+        }
+        let (peer_retire_prior_to, buffer) = buffer.decode::<u32>()?;
+
+        let (cipher_suite, buffer) = decode_cipher_suite(buffer)?;
+        let (sealer, buffer) = decode_directional_key(buffer)?;
+        let (opener, buffer) = decode_directional_key(buffer)?;
+        let key = ExportedKey {
+            cipher_suite,
+            sealer,
+            opener,
+        };
+        let (sealer, buffer) = decode_secret(buffer)?;
+        let (opener, buffer) = decode_secret(buffer)?;
+        let header_key = ExportedHeaderKey {
+            cipher_suite,
+            sealer,
+            opener,
+        };
+
+        let (key_phase, buffer) = buffer.decode::<u8>()?;
+        decoder_invariant!(key_phase <= 1, "invalid key phase");
+        let (generation, buffer) = buffer.decode::<u16>()?;
+        let (encrypted_packets, buffer) = buffer.decode::<u64>()?;
+        let (packet_decryption_failures, buffer) = buffer.decode::<u64>()?;
+        let key_set = KeySetState {
+            key_phase: KeyPhase::from(key_phase),
+            generation,
+            encrypted_packets,
+            packet_decryption_failures,
+        };
+
+        let (next_packet_number, buffer) = buffer.decode::<VarInt>()?;
+        let (largest_received_packet_number, buffer) = decode_optional_varint(buffer)?;
+        let (streams, buffer) = buffer.decode::<Streams>()?;
+        let (local_limits, buffer) = decode_limits(buffer)?;
+        let (peer_limits, buffer) = decode_limits(buffer)?;
+        let (active_connection_id_limit, buffer) = buffer.decode::<VarInt>()?;
+        let (max_idle_timeout, buffer) = buffer.decode::<VarInt>()?;
+        let max_idle_timeout = if max_idle_timeout == VarInt::from_u8(0) {
+            None
+        } else {
+            Some(Duration::from_millis(max_idle_timeout.as_u64()))
+        };
+        let (max_ack_delay, buffer) = buffer.decode::<VarInt>()?;
+        let (max_datagram_payload, buffer) = buffer.decode::<VarInt>()?;
+        let (grease_quic_bit, buffer) = buffer.decode::<u8>()?;
+        buffer.ensure_empty()?;
+
+        Ok(Self {
+            quic_version,
+            correlation_id,
+            local_address,
+            remote_address,
+            server_name,
+            application_protocol,
+            local_connection_id,
+            local_connection_ids,
+            next_local_sequence_number,
+            local_retire_prior_to,
+            peer_connection_id,
+            peer_connection_ids,
+            peer_retire_prior_to,
+            key,
+            header_key,
+            key_set,
+            next_packet_number,
+            largest_received_packet_number,
+            streams,
+            local_limits,
+            peer_limits,
+            active_connection_id_limit: active_connection_id_limit.as_u64(),
+            max_idle_timeout,
+            max_ack_delay: Duration::from_micros(max_ack_delay.as_u64()),
+            max_datagram_payload: max_datagram_payload.as_u64(),
+            grease_quic_bit: grease_quic_bit != 0,
+        })
+    }
+}
+
+impl EncoderValue for State {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        encoder.encode(&VERSION);
+        encoder.encode(&self.quic_version);
+        encoder.encode(&self.correlation_id);
+        encode_address(&self.local_address, encoder);
+        encode_address(&self.remote_address, encoder);
+        let server_name = self
+            .server_name
+            .as_ref()
+            .map_or(&[][..], |name| name.as_bytes());
+        encoder.encode_with_len_prefix::<VarInt, _>(&server_name);
+        encoder.encode_with_len_prefix::<VarInt, _>(&&self.application_protocol[..]);
+
+        encoder.encode_with_len_prefix::<u8, _>(&self.local_connection_id);
+        encoder.encode(&(self.local_connection_ids.len() as u8));
+        for id in &self.local_connection_ids {
+            encoder.encode(id);
+        }
+        encoder.encode(&self.next_local_sequence_number);
+        encoder.encode(&self.local_retire_prior_to);
+
+        encoder.encode_with_len_prefix::<u8, _>(&self.peer_connection_id);
+        encoder.encode(&(self.peer_connection_ids.len() as u8));
+        for id in &self.peer_connection_ids {
+            encoder.encode(id);
+        }
+        encoder.encode(&self.peer_retire_prior_to);
+
+        encode_cipher_suite(self.key.cipher_suite, encoder);
+        encode_directional_key(&self.key.sealer, encoder);
+        encode_directional_key(&self.key.opener, encoder);
+        encode_secret(&self.header_key.sealer, encoder);
+        encode_secret(&self.header_key.opener, encoder);
+
+        encoder.encode(&(self.key_set.key_phase as u8));
+        encoder.encode(&self.key_set.generation);
+        encoder.encode(&self.key_set.encrypted_packets);
+        encoder.encode(&self.key_set.packet_decryption_failures);
+
+        encoder.encode(&self.next_packet_number);
+        encode_optional_varint(self.largest_received_packet_number, encoder);
+        encoder.encode(&self.streams);
+        encode_limits(&self.local_limits, encoder);
+        encode_limits(&self.peer_limits, encoder);
+        encoder.encode(&saturating_varint(self.active_connection_id_limit));
+        let max_idle_timeout = self
+            .max_idle_timeout
+            .map_or(0, |timeout| timeout.as_millis() as u64);
+        encoder.encode(&saturating_varint(max_idle_timeout));
+        encoder.encode(&saturating_varint(self.max_ack_delay.as_micros() as u64));
+        encoder.encode(&saturating_varint(self.max_datagram_payload));
+        encoder.encode(&(self.grease_quic_bit as u8));
+    }
+}
+
+impl EncoderValue for LocalConnectionId {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        encoder.encode(&self.sequence_number);
+        encoder.encode_with_len_prefix::<u8, _>(&self.id);
+        encoder.encode(&self.stateless_reset_token);
+        let status: u8 = match self.status {
+            LocalIdStatus::Active => 0,
+            LocalIdStatus::Pending => 1,
+            LocalIdStatus::Retired => 2,
+        };
+        encoder.encode(&status);
+    }
+}
+
+decoder_value!(
+    impl<'a> LocalConnectionId {
+        fn decode(buffer: Buffer) -> Result<Self> {
+            let (sequence_number, buffer) = buffer.decode::<u32>()?;
+            let (id, buffer) = buffer.decode_with_len_prefix::<u8, LocalId>()?;
+            let (stateless_reset_token, buffer) = buffer.decode()?;
+            let (status, buffer) = buffer.decode::<u8>()?;
+            let status = match status {
+                0 => LocalIdStatus::Active,
+                1 => LocalIdStatus::Pending,
+                2 => LocalIdStatus::Retired,
+                _ => return Err(DecoderError::InvariantViolation("invalid local id status")),
+            };
+
+            let id = LocalConnectionId {
+                sequence_number,
+                id,
+                stateless_reset_token,
+                status,
+            };
+
+            Ok((id, buffer))
+        }
+    }
+);
+
+impl EncoderValue for PeerConnectionId {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        encoder.encode(&self.sequence_number);
+        encoder.encode_with_len_prefix::<u8, _>(&self.id);
+        encoder.encode(&(self.stateless_reset_token.is_some() as u8));
+        if let Some(token) = self.stateless_reset_token.as_ref() {
+            encoder.encode(token);
+        }
+        let status: u8 = match self.status {
+            PeerIdStatus::New => 0,
+            PeerIdStatus::InUse => 1,
+            PeerIdStatus::InUsePendingNewConnectionId => 2,
+            PeerIdStatus::Retired => 3,
+        };
+        encoder.encode(&status);
+    }
+}
+
+decoder_value!(
+    impl<'a> PeerConnectionId {
+        fn decode(buffer: Buffer) -> Result<Self> {
+            let (sequence_number, buffer) = buffer.decode::<u32>()?;
+            let (id, buffer) = buffer.decode_with_len_prefix::<u8, PeerId>()?;
+            let (has_token, buffer) = buffer.decode::<u8>()?;
+            let (stateless_reset_token, buffer) = if has_token != 0 {
+                let (token, buffer) = buffer.decode()?;
+                (Some(token), buffer)
+            } else {
+                (None, buffer)
+            };
+            let (status, buffer) = buffer.decode::<u8>()?;
+            let status = match status {
+                0 => PeerIdStatus::New,
+                1 => PeerIdStatus::InUse,
+                2 => PeerIdStatus::InUsePendingNewConnectionId,
+                3 => PeerIdStatus::Retired,
+                _ => return Err(DecoderError::InvariantViolation("invalid peer id status")),
+            };
+
+            let id = PeerConnectionId {
+                sequence_number,
+                id,
+                stateless_reset_token,
+                status,
+            };
+
+            Ok((id, buffer))
+        }
+    }
+);
+
+impl EncoderValue for Streams {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        encoder.encode(&self.local_bidirectional_opened);
+        encoder.encode(&self.local_unidirectional_opened);
+        encoder.encode(&self.remote_bidirectional_opened);
+        encoder.encode(&self.remote_unidirectional_opened);
+        encoder.encode(&self.peer_max_bidirectional_streams);
+        encoder.encode(&self.peer_max_unidirectional_streams);
+        encoder.encode(&self.received_data);
+        encoder.encode(&self.consumed_data);
+        encoder.encode(&self.peer_max_data);
+        encoder.encode(&self.sent_data);
+    }
+}
+
+decoder_value!(
+    impl<'a> Streams {
+        fn decode(buffer: Buffer) -> Result<Self> {
+            let (local_bidirectional_opened, buffer) = buffer.decode()?;
+            let (local_unidirectional_opened, buffer) = buffer.decode()?;
+            let (remote_bidirectional_opened, buffer) = buffer.decode()?;
+            let (remote_unidirectional_opened, buffer) = buffer.decode()?;
+            let (peer_max_bidirectional_streams, buffer) = buffer.decode()?;
+            let (peer_max_unidirectional_streams, buffer) = buffer.decode()?;
+            let (received_data, buffer) = buffer.decode()?;
+            let (consumed_data, buffer) = buffer.decode()?;
+            let (peer_max_data, buffer) = buffer.decode()?;
+            let (sent_data, buffer) = buffer.decode()?;
+
+            let streams = Streams {
+                local_bidirectional_opened,
+                local_unidirectional_opened,
+                remote_bidirectional_opened,
+                remote_unidirectional_opened,
+                peer_max_bidirectional_streams,
+                peer_max_unidirectional_streams,
+                received_data,
+                consumed_data,
+                peer_max_data,
+                sent_data,
+            };
+
+            Ok((streams, buffer))
+        }
+    }
+);
+
+#[inline]
+fn saturating_varint(value: u64) -> VarInt {
+    VarInt::new(value).unwrap_or(VarInt::MAX)
+}
+
+fn encode_address<E: Encoder>(address: &SocketAddress, encoder: &mut E) {
+    match address {
+        SocketAddress::IpV4(address) => {
+            encoder.encode(&4u8);
+            encoder.encode(address);
+        }
+        SocketAddress::IpV6(address) => {
+            encoder.encode(&6u8);
+            encoder.encode(address);
+        }
+    }
+}
+
+fn decode_address(buffer: DecoderBuffer) -> Result<(SocketAddress, DecoderBuffer), DecoderError> {
+    let (family, buffer) = buffer.decode::<u8>()?;
+    match family {
+        4 => {
+            let (address, buffer) = buffer.decode::<SocketAddressV4>()?;
+            Ok((address.into(), buffer))
+        }
+        6 => {
+            let (address, buffer) = buffer.decode::<SocketAddressV6>()?;
+            Ok((address.into(), buffer))
+        }
+        _ => Err(DecoderError::InvariantViolation("invalid address family")),
+    }
+}
+
+fn encode_optional_varint<E: Encoder>(value: Option<VarInt>, encoder: &mut E) {
+    encoder.encode(&(value.is_some() as u8));
+    if let Some(value) = value {
+        encoder.encode(&value);
+    }
+}
+
+fn decode_optional_varint(
+    buffer: DecoderBuffer,
+) -> Result<(Option<VarInt>, DecoderBuffer), DecoderError> {
+    let (is_some, buffer) = buffer.decode::<u8>()?;
+    if is_some == 0 {
+        return Ok((None, buffer));
+    }
+    let (value, buffer) = buffer.decode::<VarInt>()?;
+    Ok((Some(value), buffer))
+}
+
+fn encode_cipher_suite<E: Encoder>(cipher_suite: CipherSuite, encoder: &mut E) {
+    // the IANA code points of the cipher suites
+    let code: u16 = match cipher_suite {
+        CipherSuite::TLS_AES_128_GCM_SHA256 => 0x1301,
+        CipherSuite::TLS_AES_256_GCM_SHA384 => 0x1302,
+        CipherSuite::TLS_CHACHA20_POLY1305_SHA256 => 0x1303,
+        CipherSuite::Unknown => 0,
+    };
+    encoder.encode(&code);
+}
+
+fn decode_cipher_suite(
+    buffer: DecoderBuffer,
+) -> Result<(CipherSuite, DecoderBuffer), DecoderError> {
+    let (code, buffer) = buffer.decode::<u16>()?;
+    let cipher_suite = match code {
+        0x1301 => CipherSuite::TLS_AES_128_GCM_SHA256,
+        0x1302 => CipherSuite::TLS_AES_256_GCM_SHA384,
+        0x1303 => CipherSuite::TLS_CHACHA20_POLY1305_SHA256,
+        _ => CipherSuite::Unknown,
+    };
+    Ok((cipher_suite, buffer))
+}
+
+fn encode_secret<E: Encoder>(secret: &Secret, encoder: &mut E) {
+    encoder.encode_with_len_prefix::<u8, _>(&secret.as_bytes());
+}
+
+fn decode_secret(buffer: DecoderBuffer) -> Result<(Secret, DecoderBuffer), DecoderError> {
+    let (secret, buffer) = buffer.decode_slice_with_len_prefix::<u8>()?;
+    let secret = Secret::new(secret.into_less_safe_slice())
+        .ok_or(DecoderError::InvariantViolation("secret too long"))?;
+    Ok((secret, buffer))
+}
+
+fn encode_directional_key<E: Encoder>(key: &DirectionalKey, encoder: &mut E) {
+    encode_secret(&key.key, encoder);
+    encode_secret(&key.iv, encoder);
+    encode_secret(&key.next_secret, encoder);
+}
+
+fn decode_directional_key(
+    buffer: DecoderBuffer,
+) -> Result<(DirectionalKey, DecoderBuffer), DecoderError> {
+    let (key, buffer) = decode_secret(buffer)?;
+    let (iv, buffer) = decode_secret(buffer)?;
+    let (next_secret, buffer) = decode_secret(buffer)?;
+    let key = DirectionalKey {
+        key,
+        iv,
+        next_secret,
+    };
+    Ok((key, buffer))
+}
+
+fn encode_limits<E: Encoder>(limits: &InitialFlowControlLimits, encoder: &mut E) {
+    encoder.encode(&limits.stream_limits.max_data_bidi_local);
+    encoder.encode(&limits.stream_limits.max_data_bidi_remote);
+    encoder.encode(&limits.stream_limits.max_data_uni);
+    encoder.encode(&limits.max_data);
+    encoder.encode(&limits.max_open_remote_bidirectional_streams);
+    encoder.encode(&limits.max_open_remote_unidirectional_streams);
+}
+
+fn decode_limits(
+    buffer: DecoderBuffer,
+) -> Result<(InitialFlowControlLimits, DecoderBuffer), DecoderError> {
+    let (max_data_bidi_local, buffer) = buffer.decode()?;
+    let (max_data_bidi_remote, buffer) = buffer.decode()?;
+    let (max_data_uni, buffer) = buffer.decode()?;
+    let (max_data, buffer) = buffer.decode()?;
+    let (max_open_remote_bidirectional_streams, buffer) = buffer.decode()?;
+    let (max_open_remote_unidirectional_streams, buffer) = buffer.decode()?;
+
+    let limits = InitialFlowControlLimits {
+        stream_limits: InitialStreamLimits {
+            max_data_bidi_local,
+            max_data_bidi_remote,
+            max_data_uni,
+        },
+        max_data,
+        max_open_remote_bidirectional_streams,
+        max_open_remote_unidirectional_streams,
+    };
+
+    Ok((limits, buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret(value: u8, len: usize) -> Secret {
+        Secret::new(&[value; Secret::CAPACITY][..len]).unwrap()
+    }
+
+    fn state() -> State {
+        let directional_key = |value| DirectionalKey {
+            key: secret(value, 32),
+            iv: secret(value + 1, 12),
+            next_secret: secret(value + 2, 48),
+        };
+
+        State {
+            quic_version: 1,
+            correlation_id: CorrelationId::from(42u128),
+            local_address: SocketAddressV4::new([127, 0, 0, 1], 4433).into(),
+            remote_address: SocketAddressV6::new([1; 16], 1234).into(),
+            server_name: Some(ServerName::from("localhost")),
+            application_protocol: Bytes::from_static(b"h3"),
+            local_connection_id: LocalId::try_from_bytes(&[1; 16]).unwrap(),
+            local_connection_ids: vec![
+                LocalConnectionId {
+                    sequence_number: 0,
+                    id: LocalId::try_from_bytes(&[1; 16]).unwrap(),
+                    stateless_reset_token: [2; 16].into(),
+                    status: LocalIdStatus::Active,
+                },
+                LocalConnectionId {
+                    sequence_number: 1,
+                    id: LocalId::try_from_bytes(&[3; 16]).unwrap(),
+                    stateless_reset_token: [4; 16].into(),
+                    status: LocalIdStatus::Pending,
+                },
+            ],
+            next_local_sequence_number: 2,
+            local_retire_prior_to: 0,
+            peer_connection_id: PeerId::try_from_bytes(&[5; 8]).unwrap(),
+            peer_connection_ids: vec![PeerConnectionId {
+                sequence_number: 1,
+                id: PeerId::try_from_bytes(&[5; 8]).unwrap(),
+                stateless_reset_token: Some([6; 16].into()),
+                status: PeerIdStatus::InUse,
+            }],
+            peer_retire_prior_to: 1,
+            key: ExportedKey {
+                cipher_suite: CipherSuite::TLS_AES_256_GCM_SHA384,
+                sealer: directional_key(7),
+                opener: directional_key(10),
+            },
+            header_key: ExportedHeaderKey {
+                cipher_suite: CipherSuite::TLS_AES_256_GCM_SHA384,
+                sealer: secret(13, 32),
+                opener: secret(14, 32),
+            },
+            key_set: KeySetState {
+                key_phase: KeyPhase::One,
+                generation: 3,
+                encrypted_packets: 100,
+                packet_decryption_failures: 1,
+            },
+            next_packet_number: VarInt::from_u32(1000),
+            largest_received_packet_number: Some(VarInt::from_u32(900)),
+            streams: Streams {
+                local_bidirectional_opened: VarInt::from_u8(1),
+                remote_bidirectional_opened: VarInt::from_u8(2),
+                peer_max_bidirectional_streams: VarInt::from_u8(100),
+                received_data: VarInt::from_u16(500),
+                consumed_data: VarInt::from_u16(500),
+                peer_max_data: VarInt::from_u32(100_000),
+                sent_data: VarInt::from_u16(400),
+                ..Default::default()
+            },
+            local_limits: InitialFlowControlLimits {
+                max_data: VarInt::from_u32(10_000),
+                ..Default::default()
+            },
+            peer_limits: InitialFlowControlLimits {
+                max_open_remote_unidirectional_streams: VarInt::from_u8(3),
+                ..Default::default()
+            },
+            active_connection_id_limit: 3,
+            max_idle_timeout: Some(Duration::from_secs(30)),
+            max_ack_delay: Duration::from_millis(25),
+            max_datagram_payload: 1200,
+            grease_quic_bit: true,
+        }
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let state = state();
+        let encoded = state.encode_to_vec();
+        assert_eq!(encoded.len(), state.encoding_size());
+
+        let decoded = State::decode(&encoded).unwrap();
+        assert_eq!(decoded.encode_to_vec(), encoded);
+        assert_eq!(decoded.correlation_id, state.correlation_id);
+        assert_eq!(decoded.local_address, state.local_address);
+        assert_eq!(decoded.remote_address, state.remote_address);
+        assert_eq!(decoded.server_name.as_deref(), Some("localhost"));
+        assert_eq!(decoded.local_connection_ids, state.local_connection_ids);
+        assert_eq!(decoded.peer_connection_ids, state.peer_connection_ids);
+        assert_eq!(decoded.key.sealer, state.key.sealer);
+        assert_eq!(decoded.key.opener, state.key.opener);
+        assert_eq!(decoded.key_set, state.key_set);
+        assert_eq!(decoded.streams, state.streams);
+        assert_eq!(decoded.local_limits, state.local_limits);
+        assert_eq!(decoded.peer_limits, state.peer_limits);
+        assert_eq!(decoded.max_idle_timeout, state.max_idle_timeout);
+        assert_eq!(decoded.max_ack_delay, state.max_ack_delay);
+        assert!(decoded.grease_quic_bit);
+    }
+
+    #[test]
+    fn invalid_state_test() {
+        let encoded = state().encode_to_vec();
+
+        // truncated states are rejected
+        for len in 0..encoded.len() {
+            assert_eq!(
+                State::decode(&encoded[..len]).unwrap_err(),
+                Error::InvalidState
+            );
+        }
+
+        // trailing bytes are rejected
+        let mut trailing = encoded.clone();
+        trailing.push(0);
+        assert_eq!(State::decode(&trailing).unwrap_err(), Error::InvalidState);
+
+        // unknown versions are rejected
+        let mut version = encoded;
+        version[0] = VERSION + 1;
+        assert_eq!(State::decode(&version).unwrap_err(), Error::InvalidState);
+    }
+}
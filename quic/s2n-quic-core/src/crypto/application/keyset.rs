@@ -3,7 +3,7 @@
 
 use crate::{
     connection::ProcessingError,
-    crypto::{application::limited, OneRttKey, ProtectedPayload},
+    crypto::{application::limited, one_rtt::ExportedKey, OneRttKey, ProtectedPayload},
     packet::{
         encoding::PacketEncodingError,
         number::PacketNumber,
@@ -37,6 +37,19 @@ pub struct KeySet<K> {
     limits: limited::Limits,
 }
 
+/// The progress of a [`KeySet`] which is transferred along with its active key
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeySetState {
+    /// The current [`KeyPhase`]
+    pub key_phase: KeyPhase,
+    /// The number of times the key has been rotated
+    pub generation: u16,
+    /// The number of packets which were encrypted with the active key
+    pub encrypted_packets: u64,
+    /// The number of received packets which failed authentication
+    pub packet_decryption_failures: u64,
+}
+
 impl<K: OneRttKey> KeySet<K> {
     pub fn new(crypto: K, limits: limited::Limits) -> Self {
         //= https://www.rfc-editor.org/rfc/rfc9001#section-6
@@ -71,6 +84,50 @@ impl<K: OneRttKey> KeySet<K> {
         }
     }
 
+    /// Exports the active key and the progress of the key set
+    ///
+    /// Returns `None` if the key can't be exported or if a key update is in progress, in which
+    /// case the key of the previous phase is still needed.
+    pub fn export(&self) -> Option<(ExportedKey, KeySetState)> {
+        if self.key_update_in_progress() {
+            return None;
+        }
+
+        let key = self.active_key().key().export()?;
+        let state = KeySetState {
+            key_phase: self.key_phase,
+            generation: self.generation,
+            encrypted_packets: self.active_key().encrypted_packets(),
+            packet_decryption_failures: self.packet_decryption_failures,
+        };
+
+        Some((key, state))
+    }
+
+    /// Recreates a key set from the material returned by [`Self::export`]
+    pub fn import(key: &ExportedKey, state: KeySetState, limits: limited::Limits) -> Option<Self> {
+        let crypto = K::import(key)?;
+        let aead_integrity_limit = crypto.aead_integrity_limit();
+        let next_key = limited::Key::new(crypto.derive_next_key());
+        let mut active_key = limited::Key::new(crypto);
+        active_key.set_encrypted_packets(state.encrypted_packets);
+
+        let keys = match state.key_phase {
+            KeyPhase::Zero => [active_key, next_key],
+            KeyPhase::One => [next_key, active_key],
+        };
+
+        Some(Self {
+            key_phase: state.key_phase,
+            key_derivation_timer: Default::default(),
+            packet_decryption_failures: state.packet_decryption_failures,
+            aead_integrity_limit,
+            generation: state.generation,
+            crypto: KeyArray(keys),
+            limits,
+        })
+    }
+
     /// Rotating the phase will switch the active key
     fn rotate_phase(&mut self) {
         self.generation += 1;
@@ -380,6 +437,37 @@ mod tests {
         assert_eq!(keyset.crypto[KeyPhase::One].key().derivations, 1);
     }
 
+    #[test]
+    fn test_export_import() {
+        let mut keyset = KeySet::new(TestKey::default(), Default::default());
+        keyset.rotate_phase();
+        keyset.derive_and_store_next_key();
+        keyset.active_key_mut().set_encrypted_packets(5);
+        keyset.packet_decryption_failures = 2;
+
+        let (key, state) = keyset.export().unwrap();
+        assert_eq!(
+            state,
+            KeySetState {
+                key_phase: KeyPhase::One,
+                generation: 1,
+                encrypted_packets: 5,
+                packet_decryption_failures: 2,
+            }
+        );
+
+        let imported = KeySet::<TestKey>::import(&key, state, Default::default()).unwrap();
+        assert_eq!(imported.key_phase(), KeyPhase::One);
+        assert_eq!(imported.active_key().key().derivations, 1);
+        assert_eq!(imported.active_key().encrypted_packets(), 5);
+        assert_eq!(imported.crypto[KeyPhase::Zero].key().derivations, 2);
+        assert_eq!(imported.decryption_error_count(), 2);
+
+        // the previous key is still needed while a key update is in progress
+        keyset.set_derivation_timer(Clock::default().get_time());
+        assert!(keyset.export().is_none());
+    }
+
     #[test]
     fn test_phase_rotation() {
         let mut keyset = KeySet::new(TestKey::default(), Default::default());
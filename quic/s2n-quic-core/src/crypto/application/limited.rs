@@ -83,6 +83,12 @@ impl<K: OneRttKey> Key<K> {
         self.encrypted_packets
     }
 
+    /// Restores the number of packets which were encrypted with a transferred key
+    #[inline]
+    pub fn set_encrypted_packets(&mut self, encrypted_packets: u64) {
+        self.encrypted_packets = encrypted_packets;
+    }
+
     #[inline]
     pub fn on_packet_encryption(&mut self, limits: &Limits) {
         self.encrypted_packets += 1;
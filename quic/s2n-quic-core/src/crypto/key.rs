@@ -36,6 +36,7 @@ pub trait Key: Send {
 #[cfg(any(test, feature = "testing"))]
 pub mod testing {
     use crate::crypto::{
+        one_rtt::{DirectionalKey, ExportedKey, Secret},
         retry::{IntegrityTag, INTEGRITY_TAG_LEN},
         CryptoError, HandshakeHeaderKey, HandshakeKey, HeaderKey as CryptoHeaderKey,
         HeaderProtectionMask, InitialHeaderKey, InitialKey, OneRttHeaderKey, OneRttKey, RetryKey,
@@ -136,6 +137,30 @@ pub mod testing {
 
         fn update_sealer_pmtu(&mut self, _pmtu: u16) {}
         fn update_opener_pmtu(&mut self, _pmtu: u16) {}
+
+        fn export(&self) -> Option<ExportedKey> {
+            // the number of derivations takes the place of the key material
+            let key = DirectionalKey {
+                key: Secret::new(&self.derivations.to_be_bytes())?,
+                iv: Secret::new(&[])?,
+                next_secret: Secret::new(&[])?,
+            };
+
+            Some(ExportedKey {
+                cipher_suite: crate::crypto::Key::cipher_suite(self),
+                sealer: key.clone(),
+                opener: key,
+            })
+        }
+
+        fn import(key: &ExportedKey) -> Option<Self> {
+            let derivations = key.sealer.key.as_bytes().try_into().ok()?;
+
+            Some(Self {
+                derivations: u64::from_be_bytes(derivations),
+                ..Default::default()
+            })
+        }
     }
     impl ZeroRttKey for Key {}
     impl RetryKey for Key {
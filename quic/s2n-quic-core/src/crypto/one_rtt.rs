@@ -1,7 +1,8 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::crypto::{HeaderKey, Key};
+use crate::crypto::{tls::CipherSuite, HeaderKey, Key};
+use core::fmt;
 
 /// Types for which are able to perform 1-RTT cryptography.
 ///
@@ -14,6 +15,23 @@ pub trait OneRttKey: Key {
 
     fn update_sealer_pmtu(&mut self, pmtu: u16);
     fn update_opener_pmtu(&mut self, pmtu: u16);
+
+    /// Exports the key material so the key can be recreated with [`Self::import`]
+    ///
+    /// This is used to transfer an established connection to another endpoint. Returns `None`
+    /// if the implementation doesn't support exporting keys.
+    fn export(&self) -> Option<ExportedKey> {
+        None
+    }
+
+    /// Recreates a key from the material returned by [`Self::export`]
+    fn import(key: &ExportedKey) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let _ = key;
+        None
+    }
 }
 
 /// Types for which are able to perform 1-RTT header cryptography.
@@ -21,4 +39,94 @@ pub trait OneRttKey: Key {
 /// This trait ensures only 1-RTT-level header keys
 /// are used with Short packets. Any key misuses are
 /// caught by the type system.
-pub trait OneRttHeaderKey: HeaderKey {}
+pub trait OneRttHeaderKey: HeaderKey {
+    /// Exports the key material so the key can be recreated with [`Self::import`]
+    ///
+    /// Returns `None` if the implementation doesn't support exporting keys.
+    fn export(&self) -> Option<ExportedHeaderKey> {
+        None
+    }
+
+    /// Recreates a key from the material returned by [`Self::export`]
+    fn import(key: &ExportedHeaderKey) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let _ = key;
+        None
+    }
+}
+
+/// Secret key material of up to [`Secret::CAPACITY`] bytes
+///
+/// The contents are omitted from the `Debug` output and are overwritten when the value is
+/// dropped.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret {
+    len: u8,
+    bytes: [u8; Self::CAPACITY],
+}
+
+impl Secret {
+    /// The length of the largest secret, which is the output of SHA-384
+    pub const CAPACITY: usize = 48;
+
+    /// Copies the secret, or returns `None` if it exceeds [`Self::CAPACITY`]
+    pub fn new(secret: &[u8]) -> Option<Self> {
+        if secret.len() > Self::CAPACITY {
+            return None;
+        }
+
+        let mut bytes = [0; Self::CAPACITY];
+        bytes[..secret.len()].copy_from_slice(secret);
+
+        Some(Self {
+            len: secret.len() as u8,
+            bytes,
+        })
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Secret").field("len", &self.len).finish()
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.bytes = [0; Self::CAPACITY];
+    }
+}
+
+/// The packet protection key material for a single direction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirectionalKey {
+    /// The AEAD key
+    pub key: Secret,
+    /// The AEAD initialization vector
+    pub iv: Secret,
+    /// The secret which the key of the next key phase is derived from
+    pub next_secret: Secret,
+}
+
+/// The exported material of a [`OneRttKey`]
+#[derive(Clone, Debug)]
+pub struct ExportedKey {
+    pub cipher_suite: CipherSuite,
+    pub sealer: DirectionalKey,
+    pub opener: DirectionalKey,
+}
+
+/// The exported material of a [`OneRttHeaderKey`]
+#[derive(Clone, Debug)]
+pub struct ExportedHeaderKey {
+    pub cipher_suite: CipherSuite,
+    pub sealer: Secret,
+    pub opener: Secret,
+}
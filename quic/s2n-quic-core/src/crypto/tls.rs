@@ -1,6 +1,19 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
+//! The interface between the QUIC transport and a TLS implementation
+//!
+//! A TLS provider implements [`Endpoint`], which creates a [`Session`] for each connection. The
+//! transport drives the session by calling [`Session::poll`] with a [`Context`], through which
+//! the session exchanges handshake messages with the peer and hands over the secrets it derives
+//! as packet protection keys.
+//!
+//! The session owns the TLS state machine, including the handshake transcript. The transport
+//! delivers the peer's handshake messages as a contiguous stream of bytes in each packet space,
+//! so the session never needs to access the transcript through the transport. The `testing::conformance` module,
+//! which is enabled with the `testing` feature, contains checks providers can run to ensure they
+//! adhere to this interface.
+
 use crate::{application::ServerName, crypto::CryptoSuite, transport};
 pub use bytes::{Bytes, BytesMut};
 use core::{
@@ -11,9 +24,14 @@ use core::{
 use s2n_codec::EncoderValue;
 use zerocopy::{AsBytes, FromBytes, Unaligned};
 
+#[cfg(feature = "alloc")]
+pub mod application_settings;
 #[cfg(any(test, feature = "testing"))]
 pub mod testing;
 
+#[cfg(feature = "alloc")]
+pub use application_settings::ApplicationSettings;
+
 /// Holds all application parameters which are exchanged within the TLS handshake.
 #[derive(Debug)]
 pub struct ApplicationParameters<'a> {
@@ -28,13 +46,24 @@ pub struct ApplicationParameters<'a> {
 //# SHOULD provide an interface for the cryptographic protocol
 //# implementation to communicate its buffering limits.
 
+/// The connection state exposed to a [`Session`] while it is polled
+///
+/// All of the callbacks returning a `Result` may fail, in which case the session should return
+/// the error from [`Session::poll`] and the transport closes the connection with it.
 pub trait Context<Crypto: CryptoSuite> {
+    /// Installs the keys for the handshake packet space
+    ///
+    /// This is called exactly once, as soon as the session derived the handshake traffic secrets.
+    /// Handshake data can't be sent before this is called.
     fn on_handshake_keys(
         &mut self,
         key: Crypto::HandshakeKey,
         header_key: Crypto::HandshakeHeaderKey,
     ) -> Result<(), transport::Error>;
 
+    /// Installs the keys for 0-RTT packets, along with the parameters of the session being resumed
+    ///
+    /// This is called at most once.
     fn on_zero_rtt_keys(
         &mut self,
         key: Crypto::ZeroRttKey,
@@ -42,6 +71,11 @@ pub trait Context<Crypto: CryptoSuite> {
         application_parameters: ApplicationParameters,
     ) -> Result<(), transport::Error>;
 
+    /// Installs the keys for the application packet space, along with the peer's parameters
+    ///
+    /// This is called exactly once, after [`Self::on_handshake_keys`]. The server derives these
+    /// keys after sending its Finished message, while the client derives them after reading the
+    /// server's Finished message.
     fn on_one_rtt_keys(
         &mut self,
         key: Crypto::OneRttKey,
@@ -49,27 +83,58 @@ pub trait Context<Crypto: CryptoSuite> {
         application_parameters: ApplicationParameters,
     ) -> Result<(), transport::Error>;
 
+    /// Reports the server name sent by the client
     fn on_server_name(
         &mut self,
         server_name: crate::application::ServerName,
     ) -> Result<(), transport::Error>;
 
+    /// Reports the negotiated application protocol
+    ///
+    /// This needs to be called before [`Self::on_handshake_complete`], since connections without
+    /// an application protocol are closed.
     fn on_application_protocol(
         &mut self,
         application_protocol: Bytes,
     ) -> Result<(), transport::Error>;
 
+    /// Reports the DER-encoded certificate chain presented by the peer
+    ///
+    /// The chain starts with the peer's end-entity certificate. This should be called before
+    /// [`Self::on_handshake_complete`] if the peer presented certificates and the provider exposes
+    /// them.
+    #[cfg(feature = "alloc")]
+    fn on_peer_certificates(
+        &mut self,
+        certificates: alloc::vec::Vec<Bytes>,
+    ) -> Result<(), transport::Error>;
+
+    /// Reports the application settings the peer sent for the negotiated application protocol
+    ///
+    /// This should be called after [`Self::on_application_protocol`] and before
+    /// [`Self::on_handshake_complete`] if the peer sent settings for the protocol. See
+    /// [`ApplicationSettings`] for how the settings are exchanged.
+    fn on_application_settings(&mut self, settings: Bytes) -> Result<(), transport::Error>;
+
     //= https://www.rfc-editor.org/rfc/rfc9001#section-4.1.1
     //# The TLS handshake is considered complete when the
     //# TLS stack has reported that the handshake is complete.  This happens
     //# when the TLS stack has both sent a Finished message and verified the
     //# peer's Finished message.
+    /// Signals that the handshake is complete
+    ///
+    /// This is called exactly once, after [`Self::on_one_rtt_keys`].
     fn on_handshake_complete(&mut self) -> Result<(), transport::Error>;
 
     /// Receives data from the initial packet space
     ///
     /// A `max_len` may be provided to indicate how many bytes the TLS implementation
     /// is willing to buffer.
+    ///
+    /// The data is returned in order, in chunks which don't necessarily align with the handshake
+    /// messages. The session takes ownership of any returned chunk, so it needs to buffer any
+    /// partial message until the rest of it is received. `None` is returned if no more data is
+    /// currently available, in which case the connection is woken up once it is.
     fn receive_initial(&mut self, max_len: Option<usize>) -> Option<Bytes>;
 
     /// Receives data from the handshake packet space
@@ -84,26 +149,59 @@ pub trait Context<Crypto: CryptoSuite> {
     /// is willing to buffer.
     fn receive_application(&mut self, max_len: Option<usize>) -> Option<Bytes>;
 
+    /// Returns `true` if data can be sent in the initial packet space
     fn can_send_initial(&self) -> bool;
+
+    /// Sends data in the initial packet space
+    ///
+    /// The transport takes ownership of the data and is responsible for retransmitting it until
+    /// the peer acknowledges it.
     fn send_initial(&mut self, transmission: Bytes);
 
+    /// Returns `true` if data can be sent in the handshake packet space
+    ///
+    /// This returns `false` until the handshake keys are installed.
     fn can_send_handshake(&self) -> bool;
+
+    /// Sends data in the handshake packet space
+    ///
+    /// This may only be called if [`Self::can_send_handshake`] returns `true`.
     fn send_handshake(&mut self, transmission: Bytes);
 
+    /// Returns `true` if data can be sent in the application packet space
+    ///
+    /// This returns `false` until the 1-RTT keys are installed.
     fn can_send_application(&self) -> bool;
+
+    /// Sends data in the application packet space, e.g. session tickets
+    ///
+    /// This may only be called if [`Self::can_send_application`] returns `true`.
     fn send_application(&mut self, transmission: Bytes);
 
+    /// Returns the waker for the connection
+    ///
+    /// Sessions which complete work asynchronously, e.g. certificate lookups, should wake it once
+    /// they can make progress.
     fn waker(&self) -> &Waker;
 }
 
+/// A TLS provider for an endpoint, which creates a [`Session`] for each connection
 pub trait Endpoint: 'static + Sized + Send {
     type Session: Session;
 
+    /// Creates a session for a connection accepted by a server
+    ///
+    /// The encoded `transport_parameters` need to be sent to the client in the
+    /// `quic_transport_parameters` extension.
     fn new_server_session<Params: EncoderValue>(
         &mut self,
         transport_parameters: &Params,
     ) -> Self::Session;
 
+    /// Creates a session for a connection initiated by a client
+    ///
+    /// The encoded `transport_parameters` need to be sent to the server in the
+    /// `quic_transport_parameters` extension, along with the `server_name`.
     fn new_client_session<Params: EncoderValue>(
         &mut self,
         transport_parameters: &Params,
@@ -114,7 +212,14 @@ pub trait Endpoint: 'static + Sized + Send {
     fn max_tag_length(&self) -> usize;
 }
 
+/// The TLS state of a single connection
 pub trait Session: CryptoSuite + Sized + Send + Debug {
+    /// Makes progress on the handshake
+    ///
+    /// The transport calls this whenever the connection is woken up, which doesn't mean the
+    /// context has any new data to receive. Once the handshake is complete and there's nothing
+    /// left to send, this should return `Poll::Ready(Ok(()))`, after which the transport drops
+    /// the session.
     fn poll<C: Context<Self>>(&mut self, context: &mut C) -> Poll<Result<(), transport::Error>>;
 }
 
@@ -0,0 +1,255 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Exchanges application settings during the handshake
+//!
+//! Similar to the ALPS TLS extension, each endpoint can send a small settings blob for each of
+//! its application protocols. Once the handshake is complete, each endpoint reads the settings
+//! the peer sent for the negotiated application protocol, which allows protocols to skip a round
+//! trip of settings negotiation.
+//!
+//! The settings are carried in a transport parameter, since TLS providers don't allow adding
+//! arbitrary extensions. As a consequence, the client doesn't know which application protocol
+//! will be negotiated and sends the settings of all of its protocols. The settings of the client
+//! are sent in the ClientHello, so they aren't encrypted and shouldn't contain any secrets.
+
+use crate::{
+    transport::parameters::{
+        CustomParametersIter, TransportParameterId, TransportParameterLength, ValidationError,
+    },
+    varint::VarInt,
+};
+use alloc::vec::Vec;
+use core::fmt;
+use s2n_codec::{DecoderBuffer, Encoder, EncoderBuffer, EncoderValue};
+
+const INVALID_APPLICATION_PROTOCOL: ValidationError =
+    ValidationError::new("the application protocol needs to be between 1 and 255 bytes");
+
+const DUPLICATE_APPLICATION_PROTOCOL: ValidationError =
+    ValidationError::new("the settings of the application protocol were already added");
+
+const CAPACITY_EXCEEDED: ValidationError =
+    ValidationError::new("the application settings exceed the maximum length");
+
+/// The application settings of each application protocol of an endpoint
+///
+/// The settings are stored in their encoded form, as a sequence of application protocols with a
+/// one byte length prefix, each followed by its settings with a variable-length integer prefix.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct ApplicationSettings {
+    value: Vec<u8>,
+}
+
+impl ApplicationSettings {
+    /// The ID of the transport parameter which carries the settings
+    ///
+    /// This reuses the codepoint of the ALPS TLS extension. Custom transport parameters with
+    /// the same ID can't be used along with application settings.
+    pub const TRANSPORT_PARAMETER_ID: TransportParameterId = TransportParameterId::from_u16(0x4469);
+
+    /// The maximum length of the encoded settings of all application protocols
+    ///
+    /// The settings of the client are sent in the ClientHello, which needs to fit into the
+    /// first few Initial packets.
+    pub const MAX_LEN: usize = 1024;
+
+    pub const fn new() -> Self {
+        Self { value: Vec::new() }
+    }
+
+    /// Returns `true` if no settings are stored
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Adds the settings for an application protocol
+    ///
+    /// Returns an error if the settings for the application protocol were already added, or if
+    /// the encoded settings would exceed [`Self::MAX_LEN`].
+    pub fn insert(
+        &mut self,
+        application_protocol: &[u8],
+        settings: &[u8],
+    ) -> Result<(), ValidationError> {
+        if application_protocol.is_empty() || application_protocol.len() > u8::MAX as usize {
+            return Err(INVALID_APPLICATION_PROTOCOL);
+        }
+
+        if self.get(application_protocol).is_some() {
+            return Err(DUPLICATE_APPLICATION_PROTOCOL);
+        }
+
+        let settings_len = VarInt::try_from(settings.len()).map_err(|_| CAPACITY_EXCEEDED)?;
+        let encoding_size =
+            1 + application_protocol.len() + settings_len.encoding_size() + settings.len();
+        let offset = self.value.len();
+        if offset + encoding_size > Self::MAX_LEN {
+            return Err(CAPACITY_EXCEEDED);
+        }
+
+        self.value.resize(offset + encoding_size, 0);
+        let mut encoder = EncoderBuffer::new(&mut self.value[offset..]);
+        encoder.encode_with_len_prefix::<u8, _>(&application_protocol);
+        encoder.encode_with_len_prefix::<VarInt, _>(&settings);
+
+        Ok(())
+    }
+
+    /// Returns the settings for the application protocol
+    pub fn get(&self, application_protocol: &[u8]) -> Option<&[u8]> {
+        find(self.iter(), application_protocol)
+    }
+
+    /// Returns an iterator over the application protocols and their settings
+    #[inline]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter::new(&self.value)
+    }
+
+    /// Returns the settings the peer sent for the negotiated application protocol
+    ///
+    /// `transport_parameters` are the encoded transport parameters which were received from the
+    /// peer. Returns `None` if the peer didn't send any settings for the application protocol.
+    pub fn peer_settings<'a>(
+        transport_parameters: &'a [u8],
+        application_protocol: &[u8],
+    ) -> Option<&'a [u8]> {
+        let (_, value) = CustomParametersIter::new(transport_parameters)
+            .find(|(id, _)| *id == Self::TRANSPORT_PARAMETER_ID)?;
+        find(Iter::new(value), application_protocol)
+    }
+}
+
+#[inline]
+fn find<'a>(mut iter: Iter<'a>, application_protocol: &[u8]) -> Option<&'a [u8]> {
+    iter.find(|(protocol, _)| *protocol == application_protocol)
+        .map(|(_, settings)| settings)
+}
+
+impl fmt::Debug for ApplicationSettings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl EncoderValue for ApplicationSettings {
+    /// Encodes the settings as a transport parameter
+    ///
+    /// Nothing is encoded if no settings are stored.
+    #[inline]
+    fn encode<E: Encoder>(&self, buffer: &mut E) {
+        if self.is_empty() {
+            return;
+        }
+
+        buffer.encode(&Self::TRANSPORT_PARAMETER_ID);
+        buffer.encode_with_len_prefix::<TransportParameterLength, _>(&self.value.as_slice());
+    }
+}
+
+/// Iterates over the application protocols and settings of an encoded transport parameter
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+    buffer: DecoderBuffer<'a>,
+}
+
+impl<'a> Iter<'a> {
+    #[inline]
+    fn new(encoded: &'a [u8]) -> Self {
+        Self {
+            buffer: DecoderBuffer::new(encoded),
+        }
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a [u8], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let buffer = core::mem::replace(&mut self.buffer, DecoderBuffer::new(&[]));
+        let (protocol, buffer) = buffer.decode_slice_with_len_prefix::<u8>().ok()?;
+        let (settings, buffer) = buffer.decode_slice_with_len_prefix::<VarInt>().ok()?;
+        self.buffer = buffer;
+        Some((
+            protocol.into_less_safe_slice(),
+            settings.into_less_safe_slice(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::parameters::{ClientTransportParameters, CustomParameters};
+    use s2n_codec::DecoderValue;
+
+    #[test]
+    fn insert_test() {
+        let mut settings = ApplicationSettings::new();
+        assert!(settings.is_empty());
+
+        settings.insert(b"h3", &[1, 2, 3]).unwrap();
+        settings.insert(b"hq-interop", &[]).unwrap();
+
+        assert_eq!(settings.get(b"h3"), Some(&[1, 2, 3][..]));
+        assert_eq!(settings.get(b"hq-interop"), Some(&[][..]));
+        assert_eq!(settings.get(b"h2"), None);
+
+        assert_eq!(
+            settings.insert(b"h3", &[4]),
+            Err(DUPLICATE_APPLICATION_PROTOCOL)
+        );
+        assert_eq!(
+            settings.insert(b"", &[4]),
+            Err(INVALID_APPLICATION_PROTOCOL)
+        );
+        assert_eq!(
+            settings.insert(&[b'a'; 256], &[4]),
+            Err(INVALID_APPLICATION_PROTOCOL)
+        );
+        assert_eq!(
+            settings.insert(b"large", &[0; ApplicationSettings::MAX_LEN]),
+            Err(CAPACITY_EXCEEDED)
+        );
+    }
+
+    #[test]
+    fn peer_settings_test() {
+        let mut settings = ApplicationSettings::new();
+        settings.insert(b"h3", &[1, 2, 3]).unwrap();
+
+        let mut buffer = vec![0; 32 * 1024];
+        let mut encoder = EncoderBuffer::new(&mut buffer);
+        encoder.encode(&ClientTransportParameters::default());
+        encoder.encode(&settings);
+        let (transport_parameters, _) = encoder.split_off();
+
+        assert_eq!(
+            ApplicationSettings::peer_settings(transport_parameters, b"h3"),
+            Some(&[1, 2, 3][..])
+        );
+        assert_eq!(
+            ApplicationSettings::peer_settings(transport_parameters, b"h2"),
+            None
+        );
+
+        // the transport treats the settings as an unknown parameter
+        let (params, remaining) =
+            ClientTransportParameters::decode(DecoderBuffer::new(transport_parameters)).unwrap();
+        assert!(remaining.is_empty());
+        let mut expected = CustomParameters::new();
+        expected
+            .insert(ApplicationSettings::TRANSPORT_PARAMETER_ID, &settings.value)
+            .unwrap();
+        assert_eq!(params.custom_parameters, expected);
+    }
+
+    #[test]
+    fn empty_encoding_test() {
+        let settings = ApplicationSettings::new();
+        assert_eq!(settings.encoding_size(), 0);
+        assert_eq!(ApplicationSettings::peer_settings(&[], b"h3"), None);
+    }
+}
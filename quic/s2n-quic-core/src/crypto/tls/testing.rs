@@ -112,8 +112,10 @@ pub struct Pair<S: tls::Session, C: tls::Session> {
     pub server_name: ServerName,
 }
 
-const TEST_SERVER_TRANSPORT_PARAMS: &[u8] = &[1, 2, 3];
-const TEST_CLIENT_TRANSPORT_PARAMS: &[u8] = &[3, 2, 1];
+// The parameters are encoded as reserved transport parameters, since providers can append their
+// own parameters, e.g. for application settings
+const TEST_SERVER_TRANSPORT_PARAMS: &[u8] = &[27, 3, 1, 2, 3];
+const TEST_CLIENT_TRANSPORT_PARAMS: &[u8] = &[27, 3, 3, 2, 1];
 
 impl<S: tls::Session, C: tls::Session> Pair<S, C> {
     pub fn new<SE, CE>(
@@ -231,14 +233,22 @@ impl<S: tls::Session, C: tls::Session> Pair<S, C> {
     pub fn finish(&self) {
         self.client.context.finish(&self.server.context);
 
-        assert_eq!(
-            self.client.context.transport_parameters.as_ref().unwrap(),
-            TEST_SERVER_TRANSPORT_PARAMS,
+        assert!(
+            self.client
+                .context
+                .transport_parameters
+                .as_ref()
+                .unwrap()
+                .starts_with(TEST_SERVER_TRANSPORT_PARAMS),
             "client did not receive the server transport parameters"
         );
-        assert_eq!(
-            self.server.context.transport_parameters.as_ref().unwrap(),
-            TEST_CLIENT_TRANSPORT_PARAMS,
+        assert!(
+            self.server
+                .context
+                .transport_parameters
+                .as_ref()
+                .unwrap()
+                .starts_with(TEST_CLIENT_TRANSPORT_PARAMS),
             "server did not receive the client transport parameters"
         );
         assert_eq!(
@@ -272,6 +282,75 @@ impl<S: tls::Session, C: tls::Session> Pair<S, C> {
     }
 }
 
+/// A conformance suite for TLS providers
+///
+/// Each check drives a handshake between sessions created by the given endpoints and asserts
+/// the sessions adhere to the contract of the [`tls::Session`] and [`tls::Context`] traits.
+/// Providers implemented outside of this repository can call [`conformance::run`] from their
+/// own tests. The endpoints need to agree on an application protocol and the client needs to
+/// trust the server's certificate for `localhost`, e.g. by using the [`certificates`].
+pub mod conformance {
+    use super::*;
+    use tls::Session as _;
+
+    /// Runs all of the conformance checks
+    pub fn run<SE: tls::Endpoint, CE: tls::Endpoint>(server: &mut SE, client: &mut CE) {
+        handshake(server, client);
+        fragmented_handshake(server, client);
+        spurious_poll(server, client);
+    }
+
+    /// Completes a handshake and checks the negotiated keys and parameters are consistent
+    pub fn handshake<SE: tls::Endpoint, CE: tls::Endpoint>(server: &mut SE, client: &mut CE) {
+        let mut pair = Pair::new(server, client, "localhost".into());
+        complete(&mut pair);
+    }
+
+    /// Completes a handshake in which the peer's crypto data is received one byte at a time
+    ///
+    /// The transport delivers crypto data as it is received in CRYPTO frames, which can split
+    /// handshake messages at arbitrary offsets.
+    pub fn fragmented_handshake<SE: tls::Endpoint, CE: tls::Endpoint>(
+        server: &mut SE,
+        client: &mut CE,
+    ) {
+        let mut pair = Pair::new(server, client, "localhost".into());
+        pair.server.context.set_max_rx_chunk_len(1);
+        pair.client.context.set_max_rx_chunk_len(1);
+        complete(&mut pair);
+    }
+
+    /// Completes a handshake in which the sessions are also polled without any new data
+    ///
+    /// The transport polls the session whenever the connection is woken up, which doesn't mean
+    /// any crypto data was received.
+    pub fn spurious_poll<SE: tls::Endpoint, CE: tls::Endpoint>(server: &mut SE, client: &mut CE) {
+        let mut pair = Pair::new(server, client, "localhost".into());
+
+        while pair.is_handshaking() {
+            for _ in 0..2 {
+                if let Poll::Ready(res) = pair.client.session.poll(&mut pair.client.context) {
+                    res.unwrap();
+                }
+                if let Poll::Ready(res) = pair.server.session.poll(&mut pair.server.context) {
+                    res.unwrap();
+                }
+            }
+            pair.poll(None).unwrap();
+        }
+
+        pair.finish();
+    }
+
+    fn complete<S: tls::Session, C: tls::Session>(pair: &mut Pair<S, C>) {
+        while pair.is_handshaking() {
+            pair.poll(None).unwrap();
+        }
+
+        pair.finish();
+    }
+}
+
 #[derive(Debug)]
 pub enum ClientState {
     ClientHelloSent,
@@ -327,6 +406,8 @@ pub struct Context<C: CryptoSuite, State: Debug> {
     pub handshake_complete: bool,
     pub server_name: Option<Bytes>,
     pub application_protocol: Option<Bytes>,
+    pub peer_certificates: Option<Vec<Bytes>>,
+    pub application_settings: Option<Bytes>,
     pub transport_parameters: Option<Bytes>,
     endpoint: endpoint::Type,
     pub state: State,
@@ -343,6 +424,8 @@ impl<C: CryptoSuite, State: Debug> fmt::Debug for Context<C, State> {
             .field("handshake_complete", &self.handshake_complete)
             .field("sni", &self.server_name)
             .field("application_protocol", &self.application_protocol)
+            .field("peer_certificates", &self.peer_certificates)
+            .field("application_settings", &self.application_settings)
             .field("transport_parameters", &self.transport_parameters)
             .field("endpoint", &self.endpoint)
             .finish()
@@ -360,6 +443,8 @@ impl<C: CryptoSuite, State: Debug> Context<C, State> {
             handshake_complete: false,
             server_name: None,
             application_protocol: None,
+            peer_certificates: None,
+            application_settings: None,
             transport_parameters: None,
             endpoint,
             state,
@@ -367,6 +452,13 @@ impl<C: CryptoSuite, State: Debug> Context<C, State> {
         }
     }
 
+    /// Limits the number of bytes returned by each call to receive data in any space
+    pub fn set_max_rx_chunk_len(&mut self, max_len: usize) {
+        self.initial.max_rx_chunk_len = Some(max_len);
+        self.handshake.max_rx_chunk_len = Some(max_len);
+        self.application.max_rx_chunk_len = Some(max_len);
+    }
+
     /// Transfers incoming and outgoing buffers between two contexts
     pub fn transfer<O: CryptoSuite, OS: Debug>(&mut self, other: &mut Context<O, OS>) {
         self.initial.transfer(&mut other.initial);
@@ -430,6 +522,8 @@ pub struct Space<K: Key, Hk: HeaderKey> {
     pub crypto: Option<(K, Hk)>,
     pub rx: VecDeque<Bytes>,
     pub tx: VecDeque<Bytes>,
+    /// The maximum number of bytes returned by each call to receive data
+    pub max_rx_chunk_len: Option<usize>,
 }
 
 impl<K: Key, Hk: HeaderKey> Default for Space<K, Hk> {
@@ -438,6 +532,7 @@ impl<K: Key, Hk: HeaderKey> Default for Space<K, Hk> {
             crypto: None,
             rx: VecDeque::new(),
             tx: VecDeque::new(),
+            max_rx_chunk_len: None,
         }
     }
 }
@@ -448,6 +543,7 @@ impl<K: Key, Hk: HeaderKey> fmt::Debug for Space<K, Hk> {
             .field("crypto", &self.crypto.is_some())
             .field("rx", &self.rx)
             .field("tx", &self.tx)
+            .field("max_rx_chunk_len", &self.max_rx_chunk_len)
             .finish()
     }
 }
@@ -470,7 +566,9 @@ impl<K: Key, Hk: HeaderKey> Space<K, Hk> {
                 continue;
             }
 
-            let max_len = max_len.unwrap_or(usize::MAX);
+            let max_len = max_len
+                .unwrap_or(usize::MAX)
+                .min(self.max_rx_chunk_len.unwrap_or(usize::MAX));
 
             if chunk.len() > max_len {
                 self.rx.push_front(chunk.split_off(max_len));
@@ -594,6 +692,10 @@ impl<C: CryptoSuite, State: Debug> tls::Context<C> for Context<C, State> {
             self.application.crypto.is_none(),
             "1-rtt keys emitted multiple times"
         );
+        assert!(
+            self.handshake.crypto.is_some(),
+            "handshake keys need to be emitted before 1-rtt keys"
+        );
         self.log("1-rtt keys");
         self.application.crypto = Some((key, header_key));
         self.on_application_params(params);
@@ -619,13 +721,53 @@ impl<C: CryptoSuite, State: Debug> tls::Context<C> for Context<C, State> {
         Ok(())
     }
 
+    fn on_peer_certificates(&mut self, certificates: Vec<Bytes>) -> Result<(), transport::Error> {
+        assert!(
+            self.peer_certificates.is_none(),
+            "peer certificates emitted multiple times"
+        );
+        assert!(
+            !self.handshake_complete,
+            "peer certificates need to be emitted before the handshake is complete"
+        );
+        self.log("peer certificates");
+        self.peer_certificates = Some(certificates);
+        Ok(())
+    }
+
+    fn on_application_settings(&mut self, settings: Bytes) -> Result<(), transport::Error> {
+        assert!(
+            self.application_settings.is_none(),
+            "application settings emitted multiple times"
+        );
+        assert!(
+            self.application_protocol.is_some(),
+            "application settings need to be emitted after the application protocol"
+        );
+        assert!(
+            !self.handshake_complete,
+            "application settings need to be emitted before the handshake is complete"
+        );
+        self.log("application settings");
+        self.application_settings = Some(settings);
+        Ok(())
+    }
+
     fn on_handshake_complete(&mut self) -> Result<(), transport::Error> {
         assert!(
             !self.handshake_complete,
             "handshake complete called multiple times"
         );
         assert!(
-            !self.application_protocol.as_ref().unwrap().is_empty(),
+            self.application.crypto.is_some(),
+            "1-rtt keys need to be emitted before the handshake is complete"
+        );
+        assert!(
+            !self
+                .application_protocol
+                .as_ref()
+                .expect("application_protocol needs to be emitted before the handshake is complete")
+                .is_empty(),
             "application_protocol is empty at handshake complete"
         );
         self.handshake_complete = true;
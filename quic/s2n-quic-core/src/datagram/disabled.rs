@@ -3,7 +3,7 @@
 
 use crate::datagram::{ConnectionInfo, Endpoint, Packet, PreConnectionInfo, Receiver, Sender};
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Disabled(());
 
 impl Endpoint for Disabled {
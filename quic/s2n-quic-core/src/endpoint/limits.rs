@@ -59,6 +59,62 @@ impl Outcome {
     }
 }
 
+/// How the endpoint shares the transmission queue between its connections
+///
+/// The endpoint gives each connection which wants to transmit a turn to write its packets into
+/// the transmission queue. Without fairness, a connection with a large amount of data and a
+/// large congestion window can fill the whole queue in its turn.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TransmitFairness {
+    /// Each connection transmits as much as it can in its turn
+    ///
+    /// Use `TransmitFairness::disabled()` to construct this variant
+    #[non_exhaustive]
+    Disabled,
+
+    /// Each connection transmits up to `quantum` bytes in its turn
+    ///
+    /// Connections which used their quantum take another turn after all of the other
+    /// connections, until the queue is full. A connection may exceed its quantum by up to one
+    /// datagram.
+    ///
+    /// Use `TransmitFairness::round_robin(quantum)` to construct this variant
+    #[non_exhaustive]
+    RoundRobin { quantum: usize },
+}
+
+impl Default for TransmitFairness {
+    fn default() -> Self {
+        Self::Disabled
+    }
+}
+
+impl TransmitFairness {
+    /// Lets each connection transmit as much as it can in its turn
+    pub fn disabled() -> Self {
+        Self::Disabled
+    }
+
+    /// Lets each connection transmit up to `quantum` bytes in its turn
+    ///
+    /// A quantum of zero is rounded up to a single datagram per turn.
+    pub fn round_robin(quantum: usize) -> Self {
+        Self::RoundRobin {
+            quantum: quantum.max(1),
+        }
+    }
+
+    /// Returns the number of bytes each connection can transmit in its turn, if limited
+    #[inline]
+    pub fn quantum(&self) -> Option<usize> {
+        match self {
+            Self::Disabled => None,
+            Self::RoundRobin { quantum } => Some(*quantum),
+        }
+    }
+}
+
 /// A ConnectionAttempt holds information about the state of endpoint receiving a connect, along
 /// with information about the connection. This can be used to make decisions about the Outcome of
 /// an attempted connection
@@ -71,6 +127,13 @@ pub struct ConnectionAttempt<'a> {
     /// Number of open connections
     pub connection_count: usize,
 
+    /// Number of received datagrams which are waiting to be processed by the endpoint,
+    /// including the datagram of the connection attempt
+    pub rx_backlog: usize,
+
+    /// Number of bytes which were queued for transmission but not yet sent by the IO provider
+    pub unsent_bytes: usize,
+
     /// The unverified address of the connecting peer
     /// This address comes from the datagram
     pub remote_address: SocketAddress<'a>,
@@ -88,10 +151,19 @@ impl<'a> ConnectionAttempt<'a> {
         Self {
             inflight_handshakes,
             connection_count,
+            rx_backlog: 0,
+            unsent_bytes: 0,
             remote_address: remote_address.into_event(),
             timestamp,
         }
     }
+
+    #[doc(hidden)]
+    pub fn with_queue_depths(mut self, rx_backlog: usize, unsent_bytes: usize) -> Self {
+        self.rx_backlog = rx_backlog;
+        self.unsent_bytes = unsent_bytes;
+        self
+    }
 }
 
 pub trait Limiter: 'static + Send {
@@ -118,5 +190,34 @@ pub trait Limiter: 'static + Send {
     ///    }
     /// }
     /// ```
+    ///
+    /// The queue depths of the endpoint can be used to shed load gracefully, e.g. by deferring new
+    /// connections with Retry packets once the endpoint falls behind and dropping them once it's
+    /// overwhelmed.
+    ///
+    /// ```rust
+    /// # mod s2n_quic { pub mod provider { pub mod endpoint_limits { pub use s2n_quic_core::endpoint::limits::*; } } }
+    /// use s2n_quic::provider::endpoint_limits::{Limiter, ConnectionAttempt, Outcome};
+    ///
+    /// struct Brownout;
+    ///
+    /// impl Limiter for Brownout {
+    ///    fn on_connection_attempt(&mut self, info: &ConnectionAttempt) -> Outcome {
+    ///        match info.rx_backlog {
+    ///            0..=63 => Outcome::allow(),
+    ///            64..=255 => Outcome::retry(),
+    ///            _ => Outcome::drop(),
+    ///        }
+    ///    }
+    /// }
+    /// ```
     fn on_connection_attempt(&mut self, info: &ConnectionAttempt) -> Outcome;
+
+    /// Returns how the transmission queue is shared between the connections of the endpoint
+    ///
+    /// This is queried each time the endpoint fills the transmission queue.
+    #[inline]
+    fn transmit_fairness(&self) -> TransmitFairness {
+        TransmitFairness::default()
+    }
 }
@@ -13,6 +13,8 @@ use core::{
 };
 
 pub mod limits;
+pub mod retry_offload;
+pub mod tenant;
 pub use limits::Limiter;
 
 /// Enumerates endpoint types
@@ -0,0 +1,99 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Splits a server into stateless front-ends, which validate the addresses of clients, and
+//! stateful back-ends, which handle the connections
+//!
+//! A front-end never creates connections. It answers every Initial packet without an address
+//! validation token with a Retry packet and drops Initial packets with invalid tokens. All of the
+//! other datagrams are handed to a [`Forwarder`], which delivers them to the back-ends. Floods of
+//! Initial packets from spoofed addresses are therefore absorbed by the front-ends without
+//! allocating any state, while the back-ends only see clients which proved that they own their
+//! address.
+//!
+//! The front-ends and back-ends need to share the keys of the address validation tokens. The
+//! back-ends validate the token of each forwarded Initial packet again, which proves that the
+//! packet was validated by a front-end, so forwarders must preserve the address of the client,
+//! e.g. by encapsulating the datagrams. The back-ends send their packets to the clients directly.
+//!
+//! Each datagram of a connection needs to be forwarded to the same back-end. Initial packets
+//! carry the token until the handshake completes, so forwarders can route datagrams with an
+//! [`original_destination_connection_id`](Datagram::original_destination_connection_id) by that
+//! connection ID, and all of the other datagrams by the connection IDs issued by the back-ends,
+//! e.g. with [`shard::index`](crate::connection::id::shard::index).
+
+use crate::{
+    event::{api::SocketAddress, IntoEvent, Timestamp},
+    inet,
+};
+
+/// A datagram which was received by a front-end and is forwarded to a back-end
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct Datagram<'a> {
+    /// The address of the client
+    pub remote_address: SocketAddress<'a>,
+
+    /// The address the datagram was received on
+    pub local_address: SocketAddress<'a>,
+
+    /// The payload of the datagram, as it was received from the client
+    pub payload: &'a [u8],
+
+    /// The Destination Connection ID of the first Initial packet of the client
+    ///
+    /// This is only set if the datagram starts with an Initial packet with a valid address
+    /// validation token. Other datagrams belong to connections which were already forwarded to
+    /// a back-end.
+    pub original_destination_connection_id: Option<&'a [u8]>,
+
+    pub timestamp: Timestamp,
+}
+
+impl<'a> Datagram<'a> {
+    #[doc(hidden)]
+    pub fn new(
+        remote_address: &'a inet::SocketAddress,
+        local_address: &'a inet::SocketAddress,
+        payload: &'a [u8],
+        original_destination_connection_id: Option<&'a [u8]>,
+        timestamp: Timestamp,
+    ) -> Self {
+        Self {
+            remote_address: remote_address.into_event(),
+            local_address: local_address.into_event(),
+            payload,
+            original_destination_connection_id,
+            timestamp,
+        }
+    }
+}
+
+/// Delivers the datagrams received by a front-end to the back-ends
+pub trait Forwarder: 'static + Send {
+    /// Returns `true` if the endpoint is a front-end
+    ///
+    /// Front-ends don't create any connections and hand all of the datagrams which aren't
+    /// answered with a Retry packet to [`Forwarder::forward`].
+    #[inline]
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    /// Called with each datagram which should be handled by a back-end
+    fn forward(&mut self, datagram: &Datagram);
+}
+
+/// The endpoint handles all of the connections itself
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Disabled;
+
+impl Forwarder for Disabled {
+    #[inline]
+    fn is_enabled(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn forward(&mut self, _datagram: &Datagram) {}
+}
@@ -0,0 +1,169 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Groups the connections of a server into tenants in order to limit the resources each tenant
+//! may use
+//!
+//! Servers shared by multiple tenants, e.g. gateways terminating the connections of several
+//! domains, can assign each connection to a tenant with a [`Classifier`]. The classifier is
+//! consulted once the first Initial packet of a connection has been processed, at which point the
+//! server name requested by the client is usually known. It either admits the connection on
+//! behalf of a [`Tenant`], which is notified of the resources used by the connection for the rest
+//! of its lifetime, or refuses the connection if the tenant exhausted its quota.
+
+use crate::{
+    application::ServerName,
+    event::{api::SocketAddress, IntoEvent, Timestamp},
+    inet,
+};
+use core::fmt;
+
+/// Information about the connection being classified
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct Info<'a> {
+    /// The server name requested by the client
+    ///
+    /// This is `None` if the client didn't send a server name, or if the ClientHello didn't fit
+    /// in the first Initial packet.
+    pub server_name: Option<&'a ServerName>,
+
+    /// The address token included in the first Initial packet
+    ///
+    /// This is empty if the client didn't send a token.
+    pub token: &'a [u8],
+
+    /// The address of the peer
+    pub remote_address: SocketAddress<'a>,
+
+    pub timestamp: Timestamp,
+}
+
+impl<'a> Info<'a> {
+    #[doc(hidden)]
+    pub fn new(
+        server_name: Option<&'a ServerName>,
+        token: &'a [u8],
+        remote_address: &'a inet::SocketAddress,
+        timestamp: Timestamp,
+    ) -> Self {
+        Self {
+            server_name,
+            token,
+            remote_address: remote_address.into_event(),
+            timestamp,
+        }
+    }
+}
+
+/// Outcome describes how the endpoint proceeds with a classified connection
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum Outcome<T> {
+    /// The connection doesn't belong to any tenant and isn't subject to quotas
+    ///
+    /// Use `Outcome::exempt()` to construct this variant
+    #[non_exhaustive]
+    Exempt,
+
+    /// The connection is admitted on behalf of the tenant
+    ///
+    /// Use `Outcome::admit(tenant)` to construct this variant
+    #[non_exhaustive]
+    Admit { tenant: T },
+
+    /// The tenant exhausted its quota and the connection is refused
+    ///
+    /// The connection is closed with a `CONNECTION_REFUSED` error.
+    ///
+    /// Use `Outcome::refuse()` to construct this variant
+    #[non_exhaustive]
+    Refuse,
+}
+
+impl<T> Outcome<T> {
+    /// The connection isn't subject to quotas
+    pub fn exempt() -> Self {
+        Self::Exempt
+    }
+
+    /// Admit the connection on behalf of the tenant
+    pub fn admit(tenant: T) -> Self {
+        Self::Admit { tenant }
+    }
+
+    /// Refuse the connection
+    pub fn refuse() -> Self {
+        Self::Refuse
+    }
+}
+
+/// Tracks the resources a single connection uses on behalf of a tenant
+///
+/// The tenant is dropped along with the connection, which releases the resources held
+/// by it.
+pub trait Tenant: 'static + Send + fmt::Debug {
+    /// Called when the number of streams open on the connection changed
+    ///
+    /// The count includes the streams opened by both the application and the peer.
+    fn on_open_streams(&mut self, count: u64);
+
+    /// Called with the number of stream bytes the connection sent or received
+    fn on_stream_bytes(&mut self, bytes: u64);
+}
+
+/// Assigns connections to tenants
+pub trait Classifier: 'static + Send {
+    type Tenant: Tenant;
+
+    /// Called once the first Initial packet of a connection has been processed
+    ///
+    /// ```rust
+    /// # mod s2n_quic { pub mod provider { pub mod tenant { pub use s2n_quic_core::endpoint::tenant::*; } } }
+    /// use s2n_quic::provider::tenant::{Classifier, Info, Outcome, Tenant};
+    ///
+    /// /// Refuses connections for an unknown server name
+    /// struct KnownServerNames;
+    ///
+    /// #[derive(Debug)]
+    /// struct Connection;
+    ///
+    /// impl Tenant for Connection {
+    ///     fn on_open_streams(&mut self, _count: u64) {}
+    ///     fn on_stream_bytes(&mut self, _bytes: u64) {}
+    /// }
+    ///
+    /// impl Classifier for KnownServerNames {
+    ///     type Tenant = Connection;
+    ///
+    ///     fn on_connection(&mut self, info: &Info) -> Outcome<Connection> {
+    ///         match info.server_name.map(|name| &**name) {
+    ///             Some("example.com") => Outcome::admit(Connection),
+    ///             _ => Outcome::refuse(),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    fn on_connection(&mut self, info: &Info) -> Outcome<Self::Tenant>;
+}
+
+/// Exempts all connections from quotas
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Disabled;
+
+impl Classifier for Disabled {
+    type Tenant = Disabled;
+
+    #[inline]
+    fn on_connection(&mut self, _info: &Info) -> Outcome<Self::Tenant> {
+        Outcome::exempt()
+    }
+}
+
+impl Tenant for Disabled {
+    #[inline]
+    fn on_open_streams(&mut self, _count: u64) {}
+
+    #[inline]
+    fn on_stream_bytes(&mut self, _bytes: u64) {}
+}
@@ -55,6 +55,7 @@ ident_into_event!(
     isize,
     Duration,
     bool,
+    connection::CorrelationId,
     connection::Error,
     endpoint::Location,
 );
@@ -102,6 +103,7 @@ impl Timestamp {
     /// # let meta: event::api::ConnectionMeta = event::builder::ConnectionMeta {
     /// #     endpoint_type: endpoint::Type::Server,
     /// #     id: 0,
+    /// #     correlation_id: Default::default(),
     /// #     timestamp: unsafe { Timestamp::from_duration(Duration::from_secs(1) )},
     /// # }.into_event();
     /// let event_time = start_time + meta.timestamp.duration_since_start();
@@ -15,6 +15,8 @@ pub mod api {
     pub struct ConnectionMeta {
         pub endpoint_type: EndpointType,
         pub id: u64,
+        #[doc = " The correlation ID of the connection, which is either random or provided by the application"]
+        pub correlation_id: crate::connection::CorrelationId,
         pub timestamp: crate::event::Timestamp,
     }
     #[derive(Clone, Debug)]
@@ -46,6 +48,11 @@ pub mod api {
         pub initial_max_streams_bidi: u64,
         pub initial_max_streams_uni: u64,
         pub max_datagram_frame_size: u64,
+        #[doc = " The parameters with IDs which are not known to s2n-quic"]
+        #[doc = ""]
+        #[doc = " The parameters are encoded as a sequence of transport parameters, which can be read with"]
+        #[doc = " `s2n_quic_core::transport::parameters::CustomParametersIter`."]
+        pub custom_parameters: &'a [u8],
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
@@ -93,6 +100,24 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
+    pub enum AckRangeRejectReason {
+        #[non_exhaustive]
+        #[doc = " The range acknowledged a packet number that has not been sent yet."]
+        PacketNotSent {},
+        #[non_exhaustive]
+        #[doc = " The range acknowledged a packet number that was intentionally skipped."]
+        #[doc = ""]
+        #[doc = " Packet numbers are periodically skipped to detect peers optimistically"]
+        #[doc = " acknowledging packets in an attempt to increase the congestion window."]
+        SkippedPacketNumber {},
+        #[non_exhaustive]
+        #[doc = " The ACK frame contained more ranges than the endpoint accepts for a single frame."]
+        #[doc = ""]
+        #[doc = " The reported range spans all of the ranges in the frame."]
+        RangeLimitExceeded {},
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
     pub enum DuplicatePacketError {
         #[non_exhaustive]
         #[doc = " The packet number was already received and is a duplicate."]
@@ -174,6 +199,9 @@ pub mod api {
         HandshakeDone {},
         #[non_exhaustive]
         Datagram { len: u16 },
+        #[non_exhaustive]
+        #[doc = " A frame of an extension which was registered by the application"]
+        Extension { frame_type: u64, len: u16 },
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
@@ -277,6 +305,26 @@ pub mod api {
         #[non_exhaustive]
         #[doc = " The peer initiated a connection migration without supplying enough connection IDs to use."]
         InsufficientConnectionIds {},
+        #[non_exhaustive]
+        #[doc = " The datagram contained a duplicate Initial packet for a handshake which is already in progress."]
+        #[doc = ""]
+        #[doc = " Clients which retransmit their first Initial packet with a new Destination Connection Id"]
+        #[doc = " are identified by their address, Source Connection Id and token."]
+        DuplicateInitial {},
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " The outcome of a connection attempt which the endpoint limits didn't allow"]
+    pub enum ConnectionAttemptOutcome {
+        #[non_exhaustive]
+        #[doc = " The client was asked to validate its address with a Retry packet"]
+        Retry {},
+        #[non_exhaustive]
+        #[doc = " The connection attempt was silently dropped"]
+        Drop {},
+        #[non_exhaustive]
+        #[doc = " The connection attempt was rejected"]
+        Close {},
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
@@ -485,6 +533,82 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
+    #[doc = " The state of a congestion controller"]
+    pub enum CongestionState {
+        #[non_exhaustive]
+        #[doc = " The congestion window grows by the number of bytes acknowledged"]
+        SlowStart {},
+        #[non_exhaustive]
+        #[doc = " HyStart++ detected an increase in the round trip time and grows the congestion window"]
+        #[doc = " more slowly until slow start is resumed or exited"]
+        ConservativeSlowStart {},
+        #[non_exhaustive]
+        #[doc = " The congestion window was reduced in response to congestion, and is not increased"]
+        #[doc = " until a packet sent after the reduction is acknowledged"]
+        Recovery {},
+        #[non_exhaustive]
+        #[doc = " The congestion window grows according to the congestion avoidance algorithm"]
+        CongestionAvoidance {},
+        #[non_exhaustive]
+        #[doc = " The congestion window is not increased since the application doesn't send enough data"]
+        #[doc = " to fill it"]
+        ApplicationLimited {},
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " A metric of a connection which is compared against a threshold of the connection limits"]
+    pub enum SloMetric {
+        #[non_exhaustive]
+        #[doc = " The smoothed round trip time of the active path"]
+        RoundTripTime { smoothed_rtt: Duration },
+        #[non_exhaustive]
+        #[doc = " The packets declared lost out of all packets which were acknowledged or declared lost"]
+        #[doc = " during the last period"]
+        LossRate {
+            lost_packets: u64,
+            total_packets: u64,
+        },
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " A phase of the handshake of a connection"]
+    pub enum HandshakePhase {
+        #[non_exhaustive]
+        #[doc = " The first Initial packet was sent"]
+        InitialSent {},
+        #[non_exhaustive]
+        #[doc = " The first Initial packet was received from the peer"]
+        InitialReceived {},
+        #[non_exhaustive]
+        #[doc = " The first Handshake packet was sent"]
+        HandshakeSent {},
+        #[non_exhaustive]
+        #[doc = " The first Handshake packet was received from the peer"]
+        HandshakeReceived {},
+        #[non_exhaustive]
+        #[doc = " The handshake was confirmed"]
+        HandshakeConfirmed {},
+        #[non_exhaustive]
+        #[doc = " The first byte of stream data was received from the peer"]
+        ApplicationDataReceived {},
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " A protocol violation by the peer that the specification permits ignoring"]
+    pub enum ProtocolViolation {
+        #[non_exhaustive]
+        #[doc = " A RETIRE_CONNECTION_ID frame referred to the connection ID the packet containing it was"]
+        #[doc = " sent to"]
+        RetireCurrentConnectionId {},
+        #[non_exhaustive]
+        #[doc = " A NEW_CONNECTION_ID frame was inconsistent with a previously issued connection ID"]
+        InconsistentNewConnectionId {},
+        #[non_exhaustive]
+        #[doc = " An ACK frame acknowledged a packet number that was never sent"]
+        AckForUnsentPacket {},
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
     #[doc = " The reason the MTU was updated"]
     pub enum MtuUpdatedCause {
         #[non_exhaustive]
@@ -663,6 +787,20 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
+    #[doc = " ACK range was rejected"]
+    #[doc = ""]
+    #[doc = " Rejected ranges cause the connection to be closed with a PROTOCOL_VIOLATION."]
+    pub struct AckRangeRejected<'a> {
+        pub packet_header: PacketHeader,
+        pub path: Path<'a>,
+        pub ack_range: RangeInclusive<u64>,
+        pub reason: AckRangeRejectReason,
+    }
+    impl<'a> Event for AckRangeRejected<'a> {
+        const NAME: &'static str = "recovery:ack_range_rejected";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
     #[doc = " Packet was dropped with the given reason"]
     pub struct PacketDropped<'a> {
         pub reason: PacketDropReason<'a>,
@@ -728,6 +866,21 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
+    #[doc = " Transport parameters negotiated by the connection"]
+    #[doc = ""]
+    #[doc = " This is emitted once the handshake completes, allowing the values sent to the peer to be"]
+    #[doc = " compared with the values received from the peer."]
+    pub struct TransportParametersNegotiated<'a> {
+        #[doc = " The transport parameters sent to the peer"]
+        pub local_transport_parameters: TransportParameters<'a>,
+        #[doc = " The transport parameters received from the peer"]
+        pub peer_transport_parameters: TransportParameters<'a>,
+    }
+    impl<'a> Event for TransportParametersNegotiated<'a> {
+        const NAME: &'static str = "transport:transport_parameters_negotiated";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
     #[doc = " Datagram sent by a connection"]
     pub struct DatagramSent {
         pub len: u16,
@@ -828,6 +981,22 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
+    #[doc = " The peer sent more bytes than allowed before its address was validated"]
+    #[doc = ""]
+    #[doc = " The connection is closed without notifying the peer."]
+    pub struct UnvalidatedHandshakeBytesExceeded {
+        #[doc = " The number of bytes received from the peer before its address was validated"]
+        pub bytes_received: u64,
+        #[doc = " The number of bytes sent to the peer before its address was validated"]
+        pub bytes_sent: u64,
+        #[doc = " The configured limit of bytes received from the peer before its address is validated"]
+        pub max_unvalidated_handshake_bytes: u64,
+    }
+    impl Event for UnvalidatedHandshakeBytesExceeded {
+        const NAME: &'static str = "security:unvalidated_handshake_bytes_exceeded";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
     pub struct RxStreamProgress {
         pub bytes: usize,
     }
@@ -836,6 +1005,54 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
+    #[doc = " A STREAM frame was received and accepted by the stream"]
+    #[doc = ""]
+    #[doc = " The event is published for each STREAM frame as it was received, so the range may overlap"]
+    #[doc = " data that was previously received."]
+    pub struct StreamDataReceived<'a> {
+        pub stream_id: u64,
+        pub offset: u64,
+        pub len: usize,
+        pub is_fin: bool,
+        #[doc = " The plaintext data carried by the frame"]
+        #[doc = ""]
+        #[doc = " This is only set if the subscriber requests access through"]
+        #[doc = " `Subscriber::stream_data_access`."]
+        pub data: Option<&'a [u8]>,
+    }
+    impl<'a> Event for StreamDataReceived<'a> {
+        const NAME: &'static str = "transport:stream_data_received";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " Data sent on a stream was acknowledged by the peer"]
+    #[doc = ""]
+    #[doc = " The event is published for each range of the stream which was carried by an acknowledged"]
+    #[doc = " packet, so the range may overlap data that was previously acknowledged if it was retransmitted."]
+    pub struct StreamDataAcked {
+        pub stream_id: u64,
+        pub offset: u64,
+        pub len: u64,
+    }
+    impl Event for StreamDataAcked {
+        const NAME: &'static str = "transport:stream_data_acked";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " Data sent on a stream was declared lost"]
+    #[doc = ""]
+    #[doc = " The range is retransmitted, unless it was acknowledged in another packet or the stream was"]
+    #[doc = " reset in the meantime."]
+    pub struct StreamDataLost {
+        pub stream_id: u64,
+        pub offset: u64,
+        pub len: u64,
+    }
+    impl Event for StreamDataLost {
+        const NAME: &'static str = "transport:stream_data_lost";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
     pub struct TxStreamProgress {
         pub bytes: usize,
     }
@@ -863,6 +1080,15 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
+    #[doc = " A protocol violation by the peer was ignored by the connection's protocol violation policy"]
+    pub struct ProtocolViolationIgnored {
+        pub violation: ProtocolViolation,
+    }
+    impl Event for ProtocolViolationIgnored {
+        const NAME: &'static str = "transport:protocol_violation_ignored";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
     #[doc = " The slow start congestion controller state has been exited"]
     pub struct SlowStartExited<'a> {
         pub path: Path<'a>,
@@ -874,6 +1100,58 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
+    #[doc = " The congestion controller entered a new state"]
+    pub struct CongestionStateUpdated<'a> {
+        pub path: Path<'a>,
+        pub previous_state: CongestionState,
+        pub state: CongestionState,
+        pub congestion_window: u32,
+    }
+    impl<'a> Event for CongestionStateUpdated<'a> {
+        const NAME: &'static str = "recovery:congestion_state_updated";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " The estimated bandwidth or minimum round trip time of a path changed"]
+    #[doc = ""]
+    #[doc = " Applications can subscribe to this event to adapt to changes of the path capacity,"]
+    #[doc = " for example by selecting a different bitrate, without measuring the throughput themselves."]
+    pub struct BandwidthEstimateUpdated<'a> {
+        pub path: Path<'a>,
+        #[doc = " The estimated bandwidth in bytes per second, or `None` if the congestion"]
+        #[doc = " controller does not estimate the bandwidth"]
+        pub bytes_per_second: Option<u64>,
+        #[doc = " The minimum round trip time observed on the path"]
+        pub min_rtt: Duration,
+    }
+    impl<'a> Event for BandwidthEstimateUpdated<'a> {
+        const NAME: &'static str = "recovery:bandwidth_estimate_updated";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " A round trip time or loss rate threshold of the connection was breached or cleared"]
+    pub struct SloUpdated<'a> {
+        pub path: Path<'a>,
+        pub metric: SloMetric,
+        #[doc = " `true` if the threshold was breached, `false` if it was cleared"]
+        pub breached: bool,
+    }
+    impl<'a> Event for SloUpdated<'a> {
+        const NAME: &'static str = "recovery:slo_updated";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " The connection reached a phase of the handshake for the first time"]
+    pub struct HandshakePhaseReached {
+        pub phase: HandshakePhase,
+        #[doc = " The time elapsed since the connection was created"]
+        pub elapsed: Duration,
+    }
+    impl Event for HandshakePhaseReached {
+        const NAME: &'static str = "connectivity:handshake_phase_reached";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
     #[doc = " QUIC version"]
     pub struct VersionInformation<'a> {
         pub server_versions: &'a [u32],
@@ -922,6 +1200,17 @@ pub mod api {
     #[doc = " Datagram received by the endpoint"]
     pub struct EndpointDatagramReceived {
         pub len: u16,
+        #[doc = " The time at which the kernel received the datagram, relative to the UNIX epoch"]
+        #[doc = ""]
+        #[doc = " This is only available if the IO provider enabled timestamping on the socket."]
+        pub software_timestamp: Option<Duration>,
+        #[doc = " The time at which the network interface received the datagram"]
+        #[doc = ""]
+        #[doc = " This is only available if the IO provider enabled hardware timestamping on the socket"]
+        #[doc = " and the network interface."]
+        pub hardware_timestamp: Option<Duration>,
+        #[doc = " How long the datagram waited in the socket receive queue before the endpoint read it"]
+        pub queue_delay: Option<Duration>,
     }
     impl Event for EndpointDatagramReceived {
         const NAME: &'static str = "transport:datagram_received";
@@ -938,6 +1227,29 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
+    #[doc = " Emitted when a datagram was checked for a stateless reset but didn't end in a known token"]
+    #[doc = ""]
+    #[doc = " A large number of these events may indicate that an off-path attacker is attempting to guess"]
+    #[doc = " a stateless reset token in order to close connections."]
+    pub struct EndpointStatelessResetTokenMismatch {
+        pub len: u16,
+    }
+    impl Event for EndpointStatelessResetTokenMismatch {
+        const NAME: &'static str = "security:stateless_reset_token_mismatch";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " Emitted when the endpoint limits don't allow a connection attempt to proceed"]
+    pub struct EndpointConnectionAttemptLimited<'a> {
+        #[doc = " The unvalidated address of the peer"]
+        pub remote_address: SocketAddress<'a>,
+        pub outcome: ConnectionAttemptOutcome,
+    }
+    impl<'a> Event for EndpointConnectionAttemptLimited<'a> {
+        const NAME: &'static str = "transport:connection_attempt_limited";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
     pub struct EndpointConnectionAttemptFailed {
         pub error: crate::connection::Error,
     }
@@ -946,6 +1258,52 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
+    #[doc = " Emitted when the endpoint stops accepting connections and starts draining the open ones"]
+    pub struct EndpointDrainingStarted {
+        #[doc = " The number of connections which are still open"]
+        pub active_connections: usize,
+        #[doc = " The amount of time the open connections have to close before the endpoint closes them"]
+        pub deadline: Option<Duration>,
+    }
+    impl Event for EndpointDrainingStarted {
+        const NAME: &'static str = "connectivity:draining_started";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " Emitted when a connection finishes while the endpoint is draining"]
+    pub struct EndpointDrainingProgress {
+        #[doc = " The number of connections which are still open"]
+        pub remaining_connections: usize,
+    }
+    impl Event for EndpointDrainingProgress {
+        const NAME: &'static str = "connectivity:draining_progress";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " Emitted when the draining deadline expires and the endpoint closes the remaining connections"]
+    pub struct EndpointDrainingDeadlineExceeded {
+        #[doc = " The number of connections which were closed by the endpoint"]
+        pub remaining_connections: usize,
+    }
+    impl Event for EndpointDrainingDeadlineExceeded {
+        const NAME: &'static str = "connectivity:draining_deadline_exceeded";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " Emitted when the endpoint limited the connections to their quantum while filling the transmission queue"]
+    pub struct EndpointTransmitScheduled {
+        #[doc = " The number of rounds over the connections which wanted to transmit"]
+        pub rounds: usize,
+        #[doc = " The number of turns the connections took to transmit"]
+        pub turns: usize,
+        #[doc = " The number of turns which ended because the connection used its quantum"]
+        pub quantum_exhausted: usize,
+    }
+    impl Event for EndpointTransmitScheduled {
+        const NAME: &'static str = "transport:transmit_scheduled";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
     #[doc = " Emitted when the platform sends at least one packet"]
     pub struct PlatformTx {
         #[doc = " The number of packets sent"]
@@ -1032,6 +1390,20 @@ pub mod api {
         #[non_exhaustive]
         #[doc = " Emitted when the maximum transmission unit is configured"]
         MaxMtu { mtu: u16 },
+        #[non_exhaustive]
+        #[doc = " Emitted when timestamping of received datagrams is enabled"]
+        RxTimestamps {
+            #[doc = " Whether the timestamps of the network interface were requested as well"]
+            hardware: bool,
+        },
+        #[non_exhaustive]
+        #[doc = " Emitted when the offloading of pacing to the kernel was configured"]
+        PacingOffload {
+            #[doc = " Whether the socket supports transmit times"]
+            #[doc = ""]
+            #[doc = " If this value is `false`, the endpoint paces its transmissions itself."]
+            enabled: bool,
+        },
     }
     impl<'a> IntoEvent<builder::PreferredAddress<'a>>
         for &'a crate::transport::parameters::PreferredAddress
@@ -1202,6 +1574,16 @@ pub mod api {
             }
         }
     }
+    impl IntoEvent<builder::AckRangeRejectReason> for crate::ack::Error {
+        fn into_event(self) -> builder::AckRangeRejectReason {
+            use crate::ack::Error;
+            match self {
+                Error::PacketNotSent => builder::AckRangeRejectReason::PacketNotSent {},
+                Error::SkippedPacketNumber => builder::AckRangeRejectReason::SkippedPacketNumber {},
+                Error::RangeLimitExceeded => builder::AckRangeRejectReason::RangeLimitExceeded {},
+            }
+        }
+    }
     impl IntoEvent<builder::DuplicatePacketError> for crate::packet::number::SlidingWindowError {
         fn into_event(self) -> builder::DuplicatePacketError {
             use crate::packet::number::SlidingWindowError;
@@ -1485,7 +1867,7 @@ pub mod tracing {
                 api::EndpointType::Client {} => self.client.id(),
                 api::EndpointType::Server {} => self.server.id(),
             };
-            tracing :: span ! (target : "s2n_quic" , parent : parent , tracing :: Level :: DEBUG , "conn" , id = meta . id)
+            tracing :: span ! (target : "s2n_quic" , parent : parent , tracing :: Level :: DEBUG , "conn" , id = meta . id , correlation_id = tracing :: field :: display (meta . correlation_id))
         }
         #[inline]
         fn on_application_protocol_information(
@@ -1678,6 +2060,22 @@ pub mod tracing {
             tracing :: event ! (target : "ack_range_received" , parent : id , tracing :: Level :: DEBUG , packet_header = tracing :: field :: debug (packet_header) , path = tracing :: field :: debug (path) , ack_range = tracing :: field :: debug (ack_range));
         }
         #[inline]
+        fn on_ack_range_rejected(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::AckRangeRejected,
+        ) {
+            let id = context.id();
+            let api::AckRangeRejected {
+                packet_header,
+                path,
+                ack_range,
+                reason,
+            } = event;
+            tracing :: event ! (target : "ack_range_rejected" , parent : id , tracing :: Level :: DEBUG , packet_header = tracing :: field :: debug (packet_header) , path = tracing :: field :: debug (path) , ack_range = tracing :: field :: debug (ack_range) , reason = tracing :: field :: debug (reason));
+        }
+        #[inline]
         fn on_packet_dropped(
             &mut self,
             context: &mut Self::ConnectionContext,
@@ -1764,6 +2162,20 @@ pub mod tracing {
             tracing :: event ! (target : "transport_parameters_received" , parent : id , tracing :: Level :: DEBUG , transport_parameters = tracing :: field :: debug (transport_parameters));
         }
         #[inline]
+        fn on_transport_parameters_negotiated(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::TransportParametersNegotiated,
+        ) {
+            let id = context.id();
+            let api::TransportParametersNegotiated {
+                local_transport_parameters,
+                peer_transport_parameters,
+            } = event;
+            tracing :: event ! (target : "transport_parameters_negotiated" , parent : id , tracing :: Level :: DEBUG , local_transport_parameters = tracing :: field :: debug (local_transport_parameters) , peer_transport_parameters = tracing :: field :: debug (peer_transport_parameters));
+        }
+        #[inline]
         fn on_datagram_sent(
             &mut self,
             context: &mut Self::ConnectionContext,
@@ -1883,6 +2295,21 @@ pub mod tracing {
             tracing :: event ! (target : "tls_server_hello" , parent : id , tracing :: Level :: DEBUG , payload = tracing :: field :: debug (payload));
         }
         #[inline]
+        fn on_unvalidated_handshake_bytes_exceeded(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::UnvalidatedHandshakeBytesExceeded,
+        ) {
+            let id = context.id();
+            let api::UnvalidatedHandshakeBytesExceeded {
+                bytes_received,
+                bytes_sent,
+                max_unvalidated_handshake_bytes,
+            } = event;
+            tracing :: event ! (target : "unvalidated_handshake_bytes_exceeded" , parent : id , tracing :: Level :: DEBUG , bytes_received = tracing :: field :: debug (bytes_received) , bytes_sent = tracing :: field :: debug (bytes_sent) , max_unvalidated_handshake_bytes = tracing :: field :: debug (max_unvalidated_handshake_bytes));
+        }
+        #[inline]
         fn on_rx_stream_progress(
             &mut self,
             context: &mut Self::ConnectionContext,
@@ -1894,6 +2321,53 @@ pub mod tracing {
             tracing :: event ! (target : "rx_stream_progress" , parent : id , tracing :: Level :: DEBUG , bytes = tracing :: field :: debug (bytes));
         }
         #[inline]
+        fn on_stream_data_received(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::StreamDataReceived,
+        ) {
+            let id = context.id();
+            let api::StreamDataReceived {
+                stream_id,
+                offset,
+                len,
+                is_fin,
+                data,
+            } = event;
+            tracing :: event ! (target : "stream_data_received" , parent : id , tracing :: Level :: DEBUG , stream_id = tracing :: field :: debug (stream_id) , offset = tracing :: field :: debug (offset) , len = tracing :: field :: debug (len) , is_fin = tracing :: field :: debug (is_fin) , data = tracing :: field :: debug (data));
+        }
+        #[inline]
+        fn on_stream_data_acked(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::StreamDataAcked,
+        ) {
+            let id = context.id();
+            let api::StreamDataAcked {
+                stream_id,
+                offset,
+                len,
+            } = event;
+            tracing :: event ! (target : "stream_data_acked" , parent : id , tracing :: Level :: DEBUG , stream_id = tracing :: field :: debug (stream_id) , offset = tracing :: field :: debug (offset) , len = tracing :: field :: debug (len));
+        }
+        #[inline]
+        fn on_stream_data_lost(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::StreamDataLost,
+        ) {
+            let id = context.id();
+            let api::StreamDataLost {
+                stream_id,
+                offset,
+                len,
+            } = event;
+            tracing :: event ! (target : "stream_data_lost" , parent : id , tracing :: Level :: DEBUG , stream_id = tracing :: field :: debug (stream_id) , offset = tracing :: field :: debug (offset) , len = tracing :: field :: debug (len));
+        }
+        #[inline]
         fn on_tx_stream_progress(
             &mut self,
             context: &mut Self::ConnectionContext,
@@ -1931,6 +2405,17 @@ pub mod tracing {
             tracing :: event ! (target : "mtu_updated" , parent : id , tracing :: Level :: DEBUG , path_id = tracing :: field :: debug (path_id) , mtu = tracing :: field :: debug (mtu) , cause = tracing :: field :: debug (cause));
         }
         #[inline]
+        fn on_protocol_violation_ignored(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::ProtocolViolationIgnored,
+        ) {
+            let id = context.id();
+            let api::ProtocolViolationIgnored { violation } = event;
+            tracing :: event ! (target : "protocol_violation_ignored" , parent : id , tracing :: Level :: DEBUG , violation = tracing :: field :: debug (violation));
+        }
+        #[inline]
         fn on_slow_start_exited(
             &mut self,
             context: &mut Self::ConnectionContext,
@@ -1946,6 +2431,63 @@ pub mod tracing {
             tracing :: event ! (target : "slow_start_exited" , parent : id , tracing :: Level :: DEBUG , path = tracing :: field :: debug (path) , cause = tracing :: field :: debug (cause) , congestion_window = tracing :: field :: debug (congestion_window));
         }
         #[inline]
+        fn on_congestion_state_updated(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::CongestionStateUpdated,
+        ) {
+            let id = context.id();
+            let api::CongestionStateUpdated {
+                path,
+                previous_state,
+                state,
+                congestion_window,
+            } = event;
+            tracing :: event ! (target : "congestion_state_updated" , parent : id , tracing :: Level :: DEBUG , path = tracing :: field :: debug (path) , previous_state = tracing :: field :: debug (previous_state) , state = tracing :: field :: debug (state) , congestion_window = tracing :: field :: debug (congestion_window));
+        }
+        #[inline]
+        fn on_bandwidth_estimate_updated(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::BandwidthEstimateUpdated,
+        ) {
+            let id = context.id();
+            let api::BandwidthEstimateUpdated {
+                path,
+                bytes_per_second,
+                min_rtt,
+            } = event;
+            tracing :: event ! (target : "bandwidth_estimate_updated" , parent : id , tracing :: Level :: DEBUG , path = tracing :: field :: debug (path) , bytes_per_second = tracing :: field :: debug (bytes_per_second) , min_rtt = tracing :: field :: debug (min_rtt));
+        }
+        #[inline]
+        fn on_slo_updated(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::SloUpdated,
+        ) {
+            let id = context.id();
+            let api::SloUpdated {
+                path,
+                metric,
+                breached,
+            } = event;
+            tracing :: event ! (target : "slo_updated" , parent : id , tracing :: Level :: DEBUG , path = tracing :: field :: debug (path) , metric = tracing :: field :: debug (metric) , breached = tracing :: field :: debug (breached));
+        }
+        #[inline]
+        fn on_handshake_phase_reached(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::HandshakePhaseReached,
+        ) {
+            let id = context.id();
+            let api::HandshakePhaseReached { phase, elapsed } = event;
+            tracing :: event ! (target : "handshake_phase_reached" , parent : id , tracing :: Level :: DEBUG , phase = tracing :: field :: debug (phase) , elapsed = tracing :: field :: debug (elapsed));
+        }
+        #[inline]
         fn on_version_information(
             &mut self,
             meta: &api::EndpointMeta,
@@ -2011,8 +2553,13 @@ pub mod tracing {
                 api::EndpointType::Client {} => self.client.id(),
                 api::EndpointType::Server {} => self.server.id(),
             };
-            let api::EndpointDatagramReceived { len } = event;
-            tracing :: event ! (target : "endpoint_datagram_received" , parent : parent , tracing :: Level :: DEBUG , len = tracing :: field :: debug (len));
+            let api::EndpointDatagramReceived {
+                len,
+                software_timestamp,
+                hardware_timestamp,
+                queue_delay,
+            } = event;
+            tracing :: event ! (target : "endpoint_datagram_received" , parent : parent , tracing :: Level :: DEBUG , len = tracing :: field :: debug (len) , software_timestamp = tracing :: field :: debug (software_timestamp) , hardware_timestamp = tracing :: field :: debug (hardware_timestamp) , queue_delay = tracing :: field :: debug (queue_delay));
         }
         #[inline]
         fn on_endpoint_datagram_dropped(
@@ -2028,33 +2575,125 @@ pub mod tracing {
             tracing :: event ! (target : "endpoint_datagram_dropped" , parent : parent , tracing :: Level :: DEBUG , len = tracing :: field :: debug (len) , reason = tracing :: field :: debug (reason));
         }
         #[inline]
-        fn on_endpoint_connection_attempt_failed(
+        fn on_endpoint_stateless_reset_token_mismatch(
             &mut self,
             meta: &api::EndpointMeta,
-            event: &api::EndpointConnectionAttemptFailed,
+            event: &api::EndpointStatelessResetTokenMismatch,
         ) {
             let parent = match meta.endpoint_type {
                 api::EndpointType::Client {} => self.client.id(),
                 api::EndpointType::Server {} => self.server.id(),
             };
-            let api::EndpointConnectionAttemptFailed { error } = event;
-            tracing :: event ! (target : "endpoint_connection_attempt_failed" , parent : parent , tracing :: Level :: DEBUG , error = tracing :: field :: debug (error));
+            let api::EndpointStatelessResetTokenMismatch { len } = event;
+            tracing :: event ! (target : "endpoint_stateless_reset_token_mismatch" , parent : parent , tracing :: Level :: DEBUG , len = tracing :: field :: debug (len));
         }
         #[inline]
-        fn on_platform_tx(&mut self, meta: &api::EndpointMeta, event: &api::PlatformTx) {
+        fn on_endpoint_connection_attempt_limited(
+            &mut self,
+            meta: &api::EndpointMeta,
+            event: &api::EndpointConnectionAttemptLimited,
+        ) {
             let parent = match meta.endpoint_type {
                 api::EndpointType::Client {} => self.client.id(),
                 api::EndpointType::Server {} => self.server.id(),
             };
-            let api::PlatformTx { count } = event;
-            tracing :: event ! (target : "platform_tx" , parent : parent , tracing :: Level :: DEBUG , count = tracing :: field :: debug (count));
+            let api::EndpointConnectionAttemptLimited {
+                remote_address,
+                outcome,
+            } = event;
+            tracing :: event ! (target : "endpoint_connection_attempt_limited" , parent : parent , tracing :: Level :: DEBUG , remote_address = tracing :: field :: debug (remote_address) , outcome = tracing :: field :: debug (outcome));
         }
         #[inline]
-        fn on_platform_tx_error(&mut self, meta: &api::EndpointMeta, event: &api::PlatformTxError) {
-            let parent = match meta.endpoint_type {
-                api::EndpointType::Client {} => self.client.id(),
-                api::EndpointType::Server {} => self.server.id(),
-            };
+        fn on_endpoint_connection_attempt_failed(
+            &mut self,
+            meta: &api::EndpointMeta,
+            event: &api::EndpointConnectionAttemptFailed,
+        ) {
+            let parent = match meta.endpoint_type {
+                api::EndpointType::Client {} => self.client.id(),
+                api::EndpointType::Server {} => self.server.id(),
+            };
+            let api::EndpointConnectionAttemptFailed { error } = event;
+            tracing :: event ! (target : "endpoint_connection_attempt_failed" , parent : parent , tracing :: Level :: DEBUG , error = tracing :: field :: debug (error));
+        }
+        #[inline]
+        fn on_endpoint_draining_started(
+            &mut self,
+            meta: &api::EndpointMeta,
+            event: &api::EndpointDrainingStarted,
+        ) {
+            let parent = match meta.endpoint_type {
+                api::EndpointType::Client {} => self.client.id(),
+                api::EndpointType::Server {} => self.server.id(),
+            };
+            let api::EndpointDrainingStarted {
+                active_connections,
+                deadline,
+            } = event;
+            tracing :: event ! (target : "endpoint_draining_started" , parent : parent , tracing :: Level :: DEBUG , active_connections = tracing :: field :: debug (active_connections) , deadline = tracing :: field :: debug (deadline));
+        }
+        #[inline]
+        fn on_endpoint_draining_progress(
+            &mut self,
+            meta: &api::EndpointMeta,
+            event: &api::EndpointDrainingProgress,
+        ) {
+            let parent = match meta.endpoint_type {
+                api::EndpointType::Client {} => self.client.id(),
+                api::EndpointType::Server {} => self.server.id(),
+            };
+            let api::EndpointDrainingProgress {
+                remaining_connections,
+            } = event;
+            tracing :: event ! (target : "endpoint_draining_progress" , parent : parent , tracing :: Level :: DEBUG , remaining_connections = tracing :: field :: debug (remaining_connections));
+        }
+        #[inline]
+        fn on_endpoint_draining_deadline_exceeded(
+            &mut self,
+            meta: &api::EndpointMeta,
+            event: &api::EndpointDrainingDeadlineExceeded,
+        ) {
+            let parent = match meta.endpoint_type {
+                api::EndpointType::Client {} => self.client.id(),
+                api::EndpointType::Server {} => self.server.id(),
+            };
+            let api::EndpointDrainingDeadlineExceeded {
+                remaining_connections,
+            } = event;
+            tracing :: event ! (target : "endpoint_draining_deadline_exceeded" , parent : parent , tracing :: Level :: DEBUG , remaining_connections = tracing :: field :: debug (remaining_connections));
+        }
+        #[inline]
+        fn on_endpoint_transmit_scheduled(
+            &mut self,
+            meta: &api::EndpointMeta,
+            event: &api::EndpointTransmitScheduled,
+        ) {
+            let parent = match meta.endpoint_type {
+                api::EndpointType::Client {} => self.client.id(),
+                api::EndpointType::Server {} => self.server.id(),
+            };
+            let api::EndpointTransmitScheduled {
+                rounds,
+                turns,
+                quantum_exhausted,
+            } = event;
+            tracing :: event ! (target : "endpoint_transmit_scheduled" , parent : parent , tracing :: Level :: DEBUG , rounds = tracing :: field :: debug (rounds) , turns = tracing :: field :: debug (turns) , quantum_exhausted = tracing :: field :: debug (quantum_exhausted));
+        }
+        #[inline]
+        fn on_platform_tx(&mut self, meta: &api::EndpointMeta, event: &api::PlatformTx) {
+            let parent = match meta.endpoint_type {
+                api::EndpointType::Client {} => self.client.id(),
+                api::EndpointType::Server {} => self.server.id(),
+            };
+            let api::PlatformTx { count } = event;
+            tracing :: event ! (target : "platform_tx" , parent : parent , tracing :: Level :: DEBUG , count = tracing :: field :: debug (count));
+        }
+        #[inline]
+        fn on_platform_tx_error(&mut self, meta: &api::EndpointMeta, event: &api::PlatformTxError) {
+            let parent = match meta.endpoint_type {
+                api::EndpointType::Client {} => self.client.id(),
+                api::EndpointType::Server {} => self.server.id(),
+            };
             let api::PlatformTxError { errno } = event;
             tracing :: event ! (target : "platform_tx_error" , parent : parent , tracing :: Level :: DEBUG , errno = tracing :: field :: debug (errno));
         }
@@ -2131,6 +2770,8 @@ pub mod builder {
     pub struct ConnectionMeta {
         pub endpoint_type: crate::endpoint::Type,
         pub id: u64,
+        #[doc = " The correlation ID of the connection, which is either random or provided by the application"]
+        pub correlation_id: crate::connection::CorrelationId,
         pub timestamp: crate::time::Timestamp,
     }
     impl IntoEvent<api::ConnectionMeta> for ConnectionMeta {
@@ -2139,11 +2780,13 @@ pub mod builder {
             let ConnectionMeta {
                 endpoint_type,
                 id,
+                correlation_id,
                 timestamp,
             } = self;
             api::ConnectionMeta {
                 endpoint_type: endpoint_type.into_event(),
                 id: id.into_event(),
+                correlation_id: correlation_id.into_event(),
                 timestamp: timestamp.into_event(),
             }
         }
@@ -2194,6 +2837,11 @@ pub mod builder {
         pub initial_max_streams_bidi: u64,
         pub initial_max_streams_uni: u64,
         pub max_datagram_frame_size: u64,
+        #[doc = " The parameters with IDs which are not known to s2n-quic"]
+        #[doc = ""]
+        #[doc = " The parameters are encoded as a sequence of transport parameters, which can be read with"]
+        #[doc = " `s2n_quic_core::transport::parameters::CustomParametersIter`."]
+        pub custom_parameters: &'a [u8],
     }
     impl<'a> IntoEvent<api::TransportParameters<'a>> for TransportParameters<'a> {
         #[inline]
@@ -2216,6 +2864,7 @@ pub mod builder {
                 initial_max_streams_bidi,
                 initial_max_streams_uni,
                 max_datagram_frame_size,
+                custom_parameters,
             } = self;
             api::TransportParameters {
                 original_destination_connection_id: original_destination_connection_id.into_event(),
@@ -2236,6 +2885,7 @@ pub mod builder {
                 initial_max_streams_bidi: initial_max_streams_bidi.into_event(),
                 initial_max_streams_uni: initial_max_streams_uni.into_event(),
                 max_datagram_frame_size: max_datagram_frame_size.into_event(),
+                custom_parameters: custom_parameters.into_event(),
             }
         }
     }
@@ -2355,6 +3005,31 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
+    pub enum AckRangeRejectReason {
+        #[doc = " The range acknowledged a packet number that has not been sent yet."]
+        PacketNotSent,
+        #[doc = " The range acknowledged a packet number that was intentionally skipped."]
+        #[doc = ""]
+        #[doc = " Packet numbers are periodically skipped to detect peers optimistically"]
+        #[doc = " acknowledging packets in an attempt to increase the congestion window."]
+        SkippedPacketNumber,
+        #[doc = " The ACK frame contained more ranges than the endpoint accepts for a single frame."]
+        #[doc = ""]
+        #[doc = " The reported range spans all of the ranges in the frame."]
+        RangeLimitExceeded,
+    }
+    impl IntoEvent<api::AckRangeRejectReason> for AckRangeRejectReason {
+        #[inline]
+        fn into_event(self) -> api::AckRangeRejectReason {
+            use api::AckRangeRejectReason::*;
+            match self {
+                Self::PacketNotSent => PacketNotSent {},
+                Self::SkippedPacketNumber => SkippedPacketNumber {},
+                Self::RangeLimitExceeded => RangeLimitExceeded {},
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
     pub enum DuplicatePacketError {
         #[doc = " The packet number was already received and is a duplicate."]
         Duplicate,
@@ -2437,6 +3112,11 @@ pub mod builder {
         Datagram {
             len: u16,
         },
+        #[doc = " A frame of an extension which was registered by the application"]
+        Extension {
+            frame_type: u64,
+            len: u16,
+        },
     }
     impl IntoEvent<api::Frame> for Frame {
         #[inline]
@@ -2525,6 +3205,10 @@ pub mod builder {
                 Self::Datagram { len } => Datagram {
                     len: len.into_event(),
                 },
+                Self::Extension { frame_type, len } => Extension {
+                    frame_type: frame_type.into_event(),
+                    len: len.into_event(),
+                },
             }
         }
     }
@@ -2672,6 +3356,11 @@ pub mod builder {
         PathLimitExceeded,
         #[doc = " The peer initiated a connection migration without supplying enough connection IDs to use."]
         InsufficientConnectionIds,
+        #[doc = " The datagram contained a duplicate Initial packet for a handshake which is already in progress."]
+        #[doc = ""]
+        #[doc = " Clients which retransmit their first Initial packet with a new Destination Connection Id"]
+        #[doc = " are identified by their address, Source Connection Id and token."]
+        DuplicateInitial,
     }
     impl IntoEvent<api::DatagramDropReason> for DatagramDropReason {
         #[inline]
@@ -2690,6 +3379,28 @@ pub mod builder {
                 Self::RejectedConnectionMigration => RejectedConnectionMigration {},
                 Self::PathLimitExceeded => PathLimitExceeded {},
                 Self::InsufficientConnectionIds => InsufficientConnectionIds {},
+                Self::DuplicateInitial => DuplicateInitial {},
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " The outcome of a connection attempt which the endpoint limits didn't allow"]
+    pub enum ConnectionAttemptOutcome {
+        #[doc = " The client was asked to validate its address with a Retry packet"]
+        Retry,
+        #[doc = " The connection attempt was silently dropped"]
+        Drop,
+        #[doc = " The connection attempt was rejected"]
+        Close,
+    }
+    impl IntoEvent<api::ConnectionAttemptOutcome> for ConnectionAttemptOutcome {
+        #[inline]
+        fn into_event(self) -> api::ConnectionAttemptOutcome {
+            use api::ConnectionAttemptOutcome::*;
+            match self {
+                Self::Retry => Retry {},
+                Self::Drop => Drop {},
+                Self::Close => Close {},
             }
         }
     }
@@ -3012,6 +3723,118 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
+    #[doc = " The state of a congestion controller"]
+    pub enum CongestionState {
+        #[doc = " The congestion window grows by the number of bytes acknowledged"]
+        SlowStart,
+        #[doc = " HyStart++ detected an increase in the round trip time and grows the congestion window"]
+        #[doc = " more slowly until slow start is resumed or exited"]
+        ConservativeSlowStart,
+        #[doc = " The congestion window was reduced in response to congestion, and is not increased"]
+        #[doc = " until a packet sent after the reduction is acknowledged"]
+        Recovery,
+        #[doc = " The congestion window grows according to the congestion avoidance algorithm"]
+        CongestionAvoidance,
+        #[doc = " The congestion window is not increased since the application doesn't send enough data"]
+        #[doc = " to fill it"]
+        ApplicationLimited,
+    }
+    impl IntoEvent<api::CongestionState> for CongestionState {
+        #[inline]
+        fn into_event(self) -> api::CongestionState {
+            use api::CongestionState::*;
+            match self {
+                Self::SlowStart => SlowStart {},
+                Self::ConservativeSlowStart => ConservativeSlowStart {},
+                Self::Recovery => Recovery {},
+                Self::CongestionAvoidance => CongestionAvoidance {},
+                Self::ApplicationLimited => ApplicationLimited {},
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " A metric of a connection which is compared against a threshold of the connection limits"]
+    pub enum SloMetric {
+        #[doc = " The smoothed round trip time of the active path"]
+        RoundTripTime { smoothed_rtt: Duration },
+        #[doc = " The packets declared lost out of all packets which were acknowledged or declared lost"]
+        #[doc = " during the last period"]
+        LossRate {
+            lost_packets: u64,
+            total_packets: u64,
+        },
+    }
+    impl IntoEvent<api::SloMetric> for SloMetric {
+        #[inline]
+        fn into_event(self) -> api::SloMetric {
+            use api::SloMetric::*;
+            match self {
+                Self::RoundTripTime { smoothed_rtt } => RoundTripTime {
+                    smoothed_rtt: smoothed_rtt.into_event(),
+                },
+                Self::LossRate {
+                    lost_packets,
+                    total_packets,
+                } => LossRate {
+                    lost_packets: lost_packets.into_event(),
+                    total_packets: total_packets.into_event(),
+                },
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " A phase of the handshake of a connection"]
+    pub enum HandshakePhase {
+        #[doc = " The first Initial packet was sent"]
+        InitialSent,
+        #[doc = " The first Initial packet was received from the peer"]
+        InitialReceived,
+        #[doc = " The first Handshake packet was sent"]
+        HandshakeSent,
+        #[doc = " The first Handshake packet was received from the peer"]
+        HandshakeReceived,
+        #[doc = " The handshake was confirmed"]
+        HandshakeConfirmed,
+        #[doc = " The first byte of stream data was received from the peer"]
+        ApplicationDataReceived,
+    }
+    impl IntoEvent<api::HandshakePhase> for HandshakePhase {
+        #[inline]
+        fn into_event(self) -> api::HandshakePhase {
+            use api::HandshakePhase::*;
+            match self {
+                Self::InitialSent => InitialSent {},
+                Self::InitialReceived => InitialReceived {},
+                Self::HandshakeSent => HandshakeSent {},
+                Self::HandshakeReceived => HandshakeReceived {},
+                Self::HandshakeConfirmed => HandshakeConfirmed {},
+                Self::ApplicationDataReceived => ApplicationDataReceived {},
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " A protocol violation by the peer that the specification permits ignoring"]
+    pub enum ProtocolViolation {
+        #[doc = " A RETIRE_CONNECTION_ID frame referred to the connection ID the packet containing it was"]
+        #[doc = " sent to"]
+        RetireCurrentConnectionId,
+        #[doc = " A NEW_CONNECTION_ID frame was inconsistent with a previously issued connection ID"]
+        InconsistentNewConnectionId,
+        #[doc = " An ACK frame acknowledged a packet number that was never sent"]
+        AckForUnsentPacket,
+    }
+    impl IntoEvent<api::ProtocolViolation> for ProtocolViolation {
+        #[inline]
+        fn into_event(self) -> api::ProtocolViolation {
+            use api::ProtocolViolation::*;
+            match self {
+                Self::RetireCurrentConnectionId => RetireCurrentConnectionId {},
+                Self::InconsistentNewConnectionId => InconsistentNewConnectionId {},
+                Self::AckForUnsentPacket => AckForUnsentPacket {},
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
     #[doc = " The reason the MTU was updated"]
     pub enum MtuUpdatedCause {
         #[doc = " The MTU was initialized with the default value"]
@@ -3325,6 +4148,33 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
+    #[doc = " ACK range was rejected"]
+    #[doc = ""]
+    #[doc = " Rejected ranges cause the connection to be closed with a PROTOCOL_VIOLATION."]
+    pub struct AckRangeRejected<'a> {
+        pub packet_header: PacketHeader,
+        pub path: Path<'a>,
+        pub ack_range: RangeInclusive<u64>,
+        pub reason: AckRangeRejectReason,
+    }
+    impl<'a> IntoEvent<api::AckRangeRejected<'a>> for AckRangeRejected<'a> {
+        #[inline]
+        fn into_event(self) -> api::AckRangeRejected<'a> {
+            let AckRangeRejected {
+                packet_header,
+                path,
+                ack_range,
+                reason,
+            } = self;
+            api::AckRangeRejected {
+                packet_header: packet_header.into_event(),
+                path: path.into_event(),
+                ack_range: ack_range.into_event(),
+                reason: reason.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
     #[doc = " Packet was dropped with the given reason"]
     pub struct PacketDropped<'a> {
         pub reason: PacketDropReason<'a>,
@@ -3437,6 +4287,30 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
+    #[doc = " Transport parameters negotiated by the connection"]
+    #[doc = ""]
+    #[doc = " This is emitted once the handshake completes, allowing the values sent to the peer to be"]
+    #[doc = " compared with the values received from the peer."]
+    pub struct TransportParametersNegotiated<'a> {
+        #[doc = " The transport parameters sent to the peer"]
+        pub local_transport_parameters: TransportParameters<'a>,
+        #[doc = " The transport parameters received from the peer"]
+        pub peer_transport_parameters: TransportParameters<'a>,
+    }
+    impl<'a> IntoEvent<api::TransportParametersNegotiated<'a>> for TransportParametersNegotiated<'a> {
+        #[inline]
+        fn into_event(self) -> api::TransportParametersNegotiated<'a> {
+            let TransportParametersNegotiated {
+                local_transport_parameters,
+                peer_transport_parameters,
+            } = self;
+            api::TransportParametersNegotiated {
+                local_transport_parameters: local_transport_parameters.into_event(),
+                peer_transport_parameters: peer_transport_parameters.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
     #[doc = " Datagram sent by a connection"]
     pub struct DatagramSent {
         pub len: u16,
@@ -3604,6 +4478,33 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
+    #[doc = " The peer sent more bytes than allowed before its address was validated"]
+    #[doc = ""]
+    #[doc = " The connection is closed without notifying the peer."]
+    pub struct UnvalidatedHandshakeBytesExceeded {
+        #[doc = " The number of bytes received from the peer before its address was validated"]
+        pub bytes_received: u64,
+        #[doc = " The number of bytes sent to the peer before its address was validated"]
+        pub bytes_sent: u64,
+        #[doc = " The configured limit of bytes received from the peer before its address is validated"]
+        pub max_unvalidated_handshake_bytes: u64,
+    }
+    impl IntoEvent<api::UnvalidatedHandshakeBytesExceeded> for UnvalidatedHandshakeBytesExceeded {
+        #[inline]
+        fn into_event(self) -> api::UnvalidatedHandshakeBytesExceeded {
+            let UnvalidatedHandshakeBytesExceeded {
+                bytes_received,
+                bytes_sent,
+                max_unvalidated_handshake_bytes,
+            } = self;
+            api::UnvalidatedHandshakeBytesExceeded {
+                bytes_received: bytes_received.into_event(),
+                bytes_sent: bytes_sent.into_event(),
+                max_unvalidated_handshake_bytes: max_unvalidated_handshake_bytes.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
     pub struct RxStreamProgress {
         pub bytes: usize,
     }
@@ -3617,6 +4518,91 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
+    #[doc = " A STREAM frame was received and accepted by the stream"]
+    #[doc = ""]
+    #[doc = " The event is published for each STREAM frame as it was received, so the range may overlap"]
+    #[doc = " data that was previously received."]
+    pub struct StreamDataReceived<'a> {
+        pub stream_id: u64,
+        pub offset: u64,
+        pub len: usize,
+        pub is_fin: bool,
+        #[doc = " The plaintext data carried by the frame"]
+        #[doc = ""]
+        #[doc = " This is only set if the subscriber requests access through"]
+        #[doc = " `Subscriber::stream_data_access`."]
+        pub data: Option<&'a [u8]>,
+    }
+    impl<'a> IntoEvent<api::StreamDataReceived<'a>> for StreamDataReceived<'a> {
+        #[inline]
+        fn into_event(self) -> api::StreamDataReceived<'a> {
+            let StreamDataReceived {
+                stream_id,
+                offset,
+                len,
+                is_fin,
+                data,
+            } = self;
+            api::StreamDataReceived {
+                stream_id: stream_id.into_event(),
+                offset: offset.into_event(),
+                len: len.into_event(),
+                is_fin: is_fin.into_event(),
+                data: data.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " Data sent on a stream was acknowledged by the peer"]
+    #[doc = ""]
+    #[doc = " The event is published for each range of the stream which was carried by an acknowledged"]
+    #[doc = " packet, so the range may overlap data that was previously acknowledged if it was retransmitted."]
+    pub struct StreamDataAcked {
+        pub stream_id: u64,
+        pub offset: u64,
+        pub len: u64,
+    }
+    impl IntoEvent<api::StreamDataAcked> for StreamDataAcked {
+        #[inline]
+        fn into_event(self) -> api::StreamDataAcked {
+            let StreamDataAcked {
+                stream_id,
+                offset,
+                len,
+            } = self;
+            api::StreamDataAcked {
+                stream_id: stream_id.into_event(),
+                offset: offset.into_event(),
+                len: len.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " Data sent on a stream was declared lost"]
+    #[doc = ""]
+    #[doc = " The range is retransmitted, unless it was acknowledged in another packet or the stream was"]
+    #[doc = " reset in the meantime."]
+    pub struct StreamDataLost {
+        pub stream_id: u64,
+        pub offset: u64,
+        pub len: u64,
+    }
+    impl IntoEvent<api::StreamDataLost> for StreamDataLost {
+        #[inline]
+        fn into_event(self) -> api::StreamDataLost {
+            let StreamDataLost {
+                stream_id,
+                offset,
+                len,
+            } = self;
+            api::StreamDataLost {
+                stream_id: stream_id.into_event(),
+                offset: offset.into_event(),
+                len: len.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
     pub struct TxStreamProgress {
         pub bytes: usize,
     }
@@ -3665,6 +4651,20 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
+    #[doc = " A protocol violation by the peer was ignored by the connection's protocol violation policy"]
+    pub struct ProtocolViolationIgnored {
+        pub violation: ProtocolViolation,
+    }
+    impl IntoEvent<api::ProtocolViolationIgnored> for ProtocolViolationIgnored {
+        #[inline]
+        fn into_event(self) -> api::ProtocolViolationIgnored {
+            let ProtocolViolationIgnored { violation } = self;
+            api::ProtocolViolationIgnored {
+                violation: violation.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
     #[doc = " The slow start congestion controller state has been exited"]
     pub struct SlowStartExited<'a> {
         pub path: Path<'a>,
@@ -3687,6 +4687,99 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
+    #[doc = " The congestion controller entered a new state"]
+    pub struct CongestionStateUpdated<'a> {
+        pub path: Path<'a>,
+        pub previous_state: CongestionState,
+        pub state: CongestionState,
+        pub congestion_window: u32,
+    }
+    impl<'a> IntoEvent<api::CongestionStateUpdated<'a>> for CongestionStateUpdated<'a> {
+        #[inline]
+        fn into_event(self) -> api::CongestionStateUpdated<'a> {
+            let CongestionStateUpdated {
+                path,
+                previous_state,
+                state,
+                congestion_window,
+            } = self;
+            api::CongestionStateUpdated {
+                path: path.into_event(),
+                previous_state: previous_state.into_event(),
+                state: state.into_event(),
+                congestion_window: congestion_window.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " The estimated bandwidth or minimum round trip time of a path changed"]
+    #[doc = ""]
+    #[doc = " Applications can subscribe to this event to adapt to changes of the path capacity,"]
+    #[doc = " for example by selecting a different bitrate, without measuring the throughput themselves."]
+    pub struct BandwidthEstimateUpdated<'a> {
+        pub path: Path<'a>,
+        #[doc = " The estimated bandwidth in bytes per second, or `None` if the congestion"]
+        #[doc = " controller does not estimate the bandwidth"]
+        pub bytes_per_second: Option<u64>,
+        #[doc = " The minimum round trip time observed on the path"]
+        pub min_rtt: Duration,
+    }
+    impl<'a> IntoEvent<api::BandwidthEstimateUpdated<'a>> for BandwidthEstimateUpdated<'a> {
+        #[inline]
+        fn into_event(self) -> api::BandwidthEstimateUpdated<'a> {
+            let BandwidthEstimateUpdated {
+                path,
+                bytes_per_second,
+                min_rtt,
+            } = self;
+            api::BandwidthEstimateUpdated {
+                path: path.into_event(),
+                bytes_per_second: bytes_per_second.into_event(),
+                min_rtt: min_rtt.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " A round trip time or loss rate threshold of the connection was breached or cleared"]
+    pub struct SloUpdated<'a> {
+        pub path: Path<'a>,
+        pub metric: SloMetric,
+        #[doc = " `true` if the threshold was breached, `false` if it was cleared"]
+        pub breached: bool,
+    }
+    impl<'a> IntoEvent<api::SloUpdated<'a>> for SloUpdated<'a> {
+        #[inline]
+        fn into_event(self) -> api::SloUpdated<'a> {
+            let SloUpdated {
+                path,
+                metric,
+                breached,
+            } = self;
+            api::SloUpdated {
+                path: path.into_event(),
+                metric: metric.into_event(),
+                breached: breached.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " The connection reached a phase of the handshake for the first time"]
+    pub struct HandshakePhaseReached {
+        pub phase: HandshakePhase,
+        #[doc = " The time elapsed since the connection was created"]
+        pub elapsed: Duration,
+    }
+    impl IntoEvent<api::HandshakePhaseReached> for HandshakePhaseReached {
+        #[inline]
+        fn into_event(self) -> api::HandshakePhaseReached {
+            let HandshakePhaseReached { phase, elapsed } = self;
+            api::HandshakePhaseReached {
+                phase: phase.into_event(),
+                elapsed: elapsed.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
     #[doc = " QUIC version"]
     pub struct VersionInformation<'a> {
         pub server_versions: &'a [u32],
@@ -3762,13 +4855,32 @@ pub mod builder {
     #[doc = " Datagram received by the endpoint"]
     pub struct EndpointDatagramReceived {
         pub len: u16,
+        #[doc = " The time at which the kernel received the datagram, relative to the UNIX epoch"]
+        #[doc = ""]
+        #[doc = " This is only available if the IO provider enabled timestamping on the socket."]
+        pub software_timestamp: Option<Duration>,
+        #[doc = " The time at which the network interface received the datagram"]
+        #[doc = ""]
+        #[doc = " This is only available if the IO provider enabled hardware timestamping on the socket"]
+        #[doc = " and the network interface."]
+        pub hardware_timestamp: Option<Duration>,
+        #[doc = " How long the datagram waited in the socket receive queue before the endpoint read it"]
+        pub queue_delay: Option<Duration>,
     }
     impl IntoEvent<api::EndpointDatagramReceived> for EndpointDatagramReceived {
         #[inline]
         fn into_event(self) -> api::EndpointDatagramReceived {
-            let EndpointDatagramReceived { len } = self;
+            let EndpointDatagramReceived {
+                len,
+                software_timestamp,
+                hardware_timestamp,
+                queue_delay,
+            } = self;
             api::EndpointDatagramReceived {
                 len: len.into_event(),
+                software_timestamp: software_timestamp.into_event(),
+                hardware_timestamp: hardware_timestamp.into_event(),
+                queue_delay: queue_delay.into_event(),
             }
         }
     }
@@ -3789,6 +4901,45 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
+    #[doc = " Emitted when a datagram was checked for a stateless reset but didn't end in a known token"]
+    #[doc = ""]
+    #[doc = " A large number of these events may indicate that an off-path attacker is attempting to guess"]
+    #[doc = " a stateless reset token in order to close connections."]
+    pub struct EndpointStatelessResetTokenMismatch {
+        pub len: u16,
+    }
+    impl IntoEvent<api::EndpointStatelessResetTokenMismatch> for EndpointStatelessResetTokenMismatch {
+        #[inline]
+        fn into_event(self) -> api::EndpointStatelessResetTokenMismatch {
+            let EndpointStatelessResetTokenMismatch { len } = self;
+            api::EndpointStatelessResetTokenMismatch {
+                len: len.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " Emitted when the endpoint limits don't allow a connection attempt to proceed"]
+    pub struct EndpointConnectionAttemptLimited<'a> {
+        #[doc = " The unvalidated address of the peer"]
+        pub remote_address: SocketAddress<'a>,
+        pub outcome: ConnectionAttemptOutcome,
+    }
+    impl<'a> IntoEvent<api::EndpointConnectionAttemptLimited<'a>>
+        for EndpointConnectionAttemptLimited<'a>
+    {
+        #[inline]
+        fn into_event(self) -> api::EndpointConnectionAttemptLimited<'a> {
+            let EndpointConnectionAttemptLimited {
+                remote_address,
+                outcome,
+            } = self;
+            api::EndpointConnectionAttemptLimited {
+                remote_address: remote_address.into_event(),
+                outcome: outcome.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
     pub struct EndpointConnectionAttemptFailed {
         pub error: crate::connection::Error,
     }
@@ -3802,23 +4953,103 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
-    #[doc = " Emitted when the platform sends at least one packet"]
-    pub struct PlatformTx {
-        #[doc = " The number of packets sent"]
-        pub count: usize,
+    #[doc = " Emitted when the endpoint stops accepting connections and starts draining the open ones"]
+    pub struct EndpointDrainingStarted {
+        #[doc = " The number of connections which are still open"]
+        pub active_connections: usize,
+        #[doc = " The amount of time the open connections have to close before the endpoint closes them"]
+        pub deadline: Option<Duration>,
     }
-    impl IntoEvent<api::PlatformTx> for PlatformTx {
+    impl IntoEvent<api::EndpointDrainingStarted> for EndpointDrainingStarted {
         #[inline]
-        fn into_event(self) -> api::PlatformTx {
-            let PlatformTx { count } = self;
-            api::PlatformTx {
-                count: count.into_event(),
+        fn into_event(self) -> api::EndpointDrainingStarted {
+            let EndpointDrainingStarted {
+                active_connections,
+                deadline,
+            } = self;
+            api::EndpointDrainingStarted {
+                active_connections: active_connections.into_event(),
+                deadline: deadline.into_event(),
             }
         }
     }
     #[derive(Clone, Debug)]
-    #[doc = " Emitted when the platform returns an error while sending datagrams"]
-    pub struct PlatformTxError {
+    #[doc = " Emitted when a connection finishes while the endpoint is draining"]
+    pub struct EndpointDrainingProgress {
+        #[doc = " The number of connections which are still open"]
+        pub remaining_connections: usize,
+    }
+    impl IntoEvent<api::EndpointDrainingProgress> for EndpointDrainingProgress {
+        #[inline]
+        fn into_event(self) -> api::EndpointDrainingProgress {
+            let EndpointDrainingProgress {
+                remaining_connections,
+            } = self;
+            api::EndpointDrainingProgress {
+                remaining_connections: remaining_connections.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " Emitted when the draining deadline expires and the endpoint closes the remaining connections"]
+    pub struct EndpointDrainingDeadlineExceeded {
+        #[doc = " The number of connections which were closed by the endpoint"]
+        pub remaining_connections: usize,
+    }
+    impl IntoEvent<api::EndpointDrainingDeadlineExceeded> for EndpointDrainingDeadlineExceeded {
+        #[inline]
+        fn into_event(self) -> api::EndpointDrainingDeadlineExceeded {
+            let EndpointDrainingDeadlineExceeded {
+                remaining_connections,
+            } = self;
+            api::EndpointDrainingDeadlineExceeded {
+                remaining_connections: remaining_connections.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " Emitted when the endpoint limited the connections to their quantum while filling the transmission queue"]
+    pub struct EndpointTransmitScheduled {
+        #[doc = " The number of rounds over the connections which wanted to transmit"]
+        pub rounds: usize,
+        #[doc = " The number of turns the connections took to transmit"]
+        pub turns: usize,
+        #[doc = " The number of turns which ended because the connection used its quantum"]
+        pub quantum_exhausted: usize,
+    }
+    impl IntoEvent<api::EndpointTransmitScheduled> for EndpointTransmitScheduled {
+        #[inline]
+        fn into_event(self) -> api::EndpointTransmitScheduled {
+            let EndpointTransmitScheduled {
+                rounds,
+                turns,
+                quantum_exhausted,
+            } = self;
+            api::EndpointTransmitScheduled {
+                rounds: rounds.into_event(),
+                turns: turns.into_event(),
+                quantum_exhausted: quantum_exhausted.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " Emitted when the platform sends at least one packet"]
+    pub struct PlatformTx {
+        #[doc = " The number of packets sent"]
+        pub count: usize,
+    }
+    impl IntoEvent<api::PlatformTx> for PlatformTx {
+        #[inline]
+        fn into_event(self) -> api::PlatformTx {
+            let PlatformTx { count } = self;
+            api::PlatformTx {
+                count: count.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " Emitted when the platform returns an error while sending datagrams"]
+    pub struct PlatformTxError {
         #[doc = " The error code returned by the platform"]
         pub errno: i32,
     }
@@ -3932,6 +5163,18 @@ pub mod builder {
         Ecn { enabled: bool },
         #[doc = " Emitted when the maximum transmission unit is configured"]
         MaxMtu { mtu: u16 },
+        #[doc = " Emitted when timestamping of received datagrams is enabled"]
+        RxTimestamps {
+            #[doc = " Whether the timestamps of the network interface were requested as well"]
+            hardware: bool,
+        },
+        #[doc = " Emitted when the offloading of pacing to the kernel was configured"]
+        PacingOffload {
+            #[doc = " Whether the socket supports transmit times"]
+            #[doc = ""]
+            #[doc = " If this value is `false`, the endpoint paces its transmissions itself."]
+            enabled: bool,
+        },
     }
     impl IntoEvent<api::PlatformFeatureConfiguration> for PlatformFeatureConfiguration {
         #[inline]
@@ -3947,6 +5190,12 @@ pub mod builder {
                 Self::MaxMtu { mtu } => MaxMtu {
                     mtu: mtu.into_event(),
                 },
+                Self::RxTimestamps { hardware } => RxTimestamps {
+                    hardware: hardware.into_event(),
+                },
+                Self::PacingOffload { enabled } => PacingOffload {
+                    enabled: enabled.into_event(),
+                },
             }
         }
     }
@@ -3986,6 +5235,10 @@ pub mod supervisor {
         pub remote_address: SocketAddress<'a>,
         #[doc = r" True if the connection is in the handshake state, false otherwise"]
         pub is_handshaking: bool,
+        #[doc = r" Number of bytes received from the peer before its address was validated"]
+        pub unvalidated_bytes_received: u64,
+        #[doc = r" Number of bytes sent to the peer before its address was validated"]
+        pub unvalidated_bytes_sent: u64,
     }
     impl<'a> Context<'a> {
         pub fn new(
@@ -3999,8 +5252,16 @@ pub mod supervisor {
                 connection_count,
                 remote_address: remote_address.into_event(),
                 is_handshaking,
+                unvalidated_bytes_received: 0,
+                unvalidated_bytes_sent: 0,
             }
         }
+        #[doc = r" Sets the number of bytes exchanged with the peer before its address was validated"]
+        pub fn with_unvalidated_bytes(mut self, received: u64, sent: u64) -> Self {
+            self.unvalidated_bytes_received = received;
+            self.unvalidated_bytes_sent = sent;
+            self
+        }
     }
 }
 pub use traits::*;
@@ -4127,6 +5388,18 @@ mod traits {
         ) -> supervisor::Outcome {
             supervisor::Outcome::default()
         }
+        #[doc = r" Returns `true` if the subscriber requires access to the plaintext stream data"]
+        #[doc = r""]
+        #[doc = r" When `false`, the `data` field of [`StreamDataReceived`](api::StreamDataReceived)"]
+        #[doc = r" events is always `None`. When `true`, the field references the received bytes"]
+        #[doc = r" without copying them."]
+        #[doc = r""]
+        #[doc = r" If multiple `event::Subscriber`s are composed together, the data is exposed to"]
+        #[doc = r" all of them if any `event::Subscriber` requests access."]
+        #[inline]
+        fn stream_data_access(&self) -> bool {
+            false
+        }
         #[doc = "Called when the `ApplicationProtocolInformation` event is triggered"]
         #[inline]
         fn on_application_protocol_information(
@@ -4297,6 +5570,18 @@ mod traits {
             let _ = meta;
             let _ = event;
         }
+        #[doc = "Called when the `AckRangeRejected` event is triggered"]
+        #[inline]
+        fn on_ack_range_rejected(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &AckRangeRejected,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
         #[doc = "Called when the `PacketDropped` event is triggered"]
         #[inline]
         fn on_packet_dropped(
@@ -4381,6 +5666,18 @@ mod traits {
             let _ = meta;
             let _ = event;
         }
+        #[doc = "Called when the `TransportParametersNegotiated` event is triggered"]
+        #[inline]
+        fn on_transport_parameters_negotiated(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &TransportParametersNegotiated,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
         #[doc = "Called when the `DatagramSent` event is triggered"]
         #[inline]
         fn on_datagram_sent(
@@ -4501,6 +5798,18 @@ mod traits {
             let _ = meta;
             let _ = event;
         }
+        #[doc = "Called when the `UnvalidatedHandshakeBytesExceeded` event is triggered"]
+        #[inline]
+        fn on_unvalidated_handshake_bytes_exceeded(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &UnvalidatedHandshakeBytesExceeded,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
         #[doc = "Called when the `RxStreamProgress` event is triggered"]
         #[inline]
         fn on_rx_stream_progress(
@@ -4513,6 +5822,42 @@ mod traits {
             let _ = meta;
             let _ = event;
         }
+        #[doc = "Called when the `StreamDataReceived` event is triggered"]
+        #[inline]
+        fn on_stream_data_received(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &StreamDataReceived,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
+        #[doc = "Called when the `StreamDataAcked` event is triggered"]
+        #[inline]
+        fn on_stream_data_acked(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &StreamDataAcked,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
+        #[doc = "Called when the `StreamDataLost` event is triggered"]
+        #[inline]
+        fn on_stream_data_lost(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &StreamDataLost,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
         #[doc = "Called when the `TxStreamProgress` event is triggered"]
         #[inline]
         fn on_tx_stream_progress(
@@ -4549,6 +5894,18 @@ mod traits {
             let _ = meta;
             let _ = event;
         }
+        #[doc = "Called when the `ProtocolViolationIgnored` event is triggered"]
+        #[inline]
+        fn on_protocol_violation_ignored(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &ProtocolViolationIgnored,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
         #[doc = "Called when the `SlowStartExited` event is triggered"]
         #[inline]
         fn on_slow_start_exited(
@@ -4561,6 +5918,54 @@ mod traits {
             let _ = meta;
             let _ = event;
         }
+        #[doc = "Called when the `CongestionStateUpdated` event is triggered"]
+        #[inline]
+        fn on_congestion_state_updated(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &CongestionStateUpdated,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
+        #[doc = "Called when the `BandwidthEstimateUpdated` event is triggered"]
+        #[inline]
+        fn on_bandwidth_estimate_updated(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &BandwidthEstimateUpdated,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
+        #[doc = "Called when the `SloUpdated` event is triggered"]
+        #[inline]
+        fn on_slo_updated(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &SloUpdated,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
+        #[doc = "Called when the `HandshakePhaseReached` event is triggered"]
+        #[inline]
+        fn on_handshake_phase_reached(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &HandshakePhaseReached,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
         #[doc = "Called when the `VersionInformation` event is triggered"]
         #[inline]
         fn on_version_information(&mut self, meta: &EndpointMeta, event: &VersionInformation) {
@@ -4609,6 +6014,26 @@ mod traits {
             let _ = meta;
             let _ = event;
         }
+        #[doc = "Called when the `EndpointStatelessResetTokenMismatch` event is triggered"]
+        #[inline]
+        fn on_endpoint_stateless_reset_token_mismatch(
+            &mut self,
+            meta: &EndpointMeta,
+            event: &EndpointStatelessResetTokenMismatch,
+        ) {
+            let _ = meta;
+            let _ = event;
+        }
+        #[doc = "Called when the `EndpointConnectionAttemptLimited` event is triggered"]
+        #[inline]
+        fn on_endpoint_connection_attempt_limited(
+            &mut self,
+            meta: &EndpointMeta,
+            event: &EndpointConnectionAttemptLimited,
+        ) {
+            let _ = meta;
+            let _ = event;
+        }
         #[doc = "Called when the `EndpointConnectionAttemptFailed` event is triggered"]
         #[inline]
         fn on_endpoint_connection_attempt_failed(
@@ -4619,6 +6044,46 @@ mod traits {
             let _ = meta;
             let _ = event;
         }
+        #[doc = "Called when the `EndpointDrainingStarted` event is triggered"]
+        #[inline]
+        fn on_endpoint_draining_started(
+            &mut self,
+            meta: &EndpointMeta,
+            event: &EndpointDrainingStarted,
+        ) {
+            let _ = meta;
+            let _ = event;
+        }
+        #[doc = "Called when the `EndpointDrainingProgress` event is triggered"]
+        #[inline]
+        fn on_endpoint_draining_progress(
+            &mut self,
+            meta: &EndpointMeta,
+            event: &EndpointDrainingProgress,
+        ) {
+            let _ = meta;
+            let _ = event;
+        }
+        #[doc = "Called when the `EndpointDrainingDeadlineExceeded` event is triggered"]
+        #[inline]
+        fn on_endpoint_draining_deadline_exceeded(
+            &mut self,
+            meta: &EndpointMeta,
+            event: &EndpointDrainingDeadlineExceeded,
+        ) {
+            let _ = meta;
+            let _ = event;
+        }
+        #[doc = "Called when the `EndpointTransmitScheduled` event is triggered"]
+        #[inline]
+        fn on_endpoint_transmit_scheduled(
+            &mut self,
+            meta: &EndpointMeta,
+            event: &EndpointTransmitScheduled,
+        ) {
+            let _ = meta;
+            let _ = event;
+        }
         #[doc = "Called when the `PlatformTx` event is triggered"]
         #[inline]
         fn on_platform_tx(&mut self, meta: &EndpointMeta, event: &PlatformTx) {
@@ -4772,6 +6237,10 @@ mod traits {
             }
         }
         #[inline]
+        fn stream_data_access(&self) -> bool {
+            self.0.stream_data_access() || self.1.stream_data_access()
+        }
+        #[inline]
         fn on_application_protocol_information(
             &mut self,
             context: &mut Self::ConnectionContext,
@@ -4913,6 +6382,16 @@ mod traits {
             (self.1).on_ack_range_received(&mut context.1, meta, event);
         }
         #[inline]
+        fn on_ack_range_rejected(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &AckRangeRejected,
+        ) {
+            (self.0).on_ack_range_rejected(&mut context.0, meta, event);
+            (self.1).on_ack_range_rejected(&mut context.1, meta, event);
+        }
+        #[inline]
         fn on_packet_dropped(
             &mut self,
             context: &mut Self::ConnectionContext,
@@ -4983,6 +6462,16 @@ mod traits {
             (self.1).on_transport_parameters_received(&mut context.1, meta, event);
         }
         #[inline]
+        fn on_transport_parameters_negotiated(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &TransportParametersNegotiated,
+        ) {
+            (self.0).on_transport_parameters_negotiated(&mut context.0, meta, event);
+            (self.1).on_transport_parameters_negotiated(&mut context.1, meta, event);
+        }
+        #[inline]
         fn on_datagram_sent(
             &mut self,
             context: &mut Self::ConnectionContext,
@@ -5083,6 +6572,16 @@ mod traits {
             (self.1).on_tls_server_hello(&mut context.1, meta, event);
         }
         #[inline]
+        fn on_unvalidated_handshake_bytes_exceeded(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &UnvalidatedHandshakeBytesExceeded,
+        ) {
+            (self.0).on_unvalidated_handshake_bytes_exceeded(&mut context.0, meta, event);
+            (self.1).on_unvalidated_handshake_bytes_exceeded(&mut context.1, meta, event);
+        }
+        #[inline]
         fn on_rx_stream_progress(
             &mut self,
             context: &mut Self::ConnectionContext,
@@ -5093,6 +6592,36 @@ mod traits {
             (self.1).on_rx_stream_progress(&mut context.1, meta, event);
         }
         #[inline]
+        fn on_stream_data_received(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &StreamDataReceived,
+        ) {
+            (self.0).on_stream_data_received(&mut context.0, meta, event);
+            (self.1).on_stream_data_received(&mut context.1, meta, event);
+        }
+        #[inline]
+        fn on_stream_data_acked(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &StreamDataAcked,
+        ) {
+            (self.0).on_stream_data_acked(&mut context.0, meta, event);
+            (self.1).on_stream_data_acked(&mut context.1, meta, event);
+        }
+        #[inline]
+        fn on_stream_data_lost(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &StreamDataLost,
+        ) {
+            (self.0).on_stream_data_lost(&mut context.0, meta, event);
+            (self.1).on_stream_data_lost(&mut context.1, meta, event);
+        }
+        #[inline]
         fn on_tx_stream_progress(
             &mut self,
             context: &mut Self::ConnectionContext,
@@ -5123,6 +6652,16 @@ mod traits {
             (self.1).on_mtu_updated(&mut context.1, meta, event);
         }
         #[inline]
+        fn on_protocol_violation_ignored(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &ProtocolViolationIgnored,
+        ) {
+            (self.0).on_protocol_violation_ignored(&mut context.0, meta, event);
+            (self.1).on_protocol_violation_ignored(&mut context.1, meta, event);
+        }
+        #[inline]
         fn on_slow_start_exited(
             &mut self,
             context: &mut Self::ConnectionContext,
@@ -5133,26 +6672,66 @@ mod traits {
             (self.1).on_slow_start_exited(&mut context.1, meta, event);
         }
         #[inline]
-        fn on_version_information(&mut self, meta: &EndpointMeta, event: &VersionInformation) {
-            (self.0).on_version_information(meta, event);
-            (self.1).on_version_information(meta, event);
+        fn on_congestion_state_updated(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &CongestionStateUpdated,
+        ) {
+            (self.0).on_congestion_state_updated(&mut context.0, meta, event);
+            (self.1).on_congestion_state_updated(&mut context.1, meta, event);
         }
         #[inline]
-        fn on_endpoint_packet_sent(&mut self, meta: &EndpointMeta, event: &EndpointPacketSent) {
-            (self.0).on_endpoint_packet_sent(meta, event);
-            (self.1).on_endpoint_packet_sent(meta, event);
+        fn on_bandwidth_estimate_updated(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &BandwidthEstimateUpdated,
+        ) {
+            (self.0).on_bandwidth_estimate_updated(&mut context.0, meta, event);
+            (self.1).on_bandwidth_estimate_updated(&mut context.1, meta, event);
         }
         #[inline]
-        fn on_endpoint_packet_received(
+        fn on_slo_updated(
             &mut self,
-            meta: &EndpointMeta,
-            event: &EndpointPacketReceived,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &SloUpdated,
         ) {
-            (self.0).on_endpoint_packet_received(meta, event);
-            (self.1).on_endpoint_packet_received(meta, event);
+            (self.0).on_slo_updated(&mut context.0, meta, event);
+            (self.1).on_slo_updated(&mut context.1, meta, event);
         }
         #[inline]
-        fn on_endpoint_datagram_sent(&mut self, meta: &EndpointMeta, event: &EndpointDatagramSent) {
+        fn on_handshake_phase_reached(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &HandshakePhaseReached,
+        ) {
+            (self.0).on_handshake_phase_reached(&mut context.0, meta, event);
+            (self.1).on_handshake_phase_reached(&mut context.1, meta, event);
+        }
+        #[inline]
+        fn on_version_information(&mut self, meta: &EndpointMeta, event: &VersionInformation) {
+            (self.0).on_version_information(meta, event);
+            (self.1).on_version_information(meta, event);
+        }
+        #[inline]
+        fn on_endpoint_packet_sent(&mut self, meta: &EndpointMeta, event: &EndpointPacketSent) {
+            (self.0).on_endpoint_packet_sent(meta, event);
+            (self.1).on_endpoint_packet_sent(meta, event);
+        }
+        #[inline]
+        fn on_endpoint_packet_received(
+            &mut self,
+            meta: &EndpointMeta,
+            event: &EndpointPacketReceived,
+        ) {
+            (self.0).on_endpoint_packet_received(meta, event);
+            (self.1).on_endpoint_packet_received(meta, event);
+        }
+        #[inline]
+        fn on_endpoint_datagram_sent(&mut self, meta: &EndpointMeta, event: &EndpointDatagramSent) {
             (self.0).on_endpoint_datagram_sent(meta, event);
             (self.1).on_endpoint_datagram_sent(meta, event);
         }
@@ -5175,6 +6754,24 @@ mod traits {
             (self.1).on_endpoint_datagram_dropped(meta, event);
         }
         #[inline]
+        fn on_endpoint_stateless_reset_token_mismatch(
+            &mut self,
+            meta: &EndpointMeta,
+            event: &EndpointStatelessResetTokenMismatch,
+        ) {
+            (self.0).on_endpoint_stateless_reset_token_mismatch(meta, event);
+            (self.1).on_endpoint_stateless_reset_token_mismatch(meta, event);
+        }
+        #[inline]
+        fn on_endpoint_connection_attempt_limited(
+            &mut self,
+            meta: &EndpointMeta,
+            event: &EndpointConnectionAttemptLimited,
+        ) {
+            (self.0).on_endpoint_connection_attempt_limited(meta, event);
+            (self.1).on_endpoint_connection_attempt_limited(meta, event);
+        }
+        #[inline]
         fn on_endpoint_connection_attempt_failed(
             &mut self,
             meta: &EndpointMeta,
@@ -5184,6 +6781,42 @@ mod traits {
             (self.1).on_endpoint_connection_attempt_failed(meta, event);
         }
         #[inline]
+        fn on_endpoint_draining_started(
+            &mut self,
+            meta: &EndpointMeta,
+            event: &EndpointDrainingStarted,
+        ) {
+            (self.0).on_endpoint_draining_started(meta, event);
+            (self.1).on_endpoint_draining_started(meta, event);
+        }
+        #[inline]
+        fn on_endpoint_draining_progress(
+            &mut self,
+            meta: &EndpointMeta,
+            event: &EndpointDrainingProgress,
+        ) {
+            (self.0).on_endpoint_draining_progress(meta, event);
+            (self.1).on_endpoint_draining_progress(meta, event);
+        }
+        #[inline]
+        fn on_endpoint_draining_deadline_exceeded(
+            &mut self,
+            meta: &EndpointMeta,
+            event: &EndpointDrainingDeadlineExceeded,
+        ) {
+            (self.0).on_endpoint_draining_deadline_exceeded(meta, event);
+            (self.1).on_endpoint_draining_deadline_exceeded(meta, event);
+        }
+        #[inline]
+        fn on_endpoint_transmit_scheduled(
+            &mut self,
+            meta: &EndpointMeta,
+            event: &EndpointTransmitScheduled,
+        ) {
+            (self.0).on_endpoint_transmit_scheduled(meta, event);
+            (self.1).on_endpoint_transmit_scheduled(meta, event);
+        }
+        #[inline]
         fn on_platform_tx(&mut self, meta: &EndpointMeta, event: &PlatformTx) {
             (self.0).on_platform_tx(meta, event);
             (self.1).on_platform_tx(meta, event);
@@ -5279,11 +6912,32 @@ mod traits {
         fn on_endpoint_datagram_received(&mut self, event: builder::EndpointDatagramReceived);
         #[doc = "Publishes a `EndpointDatagramDropped` event to the publisher's subscriber"]
         fn on_endpoint_datagram_dropped(&mut self, event: builder::EndpointDatagramDropped);
+        #[doc = "Publishes a `EndpointStatelessResetTokenMismatch` event to the publisher's subscriber"]
+        fn on_endpoint_stateless_reset_token_mismatch(
+            &mut self,
+            event: builder::EndpointStatelessResetTokenMismatch,
+        );
+        #[doc = "Publishes a `EndpointConnectionAttemptLimited` event to the publisher's subscriber"]
+        fn on_endpoint_connection_attempt_limited(
+            &mut self,
+            event: builder::EndpointConnectionAttemptLimited,
+        );
         #[doc = "Publishes a `EndpointConnectionAttemptFailed` event to the publisher's subscriber"]
         fn on_endpoint_connection_attempt_failed(
             &mut self,
             event: builder::EndpointConnectionAttemptFailed,
         );
+        #[doc = "Publishes a `EndpointDrainingStarted` event to the publisher's subscriber"]
+        fn on_endpoint_draining_started(&mut self, event: builder::EndpointDrainingStarted);
+        #[doc = "Publishes a `EndpointDrainingProgress` event to the publisher's subscriber"]
+        fn on_endpoint_draining_progress(&mut self, event: builder::EndpointDrainingProgress);
+        #[doc = "Publishes a `EndpointDrainingDeadlineExceeded` event to the publisher's subscriber"]
+        fn on_endpoint_draining_deadline_exceeded(
+            &mut self,
+            event: builder::EndpointDrainingDeadlineExceeded,
+        );
+        #[doc = "Publishes a `EndpointTransmitScheduled` event to the publisher's subscriber"]
+        fn on_endpoint_transmit_scheduled(&mut self, event: builder::EndpointTransmitScheduled);
         #[doc = "Publishes a `PlatformTx` event to the publisher's subscriber"]
         fn on_platform_tx(&mut self, event: builder::PlatformTx);
         #[doc = "Publishes a `PlatformTxError` event to the publisher's subscriber"]
@@ -5370,6 +7024,26 @@ mod traits {
             self.subscriber.on_event(&self.meta, &event);
         }
         #[inline]
+        fn on_endpoint_stateless_reset_token_mismatch(
+            &mut self,
+            event: builder::EndpointStatelessResetTokenMismatch,
+        ) {
+            let event = event.into_event();
+            self.subscriber
+                .on_endpoint_stateless_reset_token_mismatch(&self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
+        fn on_endpoint_connection_attempt_limited(
+            &mut self,
+            event: builder::EndpointConnectionAttemptLimited,
+        ) {
+            let event = event.into_event();
+            self.subscriber
+                .on_endpoint_connection_attempt_limited(&self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
         fn on_endpoint_connection_attempt_failed(
             &mut self,
             event: builder::EndpointConnectionAttemptFailed,
@@ -5380,6 +7054,37 @@ mod traits {
             self.subscriber.on_event(&self.meta, &event);
         }
         #[inline]
+        fn on_endpoint_draining_started(&mut self, event: builder::EndpointDrainingStarted) {
+            let event = event.into_event();
+            self.subscriber
+                .on_endpoint_draining_started(&self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
+        fn on_endpoint_draining_progress(&mut self, event: builder::EndpointDrainingProgress) {
+            let event = event.into_event();
+            self.subscriber
+                .on_endpoint_draining_progress(&self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
+        fn on_endpoint_draining_deadline_exceeded(
+            &mut self,
+            event: builder::EndpointDrainingDeadlineExceeded,
+        ) {
+            let event = event.into_event();
+            self.subscriber
+                .on_endpoint_draining_deadline_exceeded(&self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
+        fn on_endpoint_transmit_scheduled(&mut self, event: builder::EndpointTransmitScheduled) {
+            let event = event.into_event();
+            self.subscriber
+                .on_endpoint_transmit_scheduled(&self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
         fn on_platform_tx(&mut self, event: builder::PlatformTx) {
             let event = event.into_event();
             self.subscriber.on_platform_tx(&self.meta, &event);
@@ -5461,6 +7166,8 @@ mod traits {
         fn on_rx_ack_range_dropped(&mut self, event: builder::RxAckRangeDropped);
         #[doc = "Publishes a `AckRangeReceived` event to the publisher's subscriber"]
         fn on_ack_range_received(&mut self, event: builder::AckRangeReceived);
+        #[doc = "Publishes a `AckRangeRejected` event to the publisher's subscriber"]
+        fn on_ack_range_rejected(&mut self, event: builder::AckRangeRejected);
         #[doc = "Publishes a `PacketDropped` event to the publisher's subscriber"]
         fn on_packet_dropped(&mut self, event: builder::PacketDropped);
         #[doc = "Publishes a `KeyUpdate` event to the publisher's subscriber"]
@@ -5475,6 +7182,11 @@ mod traits {
         fn on_duplicate_packet(&mut self, event: builder::DuplicatePacket);
         #[doc = "Publishes a `TransportParametersReceived` event to the publisher's subscriber"]
         fn on_transport_parameters_received(&mut self, event: builder::TransportParametersReceived);
+        #[doc = "Publishes a `TransportParametersNegotiated` event to the publisher's subscriber"]
+        fn on_transport_parameters_negotiated(
+            &mut self,
+            event: builder::TransportParametersNegotiated,
+        );
         #[doc = "Publishes a `DatagramSent` event to the publisher's subscriber"]
         fn on_datagram_sent(&mut self, event: builder::DatagramSent);
         #[doc = "Publishes a `DatagramReceived` event to the publisher's subscriber"]
@@ -5495,20 +7207,43 @@ mod traits {
         fn on_tls_client_hello(&mut self, event: builder::TlsClientHello);
         #[doc = "Publishes a `TlsServerHello` event to the publisher's subscriber"]
         fn on_tls_server_hello(&mut self, event: builder::TlsServerHello);
+        #[doc = "Publishes a `UnvalidatedHandshakeBytesExceeded` event to the publisher's subscriber"]
+        fn on_unvalidated_handshake_bytes_exceeded(
+            &mut self,
+            event: builder::UnvalidatedHandshakeBytesExceeded,
+        );
         #[doc = "Publishes a `RxStreamProgress` event to the publisher's subscriber"]
         fn on_rx_stream_progress(&mut self, event: builder::RxStreamProgress);
+        #[doc = "Publishes a `StreamDataReceived` event to the publisher's subscriber"]
+        fn on_stream_data_received(&mut self, event: builder::StreamDataReceived);
+        #[doc = "Publishes a `StreamDataAcked` event to the publisher's subscriber"]
+        fn on_stream_data_acked(&mut self, event: builder::StreamDataAcked);
+        #[doc = "Publishes a `StreamDataLost` event to the publisher's subscriber"]
+        fn on_stream_data_lost(&mut self, event: builder::StreamDataLost);
         #[doc = "Publishes a `TxStreamProgress` event to the publisher's subscriber"]
         fn on_tx_stream_progress(&mut self, event: builder::TxStreamProgress);
         #[doc = "Publishes a `KeepAliveTimerExpired` event to the publisher's subscriber"]
         fn on_keep_alive_timer_expired(&mut self, event: builder::KeepAliveTimerExpired);
         #[doc = "Publishes a `MtuUpdated` event to the publisher's subscriber"]
         fn on_mtu_updated(&mut self, event: builder::MtuUpdated);
+        #[doc = "Publishes a `ProtocolViolationIgnored` event to the publisher's subscriber"]
+        fn on_protocol_violation_ignored(&mut self, event: builder::ProtocolViolationIgnored);
         #[doc = "Publishes a `SlowStartExited` event to the publisher's subscriber"]
         fn on_slow_start_exited(&mut self, event: builder::SlowStartExited);
+        #[doc = "Publishes a `CongestionStateUpdated` event to the publisher's subscriber"]
+        fn on_congestion_state_updated(&mut self, event: builder::CongestionStateUpdated);
+        #[doc = "Publishes a `BandwidthEstimateUpdated` event to the publisher's subscriber"]
+        fn on_bandwidth_estimate_updated(&mut self, event: builder::BandwidthEstimateUpdated);
+        #[doc = "Publishes a `SloUpdated` event to the publisher's subscriber"]
+        fn on_slo_updated(&mut self, event: builder::SloUpdated);
+        #[doc = "Publishes a `HandshakePhaseReached` event to the publisher's subscriber"]
+        fn on_handshake_phase_reached(&mut self, event: builder::HandshakePhaseReached);
         #[doc = r" Returns the QUIC version negotiated for the current connection, if any"]
         fn quic_version(&self) -> u32;
         #[doc = r" Returns the [`Subject`] for the current publisher"]
         fn subject(&self) -> Subject;
+        #[doc = r" Returns `true` if the subscriber requires access to the plaintext stream data"]
+        fn stream_data_access(&self) -> bool;
     }
     pub struct ConnectionPublisherSubscriber<'a, Sub: Subscriber> {
         meta: ConnectionMeta,
@@ -5672,6 +7407,15 @@ mod traits {
             self.subscriber.on_event(&self.meta, &event);
         }
         #[inline]
+        fn on_ack_range_rejected(&mut self, event: builder::AckRangeRejected) {
+            let event = event.into_event();
+            self.subscriber
+                .on_ack_range_rejected(self.context, &self.meta, &event);
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
         fn on_packet_dropped(&mut self, event: builder::PacketDropped) {
             let event = event.into_event();
             self.subscriber
@@ -5738,6 +7482,18 @@ mod traits {
             self.subscriber.on_event(&self.meta, &event);
         }
         #[inline]
+        fn on_transport_parameters_negotiated(
+            &mut self,
+            event: builder::TransportParametersNegotiated,
+        ) {
+            let event = event.into_event();
+            self.subscriber
+                .on_transport_parameters_negotiated(self.context, &self.meta, &event);
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
         fn on_datagram_sent(&mut self, event: builder::DatagramSent) {
             let event = event.into_event();
             self.subscriber
@@ -5828,6 +7584,21 @@ mod traits {
             self.subscriber.on_event(&self.meta, &event);
         }
         #[inline]
+        fn on_unvalidated_handshake_bytes_exceeded(
+            &mut self,
+            event: builder::UnvalidatedHandshakeBytesExceeded,
+        ) {
+            let event = event.into_event();
+            self.subscriber.on_unvalidated_handshake_bytes_exceeded(
+                self.context,
+                &self.meta,
+                &event,
+            );
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
         fn on_rx_stream_progress(&mut self, event: builder::RxStreamProgress) {
             let event = event.into_event();
             self.subscriber
@@ -5837,6 +7608,33 @@ mod traits {
             self.subscriber.on_event(&self.meta, &event);
         }
         #[inline]
+        fn on_stream_data_received(&mut self, event: builder::StreamDataReceived) {
+            let event = event.into_event();
+            self.subscriber
+                .on_stream_data_received(self.context, &self.meta, &event);
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
+        fn on_stream_data_acked(&mut self, event: builder::StreamDataAcked) {
+            let event = event.into_event();
+            self.subscriber
+                .on_stream_data_acked(self.context, &self.meta, &event);
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
+        fn on_stream_data_lost(&mut self, event: builder::StreamDataLost) {
+            let event = event.into_event();
+            self.subscriber
+                .on_stream_data_lost(self.context, &self.meta, &event);
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
         fn on_tx_stream_progress(&mut self, event: builder::TxStreamProgress) {
             let event = event.into_event();
             self.subscriber
@@ -5864,6 +7662,15 @@ mod traits {
             self.subscriber.on_event(&self.meta, &event);
         }
         #[inline]
+        fn on_protocol_violation_ignored(&mut self, event: builder::ProtocolViolationIgnored) {
+            let event = event.into_event();
+            self.subscriber
+                .on_protocol_violation_ignored(self.context, &self.meta, &event);
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
         fn on_slow_start_exited(&mut self, event: builder::SlowStartExited) {
             let event = event.into_event();
             self.subscriber
@@ -5873,6 +7680,42 @@ mod traits {
             self.subscriber.on_event(&self.meta, &event);
         }
         #[inline]
+        fn on_congestion_state_updated(&mut self, event: builder::CongestionStateUpdated) {
+            let event = event.into_event();
+            self.subscriber
+                .on_congestion_state_updated(self.context, &self.meta, &event);
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
+        fn on_bandwidth_estimate_updated(&mut self, event: builder::BandwidthEstimateUpdated) {
+            let event = event.into_event();
+            self.subscriber
+                .on_bandwidth_estimate_updated(self.context, &self.meta, &event);
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
+        fn on_slo_updated(&mut self, event: builder::SloUpdated) {
+            let event = event.into_event();
+            self.subscriber
+                .on_slo_updated(self.context, &self.meta, &event);
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
+        fn on_handshake_phase_reached(&mut self, event: builder::HandshakePhaseReached) {
+            let event = event.into_event();
+            self.subscriber
+                .on_handshake_phase_reached(self.context, &self.meta, &event);
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
         fn quic_version(&self) -> u32 {
             self.quic_version
         }
@@ -5880,6 +7723,10 @@ mod traits {
         fn subject(&self) -> api::Subject {
             self.meta.subject()
         }
+        #[inline]
+        fn stream_data_access(&self) -> bool {
+            self.subscriber.stream_data_access()
+        }
     }
 }
 #[cfg(any(test, feature = "testing"))]
@@ -5903,6 +7750,7 @@ pub mod testing {
         pub ack_processed: u32,
         pub rx_ack_range_dropped: u32,
         pub ack_range_received: u32,
+        pub ack_range_rejected: u32,
         pub packet_dropped: u32,
         pub key_update: u32,
         pub key_space_discarded: u32,
@@ -5910,6 +7758,7 @@ pub mod testing {
         pub connection_closed: u32,
         pub duplicate_packet: u32,
         pub transport_parameters_received: u32,
+        pub transport_parameters_negotiated: u32,
         pub datagram_sent: u32,
         pub datagram_received: u32,
         pub datagram_dropped: u32,
@@ -5920,18 +7769,33 @@ pub mod testing {
         pub path_challenge_updated: u32,
         pub tls_client_hello: u32,
         pub tls_server_hello: u32,
+        pub unvalidated_handshake_bytes_exceeded: u32,
         pub rx_stream_progress: u32,
+        pub stream_data_received: u32,
+        pub stream_data_acked: u32,
+        pub stream_data_lost: u32,
         pub tx_stream_progress: u32,
         pub keep_alive_timer_expired: u32,
         pub mtu_updated: u32,
+        pub protocol_violation_ignored: u32,
         pub slow_start_exited: u32,
+        pub congestion_state_updated: u32,
+        pub bandwidth_estimate_updated: u32,
+        pub slo_updated: u32,
+        pub handshake_phase_reached: u32,
         pub version_information: u32,
         pub endpoint_packet_sent: u32,
         pub endpoint_packet_received: u32,
         pub endpoint_datagram_sent: u32,
         pub endpoint_datagram_received: u32,
         pub endpoint_datagram_dropped: u32,
+        pub endpoint_stateless_reset_token_mismatch: u32,
+        pub endpoint_connection_attempt_limited: u32,
         pub endpoint_connection_attempt_failed: u32,
+        pub endpoint_draining_started: u32,
+        pub endpoint_draining_progress: u32,
+        pub endpoint_draining_deadline_exceeded: u32,
+        pub endpoint_transmit_scheduled: u32,
         pub platform_tx: u32,
         pub platform_tx_error: u32,
         pub platform_rx: u32,
@@ -5977,6 +7841,7 @@ pub mod testing {
                 ack_processed: 0,
                 rx_ack_range_dropped: 0,
                 ack_range_received: 0,
+                ack_range_rejected: 0,
                 packet_dropped: 0,
                 key_update: 0,
                 key_space_discarded: 0,
@@ -5984,6 +7849,7 @@ pub mod testing {
                 connection_closed: 0,
                 duplicate_packet: 0,
                 transport_parameters_received: 0,
+                transport_parameters_negotiated: 0,
                 datagram_sent: 0,
                 datagram_received: 0,
                 datagram_dropped: 0,
@@ -5994,18 +7860,33 @@ pub mod testing {
                 path_challenge_updated: 0,
                 tls_client_hello: 0,
                 tls_server_hello: 0,
+                unvalidated_handshake_bytes_exceeded: 0,
                 rx_stream_progress: 0,
+                stream_data_received: 0,
+                stream_data_acked: 0,
+                stream_data_lost: 0,
                 tx_stream_progress: 0,
                 keep_alive_timer_expired: 0,
                 mtu_updated: 0,
+                protocol_violation_ignored: 0,
                 slow_start_exited: 0,
+                congestion_state_updated: 0,
+                bandwidth_estimate_updated: 0,
+                slo_updated: 0,
+                handshake_phase_reached: 0,
                 version_information: 0,
                 endpoint_packet_sent: 0,
                 endpoint_packet_received: 0,
                 endpoint_datagram_sent: 0,
                 endpoint_datagram_received: 0,
                 endpoint_datagram_dropped: 0,
+                endpoint_stateless_reset_token_mismatch: 0,
+                endpoint_connection_attempt_limited: 0,
                 endpoint_connection_attempt_failed: 0,
+                endpoint_draining_started: 0,
+                endpoint_draining_progress: 0,
+                endpoint_draining_deadline_exceeded: 0,
+                endpoint_transmit_scheduled: 0,
                 platform_tx: 0,
                 platform_tx_error: 0,
                 platform_rx: 0,
@@ -6179,6 +8060,17 @@ pub mod testing {
                 self.output.push(format!("{:?} {:?}", meta, event));
             }
         }
+        fn on_ack_range_rejected(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::AckRangeRejected,
+        ) {
+            self.ack_range_rejected += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{:?} {:?}", meta, event));
+            }
+        }
         fn on_packet_dropped(
             &mut self,
             _context: &mut Self::ConnectionContext,
@@ -6256,6 +8148,17 @@ pub mod testing {
                 self.output.push(format!("{:?} {:?}", meta, event));
             }
         }
+        fn on_transport_parameters_negotiated(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::TransportParametersNegotiated,
+        ) {
+            self.transport_parameters_negotiated += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{:?} {:?}", meta, event));
+            }
+        }
         fn on_datagram_sent(
             &mut self,
             _context: &mut Self::ConnectionContext,
@@ -6366,6 +8269,17 @@ pub mod testing {
                 self.output.push(format!("{:?} {:?}", meta, event));
             }
         }
+        fn on_unvalidated_handshake_bytes_exceeded(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::UnvalidatedHandshakeBytesExceeded,
+        ) {
+            self.unvalidated_handshake_bytes_exceeded += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{:?} {:?}", meta, event));
+            }
+        }
         fn on_rx_stream_progress(
             &mut self,
             _context: &mut Self::ConnectionContext,
@@ -6377,6 +8291,39 @@ pub mod testing {
                 self.output.push(format!("{:?} {:?}", meta, event));
             }
         }
+        fn on_stream_data_received(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::StreamDataReceived,
+        ) {
+            self.stream_data_received += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{:?} {:?}", meta, event));
+            }
+        }
+        fn on_stream_data_acked(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::StreamDataAcked,
+        ) {
+            self.stream_data_acked += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{:?} {:?}", meta, event));
+            }
+        }
+        fn on_stream_data_lost(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::StreamDataLost,
+        ) {
+            self.stream_data_lost += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{:?} {:?}", meta, event));
+            }
+        }
         fn on_tx_stream_progress(
             &mut self,
             _context: &mut Self::ConnectionContext,
@@ -6410,6 +8357,17 @@ pub mod testing {
                 self.output.push(format!("{:?} {:?}", meta, event));
             }
         }
+        fn on_protocol_violation_ignored(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::ProtocolViolationIgnored,
+        ) {
+            self.protocol_violation_ignored += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{:?} {:?}", meta, event));
+            }
+        }
         fn on_slow_start_exited(
             &mut self,
             _context: &mut Self::ConnectionContext,
@@ -6421,6 +8379,50 @@ pub mod testing {
                 self.output.push(format!("{:?} {:?}", meta, event));
             }
         }
+        fn on_congestion_state_updated(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::CongestionStateUpdated,
+        ) {
+            self.congestion_state_updated += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{:?} {:?}", meta, event));
+            }
+        }
+        fn on_bandwidth_estimate_updated(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::BandwidthEstimateUpdated,
+        ) {
+            self.bandwidth_estimate_updated += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{:?} {:?}", meta, event));
+            }
+        }
+        fn on_slo_updated(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::SloUpdated,
+        ) {
+            self.slo_updated += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{:?} {:?}", meta, event));
+            }
+        }
+        fn on_handshake_phase_reached(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::HandshakePhaseReached,
+        ) {
+            self.handshake_phase_reached += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{:?} {:?}", meta, event));
+            }
+        }
         fn on_version_information(
             &mut self,
             meta: &api::EndpointMeta,
@@ -6469,6 +8471,22 @@ pub mod testing {
             self.endpoint_datagram_dropped += 1;
             self.output.push(format!("{:?} {:?}", meta, event));
         }
+        fn on_endpoint_stateless_reset_token_mismatch(
+            &mut self,
+            meta: &api::EndpointMeta,
+            event: &api::EndpointStatelessResetTokenMismatch,
+        ) {
+            self.endpoint_stateless_reset_token_mismatch += 1;
+            self.output.push(format!("{:?} {:?}", meta, event));
+        }
+        fn on_endpoint_connection_attempt_limited(
+            &mut self,
+            meta: &api::EndpointMeta,
+            event: &api::EndpointConnectionAttemptLimited,
+        ) {
+            self.endpoint_connection_attempt_limited += 1;
+            self.output.push(format!("{:?} {:?}", meta, event));
+        }
         fn on_endpoint_connection_attempt_failed(
             &mut self,
             meta: &api::EndpointMeta,
@@ -6477,6 +8495,38 @@ pub mod testing {
             self.endpoint_connection_attempt_failed += 1;
             self.output.push(format!("{:?} {:?}", meta, event));
         }
+        fn on_endpoint_draining_started(
+            &mut self,
+            meta: &api::EndpointMeta,
+            event: &api::EndpointDrainingStarted,
+        ) {
+            self.endpoint_draining_started += 1;
+            self.output.push(format!("{:?} {:?}", meta, event));
+        }
+        fn on_endpoint_draining_progress(
+            &mut self,
+            meta: &api::EndpointMeta,
+            event: &api::EndpointDrainingProgress,
+        ) {
+            self.endpoint_draining_progress += 1;
+            self.output.push(format!("{:?} {:?}", meta, event));
+        }
+        fn on_endpoint_draining_deadline_exceeded(
+            &mut self,
+            meta: &api::EndpointMeta,
+            event: &api::EndpointDrainingDeadlineExceeded,
+        ) {
+            self.endpoint_draining_deadline_exceeded += 1;
+            self.output.push(format!("{:?} {:?}", meta, event));
+        }
+        fn on_endpoint_transmit_scheduled(
+            &mut self,
+            meta: &api::EndpointMeta,
+            event: &api::EndpointTransmitScheduled,
+        ) {
+            self.endpoint_transmit_scheduled += 1;
+            self.output.push(format!("{:?} {:?}", meta, event));
+        }
         fn on_platform_tx(&mut self, meta: &api::EndpointMeta, event: &api::PlatformTx) {
             self.platform_tx += 1;
             self.output.push(format!("{:?} {:?}", meta, event));
@@ -6536,6 +8586,7 @@ pub mod testing {
         pub ack_processed: u32,
         pub rx_ack_range_dropped: u32,
         pub ack_range_received: u32,
+        pub ack_range_rejected: u32,
         pub packet_dropped: u32,
         pub key_update: u32,
         pub key_space_discarded: u32,
@@ -6543,6 +8594,7 @@ pub mod testing {
         pub connection_closed: u32,
         pub duplicate_packet: u32,
         pub transport_parameters_received: u32,
+        pub transport_parameters_negotiated: u32,
         pub datagram_sent: u32,
         pub datagram_received: u32,
         pub datagram_dropped: u32,
@@ -6553,18 +8605,33 @@ pub mod testing {
         pub path_challenge_updated: u32,
         pub tls_client_hello: u32,
         pub tls_server_hello: u32,
+        pub unvalidated_handshake_bytes_exceeded: u32,
         pub rx_stream_progress: u32,
+        pub stream_data_received: u32,
+        pub stream_data_acked: u32,
+        pub stream_data_lost: u32,
         pub tx_stream_progress: u32,
         pub keep_alive_timer_expired: u32,
         pub mtu_updated: u32,
+        pub protocol_violation_ignored: u32,
         pub slow_start_exited: u32,
+        pub congestion_state_updated: u32,
+        pub bandwidth_estimate_updated: u32,
+        pub slo_updated: u32,
+        pub handshake_phase_reached: u32,
         pub version_information: u32,
         pub endpoint_packet_sent: u32,
         pub endpoint_packet_received: u32,
         pub endpoint_datagram_sent: u32,
         pub endpoint_datagram_received: u32,
         pub endpoint_datagram_dropped: u32,
+        pub endpoint_stateless_reset_token_mismatch: u32,
+        pub endpoint_connection_attempt_limited: u32,
         pub endpoint_connection_attempt_failed: u32,
+        pub endpoint_draining_started: u32,
+        pub endpoint_draining_progress: u32,
+        pub endpoint_draining_deadline_exceeded: u32,
+        pub endpoint_transmit_scheduled: u32,
         pub platform_tx: u32,
         pub platform_tx_error: u32,
         pub platform_rx: u32,
@@ -6600,6 +8667,7 @@ pub mod testing {
                 ack_processed: 0,
                 rx_ack_range_dropped: 0,
                 ack_range_received: 0,
+                ack_range_rejected: 0,
                 packet_dropped: 0,
                 key_update: 0,
                 key_space_discarded: 0,
@@ -6607,6 +8675,7 @@ pub mod testing {
                 connection_closed: 0,
                 duplicate_packet: 0,
                 transport_parameters_received: 0,
+                transport_parameters_negotiated: 0,
                 datagram_sent: 0,
                 datagram_received: 0,
                 datagram_dropped: 0,
@@ -6617,18 +8686,33 @@ pub mod testing {
                 path_challenge_updated: 0,
                 tls_client_hello: 0,
                 tls_server_hello: 0,
+                unvalidated_handshake_bytes_exceeded: 0,
                 rx_stream_progress: 0,
+                stream_data_received: 0,
+                stream_data_acked: 0,
+                stream_data_lost: 0,
                 tx_stream_progress: 0,
                 keep_alive_timer_expired: 0,
                 mtu_updated: 0,
+                protocol_violation_ignored: 0,
                 slow_start_exited: 0,
+                congestion_state_updated: 0,
+                bandwidth_estimate_updated: 0,
+                slo_updated: 0,
+                handshake_phase_reached: 0,
                 version_information: 0,
                 endpoint_packet_sent: 0,
                 endpoint_packet_received: 0,
                 endpoint_datagram_sent: 0,
                 endpoint_datagram_received: 0,
                 endpoint_datagram_dropped: 0,
+                endpoint_stateless_reset_token_mismatch: 0,
+                endpoint_connection_attempt_limited: 0,
                 endpoint_connection_attempt_failed: 0,
+                endpoint_draining_started: 0,
+                endpoint_draining_progress: 0,
+                endpoint_draining_deadline_exceeded: 0,
+                endpoint_transmit_scheduled: 0,
                 platform_tx: 0,
                 platform_tx_error: 0,
                 platform_rx: 0,
@@ -6670,6 +8754,22 @@ pub mod testing {
             let event = event.into_event();
             self.output.push(format!("{:?}", event));
         }
+        fn on_endpoint_stateless_reset_token_mismatch(
+            &mut self,
+            event: builder::EndpointStatelessResetTokenMismatch,
+        ) {
+            self.endpoint_stateless_reset_token_mismatch += 1;
+            let event = event.into_event();
+            self.output.push(format!("{:?}", event));
+        }
+        fn on_endpoint_connection_attempt_limited(
+            &mut self,
+            event: builder::EndpointConnectionAttemptLimited,
+        ) {
+            self.endpoint_connection_attempt_limited += 1;
+            let event = event.into_event();
+            self.output.push(format!("{:?}", event));
+        }
         fn on_endpoint_connection_attempt_failed(
             &mut self,
             event: builder::EndpointConnectionAttemptFailed,
@@ -6678,6 +8778,29 @@ pub mod testing {
             let event = event.into_event();
             self.output.push(format!("{:?}", event));
         }
+        fn on_endpoint_draining_started(&mut self, event: builder::EndpointDrainingStarted) {
+            self.endpoint_draining_started += 1;
+            let event = event.into_event();
+            self.output.push(format!("{:?}", event));
+        }
+        fn on_endpoint_draining_progress(&mut self, event: builder::EndpointDrainingProgress) {
+            self.endpoint_draining_progress += 1;
+            let event = event.into_event();
+            self.output.push(format!("{:?}", event));
+        }
+        fn on_endpoint_draining_deadline_exceeded(
+            &mut self,
+            event: builder::EndpointDrainingDeadlineExceeded,
+        ) {
+            self.endpoint_draining_deadline_exceeded += 1;
+            let event = event.into_event();
+            self.output.push(format!("{:?}", event));
+        }
+        fn on_endpoint_transmit_scheduled(&mut self, event: builder::EndpointTransmitScheduled) {
+            self.endpoint_transmit_scheduled += 1;
+            let event = event.into_event();
+            self.output.push(format!("{:?}", event));
+        }
         fn on_platform_tx(&mut self, event: builder::PlatformTx) {
             self.platform_tx += 1;
             let event = event.into_event();
@@ -6820,6 +8943,13 @@ pub mod testing {
                 self.output.push(format!("{:?}", event));
             }
         }
+        fn on_ack_range_rejected(&mut self, event: builder::AckRangeRejected) {
+            self.ack_range_rejected += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{:?}", event));
+            }
+        }
         fn on_packet_dropped(&mut self, event: builder::PacketDropped) {
             self.packet_dropped += 1;
             let event = event.into_event();
@@ -6872,6 +9002,16 @@ pub mod testing {
                 self.output.push(format!("{:?}", event));
             }
         }
+        fn on_transport_parameters_negotiated(
+            &mut self,
+            event: builder::TransportParametersNegotiated,
+        ) {
+            self.transport_parameters_negotiated += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{:?}", event));
+            }
+        }
         fn on_datagram_sent(&mut self, event: builder::DatagramSent) {
             self.datagram_sent += 1;
             let event = event.into_event();
@@ -6942,6 +9082,16 @@ pub mod testing {
                 self.output.push(format!("{:?}", event));
             }
         }
+        fn on_unvalidated_handshake_bytes_exceeded(
+            &mut self,
+            event: builder::UnvalidatedHandshakeBytesExceeded,
+        ) {
+            self.unvalidated_handshake_bytes_exceeded += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{:?}", event));
+            }
+        }
         fn on_rx_stream_progress(&mut self, event: builder::RxStreamProgress) {
             self.rx_stream_progress += 1;
             let event = event.into_event();
@@ -6949,6 +9099,27 @@ pub mod testing {
                 self.output.push(format!("{:?}", event));
             }
         }
+        fn on_stream_data_received(&mut self, event: builder::StreamDataReceived) {
+            self.stream_data_received += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{:?}", event));
+            }
+        }
+        fn on_stream_data_acked(&mut self, event: builder::StreamDataAcked) {
+            self.stream_data_acked += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{:?}", event));
+            }
+        }
+        fn on_stream_data_lost(&mut self, event: builder::StreamDataLost) {
+            self.stream_data_lost += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{:?}", event));
+            }
+        }
         fn on_tx_stream_progress(&mut self, event: builder::TxStreamProgress) {
             self.tx_stream_progress += 1;
             let event = event.into_event();
@@ -6970,6 +9141,13 @@ pub mod testing {
                 self.output.push(format!("{:?}", event));
             }
         }
+        fn on_protocol_violation_ignored(&mut self, event: builder::ProtocolViolationIgnored) {
+            self.protocol_violation_ignored += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{:?}", event));
+            }
+        }
         fn on_slow_start_exited(&mut self, event: builder::SlowStartExited) {
             self.slow_start_exited += 1;
             let event = event.into_event();
@@ -6977,12 +9155,43 @@ pub mod testing {
                 self.output.push(format!("{:?}", event));
             }
         }
+        fn on_congestion_state_updated(&mut self, event: builder::CongestionStateUpdated) {
+            self.congestion_state_updated += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{:?}", event));
+            }
+        }
+        fn on_bandwidth_estimate_updated(&mut self, event: builder::BandwidthEstimateUpdated) {
+            self.bandwidth_estimate_updated += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{:?}", event));
+            }
+        }
+        fn on_slo_updated(&mut self, event: builder::SloUpdated) {
+            self.slo_updated += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{:?}", event));
+            }
+        }
+        fn on_handshake_phase_reached(&mut self, event: builder::HandshakePhaseReached) {
+            self.handshake_phase_reached += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{:?}", event));
+            }
+        }
         fn quic_version(&self) -> u32 {
             1
         }
         fn subject(&self) -> api::Subject {
             api::Subject::Connection { id: 0 }
         }
+        fn stream_data_access(&self) -> bool {
+            false
+        }
     }
     impl Drop for Publisher {
         fn drop(&mut self) {
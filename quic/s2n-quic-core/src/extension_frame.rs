@@ -0,0 +1,247 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Handlers for the frames of private or experimental extensions
+//!
+//! An [`Endpoint`] creates a [`Handler`] for each connection, which registers the
+//! [`FrameType`]s of the extension. The extension is negotiated with a custom transport
+//! parameter: the handler's parameter is sent to the peer, and the extension is only enabled if
+//! the peer sends a parameter with the same ID. Extension frames are encoded as described in
+//! [`frame::extension`](crate::frame::extension) and are only exchanged in 1-RTT packets.
+//!
+//! The registration of a frame type decides if the frame elicits an ACK and if the transport
+//! retransmits it when the packet carrying it is declared lost. Frames which aren't retransmitted
+//! are reported to the handler instead, so the extension can decide what to do about the loss.
+
+use crate::{
+    connection,
+    event::{api::SocketAddress, IntoEvent},
+    inet, transport,
+    varint::VarInt,
+};
+use core::fmt;
+
+/// A frame type which is handled by an extension
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameType {
+    id: VarInt,
+    ack_eliciting: bool,
+    retransmitted: bool,
+}
+
+impl FrameType {
+    /// Registers an ack-eliciting frame type which is retransmitted when lost
+    ///
+    /// Returns `None` if the frame type is defined by QUIC or one of the extensions implemented
+    /// by this crate.
+    pub fn new(id: VarInt) -> Option<Self> {
+        if crate::frame::extension::is_reserved_frame_type(id) {
+            return None;
+        }
+
+        Some(Self {
+            id,
+            ack_eliciting: true,
+            retransmitted: true,
+        })
+    }
+
+    /// Sets if the frame elicits an ACK from the peer
+    ///
+    /// Frames which don't elicit an ACK are never retransmitted, since the peer might not
+    /// acknowledge the packets which only contain them.
+    #[must_use]
+    pub const fn with_ack_eliciting(mut self, ack_eliciting: bool) -> Self {
+        self.ack_eliciting = ack_eliciting;
+        self.retransmitted &= ack_eliciting;
+        self
+    }
+
+    /// Sets if the frame is retransmitted by the transport when it is lost
+    #[must_use]
+    pub const fn with_retransmitted(mut self, retransmitted: bool) -> Self {
+        self.retransmitted = retransmitted && self.ack_eliciting;
+        self
+    }
+
+    /// Returns the ID of the frame type
+    #[inline]
+    pub const fn id(&self) -> VarInt {
+        self.id
+    }
+
+    /// Returns `true` if the frame elicits an ACK from the peer
+    #[inline]
+    pub const fn is_ack_eliciting(&self) -> bool {
+        self.ack_eliciting
+    }
+
+    /// Returns `true` if the frame is retransmitted by the transport when it is lost
+    #[inline]
+    pub const fn is_retransmitted(&self) -> bool {
+        self.retransmitted
+    }
+}
+
+/// Information about the connection that the handler is being created for
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct ConnectionInfo<'a> {
+    /// The address of the peer
+    pub remote_address: SocketAddress<'a>,
+}
+
+impl<'a> ConnectionInfo<'a> {
+    #[inline]
+    #[doc(hidden)]
+    pub fn new(remote_address: &'a inet::SocketAddress) -> Self {
+        Self {
+            remote_address: remote_address.into_event(),
+        }
+    }
+}
+
+/// Creates a [`Handler`] for each connection
+pub trait Endpoint: 'static + Send {
+    type Handler: Handler;
+
+    /// Called when a connection is created to return the handler of the extension, or `None` if
+    /// the extension isn't offered on the connection
+    fn new_handler(&mut self, info: &ConnectionInfo) -> Option<Self::Handler>;
+}
+
+/// Reasons why an extension frame could not be written
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WriteError {
+    /// The frame type wasn't registered by the handler
+    UnregisteredFrameType,
+    /// The frame doesn't fit into the remaining capacity of the packet
+    ExceedsPacketCapacity,
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnregisteredFrameType => write!(f, "the frame type was not registered"),
+            Self::ExceedsPacketCapacity => write!(f, "the frame exceeds the packet capacity"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WriteError {}
+
+/// Writes extension frames into a packet
+pub trait Writer {
+    /// Returns the largest payload of a single frame which fits into the packet
+    fn remaining_capacity(&self) -> usize;
+
+    /// Writes a frame of a registered frame type into the packet
+    fn write_frame(&mut self, frame_type: VarInt, payload: &[u8]) -> Result<(), WriteError>;
+}
+
+/// Sends and receives the frames of an extension on a connection
+pub trait Handler: 'static + Send + fmt::Debug {
+    /// Returns the ID and the value of the transport parameter which negotiates the extension
+    ///
+    /// The ID must not belong to a transport parameter which is defined by this crate.
+    fn transport_parameter(&self) -> (VarInt, &[u8]);
+
+    /// Returns the frame types of the extension
+    fn frame_types(&self) -> &[FrameType];
+
+    /// Called with the value of the peer's transport parameter once the extension is negotiated
+    ///
+    /// Returning an error closes the connection.
+    fn on_negotiated(&mut self, peer_value: &[u8]) -> Result<(), transport::Error> {
+        let _ = peer_value;
+        Ok(())
+    }
+
+    /// Called for each extension frame which is received from the peer
+    ///
+    /// Returning an error closes the connection.
+    fn on_frame(&mut self, frame_type: VarInt, payload: &[u8]) -> Result<(), transport::Error>;
+
+    /// Returns `true` if the handler has frames to send
+    ///
+    /// The transport calls [`Self::on_transmit`] when the handler has transmission interest.
+    fn has_transmission_interest(&self) -> bool {
+        false
+    }
+
+    /// Called when a packet is assembled to allow the handler to write frames into it
+    fn on_transmit<W: Writer>(&mut self, writer: &mut W) {
+        let _ = writer;
+    }
+
+    /// Called when a frame of a frame type which isn't retransmitted was lost
+    ///
+    /// Only the loss of ack-eliciting frames is detected.
+    fn on_frame_lost(&mut self, frame_type: VarInt) {
+        let _ = frame_type;
+    }
+
+    /// Called when the connection is closed
+    fn on_connection_error(&mut self, error: connection::Error) {
+        let _ = error;
+    }
+}
+
+/// An endpoint which doesn't offer any extensions
+#[derive(Clone, Debug, Default)]
+pub struct Disabled(());
+
+impl Endpoint for Disabled {
+    type Handler = DisabledHandler;
+
+    #[inline]
+    fn new_handler(&mut self, _info: &ConnectionInfo) -> Option<Self::Handler> {
+        None
+    }
+}
+
+/// The handler of the [`Disabled`] endpoint, which is never created
+#[derive(Debug)]
+pub struct DisabledHandler(());
+
+impl Handler for DisabledHandler {
+    fn transport_parameter(&self) -> (VarInt, &[u8]) {
+        unreachable!("the disabled handler is never created")
+    }
+
+    fn frame_types(&self) -> &[FrameType] {
+        &[]
+    }
+
+    fn on_frame(&mut self, _frame_type: VarInt, _payload: &[u8]) -> Result<(), transport::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_type_test() {
+        assert_eq!(FrameType::new(VarInt::from_u8(0x08)), None);
+        assert_eq!(FrameType::new(VarInt::from_u8(0x31)), None);
+
+        let frame_type = FrameType::new(VarInt::from_u16(0x3a11)).unwrap();
+        assert!(frame_type.is_ack_eliciting());
+        assert!(frame_type.is_retransmitted());
+
+        let frame_type = frame_type.with_retransmitted(false);
+        assert!(frame_type.is_ack_eliciting());
+        assert!(!frame_type.is_retransmitted());
+
+        // frames which don't elicit ACKs are never retransmitted
+        let frame_type = frame_type
+            .with_ack_eliciting(false)
+            .with_retransmitted(true);
+        assert!(!frame_type.is_ack_eliciting());
+        assert!(!frame_type.is_retransmitted());
+    }
+}
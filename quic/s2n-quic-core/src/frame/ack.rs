@@ -1,9 +1,15 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{frame::Tag, inet::ExplicitCongestionNotification, number::CheckedSub, varint::VarInt};
+use crate::{
+    frame::{debug_assert_encoding_size, Tag},
+    inet::ExplicitCongestionNotification,
+    number::CheckedSub,
+    varint::VarInt,
+};
 use core::{
     convert::TryInto,
+    mem::size_of,
     ops::{RangeInclusive, SubAssign},
 };
 use s2n_codec::{
@@ -173,6 +179,48 @@ impl<A: AckRanges> EncoderValue for Ack<A> {
             buffer.encode(ecn_counts);
         }
     }
+
+    /// We hand optimize this encoding size so the ranges are only iterated over once,
+    /// without calling into the encoder for each value
+    #[inline]
+    fn encoding_size_for_encoder<E: Encoder>(&self, encoder: &E) -> usize {
+        let mut iter = self.ack_ranges.ack_ranges();
+
+        let first_ack_range = iter.next().expect("at least one ack range is required");
+        let (mut smallest, largest_acknowledged) = first_ack_range.into_inner();
+        let first_ack_range = largest_acknowledged - smallest;
+
+        let ack_range_count: VarInt = iter
+            .len()
+            .try_into()
+            .expect("ack range count cannot exceed VarInt::MAX");
+
+        let mut len = 0;
+        len += size_of::<Tag>();
+        len += largest_acknowledged.encoding_size();
+        len += self.ack_delay.encoding_size();
+        len += ack_range_count.encoding_size();
+        len += first_ack_range.encoding_size();
+
+        for range in iter {
+            let (start, end) = range.into_inner();
+            let gap = smallest - end - 2;
+            let ack_range = end - start;
+
+            len += gap.encoding_size();
+            len += ack_range.encoding_size();
+
+            smallest = start;
+        }
+
+        if let Some(ecn_counts) = self.ecn_counts.as_ref() {
+            len += ecn_counts.encoding_size();
+        }
+
+        debug_assert_encoding_size(self, encoder, len);
+
+        len
+    }
 }
 
 //= https://www.rfc-editor.org/rfc/rfc9000#section-19.3.1
@@ -535,6 +583,13 @@ impl EncoderValue for EcnCounts {
         buffer.encode(&self.ect_1_count);
         buffer.encode(&self.ce_count);
     }
+
+    #[inline]
+    fn encoding_size_for_encoder<E: Encoder>(&self, _encoder: &E) -> usize {
+        self.ect_0_count.encoding_size()
+            + self.ect_1_count.encoding_size()
+            + self.ce_count.encoding_size()
+    }
 }
 
 #[cfg(test)]
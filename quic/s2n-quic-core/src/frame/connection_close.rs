@@ -1,7 +1,12 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{application, frame::Tag, varint::VarInt};
+use crate::{
+    application,
+    frame::{debug_assert_encoding_size, Tag},
+    varint::VarInt,
+};
+use core::{convert::TryFrom, mem::size_of};
 use s2n_codec::{decoder_parameterized_value, Encoder, EncoderValue};
 
 //= https://www.rfc-editor.org/rfc/rfc9000#section-19.19
@@ -142,4 +147,26 @@ impl<'a> EncoderValue for ConnectionClose<'a> {
             buffer.encode(&0u8);
         }
     }
+
+    #[inline]
+    fn encoding_size_for_encoder<E: Encoder>(&self, encoder: &E) -> usize {
+        let mut len = 0;
+        len += size_of::<Tag>();
+        len += self.error_code.encoding_size();
+
+        if let Some(frame_type) = &self.frame_type {
+            len += frame_type.encoding_size();
+        }
+
+        if let Some(reason) = &self.reason {
+            len += VarInt::try_from(reason.len()).unwrap().encoding_size();
+            len += reason.len();
+        } else {
+            len += size_of::<u8>();
+        }
+
+        debug_assert_encoding_size(self, encoder, len);
+
+        len
+    }
 }
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    frame::{FitError, Tag},
+    frame::{debug_assert_encoding_size, FitError, Tag},
     varint::VarInt,
 };
 use core::{convert::TryFrom, mem::size_of};
@@ -113,6 +113,21 @@ impl<Data: EncoderValue> EncoderValue for Crypto<Data> {
         buffer.encode(&self.offset);
         buffer.encode_with_len_prefix::<VarInt, _>(&self.data);
     }
+
+    #[inline]
+    fn encoding_size_for_encoder<E: Encoder>(&self, encoder: &E) -> usize {
+        let mut len = 0;
+        len += size_of::<Tag>();
+        len += self.offset.encoding_size();
+
+        let data_len = self.data.encoding_size_for_encoder(encoder);
+        len += VarInt::try_from(data_len).unwrap().encoding_size();
+        len += data_len;
+
+        debug_assert_encoding_size(self, encoder, len);
+
+        len
+    }
 }
 
 impl<'a> From<Crypto<DecoderBuffer<'a>>> for CryptoRef<'a> {
@@ -1,7 +1,11 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{frame::Tag, varint::VarInt};
+use crate::{
+    frame::{debug_assert_encoding_size, Tag},
+    varint::VarInt,
+};
+use core::{convert::TryFrom, mem::size_of};
 
 use s2n_codec::{
     decoder_parameterized_value, DecoderBuffer, DecoderBufferMut, Encoder, EncoderValue,
@@ -115,6 +119,24 @@ impl<Data: EncoderValue> EncoderValue for Datagram<Data> {
             buffer.encode_with_len_prefix::<VarInt, _>(&self.data);
         }
     }
+
+    #[inline]
+    fn encoding_size_for_encoder<E: Encoder>(&self, encoder: &E) -> usize {
+        let mut len = 0;
+        len += size_of::<Tag>();
+
+        let data_len = self.data.encoding_size_for_encoder(encoder);
+        len += data_len;
+
+        // include the len prefix
+        if !self.is_last_frame {
+            len += VarInt::try_from(data_len).unwrap().encoding_size();
+        }
+
+        debug_assert_encoding_size(self, encoder, len);
+
+        len
+    }
 }
 
 impl<'a> From<Datagram<DecoderBuffer<'a>>> for DatagramRef<'a> {
@@ -0,0 +1,192 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Frames of private or experimental extensions
+//!
+//! The contents of these frames are opaque to the transport. In order to be skipped or forwarded
+//! without knowing their format, they are encoded with a length prefix:
+//!
+//! ```text
+//! Extension Frame {
+//!   Type (i),
+//!   Length (i),
+//!   Payload (..),
+//! }
+//! ```
+//!
+//! Extension frames are only decoded for frame types which were registered with an
+//! [`extension_frame::Handler`](crate::extension_frame::Handler) that was negotiated on the
+//! connection.
+
+use crate::{
+    event,
+    frame::{
+        ack_elicitation::{AckElicitable, AckElicitation},
+        congestion_controlled::CongestionControlled,
+        debug_assert_encoding_size,
+        path_validation::Probing,
+    },
+    varint::VarInt,
+};
+use core::convert::TryFrom;
+use s2n_codec::{DecoderBuffer, DecoderBufferMut, DecoderError, Encoder, EncoderValue};
+
+pub type ExtensionRef<'a> = Extension<&'a [u8]>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Extension<Data> {
+    /// The type of the frame
+    pub frame_type: VarInt,
+
+    /// If true, the frame elicits an ACK from the peer
+    ///
+    /// This isn't encoded; both peers derive it from the registration of the frame type.
+    pub ack_eliciting: bool,
+
+    /// The contents of the frame
+    pub payload: Data,
+}
+
+impl<Data> Extension<Data> {
+    /// Converts the payload from one type to another
+    #[inline]
+    pub fn map_payload<F: FnOnce(Data) -> Out, Out>(self, map: F) -> Extension<Out> {
+        Extension {
+            frame_type: self.frame_type,
+            ack_eliciting: self.ack_eliciting,
+            payload: map(self.payload),
+        }
+    }
+}
+
+/// Returns `true` if the frame type is defined by QUIC or one of the extensions implemented by
+/// this crate, in which case it can't be used by extension frames
+#[inline]
+pub fn is_reserved_frame_type(frame_type: VarInt) -> bool {
+    match frame_type.as_u64() {
+        // the frames from RFC 9000, from PADDING to HANDSHAKE_DONE
+        0x00..=0x1e => true,
+        // DATAGRAM frames from RFC 9221
+        0x30..=0x31 => true,
+        _ => false,
+    }
+}
+
+impl<'a> Extension<DecoderBufferMut<'a>> {
+    /// Decodes an extension frame with a length prefixed payload
+    #[inline]
+    pub fn decode(
+        buffer: DecoderBufferMut<'a>,
+        ack_eliciting: bool,
+    ) -> Result<(Self, DecoderBufferMut<'a>), DecoderError> {
+        let (frame_type, buffer) = buffer.decode::<VarInt>()?;
+        let (payload, buffer) = buffer.decode_slice_with_len_prefix::<VarInt>()?;
+
+        let frame = Self {
+            frame_type,
+            ack_eliciting,
+            payload,
+        };
+
+        Ok((frame, buffer))
+    }
+}
+
+impl<'a> From<Extension<DecoderBufferMut<'a>>> for ExtensionRef<'a> {
+    #[inline]
+    fn from(frame: Extension<DecoderBufferMut<'a>>) -> Self {
+        frame.map_payload(|payload| &*payload.into_less_safe_slice())
+    }
+}
+
+impl<'a> From<Extension<DecoderBuffer<'a>>> for ExtensionRef<'a> {
+    #[inline]
+    fn from(frame: Extension<DecoderBuffer<'a>>) -> Self {
+        frame.map_payload(|payload| payload.into_less_safe_slice())
+    }
+}
+
+impl<Data: EncoderValue> EncoderValue for Extension<Data> {
+    #[inline]
+    fn encode<E: Encoder>(&self, buffer: &mut E) {
+        buffer.encode(&self.frame_type);
+        buffer.encode_with_len_prefix::<VarInt, _>(&self.payload);
+    }
+
+    #[inline]
+    fn encoding_size_for_encoder<E: Encoder>(&self, encoder: &E) -> usize {
+        let payload_len = self.payload.encoding_size_for_encoder(encoder);
+        let len = self.frame_type.encoding_size()
+            + VarInt::try_from(payload_len).unwrap().encoding_size()
+            + payload_len;
+
+        debug_assert_encoding_size(self, encoder, len);
+
+        len
+    }
+}
+
+impl<Data> AckElicitable for Extension<Data> {
+    #[inline]
+    fn ack_elicitation(&self) -> AckElicitation {
+        if self.ack_eliciting {
+            AckElicitation::Eliciting
+        } else {
+            AckElicitation::NonEliciting
+        }
+    }
+}
+
+impl<Data> CongestionControlled for Extension<Data> {}
+
+impl<Data> Probing for Extension<Data> {}
+
+impl<Data: EncoderValue> event::IntoEvent<event::builder::Frame> for &Extension<Data> {
+    #[inline]
+    fn into_event(self) -> event::builder::Frame {
+        event::builder::Frame::Extension {
+            frame_type: self.frame_type.as_u64(),
+            len: self.payload.encoding_size() as _,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_codec::EncoderBuffer;
+
+    #[test]
+    fn codec_test() {
+        let frame = Extension {
+            frame_type: VarInt::from_u16(0x3a11),
+            ack_eliciting: true,
+            payload: &[1u8, 2, 3][..],
+        };
+        let mut encoded = vec![0; 16];
+        let mut encoder = EncoderBuffer::new(&mut encoded);
+        encoder.encode(&frame);
+        let len = encoder.len();
+        encoded.truncate(len);
+        assert_eq!(encoded, [0x7a, 0x11, 3, 1, 2, 3]);
+        assert_eq!(frame.encoding_size(), encoded.len());
+
+        encoded.push(0xff);
+        let (decoded, remaining) =
+            Extension::decode(DecoderBufferMut::new(&mut encoded), false).unwrap();
+        assert_eq!(decoded.frame_type, frame.frame_type);
+        assert!(!decoded.ack_elicitation().is_ack_eliciting());
+        assert_eq!(decoded.payload.into_less_safe_slice(), &[1, 2, 3]);
+        assert_eq!(remaining.into_less_safe_slice(), &[0xff]);
+    }
+
+    #[test]
+    fn reserved_frame_type_test() {
+        for frame_type in [0x00u8, 0x01, 0x08, 0x1e, 0x30, 0x31] {
+            assert!(is_reserved_frame_type(VarInt::from_u8(frame_type)));
+        }
+        for frame_type in [0x1fu16, 0x2f, 0x32, 0xaf, 0x3a11] {
+            assert!(!is_reserved_frame_type(VarInt::from_u16(frame_type)));
+        }
+    }
+}
@@ -1,7 +1,12 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{frame::Tag, stream::StreamType, varint::VarInt};
+use crate::{
+    frame::{debug_assert_encoding_size, Tag},
+    stream::StreamType,
+    varint::VarInt,
+};
+use core::mem::size_of;
 use s2n_codec::{decoder_invariant, decoder_parameterized_value, Encoder, EncoderValue};
 
 //= https://www.rfc-editor.org/rfc/rfc9000#section-19.11
@@ -96,4 +101,13 @@ impl EncoderValue for MaxStreams {
         buffer.encode(&self.tag());
         buffer.encode(&self.maximum_streams);
     }
+
+    #[inline]
+    fn encoding_size_for_encoder<E: Encoder>(&self, encoder: &E) -> usize {
+        let len = size_of::<Tag>() + self.maximum_streams.encoding_size();
+
+        debug_assert_encoding_size(self, encoder, len);
+
+        len
+    }
 }
@@ -15,6 +15,7 @@ use s2n_codec::{
 
 pub mod ack_elicitation;
 pub mod congestion_controlled;
+pub mod extension;
 pub mod path_validation;
 
 //= https://www.rfc-editor.org/rfc/rfc9000#section-19
@@ -123,6 +124,15 @@ macro_rules! frames {
                     )*
                 }
             }
+
+            #[inline]
+            fn encoding_size_for_encoder<E: Encoder>(&self, encoder: &E) -> usize {
+                match self {
+                    $(
+                        Frame::$ty(frame) => frame.encoding_size_for_encoder(encoder),
+                    )*
+                }
+            }
         }
 
         struct BasicFrameDecoder;
@@ -191,6 +201,29 @@ macro_rules! frames {
                     ));
                 }
             )*
+
+            #[test]
+            #[cfg_attr(miri, ignore)] // reading sample files isn't supported on miri
+            fn encoding_size_test() {
+                $(
+                    let mut bytes = std::fs::read(concat!(
+                        env!("CARGO_MANIFEST_DIR"),
+                        "/src/frame/test_samples/",
+                        stringify!($module),
+                        ".bin"
+                    )).unwrap();
+                    let mut buffer = DecoderBufferMut::new(&mut bytes);
+
+                    while !buffer.is_empty() {
+                        let len = buffer.len();
+                        let (frame, remaining) = buffer.decode::<FrameMut>().unwrap();
+                        // the precomputed size should match the number of bytes the frame was
+                        // decoded from
+                        assert_eq!(frame.encoding_size(), len - remaining.len(), "{:?}", frame);
+                        buffer = remaining;
+                    }
+                )*
+            }
         }
     };
 }
@@ -225,6 +258,18 @@ macro_rules! simple_frame_codec {
                     buffer.encode(&self.$field);
                 )*
             }
+
+            #[inline]
+            fn encoding_size_for_encoder<E: s2n_codec::Encoder>(&self, encoder: &E) -> usize {
+                let len = core::mem::size_of::<crate::frame::Tag>()
+                    $(
+                        + s2n_codec::EncoderValue::encoding_size(&self.$field)
+                    )*;
+
+                crate::frame::debug_assert_encoding_size(self, encoder, len);
+
+                len
+            }
         }
     };
 }
@@ -254,6 +299,26 @@ frames! {
     datagram_tag => datagram, handle_datagram_frame, Datagram[Data];
 }
 
+/// Checks that a hand-optimized frame encoding size matches what is actually encoded
+///
+/// Frames compute their encoding size without encoding themselves, which allows packets to be
+/// assembled without an estimation pass over each frame. The check only runs with
+/// `debug_assertions` enabled.
+#[inline]
+pub(crate) fn debug_assert_encoding_size<Frame: EncoderValue, E: Encoder>(
+    frame: &Frame,
+    encoder: &E,
+    len: usize,
+) {
+    if cfg!(debug_assertions) {
+        use s2n_codec::EncoderLenEstimator;
+
+        let mut estimator = EncoderLenEstimator::new(encoder.remaining_capacity());
+        frame.encode(&mut estimator);
+        assert_eq!(estimator.len(), len);
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default)]
 /// Indicates the packet will not fit into the provided capacity
 pub struct FitError;
@@ -1,7 +1,10 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{frame::Tag, varint::VarInt};
+use crate::{
+    frame::{debug_assert_encoding_size, Tag},
+    varint::VarInt,
+};
 use core::{convert::TryInto, mem::size_of};
 use s2n_codec::{decoder_invariant, decoder_parameterized_value, Encoder, EncoderValue};
 
@@ -131,4 +134,19 @@ impl<'a> EncoderValue for NewConnectionId<'a> {
         buffer.encode_with_len_prefix::<u8, _>(&self.connection_id);
         buffer.encode(&self.stateless_reset_token.as_ref());
     }
+
+    #[inline]
+    fn encoding_size_for_encoder<E: Encoder>(&self, encoder: &E) -> usize {
+        let mut len = 0;
+        len += size_of::<Tag>();
+        len += self.sequence_number.encoding_size();
+        len += self.retire_prior_to.encoding_size();
+        len += size_of::<u8>();
+        len += self.connection_id.len();
+        len += STATELESS_RESET_TOKEN_LEN;
+
+        debug_assert_encoding_size(self, encoder, len);
+
+        len
+    }
 }
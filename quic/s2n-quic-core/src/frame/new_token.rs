@@ -1,7 +1,11 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{frame::Tag, varint::VarInt};
+use crate::{
+    frame::{debug_assert_encoding_size, Tag},
+    varint::VarInt,
+};
+use core::{convert::TryFrom, mem::size_of};
 use s2n_codec::{decoder_invariant, decoder_parameterized_value, Encoder, EncoderValue};
 
 //= https://www.rfc-editor.org/rfc/rfc9000#section-19.7
@@ -69,4 +73,16 @@ impl<'a> EncoderValue for NewToken<'a> {
         buffer.encode(&self.tag());
         buffer.encode_with_len_prefix::<VarInt, _>(&self.token);
     }
+
+    #[inline]
+    fn encoding_size_for_encoder<E: Encoder>(&self, encoder: &E) -> usize {
+        let mut len = 0;
+        len += size_of::<Tag>();
+        len += VarInt::try_from(self.token.len()).unwrap().encoding_size();
+        len += self.token.len();
+
+        debug_assert_encoding_size(self, encoder, len);
+
+        len
+    }
 }
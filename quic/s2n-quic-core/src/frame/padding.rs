@@ -67,4 +67,14 @@ impl EncoderValue for Padding {
     fn encode<E: Encoder>(&self, encoder: &mut E) {
         encoder.write_repeated(self.length, 0)
     }
+
+    #[inline]
+    fn encoding_size(&self) -> usize {
+        self.length
+    }
+
+    #[inline]
+    fn encoding_size_for_encoder<E: Encoder>(&self, _encoder: &E) -> usize {
+        self.length
+    }
 }
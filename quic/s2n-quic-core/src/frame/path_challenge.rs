@@ -1,8 +1,8 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::frame::Tag;
-use core::convert::TryInto;
+use crate::frame::{debug_assert_encoding_size, Tag};
+use core::{convert::TryInto, mem::size_of};
 use s2n_codec::{decoder_parameterized_value, Encoder, EncoderValue};
 
 //= https://www.rfc-editor.org/rfc/rfc9000#section-19.17
@@ -61,4 +61,13 @@ impl<'a> EncoderValue for PathChallenge<'a> {
         buffer.encode(&self.tag());
         buffer.encode(&self.data.as_ref());
     }
+
+    #[inline]
+    fn encoding_size_for_encoder<E: Encoder>(&self, encoder: &E) -> usize {
+        let len = size_of::<Tag>() + DATA_LEN;
+
+        debug_assert_encoding_size(self, encoder, len);
+
+        len
+    }
 }
@@ -1,7 +1,8 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::frame::Tag;
+use crate::frame::{debug_assert_encoding_size, Tag};
+use core::mem::size_of;
 use s2n_codec::{decoder_parameterized_value, Encoder, EncoderValue};
 
 //= https://www.rfc-editor.org/rfc/rfc9000#section-19.18
@@ -56,4 +57,13 @@ impl<'a> EncoderValue for PathResponse<'a> {
         buffer.encode(&self.tag());
         buffer.encode(&self.data.as_ref());
     }
+
+    #[inline]
+    fn encoding_size_for_encoder<E: Encoder>(&self, encoder: &E) -> usize {
+        let len = size_of::<Tag>() + DATA_LEN;
+
+        debug_assert_encoding_size(self, encoder, len);
+
+        len
+    }
 }
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    frame::{FitError, Tag},
+    frame::{debug_assert_encoding_size, FitError, Tag},
     varint::VarInt,
 };
 use core::{convert::TryFrom, mem::size_of};
@@ -250,13 +250,7 @@ impl<Data: EncoderValue> EncoderValue for Stream<Data> {
         }
 
         // make sure the encoding size matches what we would actually encode
-        if cfg!(debug_assertions) {
-            use s2n_codec::EncoderLenEstimator;
-
-            let mut estimator = EncoderLenEstimator::new(encoder.remaining_capacity());
-            self.encode(&mut estimator);
-            assert_eq!(estimator.len(), len);
-        }
+        debug_assert_encoding_size(self, encoder, len);
 
         len
     }
@@ -1,7 +1,12 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{frame::Tag, stream::StreamType, varint::VarInt};
+use crate::{
+    frame::{debug_assert_encoding_size, Tag},
+    stream::StreamType,
+    varint::VarInt,
+};
+use core::mem::size_of;
 use s2n_codec::{decoder_invariant, decoder_parameterized_value, Encoder, EncoderValue};
 
 //= https://www.rfc-editor.org/rfc/rfc9000#section-19.14
@@ -85,4 +90,13 @@ impl EncoderValue for StreamsBlocked {
         buffer.encode(&self.tag());
         buffer.encode(&self.stream_limit);
     }
+
+    #[inline]
+    fn encoding_size_for_encoder<E: Encoder>(&self, encoder: &E) -> usize {
+        let len = size_of::<Tag>() + self.stream_limit.encoding_size();
+
+        debug_assert_encoding_size(self, encoder, len);
+
+        len
+    }
 }
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    connection, inet::ExplicitCongestionNotification, path::LocalAddress, time::Timestamp,
+    connection,
+    inet::ExplicitCongestionNotification,
+    path::LocalAddress,
+    time::{Duration, Timestamp},
 };
 
 /// Header information for a datagram sent/received over the network
@@ -10,6 +13,8 @@ use crate::{
 pub struct Header<Path> {
     pub path: Path,
     pub ecn: ExplicitCongestionNotification,
+    /// The timestamps which were recorded when the datagram was received
+    pub rx_timestamps: RxTimestamps,
 }
 
 /// Metadata for a datagram sent/received over the network
@@ -32,4 +37,20 @@ pub struct AncillaryData {
     /// Correctly threading this value through to connections ensures packets end up on the same
     /// network interfaces and thereby have consistent MAC addresses.
     pub local_interface: Option<u32>,
+    pub rx_timestamps: RxTimestamps,
+}
+
+/// Timestamps which were recorded for a received datagram before it was read from the socket
+///
+/// These are only available if the IO provider enabled timestamping on the socket.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RxTimestamps {
+    /// The time at which the kernel received the datagram, relative to the UNIX epoch
+    pub software: Option<Duration>,
+    /// The time at which the network interface received the datagram
+    ///
+    /// The clock of the network interface isn't necessarily synchronized with the system clock.
+    pub hardware: Option<Duration>,
+    /// How long the datagram waited in the socket receive queue before it was read
+    pub queue_delay: Option<Duration>,
 }
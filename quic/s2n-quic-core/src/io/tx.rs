@@ -42,6 +42,16 @@ pub trait Queue {
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Returns how long before their departure time paced messages may be pushed
+    ///
+    /// Queues which hold back each message for its [`Message::delay`], e.g. with the SO_TXTIME
+    /// socket option, accept paced messages ahead of time, which saves the sender from waking
+    /// up for every paced burst. Queues which send messages as soon as possible return zero.
+    #[inline]
+    fn pacing_horizon(&self) -> Duration {
+        Duration::ZERO
+    }
 }
 
 pub struct Outcome {
@@ -92,7 +102,9 @@ pub trait Message {
 
     /// Returns the Duration for which the message will be delayed.
     ///
-    /// This is used in scenarios where packets need to be paced.
+    /// This is used in scenarios where packets need to be paced. The delay is queried before
+    /// the payload is written, since writing the payload may update the departure time of the
+    /// next message.
     fn delay(&mut self) -> Duration;
 
     /// Returns the IPv6 flow label for the message
@@ -15,6 +15,7 @@ pub mod ct;
 pub mod datagram;
 pub mod endpoint;
 pub mod event;
+pub mod extension_frame;
 pub mod frame;
 pub mod havoc;
 pub mod inet;
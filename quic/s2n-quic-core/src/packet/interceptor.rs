@@ -78,7 +78,7 @@ pub trait Interceptor: 'static + Send {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Disabled(());
 
 impl Interceptor for Disabled {}
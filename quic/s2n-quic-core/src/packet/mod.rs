@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::connection;
-use s2n_codec::{decoder_invariant, DecoderBufferMut, DecoderBufferMutResult, DecoderError};
+use s2n_codec::{DecoderBufferMut, DecoderBufferMutResult, DecoderError};
 
 pub(crate) type Tag = u8;
 
@@ -24,11 +24,15 @@ pub mod encoding;
 pub mod interceptor;
 pub mod key_phase;
 pub mod long;
+pub mod observer;
 
 pub mod number;
+pub mod quic_bit;
 pub mod stateless_reset;
+pub mod tool;
 
 pub use key_phase::{KeyPhase, ProtectedKeyPhase};
+pub use quic_bit::QuicBit;
 
 use connection::id::ConnectionInfo;
 use handshake::ProtectedHandshake;
@@ -85,6 +89,20 @@ impl<'a> ProtectedPacket<'a> {
         }
     }
 
+    /// Returns the packet's QUIC bit
+    ///
+    /// The bit is unused in Version Negotiation packets, so they always return `QuicBit::One`.
+    pub fn quic_bit(&self) -> QuicBit {
+        match self {
+            ProtectedPacket::Short(packet) => packet.quic_bit,
+            ProtectedPacket::VersionNegotiation(_packet) => QuicBit::One,
+            ProtectedPacket::Initial(packet) => QuicBit::from_tag(packet.payload.get_tag()),
+            ProtectedPacket::ZeroRtt(packet) => QuicBit::from_tag(packet.payload.get_tag()),
+            ProtectedPacket::Handshake(packet) => QuicBit::from_tag(packet.payload.get_tag()),
+            ProtectedPacket::Retry(packet) => QuicBit::from_tag(packet.tag),
+        }
+    }
+
     pub fn version(&self) -> Option<u32> {
         match self {
             ProtectedPacket::Short(_) => None,
@@ -221,7 +239,10 @@ pub trait PacketDecoder<'a> {
             }};
         }
 
-        match tag >> 4 {
+        // Peers that negotiated greasing the QUIC bit with the `grease_quic_bit` transport
+        // parameter (RFC 9287) may clear it, so packets are classified without it. Connections
+        // which didn't advertise the parameter discard the packets with the bit cleared.
+        match (tag | quic_bit::QUIC_BIT_MASK) >> 4 {
             short_tag!() => {
                 let (packet, buffer) = short::ProtectedShort::decode(
                     tag,
@@ -232,14 +253,6 @@ pub trait PacketDecoder<'a> {
                 let output = self.handle_short_packet(packet)?;
                 Ok((output, buffer))
             }
-            version_negotiation_no_fixed_bit_tag!() => {
-                let (version, _peek) = peek.decode()?;
-                decoder_invariant!(
-                    version_negotiation::VERSION == version,
-                    "invalid version negotiation packet"
-                );
-                version_negotiation!(version)
-            }
             initial_tag!() => long_packet!(ProtectedInitial, handle_initial_packet),
             zero_rtt_tag!() => long_packet!(ProtectedZeroRtt, handle_zero_rtt_packet),
             handshake_tag!() => long_packet!(ProtectedHandshake, handle_handshake_packet),
@@ -249,6 +262,49 @@ pub trait PacketDecoder<'a> {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cleared_quic_bit_test() {
+        let samples: [&[u8]; 5] = [
+            include_bytes!("test_samples/short.bin"),
+            include_bytes!("test_samples/initial.bin"),
+            include_bytes!("test_samples/zero_rtt.bin"),
+            include_bytes!("test_samples/handshake.bin"),
+            include_bytes!("test_samples/retry.bin"),
+        ];
+
+        let remote_address = crate::inet::ip::SocketAddress::default();
+        let connection_info = ConnectionInfo::new(&remote_address);
+
+        for sample in samples.iter() {
+            let mut bytes = sample.to_vec();
+            let (packet, _) = ProtectedPacket::decode(
+                DecoderBufferMut::new(&mut bytes),
+                &connection_info,
+                &long::DESTINATION_CONNECTION_ID_MAX_LEN,
+            )
+            .unwrap();
+            assert_eq!(packet.quic_bit(), QuicBit::One);
+            let expected = core::mem::discriminant(&packet);
+
+            // packets with the bit cleared are still decoded as the same packet type
+            let mut bytes = sample.to_vec();
+            bytes[0] ^= quic_bit::QUIC_BIT_MASK;
+            let (packet, _) = ProtectedPacket::decode(
+                DecoderBufferMut::new(&mut bytes),
+                &connection_info,
+                &long::DESTINATION_CONNECTION_ID_MAX_LEN,
+            )
+            .unwrap();
+            assert_eq!(packet.quic_bit(), QuicBit::Zero);
+            assert_eq!(core::mem::discriminant(&packet), expected);
+        }
+    }
+}
+
 #[cfg(test)]
 mod snapshots {
     use super::*;
@@ -249,6 +249,22 @@ impl<V> Map<V> {
         self.index = self.values.len();
     }
 
+    /// Returns the number of entries the map can hold without reallocating
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Releases the storage which was allocated beyond the default capacity
+    ///
+    /// Only empty maps are shrunk, as the entries would need to be moved otherwise.
+    #[inline]
+    pub fn shrink(&mut self) {
+        if self.is_empty() && self.values.len() > DEFAULT_CAPACITY {
+            *self = Self::default();
+        }
+    }
+
     #[inline]
     fn pn_index(&self, packet_number: PacketNumber) -> Option<usize> {
         // the map is empty so there are no valid entries
@@ -565,6 +581,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn shrink() {
+        let mut sent_packets = TestMap::default();
+
+        let first = PacketNumberSpace::Initial.new_packet_number(VarInt::from_u8(0));
+        let packet_numbers: Vec<_> = core::iter::successors(Some(first), |pn| pn.next())
+            .take(32)
+            .collect();
+
+        for (value, packet_number) in packet_numbers.iter().enumerate() {
+            sent_packets.insert(*packet_number, value as u64);
+        }
+        let capacity = sent_packets.capacity();
+        assert!(capacity > DEFAULT_CAPACITY);
+
+        // the map isn't shrunk while it contains entries
+        sent_packets.remove(packet_numbers[0]);
+        sent_packets.shrink();
+        assert_eq!(sent_packets.capacity(), capacity);
+
+        for packet_number in &packet_numbers[1..] {
+            sent_packets.remove(*packet_number);
+        }
+        sent_packets.shrink();
+        assert_eq!(sent_packets.capacity(), DEFAULT_CAPACITY);
+        assert!(sent_packets.is_empty());
+
+        // the map is still usable after shrinking
+        let packet_number = packet_numbers[31].next().unwrap();
+        sent_packets.insert(packet_number, 32);
+        assert_eq!(sent_packets.get(packet_number), Some(&32));
+    }
+
     #[test]
     fn remove() {
         let mut sent_packets = TestMap::default();
@@ -186,36 +186,51 @@ fn decode_packet_number(
     largest_pn: PacketNumber,
     truncated_pn: TruncatedPacketNumber,
 ) -> PacketNumber {
-    use crate::ct::{ConditionallySelectable, Number};
+    use crate::ct::{Choice, ConditionallySelectable};
+
+    /// Returns `1` if `a < b`, otherwise `0`
+    ///
+    /// The comparison is derived from the borrow of the subtraction, which requires both values
+    /// to be less than `2^63`.
+    #[inline(always)]
+    fn lt(a: u64, b: u64) -> u8 {
+        debug_assert!(a < 1 << 63 && b < 1 << 63);
+        (a.wrapping_sub(b) >> 63) as u8
+    }
 
     let space = largest_pn.space();
     space.assert_eq(truncated_pn.space());
 
+    // All of the values are computed in a single word without any branches. Since the largest
+    // packet number is at most `2^62 - 1` and the window is at most `2^32`, none of the
+    // intermediate values can exceed `2^63`, so they don't need to be checked for overflow.
     let pn_nbits = truncated_pn.bitsize();
-    // deref to u64 so we have enough room
     let expected_pn = largest_pn.as_u64() + 1;
     let pn_win = 1 << pn_nbits;
     let pn_hwin = pn_win / 2;
     let pn_mask = pn_win - 1;
     let candidate_pn = (expected_pn & !pn_mask) | truncated_pn.into_u64();
+    let max_pn = VarInt::MAX.as_u64();
 
-    // convert numbers into checked Number
-    let expected_pn = Number::new(expected_pn);
-    let mut candidate_pn = Number::new(candidate_pn);
-
+    // if candidate_pn <= expected_pn - pn_hwin and candidate_pn < (1 << 62) - pn_win
     let a_value = candidate_pn + pn_win;
-    let a_choice = candidate_pn.ct_le(expected_pn - pn_hwin)
-        & a_value.ct_le(Number::new(VarInt::MAX.as_u64()));
+    let a_choice = (1 ^ lt(expected_pn, pn_hwin))
+        // the lower bound is masked to keep the comparison in range if `expected_pn - pn_hwin`
+        // underflowed, in which case the first condition already rejects the choice
+        & (1 ^ lt(expected_pn.wrapping_sub(pn_hwin) & max_pn, candidate_pn))
+        & (1 ^ lt(max_pn, a_value));
 
-    let b_value = candidate_pn - pn_win;
-    let b_choice = candidate_pn.ct_gt(expected_pn + pn_hwin) & b_value.is_valid();
+    // if candidate_pn > expected_pn + pn_hwin and candidate_pn >= pn_win
+    let b_value = candidate_pn.wrapping_sub(pn_win);
+    let b_choice = lt(expected_pn + pn_hwin, candidate_pn) & (1 ^ lt(candidate_pn, pn_win));
 
     // apply the choices in reverse since it's easier to emulate the early returns
     // with the `conditional_assign` calls
-    candidate_pn.conditional_assign(&b_value, b_choice);
-    candidate_pn.conditional_assign(&a_value, a_choice);
+    let mut candidate_pn = candidate_pn;
+    candidate_pn.conditional_assign(&b_value, Choice::from(b_choice));
+    candidate_pn.conditional_assign(&a_value, Choice::from(a_choice));
 
-    let candidate_pn = candidate_pn.unwrap_or_default().min(VarInt::MAX.as_u64());
+    let candidate_pn = candidate_pn.min(max_pn);
 
     let candidate_pn = unsafe {
         // Safety: the value has already been checked in constant time above
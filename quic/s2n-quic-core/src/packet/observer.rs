@@ -0,0 +1,218 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parses the unprotected header fields of the packets in a datagram
+//!
+//! The headers are parsed with the same decoder as the endpoint uses, without any keys, so
+//! observers outside of the endpoint, such as sidecars or taps on the network, can classify
+//! traffic the same way the endpoint does.
+//!
+//! Short header packets don't encode the length of their Destination Connection ID, so the
+//! observer needs to know the length of the connection IDs the endpoint issues. The packet types
+//! of long header packets are interpreted as defined by QUIC version 1.
+//!
+//! ```rust
+//! use s2n_quic_core::packet::observer::{Datagram, Kind};
+//!
+//! // a Version Negotiation packet
+//! let mut datagram = [
+//!     0x80, 0, 0, 0, 0, // header form and version
+//!     2, 1, 2, // destination connection id
+//!     2, 3, 4, // source connection id
+//!     0, 0, 0, 1, // supported version
+//! ];
+//!
+//! let mut headers = Datagram::new(&mut datagram, 8);
+//! let header = headers.next().unwrap().unwrap();
+//! assert_eq!(header.kind, Kind::VersionNegotiation);
+//! assert_eq!(header.destination_connection_id, &[1, 2]);
+//! assert_eq!(header.source_connection_id, Some(&[3, 4][..]));
+//! assert!(headers.next().is_none());
+//! ```
+
+use crate::{
+    connection::id::ConnectionInfo,
+    inet::SocketAddress,
+    packet::{version_negotiation, ProtectedPacket, QuicBit},
+};
+use core::ops::Range;
+use s2n_codec::{DecoderBufferMut, DecoderError};
+
+/// The types of packets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Short,
+    VersionNegotiation,
+    Initial,
+    ZeroRtt,
+    Handshake,
+    Retry,
+}
+
+/// The unprotected header fields of a packet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Header<'a> {
+    pub kind: Kind,
+    /// The version of long header packets
+    ///
+    /// Version Negotiation packets use the reserved version 0.
+    pub version: Option<u32>,
+    pub destination_connection_id: &'a [u8],
+    /// The Source Connection ID of long header packets
+    pub source_connection_id: Option<&'a [u8]>,
+    pub quic_bit: QuicBit,
+    /// The length of the packet, including the header
+    pub len: usize,
+}
+
+impl<'a> Header<'a> {
+    /// Decodes the header of the packet at the front of the buffer
+    ///
+    /// The buffer isn't modified. Returns the header and the coalesced packets which follow the
+    /// packet.
+    pub fn decode(
+        buffer: &'a mut [u8],
+        destination_connection_id_len: usize,
+    ) -> Result<(Self, &'a mut [u8]), DecoderError> {
+        let remote_address = SocketAddress::default();
+        let connection_info = ConnectionInfo::new(&remote_address);
+
+        // the fields are recorded as offsets, since the decoded packet borrows the buffer mutably
+        let start = buffer.as_ptr() as usize;
+        let range = |field: &[u8]| -> Range<usize> {
+            let offset = field.as_ptr() as usize - start;
+            offset..offset + field.len()
+        };
+
+        let capacity = buffer.len();
+        let (packet, remaining) = ProtectedPacket::decode(
+            DecoderBufferMut::new(&mut *buffer),
+            &connection_info,
+            &destination_connection_id_len,
+        )?;
+        let len = capacity - remaining.len();
+
+        let kind = match &packet {
+            ProtectedPacket::Short(_) => Kind::Short,
+            ProtectedPacket::VersionNegotiation(_) => Kind::VersionNegotiation,
+            ProtectedPacket::Initial(_) => Kind::Initial,
+            ProtectedPacket::ZeroRtt(_) => Kind::ZeroRtt,
+            ProtectedPacket::Handshake(_) => Kind::Handshake,
+            ProtectedPacket::Retry(_) => Kind::Retry,
+        };
+        let version = match kind {
+            Kind::VersionNegotiation => Some(version_negotiation::VERSION),
+            _ => packet.version(),
+        };
+        let quic_bit = packet.quic_bit();
+        let destination_connection_id = range(packet.destination_connection_id());
+        let source_connection_id = packet.source_connection_id().map(range);
+
+        let (packet, remaining) = buffer.split_at_mut(len);
+        let packet: &'a [u8] = packet;
+
+        let header = Self {
+            kind,
+            version,
+            destination_connection_id: &packet[destination_connection_id],
+            source_connection_id: source_connection_id.map(|range| &packet[range]),
+            quic_bit,
+            len,
+        };
+
+        Ok((header, remaining))
+    }
+}
+
+/// Iterates over the headers of the coalesced packets in a datagram
+///
+/// The iteration stops after the first packet which fails to decode.
+#[derive(Debug)]
+pub struct Datagram<'a> {
+    buffer: &'a mut [u8],
+    destination_connection_id_len: usize,
+}
+
+impl<'a> Datagram<'a> {
+    /// Creates an iterator over the packets in the datagram
+    ///
+    /// `destination_connection_id_len` is the length of the Destination Connection ID of short
+    /// header packets.
+    pub fn new(buffer: &'a mut [u8], destination_connection_id_len: usize) -> Self {
+        Self {
+            buffer,
+            destination_connection_id_len,
+        }
+    }
+}
+
+impl<'a> Iterator for Datagram<'a> {
+    type Item = Result<Header<'a>, DecoderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        let buffer = core::mem::take(&mut self.buffer);
+        match Header::decode(buffer, self.destination_connection_id_len) {
+            Ok((header, remaining)) => {
+                self.buffer = remaining;
+                Some(Ok(header))
+            }
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INITIAL: &[u8] = include_bytes!("test_samples/initial.bin");
+    static HANDSHAKE: &[u8] = include_bytes!("test_samples/handshake.bin");
+    static SHORT: &[u8] = include_bytes!("test_samples/short.bin");
+    static CONNECTION_ID: &[u8] = &[
+        1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20,
+    ];
+
+    #[test]
+    fn coalesced_test() {
+        let mut datagram = [INITIAL, HANDSHAKE, SHORT].concat();
+        let expected = datagram.clone();
+
+        let headers: Vec<_> = Datagram::new(&mut datagram, CONNECTION_ID.len())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        let kinds: Vec<_> = headers.iter().map(|header| header.kind).collect();
+        assert_eq!(kinds, [Kind::Initial, Kind::Handshake, Kind::Short]);
+
+        let lens: Vec<_> = headers.iter().map(|header| header.len).collect();
+        assert_eq!(lens, [INITIAL.len(), HANDSHAKE.len(), SHORT.len()]);
+
+        for header in &headers {
+            assert_eq!(header.destination_connection_id, CONNECTION_ID);
+            assert_eq!(header.quic_bit, QuicBit::One);
+        }
+
+        assert_eq!(headers[0].version, Some(0x0102_0304));
+        assert_eq!(headers[0].source_connection_id, Some(CONNECTION_ID));
+        assert_eq!(headers[2].version, None);
+        assert_eq!(headers[2].source_connection_id, None);
+
+        // the datagram isn't modified
+        assert_eq!(datagram, expected);
+    }
+
+    #[test]
+    fn invalid_packet_test() {
+        let mut datagram = [INITIAL, &INITIAL[..INITIAL.len() - 1]].concat();
+        let mut headers = Datagram::new(&mut datagram, CONNECTION_ID.len());
+
+        assert_eq!(headers.next().unwrap().unwrap().kind, Kind::Initial);
+        assert!(headers.next().unwrap().is_err());
+        assert!(headers.next().is_none());
+    }
+}
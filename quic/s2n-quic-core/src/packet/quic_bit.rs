@@ -0,0 +1,122 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::Tag;
+use crate::random;
+
+//= https://www.rfc-editor.org/rfc/rfc9000#section-17.2
+//# Fixed Bit:  The next bit (0x40) of byte 0 is set to 1, unless the
+//#    packet is a Version Negotiation packet.
+
+//= https://www.rfc-editor.org/rfc/rfc9000#section-17.3.1
+//# Fixed Bit:  The next bit (0x40) of byte 0 is set to 1.
+
+pub(crate) const QUIC_BIT_MASK: u8 = 0x40;
+
+/// The value of the QUIC bit, also known as the Fixed Bit, of a packet
+///
+/// The bit is always set, unless the peer advertised the `grease_quic_bit` transport
+/// parameter from RFC 9287, which allows it to be set to an arbitrary value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuicBit {
+    Zero,
+    One,
+}
+
+impl Default for QuicBit {
+    fn default() -> Self {
+        Self::One
+    }
+}
+
+impl QuicBit {
+    #[inline]
+    pub fn from_tag(tag: Tag) -> Self {
+        if tag & QUIC_BIT_MASK == QUIC_BIT_MASK {
+            Self::One
+        } else {
+            Self::Zero
+        }
+    }
+
+    #[inline]
+    pub fn into_packet_tag_mask(self) -> u8 {
+        match self {
+            Self::One => QUIC_BIT_MASK,
+            Self::Zero => 0,
+        }
+    }
+
+    /// Returns `true` if the bit was cleared
+    #[inline]
+    pub fn is_zero(self) -> bool {
+        matches!(self, Self::Zero)
+    }
+}
+
+/// Chooses unpredictable values for the QUIC bit of packets sent to a peer that supports
+/// greasing it
+///
+/// The value doesn't need to be secret, so a cheap generator is seeded once per connection
+/// instead of querying the random generator for every packet.
+#[derive(Clone, Copy, Debug)]
+pub struct Grease {
+    state: u64,
+}
+
+impl Grease {
+    pub fn new<R: random::Generator + ?Sized>(random_generator: &mut R) -> Self {
+        let mut seed = [0; 8];
+        random_generator.public_random_fill(&mut seed);
+
+        Self {
+            // the xorshift state must not be zero
+            state: u64::from_le_bytes(seed) | 1,
+        }
+    }
+
+    /// Returns the QUIC bit for the next packet
+    #[inline]
+    pub fn next_bit(&mut self) -> QuicBit {
+        // xorshift64
+        let mut state = self.state;
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        self.state = state;
+
+        if state >> 63 == 1 {
+            QuicBit::One
+        } else {
+            QuicBit::Zero
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quic_bit_from_tag_test() {
+        for tag in 0..=255 {
+            let bit = QuicBit::from_tag(tag);
+            assert_eq!(bit.into_packet_tag_mask(), tag & QUIC_BIT_MASK);
+        }
+    }
+
+    #[test]
+    fn grease_test() {
+        let mut grease = Grease::new(&mut random::testing::Generator(123));
+
+        let mut ones = 0;
+        for _ in 0..1000 {
+            if !grease.next_bit().is_zero() {
+                ones += 1;
+            }
+        }
+
+        // both values should be used roughly as often
+        assert!((400..600).contains(&ones), "{}", ones);
+    }
+}
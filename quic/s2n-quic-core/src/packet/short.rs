@@ -12,7 +12,7 @@ use crate::{
             PacketNumber, PacketNumberLen, PacketNumberSpace, ProtectedPacketNumber,
             TruncatedPacketNumber,
         },
-        KeyPhase, ProtectedKeyPhase, Tag,
+        KeyPhase, ProtectedKeyPhase, QuicBit, Tag,
     },
     transport,
 };
@@ -43,8 +43,6 @@ macro_rules! short_tag {
     };
 }
 
-const ENCODING_TAG: u8 = 0b0100_0000;
-
 //= https://www.rfc-editor.org/rfc/rfc9000#section-17.3.1
 //# Spin Bit:  The third most significant bit (0x20) of byte 0 is the
 //#    latency spin bit, set as described in Section 17.4.
@@ -115,6 +113,7 @@ impl SpinBit {
 
 #[derive(Debug)]
 pub struct Short<DCID, KeyPhase, PacketNumber, Payload> {
+    pub quic_bit: QuicBit,
     pub spin_bit: SpinBit,
     pub key_phase: KeyPhase,
     pub destination_connection_id: DCID,
@@ -137,6 +136,7 @@ impl<'a> ProtectedShort<'a> {
     ) -> DecoderBufferMutResult<'a, ProtectedShort<'a>> {
         let mut decoder = HeaderDecoder::new_short(&buffer);
 
+        let quic_bit = QuicBit::from_tag(tag);
         let spin_bit = SpinBit::from_tag(tag);
         let key_phase = ProtectedKeyPhase;
 
@@ -150,6 +150,7 @@ impl<'a> ProtectedShort<'a> {
             decoder.finish_short()?.split_off_packet(buffer)?;
 
         let packet = Short {
+            quic_bit,
             spin_bit,
             key_phase,
             destination_connection_id,
@@ -166,6 +167,7 @@ impl<'a> ProtectedShort<'a> {
         largest_acknowledged_packet_number: PacketNumber,
     ) -> Result<EncryptedShort<'a>, CryptoError> {
         let Short {
+            quic_bit,
             spin_bit,
             destination_connection_id,
             payload,
@@ -180,6 +182,7 @@ impl<'a> ProtectedShort<'a> {
         let packet_number = truncated_packet_number.expand(largest_acknowledged_packet_number);
 
         Ok(Short {
+            quic_bit,
             spin_bit,
             key_phase,
             destination_connection_id,
@@ -199,6 +202,7 @@ impl<'a> ProtectedShort<'a> {
 impl<'a> EncryptedShort<'a> {
     pub fn decrypt<C: OneRttKey>(self, crypto: &C) -> Result<CleartextShort<'a>, transport::Error> {
         let Short {
+            quic_bit,
             spin_bit,
             key_phase,
             destination_connection_id,
@@ -224,6 +228,7 @@ impl<'a> EncryptedShort<'a> {
         let destination_connection_id = destination_connection_id.get(header);
 
         Ok(Short {
+            quic_bit,
             spin_bit,
             key_phase,
             destination_connection_id,
@@ -266,7 +271,7 @@ impl<DCID: EncoderValue, Payload: EncoderValue> EncoderValue
 impl<DCID: EncoderValue, PacketNumber, Payload> Short<DCID, KeyPhase, PacketNumber, Payload> {
     #[inline]
     fn encode_header<E: Encoder>(&self, packet_number_len: PacketNumberLen, encoder: &mut E) {
-        (ENCODING_TAG
+        (self.quic_bit.into_packet_tag_mask()
             | self.spin_bit.into_packet_tag_mask()
             | self.key_phase.into_packet_tag_mask()
             | packet_number_len.into_packet_tag_mask())
@@ -0,0 +1,712 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Crafts and parses raw QUIC packets
+//!
+//! The types in this module wrap the packet encoders and decoders which are used by the
+//! transport, with plain byte slices for all of the fields. This allows testing tools and
+//! scanners to generate and inspect protocol traffic without having to drive a connection.
+//!
+//! Initial and Handshake packets are protected with the keys of a crypto provider, such as
+//! `s2n-quic-crypto`. Payloads are passed as encoded frames, which aren't validated.
+//!
+//! ```rust
+//! use s2n_quic_core::packet::tool::{VersionNegotiation, VERSION_1};
+//!
+//! let packet = VersionNegotiation {
+//!     destination_connection_id: &[1, 2, 3, 4],
+//!     source_connection_id: &[5, 6, 7, 8],
+//!     supported_versions: &[VERSION_1],
+//! };
+//!
+//! let mut buffer = [0u8; 1500];
+//! let len = packet.encode(&mut buffer).unwrap();
+//!
+//! let (decoded, _remaining) = VersionNegotiation::decode(&mut buffer[..len]).unwrap();
+//! assert_eq!(decoded.destination_connection_id(), &[1, 2, 3, 4]);
+//! assert_eq!(decoded.iter().collect::<Vec<_>>(), [VERSION_1]);
+//! ```
+
+use crate::{
+    connection::id::{self, ConnectionInfo},
+    crypto::{
+        retry::{IntegrityTag, INTEGRITY_TAG_LEN},
+        CryptoError, HandshakeHeaderKey, HandshakeKey, InitialHeaderKey, InitialKey, RetryKey,
+    },
+    inet::SocketAddress,
+    packet::{
+        self,
+        encoding::{PacketEncoder, PacketEncodingError, PacketPayloadEncoder},
+        long::DESTINATION_CONNECTION_ID_MAX_LEN,
+        number::{PacketNumber, PacketNumberSpace},
+        retry::PseudoRetry,
+        version_negotiation::ProtectedVersionNegotiation,
+        ProtectedPacket,
+    },
+    transport,
+    varint::VarInt,
+};
+use core::fmt;
+use s2n_codec::{
+    DecoderBufferMut, DecoderError, Encoder, EncoderBuffer, EncoderLenEstimator, EncoderValue,
+};
+
+/// The QUIC version 1 from [RFC 9000](https://www.rfc-editor.org/rfc/rfc9000)
+pub const VERSION_1: u32 = 0x0000_0001;
+
+/// An error which occurred while crafting or parsing a packet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The buffer is too small to hold the packet
+    InsufficientSpace,
+    /// The packet number exceeds the maximum value of a varint
+    InvalidPacketNumber,
+    /// A connection ID is longer than 20 bytes
+    InvalidConnectionId,
+    /// The packet is of a different type than was requested
+    UnexpectedPacket,
+    /// The packet is malformed
+    Decoder(&'static str),
+    /// The packet failed to be protected or unprotected
+    Crypto(CryptoError),
+    /// The packet violates the protocol, for example because its reserved bits are set
+    Transport(transport::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InsufficientSpace => write!(f, "the buffer is too small to hold the packet"),
+            Self::InvalidPacketNumber => write!(f, "the packet number is out of range"),
+            Self::InvalidConnectionId => write!(f, "the connection ID is too long"),
+            Self::UnexpectedPacket => write!(f, "unexpected packet type"),
+            Self::Decoder(error) => write!(f, "malformed packet: {}", error),
+            Self::Crypto(error) => write!(f, "{}", error),
+            Self::Transport(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<DecoderError> for Error {
+    fn from(error: DecoderError) -> Self {
+        Self::Decoder(error.into())
+    }
+}
+
+impl From<CryptoError> for Error {
+    fn from(error: CryptoError) -> Self {
+        Self::Crypto(error)
+    }
+}
+
+impl From<transport::Error> for Error {
+    fn from(error: transport::Error) -> Self {
+        Self::Transport(error)
+    }
+}
+
+impl<'a> From<PacketEncodingError<'a>> for Error {
+    fn from(error: PacketEncodingError<'a>) -> Self {
+        match error {
+            PacketEncodingError::PacketNumberTruncationError(_) => Self::InvalidPacketNumber,
+            // the payload is always padded, so it only fails to be written if it doesn't fit
+            PacketEncodingError::InsufficientSpace(_) | PacketEncodingError::EmptyPayload(_) => {
+                Self::InsufficientSpace
+            }
+            PacketEncodingError::AeadLimitReached(_) => {
+                Self::Crypto(CryptoError::INTERNAL_ERROR.with_reason("AEAD limit reached"))
+            }
+        }
+    }
+}
+
+/// An Initial packet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Initial<'a> {
+    pub version: u32,
+    pub destination_connection_id: &'a [u8],
+    pub source_connection_id: &'a [u8],
+    pub token: &'a [u8],
+    pub packet_number: u64,
+    /// The encoded frames of the packet
+    pub payload: &'a [u8],
+    /// The payload is padded with PADDING frames until the packet reaches this length
+    ///
+    /// Clients need to send Initial packets of at least 1200 bytes for servers to accept them.
+    /// This is ignored when decoding.
+    pub min_packet_len: usize,
+}
+
+impl<'a> Initial<'a> {
+    /// Creates a version 1 Initial packet without a token
+    pub fn new(
+        destination_connection_id: &'a [u8],
+        source_connection_id: &'a [u8],
+        payload: &'a [u8],
+    ) -> Self {
+        Self {
+            version: VERSION_1,
+            destination_connection_id,
+            source_connection_id,
+            token: &[],
+            packet_number: 0,
+            payload,
+            min_packet_len: 0,
+        }
+    }
+
+    /// Encodes and protects the packet into the buffer
+    ///
+    /// Returns the length of the packet.
+    pub fn encode<K: InitialKey, H: InitialHeaderKey>(
+        &self,
+        key: &K,
+        header_key: &H,
+        buffer: &mut [u8],
+    ) -> Result<usize, Error> {
+        validate_connection_ids(self.destination_connection_id, self.source_connection_id)?;
+        let packet_number = packet_number(PacketNumberSpace::Initial, self.packet_number)?;
+
+        let packet = packet::initial::Initial {
+            version: self.version,
+            destination_connection_id: self.destination_connection_id,
+            source_connection_id: self.source_connection_id,
+            token: self.token,
+            packet_number,
+            payload: Padded(self.payload),
+        };
+
+        encode_packet(packet, key, header_key, self.min_packet_len, buffer)
+    }
+
+    /// Decodes and unprotects an Initial packet from the front of the buffer
+    ///
+    /// The packet is decrypted in place. Returns the packet and the coalesced packets which
+    /// follow it.
+    pub fn decode<K: InitialKey, H: InitialHeaderKey>(
+        buffer: &'a mut [u8],
+        key: &K,
+        header_key: &H,
+    ) -> Result<(Self, &'a mut [u8]), Error> {
+        let (packet, remaining) = decode_packet(buffer)?;
+        let packet = match packet {
+            ProtectedPacket::Initial(packet) => packet,
+            _ => return Err(Error::UnexpectedPacket),
+        };
+
+        let packet = packet
+            .unprotect(
+                header_key,
+                largest_packet_number(PacketNumberSpace::Initial),
+            )?
+            .decrypt(key)?;
+
+        let packet = Self {
+            version: packet.version,
+            destination_connection_id: packet.destination_connection_id,
+            source_connection_id: packet.source_connection_id,
+            token: packet.token,
+            packet_number: packet.packet_number.as_u64(),
+            payload: packet.payload.into_less_safe_slice(),
+            min_packet_len: 0,
+        };
+
+        Ok((packet, remaining))
+    }
+}
+
+/// A Handshake packet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Handshake<'a> {
+    pub version: u32,
+    pub destination_connection_id: &'a [u8],
+    pub source_connection_id: &'a [u8],
+    pub packet_number: u64,
+    /// The encoded frames of the packet
+    pub payload: &'a [u8],
+    /// The payload is padded with PADDING frames until the packet reaches this length
+    ///
+    /// This is ignored when decoding.
+    pub min_packet_len: usize,
+}
+
+impl<'a> Handshake<'a> {
+    /// Creates a version 1 Handshake packet
+    pub fn new(
+        destination_connection_id: &'a [u8],
+        source_connection_id: &'a [u8],
+        payload: &'a [u8],
+    ) -> Self {
+        Self {
+            version: VERSION_1,
+            destination_connection_id,
+            source_connection_id,
+            packet_number: 0,
+            payload,
+            min_packet_len: 0,
+        }
+    }
+
+    /// Encodes and protects the packet into the buffer
+    ///
+    /// Returns the length of the packet.
+    pub fn encode<K: HandshakeKey, H: HandshakeHeaderKey>(
+        &self,
+        key: &K,
+        header_key: &H,
+        buffer: &mut [u8],
+    ) -> Result<usize, Error> {
+        validate_connection_ids(self.destination_connection_id, self.source_connection_id)?;
+        let packet_number = packet_number(PacketNumberSpace::Handshake, self.packet_number)?;
+
+        let packet = packet::handshake::Handshake {
+            version: self.version,
+            destination_connection_id: self.destination_connection_id,
+            source_connection_id: self.source_connection_id,
+            packet_number,
+            payload: Padded(self.payload),
+        };
+
+        encode_packet(packet, key, header_key, self.min_packet_len, buffer)
+    }
+
+    /// Decodes and unprotects a Handshake packet from the front of the buffer
+    ///
+    /// The packet is decrypted in place. Returns the packet and the coalesced packets which
+    /// follow it.
+    pub fn decode<K: HandshakeKey, H: HandshakeHeaderKey>(
+        buffer: &'a mut [u8],
+        key: &K,
+        header_key: &H,
+    ) -> Result<(Self, &'a mut [u8]), Error> {
+        let (packet, remaining) = decode_packet(buffer)?;
+        let packet = match packet {
+            ProtectedPacket::Handshake(packet) => packet,
+            _ => return Err(Error::UnexpectedPacket),
+        };
+
+        let packet = packet
+            .unprotect(
+                header_key,
+                largest_packet_number(PacketNumberSpace::Handshake),
+            )?
+            .decrypt(key)?;
+
+        let packet = Self {
+            version: packet.version,
+            destination_connection_id: packet.destination_connection_id,
+            source_connection_id: packet.source_connection_id,
+            packet_number: packet.packet_number.as_u64(),
+            payload: packet.payload.into_less_safe_slice(),
+            min_packet_len: 0,
+        };
+
+        Ok((packet, remaining))
+    }
+}
+
+/// A Retry packet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Retry<'a> {
+    pub version: u32,
+    pub destination_connection_id: &'a [u8],
+    pub source_connection_id: &'a [u8],
+    pub retry_token: &'a [u8],
+    /// The Retry Integrity Tag, which is computed when encoding
+    pub integrity_tag: IntegrityTag,
+}
+
+impl<'a> Retry<'a> {
+    /// Creates a version 1 Retry packet
+    pub fn new(
+        destination_connection_id: &'a [u8],
+        source_connection_id: &'a [u8],
+        retry_token: &'a [u8],
+    ) -> Self {
+        Self {
+            version: VERSION_1,
+            destination_connection_id,
+            source_connection_id,
+            retry_token,
+            integrity_tag: [0; INTEGRITY_TAG_LEN],
+        }
+    }
+
+    /// Encodes the packet into the buffer
+    ///
+    /// The integrity tag is computed over the Destination Connection ID of the Initial packet
+    /// the Retry is sent in response to. Returns the length of the packet.
+    pub fn encode<K: RetryKey>(
+        &self,
+        original_destination_connection_id: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<usize, Error> {
+        validate_connection_ids(self.destination_connection_id, self.source_connection_id)?;
+        validate_connection_ids(original_destination_connection_id, &[])?;
+
+        let pseudo_packet = self.pseudo_packet(original_destination_connection_id);
+        let pseudo_len = pseudo_packet.encoding_size();
+        // the tag replaces the original destination connection ID at the front of the pseudo
+        // packet
+        let prefix_len = 1 + original_destination_connection_id.len();
+        let len = (pseudo_len - prefix_len + INTEGRITY_TAG_LEN).max(pseudo_len);
+        if buffer.len() < len {
+            return Err(Error::InsufficientSpace);
+        }
+
+        EncoderBuffer::new(buffer).encode(&pseudo_packet);
+        let tag = K::generate_tag(&buffer[..pseudo_len]);
+
+        buffer.copy_within(prefix_len..pseudo_len, 0);
+        let len = pseudo_len - prefix_len;
+        buffer[len..len + INTEGRITY_TAG_LEN].copy_from_slice(&tag);
+
+        Ok(len + INTEGRITY_TAG_LEN)
+    }
+
+    /// Decodes a Retry packet from the front of the buffer
+    ///
+    /// The integrity tag isn't validated, see [`Self::validate`].
+    pub fn decode(buffer: &'a mut [u8]) -> Result<(Self, &'a mut [u8]), Error> {
+        let (packet, remaining) = decode_packet(buffer)?;
+        let packet = match packet {
+            ProtectedPacket::Retry(packet) => packet,
+            _ => return Err(Error::UnexpectedPacket),
+        };
+
+        let packet = Self {
+            version: packet.version,
+            destination_connection_id: packet.destination_connection_id,
+            source_connection_id: packet.source_connection_id,
+            retry_token: packet.retry_token,
+            integrity_tag: *packet.retry_integrity_tag,
+        };
+
+        Ok((packet, remaining))
+    }
+
+    /// Validates the integrity tag against the Destination Connection ID of the Initial packet
+    /// the Retry was sent in response to
+    #[cfg(feature = "alloc")]
+    pub fn validate<K: RetryKey>(
+        &self,
+        original_destination_connection_id: &[u8],
+    ) -> Result<(), Error> {
+        let pseudo_packet = self.pseudo_packet(original_destination_connection_id);
+        let mut buffer = alloc::vec![0; pseudo_packet.encoding_size()];
+        EncoderBuffer::new(&mut buffer).encode(&pseudo_packet);
+        K::validate(&buffer, self.integrity_tag)?;
+        Ok(())
+    }
+
+    fn pseudo_packet(&self, original_destination_connection_id: &'a [u8]) -> PseudoRetry<'a> {
+        PseudoRetry::new(
+            original_destination_connection_id,
+            //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2.5
+            //# The value in the Unused field is set to an arbitrary value
+            //# by the server; a client MUST ignore these bits.
+            // The unused bits are set like the server does, to allow matching the example
+            // packets in RFC 9001.
+            (retry_tag!() << 4) | 0x0f,
+            self.version,
+            self.destination_connection_id,
+            self.source_connection_id,
+            self.retry_token,
+        )
+    }
+}
+
+/// A Version Negotiation packet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VersionNegotiation<'a> {
+    pub destination_connection_id: &'a [u8],
+    pub source_connection_id: &'a [u8],
+    pub supported_versions: &'a [u32],
+}
+
+impl<'a> VersionNegotiation<'a> {
+    /// Encodes the packet into the buffer
+    ///
+    /// Returns the length of the packet.
+    pub fn encode(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+        validate_connection_ids(self.destination_connection_id, self.source_connection_id)?;
+
+        let packet = packet::version_negotiation::VersionNegotiation {
+            tag: 0,
+            destination_connection_id: self.destination_connection_id,
+            source_connection_id: self.source_connection_id,
+            supported_versions: SupportedVersions(self.supported_versions),
+        };
+
+        let mut estimator = EncoderLenEstimator::new(buffer.len());
+        estimator.encode(&packet);
+        if estimator.overflowed() {
+            return Err(Error::InsufficientSpace);
+        }
+
+        let mut encoder = EncoderBuffer::new(buffer);
+        encoder.encode(&packet);
+        Ok(encoder.len())
+    }
+
+    /// Decodes a Version Negotiation packet from the front of the buffer
+    ///
+    /// The supported versions are available through [`ProtectedVersionNegotiation::iter`].
+    pub fn decode(
+        buffer: &'a mut [u8],
+    ) -> Result<(ProtectedVersionNegotiation<'a>, &'a mut [u8]), Error> {
+        let (packet, remaining) = decode_packet(buffer)?;
+        match packet {
+            ProtectedPacket::VersionNegotiation(packet) => Ok((packet, remaining)),
+            _ => Err(Error::UnexpectedPacket),
+        }
+    }
+}
+
+/// Encodes the supported versions of a Version Negotiation packet
+struct SupportedVersions<'a>(&'a [u32]);
+
+impl<'a> EncoderValue for SupportedVersions<'a> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        for version in self.0 {
+            encoder.encode(version);
+        }
+    }
+}
+
+/// Pads the payload with PADDING frames to reach the minimum length
+struct Padded<'a>(&'a [u8]);
+
+impl<'a> PacketPayloadEncoder for Padded<'a> {
+    fn encoding_size_hint<E: Encoder>(&mut self, _encoder: &E, minimum_len: usize) -> usize {
+        self.0.len().max(minimum_len)
+    }
+
+    fn encode(
+        &mut self,
+        buffer: &mut EncoderBuffer,
+        minimum_len: usize,
+        _header_len: usize,
+        _tag_len: usize,
+    ) {
+        buffer.write_slice(self.0);
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-19.1
+        //# A PADDING frame (type=0x00) has no semantic value.
+        buffer.write_repeated(minimum_len.saturating_sub(self.0.len()), 0);
+    }
+}
+
+fn encode_packet<'p, K, H, P>(
+    packet: P,
+    key: &K,
+    header_key: &H,
+    min_packet_len: usize,
+    buffer: &mut [u8],
+) -> Result<usize, Error>
+where
+    K: crate::crypto::Key,
+    H: crate::crypto::HeaderKey,
+    P: PacketEncoder<K, H, Padded<'p>>,
+{
+    let capacity = buffer.len();
+    let largest_acknowledged_packet_number = largest_packet_number(packet.packet_number().space());
+    let (_protected_payload, remaining) = packet.encode_packet(
+        key,
+        header_key,
+        largest_acknowledged_packet_number,
+        Some(min_packet_len),
+        EncoderBuffer::new(buffer),
+    )?;
+    Ok(capacity - remaining.remaining_capacity())
+}
+
+fn decode_packet<'a>(buffer: &'a mut [u8]) -> Result<(ProtectedPacket<'a>, &'a mut [u8]), Error> {
+    let remote_address = SocketAddress::default();
+    let connection_info = ConnectionInfo::new(&remote_address);
+    // only long header packets are decoded, which carry the length of their connection IDs
+    let (packet, remaining) = ProtectedPacket::decode(
+        DecoderBufferMut::new(buffer),
+        &connection_info,
+        &id::MAX_LEN,
+    )?;
+    Ok((packet, remaining.into_less_safe_slice()))
+}
+
+fn packet_number(space: PacketNumberSpace, packet_number: u64) -> Result<PacketNumber, Error> {
+    let packet_number = VarInt::new(packet_number).map_err(|_| Error::InvalidPacketNumber)?;
+    Ok(space.new_packet_number(packet_number))
+}
+
+/// Packet numbers are encoded and decoded relative to the start of the packet number space
+fn largest_packet_number(space: PacketNumberSpace) -> PacketNumber {
+    space.new_packet_number(VarInt::from_u8(0))
+}
+
+fn validate_connection_ids(
+    destination_connection_id: &[u8],
+    source_connection_id: &[u8],
+) -> Result<(), Error> {
+    if destination_connection_id.len() > DESTINATION_CONNECTION_ID_MAX_LEN
+        || source_connection_id.len() > DESTINATION_CONNECTION_ID_MAX_LEN
+    {
+        return Err(Error::InvalidConnectionId);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{
+        key::testing::{HeaderKey, Key},
+        retry::example,
+    };
+
+    #[test]
+    fn initial_round_trip_test() {
+        let payload = [0x01];
+        let mut packet = Initial::new(&[1, 2, 3, 4], &[5, 6, 7, 8], &payload);
+        packet.token = &[9, 9];
+        packet.packet_number = 1234;
+        packet.min_packet_len = 1200;
+
+        let mut buffer = [0u8; 1500];
+        let len = packet
+            .encode(&Key::new(), &HeaderKey::new(), &mut buffer)
+            .unwrap();
+        assert_eq!(len, 1200);
+
+        // a coalesced handshake packet
+        let handshake = Handshake::new(&[1, 2, 3, 4], &[5, 6, 7, 8], &payload);
+        let handshake_len = handshake
+            .encode(&Key::new(), &HeaderKey::new(), &mut buffer[len..])
+            .unwrap();
+
+        let (decoded, remaining) = Initial::decode(
+            &mut buffer[..len + handshake_len],
+            &Key::new(),
+            &HeaderKey::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            decoded.destination_connection_id,
+            packet.destination_connection_id
+        );
+        assert_eq!(decoded.source_connection_id, packet.source_connection_id);
+        assert_eq!(decoded.token, packet.token);
+        assert_eq!(decoded.packet_number, packet.packet_number);
+        assert_eq!(decoded.payload[0], 0x01);
+        // the payload was padded
+        assert!(decoded.payload[1..].iter().all(|byte| *byte == 0));
+
+        assert_eq!(
+            Initial::decode(remaining, &Key::new(), &HeaderKey::new()).unwrap_err(),
+            Error::UnexpectedPacket
+        );
+
+        let (decoded, remaining) = Handshake::decode(
+            &mut buffer[len..len + handshake_len],
+            &Key::new(),
+            &HeaderKey::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            decoded,
+            Handshake {
+                payload: decoded.payload,
+                ..handshake
+            }
+        );
+        assert_eq!(decoded.payload[0], 0x01);
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn encode_errors_test() {
+        let payload = [0x01];
+        let mut buffer = [0u8; 64];
+
+        let packet = Initial::new(&[1; 21], &[], &payload);
+        assert_eq!(
+            packet.encode(&Key::new(), &HeaderKey::new(), &mut buffer),
+            Err(Error::InvalidConnectionId)
+        );
+
+        let mut packet = Initial::new(&[1; 8], &[], &payload);
+        packet.min_packet_len = 1200;
+        assert_eq!(
+            packet.encode(&Key::new(), &HeaderKey::new(), &mut buffer),
+            Err(Error::InsufficientSpace)
+        );
+
+        packet.min_packet_len = 0;
+        packet.packet_number = u64::MAX;
+        assert_eq!(
+            packet.encode(&Key::new(), &HeaderKey::new(), &mut buffer),
+            Err(Error::InvalidPacketNumber)
+        );
+
+        let packet = VersionNegotiation {
+            destination_connection_id: &[1; 8],
+            source_connection_id: &[2; 8],
+            supported_versions: &[VERSION_1; 16],
+        };
+        assert_eq!(packet.encode(&mut buffer), Err(Error::InsufficientSpace));
+    }
+
+    #[test]
+    fn retry_test() {
+        let packet = Retry::new(&example::DCID, &example::SCID, &example::TOKEN);
+
+        let mut buffer = [0u8; 64];
+        let len = packet.encode::<Key>(&example::ODCID, &mut buffer).unwrap();
+
+        // the test key computes an empty tag
+        assert_eq!(len, example::PACKET_LEN);
+        assert_eq!(
+            &buffer[..len - INTEGRITY_TAG_LEN],
+            &example::PACKET[..len - INTEGRITY_TAG_LEN]
+        );
+
+        let (decoded, remaining) = Retry::decode(&mut buffer[..len]).unwrap();
+        assert_eq!(decoded, packet);
+        assert!(remaining.is_empty());
+        #[cfg(feature = "alloc")]
+        assert!(decoded.validate::<Key>(&example::ODCID).is_ok());
+
+        assert_eq!(
+            Retry::new(&example::DCID, &example::SCID, &example::TOKEN)
+                .encode::<Key>(&example::ODCID, &mut buffer[..len - 1]),
+            Err(Error::InsufficientSpace)
+        );
+    }
+
+    #[test]
+    fn version_negotiation_test() {
+        let versions = [VERSION_1, 0xff00_001d];
+        let packet = VersionNegotiation {
+            destination_connection_id: &[1, 2, 3],
+            source_connection_id: &[4, 5, 6],
+            supported_versions: &versions,
+        };
+
+        let mut buffer = [0u8; 64];
+        let len = packet.encode(&mut buffer).unwrap();
+        assert_eq!(len, 1 + 4 + 4 + 4 + 8);
+
+        let (decoded, remaining) = VersionNegotiation::decode(&mut buffer[..len]).unwrap();
+        assert_eq!(decoded.destination_connection_id(), &[1, 2, 3]);
+        assert_eq!(decoded.source_connection_id(), &[4, 5, 6]);
+        assert!(decoded.iter().eq(versions.iter().copied()));
+        assert!(remaining.is_empty());
+
+        assert_eq!(
+            Retry::decode(&mut buffer[..len]).unwrap_err(),
+            Error::UnexpectedPacket
+        );
+    }
+}
@@ -34,12 +34,6 @@ use s2n_codec::{
 //# SHOULD set the most significant bit of this field (0x40) to 1 so that
 //# Version Negotiation packets appear to have the Fixed Bit field.
 
-macro_rules! version_negotiation_no_fixed_bit_tag {
-    () => {
-        0b1000u8..=0b1011u8
-    };
-}
-
 const ENCODING_TAG: u8 = 0b1100_0000;
 
 //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2.1
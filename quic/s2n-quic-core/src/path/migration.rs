@@ -2,13 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    event,
+    connection, event,
     event::{
         api::{Path, SocketAddress},
         IntoEvent,
     },
     inet,
 };
+use core::fmt;
 
 #[derive(Debug)]
 #[non_exhaustive]
@@ -103,6 +104,58 @@ impl IntoEvent<event::builder::ConnectionMigrationDenied> for DenyReason {
     }
 }
 
+/// The reason a connection could not be migrated to a new local address
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The connection encountered an error
+    Connection(connection::Error),
+    /// Only clients are allowed to initiate a migration
+    NotClient,
+    /// A migration can't be initiated before the handshake is confirmed
+    HandshakeNotConfirmed,
+    /// The peer disabled active migration with the `disable_active_migration` transport parameter
+    ActiveMigrationDisabled,
+    /// The peer hasn't issued any unused connection IDs for the new path
+    InsufficientConnectionIds,
+    /// The connection has reached its limit of paths
+    PathLimitExceeded,
+    /// Packets can't be sent from the requested local address
+    ///
+    /// Only the IP of the local address can change, since the endpoint keeps using the same
+    /// socket. Changing the IP requires support from the IO provider.
+    UnsupportedLocalAddress,
+}
+
+impl From<connection::Error> for Error {
+    fn from(error: connection::Error) -> Self {
+        Self::Connection(error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Connection(error) => write!(f, "{}", error),
+            Self::NotClient => write!(f, "only clients can initiate a migration"),
+            Self::HandshakeNotConfirmed => {
+                write!(f, "the handshake has not been confirmed yet")
+            }
+            Self::ActiveMigrationDisabled => write!(f, "the peer disabled active migration"),
+            Self::InsufficientConnectionIds => {
+                write!(f, "the peer has not issued enough connection IDs")
+            }
+            Self::PathLimitExceeded => write!(f, "the connection has too many paths"),
+            Self::UnsupportedLocalAddress => {
+                write!(f, "packets can't be sent from the local address")
+            }
+        }
+    }
+}
+
 /// Validates a path migration attempt from an active path to another
 pub trait Validator: 'static + Send {
     /// Called on each connection migration attempt for a connection
@@ -113,7 +166,7 @@ pub mod default {
     use super::*;
     use crate::path::remote_port_blocked;
 
-    #[derive(Debug, Default)]
+    #[derive(Clone, Debug, Default)]
     pub struct Validator;
 
     impl super::Validator for Validator {
@@ -16,6 +16,7 @@ use core::{
 use bolero_generator::*;
 
 pub mod migration;
+pub mod mtu;
 
 //= https://www.rfc-editor.org/rfc/rfc9000#section-14
 //# QUIC MUST NOT be used if the network path cannot support a
@@ -91,6 +92,11 @@ pub trait Handle: 'static + Copy + Send + fmt::Debug {
     /// Returns the local address for the given handle
     fn local_address(&self) -> LocalAddress;
 
+    /// Updates the local address for the given handle
+    ///
+    /// Handles that don't track the local address ignore the update.
+    fn set_local_address(&mut self, local_address: LocalAddress);
+
     /// Returns `true` if the two handles are equal from a network perspective
     ///
     /// This function is used to determine if a connection has migrated to another
@@ -165,6 +171,9 @@ impl Handle for RemoteAddress {
         SocketAddressV4::UNSPECIFIED.into()
     }
 
+    #[inline]
+    fn set_local_address(&mut self, _local_address: LocalAddress) {}
+
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         PartialEq::eq(&self.unmap(), &other.unmap())
@@ -211,6 +220,11 @@ impl Handle for Tuple {
         self.local_address
     }
 
+    #[inline]
+    fn set_local_address(&mut self, local_address: LocalAddress) {
+        self.local_address = local_address;
+    }
+
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         PartialEq::eq(&self.local_address.unmap(), &other.local_address.unmap())
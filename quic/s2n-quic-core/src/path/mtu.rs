@@ -0,0 +1,278 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Configures the maximum transmission unit (MTU) used on each path
+
+use crate::{
+    event::{api::SocketAddress, IntoEvent},
+    inet,
+    path::{MaxMtu, DEFAULT_MAX_MTU, IPV4_MIN_HEADER_LEN, MINIMUM_MTU, UDP_HEADER_LEN},
+    transport::parameters::ValidationError,
+};
+use core::convert::TryFrom;
+
+/// The smallest MTU that is able to carry a QUIC datagram of the minimum allowed size
+const MIN_MTU: u16 = MINIMUM_MTU + UDP_HEADER_LEN + IPV4_MIN_HEADER_LEN;
+
+/// The minimum length of the data field of a packet sent over an
+/// Ethernet is 1500 octets, thus the maximum length of an IP datagram
+/// sent over an Ethernet is 1500 octets.
+/// See https://www.rfc-editor.org/rfc/rfc894.txt
+const ETHERNET_MTU: u16 = 1500;
+
+const MTU_TOO_SMALL: ValidationError = ValidationError::new("MTU must be at least 1228 bytes");
+const MAX_MTU_TOO_SMALL: ValidationError =
+    ValidationError::new("max MTU is smaller than the minimum allowed max MTU");
+const BASE_MTU_TOO_LARGE: ValidationError =
+    ValidationError::new("base MTU must not be larger than the initial MTU");
+const INITIAL_MTU_TOO_LARGE: ValidationError =
+    ValidationError::new("initial MTU must not be larger than the max MTU");
+
+/// Information about the path that is being created
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct PathInfo<'a> {
+    /// The address of the peer
+    pub remote_address: SocketAddress<'a>,
+    /// The local address of the path
+    pub local_address: SocketAddress<'a>,
+}
+
+impl<'a> PathInfo<'a> {
+    #[doc(hidden)]
+    pub fn new(
+        remote_address: &'a inet::SocketAddress,
+        local_address: &'a inet::SocketAddress,
+    ) -> Self {
+        Self {
+            remote_address: remote_address.into_event(),
+            local_address: local_address.into_event(),
+        }
+    }
+}
+
+/// The MTU configuration for a path
+///
+/// All of the values are the size of the IP packet, including the IP and UDP headers.
+/// The values are limited by the MTU supported by the IO provider, so jumbo frames
+/// also need to be enabled on the IO provider with `with_max_mtu`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Config {
+    initial_mtu: u16,
+    base_mtu: u16,
+    max_mtu: MaxMtu,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            initial_mtu: ETHERNET_MTU,
+            base_mtu: MIN_MTU,
+            max_mtu: DEFAULT_MAX_MTU,
+        }
+    }
+}
+
+impl Config {
+    /// Returns a builder for a path MTU `Config`
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// The MTU that is probed first once the handshake has been confirmed
+    pub fn initial_mtu(&self) -> u16 {
+        self.initial_mtu
+    }
+
+    /// The MTU used until a larger MTU has been confirmed and after a
+    /// black hole has been detected
+    pub fn base_mtu(&self) -> u16 {
+        self.base_mtu
+    }
+
+    /// The largest MTU that will be probed on the path
+    pub fn max_mtu(&self) -> MaxMtu {
+        self.max_mtu
+    }
+}
+
+/// Builds a path MTU [`Config`]
+#[derive(Debug, Default)]
+pub struct Builder {
+    initial_mtu: Option<u16>,
+    base_mtu: Option<u16>,
+    max_mtu: Option<MaxMtu>,
+}
+
+impl Builder {
+    /// Sets the MTU that is probed first once the handshake has been confirmed
+    ///
+    /// Defaults to 1500, or the max MTU if it is smaller.
+    pub fn with_initial_mtu(mut self, initial_mtu: u16) -> Result<Self, ValidationError> {
+        if initial_mtu < MIN_MTU {
+            return Err(MTU_TOO_SMALL);
+        }
+        self.initial_mtu = Some(initial_mtu);
+        Ok(self)
+    }
+
+    /// Sets the MTU used until a larger MTU has been confirmed and after a
+    /// black hole has been detected
+    ///
+    /// Defaults to the smallest MTU allowed by QUIC. Setting the base MTU to the max MTU
+    /// sends all packets on the path with a fixed size and disables probing.
+    pub fn with_base_mtu(mut self, base_mtu: u16) -> Result<Self, ValidationError> {
+        if base_mtu < MIN_MTU {
+            return Err(MTU_TOO_SMALL);
+        }
+        self.base_mtu = Some(base_mtu);
+        Ok(self)
+    }
+
+    /// Sets the largest MTU that will be probed on the path
+    ///
+    /// Defaults to 1500.
+    pub fn with_max_mtu(mut self, max_mtu: u16) -> Result<Self, ValidationError> {
+        let max_mtu = MaxMtu::try_from(max_mtu).map_err(|_| MAX_MTU_TOO_SMALL)?;
+        self.max_mtu = Some(max_mtu);
+        Ok(self)
+    }
+
+    /// Builds the [`Config`], ensuring `base_mtu <= initial_mtu <= max_mtu`
+    pub fn build(self) -> Result<Config, ValidationError> {
+        let max_mtu = self.max_mtu.unwrap_or(DEFAULT_MAX_MTU);
+        let initial_mtu = self
+            .initial_mtu
+            .unwrap_or_else(|| ETHERNET_MTU.min(max_mtu.into()));
+        let base_mtu = self.base_mtu.unwrap_or(MIN_MTU);
+
+        if initial_mtu > max_mtu.into() {
+            return Err(INITIAL_MTU_TOO_LARGE);
+        }
+        if base_mtu > initial_mtu {
+            return Err(BASE_MTU_TOO_LARGE);
+        }
+
+        Ok(Config {
+            initial_mtu,
+            base_mtu,
+            max_mtu,
+        })
+    }
+}
+
+/// Configures the MTU of each path created by the endpoint
+pub trait Endpoint: 'static + Send {
+    /// Called when a new path is created
+    ///
+    /// Returning `None` uses the MTU configured on the IO provider and the connection limits.
+    ///
+    /// ```rust
+    /// # mod s2n_quic { pub mod provider { pub mod mtu { pub use s2n_quic_core::path::mtu::*; } } }
+    /// use s2n_quic::provider::mtu::{Config, Endpoint, PathInfo};
+    /// use std::net::{IpAddr, SocketAddr};
+    ///
+    /// struct Datacenter {
+    ///     jumbo: Config,
+    /// }
+    ///
+    /// impl Endpoint for Datacenter {
+    ///     fn on_path(&mut self, info: &PathInfo) -> Option<Config> {
+    ///         // use jumbo frames for peers within 10.0.0.0/8
+    ///         match SocketAddr::from(&info.remote_address).ip() {
+    ///             IpAddr::V4(ip) if ip.octets()[0] == 10 => Some(self.jumbo),
+    ///             _ => None,
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    fn on_path(&mut self, info: &PathInfo) -> Option<Config>;
+}
+
+/// Applies the same MTU configuration to every path
+impl Endpoint for Config {
+    #[inline]
+    fn on_path(&mut self, _info: &PathInfo) -> Option<Config> {
+        Some(*self)
+    }
+}
+
+pub mod default {
+    use super::*;
+
+    /// Uses the MTU configured on the IO provider and the connection limits for every path
+    #[derive(Clone, Debug, Default)]
+    pub struct Endpoint;
+
+    impl super::Endpoint for Endpoint {
+        #[inline]
+        fn on_path(&mut self, _info: &PathInfo) -> Option<Config> {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_defaults_test() {
+        let config = Config::builder().build().unwrap();
+        assert_eq!(config, Config::default());
+
+        let config = Config::builder()
+            .with_max_mtu(1300)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(config.initial_mtu(), 1300);
+        assert_eq!(config.base_mtu(), MIN_MTU);
+        assert_eq!(u16::from(config.max_mtu()), 1300);
+    }
+
+    #[test]
+    fn builder_validation_test() {
+        assert_eq!(
+            Config::builder().with_base_mtu(MIN_MTU - 1).unwrap_err(),
+            MTU_TOO_SMALL
+        );
+        assert_eq!(
+            Config::builder().with_initial_mtu(MIN_MTU - 1).unwrap_err(),
+            MTU_TOO_SMALL
+        );
+        assert!(Config::builder().with_max_mtu(MIN_MTU - 1).is_err());
+
+        assert_eq!(
+            Config::builder()
+                .with_initial_mtu(9001)
+                .unwrap()
+                .build()
+                .unwrap_err(),
+            INITIAL_MTU_TOO_LARGE
+        );
+        assert_eq!(
+            Config::builder()
+                .with_base_mtu(1400)
+                .unwrap()
+                .with_initial_mtu(1300)
+                .unwrap()
+                .build()
+                .unwrap_err(),
+            BASE_MTU_TOO_LARGE
+        );
+
+        let config = Config::builder()
+            .with_max_mtu(9001)
+            .unwrap()
+            .with_initial_mtu(9001)
+            .unwrap()
+            .with_base_mtu(1500)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(config.initial_mtu(), 9001);
+        assert_eq!(config.base_mtu(), 1500);
+        assert_eq!(u16::from(config.max_mtu()), 9001);
+    }
+}
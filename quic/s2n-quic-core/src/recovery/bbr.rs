@@ -467,6 +467,11 @@ impl CongestionController for BbrCongestionController {
     fn send_quantum(&self) -> Option<usize> {
         Some(self.pacer.send_quantum())
     }
+
+    #[inline]
+    fn bandwidth_estimate(&self) -> Option<Bandwidth> {
+        Some(self.data_rate_model.bw())
+    }
 }
 
 impl BbrCongestionController {
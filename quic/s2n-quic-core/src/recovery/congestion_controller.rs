@@ -2,11 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    event::{api::SocketAddress, IntoEvent},
+    event::{self, api::SocketAddress, IntoEvent},
     inet,
     path::MINIMUM_MTU,
     random,
-    recovery::RttEstimator,
+    recovery::{bandwidth::Bandwidth, RttEstimator},
     time::Timestamp,
 };
 use core::fmt::Debug;
@@ -36,6 +36,36 @@ impl<'a> PathInfo<'a> {
     }
 }
 
+/// The state of a congestion controller
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum State {
+    /// The congestion window grows by the number of bytes acknowledged
+    SlowStart,
+    /// The congestion window grows more slowly after HyStart++ detected an increase in the
+    /// round trip time, until slow start is resumed or exited
+    ConservativeSlowStart,
+    /// The congestion window was reduced in response to congestion
+    Recovery,
+    /// The congestion window grows according to the congestion avoidance algorithm
+    CongestionAvoidance,
+    /// The congestion window is not increased since it is not fully utilized
+    ApplicationLimited,
+}
+
+impl IntoEvent<event::builder::CongestionState> for State {
+    #[inline]
+    fn into_event(self) -> event::builder::CongestionState {
+        match self {
+            Self::SlowStart => event::builder::CongestionState::SlowStart,
+            Self::ConservativeSlowStart => event::builder::CongestionState::ConservativeSlowStart,
+            Self::Recovery => event::builder::CongestionState::Recovery,
+            Self::CongestionAvoidance => event::builder::CongestionState::CongestionAvoidance,
+            Self::ApplicationLimited => event::builder::CongestionState::ApplicationLimited,
+        }
+    }
+}
+
 pub trait CongestionController: 'static + Clone + Send + Debug {
     /// Additional metadata about a packet to track until a sent packet
     /// is either acknowledged or declared lost
@@ -55,6 +85,19 @@ pub trait CongestionController: 'static + Clone + Send + Debug {
     /// Returns `true` if the congestion controller is in the "Slow Start" state
     fn is_slow_start(&self) -> bool;
 
+    /// Returns the current state of the congestion controller
+    ///
+    /// Changes of the state are published as events. The default implementation only
+    /// distinguishes between slow start and congestion avoidance.
+    #[inline]
+    fn state(&self) -> State {
+        if self.is_slow_start() {
+            State::SlowStart
+        } else {
+            State::CongestionAvoidance
+        }
+    }
+
     /// Returns `true` if the current state of the congestion controller
     /// requires a packet to be transmitted without respecting the
     /// available congestion window
@@ -140,6 +183,16 @@ pub trait CongestionController: 'static + Clone + Send + Debug {
     fn send_quantum(&self) -> Option<usize> {
         None
     }
+
+    /// Returns the estimated bandwidth of the path
+    ///
+    /// If the value is `None`, the congestion controller does not estimate the bandwidth.
+    /// The estimate is passed to [`Pacer`](crate::recovery::pacing::Pacer)s, which may
+    /// derive their pacing rate from it.
+    #[inline]
+    fn bandwidth_estimate(&self) -> Option<Bandwidth> {
+        None
+    }
 }
 
 #[cfg(any(test, feature = "testing"))]
@@ -8,7 +8,7 @@ use crate::{
         congestion_controller::{self, CongestionController},
         cubic::{FastRetransmission::*, State::*},
         hybrid_slow_start::HybridSlowStart,
-        pacing::Pacer,
+        pacing::WindowPacer,
         RttEstimator,
     },
     time::Timestamp,
@@ -142,7 +142,7 @@ pub struct CubicCongestionController {
     //# slow start [RFC3742] or hybrid slow start [HR08] for fast and long-
     //# distance networks.
     slow_start: HybridSlowStart,
-    pacer: Pacer,
+    pacer: WindowPacer,
     max_datagram_size: u16,
     congestion_window: f32,
     state: State,
@@ -191,6 +191,19 @@ impl CongestionController for CubicCongestionController {
         matches!(self.state, SlowStart)
     }
 
+    #[inline]
+    fn state(&self) -> congestion_controller::State {
+        match self.state {
+            Recovery(_, _) => congestion_controller::State::Recovery,
+            _ if self.under_utilized => congestion_controller::State::ApplicationLimited,
+            SlowStart if self.slow_start.is_conservative() => {
+                congestion_controller::State::ConservativeSlowStart
+            }
+            SlowStart => congestion_controller::State::SlowStart,
+            CongestionAvoidance(_) => congestion_controller::State::CongestionAvoidance,
+        }
+    }
+
     #[inline]
     fn requires_fast_retransmission(&self) -> bool {
         matches!(self.state, Recovery(_, RequiresTransmission))
@@ -292,6 +305,18 @@ impl CongestionController for CubicCongestionController {
             .try_sub(bytes_acknowledged)
             .expect("bytes_acknowledged should not exceed u32::MAX");
 
+        // Check if this ack causes the controller to exit recovery. This is checked before the
+        // utilization of the congestion window, since the recovery period also needs to end
+        // while the sender is app limited.
+        if let State::Recovery(recovery_start_time, _) = self.state {
+            if newest_acked_time_sent > recovery_start_time {
+                //= https://www.rfc-editor.org/rfc/rfc9002#section-7.3.2
+                //# A recovery period ends and the sender enters congestion avoidance
+                //# when a packet sent during the recovery period is acknowledged.
+                self.state = State::congestion_avoidance(ack_receive_time)
+            }
+        };
+
         if self.under_utilized {
             self.state.on_app_limited(ack_receive_time);
 
@@ -304,16 +329,6 @@ impl CongestionController for CubicCongestionController {
             return;
         }
 
-        // Check if this ack causes the controller to exit recovery
-        if let State::Recovery(recovery_start_time, _) = self.state {
-            if newest_acked_time_sent > recovery_start_time {
-                //= https://www.rfc-editor.org/rfc/rfc9002#section-7.3.2
-                //# A recovery period ends and the sender enters congestion avoidance
-                //# when a packet sent during the recovery period is acknowledged.
-                self.state = State::congestion_avoidance(ack_receive_time)
-            }
-        };
-
         // The congestion window may continue to grow while app-limited due to pacing
         // interrupting sending while momentarily not app-limited. To avoid the congestion
         // window growing too far beyond bytes in flight, we limit the maximum cwnd to
@@ -473,7 +488,7 @@ impl CubicCongestionController {
         Self {
             cubic: Cubic::new(max_datagram_size),
             slow_start: HybridSlowStart::new(max_datagram_size),
-            pacer: Pacer::default(),
+            pacer: WindowPacer::default(),
             max_datagram_size,
             congestion_window: CubicCongestionController::initial_window(max_datagram_size) as f32,
             state: SlowStart,
@@ -836,8 +851,23 @@ impl Cubic {
 }
 
 #[non_exhaustive]
-#[derive(Debug, Default)]
-pub struct Endpoint {}
+#[derive(Clone, Debug, Default)]
+pub struct Endpoint {
+    hystart_plus_plus: Option<bool>,
+}
+
+impl Endpoint {
+    /// Sets whether HyStart++ is used to exit the initial slow start
+    ///
+    /// HyStart++ enters a Conservative Slow Start phase when it detects an increase in the
+    /// round trip time, instead of exiting slow start immediately, which avoids exiting slow
+    /// start prematurely due to jitter. By default, HyStart++ is only used if the
+    /// `S2N_UNSTABLE_USE_HYSTART_PP` environment variable is set.
+    pub fn with_hystart_plus_plus(mut self, enabled: bool) -> Self {
+        self.hystart_plus_plus = Some(enabled);
+        self
+    }
+}
 
 impl congestion_controller::Endpoint for Endpoint {
     type CongestionController = CubicCongestionController;
@@ -846,7 +876,13 @@ impl congestion_controller::Endpoint for Endpoint {
         &mut self,
         path_info: congestion_controller::PathInfo,
     ) -> Self::CongestionController {
-        CubicCongestionController::new(path_info.max_datagram_size)
+        let mut congestion_controller = CubicCongestionController::new(path_info.max_datagram_size);
+        if let Some(enabled) = self.hystart_plus_plus {
+            congestion_controller
+                .slow_start
+                .set_hystart_plus_plus(enabled);
+        }
+        congestion_controller
     }
 }
 
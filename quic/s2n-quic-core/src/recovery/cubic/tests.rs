@@ -903,6 +903,42 @@ fn on_packet_ack_recovery_to_congestion_avoidance() {
     );
 }
 
+#[test]
+//= https://www.rfc-editor.org/rfc/rfc9002#section-7.3.2
+//= type=test
+fn on_packet_ack_limited_recovery_to_congestion_avoidance() {
+    let mut cc = CubicCongestionController::new(5000);
+    let now = NoopClock.get_time();
+    let random = &mut random::testing::Generator::default();
+
+    cc.cubic.w_max = bytes_to_packets(25000.0, 5000);
+    cc.congestion_window = 25000.0;
+    cc.state = Recovery(now, Idle);
+    cc.bytes_in_flight = BytesInFlight::new(10000);
+    cc.under_utilized = true;
+
+    cc.on_ack(
+        now + Duration::from_millis(1),
+        1,
+        (),
+        &RttEstimator::default(),
+        random,
+        now + Duration::from_millis(2),
+    );
+
+    // the recovery period ends even though the sender is app limited
+    assert_eq!(
+        cc.state,
+        State::CongestionAvoidance(CongestionAvoidanceTiming {
+            start_time: now + Duration::from_millis(2),
+            window_increase_time: now + Duration::from_millis(2),
+            app_limited_time: Some(now + Duration::from_millis(2)),
+        })
+    );
+    // the congestion window doesn't grow while app limited
+    assert_delta!(cc.congestion_window, 25000.0, 0.001);
+}
+
 #[test]
 //= https://www.rfc-editor.org/rfc/rfc9002#section-7.3.2
 //= type=test
@@ -1108,3 +1144,24 @@ fn on_packet_ack_congestion_avoidance_too_large_increase() {
     assert!(cc.cubic.w_cubic(t) > cc.cubic.w_est(t, rtt));
     assert_delta!(cc.congestion_window, 3_600_000.0 + 1000.0 / 2.0, 0.001);
 }
+
+#[test]
+fn state() {
+    use congestion_controller::State;
+
+    let mut cc = CubicCongestionController::new(5000);
+    let now = NoopClock.get_time();
+
+    cc.under_utilized = false;
+    assert_eq!(cc.state(), State::SlowStart);
+
+    cc.under_utilized = true;
+    assert_eq!(cc.state(), State::ApplicationLimited);
+
+    cc.state = Recovery(now, Idle);
+    assert_eq!(cc.state(), State::Recovery);
+
+    cc.under_utilized = false;
+    cc.state = super::State::congestion_avoidance(now);
+    assert_eq!(cc.state(), State::CongestionAvoidance);
+}
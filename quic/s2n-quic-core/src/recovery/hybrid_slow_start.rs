@@ -85,6 +85,16 @@ impl HybridSlowStart {
         }
     }
 
+    /// Enables or disables HyStart++ for the initial slow start
+    pub fn set_hystart_plus_plus(&mut self, enabled: bool) {
+        self.use_hystart_plus_plus = enabled;
+    }
+
+    /// Returns `true` if HyStart++ is in the Conservative Slow Start phase
+    pub fn is_conservative(&self) -> bool {
+        self.ss_growth_divisor > 1.0
+    }
+
     /// Called each time the round trip time estimate is
     /// updated. The algorithm detects if the min RTT over
     /// a number of samples has increased since the last
@@ -518,6 +528,7 @@ mod test {
         assert_delta!(slow_start.css_threshold, 5000.0, 0.001);
         assert_eq!(slow_start.css_baseline_min_rtt, Duration::from_millis(126));
         assert_delta!(slow_start.ss_growth_divisor, 4.0, 0.001);
+        assert!(slow_start.is_conservative());
 
         // -- Round 4 --
         // t=50-59: Send packet #31-40
@@ -594,6 +605,7 @@ mod test {
         assert_delta!(slow_start.css_threshold, 5000.0, 0.001);
         assert_eq!(slow_start.css_baseline_min_rtt, Duration::from_millis(126));
         assert_delta!(slow_start.ss_growth_divisor, 4.0, 0.001);
+        assert!(slow_start.is_conservative());
 
         // -- Round 4 --
         // t=50-59: Send packet #31-40
@@ -626,5 +638,6 @@ mod test {
         assert_delta!(slow_start.ss_growth_divisor, 1.0, 0.001);
         assert_delta!(slow_start.css_threshold, f32::MAX, 0.001);
         assert_eq!(slow_start.css_count, 0);
+        assert!(!slow_start.is_conservative());
     }
 }
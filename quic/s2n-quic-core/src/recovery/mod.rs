@@ -11,9 +11,10 @@ pub mod bbr;
 pub mod congestion_controller;
 pub mod cubic;
 mod hybrid_slow_start;
-mod pacing;
+pub mod pacing;
 mod rtt_estimator;
 mod sent_packets;
+pub mod slo;
 
 //= https://www.rfc-editor.org/rfc/rfc9002#section-7.7
 //# Senders SHOULD limit bursts to the initial congestion window; see
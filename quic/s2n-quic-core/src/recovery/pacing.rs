@@ -1,14 +1,360 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
+//! Pacing spreads the packets of a path over time instead of sending them in bursts
+//!
+//! Congestion controllers pace their packets themselves. [`Paced`] replaces the pacing of a
+//! congestion controller with a [`Pacer`], which allows combining the congestion window of one
+//! algorithm with the pacing of another, or disabling pacing entirely with [`Disabled`].
+
 use crate::{
     counter::{Counter, Saturating},
-    recovery::{RttEstimator, MAX_BURST_PACKETS},
+    random,
+    recovery::{
+        bandwidth::Bandwidth,
+        congestion_controller::{self, CongestionController, PathInfo, State},
+        RttEstimator, MAX_BURST_PACKETS,
+    },
     time::{Duration, Timestamp},
 };
-use core::ops::Div;
+use core::{fmt::Debug, ops::Div};
 use num_rational::Ratio;
 
+/// Creates a [`Pacer`] for each path
+pub trait Endpoint: 'static + Debug + Send {
+    type Pacer: Pacer;
+
+    /// Called when a path is created to return the pacer for the path
+    fn new_pacer(&mut self, path_info: &PathInfo) -> Self::Pacer;
+}
+
+/// Information about the path which is passed to a [`Pacer`] for each sent packet
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug)]
+pub struct Info<'a> {
+    /// The round trip time estimates of the path
+    pub rtt_estimator: &'a RttEstimator,
+    /// The congestion window of the congestion controller, in bytes
+    pub congestion_window: u32,
+    /// The bytes in flight, including the packet which was just sent
+    pub bytes_in_flight: u32,
+    /// The maximum size of a datagram on the path
+    pub max_datagram_size: u16,
+    /// The state of the congestion controller
+    pub state: State,
+    /// The bandwidth estimate of the congestion controller, if it estimates the bandwidth
+    pub bandwidth_estimate: Option<Bandwidth>,
+}
+
+impl<'a> Info<'a> {
+    #[inline]
+    #[doc(hidden)]
+    pub fn new<CC: CongestionController>(
+        congestion_controller: &CC,
+        rtt_estimator: &'a RttEstimator,
+        max_datagram_size: u16,
+    ) -> Self {
+        Self {
+            rtt_estimator,
+            congestion_window: congestion_controller.congestion_window(),
+            bytes_in_flight: congestion_controller.bytes_in_flight(),
+            max_datagram_size,
+            state: congestion_controller.state(),
+            bandwidth_estimate: congestion_controller.bandwidth_estimate(),
+        }
+    }
+}
+
+/// Decides when the packets of a path may be transmitted
+///
+/// ```rust
+/// # mod s2n_quic { pub mod provider { pub mod congestion_controller { pub use s2n_quic_core::recovery::pacing; } } }
+/// use core::time::Duration;
+/// use s2n_quic::provider::congestion_controller::pacing::{Info, Pacer};
+/// use s2n_quic_core::time::Timestamp;
+///
+/// /// Sends packets at twice the bandwidth estimate of the congestion controller
+/// #[derive(Clone, Debug, Default)]
+/// struct DoubleBandwidth {
+///     next_departure_time: Option<Timestamp>,
+/// }
+///
+/// impl Pacer for DoubleBandwidth {
+///     fn on_packet_sent(&mut self, time_sent: Timestamp, bytes_sent: usize, info: &Info) {
+///         self.next_departure_time = info.bandwidth_estimate.map(|bandwidth| {
+///             time_sent + (bytes_sent as u64 / bandwidth) / 2
+///         });
+///     }
+///
+///     fn earliest_departure_time(&self) -> Option<Timestamp> {
+///         self.next_departure_time
+///     }
+/// }
+/// ```
+pub trait Pacer: 'static + Clone + Send + Debug {
+    /// Invoked when a packet is sent
+    ///
+    /// `info` reflects the state of the path after the congestion controller was notified
+    /// of the packet. Pure ACK packets are not paced, so this is not invoked for them.
+    fn on_packet_sent(&mut self, time_sent: Timestamp, bytes_sent: usize, info: &Info);
+
+    /// Returns the earliest time that a packet may be transmitted.
+    ///
+    /// If the time is in the past or is `None`, the packet should be transmitted immediately.
+    fn earliest_departure_time(&self) -> Option<Timestamp>;
+
+    /// The maximum number of bytes for an aggregation of packets scheduled and transmitted together.
+    ///
+    /// If the value is `None`, the send quantum of the congestion controller is used.
+    #[inline]
+    fn send_quantum(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Disables pacing, so packets are transmitted as soon as the congestion window allows
+///
+/// This is useful on links with a very low round trip time, such as within a datacenter,
+/// where waking up from a timer takes longer than delivering a packet.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Disabled;
+
+impl Endpoint for Disabled {
+    type Pacer = Self;
+
+    #[inline]
+    fn new_pacer(&mut self, _path_info: &PathInfo) -> Self::Pacer {
+        *self
+    }
+}
+
+impl Pacer for Disabled {
+    #[inline]
+    fn on_packet_sent(&mut self, _time_sent: Timestamp, _bytes_sent: usize, _info: &Info) {}
+
+    #[inline]
+    fn earliest_departure_time(&self) -> Option<Timestamp> {
+        None
+    }
+}
+
+/// Creates congestion controllers which are paced by a [`Pacer`] instead of pacing themselves
+///
+/// ```rust
+/// # mod s2n_quic { pub mod provider { pub mod congestion_controller { pub use s2n_quic_core::recovery::{bbr::Endpoint as Bbr, pacing}; } } }
+/// use s2n_quic::provider::congestion_controller::{pacing, Bbr};
+///
+/// let congestion_controller = pacing::Paced::new(Bbr::default(), pacing::Disabled);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Paced<C, P> {
+    congestion_controller: C,
+    pacer: P,
+}
+
+impl<C, P> Paced<C, P> {
+    /// Creates congestion controllers from `congestion_controller`, which are paced by
+    /// the pacers created by `pacer`
+    pub fn new(congestion_controller: C, pacer: P) -> Self {
+        Self {
+            congestion_controller,
+            pacer,
+        }
+    }
+}
+
+impl<C: congestion_controller::Endpoint, P: Endpoint> congestion_controller::Endpoint
+    for Paced<C, P>
+{
+    type CongestionController = PacedCongestionController<C::CongestionController, P::Pacer>;
+
+    #[inline]
+    fn new_congestion_controller(&mut self, path_info: PathInfo) -> Self::CongestionController {
+        let max_datagram_size = path_info.max_datagram_size;
+        let pacer = self.pacer.new_pacer(&path_info);
+        let congestion_controller = self
+            .congestion_controller
+            .new_congestion_controller(path_info);
+        PacedCongestionController::new(congestion_controller, pacer, max_datagram_size)
+    }
+}
+
+/// A congestion controller which uses a [`Pacer`] instead of its own pacing
+#[derive(Clone, Debug)]
+pub struct PacedCongestionController<C, P> {
+    congestion_controller: C,
+    pacer: P,
+    max_datagram_size: u16,
+}
+
+impl<C: CongestionController, P: Pacer> PacedCongestionController<C, P> {
+    /// Wraps the congestion controller of a path with a maximum datagram size of
+    /// `max_datagram_size`
+    pub fn new(congestion_controller: C, pacer: P, max_datagram_size: u16) -> Self {
+        Self {
+            congestion_controller,
+            pacer,
+            max_datagram_size,
+        }
+    }
+
+    /// Returns the wrapped congestion controller
+    pub fn congestion_controller(&self) -> &C {
+        &self.congestion_controller
+    }
+
+    /// Returns the pacer
+    pub fn pacer(&self) -> &P {
+        &self.pacer
+    }
+}
+
+impl<C: CongestionController, P: Pacer> CongestionController for PacedCongestionController<C, P> {
+    type PacketInfo = C::PacketInfo;
+
+    #[inline]
+    fn congestion_window(&self) -> u32 {
+        self.congestion_controller.congestion_window()
+    }
+
+    #[inline]
+    fn bytes_in_flight(&self) -> u32 {
+        self.congestion_controller.bytes_in_flight()
+    }
+
+    #[inline]
+    fn is_congestion_limited(&self) -> bool {
+        self.congestion_controller.is_congestion_limited()
+    }
+
+    #[inline]
+    fn is_slow_start(&self) -> bool {
+        self.congestion_controller.is_slow_start()
+    }
+
+    #[inline]
+    fn state(&self) -> State {
+        self.congestion_controller.state()
+    }
+
+    #[inline]
+    fn requires_fast_retransmission(&self) -> bool {
+        self.congestion_controller.requires_fast_retransmission()
+    }
+
+    #[inline]
+    fn on_packet_sent(
+        &mut self,
+        time_sent: Timestamp,
+        sent_bytes: usize,
+        app_limited: Option<bool>,
+        rtt_estimator: &RttEstimator,
+    ) -> Self::PacketInfo {
+        let packet_info = self.congestion_controller.on_packet_sent(
+            time_sent,
+            sent_bytes,
+            app_limited,
+            rtt_estimator,
+        );
+
+        if sent_bytes > 0 {
+            let info = Info::new(
+                &self.congestion_controller,
+                rtt_estimator,
+                self.max_datagram_size,
+            );
+            self.pacer.on_packet_sent(time_sent, sent_bytes, &info);
+        }
+
+        packet_info
+    }
+
+    #[inline]
+    fn on_rtt_update(
+        &mut self,
+        time_sent: Timestamp,
+        now: Timestamp,
+        rtt_estimator: &RttEstimator,
+    ) {
+        self.congestion_controller
+            .on_rtt_update(time_sent, now, rtt_estimator)
+    }
+
+    #[inline]
+    fn on_ack(
+        &mut self,
+        newest_acked_time_sent: Timestamp,
+        bytes_acknowledged: usize,
+        newest_acked_packet_info: Self::PacketInfo,
+        rtt_estimator: &RttEstimator,
+        random_generator: &mut dyn random::Generator,
+        ack_receive_time: Timestamp,
+    ) {
+        self.congestion_controller.on_ack(
+            newest_acked_time_sent,
+            bytes_acknowledged,
+            newest_acked_packet_info,
+            rtt_estimator,
+            random_generator,
+            ack_receive_time,
+        )
+    }
+
+    #[inline]
+    fn on_packet_lost(
+        &mut self,
+        lost_bytes: u32,
+        packet_info: Self::PacketInfo,
+        persistent_congestion: bool,
+        new_loss_burst: bool,
+        random_generator: &mut dyn random::Generator,
+        timestamp: Timestamp,
+    ) {
+        self.congestion_controller.on_packet_lost(
+            lost_bytes,
+            packet_info,
+            persistent_congestion,
+            new_loss_burst,
+            random_generator,
+            timestamp,
+        )
+    }
+
+    #[inline]
+    fn on_explicit_congestion(&mut self, ce_count: u64, event_time: Timestamp) {
+        self.congestion_controller
+            .on_explicit_congestion(ce_count, event_time)
+    }
+
+    #[inline]
+    fn on_mtu_update(&mut self, max_data_size: u16) {
+        self.max_datagram_size = max_data_size;
+        self.congestion_controller.on_mtu_update(max_data_size)
+    }
+
+    #[inline]
+    fn on_packet_discarded(&mut self, bytes_sent: usize) {
+        self.congestion_controller.on_packet_discarded(bytes_sent)
+    }
+
+    #[inline]
+    fn earliest_departure_time(&self) -> Option<Timestamp> {
+        self.pacer.earliest_departure_time()
+    }
+
+    #[inline]
+    fn send_quantum(&self) -> Option<usize> {
+        self.pacer
+            .send_quantum()
+            .or_else(|| self.congestion_controller.send_quantum())
+    }
+
+    #[inline]
+    fn bandwidth_estimate(&self) -> Option<Bandwidth> {
+        self.congestion_controller.bandwidth_estimate()
+    }
+}
+
 struct PacingGain(Ratio<u32>);
 
 impl Div<PacingGain> for Duration {
@@ -35,22 +381,24 @@ const SLOW_START_N: PacingGain = PacingGain(Ratio::new_raw(2, 1)); // 2/1 = 2.00
 // value to a Duration greater than zero will introduce that delay into the second packet.
 // See https://www.ietf.org/proceedings/88/slides/slides-88-tsvarea-10.pdf
 // TODO: Determine an appropriate value for this that balances improvements to 2nd packet loss and delay
-pub const INITIAL_INTERVAL: Duration = Duration::from_millis(0);
+pub(crate) const INITIAL_INTERVAL: Duration = Duration::from_millis(0);
 
 /// low RTT networks should not be using pacing since it'll take longer to wake up from
 /// a timer than it would to deliver a packet
-pub const MINIMUM_PACING_RTT: Duration = Duration::from_millis(2);
+pub(crate) const MINIMUM_PACING_RTT: Duration = Duration::from_millis(2);
 
 /// A packet pacer that returns departure times that evenly distribute bursts of packets over time
+///
+/// The pacing rate is derived from the congestion window and the smoothed round trip time.
 #[derive(Clone, Debug, Default)]
-pub struct Pacer {
+pub(crate) struct WindowPacer {
     // The capacity of the current departure time slot
     capacity: Counter<u32, Saturating>,
     // The time the next packet should be transmitted
     next_packet_departure_time: Option<Timestamp>,
 }
 
-impl Pacer {
+impl WindowPacer {
     /// Called when each packet has been written
     #[inline]
     pub fn on_packet_sent(
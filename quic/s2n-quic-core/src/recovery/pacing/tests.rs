@@ -2,10 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    inet,
     packet::number::PacketNumberSpace,
     path::MINIMUM_MTU,
     recovery::{
-        pacing::{Pacer, INITIAL_INTERVAL, N, SLOW_START_N},
+        bandwidth::Bandwidth,
+        bbr,
+        congestion_controller::{CongestionController, Endpoint as _, PathInfo},
+        cubic,
+        pacing::{self, *},
         RttEstimator,
     },
     time::{Clock, NoopClock, Timestamp},
@@ -15,7 +20,7 @@ use num_traits::ToPrimitive;
 
 #[test]
 fn earliest_departure_time() {
-    let mut pacer = Pacer::default();
+    let mut pacer = WindowPacer::default();
     assert_eq!(None, pacer.next_packet_departure_time);
 
     let now = NoopClock.get_time();
@@ -33,7 +38,7 @@ fn earliest_departure_time() {
 
 #[test]
 fn on_packet_sent_large_bytes_sent() {
-    let mut pacer = Pacer::default();
+    let mut pacer = WindowPacer::default();
 
     let now = NoopClock.get_time();
     let rtt = RttEstimator::default();
@@ -58,7 +63,7 @@ fn post_slow_start() {
 }
 
 fn test_one_rtt(slow_start: bool) {
-    let mut pacer = Pacer::default();
+    let mut pacer = WindowPacer::default();
     let now = NoopClock.get_time();
     let rtt = RttEstimator::default();
 
@@ -112,7 +117,7 @@ fn test_one_rtt(slow_start: bool) {
 
 #[test]
 fn earliest_departure_time_before_now() {
-    let mut pacer = Pacer::default();
+    let mut pacer = WindowPacer::default();
     let now = NoopClock.get_time();
     let rtt = RttEstimator::default();
 
@@ -138,7 +143,7 @@ fn earliest_departure_time_before_now() {
 
 #[test]
 fn interval_change() {
-    let mut pacer = Pacer::default();
+    let mut pacer = WindowPacer::default();
     let now = NoopClock.get_time();
     let mut rtt = RttEstimator::default();
 
@@ -192,7 +197,7 @@ fn interval_change() {
 // between the new earliest departure time and the original earliest departure time
 fn get_interval(
     now: Timestamp,
-    pacer: &mut Pacer,
+    pacer: &mut WindowPacer,
     rtt_estimator: &RttEstimator,
     congestion_window: u32,
     max_datagram_size: u16,
@@ -216,3 +221,96 @@ fn get_interval(
         }
     }
 }
+
+#[test]
+fn disabled_pacing() {
+    let remote_address = inet::SocketAddress::default();
+    let mut endpoint = Paced::new(cubic::Endpoint::default(), Disabled);
+    let mut congestion_controller =
+        endpoint.new_congestion_controller(PathInfo::new(&remote_address));
+
+    let now = NoopClock.get_time();
+    let rtt = RttEstimator::default();
+    for _ in 0..20 {
+        congestion_controller.on_packet_sent(now, MINIMUM_MTU as usize, Some(false), &rtt);
+    }
+
+    // the wrapped congestion controller still paces its packets, but it is ignored
+    assert!(congestion_controller
+        .congestion_controller()
+        .earliest_departure_time()
+        .is_some());
+    assert_eq!(None, congestion_controller.earliest_departure_time());
+    assert_eq!(
+        MINIMUM_MTU as u32 * 20,
+        congestion_controller.bytes_in_flight()
+    );
+}
+
+#[test]
+fn custom_pacing() {
+    #[derive(Clone, Debug, Default)]
+    struct Recorder {
+        packets: u32,
+        max_datagram_size: u16,
+        bytes_in_flight: u32,
+        bandwidth_estimate: Option<Bandwidth>,
+        departure_time: Option<Timestamp>,
+    }
+
+    impl pacing::Endpoint for Recorder {
+        type Pacer = Self;
+
+        fn new_pacer(&mut self, _path_info: &PathInfo) -> Self::Pacer {
+            Self::default()
+        }
+    }
+
+    impl Pacer for Recorder {
+        fn on_packet_sent(&mut self, time_sent: Timestamp, _bytes_sent: usize, info: &Info) {
+            self.packets += 1;
+            self.max_datagram_size = info.max_datagram_size;
+            self.bytes_in_flight = info.bytes_in_flight;
+            self.bandwidth_estimate = info.bandwidth_estimate;
+            self.departure_time = Some(time_sent + Duration::from_millis(1));
+        }
+
+        fn earliest_departure_time(&self) -> Option<Timestamp> {
+            self.departure_time
+        }
+
+        fn send_quantum(&self) -> Option<usize> {
+            Some(1234)
+        }
+    }
+
+    let remote_address = inet::SocketAddress::default();
+    let mut endpoint = Paced::new(bbr::Endpoint::default(), Recorder::default());
+    let mut congestion_controller =
+        endpoint.new_congestion_controller(PathInfo::new(&remote_address));
+
+    let now = NoopClock.get_time();
+    let rtt = RttEstimator::default();
+    congestion_controller.on_packet_sent(now, MINIMUM_MTU as usize, Some(false), &rtt);
+    // pure ACK packets are not paced
+    congestion_controller.on_packet_sent(now, 0, Some(false), &rtt);
+    congestion_controller.on_mtu_update(1400);
+    congestion_controller.on_packet_sent(now, 1400, Some(false), &rtt);
+
+    let pacer = congestion_controller.pacer();
+    assert_eq!(2, pacer.packets);
+    assert_eq!(1400, pacer.max_datagram_size);
+    assert_eq!(MINIMUM_MTU as u32 + 1400, pacer.bytes_in_flight);
+    assert_eq!(
+        congestion_controller
+            .congestion_controller()
+            .bandwidth_estimate(),
+        pacer.bandwidth_estimate
+    );
+    assert!(pacer.bandwidth_estimate.is_some());
+    assert_eq!(
+        Some(now + Duration::from_millis(1)),
+        congestion_controller.earliest_departure_time()
+    );
+    assert_eq!(Some(1234), congestion_controller.send_quantum());
+}
@@ -43,6 +43,8 @@ pub struct RttEstimator {
     max_ack_delay: Duration,
     /// The time that the first RTT sample was obtained
     first_rtt_sample: Option<Timestamp>,
+    /// The RTT assumed before any samples are available
+    initial_rtt: Duration,
 }
 
 impl Default for RttEstimator {
@@ -54,6 +56,12 @@ impl Default for RttEstimator {
 impl RttEstimator {
     /// Creates a new RTT Estimator with default initial values using the given `max_ack_delay`.
     pub fn new(max_ack_delay: Duration) -> Self {
+        Self::new_with_initial_rtt(max_ack_delay, DEFAULT_INITIAL_RTT)
+    }
+
+    /// Creates a new RTT Estimator which assumes the given `initial_rtt` until the first RTT
+    /// sample is taken
+    pub fn new_with_initial_rtt(max_ack_delay: Duration, initial_rtt: Duration) -> Self {
         //= https://www.rfc-editor.org/rfc/rfc9002#section-5.3
         //# Before any RTT samples are available for a new path or when the
         //# estimator is reset, the estimator is initialized using the initial RTT;
@@ -64,19 +72,26 @@ impl RttEstimator {
         //
         //# smoothed_rtt = kInitialRtt
         //# rttvar = kInitialRtt / 2
-        let smoothed_rtt = DEFAULT_INITIAL_RTT;
-        let rttvar = DEFAULT_INITIAL_RTT / 2;
+        let smoothed_rtt = initial_rtt;
+        let rttvar = initial_rtt / 2;
 
         Self {
-            latest_rtt: DEFAULT_INITIAL_RTT,
-            min_rtt: DEFAULT_INITIAL_RTT,
+            latest_rtt: initial_rtt,
+            min_rtt: initial_rtt,
             smoothed_rtt,
             rttvar,
             max_ack_delay,
             first_rtt_sample: None,
+            initial_rtt,
         }
     }
 
+    /// Creates a new RTT Estimator with initial values for a new path, using the same
+    /// `max_ack_delay` and initial RTT as this estimator
+    pub fn for_new_path(&self) -> Self {
+        Self::new_with_initial_rtt(self.max_ack_delay, self.initial_rtt)
+    }
+
     /// Gets the latest round trip time sample
     #[inline]
     pub fn latest_rtt(&self) -> Duration {
@@ -323,6 +338,36 @@ mod test {
         );
     }
 
+    /// Test a configured initial RTT is used before any RTT samples
+    #[test]
+    fn configured_initial_rtt() {
+        let initial_rtt = Duration::from_millis(100);
+        let mut rtt_estimator =
+            RttEstimator::new_with_initial_rtt(Duration::from_millis(10), initial_rtt);
+        assert_eq!(rtt_estimator.min_rtt, initial_rtt);
+        assert_eq!(rtt_estimator.smoothed_rtt(), initial_rtt);
+        assert_eq!(rtt_estimator.rttvar(), initial_rtt / 2);
+        assert_eq!(
+            rtt_estimator.pto_period(INITIAL_PTO_BACKOFF, PacketNumberSpace::Initial),
+            Duration::from_millis(300)
+        );
+
+        rtt_estimator.update_rtt(
+            Duration::from_millis(0),
+            Duration::from_millis(500),
+            NoopClock.get_time(),
+            false,
+            PacketNumberSpace::Initial,
+        );
+        assert_ne!(rtt_estimator.smoothed_rtt(), initial_rtt);
+
+        // new paths start over with the configured initial RTT
+        let new_path = rtt_estimator.for_new_path();
+        assert_eq!(new_path.smoothed_rtt(), initial_rtt);
+        assert_eq!(new_path.max_ack_delay(), Duration::from_millis(10));
+        assert_eq!(new_path.first_rtt_sample(), None);
+    }
+
     /// Test a zero RTT value is treated as 1 ms
     #[test]
     fn zero_rtt_sample() {
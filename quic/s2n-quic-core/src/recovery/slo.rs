@@ -0,0 +1,275 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Service level objectives for the round trip time and the loss rate of a connection
+//!
+//! Thresholds are registered with the connection [`Limits`](crate::connection::Limits). The
+//! [`Monitor`] reports each time a threshold is breached and each time it is cleared again, which
+//! allows applications to steer traffic to other connections or to raise alerts without
+//! evaluating every RTT sample and packet loss themselves.
+
+use crate::time::{Duration, Timestamp};
+
+/// A threshold for the smoothed round trip time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RttThreshold {
+    /// The smoothed RTT which must not be exceeded
+    pub max_rtt: Duration,
+    /// How long the smoothed RTT needs to exceed `max_rtt` before the threshold is breached
+    pub duration: Duration,
+}
+
+/// A threshold for the fraction of packets which are declared lost
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LossThreshold {
+    /// The fraction of packets which may be lost, from 0 to 1
+    pub max_loss_rate: f32,
+    /// The period over which the loss rate is measured
+    pub period: Duration,
+}
+
+/// The thresholds which are monitored on a connection
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Thresholds {
+    pub rtt: Option<RttThreshold>,
+    pub loss: Option<LossThreshold>,
+}
+
+impl Thresholds {
+    /// No thresholds are monitored by default
+    pub const fn new() -> Self {
+        Self {
+            rtt: None,
+            loss: None,
+        }
+    }
+}
+
+/// A change of the state of a monitored threshold
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Update {
+    /// The smoothed round trip time threshold was breached or cleared
+    RoundTripTime {
+        breached: bool,
+        smoothed_rtt: Duration,
+    },
+    /// The loss rate threshold was breached or cleared during the last period
+    LossRate {
+        breached: bool,
+        lost_packets: u64,
+        total_packets: u64,
+    },
+}
+
+/// Evaluates the thresholds of a connection
+#[derive(Clone, Debug, Default)]
+pub struct Monitor {
+    thresholds: Thresholds,
+    /// The time since which the smoothed RTT exceeds the threshold
+    rtt_exceeded_since: Option<Timestamp>,
+    rtt_breached: bool,
+    /// The start of the current loss period
+    loss_period_start: Option<Timestamp>,
+    acked_packets: u64,
+    lost_packets: u64,
+    loss_breached: bool,
+}
+
+impl Monitor {
+    pub fn new(thresholds: Thresholds) -> Self {
+        Self {
+            thresholds,
+            ..Default::default()
+        }
+    }
+
+    /// Called each time the smoothed round trip time is updated
+    ///
+    /// Returns an update if the RTT threshold was breached or cleared.
+    pub fn on_rtt_update(&mut self, smoothed_rtt: Duration, now: Timestamp) -> Option<Update> {
+        let threshold = self.thresholds.rtt?;
+
+        if smoothed_rtt > threshold.max_rtt {
+            let exceeded_since = *self.rtt_exceeded_since.get_or_insert(now);
+            if self.rtt_breached || now - exceeded_since < threshold.duration {
+                return None;
+            }
+            self.rtt_breached = true;
+        } else {
+            self.rtt_exceeded_since = None;
+            if !self.rtt_breached {
+                return None;
+            }
+            self.rtt_breached = false;
+        }
+
+        Some(Update::RoundTripTime {
+            breached: self.rtt_breached,
+            smoothed_rtt,
+        })
+    }
+
+    /// Called when packets are acknowledged or declared lost
+    ///
+    /// The loss rate is evaluated once the current period elapsed. Returns an update if the
+    /// loss rate threshold was breached or cleared.
+    pub fn on_packets(
+        &mut self,
+        acked_packets: u64,
+        lost_packets: u64,
+        now: Timestamp,
+    ) -> Option<Update> {
+        let threshold = self.thresholds.loss?;
+
+        self.acked_packets += acked_packets;
+        self.lost_packets += lost_packets;
+
+        let period_start = *self.loss_period_start.get_or_insert(now);
+        if now - period_start < threshold.period {
+            return None;
+        }
+
+        let lost_packets = core::mem::take(&mut self.lost_packets);
+        let total_packets = core::mem::take(&mut self.acked_packets) + lost_packets;
+        self.loss_period_start = Some(now);
+
+        if total_packets == 0 {
+            return None;
+        }
+
+        let breached = lost_packets as f32 / total_packets as f32 > threshold.max_loss_rate;
+        if breached == self.loss_breached {
+            return None;
+        }
+        self.loss_breached = breached;
+
+        Some(Update::LossRate {
+            breached,
+            lost_packets,
+            total_packets,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::{Clock, NoopClock};
+
+    #[test]
+    fn rtt_threshold_test() {
+        let mut monitor = Monitor::new(Thresholds {
+            rtt: Some(RttThreshold {
+                max_rtt: Duration::from_millis(300),
+                duration: Duration::from_secs(5),
+            }),
+            loss: None,
+        });
+        let now = NoopClock.get_time();
+        let high = Duration::from_millis(400);
+        let low = Duration::from_millis(100);
+
+        assert_eq!(monitor.on_rtt_update(low, now), None);
+        assert_eq!(monitor.on_rtt_update(high, now), None);
+        assert_eq!(
+            monitor.on_rtt_update(high, now + Duration::from_secs(4)),
+            None
+        );
+
+        // the RTT needs to exceed the threshold continuously
+        assert_eq!(
+            monitor.on_rtt_update(low, now + Duration::from_secs(4)),
+            None
+        );
+        let now = now + Duration::from_secs(5);
+        assert_eq!(monitor.on_rtt_update(high, now), None);
+
+        let now = now + Duration::from_secs(5);
+        assert_eq!(
+            monitor.on_rtt_update(high, now),
+            Some(Update::RoundTripTime {
+                breached: true,
+                smoothed_rtt: high
+            })
+        );
+        // a breach is only reported once
+        assert_eq!(
+            monitor.on_rtt_update(high, now + Duration::from_secs(10)),
+            None
+        );
+
+        assert_eq!(
+            monitor.on_rtt_update(low, now + Duration::from_secs(11)),
+            Some(Update::RoundTripTime {
+                breached: false,
+                smoothed_rtt: low
+            })
+        );
+        assert_eq!(
+            monitor.on_rtt_update(low, now + Duration::from_secs(12)),
+            None
+        );
+    }
+
+    #[test]
+    fn loss_threshold_test() {
+        let period = Duration::from_secs(1);
+        let mut monitor = Monitor::new(Thresholds {
+            rtt: None,
+            loss: Some(LossThreshold {
+                max_loss_rate: 0.02,
+                period,
+            }),
+        });
+        let now = NoopClock.get_time();
+
+        // 2% loss doesn't breach the threshold
+        assert_eq!(monitor.on_packets(0, 0, now), None);
+        assert_eq!(monitor.on_packets(98, 2, now), None);
+        let now = now + period;
+        assert_eq!(monitor.on_packets(0, 0, now), None);
+
+        // 3% loss does
+        assert_eq!(monitor.on_packets(97, 3, now), None);
+        let now = now + period;
+        assert_eq!(
+            monitor.on_packets(0, 0, now),
+            Some(Update::LossRate {
+                breached: true,
+                lost_packets: 3,
+                total_packets: 100
+            })
+        );
+
+        // the breach is only reported once
+        assert_eq!(monitor.on_packets(50, 4, now), None);
+        let now = now + period;
+        assert_eq!(monitor.on_packets(46, 0, now), None);
+
+        assert_eq!(monitor.on_packets(100, 0, now), None);
+        let now = now + period;
+        assert_eq!(
+            monitor.on_packets(0, 0, now),
+            Some(Update::LossRate {
+                breached: false,
+                lost_packets: 0,
+                total_packets: 100
+            })
+        );
+    }
+
+    #[test]
+    fn disabled_test() {
+        let mut monitor = Monitor::default();
+        let now = NoopClock.get_time();
+        assert_eq!(monitor.on_rtt_update(Duration::from_secs(10), now), None);
+        assert_eq!(
+            monitor.on_rtt_update(Duration::from_secs(10), now + Duration::from_secs(10)),
+            None
+        );
+        assert_eq!(
+            monitor.on_packets(0, 100, now + Duration::from_secs(10)),
+            None
+        );
+    }
+}
@@ -58,6 +58,13 @@ pub enum StreamError {
     SendingBlocked {
         source: &'static panic::Location<'static>,
     },
+    /// The stream did not have any data available for reading
+    ///
+    /// This is caused by trying to receive data before polling readiness
+    #[non_exhaustive]
+    ReceivingBlocked {
+        source: &'static panic::Location<'static>,
+    },
     /// The stream was provided a non-empty placeholder buffer for receiving data.
     ///
     /// The application should ensure only empty buffers are provided to receive calls,
@@ -94,6 +101,7 @@ impl fmt::Display for StreamError {
             Self::NonReadable { .. } => write!(f, "The stream is not readable"),
             Self::NonWritable { .. } => write!(f, "The stream is not writable"),
             Self::SendingBlocked { .. } => write!(f, "The stream is blocked on writing data"),
+            Self::ReceivingBlocked { .. } => write!(f, "The stream is blocked on reading data"),
             Self::NonEmptyOutput { .. } => write!(
                 f,
                 "The stream was provided a non-empty placeholder buffer for receiving data."
@@ -114,6 +122,7 @@ impl StreamError {
             StreamError::NonReadable { source } => source,
             StreamError::NonWritable { source } => source,
             StreamError::SendingBlocked { source } => source,
+            StreamError::ReceivingBlocked { source } => source,
             StreamError::NonEmptyOutput { source } => source,
         }
     }
@@ -174,6 +183,14 @@ impl StreamError {
         StreamError::SendingBlocked { source }
     }
 
+    #[track_caller]
+    #[inline]
+    #[doc(hidden)]
+    pub fn receiving_blocked() -> StreamError {
+        let source = panic::Location::caller();
+        StreamError::ReceivingBlocked { source }
+    }
+
     #[track_caller]
     #[inline]
     #[doc(hidden)]
@@ -185,10 +202,10 @@ impl StreamError {
 
 impl application::error::TryInto for StreamError {
     fn application_error(&self) -> Option<application::Error> {
-        if let StreamError::ConnectionError { error, .. } = self {
-            error.application_error()
-        } else {
-            None
+        match self {
+            StreamError::StreamReset { error, .. } => Some(*error),
+            StreamError::ConnectionError { error, .. } => error.application_error(),
+            _ => None,
         }
     }
 }
@@ -236,6 +253,7 @@ impl From<StreamError> for std::io::ErrorKind {
             StreamError::NonReadable { .. } => ErrorKind::Other,
             StreamError::NonWritable { .. } => ErrorKind::Other,
             StreamError::SendingBlocked { .. } => ErrorKind::WouldBlock,
+            StreamError::ReceivingBlocked { .. } => ErrorKind::WouldBlock,
             StreamError::NonEmptyOutput { .. } => ErrorKind::InvalidInput,
         }
     }
@@ -113,3 +113,96 @@ impl From<InitialMaxStreamsBidi> for LocalBidirectional {
         Self(value.as_varint())
     }
 }
+
+/// The memory and the number of gaps of the data which was received out of order and is
+/// buffered until the preceding data arrives
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ReassemblyUsage {
+    /// The number of bytes buffered after the first gap, including the allocated space which
+    /// wasn't filled yet
+    pub bytes: u64,
+    /// The number of missing ranges which precede received data
+    pub gaps: u64,
+}
+
+impl ReassemblyUsage {
+    #[inline]
+    pub fn saturating_add(self, other: Self) -> Self {
+        Self {
+            bytes: self.bytes.saturating_add(other.bytes),
+            gaps: self.gaps.saturating_add(other.gaps),
+        }
+    }
+
+    #[inline]
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Self {
+            bytes: self.bytes.saturating_sub(other.bytes),
+            gaps: self.gaps.saturating_sub(other.gaps),
+        }
+    }
+}
+
+/// Limits the data which is buffered for reassembling data received out of order
+///
+/// A peer which sends small chunks of data ahead of a missing range makes the receiver hold
+/// on to buffers until the range arrives, even though the data stays within the flow control
+/// window. The limit bounds the memory and bookkeeping each peer can cause this way.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ReassemblyLimit {
+    /// The maximum number of bytes buffered after the first gap
+    pub max_bytes: Option<u64>,
+    /// The maximum number of gaps
+    pub max_gaps: Option<u64>,
+}
+
+impl ReassemblyLimit {
+    pub const UNLIMITED: Self = Self {
+        max_bytes: None,
+        max_gaps: None,
+    };
+
+    /// Returns `true` if any of the values is limited
+    #[inline]
+    pub fn is_limited(&self) -> bool {
+        self.max_bytes.is_some() || self.max_gaps.is_some()
+    }
+
+    /// Returns `true` if the usage exceeds the limit
+    #[inline]
+    pub fn is_exceeded(&self, usage: ReassemblyUsage) -> bool {
+        self.max_bytes.map_or(false, |max| usage.bytes > max)
+            || self.max_gaps.map_or(false, |max| usage.gaps > max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reassembly_limit_test() {
+        let usage = ReassemblyUsage {
+            bytes: 8192,
+            gaps: 2,
+        };
+
+        assert!(!ReassemblyLimit::UNLIMITED.is_limited());
+        assert!(!ReassemblyLimit::UNLIMITED.is_exceeded(usage));
+
+        let limit = ReassemblyLimit {
+            max_bytes: Some(8192),
+            max_gaps: None,
+        };
+        assert!(limit.is_limited());
+        assert!(!limit.is_exceeded(usage));
+        assert!(limit.is_exceeded(usage.saturating_add(ReassemblyUsage { bytes: 1, gaps: 0 })));
+
+        let limit = ReassemblyLimit {
+            max_bytes: None,
+            max_gaps: Some(1),
+        };
+        assert!(limit.is_exceeded(usage));
+        assert!(!limit.is_exceeded(usage.saturating_sub(ReassemblyUsage { bytes: 0, gaps: 1 })));
+    }
+}
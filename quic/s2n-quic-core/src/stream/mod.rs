@@ -6,6 +6,7 @@ mod id;
 pub mod iter;
 pub mod limits;
 pub mod ops;
+pub mod scheduler;
 mod type_;
 
 pub use error::*;
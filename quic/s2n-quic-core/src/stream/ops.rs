@@ -54,6 +54,12 @@ impl<'a> Request<'a> {
         self
     }
 
+    /// Resets the tx stream with an error code instead of retransmitting any data which is lost
+    pub fn reset_on_loss(&mut self, error: application::Error) -> &mut Self {
+        self.tx_mut().reset_on_loss = Some(error);
+        self
+    }
+
     /// Flushes any pending tx data to be ACKed before unblocking
     pub fn flush(&mut self) -> &mut Self {
         self.tx_mut().flush = true;
@@ -66,6 +72,14 @@ impl<'a> Request<'a> {
         self
     }
 
+    /// Notifies the `delivery` once the peer has acknowledged all of the data enqueued on the tx
+    /// stream, including the data sent by this request
+    #[cfg(feature = "alloc")]
+    pub fn notify_delivery(&mut self, delivery: tx::Delivery) -> &mut Self {
+        self.tx_mut().delivery = Some(delivery);
+        self
+    }
+
     /// Requests data on the rx stream to be received into the provided slice of chunks
     pub fn receive(&mut self, chunks: &'a mut [bytes::Bytes]) -> &mut Self {
         self.rx_mut().chunks = Some(chunks);
@@ -187,6 +201,10 @@ pub mod tx {
         /// Optionally reset the stream with an error
         pub reset: Option<application::Error>,
 
+        /// Optionally reset the stream with an error once any of its data is lost, instead of
+        /// retransmitting the data
+        pub reset_on_loss: Option<application::Error>,
+
         /// Waits for an ACK on resets and finishes
         pub flush: bool,
 
@@ -196,6 +214,38 @@ pub mod tx {
         /// Marks the tx stream as detached, which makes the stream make progress, regardless of
         /// application observations.
         pub detached: bool,
+
+        /// Optionally notify the caller once the data enqueued on the stream has been acknowledged
+        #[cfg(feature = "alloc")]
+        pub delivery: Option<Delivery>,
+    }
+
+    /// A callback which is notified once the peer has acknowledged the data on a stream up to an
+    /// offset
+    ///
+    /// If the stream is reset before all of the data is acknowledged, the callback is notified with
+    /// the error instead.
+    #[cfg(feature = "alloc")]
+    pub struct Delivery(alloc::boxed::Box<dyn FnOnce(Result<(), stream::StreamError>) + Send>);
+
+    #[cfg(feature = "alloc")]
+    impl Delivery {
+        /// Creates a delivery notification which calls `f` with the outcome of the delivery
+        pub fn new<F: 'static + FnOnce(Result<(), stream::StreamError>) + Send>(f: F) -> Self {
+            Self(alloc::boxed::Box::new(f))
+        }
+
+        /// Notifies the callback of the outcome of the delivery
+        pub fn notify(self, result: Result<(), stream::StreamError>) {
+            (self.0)(result)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl core::fmt::Debug for Delivery {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            f.debug_tuple("Delivery").finish()
+        }
     }
 
     /// The result of a tx request
@@ -511,7 +561,9 @@ mod tests {
                     finish: true,
                     flush: true,
                     reset: Some(reset),
+                    reset_on_loss: None,
                     detached: false,
+                    delivery: None,
                 }),
                 rx: Some(rx::Request {
                     chunks: Some(rx_chunks),
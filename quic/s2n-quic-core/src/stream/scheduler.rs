@@ -0,0 +1,152 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decides how the transmission capacity of a connection is shared between its streams
+//!
+//! Streams are assigned to classes, which are either a stream group or the class of all
+//! streams which didn't join a group. Once groups are created on a connection, each class
+//! receives a share of the transmission capacity which is proportional to the weight the
+//! [`Scheduler`] assigns to it, using deficit round robin. Every class with pending data
+//! is served in each round, so classes with a low weight are slowed down but never starved.
+//! Streams of the same class are served in round robin order, unless they are marked as
+//! non-incremental, in which case they are sent one at a time in the order they were queued.
+//!
+//! Lost data is retransmitted before any new data, regardless of the weights.
+
+use crate::{
+    event::{api::SocketAddress, IntoEvent},
+    inet,
+};
+use core::fmt;
+
+/// The most important urgency
+pub const MIN_URGENCY: u8 = 0;
+
+/// The least important urgency
+pub const MAX_URGENCY: u8 = 7;
+
+/// The urgency of streams which didn't join a group, and of newly created groups
+///
+/// See [RFC 9218](https://www.rfc-editor.org/rfc/rfc9218#section-4.1).
+pub const DEFAULT_URGENCY: u8 = 3;
+
+/// Information about the connection that the scheduler is being created for
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct ConnectionInfo<'a> {
+    /// The address of the peer
+    pub remote_address: SocketAddress<'a>,
+}
+
+impl<'a> ConnectionInfo<'a> {
+    #[inline]
+    #[doc(hidden)]
+    pub fn new(remote_address: &'a inet::SocketAddress) -> Self {
+        Self {
+            remote_address: remote_address.into_event(),
+        }
+    }
+}
+
+/// A class of streams which shares the transmission capacity assigned to it
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Class {
+    /// The urgency of the class, from [`MIN_URGENCY`] (most important) to [`MAX_URGENCY`]
+    pub urgency: u8,
+    /// The identifier of the stream group, or `None` for streams which didn't join a group
+    pub group: Option<u64>,
+}
+
+impl Class {
+    #[inline]
+    #[doc(hidden)]
+    pub fn new(urgency: u8, group: Option<u64>) -> Self {
+        Self { urgency, group }
+    }
+}
+
+/// Creates a [`Scheduler`] for each connection
+pub trait Endpoint: 'static + Send {
+    type Scheduler: Scheduler;
+
+    /// Called when a connection is created to return the scheduler for the connection
+    fn new_scheduler(&mut self, info: &ConnectionInfo) -> Self::Scheduler;
+}
+
+/// Assigns weights to the classes of streams on a connection
+pub trait Scheduler: 'static + Send + fmt::Debug {
+    /// Returns the weight of the given class
+    ///
+    /// Each class with pending data is allowed to transmit an amount of data that is
+    /// proportional to its weight in every round. A weight of 0 is treated as 1 to ensure
+    /// that every class makes progress.
+    ///
+    /// The weight is queried each time the class is replenished, so it may be changed at any
+    /// time.
+    ///
+    /// ```rust
+    /// # mod s2n_quic { pub mod provider { pub mod stream_scheduler { pub use s2n_quic_core::stream::scheduler::*; } } }
+    /// use s2n_quic::provider::stream_scheduler::{Class, Scheduler};
+    ///
+    /// /// Shares the capacity equally between all classes
+    /// #[derive(Debug)]
+    /// struct Equal;
+    ///
+    /// impl Scheduler for Equal {
+    ///     fn weight(&mut self, _class: &Class) -> u32 {
+    ///         1
+    ///     }
+    /// }
+    /// ```
+    fn weight(&mut self, class: &Class) -> u32;
+}
+
+pub mod default {
+    use super::*;
+
+    /// Derives the weight of each class from its urgency
+    #[derive(Clone, Debug, Default)]
+    pub struct Endpoint;
+
+    impl super::Endpoint for Endpoint {
+        type Scheduler = Scheduler;
+
+        #[inline]
+        fn new_scheduler(&mut self, _info: &ConnectionInfo) -> Self::Scheduler {
+            Scheduler
+        }
+    }
+
+    /// Derives the weight of each class from its urgency
+    ///
+    /// Each urgency level receives twice the capacity of the next less important level.
+    #[derive(Debug, Default)]
+    pub struct Scheduler;
+
+    impl super::Scheduler for Scheduler {
+        #[inline]
+        fn weight(&mut self, class: &Class) -> u32 {
+            1 << (MAX_URGENCY - class.urgency.min(MAX_URGENCY))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{super::Scheduler as _, *};
+
+        #[test]
+        fn weight_test() {
+            let mut scheduler = Scheduler;
+            let weight = |scheduler: &mut Scheduler, urgency| {
+                scheduler.weight(&Class::new(urgency, Some(1)))
+            };
+
+            assert_eq!(weight(&mut scheduler, MIN_URGENCY), 128);
+            assert_eq!(weight(&mut scheduler, DEFAULT_URGENCY), 16);
+            assert_eq!(weight(&mut scheduler, MAX_URGENCY), 1);
+            // invalid urgencies are treated as the least important urgency
+            assert_eq!(weight(&mut scheduler, u8::MAX), 1);
+        }
+    }
+}
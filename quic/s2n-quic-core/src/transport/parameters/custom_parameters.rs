@@ -0,0 +1,213 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{TransportParameterId, TransportParameterLength, ValidationError};
+use crate::varint::VarInt;
+use core::fmt;
+use s2n_codec::{DecoderBuffer, Encoder, EncoderBuffer, EncoderValue};
+
+const DUPLICATE_PARAMETER: ValidationError =
+    ValidationError::new("the custom transport parameter was already added");
+
+const CAPACITY_EXCEEDED: ValidationError =
+    ValidationError::new("the custom transport parameters exceed the maximum length");
+
+/// Transport parameters which are not defined by this crate
+///
+/// On the sending side, these are attached to the transport parameters, which allows for
+/// experimenting with private extensions. On the receiving side, the parameters from the peer
+/// with an unknown ID are preserved here, except for reserved parameters, which carry no
+/// semantics.
+///
+/// The parameters are stored in their encoded form, as a sequence of transport parameters.
+/// Unknown peer parameters which don't fit into [`Self::CAPACITY`] are ignored.
+#[derive(Clone, Copy)]
+pub struct CustomParameters {
+    len: u16,
+    bytes: [u8; Self::CAPACITY],
+}
+
+impl CustomParameters {
+    /// The maximum length of the encoded parameters
+    pub const CAPACITY: usize = 256;
+
+    pub const fn new() -> Self {
+        Self {
+            len: 0,
+            bytes: [0; Self::CAPACITY],
+        }
+    }
+
+    /// Returns `true` if no parameters are stored
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds a parameter with the given ID and value
+    ///
+    /// Returns an error if a parameter with the same ID was already added or if the encoded
+    /// parameters would exceed [`Self::CAPACITY`].
+    pub fn insert(
+        &mut self,
+        id: TransportParameterId,
+        value: &[u8],
+    ) -> Result<(), ValidationError> {
+        if self.get(id).is_some() {
+            return Err(DUPLICATE_PARAMETER);
+        }
+
+        let value_len = VarInt::try_from(value.len()).map_err(|_| CAPACITY_EXCEEDED)?;
+        let encoding_size = id.encoding_size() + value_len.encoding_size() + value.len();
+        let offset = self.len as usize;
+        if offset + encoding_size > Self::CAPACITY {
+            return Err(CAPACITY_EXCEEDED);
+        }
+
+        let mut encoder = EncoderBuffer::new(&mut self.bytes[offset..]);
+        encoder.encode(&id);
+        encoder.encode_with_len_prefix::<TransportParameterLength, _>(&value);
+        self.len += encoder.len() as u16;
+
+        Ok(())
+    }
+
+    /// Returns the value of the parameter with the given ID
+    pub fn get(&self, id: TransportParameterId) -> Option<&[u8]> {
+        self.iter()
+            .find(|(parameter_id, _)| *parameter_id == id)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over the IDs and values of the parameters
+    #[inline]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter::new(self.as_bytes())
+    }
+
+    /// Returns the parameters encoded as a sequence of transport parameters
+    ///
+    /// This is the representation which is used for the `custom_parameters` field of
+    /// transport parameter events.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+}
+
+impl Default for CustomParameters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for CustomParameters {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for CustomParameters {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for CustomParameters {}
+
+impl EncoderValue for CustomParameters {
+    #[inline]
+    fn encode<E: Encoder>(&self, buffer: &mut E) {
+        buffer.write_slice(self.as_bytes());
+    }
+}
+
+/// Iterates over the IDs and values of a sequence of encoded transport parameters
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+    buffer: DecoderBuffer<'a>,
+}
+
+impl<'a> Iter<'a> {
+    /// Creates an iterator over a sequence of encoded transport parameters
+    ///
+    /// This can be used to read the `custom_parameters` field of transport parameter events.
+    /// The iteration stops at the first parameter which can't be decoded.
+    #[inline]
+    pub fn new(encoded: &'a [u8]) -> Self {
+        Self {
+            buffer: DecoderBuffer::new(encoded),
+        }
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (TransportParameterId, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let buffer = core::mem::replace(&mut self.buffer, DecoderBuffer::new(&[]));
+        let (id, buffer) = buffer.decode::<TransportParameterId>().ok()?;
+        let (value, buffer) = buffer
+            .decode_slice_with_len_prefix::<TransportParameterLength>()
+            .ok()?;
+        self.buffer = buffer;
+        Some((id, value.into_less_safe_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_test() {
+        let mut parameters = CustomParameters::new();
+        assert!(parameters.is_empty());
+
+        let a = VarInt::from_u16(0x3a11);
+        let b = VarInt::from_u32(0xff00_0001);
+        parameters.insert(a, &[1, 2, 3]).unwrap();
+        parameters.insert(b, &[]).unwrap();
+
+        assert_eq!(parameters.get(a), Some(&[1, 2, 3][..]));
+        assert_eq!(parameters.get(b), Some(&[][..]));
+        assert_eq!(parameters.get(VarInt::from_u8(1)), None);
+        assert_eq!(
+            parameters.iter().collect::<Vec<_>>(),
+            [(a, &[1, 2, 3][..]), (b, &[][..])]
+        );
+        assert_eq!(
+            parameters.as_bytes(),
+            [0x7a, 0x11, 3, 1, 2, 3, 0xc0, 0, 0, 0, 0xff, 0, 0, 1, 0]
+        );
+
+        // parameters can only be added once
+        assert_eq!(parameters.insert(a, &[4]), Err(DUPLICATE_PARAMETER));
+        assert_eq!(parameters.get(a), Some(&[1, 2, 3][..]));
+    }
+
+    #[test]
+    fn capacity_test() {
+        let mut parameters = CustomParameters::new();
+        let value = [42; CustomParameters::CAPACITY];
+
+        assert_eq!(
+            parameters.insert(VarInt::from_u8(0x21), &value),
+            Err(CAPACITY_EXCEEDED)
+        );
+        assert!(parameters.is_empty());
+
+        // the ID and the length of the first parameter take up 3 bytes, which leaves space
+        // for the 2 bytes of an empty parameter
+        let len = CustomParameters::CAPACITY - 3 - 2;
+        parameters
+            .insert(VarInt::from_u8(0x21), &value[..len])
+            .unwrap();
+        assert_eq!(
+            parameters.insert(VarInt::from_u8(0x22), &[1]),
+            Err(CAPACITY_EXCEEDED)
+        );
+        parameters.insert(VarInt::from_u8(0x22), &[]).unwrap();
+        assert_eq!(parameters.as_bytes().len(), CustomParameters::CAPACITY);
+    }
+}
@@ -173,11 +173,14 @@ decoder_value!(
 //# QUIC encodes transport parameters into a sequence of bytes, which is
 //# then included in the cryptographic handshake.
 
-type TransportParameterId = VarInt;
-type TransportParameterLength = VarInt;
+pub type TransportParameterId = VarInt;
+pub type TransportParameterLength = VarInt;
 
 /// Utility struct for encoding and decoding transport parameters
-struct TransportParameterCodec<T>(T);
+///
+/// This encodes the ID and the length prefixed value of a [`TransportParameter`], which allows
+/// parameters for private extensions to be defined outside of this crate.
+pub struct TransportParameterCodec<T>(pub T);
 
 impl<'a, T: TransportParameter> DecoderValue<'a> for TransportParameterCodec<T>
 where
@@ -217,12 +220,24 @@ pub struct ValidationError(&'static str);
 const MAX_ENCODABLE_VALUE: ValidationError =
     ValidationError("provided value exceeds maximum encodable value");
 
+const KNOWN_PARAMETER: ValidationError =
+    ValidationError("the transport parameter is not a custom parameter");
+
+impl ValidationError {
+    pub(crate) const fn new(reason: &'static str) -> Self {
+        Self(reason)
+    }
+}
+
 impl core::fmt::Display for ValidationError {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for ValidationError {}
+
 impl From<DecoderError> for ValidationError {
     fn from(error: DecoderError) -> Self {
         ValidationError(error.into())
@@ -508,6 +523,39 @@ impl MaxIdleTimeout {
     }
 }
 
+#[test]
+fn max_idle_timeout_load_peer_test() {
+    let timeout = |ms: u32| MaxIdleTimeout(VarInt::from_u32(ms));
+    let effective = |local: MaxIdleTimeout, peer: MaxIdleTimeout| {
+        let mut value = local;
+        value.load_peer(&peer);
+        value.as_duration()
+    };
+
+    // the minimum of the two values is used
+    assert_eq!(
+        effective(timeout(1_000), timeout(2_000)),
+        Some(Duration::from_secs(1))
+    );
+    assert_eq!(
+        effective(timeout(2_000), timeout(1_000)),
+        Some(Duration::from_secs(1))
+    );
+
+    // the sole advertised value is used
+    assert_eq!(
+        effective(timeout(0), timeout(1_000)),
+        Some(Duration::from_secs(1))
+    );
+    assert_eq!(
+        effective(timeout(1_000), timeout(0)),
+        Some(Duration::from_secs(1))
+    );
+
+    // the idle timeout is disabled if neither endpoint advertises a value
+    assert_eq!(effective(timeout(0), timeout(0)), None);
+}
+
 impl TransportParameterValidator for MaxIdleTimeout {}
 
 impl TryFrom<Duration> for MaxIdleTimeout {
@@ -813,6 +861,56 @@ impl TryFrom<u64> for MaxDatagramFrameSize {
     }
 }
 
+// RFC 9287 Section 3: An endpoint that advertises the grease_quic_bit transport
+// parameter MUST accept packets with the QUIC Bit set to a value of 0. The
+// grease_quic_bit transport parameter (0x2ab2) can be sent by both client and server.
+// The transport parameter is sent with an empty value; an endpoint that understands
+// this transport parameter MUST treat receipt of a non-empty value of the transport
+// parameter as a connection error of type TRANSPORT_PARAMETER_ERROR.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GreaseQuicBit {
+    Enabled,
+    Disabled,
+}
+
+impl Default for GreaseQuicBit {
+    fn default() -> Self {
+        GreaseQuicBit::Disabled
+    }
+}
+
+impl GreaseQuicBit {
+    /// Returns `true` if the QUIC bit can be set to an arbitrary value
+    pub fn is_enabled(self) -> bool {
+        matches!(self, Self::Enabled)
+    }
+}
+
+impl TransportParameter for GreaseQuicBit {
+    type CodecValue = ();
+
+    const ID: TransportParameterId = TransportParameterId::from_u16(0x2ab2);
+
+    fn from_codec_value(_value: ()) -> Self {
+        GreaseQuicBit::Enabled
+    }
+
+    fn try_into_codec_value(&self) -> Option<&()> {
+        if let GreaseQuicBit::Enabled = self {
+            Some(&())
+        } else {
+            None
+        }
+    }
+
+    fn default_value() -> Self {
+        GreaseQuicBit::Disabled
+    }
+}
+
+impl TransportParameterValidator for GreaseQuicBit {}
+
 //= https://www.rfc-editor.org/rfc/rfc9000#section-18.2
 //# ack_delay_exponent (0x0a):  The acknowledgement delay exponent is an
 //#    integer value indicating an exponent used to decode the ACK Delay
@@ -853,6 +951,13 @@ duration_transport_parameter!(MaxAckDelay, 0x0b, VarInt::from_u8(25));
 impl MaxAckDelay {
     /// The recommended value comes from the default of 25ms
     pub const RECOMMENDED: Self = Self(VarInt::from_u8(25));
+
+    /// Creates a `MaxAckDelay` from a number of milliseconds
+    ///
+    /// Any `u8` value is below the maximum of 2^14 so the value is always valid.
+    pub(crate) const fn from_millis_u8(millis: u8) -> Self {
+        Self(VarInt::from_u8(millis))
+    }
 }
 
 impl TransportParameterValidator for MaxAckDelay {
@@ -1205,7 +1310,9 @@ impl<
 //# treat receipt of any of these transport parameters as a connection
 //# error of type TRANSPORT_PARAMETER_ERROR.
 
+mod custom_parameters;
 mod disabled_parameter;
+pub use custom_parameters::{CustomParameters, Iter as CustomParametersIter};
 pub use disabled_parameter::DisabledParameter;
 
 /// Specific TransportParameters sent by the client endpoint
@@ -1263,6 +1370,7 @@ impl<'a> IntoEvent<event::builder::TransportParameters<'a>> for &'a ServerTransp
             initial_max_streams_bidi: self.initial_max_streams_bidi.into_event(),
             initial_max_streams_uni: self.initial_max_streams_uni.into_event(),
             max_datagram_frame_size: self.max_datagram_frame_size.into_event(),
+            custom_parameters: self.custom_parameters.as_bytes(),
         }
     }
 }
@@ -1294,8 +1402,71 @@ impl<'a> IntoEvent<event::builder::TransportParameters<'a>> for &'a ClientTransp
             initial_max_streams_bidi: self.initial_max_streams_bidi.into_event(),
             initial_max_streams_uni: self.initial_max_streams_uni.into_event(),
             max_datagram_frame_size: self.max_datagram_frame_size.into_event(),
+            custom_parameters: self.custom_parameters.as_bytes(),
+        }
+    }
+}
+
+//= https://www.rfc-editor.org/rfc/rfc9000#section-18.1
+//# Transport parameters with an identifier of the form "31 * N + 27" for
+//# integer values of N are reserved to exercise the requirement that
+//# unknown transport parameters be ignored.  These transport parameters
+//# have no semantics and can carry arbitrary values.
+
+/// A transport parameter with a reserved ID, which is sent to ensure peers ignore unknown
+/// transport parameters
+///
+/// Peers which don't tolerate unknown parameters would otherwise prevent new parameters from
+/// ever being deployed. The ID and value are chosen at random for each connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReservedParameter {
+    id: TransportParameterId,
+    len: u8,
+    value: [u8; Self::MAX_VALUE_LEN],
+}
+
+impl ReservedParameter {
+    /// The maximum length of the value of reserved parameters which are sent
+    pub const MAX_VALUE_LEN: usize = 16;
+
+    /// Creates a parameter with a random reserved ID and value
+    pub fn random<R: crate::random::Generator + ?Sized>(random_generator: &mut R) -> Self {
+        let mut n = [0; 4];
+        random_generator.public_random_fill(&mut n);
+        let n = u32::from_le_bytes(n) as u64;
+
+        let len = crate::random::gen_range_biased(random_generator, 0..=Self::MAX_VALUE_LEN);
+        let mut value = [0; Self::MAX_VALUE_LEN];
+        random_generator.public_random_fill(&mut value[..len]);
+
+        Self {
+            id: VarInt::new(31 * n + 27).expect("the ID is less than 2^62"),
+            len: len as u8,
+            value,
         }
     }
+
+    /// Returns the ID of the parameter
+    pub fn id(&self) -> VarInt {
+        self.id
+    }
+
+    /// Returns the value of the parameter
+    pub fn value(&self) -> &[u8] {
+        &self.value[..self.len as usize]
+    }
+
+    /// Returns `true` if the ID is reserved for exercising the handling of unknown parameters
+    pub fn is_reserved(id: TransportParameterId) -> bool {
+        id.as_u64() % 31 == 27
+    }
+}
+
+impl EncoderValue for ReservedParameter {
+    fn encode<E: Encoder>(&self, buffer: &mut E) {
+        buffer.encode(&self.id);
+        buffer.encode_with_len_prefix::<TransportParameterLength, _>(&self.value());
+    }
 }
 
 macro_rules! impl_transport_parameters {
@@ -1307,8 +1478,18 @@ macro_rules! impl_transport_parameters {
         #[derive(Clone, Copy, Debug, PartialEq)]
         pub struct TransportParameters<$($server_param),*> {
             $(
-                pub $field: $field_ty
-            ),*
+                pub $field: $field_ty,
+            )*
+            /// A reserved parameter which is sent along with the other parameters
+            ///
+            /// Reserved parameters from the peer are ignored, so this is always `None` after
+            /// decoding.
+            pub reserved_parameter: Option<ReservedParameter>,
+            /// Parameters which are not defined by this crate
+            ///
+            /// These are sent along with the other parameters. After decoding, this contains the
+            /// parameters from the peer with unknown IDs.
+            pub custom_parameters: CustomParameters,
         }
 
         impl<$($server_param),*> Default for TransportParameters<$($server_param),*>
@@ -1322,10 +1503,44 @@ macro_rules! impl_transport_parameters {
                     $(
                         $field: TransportParameter::default_value(),
                     )*
+                    reserved_parameter: None,
+                    custom_parameters: CustomParameters::new(),
                 }
             }
         }
 
+        impl<$($server_param),*> TransportParameters<$($server_param),*>
+        where
+            $(
+                $server_param: TransportParameter,
+            )*
+        {
+            /// Returns `true` if the ID belongs to a parameter which is defined by this crate
+            pub fn is_known_parameter(id: TransportParameterId) -> bool {
+                $(
+                    if id == <$field_ty>::ID {
+                        return true;
+                    }
+                )*
+                false
+            }
+
+            /// Attaches a parameter which isn't defined by this crate
+            ///
+            /// Returns an error if the ID belongs to a parameter which is defined by this crate,
+            /// or if the parameter can't be added to the [`CustomParameters`].
+            pub fn add_custom_parameter(
+                &mut self,
+                id: TransportParameterId,
+                value: &[u8],
+            ) -> Result<(), ValidationError> {
+                if Self::is_known_parameter(id) {
+                    return Err(KNOWN_PARAMETER);
+                }
+                self.custom_parameters.insert(id, value)
+            }
+        }
+
         impl<$($server_param),*> EncoderValue for TransportParameters<$($server_param),*>
         where
             $(
@@ -1337,6 +1552,10 @@ macro_rules! impl_transport_parameters {
                 $(
                     buffer.encode(&TransportParameterCodec(&self.$field));
                 )*
+                if let Some(reserved_parameter) = &self.reserved_parameter {
+                    buffer.encode(reserved_parameter);
+                }
+                buffer.encode(&self.custom_parameters);
             }
         }
 
@@ -1399,10 +1618,20 @@ macro_rules! impl_transport_parameters {
                             //# An endpoint MUST ignore transport parameters that it does
                             //# not support.
 
-                            // ignore transport parameters with unknown tags
-                            // We need to skip the actual content of the parameters, which
-                            // consists of a VarInt length field plus payload
-                            inner_buffer.skip_with_len_prefix::<TransportParameterLength>()?
+                            // ignore transport parameters with unknown tags, but preserve
+                            // their values for applications which implement extensions
+                            let (value, inner_buffer) = inner_buffer
+                                .decode_slice_with_len_prefix::<TransportParameterLength>()?;
+
+                            // reserved parameters have no semantics
+                            if !ReservedParameter::is_reserved(tag) {
+                                // parameters which don't fit are ignored
+                                let _ = parameters
+                                    .custom_parameters
+                                    .insert(tag, value.into_less_safe_slice());
+                            }
+
+                            inner_buffer
                         }
                     }
                 }
@@ -1438,6 +1667,7 @@ impl_transport_parameters!(
         preferred_address: PreferredAddress,
         initial_source_connection_id: Option<InitialSourceConnectionId>,
         retry_source_connection_id: RetrySourceConnectionId,
+        grease_quic_bit: GreaseQuicBit,
     }
 );
 
@@ -1461,6 +1691,15 @@ impl<
             };
         }
 
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-18.2
+        //# Idle timeout is disabled when both endpoints omit this transport
+        //# parameter or specify a value of 0.
+        if limits.idle_timeout_enabled {
+            load!(max_idle_timeout, max_idle_timeout);
+        } else {
+            self.max_idle_timeout = MaxIdleTimeout::default();
+        }
+
         load!(max_ack_delay, max_ack_delay);
         load!(data_window, initial_max_data);
         load!(
@@ -1484,6 +1723,14 @@ impl<
         load!(max_ack_delay, max_ack_delay);
         load!(max_active_connection_ids, active_connection_id_limit);
         load!(max_datagram_frame_size, max_datagram_frame_size);
+
+        self.grease_quic_bit = if limits.grease_quic_bit_enabled {
+            GreaseQuicBit::Enabled
+        } else {
+            GreaseQuicBit::Disabled
+        };
+
+        self.custom_parameters = limits.custom_transport_parameters;
     }
 }
 
@@ -1553,6 +1800,9 @@ mod snapshot_tests {
             }),
             initial_source_connection_id: Some([1, 2, 3, 4][..].try_into().unwrap()),
             retry_source_connection_id: Some([1, 2, 3, 4][..].try_into().unwrap()),
+            grease_quic_bit: GreaseQuicBit::Enabled,
+            reserved_parameter: None,
+            custom_parameters: Default::default(),
         }
     }
 
@@ -1590,6 +1840,9 @@ mod snapshot_tests {
             preferred_address: Default::default(),
             initial_source_connection_id: Some([1, 2, 3, 4][..].try_into().unwrap()),
             retry_source_connection_id: Default::default(),
+            grease_quic_bit: GreaseQuicBit::Enabled,
+            reserved_parameter: None,
+            custom_parameters: Default::default(),
         }
     }
 
@@ -1632,4 +1885,150 @@ mod snapshot_tests {
         assert_eq!(value, decoded_params);
         assert_eq!(0, remaining.len());
     }
+
+    #[test]
+    fn unknown_parameter_preserved_test() {
+        use s2n_codec::EncoderBuffer;
+
+        let value = client_transport_parameters();
+
+        let mut buffer = vec![0; 32 * 1024];
+        let mut encoder = EncoderBuffer::new(&mut buffer);
+        encoder.encode(&value);
+
+        let id: TransportParameterId = VarInt::from_u16(0x3a11);
+        encoder.encode(&id);
+        encoder.encode_with_len_prefix::<TransportParameterLength, _>(&&[1u8, 2, 3][..]);
+
+        let (encoded, _) = encoder.split_off();
+        let (decoded_params, _) = ClientTransportParameters::decode(DecoderBuffer::new(encoded))
+            .expect("Decoding succeeds");
+        assert_eq!(
+            decoded_params.custom_parameters.iter().collect::<Vec<_>>(),
+            [(id, &[1, 2, 3][..])]
+        );
+    }
+
+    #[test]
+    fn custom_parameter_test() {
+        use crate::connection::limits::Limits;
+
+        let id = VarInt::from_u16(0x3a11);
+        let mut value = client_transport_parameters();
+
+        // parameters defined by this crate can't be added, including server-only parameters
+        assert!(value
+            .add_custom_parameter(InitialMaxData::ID, &[1])
+            .is_err());
+        assert!(value
+            .add_custom_parameter(OriginalDestinationConnectionId::ID, &[1])
+            .is_err());
+
+        value.add_custom_parameter(id, &[1, 2, 3]).unwrap();
+        assert!(value.add_custom_parameter(id, &[4]).is_err());
+
+        // the parameter is sent and decoded by the peer
+        assert_codec_round_trip_value!(ClientTransportParameters, value);
+
+        // custom parameters can be configured with the limits
+        assert!(Limits::default()
+            .with_custom_transport_parameter(InitialMaxData::ID, &[1])
+            .is_err());
+        let limits = Limits::default()
+            .with_custom_transport_parameter(id, &[1, 2, 3])
+            .unwrap();
+        let mut params = ServerTransportParameters::default();
+        params.load_limits(&limits);
+        assert_eq!(params.custom_parameters.get(id), Some(&[1, 2, 3][..]));
+    }
+
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-18.1
+    //= type=test
+    //# Transport parameters with an identifier of the form "31 * N + 27" for
+    //# integer values of N are reserved to exercise the requirement that
+    //# unknown transport parameters be ignored.
+    #[test]
+    fn reserved_parameter_test() {
+        use crate::random::testing::Generator;
+        use s2n_codec::EncoderBuffer;
+
+        let mut random_generator = Generator::default();
+        let value = client_transport_parameters();
+
+        for _ in 0..100 {
+            let reserved_parameter = ReservedParameter::random(&mut random_generator);
+            assert_eq!(reserved_parameter.id().as_u64() % 31, 27);
+            assert!(reserved_parameter.value().len() <= ReservedParameter::MAX_VALUE_LEN);
+
+            let mut with_reserved = value;
+            with_reserved.reserved_parameter = Some(reserved_parameter);
+
+            let mut buffer = vec![0; 32 * 1024];
+            let mut encoder = EncoderBuffer::new(&mut buffer);
+            encoder.encode(&with_reserved);
+            let len = encoder.len();
+            assert_eq!(
+                len,
+                value.encoding_size() + reserved_parameter.encoding_size()
+            );
+
+            // the reserved parameter is ignored by the peer
+            let (encoded, _) = encoder.split_off();
+            let (decoded_params, remaining) =
+                ClientTransportParameters::decode(DecoderBuffer::new(encoded))
+                    .expect("Decoding succeeds");
+            assert_eq!(value, decoded_params);
+            assert_eq!(0, remaining.len());
+        }
+    }
+
+    #[test]
+    fn load_limits_idle_timeout_test() {
+        use crate::connection::limits::Limits;
+
+        let limits = Limits::default()
+            .with_max_idle_timeout(Duration::from_secs(5))
+            .unwrap();
+        let mut params = ServerTransportParameters::default();
+        params.load_limits(&limits);
+        assert_eq!(
+            params.max_idle_timeout.as_duration(),
+            Some(Duration::from_secs(5))
+        );
+
+        // a disabled idle timeout is advertised as 0
+        let limits = limits.with_idle_timeout_enabled(false).unwrap();
+        params.load_limits(&limits);
+        assert_eq!(params.max_idle_timeout.as_duration(), None);
+    }
+
+    #[test]
+    fn grease_quic_bit_test() {
+        use crate::connection::limits::Limits;
+
+        let mut params = ClientTransportParameters::default();
+        params.load_limits(&Limits::default());
+        assert_eq!(params.grease_quic_bit, GreaseQuicBit::Disabled);
+
+        params.load_limits(
+            &Limits::default()
+                .with_grease_quic_bit_enabled(true)
+                .unwrap(),
+        );
+        assert_eq!(params.grease_quic_bit, GreaseQuicBit::Enabled);
+
+        // the parameter is sent with an empty value
+        let encoded: Vec<u8> = assert_codec_round_trip_value!(
+            ClientTransportParameters,
+            ClientTransportParameters {
+                grease_quic_bit: GreaseQuicBit::Enabled,
+                ..Default::default()
+            }
+        );
+        assert_eq!(encoded, [0x6a, 0xb2, 0x00]);
+
+        // non-empty values are rejected
+        let invalid = [0x6a, 0xb2, 0x01, 0x00];
+        assert!(ClientTransportParameters::decode(DecoderBuffer::new(&invalid)).is_err());
+    }
 }
@@ -5,7 +5,7 @@ use core::{
     convert::{TryFrom, TryInto},
     ops::Deref,
 };
-use s2n_codec::{decoder_value, Encoder, EncoderValue};
+use s2n_codec::{decoder_value, DecoderBuffer, DecoderBufferResult, Encoder, EncoderValue};
 
 #[cfg(any(test, feature = "generator"))]
 use bolero_generator::*;
@@ -151,6 +151,53 @@ mod tests {
     sequence_test!(two_byte_sequence_test([0x7b, 0xbd], 15293));
 
     sequence_test!(one_byte_sequence_test([0x25], 37));
+
+    #[test]
+    fn decode_batch_test() {
+        use bolero::check;
+        use s2n_codec::EncoderBuffer;
+
+        check!().with_type::<Vec<VarInt>>().for_each(|expected| {
+            let len = expected.iter().map(|value| value.encoding_size()).sum();
+            let mut bytes = vec![0; len];
+            let mut encoder = EncoderBuffer::new(&mut bytes);
+            for value in expected {
+                encoder.encode(value);
+            }
+
+            // make room for an extra value to ensure decoding stops at the end of the buffer
+            let mut actual = vec![VarInt::default(); expected.len() + 1];
+            let (count, remaining) =
+                VarInt::decode_batch(DecoderBuffer::new(&bytes), &mut actual).unwrap();
+            assert_eq!(&actual[..count], &expected[..]);
+            assert!(remaining.is_empty());
+
+            // decoding individual values should take the same fast and fallback paths
+            let mut buffer = DecoderBuffer::new(&bytes);
+            for value in expected {
+                let (actual, remaining) = buffer.decode::<VarInt>().unwrap();
+                assert_eq!(&actual, value);
+                buffer = remaining;
+            }
+        });
+    }
+
+    #[test]
+    fn decode_batch_partial_test() {
+        let bytes = [0x25, 0x7b, 0xbd, 0x9d, 0x7f, 0x3e, 0x7d, 0xc2, 0x19];
+
+        // decoding stops once the output is full
+        let mut values = [VarInt::default(); 2];
+        let (count, remaining) =
+            VarInt::decode_batch(DecoderBuffer::new(&bytes), &mut values).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(values, [VarInt::from_u8(37), VarInt::from_u16(15293)]);
+        assert_eq!(remaining.len(), 6);
+
+        // the buffer ends in the middle of the last integer
+        let mut values = [VarInt::default(); 4];
+        assert!(VarInt::decode_batch(DecoderBuffer::new(&bytes), &mut values).is_err());
+    }
 }
 
 // === API ===
@@ -249,6 +296,42 @@ impl VarInt {
         Some(Self(self.0.checked_div(value.0)?))
     }
 
+    /// Decodes consecutive variable-length integers from the buffer into `values`
+    ///
+    /// Decoding stops once `values` is full or the buffer is empty, and the number of decoded
+    /// integers is returned. While at least 8 bytes remain, each integer is decoded from a
+    /// single word load. The last few bytes fall back to reading the length prefix first.
+    #[inline]
+    pub fn decode_batch<'a>(
+        buffer: DecoderBuffer<'a>,
+        values: &mut [Self],
+    ) -> DecoderBufferResult<'a, usize> {
+        let bytes = buffer.into_less_safe_slice();
+        let mut offset = 0;
+        let mut count = 0;
+
+        for value in values.iter_mut() {
+            let remaining = &bytes[offset..];
+
+            let len = if let Some(word) = remaining.get(..WORD_LEN) {
+                let (decoded, len) = decode_word(word);
+                *value = decoded;
+                len
+            } else if remaining.is_empty() {
+                break;
+            } else {
+                let (decoded, buffer) = DecoderBuffer::new(remaining).decode::<Self>()?;
+                *value = decoded;
+                remaining.len() - buffer.len()
+            };
+
+            offset += len;
+            count += 1;
+        }
+
+        Ok((count, DecoderBuffer::new(&bytes[offset..])))
+    }
+
     /// Re-encodes a replacement value where `self` was used as a placeholder.
     #[inline]
     pub fn encode_updated<E: Encoder>(self, replacement: Self, encoder: &mut E) {
@@ -342,9 +425,35 @@ impl EncoderValue for VarInt {
     }
 }
 
+/// The number of bytes loaded by the word decoding fast path
+const WORD_LEN: usize = 8;
+
+/// Decodes the variable-length integer at the start of an 8 byte `word`
+///
+/// The value is extracted from a single load, rather than reading the two bit length prefix
+/// before reading each of the possible integer sizes. The returned length can be shorter than
+/// the word, in which case the remaining bytes belong to the following fields.
+#[inline(always)]
+fn decode_word(word: &[u8]) -> (VarInt, usize) {
+    let word = u64::from_be_bytes(word.try_into().expect("word should be 8 bytes"));
+
+    match word >> 62 {
+        0b00 => (VarInt((word >> 56) & 0x3f), 1),
+        0b01 => (VarInt((word >> 48) & 0x3fff), 2),
+        0b10 => (VarInt((word >> 32) & 0x3fff_ffff), 4),
+        _ => (VarInt(word & MAX_VARINT_VALUE), 8),
+    }
+}
+
 decoder_value!(
     impl<'a> VarInt {
         fn decode(buffer: Buffer) -> Result<Self> {
+            if let Ok(word) = buffer.peek_range(0..WORD_LEN) {
+                let (value, len) = decode_word(word.into_less_safe_slice());
+                let buffer = buffer.skip(len)?;
+                return Ok((value, buffer));
+            }
+
             let header = buffer.peek_byte(0)?;
 
             Ok(match (header >> 6) & 0b11 {
@@ -1,10 +1,14 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{aead::Aead, header_key::HeaderKey, iv};
+use crate::{aead::Aead, header_key::HeaderKey, iv, offload};
 use ::ring::{aead, hkdf};
 use core::fmt;
-use s2n_quic_core::crypto::{label, CryptoError};
+use s2n_quic_core::crypto::{
+    label,
+    one_rtt::{DirectionalKey, Secret},
+    CryptoError,
+};
 use zeroize::{Zeroize, Zeroizing};
 
 mod negotiated;
@@ -23,6 +27,7 @@ macro_rules! impl_cipher_suite {
         $name:ident,
         $lower:ident,
         $digest:path,
+        $secret_len:expr,
         $cipher:path,
         $cipher_key_len:expr,
         $header_protection:path,
@@ -38,6 +43,7 @@ macro_rules! impl_cipher_suite {
             use super::*;
 
             pub const KEY_LEN: usize = $cipher_key_len;
+            pub const SECRET_LEN: usize = $secret_len;
             pub const TAG_LEN: usize = 16;
             pub const NONCE_LEN: usize = crate::aesgcm::NONCE_LEN;
 
@@ -46,21 +52,34 @@ macro_rules! impl_cipher_suite {
             // ignore casing warnings in order to preserve the IANA name
             #[allow(non_camel_case_types, clippy::all)]
             pub struct $name {
-                secret: hkdf::Prk,
+                key_secret: Zeroizing<[u8; KEY_LEN]>,
                 iv: iv::Iv,
+                next_secret: Zeroizing<[u8; SECRET_LEN]>,
                 key: Key,
+                offload: Option<offload::Key>,
             }
 
             impl $name {
-                pub fn new(secret: hkdf::Prk) -> (Self, HeaderKey) {
+                /// Creates the key and offers it to the offload engine, if any
+                pub fn new(
+                    secret: hkdf::Prk,
+                    binding: Option<&offload::Binding>,
+                ) -> (Self, HeaderKey) {
                     let iv = Self::new_iv(&secret);
-                    let key = {
-                        let secret = Self::new_key_secret(&secret);
-                        Key::new(&*secret)
+                    let key_secret = Self::new_key_secret(&secret);
+                    let next_secret = Self::new_next_secret(&secret);
+                    let offload = binding.map(|binding| {
+                        offload::Key::new(binding, Self::CIPHER_SUITE, &*key_secret, iv.as_bytes())
+                    });
+                    let header_key = Self::new_header_key(&secret, binding);
+
+                    let key = Self {
+                        key: Key::new(&*key_secret),
+                        key_secret,
+                        iv,
+                        next_secret,
+                        offload,
                     };
-                    let header_key = Self::new_header_key(&secret);
-
-                    let key = Self { secret, iv, key };
 
                     (key, header_key)
                 }
@@ -69,28 +88,75 @@ macro_rules! impl_cipher_suite {
                 /// https://www.rfc-editor.org/rfc/rfc9001#section-6
                 #[inline]
                 pub fn update(&self) -> Self {
-                    let secret: hkdf::Prk = self
-                        .secret
-                        .expand(&[&$key_update_label], $digest)
-                        .expect("label size verified")
-                        .into();
+                    let secret = hkdf::Prk::new_less_safe($digest, &*self.next_secret);
 
                     let iv = Self::new_iv(&secret);
-                    let key = {
-                        let key = Self::new_key_secret(&secret);
-                        // ask the existing key to derive the next one so it can persist any
-                        // configuration
-                        self.key.update(&*key)
-                    };
-                    Self { secret, iv, key }
+                    let key_secret = Self::new_key_secret(&secret);
+                    let next_secret = Self::new_next_secret(&secret);
+                    // offer the next key to the same engine, even if it declined this one
+                    let offload = self.offload.as_ref().map(|offload| {
+                        offload::Key::new(
+                            offload.binding(),
+                            Self::CIPHER_SUITE,
+                            &*key_secret,
+                            iv.as_bytes(),
+                        )
+                    });
+                    // ask the existing key to derive the next one so it can persist any
+                    // configuration
+                    let key = self.key.update(&*key_secret);
+
+                    Self {
+                        key_secret,
+                        iv,
+                        next_secret,
+                        key,
+                        offload,
+                    }
                 }
 
                 #[inline]
                 pub fn update_pmtu(&mut self, mtu: u16) {
                     if self.key.should_update_pmtu(mtu) {
-                        let secret = Self::new_key_secret(&self.secret);
-                        self.key.update_pmtu(&*secret, mtu);
+                        self.key.update_pmtu(&*self.key_secret, mtu);
+                    }
+                }
+
+                /// Exports the key material so the key can be recreated with [`Self::import`]
+                pub fn export(&self) -> Option<DirectionalKey> {
+                    Some(DirectionalKey {
+                        key: Secret::new(&*self.key_secret)?,
+                        iv: Secret::new(self.iv.as_bytes())?,
+                        next_secret: Secret::new(&*self.next_secret)?,
+                    })
+                }
+
+                /// Recreates a key from exported key material
+                ///
+                /// Imported keys are never offloaded.
+                pub fn import(key: &DirectionalKey) -> Option<Self> {
+                    let mut key_secret = Zeroizing::new([0u8; KEY_LEN]);
+                    let mut iv = [0u8; iv::NONCE_LEN];
+                    let mut next_secret = Zeroizing::new([0u8; SECRET_LEN]);
+
+                    for (value, exported) in [
+                        (&mut key_secret[..], &key.key),
+                        (&mut iv[..], &key.iv),
+                        (&mut next_secret[..], &key.next_secret),
+                    ] {
+                        if value.len() != exported.as_bytes().len() {
+                            return None;
+                        }
+                        value.copy_from_slice(exported.as_bytes());
                     }
+
+                    Some(Self {
+                        key: Key::new(&*key_secret),
+                        key_secret,
+                        iv: iv::Iv::from_bytes(iv),
+                        next_secret,
+                        offload: None,
+                    })
                 }
 
                 fn new_key_secret(secret: &hkdf::Prk) -> Zeroizing<[u8; KEY_LEN]> {
@@ -105,19 +171,46 @@ macro_rules! impl_cipher_suite {
                     key
                 }
 
+                fn new_next_secret(secret: &hkdf::Prk) -> Zeroizing<[u8; SECRET_LEN]> {
+                    let mut next_secret = Zeroizing::new([0u8; SECRET_LEN]);
+
+                    secret
+                        .expand(&[&$key_update_label], $digest)
+                        .expect("label size verified")
+                        .fill(&mut next_secret.as_mut())
+                        .expect("fill size verified");
+
+                    next_secret
+                }
+
                 fn new_iv(secret: &hkdf::Prk) -> iv::Iv {
                     iv::Iv::new(secret, &$iv_label)
                 }
 
-                fn new_header_key(secret: &hkdf::Prk) -> HeaderKey {
-                    HeaderKey::new::<{ KEY_LEN }>(secret, &$hp_label, &$header_protection)
+                fn new_header_key(
+                    secret: &hkdf::Prk,
+                    binding: Option<&offload::Binding>,
+                ) -> HeaderKey {
+                    HeaderKey::new::<{ KEY_LEN }>(
+                        secret,
+                        &$hp_label,
+                        &$header_protection,
+                        binding.map(|binding| (binding, Self::CIPHER_SUITE)),
+                    )
                 }
+
+                const CIPHER_SUITE: s2n_quic_core::crypto::tls::CipherSuite =
+                    s2n_quic_core::crypto::tls::CipherSuite::$name;
             }
 
             impl Zeroize for $name {
                 fn zeroize(&mut self) {
+                    self.key_secret.zeroize();
                     self.iv.zeroize();
+                    self.next_secret.zeroize();
                     self.key.zeroize();
+                    // uninstall the key from the engine
+                    self.offload = None;
                 }
             }
 
@@ -129,6 +222,14 @@ macro_rules! impl_cipher_suite {
                     header: &[u8],
                     payload: &mut [u8],
                 ) -> Result<(), CryptoError> {
+                    if let Some(result) = self
+                        .offload
+                        .as_ref()
+                        .and_then(|key| key.decrypt(packet_number, header, payload))
+                    {
+                        return result;
+                    }
+
                     let nonce = self.iv.nonce(packet_number);
 
                     let payload_len = payload
@@ -158,6 +259,14 @@ macro_rules! impl_cipher_suite {
                     header: &[u8],
                     payload: &mut [u8],
                 ) -> Result<(), CryptoError> {
+                    if let Some(result) = self
+                        .offload
+                        .as_ref()
+                        .and_then(|key| key.encrypt(packet_number, header, payload))
+                    {
+                        return result;
+                    }
+
                     let nonce = self.iv.nonce(packet_number);
 
                     let payload_len = payload
@@ -201,7 +310,7 @@ macro_rules! impl_cipher_suite {
 
                 #[inline]
                 fn cipher_suite(&self) -> s2n_quic_core::crypto::tls::CipherSuite {
-                    s2n_quic_core::crypto::tls::CipherSuite::$name
+                    Self::CIPHER_SUITE
                 }
             }
 
@@ -236,6 +345,12 @@ macro_rules! impl_cipher_suite {
 
                 assert_eq!(KEY_LEN, $cipher.key_len(), "key len mismatch");
 
+                assert_eq!(
+                    SECRET_LEN,
+                    $digest.hmac_algorithm().digest_algorithm().output_len,
+                    "secret len mismatch"
+                );
+
                 assert_eq!(
                     compute_vec_label($cipher.key_len(), b"quic key"),
                     $key_label,
@@ -276,6 +391,7 @@ impl_cipher_suite!(
     TLS_AES_256_GCM_SHA384,
     aes256_gcm,
     hkdf::HKDF_SHA384,
+    384 / 8, // 384-bit secret
     aead::AES_256_GCM,
     256 / 8, // 256-bit key
     aead::quic::AES_256,
@@ -296,6 +412,7 @@ impl_cipher_suite!(
     TLS_CHACHA20_POLY1305_SHA256,
     chacha20_poly1305,
     hkdf::HKDF_SHA256,
+    256 / 8, // 256-bit secret
     aead::CHACHA20_POLY1305,
     256 / 8, // 256-bit key
     aead::quic::CHACHA20,
@@ -313,6 +430,7 @@ impl_cipher_suite!(
     TLS_AES_128_GCM_SHA256,
     aes128_gcm,
     hkdf::HKDF_SHA256,
+    256 / 8, // 256-bit secret
     aead::AES_128_GCM,
     128 / 8, // 128-bit key
     aead::quic::AES_128,
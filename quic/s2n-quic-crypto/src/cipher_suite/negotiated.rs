@@ -4,10 +4,11 @@
 use crate::{
     cipher_suite::{TLS_AES_128_GCM_SHA256, TLS_AES_256_GCM_SHA384, TLS_CHACHA20_POLY1305_SHA256},
     header_key::HeaderKey,
+    offload,
 };
 use core::fmt;
 use ring::{aead, hkdf};
-use s2n_quic_core::crypto::{self, CryptoError};
+use s2n_quic_core::crypto::{self, one_rtt::DirectionalKey, tls, CryptoError};
 
 // ignore casing warnings in order to preserve the IANA name
 #[allow(non_camel_case_types, clippy::all)]
@@ -48,18 +49,24 @@ impl From<TLS_AES_128_GCM_SHA256> for NegotiatedCipherSuite {
 
 impl NegotiatedCipherSuite {
     /// Create a cipher_suite with a given negotiated algorithm and secret
-    pub fn new(algorithm: &aead::Algorithm, secret: hkdf::Prk) -> Option<(Self, HeaderKey)> {
+    ///
+    /// The cipher_suite is offered to the offload engine, if any.
+    pub fn new(
+        algorithm: &aead::Algorithm,
+        secret: hkdf::Prk,
+        binding: Option<&offload::Binding>,
+    ) -> Option<(Self, HeaderKey)> {
         Some(match algorithm {
             _ if algorithm == &aead::AES_256_GCM => {
-                let (cipher_suite, header_key) = TLS_AES_256_GCM_SHA384::new(secret);
+                let (cipher_suite, header_key) = TLS_AES_256_GCM_SHA384::new(secret, binding);
                 (cipher_suite.into(), header_key)
             }
             _ if algorithm == &aead::CHACHA20_POLY1305 => {
-                let (cipher_suite, header_key) = TLS_CHACHA20_POLY1305_SHA256::new(secret);
+                let (cipher_suite, header_key) = TLS_CHACHA20_POLY1305_SHA256::new(secret, binding);
                 (cipher_suite.into(), header_key)
             }
             _ if algorithm == &aead::AES_128_GCM => {
-                let (cipher_suite, header_key) = TLS_AES_128_GCM_SHA256::new(secret);
+                let (cipher_suite, header_key) = TLS_AES_128_GCM_SHA256::new(secret, binding);
                 (cipher_suite.into(), header_key)
             }
             _ => return None,
@@ -76,6 +83,23 @@ impl NegotiatedCipherSuite {
     pub fn update_pmtu(&mut self, pmtu: u16) {
         dispatch!(self, |cipher| cipher.update_pmtu(pmtu))
     }
+
+    /// Exports the key material so the cipher_suite can be recreated with [`Self::import`]
+    pub fn export(&self) -> Option<DirectionalKey> {
+        dispatch!(self, |cipher| cipher.export())
+    }
+
+    /// Recreates a cipher_suite from exported key material
+    pub fn import(cipher_suite: tls::CipherSuite, key: &DirectionalKey) -> Option<Self> {
+        Some(match cipher_suite {
+            tls::CipherSuite::TLS_AES_256_GCM_SHA384 => TLS_AES_256_GCM_SHA384::import(key)?.into(),
+            tls::CipherSuite::TLS_CHACHA20_POLY1305_SHA256 => {
+                TLS_CHACHA20_POLY1305_SHA256::import(key)?.into()
+            }
+            tls::CipherSuite::TLS_AES_128_GCM_SHA256 => TLS_AES_128_GCM_SHA256::import(key)?.into(),
+            tls::CipherSuite::Unknown => return None,
+        })
+    }
 }
 
 impl crypto::Key for NegotiatedCipherSuite {
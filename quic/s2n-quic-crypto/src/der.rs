@@ -0,0 +1,135 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reads and writes the DER values of certificates
+
+pub const BOOLEAN: u8 = 0x01;
+pub const INTEGER: u8 = 0x02;
+pub const BIT_STRING: u8 = 0x03;
+pub const OCTET_STRING: u8 = 0x04;
+pub const OBJECT_IDENTIFIER: u8 = 0x06;
+pub const SEQUENCE: u8 = 0x30;
+/// The explicit tag of the version of a TBSCertificate
+pub const VERSION: u8 = 0xa0;
+/// The explicit tag of the extensions of a TBSCertificate
+pub const EXTENSIONS: u8 = 0xa3;
+
+/// A DER value
+#[derive(Clone, Copy, Debug)]
+pub struct Value<'a> {
+    pub tag: u8,
+    pub contents: &'a [u8],
+    /// The encoding of the value, including the tag and length
+    pub encoding: &'a [u8],
+}
+
+/// Reads the value at the front of the buffer and returns it along with the values which follow
+pub fn read(buffer: &[u8]) -> Option<(Value, &[u8])> {
+    let (&tag, after_tag) = buffer.split_first()?;
+    let (&len, mut after_len) = after_tag.split_first()?;
+
+    let len = if len < 0x80 {
+        len as usize
+    } else {
+        // the long form encodes the number of length bytes in the low bits
+        let count = (len & 0x7f) as usize;
+        if count == 0 || count > core::mem::size_of::<u32>() || after_len.len() < count {
+            return None;
+        }
+        let (bytes, remaining) = after_len.split_at(count);
+        after_len = remaining;
+        bytes
+            .iter()
+            .fold(0usize, |len, byte| (len << 8) | *byte as usize)
+    };
+
+    if after_len.len() < len {
+        return None;
+    }
+
+    let (contents, remaining) = after_len.split_at(len);
+    let encoding = &buffer[..buffer.len() - remaining.len()];
+    let value = Value {
+        tag,
+        contents,
+        encoding,
+    };
+
+    Some((value, remaining))
+}
+
+/// Splits the contents of a value with the expected tag from the values which follow it
+pub fn expect(buffer: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    let (value, remaining) = read(buffer)?;
+    if value.tag != tag {
+        return None;
+    }
+    Some((value.contents, remaining))
+}
+
+/// Reads all of the values in the buffer
+pub fn values(mut buffer: &[u8]) -> Option<Vec<Value>> {
+    let mut values = Vec::new();
+    while !buffer.is_empty() {
+        let (value, remaining) = read(buffer)?;
+        values.push(value);
+        buffer = remaining;
+    }
+    Some(values)
+}
+
+/// Appends the encoding of a value to `out`
+pub fn encode(tag: u8, contents: &[u8], out: &mut Vec<u8>) {
+    out.push(tag);
+
+    let len = contents.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes = (len as u32).to_be_bytes();
+        let skip = bytes.iter().take_while(|byte| **byte == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+
+    out.extend_from_slice(contents);
+}
+
+/// Returns the values in the TBSCertificate of a certificate
+pub fn tbs_certificate(certificate: &[u8]) -> Option<&[u8]> {
+    // Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signatureValue }
+    let (certificate, _) = expect(certificate, SEQUENCE)?;
+    let (tbs_certificate, _) = expect(certificate, SEQUENCE)?;
+    Some(tbs_certificate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_test() {
+        for len in [0, 1, 0x7f, 0x80, 0xff, 0x100, 0x1_0000] {
+            let contents = vec![42; len];
+            let mut encoding = Vec::new();
+            encode(OCTET_STRING, &contents, &mut encoding);
+            encoding.push(0);
+
+            let (value, remaining) = read(&encoding).unwrap();
+            assert_eq!(value.tag, OCTET_STRING);
+            assert_eq!(value.contents, &contents[..]);
+            assert_eq!(value.encoding, &encoding[..encoding.len() - 1]);
+            assert_eq!(remaining, &[0]);
+        }
+    }
+
+    #[test]
+    fn invalid_length_test() {
+        assert!(read(&[]).is_none());
+        assert!(read(&[SEQUENCE]).is_none());
+        assert!(read(&[SEQUENCE, 2, 0]).is_none());
+        assert!(read(&[SEQUENCE, 0x80]).is_none());
+        assert!(read(&[SEQUENCE, 0x84, 0xff, 0xff, 0xff, 0xff]).is_none());
+        assert!(read(&[SEQUENCE, 0x85, 0, 0, 0, 0, 0]).is_none());
+    }
+}
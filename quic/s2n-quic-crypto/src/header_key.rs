@@ -1,11 +1,22 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::offload;
 use core::fmt;
 use ring::{aead, hkdf};
-use s2n_quic_core::crypto::{self, HeaderProtectionMask};
-
-pub struct HeaderKey(pub(crate) aead::quic::HeaderProtectionKey);
+use s2n_quic_core::crypto::{
+    self,
+    one_rtt::{ExportedHeaderKey, Secret},
+    tls::CipherSuite,
+    HeaderProtectionMask,
+};
+
+pub struct HeaderKey {
+    key: aead::quic::HeaderProtectionKey,
+    /// The key material, which is retained for exporting the key
+    secret: Secret,
+    offload: Option<Box<dyn offload::HeaderKey>>,
+}
 
 impl crypto::HeaderKey for HeaderKey {
     #[inline]
@@ -15,7 +26,7 @@ impl crypto::HeaderKey for HeaderKey {
 
     #[inline]
     fn opening_sample_len(&self) -> usize {
-        self.0.algorithm().sample_len()
+        self.key.algorithm().sample_len()
     }
 
     #[inline]
@@ -25,7 +36,7 @@ impl crypto::HeaderKey for HeaderKey {
 
     #[inline]
     fn sealing_sample_len(&self) -> usize {
-        self.0.algorithm().sample_len()
+        self.key.algorithm().sample_len()
     }
 }
 
@@ -34,6 +45,7 @@ impl HeaderKey {
         secret: &hkdf::Prk,
         label: &[u8],
         alg: &'static aead::quic::Algorithm,
+        offload: Option<(&offload::Binding, CipherSuite)>,
     ) -> Self {
         let mut bytes = zeroize::Zeroizing::new([0u8; KEY_LEN]);
 
@@ -43,14 +55,48 @@ impl HeaderKey {
             .fill(bytes.as_mut())
             .expect("fill size verified");
 
+        let offload = offload.and_then(|(binding, cipher_suite)| {
+            let info = offload::HeaderKeyInfo {
+                cipher_suite,
+                direction: binding.direction,
+                key: bytes.as_ref(),
+            };
+            binding.engine.new_header_key(&info)
+        });
+
         let key = aead::quic::HeaderProtectionKey::new(alg, bytes.as_ref())
             .expect("header secret length already checked");
-        Self(key)
+        let secret = Secret::new(bytes.as_ref()).expect("header secret length already checked");
+        Self {
+            key,
+            secret,
+            offload,
+        }
+    }
+
+    /// Recreates a key from exported key material
+    ///
+    /// Imported keys are never offloaded.
+    pub fn import(alg: &'static aead::quic::Algorithm, secret: &Secret) -> Option<Self> {
+        let key = aead::quic::HeaderProtectionKey::new(alg, secret.as_bytes()).ok()?;
+        Some(Self {
+            key,
+            secret: secret.clone(),
+            offload: None,
+        })
     }
 
     #[inline]
     fn header_protection_mask(&self, sample: &[u8]) -> HeaderProtectionMask {
-        self.0
+        if let Some(mask) = self
+            .offload
+            .as_ref()
+            .and_then(|key| key.header_protection_mask(sample))
+        {
+            return mask;
+        }
+
+        self.key
             .new_mask(sample)
             .expect("sample length already checked")
     }
@@ -62,18 +108,49 @@ impl fmt::Debug for HeaderKey {
     }
 }
 
-impl From<aead::quic::HeaderProtectionKey> for HeaderKey {
-    fn from(key: aead::quic::HeaderProtectionKey) -> Self {
-        Self(key)
-    }
-}
-
 #[derive(Debug)]
 pub struct HeaderKeyPair {
     pub(crate) sealer: HeaderKey,
     pub(crate) opener: HeaderKey,
 }
 
+impl HeaderKeyPair {
+    /// Exports the key material so the keys can be recreated with [`Self::import`]
+    pub fn export(&self) -> Option<ExportedHeaderKey> {
+        let alg = self.sealer.key.algorithm();
+        let cipher_suite = if alg == &aead::quic::AES_128 {
+            CipherSuite::TLS_AES_128_GCM_SHA256
+        } else if alg == &aead::quic::AES_256 {
+            CipherSuite::TLS_AES_256_GCM_SHA384
+        } else if alg == &aead::quic::CHACHA20 {
+            CipherSuite::TLS_CHACHA20_POLY1305_SHA256
+        } else {
+            return None;
+        };
+
+        Some(ExportedHeaderKey {
+            cipher_suite,
+            sealer: self.sealer.secret.clone(),
+            opener: self.opener.secret.clone(),
+        })
+    }
+
+    /// Recreates the keys from exported key material
+    pub fn import(key: &ExportedHeaderKey) -> Option<Self> {
+        let alg = match key.cipher_suite {
+            CipherSuite::TLS_AES_128_GCM_SHA256 => &aead::quic::AES_128,
+            CipherSuite::TLS_AES_256_GCM_SHA384 => &aead::quic::AES_256,
+            CipherSuite::TLS_CHACHA20_POLY1305_SHA256 => &aead::quic::CHACHA20,
+            CipherSuite::Unknown => return None,
+        };
+
+        Some(Self {
+            sealer: HeaderKey::import(alg, &key.sealer)?,
+            opener: HeaderKey::import(alg, &key.opener)?,
+        })
+    }
+}
+
 impl crypto::HeaderKey for HeaderKeyPair {
     #[inline]
     fn opening_header_protection_mask(&self, sample: &[u8]) -> HeaderProtectionMask {
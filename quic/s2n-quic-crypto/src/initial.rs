@@ -1,7 +1,11 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{cipher_suite::TLS_AES_128_GCM_SHA256 as CipherSuite, header_key::HeaderKeyPair};
+use crate::{
+    cipher_suite::TLS_AES_128_GCM_SHA256 as CipherSuite,
+    header_key::HeaderKeyPair,
+    offload::{self, Direction, Offload},
+};
 use ring::hkdf;
 use s2n_quic_core::{
     crypto::{
@@ -11,6 +15,7 @@ use s2n_quic_core::{
     },
     endpoint,
 };
+use std::sync::Arc;
 
 header_key!(InitialHeaderKey);
 
@@ -28,7 +33,23 @@ lazy_static::lazy_static! {
 }
 
 impl InitialKey {
-    fn new(endpoint: endpoint::Type, connection_id: &[u8]) -> (Self, InitialHeaderKey) {
+    /// Create the Initial keys for the connection ID and delegate packet protection to an
+    /// offload engine
+    ///
+    /// Keys the engine declines are used in software.
+    pub fn new_with_offload(
+        endpoint: endpoint::Type,
+        connection_id: &[u8],
+        offload: &Arc<dyn Offload>,
+    ) -> (Self, InitialHeaderKey) {
+        Self::new(endpoint, connection_id, Some(offload))
+    }
+
+    fn new(
+        endpoint: endpoint::Type,
+        connection_id: &[u8],
+        offload: Option<&Arc<dyn Offload>>,
+    ) -> (Self, InitialHeaderKey) {
         let initial_secret = INITIAL_SIGNING_KEY.extract(connection_id);
         let digest = INITIAL_SIGNING_KEY.algorithm();
 
@@ -42,19 +63,16 @@ impl InitialKey {
             .expect("label size verified")
             .into();
 
-        let (sealer, opener) = match endpoint {
-            endpoint::Type::Client => (
-                CipherSuite::new(client_secret),
-                CipherSuite::new(server_secret),
-            ),
-            endpoint::Type::Server => (
-                CipherSuite::new(server_secret),
-                CipherSuite::new(client_secret),
-            ),
+        let (sealer_secret, opener_secret) = match endpoint {
+            endpoint::Type::Client => (client_secret, server_secret),
+            endpoint::Type::Server => (server_secret, client_secret),
         };
 
-        let (key_sealer, header_sealer) = sealer;
-        let (key_opener, header_opener) = opener;
+        let binding = |direction| offload.map(|engine| offload::Binding::new(engine, direction));
+        let (key_sealer, header_sealer) =
+            CipherSuite::new(sealer_secret, binding(Direction::Seal).as_ref());
+        let (key_opener, header_opener) =
+            CipherSuite::new(opener_secret, binding(Direction::Open).as_ref());
         let key = Self {
             sealer: key_sealer,
             opener: key_opener,
@@ -72,11 +90,11 @@ impl crypto::InitialKey for InitialKey {
     type HeaderKey = InitialHeaderKey;
 
     fn new_server(connection_id: &[u8]) -> (Self, Self::HeaderKey) {
-        Self::new(endpoint::Type::Server, connection_id)
+        Self::new(endpoint::Type::Server, connection_id, None)
     }
 
     fn new_client(connection_id: &[u8]) -> (Self, Self::HeaderKey) {
-        Self::new(endpoint::Type::Client, connection_id)
+        Self::new(endpoint::Type::Client, connection_id, None)
     }
 }
 
@@ -23,6 +23,16 @@ impl Iv {
         Self(bytes)
     }
 
+    #[inline]
+    pub fn from_bytes(bytes: [u8; NONCE_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; NONCE_LEN] {
+        &self.0
+    }
+
     #[inline]
     pub fn nonce(&self, packet_number: u64) -> [u8; NONCE_LEN] {
         let mut nonce = [0; NONCE_LEN];
@@ -31,6 +31,7 @@ mod arch;
 mod block;
 mod cipher_suite;
 mod ctr;
+mod der;
 mod ghash;
 mod iv;
 
@@ -49,8 +50,11 @@ pub struct SecretPair {
 
 pub mod handshake;
 pub mod initial;
+pub mod offload;
 pub mod one_rtt;
 pub mod retry;
+pub mod sct;
+pub mod spki;
 pub mod zero_rtt;
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -2,13 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    cipher_suite::NegotiatedCipherSuite as CipherSuite, header_key::HeaderKeyPair, Algorithm,
-    SecretPair,
+    cipher_suite::NegotiatedCipherSuite as CipherSuite,
+    header_key::HeaderKeyPair,
+    offload::{self, Direction, Offload},
+    Algorithm, SecretPair,
 };
 use s2n_quic_core::{
-    crypto::{CryptoError, Key},
+    crypto::{one_rtt::ExportedKey, CryptoError, Key},
     endpoint,
 };
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct KeyPair {
@@ -21,14 +24,18 @@ impl KeyPair {
         endpoint: endpoint::Type,
         algorithm: &Algorithm,
         secrets: SecretPair,
+        offload: Option<&Arc<dyn Offload>>,
     ) -> Option<(Self, HeaderKeyPair)> {
         let (sealer_secret, opener_secret) = match endpoint {
             endpoint::Type::Client => (secrets.client, secrets.server),
             endpoint::Type::Server => (secrets.server, secrets.client),
         };
 
-        let (sealer, header_sealer) = CipherSuite::new(algorithm, sealer_secret)?;
-        let (opener, header_opener) = CipherSuite::new(algorithm, opener_secret)?;
+        let binding = |direction| offload.map(|engine| offload::Binding::new(engine, direction));
+        let (sealer, header_sealer) =
+            CipherSuite::new(algorithm, sealer_secret, binding(Direction::Seal).as_ref())?;
+        let (opener, header_opener) =
+            CipherSuite::new(algorithm, opener_secret, binding(Direction::Open).as_ref())?;
 
         let key = Self { sealer, opener };
         let header_key = HeaderKeyPair {
@@ -48,6 +55,23 @@ impl KeyPair {
             opener: self.opener.update(),
         }
     }
+
+    /// Exports the key material so the keys can be recreated with [`Self::import`]
+    pub fn export(&self) -> Option<ExportedKey> {
+        Some(ExportedKey {
+            cipher_suite: self.cipher_suite(),
+            sealer: self.sealer.export()?,
+            opener: self.opener.export()?,
+        })
+    }
+
+    /// Recreates the keys from exported key material
+    pub fn import(key: &ExportedKey) -> Option<Self> {
+        Some(Self {
+            sealer: CipherSuite::import(key.cipher_suite, &key.sealer)?,
+            opener: CipherSuite::import(key.cipher_suite, &key.opener)?,
+        })
+    }
 }
 
 impl Key for KeyPair {
@@ -119,9 +143,31 @@ macro_rules! negotiated_crypto {
                 endpoint: s2n_quic_core::endpoint::Type,
                 algorithm: &$crate::Algorithm,
                 secrets: $crate::SecretPair,
+            ) -> Option<(Self, $header_key)> {
+                Self::new_keys(endpoint, algorithm, secrets, None)
+            }
+
+            /// Create a cipher_suite which delegates packet protection to an offload engine
+            ///
+            /// Keys the engine declines, including the ones derived by key updates, are used in
+            /// software.
+            pub fn new_with_offload(
+                endpoint: s2n_quic_core::endpoint::Type,
+                algorithm: &$crate::Algorithm,
+                secrets: $crate::SecretPair,
+                offload: &std::sync::Arc<dyn $crate::offload::Offload>,
+            ) -> Option<(Self, $header_key)> {
+                Self::new_keys(endpoint, algorithm, secrets, Some(offload))
+            }
+
+            fn new_keys(
+                endpoint: s2n_quic_core::endpoint::Type,
+                algorithm: &$crate::Algorithm,
+                secrets: $crate::SecretPair,
+                offload: Option<&std::sync::Arc<dyn $crate::offload::Offload>>,
             ) -> Option<(Self, $header_key)> {
                 let (key, header_key) =
-                    crate::negotiated::KeyPair::new(endpoint, algorithm, secrets)?;
+                    crate::negotiated::KeyPair::new(endpoint, algorithm, secrets, offload)?;
 
                 let key = Self(key);
                 let header_key = $header_key::from(header_key);
@@ -0,0 +1,425 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Delegates packet protection to external hardware
+//!
+//! An [`Offload`] engine, such as a NIC with inline QUIC crypto, is asked for a key each time
+//! the Initial, Handshake or 1-RTT keys of a connection are derived, including after each key
+//! update. Engines can decline a key by returning `None`, and installed keys can refuse to
+//! protect individual packets by returning [`Error::Unavailable`]. In both cases the packet is
+//! protected in software instead, so engines are free to only support some of the cipher suites
+//! or to run out of capacity.
+
+use crate::iv::NONCE_LEN;
+use core::fmt;
+use s2n_quic_core::crypto::{tls::CipherSuite, CryptoError, HeaderProtectionMask};
+use std::{
+    panic::{RefUnwindSafe, UnwindSafe},
+    sync::Arc,
+};
+
+/// Whether a key protects outgoing or incoming packets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// The key encrypts packets which are sent to the peer
+    Seal,
+    /// The key decrypts packets which are received from the peer
+    Open,
+}
+
+/// The key material of a packet protection key
+#[non_exhaustive]
+pub struct PacketKeyInfo<'a> {
+    pub cipher_suite: CipherSuite,
+    pub direction: Direction,
+    /// The AEAD key
+    pub key: &'a [u8],
+    /// The IV which is combined with the packet number to form the nonce
+    pub iv: &'a [u8; NONCE_LEN],
+}
+
+/// The key material of a header protection key
+#[non_exhaustive]
+pub struct HeaderKeyInfo<'a> {
+    pub cipher_suite: CipherSuite,
+    pub direction: Direction,
+    pub key: &'a [u8],
+}
+
+/// An error returned by an offloaded key
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The packet can't be processed by the engine and should be protected in software
+    ///
+    /// The payload must be left untouched when this is returned.
+    Unavailable,
+    /// The packet failed to be protected, for example because it failed authentication
+    Crypto(CryptoError),
+}
+
+impl From<CryptoError> for Error {
+    fn from(error: CryptoError) -> Self {
+        Self::Crypto(error)
+    }
+}
+
+/// A packet protection key which is installed in an [`Offload`] engine
+pub trait PacketKey: 'static + Send + Sync + RefUnwindSafe + UnwindSafe {
+    /// Encrypts the payload in place, followed by the authentication tag
+    ///
+    /// The same arguments are passed as for [`s2n_quic_core::crypto::Key::encrypt`].
+    fn encrypt(&self, packet_number: u64, header: &[u8], payload: &mut [u8]) -> Result<(), Error>;
+
+    /// Decrypts the payload in place, which is followed by the authentication tag
+    ///
+    /// The same arguments are passed as for [`s2n_quic_core::crypto::Key::decrypt`].
+    fn decrypt(&self, packet_number: u64, header: &[u8], payload: &mut [u8]) -> Result<(), Error>;
+}
+
+/// A header protection key which is installed in an [`Offload`] engine
+pub trait HeaderKey: 'static + Send + Sync + RefUnwindSafe + UnwindSafe {
+    /// Returns the header protection mask for the given sample
+    ///
+    /// Returning `None` computes the mask in software instead.
+    fn header_protection_mask(&self, sample: &[u8]) -> Option<HeaderProtectionMask>;
+}
+
+/// Installs packet protection keys in external hardware
+///
+/// ```rust
+/// use s2n_quic_crypto::offload::{Offload, PacketKey, PacketKeyInfo};
+///
+/// /// An engine which leaves packet protection to software
+/// struct Software;
+///
+/// impl Offload for Software {
+///     fn new_packet_key(&self, _info: &PacketKeyInfo) -> Option<Box<dyn PacketKey>> {
+///         None
+///     }
+/// }
+/// ```
+pub trait Offload: 'static + Send + Sync + RefUnwindSafe + UnwindSafe {
+    /// Called each time a packet protection key is derived
+    ///
+    /// Returns `None` if the key can't be installed, in which case it's used in software.
+    fn new_packet_key(&self, info: &PacketKeyInfo) -> Option<Box<dyn PacketKey>>;
+
+    /// Called each time a header protection key is derived
+    ///
+    /// Returns `None` if the key can't be installed, in which case it's used in software.
+    fn new_header_key(&self, info: &HeaderKeyInfo) -> Option<Box<dyn HeaderKey>> {
+        let _ = info;
+        None
+    }
+}
+
+/// The engine which is asked for the keys of one direction
+#[derive(Clone)]
+pub(crate) struct Binding {
+    pub engine: Arc<dyn Offload>,
+    pub direction: Direction,
+}
+
+impl Binding {
+    pub fn new(engine: &Arc<dyn Offload>, direction: Direction) -> Self {
+        Self {
+            engine: engine.clone(),
+            direction,
+        }
+    }
+}
+
+/// A packet protection key which may be installed in an engine
+///
+/// The binding is kept for keys the engine declined, so the keys derived by a key update are
+/// offered to the engine again.
+pub(crate) struct Key {
+    binding: Binding,
+    key: Option<Box<dyn PacketKey>>,
+}
+
+impl Key {
+    pub fn new(
+        binding: &Binding,
+        cipher_suite: CipherSuite,
+        key: &[u8],
+        iv: &[u8; NONCE_LEN],
+    ) -> Self {
+        let info = PacketKeyInfo {
+            cipher_suite,
+            direction: binding.direction,
+            key,
+            iv,
+        };
+        Self {
+            key: binding.engine.new_packet_key(&info),
+            binding: binding.clone(),
+        }
+    }
+
+    #[inline]
+    pub fn binding(&self) -> &Binding {
+        &self.binding
+    }
+
+    /// Returns `None` if the packet needs to be encrypted in software
+    #[inline]
+    pub fn encrypt(
+        &self,
+        packet_number: u64,
+        header: &[u8],
+        payload: &mut [u8],
+    ) -> Option<Result<(), CryptoError>> {
+        let key = self.key.as_ref()?;
+        match key.encrypt(packet_number, header, payload) {
+            Ok(()) => Some(Ok(())),
+            Err(Error::Crypto(error)) => Some(Err(error)),
+            Err(Error::Unavailable) => None,
+        }
+    }
+
+    /// Returns `None` if the packet needs to be decrypted in software
+    #[inline]
+    pub fn decrypt(
+        &self,
+        packet_number: u64,
+        header: &[u8],
+        payload: &mut [u8],
+    ) -> Option<Result<(), CryptoError>> {
+        let key = self.key.as_ref()?;
+        match key.decrypt(packet_number, header, payload) {
+            Ok(()) => Some(Ok(())),
+            Err(Error::Crypto(error)) => Some(Err(error)),
+            Err(Error::Unavailable) => None,
+        }
+    }
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Key")
+            .field("direction", &self.binding.direction)
+            .field("is_installed", &self.key.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{initial::InitialKey, one_rtt::OneRttKey, SecretPair};
+    use ring::{aead, hkdf};
+    use s2n_quic_core::{
+        crypto::{initial::EXAMPLE_DCID, HeaderKey as _, InitialKey as _, OneRttKey as _},
+        endpoint,
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    /// Records the key material and protects every other packet with it
+    #[derive(Default)]
+    struct Engine {
+        packet_keys: Mutex<Vec<(Direction, usize)>>,
+        header_keys: AtomicUsize,
+        protected_packets: Arc<AtomicUsize>,
+    }
+
+    impl Offload for Engine {
+        fn new_packet_key(&self, info: &PacketKeyInfo) -> Option<Box<dyn PacketKey>> {
+            self.packet_keys
+                .lock()
+                .unwrap()
+                .push((info.direction, info.key.len()));
+
+            let algorithm = match info.cipher_suite {
+                CipherSuite::TLS_AES_128_GCM_SHA256 => &aead::AES_128_GCM,
+                CipherSuite::TLS_AES_256_GCM_SHA384 => &aead::AES_256_GCM,
+                // the engine doesn't support the cipher suite
+                _ => return None,
+            };
+            let key = aead::UnboundKey::new(algorithm, info.key).unwrap();
+
+            Some(Box::new(EngineKey {
+                key: aead::LessSafeKey::new(key),
+                iv: *info.iv,
+                protected_packets: self.protected_packets.clone(),
+            }))
+        }
+
+        fn new_header_key(&self, _info: &HeaderKeyInfo) -> Option<Box<dyn HeaderKey>> {
+            self.header_keys.fetch_add(1, Ordering::Relaxed);
+            Some(Box::new(EngineHeaderKey))
+        }
+    }
+
+    struct EngineKey {
+        key: aead::LessSafeKey,
+        iv: [u8; NONCE_LEN],
+        protected_packets: Arc<AtomicUsize>,
+    }
+
+    impl EngineKey {
+        fn nonce(&self, packet_number: u64) -> Result<aead::Nonce, Error> {
+            // odd packets are left to software
+            if packet_number % 2 == 1 {
+                return Err(Error::Unavailable);
+            }
+
+            self.protected_packets.fetch_add(1, Ordering::Relaxed);
+
+            let mut nonce = self.iv;
+            for (a, b) in nonce[4..]
+                .iter_mut()
+                .zip(packet_number.to_be_bytes().iter())
+            {
+                *a ^= b;
+            }
+            Ok(aead::Nonce::assume_unique_for_key(nonce))
+        }
+    }
+
+    impl PacketKey for EngineKey {
+        fn encrypt(
+            &self,
+            packet_number: u64,
+            header: &[u8],
+            payload: &mut [u8],
+        ) -> Result<(), Error> {
+            let nonce = self.nonce(packet_number)?;
+            let (payload, tag) = payload.split_at_mut(payload.len() - aead::MAX_TAG_LEN);
+            let computed = self
+                .key
+                .seal_in_place_separate_tag(nonce, aead::Aad::from(header), payload)
+                .map_err(|_| CryptoError::INTERNAL_ERROR)?;
+            tag.copy_from_slice(computed.as_ref());
+            Ok(())
+        }
+
+        fn decrypt(
+            &self,
+            packet_number: u64,
+            header: &[u8],
+            payload: &mut [u8],
+        ) -> Result<(), Error> {
+            let nonce = self.nonce(packet_number)?;
+            self.key
+                .open_in_place(nonce, aead::Aad::from(header), payload)
+                .map_err(|_| CryptoError::DECRYPT_ERROR)?;
+            Ok(())
+        }
+    }
+
+    /// Refuses every sample so header protection falls back to software
+    struct EngineHeaderKey;
+
+    impl HeaderKey for EngineHeaderKey {
+        fn header_protection_mask(&self, _sample: &[u8]) -> Option<HeaderProtectionMask> {
+            None
+        }
+    }
+
+    fn round_trip<K: s2n_quic_core::crypto::Key>(sealer: &K, opener: &K, packet_number: u64) {
+        let header = [1, 2, 3];
+        let cleartext = [42u8; 32];
+        let mut payload = [0u8; 32 + aead::MAX_TAG_LEN];
+        payload[..32].copy_from_slice(&cleartext);
+
+        sealer
+            .encrypt(packet_number, &header, &mut payload)
+            .unwrap();
+        assert_ne!(&payload[..32], &cleartext[..]);
+        opener
+            .decrypt(packet_number, &header, &mut payload)
+            .unwrap();
+        assert_eq!(&payload[..32], &cleartext[..]);
+
+        // tampered packets are rejected regardless of where they are decrypted
+        sealer
+            .encrypt(packet_number, &header, &mut payload)
+            .unwrap();
+        payload[0] ^= 1;
+        assert!(opener
+            .decrypt(packet_number, &header, &mut payload)
+            .is_err());
+    }
+
+    #[test]
+    fn initial_test() {
+        let engine = Arc::new(Engine::default());
+        let offload: Arc<dyn Offload> = engine.clone();
+
+        let (client, client_header) =
+            InitialKey::new_with_offload(endpoint::Type::Client, &EXAMPLE_DCID, &offload);
+        // the server protects its packets in software
+        let (server, server_header) = InitialKey::new_server(&EXAMPLE_DCID);
+
+        assert_eq!(
+            *engine.packet_keys.lock().unwrap(),
+            [(Direction::Seal, 16), (Direction::Open, 16)]
+        );
+        assert_eq!(engine.header_keys.load(Ordering::Relaxed), 2);
+
+        for packet_number in 0..4 {
+            round_trip(&client, &server, packet_number);
+            round_trip(&server, &client, packet_number);
+        }
+        // only the even packets were protected by the engine
+        assert_eq!(engine.protected_packets.load(Ordering::Relaxed), 8);
+
+        // header protection fell back to software
+        let sample = [7u8; 16];
+        assert_eq!(
+            client_header.sealing_header_protection_mask(&sample),
+            server_header.opening_header_protection_mask(&sample)
+        );
+    }
+
+    #[test]
+    fn key_update_test() {
+        let engine = Arc::new(Engine::default());
+        let offload: Arc<dyn Offload> = engine.clone();
+
+        let secrets = |secret: &[u8]| SecretPair {
+            server: hkdf::Prk::new_less_safe(hkdf::HKDF_SHA256, secret),
+            client: hkdf::Prk::new_less_safe(hkdf::HKDF_SHA256, &[secret, secret].concat()),
+        };
+
+        let (client, _) = OneRttKey::new_with_offload(
+            endpoint::Type::Client,
+            &aead::AES_128_GCM,
+            secrets(&[1; 32]),
+            &offload,
+        )
+        .unwrap();
+        let (server, _) = OneRttKey::new_server(&aead::AES_128_GCM, secrets(&[1; 32])).unwrap();
+
+        round_trip(&client, &server, 0);
+        assert_eq!(engine.protected_packets.load(Ordering::Relaxed), 2);
+
+        // the updated keys are installed as well
+        let client = client.derive_next_key();
+        let server = server.derive_next_key();
+        assert_eq!(engine.packet_keys.lock().unwrap().len(), 4);
+
+        round_trip(&client, &server, 2);
+        round_trip(&server, &client, 2);
+        assert_eq!(engine.protected_packets.load(Ordering::Relaxed), 6);
+
+        // keys the engine declined are protected in software
+        let (client, _) = OneRttKey::new_with_offload(
+            endpoint::Type::Client,
+            &aead::CHACHA20_POLY1305,
+            secrets(&[2; 32]),
+            &offload,
+        )
+        .unwrap();
+        let (server, _) =
+            OneRttKey::new_server(&aead::CHACHA20_POLY1305, secrets(&[2; 32])).unwrap();
+        assert_eq!(engine.packet_keys.lock().unwrap().len(), 6);
+
+        round_trip(&client, &server, 0);
+        assert_eq!(engine.protected_packets.load(Ordering::Relaxed), 6);
+    }
+}
@@ -1,7 +1,11 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use s2n_quic_core::crypto;
+use crate::{header_key::HeaderKeyPair, negotiated::KeyPair};
+use s2n_quic_core::crypto::{
+    self,
+    one_rtt::{ExportedHeaderKey, ExportedKey},
+};
 
 header_key!(OneRttHeaderKey);
 negotiated_crypto!(OneRttKey, OneRttHeaderKey);
@@ -22,9 +26,29 @@ impl crypto::OneRttKey for OneRttKey {
     fn update_opener_pmtu(&mut self, pmtu: u16) {
         self.0.opener.update_pmtu(pmtu)
     }
+
+    #[inline]
+    fn export(&self) -> Option<ExportedKey> {
+        self.0.export()
+    }
+
+    #[inline]
+    fn import(key: &ExportedKey) -> Option<Self> {
+        KeyPair::import(key).map(Self)
+    }
 }
 
-impl crypto::OneRttHeaderKey for OneRttHeaderKey {}
+impl crypto::OneRttHeaderKey for OneRttHeaderKey {
+    #[inline]
+    fn export(&self) -> Option<ExportedHeaderKey> {
+        self.0.export()
+    }
+
+    #[inline]
+    fn import(key: &ExportedHeaderKey) -> Option<Self> {
+        HeaderKeyPair::import(key).map(Self)
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -75,14 +99,14 @@ mod tests {
     ) -> (TLS_CHACHA20_POLY1305_SHA256, TLS_CHACHA20_POLY1305_SHA256) {
         // Create a cipher based on the initial secret
         let key = hkdf::Prk::new_less_safe(hkdf::HKDF_SHA256, secret);
-        let cipher = TLS_CHACHA20_POLY1305_SHA256::new(key);
+        let cipher = TLS_CHACHA20_POLY1305_SHA256::new(key, None);
 
         // Create the cipher after a Key Update has occurred
         let next_cipher = cipher.0.update();
 
         // Create a cipher based on the expected post-update secret
         let next_key = hkdf::Prk::new_less_safe(hkdf::HKDF_SHA256, next_secret);
-        let expected_next_cipher = TLS_CHACHA20_POLY1305_SHA256::new(next_key);
+        let expected_next_cipher = TLS_CHACHA20_POLY1305_SHA256::new(next_key, None);
 
         (next_cipher, expected_next_cipher.0)
     }
@@ -104,6 +128,59 @@ mod tests {
         assert_eq!(next_cipher_output, expected_cipher_output);
     }
 
+    #[test]
+    fn test_export_import() {
+        use super::{OneRttHeaderKey, OneRttKey};
+        use crate::SecretPair;
+        use ring::aead;
+        use s2n_quic_core::crypto::{HeaderKey as _, OneRttHeaderKey as _, OneRttKey as _};
+
+        for algorithm in [
+            &aead::AES_128_GCM,
+            &aead::AES_256_GCM,
+            &aead::CHACHA20_POLY1305,
+        ] {
+            let digest = if algorithm == &aead::AES_256_GCM {
+                hkdf::HKDF_SHA384
+            } else {
+                hkdf::HKDF_SHA256
+            };
+            let secrets = SecretPair {
+                server: hkdf::Prk::new_less_safe(digest, &SECRET),
+                client: hkdf::Prk::new_less_safe(digest, &KU_SECRET),
+            };
+            let (key, header_key) = OneRttKey::new_server(algorithm, secrets).unwrap();
+
+            let imported = OneRttKey::import(&key.export().unwrap()).unwrap();
+            let imported_header_key =
+                OneRttHeaderKey::import(&header_key.export().unwrap()).unwrap();
+
+            // the imported keys must also derive the same keys after a key update
+            let next_key = key.derive_next_key();
+            let imported_next_key = imported.derive_next_key();
+
+            for (key, imported) in [(&key, &imported), (&next_key, &imported_next_key)] {
+                let mut expected = [0; 32];
+                let mut actual = [0; 32];
+                key.encrypt(1, &[], &mut expected[..]).unwrap();
+                imported.encrypt(1, &[], &mut actual[..]).unwrap();
+                assert_eq!(expected, actual);
+
+                imported.decrypt(1, &[], &mut expected[..]).unwrap_err();
+            }
+
+            let sample = [1; 16];
+            assert_eq!(
+                header_key.sealing_header_protection_mask(&sample),
+                imported_header_key.sealing_header_protection_mask(&sample)
+            );
+            assert_eq!(
+                header_key.opening_header_protection_mask(&sample),
+                imported_header_key.opening_header_protection_mask(&sample)
+            );
+        }
+    }
+
     #[test]
     fn test_key_update_failure() {
         let (next_cipher, expected_next_cipher) = generate_ciphers(&INVALID_SECRET, &KU_SECRET);
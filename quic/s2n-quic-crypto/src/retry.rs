@@ -114,6 +114,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_packet_tool() {
+        let packet = packet::tool::Retry::new(
+            &retry::example::DCID,
+            &retry::example::SCID,
+            &retry::example::TOKEN,
+        );
+
+        let mut buf = [0u8; retry::example::PACKET_LEN];
+        let len = packet
+            .encode::<RetryKey>(&retry::example::ODCID, &mut buf)
+            .unwrap();
+        assert_eq!(&buf[..len], &retry::example::PACKET[..]);
+
+        let (packet, _) = packet::tool::Retry::decode(&mut buf).unwrap();
+        assert!(packet.validate::<RetryKey>(&retry::example::ODCID).is_ok());
+        assert!(packet.validate::<RetryKey>(&retry::example::SCID).is_err());
+    }
+
     #[test]
     #[should_panic]
     fn test_odcid_different_from_local_cid() {
@@ -0,0 +1,476 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Enforces a Certificate Transparency policy on the certificates presented by servers
+//!
+//! Certificate Transparency logs publish the certificates issued by CAs, so misissued
+//! certificates can be detected. Each log which accepted a certificate returns a Signed
+//! Certificate Timestamp (SCT), which CAs embed in the certificate they issue. A [`Policy`]
+//! requires the end-entity certificate to embed valid SCTs from a minimum number of distinct
+//! known [`Log`]s. SCTs which are delivered in the TLS handshake or in OCSP responses are not
+//! considered.
+//!
+//! Each [`Verification`] reports how many of the embedded SCTs could be verified, so clients can
+//! also monitor their servers without enforcing a policy, by requiring zero logs.
+
+use crate::der;
+use core::fmt;
+use ring::{digest, signature};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The length of a log ID
+pub const LOG_ID_LEN: usize = 32;
+
+/// The SHA-256 digest of the DER-encoded public key of a log
+pub type LogId = [u8; LOG_ID_LEN];
+
+/// The OID of the embedded SCT list extension, 1.3.6.1.4.1.11129.2.4.2
+const SCT_LIST_OID: &[u8] = &[0x2b, 0x06, 0x01, 0x04, 0x01, 0xd6, 0x79, 0x02, 0x04, 0x02];
+/// The OID of elliptic curve public keys, 1.2.840.10045.2.1
+const EC_PUBLIC_KEY_OID: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+/// The OID of the P-256 curve, 1.2.840.10045.3.1.7
+const P256_OID: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+/// The OID of RSA public keys, 1.2.840.113549.1.1.1
+const RSA_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+
+/// The `sha256` hash algorithm of a TLS `DigitallySigned` struct
+const SHA256: u8 = 4;
+/// The `rsa` signature algorithm of a TLS `DigitallySigned` struct
+const RSA: u8 = 1;
+/// The `ecdsa` signature algorithm of a TLS `DigitallySigned` struct
+const ECDSA: u8 = 3;
+
+/// A Certificate Transparency log
+#[derive(Clone)]
+pub struct Log {
+    id: LogId,
+    public_key: Vec<u8>,
+    signature_algorithm: u8,
+}
+
+impl fmt::Debug for Log {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Log").field("id", &self.id).finish()
+    }
+}
+
+impl Log {
+    /// Creates a log from its DER-encoded SubjectPublicKeyInfo, as published in the log lists
+    ///
+    /// Returns `None` if the key isn't a P-256 or RSA key, which are the only keys logs use.
+    pub fn new(public_key: &[u8]) -> Option<Self> {
+        // SubjectPublicKeyInfo ::= SEQUENCE { algorithm AlgorithmIdentifier, subjectPublicKey }
+        let (spki, _) = der::expect(public_key, der::SEQUENCE)?;
+        let (algorithm, remaining) = der::expect(spki, der::SEQUENCE)?;
+        let (key, _) = der::expect(remaining, der::BIT_STRING)?;
+
+        // the key is always a whole number of bytes
+        let (&unused_bits, key) = key.split_first()?;
+        if unused_bits != 0 {
+            return None;
+        }
+
+        let (oid, parameters) = der::expect(algorithm, der::OBJECT_IDENTIFIER)?;
+        let signature_algorithm = if oid == EC_PUBLIC_KEY_OID {
+            let (curve, _) = der::expect(parameters, der::OBJECT_IDENTIFIER)?;
+            if curve != P256_OID {
+                return None;
+            }
+            ECDSA
+        } else if oid == RSA_OID {
+            RSA
+        } else {
+            return None;
+        };
+
+        let mut id = [0; LOG_ID_LEN];
+        id.copy_from_slice(digest::digest(&digest::SHA256, public_key).as_ref());
+
+        Some(Self {
+            id,
+            public_key: key.to_vec(),
+            signature_algorithm,
+        })
+    }
+
+    /// Returns the ID of the log, which is included in each SCT it issues
+    pub fn id(&self) -> &LogId {
+        &self.id
+    }
+
+    fn verify(&self, message: &[u8], hash: u8, signature_algorithm: u8, signature: &[u8]) -> bool {
+        if hash != SHA256 || signature_algorithm != self.signature_algorithm {
+            return false;
+        }
+
+        let algorithm: &dyn signature::VerificationAlgorithm = match signature_algorithm {
+            ECDSA => &signature::ECDSA_P256_SHA256_ASN1,
+            _ => &signature::RSA_PKCS1_2048_8192_SHA256,
+        };
+
+        signature::UnparsedPublicKey::new(algorithm, &self.public_key)
+            .verify(message, signature)
+            .is_ok()
+    }
+}
+
+/// Requires certificates to embed valid SCTs from a number of distinct logs
+#[derive(Clone, Debug, Default)]
+pub struct Policy {
+    logs: Vec<Log>,
+    required_logs: usize,
+}
+
+impl Policy {
+    /// Creates a policy which requires SCTs from `required_logs` distinct logs
+    ///
+    /// Only SCTs of logs which are added with [`Self::with_log`] are counted.
+    pub fn new(required_logs: usize) -> Self {
+        Self {
+            logs: Vec::new(),
+            required_logs,
+        }
+    }
+
+    /// Adds a log which is trusted to issue SCTs
+    pub fn with_log(mut self, log: Log) -> Self {
+        self.logs.push(log);
+        self
+    }
+
+    /// Verifies the SCTs embedded in a DER-encoded end-entity certificate
+    ///
+    /// The SCTs are signed over the issuer's public key, so they can only be verified if the
+    /// DER-encoded certificate of the issuer is known.
+    pub fn verify(
+        &self,
+        end_entity: &[u8],
+        issuer: Option<&[u8]>,
+        now: SystemTime,
+    ) -> Verification {
+        let mut verification = Verification {
+            required_logs: self.required_logs,
+            ..Default::default()
+        };
+
+        let certificate = if let Some(certificate) = EmbeddedScts::new(end_entity) {
+            certificate
+        } else {
+            return verification;
+        };
+
+        let issuer_key_hash = issuer
+            .and_then(crate::spki::subject_public_key_info)
+            .map(|spki| digest::digest(&digest::SHA256, spki));
+        let now = now
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_millis() as u64);
+
+        let mut valid_logs: Vec<&LogId> = Vec::new();
+
+        for sct in certificate.scts() {
+            verification.embedded += 1;
+
+            let sct = if let Some(sct) = sct {
+                sct
+            } else {
+                verification.invalid += 1;
+                continue;
+            };
+
+            let log = if let Some(log) = self.logs.iter().find(|log| log.id == sct.log_id) {
+                log
+            } else {
+                verification.unknown_logs += 1;
+                continue;
+            };
+
+            let is_valid = issuer_key_hash.as_ref().map_or(false, |issuer_key_hash| {
+                let message = sct.message(issuer_key_hash.as_ref(), &certificate.precertificate);
+                sct.timestamp <= now
+                    && log.verify(&message, sct.hash, sct.signature_algorithm, sct.signature)
+            });
+
+            if !is_valid {
+                verification.invalid += 1;
+            } else if !valid_logs.contains(&&log.id) {
+                valid_logs.push(&log.id);
+            }
+        }
+
+        verification.valid_logs = valid_logs.len();
+        verification
+    }
+}
+
+/// The results of verifying the SCTs embedded in a certificate
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Verification {
+    /// The number of distinct logs with a valid SCT
+    pub valid_logs: usize,
+    /// The number of distinct logs the policy requires
+    pub required_logs: usize,
+    /// The number of SCTs embedded in the certificate
+    pub embedded: usize,
+    /// The number of SCTs which were issued by logs which aren't part of the policy
+    pub unknown_logs: usize,
+    /// The number of SCTs which couldn't be parsed, had an invalid signature or a timestamp in
+    /// the future
+    pub invalid: usize,
+}
+
+impl Verification {
+    /// Returns `true` if the certificate complies with the policy
+    pub fn is_compliant(&self) -> bool {
+        self.valid_logs >= self.required_logs
+    }
+}
+
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "valid SCTs from {} of {} required logs ({} embedded, {} from unknown logs, {} invalid)",
+            self.valid_logs, self.required_logs, self.embedded, self.unknown_logs, self.invalid
+        )
+    }
+}
+
+/// The SCT list of a certificate, along with the certificate the SCTs were issued for
+struct EmbeddedScts<'a> {
+    list: &'a [u8],
+    /// The TBSCertificate without the SCT list extension
+    precertificate: Vec<u8>,
+}
+
+impl<'a> EmbeddedScts<'a> {
+    /// Returns `None` if the certificate doesn't embed an SCT list
+    fn new(certificate: &'a [u8]) -> Option<Self> {
+        let mut list = None;
+        let mut precertificate = Vec::new();
+
+        for value in der::values(der::tbs_certificate(certificate)?)? {
+            if value.tag != der::EXTENSIONS {
+                precertificate.extend_from_slice(value.encoding);
+                continue;
+            }
+
+            // Extension ::= SEQUENCE { extnID, critical BOOLEAN DEFAULT FALSE, extnValue }
+            let (extensions, _) = der::expect(value.contents, der::SEQUENCE)?;
+            let mut remaining_extensions = Vec::new();
+
+            for extension in der::values(extensions)? {
+                if extension.tag != der::SEQUENCE {
+                    return None;
+                }
+                let (oid, remaining) = der::expect(extension.contents, der::OBJECT_IDENTIFIER)?;
+
+                if oid != SCT_LIST_OID {
+                    remaining_extensions.extend_from_slice(extension.encoding);
+                    continue;
+                }
+
+                let remaining = if remaining.first() == Some(&der::BOOLEAN) {
+                    der::expect(remaining, der::BOOLEAN)?.1
+                } else {
+                    remaining
+                };
+
+                // the TLS-encoded list is wrapped in another OCTET STRING
+                let (value, _) = der::expect(remaining, der::OCTET_STRING)?;
+                let (value, _) = der::expect(value, der::OCTET_STRING)?;
+                let (value, _) = read_u16_prefixed(value)?;
+                list = Some(value);
+            }
+
+            // the extensions are omitted if the SCT list was the only one
+            if !remaining_extensions.is_empty() {
+                let mut sequence = Vec::new();
+                der::encode(der::SEQUENCE, &remaining_extensions, &mut sequence);
+                der::encode(der::EXTENSIONS, &sequence, &mut precertificate);
+            }
+        }
+
+        let mut tbs_certificate = Vec::new();
+        der::encode(der::SEQUENCE, &precertificate, &mut tbs_certificate);
+
+        Some(Self {
+            list: list?,
+            precertificate: tbs_certificate,
+        })
+    }
+
+    /// Iterates over the SCTs, returning `None` for SCTs which can't be parsed
+    fn scts(&self) -> impl Iterator<Item = Option<Sct<'a>>> {
+        let mut list = self.list;
+        core::iter::from_fn(move || {
+            if list.is_empty() {
+                return None;
+            }
+
+            if let Some((sct, remaining)) = read_u16_prefixed(list) {
+                list = remaining;
+                Some(Sct::new(sct))
+            } else {
+                list = &[];
+                Some(None)
+            }
+        })
+    }
+}
+
+/// A version 1 Signed Certificate Timestamp
+struct Sct<'a> {
+    log_id: LogId,
+    timestamp: u64,
+    extensions: &'a [u8],
+    hash: u8,
+    signature_algorithm: u8,
+    signature: &'a [u8],
+}
+
+impl<'a> Sct<'a> {
+    fn new(sct: &'a [u8]) -> Option<Self> {
+        let (&version, sct) = sct.split_first()?;
+        if version != 0 || sct.len() < LOG_ID_LEN + 8 {
+            return None;
+        }
+
+        let (id, sct) = sct.split_at(LOG_ID_LEN);
+        let mut log_id = [0; LOG_ID_LEN];
+        log_id.copy_from_slice(id);
+
+        let (timestamp, sct) = sct.split_at(8);
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(timestamp);
+        let timestamp = u64::from_be_bytes(bytes);
+
+        let (extensions, sct) = read_u16_prefixed(sct)?;
+        let (&hash, sct) = sct.split_first()?;
+        let (&signature_algorithm, sct) = sct.split_first()?;
+        let (signature, sct) = read_u16_prefixed(sct)?;
+        if !sct.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            log_id,
+            timestamp,
+            extensions,
+            hash,
+            signature_algorithm,
+            signature,
+        })
+    }
+
+    /// Returns the message which was signed by the log
+    fn message(&self, issuer_key_hash: &[u8], precertificate: &[u8]) -> Vec<u8> {
+        let mut message = Vec::with_capacity(precertificate.len() + 64);
+        // version v1 and signature type certificate_timestamp
+        message.extend_from_slice(&[0, 0]);
+        message.extend_from_slice(&self.timestamp.to_be_bytes());
+        // entry type precert_entry
+        message.extend_from_slice(&1u16.to_be_bytes());
+        message.extend_from_slice(issuer_key_hash);
+        message.extend_from_slice(&(precertificate.len() as u32).to_be_bytes()[1..]);
+        message.extend_from_slice(precertificate);
+        message.extend_from_slice(&(self.extensions.len() as u16).to_be_bytes());
+        message.extend_from_slice(self.extensions);
+        message
+    }
+}
+
+/// Splits a value with a 16-bit length prefix from the bytes which follow it
+fn read_u16_prefixed(buffer: &[u8]) -> Option<(&[u8], &[u8])> {
+    if buffer.len() < 2 {
+        return None;
+    }
+    let (len, buffer) = buffer.split_at(2);
+    let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+    if buffer.len() < len {
+        return None;
+    }
+    Some(buffer.split_at(len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::time::Duration;
+    use s2n_quic_core::crypto::tls::testing::certificates::CERT_DER;
+
+    /// The certificate embeds SCTs from both logs and from a third log, which is unknown
+    static CERT: &[u8] = include_bytes!("test_samples/sct/cert.der");
+    static ISSUER: &[u8] = include_bytes!("test_samples/sct/issuer.der");
+    static LOG_1: &[u8] = include_bytes!("test_samples/sct/log1.der");
+    static LOG_2: &[u8] = include_bytes!("test_samples/sct/log2.der");
+
+    /// The SCTs were issued at 1_600_000_000_000 milliseconds since the epoch
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(1_600_000_000_000)
+    }
+
+    fn policy(required_logs: usize) -> Policy {
+        Policy::new(required_logs)
+            .with_log(Log::new(LOG_1).unwrap())
+            .with_log(Log::new(LOG_2).unwrap())
+    }
+
+    #[test]
+    fn compliant_test() {
+        let verification = policy(2).verify(CERT, Some(ISSUER), now());
+        assert!(verification.is_compliant());
+        assert_eq!(
+            verification,
+            Verification {
+                valid_logs: 2,
+                required_logs: 2,
+                embedded: 3,
+                unknown_logs: 1,
+                invalid: 0,
+            }
+        );
+
+        assert!(!policy(3).verify(CERT, Some(ISSUER), now()).is_compliant());
+    }
+
+    #[test]
+    fn invalid_sct_test() {
+        // the issuer's key is part of the signed message
+        let verification = policy(1).verify(CERT, Some(CERT_DER), now());
+        assert_eq!(verification.valid_logs, 0);
+        assert_eq!(verification.invalid, 2);
+
+        let verification = policy(1).verify(CERT, None, now());
+        assert_eq!(verification.invalid, 2);
+
+        // SCTs from the future are rejected
+        let verification = policy(1).verify(CERT, Some(ISSUER), now() - Duration::from_secs(1));
+        assert_eq!(verification.invalid, 2);
+        assert!(!verification.is_compliant());
+    }
+
+    #[test]
+    fn missing_scts_test() {
+        let verification = policy(1).verify(CERT_DER, Some(ISSUER), now());
+        assert_eq!(verification.embedded, 0);
+        assert!(!verification.is_compliant());
+
+        // policies which don't require any logs only report the SCTs
+        assert!(Policy::new(0).verify(CERT_DER, None, now()).is_compliant());
+    }
+
+    #[test]
+    fn log_test() {
+        let log = Log::new(LOG_1).unwrap();
+        assert_eq!(
+            log.id().as_ref(),
+            digest::digest(&digest::SHA256, LOG_1).as_ref()
+        );
+
+        // only P-256 and RSA keys are supported
+        assert!(Log::new(&LOG_1[..LOG_1.len() - 1]).is_none());
+        assert!(Log::new(crate::spki::subject_public_key_info(CERT_DER).unwrap()).is_some());
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pins the public keys of the certificates presented by a peer
+//!
+//! A pin is the SHA-256 digest of the DER-encoded SubjectPublicKeyInfo of a certificate, which is
+//! the format used by HTTP Public Key Pinning. Pins can be computed from a certificate with
+//! `openssl x509 -pubkey -noout | openssl pkey -pubin -outform der | openssl dgst -sha256`.
+
+use crate::der;
+use ring::digest;
+
+/// The length of a pin
+pub const DIGEST_LEN: usize = 32;
+
+/// The SHA-256 digest of a DER-encoded SubjectPublicKeyInfo
+pub type Digest = [u8; DIGEST_LEN];
+
+/// Returns the pin of a DER-encoded certificate
+///
+/// Returns `None` if the certificate couldn't be parsed.
+pub fn digest(certificate: &[u8]) -> Option<Digest> {
+    let spki = subject_public_key_info(certificate)?;
+    let mut value = [0; DIGEST_LEN];
+    value.copy_from_slice(digest::digest(&digest::SHA256, spki).as_ref());
+    Some(value)
+}
+
+/// Returns `true` if the public key of any of the DER-encoded certificates is pinned
+pub fn is_pinned<'a, C: IntoIterator<Item = &'a [u8]>>(pins: &[Digest], certificates: C) -> bool {
+    certificates
+        .into_iter()
+        .filter_map(digest)
+        .any(|digest| pins.contains(&digest))
+}
+
+/// Returns the DER-encoded SubjectPublicKeyInfo of a DER-encoded certificate
+pub fn subject_public_key_info(certificate: &[u8]) -> Option<&[u8]> {
+    // TBSCertificate ::= SEQUENCE { version [0] OPTIONAL, serialNumber, signature, issuer,
+    //     validity, subject, subjectPublicKeyInfo, ... } (RFC 5280, section 4.1)
+    let mut remaining = der::tbs_certificate(certificate)?;
+    if remaining.first() == Some(&der::VERSION) {
+        remaining = der::expect(remaining, der::VERSION)?.1;
+    }
+    remaining = der::expect(remaining, der::INTEGER)?.1;
+    for _field in ["signature", "issuer", "validity", "subject"] {
+        remaining = der::expect(remaining, der::SEQUENCE)?.1;
+    }
+
+    let (value, _) = der::read(remaining)?;
+    if value.tag != der::SEQUENCE {
+        return None;
+    }
+    Some(value.encoding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_quic_core::crypto::tls::testing::certificates::CERT_DER;
+
+    #[test]
+    fn spki_test() {
+        let spki = subject_public_key_info(CERT_DER).unwrap();
+        assert_eq!(spki[0], der::SEQUENCE);
+
+        // the key is embedded in the certificate
+        assert!(CERT_DER.windows(spki.len()).any(|window| window == spki));
+
+        let pin = digest(CERT_DER).unwrap();
+        assert!(is_pinned(&[pin], [CERT_DER]));
+        assert!(!is_pinned(&[[0; DIGEST_LEN]], [CERT_DER]));
+    }
+
+    #[test]
+    fn invalid_certificate_test() {
+        assert!(digest(&[]).is_none());
+        assert!(digest(&CERT_DER[..CERT_DER.len() / 2]).is_none());
+        assert!(digest(&[der::SEQUENCE, 0x84, 0xff, 0xff, 0xff, 0xff]).is_none());
+    }
+}
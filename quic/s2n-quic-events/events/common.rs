@@ -7,6 +7,9 @@ struct ConnectionMeta {
 
     id: u64,
 
+    /// The correlation ID of the connection, which is either random or provided by the application
+    correlation_id: crate::connection::CorrelationId,
+
     #[builder(crate::time::Timestamp)]
     timestamp: crate::event::Timestamp,
 }
@@ -40,6 +43,11 @@ struct TransportParameters<'a> {
     initial_max_streams_bidi: u64,
     initial_max_streams_uni: u64,
     max_datagram_frame_size: u64,
+    /// The parameters with IDs which are not known to s2n-quic
+    ///
+    /// The parameters are encoded as a sequence of transport parameters, which can be read with
+    /// `s2n_quic_core::transport::parameters::CustomParametersIter`.
+    custom_parameters: &'a [u8],
 }
 
 struct PreferredAddress<'a> {
@@ -256,6 +264,31 @@ impl From<&SocketAddress<'_>> for std::net::SocketAddr {
     }
 }
 
+enum AckRangeRejectReason {
+    /// The range acknowledged a packet number that has not been sent yet.
+    PacketNotSent,
+    /// The range acknowledged a packet number that was intentionally skipped.
+    ///
+    /// Packet numbers are periodically skipped to detect peers optimistically
+    /// acknowledging packets in an attempt to increase the congestion window.
+    SkippedPacketNumber,
+    /// The ACK frame contained more ranges than the endpoint accepts for a single frame.
+    ///
+    /// The reported range spans all of the ranges in the frame.
+    RangeLimitExceeded,
+}
+
+impl IntoEvent<builder::AckRangeRejectReason> for crate::ack::Error {
+    fn into_event(self) -> builder::AckRangeRejectReason {
+        use crate::ack::Error;
+        match self {
+            Error::PacketNotSent => builder::AckRangeRejectReason::PacketNotSent {},
+            Error::SkippedPacketNumber => builder::AckRangeRejectReason::SkippedPacketNumber {},
+            Error::RangeLimitExceeded => builder::AckRangeRejectReason::RangeLimitExceeded {},
+        }
+    }
+}
+
 enum DuplicatePacketError {
     /// The packet number was already received and is a duplicate.
     Duplicate,
@@ -353,7 +386,7 @@ enum Frame {
     },
     StreamsBlocked {
         stream_type: StreamType,
-        stream_limit: u64
+        stream_limit: u64,
     },
     NewConnectionId,
     RetireConnectionId,
@@ -364,6 +397,11 @@ enum Frame {
     Datagram {
         len: u16,
     },
+    /// A frame of an extension which was registered by the application
+    Extension {
+        frame_type: u64,
+        len: u16,
+    },
 }
 
 impl IntoEvent<builder::Frame> for &crate::frame::Padding {
@@ -682,6 +720,21 @@ enum DatagramDropReason {
     PathLimitExceeded,
     /// The peer initiated a connection migration without supplying enough connection IDs to use.
     InsufficientConnectionIds,
+    /// The datagram contained a duplicate Initial packet for a handshake which is already in progress.
+    ///
+    /// Clients which retransmit their first Initial packet with a new Destination Connection Id
+    /// are identified by their address, Source Connection Id and token.
+    DuplicateInitial,
+}
+
+/// The outcome of a connection attempt which the endpoint limits didn't allow
+enum ConnectionAttemptOutcome {
+    /// The client was asked to validate its address with a Retry packet
+    Retry,
+    /// The connection attempt was silently dropped
+    Drop,
+    /// The connection attempt was rejected
+    Close,
 }
 
 enum KeySpace {
@@ -848,6 +901,65 @@ enum SlowStartExitCause {
     Other,
 }
 
+//= https://tools.ietf.org/id/draft-marx-qlog-event-definitions-quic-h3-02#5.4.3
+//# This event signifies when the congestion controller enters a
+//# significant new state and changes its behaviour.
+/// The state of a congestion controller
+enum CongestionState {
+    /// The congestion window grows by the number of bytes acknowledged
+    SlowStart,
+    /// HyStart++ detected an increase in the round trip time and grows the congestion window
+    /// more slowly until slow start is resumed or exited
+    ConservativeSlowStart,
+    /// The congestion window was reduced in response to congestion, and is not increased
+    /// until a packet sent after the reduction is acknowledged
+    Recovery,
+    /// The congestion window grows according to the congestion avoidance algorithm
+    CongestionAvoidance,
+    /// The congestion window is not increased since the application doesn't send enough data
+    /// to fill it
+    ApplicationLimited,
+}
+
+/// A metric of a connection which is compared against a threshold of the connection limits
+enum SloMetric {
+    /// The smoothed round trip time of the active path
+    RoundTripTime { smoothed_rtt: Duration },
+    /// The packets declared lost out of all packets which were acknowledged or declared lost
+    /// during the last period
+    LossRate {
+        lost_packets: u64,
+        total_packets: u64,
+    },
+}
+
+/// A phase of the handshake of a connection
+enum HandshakePhase {
+    /// The first Initial packet was sent
+    InitialSent,
+    /// The first Initial packet was received from the peer
+    InitialReceived,
+    /// The first Handshake packet was sent
+    HandshakeSent,
+    /// The first Handshake packet was received from the peer
+    HandshakeReceived,
+    /// The handshake was confirmed
+    HandshakeConfirmed,
+    /// The first byte of stream data was received from the peer
+    ApplicationDataReceived,
+}
+
+/// A protocol violation by the peer that the specification permits ignoring
+enum ProtocolViolation {
+    /// A RETIRE_CONNECTION_ID frame referred to the connection ID the packet containing it was
+    /// sent to
+    RetireCurrentConnectionId,
+    /// A NEW_CONNECTION_ID frame was inconsistent with a previously issued connection ID
+    InconsistentNewConnectionId,
+    /// An ACK frame acknowledged a packet number that was never sent
+    AckForUnsentPacket,
+}
+
 /// The reason the MTU was updated
 enum MtuUpdatedCause {
     /// The MTU was initialized with the default value
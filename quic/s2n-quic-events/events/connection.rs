@@ -138,6 +138,17 @@ struct AckRangeReceived<'a> {
     ack_range: RangeInclusive<u64>,
 }
 
+#[event("recovery:ack_range_rejected")]
+/// ACK range was rejected
+///
+/// Rejected ranges cause the connection to be closed with a PROTOCOL_VIOLATION.
+struct AckRangeRejected<'a> {
+    packet_header: PacketHeader,
+    path: Path<'a>,
+    ack_range: RangeInclusive<u64>,
+    reason: AckRangeRejectReason,
+}
+
 #[event("transport:packet_dropped")]
 /// Packet was dropped with the given reason
 struct PacketDropped<'a> {
@@ -185,6 +196,18 @@ struct TransportParametersReceived<'a> {
     transport_parameters: TransportParameters<'a>,
 }
 
+#[event("transport:transport_parameters_negotiated")]
+/// Transport parameters negotiated by the connection
+///
+/// This is emitted once the handshake completes, allowing the values sent to the peer to be
+/// compared with the values received from the peer.
+struct TransportParametersNegotiated<'a> {
+    /// The transport parameters sent to the peer
+    local_transport_parameters: TransportParameters<'a>,
+    /// The transport parameters received from the peer
+    peer_transport_parameters: TransportParameters<'a>,
+}
+
 #[event("transport:datagram_sent")]
 //= https://tools.ietf.org/id/draft-marx-qlog-event-definitions-quic-h3-02#5.3.10
 /// Datagram sent by a connection
@@ -259,11 +282,63 @@ struct TlsServerHello<'a> {
     payload: &'a [&'a [u8]],
 }
 
+#[event("security:unvalidated_handshake_bytes_exceeded")]
+/// The peer sent more bytes than allowed before its address was validated
+///
+/// The connection is closed without notifying the peer.
+struct UnvalidatedHandshakeBytesExceeded {
+    /// The number of bytes received from the peer before its address was validated
+    bytes_received: u64,
+    /// The number of bytes sent to the peer before its address was validated
+    bytes_sent: u64,
+    /// The configured limit of bytes received from the peer before its address is validated
+    max_unvalidated_handshake_bytes: u64,
+}
+
 #[event("transport:rx_stream_progress")]
 struct RxStreamProgress {
     bytes: usize,
 }
 
+#[event("transport:stream_data_received")]
+/// A STREAM frame was received and accepted by the stream
+///
+/// The event is published for each STREAM frame as it was received, so the range may overlap
+/// data that was previously received.
+struct StreamDataReceived<'a> {
+    stream_id: u64,
+    offset: u64,
+    len: usize,
+    is_fin: bool,
+    /// The plaintext data carried by the frame
+    ///
+    /// This is only set if the subscriber requests access through
+    /// `Subscriber::stream_data_access`.
+    data: Option<&'a [u8]>,
+}
+
+#[event("transport:stream_data_acked")]
+/// Data sent on a stream was acknowledged by the peer
+///
+/// The event is published for each range of the stream which was carried by an acknowledged
+/// packet, so the range may overlap data that was previously acknowledged if it was retransmitted.
+struct StreamDataAcked {
+    stream_id: u64,
+    offset: u64,
+    len: u64,
+}
+
+#[event("transport:stream_data_lost")]
+/// Data sent on a stream was declared lost
+///
+/// The range is retransmitted, unless it was acknowledged in another packet or the stream was
+/// reset in the meantime.
+struct StreamDataLost {
+    stream_id: u64,
+    offset: u64,
+    len: u64,
+}
+
 #[event("transport:tx_stream_progress")]
 struct TxStreamProgress {
     bytes: usize,
@@ -282,6 +357,12 @@ struct MtuUpdated {
     cause: MtuUpdatedCause,
 }
 
+#[event("transport:protocol_violation_ignored")]
+/// A protocol violation by the peer was ignored by the connection's protocol violation policy
+struct ProtocolViolationIgnored {
+    violation: ProtocolViolation,
+}
+
 #[event("recovery:slow_start_exited")]
 /// The slow start congestion controller state has been exited
 struct SlowStartExited<'a> {
@@ -289,3 +370,44 @@ struct SlowStartExited<'a> {
     cause: SlowStartExitCause,
     congestion_window: u32,
 }
+
+#[event("recovery:congestion_state_updated")]
+//= https://tools.ietf.org/id/draft-marx-qlog-event-definitions-quic-h3-02#5.4.3
+/// The congestion controller entered a new state
+struct CongestionStateUpdated<'a> {
+    path: Path<'a>,
+    previous_state: CongestionState,
+    state: CongestionState,
+    congestion_window: u32,
+}
+
+#[event("recovery:bandwidth_estimate_updated")]
+/// The estimated bandwidth or minimum round trip time of a path changed
+///
+/// Applications can subscribe to this event to adapt to changes of the path capacity,
+/// for example by selecting a different bitrate, without measuring the throughput themselves.
+struct BandwidthEstimateUpdated<'a> {
+    path: Path<'a>,
+    /// The estimated bandwidth in bytes per second, or `None` if the congestion
+    /// controller does not estimate the bandwidth
+    bytes_per_second: Option<u64>,
+    /// The minimum round trip time observed on the path
+    min_rtt: Duration,
+}
+
+#[event("recovery:slo_updated")]
+/// A round trip time or loss rate threshold of the connection was breached or cleared
+struct SloUpdated<'a> {
+    path: Path<'a>,
+    metric: SloMetric,
+    /// `true` if the threshold was breached, `false` if it was cleared
+    breached: bool,
+}
+
+#[event("connectivity:handshake_phase_reached")]
+/// The connection reached a phase of the handshake for the first time
+struct HandshakePhaseReached {
+    phase: HandshakePhase,
+    /// The time elapsed since the connection was created
+    elapsed: Duration,
+}
@@ -50,6 +50,17 @@ struct EndpointDatagramSent {
 /// Datagram received by the endpoint
 struct EndpointDatagramReceived {
     len: u16,
+    /// The time at which the kernel received the datagram, relative to the UNIX epoch
+    ///
+    /// This is only available if the IO provider enabled timestamping on the socket.
+    software_timestamp: Option<Duration>,
+    /// The time at which the network interface received the datagram
+    ///
+    /// This is only available if the IO provider enabled hardware timestamping on the socket
+    /// and the network interface.
+    hardware_timestamp: Option<Duration>,
+    /// How long the datagram waited in the socket receive queue before the endpoint read it
+    queue_delay: Option<Duration>,
 }
 
 #[event("transport:datagram_dropped")]
@@ -61,8 +72,65 @@ struct EndpointDatagramDropped {
     reason: DatagramDropReason,
 }
 
+#[event("security:stateless_reset_token_mismatch")]
+#[subject(endpoint)]
+/// Emitted when a datagram was checked for a stateless reset but didn't end in a known token
+///
+/// A large number of these events may indicate that an off-path attacker is attempting to guess
+/// a stateless reset token in order to close connections.
+struct EndpointStatelessResetTokenMismatch {
+    len: u16,
+}
+
+#[event("transport:connection_attempt_limited")]
+#[subject(endpoint)]
+/// Emitted when the endpoint limits don't allow a connection attempt to proceed
+struct EndpointConnectionAttemptLimited<'a> {
+    /// The unvalidated address of the peer
+    remote_address: SocketAddress<'a>,
+    outcome: ConnectionAttemptOutcome,
+}
+
 #[event("transport:connection_attempt_failed")]
 #[subject(endpoint)]
 struct EndpointConnectionAttemptFailed {
     error: crate::connection::Error,
 }
+
+#[event("connectivity:draining_started")]
+#[subject(endpoint)]
+/// Emitted when the endpoint stops accepting connections and starts draining the open ones
+struct EndpointDrainingStarted {
+    /// The number of connections which are still open
+    active_connections: usize,
+    /// The amount of time the open connections have to close before the endpoint closes them
+    deadline: Option<Duration>,
+}
+
+#[event("connectivity:draining_progress")]
+#[subject(endpoint)]
+/// Emitted when a connection finishes while the endpoint is draining
+struct EndpointDrainingProgress {
+    /// The number of connections which are still open
+    remaining_connections: usize,
+}
+
+#[event("connectivity:draining_deadline_exceeded")]
+#[subject(endpoint)]
+/// Emitted when the draining deadline expires and the endpoint closes the remaining connections
+struct EndpointDrainingDeadlineExceeded {
+    /// The number of connections which were closed by the endpoint
+    remaining_connections: usize,
+}
+
+#[event("transport:transmit_scheduled")]
+#[subject(endpoint)]
+/// Emitted when the endpoint limited the connections to their quantum while filling the transmission queue
+struct EndpointTransmitScheduled {
+    /// The number of rounds over the connections which wanted to transmit
+    rounds: usize,
+    /// The number of turns the connections took to transmit
+    turns: usize,
+    /// The number of turns which ended because the connection used its quantum
+    quantum_exhausted: usize,
+}
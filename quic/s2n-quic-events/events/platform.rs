@@ -66,6 +66,18 @@ enum PlatformFeatureConfiguration {
     Ecn { enabled: bool },
     /// Emitted when the maximum transmission unit is configured
     MaxMtu { mtu: u16 },
+    /// Emitted when timestamping of received datagrams is enabled
+    RxTimestamps {
+        /// Whether the timestamps of the network interface were requested as well
+        hardware: bool,
+    },
+    /// Emitted when the offloading of pacing to the kernel was configured
+    PacingOffload {
+        /// Whether the socket supports transmit times
+        ///
+        /// If this value is `false`, the endpoint paces its transmissions itself.
+        enabled: bool,
+    },
 }
 
 #[event("platform:event_loop_wakeup")]
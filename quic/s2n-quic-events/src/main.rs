@@ -99,7 +99,7 @@ impl ToTokens for Output {
                                 self.server.id()
                             }
                         };
-                        tracing::span!(target: "s2n_quic", parent: parent, tracing::Level::DEBUG, "conn", id = meta.id)
+                        tracing::span!(target: "s2n_quic", parent: parent, tracing::Level::DEBUG, "conn", id = meta.id, correlation_id = tracing::field::display(meta.correlation_id))
                     }
 
                     #tracing_subscriber
@@ -156,6 +156,12 @@ impl ToTokens for Output {
 
                     /// True if the connection is in the handshake state, false otherwise
                     pub is_handshaking: bool,
+
+                    /// Number of bytes received from the peer before its address was validated
+                    pub unvalidated_bytes_received: u64,
+
+                    /// Number of bytes sent to the peer before its address was validated
+                    pub unvalidated_bytes_sent: u64,
                 }
 
                 impl<'a> Context<'a> {
@@ -170,8 +176,17 @@ impl ToTokens for Output {
                             connection_count,
                             remote_address: remote_address.into_event(),
                             is_handshaking,
+                            unvalidated_bytes_received: 0,
+                            unvalidated_bytes_sent: 0,
                         }
                     }
+
+                    /// Sets the number of bytes exchanged with the peer before its address was validated
+                    pub fn with_unvalidated_bytes(mut self, received: u64, sent: u64) -> Self {
+                        self.unvalidated_bytes_received = received;
+                        self.unvalidated_bytes_sent = sent;
+                        self
+                    }
                 }
             }
 
@@ -300,6 +315,19 @@ impl ToTokens for Output {
                         supervisor::Outcome::default()
                     }
 
+                    /// Returns `true` if the subscriber requires access to the plaintext stream data
+                    ///
+                    /// When `false`, the `data` field of [`StreamDataReceived`](api::StreamDataReceived)
+                    /// events is always `None`. When `true`, the field references the received bytes
+                    /// without copying them.
+                    ///
+                    /// If multiple `event::Subscriber`s are composed together, the data is exposed to
+                    /// all of them if any `event::Subscriber` requests access.
+                    #[inline]
+                    fn stream_data_access(&self) -> bool {
+                        false
+                    }
+
                     #subscriber
 
                     /// Called for each event that relates to the endpoint and all connections
@@ -366,6 +394,11 @@ impl ToTokens for Output {
                         }
                     }
 
+                    #[inline]
+                    fn stream_data_access(&self) -> bool {
+                        self.0.stream_data_access() || self.1.stream_data_access()
+                    }
+
                     #tuple_subscriber
 
                     #[inline]
@@ -449,6 +482,9 @@ impl ToTokens for Output {
 
                     /// Returns the [`Subject`] for the current publisher
                     fn subject(&self) -> Subject;
+
+                    /// Returns `true` if the subscriber requires access to the plaintext stream data
+                    fn stream_data_access(&self) -> bool;
                 }
 
                 pub struct ConnectionPublisherSubscriber<'a, Sub: Subscriber> {
@@ -496,6 +532,11 @@ impl ToTokens for Output {
                     fn subject(&self) -> api::Subject {
                         self.meta.subject()
                     }
+
+                    #[inline]
+                    fn stream_data_access(&self) -> bool {
+                        self.subscriber.stream_data_access()
+                    }
                 }
             }
 
@@ -594,6 +635,10 @@ impl ToTokens for Output {
                     fn subject(&self) -> api::Subject {
                         api::Subject::Connection { id: 0 }
                     }
+
+                    fn stream_data_access(&self) -> bool {
+                        false
+                    }
                 }
 
                 impl Drop for Publisher {
@@ -21,11 +21,18 @@ fn main() -> Result<(), Error> {
             supports("gso");
             supports("mtu_disc");
             supports("pktinfo");
+            supports("timestamping");
             supports("tos");
+            supports("txtime");
         }
         "macos" => {
             supports("pktinfo");
             supports("tos");
+            // sendmsg_x and recvmsg_x are used in place of sendmmsg and recvmmsg. They are
+            // undocumented so they need to be opted into with `--cfg s2n_quic_enable_macos_mmsg`.
+            if env.macos_mmsg {
+                supports("socket_mmsg");
+            }
         }
         _ => {
             // TODO others
@@ -44,6 +51,7 @@ struct Env {
     out_dir: String,
     target: String,
     target_os: String,
+    macos_mmsg: bool,
 }
 
 impl Env {
@@ -54,6 +62,7 @@ impl Env {
             out_dir: env("OUT_DIR"),
             target: env("TARGET"),
             target_os: env("CARGO_CFG_TARGET_OS"),
+            macos_mmsg: option_env("CARGO_CFG_S2N_QUIC_ENABLE_MACOS_MMSG").is_some(),
         }
     }
 
@@ -93,3 +102,8 @@ fn env(name: &str) -> String {
     std::env::var(name)
         .unwrap_or_else(|_| panic!("build script missing {:?} environment variable", name))
 }
+
+fn option_env(name: &str) -> Option<String> {
+    println!("cargo:rerun-if-env-changed={}", name);
+    std::env::var(name).ok()
+}
@@ -1,8 +1,13 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+mod common;
 mod select;
 
+#[cfg(feature = "async-io")]
+pub mod async_io;
+
 #[cfg(feature = "tokio")]
 pub mod tokio;
 
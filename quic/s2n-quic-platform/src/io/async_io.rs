@@ -0,0 +1,506 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Drives an endpoint with the [`async-io`](https://docs.rs/async-io) reactor
+//!
+//! The reactor is shared by the `smol` and `async-std` runtimes, so applications which use either
+//! of them can run an endpoint without starting a Tokio runtime. Since there isn't a common way to
+//! spawn tasks, the endpoint is handed to a [`Driver`], which the application polls on an executor
+//! of its choosing.
+
+use super::{
+    common::{bind, queue},
+    select::{self, Select},
+};
+use crate::{buffer::default as buffer, features::gso, socket::default as socket};
+use async_io::Async;
+use s2n_quic_core::{
+    endpoint::Endpoint,
+    event::{self, EndpointPublisher as _},
+    inet::{self, SocketAddress},
+    path::MaxMtu,
+    time::Clock as ClockTrait,
+};
+use std::{convert::TryInto, io, io::ErrorKind};
+
+pub type PathHandle = socket::Handle;
+
+mod clock;
+mod driver;
+use clock::Clock;
+pub use driver::Driver;
+
+#[derive(Debug)]
+pub struct Io {
+    builder: Builder,
+    driver: driver::Sender,
+}
+
+impl Io {
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    pub fn new<A: std::net::ToSocketAddrs>(addr: A) -> io::Result<(Self, Driver)> {
+        let address = addr.to_socket_addrs()?.next().expect("missing address");
+        Builder::default().with_receive_address(address)?.build()
+    }
+
+    /// Starts the endpoint
+    ///
+    /// The event loop is handed to the [`Driver`], which needs to be polled for the endpoint to
+    /// make progress.
+    pub fn start<E: Endpoint<PathHandle = PathHandle>>(
+        self,
+        mut endpoint: E,
+    ) -> io::Result<SocketAddress> {
+        let Self { builder, driver } = self;
+        let Builder {
+            socket,
+            recv_addr,
+            max_mtu,
+            max_segments,
+        } = builder;
+
+        endpoint.set_max_mtu(max_mtu);
+
+        let clock = Clock::default();
+
+        let mut publisher = event::EndpointPublisherSubscriber::new(
+            event::builder::EndpointMeta {
+                endpoint_type: E::ENDPOINT_TYPE,
+                timestamp: clock.get_time(),
+            },
+            None,
+            endpoint.subscriber(),
+        );
+
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::MaxMtu {
+                mtu: max_mtu.into(),
+            },
+        });
+
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Gso {
+                max_segments: max_segments.into(),
+            },
+        });
+
+        let socket = if let Some(socket) = socket {
+            socket
+        } else if let Some(recv_addr) = recv_addr {
+            bind(recv_addr, false)?
+        } else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "missing bind address",
+            ));
+        };
+
+        let socket: std::net::UdpSocket = socket.into();
+        let local_addr = socket.local_addr()?;
+
+        #[cfg(s2n_quic_platform_mtu_disc)]
+        super::common::set_mtu_discovery(&socket, &local_addr)?;
+
+        // Set up the socket to pass ECN information
+        #[cfg(s2n_quic_platform_tos)]
+        super::common::set_recv_tos(&socket, &local_addr, true)?;
+
+        // Set up the socket to pass information about the local address and interface
+        #[cfg(s2n_quic_platform_pktinfo)]
+        super::common::set_recv_pktinfo(&socket, &local_addr, true)?;
+
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Ecn {
+                enabled: cfg!(s2n_quic_platform_tos),
+            },
+        });
+
+        let mut rx = queue(max_segments, None, false);
+        let tx = queue(max_segments, None, false);
+
+        // tell the queue the local address so it can fill it in on each message
+        rx.set_local_address({
+            let addr: inet::SocketAddress = local_addr.into();
+            addr.into()
+        });
+
+        // registering the socket also makes it non-blocking
+        let socket = Async::new(socket)?;
+
+        let instance = Instance {
+            clock,
+            socket,
+            rx,
+            tx,
+            endpoint,
+        };
+
+        // errors are returned to the application through the driver
+        driver.send(Box::pin(instance.event_loop()));
+
+        Ok(local_addr.into())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Builder {
+    socket: Option<socket2::Socket>,
+    recv_addr: Option<std::net::SocketAddr>,
+    max_mtu: MaxMtu,
+    max_segments: gso::MaxSegments,
+}
+
+impl Builder {
+    /// Sets the local address for the endpoint to listen on. The address is also used for
+    /// transmitting from.
+    ///
+    /// NOTE: this method is mutually exclusive with `with_socket`
+    pub fn with_receive_address(mut self, addr: std::net::SocketAddr) -> io::Result<Self> {
+        debug_assert!(self.socket.is_none(), "socket has already been set");
+        self.recv_addr = Some(addr);
+        Ok(self)
+    }
+
+    /// Sets the socket used for receiving and transmitting
+    ///
+    /// NOTE: this method is mutually exclusive with `with_receive_address`
+    pub fn with_socket(mut self, socket: std::net::UdpSocket) -> io::Result<Self> {
+        debug_assert!(
+            self.recv_addr.is_none(),
+            "recv address has already been set"
+        );
+        self.socket = Some(socket.into());
+        Ok(self)
+    }
+
+    /// Sets the largest maximum transmission unit (MTU) that can be sent on a path
+    pub fn with_max_mtu(mut self, max_mtu: u16) -> io::Result<Self> {
+        self.max_mtu = max_mtu
+            .try_into()
+            .map_err(|err| io::Error::new(ErrorKind::InvalidInput, format!("{}", err)))?;
+        Ok(self)
+    }
+
+    /// Disables Generic Segmentation Offload (GSO)
+    ///
+    /// By default, GSO will be used unless the platform does not support it or an attempt to use
+    /// GSO fails. If it is known that GSO is not available, set this option to explicitly disable it.
+    pub fn with_gso_disabled(mut self) -> io::Result<Self> {
+        self.max_segments = 1.try_into().expect("1 is always a valid MaxSegments value");
+        Ok(self)
+    }
+
+    /// Builds the IO provider and the [`Driver`] which runs the endpoint once it's started
+    pub fn build(self) -> io::Result<(Io, Driver)> {
+        let (sender, driver) = driver::new();
+        let io = Io {
+            builder: self,
+            driver: sender,
+        };
+        Ok((io, driver))
+    }
+}
+
+#[derive(Debug)]
+struct Instance<E> {
+    clock: Clock,
+    socket: Async<std::net::UdpSocket>,
+    rx: socket::Queue<buffer::Buffer>,
+    tx: socket::Queue<buffer::Buffer>,
+    endpoint: E,
+}
+
+impl<E: Endpoint<PathHandle = PathHandle>> Instance<E> {
+    async fn event_loop(self) -> io::Result<()> {
+        let Self {
+            clock,
+            socket,
+            mut rx,
+            mut tx,
+            mut endpoint,
+        } = self;
+
+        let mut timer = clock.timer();
+
+        loop {
+            // Poll for readability if we have free slots available
+            let rx_interest = rx.free_len() > 0;
+            let rx_task = async {
+                if rx_interest {
+                    socket.readable().await
+                } else {
+                    futures::future::pending().await
+                }
+            };
+
+            // Poll for writablity if we have occupied slots available
+            let tx_interest = tx.occupied_len() > 0;
+            let tx_task = async {
+                if tx_interest {
+                    socket.writable().await
+                } else {
+                    futures::future::pending().await
+                }
+            };
+
+            let wakeups = endpoint.wakeups(&clock);
+            // pin the wakeups future so we don't have to move it into the Select future.
+            futures::pin_mut!(wakeups);
+
+            let select::Outcome {
+                rx_result,
+                tx_result,
+                timeout_expired,
+                application_wakeup,
+            } = if let Ok(res) = Select::new(rx_task, tx_task, &mut wakeups, &mut timer).await {
+                res
+            } else {
+                // The endpoint has shut down
+                return Ok(());
+            };
+
+            let wakeup_timestamp = clock.get_time();
+            let subscriber = endpoint.subscriber();
+            let mut publisher = event::EndpointPublisherSubscriber::new(
+                event::builder::EndpointMeta {
+                    endpoint_type: E::ENDPOINT_TYPE,
+                    timestamp: wakeup_timestamp,
+                },
+                None,
+                subscriber,
+            );
+
+            publisher.on_platform_event_loop_wakeup(event::builder::PlatformEventLoopWakeup {
+                timeout_expired,
+                rx_ready: rx_result.is_some(),
+                tx_ready: tx_result.is_some(),
+                application_wakeup,
+            });
+
+            if let Some(result) = tx_result {
+                result?;
+                ignore_would_block(tx.tx(socket.get_ref(), &mut publisher))?;
+            }
+
+            if let Some(result) = rx_result {
+                result?;
+                ignore_would_block(rx.rx(socket.get_ref(), &mut publisher))?;
+                endpoint.receive(&mut rx.rx_queue(), &clock);
+            }
+
+            endpoint.transmit(&mut tx.tx_queue(), &clock);
+
+            let timeout = endpoint.timeout();
+
+            if let Some(timeout) = timeout {
+                timer.update(timeout);
+            }
+
+            let timestamp = clock.get_time();
+            let subscriber = endpoint.subscriber();
+            let mut publisher = event::EndpointPublisherSubscriber::new(
+                event::builder::EndpointMeta {
+                    endpoint_type: E::ENDPOINT_TYPE,
+                    timestamp,
+                },
+                None,
+                subscriber,
+            );
+
+            // notify the application that we're going to sleep
+            let timeout = timeout.map(|t| t.saturating_duration_since(timestamp));
+            publisher.on_platform_event_loop_sleep(event::builder::PlatformEventLoopSleep {
+                timeout,
+                processing_duration: timestamp.saturating_duration_since(wakeup_timestamp),
+            });
+        }
+    }
+}
+
+/// Ignores the error of a socket which was reported as ready but had nothing to do
+///
+/// The reactor is notified again once the socket becomes ready.
+fn ignore_would_block<T>(result: io::Result<T>) -> io::Result<()> {
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::WouldBlock => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::task::{Context, Poll};
+    use s2n_quic_core::{
+        endpoint::{self, CloseError},
+        io::{
+            rx::{self, Entry as _},
+            tx,
+        },
+        path::Handle as _,
+        time::{Clock, Duration, Timestamp},
+    };
+    use std::collections::BTreeMap;
+
+    /// Sends numbered datagrams to itself until all of them were received
+    struct TestEndpoint {
+        addr: SocketAddress,
+        messages: BTreeMap<u32, Option<Timestamp>>,
+        now: Option<Timestamp>,
+        subscriber: NoopSubscriber,
+    }
+
+    impl TestEndpoint {
+        fn new(addr: SocketAddress) -> Self {
+            Self {
+                addr,
+                messages: (0..1000).map(|id| (id, None)).collect(),
+                now: None,
+                subscriber: Default::default(),
+            }
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct NoopSubscriber;
+
+    impl event::Subscriber for NoopSubscriber {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &event::api::ConnectionMeta,
+            _info: &event::api::ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+    }
+
+    impl Endpoint for TestEndpoint {
+        type PathHandle = PathHandle;
+        type Subscriber = NoopSubscriber;
+
+        const ENDPOINT_TYPE: endpoint::Type = endpoint::Type::Server;
+
+        fn transmit<Tx: tx::Queue<Handle = PathHandle>, C: Clock>(
+            &mut self,
+            queue: &mut Tx,
+            clock: &C,
+        ) {
+            let now = clock.get_time();
+            self.now = Some(now);
+
+            for (id, tx_time) in &mut self.messages {
+                match tx_time {
+                    Some(time)
+                        if now.saturating_duration_since(*time) < Duration::from_millis(50) =>
+                    {
+                        continue
+                    }
+                    _ => {
+                        let msg = (
+                            PathHandle::from_remote_address(self.addr.into()),
+                            id.to_be_bytes(),
+                        );
+                        if queue.push(msg).is_ok() {
+                            *tx_time = Some(now);
+                        } else {
+                            // no more capacity
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+
+        fn receive<Rx: rx::Queue<Handle = PathHandle>, C: Clock>(
+            &mut self,
+            queue: &mut Rx,
+            clock: &C,
+        ) {
+            let now = clock.get_time();
+            self.now = Some(now);
+            let local_address = queue.local_address();
+            let entries = queue.as_slice_mut();
+            let len = entries.len();
+            for entry in entries {
+                if let Some((_header, payload)) = entry.read(&local_address) {
+                    assert_eq!(payload.len(), 4, "invalid payload {:?}", payload);
+
+                    let id = (&*payload).try_into().unwrap();
+                    let id = u32::from_be_bytes(id);
+                    self.messages.remove(&id);
+                }
+            }
+            queue.finish(len);
+        }
+
+        fn poll_wakeups<C: Clock>(
+            &mut self,
+            _cx: &mut Context<'_>,
+            clock: &C,
+        ) -> Poll<Result<usize, CloseError>> {
+            let now = clock.get_time();
+            self.now = Some(now);
+
+            if self.messages.is_empty() {
+                return Err(CloseError).into();
+            }
+
+            Poll::Pending
+        }
+
+        fn timeout(&self) -> Option<Timestamp> {
+            self.now.map(|now| now + Duration::from_millis(50))
+        }
+
+        fn set_max_mtu(&mut self, _max_mtu: MaxMtu) {
+            // noop
+        }
+
+        fn subscriber(&mut self) -> &mut Self::Subscriber {
+            &mut self.subscriber
+        }
+    }
+
+    fn test<A: std::net::ToSocketAddrs>(addr: A) -> io::Result<()> {
+        let socket: std::net::UdpSocket = bind(addr, false)?.into();
+        let addr = socket.local_addr()?;
+
+        let (io, driver) = Io::builder().with_socket(socket)?.build()?;
+
+        let local_addr: std::net::SocketAddr = io.start(TestEndpoint::new(addr.into()))?.into();
+        assert_eq!(local_addr, addr);
+
+        // the driver isn't tied to a runtime
+        futures::executor::block_on(driver)
+    }
+
+    #[test]
+    fn ipv4_test() -> io::Result<()> {
+        test(("127.0.0.1", 0))
+    }
+
+    #[test]
+    fn ipv6_test() -> io::Result<()> {
+        match test(("::1", 0)) {
+            Err(err) if err.kind() == io::ErrorKind::AddrNotAvailable => {
+                eprintln!("The current environment does not support IPv6; skipping");
+                Ok(())
+            }
+            other => other,
+        }
+    }
+
+    #[test]
+    fn dropped_test() {
+        let (io, driver) = Io::builder().build().unwrap();
+        drop(io);
+
+        let err = futures::executor::block_on(driver).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotConnected);
+    }
+}
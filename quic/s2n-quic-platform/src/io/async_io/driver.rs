@@ -0,0 +1,120 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+pub type EventLoop = Pin<Box<dyn Future<Output = io::Result<()>> + Send>>;
+
+#[derive(Default)]
+struct Slot {
+    /// The event loop, once the endpoint is started
+    event_loop: Option<EventLoop>,
+    /// The waker of the driver waiting for the endpoint to be started
+    waker: Option<Waker>,
+    /// Set when the sender is dropped
+    is_closed: bool,
+}
+
+impl Slot {
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+pub fn new() -> (Sender, Driver) {
+    let slot = Arc::new(Mutex::new(Slot::default()));
+    let sender = Sender(slot.clone());
+    let driver = Driver {
+        slot,
+        event_loop: None,
+    };
+    (sender, driver)
+}
+
+/// Hands the event loop of a started endpoint to the [`Driver`]
+pub struct Sender(Arc<Mutex<Slot>>);
+
+impl Sender {
+    pub fn send(self, event_loop: EventLoop) {
+        let mut slot = self.0.lock().unwrap();
+        slot.event_loop = Some(event_loop);
+        slot.wake();
+    }
+}
+
+impl Drop for Sender {
+    fn drop(&mut self) {
+        let mut slot = self.0.lock().unwrap();
+        slot.is_closed = true;
+        slot.wake();
+    }
+}
+
+impl fmt::Debug for Sender {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+/// Drives the IO of an endpoint
+///
+/// The endpoint makes no progress until the driver is polled. The driver completes once the
+/// endpoint has shut down or a fatal IO error occurs.
+///
+/// The sockets and timers are registered with the `async-io` reactor, which runs on its own
+/// thread if it isn't driven by the executor, so the driver can be polled by any executor, e.g.
+/// spawned with `smol::spawn` or `async_std::task::spawn`.
+#[must_use = "the endpoint makes no progress unless the driver is polled"]
+pub struct Driver {
+    slot: Arc<Mutex<Slot>>,
+    event_loop: Option<EventLoop>,
+}
+
+impl fmt::Debug for Driver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Driver")
+            .field("is_started", &self.event_loop.is_some())
+            .finish()
+    }
+}
+
+impl Future for Driver {
+    type Output = io::Result<()>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+
+        if this.event_loop.is_none() {
+            let mut slot = this.slot.lock().unwrap();
+
+            if let Some(event_loop) = slot.event_loop.take() {
+                this.event_loop = Some(event_loop);
+            } else if slot.is_closed {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::NotConnected,
+                    "the IO provider was dropped without starting an endpoint",
+                )));
+            } else {
+                slot.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+        }
+
+        this.event_loop
+            .as_mut()
+            .expect("the event loop is set above")
+            .as_mut()
+            .poll(cx)
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Socket helpers which are shared by the runtime integrations
+
+use crate::{buffer::default as buffer, features::gso, socket::default as socket};
+use cfg_if::cfg_if;
+use std::io;
+
+/// How long before their departure time paced datagrams are handed to the kernel
+///
+/// This covers the timer granularity of the endpoint, which would otherwise wake up for every
+/// paced burst.
+#[cfg(any(s2n_quic_platform_socket_mmsg, s2n_quic_platform_socket_msg))]
+const PACING_HORIZON: core::time::Duration = core::time::Duration::from_millis(2);
+
+/// Creates a message queue
+///
+/// `pacing_offload` should only be set for tx queues of sockets which were configured with
+/// SO_TXTIME.
+pub fn queue(
+    max_segments: gso::MaxSegments,
+    batch_size: Option<usize>,
+    pacing_offload: bool,
+) -> socket::Queue<buffer::Buffer> {
+    cfg_if! {
+        if #[cfg(s2n_quic_platform_socket_mmsg)] {
+            let mut queue = socket::Queue::<buffer::Buffer>::new(buffer::Buffer::default(), max_segments.into());
+            if let Some(batch_size) = batch_size {
+                queue.set_batch_size(batch_size);
+            }
+            if pacing_offload {
+                queue.set_pacing_horizon(PACING_HORIZON);
+            }
+            queue
+        } else if #[cfg(s2n_quic_platform_socket_msg)] {
+            // messages are sent and received with a syscall each
+            let _ = batch_size;
+            let mut queue = socket::Queue::<buffer::Buffer>::new(buffer::Buffer::default(), max_segments.into());
+            if pacing_offload {
+                queue.set_pacing_horizon(PACING_HORIZON);
+            }
+            queue
+        } else {
+            let _ = (max_segments, batch_size, pacing_offload);
+            socket::Queue::default()
+        }
+    }
+}
+
+pub fn bind<A: std::net::ToSocketAddrs>(addr: A, reuse_port: bool) -> io::Result<socket2::Socket> {
+    use socket2::{Domain, Protocol, Socket, Type};
+
+    let addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
+        std::io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the provided bind address was empty",
+        )
+    })?;
+
+    let domain = Domain::for_address(addr);
+    let socket_type = Type::DGRAM;
+    let protocol = Some(Protocol::UDP);
+
+    cfg_if! {
+        // Set non-blocking mode in a single syscall if supported
+        if #[cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "fuchsia",
+            target_os = "illumos",
+            target_os = "linux",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))] {
+            let socket_type = socket_type.nonblocking();
+            let socket = Socket::new(domain, socket_type, protocol)?;
+        } else {
+            let socket = Socket::new(domain, socket_type, protocol)?;
+            socket.set_nonblocking(true)?;
+        }
+    };
+
+    // allow ipv4 to also connect
+    if addr.is_ipv6() {
+        socket.set_only_v6(false)?;
+    }
+
+    socket.set_reuse_address(true)?;
+
+    #[cfg(unix)]
+    socket.set_reuse_port(reuse_port)?;
+
+    // mark the variable as "used" regardless of platform support
+    let _ = reuse_port;
+
+    socket.bind(&addr.into())?;
+
+    Ok(socket)
+}
+
+/// Configures the tx socket to set the Don't Fragment bit without enforcing the path MTU
+#[cfg(s2n_quic_platform_mtu_disc)]
+pub fn set_mtu_discovery<S: std::os::unix::io::AsRawFd>(
+    socket: &S,
+    addr: &std::net::SocketAddr,
+) -> io::Result<()> {
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-14
+    //# UDP datagrams MUST NOT be fragmented at the IP layer.
+
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-14
+    //# In IPv4 [IPv4], the Don't Fragment (DF) bit MUST be set if possible, to
+    //# prevent fragmentation on the path.
+
+    //= https://www.rfc-editor.org/rfc/rfc8899#section-3
+    //# In IPv4, a probe packet MUST be sent with the Don't
+    //# Fragment (DF) bit set in the IP header and without network layer
+    //# endpoint fragmentation.
+
+    //= https://www.rfc-editor.org/rfc/rfc8899#section-4.5
+    //# A PL implementing this specification MUST suspend network layer
+    //# processing of outgoing packets that enforces a PMTU
+    //# [RFC1191][RFC8201] for each flow utilizing DPLPMTUD and instead use
+    //# DPLPMTUD to control the size of packets that are sent by a flow.
+
+    // IP_PMTUDISC_PROBE setting will set the DF (Don't Fragment) flag
+    // while also ignoring the Path MTU. This means packets will not
+    // be fragmented, and the EMSGSIZE error will not be returned for
+    // packets larger than the Path MTU according to the kernel.
+    libc!(setsockopt(
+        socket.as_raw_fd(),
+        libc::IPPROTO_IP,
+        libc::IP_MTU_DISCOVER,
+        &libc::IP_PMTUDISC_PROBE as *const _ as _,
+        core::mem::size_of_val(&libc::IP_PMTUDISC_PROBE) as _,
+    ))?;
+
+    if addr.is_ipv6() {
+        libc!(setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_IPV6,
+            libc::IPV6_MTU_DISCOVER,
+            &libc::IP_PMTUDISC_PROBE as *const _ as _,
+            core::mem::size_of_val(&libc::IP_PMTUDISC_PROBE) as _,
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Configures whether the rx socket passes the ECN markings of received datagrams
+#[cfg(s2n_quic_platform_tos)]
+pub fn set_recv_tos<S: std::os::unix::io::AsRawFd>(
+    socket: &S,
+    addr: &std::net::SocketAddr,
+    enabled: bool,
+) -> io::Result<()> {
+    let enabled: libc::c_int = enabled as _;
+
+    // This option needs to be enabled regardless of domain (IPv4 vs IPv6), except on mac
+    if addr.is_ipv4() || !cfg!(any(target_os = "macos", target_os = "ios")) {
+        libc!(setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_IP,
+            libc::IP_RECVTOS,
+            &enabled as *const _ as _,
+            core::mem::size_of_val(&enabled) as _,
+        ))?;
+    }
+
+    if addr.is_ipv6() {
+        libc!(setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_IPV6,
+            libc::IPV6_RECVTCLASS,
+            &enabled as *const _ as _,
+            core::mem::size_of_val(&enabled) as _,
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Configures whether the rx socket passes the local address and interface of received datagrams
+#[cfg(s2n_quic_platform_pktinfo)]
+pub fn set_recv_pktinfo<S: std::os::unix::io::AsRawFd>(
+    socket: &S,
+    addr: &std::net::SocketAddr,
+    enabled: bool,
+) -> io::Result<()> {
+    let enabled: libc::c_int = enabled as _;
+
+    if addr.is_ipv4() {
+        libc!(setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_IP,
+            libc::IP_PKTINFO,
+            &enabled as *const _ as _,
+            core::mem::size_of_val(&enabled) as _,
+        ))?;
+    } else {
+        libc!(setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_IPV6,
+            libc::IPV6_RECVPKTINFO,
+            &enabled as *const _ as _,
+            core::mem::size_of_val(&enabled) as _,
+        ))?;
+    }
+
+    Ok(())
+}
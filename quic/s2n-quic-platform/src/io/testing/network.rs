@@ -386,6 +386,7 @@ impl io::rx::Entry for Packet {
         let header = datagram::Header {
             path: self.path,
             ecn: self.ecn,
+            rx_timestamps: Default::default(),
         };
         let payload = &mut self.payload;
         Some((header, payload))
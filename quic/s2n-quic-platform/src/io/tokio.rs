@@ -1,10 +1,14 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::select::{self, Select};
+use super::{
+    common::{bind, queue},
+    select::{self, Select},
+};
 use crate::{buffer::default as buffer, features::gso, socket::default as socket};
 use cfg_if::cfg_if;
 use s2n_quic_core::{
+    connection::id::shard::MAX_SHARDS,
     endpoint::Endpoint,
     event::{self, EndpointPublisher as _},
     inet::{self, SocketAddress},
@@ -17,7 +21,12 @@ use tokio::{net::UdpSocket, runtime::Handle};
 pub type PathHandle = socket::Handle;
 
 mod clock;
+mod driver;
+mod features;
+mod shard;
 use clock::Clock;
+pub use driver::Driver;
+pub use features::Features;
 
 impl crate::socket::std::Socket for UdpSocket {
     type Error = io::Error;
@@ -35,6 +44,7 @@ impl crate::socket::std::Socket for UdpSocket {
 #[derive(Debug, Default)]
 pub struct Io {
     builder: Builder,
+    driver: Option<driver::Sender>,
 }
 
 impl Io {
@@ -42,28 +52,38 @@ impl Io {
         Builder::default()
     }
 
+    /// Returns a handle to the socket features used by the endpoint
+    ///
+    /// The handle reports the features once the endpoint is started and can be used to disable
+    /// them at runtime.
+    pub fn features(&self) -> Features {
+        self.builder.features.clone()
+    }
+
     pub fn new<A: std::net::ToSocketAddrs>(addr: A) -> io::Result<Self> {
         let address = addr.to_socket_addrs()?.next().expect("missing address");
         let builder = Builder::default().with_receive_address(address)?;
-        Ok(Self { builder })
+        Ok(Self {
+            builder,
+            driver: None,
+        })
     }
 
+    /// Starts the endpoint
+    ///
+    /// The event loop is spawned on the runtime and its task is returned, unless the provider
+    /// was built with [`Builder::build_no_spawn`], in which case the event loop is handed to the
+    /// [`Driver`] and no task is returned.
     pub fn start<E: Endpoint<PathHandle = PathHandle>>(
         self,
         mut endpoint: E,
-    ) -> io::Result<(tokio::task::JoinHandle<()>, SocketAddress)> {
-        let Builder {
-            handle,
-            rx_socket,
-            tx_socket,
-            recv_addr,
-            send_addr,
-            recv_buffer_size,
-            send_buffer_size,
-            max_mtu,
-            max_segments,
-            reuse_port,
-        } = self.builder;
+    ) -> io::Result<(Option<tokio::task::JoinHandle<()>>, SocketAddress)> {
+        let Self {
+            mut builder,
+            driver,
+        } = self;
+        let max_mtu = builder.max_mtu;
+        let max_segments = builder.max_segments;
 
         endpoint.set_max_mtu(max_mtu);
 
@@ -90,255 +110,462 @@ impl Io {
             },
         });
 
-        let handle = if let Some(handle) = handle {
-            handle
+        let handle = if let Some(handle) = builder.handle.take() {
+            Some(handle)
+        } else if driver.is_none() {
+            Some(
+                Handle::try_current()
+                    .map_err(|err| std::io::Error::new(io::ErrorKind::Other, err))?,
+            )
         } else {
-            Handle::try_current().map_err(|err| std::io::Error::new(io::ErrorKind::Other, err))?
+            // the driver is polled within the application's runtime
+            None
         };
 
-        let guard = handle.enter();
+        let guard = handle.as_ref().map(Handle::enter);
 
-        let rx_socket = if let Some(rx_socket) = rx_socket {
-            // ensure the socket is non-blocking
-            rx_socket.set_nonblocking(true)?;
-            rx_socket
-        } else if let Some(recv_addr) = recv_addr {
-            bind(recv_addr, reuse_port)?
+        let (rx_socket, tx_socket, rx_addr) = builder.open_sockets()?;
+
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Ecn {
+                enabled: cfg!(s2n_quic_platform_tos),
+            },
+        });
+
+        if builder.is_rx_timestamping_enabled() {
+            publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+                configuration: event::builder::PlatformFeatureConfiguration::RxTimestamps {
+                    hardware: builder.rx_hardware_timestamps,
+                },
+            });
+        }
+
+        let pacing_offload = builder.pacing_offload && set_txtime(&tx_socket);
+        if builder.pacing_offload {
+            publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+                configuration: event::builder::PlatformFeatureConfiguration::PacingOffload {
+                    enabled: pacing_offload,
+                },
+            });
+        }
+
+        let mut rx = queue(max_segments, builder.recv_batch_size, false);
+        let tx = queue(max_segments, builder.send_batch_size, pacing_offload);
+
+        let features = builder.features.clone();
+        features.set_gso_max_segments(tx.max_gso());
+        features.set_ecn(cfg!(s2n_quic_platform_tos));
+        features.set_pktinfo(cfg!(s2n_quic_platform_pktinfo));
+        features.set_rx_timestamping(builder.is_rx_timestamping_enabled());
+        features.set_pacing_offload(pacing_offload);
+
+        // tell the queue the local address so it can fill it in on each message
+        rx.set_local_address({
+            let addr: inet::SocketAddress = rx_addr.into();
+            addr.into()
+        });
+
+        let instance = Instance {
+            clock,
+            rx_socket: rx_socket.into(),
+            rx_addr,
+            tx_socket: tx_socket.into(),
+            rx,
+            tx,
+            features,
+            endpoint,
+        };
+
+        let local_addr = instance.rx_socket.local_addr()?.into();
+
+        let task = if let Some(driver) = driver {
+            // errors are returned to the application through the driver
+            driver.send(Box::pin(instance.event_loop()), handle.clone());
+            None
         } else {
+            let handle = handle
+                .as_ref()
+                .expect("a runtime handle is required to spawn the endpoint");
+
+            Some(spawn(handle, instance.event_loop()))
+        };
+
+        drop(guard);
+
+        Ok((task, local_addr))
+    }
+
+    /// Starts multiple endpoints which share the provider's sockets
+    ///
+    /// A single IO task reads the datagrams from the socket and hands each of them to the
+    /// endpoint which owns its connection, based on a hash of the destination connection ID.
+    /// Each endpoint runs in its own task and transmits its datagrams directly, which allows the
+    /// connections to be processed on multiple cores. The endpoints must be configured with a
+    /// [`shard::Format`](s2n_quic_core::connection::id::shard::Format) for the same number of
+    /// shards, in order.
+    ///
+    /// The tasks are spawned on the runtime, so idle runtime threads steal the tasks of busy
+    /// shards. Datagrams are dropped if an endpoint falls too far behind in processing them.
+    ///
+    /// If the provider was built with [`Builder::with_steering`], each endpoint receives the
+    /// datagrams on its own socket instead, which the kernel selects for each datagram.
+    pub fn start_sharded<E: Endpoint<PathHandle = PathHandle>>(
+        self,
+        mut endpoints: Vec<E>,
+    ) -> io::Result<(Vec<tokio::task::JoinHandle<()>>, SocketAddress)> {
+        if endpoints.len() > MAX_SHARDS {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
-                "missing bind address",
+                format!("at most {} shards are supported", MAX_SHARDS),
             ));
-        };
+        }
 
-        let tx_socket = if let Some(tx_socket) = tx_socket {
-            // ensure the socket is non-blocking
-            tx_socket.set_nonblocking(true)?;
-            tx_socket
-        } else if let Some(send_addr) = send_addr {
-            bind(send_addr, reuse_port)?
-        } else {
-            // No tx_socket or send address was specified, so the tx socket
-            // will be a handle to the rx socket.
-            rx_socket.try_clone()?
-        };
+        if endpoints.len() == 1 {
+            let endpoint = endpoints.pop().expect("length was checked");
+            let (task, local_addr) = self.start(endpoint)?;
+            return Ok((task.into_iter().collect(), local_addr));
+        }
 
-        if let Some(size) = send_buffer_size {
-            tx_socket.set_send_buffer_size(size)?;
+        let Self {
+            mut builder,
+            driver,
+        } = self;
+
+        if endpoints.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "at least one endpoint is required",
+            ));
         }
 
-        if let Some(size) = recv_buffer_size {
-            rx_socket.set_recv_buffer_size(size)?;
+        if driver.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "sharded endpoints require spawning tasks",
+            ));
         }
 
-        fn convert_addr_to_std(addr: socket2::SockAddr) -> io::Result<std::net::SocketAddr> {
-            addr.as_socket().ok_or_else(|| {
-                io::Error::new(io::ErrorKind::InvalidInput, "invalid domain for socket")
-            })
+        let max_mtu = builder.max_mtu;
+        let max_segments = builder.max_segments;
+
+        let handle = if let Some(handle) = builder.handle.take() {
+            handle
+        } else {
+            Handle::try_current().map_err(|err| std::io::Error::new(io::ErrorKind::Other, err))?
+        };
+
+        #[cfg(target_os = "linux")]
+        if builder.steering {
+            return Self::start_steered(builder, handle, endpoints);
         }
 
-        #[allow(unused_variables)] // some platform builds won't use these so ignore warnings
-        let (tx_addr, rx_addr) = (
-            convert_addr_to_std(tx_socket.local_addr()?)?,
-            convert_addr_to_std(rx_socket.local_addr()?)?,
-        );
+        let guard = handle.enter();
 
-        //= https://www.rfc-editor.org/rfc/rfc9000#section-14
-        //# UDP datagrams MUST NOT be fragmented at the IP layer.
+        let (rx_socket, tx_socket, rx_addr) = builder.open_sockets()?;
 
-        //= https://www.rfc-editor.org/rfc/rfc9000#section-14
-        //# In IPv4 [IPv4], the Don't Fragment (DF) bit MUST be set if possible, to
-        //# prevent fragmentation on the path.
+        let local_address = {
+            let addr: inet::SocketAddress = rx_addr.into();
+            addr.into()
+        };
 
-        //= https://www.rfc-editor.org/rfc/rfc8899#section-3
-        //# In IPv4, a probe packet MUST be sent with the Don't
-        //# Fragment (DF) bit set in the IP header and without network layer
-        //# endpoint fragmentation.
+        let pacing_offload = builder.pacing_offload && set_txtime(&tx_socket);
 
-        //= https://www.rfc-editor.org/rfc/rfc8899#section-4.5
-        //# A PL implementing this specification MUST suspend network layer
-        //# processing of outgoing packets that enforces a PMTU
-        //# [RFC1191][RFC8201] for each flow utilizing DPLPMTUD and instead use
-        //# DPLPMTUD to control the size of packets that are sent by a flow.
-        #[cfg(s2n_quic_platform_mtu_disc)]
-        {
-            use std::os::unix::io::AsRawFd;
-
-            // IP_PMTUDISC_PROBE setting will set the DF (Don't Fragment) flag
-            // while also ignoring the Path MTU. This means packets will not
-            // be fragmented, and the EMSGSIZE error will not be returned for
-            // packets larger than the Path MTU according to the kernel.
-            libc!(setsockopt(
-                tx_socket.as_raw_fd(),
-                libc::IPPROTO_IP,
-                libc::IP_MTU_DISCOVER,
-                &libc::IP_PMTUDISC_PROBE as *const _ as _,
-                core::mem::size_of_val(&libc::IP_PMTUDISC_PROBE) as _,
-            ))?;
-
-            if tx_addr.is_ipv6() {
-                libc!(setsockopt(
-                    tx_socket.as_raw_fd(),
-                    libc::IPPROTO_IPV6,
-                    libc::IPV6_MTU_DISCOVER,
-                    &libc::IP_PMTUDISC_PROBE as *const _ as _,
-                    core::mem::size_of_val(&libc::IP_PMTUDISC_PROBE) as _,
-                ))?;
-            }
-        }
+        let features = builder.features.clone();
+        features.set_gso_max_segments(max_segments.into());
+        features.set_ecn(cfg!(s2n_quic_platform_tos));
+        features.set_pktinfo(cfg!(s2n_quic_platform_pktinfo));
+        features.set_rx_timestamping(builder.is_rx_timestamping_enabled());
+        features.set_pacing_offload(pacing_offload);
 
-        // Set up the RX socket to pass ECN information
-        #[cfg(s2n_quic_platform_tos)]
-        {
-            use std::os::unix::io::AsRawFd;
-            let enabled: libc::c_int = 1;
-
-            // This option needs to be enabled regardless of domain (IPv4 vs IPv6), except on mac
-            if rx_addr.is_ipv4() || !cfg!(any(target_os = "macos", target_os = "ios")) {
-                libc!(setsockopt(
-                    rx_socket.as_raw_fd(),
-                    libc::IPPROTO_IP,
-                    libc::IP_RECVTOS,
-                    &enabled as *const _ as _,
-                    core::mem::size_of_val(&enabled) as _,
-                ))?;
-            }
+        let mut tasks = Vec::with_capacity(endpoints.len() + 1);
+        let mut senders = Vec::with_capacity(endpoints.len());
 
-            if rx_addr.is_ipv6() {
-                libc!(setsockopt(
-                    rx_socket.as_raw_fd(),
-                    libc::IPPROTO_IPV6,
-                    libc::IPV6_RECVTCLASS,
-                    &enabled as *const _ as _,
-                    core::mem::size_of_val(&enabled) as _,
-                ))?;
-            }
-        }
-        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
-            configuration: event::builder::PlatformFeatureConfiguration::Ecn {
-                enabled: cfg!(s2n_quic_platform_tos),
-            },
-        });
+        for mut endpoint in endpoints {
+            endpoint.set_max_mtu(max_mtu);
 
-        // Set up the RX socket to pass information about the local address and interface
-        #[cfg(s2n_quic_platform_pktinfo)]
-        {
-            use std::os::unix::io::AsRawFd;
-            let enabled: libc::c_int = 1;
-
-            if rx_addr.is_ipv4() {
-                libc!(setsockopt(
-                    rx_socket.as_raw_fd(),
-                    libc::IPPROTO_IP,
-                    libc::IP_PKTINFO,
-                    &enabled as *const _ as _,
-                    core::mem::size_of_val(&enabled) as _,
-                ))?;
-            } else {
-                libc!(setsockopt(
-                    rx_socket.as_raw_fd(),
-                    libc::IPPROTO_IPV6,
-                    libc::IPV6_RECVPKTINFO,
-                    &enabled as *const _ as _,
-                    core::mem::size_of_val(&enabled) as _,
-                ))?;
+            let clock = Clock::default();
+
+            let mut publisher = event::EndpointPublisherSubscriber::new(
+                event::builder::EndpointMeta {
+                    endpoint_type: E::ENDPOINT_TYPE,
+                    timestamp: clock.get_time(),
+                },
+                None,
+                endpoint.subscriber(),
+            );
+
+            publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+                configuration: event::builder::PlatformFeatureConfiguration::MaxMtu {
+                    mtu: max_mtu.into(),
+                },
+            });
+
+            publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+                configuration: event::builder::PlatformFeatureConfiguration::Gso {
+                    max_segments: max_segments.into(),
+                },
+            });
+
+            publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+                configuration: event::builder::PlatformFeatureConfiguration::Ecn {
+                    enabled: cfg!(s2n_quic_platform_tos),
+                },
+            });
+
+            if builder.is_rx_timestamping_enabled() {
+                publisher.on_platform_feature_configured(
+                    event::builder::PlatformFeatureConfigured {
+                        configuration: event::builder::PlatformFeatureConfiguration::RxTimestamps {
+                            hardware: builder.rx_hardware_timestamps,
+                        },
+                    },
+                );
             }
-        }
 
-        cfg_if! {
-            if #[cfg(any(s2n_quic_platform_socket_msg, s2n_quic_platform_socket_mmsg))] {
-                let mut rx = socket::Queue::<buffer::Buffer>::new(buffer::Buffer::default(), max_segments.into());
-                let tx = socket::Queue::<buffer::Buffer>::new(buffer::Buffer::default(), max_segments.into());
-            } else {
-                let mut rx = socket::Queue::default();
-                let tx = socket::Queue::default();
+            if builder.pacing_offload {
+                publisher.on_platform_feature_configured(
+                    event::builder::PlatformFeatureConfigured {
+                        configuration:
+                            event::builder::PlatformFeatureConfiguration::PacingOffload {
+                                enabled: pacing_offload,
+                            },
+                    },
+                );
             }
+
+            let (sender, receiver) = shard::channel();
+            senders.push(sender);
+
+            let worker = shard::Worker {
+                clock,
+                receiver,
+                rx: shard::Queue::new(local_address),
+                tx_socket: tx_socket.try_clone()?.into(),
+                tx: queue(max_segments, builder.send_batch_size, pacing_offload),
+                features: features.clone(),
+                endpoint,
+            };
+
+            tasks.push(spawn(&handle, worker.event_loop()));
         }
 
-        // tell the queue the local address so it can fill it in on each message
-        rx.set_local_address({
-            let addr: inet::SocketAddress = rx_addr.into();
-            addr.into()
-        });
+        let mut rx = queue(max_segments, builder.recv_batch_size, false);
+        rx.set_local_address(local_address);
 
-        let instance = Instance {
-            clock,
+        let dispatcher = shard::Dispatcher {
+            endpoint_type: E::ENDPOINT_TYPE,
             rx_socket: rx_socket.into(),
-            tx_socket: tx_socket.into(),
+            rx_addr,
             rx,
-            tx,
-            endpoint,
+            features,
+            senders,
         };
 
-        let local_addr = instance.rx_socket.local_addr()?.into();
+        let local_addr = dispatcher.rx_socket.local_addr()?.into();
 
-        let task = handle.spawn(async move {
-            if let Err(err) = instance.event_loop().await {
-                let debug = format!("A fatal IO error occurred ({:?}): {}", err.kind(), err);
-                if cfg!(test) {
-                    panic!("{}", debug);
-                } else {
-                    eprintln!("{}", debug);
-                }
-            }
-        });
+        tasks.push(spawn(&handle, dispatcher.event_loop()));
 
         drop(guard);
 
-        Ok((task, local_addr))
+        Ok((tasks, local_addr))
+    }
+
+    /// Starts each endpoint on its own socket, with the datagrams steered to the sockets by the
+    /// kernel
+    #[cfg(target_os = "linux")]
+    fn start_steered<E: Endpoint<PathHandle = PathHandle>>(
+        builder: Builder,
+        handle: Handle,
+        endpoints: Vec<E>,
+    ) -> io::Result<(Vec<tokio::task::JoinHandle<()>>, SocketAddress)> {
+        let mut addr = match builder.recv_addr {
+            Some(addr)
+                if builder.rx_socket.is_none()
+                    && builder.tx_socket.is_none()
+                    && builder.send_addr.is_none() =>
+            {
+                addr
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "steering requires a receive address and no other sockets",
+                ))
+            }
+        };
+
+        let shards = endpoints.len();
+        let mut sockets = Vec::with_capacity(shards);
+
+        // the sockets are numbered in the order they join the reuse port group
+        for _ in 0..shards {
+            let socket = bind(addr, true)?;
+            // the other shards join the port which was assigned to the first one
+            addr = socket.local_addr()?.as_socket().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "invalid domain for socket")
+            })?;
+            sockets.push(socket);
+        }
+
+        crate::socket::steering::attach(&sockets[0], shards)?;
+
+        let mut tasks = Vec::with_capacity(shards);
+
+        for (endpoint, socket) in endpoints.into_iter().zip(sockets) {
+            let io = Io {
+                builder: builder.for_shard(socket, handle.clone()),
+                driver: None,
+            };
+            let (task, _local_addr) = io.start(endpoint)?;
+            tasks.extend(task);
+        }
+
+        Ok((tasks, addr.into()))
     }
 }
 
-fn bind<A: std::net::ToSocketAddrs>(addr: A, reuse_port: bool) -> io::Result<socket2::Socket> {
-    use socket2::{Domain, Protocol, Socket, Type};
+/// Spawns an event loop on the runtime, reporting any fatal errors
+fn spawn<F>(handle: &Handle, event_loop: F) -> tokio::task::JoinHandle<()>
+where
+    F: 'static + Send + core::future::Future<Output = io::Result<()>>,
+{
+    handle.spawn(async move {
+        if let Err(err) = event_loop.await {
+            let debug = format!("A fatal IO error occurred ({:?}): {}", err.kind(), err);
+            if cfg!(test) {
+                panic!("{}", debug);
+            } else {
+                eprintln!("{}", debug);
+            }
+        }
+    })
+}
 
-    let addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
-        std::io::Error::new(
+/// The largest number of messages which can be passed to `sendmmsg` and `recvmmsg`
+const MAX_BATCH_SIZE: usize = 1024;
+
+fn batch_size(batch_size: usize) -> io::Result<usize> {
+    if (1..=MAX_BATCH_SIZE).contains(&batch_size) {
+        Ok(batch_size)
+    } else {
+        Err(io::Error::new(
             io::ErrorKind::InvalidInput,
-            "the provided bind address was empty",
-        )
-    })?;
-
-    let domain = Domain::for_address(addr);
-    let socket_type = Type::DGRAM;
-    let protocol = Some(Protocol::UDP);
-
-    cfg_if! {
-        // Set non-blocking mode in a single syscall if supported
-        if #[cfg(any(
-            target_os = "android",
-            target_os = "dragonfly",
-            target_os = "freebsd",
-            target_os = "fuchsia",
-            target_os = "illumos",
-            target_os = "linux",
-            target_os = "netbsd",
-            target_os = "openbsd"
-        ))] {
-            let socket_type = socket_type.nonblocking();
-            let socket = Socket::new(domain, socket_type, protocol)?;
-        } else {
-            let socket = Socket::new(domain, socket_type, protocol)?;
-            socket.set_nonblocking(true)?;
-        }
+            format!("batch size must be between 1 and {}", MAX_BATCH_SIZE),
+        ))
+    }
+}
+
+/// Configures the rx socket to report the time at which datagrams were received
+///
+/// The software timestamps are always requested. The hardware timestamps are only reported
+/// if timestamping was also enabled on the network interface.
+#[cfg(s2n_quic_platform_timestamping)]
+fn set_rx_timestamping<S: std::os::unix::io::AsRawFd>(
+    socket: &S,
+    hardware: bool,
+) -> io::Result<()> {
+    let mut flags = libc::SOF_TIMESTAMPING_RX_SOFTWARE | libc::SOF_TIMESTAMPING_SOFTWARE;
+    if hardware {
+        flags |= libc::SOF_TIMESTAMPING_RX_HARDWARE | libc::SOF_TIMESTAMPING_RAW_HARDWARE;
+    }
+    let flags = flags as libc::c_int;
+
+    libc!(setsockopt(
+        socket.as_raw_fd(),
+        libc::SOL_SOCKET,
+        libc::SO_TIMESTAMPING,
+        &flags as *const _ as _,
+        core::mem::size_of_val(&flags) as _,
+    ))?;
+
+    Ok(())
+}
+
+/// Configures the tx socket to hold back each datagram until the transmit time set on it
+///
+/// Returns `false` if the socket option isn't supported, in which case the endpoint keeps
+/// pacing its transmissions itself.
+#[cfg(s2n_quic_platform_txtime)]
+fn set_txtime<S: std::os::unix::io::AsRawFd>(socket: &S) -> bool {
+    let config = libc::sock_txtime {
+        clockid: libc::CLOCK_MONOTONIC,
+        flags: 0,
     };
 
-    // allow ipv4 to also connect
-    if addr.is_ipv6() {
-        socket.set_only_v6(false)?;
+    libc!(setsockopt(
+        socket.as_raw_fd(),
+        libc::SOL_SOCKET,
+        libc::SO_TXTIME,
+        &config as *const _ as _,
+        core::mem::size_of_val(&config) as _,
+    ))
+    .is_ok()
+}
+
+/// Transmit times aren't supported on the current platform
+#[cfg(not(s2n_quic_platform_txtime))]
+fn set_txtime<S>(_socket: &S) -> bool {
+    false
+}
+
+/// Applies the application's requests to disable the features of the rx socket
+#[cfg(any(s2n_quic_platform_tos, s2n_quic_platform_pktinfo))]
+fn disable_rx_features<S: std::os::unix::io::AsRawFd, P: event::EndpointPublisher>(
+    socket: &S,
+    addr: &std::net::SocketAddr,
+    requests: features::Requests,
+    features: &Features,
+    publisher: &mut P,
+) -> io::Result<()> {
+    #[cfg(s2n_quic_platform_tos)]
+    if requests.ecn() && features.is_ecn_active() {
+        super::common::set_recv_tos(socket, addr, false)?;
+        features.set_ecn(false);
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Ecn { enabled: false },
+        });
     }
 
-    socket.set_reuse_address(true)?;
+    #[cfg(s2n_quic_platform_pktinfo)]
+    if requests.pktinfo() && features.is_pktinfo_active() {
+        super::common::set_recv_pktinfo(socket, addr, false)?;
+        features.set_pktinfo(false);
+    }
 
-    #[cfg(unix)]
-    socket.set_reuse_port(reuse_port)?;
+    // mark the variables as "used" regardless of platform support
+    let _ = (requests, publisher);
 
-    // mark the variable as "used" regardless of platform support
-    let _ = reuse_port;
+    Ok(())
+}
 
-    socket.bind(&addr.into())?;
+/// The rx socket features aren't configured on the current platform
+#[cfg(not(any(s2n_quic_platform_tos, s2n_quic_platform_pktinfo)))]
+fn disable_rx_features<S, P: event::EndpointPublisher>(
+    _socket: &S,
+    _addr: &std::net::SocketAddr,
+    _requests: features::Requests,
+    _features: &Features,
+    _publisher: &mut P,
+) -> io::Result<()> {
+    Ok(())
+}
 
-    Ok(socket)
+/// Disables GSO on the tx queue if the application requested it
+fn disable_tx_features<P: event::EndpointPublisher>(
+    tx: &mut socket::Queue<buffer::Buffer>,
+    requests: features::Requests,
+    features: &Features,
+    publisher: &mut P,
+) {
+    if requests.gso() && tx.max_gso() > 1 {
+        tx.disable_gso();
+        features.set_gso_max_segments(tx.max_gso());
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Gso {
+                max_segments: tx.max_gso(),
+            },
+        });
+    }
 }
 
 #[derive(Debug, Default)]
@@ -353,6 +580,13 @@ pub struct Builder {
     max_mtu: MaxMtu,
     max_segments: gso::MaxSegments,
     reuse_port: bool,
+    send_batch_size: Option<usize>,
+    recv_batch_size: Option<usize>,
+    rx_timestamps: bool,
+    rx_hardware_timestamps: bool,
+    pacing_offload: bool,
+    steering: bool,
+    features: Features,
 }
 
 impl Builder {
@@ -437,6 +671,27 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sets the maximum number of datagrams which are sent with a single syscall
+    ///
+    /// Batching datagrams with `sendmmsg` amortizes the cost of the syscall on platforms which
+    /// don't support GSO, or for datagrams which can't be segmented, e.g. to different peers.
+    /// Smaller batches return to the event loop sooner. The default is 1024, which is also the
+    /// maximum. The option has no effect on platforms without `sendmmsg`.
+    pub fn with_send_batch_size(mut self, send_batch_size: usize) -> io::Result<Self> {
+        self.send_batch_size = Some(batch_size(send_batch_size)?);
+        Ok(self)
+    }
+
+    /// Sets the maximum number of datagrams which are received with a single syscall
+    ///
+    /// Batching datagrams with `recvmmsg` amortizes the cost of the syscall. The default is
+    /// 1024, which is also the maximum. The option has no effect on platforms without
+    /// `recvmmsg`.
+    pub fn with_recv_batch_size(mut self, recv_batch_size: usize) -> io::Result<Self> {
+        self.recv_batch_size = Some(batch_size(recv_batch_size)?);
+        Ok(self)
+    }
+
     /// Enables the port reuse (SO_REUSEPORT) socket option
     pub fn with_reuse_port(mut self) -> io::Result<Self> {
         if !cfg!(unix) {
@@ -449,8 +704,192 @@ impl Builder {
         Ok(self)
     }
 
+    /// Enables timestamping of received datagrams with the software clock of the kernel
+    ///
+    /// The endpoint subtracts the time datagrams spent in the socket receive queue from the time
+    /// it received them, which excludes its own scheduling delays from the RTT samples. The
+    /// timestamps are also reported to event subscribers.
+    ///
+    /// Timestamping is only supported on Linux (SO_TIMESTAMPING).
+    pub fn with_rx_timestamps(mut self) -> io::Result<Self> {
+        if !cfg!(s2n_quic_platform_timestamping) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "rx timestamps are not supported on the current platform",
+            ));
+        }
+        self.rx_timestamps = true;
+        Ok(self)
+    }
+
+    /// Enables timestamping of received datagrams with both the software clock of the kernel and
+    /// the clock of the network interface
+    ///
+    /// The hardware timestamps are only reported to event subscribers, since the clock of the
+    /// network interface isn't necessarily synchronized with the system clock. They are only
+    /// available if timestamping was enabled on the network interface, e.g. with `hwstamp_ctl`.
+    ///
+    /// Timestamping is only supported on Linux (SO_TIMESTAMPING).
+    pub fn with_rx_hardware_timestamps(mut self) -> io::Result<Self> {
+        self = self.with_rx_timestamps()?;
+        self.rx_hardware_timestamps = true;
+        Ok(self)
+    }
+
+    /// Offloads the pacing of transmissions to the kernel (SO_TXTIME)
+    ///
+    /// Paced datagrams are handed to the kernel shortly before their departure time, along with
+    /// the time at which they should be sent, instead of the endpoint arming a timer for every
+    /// paced burst. This requires a qdisc which honors transmit times on the interface, e.g.
+    /// `fq`; other qdiscs send the datagrams right away.
+    ///
+    /// If the socket doesn't support transmit times, the endpoint keeps pacing transmissions
+    /// itself, which can be checked with [`Features::is_pacing_offload_active`].
+    pub fn with_pacing_offload(mut self) -> io::Result<Self> {
+        self.pacing_offload = true;
+        Ok(self)
+    }
+
+    /// Steers the datagrams of sharded endpoints to their shards in the kernel
+    ///
+    /// Instead of reading all of the datagrams in a single task and handing them to the shards,
+    /// [`Io::start_sharded`] binds a `SO_REUSEPORT` socket to the receive address for each shard
+    /// and attaches an eBPF program to them, which steers each datagram to the socket of the
+    /// shard that issued its destination connection ID. This avoids moving datagrams between
+    /// cores. Loading the program might require `CAP_BPF`, in which case starting the endpoints
+    /// fails.
+    ///
+    /// Steering requires a receive address, and is only supported on Linux.
+    pub fn with_steering(mut self) -> io::Result<Self> {
+        if !cfg!(target_os = "linux") {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "steering is not supported on the current platform",
+            ));
+        }
+        self.steering = true;
+        Ok(self)
+    }
+
+    /// Returns a builder with the same options for the socket of a steered shard
+    #[cfg(target_os = "linux")]
+    fn for_shard(&self, socket: socket2::Socket, handle: Handle) -> Self {
+        Self {
+            handle: Some(handle),
+            rx_socket: Some(socket),
+            tx_socket: None,
+            recv_addr: None,
+            send_addr: None,
+            recv_buffer_size: self.recv_buffer_size,
+            send_buffer_size: self.send_buffer_size,
+            max_mtu: self.max_mtu,
+            max_segments: self.max_segments,
+            reuse_port: true,
+            send_batch_size: self.send_batch_size,
+            recv_batch_size: self.recv_batch_size,
+            rx_timestamps: self.rx_timestamps,
+            rx_hardware_timestamps: self.rx_hardware_timestamps,
+            pacing_offload: self.pacing_offload,
+            steering: false,
+            features: self.features.clone(),
+        }
+    }
+
+    /// Returns `true` if the rx socket is configured to report timestamps
+    fn is_rx_timestamping_enabled(&self) -> bool {
+        cfg!(s2n_quic_platform_timestamping) && self.rx_timestamps
+    }
+
+    /// Opens and configures the sockets
+    ///
+    /// Returns the rx socket, the tx socket, and the local address of the rx socket.
+    fn open_sockets(
+        &mut self,
+    ) -> io::Result<(socket2::Socket, socket2::Socket, std::net::SocketAddr)> {
+        let rx_socket = if let Some(rx_socket) = self.rx_socket.take() {
+            // ensure the socket is non-blocking
+            rx_socket.set_nonblocking(true)?;
+            rx_socket
+        } else if let Some(recv_addr) = self.recv_addr {
+            bind(recv_addr, self.reuse_port)?
+        } else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "missing bind address",
+            ));
+        };
+
+        let tx_socket = if let Some(tx_socket) = self.tx_socket.take() {
+            // ensure the socket is non-blocking
+            tx_socket.set_nonblocking(true)?;
+            tx_socket
+        } else if let Some(send_addr) = self.send_addr {
+            bind(send_addr, self.reuse_port)?
+        } else {
+            // No tx_socket or send address was specified, so the tx socket
+            // will be a handle to the rx socket.
+            rx_socket.try_clone()?
+        };
+
+        if let Some(size) = self.send_buffer_size {
+            tx_socket.set_send_buffer_size(size)?;
+        }
+
+        if let Some(size) = self.recv_buffer_size {
+            rx_socket.set_recv_buffer_size(size)?;
+        }
+
+        fn convert_addr_to_std(addr: socket2::SockAddr) -> io::Result<std::net::SocketAddr> {
+            addr.as_socket().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "invalid domain for socket")
+            })
+        }
+
+        #[allow(unused_variables)] // some platform builds won't use these so ignore warnings
+        let (tx_addr, rx_addr) = (
+            convert_addr_to_std(tx_socket.local_addr()?)?,
+            convert_addr_to_std(rx_socket.local_addr()?)?,
+        );
+
+        #[cfg(s2n_quic_platform_mtu_disc)]
+        super::common::set_mtu_discovery(&tx_socket, &tx_addr)?;
+
+        // Set up the RX socket to pass ECN information
+        #[cfg(s2n_quic_platform_tos)]
+        super::common::set_recv_tos(&rx_socket, &rx_addr, true)?;
+
+        // Set up the RX socket to pass information about the local address and interface
+        #[cfg(s2n_quic_platform_pktinfo)]
+        super::common::set_recv_pktinfo(&rx_socket, &rx_addr, true)?;
+
+        // Set up the RX socket to report when datagrams were received
+        #[cfg(s2n_quic_platform_timestamping)]
+        if self.rx_timestamps {
+            set_rx_timestamping(&rx_socket, self.rx_hardware_timestamps)?;
+        }
+
+        Ok((rx_socket, tx_socket, rx_addr))
+    }
+
     pub fn build(self) -> io::Result<Io> {
-        Ok(Io { builder: self })
+        Ok(Io {
+            builder: self,
+            driver: None,
+        })
+    }
+
+    /// Builds the IO provider without spawning a task for the endpoint
+    ///
+    /// Instead, the event loop is handed to the returned [`Driver`] when the endpoint is started,
+    /// which the application polls on a task or thread of its choosing. A runtime is not required
+    /// to start the endpoint in this mode.
+    pub fn build_no_spawn(self) -> io::Result<(Io, Driver)> {
+        let (sender, driver) = driver::new();
+        let io = Io {
+            builder: self,
+            driver: Some(sender),
+        };
+        Ok((io, driver))
     }
 }
 
@@ -458,9 +897,11 @@ impl Builder {
 struct Instance<E> {
     clock: Clock,
     rx_socket: std::net::UdpSocket,
+    rx_addr: std::net::SocketAddr,
     tx_socket: std::net::UdpSocket,
     rx: socket::Queue<buffer::Buffer>,
     tx: socket::Queue<buffer::Buffer>,
+    features: Features,
     endpoint: E,
 }
 
@@ -469,9 +910,11 @@ impl<E: Endpoint<PathHandle = PathHandle>> Instance<E> {
         let Self {
             clock,
             rx_socket,
+            rx_addr,
             tx_socket,
             mut rx,
             mut tx,
+            features,
             mut endpoint,
         } = self;
 
@@ -486,6 +929,7 @@ impl<E: Endpoint<PathHandle = PathHandle>> Instance<E> {
         }
 
         let mut timer = clock.timer();
+        let mut applied_requests = features::Requests::default();
 
         loop {
             // Poll for readability if we have free slots available
@@ -542,10 +986,24 @@ impl<E: Endpoint<PathHandle = PathHandle>> Instance<E> {
                 application_wakeup,
             });
 
+            let requests = features.take_requests(&mut applied_requests);
+            if !requests.is_empty() {
+                disable_tx_features(&mut tx, requests, &features, &mut publisher);
+                disable_rx_features(
+                    rx_socket.get_ref(),
+                    &rx_addr,
+                    requests,
+                    &features,
+                    &mut publisher,
+                )?;
+            }
+
             if let Some(guard) = tx_result {
                 if let Ok(result) = guard?.try_io(|socket| tx.tx(socket, &mut publisher)) {
                     result?;
                 }
+                // GSO is disabled if the kernel rejects segmented datagrams
+                features.set_gso_max_segments(tx.max_gso());
             }
 
             if let Some(guard) = rx_result {
@@ -602,6 +1060,10 @@ mod async_fd_shim {
             Ok(Self(socket))
         }
 
+        pub fn get_ref(&self) -> &tokio::net::UdpSocket {
+            &self.0
+        }
+
         pub async fn readable(&self) -> io::Result<TryIo<'_>> {
             self.0.readable().await?;
             Ok(TryIo(&self.0))
@@ -637,6 +1099,7 @@ mod tests {
         task::{Context, Poll},
     };
     use s2n_quic_core::{
+        connection::id::shard,
         endpoint::{self, CloseError},
         event,
         inet::SocketAddress,
@@ -647,25 +1110,109 @@ mod tests {
         path::Handle as _,
         time::{Clock, Duration, Timestamp},
     };
-    use std::collections::BTreeMap;
+    use std::{
+        collections::BTreeMap,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
 
     struct TestEndpoint {
         addr: SocketAddress,
         messages: BTreeMap<u32, Option<Timestamp>>,
+        shard: usize,
+        shards: usize,
         now: Option<Timestamp>,
+        /// Set if every received datagram should carry a software timestamp
+        expect_rx_timestamps: bool,
+        /// The delay which is set on every transmitted datagram
+        tx_delay: Duration,
+        /// The number of endpoints which are still waiting for their messages
+        ///
+        /// The endpoint only closes once every endpoint which shares the counter is done.
+        pending: Arc<AtomicUsize>,
+        /// Set once the endpoint received all of its messages
+        done: bool,
         subscriber: NoopSubscriber,
     }
 
     impl TestEndpoint {
         fn new(addr: SocketAddress) -> Self {
-            let messages = (0..1000).map(|id| (id, None)).collect();
+            Self::with_shard(addr, 0, 1)
+        }
+
+        /// Creates an endpoint which only sends the messages that are routed to the `shard`
+        fn with_shard(addr: SocketAddress, shard: usize, shards: usize) -> Self {
+            let messages = (0..1000)
+                .filter(|id: &u32| shard::index_for_datagram(&id.to_be_bytes(), shards) == shard)
+                .map(|id| (id, None))
+                .collect();
             Self {
                 addr,
                 messages,
+                shard,
+                shards,
                 now: None,
+                expect_rx_timestamps: false,
+                tx_delay: Duration::ZERO,
+                pending: Arc::new(AtomicUsize::new(1)),
+                done: false,
                 subscriber: Default::default(),
             }
         }
+
+        /// Creates an endpoint for each of the `shards`, which stay open until all of the shards
+        /// received their messages
+        #[cfg(target_os = "linux")]
+        fn shards(addr: SocketAddress, shards: usize) -> Vec<Self> {
+            let pending = Arc::new(AtomicUsize::new(shards));
+            (0..shards)
+                .map(|shard| Self {
+                    pending: pending.clone(),
+                    ..Self::with_shard(addr, shard, shards)
+                })
+                .collect()
+        }
+    }
+
+    /// A datagram which is held back by the socket, if it supports transmit times
+    struct DelayedMessage {
+        handle: PathHandle,
+        payload: [u8; 4],
+        delay: Duration,
+    }
+
+    impl tx::Message for DelayedMessage {
+        type Handle = PathHandle;
+
+        fn path_handle(&self) -> &Self::Handle {
+            &self.handle
+        }
+
+        fn ecn(&mut self) -> inet::ExplicitCongestionNotification {
+            Default::default()
+        }
+
+        fn delay(&mut self) -> Duration {
+            self.delay
+        }
+
+        fn ipv6_flow_label(&mut self) -> u32 {
+            0
+        }
+
+        fn can_gso(&self, segment_len: usize, _segment_count: usize) -> bool {
+            self.delay.is_zero() && segment_len >= self.payload.len()
+        }
+
+        fn write_payload(
+            &mut self,
+            mut buffer: tx::PayloadBuffer,
+            _gso_offset: usize,
+        ) -> Result<usize, tx::Error> {
+            buffer.write(&self.payload)
+        }
     }
 
     #[derive(Debug, Default)]
@@ -704,9 +1251,11 @@ mod tests {
                         continue
                     }
                     _ => {
-                        let payload = id.to_be_bytes();
-                        let addr = PathHandle::from_remote_address(self.addr.into());
-                        let msg = (addr, payload);
+                        let msg = DelayedMessage {
+                            handle: PathHandle::from_remote_address(self.addr.into()),
+                            payload: id.to_be_bytes(),
+                            delay: self.tx_delay,
+                        };
                         if queue.push(msg).is_ok() {
                             *tx_time = Some(now);
                         } else {
@@ -729,8 +1278,17 @@ mod tests {
             let entries = queue.as_slice_mut();
             let len = entries.len();
             for entry in entries {
-                if let Some((_header, payload)) = entry.read(&local_address) {
+                if let Some((header, payload)) = entry.read(&local_address) {
                     assert_eq!(payload.len(), 4, "invalid payload {:?}", payload);
+                    if self.expect_rx_timestamps {
+                        assert!(header.rx_timestamps.software.is_some());
+                        assert!(header.rx_timestamps.queue_delay.is_some());
+                    }
+                    assert_eq!(
+                        shard::index_for_datagram(payload, self.shards),
+                        self.shard,
+                        "datagram routed to the wrong shard"
+                    );
 
                     let id = (&*payload).try_into().unwrap();
                     let id = u32::from_be_bytes(id);
@@ -748,7 +1306,12 @@ mod tests {
             let now = clock.get_time();
             self.now = Some(now);
 
-            if self.messages.is_empty() {
+            if self.messages.is_empty() && !self.done {
+                self.done = true;
+                self.pending.fetch_sub(1, Ordering::Relaxed);
+            }
+
+            if self.pending.load(Ordering::Relaxed) == 0 {
                 return Err(CloseError).into();
             }
 
@@ -771,6 +1334,14 @@ mod tests {
     async fn test<A: std::net::ToSocketAddrs>(
         receive_addr: A,
         send_addr: Option<A>,
+    ) -> io::Result<()> {
+        test_with(receive_addr, send_addr, false).await
+    }
+
+    async fn test_with<A: std::net::ToSocketAddrs>(
+        receive_addr: A,
+        send_addr: Option<A>,
+        no_spawn: bool,
     ) -> io::Result<()> {
         let rx_socket = bind(receive_addr, false)?;
         let rx_socket: std::net::UdpSocket = rx_socket.into();
@@ -784,17 +1355,28 @@ mod tests {
             io_builder = io_builder.with_tx_socket(tx_socket)?
         }
 
-        let io = io_builder.build()?;
-
         let endpoint = TestEndpoint::new(addr.into());
 
-        let (task, local_addr) = io.start(endpoint)?;
+        if no_spawn {
+            let (io, driver) = io_builder.build_no_spawn()?;
 
-        let local_addr: std::net::SocketAddr = local_addr.into();
+            let (task, local_addr) = io.start(endpoint)?;
+            assert!(task.is_none());
 
-        assert_eq!(local_addr, addr);
+            let local_addr: std::net::SocketAddr = local_addr.into();
+            assert_eq!(local_addr, addr);
+
+            driver.await?;
+        } else {
+            let io = io_builder.build()?;
+
+            let (task, local_addr) = io.start(endpoint)?;
 
-        task.await?;
+            let local_addr: std::net::SocketAddr = local_addr.into();
+            assert_eq!(local_addr, addr);
+
+            task.expect("the endpoint should be spawned").await?;
+        }
 
         Ok(())
     }
@@ -809,6 +1391,218 @@ mod tests {
         test("127.0.0.1:0", Some("127.0.0.1:0")).await
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn ipv4_sharded_test() -> io::Result<()> {
+        let rx_socket: std::net::UdpSocket = bind("127.0.0.1:0", false)?.into();
+        let addr = rx_socket.local_addr()?;
+
+        let shards = 4;
+        let endpoints = (0..shards)
+            .map(|shard| TestEndpoint::with_shard(addr.into(), shard, shards))
+            .collect();
+
+        let io = Io::builder().with_rx_socket(rx_socket)?.build()?;
+        let (tasks, local_addr) = io.start_sharded(endpoints)?;
+
+        let local_addr: std::net::SocketAddr = local_addr.into();
+        assert_eq!(local_addr, addr);
+
+        // a task for each shard and the dispatcher
+        assert_eq!(tasks.len(), shards + 1);
+
+        for task in tasks {
+            task.await?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn ipv4_steered_test() -> io::Result<()> {
+        // reserve a port for the shards
+        let addr = std::net::UdpSocket::bind("127.0.0.1:0")?.local_addr()?;
+
+        // closing a socket renumbers the sockets of the reuse port group, so the shards stay open
+        // until all of them are done
+        let shards = 4;
+        let endpoints = TestEndpoint::shards(addr.into(), shards);
+
+        // the kernel steers the segments of a GSO datagram together and the test payloads are
+        // shorter than the key, which would then include the bytes of the following segments
+        let io = Io::builder()
+            .with_receive_address(addr)?
+            .with_steering()?
+            .with_gso_disabled()?
+            .build()?;
+
+        let (tasks, local_addr) = match io.start_sharded(endpoints) {
+            Ok(result) => result,
+            // eBPF might not be available to the test process
+            Err(err) if err.kind() == io::ErrorKind::PermissionDenied => return Ok(()),
+            Err(err) => return Err(err),
+        };
+
+        let local_addr: std::net::SocketAddr = local_addr.into();
+        assert_eq!(local_addr, addr);
+
+        // a task for each shard without a dispatcher
+        assert_eq!(tasks.len(), shards);
+
+        for task in tasks {
+            task.await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn batch_size_test() -> io::Result<()> {
+        for batch_size in [1, 7, MAX_BATCH_SIZE] {
+            let rx_socket: std::net::UdpSocket = bind("127.0.0.1:0", false)?.into();
+            let addr = rx_socket.local_addr()?;
+
+            let io = Io::builder()
+                .with_rx_socket(rx_socket)?
+                .with_send_batch_size(batch_size)?
+                .with_recv_batch_size(batch_size)?
+                .build()?;
+
+            let (task, _local_addr) = io.start(TestEndpoint::new(addr.into()))?;
+            task.expect("the endpoint should be spawned").await?;
+        }
+
+        for batch_size in [0, MAX_BATCH_SIZE + 1] {
+            let err = Io::builder().with_send_batch_size(batch_size).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+            let err = Io::builder().with_recv_batch_size(batch_size).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(s2n_quic_platform_timestamping)]
+    #[tokio::test]
+    async fn rx_timestamps_test() -> io::Result<()> {
+        let rx_socket: std::net::UdpSocket = bind("127.0.0.1:0", false)?.into();
+        let addr = rx_socket.local_addr()?;
+
+        let io = Io::builder()
+            .with_rx_socket(rx_socket)?
+            .with_rx_timestamps()?
+            .build()?;
+        let features = io.features();
+
+        let mut endpoint = TestEndpoint::new(addr.into());
+        endpoint.expect_rx_timestamps = true;
+        let (task, _local_addr) = io.start(endpoint)?;
+        task.expect("the endpoint should be spawned").await?;
+
+        assert!(features.is_rx_timestamping_active());
+
+        Ok(())
+    }
+
+    #[cfg(s2n_quic_platform_txtime)]
+    #[tokio::test]
+    async fn pacing_offload_test() -> io::Result<()> {
+        let rx_socket: std::net::UdpSocket = bind("127.0.0.1:0", false)?.into();
+        let addr = rx_socket.local_addr()?;
+
+        let io = Io::builder()
+            .with_rx_socket(rx_socket)?
+            .with_pacing_offload()?
+            .build()?;
+        let features = io.features();
+
+        // the datagrams still arrive when they are held back by the kernel
+        let mut endpoint = TestEndpoint::new(addr.into());
+        endpoint.tx_delay = Duration::from_millis(1);
+        let (task, _local_addr) = io.start(endpoint)?;
+        task.expect("the endpoint should be spawned").await?;
+
+        assert!(features.is_pacing_offload_active());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn disabled_features_test() -> io::Result<()> {
+        let rx_socket: std::net::UdpSocket = bind("127.0.0.1:0", false)?.into();
+        let addr = rx_socket.local_addr()?;
+
+        let io = Io::builder().with_rx_socket(rx_socket)?.build()?;
+        let features = io.features();
+
+        // the requests are applied once the event loop wakes up
+        features.disable_gso();
+        features.disable_ecn();
+        features.disable_pktinfo();
+
+        let (task, _local_addr) = io.start(TestEndpoint::new(addr.into()))?;
+        task.expect("the endpoint should be spawned").await?;
+
+        assert_eq!(features.gso_max_segments(), 1);
+        assert!(!features.is_gso_active());
+        assert!(!features.is_gro_active());
+        assert!(!features.is_ecn_active());
+        assert!(!features.is_pktinfo_active());
+        assert!(!features.is_rx_timestamping_active());
+        assert!(!features.is_pacing_offload_active());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sharded_no_spawn_test() -> io::Result<()> {
+        let (io, _driver) = Io::builder()
+            .with_receive_address("127.0.0.1:0".parse().unwrap())?
+            .build_no_spawn()?;
+
+        let endpoints = (0..2)
+            .map(|shard| TestEndpoint::with_shard(Default::default(), shard, 2))
+            .collect();
+
+        let err = io.start_sharded(endpoints).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ipv4_no_spawn_test() -> io::Result<()> {
+        test_with("127.0.0.1:0", None, true).await
+    }
+
+    #[test]
+    fn no_spawn_start_without_runtime_test() -> io::Result<()> {
+        let rx_socket: std::net::UdpSocket = bind("127.0.0.1:0", false)?.into();
+        let addr = rx_socket.local_addr()?;
+
+        let (io, driver) = Io::builder().with_rx_socket(rx_socket)?.build_no_spawn()?;
+
+        // the endpoint can be started outside of a runtime
+        io.start(TestEndpoint::new(addr.into()))?;
+
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(driver)
+    }
+
+    #[tokio::test]
+    async fn no_spawn_dropped_test() -> io::Result<()> {
+        let (io, driver) = Io::builder().build_no_spawn()?;
+        drop(io);
+
+        let err = driver.await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotConnected);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn ipv6_test() -> io::Result<()> {
         match test(("::1", 0), None).await {
@@ -0,0 +1,126 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+use tokio::runtime::Handle;
+
+pub type EventLoop = Pin<Box<dyn Future<Output = io::Result<()>> + Send>>;
+
+#[derive(Default)]
+struct Slot {
+    /// The event loop and the runtime it should be polled in, once the endpoint is started
+    event_loop: Option<(EventLoop, Option<Handle>)>,
+    /// The waker of the driver waiting for the endpoint to be started
+    waker: Option<Waker>,
+    /// Set when the sender is dropped
+    is_closed: bool,
+}
+
+impl Slot {
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+pub fn new() -> (Sender, Driver) {
+    let slot = Arc::new(Mutex::new(Slot::default()));
+    let sender = Sender(slot.clone());
+    let driver = Driver {
+        slot,
+        event_loop: None,
+        handle: None,
+    };
+    (sender, driver)
+}
+
+/// Hands the event loop of a started endpoint to the [`Driver`]
+pub struct Sender(Arc<Mutex<Slot>>);
+
+impl Sender {
+    pub fn send(self, event_loop: EventLoop, handle: Option<Handle>) {
+        let mut slot = self.0.lock().unwrap();
+        slot.event_loop = Some((event_loop, handle));
+        slot.wake();
+    }
+}
+
+impl Drop for Sender {
+    fn drop(&mut self) {
+        let mut slot = self.0.lock().unwrap();
+        slot.is_closed = true;
+        slot.wake();
+    }
+}
+
+impl fmt::Debug for Sender {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+/// Drives the IO of an endpoint started without spawning a task
+///
+/// The endpoint makes no progress until the driver is polled. The driver completes once the
+/// endpoint has shut down or a fatal IO error occurs.
+///
+/// The sockets and timers are registered with Tokio, so the driver must be polled within the
+/// context of a Tokio runtime, unless a runtime handle was configured with
+/// [`Builder::with_handle`](super::Builder::with_handle). A `current_thread` runtime is sufficient.
+#[must_use = "the endpoint makes no progress unless the driver is polled"]
+pub struct Driver {
+    slot: Arc<Mutex<Slot>>,
+    event_loop: Option<EventLoop>,
+    handle: Option<Handle>,
+}
+
+impl fmt::Debug for Driver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Driver")
+            .field("is_started", &self.event_loop.is_some())
+            .finish()
+    }
+}
+
+impl Future for Driver {
+    type Output = io::Result<()>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+
+        if this.event_loop.is_none() {
+            let mut slot = this.slot.lock().unwrap();
+
+            if let Some((event_loop, handle)) = slot.event_loop.take() {
+                this.event_loop = Some(event_loop);
+                this.handle = handle;
+            } else if slot.is_closed {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::NotConnected,
+                    "the IO provider was dropped without starting an endpoint",
+                )));
+            } else {
+                slot.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+        }
+
+        let _guard = this.handle.as_ref().map(|handle| handle.enter());
+
+        this.event_loop
+            .as_mut()
+            .expect("the event loop is set above")
+            .as_mut()
+            .poll(cx)
+    }
+}
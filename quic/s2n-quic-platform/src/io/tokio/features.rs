@@ -0,0 +1,197 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::{
+    atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering},
+    Arc,
+};
+
+const GSO: u8 = 1;
+const ECN: u8 = 1 << 1;
+const PKTINFO: u8 = 1 << 2;
+
+/// Reports the socket features which are active for an endpoint
+///
+/// The handle can be obtained from the IO provider before it is started, and is updated once the
+/// sockets are configured. Features can be disabled while the endpoint is running, e.g. when a
+/// kernel bug is suspected, without re-creating the endpoint. Requests are applied the next time
+/// the event loop wakes up.
+#[derive(Clone, Debug, Default)]
+pub struct Features(Arc<State>);
+
+#[derive(Debug, Default)]
+struct State {
+    gso_max_segments: AtomicUsize,
+    ecn: AtomicBool,
+    pktinfo: AtomicBool,
+    rx_timestamping: AtomicBool,
+    pacing_offload: AtomicBool,
+    /// The features the application asked to disable
+    disabled: AtomicU8,
+}
+
+impl Features {
+    /// Returns the maximum number of segments sent in a single datagram with Generic
+    /// Segmentation Offload (GSO)
+    ///
+    /// A value of 1 indicates that GSO is inactive.
+    pub fn gso_max_segments(&self) -> usize {
+        self.0.gso_max_segments.load(Ordering::Relaxed).max(1)
+    }
+
+    /// Returns `true` if the endpoint transmits with GSO
+    ///
+    /// GSO is disabled automatically if the kernel rejects segmented datagrams.
+    pub fn is_gso_active(&self) -> bool {
+        self.gso_max_segments() > 1
+    }
+
+    /// Returns `true` if the endpoint receives with Generic Receive Offload (GRO)
+    ///
+    /// GRO isn't supported by the IO provider yet, so this always returns `false`.
+    pub fn is_gro_active(&self) -> bool {
+        false
+    }
+
+    /// Returns `true` if the socket reports the ECN markings of received datagrams
+    pub fn is_ecn_active(&self) -> bool {
+        self.0.ecn.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the socket reports the local address and interface of received
+    /// datagrams
+    pub fn is_pktinfo_active(&self) -> bool {
+        self.0.pktinfo.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the socket reports the time at which datagrams were received
+    ///
+    /// Timestamping is disabled by default and can be enabled with the IO provider builder.
+    pub fn is_rx_timestamping_active(&self) -> bool {
+        self.0.rx_timestamping.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the kernel paces the transmissions of the endpoint
+    ///
+    /// Pacing is only offloaded if it was requested with the IO provider builder and the socket
+    /// supports transmit times.
+    pub fn is_pacing_offload_active(&self) -> bool {
+        self.0.pacing_offload.load(Ordering::Relaxed)
+    }
+
+    /// Disables GSO for the remaining lifetime of the endpoint
+    pub fn disable_gso(&self) {
+        self.request_disable(GSO);
+    }
+
+    /// Stops the socket from reporting the ECN markings of received datagrams
+    ///
+    /// Datagrams sent by the endpoint keep their ECN markings, which are controlled by the
+    /// validation of each path.
+    pub fn disable_ecn(&self) {
+        self.request_disable(ECN);
+    }
+
+    /// Stops the socket from reporting the local address and interface of received datagrams
+    ///
+    /// Received datagrams are attributed to the bound address instead.
+    pub fn disable_pktinfo(&self) {
+        self.request_disable(PKTINFO);
+    }
+
+    fn request_disable(&self, feature: u8) {
+        self.0.disabled.fetch_or(feature, Ordering::Relaxed);
+    }
+
+    pub(super) fn set_gso_max_segments(&self, max_segments: usize) {
+        self.0
+            .gso_max_segments
+            .store(max_segments, Ordering::Relaxed);
+    }
+
+    pub(super) fn set_ecn(&self, enabled: bool) {
+        self.0.ecn.store(enabled, Ordering::Relaxed);
+    }
+
+    pub(super) fn set_pktinfo(&self, enabled: bool) {
+        self.0.pktinfo.store(enabled, Ordering::Relaxed);
+    }
+
+    pub(super) fn set_rx_timestamping(&self, enabled: bool) {
+        self.0.rx_timestamping.store(enabled, Ordering::Relaxed);
+    }
+
+    pub(super) fn set_pacing_offload(&self, enabled: bool) {
+        self.0.pacing_offload.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Returns the features which were disabled since the event loop last checked
+    ///
+    /// Each event loop tracks the requests it already applied, since the sockets may be shared
+    /// by multiple event loops.
+    pub(super) fn take_requests(&self, applied: &mut Requests) -> Requests {
+        let pending = self.0.disabled.load(Ordering::Relaxed) & !applied.0;
+        applied.0 |= pending;
+        Requests(pending)
+    }
+}
+
+/// A set of features which the application requested to disable
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct Requests(u8);
+
+impl Requests {
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn gso(self) -> bool {
+        self.0 & GSO != 0
+    }
+
+    pub fn ecn(self) -> bool {
+        self.0 & ECN != 0
+    }
+
+    pub fn pktinfo(self) -> bool {
+        self.0 & PKTINFO != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_test() {
+        let features = Features::default();
+        let mut applied = Requests::default();
+        assert!(features.take_requests(&mut applied).is_empty());
+        assert_eq!(features.gso_max_segments(), 1);
+        assert!(!features.is_gso_active());
+
+        let handle = features.clone();
+        handle.disable_gso();
+        handle.disable_pktinfo();
+
+        let requests = features.take_requests(&mut applied);
+        assert!(requests.gso());
+        assert!(!requests.ecn());
+        assert!(requests.pktinfo());
+
+        // requests are only taken once by each event loop
+        assert!(features.take_requests(&mut applied).is_empty());
+        handle.disable_gso();
+        assert!(features.take_requests(&mut applied).is_empty());
+
+        // other event loops sharing the sockets still see them
+        let mut other = Requests::default();
+        assert!(features.take_requests(&mut other).gso());
+
+        features.set_gso_max_segments(10);
+        features.set_ecn(true);
+        assert!(handle.is_gso_active());
+        assert!(handle.is_ecn_active());
+        assert!(!handle.is_pktinfo_active());
+    }
+}
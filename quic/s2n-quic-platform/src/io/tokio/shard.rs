@@ -0,0 +1,355 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Runs multiple endpoints which share a single set of sockets
+//!
+//! The [`Dispatcher`] reads datagrams from the rx socket and hands them to the [`Worker`] of
+//! each shard through a bounded channel. The channel is backed by a lock-free queue, so the
+//! dispatcher never blocks on a worker which is busy processing its connections.
+
+use super::{
+    buffer, disable_rx_features, disable_tx_features, features,
+    select::{self, Select},
+    socket, Clock, Features, PathHandle,
+};
+use cfg_if::cfg_if;
+use s2n_quic_core::{
+    connection::id::shard,
+    endpoint::{self, Endpoint},
+    event::{self, EndpointPublisher as _},
+    inet::datagram,
+    io::rx::{self, Entry as _, Queue as _},
+    path::LocalAddress,
+    time::Clock as ClockTrait,
+};
+use std::{collections::VecDeque, io};
+use tokio::sync::mpsc::{self, error::TryRecvError};
+
+/// The number of datagrams which can be queued for each shard
+const CAPACITY: usize = 1024;
+
+/// A datagram handed from the dispatcher to a worker
+#[derive(Debug)]
+pub struct Datagram {
+    header: datagram::Header<PathHandle>,
+    payload: Vec<u8>,
+}
+
+pub type Sender = mpsc::Sender<Datagram>;
+pub type Receiver = mpsc::Receiver<Datagram>;
+
+pub fn channel() -> (Sender, Receiver) {
+    mpsc::channel(CAPACITY)
+}
+
+/// Reads datagrams from the rx socket and routes them to the shards
+#[derive(Debug)]
+pub struct Dispatcher {
+    pub endpoint_type: endpoint::Type,
+    pub rx_socket: std::net::UdpSocket,
+    pub rx_addr: std::net::SocketAddr,
+    pub rx: socket::Queue<buffer::Buffer>,
+    pub features: Features,
+    pub senders: Vec<Sender>,
+}
+
+impl Dispatcher {
+    pub async fn event_loop(self) -> io::Result<()> {
+        let Self {
+            endpoint_type,
+            rx_socket,
+            rx_addr,
+            mut rx,
+            features,
+            senders,
+        } = self;
+
+        cfg_if! {
+            if #[cfg(any(s2n_quic_platform_socket_msg, s2n_quic_platform_socket_mmsg))] {
+                let rx_socket = tokio::io::unix::AsyncFd::new(rx_socket)?;
+            } else {
+                let rx_socket = super::async_fd_shim::AsyncFd::new(rx_socket)?;
+            }
+        }
+
+        let clock = Clock::default();
+        let mut subscriber = Subscriber;
+        let mut applied_requests = features::Requests::default();
+
+        // the endpoints have all shut down once every receiver is dropped
+        let closed = async {
+            for sender in &senders {
+                sender.closed().await;
+            }
+        };
+        tokio::pin!(closed);
+
+        loop {
+            let readable = rx_socket.readable();
+            tokio::pin!(readable);
+
+            let guard = match futures::future::select(readable, closed.as_mut()).await {
+                futures::future::Either::Left((guard, _)) => guard,
+                futures::future::Either::Right(_) => return Ok(()),
+            };
+
+            let mut publisher = event::EndpointPublisherSubscriber::new(
+                event::builder::EndpointMeta {
+                    endpoint_type,
+                    timestamp: clock.get_time(),
+                },
+                None,
+                &mut subscriber,
+            );
+
+            // the workers apply the requests for the tx sockets
+            let requests = features.take_requests(&mut applied_requests);
+            if !requests.is_empty() {
+                disable_rx_features(
+                    rx_socket.get_ref(),
+                    &rx_addr,
+                    requests,
+                    &features,
+                    &mut publisher,
+                )?;
+            }
+
+            if let Ok(result) = guard?.try_io(|socket| rx.rx(socket, &mut publisher)) {
+                result?;
+            }
+
+            let mut queue = rx.rx_queue();
+            let local_address = queue.local_address();
+            let entries = queue.as_slice_mut();
+
+            for entry in entries.iter_mut() {
+                if let Some((header, payload)) = entry.read(&local_address) {
+                    let index = shard::index_for_datagram(payload, senders.len());
+                    let datagram = Datagram {
+                        header,
+                        payload: payload.to_vec(),
+                    };
+
+                    // drop the datagram if the shard is too far behind or has shut down, the
+                    // same as if the socket's receive buffer was full
+                    let _ = senders[index].try_send(datagram);
+                }
+            }
+
+            let len = entries.len();
+            queue.finish(len);
+        }
+    }
+}
+
+/// Processes the connections of a single shard
+#[derive(Debug)]
+pub struct Worker<E> {
+    pub clock: Clock,
+    pub receiver: Receiver,
+    pub rx: Queue,
+    pub tx_socket: std::net::UdpSocket,
+    pub tx: socket::Queue<buffer::Buffer>,
+    pub features: Features,
+    pub endpoint: E,
+}
+
+impl<E: Endpoint<PathHandle = PathHandle>> Worker<E> {
+    pub async fn event_loop(self) -> io::Result<()> {
+        let Self {
+            clock,
+            mut receiver,
+            mut rx,
+            tx_socket,
+            mut tx,
+            features,
+            mut endpoint,
+        } = self;
+
+        cfg_if! {
+            if #[cfg(any(s2n_quic_platform_socket_msg, s2n_quic_platform_socket_mmsg))] {
+                let tx_socket = tokio::io::unix::AsyncFd::new(tx_socket)?;
+            } else {
+                let tx_socket = super::async_fd_shim::AsyncFd::new(tx_socket)?;
+            }
+        }
+
+        let mut timer = clock.timer();
+        let mut applied_requests = features::Requests::default();
+
+        loop {
+            let rx_task = receiver.recv();
+
+            // Poll for writablity if we have occupied slots available
+            let tx_interest = tx.occupied_len() > 0;
+            let tx_task = async {
+                if tx_interest {
+                    tx_socket.writable().await
+                } else {
+                    futures::future::pending().await
+                }
+            };
+
+            let wakeups = endpoint.wakeups(&clock);
+            // pin the wakeups future so we don't have to move it into the Select future.
+            tokio::pin!(wakeups);
+
+            let select::Outcome {
+                rx_result,
+                tx_result,
+                timeout_expired,
+                application_wakeup,
+            } = if let Ok(res) = Select::new(rx_task, tx_task, &mut wakeups, &mut timer).await {
+                res
+            } else {
+                // The endpoint has shut down
+                return Ok(());
+            };
+
+            let wakeup_timestamp = clock.get_time();
+            let subscriber = endpoint.subscriber();
+            let mut publisher = event::EndpointPublisherSubscriber::new(
+                event::builder::EndpointMeta {
+                    endpoint_type: E::ENDPOINT_TYPE,
+                    timestamp: wakeup_timestamp,
+                },
+                None,
+                subscriber,
+            );
+
+            publisher.on_platform_event_loop_wakeup(event::builder::PlatformEventLoopWakeup {
+                timeout_expired,
+                rx_ready: rx_result.is_some(),
+                tx_ready: tx_result.is_some(),
+                application_wakeup,
+            });
+
+            let requests = features.take_requests(&mut applied_requests);
+            if !requests.is_empty() {
+                disable_tx_features(&mut tx, requests, &features, &mut publisher);
+            }
+
+            if let Some(guard) = tx_result {
+                if let Ok(result) = guard?.try_io(|socket| tx.tx(socket, &mut publisher)) {
+                    result?;
+                }
+                // GSO is disabled if the kernel rejects segmented datagrams
+                features.set_gso_max_segments(tx.max_gso());
+            }
+
+            if let Some(datagram) = rx_result {
+                if let Some(datagram) = datagram {
+                    rx.push(datagram);
+                } else {
+                    // the dispatcher has shut down so no more datagrams will be received
+                    return Ok(());
+                }
+
+                // take all of the datagrams which are ready
+                loop {
+                    match receiver.try_recv() {
+                        Ok(datagram) => rx.push(datagram),
+                        Err(TryRecvError::Empty) => break,
+                        Err(TryRecvError::Disconnected) => return Ok(()),
+                    }
+                }
+
+                publisher.on_platform_rx(event::builder::PlatformRx { count: rx.len() });
+
+                endpoint.receive(&mut rx, &clock);
+            }
+
+            endpoint.transmit(&mut tx.tx_queue(), &clock);
+
+            let timeout = endpoint.timeout();
+
+            if let Some(timeout) = timeout {
+                timer.update(timeout);
+            }
+
+            let timestamp = clock.get_time();
+            let subscriber = endpoint.subscriber();
+            let mut publisher = event::EndpointPublisherSubscriber::new(
+                event::builder::EndpointMeta {
+                    endpoint_type: E::ENDPOINT_TYPE,
+                    timestamp,
+                },
+                None,
+                subscriber,
+            );
+
+            // notify the application that we're going to sleep
+            let timeout = timeout.map(|t| t.saturating_duration_since(timestamp));
+            publisher.on_platform_event_loop_sleep(event::builder::PlatformEventLoopSleep {
+                timeout,
+                processing_duration: timestamp.saturating_duration_since(wakeup_timestamp),
+            });
+        }
+    }
+}
+
+/// The rx queue of a worker, containing the datagrams handed off by the dispatcher
+#[derive(Debug)]
+pub struct Queue {
+    local_address: LocalAddress,
+    datagrams: VecDeque<Datagram>,
+}
+
+impl Queue {
+    pub fn new(local_address: LocalAddress) -> Self {
+        Self {
+            local_address,
+            datagrams: VecDeque::with_capacity(CAPACITY),
+        }
+    }
+
+    fn push(&mut self, datagram: Datagram) {
+        self.datagrams.push_back(datagram);
+    }
+}
+
+impl rx::Queue for Queue {
+    type Entry = Datagram;
+    type Handle = PathHandle;
+
+    fn local_address(&self) -> LocalAddress {
+        self.local_address
+    }
+
+    fn as_slice_mut(&mut self) -> &mut [Self::Entry] {
+        self.datagrams.make_contiguous()
+    }
+
+    fn len(&self) -> usize {
+        self.datagrams.len()
+    }
+
+    fn finish(&mut self, count: usize) {
+        self.datagrams.drain(..count);
+    }
+}
+
+impl rx::Entry for Datagram {
+    type Handle = PathHandle;
+
+    fn read(
+        &mut self,
+        _local_address: &LocalAddress,
+    ) -> Option<(datagram::Header<Self::Handle>, &mut [u8])> {
+        Some((self.header, &mut self.payload))
+    }
+}
+
+/// The dispatcher isn't associated with an endpoint, so its events aren't published
+struct Subscriber;
+
+impl event::Subscriber for Subscriber {
+    type ConnectionContext = ();
+
+    fn create_connection_context(
+        &mut self,
+        _meta: &event::api::ConnectionMeta,
+        _info: &event::api::ConnectionInfo,
+    ) -> Self::ConnectionContext {
+    }
+}
@@ -14,3 +14,18 @@ macro_rules! libc {
         }
     }};
 }
+
+/// Calls the given function with the libc calling convention and wraps the result in an
+/// `io::Result`.
+///
+/// This is used for syscalls which aren't exposed by the libc crate on every platform.
+macro_rules! syscall {
+    ($($fn: ident)::+ ( $($arg: expr),* $(,)* ) ) => {{
+        let res = unsafe { $($fn)::+($($arg, )*) };
+        if res < 0 {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(res)
+        }
+    }};
+}
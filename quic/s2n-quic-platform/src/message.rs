@@ -16,7 +16,7 @@ pub mod cmsg;
 pub mod queue;
 pub mod simple;
 
-use core::ffi::c_void;
+use core::{ffi::c_void, time::Duration};
 use s2n_quic_core::{
     inet::{ExplicitCongestionNotification, SocketAddress},
     io::tx,
@@ -84,6 +84,12 @@ pub trait Message {
         panic!("cannot use GSO on the current platform");
     }
 
+    /// Holds back the transmission of the message for the given delay
+    ///
+    /// The delay is ignored on platforms which can't schedule transmissions, or if the socket
+    /// wasn't configured to do so.
+    fn set_delay(&mut self, _delay: Duration) {}
+
     /// Resets the message for future use
     ///
     /// # Safety
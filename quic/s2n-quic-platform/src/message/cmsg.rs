@@ -6,9 +6,9 @@ use s2n_quic_core::inet::{AncillaryData, ExplicitCongestionNotification};
 
 /// The maximum number of bytes allocated for cmsg data
 ///
-/// This should be enough for UDP_SEGMENT + IP_TOS + IP_PKTINFO. It may need to be increased
-/// to allow for future control messages.
-pub const MAX_LEN: usize = 128;
+/// This should be enough for UDP_SEGMENT + IP_TOS + IP_PKTINFO + SCM_TIMESTAMPING + SCM_TXTIME.
+/// It may need to be increased to allow for future control messages.
+pub const MAX_LEN: usize = 192;
 
 #[test]
 fn max_len_test() {
@@ -28,6 +28,18 @@ fn max_len_test() {
                 size_of::<libc::in_pktinfo>().max(size_of::<libc::in6_pktinfo>()) as _,
             ) as usize;
         }
+
+        // SCM_TIMESTAMPING
+        #[cfg(s2n_quic_platform_timestamping)]
+        {
+            len += libc::CMSG_LEN(size_of::<[libc::timespec; 3]>() as _) as usize;
+        }
+
+        // SCM_TXTIME
+        #[cfg(s2n_quic_platform_txtime)]
+        {
+            len += libc::CMSG_LEN(size_of::<u64>() as _) as usize;
+        }
     }
 
     // We use the MAX_LEN to determine if the cmsg has been populated at all so the actual
@@ -167,11 +179,26 @@ pub fn decode(msghdr: &libc::msghdr) -> AncillaryData {
                     result.local_address = local_address.into();
                     result.local_interface = Some(pkt_info.ipi6_ifindex as _);
                 }
+                #[cfg(s2n_quic_platform_timestamping)]
+                (libc::SOL_SOCKET, libc::SCM_TIMESTAMPING, _) => {
+                    // The first timestamp is the software timestamp, the second one is
+                    // deprecated and the third one is the raw hardware timestamp.
+                    //
+                    // See https://www.kernel.org/doc/html/latest/networking/timestamping.html
+                    let timestamps = decode_value::<[libc::timespec; 3]>(cmsg);
+                    result.rx_timestamps.software = decode_timespec(timestamps[0]);
+                    result.rx_timestamps.hardware = decode_timespec(timestamps[2]);
+                }
                 #[cfg(s2n_quic_platform_gso)]
                 (libc::SOL_UDP, libc::UDP_SEGMENT, _) => {
                     // ignore GSO settings when reading
                     continue;
                 }
+                #[cfg(s2n_quic_platform_txtime)]
+                (libc::SOL_SOCKET, libc::SCM_TXTIME, _) => {
+                    // ignore transmit times when reading
+                    continue;
+                }
                 (level, ty, len) if cfg!(test) => {
                     // if we're getting an unexpected cmsg we should know about it in testing
                     panic!(
@@ -202,6 +229,20 @@ unsafe fn decode_value<T: Copy>(cmsghdr: &libc::cmsghdr) -> T {
     ptr::read(libc::CMSG_DATA(cmsghdr) as *const T)
 }
 
+/// Converts a timestamp reported by the kernel into a `Duration`
+///
+/// Timestamps which weren't recorded are left zeroed.
+#[cfg(s2n_quic_platform_timestamping)]
+fn decode_timespec(timespec: libc::timespec) -> Option<core::time::Duration> {
+    if timespec.tv_sec == 0 && timespec.tv_nsec == 0 {
+        return None;
+    }
+    Some(core::time::Duration::new(
+        timespec.tv_sec as _,
+        timespec.tv_nsec as _,
+    ))
+}
+
 struct Iter<'a> {
     msghdr: &'a libc::msghdr,
     cmsghdr: Option<&'a libc::cmsghdr>,
@@ -50,6 +50,10 @@ macro_rules! impl_message_delegate {
                 $crate::message::Message::set_segment_size(&mut self.$field, size)
             }
 
+            fn set_delay(&mut self, delay: core::time::Duration) {
+                $crate::message::Message::set_delay(&mut self.$field, delay)
+            }
+
             unsafe fn reset(&mut self, mtu: usize) {
                 $crate::message::Message::reset(&mut self.$field, mtu)
             }
@@ -6,14 +6,27 @@ use crate::message::{
     Message as MessageTrait,
 };
 use alloc::vec::Vec;
+use cfg_if::cfg_if;
 use core::{fmt, mem::zeroed};
-use libc::mmsghdr;
 use s2n_quic_core::{
     inet::{datagram, ExplicitCongestionNotification, SocketAddress},
     io::{rx, tx},
     path,
 };
 
+cfg_if! {
+    if #[cfg(target_os = "macos")] {
+        mod apple;
+        /// The syscalls for sending and receiving batches of messages
+        pub(crate) use apple as sys;
+    } else {
+        /// The syscalls for sending and receiving batches of messages
+        pub(crate) use libc as sys;
+    }
+}
+
+use sys::mmsghdr;
+
 #[repr(transparent)]
 pub struct Message(pub(crate) mmsghdr);
 
@@ -93,6 +106,11 @@ impl MessageTrait for mmsghdr {
         self.msg_hdr.set_segment_size(size)
     }
 
+    #[inline]
+    fn set_delay(&mut self, delay: core::time::Duration) {
+        self.msg_hdr.set_delay(delay)
+    }
+
     #[inline]
     unsafe fn reset(&mut self, mtu: usize) {
         self.set_payload_len(mtu);
@@ -201,6 +219,9 @@ impl tx::Entry for Message {
         &mut self,
         mut message: M,
     ) -> Result<usize, tx::Error> {
+        // the delay needs to be queried before the payload is written
+        let delay = message.delay();
+
         let payload = MessageTrait::payload_mut(self);
 
         let len = message.write_payload(tx::PayloadBuffer::new(payload), 0)?;
@@ -214,6 +235,7 @@ impl tx::Entry for Message {
         let handle = *message.path_handle();
         handle.update_msg_hdr(&mut self.0.msg_hdr);
         self.set_ecn(message.ecn(), &handle.remote_address.0);
+        self.set_delay(delay);
 
         Ok(len)
     }
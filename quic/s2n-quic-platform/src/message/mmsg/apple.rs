@@ -0,0 +1,77 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Batched IO for macOS with `sendmsg_x` and `recvmsg_x`
+//!
+//! These syscalls are the macOS equivalents of `sendmmsg` and `recvmmsg`. They aren't exposed by
+//! the libc crate, so the definitions from xnu's `sys/socket.h` are declared here with the same
+//! names and signatures as their Linux counterparts. This allows the `mmsg` message and socket
+//! implementations to be shared between both platforms.
+
+#![allow(non_camel_case_types)]
+
+use libc::{c_int, c_uint, msghdr, ssize_t, timespec};
+
+/// The message header for `sendmsg_x` and `recvmsg_x`
+///
+/// The layout matches `struct msghdr_x`, which extends `struct msghdr` with the length of the
+/// datagram in the same way that `struct mmsghdr` does on Linux.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct mmsghdr {
+    pub msg_hdr: msghdr,
+    /// Named `msg_datalen` in `struct msghdr_x`
+    pub msg_len: usize,
+}
+
+extern "C" {
+    #[link_name = "sendmsg_x"]
+    fn sendmsg_x(s: c_int, msgp: *const mmsghdr, cnt: c_uint, flags: c_int) -> ssize_t;
+
+    #[link_name = "recvmsg_x"]
+    fn recvmsg_x(s: c_int, msgp: *mut mmsghdr, cnt: c_uint, flags: c_int) -> ssize_t;
+}
+
+/// Sends multiple datagrams on the socket
+///
+/// Returns the number of datagrams which were sent, or -1 on error.
+///
+/// # Safety
+///
+/// `msgvec` must point to `vlen` initialized messages.
+pub unsafe fn sendmmsg(sockfd: c_int, msgvec: *mut mmsghdr, vlen: c_uint, flags: c_int) -> c_int {
+    sendmsg_x(sockfd, msgvec, vlen, flags) as _
+}
+
+/// Receives multiple datagrams from the socket
+///
+/// Returns the number of datagrams which were received, or -1 on error. The length of each
+/// datagram is written to `msg_len`.
+///
+/// `recvmsg_x` doesn't accept a timeout, which is only needed for blocking sockets.
+///
+/// # Safety
+///
+/// `msgvec` must point to `vlen` initialized messages.
+pub unsafe fn recvmmsg(
+    sockfd: c_int,
+    msgvec: *mut mmsghdr,
+    vlen: c_uint,
+    flags: c_int,
+    timeout: *mut timespec,
+) -> c_int {
+    debug_assert!(timeout.is_null(), "timeouts are not supported");
+    recvmsg_x(sockfd, msgvec, vlen, flags) as _
+}
+
+#[test]
+fn layout_test() {
+    use core::mem::{align_of, size_of};
+
+    // `msg_datalen` directly follows the fields of `struct msghdr`
+    assert_eq!(
+        size_of::<mmsghdr>(),
+        size_of::<msghdr>() + size_of::<usize>()
+    );
+    assert_eq!(align_of::<mmsghdr>(), align_of::<msghdr>());
+}
@@ -8,7 +8,7 @@ use core::{
     mem::{size_of, zeroed},
     pin::Pin,
 };
-use libc::{c_void, iovec, msghdr, sockaddr_in, sockaddr_in6, AF_INET, AF_INET6};
+use libc::{c_int, c_void, iovec, msghdr, sockaddr_in, sockaddr_in6, AF_INET, AF_INET6};
 use s2n_quic_core::{
     inet::{
         datagram, AncillaryData, ExplicitCongestionNotification, IpV4Address, IpV6Address,
@@ -24,6 +24,42 @@ use bolero_generator::*;
 #[repr(transparent)]
 pub struct Message(pub(crate) msghdr);
 
+/// The storage for the address and control messages of a single message
+///
+/// The control message buffer is stored alongside the address so `msg_control` can be cleared
+/// while the message doesn't have any control messages and restored from `msg_name` once it
+/// does. macOS rejects messages with a `msg_control` pointer and a `msg_controllen` of 0.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct Name {
+    addr: sockaddr_in6,
+    cmsg: Cmsg,
+}
+
+/// A control message buffer, aligned for the `cmsghdr` at the start of it
+#[repr(C, align(8))]
+#[derive(Clone, Copy)]
+struct Cmsg([u8; cmsg::MAX_LEN]);
+
+/// Returns the control message buffer which is stored alongside the address of the message
+#[inline]
+fn cmsg_buffer(msghdr: &msghdr) -> *mut c_void {
+    debug_assert!(!msghdr.msg_name.is_null());
+    let name = msghdr.msg_name as *mut Name;
+    // Safety: the `msg_name` of every message points to a `Name`
+    unsafe { core::ptr::addr_of_mut!((*name).cmsg) as *mut c_void }
+}
+
+/// Encodes a control message, restoring the `msg_control` pointer if it was cleared
+#[inline]
+fn encode_cmsg<T: Copy>(msghdr: &mut msghdr, level: c_int, ty: c_int, value: T) {
+    if msghdr.msg_control.is_null() {
+        msghdr.msg_control = cmsg_buffer(msghdr);
+    }
+
+    msghdr.encode_cmsg(level, ty, value);
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(any(test, feature = "generator"), derive(TypeGenerator))]
 pub struct Handle {
@@ -47,6 +83,7 @@ impl Handle {
     pub(crate) fn update_msg_hdr(self, msghdr: &mut msghdr) {
         // when sending a packet, we start out with no cmsg items
         msghdr.msg_controllen = 0;
+        msghdr.msg_control = core::ptr::null_mut();
 
         msghdr.set_remote_address(&self.remote_address.0);
 
@@ -64,7 +101,7 @@ impl Handle {
                 let mut pkt_info = unsafe { core::mem::zeroed::<libc::in_pktinfo>() };
                 pkt_info.ipi_spec_dst.s_addr = u32::from_ne_bytes((*ip).into());
 
-                msghdr.encode_cmsg(libc::IPPROTO_IP, libc::IP_PKTINFO, pkt_info);
+                encode_cmsg(msghdr, libc::IPPROTO_IP, libc::IP_PKTINFO, pkt_info);
             }
             SocketAddress::IpV6(addr) => {
                 use s2n_quic_core::inet::Unspecified;
@@ -79,7 +116,7 @@ impl Handle {
 
                 pkt_info.ipi6_addr.s6_addr = (*ip).into();
 
-                msghdr.encode_cmsg(libc::IPPROTO_IPV6, libc::IPV6_PKTINFO, pkt_info);
+                encode_cmsg(msghdr, libc::IPPROTO_IPV6, libc::IPV6_PKTINFO, pkt_info);
             }
         }
     }
@@ -113,6 +150,16 @@ impl path::Handle for Handle {
         }
     }
 
+    #[inline]
+    fn set_local_address(&mut self, local_address: LocalAddress) {
+        #[cfg(s2n_quic_platform_pktinfo)]
+        {
+            self.local_address = local_address;
+        }
+
+        let _ = local_address;
+    }
+
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         let mut eq = true;
@@ -180,10 +227,26 @@ impl Message {
 
         let ancillary_data = cmsg::decode(msghdr);
         let ecn = ancillary_data.ecn;
+        #[allow(unused_mut)]
+        let mut rx_timestamps = ancillary_data.rx_timestamps;
+
+        // the software timestamp is taken from the system clock, which allows computing how
+        // long the datagram was waiting to be read
+        #[cfg(all(s2n_quic_platform_timestamping, feature = "std"))]
+        if let Some(software) = rx_timestamps.software {
+            rx_timestamps.queue_delay = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()
+                .and_then(|now| now.checked_sub(software));
+        }
 
         path.with_ancillary_data(ancillary_data);
 
-        Some(datagram::Header { path, ecn })
+        Some(datagram::Header {
+            path,
+            ecn,
+            rx_timestamps,
+        })
     }
 }
 
@@ -214,9 +277,9 @@ impl MessageTrait for msghdr {
                 #[cfg(target_os = "freebsd")]
                 let ecn = ecn as libc::c_uchar;
 
-                self.encode_cmsg(libc::IPPROTO_IP, libc::IP_TOS, ecn)
+                encode_cmsg(self, libc::IPPROTO_IP, libc::IP_TOS, ecn)
             }
-            SocketAddress::IpV6(_) => self.encode_cmsg(libc::IPPROTO_IPV6, libc::IPV6_TCLASS, ecn),
+            SocketAddress::IpV6(_) => encode_cmsg(self, libc::IPPROTO_IPV6, libc::IPV6_TCLASS, ecn),
         };
     }
 
@@ -302,7 +365,25 @@ impl MessageTrait for msghdr {
     #[inline]
     fn set_segment_size(&mut self, size: usize) {
         type SegmentType = u16;
-        self.encode_cmsg(libc::SOL_UDP, libc::UDP_SEGMENT, size as SegmentType);
+        encode_cmsg(self, libc::SOL_UDP, libc::UDP_SEGMENT, size as SegmentType);
+    }
+
+    #[cfg(s2n_quic_platform_txtime)]
+    #[inline]
+    fn set_delay(&mut self, delay: core::time::Duration) {
+        if delay.is_zero() {
+            return;
+        }
+
+        // the transmit time is expressed in the clock the socket was configured with
+        let mut now = unsafe { zeroed::<libc::timespec>() };
+        unsafe {
+            libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now);
+        }
+        let now = now.tv_sec as u64 * 1_000_000_000 + now.tv_nsec as u64;
+        let txtime = now + delay.as_nanos() as u64;
+
+        encode_cmsg(self, libc::SOL_SOCKET, libc::SCM_TXTIME, txtime);
     }
 
     #[inline]
@@ -313,10 +394,13 @@ impl MessageTrait for msghdr {
         // reset the address
         self.set_remote_address(&SocketAddress::IpV6(Default::default()));
 
+        // the pointer is cleared while there aren't any control messages
+        let msg_control = cmsg_buffer(self);
+
         if cfg!(debug_assertions) && self.msg_controllen == 0 {
             // make sure nothing was written to the control message if it was set to 0
             assert!(
-                core::slice::from_raw_parts_mut(self.msg_control as *mut u8, cmsg::MAX_LEN)
+                core::slice::from_raw_parts_mut(msg_control as *mut u8, cmsg::MAX_LEN)
                     .iter()
                     .all(|v| *v == 0)
             )
@@ -324,16 +408,15 @@ impl MessageTrait for msghdr {
 
         // reset the control messages if it isn't set to the default value
         if self.msg_controllen as usize != cmsg::MAX_LEN {
-            let cmsg = core::slice::from_raw_parts_mut(
-                self.msg_control as *mut u8,
-                self.msg_controllen as _,
-            );
+            let cmsg =
+                core::slice::from_raw_parts_mut(msg_control as *mut u8, self.msg_controllen as _);
 
             for byte in cmsg.iter_mut() {
                 *byte = 0;
             }
         }
 
+        self.msg_control = msg_control;
         self.msg_controllen = cmsg::MAX_LEN as _;
     }
 
@@ -343,13 +426,12 @@ impl MessageTrait for msghdr {
             self.msg_name, other.msg_name,
             "msg_name needs to point to the same data"
         );
-        debug_assert_eq!(
-            self.msg_control, other.msg_control,
-            "msg_control needs to point to the same data"
-        );
         debug_assert_eq!(self.msg_iov, other.msg_iov);
         debug_assert_eq!(self.msg_iovlen, other.msg_iovlen);
         self.msg_namelen = other.msg_namelen;
+        // `msg_control` is cleared when there aren't any control messages, but always refers to
+        // the same data as `msg_name` otherwise
+        self.msg_control = other.msg_control;
         self.msg_controllen = other.msg_controllen;
     }
 
@@ -384,13 +466,9 @@ pub struct Storage<Payloads> {
     #[allow(dead_code)]
     pub(crate) iovecs: Pin<Box<[iovec]>>,
 
-    // this field holds references to allocated msg_names, but is never read directly
-    #[allow(dead_code)]
-    pub(crate) msg_names: Pin<Box<[sockaddr_in6]>>,
-
-    // this field holds references to allocated msg_names, but is never read directly
+    // this field holds references to allocated msg_names and cmsgs, but is never read directly
     #[allow(dead_code)]
-    pub(crate) cmsgs: Pin<Box<[u8]>>,
+    pub(crate) names: Pin<Box<[Name]>>,
 
     /// The maximum payload for any given message
     mtu: usize,
@@ -441,31 +519,28 @@ impl<Payloads: crate::buffer::Buffer> Ring<Payloads> {
 
         let mut payloads = Pin::new(payloads);
         let mut iovecs = Pin::new(vec![unsafe { zeroed() }; capacity].into_boxed_slice());
-        let mut msg_names = Pin::new(vec![unsafe { zeroed() }; capacity].into_boxed_slice());
-        let mut cmsgs = Pin::new(vec![0u8; capacity * cmsg::MAX_LEN].into_boxed_slice());
+        let mut names = Pin::new(vec![unsafe { zeroed::<Name>() }; capacity].into_boxed_slice());
 
         // double message capacity to enable contiguous access
         let mut messages = Vec::with_capacity(capacity * 2);
 
         let mut payload_buf = &mut payloads.as_mut()[..];
-        let mut cmsg_buf = &mut cmsgs.as_mut()[..];
 
         for index in 0..capacity {
             let (payload, remaining) = payload_buf.split_at_mut(mtu * max_gso);
             payload_buf = remaining;
-            let (cmsg, remaining) = cmsg_buf.split_at_mut(cmsg::MAX_LEN);
-            cmsg_buf = remaining;
 
             let mut iovec = unsafe { zeroed::<iovec>() };
             iovec.iov_base = payload.as_mut_ptr() as _;
             iovec.iov_len = mtu;
             iovecs[index] = iovec;
 
+            let name = &mut names[index];
             let msg = Message::new(
                 (&mut iovecs[index]) as *mut _,
-                (&mut msg_names[index]) as *mut _ as *mut _,
+                (&mut name.addr) as *mut _ as *mut _,
                 size_of::<sockaddr_in6>(),
-                cmsg as *mut _ as *mut _,
+                (&mut name.cmsg) as *mut _ as *mut _,
                 cmsg::MAX_LEN,
             );
 
@@ -481,8 +556,7 @@ impl<Payloads: crate::buffer::Buffer> Ring<Payloads> {
             storage: Storage {
                 payloads,
                 iovecs,
-                msg_names,
-                cmsgs,
+                names,
                 mtu,
                 max_gso,
             },
@@ -532,6 +606,9 @@ impl tx::Entry for Message {
         &mut self,
         mut message: M,
     ) -> Result<usize, tx::Error> {
+        // the delay needs to be queried before the payload is written
+        let delay = message.delay();
+
         let payload = MessageTrait::payload_mut(self);
 
         let len = message.write_payload(tx::PayloadBuffer::new(payload), 0)?;
@@ -545,6 +622,7 @@ impl tx::Entry for Message {
         let handle = *message.path_handle();
         handle.update_msg_hdr(&mut self.0);
         self.set_ecn(message.ecn(), &handle.remote_address.0);
+        self.set_delay(delay);
 
         Ok(len)
     }
@@ -594,9 +672,11 @@ mod tests {
 
         let mut msghdr = unsafe { zeroed::<msghdr>() };
 
-        let mut msgname = unsafe { zeroed::<sockaddr_in6>() };
-        msghdr.msg_name = &mut msgname as *mut _ as *mut _;
+        let mut name = unsafe { zeroed::<Name>() };
+        msghdr.msg_name = &mut name.addr as *mut _ as *mut _;
         msghdr.msg_namelen = size_of::<sockaddr_in6>() as _;
+        msghdr.msg_control = &mut name.cmsg as *mut _ as *mut _;
+        msghdr.msg_controllen = cmsg::MAX_LEN as _;
 
         let mut iovec = unsafe { zeroed::<iovec>() };
         msghdr.msg_iov = &mut iovec;
@@ -629,8 +709,8 @@ mod tests {
 
                 let mut msghdr = unsafe { zeroed::<msghdr>() };
 
-                let mut msgname = unsafe { zeroed::<sockaddr_in6>() };
-                msghdr.msg_name = &mut msgname as *mut _ as *mut _;
+                let mut name = unsafe { zeroed::<Name>() };
+                msghdr.msg_name = &mut name.addr as *mut _ as *mut _;
                 msghdr.msg_namelen = size_of::<sockaddr_in6>() as _;
 
                 let mut iovec = unsafe { zeroed::<iovec>() };
@@ -639,9 +719,8 @@ mod tests {
                 iovec.iov_base = (&mut iovec_buf[0]) as *mut u8 as _;
                 msghdr.msg_iov = &mut iovec;
 
-                let mut cmsg_buf = [0u8; cmsg::MAX_LEN];
-                msghdr.msg_controllen = cmsg_buf.len() as _;
-                msghdr.msg_control = (&mut cmsg_buf[0]) as *mut u8 as _;
+                msghdr.msg_control = &mut name.cmsg as *mut _ as *mut _;
+                msghdr.msg_controllen = cmsg::MAX_LEN as _;
 
                 let mut message = Message(msghdr);
 
@@ -14,7 +14,7 @@ pub type Occupied<'a, M> = Slice<'a, M, behavior::Occupied>;
 pub type OccupiedWipe<'a, M> = Slice<'a, M, behavior::OccupiedWipe>;
 
 use crate::message;
-use core::fmt;
+use core::{fmt, time::Duration};
 use s2n_quic_core::path::LocalAddress;
 
 /// Structure for queueing network messages
@@ -52,6 +52,8 @@ pub struct Queue<Ring: message::Ring> {
     free: Segment,
     /// The local address that the queue is bound to
     local_address: LocalAddress,
+    /// How long before their departure time paced messages may be queued
+    pacing_horizon: Duration,
 }
 
 impl<Ring> Default for Queue<Ring>
@@ -83,6 +85,7 @@ impl<Ring: message::Ring> Queue<Ring> {
             occupied,
             free,
             local_address: Default::default(),
+            pacing_horizon: Duration::ZERO,
         }
     }
 
@@ -90,6 +93,13 @@ impl<Ring: message::Ring> Queue<Ring> {
         self.local_address = local_address;
     }
 
+    /// Sets how long before their departure time paced messages may be queued
+    ///
+    /// This should only be set if the socket holds back messages until their transmit time.
+    pub fn set_pacing_horizon(&mut self, pacing_horizon: Duration) {
+        self.pacing_horizon = pacing_horizon;
+    }
+
     /// Returns the maximum size of a payload for any message
     pub fn mtu(&self) -> usize {
         self.ring.mtu()
@@ -130,6 +140,7 @@ impl<Ring: message::Ring> Queue<Ring> {
             max_gso,
             gso_segment: None,
             local_address: &self.local_address,
+            pacing_horizon: self.pacing_horizon,
         }
     }
 
@@ -145,6 +156,7 @@ impl<Ring: message::Ring> Queue<Ring> {
             max_gso,
             gso_segment: None,
             local_address: &self.local_address,
+            pacing_horizon: self.pacing_horizon,
         }
     }
 
@@ -162,6 +174,7 @@ impl<Ring: message::Ring> Queue<Ring> {
             max_gso,
             gso_segment: None,
             local_address: &self.local_address,
+            pacing_horizon: self.pacing_horizon,
         }
     }
 }
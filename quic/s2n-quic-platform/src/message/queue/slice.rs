@@ -3,7 +3,10 @@
 
 use super::{Behavior, Segment};
 use crate::message;
-use core::ops::{Deref, DerefMut};
+use core::{
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
 use s2n_quic_core::{
     io::{rx, tx},
     path::{self, LocalAddress},
@@ -26,6 +29,8 @@ pub struct Slice<'a, Message: message::Message, Behavior> {
     pub(crate) gso_segment: Option<GsoSegment>,
     /// The base handle for all of the messages to inherit
     pub(crate) local_address: &'a LocalAddress,
+    /// How long before their departure time paced messages may be pushed
+    pub(crate) pacing_horizon: Duration,
 }
 
 #[derive(Debug, Default)]
@@ -349,4 +354,9 @@ impl<
     fn len(&self) -> usize {
         self.secondary.len
     }
+
+    #[inline]
+    fn pacing_horizon(&self) -> Duration {
+        self.pacing_horizon
+    }
 }
@@ -218,6 +218,7 @@ impl rx::Entry for Message {
         let mut header = datagram::Header {
             path: self.path_handle()?,
             ecn: self.ecn(),
+            rx_timestamps: Default::default(),
         };
 
         // set the correct local address
@@ -9,6 +9,12 @@ pub mod msg;
 #[cfg(s2n_quic_platform_socket_mmsg)]
 pub mod mmsg;
 
+#[cfg(all(unix, feature = "std"))]
+pub mod passing;
+
+#[cfg(all(target_os = "linux", feature = "std"))]
+pub mod steering;
+
 pub mod std;
 
 cfg_if! {
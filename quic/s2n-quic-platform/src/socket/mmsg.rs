@@ -4,7 +4,7 @@
 use crate::{
     buffer::Buffer,
     message::{
-        mmsg::{self, Message, Ring},
+        mmsg::{self, sys, Message, Ring},
         queue,
     },
 };
@@ -12,8 +12,25 @@ use errno::errno;
 use s2n_quic_core::{event, path::LocalAddress};
 use std::{io, os::unix::io::AsRawFd};
 
-#[derive(Debug, Default)]
-pub struct Queue<B: Buffer>(queue::Queue<Ring<B>>);
+/// The maximum number of messages passed to a single `sendmmsg` or `recvmmsg` call
+///
+/// The kernel caps the number of messages to `UIO_MAXIOV`.
+pub const MAX_BATCH_SIZE: usize = 1024;
+
+#[derive(Debug)]
+pub struct Queue<B: Buffer> {
+    queue: queue::Queue<Ring<B>>,
+    batch_size: usize,
+}
+
+impl<B: Buffer + Default> Default for Queue<B> {
+    fn default() -> Self {
+        Self {
+            queue: Default::default(),
+            batch_size: MAX_BATCH_SIZE,
+        }
+    }
+}
 
 pub use mmsg::Handle;
 
@@ -21,19 +38,49 @@ impl<B: Buffer> Queue<B> {
     pub fn new(buffer: B, max_gso: usize) -> Self {
         let queue = queue::Queue::new(Ring::new(buffer, max_gso));
 
-        Self(queue)
+        Self {
+            queue,
+            batch_size: MAX_BATCH_SIZE,
+        }
+    }
+
+    /// Sets the maximum number of messages passed to a single syscall
+    ///
+    /// The value is clamped to `1..=MAX_BATCH_SIZE`.
+    pub fn set_batch_size(&mut self, batch_size: usize) {
+        self.batch_size = batch_size.clamp(1, MAX_BATCH_SIZE);
     }
 
     pub fn free_len(&self) -> usize {
-        self.0.free_len()
+        self.queue.free_len()
     }
 
     pub fn occupied_len(&self) -> usize {
-        self.0.occupied_len()
+        self.queue.occupied_len()
     }
 
     pub fn set_local_address(&mut self, local_address: LocalAddress) {
-        self.0.set_local_address(local_address)
+        self.queue.set_local_address(local_address)
+    }
+
+    /// Returns the maximum number of segments which are sent in a single GSO datagram
+    pub fn max_gso(&self) -> usize {
+        self.queue.max_gso()
+    }
+
+    /// Sets how long before their departure time paced messages may be queued
+    ///
+    /// This should only be set once the socket was configured to hold back messages until
+    /// their transmit time.
+    pub fn set_pacing_horizon(&mut self, pacing_horizon: core::time::Duration) {
+        self.queue.set_pacing_horizon(pacing_horizon)
+    }
+
+    /// Disables GSO for future transmissions
+    pub fn disable_gso(&mut self) {
+        if self.queue.max_gso() > 1 {
+            self.queue.disable_gso();
+        }
     }
 
     pub fn tx<Socket: AsRawFd, Publisher: event::EndpointPublisher>(
@@ -41,11 +88,13 @@ impl<B: Buffer> Queue<B> {
         socket: &Socket,
         publisher: &mut Publisher,
     ) -> io::Result<usize> {
-        let mut entries = self.0.occupied_mut();
+        let mut entries = self.queue.occupied_mut();
 
         // Safety: calling a libc function is inherently unsafe as rust cannot
         // make any invariant guarantees. This has to be reviewed by humans instead
         // so the [docs](https://linux.die.net/man/2/sendmmsg) are inlined here:
+        //
+        // On macOS, `sendmsg_x` is called instead, which follows the same semantics.
 
         // > The sockfd argument is the file descriptor of the socket on which data
         // > is to be transmitted.
@@ -61,7 +110,8 @@ impl<B: Buffer> Queue<B> {
         // > The size of this array is specified in vlen.
         //
         // > The value specified in vlen is capped to UIO_MAXIOV (1024).
-        let vlen = entries.len().min(1024) as _;
+        let len = entries.len().min(self.batch_size);
+        let vlen = len as _;
 
         // > The flags argument contains flags ORed together.
         //
@@ -85,7 +135,12 @@ impl<B: Buffer> Queue<B> {
         // > call to send the remaining messages.
         //
         // > On error, -1 is returned, and errno is set to indicate the error.
-        match libc!(sendmmsg(sockfd, msgvec, vlen, flags)) {
+
+        // macOS doesn't like when msg_control have valid pointers but the len is 0, which
+        // is why the messages clear `msg_control` when they don't have any control messages.
+        let result = syscall!(sys::sendmmsg(sockfd, msgvec, vlen, flags));
+
+        match result {
             Ok(status) => {
                 let count = status as usize;
                 entries.finish(count);
@@ -114,13 +169,13 @@ impl<B: Buffer> Queue<B> {
                     errno: libc::EIO as _,
                 });
 
-                if self.0.max_gso() > 1 {
-                    self.0.disable_gso();
+                if self.queue.max_gso() > 1 {
+                    self.queue.disable_gso();
 
                     publisher.on_platform_feature_configured(
                         event::builder::PlatformFeatureConfigured {
                             configuration: event::builder::PlatformFeatureConfiguration::Gso {
-                                max_segments: self.0.max_gso(),
+                                max_segments: self.queue.max_gso(),
                             },
                         },
                     );
@@ -153,7 +208,7 @@ impl<B: Buffer> Queue<B> {
         socket: &Socket,
         publisher: &mut Publisher,
     ) -> io::Result<usize> {
-        let mut entries = self.0.free_mut();
+        let mut entries = self.queue.free_mut();
 
         if entries.is_empty() {
             return Ok(0);
@@ -162,6 +217,8 @@ impl<B: Buffer> Queue<B> {
         // Safety: calling a libc function is inherently unsafe as rust cannot
         // make any invariant guarantees. This has to be reviewed by humans instead
         // so the [docs](https://linux.die.net/man/2/recvmmsg) are inlined here:
+        //
+        // On macOS, `recvmsg_x` is called instead, which follows the same semantics.
 
         // > The sockfd argument is the file descriptor of the socket to receive data from.
         let sockfd = socket.as_raw_fd();
@@ -172,7 +229,7 @@ impl<B: Buffer> Queue<B> {
         let msgvec = entries.as_mut_ptr() as _;
 
         // > The size of this array is specified in vlen.
-        let vlen = entries.len() as _;
+        let vlen = entries.len().min(self.batch_size) as _;
 
         // > The flags argument contains flags ORed together.
         //
@@ -203,7 +260,7 @@ impl<B: Buffer> Queue<B> {
         //
         // > On success, recvmmsg() returns the number of messages received in
         // > msgvec; on error, -1 is returned, and errno is set to indicate the error.
-        match libc!(recvmmsg(sockfd, msgvec, vlen, flags, timeout)) {
+        match syscall!(sys::recvmmsg(sockfd, msgvec, vlen, flags, timeout)) {
             Ok(status) => {
                 let count = status as usize;
                 entries.finish(count);
@@ -232,10 +289,10 @@ impl<B: Buffer> Queue<B> {
     }
 
     pub fn rx_queue(&mut self) -> queue::OccupiedWipe<Message> {
-        self.0.occupied_wipe_mut()
+        self.queue.occupied_wipe_mut()
     }
 
     pub fn tx_queue(&mut self) -> queue::Free<Message> {
-        self.0.free_mut()
+        self.queue.free_mut()
     }
 }
@@ -36,6 +36,26 @@ impl<B: Buffer> Queue<B> {
         self.0.set_local_address(local_address)
     }
 
+    /// Returns the maximum number of segments which are sent in a single GSO datagram
+    pub fn max_gso(&self) -> usize {
+        self.0.max_gso()
+    }
+
+    /// Sets how long before their departure time paced messages may be queued
+    ///
+    /// This should only be set once the socket was configured to hold back messages until
+    /// their transmit time.
+    pub fn set_pacing_horizon(&mut self, pacing_horizon: core::time::Duration) {
+        self.0.set_pacing_horizon(pacing_horizon)
+    }
+
+    /// Disables GSO for future transmissions
+    pub fn disable_gso(&mut self) {
+        if self.0.max_gso() > 1 {
+            self.0.disable_gso();
+        }
+    }
+
     pub fn tx<Socket: AsRawFd, Publisher: event::EndpointPublisher>(
         &mut self,
         socket: &Socket,
@@ -45,21 +65,6 @@ impl<B: Buffer> Queue<B> {
         let mut entries = self.0.occupied_mut();
 
         for entry in entries.iter_mut() {
-            // macOS doesn't like when msg_control have valid pointers but the len is 0
-            //
-            // If that's the case here, then set the `msg_control` to null and restore it after
-            // calling sendmsg.
-            #[cfg(any(target_os = "macos", target_os = "ios"))]
-            let msg_control = {
-                let msg_control = entry.0.msg_control;
-
-                if entry.0.msg_controllen == 0 {
-                    entry.0.msg_control = core::ptr::null_mut();
-                }
-
-                msg_control
-            };
-
             // Safety: calling a libc function is inherently unsafe as rust cannot
             // make any invariant guarantees. This has to be reviewed by humans instead
             // so the [docs](https://linux.die.net/man/2/sendmsg) are inlined here:
@@ -84,11 +89,6 @@ impl<B: Buffer> Queue<B> {
             // > On error, -1 is returned, and errno is set appropriately.
             let result = libc!(sendmsg(sockfd, msg, flags));
 
-            #[cfg(any(target_os = "macos", target_os = "ios"))]
-            {
-                entry.0.msg_control = msg_control;
-            }
-
             match result {
                 Ok(_len) => {
                     count += 1;
@@ -0,0 +1,177 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Passes UDP sockets to another process over a unix domain socket
+//!
+//! The sockets are sent as `SCM_RIGHTS` control messages, which duplicates the file descriptors
+//! into the receiving process. Both processes share the underlying sockets afterwards, so the
+//! datagrams which are queued on a socket aren't lost when the sending process exits.
+
+use core::{
+    mem::{size_of, size_of_val},
+    ptr,
+};
+use std::{
+    io,
+    net::UdpSocket,
+    os::unix::{
+        io::{AsRawFd, FromRawFd, RawFd},
+        net::UnixStream,
+    },
+};
+
+/// The maximum number of sockets which can be passed in a single message
+pub const MAX_SOCKETS: usize = 16;
+
+/// The number of words allocated for the control message
+///
+/// This is enough for the header of the control message and [`MAX_SOCKETS`] file descriptors.
+const CONTROL_WORDS: usize = 16;
+
+/// Sends the sockets to the process on the other end of the stream
+///
+/// The sockets are still owned by the current process after they were sent.
+pub fn send(stream: &UnixStream, sockets: &[UdpSocket]) -> io::Result<()> {
+    if sockets.is_empty() || sockets.len() > MAX_SOCKETS {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the number of sockets must be between 1 and MAX_SOCKETS",
+        ));
+    }
+
+    let fds: Vec<RawFd> = sockets.iter().map(AsRawFd::as_raw_fd).collect();
+    let fds_len = size_of_val(fds.as_slice());
+
+    // the payload contains the number of sockets so the receiver can detect truncated messages
+    let mut payload = [sockets.len() as u8];
+    let mut iov = libc::iovec {
+        iov_base: payload.as_mut_ptr() as *mut _,
+        iov_len: payload.len(),
+    };
+
+    // use u64 words so the control message is aligned
+    let mut control = [0u64; CONTROL_WORDS];
+    let control_len = unsafe { libc::CMSG_SPACE(fds_len as _) } as usize;
+    debug_assert!(control_len <= size_of_val(&control));
+
+    let mut msg: libc::msghdr = unsafe { core::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr() as *mut _;
+    msg.msg_controllen = control_len as _;
+
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        debug_assert!(!cmsg.is_null());
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(fds_len as _) as _;
+        ptr::copy_nonoverlapping(fds.as_ptr() as *const u8, libc::CMSG_DATA(cmsg), fds_len);
+    }
+
+    let result = unsafe { libc::sendmsg(stream.as_raw_fd(), &msg, 0) };
+
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+/// Receives the sockets which were sent by the process on the other end of the stream
+pub fn receive(stream: &UnixStream) -> io::Result<Vec<UdpSocket>> {
+    let mut payload = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: payload.as_mut_ptr() as *mut _,
+        iov_len: payload.len(),
+    };
+
+    let mut control = [0u64; CONTROL_WORDS];
+
+    let mut msg: libc::msghdr = unsafe { core::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr() as *mut _;
+    msg.msg_controllen = size_of_val(&control) as _;
+
+    // don't leak the sockets into processes which are spawned by the receiver
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+    let flags = libc::MSG_CMSG_CLOEXEC;
+    #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd")))]
+    let flags = 0;
+
+    let result = unsafe { libc::recvmsg(stream.as_raw_fd(), &mut msg, flags) };
+
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    if result == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    let mut sockets = Vec::new();
+
+    unsafe {
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+        while !cmsg.is_null() {
+            if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                let data = libc::CMSG_DATA(cmsg);
+                let len = (*cmsg).cmsg_len as usize - (data as usize - cmsg as usize);
+
+                for index in 0..len / size_of::<RawFd>() {
+                    let fd = ptr::read_unaligned((data as *const RawFd).add(index));
+                    // take ownership first so the sockets are closed if the message is invalid
+                    sockets.push(UdpSocket::from_raw_fd(fd));
+                }
+            }
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+    }
+
+    if msg.msg_flags & libc::MSG_CTRUNC != 0 || sockets.len() != payload[0] as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the sockets were truncated",
+        ));
+    }
+
+    Ok(sockets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_test() {
+        let (sender, receiver) = UnixStream::pair().unwrap();
+
+        let sockets = vec![
+            UdpSocket::bind("127.0.0.1:0").unwrap(),
+            UdpSocket::bind("127.0.0.1:0").unwrap(),
+        ];
+
+        send(&sender, &sockets).unwrap();
+        let received = receive(&receiver).unwrap();
+
+        assert_eq!(received.len(), sockets.len());
+        for (sent, received) in sockets.iter().zip(received.iter()) {
+            assert_eq!(sent.local_addr().unwrap(), received.local_addr().unwrap());
+        }
+
+        // datagrams sent to the original socket are read from the received socket
+        let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+        peer.send_to(b"hello", sockets[0].local_addr().unwrap())
+            .unwrap();
+        let mut buffer = [0u8; 5];
+        let (len, _) = received[0].recv_from(&mut buffer).unwrap();
+        assert_eq!(&buffer[..len], b"hello");
+    }
+
+    #[test]
+    fn invalid_count_test() {
+        let (sender, _receiver) = UnixStream::pair().unwrap();
+        assert!(send(&sender, &[]).is_err());
+    }
+}
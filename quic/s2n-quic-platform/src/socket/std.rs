@@ -84,6 +84,16 @@ impl<B: Buffer> Queue<B> {
         self.0.set_local_address(local_address)
     }
 
+    /// Returns the maximum number of segments which are sent in a single GSO datagram
+    pub fn max_gso(&self) -> usize {
+        self.0.max_gso()
+    }
+
+    /// Disables GSO for future transmissions
+    ///
+    /// GSO isn't supported by the std socket so this is a no-op.
+    pub fn disable_gso(&mut self) {}
+
     pub fn tx<S: Socket, Publisher: event::EndpointPublisher>(
         &mut self,
         socket: &S,
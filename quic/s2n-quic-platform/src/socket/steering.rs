@@ -0,0 +1,361 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Steers datagrams to the `SO_REUSEPORT` socket of their shard with an eBPF program
+//!
+//! Without steering, the kernel picks the socket of a reuse port group by hashing the addresses
+//! of a datagram, which sends the datagrams of a migrated connection to a different socket and
+//! requires the shards to hand datagrams to each other. The program instead computes
+//! `shard::index_for_datagram` in the kernel, so each datagram is received by the socket, and
+//! therefore the core, of the endpoint which issued its destination connection ID with
+//! [`shard::Format`](s2n_quic_core::connection::id::shard::Format).
+//!
+//! The sockets of a reuse port group are numbered in the order they were bound, so the socket of
+//! each shard must be bound in order before the program is attached to any of them. Closing a
+//! socket moves the last socket of the group into its place, so the sockets of all of the shards
+//! must stay open while the endpoint is running.
+
+use core::mem::size_of_val;
+use s2n_quic_core::connection::id::shard::{KEY_LEN, MAX_SHARDS};
+use std::{io, os::unix::io::AsRawFd};
+
+/// The FNV-1a offset basis used by `shard::index`
+const FNV_OFFSET: u32 = 0x811c_9dc5;
+/// The FNV-1a prime used by `shard::index`
+const FNV_PRIME: u32 = 0x0100_0193;
+
+// Opcodes of the instructions used by the program. See the kernel's `include/uapi/linux/bpf.h`.
+const LD_ABS_B: u8 = 0x30;
+const LD_IND_B: u8 = 0x50;
+const LDX_MEM_W: u8 = 0x61;
+const ALU_MUL_K: u8 = 0x24;
+const ALU_MOD_K: u8 = 0x94;
+const ALU_XOR_X: u8 = 0xac;
+const ALU_MOV_K: u8 = 0xb4;
+const ALU64_SUB_K: u8 = 0x17;
+const ALU64_MOV_K: u8 = 0xb7;
+const ALU64_MOV_X: u8 = 0xbf;
+const JMP_JA: u8 = 0x05;
+const JMP_JEQ_K: u8 = 0x15;
+const JMP_JGT_X: u8 = 0x2d;
+const JMP_JSET_K: u8 = 0x45;
+const JMP_JLT_K: u8 = 0xa5;
+const JMP_JLE_K: u8 = 0xb5;
+const JMP_EXIT: u8 = 0x95;
+
+/// The program type for socket filters, which are also used for reuse port groups
+const BPF_PROG_TYPE_SOCKET_FILTER: u32 = 1;
+/// The `bpf` command which loads a program
+const BPF_PROG_LOAD: libc::c_long = 5;
+
+/// An eBPF instruction (`struct bpf_insn`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct Instruction {
+    pub code: u8,
+    /// The destination register in the lower nibble and the source register in the upper nibble
+    pub registers: u8,
+    pub offset: i16,
+    pub immediate: i32,
+}
+
+impl Instruction {
+    #[inline]
+    const fn new(code: u8, dst: u8, src: u8, offset: i16, immediate: i32) -> Self {
+        Self {
+            code,
+            registers: (src << 4) | dst,
+            offset,
+            immediate,
+        }
+    }
+
+    #[inline]
+    pub fn dst(&self) -> usize {
+        (self.registers & 0xf) as usize
+    }
+
+    #[inline]
+    pub fn src(&self) -> usize {
+        (self.registers >> 4) as usize
+    }
+}
+
+/// Returns the program which steers datagrams to one of `shards` sockets
+///
+/// The program returns the same index as `shard::index_for_datagram` for the UDP payload of each
+/// datagram.
+pub fn program(shards: usize) -> Vec<Instruction> {
+    assert!(
+        (1..=MAX_SHARDS).contains(&shards),
+        "the number of shards must be between 1 and {}",
+        MAX_SHARDS
+    );
+
+    use Instruction as I;
+
+    // r6: the socket buffer, which the packet loads read implicitly
+    // r7: the length of the payload, and the hash once the key is located
+    // r8: the offset of the key
+    // r9: the length of the key
+    //
+    // The packet loads clobber r0 to r5, so the state is kept in the callee-saved registers.
+    const HASH: i16 = 16;
+    const BYTES: i16 = HASH + 3;
+    const DONE: i16 = BYTES + 4 * KEY_LEN as i16;
+    const ZERO: i16 = DONE + 3;
+
+    // returns the offset of a jump from `pc` to `target`
+    let jump = |pc: i16, target: i16| target - pc - 1;
+
+    let mut program = vec![
+        I::new(ALU64_MOV_X, 6, 1, 0, 0),
+        // the length of the payload (`__sk_buff.len`)
+        I::new(LDX_MEM_W, 7, 6, 0, 0),
+        I::new(JMP_JEQ_K, 7, 0, jump(2, ZERO), 0),
+        I::new(LD_ABS_B, 0, 0, 0, 0),
+        I::new(JMP_JSET_K, 0, 0, jump(4, 9), 0x80),
+        // short header: the key follows the tag
+        I::new(ALU64_MOV_K, 8, 0, 0, 1),
+        I::new(ALU64_MOV_X, 9, 7, 0, 0),
+        I::new(ALU64_SUB_K, 9, 0, 0, 1),
+        I::new(JMP_JA, 0, 0, jump(8, HASH), 0),
+        // long header: the key follows the length of the destination connection ID
+        I::new(JMP_JLT_K, 7, 0, jump(9, ZERO), 6),
+        I::new(LD_ABS_B, 0, 0, 0, 5),
+        I::new(ALU64_MOV_K, 8, 0, 0, 6),
+        I::new(ALU64_MOV_X, 9, 7, 0, 0),
+        I::new(ALU64_SUB_K, 9, 0, 0, 6),
+        // the destination connection ID is truncated
+        I::new(JMP_JGT_X, 0, 9, jump(14, ZERO), 0),
+        I::new(ALU64_MOV_X, 9, 0, 0, 0),
+        // HASH: only the first `KEY_LEN` bytes are hashed
+        I::new(JMP_JLE_K, 9, 0, jump(HASH, HASH + 2), KEY_LEN as i32),
+        I::new(ALU64_MOV_K, 9, 0, 0, KEY_LEN as i32),
+        I::new(ALU_MOV_K, 7, 0, 0, FNV_OFFSET as i32),
+    ];
+
+    // the verifier of older kernels rejects loops, so the hash is unrolled
+    for index in 0..KEY_LEN as i16 {
+        let pc = BYTES + 4 * index;
+        program.extend_from_slice(&[
+            I::new(JMP_JEQ_K, 9, 0, jump(pc, DONE), index as i32),
+            I::new(LD_IND_B, 0, 8, 0, index as i32),
+            I::new(ALU_XOR_X, 7, 0, 0, 0),
+            I::new(ALU_MUL_K, 7, 0, 0, FNV_PRIME as i32),
+        ]);
+    }
+
+    program.extend_from_slice(&[
+        // DONE
+        I::new(ALU_MOD_K, 7, 0, 0, shards as i32),
+        I::new(ALU64_MOV_X, 0, 7, 0, 0),
+        I::new(JMP_EXIT, 0, 0, 0, 0),
+        // ZERO: datagrams without a connection ID are steered to the first shard
+        I::new(ALU64_MOV_K, 0, 0, 0, 0),
+        I::new(JMP_EXIT, 0, 0, 0, 0),
+    ]);
+
+    debug_assert_eq!(program.len(), ZERO as usize + 2);
+
+    program
+}
+
+/// Attaches the steering program for `shards` sockets to the reuse port group of the socket
+///
+/// The program applies to all of the sockets in the group, including the ones which are bound
+/// afterwards. Loading the program requires `CAP_BPF` or `CAP_SYS_ADMIN` if unprivileged eBPF is
+/// disabled on the host.
+pub fn attach<S: AsRawFd>(socket: &S, shards: usize) -> io::Result<()> {
+    if !(1..=MAX_SHARDS).contains(&shards) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("the number of shards must be between 1 and {}", MAX_SHARDS),
+        ));
+    }
+
+    let program = program(shards);
+    let fd = load(&program)?;
+
+    let result = libc!(setsockopt(
+        socket.as_raw_fd(),
+        libc::SOL_SOCKET,
+        libc::SO_ATTACH_REUSEPORT_EBPF,
+        &fd as *const _ as _,
+        size_of_val(&fd) as _,
+    ));
+
+    // the socket holds a reference to the program once it's attached
+    unsafe {
+        libc::close(fd);
+    }
+
+    result?;
+
+    Ok(())
+}
+
+/// Loads the program into the kernel and returns its file descriptor
+fn load(program: &[Instruction]) -> io::Result<libc::c_int> {
+    /// The prefix of `union bpf_attr` which is used by `BPF_PROG_LOAD`
+    #[repr(C)]
+    struct ProgramLoad {
+        prog_type: u32,
+        insn_cnt: u32,
+        insns: u64,
+        license: u64,
+        log_level: u32,
+        log_size: u32,
+        log_buf: u64,
+        kern_version: u32,
+    }
+
+    const LICENSE: &[u8] = b"Apache-2.0\0";
+
+    let attributes = ProgramLoad {
+        prog_type: BPF_PROG_TYPE_SOCKET_FILTER,
+        insn_cnt: program.len() as u32,
+        insns: program.as_ptr() as u64,
+        license: LICENSE.as_ptr() as u64,
+        log_level: 0,
+        log_size: 0,
+        log_buf: 0,
+        kern_version: 0,
+    };
+
+    let fd = syscall!(libc::syscall(
+        libc::SYS_bpf,
+        BPF_PROG_LOAD,
+        &attributes as *const ProgramLoad,
+        size_of_val(&attributes),
+    ))?;
+
+    Ok(fd as _)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bolero::check;
+    use s2n_quic_core::connection::id::shard;
+
+    /// Interprets the instructions used by the steering program
+    fn run(program: &[Instruction], payload: &[u8]) -> u32 {
+        let mut registers = [0u64; 11];
+        let mut pc = 0;
+
+        loop {
+            let instruction = program[pc];
+            let dst = instruction.dst();
+            let src = instruction.src();
+            let immediate = instruction.immediate as i64 as u64;
+            let offset = instruction.offset as isize;
+            pc += 1;
+
+            // out of bounds loads abort the program
+            let load = |index: u64| payload.get(index as usize).map(|byte| *byte as u64);
+
+            match instruction.code {
+                LD_ABS_B => match load(immediate) {
+                    Some(value) => registers[0] = value,
+                    None => return 0,
+                },
+                LD_IND_B => match load(registers[src] + immediate) {
+                    Some(value) => registers[0] = value,
+                    None => return 0,
+                },
+                LDX_MEM_W => registers[dst] = payload.len() as u64,
+                ALU_MUL_K => {
+                    registers[dst] = (registers[dst] as u32).wrapping_mul(immediate as u32) as u64
+                }
+                ALU_MOD_K => registers[dst] = (registers[dst] as u32 % immediate as u32) as u64,
+                ALU_XOR_X => {
+                    registers[dst] = (registers[dst] as u32 ^ registers[src] as u32) as u64
+                }
+                ALU_MOV_K => registers[dst] = immediate as u32 as u64,
+                ALU64_SUB_K => registers[dst] = registers[dst].wrapping_sub(immediate),
+                ALU64_MOV_K => registers[dst] = immediate,
+                ALU64_MOV_X => registers[dst] = registers[src],
+                JMP_EXIT => return registers[0] as u32,
+                code => {
+                    let taken = match code {
+                        JMP_JA => true,
+                        JMP_JEQ_K => registers[dst] == immediate,
+                        JMP_JGT_X => registers[dst] > registers[src],
+                        JMP_JSET_K => registers[dst] & immediate != 0,
+                        JMP_JLT_K => registers[dst] < immediate,
+                        JMP_JLE_K => registers[dst] <= immediate,
+                        _ => panic!("unexpected opcode {:#x}", code),
+                    };
+                    if taken {
+                        pc = (pc as isize + offset) as usize;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn program_test() {
+        check!()
+            .with_type::<(u8, Vec<u8>)>()
+            .for_each(|(shards, payload)| {
+                let shards = *shards as usize + 1;
+                let program = program(shards);
+                assert_eq!(
+                    run(&program, payload) as usize,
+                    shard::index_for_datagram(payload, shards)
+                );
+            });
+    }
+
+    #[test]
+    fn headers_test() {
+        let program = program(7);
+
+        // a long header with a truncated destination connection ID, and a short header with a
+        // connection ID which is shorter than the key
+        let payloads: &[&[u8]] = &[
+            &[],
+            &[0x40],
+            &[0x40, 1, 2],
+            &[0x40, 1, 2, 3, 4, 5, 6, 7, 8],
+            &[0xc0, 0, 0, 0, 1],
+            &[0xc0, 0, 0, 0, 1, 0],
+            &[0xc0, 0, 0, 0, 1, 8, 1, 2, 3],
+            &[0xc0, 0, 0, 0, 1, 3, 1, 2, 3],
+            &[0xc0, 0, 0, 0, 1, 8, 1, 2, 3, 4, 5, 6, 7, 8],
+        ];
+
+        for payload in payloads {
+            assert_eq!(
+                run(&program, payload) as usize,
+                shard::index_for_datagram(payload, 7),
+                "{:?}",
+                payload
+            );
+        }
+    }
+
+    #[test]
+    fn attach_test() {
+        use socket2::{Domain, Socket, Type};
+
+        let socket = Socket::new(Domain::IPV4, Type::DGRAM, None).unwrap();
+        // the program can only be attached to sockets in a reuse port group
+        socket.set_reuse_port(true).unwrap();
+        let addr: std::net::SocketAddr = "127.0.0.1:0".parse().unwrap();
+        socket.bind(&addr.into()).unwrap();
+
+        match attach(&socket, 4) {
+            Ok(()) => {}
+            // eBPF might not be available to the test process
+            Err(error)
+                if matches!(
+                    error.raw_os_error(),
+                    Some(libc::EPERM) | Some(libc::EACCES) | Some(libc::ENOSYS)
+                ) => {}
+            Err(error) => panic!("{}", error),
+        }
+    }
+}
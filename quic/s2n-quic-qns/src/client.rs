@@ -5,6 +5,8 @@ mod h09;
 mod h3;
 pub mod interop;
 pub mod perf;
+pub mod self_test;
 
 pub use interop::Interop;
 pub use perf::Perf;
+pub use self_test::SelfTest;
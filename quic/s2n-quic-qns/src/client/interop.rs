@@ -243,7 +243,7 @@ fn is_supported_testcase(testcase: Testcase) -> bool {
     }
 }
 
-fn parse_duration(duration: &str) -> Result<Duration> {
+pub(crate) fn parse_duration(duration: &str) -> Result<Duration> {
     let seconds = duration.parse()?;
     Ok(Duration::from_secs(seconds))
 }
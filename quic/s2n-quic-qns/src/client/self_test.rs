@@ -0,0 +1,446 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks the interoperability of the client with public QUIC endpoints
+//!
+//! Each endpoint is connected to twice. The first connection checks the handshake and stores the
+//! session ticket issued by the endpoint, which the second connection uses to resume the session.
+//! The application can't initiate key updates, so the key update check is only performed if the
+//! endpoint updates its keys while one of the connections is open.
+//!
+//! The report contains one JSON object per endpoint and line, so the reports of different builds
+//! can be compared.
+
+use crate::{client::interop::parse_duration, tls, Result};
+use core::time::Duration;
+use s2n_quic::{
+    client::Connect,
+    provider::{
+        event::{self, events, Subscriber},
+        io,
+        tls::rustls::rustls,
+    },
+    Client,
+};
+use serde::Serialize;
+use std::{
+    io::Write,
+    path::PathBuf,
+    sync::Arc,
+    time::{Instant, SystemTime},
+};
+use structopt::StructOpt;
+use tokio::net::lookup_host;
+
+/// Public endpoints which are operated by other QUIC implementations
+const DEFAULT_ENDPOINTS: &[&str] = &[
+    // quiche
+    "quic.tech:4433",
+    // aioquic
+    "quic.aiortc.org:443",
+    // ngtcp2
+    "nghttp2.org:4433",
+    // picoquic
+    "test.privateoctopus.com:4433",
+    // quic-go
+    "interop.seemann.io:443",
+    // nginx
+    "quic.nginx.org:443",
+];
+
+/// How long each connection is kept open after the handshake
+///
+/// Endpoints send session tickets after the handshake, so the first connection needs to stay
+/// open until the ticket is received.
+const LINGER: Duration = Duration::from_secs(1);
+
+#[derive(Debug, StructOpt)]
+pub struct SelfTest {
+    /// The `host:port` endpoints to check. The public endpoints of other QUIC implementations
+    /// are checked if no endpoints are specified.
+    endpoints: Vec<String>,
+
+    /// Reads additional endpoints from a file, one per line
+    #[structopt(long)]
+    endpoints_file: Option<PathBuf>,
+
+    /// Verifies the certificates of the endpoints with the given certificate authority. The
+    /// certificates are not verified otherwise.
+    #[structopt(long)]
+    ca: Option<PathBuf>,
+
+    #[structopt(long, default_value = "h3")]
+    application_protocols: Vec<String>,
+
+    /// The number of seconds after which a handshake is considered failed
+    #[structopt(long, default_value = "10", parse(try_from_str = parse_duration))]
+    timeout: Duration,
+
+    #[structopt(short, long, default_value = "::")]
+    local_ip: std::net::IpAddr,
+
+    /// Writes the report to the given file instead of stdout
+    #[structopt(long)]
+    output: Option<PathBuf>,
+}
+
+impl SelfTest {
+    pub async fn run(&self) -> Result<()> {
+        let endpoints = self.endpoints()?;
+        let mut client = self.client()?;
+
+        let mut output: Box<dyn Write> = if let Some(path) = self.output.as_ref() {
+            Box::new(std::fs::File::create(path)?)
+        } else {
+            Box::new(std::io::stdout())
+        };
+
+        let mut failures = 0;
+        for endpoint in &endpoints {
+            let report = self.check(&client, endpoint).await;
+            failures += report.failures();
+            serde_json::to_writer(&mut output, &report)?;
+            writeln!(output)?;
+        }
+        output.flush()?;
+
+        client.wait_idle().await?;
+
+        if failures > 0 {
+            return Err(format!("{} checks failed", failures).into());
+        }
+
+        Ok(())
+    }
+
+    fn endpoints(&self) -> Result<Vec<String>> {
+        let mut endpoints = self.endpoints.clone();
+
+        if let Some(path) = self.endpoints_file.as_ref() {
+            let contents = std::fs::read_to_string(path)?;
+            endpoints.extend(
+                contents
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(String::from),
+            );
+        }
+
+        if endpoints.is_empty() {
+            endpoints.extend(DEFAULT_ENDPOINTS.iter().copied().map(String::from));
+        }
+
+        Ok(endpoints)
+    }
+
+    fn client(&self) -> Result<Client> {
+        let io = io::Default::builder()
+            .with_receive_address((self.local_ip, 0u16).into())?
+            .build()?;
+
+        // rustls caches the session tickets of the client, which is required for the
+        // resumption check
+        let tls = s2n_quic::provider::tls::rustls::Client::builder();
+        let tls = if self.ca.is_some() {
+            tls.with_certificate(tls::rustls::ca(self.ca.as_ref())?)?
+        } else {
+            tls.with_server_cert_verifier(Arc::new(NoVerification))?
+        };
+        let tls = tls
+            .with_application_protocols(self.application_protocols.iter().map(String::as_bytes))?
+            .build()?;
+
+        let client = Client::builder()
+            .with_io(io)?
+            .with_event((Recorder, event::tracing::Subscriber::default()))?
+            .with_tls(tls)?
+            .start()?;
+
+        Ok(client)
+    }
+
+    async fn check(&self, client: &Client, endpoint: &str) -> Report {
+        let mut report = Report::new(endpoint);
+
+        let connect = match resolve(endpoint).await {
+            Ok(connect) => connect,
+            Err(error) => {
+                report.handshake = Check::failed(error);
+                return report;
+            }
+        };
+
+        let first = match self.connect(client, connect.clone()).await {
+            Ok(outcome) => outcome,
+            Err(error) => {
+                report.handshake = Check::failed(error);
+                return report;
+            }
+        };
+        report.handshake = Check::passed(first.duration);
+        report.application_protocol = Some(first.application_protocol.clone());
+
+        let mut key_updates = first.observations.key_updates;
+
+        report.resumption = match self.connect(client, connect).await {
+            Ok(second) if second.observations.resumed => {
+                key_updates += second.observations.key_updates;
+                Check::passed(second.duration)
+            }
+            Ok(second) => {
+                key_updates += second.observations.key_updates;
+                Check::failed("the session was not resumed")
+            }
+            Err(error) => Check::failed(error),
+        };
+
+        report.key_update = if key_updates > 0 {
+            Check::Passed { duration_ms: None }
+        } else {
+            Check::skipped("the endpoint did not update its keys")
+        };
+
+        report
+    }
+
+    async fn connect(&self, client: &Client, connect: Connect) -> Result<Outcome> {
+        let start = Instant::now();
+        let mut connection = tokio::time::timeout(self.timeout, client.connect(connect))
+            .await
+            .map_err(|_| "the handshake timed out")??;
+        let duration = start.elapsed();
+
+        let application_protocol =
+            String::from_utf8_lossy(&connection.application_protocol()?).into_owned();
+
+        connection.ping()?;
+        tokio::time::sleep(LINGER).await;
+
+        let observations = connection.query_event_context(|context: &Observations| *context)?;
+
+        Ok(Outcome {
+            duration,
+            application_protocol,
+            observations,
+        })
+    }
+}
+
+async fn resolve(endpoint: &str) -> Result<Connect> {
+    let (host, _port) = endpoint
+        .rsplit_once(':')
+        .ok_or("endpoints must be specified as `host:port`")?;
+    let address = lookup_host(endpoint)
+        .await?
+        .next()
+        .ok_or("the host did not resolve to any addresses")?;
+
+    Ok(Connect::new(address).with_server_name(host))
+}
+
+/// The results of the checks for a single endpoint
+#[derive(Debug, Serialize)]
+struct Report {
+    endpoint: String,
+    application_protocol: Option<String>,
+    handshake: Check,
+    resumption: Check,
+    key_update: Check,
+}
+
+impl Report {
+    fn new(endpoint: &str) -> Self {
+        let not_performed = || Check::skipped("the handshake failed");
+        Self {
+            endpoint: endpoint.to_string(),
+            application_protocol: None,
+            handshake: not_performed(),
+            resumption: not_performed(),
+            key_update: not_performed(),
+        }
+    }
+
+    fn failures(&self) -> usize {
+        [&self.handshake, &self.resumption, &self.key_update]
+            .iter()
+            .filter(|check| matches!(check, Check::Failed { .. }))
+            .count()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+enum Check {
+    Passed {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        duration_ms: Option<u128>,
+    },
+    Failed {
+        reason: String,
+    },
+    Skipped {
+        reason: String,
+    },
+}
+
+impl Check {
+    fn passed(duration: Duration) -> Self {
+        Self::Passed {
+            duration_ms: Some(duration.as_millis()),
+        }
+    }
+
+    fn failed<E: ToString>(reason: E) -> Self {
+        Self::Failed {
+            reason: reason.to_string(),
+        }
+    }
+
+    fn skipped(reason: &str) -> Self {
+        Self::Skipped {
+            reason: reason.to_string(),
+        }
+    }
+}
+
+struct Outcome {
+    duration: Duration,
+    application_protocol: String,
+    observations: Observations,
+}
+
+/// What the client observed on a connection
+#[derive(Clone, Copy, Debug, Default)]
+struct Observations {
+    /// The endpoint accepted the session ticket of a previous connection
+    resumed: bool,
+    /// The number of times the 1-RTT keys were updated
+    key_updates: u16,
+}
+
+#[derive(Debug, Default)]
+struct Recorder;
+
+impl Subscriber for Recorder {
+    type ConnectionContext = Observations;
+
+    fn create_connection_context(
+        &mut self,
+        _meta: &events::ConnectionMeta,
+        _info: &events::ConnectionInfo,
+    ) -> Self::ConnectionContext {
+        Observations::default()
+    }
+
+    fn on_tls_server_hello(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &events::ConnectionMeta,
+        event: &events::TlsServerHello,
+    ) {
+        context.resumed = selects_pre_shared_key(event.payload).unwrap_or(false);
+    }
+
+    fn on_key_update(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &events::ConnectionMeta,
+        event: &events::KeyUpdate,
+    ) {
+        if let events::KeyType::OneRtt { generation, .. } = event.key_type {
+            context.key_updates = context.key_updates.max(generation);
+        }
+    }
+}
+
+/// Returns `true` if the ServerHello selects one of the pre-shared keys offered by the client
+///
+/// The server only includes the `pre_shared_key` extension if it resumes the session. `None` is
+/// returned if the message is malformed.
+fn selects_pre_shared_key(payload: &[&[u8]]) -> Option<bool> {
+    const PRE_SHARED_KEY: u16 = 41;
+
+    let hello = payload.concat();
+    // skip the legacy_version and random fields
+    let hello = hello.get(34..)?;
+    let session_id_len = *hello.first()? as usize;
+    // skip the legacy_session_id_echo, cipher_suite and legacy_compression_method fields
+    let hello = hello.get(1 + session_id_len + 3..)?;
+    let (extensions_len, hello) = split_u16(hello)?;
+    let mut extensions = hello.get(..extensions_len as usize)?;
+
+    while !extensions.is_empty() {
+        let (extension_type, rest) = split_u16(extensions)?;
+        let (len, rest) = split_u16(rest)?;
+        if extension_type == PRE_SHARED_KEY {
+            return Some(true);
+        }
+        extensions = rest.get(len as usize..)?;
+    }
+
+    Some(false)
+}
+
+fn split_u16(bytes: &[u8]) -> Option<(u16, &[u8])> {
+    let value = bytes.get(..2)?;
+    Some((u16::from_be_bytes([value[0], value[1]]), &bytes[2..]))
+}
+
+/// Accepts the certificates of all endpoints
+///
+/// The public endpoints use certificates from various authorities, some of which are
+/// self-signed, and the checks are about the QUIC implementations rather than their PKI.
+struct NoVerification;
+
+impl rustls::client::ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
+}
+
+#[test]
+fn selects_pre_shared_key_test() {
+    fn server_hello(extensions: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut extension_bytes = vec![];
+        for (extension_type, value) in extensions {
+            extension_bytes.extend_from_slice(&extension_type.to_be_bytes());
+            extension_bytes.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            extension_bytes.extend_from_slice(value);
+        }
+
+        let mut hello = vec![0x03, 0x03];
+        hello.extend_from_slice(&[0; 32]);
+        // session id
+        hello.push(0);
+        // TLS_AES_128_GCM_SHA256 and no compression
+        hello.extend_from_slice(&[0x13, 0x01, 0]);
+        hello.extend_from_slice(&(extension_bytes.len() as u16).to_be_bytes());
+        hello.extend_from_slice(&extension_bytes);
+        hello
+    }
+
+    let supported_versions: (u16, &[u8]) = (43, &[0x03, 0x04]);
+    let key_share: (u16, &[u8]) = (51, &[0; 36]);
+    let pre_shared_key: (u16, &[u8]) = (41, &[0, 0]);
+
+    let full = server_hello(&[supported_versions, key_share]);
+    assert_eq!(selects_pre_shared_key(&[&full]), Some(false));
+
+    let resumed = server_hello(&[supported_versions, pre_shared_key, key_share]);
+    assert_eq!(selects_pre_shared_key(&[&resumed]), Some(true));
+
+    // the payload can be split into multiple chunks
+    let (a, b) = resumed.split_at(40);
+    assert_eq!(selects_pre_shared_key(&[a, b]), Some(true));
+
+    assert_eq!(selects_pre_shared_key(&[&resumed[..60]]), None);
+}
@@ -55,6 +55,8 @@ async fn main() {
 enum Arguments {
     Interop(Interop),
     Perf(Perf),
+    /// Checks the interoperability of the client with public QUIC endpoints
+    SelfTest(client::SelfTest),
 }
 
 impl Arguments {
@@ -62,6 +64,7 @@ impl Arguments {
         match self {
             Self::Interop(subject) => subject.run().await,
             Self::Perf(subject) => subject.run().await,
+            Self::SelfTest(subject) => subject.run().await,
         }
     }
 }
@@ -132,6 +132,9 @@ fn parse_h09_request(chunks: &[Bytes], path: &mut String, is_open: bool) -> Resu
             Some(b'.') => path.push('.'),
             Some(b'/') => path.push('/'),
             Some(b'-') => path.push('-'),
+            // the remaining unreserved characters of RFC 3986 Section 2.3
+            Some(b'_') => path.push('_'),
+            Some(b'~') => path.push('~'),
             Some(b'\n' | b'\r') => return Ok(true),
             // https://www.w3.org/Protocols/HTTP/AsImplemented.html
             // > The document address will consist of a single word (ie no spaces).
@@ -169,6 +172,8 @@ fn parse_h09_request_test() {
     test!(["GET /abc/123"], Ok(Some("abc/123")));
     test!(["GET /CAPS/lower"], Ok(Some("CAPS/lower")));
     test!(["GET /abc\rextra stuff"], Ok(Some("abc")));
+    test!(["GET /a_b~c-d.e"], Ok(Some("a_b~c-d.e")));
+    test!(["GET /a%20b"], Err(_));
     test!(
         ["G", "E", "T", " ", "/", "t", "E", "s", "T"],
         Ok(Some("tEsT"))
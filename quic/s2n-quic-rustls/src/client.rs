@@ -5,17 +5,23 @@ use crate::{certificate, encode_transport_parameters, session::Session};
 use core::convert::TryFrom;
 use rustls::{quic, ClientConfig};
 use s2n_codec::EncoderValue;
-use s2n_quic_core::{application::ServerName, crypto::tls};
-use std::sync::Arc;
+use s2n_quic_core::{
+    application::ServerName,
+    crypto::tls::{self, ApplicationSettings},
+};
+use s2n_quic_crypto::{sct, spki};
+use std::{sync::Arc, time::SystemTime};
 
 pub struct Client {
     config: Arc<ClientConfig>,
+    application_settings: ApplicationSettings,
 }
 
 impl Client {
     pub fn new(config: ClientConfig) -> Self {
         Self {
             config: Arc::new(config),
+            application_settings: ApplicationSettings::new(),
         }
     }
 
@@ -58,7 +64,8 @@ impl tls::Endpoint for Client {
 
         //= https://www.rfc-editor.org/rfc/rfc9001#section-8.2
         //# Endpoints MUST send the quic_transport_parameters extension;
-        let transport_parameters = encode_transport_parameters(transport_parameters);
+        let transport_parameters =
+            encode_transport_parameters(transport_parameters, &self.application_settings);
 
         let rustls_server_name =
             rustls::ServerName::try_from(server_name.as_ref()).expect("invalid server name");
@@ -81,8 +88,13 @@ impl tls::Endpoint for Client {
 
 pub struct Builder {
     cert_store: rustls::RootCertStore,
+    cert_verifier: Option<Arc<dyn rustls::client::ServerCertVerifier>>,
+    spki_pins: Vec<spki::Digest>,
+    sct_policy: Option<sct::Policy>,
+    client_identity: Option<(certificate::Certificate, certificate::PrivateKey)>,
     application_protocols: Vec<Vec<u8>>,
     key_log: Option<Arc<dyn rustls::KeyLog>>,
+    application_settings: ApplicationSettings,
 }
 
 impl Default for Builder {
@@ -95,22 +107,108 @@ impl Builder {
     pub fn new() -> Self {
         Self {
             cert_store: rustls::RootCertStore::empty(),
+            cert_verifier: None,
+            spki_pins: Vec::new(),
+            sct_policy: None,
+            client_identity: None,
             application_protocols: vec![b"h3".to_vec()],
             key_log: None,
+            application_settings: ApplicationSettings::new(),
         }
     }
 
+    /// Adds the certificates to the trust store
+    ///
+    /// PEM-encoded bundles can contain any number of certificates, which are all trusted.
     pub fn with_certificate<C: certificate::IntoCertificate>(
         mut self,
         certificate: C,
     ) -> Result<Self, rustls::Error> {
         let certificates = certificate.into_certificate()?;
-        let root_certificate = certificates.0.get(0).ok_or_else(|| {
-            rustls::Error::General("Certificate chain needs to have at least one entry".to_string())
-        })?;
-        self.cert_store
-            .add(root_certificate)
+        if certificates.0.is_empty() {
+            return Err(rustls::Error::General(
+                "Certificate chain needs to have at least one entry".to_string(),
+            ));
+        }
+        for certificate in &certificates.0 {
+            self.cert_store
+                .add(certificate)
+                .map_err(|err| rustls::Error::General(err.to_string()))?;
+        }
+        Ok(self)
+    }
+
+    /// Clears the trust store for this client
+    ///
+    /// The trust store of rustls clients is empty by default, so this only removes the
+    /// certificates which were added before.
+    pub fn with_empty_trust_store(mut self) -> Result<Self, rustls::Error> {
+        self.cert_store = rustls::RootCertStore::empty();
+        Ok(self)
+    }
+
+    /// Adds the certificates of the platform trust store to the trust store
+    ///
+    /// Certificates of the platform which can't be parsed are skipped.
+    pub fn with_system_trust_store(mut self) -> Result<Self, rustls::Error> {
+        let certificates = rustls_native_certs::load_native_certs()
             .map_err(|err| rustls::Error::General(err.to_string()))?;
+        let certificates: Vec<_> = certificates
+            .into_iter()
+            .map(|certificate| certificate.0)
+            .collect();
+        self.cert_store.add_parsable_certificates(&certificates);
+        Ok(self)
+    }
+
+    /// Pins the public keys the server may present
+    ///
+    /// Each pin is the SHA-256 digest of a DER-encoded SubjectPublicKeyInfo, as returned by
+    /// [`spki::digest`]. The certificate chain of the server is still verified as usual, but is
+    /// additionally required to contain a certificate with one of the pinned keys.
+    pub fn with_spki_pins<P: IntoIterator<Item = spki::Digest>>(
+        mut self,
+        pins: P,
+    ) -> Result<Self, rustls::Error> {
+        self.spki_pins.extend(pins);
+        Ok(self)
+    }
+
+    /// Requires the certificate of the server to embed SCTs which comply with the policy
+    ///
+    /// The SCTs are verified after the certificate chain of the server. Servers which don't
+    /// comply with the policy are rejected with an error describing the [`sct::Verification`].
+    pub fn with_sct_policy(mut self, policy: sct::Policy) -> Result<Self, rustls::Error> {
+        self.sct_policy = Some(policy);
+        Ok(self)
+    }
+
+    /// Sets the verifier for the certificate chain presented by the server
+    ///
+    /// The verifier replaces the verification against the certificates added with
+    /// [`Self::with_certificate`].
+    pub fn with_server_cert_verifier(
+        mut self,
+        verifier: Arc<dyn rustls::client::ServerCertVerifier>,
+    ) -> Result<Self, rustls::Error> {
+        self.cert_verifier = Some(verifier);
+        Ok(self)
+    }
+
+    /// Sets the certificate chain and private key the client authenticates itself with
+    ///
+    /// The client only presents the certificate if the server requests client authentication.
+    pub fn with_client_identity<
+        C: certificate::IntoCertificate,
+        PK: certificate::IntoPrivateKey,
+    >(
+        mut self,
+        certificate: C,
+        private_key: PK,
+    ) -> Result<Self, rustls::Error> {
+        let certificate = certificate.into_certificate()?;
+        let private_key = private_key.into_private_key()?;
+        self.client_identity = Some((certificate, private_key));
         Ok(self)
     }
 
@@ -128,27 +226,63 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sends settings for an application protocol to the server during the handshake
+    ///
+    /// The server only uses the settings if the application protocol is negotiated. Likewise,
+    /// the settings the server sent for the negotiated protocol are available on the connection
+    /// once the handshake is complete. See [`ApplicationSettings`] for how the settings are
+    /// exchanged.
+    pub fn with_application_settings(
+        mut self,
+        application_protocol: &[u8],
+        settings: &[u8],
+    ) -> Result<Self, rustls::Error> {
+        self.application_settings
+            .insert(application_protocol, settings)
+            .map_err(|err| rustls::Error::General(err.to_string()))?;
+        Ok(self)
+    }
+
     pub fn with_key_logging(mut self) -> Result<Self, rustls::Error> {
         self.key_log = Some(Arc::new(rustls::KeyLogFile::new()));
         Ok(self)
     }
 
     pub fn build(self) -> Result<Client, rustls::Error> {
-        // TODO load system root store?
-        if self.cert_store.is_empty() {
-            //= https://www.rfc-editor.org/rfc/rfc9001#section-4.4
-            //# A client MUST authenticate the identity of the server.
-            return Err(rustls::Error::General(
-                "missing trusted root certificate(s)".to_string(),
-            ));
-        }
-
-        let mut config = ClientConfig::builder()
+        let builder = ClientConfig::builder()
             .with_cipher_suites(crate::cipher_suite::DEFAULT_CIPHERSUITES)
             .with_safe_default_kx_groups()
-            .with_protocol_versions(crate::PROTOCOL_VERSIONS)?
-            .with_root_certificates(self.cert_store)
-            .with_no_client_auth();
+            .with_protocol_versions(crate::PROTOCOL_VERSIONS)?;
+
+        let cert_verifier = if let Some(cert_verifier) = self.cert_verifier {
+            cert_verifier
+        } else {
+            if self.cert_store.is_empty() {
+                //= https://www.rfc-editor.org/rfc/rfc9001#section-4.4
+                //# A client MUST authenticate the identity of the server.
+                return Err(rustls::Error::General(
+                    "missing trusted root certificate(s)".to_string(),
+                ));
+            }
+
+            Arc::new(rustls::client::WebPkiVerifier::new(self.cert_store, None))
+        };
+        let cert_verifier = if self.spki_pins.is_empty() && self.sct_policy.is_none() {
+            cert_verifier
+        } else {
+            Arc::new(PolicyVerifier {
+                verifier: cert_verifier,
+                spki_pins: self.spki_pins,
+                sct_policy: self.sct_policy,
+            })
+        };
+        let builder = builder.with_custom_certificate_verifier(cert_verifier);
+
+        let mut config = if let Some((certificate, private_key)) = self.client_identity {
+            builder.with_single_cert(certificate.0, private_key.0)?
+        } else {
+            builder.with_no_client_auth()
+        };
 
         config.max_fragment_size = None;
         config.alpn_protocols = self.application_protocols;
@@ -157,6 +291,86 @@ impl Builder {
             config.key_log = key_log;
         }
 
-        Ok(Client::new(config))
+        Ok(Client {
+            config: Arc::new(config),
+            application_settings: self.application_settings,
+        })
+    }
+}
+
+/// Applies the policies of the client after the certificate chain of the server is verified
+struct PolicyVerifier {
+    verifier: Arc<dyn rustls::client::ServerCertVerifier>,
+    spki_pins: Vec<spki::Digest>,
+    sct_policy: Option<sct::Policy>,
+}
+
+impl rustls::client::ServerCertVerifier for PolicyVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::Certificate,
+        intermediates: &[rustls::Certificate],
+        server_name: &rustls::ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        let verified = self.verifier.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            scts,
+            ocsp_response,
+            now,
+        )?;
+
+        let certificates = core::iter::once(end_entity)
+            .chain(intermediates)
+            .map(|certificate| certificate.0.as_slice());
+
+        if !self.spki_pins.is_empty() && !spki::is_pinned(&self.spki_pins, certificates) {
+            return Err(rustls::Error::InvalidCertificateData(
+                "none of the presented public keys are pinned".to_string(),
+            ));
+        }
+
+        if let Some(policy) = self.sct_policy.as_ref() {
+            let issuer = intermediates.first().map(|issuer| issuer.0.as_slice());
+            let verification = policy.verify(&end_entity.0, issuer, now);
+            if !verification.is_compliant() {
+                return Err(rustls::Error::InvalidCertificateData(format!(
+                    "the certificate transparency policy isn't met: {}",
+                    verification
+                )));
+            }
+        }
+
+        Ok(verified)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::Certificate,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::HandshakeSignatureValid, rustls::Error> {
+        self.verifier.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::Certificate,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::HandshakeSignatureValid, rustls::Error> {
+        self.verifier.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.verifier.supported_verify_schemes()
+    }
+
+    fn request_scts(&self) -> bool {
+        self.verifier.request_scts()
     }
 }
@@ -4,6 +4,10 @@
 #![forbid(unsafe_code)]
 
 pub use rustls::{self, Certificate, PrivateKey};
+pub use s2n_quic_crypto::{sct, spki};
+
+use s2n_codec::EncoderValue as _;
+use s2n_quic_core::crypto::tls::ApplicationSettings;
 
 mod cipher_suite;
 mod error;
@@ -12,6 +16,7 @@ mod session;
 pub mod certificate;
 pub mod client;
 pub mod server;
+pub mod ticket;
 
 pub use client::Client;
 pub use server::Server;
@@ -23,13 +28,16 @@ static PROTOCOL_VERSIONS: &[&rustls::SupportedProtocolVersion] = &[&rustls::vers
 /// The supported version of quic
 const QUIC_VERSION: rustls::quic::Version = rustls::quic::Version::V1;
 
-/// Encodes transport parameters into a byte vec
+/// Encodes transport parameters into a byte vec, followed by the application settings
 pub(crate) fn encode_transport_parameters<Params: s2n_codec::EncoderValue>(
     params: &Params,
+    application_settings: &ApplicationSettings,
 ) -> Vec<u8> {
-    let len = params.encoding_size();
+    let len = params.encoding_size() + application_settings.encoding_size();
     let mut buffer = vec![0; len];
-    params.encode(&mut s2n_codec::EncoderBuffer::new(&mut buffer));
+    let mut encoder = s2n_codec::EncoderBuffer::new(&mut buffer);
+    params.encode(&mut encoder);
+    application_settings.encode(&mut encoder);
     buffer
 }
 
@@ -57,3 +65,408 @@ fn client_server_test() {
 
     pair.finish();
 }
+
+#[test]
+fn conformance_test() {
+    use s2n_quic_core::crypto::tls::{self, testing::certificates::*};
+
+    let mut client = client::Builder::new()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let mut server = server::Builder::new()
+        .with_certificate(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    tls::testing::conformance::run(&mut server, &mut client);
+}
+
+#[test]
+fn client_authentication_test() {
+    use s2n_quic_core::crypto::tls::{self, testing::certificates::*};
+
+    let server = || {
+        server::Builder::new()
+            .with_certificate(CERT_PEM, KEY_PEM)
+            .unwrap()
+            .with_trusted_certificate(CERT_PEM)
+            .unwrap()
+            .with_client_authentication()
+            .unwrap()
+            .build()
+            .unwrap()
+    };
+
+    let mut client = client::Builder::new()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .with_client_identity(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let mut server_endpoint = server();
+    let mut pair = tls::testing::Pair::new(&mut server_endpoint, &mut client, "localhost".into());
+
+    while pair.is_handshaking() {
+        pair.poll(None).unwrap();
+    }
+
+    pair.finish();
+
+    // both endpoints expose the certificates presented by their peer
+    use certificate::IntoCertificate;
+    let certificates = CERT_PEM
+        .into_certificate()
+        .unwrap()
+        .0
+        .into_iter()
+        .map(|cert| bytes::Bytes::from(cert.0))
+        .collect();
+    let certificates = Some(certificates);
+    assert_eq!(pair.server.context.peer_certificates, certificates);
+    assert_eq!(pair.client.context.peer_certificates, certificates);
+
+    // the handshake fails if the client doesn't present a certificate
+    let mut client = client::Builder::new()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let mut server_endpoint = server();
+    let mut pair = tls::testing::Pair::new(&mut server_endpoint, &mut client, "localhost".into());
+
+    let result = (|| {
+        while pair.is_handshaking() {
+            pair.poll(None)?;
+        }
+        Ok::<_, s2n_quic_core::transport::Error>(())
+    })();
+    assert!(result.is_err());
+
+    // client authentication requires trusted certificates to verify the client
+    assert!(server::Builder::new()
+        .with_certificate(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .with_client_authentication()
+        .unwrap()
+        .build()
+        .is_err());
+}
+
+#[test]
+fn cert_verifier_test() {
+    use rustls::{
+        client::{ServerCertVerified, ServerCertVerifier},
+        server::{ClientCertVerified, ClientCertVerifier},
+    };
+    use s2n_quic_core::crypto::tls::{self, testing::certificates::*};
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::SystemTime,
+    };
+
+    /// Accepts any certificate and counts the verified certificates
+    #[derive(Default)]
+    struct Verifier {
+        verified: AtomicUsize,
+        reject: bool,
+    }
+
+    impl Verifier {
+        fn verify(&self) -> Result<(), rustls::Error> {
+            self.verified.fetch_add(1, Ordering::Relaxed);
+            if self.reject {
+                Err(rustls::Error::General("rejected".to_string()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl ServerCertVerifier for Verifier {
+        fn verify_server_cert(
+            &self,
+            _end_entity: &Certificate,
+            _intermediates: &[Certificate],
+            _server_name: &rustls::ServerName,
+            _scts: &mut dyn Iterator<Item = &[u8]>,
+            _ocsp_response: &[u8],
+            _now: SystemTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            self.verify()?;
+            Ok(ServerCertVerified::assertion())
+        }
+    }
+
+    impl ClientCertVerifier for Verifier {
+        fn client_auth_root_subjects(&self) -> Option<rustls::DistinguishedNames> {
+            Some(Vec::new())
+        }
+
+        fn verify_client_cert(
+            &self,
+            _end_entity: &Certificate,
+            _intermediates: &[Certificate],
+            _now: SystemTime,
+        ) -> Result<ClientCertVerified, rustls::Error> {
+            self.verify()?;
+            Ok(ClientCertVerified::assertion())
+        }
+    }
+
+    let run = |server_verifier: Arc<Verifier>, client_verifier: Arc<Verifier>| {
+        let mut server = server::Builder::new()
+            .with_certificate(CERT_PEM, KEY_PEM)
+            .unwrap()
+            .with_client_cert_verifier(client_verifier)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        // the client doesn't trust any certificates, so only the verifier can accept the server
+        let mut client = client::Builder::new()
+            .with_server_cert_verifier(server_verifier)
+            .unwrap()
+            .with_client_identity(CERT_PEM, KEY_PEM)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut pair = tls::testing::Pair::new(&mut server, &mut client, "localhost".into());
+        while pair.is_handshaking() {
+            pair.poll(None)?;
+        }
+        pair.finish();
+
+        Ok::<_, s2n_quic_core::transport::Error>(())
+    };
+
+    let server_verifier = Arc::new(Verifier::default());
+    let client_verifier = Arc::new(Verifier::default());
+    run(server_verifier.clone(), client_verifier.clone()).unwrap();
+    assert_eq!(server_verifier.verified.load(Ordering::Relaxed), 1);
+    assert_eq!(client_verifier.verified.load(Ordering::Relaxed), 1);
+
+    let rejecting = || {
+        Arc::new(Verifier {
+            reject: true,
+            ..Default::default()
+        })
+    };
+    assert!(run(rejecting(), Arc::new(Verifier::default())).is_err());
+    assert!(run(Arc::new(Verifier::default()), rejecting()).is_err());
+}
+
+#[test]
+fn spki_pins_test() {
+    use s2n_quic_core::crypto::tls::{self, testing::certificates::*};
+
+    let run = |pin: spki::Digest| {
+        let mut server = server::Builder::new()
+            .with_certificate(CERT_PEM, KEY_PEM)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut client = client::Builder::new()
+            .with_certificate(CERT_PEM)
+            .unwrap()
+            .with_spki_pins([pin])
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut pair = tls::testing::Pair::new(&mut server, &mut client, "localhost".into());
+        while pair.is_handshaking() {
+            pair.poll(None)?;
+        }
+        pair.finish();
+
+        Ok::<_, s2n_quic_core::transport::Error>(())
+    };
+
+    // the DER certificate contains the same key as the PEM certificate of the server
+    run(spki::digest(CERT_DER).unwrap()).unwrap();
+    assert!(run([0; spki::DIGEST_LEN]).is_err());
+}
+
+#[test]
+fn sct_policy_test() {
+    use s2n_quic_core::crypto::tls::{self, testing::certificates::*};
+
+    let run = |policy: sct::Policy| {
+        let mut server = server::Builder::new()
+            .with_certificate(CERT_PEM, KEY_PEM)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut client = client::Builder::new()
+            .with_certificate(CERT_PEM)
+            .unwrap()
+            .with_sct_policy(policy)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut pair = tls::testing::Pair::new(&mut server, &mut client, "localhost".into());
+        while pair.is_handshaking() {
+            pair.poll(None)?;
+        }
+        pair.finish();
+
+        Ok::<_, s2n_quic_core::transport::Error>(())
+    };
+
+    // the test certificate doesn't embed any SCTs
+    run(sct::Policy::new(0)).unwrap();
+    assert!(run(sct::Policy::new(1)).is_err());
+}
+
+#[test]
+fn application_settings_test() {
+    use s2n_quic_core::crypto::tls::{self, testing::certificates::*};
+
+    let mut client = client::Builder::new()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .with_application_settings(b"h3", b"client settings")
+        .unwrap()
+        .with_application_settings(b"hq-interop", b"unused")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let server = |settings: Option<&[u8]>| {
+        let builder = server::Builder::new()
+            .with_certificate(CERT_PEM, KEY_PEM)
+            .unwrap();
+        let builder = match settings {
+            Some(settings) => builder.with_application_settings(b"h3", settings).unwrap(),
+            None => builder,
+        };
+        builder.build().unwrap()
+    };
+
+    // each endpoint receives the settings of the peer for the negotiated protocol
+    let mut server_endpoint = server(Some(&b"server settings"[..]));
+    let mut pair = tls::testing::Pair::new(&mut server_endpoint, &mut client, "localhost".into());
+    while pair.is_handshaking() {
+        pair.poll(None).unwrap();
+    }
+    pair.finish();
+    assert_eq!(
+        pair.client.context.application_settings.as_deref(),
+        Some(&b"server settings"[..])
+    );
+    assert_eq!(
+        pair.server.context.application_settings.as_deref(),
+        Some(&b"client settings"[..])
+    );
+
+    // nothing is reported if the peer didn't send any settings
+    let mut server_endpoint = server(None);
+    let mut pair = tls::testing::Pair::new(&mut server_endpoint, &mut client, "localhost".into());
+    while pair.is_handshaking() {
+        pair.poll(None).unwrap();
+    }
+    pair.finish();
+    assert_eq!(pair.client.context.application_settings, None);
+    assert_eq!(
+        pair.server.context.application_settings.as_deref(),
+        Some(&b"client settings"[..])
+    );
+
+    // protocols need to be valid
+    assert!(client::Builder::new()
+        .with_application_settings(b"", b"settings")
+        .is_err());
+}
+
+#[test]
+fn resumption_test() {
+    use core::{task::Poll, time::Duration};
+    use s2n_quic_core::crypto::tls::{self, testing::certificates::*, Session as _};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    /// Counts the tickets which were presented by the client
+    struct Keys {
+        schedule: ticket::Schedule,
+        decrypted: Arc<AtomicUsize>,
+    }
+
+    impl ticket::KeyProvider for Keys {
+        fn encryption_key(&self) -> Option<ticket::Key> {
+            self.schedule.encryption_key()
+        }
+
+        fn decryption_key(&self, name: &[u8; ticket::NAME_LEN]) -> Option<ticket::Key> {
+            let key = self.schedule.decryption_key(name)?;
+            self.decrypted.fetch_add(1, Ordering::Relaxed);
+            Some(key)
+        }
+
+        fn lifetime(&self) -> u32 {
+            self.schedule.lifetime()
+        }
+    }
+
+    let decrypted = Arc::new(AtomicUsize::new(0));
+
+    // creates a new server instance, as if the previous one was restarted
+    let server = || {
+        let keys = Keys {
+            schedule: ticket::Schedule::new(b"secret", Duration::from_secs(60)),
+            decrypted: decrypted.clone(),
+        };
+        server::Builder::new()
+            .with_certificate(CERT_PEM, KEY_PEM)
+            .unwrap()
+            .with_session_ticket_keys(keys)
+            .unwrap()
+            .build()
+            .unwrap()
+    };
+
+    let mut client = client::Builder::new()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    for expected in [0, 1] {
+        let mut server = server();
+        let mut pair = tls::testing::Pair::new(&mut server, &mut client, "localhost".into());
+
+        while pair.is_handshaking() {
+            pair.poll(None).unwrap();
+        }
+
+        pair.finish();
+
+        // deliver the session ticket, which is sent after the handshake
+        assert!(matches!(
+            pair.server.session.poll(&mut pair.server.context),
+            Poll::Ready(Ok(()))
+        ));
+        pair.client.context.transfer(&mut pair.server.context);
+        assert!(matches!(
+            pair.client.session.poll(&mut pair.client.context),
+            Poll::Ready(Ok(()))
+        ));
+
+        assert_eq!(decrypted.load(Ordering::Relaxed), expected);
+    }
+}
@@ -1,20 +1,25 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{certificate, encode_transport_parameters, session::Session};
+use crate::{certificate, encode_transport_parameters, session::Session, ticket};
 use rustls::{quic, ServerConfig};
 use s2n_codec::EncoderValue;
-use s2n_quic_core::{application::ServerName, crypto::tls};
+use s2n_quic_core::{
+    application::ServerName,
+    crypto::tls::{self, ApplicationSettings},
+};
 use std::sync::Arc;
 
 pub struct Server {
     config: Arc<ServerConfig>,
+    application_settings: ApplicationSettings,
 }
 
 impl Server {
     pub fn new(config: ServerConfig) -> Self {
         Self {
             config: Arc::new(config),
+            application_settings: ApplicationSettings::new(),
         }
     }
 
@@ -48,7 +53,8 @@ impl tls::Endpoint for Server {
 
         //= https://www.rfc-editor.org/rfc/rfc9001#section-8.2
         //# Endpoints MUST send the quic_transport_parameters extension;
-        let transport_parameters = encode_transport_parameters(transport_parameters);
+        let transport_parameters =
+            encode_transport_parameters(transport_parameters, &self.application_settings);
 
         let session = rustls::ServerConnection::new_quic(
             self.config.clone(),
@@ -75,8 +81,13 @@ impl tls::Endpoint for Server {
 
 pub struct Builder {
     cert_resolver: Option<Arc<dyn rustls::server::ResolvesServerCert>>,
+    trust_store: rustls::RootCertStore,
+    client_authentication: bool,
+    client_cert_verifier: Option<Arc<dyn rustls::server::ClientCertVerifier>>,
     application_protocols: Vec<Vec<u8>>,
     key_log: Option<Arc<dyn rustls::KeyLog>>,
+    application_settings: ApplicationSettings,
+    ticketer: Option<Arc<dyn rustls::server::ProducesTickets>>,
 }
 
 impl Default for Builder {
@@ -89,8 +100,13 @@ impl Builder {
     pub fn new() -> Self {
         Self {
             cert_resolver: None,
+            trust_store: rustls::RootCertStore::empty(),
+            client_authentication: false,
+            client_cert_verifier: None,
             application_protocols: vec![b"h3".to_vec()],
             key_log: None,
+            application_settings: ApplicationSettings::new(),
+            ticketer: None,
         }
     }
 
@@ -114,6 +130,46 @@ impl Builder {
         Ok(self)
     }
 
+    /// Adds a certificate authority which is trusted to issue client certificates
+    pub fn with_trusted_certificate<C: certificate::IntoCertificate>(
+        mut self,
+        certificate: C,
+    ) -> Result<Self, rustls::Error> {
+        let certificates = certificate.into_certificate()?;
+        let root_certificate = certificates.0.first().ok_or_else(|| {
+            rustls::Error::General("Certificate chain needs to have at least one entry".to_string())
+        })?;
+        self.trust_store
+            .add(root_certificate)
+            .map_err(|err| rustls::Error::General(err.to_string()))?;
+        Ok(self)
+    }
+
+    /// Configures this server instance to require client authentication (mutual TLS).
+    ///
+    /// Client certificates are verified against the certificates added with
+    /// [`Self::with_trusted_certificate`].
+    pub fn with_client_authentication(mut self) -> Result<Self, rustls::Error> {
+        self.client_authentication = true;
+        Ok(self)
+    }
+
+    /// Sets the verifier for the certificate chains presented by clients
+    ///
+    /// The verifier decides whether client authentication is offered or required, which takes
+    /// precedence over [`Self::with_client_authentication`].
+    pub fn with_client_cert_verifier(
+        mut self,
+        verifier: Arc<dyn rustls::server::ClientCertVerifier>,
+    ) -> Result<Self, rustls::Error> {
+        self.client_cert_verifier = Some(verifier);
+        Ok(self)
+    }
+
+    /// Sets the application protocols the server accepts, in order of preference
+    ///
+    /// Clients which don't offer any of these protocols are rejected during the handshake with
+    /// a `no_application_protocol` alert.
     pub fn with_application_protocols<P: Iterator<Item = I>, I: AsRef<[u8]>>(
         mut self,
         protocols: P,
@@ -122,17 +178,61 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sends settings for an application protocol to the client during the handshake
+    ///
+    /// The client only uses the settings if the application protocol is negotiated. Likewise,
+    /// the settings the client sent for the negotiated protocol are available on the connection
+    /// once the handshake is complete. See [`ApplicationSettings`] for how the settings are
+    /// exchanged.
+    pub fn with_application_settings(
+        mut self,
+        application_protocol: &[u8],
+        settings: &[u8],
+    ) -> Result<Self, rustls::Error> {
+        self.application_settings
+            .insert(application_protocol, settings)
+            .map_err(|err| rustls::Error::General(err.to_string()))?;
+        Ok(self)
+    }
+
     pub fn with_key_logging(mut self) -> Result<Self, rustls::Error> {
         self.key_log = Some(Arc::new(rustls::KeyLogFile::new()));
         Ok(self)
     }
 
+    /// Encrypts session tickets with the keys of the given provider
+    ///
+    /// Servers which share the same keys can resume each other's sessions, including after a
+    /// restart. See [`ticket`](crate::ticket) for more details.
+    pub fn with_session_ticket_keys<P: ticket::KeyProvider>(
+        mut self,
+        provider: P,
+    ) -> Result<Self, rustls::Error> {
+        self.ticketer = Some(Arc::new(ticket::Ticketer::new(provider)));
+        Ok(self)
+    }
+
     pub fn build(self) -> Result<Server, rustls::Error> {
         let builder = ServerConfig::builder()
             .with_cipher_suites(crate::cipher_suite::DEFAULT_CIPHERSUITES)
             .with_safe_default_kx_groups()
-            .with_protocol_versions(crate::PROTOCOL_VERSIONS)?
-            .with_no_client_auth();
+            .with_protocol_versions(crate::PROTOCOL_VERSIONS)?;
+
+        let builder = if let Some(client_cert_verifier) = self.client_cert_verifier {
+            builder.with_client_cert_verifier(client_cert_verifier)
+        } else if self.client_authentication {
+            if self.trust_store.is_empty() {
+                return Err(rustls::Error::General(
+                    "missing trusted certificate(s) for client authentication".to_string(),
+                ));
+            }
+
+            builder.with_client_cert_verifier(rustls::server::AllowAnyAuthenticatedClient::new(
+                self.trust_store,
+            ))
+        } else {
+            builder.with_no_client_auth()
+        };
 
         let mut config = if let Some(cert_resolver) = self.cert_resolver {
             builder.with_cert_resolver(cert_resolver)
@@ -150,7 +250,14 @@ impl Builder {
             config.key_log = key_log;
         }
 
-        Ok(Server::new(config))
+        if let Some(ticketer) = self.ticketer {
+            config.ticketer = ticketer;
+        }
+
+        Ok(Server {
+            config: Arc::new(config),
+            application_settings: self.application_settings,
+        })
     }
 }
 
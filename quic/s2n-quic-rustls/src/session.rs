@@ -12,7 +12,11 @@ use rustls::{
 };
 use s2n_quic_core::{
     application::ServerName,
-    crypto::{self, tls, CryptoError},
+    crypto::{
+        self,
+        tls::{self, ApplicationSettings},
+        CryptoError,
+    },
     transport,
 };
 
@@ -115,6 +119,14 @@ impl Session {
         self.connection.alpn_protocol()
     }
 
+    /// Returns the settings the peer sent for the negotiated application protocol
+    fn peer_application_settings(&self) -> Option<Bytes> {
+        let transport_parameters = self.connection.quic_transport_parameters()?;
+        let application_protocol = self.application_protocol()?;
+        ApplicationSettings::peer_settings(transport_parameters, application_protocol)
+            .map(Bytes::copy_from_slice)
+    }
+
     fn server_name(&self) -> Option<ServerName> {
         match &self.connection {
             Connection::Client(_) => self.server_name.clone(),
@@ -148,6 +160,18 @@ impl Session {
 
             // the handshake is complete!
             if !self.emitted_handshake_complete {
+                if let Some(certificates) = self.connection.peer_certificates() {
+                    let certificates = certificates
+                        .iter()
+                        .map(|certificate| Bytes::copy_from_slice(&certificate.0))
+                        .collect();
+                    context.on_peer_certificates(certificates)?;
+                }
+
+                if let Some(settings) = self.peer_application_settings() {
+                    context.on_application_settings(settings)?;
+                }
+
                 self.rx_phase.transition();
                 context.on_handshake_complete()?;
             }
@@ -156,12 +180,32 @@ impl Session {
         }
 
         if self.emitted_handshake_complete {
+            self.send_post_handshake(context);
             Poll::Ready(Ok(()))
         } else {
             Poll::Pending
         }
     }
 
+    /// Sends the messages rustls produces after the handshake, such as session tickets
+    ///
+    /// The transport stops polling the session once the handshake is complete, so these need to
+    /// be flushed before completing.
+    fn send_post_handshake<C: tls::Context<Self>>(&mut self, context: &mut C) {
+        if !context.can_send_application() {
+            return;
+        }
+
+        let mut transmission_buffer = vec![];
+
+        // QUIC doesn't use TLS KeyUpdate messages, so there are no more key changes
+        let _ = self.connection.write_hs(&mut transmission_buffer);
+
+        if !transmission_buffer.is_empty() {
+            context.send_application(transmission_buffer.into());
+        }
+    }
+
     fn poll_impl<C: tls::Context<Self>>(
         &mut self,
         context: &mut C,
@@ -0,0 +1,338 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides the keys which encrypt the session tickets issued by a server
+//!
+//! A client can only resume its session with a server which is able to decrypt the ticket it was
+//! issued. By default, rustls generates random ticket keys when the server starts, so sessions
+//! can't be resumed after the server restarts or with other instances behind a load balancer.
+//!
+//! With a [`Schedule`], all of the servers configured with the same secret derive the same
+//! sequence of keys, rotating to a new key every period. Keys can also be fetched from an
+//! external key management service by implementing [`KeyProvider`].
+//!
+//! ```rust,no_run
+//! # use std::{error::Error, time::Duration};
+//! use s2n_quic_rustls::{ticket::Schedule, Server};
+//!
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! # let (cert, key, secret) = ("cert.pem", "key.pem", [0u8; 32]);
+//! let server = Server::builder()
+//!     .with_certificate(cert, key)?
+//!     .with_session_ticket_keys(Schedule::new(&secret, Duration::from_secs(3600)))?
+//!     .build()?;
+//! #
+//! #    Ok(())
+//! # }
+//! ```
+
+use core::{convert::TryInto, fmt, time::Duration};
+use s2n_quic_crypto::ring::{
+    aead::{self, Aad, LessSafeKey, Nonce, UnboundKey},
+    hkdf,
+    rand::{SecureRandom, SystemRandom},
+};
+use std::time::SystemTime;
+
+/// The length of the name which identifies the key of a ticket
+pub const NAME_LEN: usize = 16;
+
+/// The length of the secret of a key
+pub const KEY_LEN: usize = 32;
+
+const NONCE_LEN: usize = aead::NONCE_LEN;
+const TAG_LEN: usize = 16;
+const HEADER_LEN: usize = NAME_LEN + NONCE_LEN;
+
+/// A key which encrypts and decrypts session tickets
+///
+/// Tickets are encrypted with AES-256-GCM and carry the name of their key, so servers can find
+/// the key to decrypt a ticket with.
+pub struct Key {
+    name: [u8; NAME_LEN],
+    key: LessSafeKey,
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Key").field("name", &self.name).finish()
+    }
+}
+
+impl Key {
+    /// Creates a key with the given name and secret
+    ///
+    /// The name is sent in the clear with each ticket, so it must not be derived from the secret.
+    pub fn new(name: [u8; NAME_LEN], secret: &[u8; KEY_LEN]) -> Self {
+        let key = UnboundKey::new(&aead::AES_256_GCM, secret)
+            .expect("the secret length matches the algorithm");
+        Self {
+            name,
+            key: LessSafeKey::new(key),
+        }
+    }
+
+    /// Returns the name of the key
+    pub fn name(&self) -> &[u8; NAME_LEN] {
+        &self.name
+    }
+
+    fn encrypt(&self, rng: &SystemRandom, plain: &[u8]) -> Option<Vec<u8>> {
+        let mut nonce = [0; NONCE_LEN];
+        rng.fill(&mut nonce).ok()?;
+
+        let mut ticket = Vec::with_capacity(HEADER_LEN + plain.len() + TAG_LEN);
+        ticket.extend_from_slice(&self.name);
+        ticket.extend_from_slice(&nonce);
+        ticket.extend_from_slice(plain);
+
+        let tag = self
+            .key
+            .seal_in_place_separate_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(&self.name),
+                &mut ticket[HEADER_LEN..],
+            )
+            .ok()?;
+        ticket.extend_from_slice(tag.as_ref());
+
+        Some(ticket)
+    }
+
+    fn decrypt(&self, ticket: &[u8]) -> Option<Vec<u8>> {
+        let nonce = ticket.get(NAME_LEN..HEADER_LEN)?.try_into().ok()?;
+        let mut payload = ticket.get(HEADER_LEN..)?.to_vec();
+
+        let len = self
+            .key
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(&self.name),
+                &mut payload,
+            )
+            .ok()?
+            .len();
+        payload.truncate(len);
+
+        Some(payload)
+    }
+}
+
+/// Provides the keys for encrypting and decrypting session tickets
+///
+/// Implementations are queried for every ticket which is issued or presented by a client, so
+/// keys fetched from an external service should be cached.
+pub trait KeyProvider: 'static + Send + Sync {
+    /// Returns the key which encrypts new tickets
+    ///
+    /// If `None` is returned, the client is sent an empty ticket which can't be used to resume
+    /// the session.
+    fn encryption_key(&self) -> Option<Key>;
+
+    /// Returns the key with the given name, if tickets encrypted with it are still accepted
+    fn decryption_key(&self, name: &[u8; NAME_LEN]) -> Option<Key>;
+
+    /// Returns the number of seconds for which new tickets can be used
+    ///
+    /// This is a hint for clients. Tickets should be expired by no longer returning their
+    /// key from [`KeyProvider::decryption_key`].
+    fn lifetime(&self) -> u32;
+}
+
+/// Derives a new ticket key from a shared secret every period
+///
+/// The keys only depend on the secret and the wall clock time, so servers with the same secret
+/// can resume each other's sessions, as long as their clocks are roughly synchronized. Tickets
+/// are accepted until the end of the period after the one they were issued in.
+pub struct Schedule {
+    secret: hkdf::Prk,
+    period: u64,
+}
+
+impl fmt::Debug for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Schedule")
+            .field("period", &Duration::from_secs(self.period))
+            .finish()
+    }
+}
+
+impl Schedule {
+    /// Creates a schedule which rotates the keys derived from `secret` every `period`
+    ///
+    /// The period is rounded down to whole seconds, with a minimum of one second.
+    pub fn new(secret: &[u8], period: Duration) -> Self {
+        let secret =
+            hkdf::Salt::new(hkdf::HKDF_SHA256, b"s2n-quic session ticket keys").extract(secret);
+        Self {
+            secret,
+            period: period.as_secs().max(1),
+        }
+    }
+
+    fn epoch(&self, now: SystemTime) -> u64 {
+        let now = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        now.as_secs() / self.period
+    }
+
+    fn key(&self, epoch: u64) -> Key {
+        let epoch = epoch.to_be_bytes();
+
+        // the epoch is included in the name so the key can be derived when decrypting
+        let mut name = [0; NAME_LEN];
+        name[..8].copy_from_slice(&epoch);
+        self.expand(b"name", &epoch, &mut name[8..]);
+
+        let mut secret = [0; KEY_LEN];
+        self.expand(b"key", &epoch, &mut secret);
+
+        Key::new(name, &secret)
+    }
+
+    fn expand(&self, label: &[u8], epoch: &[u8], out: &mut [u8]) {
+        struct Len(usize);
+
+        impl hkdf::KeyType for Len {
+            fn len(&self) -> usize {
+                self.0
+            }
+        }
+
+        self.secret
+            .expand(&[label, epoch], Len(out.len()))
+            .and_then(|okm| okm.fill(out))
+            .expect("the output is shorter than the HKDF limit");
+    }
+
+    fn decryption_key_at(&self, name: &[u8; NAME_LEN], now: SystemTime) -> Option<Key> {
+        let epoch = u64::from_be_bytes(name[..8].try_into().ok()?);
+        let current = self.epoch(now);
+
+        // accept keys of the next period in case the clock of the issuing server is ahead
+        if epoch.saturating_add(1) < current || epoch > current.saturating_add(1) {
+            return None;
+        }
+
+        let key = self.key(epoch);
+        if key.name() != name {
+            return None;
+        }
+
+        Some(key)
+    }
+}
+
+impl KeyProvider for Schedule {
+    fn encryption_key(&self) -> Option<Key> {
+        Some(self.key(self.epoch(SystemTime::now())))
+    }
+
+    fn decryption_key(&self, name: &[u8; NAME_LEN]) -> Option<Key> {
+        self.decryption_key_at(name, SystemTime::now())
+    }
+
+    fn lifetime(&self) -> u32 {
+        self.period.try_into().unwrap_or(u32::MAX)
+    }
+}
+
+/// Encrypts the session tickets of a rustls server with the keys of a [`KeyProvider`]
+pub(crate) struct Ticketer<P> {
+    provider: P,
+    rng: SystemRandom,
+}
+
+impl<P> Ticketer<P> {
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider,
+            rng: SystemRandom::new(),
+        }
+    }
+}
+
+impl<P: KeyProvider> rustls::server::ProducesTickets for Ticketer<P> {
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    fn lifetime(&self) -> u32 {
+        self.provider.lifetime()
+    }
+
+    fn encrypt(&self, plain: &[u8]) -> Option<Vec<u8>> {
+        self.provider.encryption_key()?.encrypt(&self.rng, plain)
+    }
+
+    fn decrypt(&self, cipher: &[u8]) -> Option<Vec<u8>> {
+        if cipher.len() < HEADER_LEN + TAG_LEN {
+            return None;
+        }
+
+        let name = cipher[..NAME_LEN].try_into().ok()?;
+        self.provider.decryption_key(name)?.decrypt(cipher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustls::server::ProducesTickets;
+
+    #[test]
+    fn round_trip_test() {
+        let issuer = Ticketer::new(Schedule::new(b"secret", Duration::from_secs(60)));
+        // a restarted server or another instance with the same secret
+        let resumer = Ticketer::new(Schedule::new(b"secret", Duration::from_secs(60)));
+        let other = Ticketer::new(Schedule::new(b"other", Duration::from_secs(60)));
+
+        let ticket = issuer.encrypt(b"session state").unwrap();
+        assert_eq!(resumer.decrypt(&ticket).unwrap(), b"session state");
+        assert!(other.decrypt(&ticket).is_none());
+
+        // tampered tickets are rejected
+        for index in 0..ticket.len() {
+            let mut tampered = ticket.clone();
+            tampered[index] ^= 1;
+            assert!(resumer.decrypt(&tampered).is_none());
+        }
+
+        assert!(resumer
+            .decrypt(&ticket[..HEADER_LEN + TAG_LEN - 1])
+            .is_none());
+        assert_eq!(issuer.lifetime(), 60);
+    }
+
+    #[test]
+    fn rotation_test() {
+        let schedule = Schedule::new(b"secret", Duration::from_secs(60));
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(6000);
+        let key = schedule.key(schedule.epoch(start));
+        let name = key.name();
+
+        let accepted = |secs: u64| {
+            schedule
+                .decryption_key_at(name, start + Duration::from_secs(secs))
+                .is_some()
+        };
+
+        // the key is accepted for the period it was issued in and the following period
+        assert!(accepted(0));
+        assert!(accepted(119));
+        assert!(!accepted(120));
+
+        // keys from servers with clocks which are ahead are also accepted
+        assert!(schedule
+            .decryption_key_at(name, start - Duration::from_secs(1))
+            .is_some());
+        assert!(schedule
+            .decryption_key_at(name, start - Duration::from_secs(61))
+            .is_none());
+
+        // each period has a new key
+        let next = schedule.key(schedule.epoch(start) + 1);
+        assert_ne!(next.name(), name);
+    }
+}
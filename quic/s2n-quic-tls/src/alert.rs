@@ -0,0 +1,104 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Maps handshake failures to the TLS alert which is sent to the peer
+//!
+//! s2n-tls errors are mapped to the alert corresponding to the failure, falling back to
+//! `HANDSHAKE_FAILURE` when the error doesn't indicate a more precise alert. Application
+//! callbacks, like a [`VerifyHostNameCallback`](s2n_tls::callbacks::VerifyHostNameCallback),
+//! can override the alert by calling [`set`] before rejecting the peer.
+
+use core::cell::Cell;
+use s2n_quic_core::crypto::CryptoError;
+use s2n_tls::error::{Error, ErrorType};
+
+std::thread_local! {
+    // Callbacks are invoked on the thread driving the handshake, while the session is polled
+    static ALERT: Cell<Option<CryptoError>> = Cell::new(None);
+}
+
+/// Sets the alert which is sent to the peer if the current callback fails the handshake
+///
+/// This may only be called from a callback invoked by s2n-tls. The alert is discarded if the
+/// handshake doesn't fail before s2n-tls returns control to the session.
+pub fn set(alert: CryptoError) {
+    ALERT.with(|cell| cell.set(Some(alert)));
+}
+
+/// Takes the alert which was set by an application callback
+pub(crate) fn take() -> Option<CryptoError> {
+    ALERT.with(|cell| cell.take())
+}
+
+/// Returns the alert for a handshake which failed with the given error
+pub(crate) fn from_error(error: &Error) -> CryptoError {
+    if let Some(alert) = error.alert() {
+        return CryptoError::new(alert);
+    }
+
+    if let Some(alert) = from_name(error.name()) {
+        return alert;
+    }
+
+    if error.kind() == Some(ErrorType::InternalError) {
+        return CryptoError::INTERNAL_ERROR;
+    }
+
+    CryptoError::HANDSHAKE_FAILURE
+}
+
+fn from_name(name: &str) -> Option<CryptoError> {
+    let alert = match name {
+        "S2N_ERR_BAD_MESSAGE" => CryptoError::UNEXPECTED_MESSAGE,
+        "S2N_ERR_CERT_UNTRUSTED" | "S2N_ERR_DECODE_CERTIFICATE" => CryptoError::BAD_CERTIFICATE,
+        "S2N_ERR_CERT_TYPE_UNSUPPORTED" => CryptoError::UNSUPPORTED_CERTIFICATE,
+        //= https://www.rfc-editor.org/rfc/rfc8446#section-4.4.3
+        //# If the verification fails, the receiver MUST terminate the handshake
+        //# with a "decrypt_error" alert.
+        "S2N_ERR_VERIFY_SIGNATURE" => CryptoError::DECRYPT_ERROR,
+        //= https://www.rfc-editor.org/rfc/rfc8446#section-4.1.3
+        //# If a match is found, the client MUST abort the
+        //# handshake with an "illegal_parameter" alert.
+        "S2N_ERR_PROTOCOL_DOWNGRADE_DETECTED" => CryptoError::ILLEGAL_PARAMETER,
+        "S2N_ERR_BAD_KEY_SHARE"
+        | "S2N_ERR_DUPLICATE_EXTENSION"
+        | "S2N_ERR_INVALID_HELLO_RETRY"
+        | "S2N_ERR_INVALID_SIGNATURE_ALGORITHM"
+        | "S2N_ERR_INVALID_SIGNATURE_SCHEME" => CryptoError::ILLEGAL_PARAMETER,
+        "S2N_ERR_PROTOCOL_VERSION_UNSUPPORTED" => CryptoError::PROTOCOL_VERSION,
+        "S2N_ERR_FALLBACK_DETECTED" => CryptoError::INAPPROPRIATE_FALLBACK,
+        "S2N_ERR_MISSING_EXTENSION" => CryptoError::MISSING_EXTENSION,
+        "S2N_ERR_UNSUPPORTED_EXTENSION" => CryptoError::UNSUPPORTED_EXTENSION,
+        "S2N_ERR_NO_APPLICATION_PROTOCOL" => CryptoError::NO_APPLICATION_PROTOCOL,
+        _ => return None,
+    };
+
+    Some(alert)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_name_test() {
+        assert_eq!(
+            from_name("S2N_ERR_CERT_UNTRUSTED"),
+            Some(CryptoError::BAD_CERTIFICATE)
+        );
+        assert_eq!(
+            from_name("S2N_ERR_NO_APPLICATION_PROTOCOL"),
+            Some(CryptoError::NO_APPLICATION_PROTOCOL)
+        );
+        assert_eq!(from_name("S2N_ERR_CANCELLED"), None);
+    }
+
+    #[test]
+    fn set_take_test() {
+        assert_eq!(take(), None);
+        set(CryptoError::ACCESS_DENIED);
+        assert_eq!(take(), Some(CryptoError::ACCESS_DENIED));
+        // the alert is only taken once
+        assert_eq!(take(), None);
+    }
+}
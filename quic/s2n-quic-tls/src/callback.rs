@@ -1,11 +1,15 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::handshake;
 use bytes::{Bytes, BytesMut};
 use core::{ffi::c_void, marker::PhantomData};
 use s2n_quic_core::{
     application::ServerName,
-    crypto::{tls, CryptoError, CryptoSuite},
+    crypto::{
+        tls::{self, ApplicationSettings},
+        CryptoError, CryptoSuite,
+    },
     endpoint, transport,
 };
 use s2n_quic_crypto::{
@@ -32,6 +36,7 @@ pub struct Callback<'a, T, C> {
     pub send_buffer: &'a mut BytesMut,
     pub emitted_server_name: &'a mut bool,
     pub server_name: &'a Option<ServerName>,
+    pub handshake_callback: Option<&'a dyn handshake::Callback>,
 }
 
 impl<'a, T, C> Callback<'a, T, C>
@@ -154,6 +159,30 @@ where
         }
     }
 
+    /// Reports the peer's certificates and applies the application's handshake policy
+    ///
+    /// # Safety
+    ///
+    /// `conn` must point to the live s2n-tls connection of the session
+    pub unsafe fn on_peer_certificates(
+        &mut self,
+        conn: *mut s2n_connection,
+    ) -> Result<(), transport::Error> {
+        if self.state.emitted_peer_certificates {
+            return Ok(());
+        }
+        self.state.emitted_peer_certificates = true;
+
+        let certificates =
+            handshake::on_peer_certificates(conn, self.endpoint, self.handshake_callback)?;
+
+        if !certificates.is_empty() {
+            self.context.on_peer_certificates(certificates)?;
+        }
+
+        Ok(())
+    }
+
     /// Handles secrets from the s2n-tls connection
     fn on_secret(
         &mut self,
@@ -161,6 +190,10 @@ where
         id: s2n_secret_type_t::Type,
         secret: &mut [u8],
     ) -> Result<(), transport::Error> {
+        if self.state.connection.is_none() {
+            self.state.connection = handshake::RawConnection::new(conn);
+        }
+
         match core::mem::replace(&mut self.state.secrets, Secrets::Waiting) {
             Secrets::Waiting => {
                 if id == s2n_secret_type_t::CLIENT_EARLY_TRAFFIC_SECRET {
@@ -216,6 +249,16 @@ where
 
                 match self.state.tx_phase {
                     HandshakePhase::Initial => {
+                        // The server selects the application protocol while processing the
+                        // ClientHello, so clients which don't support any of the configured
+                        // protocols are rejected before the handshake continues.
+                        if self.endpoint.is_server() {
+                            unsafe {
+                                // Safety: conn needs to outlive the application protocol
+                                get_application_protocol(conn)?;
+                            }
+                        }
+
                         let (key, header_key) = HandshakeKey::new(self.endpoint, aead_algo, pair)
                             .expect("invalid cipher");
 
@@ -224,6 +267,16 @@ where
                         self.state.rx_phase.transition();
                     }
                     _ => {
+                        // At this point clients have verified the server's certificates but
+                        // haven't sent their Finished message, so the handshake can still be
+                        // aborted cleanly.
+                        if self.endpoint.is_client() {
+                            unsafe {
+                                // Safety: conn is the connection which invoked the callback
+                                self.on_peer_certificates(conn)?;
+                            }
+                        }
+
                         let (key, header_key) =
                             OneRttKey::new(self.endpoint, aead_algo, pair).expect("invalid cipher");
 
@@ -235,10 +288,22 @@ where
                             //
                             // Move this event to where `on_server_name` is emitted once we expose
                             // the functionality in s2n_tls bindings
-                            let application_protocol =
-                                Bytes::copy_from_slice(get_application_protocol(conn)?);
-                            self.context.on_application_protocol(application_protocol)?;
-                            get_application_params(conn)?
+                            let application_protocol = get_application_protocol(conn)?;
+                            self.context
+                                .on_application_protocol(Bytes::copy_from_slice(
+                                    application_protocol,
+                                ))?;
+                            let params = get_application_params(conn)?;
+
+                            if let Some(settings) = ApplicationSettings::peer_settings(
+                                params.transport_parameters,
+                                application_protocol,
+                            ) {
+                                self.context
+                                    .on_application_settings(Bytes::copy_from_slice(settings))?;
+                            }
+
+                            params
                         };
 
                         self.context.on_one_rtt_keys(key, header_key, params)?;
@@ -347,6 +412,9 @@ pub struct State {
     rx_phase: HandshakePhase,
     tx_phase: HandshakePhase,
     secrets: Secrets,
+    /// The s2n-tls connection, which is recorded the first time it's passed to a callback
+    pub connection: Option<handshake::RawConnection>,
+    emitted_peer_certificates: bool,
 }
 
 impl State {
@@ -447,16 +515,6 @@ fn get_algo_type(
 unsafe fn get_application_params<'a>(
     connection: *mut s2n_connection,
 ) -> Result<tls::ApplicationParameters<'a>, CryptoError> {
-    //= https://www.rfc-editor.org/rfc/rfc9001#section-8.1
-    //# When using ALPN, endpoints MUST immediately close a connection (see
-    //# Section 10.2 of [QUIC-TRANSPORT]) with a no_application_protocol TLS
-    //# alert (QUIC error code 0x178; see Section 4.8) if an application
-    //# protocol is not negotiated.
-
-    //= https://www.rfc-editor.org/rfc/rfc9001#section-8.1
-    //# While [ALPN] only specifies that servers
-    //# use this alert, QUIC clients MUST use error 0x178 to terminate a
-    //# connection when ALPN negotiation fails.
     let transport_parameters =
         get_transport_parameters(connection).ok_or(CryptoError::MISSING_EXTENSION)?;
 
@@ -485,8 +543,19 @@ unsafe fn get_application_protocol<'a>(
     connection: *mut s2n_connection,
 ) -> Result<&'a [u8], CryptoError> {
     let ptr = s2n_get_application_protocol(connection).into_result().ok();
+
+    //= https://www.rfc-editor.org/rfc/rfc9001#section-8.1
+    //# When using ALPN, endpoints MUST immediately close a connection (see
+    //# Section 10.2 of [QUIC-TRANSPORT]) with a no_application_protocol TLS
+    //# alert (QUIC error code 0x178; see Section 4.8) if an application
+    //# protocol is not negotiated.
+
+    //= https://www.rfc-editor.org/rfc/rfc9001#section-8.1
+    //# While [ALPN] only specifies that servers
+    //# use this alert, QUIC clients MUST use error 0x178 to terminate a
+    //# connection when ALPN negotiation fails.
     ptr.and_then(|ptr| get_cstr_slice(ptr))
-        .ok_or(CryptoError::MISSING_EXTENSION)
+        .ok_or(CryptoError::NO_APPLICATION_PROTOCOL.with_reason("Missing ALPN protocol"))
 }
 
 unsafe fn get_transport_parameters<'a>(connection: *mut s2n_connection) -> Option<&'a [u8]> {
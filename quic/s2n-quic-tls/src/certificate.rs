@@ -21,6 +21,45 @@ impl Format {
             None
         }
     }
+
+    /// Returns the value in PEM format, encoding DER values with the given label
+    ///
+    /// s2n-tls only loads PEM values, so DER values need to be converted first.
+    pub fn to_pem(&self, label: &str) -> Bytes {
+        match self {
+            Format::Pem(bytes) => bytes.clone(),
+            Format::Der(bytes) => der_to_pem(label, bytes),
+        }
+    }
+}
+
+/// Encodes a DER value as a PEM block with the given label
+fn der_to_pem(label: &str, der: &[u8]) -> Bytes {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut pem = format!("-----BEGIN {}-----\n", label).into_bytes();
+
+    // each line holds 64 characters, which encode 48 bytes
+    for line in der.chunks(48) {
+        for chunk in line.chunks(3) {
+            let mut block = [0; 3];
+            block[..chunk.len()].copy_from_slice(chunk);
+            let value = u32::from_be_bytes([0, block[0], block[1], block[2]]);
+
+            for index in 0..4 {
+                if index <= chunk.len() {
+                    let sextet = (value >> (18 - 6 * index)) & 0x3f;
+                    pem.push(ALPHABET[sextet as usize]);
+                } else {
+                    pem.push(b'=');
+                }
+            }
+        }
+        pem.push(b'\n');
+    }
+
+    pem.extend_from_slice(format!("-----END {}-----\n", label).as_bytes());
+    Bytes::from(pem)
 }
 
 pub(crate) enum Format {
@@ -105,3 +144,37 @@ macro_rules! cert_type {
 
 cert_type!(PrivateKey, IntoPrivateKey, into_private_key);
 cert_type!(Certificate, IntoCertificate, into_certificate);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn der_to_pem_test() {
+        // the test vectors of RFC 4648, section 10
+        for (der, base64) in [
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            let pem = Format::Der(Bytes::from_static(der.as_bytes())).to_pem("TEST");
+            let expected = format!("-----BEGIN TEST-----\n{}\n-----END TEST-----\n", base64);
+            assert_eq!(pem, expected.as_bytes());
+        }
+
+        // lines are wrapped after 64 characters
+        let pem = der_to_pem("TEST", &[0; 100]);
+        let lines: Vec<_> = pem
+            .split(|byte| *byte == b'\n')
+            .map(|line| line.len())
+            .collect();
+        assert_eq!(lines, [20, 64, 64, 8, 18, 0]);
+
+        // PEM values are passed through
+        let pem = Format::Pem(Bytes::from_static(b"pem")).to_pem("TEST");
+        assert_eq!(pem, &b"pem"[..]);
+    }
+}
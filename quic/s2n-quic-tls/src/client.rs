@@ -3,25 +3,45 @@
 
 use crate::{
     certificate::{IntoCertificate, IntoPrivateKey},
+    handshake,
     keylog::KeyLogHandle,
     params::Params,
     session::Session,
 };
 use s2n_codec::EncoderValue;
-use s2n_quic_core::{application::ServerName, crypto::tls, endpoint};
+use s2n_quic_core::{
+    application::ServerName,
+    crypto::{
+        tls::{self, ApplicationSettings},
+        CryptoError,
+    },
+    endpoint,
+};
+use s2n_quic_crypto::{sct, spki};
 use s2n_tls::{
     callbacks::VerifyHostNameCallback,
     config::{self, Config},
     enums::ClientAuthType,
     error::Error,
 };
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc, time::SystemTime};
+
+/// The locations of the CA bundles of common operating systems
+const SYSTEM_TRUST_STORES: &[&str] = &[
+    "/etc/ssl/certs/ca-certificates.crt",
+    "/etc/pki/tls/certs/ca-bundle.crt",
+    "/etc/pki/ca-trust/extracted/pem/tls-ca-bundle.pem",
+    "/etc/ssl/ca-bundle.pem",
+    "/etc/pki/tls/cacert.pem",
+    "/etc/ssl/cert.pem",
+];
 
 pub struct Client {
     config: Config,
     #[allow(dead_code)] // we need to hold on to the handle to ensure it is cleaned up correctly
     keylog: Option<KeyLogHandle>,
     params: Params,
+    handshake_callback: Option<Arc<dyn handshake::Callback>>,
 }
 
 impl Client {
@@ -41,6 +61,10 @@ impl Default for Client {
 pub struct Builder {
     config: config::Builder,
     keylog: Option<KeyLogHandle>,
+    handshake_callback: Option<Arc<dyn handshake::Callback>>,
+    spki_pins: Vec<spki::Digest>,
+    sct_policy: Option<sct::Policy>,
+    application_settings: ApplicationSettings,
 }
 
 impl Default for Builder {
@@ -56,6 +80,10 @@ impl Default for Builder {
         Self {
             config,
             keylog: None,
+            handshake_callback: None,
+            spki_pins: Vec::new(),
+            sct_policy: None,
+            application_settings: ApplicationSettings::new(),
         }
     }
 }
@@ -69,13 +97,73 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sends settings for an application protocol to the server during the handshake
+    ///
+    /// The server only uses the settings if the application protocol is negotiated. Likewise,
+    /// the settings the server sent for the negotiated protocol are available on the connection
+    /// once the handshake is complete. See [`ApplicationSettings`] for how the settings are
+    /// exchanged.
+    pub fn with_application_settings(
+        mut self,
+        application_protocol: &[u8],
+        settings: &[u8],
+    ) -> Result<Self, Error> {
+        self.application_settings
+            .insert(application_protocol, settings)
+            .map_err(|_| Error::InvalidInput)?;
+        Ok(self)
+    }
+
+    /// Adds the certificates to the trust store
+    ///
+    /// PEM-encoded bundles can contain any number of certificates, which are all trusted.
     pub fn with_certificate<C: IntoCertificate>(mut self, certificate: C) -> Result<Self, Error> {
         let certificate = certificate.into_certificate()?;
-        let certificate = certificate
-            .0
-            .as_pem()
-            .expect("pem is currently the only certificate format supported");
-        self.config.trust_pem(certificate)?;
+        let certificate = certificate.0.to_pem("CERTIFICATE");
+        self.config.trust_pem(&certificate)?;
+        Ok(self)
+    }
+
+    /// Adds the certificates of the platform trust store to the trust store
+    ///
+    /// The trust store is initialized with the platform trust store by default, so this is only
+    /// needed after calling [`Self::with_empty_trust_store`]. The CA bundle is read from the
+    /// `SSL_CERT_FILE` environment variable, if it is set, or from the common locations of the
+    /// host operating system.
+    pub fn with_system_trust_store(mut self) -> Result<Self, Error> {
+        let path = std::env::var_os("SSL_CERT_FILE")
+            .map(PathBuf::from)
+            .into_iter()
+            .chain(SYSTEM_TRUST_STORES.iter().map(PathBuf::from))
+            .find(|path| path.is_file())
+            .ok_or(Error::InvalidInput)?;
+        let bundle = std::fs::read(path).map_err(|_| Error::InvalidInput)?;
+        self.config.trust_pem(&bundle)?;
+        Ok(self)
+    }
+
+    /// Pins the public keys the server may present
+    ///
+    /// Each pin is the SHA-256 digest of a DER-encoded SubjectPublicKeyInfo, as returned by
+    /// [`spki::digest`]. The certificate chain of the server is still verified as usual, but is
+    /// additionally required to contain a certificate with one of the pinned keys. Servers which
+    /// don't present a pinned key are rejected with a `bad_certificate` alert.
+    pub fn with_spki_pins<P: IntoIterator<Item = spki::Digest>>(
+        mut self,
+        pins: P,
+    ) -> Result<Self, Error> {
+        self.spki_pins.extend(pins);
+        Ok(self)
+    }
+
+    /// Requires the certificate of the server to embed SCTs which comply with the policy
+    ///
+    /// The SCTs are verified after the certificate chain of the server. Servers which don't
+    /// comply with the policy are rejected with a `bad_certificate` alert. Otherwise, the
+    /// [`sct::Verification`] is passed to the handshake callback in
+    /// [`handshake::Info::signed_certificate_timestamps`].
+    pub fn with_sct_policy(mut self, policy: sct::Policy) -> Result<Self, Error> {
+        self.sct_policy = Some(policy);
         Ok(self)
     }
 
@@ -125,6 +213,9 @@ impl Builder {
     /// handshake. If this function is invoked, the default server name validation
     /// logic is disabled; this should only be used in very specific cases where normal
     /// TLS hostname validation is not appropriate.
+    ///
+    /// Rejected certificates are reported to the peer with a `bad_certificate` alert, unless
+    /// the callback specifies another alert with [`crate::alert::set`].
     pub fn with_verify_host_name_callback<T: 'static + VerifyHostNameCallback>(
         mut self,
         handler: T,
@@ -138,6 +229,18 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sets the callback which is invoked once the server's certificates have been verified
+    ///
+    /// The callback can inspect the certificates and the negotiated parameters, and abort the
+    /// handshake with an alert of its choice.
+    pub fn with_handshake_callback<T: handshake::Callback>(
+        mut self,
+        callback: T,
+    ) -> Result<Self, Error> {
+        self.handshake_callback = Some(Arc::new(callback));
+        Ok(self)
+    }
+
     pub fn with_key_logging(mut self) -> Result<Self, Error> {
         use crate::keylog::KeyLog;
 
@@ -159,14 +262,76 @@ impl Builder {
     }
 
     pub fn build(self) -> Result<Client, Error> {
+        let handshake_callback = if self.spki_pins.is_empty() && self.sct_policy.is_none() {
+            self.handshake_callback
+        } else {
+            Some(Arc::new(PolicyCallback {
+                spki_pins: self.spki_pins,
+                sct_policy: self.sct_policy,
+                callback: self.handshake_callback,
+            }) as Arc<dyn handshake::Callback>)
+        };
+
         Ok(Client {
             config: self.config.build()?,
             keylog: self.keylog,
-            params: Default::default(),
+            params: Params::new(self.application_settings),
+            handshake_callback,
         })
     }
 }
 
+/// Applies the policies of the client to the verified certificate chain of the server
+///
+/// The policies are checked before the handshake callback of the application is invoked.
+struct PolicyCallback {
+    spki_pins: Vec<spki::Digest>,
+    sct_policy: Option<sct::Policy>,
+    callback: Option<Arc<dyn handshake::Callback>>,
+}
+
+impl handshake::Callback for PolicyCallback {
+    fn on_peer_certificates(&self, info: &handshake::Info) -> Result<(), CryptoError> {
+        let certificates = info
+            .peer_certificates
+            .iter()
+            .map(|certificate| certificate.as_ref());
+
+        if !self.spki_pins.is_empty() && !spki::is_pinned(&self.spki_pins, certificates) {
+            return Err(CryptoError::BAD_CERTIFICATE
+                .with_reason("none of the presented public keys are pinned"));
+        }
+
+        let verification = if let Some(policy) = self.sct_policy.as_ref() {
+            let end_entity = info
+                .peer_certificates
+                .first()
+                .ok_or(CryptoError::BAD_CERTIFICATE)?;
+            let issuer = info.peer_certificates.get(1).map(|issuer| issuer.as_ref());
+            let verification = policy.verify(end_entity, issuer, SystemTime::now());
+
+            if !verification.is_compliant() {
+                return Err(CryptoError::BAD_CERTIFICATE
+                    .with_reason("the certificate transparency policy isn't met"));
+            }
+
+            Some(verification)
+        } else {
+            None
+        };
+
+        if let Some(callback) = self.callback.as_ref() {
+            let info = handshake::Info {
+                signed_certificate_timestamps: verification.as_ref(),
+                ..info.clone()
+            };
+            callback.on_peer_certificates(&info)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl tls::Endpoint for Client {
     type Session = Session;
 
@@ -180,8 +345,16 @@ impl tls::Endpoint for Client {
         server_name: ServerName,
     ) -> Self::Session {
         let config = self.config.clone();
+        let handshake_callback = self.handshake_callback.clone();
         self.params.with(params, |params| {
-            Session::new(endpoint::Type::Client, config, params, Some(server_name)).unwrap()
+            Session::new(
+                endpoint::Type::Client,
+                config,
+                params,
+                Some(server_name),
+                handshake_callback,
+            )
+            .unwrap()
         })
     }
 
@@ -0,0 +1,220 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Inspects the parameters negotiated with the peer while the handshake is in progress
+//!
+//! A [`Callback`] is invoked once the peer's certificates have been verified, but before the
+//! handshake completes. This allows applications to enforce policies which go beyond certificate
+//! validation, e.g. a minimum key size or a set of acceptable groups, and to abort the handshake
+//! with an alert if the peer doesn't comply.
+
+use bytes::Bytes;
+use core::ptr::NonNull;
+use s2n_quic_core::{crypto::CryptoError, endpoint};
+use s2n_quic_crypto::sct;
+use s2n_tls::{error::Fallible, ffi::*};
+use std::ffi::CStr;
+
+/// Applies an application policy to the parameters negotiated with the peer
+pub trait Callback: 'static + Send + Sync {
+    /// Called once the certificates presented by the peer have been verified
+    ///
+    /// Clients invoke the callback after processing the server's handshake messages, before
+    /// sending their `Finished` message. Servers invoke the callback after processing the
+    /// client's `Finished` message, before the handshake is confirmed.
+    ///
+    /// Returning an error aborts the handshake and the error is sent to the peer as an alert.
+    fn on_peer_certificates(&self, info: &Info) -> Result<(), CryptoError>;
+}
+
+/// The parameters negotiated with the peer
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Info<'a> {
+    /// The DER-encoded certificate chain presented by the peer, starting with the end-entity
+    /// certificate
+    ///
+    /// This is empty if the peer didn't present any certificates.
+    pub peer_certificates: &'a [Bytes],
+    /// The group used for the key exchange, e.g. `x25519` or `secp256r1`
+    pub group: Option<&'static str>,
+    /// The signature scheme the peer used to prove possession of its certificate, e.g.
+    /// `ecdsa_secp256r1_sha256`
+    pub signature_scheme: Option<&'static str>,
+    /// The negotiated cipher suite, e.g. `TLS_AES_128_GCM_SHA256`
+    pub cipher_suite: Option<&'static str>,
+    /// The results of verifying the SCTs embedded in the certificate of the server
+    ///
+    /// This is only set on clients which are configured with an SCT policy.
+    pub signed_certificate_timestamps: Option<&'a sct::Verification>,
+}
+
+/// The s2n-tls connection which was passed to the session callbacks
+///
+/// s2n-tls only exposes the underlying connection to callbacks, so it is recorded in order to
+/// inspect the handshake after the callbacks return.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RawConnection(NonNull<s2n_connection>);
+
+/// # Safety
+///
+/// The pointer is owned by the session's `Connection`, which can be sent across threads
+unsafe impl Send for RawConnection {}
+
+impl RawConnection {
+    pub fn new(connection: *mut s2n_connection) -> Option<Self> {
+        NonNull::new(connection).map(Self)
+    }
+
+    pub fn as_ptr(self) -> *mut s2n_connection {
+        self.0.as_ptr()
+    }
+}
+
+/// Reads the peer's certificate chain and invokes the application's callback
+///
+/// # Safety
+///
+/// `connection` must point to a live s2n-tls connection
+pub(crate) unsafe fn on_peer_certificates(
+    connection: *mut s2n_connection,
+    endpoint: endpoint::Type,
+    callback: Option<&dyn Callback>,
+) -> Result<Vec<Bytes>, CryptoError> {
+    let peer_certificates = get_peer_certificates(connection).unwrap_or_default();
+
+    if let Some(callback) = callback {
+        let group = get_str(s2n_connection_get_kem_group_name(connection))
+            .or_else(|| get_str(s2n_connection_get_curve(connection)));
+
+        let info = Info {
+            peer_certificates: &peer_certificates,
+            group,
+            signature_scheme: get_signature_scheme(connection, endpoint),
+            cipher_suite: get_str(s2n_connection_get_cipher(connection)),
+            signed_certificate_timestamps: None,
+        };
+
+        callback.on_peer_certificates(&info)?;
+    }
+
+    Ok(peer_certificates)
+}
+
+/// Returns the validated certificate chain of the peer
+///
+/// s2n-tls returns an error if the peer didn't present any certificates.
+unsafe fn get_peer_certificates(connection: *mut s2n_connection) -> Option<Vec<Bytes>> {
+    struct Chain(NonNull<s2n_cert_chain_and_key>);
+
+    impl Drop for Chain {
+        fn drop(&mut self) {
+            unsafe {
+                let _ = s2n_cert_chain_and_key_free(self.0.as_ptr());
+            }
+        }
+    }
+
+    // the chain is freed when `owner` goes out of scope
+    let owner = Chain(s2n_cert_chain_and_key_new().into_result().ok()?);
+    let chain = owner.0.as_ptr();
+
+    s2n_connection_get_peer_cert_chain(connection, chain)
+        .into_result()
+        .ok()?;
+
+    let mut len = 0;
+    s2n_cert_chain_get_length(chain, &mut len)
+        .into_result()
+        .ok()?;
+
+    let mut certificates = Vec::with_capacity(len as usize);
+
+    for index in 0..len {
+        let mut cert = core::ptr::null_mut();
+        s2n_cert_chain_get_cert(chain, &mut cert, index)
+            .into_result()
+            .ok()?;
+
+        let mut der = core::ptr::null();
+        let mut der_len = 0;
+        s2n_cert_get_der(cert, &mut der, &mut der_len)
+            .into_result()
+            .ok()?;
+
+        if der.is_null() {
+            return None;
+        }
+
+        // the DER buffer is owned by the chain, so it needs to be copied before it's freed
+        let der = core::slice::from_raw_parts(der, der_len as usize);
+        certificates.push(Bytes::copy_from_slice(der));
+    }
+
+    Some(certificates)
+}
+
+/// Returns the TLS 1.3 name of the signature scheme used by the peer
+unsafe fn get_signature_scheme(
+    connection: *mut s2n_connection,
+    endpoint: endpoint::Type,
+) -> Option<&'static str> {
+    use s2n_tls_hash_algorithm as hash;
+    use s2n_tls_signature_algorithm as sig;
+
+    let mut signature = sig::ANONYMOUS;
+    let mut digest = hash::NONE;
+
+    // the server certificate is signed by the server, so clients need to look up its algorithm
+    // while servers look up the algorithm of the client certificate
+    if endpoint.is_client() {
+        s2n_connection_get_selected_signature_algorithm(connection, &mut signature)
+            .into_result()
+            .ok()?;
+        s2n_connection_get_selected_digest_algorithm(connection, &mut digest)
+            .into_result()
+            .ok()?;
+    } else {
+        s2n_connection_get_selected_client_cert_signature_algorithm(connection, &mut signature)
+            .into_result()
+            .ok()?;
+        s2n_connection_get_selected_client_cert_digest_algorithm(connection, &mut digest)
+            .into_result()
+            .ok()?;
+    }
+
+    //= https://www.rfc-editor.org/rfc/rfc8446#section-4.2.3
+    //# ECDSA algorithms:  Indicates a signature algorithm using ECDSA
+    //#    [ECDSA], the corresponding curve as defined in ANSI X9.62 [ECDSA]
+    //#    and FIPS 186-4 [DSS], and the corresponding hash algorithm as
+    //#    defined in [SHS].
+    let scheme = match (signature, digest) {
+        (sig::ECDSA, hash::SHA256) => "ecdsa_secp256r1_sha256",
+        (sig::ECDSA, hash::SHA384) => "ecdsa_secp384r1_sha384",
+        (sig::ECDSA, hash::SHA512) => "ecdsa_secp521r1_sha512",
+        (sig::RSA_PSS_RSAE, hash::SHA256) => "rsa_pss_rsae_sha256",
+        (sig::RSA_PSS_RSAE, hash::SHA384) => "rsa_pss_rsae_sha384",
+        (sig::RSA_PSS_RSAE, hash::SHA512) => "rsa_pss_rsae_sha512",
+        (sig::RSA_PSS_PSS, hash::SHA256) => "rsa_pss_pss_sha256",
+        (sig::RSA_PSS_PSS, hash::SHA384) => "rsa_pss_pss_sha384",
+        (sig::RSA_PSS_PSS, hash::SHA512) => "rsa_pss_pss_sha512",
+        (sig::RSA, hash::SHA256) => "rsa_pkcs1_sha256",
+        (sig::RSA, hash::SHA384) => "rsa_pkcs1_sha384",
+        (sig::RSA, hash::SHA512) => "rsa_pkcs1_sha512",
+        _ => return None,
+    };
+
+    Some(scheme)
+}
+
+/// Converts a static string returned by s2n-tls, which uses `NONE` for unset values
+unsafe fn get_str(ptr: *const libc::c_char) -> Option<&'static str> {
+    if ptr.is_null() {
+        return None;
+    }
+
+    match CStr::from_ptr(ptr).to_str().ok()? {
+        "NONE" => None,
+        value => Some(value),
+    }
+}
@@ -16,11 +16,14 @@ mod keylog;
 mod params;
 mod session;
 
+pub mod alert;
 pub mod certificate;
 pub mod client;
+pub mod handshake;
 pub mod server;
 
 pub use client::Client;
+pub use s2n_quic_crypto::{sct, spki};
 pub use server::Server;
 
 // Re-export the `ClientHelloHandler` and `Connection` to make it easier for users
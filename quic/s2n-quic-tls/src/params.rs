@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use s2n_codec::{EncoderBuffer, EncoderValue};
+use s2n_quic_core::crypto::tls::ApplicationSettings;
 
 /// A buffer used by an endpoint to encode transport parameters
 ///
@@ -10,17 +11,28 @@ use s2n_codec::{EncoderBuffer, EncoderValue};
 #[derive(Debug, Default)]
 pub struct Params {
     buffer: Vec<u8>,
+    /// The settings of the endpoint, which are appended to the transport parameters
+    application_settings: ApplicationSettings,
 }
 
 impl Params {
+    pub fn new(application_settings: ApplicationSettings) -> Self {
+        Self {
+            buffer: Vec::new(),
+            application_settings,
+        }
+    }
+
     pub fn with<P, F, R>(&mut self, params: &P, f: F) -> R
     where
         P: EncoderValue,
         F: FnOnce(&[u8]) -> R,
     {
-        let len = params.encoding_size();
+        let len = params.encoding_size() + self.application_settings.encoding_size();
         self.buffer.resize(len, 0);
-        params.encode(&mut EncoderBuffer::new(&mut self.buffer));
+        let mut encoder = EncoderBuffer::new(&mut self.buffer);
+        params.encode(&mut encoder);
+        self.application_settings.encode(&mut encoder);
         f(&self.buffer)
     }
 }
@@ -3,12 +3,17 @@
 
 use crate::{
     certificate::{IntoCertificate, IntoPrivateKey},
+    handshake,
     keylog::KeyLogHandle,
     params::Params,
     session::Session,
 };
 use s2n_codec::EncoderValue;
-use s2n_quic_core::{application::ServerName, crypto::tls, endpoint};
+use s2n_quic_core::{
+    application::ServerName,
+    crypto::tls::{self, ApplicationSettings},
+    endpoint,
+};
 #[cfg(any(test, all(s2n_quic_unstable, feature = "unstable_client_hello")))]
 use s2n_tls::callbacks::ClientHelloCallback;
 use s2n_tls::{
@@ -24,6 +29,7 @@ pub struct Server {
     #[allow(dead_code)] // we need to hold on to the handle to ensure it is cleaned up correctly
     keylog: Option<KeyLogHandle>,
     params: Params,
+    handshake_callback: Option<Arc<dyn handshake::Callback>>,
 }
 
 impl Server {
@@ -43,6 +49,8 @@ impl Default for Server {
 pub struct Builder {
     config: config::Builder,
     keylog: Option<KeyLogHandle>,
+    handshake_callback: Option<Arc<dyn handshake::Callback>>,
+    application_settings: ApplicationSettings,
 }
 
 impl Default for Builder {
@@ -58,6 +66,8 @@ impl Default for Builder {
         Self {
             config,
             keylog: None,
+            handshake_callback: None,
+            application_settings: ApplicationSettings::new(),
         }
     }
 }
@@ -72,6 +82,10 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sets the application protocols the server accepts, in order of preference
+    ///
+    /// Clients which don't offer any of these protocols are rejected during the handshake with
+    /// a `no_application_protocol` alert.
     pub fn with_application_protocols<P: IntoIterator<Item = I>, I: AsRef<[u8]>>(
         mut self,
         protocols: P,
@@ -80,6 +94,23 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sends settings for an application protocol to the client during the handshake
+    ///
+    /// The client only uses the settings if the application protocol is negotiated. Likewise,
+    /// the settings the client sent for the negotiated protocol are available on the connection
+    /// once the handshake is complete. See [`ApplicationSettings`] for how the settings are
+    /// exchanged.
+    pub fn with_application_settings(
+        mut self,
+        application_protocol: &[u8],
+        settings: &[u8],
+    ) -> Result<Self, Error> {
+        self.application_settings
+            .insert(application_protocol, settings)
+            .map_err(|_| Error::InvalidInput)?;
+        Ok(self)
+    }
+
     pub fn with_certificate<C: IntoCertificate, PK: IntoPrivateKey>(
         mut self,
         certificate: C,
@@ -105,11 +136,8 @@ impl Builder {
         certificate: C,
     ) -> Result<Self, Error> {
         let certificate = certificate.into_certificate()?;
-        let certificate = certificate
-            .0
-            .as_pem()
-            .expect("pem is currently the only certificate format supported");
-        self.config.trust_pem(certificate)?;
+        let certificate = certificate.0.to_pem("CERTIFICATE");
+        self.config.trust_pem(&certificate)?;
         Ok(self)
     }
 
@@ -148,6 +176,9 @@ impl Builder {
     ///
     /// This will be invoked when a client certificate is presented during a mutual TLS
     /// handshake.
+    ///
+    /// Rejected certificates are reported to the peer with a `bad_certificate` alert, unless
+    /// the callback specifies another alert with [`crate::alert::set`].
     pub fn with_verify_host_name_callback<T: 'static + VerifyHostNameCallback>(
         mut self,
         handler: T,
@@ -156,6 +187,18 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sets the callback which is invoked once the client's certificates have been verified
+    ///
+    /// The callback can inspect the certificates and the negotiated parameters, and abort the
+    /// handshake with an alert of its choice.
+    pub fn with_handshake_callback<T: handshake::Callback>(
+        mut self,
+        callback: T,
+    ) -> Result<Self, Error> {
+        self.handshake_callback = Some(Arc::new(callback));
+        Ok(self)
+    }
+
     pub fn with_key_logging(mut self) -> Result<Self, Error> {
         use crate::keylog::KeyLog;
 
@@ -180,7 +223,8 @@ impl Builder {
         Ok(Server {
             config: self.config.build()?,
             keylog: self.keylog,
-            params: Default::default(),
+            params: Params::new(self.application_settings),
+            handshake_callback: self.handshake_callback,
         })
     }
 }
@@ -190,8 +234,16 @@ impl tls::Endpoint for Server {
 
     fn new_server_session<Params: EncoderValue>(&mut self, params: &Params) -> Self::Session {
         let config = self.config.clone();
+        let handshake_callback = self.handshake_callback.clone();
         self.params.with(params, |params| {
-            Session::new(endpoint::Type::Server, config, params, None).unwrap()
+            Session::new(
+                endpoint::Type::Server,
+                config,
+                params,
+                None,
+                handshake_callback,
+            )
+            .unwrap()
         })
     }
 
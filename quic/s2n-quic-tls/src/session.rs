@@ -1,12 +1,16 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::callback::{self, Callback};
+use crate::{
+    alert,
+    callback::{self, Callback},
+    handshake,
+};
 use bytes::BytesMut;
-use core::{marker::PhantomData, task::Poll};
+use core::{fmt, marker::PhantomData, task::Poll};
 use s2n_quic_core::{
     application::ServerName,
-    crypto::{tls, CryptoError, CryptoSuite},
+    crypto::{tls, CryptoSuite},
     endpoint, transport,
 };
 use s2n_quic_crypto::Suite;
@@ -16,8 +20,8 @@ use s2n_tls::{
     enums::{Blinding, Mode},
     error::Error,
 };
+use std::sync::Arc;
 
-#[derive(Debug)]
 pub struct Session {
     endpoint: endpoint::Type,
     pub(crate) connection: Connection,
@@ -27,6 +31,21 @@ pub struct Session {
     emitted_server_name: bool,
     // This is only set for the client to avoid an extra allocation
     server_name: Option<ServerName>,
+    handshake_callback: Option<Arc<dyn handshake::Callback>>,
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("endpoint", &self.endpoint)
+            .field("connection", &self.connection)
+            .field("state", &self.state)
+            .field("handshake_complete", &self.handshake_complete)
+            .field("send_buffer", &self.send_buffer)
+            .field("emitted_server_name", &self.emitted_server_name)
+            .field("server_name", &self.server_name)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Session {
@@ -35,6 +54,7 @@ impl Session {
         config: Config,
         params: &[u8],
         server_name: Option<ServerName>,
+        handshake_callback: Option<Arc<dyn handshake::Callback>>,
     ) -> Result<Self, Error> {
         let mut connection = Connection::new(match endpoint {
             endpoint::Type::Server => Mode::Server,
@@ -61,6 +81,7 @@ impl Session {
             send_buffer: BytesMut::new(),
             emitted_server_name: false,
             server_name,
+            handshake_callback,
         })
     }
 }
@@ -91,6 +112,7 @@ impl tls::Session for Session {
             send_buffer: &mut self.send_buffer,
             emitted_server_name: &mut self.emitted_server_name,
             server_name: &self.server_name,
+            handshake_callback: self.handshake_callback.as_deref(),
         };
 
         unsafe {
@@ -99,8 +121,25 @@ impl tls::Session for Session {
             callback.set(&mut self.connection);
         }
 
+        // discard any alert which was set outside of the callbacks
+        let _ = alert::take();
+
         let result = self.connection.poll_negotiate().map_ok(|_| ());
 
+        // an alert set by an application callback takes precedence over the mapped error
+        let application_alert = alert::take();
+
+        // Servers only have the client's certificates once they've processed the client's
+        // Finished message, so the policy is applied before the handshake is reported as complete.
+        if let (Poll::Ready(Ok(())), Some(connection)) = (&result, callback.state.connection) {
+            unsafe {
+                // Safety: the recorded connection is owned by `self.connection`
+                if let Err(err) = callback.on_peer_certificates(connection.as_ptr()) {
+                    callback.err = Some(err);
+                }
+            }
+        }
+
         callback.unset(&mut self.connection)?;
 
         match result {
@@ -113,10 +152,8 @@ impl tls::Session for Session {
                 }
                 Poll::Ready(Ok(()))
             }
-            Poll::Ready(Err(e)) => Poll::Ready(Err(e
-                .alert()
-                .map(CryptoError::new)
-                .unwrap_or(CryptoError::HANDSHAKE_FAILURE)
+            Poll::Ready(Err(e)) => Poll::Ready(Err(application_alert
+                .unwrap_or_else(|| alert::from_error(&e))
                 .into())),
             Poll::Pending => Poll::Pending,
         }
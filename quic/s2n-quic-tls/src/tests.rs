@@ -1,23 +1,28 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{client, server};
+use crate::{client, handshake, sct, server, spki};
 use core::{
     sync::atomic::{AtomicBool, AtomicU8, Ordering},
     task::Poll,
 };
 use s2n_quic_core::{
-    crypto::tls::{
-        self,
-        testing::certificates::{CERT_PEM, KEY_PEM, UNTRUSTED_CERT_PEM, UNTRUSTED_KEY_PEM},
-        Endpoint,
+    crypto::{
+        tls::{
+            self,
+            testing::certificates::{
+                CERT_DER, CERT_PEM, KEY_PEM, UNTRUSTED_CERT_PEM, UNTRUSTED_KEY_PEM,
+            },
+            Endpoint,
+        },
+        CryptoError,
     },
     transport,
 };
 #[cfg(any(test, all(s2n_quic_unstable, feature = "unstable_client_hello")))]
 use s2n_tls::{callbacks::ClientHelloCallback, connection::Connection};
 use s2n_tls::{callbacks::VerifyHostNameCallback, error::Error};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 pub struct MyClientHelloHandler {
     done: Arc<AtomicBool>,
@@ -66,6 +71,18 @@ impl VerifyHostNameClientCertVerifier {
     }
 }
 
+/// Rejects the server certificate with a custom alert
+pub struct AlertHostNameVerifier {
+    alert: CryptoError,
+}
+
+impl VerifyHostNameCallback for AlertHostNameVerifier {
+    fn verify_host_name(&self, _host_name: &str) -> bool {
+        crate::alert::set(self.alert);
+        false
+    }
+}
+
 #[derive(Default)]
 pub struct RejectAllClientCertificatesHandler {}
 impl VerifyHostNameCallback for RejectAllClientCertificatesHandler {
@@ -74,6 +91,55 @@ impl VerifyHostNameCallback for RejectAllClientCertificatesHandler {
     }
 }
 
+/// The parameters passed to a handshake callback
+#[derive(Debug)]
+struct RecordedInfo {
+    peer_certificates: usize,
+    group: Option<&'static str>,
+    signature_scheme: Option<&'static str>,
+    cipher_suite: Option<&'static str>,
+    embedded_scts: Option<usize>,
+}
+
+/// Records the parameters negotiated with the peer, optionally rejecting the handshake
+#[derive(Clone, Default)]
+struct HandshakeRecorder {
+    infos: Arc<Mutex<Vec<RecordedInfo>>>,
+    reject: Option<CryptoError>,
+}
+
+impl HandshakeRecorder {
+    fn rejecting(alert: CryptoError) -> Self {
+        Self {
+            reject: Some(alert),
+            ..Default::default()
+        }
+    }
+
+    fn infos(&self) -> std::sync::MutexGuard<'_, Vec<RecordedInfo>> {
+        self.infos.lock().unwrap()
+    }
+}
+
+impl handshake::Callback for HandshakeRecorder {
+    fn on_peer_certificates(&self, info: &handshake::Info) -> Result<(), CryptoError> {
+        self.infos().push(RecordedInfo {
+            peer_certificates: info.peer_certificates.len(),
+            group: info.group,
+            signature_scheme: info.signature_scheme,
+            cipher_suite: info.cipher_suite,
+            embedded_scts: info
+                .signed_certificate_timestamps
+                .map(|verification| verification.embedded),
+        });
+
+        match self.reject {
+            Some(alert) => Err(alert),
+            None => Ok(()),
+        }
+    }
+}
+
 fn s2n_client() -> client::Client {
     client::Builder::default()
         .with_certificate(CERT_PEM)
@@ -107,6 +173,16 @@ fn s2n_client_with_fixed_hostname_auth(host_name: &str) -> Result<client::Client
         .build()
 }
 
+fn s2n_client_with_application_protocol(protocol: &[u8]) -> client::Client {
+    client::Builder::default()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .with_application_protocols([protocol])
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
 fn s2n_server() -> server::Server {
     server::Builder::default()
         .with_certificate(CERT_PEM, KEY_PEM)
@@ -165,6 +241,16 @@ fn rustls_client() -> s2n_quic_rustls::client::Client {
         .unwrap()
 }
 
+fn rustls_client_with_application_protocol(protocol: &[u8]) -> s2n_quic_rustls::client::Client {
+    s2n_quic_rustls::client::Builder::default()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .with_application_protocols([protocol].iter())
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn s2n_client_s2n_server_ch_callback_test() {
@@ -203,6 +289,24 @@ fn s2n_client_rustls_server_test() {
     run(&mut server_endpoint, &mut client_endpoint, None);
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_s2n_server_conformance_test() {
+    tls::testing::conformance::run(&mut s2n_server(), &mut s2n_client());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn rustls_client_s2n_server_conformance_test() {
+    tls::testing::conformance::run(&mut s2n_server(), &mut rustls_client());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_rustls_server_conformance_test() {
+    tls::testing::conformance::run(&mut rustls_server(), &mut s2n_client());
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn s2n_client_s2n_server_client_auth_test() {
@@ -224,7 +328,7 @@ fn s2n_client_no_client_auth_s2n_server_requires_client_auth_test() {
     // but the client does not support it.
     assert!(test_result.is_err());
     let e = test_result.unwrap_err();
-    assert_eq!(e.description().unwrap(), "HANDSHAKE_FAILURE");
+    assert_eq!(e.description().unwrap(), "UNEXPECTED_MESSAGE");
 }
 
 #[test]
@@ -239,7 +343,7 @@ fn s2n_client_with_client_auth_s2n_server_does_not_require_client_auth_test() {
     // but the server does not support it.
     assert!(test_result.is_err());
     let e = test_result.unwrap_err();
-    assert_eq!(e.description().unwrap(), "HANDSHAKE_FAILURE");
+    assert_eq!(e.description().unwrap(), "UNEXPECTED_MESSAGE");
 }
 
 #[test]
@@ -254,7 +358,7 @@ fn s2n_client_with_client_auth_s2n_server_does_not_trust_client_certificate() {
     // application level host verification check on the cert.
     assert!(test_result.is_err());
     let e = test_result.unwrap_err();
-    assert_eq!(e.description().unwrap(), "HANDSHAKE_FAILURE");
+    assert_eq!(e.description().unwrap(), "BAD_CERTIFICATE");
 }
 
 #[test]
@@ -269,7 +373,7 @@ fn s2n_client_with_client_auth_s2n_server_does_not_trust_issuer() {
     // by a CA that is not in the server trust store, even though the host name is validated.
     assert!(test_result.is_err());
     let e = test_result.unwrap_err();
-    assert_eq!(e.description().unwrap(), "HANDSHAKE_FAILURE");
+    assert_eq!(e.description().unwrap(), "BAD_CERTIFICATE");
 }
 
 #[test]
@@ -283,7 +387,7 @@ fn s2n_client_with_custom_hostname_auth_rejects_server_name() {
     // The handshake should fail because the hostname ("localhost") is not validated
     assert!(test_result.is_err());
     let e = test_result.unwrap_err();
-    assert_eq!(e.description().unwrap(), "HANDSHAKE_FAILURE");
+    assert_eq!(e.description().unwrap(), "BAD_CERTIFICATE");
 }
 
 #[test]
@@ -295,6 +399,316 @@ fn s2n_client_with_custom_hostname_auth_accepts_server_name() {
     run_result(&mut server_endpoint, &mut client_endpoint, None).unwrap();
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_with_custom_alert_rejects_server_name() {
+    let alert = CryptoError::ACCESS_DENIED;
+    let mut client_endpoint = client::Builder::default()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .with_verify_host_name_callback(AlertHostNameVerifier { alert })
+        .unwrap()
+        .build()
+        .unwrap();
+    let mut server_endpoint = s2n_server();
+
+    let test_result = run_result(&mut server_endpoint, &mut client_endpoint, None);
+
+    // The handshake should fail with the alert specified by the callback
+    let e = test_result.unwrap_err();
+    assert_eq!(e.description().unwrap(), "ACCESS_DENIED");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_handshake_callback_test() {
+    let recorder = HandshakeRecorder::default();
+    let mut client_endpoint = client::Builder::default()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .with_handshake_callback(recorder.clone())
+        .unwrap()
+        .build()
+        .unwrap();
+    let mut server_endpoint = s2n_server();
+
+    run(&mut server_endpoint, &mut client_endpoint, None);
+
+    let infos = recorder.infos();
+    assert_eq!(infos.len(), 1, "the callback should be invoked once");
+    let info = &infos[0];
+    assert_eq!(info.peer_certificates, 1);
+    assert!(info.group.is_some());
+    // the test certificate uses a P-256 key
+    assert_eq!(info.signature_scheme, Some("ecdsa_secp256r1_sha256"));
+    assert!(info.cipher_suite.is_some());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_handshake_callback_rejects_server() {
+    let recorder = HandshakeRecorder::rejecting(CryptoError::INSUFFICIENT_SECURITY);
+    let mut client_endpoint = client::Builder::default()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .with_handshake_callback(recorder.clone())
+        .unwrap()
+        .build()
+        .unwrap();
+    let mut server_endpoint = s2n_server();
+
+    let test_result = run_result(&mut server_endpoint, &mut client_endpoint, None);
+
+    // The handshake should fail with the alert returned by the callback
+    let e = test_result.unwrap_err();
+    assert_eq!(e.description().unwrap(), "INSUFFICIENT_SECURITY");
+    assert_eq!(recorder.infos().len(), 1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_spki_pins_test() {
+    let client = |pin: spki::Digest, recorder: HandshakeRecorder| {
+        client::Builder::default()
+            .with_certificate(CERT_PEM)
+            .unwrap()
+            .with_spki_pins([pin])
+            .unwrap()
+            .with_handshake_callback(recorder)
+            .unwrap()
+            .build()
+            .unwrap()
+    };
+
+    // the DER certificate contains the same key as the PEM certificate of the server
+    let pin = spki::digest(CERT_DER).unwrap();
+    let recorder = HandshakeRecorder::default();
+    let mut client_endpoint = client(pin, recorder.clone());
+    let mut server_endpoint = s2n_server();
+    run(&mut server_endpoint, &mut client_endpoint, None);
+    assert_eq!(recorder.infos().len(), 1);
+
+    // servers which don't present a pinned key are rejected before the application callback
+    let recorder = HandshakeRecorder::default();
+    let mut client_endpoint = client([0; spki::DIGEST_LEN], recorder.clone());
+    let mut server_endpoint = s2n_server();
+    let test_result = run_result(&mut server_endpoint, &mut client_endpoint, None);
+    let e = test_result.unwrap_err();
+    assert_eq!(e.description().unwrap(), "BAD_CERTIFICATE");
+    assert!(recorder.infos().is_empty());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_sct_policy_test() {
+    let client = |policy: sct::Policy, recorder: HandshakeRecorder| {
+        client::Builder::default()
+            .with_certificate(CERT_PEM)
+            .unwrap()
+            .with_sct_policy(policy)
+            .unwrap()
+            .with_handshake_callback(recorder)
+            .unwrap()
+            .build()
+            .unwrap()
+    };
+
+    // a policy without any required logs only reports the SCTs to the application
+    let recorder = HandshakeRecorder::default();
+    let mut client_endpoint = client(sct::Policy::new(0), recorder.clone());
+    let mut server_endpoint = s2n_server();
+    run(&mut server_endpoint, &mut client_endpoint, None);
+    let infos = recorder.infos();
+    assert_eq!(infos.len(), 1);
+    assert_eq!(infos[0].embedded_scts, Some(0));
+    drop(infos);
+
+    // the test certificate doesn't embed any SCTs
+    let recorder = HandshakeRecorder::default();
+    let mut client_endpoint = client(sct::Policy::new(1), recorder.clone());
+    let mut server_endpoint = s2n_server();
+    let test_result = run_result(&mut server_endpoint, &mut client_endpoint, None);
+    let e = test_result.unwrap_err();
+    assert_eq!(e.description().unwrap(), "BAD_CERTIFICATE");
+    assert!(recorder.infos().is_empty());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_server_handshake_callback_client_auth_test() {
+    let recorder = HandshakeRecorder::default();
+    let mut client_endpoint = s2n_client_with_client_auth().unwrap();
+    let mut server_endpoint = server::Builder::default()
+        .with_empty_trust_store()
+        .unwrap()
+        .with_client_authentication()
+        .unwrap()
+        .with_certificate(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .with_trusted_certificate(CERT_PEM)
+        .unwrap()
+        .with_handshake_callback(recorder.clone())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    run(&mut server_endpoint, &mut client_endpoint, None);
+
+    let infos = recorder.infos();
+    assert_eq!(infos.len(), 1, "the callback should be invoked once");
+    let info = &infos[0];
+    assert_eq!(info.peer_certificates, 1);
+    assert_eq!(info.signature_scheme, Some("ecdsa_secp256r1_sha256"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_server_handshake_callback_without_client_auth_test() {
+    let recorder = HandshakeRecorder::default();
+    let mut client_endpoint = s2n_client();
+    let mut server_endpoint = server::Builder::default()
+        .with_certificate(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .with_handshake_callback(recorder.clone())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    run(&mut server_endpoint, &mut client_endpoint, None);
+
+    // the callback is still invoked when the client doesn't present any certificates
+    let infos = recorder.infos();
+    assert_eq!(infos.len(), 1);
+    assert_eq!(infos[0].peer_certificates, 0);
+    assert!(infos[0].group.is_some());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_server_handshake_callback_rejects_client() {
+    let recorder = HandshakeRecorder::rejecting(CryptoError::ACCESS_DENIED);
+    let mut client_endpoint = s2n_client_with_client_auth().unwrap();
+    let mut server_endpoint = server::Builder::default()
+        .with_empty_trust_store()
+        .unwrap()
+        .with_client_authentication()
+        .unwrap()
+        .with_certificate(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .with_trusted_certificate(CERT_PEM)
+        .unwrap()
+        .with_handshake_callback(recorder)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let test_result = run_result(&mut server_endpoint, &mut client_endpoint, None);
+
+    let e = test_result.unwrap_err();
+    assert_eq!(e.description().unwrap(), "ACCESS_DENIED");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn application_settings_test() {
+    fn handshake<S: Endpoint, C: Endpoint>(server: &mut S, client: &mut C) {
+        let mut pair = tls::testing::Pair::new(server, client, "localhost".into());
+        while pair.is_handshaking() {
+            pair.poll(None).unwrap();
+        }
+        pair.finish();
+
+        assert_eq!(
+            pair.client.context.application_settings.as_deref(),
+            Some(&b"server settings"[..])
+        );
+        assert_eq!(
+            pair.server.context.application_settings.as_deref(),
+            Some(&b"client settings"[..])
+        );
+    }
+
+    let mut client_endpoint = client::Builder::default()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .with_application_settings(b"h3", b"client settings")
+        .unwrap()
+        .build()
+        .unwrap();
+    let mut server_endpoint = server::Builder::default()
+        .with_certificate(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .with_application_settings(b"h3", b"server settings")
+        .unwrap()
+        .build()
+        .unwrap();
+    handshake(&mut server_endpoint, &mut client_endpoint);
+
+    // the settings are exchanged the same way by both providers
+    let mut rustls_server_endpoint = s2n_quic_rustls::server::Builder::default()
+        .with_certificate(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .with_application_settings(b"h3", b"server settings")
+        .unwrap()
+        .build()
+        .unwrap();
+    handshake(&mut rustls_server_endpoint, &mut client_endpoint);
+
+    // nothing is reported if the peer didn't send any settings
+    let mut server_endpoint = s2n_server();
+    let mut pair = tls::testing::Pair::new(
+        &mut server_endpoint,
+        &mut client_endpoint,
+        "localhost".into(),
+    );
+    while pair.is_handshaking() {
+        pair.poll(None).unwrap();
+    }
+    pair.finish();
+    assert_eq!(pair.client.context.application_settings, None);
+
+    assert!(client::Builder::default()
+        .with_application_settings(b"h3", &[0; 2048])
+        .is_err());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_unsupported_application_protocol_s2n_server_test() {
+    let mut client_endpoint = s2n_client_with_application_protocol(b"unsupported");
+    let mut server_endpoint = s2n_server();
+
+    let test_result = run_result(&mut server_endpoint, &mut client_endpoint, None);
+
+    // The handshake should fail because the server doesn't support the client's protocol
+    let e = test_result.unwrap_err();
+    assert_eq!(e.description().unwrap(), "NO_APPLICATION_PROTOCOL");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn rustls_client_unsupported_application_protocol_s2n_server_test() {
+    let mut client_endpoint = rustls_client_with_application_protocol(b"unsupported");
+    let mut server_endpoint = s2n_server();
+
+    let test_result = run_result(&mut server_endpoint, &mut client_endpoint, None);
+
+    let e = test_result.unwrap_err();
+    assert_eq!(e.description().unwrap(), "NO_APPLICATION_PROTOCOL");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_unsupported_application_protocol_rustls_server_test() {
+    let mut client_endpoint = s2n_client_with_application_protocol(b"unsupported");
+    let mut server_endpoint = rustls_server();
+
+    let test_result = run_result(&mut server_endpoint, &mut client_endpoint, None);
+
+    let e = test_result.unwrap_err();
+    assert_eq!(e.description().unwrap(), "NO_APPLICATION_PROTOCOL");
+}
+
 /// Executes the handshake to completion
 fn run_result<S: Endpoint, C: Endpoint>(
     server: &mut S,
@@ -80,7 +80,8 @@ impl AckManager {
             ack_delay_timer: Timer::default(),
             ack_eliciting_transmissions: AckElicitingTransmissionSet::default(),
             ack_settings,
-            ack_ranges: AckRanges::new(ack_settings.ack_ranges_limit as usize),
+            ack_ranges: AckRanges::new(ack_settings.ack_ranges_limit as usize)
+                .with_advertised_limit(ack_settings.advertised_ack_ranges_limit as usize),
             largest_received_packet_number_acked: packet_space
                 .new_packet_number(VarInt::from_u8(0)),
             largest_received_packet_number_at: None,
@@ -298,30 +299,28 @@ impl AckManager {
             //# *  when the received packet has a packet number less than another
             //#    ack-eliciting packet that has been received, or
 
-            should_activate |= !is_largest;
+            let is_reordered = !is_largest;
 
             //= https://www.rfc-editor.org/rfc/rfc9000#section-13.2.1
             //# *  when the packet has a packet number larger than the highest-
             //#    numbered ack-eliciting packet that has been received and there are
             //#    missing packets between that packet and this packet.
 
-            should_activate |= !is_ordered;
+            let is_reordered = is_reordered || !is_ordered;
+
+            should_activate |= is_reordered && self.ack_settings.immediate_ack_on_reorder;
 
             //= https://www.rfc-editor.org/rfc/rfc9000#section-13.2.1
             //# Similarly, packets marked with the ECN Congestion Experienced (CE)
             //# codepoint in the IP header SHOULD be acknowledged immediately, to
             //# reduce the peer's response time to congestion events.
-            should_activate |= processed_packet.datagram.ecn.congestion_experienced();
-
-            // TODO update to draft link after published
-            // https://github.com/quicwg/base-drafts/pull/3623
-            // An ACK frame SHOULD be generated for at least every 10th ack-eliciting packet
+            should_activate |= processed_packet.datagram.ecn.congestion_experienced()
+                && self.ack_settings.immediate_ack_on_congestion;
 
             // TODO support delayed ack proposal
             // https://tools.ietf.org/html/draft-iyengar-quic-delayed-ack-00
-            let packet_tolerance = 10;
-
-            should_activate |= self.processed_packets_since_transmission >= packet_tolerance;
+            should_activate |= self.processed_packets_since_transmission
+                >= self.ack_settings.ack_eliciting_threshold;
 
             //= https://www.rfc-editor.org/rfc/rfc9000#section-9.3.3
             //# An endpoint that receives a PATH_CHALLENGE on an active path SHOULD
@@ -363,6 +362,17 @@ impl AckManager {
         self.largest_received_packet_number_acked
     }
 
+    /// Returns the largest packet number which was received
+    pub fn largest_received_packet_number(&self) -> Option<PacketNumber> {
+        self.ack_ranges.max_value()
+    }
+
+    /// Restores the largest received packet number of a transferred connection, which is
+    /// used for decoding the packet numbers of the peer
+    pub fn on_transferred(&mut self, largest_received_packet_number: PacketNumber) {
+        self.largest_received_packet_number_acked = largest_received_packet_number;
+    }
+
     /// Computes the ack_delay field for the current state
     fn ack_delay(&self, now: Timestamp) -> VarInt {
         let ack_delay = self
@@ -449,6 +459,62 @@ mod tests {
         assert!(manager.transmission_state.is_active());
     }
 
+    #[test]
+    fn ack_strategy() {
+        fn process(manager: &mut AckManager, pn: u8, ecn: ExplicitCongestionNotification) {
+            let pn = PacketNumberSpace::ApplicationData.new_packet_number(VarInt::from_u8(pn));
+            let datagram = helper_datagram_info(ecn);
+            let mut processed_packet = ProcessedPacket::new(pn, &datagram);
+            processed_packet.ack_elicitation = AckElicitation::Eliciting;
+
+            let path = helper_path_server();
+            let path_id = path::Id::test_id();
+            manager.on_processed_packet(
+                &processed_packet,
+                path_event!(path, path_id),
+                &mut Publisher::no_snapshot(),
+            );
+        }
+
+        let not_ect = ExplicitCongestionNotification::NotEct;
+        let ce = ExplicitCongestionNotification::Ce;
+
+        // the recommended strategy acknowledges reordered packets immediately
+        let mut manager =
+            AckManager::new(PacketNumberSpace::ApplicationData, ack::Settings::default());
+        process(&mut manager, 1, not_ect);
+        assert!(!manager.transmission_state.is_active());
+        process(&mut manager, 3, not_ect);
+        assert!(manager.transmission_state.is_active());
+
+        let settings = ack::Settings {
+            ack_eliciting_threshold: 3,
+            immediate_ack_on_reorder: false,
+            immediate_ack_on_congestion: false,
+            ..Default::default()
+        };
+
+        // reordered packets and ECN-CE marks are acknowledged once the threshold is reached
+        let mut manager = AckManager::new(PacketNumberSpace::ApplicationData, settings);
+        process(&mut manager, 1, not_ect);
+        assert!(!manager.transmission_state.is_active());
+        process(&mut manager, 3, ce);
+        assert!(!manager.transmission_state.is_active());
+        assert!(manager.ack_delay_timer.is_armed());
+        process(&mut manager, 2, not_ect);
+        assert!(manager.transmission_state.is_active());
+
+        let settings = ack::Settings {
+            ack_eliciting_threshold: 1,
+            ..Default::default()
+        };
+
+        // a threshold of 1 acknowledges every packet immediately
+        let mut manager = AckManager::new(PacketNumberSpace::ApplicationData, settings);
+        process(&mut manager, 1, not_ect);
+        assert!(manager.transmission_state.is_active());
+    }
+
     #[test]
     fn ecn_counts() {
         // Setup:
@@ -15,7 +15,11 @@ use s2n_quic_core::{
 };
 
 #[derive(Clone, Debug)]
-pub struct AckRanges(IntervalSet<PacketNumber>);
+pub struct AckRanges {
+    ranges: IntervalSet<PacketNumber>,
+    /// The number of ranges included in transmitted ACK frames
+    advertised_limit: NonZeroUsize,
+}
 
 impl Default for AckRanges {
     fn default() -> Self {
@@ -26,7 +30,18 @@ impl Default for AckRanges {
 impl AckRanges {
     pub fn new(limit: usize) -> Self {
         let limit = NonZeroUsize::new(limit).expect("limit should be nonzero");
-        Self(IntervalSet::with_limit(limit))
+        Self {
+            ranges: IntervalSet::with_limit(limit),
+            advertised_limit: limit,
+        }
+    }
+
+    /// Limits the number of ranges included in transmitted ACK frames
+    ///
+    /// The ranges with the largest packet numbers are advertised first.
+    pub fn with_advertised_limit(mut self, limit: usize) -> Self {
+        self.advertised_limit = NonZeroUsize::new(limit).expect("limit should be nonzero");
+        self
     }
 
     /// Inserts a packet number; dropping smaller values if needed
@@ -39,15 +54,15 @@ impl AckRanges {
             Bound::Included(pn_range.start()),
             Bound::Included(pn_range.end()),
         );
-        if self.0.insert(interval).is_ok() {
+        if self.ranges.insert(interval).is_ok() {
             return Ok(());
         }
 
         // attempt to shed the lowest packet number ranges to make room for larger ones
-        match self.0.pop_min() {
+        match self.ranges.pop_min() {
             Some(min) => {
                 if min < pn_range.start() {
-                    let insert_res = self.0.insert(interval);
+                    let insert_res = self.ranges.insert(interval);
                     debug_assert!(
                         insert_res.is_ok(),
                         "min range was removed, so it should be possible to insert another range",
@@ -63,7 +78,7 @@ impl AckRanges {
                     })
                 } else {
                     // new value is smaller than min so inset it back in the front
-                    let _ = self.0.insert_front(min);
+                    let _ = self.ranges.insert_front(min);
                     Err(AckRangesError::RangeInsertionFailed {
                         min: pn_range.start(),
                         max: pn_range.end(),
@@ -106,19 +121,27 @@ impl AckRanges {
     }
 }
 
-type AckRangesIter<'a> = core::iter::Map<
-    core::iter::Rev<RangeInclusiveIter<'a, PacketNumber>>,
-    fn(RangeInclusive<PacketNumber>) -> RangeInclusive<VarInt>,
+type AckRangesIter<'a> = core::iter::Take<
+    core::iter::Map<
+        core::iter::Rev<RangeInclusiveIter<'a, PacketNumber>>,
+        fn(RangeInclusive<PacketNumber>) -> RangeInclusive<VarInt>,
+    >,
 >;
 
 impl<'a> ack::AckRanges for &'a AckRanges {
     type Iter = AckRangesIter<'a>;
 
     fn ack_ranges(&self) -> Self::Iter {
-        self.0.inclusive_ranges().rev().map(|range| {
+        let as_varint: fn(_) -> _ = |range: RangeInclusive<PacketNumber>| {
             let (start, end) = range.into_inner();
             PacketNumber::as_varint(start)..=PacketNumber::as_varint(end)
-        })
+        };
+
+        self.ranges
+            .inclusive_ranges()
+            .rev()
+            .map(as_varint)
+            .take(self.advertised_limit.get())
     }
 }
 
@@ -126,13 +149,13 @@ impl Deref for AckRanges {
     type Target = IntervalSet<PacketNumber>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.ranges
     }
 }
 
 impl DerefMut for AckRanges {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.ranges
     }
 }
 
@@ -270,6 +293,36 @@ mod tests {
         assert_eq!(ack_ranges.interval_len(), 2);
     }
 
+    #[test]
+    fn advertised_limit_test() {
+        use s2n_quic_core::frame::ack::AckRanges as _;
+
+        let mut ack_ranges = AckRanges::new(4).with_advertised_limit(2);
+        let packet_numbers = packet_numbers_iter().step_by(2).take(4).collect::<Vec<_>>();
+
+        for pn in &packet_numbers {
+            assert!(ack_ranges.insert_packet_number(*pn).is_ok());
+        }
+
+        // all of the ranges are stored
+        assert_eq!(ack_ranges.interval_len(), 4);
+
+        // only the ranges with the largest packet numbers are advertised
+        let as_varint = |pn: &PacketNumber| PacketNumber::as_varint(*pn);
+        let advertised = (&ack_ranges).ack_ranges().collect::<Vec<_>>();
+        assert_eq!(
+            advertised,
+            [
+                as_varint(&packet_numbers[3])..=as_varint(&packet_numbers[3]),
+                as_varint(&packet_numbers[2])..=as_varint(&packet_numbers[2]),
+            ]
+        );
+        assert_eq!(
+            (&ack_ranges).largest_acknowledged(),
+            as_varint(&packet_numbers[3])
+        );
+    }
+
     #[test]
     fn large_range_test() {
         let pn_a = PacketNumberSpace::ApplicationData.new_packet_number(VarInt::from_u32(1));
@@ -0,0 +1,206 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-connection arenas for reducing the pressure on the global allocator
+//!
+//! [`Frames`] is a bump allocator which carves the buffers for received stream data out of
+//! larger chunks, while [`Slab`] recycles values of a single type once they have been released.
+//! Both track their [`Usage`], which is reported through the connection's [`Stats`].
+
+use alloc::{rc::Rc, vec::Vec};
+use bytes::BytesMut;
+use core::{cell::RefCell, ops::Deref};
+
+pub use s2n_quic_core::connection::arena::{Stats, Usage};
+
+/// The number of bytes allocated for each chunk of frame data
+///
+/// This fits 8 receive buffers of the default size. Keeping the chunk small bounds the memory
+/// retained by the application holding on to a single buffer of a chunk.
+const CHUNK_LEN: usize = 32 * 1024;
+
+/// A bump allocator for the buffers holding received frame data
+///
+/// The handle is shared by all of the streams of a connection. The allocated buffers reference
+/// the chunk they were carved out of, which is returned to the global allocator once all of
+/// its buffers have been dropped.
+#[derive(Clone, Debug, Default)]
+pub struct Frames {
+    inner: Rc<RefCell<Bump>>,
+}
+
+// Sending `Frames` between threads is safe, since the handle is only shared by the streams of a
+// single connection, which are always sent together with the connection
+#[allow(unknown_lints, clippy::non_send_fields_in_send_ty)]
+unsafe impl Send for Frames {}
+
+#[derive(Debug, Default)]
+struct Bump {
+    chunk: BytesMut,
+    usage: Usage,
+}
+
+impl Frames {
+    /// Allocates a zero-initialized buffer of `len` bytes
+    pub fn alloc(&self, len: usize) -> BytesMut {
+        let mut bump = self.inner.borrow_mut();
+        let bump = &mut *bump;
+
+        let mut heap_bytes = 0;
+
+        if bump.chunk.len() < len {
+            let chunk_len = CHUNK_LEN.max(len);
+            let mut chunk = BytesMut::with_capacity(chunk_len);
+            // The buffers need to be initialized in order to be split off of the chunk, since
+            // splitting works based on the length rather than the capacity
+            chunk.resize(chunk_len, 0);
+            bump.chunk = chunk;
+            heap_bytes = chunk_len;
+        }
+
+        bump.usage.on_allocation(heap_bytes);
+
+        bump.chunk.split_to(len)
+    }
+
+    /// Releases the remainder of the current chunk
+    ///
+    /// The chunk is returned to the global allocator once the buffers which were carved out of
+    /// it are dropped, rather than being held for the following allocations.
+    pub fn reclaim(&self) {
+        self.inner.borrow_mut().chunk = BytesMut::new();
+    }
+
+    /// Returns the usage of the arena
+    pub fn usage(&self) -> Usage {
+        self.inner.borrow().usage
+    }
+}
+
+/// Recycles released values of a single type
+///
+/// At most `capacity` released values are retained, after which they are returned to the
+/// global allocator.
+#[derive(Debug)]
+pub struct Slab<T> {
+    free: Vec<T>,
+    capacity: usize,
+    usage: Usage,
+}
+
+impl<T: Deref> Slab<T>
+where
+    T::Target: Sized,
+{
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            free: Vec::new(),
+            capacity,
+            usage: Usage::default(),
+        }
+    }
+
+    /// Returns a value for `init`
+    ///
+    /// If a released value is available, `reuse` is called to reinitialize it. Otherwise, `new`
+    /// is called to allocate a new value.
+    pub fn alloc<I>(
+        &mut self,
+        init: I,
+        new: impl FnOnce(I) -> T,
+        reuse: impl FnOnce(&mut T, I),
+    ) -> T {
+        if let Some(mut value) = self.free.pop() {
+            reuse(&mut value, init);
+            self.usage.on_allocation(0);
+            value
+        } else {
+            self.usage.on_allocation(core::mem::size_of::<T::Target>());
+            new(init)
+        }
+    }
+
+    /// Releases a value so it can be reused by a following allocation
+    pub fn free(&mut self, value: T) {
+        if self.free.len() < self.capacity {
+            self.free.push(value);
+        }
+    }
+
+    /// Returns the released values to the global allocator
+    pub fn reclaim(&mut self) {
+        self.free = Vec::new();
+    }
+
+    /// Returns the usage of the arena
+    pub fn usage(&self) -> Usage {
+        self.usage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::boxed::Box;
+
+    #[test]
+    fn frames_test() {
+        let frames = Frames::default();
+
+        let mut buffers = Vec::new();
+        for _ in 0..(CHUNK_LEN / 4096 + 1) {
+            let buffer = frames.alloc(4096);
+            assert_eq!(buffer.len(), 4096);
+            assert_eq!(buffer.capacity(), 4096);
+            assert!(buffer.iter().all(|b| *b == 0));
+            buffers.push(buffer);
+        }
+
+        // buffers which are larger than a chunk get their own chunk
+        assert_eq!(frames.alloc(CHUNK_LEN * 2).len(), CHUNK_LEN * 2);
+
+        let usage = frames.usage();
+        assert_eq!(usage.allocations, buffers.len() as u64 + 1);
+        assert_eq!(usage.heap_allocations, 3);
+        assert_eq!(usage.heap_bytes, (CHUNK_LEN * 4) as u64);
+    }
+
+    #[test]
+    fn slab_test() {
+        let mut slab = Slab::<Box<u64>>::new(1);
+
+        let a = slab.alloc(1, Box::new, |v, i| **v = i);
+        let b = slab.alloc(2, Box::new, |v, i| **v = i);
+        slab.free(a);
+        // only a single value is retained
+        slab.free(b);
+
+        let c = slab.alloc(3, Box::new, |v, i| **v = i);
+        assert_eq!(*c, 3);
+
+        let usage = slab.usage();
+        assert_eq!(usage.allocations, 3);
+        assert_eq!(usage.heap_allocations, 2);
+        assert_eq!(usage.heap_bytes, 16);
+
+        // reclaimed values aren't reused
+        slab.free(c);
+        slab.reclaim();
+        let d = slab.alloc(4, Box::new, |v, i| **v = i);
+        assert_eq!(*d, 4);
+        assert_eq!(slab.usage().heap_allocations, 3);
+    }
+
+    #[test]
+    fn frames_reclaim_test() {
+        let frames = Frames::default();
+
+        let a = frames.alloc(4096);
+        frames.reclaim();
+
+        // the following allocation can't use the remainder of the previous chunk
+        let b = frames.alloc(4096);
+        assert_eq!(frames.usage().heap_allocations, 2);
+        drop((a, b));
+    }
+}
@@ -4,9 +4,10 @@
 //! This module contains data structures for buffering incoming and outgoing data
 //! in Quic streams.
 
+use crate::arena;
 use alloc::collections::VecDeque;
 use bytes::BytesMut;
-use s2n_quic_core::varint::VarInt;
+use s2n_quic_core::{stream::limits::ReassemblyUsage, varint::VarInt};
 
 /// Enumerates error that can occur while inserting data into the Receive Buffer
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -114,6 +115,7 @@ pub struct StreamReceiveBuffer {
     start_offset: u64,
     end_offset: u64,
     buffer_size: usize,
+    arena: Option<arena::Frames>,
 }
 
 impl Default for StreamReceiveBuffer {
@@ -148,6 +150,16 @@ impl StreamReceiveBuffer {
             start_offset: 0u32.into(),
             end_offset: 0u32.into(),
             buffer_size,
+            arena: None,
+        }
+    }
+
+    /// Creates a new `StreamReceiveBuffer` which allocates its buffers from
+    /// the given arena.
+    pub fn with_arena(arena: arena::Frames) -> StreamReceiveBuffer {
+        StreamReceiveBuffer {
+            arena: Some(arena),
+            ..StreamReceiveBuffer::new()
         }
     }
 
@@ -182,9 +194,13 @@ impl StreamReceiveBuffer {
     }
 
     /// Allocates a buffer of the configured buffer size.
-    /// This currently just allocates from the heap. In the future it could use a custom allocator
-    /// or object pool.
+    /// The buffer is allocated from the arena, if one is configured, and from
+    /// the heap otherwise.
     fn allocate_buffer(&mut self) -> BytesMut {
+        if let Some(arena) = &self.arena {
+            return arena.alloc(self.buffer_size);
+        }
+
         let mut b = BytesMut::with_capacity(self.buffer_size);
         // Unfortunately it seems like at the current point of time we have to
         // initialize a BytesMut, in order to be able to properly split it later
@@ -651,9 +667,68 @@ impl StreamReceiveBuffer {
         self.consumed_len() + self.len() as u64
     }
 
+    /// Returns the number of bytes allocated for the buffers and slots.
+    pub fn memory_usage(&self) -> usize {
+        let buffers: usize = self
+            .slots
+            .iter()
+            .map(|slot| match slot {
+                SlotState::Received(buffer) | SlotState::Allocated(buffer) => buffer.capacity(),
+                SlotState::Gap(_) => 0,
+            })
+            .sum();
+
+        buffers + self.slots.capacity() * core::mem::size_of::<SlotState>()
+    }
+
+    /// Returns the memory held for data which was received after a missing range, along with
+    /// the number of missing ranges.
+    ///
+    /// The allocated space after the last received byte is not counted, since it is held for
+    /// in-order data as well.
+    pub fn reassembly_usage(&self) -> ReassemblyUsage {
+        let mut usage = ReassemblyUsage::default();
+        // the capacity of the unfilled buffers since the last received slot
+        let mut allocated = 0u64;
+        let mut in_gap = false;
+
+        let slots = self
+            .slots
+            .iter()
+            .skip_while(|slot| matches!(slot, SlotState::Received(_)));
+
+        for slot in slots {
+            match slot {
+                SlotState::Received(buffer) => {
+                    if in_gap {
+                        usage.gaps += 1;
+                        in_gap = false;
+                    }
+                    usage.bytes += allocated + buffer.capacity() as u64;
+                    allocated = 0;
+                }
+                SlotState::Allocated(buffer) => {
+                    in_gap = true;
+                    allocated += buffer.capacity() as u64;
+                }
+                SlotState::Gap(_) => in_gap = true,
+            }
+        }
+
+        usage
+    }
+
+    /// Releases the capacity which isn't used by the received data.
+    pub fn shrink_to_fit(&mut self) {
+        self.slots.shrink_to_fit();
+    }
+
     /// Resets the receive buffer.
     /// This will drop all previously received data.
     pub fn reset(&mut self) {
-        *self = StreamReceiveBuffer::with_buffer_size(self.buffer_size)
+        *self = StreamReceiveBuffer {
+            arena: self.arena.take(),
+            ..StreamReceiveBuffer::with_buffer_size(self.buffer_size)
+        }
     }
 }
@@ -6,7 +6,10 @@ use crate::buffer::{
     MIN_STREAM_RECEIVE_BUFFER_ALLOCATION_SIZE,
 };
 use core::ops::Deref;
-use s2n_quic_core::varint::{VarInt, MAX_VARINT_VALUE};
+use s2n_quic_core::{
+    stream::limits::ReassemblyUsage,
+    varint::{VarInt, MAX_VARINT_VALUE},
+};
 
 fn new_receive_buffer() -> StreamReceiveBuffer {
     let buffer = StreamReceiveBuffer::new();
@@ -732,3 +735,86 @@ fn pop_watermarked_test() {
         "the receive buffer should be empty after splitting"
     );
 }
+
+#[test]
+fn arena_test() {
+    let arena = crate::arena::Frames::default();
+    let mut buffer = StreamReceiveBuffer::with_arena(arena.clone());
+
+    // write out of order, which allocates multiple slots from the arena
+    let data: Vec<u8> = (0..DEFAULT_STREAM_RECEIVE_BUFFER_ALLOCATION_SIZE * 3)
+        .map(|v| v as u8)
+        .collect();
+    let (first, second) = data.split_at(DEFAULT_STREAM_RECEIVE_BUFFER_ALLOCATION_SIZE + 10);
+    assert!(buffer.write_at((first.len() as u32).into(), second).is_ok());
+    assert_eq!(0, buffer.len());
+    assert!(buffer.write_at(0u32.into(), first).is_ok());
+    assert_eq!(data.len(), buffer.len());
+
+    let mut received = vec![];
+    while let Some(chunk) = buffer.pop() {
+        received.extend_from_slice(&chunk);
+    }
+    assert_eq!(data, received);
+
+    let usage = arena.usage();
+    assert_eq!(usage.allocations, 3);
+    assert_eq!(usage.heap_allocations, 1);
+
+    // the arena is kept when the buffer is reset
+    buffer.reset();
+    assert!(buffer.write_at(0u32.into(), &[1, 2, 3]).is_ok());
+    assert_eq!(arena.usage().allocations, 4);
+}
+
+#[test]
+fn reassembly_usage_test() {
+    let mut buffer = new_receive_buffer();
+    let size = DEFAULT_STREAM_RECEIVE_BUFFER_ALLOCATION_SIZE as u64;
+    assert_eq!(buffer.reassembly_usage(), ReassemblyUsage::default());
+
+    // in-order data doesn't require reassembly
+    assert!(buffer.write_at(0u32.into(), &[0; 100]).is_ok());
+    assert_eq!(buffer.reassembly_usage(), ReassemblyUsage::default());
+
+    // the unfilled space before the out-of-order data is counted
+    assert!(buffer.write_at(200u32.into(), &[0; 100]).is_ok());
+    assert_eq!(
+        buffer.reassembly_usage(),
+        ReassemblyUsage {
+            bytes: 200,
+            gaps: 1
+        }
+    );
+
+    // data in a later buffer includes the rest of the current buffer but not the skipped
+    // buffer, which isn't allocated
+    assert!(buffer
+        .write_at(VarInt::new(2 * size + 100).unwrap(), &[0; 10])
+        .is_ok());
+    assert_eq!(
+        buffer.reassembly_usage(),
+        ReassemblyUsage {
+            bytes: (size - 100) + 100 + 10,
+            gaps: 2
+        }
+    );
+
+    // filling the first gap makes the data up to the second gap readable
+    assert!(buffer.write_at(100u32.into(), &[0; 100]).is_ok());
+    assert_eq!(buffer.len(), 300);
+    assert_eq!(
+        buffer.reassembly_usage(),
+        ReassemblyUsage {
+            bytes: (size - 300) + 100 + 10,
+            gaps: 1
+        }
+    );
+
+    // popping the readable data doesn't change the usage
+    assert!(buffer.pop().is_some());
+    assert_eq!(buffer.reassembly_usage().gaps, 1);
+
+    buffer.reset();
+    assert_eq!(buffer.reassembly_usage(), ReassemblyUsage::default());
+}
@@ -5,8 +5,9 @@
 
 use crate::{
     connection::{self, ConnectionApi, OpenToken},
-    stream::{ops, Stream, StreamError, StreamId},
+    stream::{group, ops, Stream, StreamError, StreamId},
 };
+use alloc::vec::Vec;
 use bytes::Bytes;
 use core::{
     fmt,
@@ -16,9 +17,12 @@ use core::{
 use s2n_quic_core::{
     application,
     application::ServerName,
+    connection::transfer,
     inet::SocketAddress,
+    path::migration,
     query::{Query, QueryMut},
     stream::StreamType,
+    varint::VarInt,
 };
 
 /// A QUIC connection
@@ -165,6 +169,82 @@ impl Connection {
         self.api.close_connection(Some(error_code));
     }
 
+    /// Creates a new group of streams with a shared send window of `max_data` bytes
+    ///
+    /// See [`group`] for more details.
+    #[inline]
+    pub fn create_stream_group(&self, max_data: VarInt) -> Result<group::Id, connection::Error> {
+        self.api.create_stream_group(max_data)
+    }
+
+    /// Adds a stream to a group
+    ///
+    /// Only the data which is sent after joining the group is accounted to the group's window.
+    /// A stream is a member of at most one group, so it leaves any group it joined before.
+    /// If the group was already reset, the stream is reset with the same error.
+    #[inline]
+    pub fn join_stream_group(
+        &self,
+        group: group::Id,
+        stream_id: StreamId,
+    ) -> Result<(), StreamError> {
+        self.api.join_stream_group(group, stream_id)
+    }
+
+    /// Increases the shared send window of a group to `max_data` bytes
+    ///
+    /// Values which are smaller than the current window are ignored.
+    #[inline]
+    pub fn set_stream_group_max_data(
+        &self,
+        group: group::Id,
+        max_data: VarInt,
+    ) -> Result<(), connection::Error> {
+        self.api.set_stream_group_max_data(group, max_data)
+    }
+
+    /// Sets the urgency of a group, from 0 (most important) to 7
+    ///
+    /// Once groups are created, each group receives a share of the transmission capacity that
+    /// is derived from its urgency by the stream scheduler. Streams which didn't join a group
+    /// share the capacity of the default urgency of 3.
+    #[inline]
+    pub fn set_stream_group_urgency(
+        &self,
+        group: group::Id,
+        urgency: u8,
+    ) -> Result<(), connection::Error> {
+        self.api.set_stream_group_urgency(group, urgency)
+    }
+
+    /// Sets whether a stream is sent incrementally or sequentially
+    ///
+    /// Incremental streams share the transmission capacity of their class in round robin order,
+    /// which is the default. Non-incremental streams are sent one at a time in the order they
+    /// were queued, and hold back the other streams of their class until they have no more
+    /// data to send.
+    #[inline]
+    pub fn set_stream_incremental(
+        &self,
+        stream_id: StreamId,
+        incremental: bool,
+    ) -> Result<(), StreamError> {
+        self.api.set_stream_incremental(stream_id, incremental)
+    }
+
+    /// Resets all streams of a group with the provided error code
+    ///
+    /// Both directions of each stream are reset. Streams which join the group afterwards are
+    /// reset as well.
+    #[inline]
+    pub fn reset_stream_group(
+        &self,
+        group: group::Id,
+        error: application::Error,
+    ) -> Result<(), connection::Error> {
+        self.api.reset_stream_group(group, error)
+    }
+
     #[inline]
     pub fn server_name(&self) -> Result<Option<ServerName>, connection::Error> {
         self.api.server_name()
@@ -175,6 +255,21 @@ impl Connection {
         self.api.application_protocol()
     }
 
+    #[inline]
+    pub fn peer_certificates(&self) -> Result<Vec<Bytes>, connection::Error> {
+        self.api.peer_certificates()
+    }
+
+    #[inline]
+    pub fn peer_application_settings(&self) -> Result<Option<Bytes>, connection::Error> {
+        self.api.peer_application_settings()
+    }
+
+    #[inline]
+    pub fn handshake_info(&self) -> Result<connection::HandshakeInfo, connection::Error> {
+        self.api.handshake_info()
+    }
+
     #[inline]
     pub fn id(&self) -> u64 {
         self.api.id()
@@ -189,6 +284,16 @@ impl Connection {
         self.api.keep_alive(enabled)
     }
 
+    #[inline]
+    pub fn migrate(&self, local_address: SocketAddress) -> Result<(), migration::Error> {
+        self.api.migrate(local_address)
+    }
+
+    #[inline]
+    pub fn export_state(&self) -> Result<transfer::State, transfer::Error> {
+        self.api.export_state()
+    }
+
     #[inline]
     pub fn local_address(&self) -> Result<SocketAddress, connection::Error> {
         self.api.local_address()
@@ -199,6 +304,32 @@ impl Connection {
         self.api.remote_address()
     }
 
+    #[inline]
+    pub fn arena_stats(&self) -> Result<connection::arena::Stats, connection::Error> {
+        self.api.arena_stats()
+    }
+
+    #[inline]
+    pub fn memory_usage(&self) -> Result<connection::memory::Usage, connection::Error> {
+        self.api.memory_usage()
+    }
+
+    #[inline]
+    pub fn handshake_timing(&self) -> Result<connection::HandshakeTiming, connection::Error> {
+        self.api.handshake_timing()
+    }
+
+    #[inline]
+    pub fn correlation_id(&self) -> Result<connection::CorrelationId, connection::Error> {
+        self.api.correlation_id()
+    }
+
+    #[cfg(feature = "state-snapshot")]
+    #[inline]
+    pub fn snapshot(&self) -> Result<connection::snapshot::Snapshot, connection::Error> {
+        self.api.snapshot()
+    }
+
     #[inline]
     pub fn query_event_context(&self, query: &mut dyn Query) -> Result<(), connection::Error> {
         self.api.query_event_context(query)
@@ -212,6 +343,16 @@ impl Connection {
         self.api.query_event_context_mut(query)
     }
 
+    #[inline]
+    pub fn query_extensions(&self, query: &mut dyn Query) -> Result<(), connection::Error> {
+        self.api.query_extensions(query)
+    }
+
+    #[inline]
+    pub fn query_extensions_mut(&self, query: &mut dyn QueryMut) -> Result<(), connection::Error> {
+        self.api.query_extensions_mut(query)
+    }
+
     #[inline]
     pub fn datagram_mut(&self, query: &mut dyn QueryMut) -> Result<(), connection::Error> {
         self.api.datagram_mut(query)
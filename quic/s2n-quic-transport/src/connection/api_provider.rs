@@ -6,9 +6,9 @@
 
 use crate::{
     connection,
-    stream::{Stream, StreamError},
+    stream::{group, Stream, StreamError},
 };
-use alloc::sync::Arc;
+use alloc::{sync::Arc, vec::Vec};
 use bytes::Bytes;
 use core::{
     sync::atomic::AtomicUsize,
@@ -17,9 +17,12 @@ use core::{
 use s2n_quic_core::{
     application,
     application::ServerName,
+    connection::transfer,
     inet::SocketAddress,
+    path::migration,
     query::{Query, QueryMut},
     stream::{ops, StreamId, StreamType},
+    varint::VarInt,
 };
 
 /// A dynamically dispatched connection API
@@ -54,23 +57,76 @@ pub(crate) trait ConnectionApiProvider: Sync + Send {
 
     fn close_connection(&self, code: Option<application::Error>);
 
+    fn create_stream_group(&self, max_data: VarInt) -> Result<group::Id, connection::Error>;
+
+    fn join_stream_group(&self, group: group::Id, stream_id: StreamId) -> Result<(), StreamError>;
+
+    fn set_stream_group_max_data(
+        &self,
+        group: group::Id,
+        max_data: VarInt,
+    ) -> Result<(), connection::Error>;
+
+    fn set_stream_group_urgency(
+        &self,
+        group: group::Id,
+        urgency: u8,
+    ) -> Result<(), connection::Error>;
+
+    fn set_stream_incremental(
+        &self,
+        stream_id: StreamId,
+        incremental: bool,
+    ) -> Result<(), StreamError>;
+
+    fn reset_stream_group(
+        &self,
+        group: group::Id,
+        error: application::Error,
+    ) -> Result<(), connection::Error>;
+
     fn server_name(&self) -> Result<Option<ServerName>, connection::Error>;
 
     fn application_protocol(&self) -> Result<Bytes, connection::Error>;
 
+    fn peer_certificates(&self) -> Result<Vec<Bytes>, connection::Error>;
+
+    fn peer_application_settings(&self) -> Result<Option<Bytes>, connection::Error>;
+
+    fn handshake_info(&self) -> Result<connection::HandshakeInfo, connection::Error>;
+
     fn id(&self) -> u64;
 
     fn ping(&self) -> Result<(), connection::Error>;
 
     fn keep_alive(&self, enabled: bool) -> Result<(), connection::Error>;
 
+    fn migrate(&self, local_address: SocketAddress) -> Result<(), migration::Error>;
+
+    fn export_state(&self) -> Result<transfer::State, transfer::Error>;
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error>;
 
     fn remote_address(&self) -> Result<SocketAddress, connection::Error>;
 
+    fn arena_stats(&self) -> Result<connection::arena::Stats, connection::Error>;
+
+    fn memory_usage(&self) -> Result<connection::memory::Usage, connection::Error>;
+
+    fn handshake_timing(&self) -> Result<connection::HandshakeTiming, connection::Error>;
+
+    fn correlation_id(&self) -> Result<connection::CorrelationId, connection::Error>;
+
+    #[cfg(feature = "state-snapshot")]
+    fn snapshot(&self) -> Result<connection::snapshot::Snapshot, connection::Error>;
+
     fn query_event_context(&self, query: &mut dyn Query) -> Result<(), connection::Error>;
 
     fn query_event_context_mut(&self, query: &mut dyn QueryMut) -> Result<(), connection::Error>;
 
+    fn query_extensions(&self, query: &mut dyn Query) -> Result<(), connection::Error>;
+
+    fn query_extensions_mut(&self, query: &mut dyn QueryMut) -> Result<(), connection::Error>;
+
     fn datagram_mut(&self, query: &mut dyn QueryMut) -> Result<(), connection::Error>;
 }
@@ -17,7 +17,7 @@ use crate::{
     },
     stream,
 };
-use alloc::{collections::BTreeMap, sync::Arc};
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
 use bytes::Bytes;
 use core::{
     cell::Cell,
@@ -33,13 +33,19 @@ use intrusive_collections::{
 use s2n_quic_core::{
     application,
     application::ServerName,
+    connection::transfer,
     event::supervisor,
     inet::SocketAddress,
+    path::migration,
     query::{Query, QueryMut},
     recovery::K_GRANULARITY,
     time::Timestamp,
     transport,
+    varint::VarInt,
 };
+use timer_wheel::TimerWheel;
+
+mod timer_wheel;
 
 // Intrusive list adapter for managing the list of `done` connections
 intrusive_adapter!(DoneConnectionsAdapter<C, L> = Arc<ConnectionNode<C, L>>: ConnectionNode<C, L> {
@@ -58,9 +64,9 @@ intrusive_adapter!(WaitingForConnectionIdAdapter<C, L> = Arc<ConnectionNode<C, L
     waiting_for_connection_id_link: LinkedListLink
 } where C: connection::Trait, L: connection::Lock<C>);
 
-// Intrusive red black tree adapter for managing a list of `waiting_for_timeout` connections
+// Intrusive list adapter for managing the timer wheel slots of `waiting_for_timeout` connections
 intrusive_adapter!(WaitingForTimeoutAdapter<C, L> = Arc<ConnectionNode<C, L>>: ConnectionNode<C, L> {
-    waiting_for_timeout_link: RBTreeLink
+    waiting_for_timeout_link: LinkedListLink
 } where C: connection::Trait, L: connection::Lock<C>);
 
 // Intrusive red black tree adapter for managing all connections in a tree for
@@ -86,9 +92,11 @@ struct ConnectionNode<C: connection::Trait, L: connection::Lock<C>> {
     /// Allows the Connection to be part of the `waiting_for_connection_id` collection
     waiting_for_connection_id_link: LinkedListLink,
     /// Allows the Connection to be part of the `waiting_for_timeout` collection
-    waiting_for_timeout_link: RBTreeLink,
+    waiting_for_timeout_link: LinkedListLink,
     /// The cached time at which the connection will timeout next
     timeout: Cell<Option<Timestamp>>,
+    /// The timer wheel slot the connection is stored in while waiting for a timeout
+    timeout_slot: Cell<usize>,
     /// The count of outstanding application handles
     application_handle_count: AtomicUsize,
     /// The inner connection type
@@ -108,8 +116,9 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionNode<C, L> {
             done_connections_link: LinkedListLink::new(),
             waiting_for_transmission_link: LinkedListLink::new(),
             waiting_for_connection_id_link: LinkedListLink::new(),
-            waiting_for_timeout_link: RBTreeLink::new(),
+            waiting_for_timeout_link: LinkedListLink::new(),
             timeout: Cell::new(None),
+            timeout_slot: Cell::new(0),
             application_handle_count: AtomicUsize::new(0),
             _connection: PhantomData,
         }
@@ -167,7 +176,7 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionNode<C, L> {
     }
 
     #[inline]
-    fn ensure_consistency(&self) {
+    fn ensure_consistency(&self, interests: &ConnectionInterests) {
         if !cfg!(debug_assertions) {
             return;
         }
@@ -189,6 +198,12 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionNode<C, L> {
             return;
         }
 
+        // Without an idle timer, a quiescent connection has no timers armed and waits for
+        // either a peer packet or an application wakeup
+        if interests.idle_timeout_disabled {
+            return;
+        }
+
         assert!(
             self.waiting_for_connection_id_link.is_linked()
                 || self.waiting_for_timeout_link.is_linked()
@@ -198,28 +213,6 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionNode<C, L> {
     }
 }
 
-impl<'a, C: connection::Trait, L: connection::Lock<C>> KeyAdapter<'a>
-    for WaitingForTimeoutAdapter<C, L>
-{
-    type Key = Timestamp;
-
-    fn get_key(&self, node: &'a ConnectionNode<C, L>) -> Timestamp {
-        if let Some(timeout) = node.timeout.get() {
-            timeout
-        } else if cfg!(debug_assertions) {
-            panic!("node was queried for timeout but none was set")
-        } else {
-            unsafe {
-                // Safety: this will simply move the connection to the beginning of the queue
-                // to ensure the timeout value is properly updated.
-                //
-                // Assuming everything is tested properly, this should never be reached
-                Timestamp::from_duration(core::time::Duration::from_secs(0))
-            }
-        }
-    }
-}
-
 // This is required to build an intrusive `RBTree` of `ConnectionNode`s which
 // utilizes `ConnectionId`s as a key.
 impl<'a, C: connection::Trait, L: connection::Lock<C>> KeyAdapter<'a>
@@ -301,6 +294,53 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionApiProvider for Con
         });
     }
 
+    fn create_stream_group(
+        &self,
+        max_data: VarInt,
+    ) -> Result<stream::group::Id, connection::Error> {
+        self.api_write_call(|conn| conn.create_stream_group(max_data))
+    }
+
+    fn join_stream_group(
+        &self,
+        group: stream::group::Id,
+        stream_id: stream::StreamId,
+    ) -> Result<(), stream::StreamError> {
+        self.api_write_call(|conn| conn.join_stream_group(group, stream_id))
+    }
+
+    fn set_stream_group_max_data(
+        &self,
+        group: stream::group::Id,
+        max_data: VarInt,
+    ) -> Result<(), connection::Error> {
+        self.api_write_call(|conn| conn.set_stream_group_max_data(group, max_data))
+    }
+
+    fn set_stream_group_urgency(
+        &self,
+        group: stream::group::Id,
+        urgency: u8,
+    ) -> Result<(), connection::Error> {
+        self.api_write_call(|conn| conn.set_stream_group_urgency(group, urgency))
+    }
+
+    fn set_stream_incremental(
+        &self,
+        stream_id: stream::StreamId,
+        incremental: bool,
+    ) -> Result<(), stream::StreamError> {
+        self.api_write_call(|conn| conn.set_stream_incremental(stream_id, incremental))
+    }
+
+    fn reset_stream_group(
+        &self,
+        group: stream::group::Id,
+        error: application::Error,
+    ) -> Result<(), connection::Error> {
+        self.api_write_call(|conn| conn.reset_stream_group(group, error))
+    }
+
     fn server_name(&self) -> Result<Option<ServerName>, connection::Error> {
         self.api_read_call(|conn| Ok(conn.server_name()))
     }
@@ -309,6 +349,18 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionApiProvider for Con
         self.api_read_call(|conn| Ok(conn.application_protocol()))
     }
 
+    fn peer_certificates(&self) -> Result<Vec<Bytes>, connection::Error> {
+        self.api_read_call(|conn| Ok(conn.peer_certificates()))
+    }
+
+    fn peer_application_settings(&self) -> Result<Option<Bytes>, connection::Error> {
+        self.api_read_call(|conn| Ok(conn.peer_application_settings()))
+    }
+
+    fn handshake_info(&self) -> Result<connection::HandshakeInfo, connection::Error> {
+        self.api_read_call(|conn| Ok(conn.handshake_info()))
+    }
+
     fn id(&self) -> u64 {
         self.internal_connection_id.into()
     }
@@ -321,6 +373,14 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionApiProvider for Con
         self.api_write_call(|conn| conn.keep_alive(enabled))
     }
 
+    fn migrate(&self, local_address: SocketAddress) -> Result<(), migration::Error> {
+        self.api_write_call(|conn| conn.migrate(local_address))
+    }
+
+    fn export_state(&self) -> Result<transfer::State, transfer::Error> {
+        self.api_write_call(|conn| conn.export_state())
+    }
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error> {
         self.api_read_call(|conn| conn.local_address())
     }
@@ -329,6 +389,27 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionApiProvider for Con
         self.api_read_call(|conn| conn.remote_address())
     }
 
+    fn arena_stats(&self) -> Result<connection::arena::Stats, connection::Error> {
+        self.api_read_call(|conn| conn.arena_stats())
+    }
+
+    fn memory_usage(&self) -> Result<connection::memory::Usage, connection::Error> {
+        self.api_read_call(|conn| conn.memory_usage())
+    }
+
+    fn handshake_timing(&self) -> Result<connection::HandshakeTiming, connection::Error> {
+        self.api_read_call(|conn| conn.handshake_timing())
+    }
+
+    fn correlation_id(&self) -> Result<connection::CorrelationId, connection::Error> {
+        self.api_read_call(|conn| conn.correlation_id())
+    }
+
+    #[cfg(feature = "state-snapshot")]
+    fn snapshot(&self) -> Result<connection::snapshot::Snapshot, connection::Error> {
+        self.api_read_call(|conn| conn.snapshot())
+    }
+
     #[inline]
     fn query_event_context(&self, query: &mut dyn Query) -> Result<(), connection::Error> {
         self.api_read_call(|conn| {
@@ -345,6 +426,22 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionApiProvider for Con
         })
     }
 
+    #[inline]
+    fn query_extensions(&self, query: &mut dyn Query) -> Result<(), connection::Error> {
+        self.api_read_call(|conn| {
+            conn.query_extensions(query);
+            Ok(())
+        })
+    }
+
+    #[inline]
+    fn query_extensions_mut(&self, query: &mut dyn QueryMut) -> Result<(), connection::Error> {
+        self.api_write_call(|conn| {
+            conn.query_extensions_mut(query);
+            Ok(())
+        })
+    }
+
     #[inline]
     fn datagram_mut(&self, query: &mut dyn QueryMut) -> Result<(), connection::Error> {
         self.api_write_call(|conn| {
@@ -366,7 +463,7 @@ struct InterestLists<C: connection::Trait, L: connection::Lock<C>> {
     /// Connections which need a new connection ID
     waiting_for_connection_id: LinkedList<WaitingForConnectionIdAdapter<C, L>>,
     /// Connections which are waiting for a timeout to occur
-    waiting_for_timeout: RBTree<WaitingForTimeoutAdapter<C, L>>,
+    waiting_for_timeout: TimerWheel<C, L>,
     waiting_for_open: BTreeMap<InternalConnectionId, ConnectionSender>,
     /// Inflight handshake count
     handshake_connections: usize,
@@ -380,7 +477,7 @@ impl<C: connection::Trait, L: connection::Lock<C>> InterestLists<C, L> {
             done_connections: LinkedList::new(DoneConnectionsAdapter::new()),
             waiting_for_transmission: LinkedList::new(WaitingForTransmissionAdapter::new()),
             waiting_for_connection_id: LinkedList::new(WaitingForConnectionIdAdapter::new()),
-            waiting_for_timeout: RBTree::new(WaitingForTimeoutAdapter::new()),
+            waiting_for_timeout: TimerWheel::new(),
             waiting_for_open: BTreeMap::new(),
             handshake_connections: 0,
             connection_count: 0,
@@ -465,7 +562,12 @@ impl<C: connection::Trait, L: connection::Lock<C>> InterestLists<C, L> {
         if node.timeout.get() != interests.timeout {
             // remove the connection if it's currently linked
             if node.waiting_for_timeout_link.is_linked() {
-                remove_interest!(waiting_for_timeout);
+                unsafe {
+                    // Safety: We know that the node is only ever part of the timer wheel.
+                    // While elements are in the list of expired connections, they always
+                    // get unlinked from it while their interest is updated.
+                    self.waiting_for_timeout.remove(node);
+                }
             }
             // set the new timeout value
             node.timeout.set(interests.timeout);
@@ -481,6 +583,24 @@ impl<C: connection::Trait, L: connection::Lock<C>> InterestLists<C, L> {
             );
         }
 
+        // Close client connections which are still handshaking if the application is no longer
+        // waiting for them, e.g. when the connection attempt was raced across multiple addresses
+        if <C::Config as endpoint::Config>::ENDPOINT_TYPE.is_client()
+            && !interests.accept
+            && !interests.finalization
+        {
+            let is_abandoned = self
+                .waiting_for_open
+                .get(&id)
+                .map_or(false, |sender| sender.is_canceled());
+
+            if is_abandoned {
+                self.waiting_for_open.remove(&id);
+                node.inner
+                    .write(|conn| conn.application_close(Some(application::Error::UNKNOWN)))?;
+            }
+        }
+
         // Accepted connections are only automatically pushed into the accepted connections queue.
         if interests.accept {
             node.inner.write(|conn| {
@@ -552,7 +672,7 @@ impl<C: connection::Trait, L: connection::Lock<C>> InterestLists<C, L> {
             }
         }
 
-        node.ensure_consistency();
+        node.ensure_consistency(&interests);
 
         Ok(())
     }
@@ -579,7 +699,15 @@ impl<C: connection::Trait, L: connection::Lock<C>> InterestLists<C, L> {
 
         remove_connection_from_list!(waiting_for_transmission, waiting_for_transmission_link);
         remove_connection_from_list!(waiting_for_connection_id, waiting_for_connection_id_link);
-        remove_connection_from_list!(waiting_for_timeout, waiting_for_timeout_link);
+
+        if connection.waiting_for_timeout_link.is_linked() {
+            unsafe {
+                // Safety: We know that the Connection is part of the timer wheel, because it
+                // is linked, and we never place Connections in other lists when
+                // `finalize_done_connections` is called.
+                self.waiting_for_timeout.remove(connection);
+            }
+        }
 
         self.connection_count -= 1;
     }
@@ -732,14 +860,7 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionContainer<C, L> {
 
     /// Returns the next `Timestamp` at which any contained connections will expire
     pub fn next_expiration(&self) -> Option<Timestamp> {
-        let cursor = self.interest_lists.waiting_for_timeout.front();
-        let node = cursor.get()?;
-        let timeout = node.timeout.get();
-        debug_assert!(
-            timeout.is_some(),
-            "a connection should only be in the timeout list when the timeout field is set"
-        );
-        timeout
+        self.interest_lists.waiting_for_timeout.next_expiration()
     }
 
     /// Insert a new server Connection into the container
@@ -788,6 +909,13 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionContainer<C, L> {
         let connection = L::new(connection);
         let connection = Arc::new(ConnectionNode::new(connection, internal_connection_id));
 
+        // Increment the inflight handshakes and total connection counter because we have accepted a new connection.
+        //
+        // This happens before updating the interests, since connections which were transferred
+        // from another endpoint already completed the handshake and are accepted immediately.
+        self.interest_lists.handshake_connections += 1;
+        self.interest_lists.connection_count += 1;
+
         if self
             .interest_lists
             .update_interests(
@@ -799,10 +927,10 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionContainer<C, L> {
             .is_ok()
         {
             self.connection_map.insert(connection);
-            // Increment the inflight handshakes and total connection counter because we have accepted a new connection
-            self.interest_lists.handshake_connections += 1;
-            self.interest_lists.connection_count += 1;
             self.ensure_counter_consistency();
+        } else {
+            self.interest_lists.handshake_connections -= 1;
+            self.interest_lists.connection_count -= 1;
         }
     }
 
@@ -918,6 +1046,22 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionContainer<C, L> {
         }
     }
 
+    /// Executes the given function on every `Connection` in the container
+    pub fn iterate_all<F>(&mut self, mut func: F)
+    where
+        F: FnMut(&mut C),
+    {
+        let ids: Vec<_> = self
+            .connection_map
+            .iter()
+            .map(|node| node.internal_connection_id)
+            .collect();
+
+        for id in ids {
+            self.with_connection(id, |conn| func(conn));
+        }
+    }
+
     /// Iterates over all `Connection`s which are waiting for transmission,
     /// and executes the given function on each `Connection`
     pub fn iterate_transmission_list<F>(&mut self, mut func: F)
@@ -952,30 +1096,10 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionContainer<C, L> {
     where
         F: FnMut(&mut C, &supervisor::Context),
     {
-        loop {
-            let mut cursor = self.interest_lists.waiting_for_timeout.front_mut();
-            let connection = if let Some(connection) = cursor.get() {
-                connection
-            } else {
-                break;
-            };
-
-            match connection.timeout.get() {
-                Some(v) if !v.has_elapsed(now) => break,
-                Some(_) => {}
-                None => {
-                    debug_assert!(false, "connection was inserted without a timeout specified");
-
-                    let conn = cursor.remove().unwrap();
-                    conn.timeout.set(None);
-                    continue;
-                }
-            }
-
-            let connection = cursor
-                .remove()
-                .expect("list capacity was already checked in the `while` condition");
+        let mut expired = self.interest_lists.waiting_for_timeout.take_expired(now);
+        let mut cursor = expired.front_mut();
 
+        while let Some(connection) = cursor.remove() {
             // Note that while we iterate over the intrusive lists here
             // `Connection` is part of no list anymore, since it also got dropped
             // from list that is described by the `cursor`.
@@ -993,6 +1117,9 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionContainer<C, L> {
                     &remote_address,
                     conn.is_handshaking(),
                 );
+                let unvalidated_bytes = conn.unvalidated_bytes();
+                let context = context
+                    .with_unvalidated_bytes(unvalidated_bytes.received, unvalidated_bytes.sent);
                 func(conn, &context);
                 conn.interests()
             }) {
@@ -17,7 +17,9 @@ use core::{
     time::Duration,
 };
 use s2n_quic_core::{
-    application, event,
+    application,
+    connection::transfer,
+    event,
     event::builder::DatagramDropReason,
     inet::{DatagramInfo, SocketAddress},
     io::tx,
@@ -29,9 +31,10 @@ use s2n_quic_core::{
         version_negotiation::ProtectedVersionNegotiation,
         zero_rtt::ProtectedZeroRtt,
     },
-    path::MaxMtu,
+    path::{migration, MaxMtu},
     query,
     time::{Timer, Timestamp},
+    varint::VarInt,
 };
 use std::sync::Mutex;
 
@@ -97,6 +100,7 @@ impl connection::Trait for TestConnection {
         &mut self,
         _connection_id_format: &mut <Self::Config as endpoint::Config>::ConnectionIdFormat,
         _stateless_reset_token_generator: &mut <Self::Config as endpoint::Config>::StatelessResetTokenGenerator,
+        _random_generator: &mut <Self::Config as endpoint::Config>::RandomGenerator,
         _timestamp: Timestamp,
     ) -> Result<(), connection::local_id_registry::LocalIdRegistrationError> {
         Ok(())
@@ -129,6 +133,10 @@ impl connection::Trait for TestConnection {
     fn on_wakeup(
         &mut self,
         _timestamp: Timestamp,
+        _congestion_controller_endpoint: &mut <Self::Config as endpoint::Config>::CongestionControllerEndpoint,
+        _mtu_endpoint: &mut <Self::Config as endpoint::Config>::MtuEndpoint,
+        _max_mtu: MaxMtu,
+        _random_generator: &mut <Self::Config as endpoint::Config>::RandomGenerator,
         _subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
         _datagram: &mut <Self::Config as endpoint::Config>::DatagramEndpoint,
     ) -> Result<(), connection::Error> {
@@ -232,12 +240,27 @@ impl connection::Trait for TestConnection {
         _datagram: &DatagramInfo,
         _congestion_controller_endpoint: &mut <Self::Config as endpoint::Config>::CongestionControllerEndpoint,
         _path_migration: &mut <Self::Config as endpoint::Config>::PathMigrationValidator,
+        _mtu_endpoint: &mut <Self::Config as endpoint::Config>::MtuEndpoint,
         _max_mtu: MaxMtu,
         _subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
     ) -> Result<path::Id, DatagramDropReason> {
         todo!()
     }
 
+    fn check_unvalidated_bytes(
+        &mut self,
+        _timestamp: Timestamp,
+        _subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
+    ) -> Result<(), connection::Error> {
+        Ok(())
+    }
+
+    fn unvalidated_bytes(&self) -> path::UnvalidatedBytes {
+        Default::default()
+    }
+
+    fn on_tenant(&mut self, _tenant: s2n_quic_core::endpoint::tenant::Disabled) {}
+
     /// Returns the Connections interests
     fn interests(&self) -> ConnectionInterests {
         self.interests
@@ -248,6 +271,10 @@ impl connection::Trait for TestConnection {
         123
     }
 
+    fn accepts_cleared_quic_bit(&self) -> bool {
+        false
+    }
+
     fn poll_stream_request(
         &mut self,
         _stream_id: stream::StreamId,
@@ -278,6 +305,53 @@ impl connection::Trait for TestConnection {
         // no-op
     }
 
+    fn create_stream_group(
+        &mut self,
+        _max_data: VarInt,
+    ) -> Result<stream::group::Id, connection::Error> {
+        todo!()
+    }
+
+    fn join_stream_group(
+        &mut self,
+        _group: stream::group::Id,
+        _stream_id: stream::StreamId,
+    ) -> Result<(), stream::StreamError> {
+        todo!()
+    }
+
+    fn set_stream_group_max_data(
+        &mut self,
+        _group: stream::group::Id,
+        _max_data: VarInt,
+    ) -> Result<(), connection::Error> {
+        todo!()
+    }
+
+    fn set_stream_group_urgency(
+        &mut self,
+        _group: stream::group::Id,
+        _urgency: u8,
+    ) -> Result<(), connection::Error> {
+        todo!()
+    }
+
+    fn set_stream_incremental(
+        &mut self,
+        _stream_id: stream::StreamId,
+        _incremental: bool,
+    ) -> Result<(), stream::StreamError> {
+        todo!()
+    }
+
+    fn reset_stream_group(
+        &mut self,
+        _group: stream::group::Id,
+        _error: application::Error,
+    ) -> Result<(), connection::Error> {
+        todo!()
+    }
+
     fn server_name(&self) -> Option<ServerName> {
         todo!()
     }
@@ -286,6 +360,18 @@ impl connection::Trait for TestConnection {
         todo!()
     }
 
+    fn peer_certificates(&self) -> Vec<Bytes> {
+        todo!()
+    }
+
+    fn peer_application_settings(&self) -> Option<Bytes> {
+        todo!()
+    }
+
+    fn handshake_info(&self) -> connection::HandshakeInfo {
+        todo!()
+    }
+
     fn ping(&mut self) -> Result<(), connection::Error> {
         todo!()
     }
@@ -294,6 +380,25 @@ impl connection::Trait for TestConnection {
         todo!()
     }
 
+    fn migrate(&mut self, _local_address: SocketAddress) -> Result<(), migration::Error> {
+        todo!()
+    }
+
+    fn export_state(&mut self) -> Result<transfer::State, transfer::Error> {
+        todo!()
+    }
+
+    fn on_transferred(
+        &mut self,
+        _state: &transfer::State,
+        _timestamp: Timestamp,
+        _random_generator: &mut <Self::Config as endpoint::Config>::RandomGenerator,
+        _subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
+        _datagram_endpoint: &mut <Self::Config as endpoint::Config>::DatagramEndpoint,
+    ) -> Result<(), transfer::Error> {
+        todo!()
+    }
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error> {
         todo!()
     }
@@ -302,6 +407,27 @@ impl connection::Trait for TestConnection {
         Ok(SocketAddress::default())
     }
 
+    fn arena_stats(&self) -> Result<connection::arena::Stats, connection::Error> {
+        todo!()
+    }
+
+    fn memory_usage(&self) -> Result<connection::memory::Usage, connection::Error> {
+        todo!()
+    }
+
+    fn handshake_timing(&self) -> Result<connection::HandshakeTiming, connection::Error> {
+        todo!()
+    }
+
+    fn correlation_id(&self) -> Result<connection::CorrelationId, connection::Error> {
+        todo!()
+    }
+
+    #[cfg(feature = "state-snapshot")]
+    fn snapshot(&self) -> Result<connection::snapshot::Snapshot, connection::Error> {
+        todo!()
+    }
+
     fn error(&self) -> Option<connection::Error> {
         None
     }
@@ -314,6 +440,14 @@ impl connection::Trait for TestConnection {
         todo!()
     }
 
+    fn query_extensions(&self, _query: &mut dyn query::Query) {
+        todo!()
+    }
+
+    fn query_extensions_mut(&mut self, _query: &mut dyn query::QueryMut) {
+        todo!()
+    }
+
     fn datagram_mut(&mut self, _query: &mut dyn query::QueryMut) {
         todo!()
     }
@@ -389,6 +523,7 @@ enum Operation {
         transmission: bool,
         new_connection_id: bool,
         timeout: Option<u16>,
+        idle_timeout_disabled: bool,
     },
     CloseApp,
     Receive,
@@ -406,7 +541,8 @@ fn container_test() {
     check!().with_type::<Vec<Operation>>().for_each(|ops| {
         let mut id_gen = InternalConnectionIdGenerator::new();
         let mut connections = vec![];
-        let (handle, acceptor, connector, _close_handle) = endpoint::handle::Handle::new(100);
+        let (handle, acceptor, connector, _close_handle, _drain_handle, _import_receiver) =
+            endpoint::handle::Handle::new(100);
         let (waker, _wake_count) = futures_test::task::new_count_waker();
         let mut now = unsafe { Timestamp::from_duration(Duration::from_secs(0)) };
 
@@ -436,6 +572,7 @@ fn container_test() {
                     transmission,
                     new_connection_id,
                     timeout,
+                    idle_timeout_disabled,
                 } => {
                     if connections.is_empty() {
                         continue;
@@ -468,9 +605,15 @@ fn container_test() {
                         i.transmission = *transmission;
                         i.new_connection_id = *new_connection_id;
                         i.timeout = timeout.map(|ms| now + Duration::from_millis(ms as _));
-
-                        // we need to express at least one interest to ensure progress
-                        if !(i.transmission || i.new_connection_id || i.timeout.is_some()) {
+                        i.idle_timeout_disabled = *idle_timeout_disabled;
+
+                        // we need to express at least one interest to ensure progress, unless
+                        // the connection can remain idle
+                        if !(i.transmission
+                            || i.new_connection_id
+                            || i.timeout.is_some()
+                            || i.idle_timeout_disabled)
+                        {
                             i.transmission = true;
                         }
                     });
@@ -573,3 +716,85 @@ fn container_test() {
         assert!(connections.next().is_none());
     });
 }
+
+#[derive(Debug, TypeGenerator)]
+enum TimerOperation {
+    Insert { timeout: Option<u64> },
+    Update { index: usize, timeout: Option<u64> },
+    Advance(u64),
+}
+
+/// The maximum offset of a timeout or time advancement, which is larger than the range of the
+/// timer wheel to exercise the overflow list
+const MAX_TIMER_OFFSET_MICROS: u64 = 1 << 48;
+
+#[test]
+fn timer_wheel_test() {
+    check!().with_type::<Vec<TimerOperation>>().for_each(|ops| {
+        let mut id_gen = InternalConnectionIdGenerator::new();
+        let mut connections = vec![];
+        let (_handle, acceptor, connector, _close_handle, _drain_handle, _import_receiver) =
+            endpoint::handle::Handle::new(100);
+        let mut now = unsafe { Timestamp::from_duration(Duration::from_secs(1)) };
+
+        let mut container: ConnectionContainer<TestConnection, TestLock> =
+            ConnectionContainer::new(acceptor, connector);
+
+        let to_timeout = |now: Timestamp, timeout: &Option<u64>| {
+            timeout.map(|micros| now + Duration::from_micros(micros % MAX_TIMER_OFFSET_MICROS))
+        };
+
+        for op in ops.iter() {
+            match op {
+                TimerOperation::Insert { timeout } => {
+                    let id = id_gen.generate_id();
+                    container.insert_connection(TestConnection::default(), id);
+                    connections.push(id);
+
+                    let timeout = to_timeout(now, timeout);
+                    container.with_connection(id, |conn| {
+                        conn.interests.timeout = timeout;
+                    });
+                }
+                TimerOperation::Update { index, timeout } => {
+                    if connections.is_empty() {
+                        continue;
+                    }
+                    let id = connections[index % connections.len()];
+
+                    let timeout = to_timeout(now, timeout);
+                    container.with_connection(id, |conn| {
+                        conn.interests.timeout = timeout;
+                    });
+                }
+                TimerOperation::Advance(micros) => {
+                    now += Duration::from_micros(micros % MAX_TIMER_OFFSET_MICROS);
+                    container.iterate_timeout_list(now, |conn, _context| {
+                        let timeout = conn.interests.timeout.take().unwrap();
+                        assert!(timeout.has_elapsed(now));
+                    });
+                }
+            }
+
+            // all of the elapsed timeouts should have been expired and the next
+            // expiration should never be later than any of the remaining timeouts
+            let mut earliest = None;
+            for id in connections.iter() {
+                container.with_connection(*id, |conn| {
+                    if let Some(timeout) = conn.interests.timeout {
+                        if matches!(op, TimerOperation::Advance(_)) {
+                            assert!(!timeout.has_elapsed(now));
+                        }
+                        earliest = Some(earliest.map_or(timeout, |e: Timestamp| e.min(timeout)));
+                    }
+                });
+            }
+
+            match (container.next_expiration(), earliest) {
+                (Some(next), Some(earliest)) => assert!(next <= earliest),
+                (None, None) => {}
+                (next, earliest) => panic!("expected {:?}, got {:?}", earliest, next),
+            }
+        }
+    });
+}
@@ -0,0 +1,306 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A hierarchical timer wheel for the connections which are waiting for a timeout
+//!
+//! Each connection combines all of its timers (PTO, idle, pacing, ACK delay, etc.) into a
+//! single timeout, which places the connection into one of the wheel's slots. Inserting and
+//! removing a connection is O(1), regardless of the number of connections being tracked,
+//! which avoids rebalancing a tree each time a connection updates its timers.
+//!
+//! The wheel is made up of `LEVELS` levels of `SLOTS` slots. Each slot in the first level
+//! covers a single tick of 1ms and each slot in the following levels covers all of the slots
+//! in the previous level. As time advances, the connections in the higher levels are moved
+//! down until they reach the first level, at which point their exact timeout is used. This
+//! means sub-millisecond timers, like the ones set by the pacer, aren't rounded to the tick.
+
+use super::{ConnectionNode, WaitingForTimeoutAdapter};
+use crate::connection;
+use alloc::{sync::Arc, vec, vec::Vec};
+use core::{cell::Cell, time::Duration};
+use intrusive_collections::LinkedList;
+use s2n_quic_core::time::Timestamp;
+
+/// The number of bits used to index the slots in a level
+const LEVEL_BITS: u32 = 6;
+
+/// The number of slots in each level
+const SLOTS: usize = 1 << LEVEL_BITS;
+
+/// The number of levels in the wheel
+///
+/// With a tick of 1ms, the wheel covers 2^36ms, which is just over 2 years. Timeouts beyond
+/// that are placed in the overflow list until the wheel gets closer to them.
+const LEVELS: usize = 6;
+
+/// The number of ticks covered by the wheel
+const WHEEL_TICKS: u64 = 1 << (LEVEL_BITS * LEVELS as u32);
+
+type List<C, L> = LinkedList<WaitingForTimeoutAdapter<C, L>>;
+
+/// The location of a list in the wheel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Slot {
+    Level { level: usize, slot: usize },
+    Overflow,
+}
+
+pub(super) struct TimerWheel<C: connection::Trait, L: connection::Lock<C>> {
+    /// The lists of connections for each slot, stored level by level
+    slots: Vec<List<C, L>>,
+    /// A bit set of the non-empty slots for each level
+    occupied: [u64; LEVELS],
+    /// The earliest timeout of the connections in each slot of the first level
+    ///
+    /// This is cleared when the earliest connection is removed from the slot and lazily
+    /// recomputed the next time it's needed, which avoids scanning the slot on every call to
+    /// `next_expiration`.
+    earliest: Vec<Cell<Option<Timestamp>>>,
+    /// Connections with a timeout beyond the range of the wheel
+    overflow: List<C, L>,
+    /// The tick the wheel has been advanced to
+    elapsed: u64,
+    /// The timestamp of the tick 0
+    ///
+    /// This is set by the first timeout inserted in the wheel.
+    epoch: Option<Timestamp>,
+}
+
+impl<C: connection::Trait, L: connection::Lock<C>> TimerWheel<C, L> {
+    pub fn new() -> Self {
+        let mut slots = Vec::with_capacity(LEVELS * SLOTS);
+        slots.resize_with(LEVELS * SLOTS, || {
+            LinkedList::new(WaitingForTimeoutAdapter::new())
+        });
+
+        Self {
+            slots,
+            occupied: [0; LEVELS],
+            earliest: vec![Cell::new(None); SLOTS],
+            overflow: LinkedList::new(WaitingForTimeoutAdapter::new()),
+            elapsed: 0,
+            epoch: None,
+        }
+    }
+
+    /// Inserts a connection into the wheel based on its current timeout
+    pub fn insert(&mut self, node: Arc<ConnectionNode<C, L>>) {
+        let timeout = node
+            .timeout
+            .get()
+            .expect("connections should only be inserted with a timeout");
+        let epoch = *self.epoch.get_or_insert(timeout);
+
+        // timeouts which have already passed are placed in the current tick
+        let tick = self.elapsed.max(Self::tick(epoch, timeout));
+        let slot = self.slot_for(tick);
+        node.timeout_slot.set(slot.index());
+
+        match slot {
+            Slot::Level { level, slot } => {
+                if level == 0 {
+                    let earliest = &self.earliest[slot];
+                    if self.slots[slot].is_empty() {
+                        earliest.set(Some(timeout));
+                    } else if let Some(current) = earliest.get() {
+                        earliest.set(Some(current.min(timeout)));
+                    }
+                }
+
+                self.occupied[level] |= 1 << slot;
+                self.slots[level * SLOTS + slot].push_back(node);
+            }
+            Slot::Overflow => self.overflow.push_back(node),
+        }
+    }
+
+    /// Removes a connection from the wheel
+    ///
+    /// # Safety
+    ///
+    /// The connection must be linked in the wheel
+    pub unsafe fn remove(&mut self, node: &ConnectionNode<C, L>) {
+        let ptr = node as *const ConnectionNode<C, L>;
+        let index = node.timeout_slot.get();
+
+        if index == OVERFLOW_INDEX {
+            let removed = self.overflow.cursor_mut_from_ptr(ptr).remove();
+            debug_assert!(removed.is_some());
+            return;
+        }
+
+        let list = &mut self.slots[index];
+        let removed = list.cursor_mut_from_ptr(ptr).remove();
+        debug_assert!(removed.is_some());
+
+        if list.is_empty() {
+            self.occupied[index / SLOTS] &= !(1 << (index % SLOTS));
+        }
+
+        // the earliest timeout of the slot needs to be recomputed if it was just removed
+        if let Some(earliest) = self.earliest.get(index) {
+            if node.timeout.get() <= earliest.get() {
+                earliest.set(None);
+            }
+        }
+    }
+
+    /// Returns the next time the wheel needs to be advanced
+    ///
+    /// If the next occupied slot is in the first level, this is the exact timeout of the
+    /// earliest connection. Otherwise, it's the time at which the connections need to be moved
+    /// to a lower level, which is never later than any of their timeouts.
+    pub fn next_expiration(&self) -> Option<Timestamp> {
+        let epoch = self.epoch?;
+        let (slot, start) = self.next_slot()?;
+
+        match slot {
+            Slot::Level { level: 0, slot } => {
+                let earliest = &self.earliest[slot];
+                if earliest.get().is_none() {
+                    let timeout = self.slots[slot]
+                        .iter()
+                        .filter_map(|node| node.timeout.get())
+                        .min();
+                    earliest.set(timeout);
+                }
+                earliest.get()
+            }
+            _ => Some(epoch + Duration::from_millis(start)),
+        }
+    }
+
+    /// Advances the wheel to `now` and returns all of the connections which have expired
+    ///
+    /// The connections in the returned list are still linked and need to be removed from
+    /// the list before updating their interests.
+    pub fn take_expired(&mut self, now: Timestamp) -> List<C, L> {
+        let mut expired = LinkedList::new(WaitingForTimeoutAdapter::new());
+
+        let epoch = if let Some(epoch) = self.epoch {
+            epoch
+        } else {
+            return expired;
+        };
+        let now_tick = Self::tick(epoch, now);
+
+        while let Some((slot, start)) = self.next_slot() {
+            if start > now_tick {
+                break;
+            }
+
+            debug_assert!(start >= self.elapsed);
+            self.elapsed = start;
+
+            let mut list = match slot {
+                Slot::Level { level, slot } => {
+                    self.occupied[level] &= !(1 << slot);
+                    if level == 0 {
+                        self.earliest[slot].set(None);
+                    }
+                    self.slots[level * SLOTS + slot].take()
+                }
+                Slot::Overflow => self.overflow.take(),
+            };
+
+            while let Some(node) = list.front_mut().remove() {
+                debug_assert!(
+                    node.timeout.get().is_some(),
+                    "connection was inserted without a timeout specified"
+                );
+
+                let is_expired = matches!(slot, Slot::Level { level: 0, .. })
+                    && node.timeout.get().map_or(true, |t| t.has_elapsed(now));
+
+                if is_expired {
+                    expired.push_back(node);
+                } else {
+                    // move the connection to a lower level
+                    self.insert(node);
+                }
+            }
+
+            // the connections remaining in the current tick haven't expired yet
+            if start == now_tick && matches!(slot, Slot::Level { level: 0, .. }) {
+                break;
+            }
+        }
+
+        self.elapsed = self.elapsed.max(now_tick);
+
+        expired
+    }
+
+    /// Returns the next non-empty slot and the tick it starts at
+    ///
+    /// If multiple slots start at the same tick, the slot in the highest level is returned
+    /// first so its connections are moved down before the lower levels are expired.
+    fn next_slot(&self) -> Option<(Slot, u64)> {
+        let mut next = None;
+
+        for level in (0..LEVELS).rev() {
+            let occupied = self.occupied[level];
+            if occupied == 0 {
+                continue;
+            }
+
+            let shift = LEVEL_BITS * level as u32;
+            let slot = occupied.trailing_zeros() as usize;
+
+            debug_assert!(
+                slot as u64 >= (self.elapsed >> shift) & (SLOTS as u64 - 1),
+                "slots behind the wheel should have been advanced"
+            );
+
+            let level_start = self.elapsed & !((1u64 << (shift + LEVEL_BITS)) - 1);
+            let start = level_start + ((slot as u64) << shift);
+
+            match next {
+                Some((_, next_start)) if next_start <= start => {}
+                _ => next = Some((Slot::Level { level, slot }, start)),
+            }
+        }
+
+        if next.is_none() && !self.overflow.is_empty() {
+            // the overflow list is checked once the wheel wraps around
+            let start = (self.elapsed | (WHEEL_TICKS - 1)) + 1;
+            next = Some((Slot::Overflow, start));
+        }
+
+        next
+    }
+
+    /// Returns the slot for the given tick
+    fn slot_for(&self, tick: u64) -> Slot {
+        debug_assert!(tick >= self.elapsed);
+
+        // the highest bit which differs from the current tick determines the level
+        let masked = (self.elapsed ^ tick) | (SLOTS as u64 - 1);
+
+        if masked >= WHEEL_TICKS {
+            return Slot::Overflow;
+        }
+
+        let significant = 63 - masked.leading_zeros();
+        let level = (significant / LEVEL_BITS) as usize;
+        let slot = ((tick >> (LEVEL_BITS * level as u32)) & (SLOTS as u64 - 1)) as usize;
+
+        Slot::Level { level, slot }
+    }
+
+    fn tick(epoch: Timestamp, timestamp: Timestamp) -> u64 {
+        timestamp.saturating_duration_since(epoch).as_millis() as u64
+    }
+}
+
+/// The index stored in the connection node for connections in the overflow list
+const OVERFLOW_INDEX: usize = usize::MAX;
+
+impl Slot {
+    fn index(self) -> usize {
+        match self {
+            Self::Level { level, slot } => level * SLOTS + slot,
+            Self::Overflow => OVERFLOW_INDEX,
+        }
+    }
+}
@@ -3,12 +3,25 @@
 
 //! Maps from external connection IDs to internal connection IDs
 
-use crate::connection::{local_id_registry::LocalIdRegistry, InternalConnectionId, PeerIdRegistry};
-use core::{convert::TryFrom as _, hash::BuildHasher};
+use crate::{
+    connection::{
+        local_id_registry::{LocalIdRegistrationError, LocalIdRegistry},
+        InternalConnectionId, PeerIdRegistry,
+    },
+    shared::Mutex,
+};
+use alloc::sync::Arc;
+use core::{
+    convert::TryFrom as _,
+    hash::{BuildHasher, Hasher},
+};
 use hashbrown::hash_map::{Entry, HashMap};
-use s2n_quic_core::{connection, endpoint, random, stateless_reset, time::Timestamp};
+use s2n_quic_core::{
+    connection::{self, transfer},
+    endpoint, inet, random, stateless_reset,
+    time::Timestamp,
+};
 use siphasher::sip::SipHasher13;
-use std::sync::{Arc, Mutex};
 
 // Since the input to the hash function (stateless reset token) come from the peer, we need to
 // ensure that maliciously crafted values do not result in poor bucketing and thus degraded
@@ -129,59 +142,184 @@ impl LocalIdMap {
     }
 }
 
+#[derive(Debug)]
+pub(crate) struct RemoteAddressMap {
+    /// Maps from the remote addresses of connections using zero-length local connection IDs
+    /// to internal connection IDs
+    map: HashMap<inet::SocketAddress, InternalConnectionId, HashState>,
+}
+
+impl RemoteAddressMap {
+    /// Constructs a new `RemoteAddressMap`
+    fn new(hash_state: HashState) -> Self {
+        Self {
+            map: HashMap::with_hasher(hash_state),
+        }
+    }
+
+    /// Gets the `InternalConnectionId` (if any) associated with the given remote address
+    pub(crate) fn get(&self, remote_address: &inet::SocketAddress) -> Option<InternalConnectionId> {
+        self.map.get(&remote_address.unmap()).copied()
+    }
+
+    /// Inserts the given remote address into the map if it is not already in the map,
+    /// otherwise returns an Err
+    pub(crate) fn try_insert(
+        &mut self,
+        remote_address: &inet::SocketAddress,
+        internal_id: InternalConnectionId,
+    ) -> Result<(), ()> {
+        let entry = self.map.entry(remote_address.unmap());
+        match entry {
+            Entry::Occupied(_) => Err(()),
+            Entry::Vacant(entry) => {
+                entry.insert(internal_id);
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes the given remote address from the map
+    pub(crate) fn remove(
+        &mut self,
+        remote_address: &inet::SocketAddress,
+    ) -> Option<InternalConnectionId> {
+        self.map.remove(&remote_address.unmap())
+    }
+}
+
+/// Identifies the handshake of a client, independent of the Destination Connection ID it chose
+///
+/// A client which restarts its first flight with a new Destination Connection ID still uses the
+/// same address, Source Connection ID and token, so the retransmitted Initial packets can be
+/// matched with the handshake which is already in progress.
+///
+/// Clients using zero-length connection IDs aren't tracked, since the handshakes of multiple
+/// connections from the same client endpoint can't be told apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct HandshakeKey {
+    remote_address: inet::SocketAddress,
+    source_connection_id: connection::PeerId,
+    /// The token is only stored as a keyed hash, since it can be large
+    token_hash: u64,
+}
+
 /// Bidirectional map for mapping from initial ID to internal connection ID and vice-versa
 #[derive(Debug)]
 pub(crate) struct InitialIdMap {
     /// Maps from initial id to internal connection ID
     initial_to_internal_id_map: HashMap<connection::InitialId, InternalConnectionId, HashState>,
-    /// Maps from internal connection ID to initial ID
-    internal_to_initial_id_map: HashMap<InternalConnectionId, connection::InitialId, HashState>,
+    /// Maps from the handshake of a client to internal connection ID
+    handshake_to_internal_id_map: HashMap<HandshakeKey, InternalConnectionId, HashState>,
+    /// Maps from internal connection ID to initial ID and handshake
+    internal_to_initial_id_map:
+        HashMap<InternalConnectionId, (connection::InitialId, Option<HandshakeKey>), HashState>,
+    /// Hashes the tokens of the handshakes
+    token_hash_state: HashState,
 }
 
 impl InitialIdMap {
     /// Constructs a new `InitialIdMap`
     fn new(
         initial_to_internal_hash_state: HashState,
+        handshake_to_internal_hash_state: HashState,
         internal_to_initial_hash_state: HashState,
+        token_hash_state: HashState,
     ) -> Self {
         Self {
             initial_to_internal_id_map: HashMap::with_hasher(initial_to_internal_hash_state),
+            handshake_to_internal_id_map: HashMap::with_hasher(handshake_to_internal_hash_state),
             internal_to_initial_id_map: HashMap::with_hasher(internal_to_initial_hash_state),
+            token_hash_state,
         }
     }
 
+    /// Computes the key of the handshake for the given first Initial packet values
+    ///
+    /// Returns `None` if the client uses a zero-length Source Connection ID
+    fn handshake_key(
+        &self,
+        remote_address: &inet::SocketAddress,
+        source_connection_id: &connection::PeerId,
+        token: &[u8],
+    ) -> Option<HandshakeKey> {
+        if source_connection_id.is_empty() {
+            return None;
+        }
+
+        let mut hasher = self.token_hash_state.build_hasher();
+        hasher.write(token);
+
+        Some(HandshakeKey {
+            remote_address: remote_address.unmap(),
+            source_connection_id: *source_connection_id,
+            token_hash: hasher.finish(),
+        })
+    }
+
     /// Gets the `InternalConnectionId` (if any) associated with the given initial id
     fn get(&self, initial_id: &connection::InitialId) -> Option<InternalConnectionId> {
         self.initial_to_internal_id_map.get(initial_id).copied()
     }
 
-    /// Inserts the given `InitialId` into the map if it is not already in the map,
-    /// otherwise returns an Err
+    /// Gets the `InternalConnectionId` (if any) of the handshake which is in progress
+    /// with the given values
+    fn get_handshake(
+        &self,
+        remote_address: &inet::SocketAddress,
+        source_connection_id: &connection::PeerId,
+        token: &[u8],
+    ) -> Option<InternalConnectionId> {
+        let key = self.handshake_key(remote_address, source_connection_id, token)?;
+        self.handshake_to_internal_id_map.get(&key).copied()
+    }
+
+    /// Inserts the given `InitialId` and handshake into the map if neither is already in the
+    /// map, otherwise returns an Err
     fn try_insert(
         &mut self,
         initial_id: connection::InitialId,
+        remote_address: &inet::SocketAddress,
+        source_connection_id: &connection::PeerId,
+        token: &[u8],
         internal_id: InternalConnectionId,
     ) -> Result<(), ()> {
+        let key = self.handshake_key(remote_address, source_connection_id, token);
         let initial_to_internal_id_entry = self.initial_to_internal_id_map.entry(initial_id);
+        let handshake_to_internal_id_entry =
+            key.map(|key| self.handshake_to_internal_id_map.entry(key));
         let internal_to_initial_id_entry = self.internal_to_initial_id_map.entry(internal_id);
 
-        match (initial_to_internal_id_entry, internal_to_initial_id_entry) {
-            (Entry::Occupied(_), _) | (_, Entry::Occupied(_)) => Err(()),
-            (Entry::Vacant(initial_entry), Entry::Vacant(internal_entry)) => {
+        match (
+            initial_to_internal_id_entry,
+            handshake_to_internal_id_entry,
+            internal_to_initial_id_entry,
+        ) {
+            (Entry::Occupied(_), _, _)
+            | (_, Some(Entry::Occupied(_)), _)
+            | (_, _, Entry::Occupied(_)) => Err(()),
+            (Entry::Vacant(initial_entry), handshake_entry, Entry::Vacant(internal_entry)) => {
                 initial_entry.insert(internal_id);
-                internal_entry.insert(initial_id);
+                if let Some(Entry::Vacant(handshake_entry)) = handshake_entry {
+                    handshake_entry.insert(internal_id);
+                }
+                internal_entry.insert((initial_id, key));
                 Ok(())
             }
         }
     }
 
-    /// Removes the `InitialId` associated with the given `InternalConnectionId` from the map
+    /// Removes the `InitialId` and handshake associated with the given `InternalConnectionId`
+    /// from the map
     pub(crate) fn remove(
         &mut self,
         internal_id: &InternalConnectionId,
     ) -> Option<connection::InitialId> {
-        let initial_id = self.internal_to_initial_id_map.remove(internal_id)?;
+        let (initial_id, key) = self.internal_to_initial_id_map.remove(internal_id)?;
         self.initial_to_internal_id_map.remove(&initial_id);
+        if let Some(key) = key {
+            self.handshake_to_internal_id_map.remove(&key);
+        }
         Some(initial_id)
     }
 }
@@ -194,6 +332,9 @@ pub(crate) struct ConnectionIdMapperState {
     pub(crate) stateless_reset_map: StatelessResetMap,
     /// Maps from initial id to internal connection IDs
     pub(crate) initial_id_map: InitialIdMap,
+    /// Maps from remote addresses to the internal IDs of connections using zero-length
+    /// local connection IDs
+    pub(crate) remote_address_map: RemoteAddressMap,
 }
 
 impl ConnectionIdMapperState {
@@ -204,7 +345,10 @@ impl ConnectionIdMapperState {
             initial_id_map: InitialIdMap::new(
                 HashState::new(random_generator),
                 HashState::new(random_generator),
+                HashState::new(random_generator),
+                HashState::new(random_generator),
             ),
+            remote_address_map: RemoteAddressMap::new(HashState::new(random_generator)),
         }
     }
 }
@@ -252,11 +396,49 @@ impl ConnectionIdMapper {
         })
     }
 
-    /// Inserts the given `InitialId` into the map if it is not already in the map,
-    /// otherwise returns an Err
+    /// Looks up the internal Connection ID of the connection using zero-length local
+    /// connection IDs with the given remote address.
+    pub fn lookup_internal_connection_id_by_remote_address(
+        &self,
+        remote_address: &inet::SocketAddress,
+    ) -> Option<InternalConnectionId> {
+        let guard = self
+            .state
+            .lock()
+            .expect("should succeed unless the lock is poisoned");
+        guard.remote_address_map.get(remote_address)
+    }
+
+    /// Looks up the internal Connection ID of the handshake which is in progress with a client
+    /// using the given address, Source Connection ID and token in its first Initial packet.
+    ///
+    /// Initial packets which match a handshake in progress, but not its `InitialId`, are
+    /// duplicates of the first flight of the client with a new Destination Connection ID.
+    /// Handshakes with clients using zero-length Source Connection IDs are never matched.
+    pub fn lookup_handshake(
+        &self,
+        remote_address: &inet::SocketAddress,
+        source_connection_id: &connection::PeerId,
+        token: &[u8],
+    ) -> Option<InternalConnectionId> {
+        debug_assert!(self.endpoint_type.is_server());
+        let guard = self
+            .state
+            .lock()
+            .expect("should succeed unless the lock is poisoned");
+        guard
+            .initial_id_map
+            .get_handshake(remote_address, source_connection_id, token)
+    }
+
+    /// Inserts the given `InitialId` and the handshake of the client into the map if neither
+    /// is already in the map, otherwise returns an Err
     pub fn try_insert_initial_id(
         &mut self,
         initial_id: connection::InitialId,
+        remote_address: &inet::SocketAddress,
+        source_connection_id: &connection::PeerId,
+        token: &[u8],
         internal_id: InternalConnectionId,
     ) -> Result<(), ()> {
         debug_assert!(self.endpoint_type.is_server());
@@ -264,7 +446,13 @@ impl ConnectionIdMapper {
             .state
             .lock()
             .expect("should succeed unless the lock is poisoned");
-        guard.initial_id_map.try_insert(initial_id, internal_id)
+        guard.initial_id_map.try_insert(
+            initial_id,
+            remote_address,
+            source_connection_id,
+            token,
+            internal_id,
+        )
     }
 
     /// Looks up the internal Connection ID which is associated with a stateless
@@ -292,7 +480,7 @@ impl ConnectionIdMapper {
         guard.stateless_reset_map.remove(peer_stateless_reset_token)
     }
 
-    /// Removes the initial id mapping associated with the given internal ID
+    /// Removes the initial id and handshake mappings associated with the given internal ID
     pub fn remove_initial_id(
         &mut self,
         internal_id: &InternalConnectionId,
@@ -324,6 +512,40 @@ impl ConnectionIdMapper {
         )
     }
 
+    /// Creates a `LocalIdRegistry` for a connection which was transferred from another
+    /// endpoint and registers all of its connection IDs.
+    ///
+    /// This will return an error if one of the connection IDs is already used by
+    /// a different internal connection.
+    pub fn import_local_id_registry(
+        &mut self,
+        internal_id: InternalConnectionId,
+        state: &transfer::State,
+    ) -> Result<LocalIdRegistry, LocalIdRegistrationError> {
+        LocalIdRegistry::import(
+            internal_id,
+            self.state.clone(),
+            &state.local_connection_ids,
+            state.next_local_sequence_number,
+            state.local_retire_prior_to,
+        )
+    }
+
+    /// Creates a `PeerIdRegistry` for a connection which was transferred from another
+    /// endpoint and registers all of the connection IDs issued by the peer.
+    pub fn import_peer_id_registry(
+        &mut self,
+        internal_id: InternalConnectionId,
+        state: &transfer::State,
+    ) -> PeerIdRegistry {
+        PeerIdRegistry::import(
+            internal_id,
+            self.state.clone(),
+            &state.peer_connection_ids,
+            state.peer_retire_prior_to,
+        )
+    }
+
     /// Creates a Server `PeerIdRegistry` for a new InternalConnectionId.
     ///
     /// The registry allows a connection to modify the mappings of it's
@@ -408,13 +630,16 @@ mod tests {
         let local_id = connection::LocalId::try_from_bytes(b"id000001").unwrap();
         let initial_id = connection::InitialId::try_from(local_id).unwrap();
 
+        let remote_address = inet::SocketAddress::default();
+        let source_id = id(b"id01");
+
         assert_eq!(None, mapper.lookup_internal_connection_id(&local_id));
 
         assert!(mapper
-            .try_insert_initial_id(initial_id, internal_id)
+            .try_insert_initial_id(initial_id, &remote_address, &source_id, &[], internal_id)
             .is_ok());
         assert!(mapper
-            .try_insert_initial_id(initial_id, internal_id)
+            .try_insert_initial_id(initial_id, &remote_address, &source_id, &[], internal_id)
             .is_err());
 
         assert_eq!(
@@ -427,6 +652,138 @@ mod tests {
         assert_eq!(None, mapper.lookup_internal_connection_id(&local_id));
     }
 
+    #[test]
+    fn initial_id_map_handshake() {
+        let mut random_generator = random::testing::Generator(123);
+        let mut mapper = ConnectionIdMapper::new(&mut random_generator, endpoint::Type::Server);
+        let mut id_generator = InternalConnectionIdGenerator::new();
+        let internal_id = id_generator.generate_id();
+        let local_id = connection::LocalId::try_from_bytes(b"id000001").unwrap();
+        let initial_id = connection::InitialId::try_from(local_id).unwrap();
+        let remote_address = inet::SocketAddress::default();
+        let source_id = id(b"id01");
+        let token = b"token";
+
+        assert_eq!(
+            None,
+            mapper.lookup_handshake(&remote_address, &source_id, token)
+        );
+
+        assert!(mapper
+            .try_insert_initial_id(initial_id, &remote_address, &source_id, token, internal_id)
+            .is_ok());
+
+        assert_eq!(
+            Some(internal_id),
+            mapper.lookup_handshake(&remote_address, &source_id, token)
+        );
+
+        // the handshake is only matched if all of the values are the same
+        assert_eq!(
+            None,
+            mapper.lookup_handshake(&remote_address, &source_id, b"other token")
+        );
+        assert_eq!(
+            None,
+            mapper.lookup_handshake(&remote_address, &id(b"id02"), token)
+        );
+        let mut other_address = remote_address;
+        other_address.set_port(1234);
+        assert_eq!(
+            None,
+            mapper.lookup_handshake(&other_address, &source_id, token)
+        );
+
+        // a second connection can't be created for the same handshake with a new initial id
+        let other_local_id = connection::LocalId::try_from_bytes(b"id000002").unwrap();
+        let other_initial_id = connection::InitialId::try_from(other_local_id).unwrap();
+        assert!(mapper
+            .try_insert_initial_id(
+                other_initial_id,
+                &remote_address,
+                &source_id,
+                token,
+                id_generator.generate_id()
+            )
+            .is_err());
+        assert_eq!(None, mapper.lookup_internal_connection_id(&other_local_id));
+
+        assert_eq!(Some(initial_id), mapper.remove_initial_id(&internal_id));
+
+        assert_eq!(
+            None,
+            mapper.lookup_handshake(&remote_address, &source_id, token)
+        );
+    }
+
+    #[test]
+    fn initial_id_map_zero_length_handshakes() {
+        let mut random_generator = random::testing::Generator(123);
+        let mut mapper = ConnectionIdMapper::new(&mut random_generator, endpoint::Type::Server);
+        let mut id_generator = InternalConnectionIdGenerator::new();
+        let remote_address = inet::SocketAddress::default();
+        let source_id = connection::PeerId::try_from_bytes(&[]).unwrap();
+
+        // two concurrent connections from a client endpoint using zero-length connection IDs
+        // share the address, Source Connection ID and token
+        let first_internal_id = id_generator.generate_id();
+        let first_local_id = connection::LocalId::try_from_bytes(b"id000001").unwrap();
+        let first_initial_id = connection::InitialId::try_from(first_local_id).unwrap();
+        let second_internal_id = id_generator.generate_id();
+        let second_local_id = connection::LocalId::try_from_bytes(b"id000002").unwrap();
+        let second_initial_id = connection::InitialId::try_from(second_local_id).unwrap();
+
+        assert!(mapper
+            .try_insert_initial_id(
+                first_initial_id,
+                &remote_address,
+                &source_id,
+                &[],
+                first_internal_id
+            )
+            .is_ok());
+
+        // the handshakes can't be told apart, so the Initial packets aren't treated as duplicates
+        assert_eq!(
+            None,
+            mapper.lookup_handshake(&remote_address, &source_id, &[])
+        );
+
+        assert!(mapper
+            .try_insert_initial_id(
+                second_initial_id,
+                &remote_address,
+                &source_id,
+                &[],
+                second_internal_id
+            )
+            .is_ok());
+
+        assert_eq!(
+            Some(first_internal_id),
+            mapper.lookup_internal_connection_id(&first_local_id)
+        );
+        assert_eq!(
+            Some(second_internal_id),
+            mapper.lookup_internal_connection_id(&second_local_id)
+        );
+
+        // removing one of the connections doesn't affect the other
+        assert_eq!(
+            Some(first_initial_id),
+            mapper.remove_initial_id(&first_internal_id)
+        );
+        assert_eq!(None, mapper.lookup_internal_connection_id(&first_local_id));
+        assert_eq!(
+            Some(second_internal_id),
+            mapper.lookup_internal_connection_id(&second_local_id)
+        );
+        assert_eq!(
+            Some(second_initial_id),
+            mapper.remove_initial_id(&second_internal_id)
+        );
+    }
+
     #[test]
     #[should_panic]
     fn initial_id_map_client_insert() {
@@ -438,7 +795,13 @@ mod tests {
 
         assert_eq!(None, mapper.lookup_internal_connection_id(&local_id));
 
-        let _ = mapper.try_insert_initial_id(initial_id, internal_id);
+        let _ = mapper.try_insert_initial_id(
+            initial_id,
+            &inet::SocketAddress::default(),
+            &id(b"id01"),
+            &[],
+            internal_id,
+        );
     }
 
     #[test]
@@ -10,6 +10,7 @@ use crate::{
         id::{ConnectionInfo, Interest},
         limits::Limits,
         local_id_registry::LocalIdRegistrationError,
+        protocol_violation::{self, Endpoint as _},
         ConnectionIdMapper, ConnectionInterests, ConnectionTimers, ConnectionTransmission,
         ConnectionTransmissionContext, InternalConnectionId, Parameters as ConnectionParameters,
         ProcessingError,
@@ -24,7 +25,7 @@ use crate::{
     transmission::interest::Provider as _,
     wakeup_queue::WakeupHandle,
 };
-use alloc::sync::Arc;
+use alloc::{sync::Arc, vec, vec::Vec};
 use bytes::Bytes;
 use core::{
     fmt,
@@ -34,14 +35,19 @@ use core::{
 use s2n_quic_core::{
     application,
     application::ServerName,
-    connection::{id::Generator as _, InitialId, PeerId},
+    connection::{id::Generator as _, transfer, Extensions, HandshakeTiming, InitialId, PeerId},
     crypto::{tls, CryptoSuite},
     datagram::{Receiver, Sender},
+    endpoint::tenant::{self, Tenant as _},
     event::{
         self,
-        builder::{DatagramDropReason, MtuUpdatedCause, RxStreamProgress, TxStreamProgress},
+        builder::{
+            DatagramDropReason, HandshakePhase, HandshakePhaseReached, MtuUpdatedCause,
+            RxStreamProgress, TxStreamProgress,
+        },
         supervisor, ConnectionPublisher as _, IntoEvent as _, Subscriber,
     },
+    extension_frame::{self, Handler as _},
     inet::{DatagramInfo, SocketAddress},
     io::tx,
     packet::{
@@ -53,12 +59,14 @@ use s2n_quic_core::{
         version_negotiation::ProtectedVersionNegotiation,
         zero_rtt::ProtectedZeroRtt,
     },
-    path::{Handle as _, MaxMtu},
+    path::{migration, Handle as _, MaxMtu},
     query,
     recovery::CongestionController,
     stateless_reset::token::Generator as _,
+    stream::scheduler::{self, Endpoint as _},
     time::{timer, Timestamp},
     transport,
+    varint::VarInt,
 };
 
 /// Possible states for handing over a connection from the endpoint to the
@@ -98,6 +106,20 @@ enum ConnectionState {
     Finished,
 }
 
+#[cfg(feature = "state-snapshot")]
+impl From<ConnectionState> for connection::snapshot::State {
+    fn from(state: ConnectionState) -> Self {
+        match state {
+            ConnectionState::Handshaking => Self::Handshaking,
+            ConnectionState::Active => Self::Active,
+            ConnectionState::Flushing => Self::Flushing,
+            ConnectionState::Closing => Self::Closing,
+            ConnectionState::Draining => Self::Draining,
+            ConnectionState::Finished => Self::Finished,
+        }
+    }
+}
+
 impl From<connection::Error> for ConnectionState {
     fn from(error: connection::Error) -> Self {
         match error {
@@ -164,12 +186,29 @@ pub struct ConnectionImpl<Config: endpoint::Config> {
     path_manager: path::Manager<Config>,
     /// The limits applied to the current connection
     limits: Limits,
+    /// Decides how the connection responds to protocol violations by the peer
+    protocol_violation_policy:
+        <Config::ProtocolViolationEndpoint as protocol_violation::Endpoint>::Policy,
+    /// The stream scheduler of the connection, until it is handed to the stream manager
+    stream_scheduler: Option<<Config::StreamSchedulerEndpoint as scheduler::Endpoint>::Scheduler>,
+    /// The extension frame handler of the connection, until the extension is negotiated
+    extension_frame_handler:
+        Option<<Config::ExtensionFrameEndpoint as extension_frame::Endpoint>::Handler>,
+    /// The tenant the connection was assigned to by the endpoint
+    tenant: Option<<Config::TenantClassifier as tenant::Classifier>::Tenant>,
+    /// The number of open streams which was last reported to the tenant
+    tenant_open_streams: u64,
     /// The error set on the connection
     ///
     /// This is stored so future calls from the application return the same error
     error: Result<(), connection::Error>,
     /// Sends CONNECTION_CLOSE close frames after the connection is closed
     close_sender: CloseSender,
+    /// Set once a packet from the peer was processed successfully
+    ///
+    /// This is used to tell timeouts of peers which never responded apart from other timeouts,
+    /// since those indicate that QUIC is likely blocked on the path.
+    peer_responded: bool,
     /// Manages all of the different packet spaces and their respective components
     space_manager: PacketSpaceManager<Config>,
     /// Holds the handle for waking up the endpoint from a application call
@@ -177,12 +216,27 @@ pub struct ConnectionImpl<Config: endpoint::Config> {
     /// A Waker to the connection.
     waker: Waker,
     event_context: EventContext<Config>,
+    /// Values attached to the connection by the application
+    extensions: Extensions,
+    /// The time the connection was created
+    created_at: Timestamp,
+    /// The time it took to complete the handshake, once it completed on this endpoint
+    handshake_duration: Option<Duration>,
+    /// The times at which the connection reached each phase of the handshake
+    ///
+    /// This is `None` if the handshake was completed by another endpoint.
+    handshake_timing: Option<HandshakeTiming>,
+    /// The address validation token the client sent in its first Initial packet
+    address_token: Option<Bytes>,
 }
 
 struct EventContext<Config: endpoint::Config> {
     /// The [`Connection`]s internal identifier
     internal_connection_id: InternalConnectionId,
 
+    /// The ID which is attached to the events of the connection
+    correlation_id: connection::CorrelationId,
+
     /// The QUIC protocol version which is used for this particular connection
     quic_version: u32,
 
@@ -194,6 +248,7 @@ impl<Config: endpoint::Config> fmt::Debug for EventContext<Config> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("EventContext")
             .field("internal_connection_id", &self.internal_connection_id)
+            .field("correlation_id", &self.correlation_id)
             .field("quic_version", &self.quic_version)
             .finish()
     }
@@ -210,6 +265,7 @@ impl<Config: endpoint::Config> EventContext<Config> {
             event::builder::ConnectionMeta {
                 endpoint_type: Config::ENDPOINT_TYPE,
                 id: self.internal_connection_id.into(),
+                correlation_id: self.correlation_id,
                 timestamp,
             },
             self.quic_version,
@@ -237,6 +293,7 @@ macro_rules! transmission_context {
         $outcome:expr,
         $path_id:expr,
         $timestamp:expr,
+        $pacing_horizon:expr,
         $transmission_mode:expr,
         $subscriber:expr,
         $packet_interceptor:expr,
@@ -249,6 +306,7 @@ macro_rules! transmission_context {
         ConnectionTransmissionContext {
             quic_version: $self.event_context.quic_version,
             timestamp: $timestamp,
+            pacing_horizon: $pacing_horizon,
             path_id: $path_id,
             path_manager: &mut $self.path_manager,
             local_id_registry: &mut $self.local_id_registry,
@@ -256,6 +314,8 @@ macro_rules! transmission_context {
             ecn,
             min_packet_len: None,
             transmission_mode: $transmission_mode,
+            packet_coalescing: $self.limits.packet_coalescing_enabled(),
+            initial_padding: $self.limits.initial_padding(),
             publisher: &mut $self.event_context.publisher($timestamp, $subscriber),
             packet_interceptor: $packet_interceptor,
         }
@@ -266,6 +326,7 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
     fn update_crypto_state(
         &mut self,
         timestamp: Timestamp,
+        random_generator: &mut Config::RandomGenerator,
         subscriber: &mut Config::EventSubscriber,
         datagram: &mut Config::DatagramEndpoint,
     ) -> Result<(), connection::Error> {
@@ -278,8 +339,11 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
             &mut self.limits,
             timestamp,
             &self.waker,
+            random_generator,
             &mut publisher,
             datagram,
+            &mut self.stream_scheduler,
+            &mut self.extension_frame_handler,
         ) {
             Poll::Ready(res) => res?,
             Poll::Pending => return Ok(()),
@@ -319,6 +383,8 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
             // Cancel the max handshake duration timer as the handshake has completed in time
             self.timers.max_handshake_duration_timer.cancel();
 
+            self.handshake_duration = Some(timestamp.saturating_duration_since(self.created_at));
+
             // We don't expect any further initial packets on this connection, so start
             // a timer to remove the mapping from the initial ID to the internal connection ID
             // to give time for any delayed initial packets to arrive.
@@ -360,6 +426,8 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
         packet: &ProcessedPacket,
         subscriber: &mut Config::EventSubscriber,
     ) -> Result<(), connection::Error> {
+        self.peer_responded = true;
+
         //= https://www.rfc-editor.org/rfc/rfc9000#section-10.1
         //# An endpoint restarts its idle timer when a packet from its peer is
         //# received and processed successfully.
@@ -370,16 +438,31 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
             self.timers.reset_peer_idle_timer_on_send = true;
         }
 
-        let mut publisher = self
-            .event_context
-            .publisher(packet.datagram.timestamp, subscriber);
+        if let Some(delay) = self.limits.idle_reclamation_delay() {
+            self.timers
+                .reclamation_timer
+                .set(packet.datagram.timestamp + delay);
+        }
+
+        self.update_tenant(packet.bytes_progressed);
+
+        let timestamp = packet.datagram.timestamp;
 
         if packet.bytes_progressed > 0 {
+            let mut publisher = self.event_context.publisher(timestamp, subscriber);
             publisher.on_rx_stream_progress(RxStreamProgress {
                 bytes: packet.bytes_progressed,
-            })
+            });
+
+            self.on_handshake_phase(
+                HandshakePhase::ApplicationDataReceived,
+                timestamp,
+                subscriber,
+            );
         }
 
+        self.poll_handshake_phases(timestamp, subscriber);
+
         // check to see if we're flushing and should now close the connection
         if self.poll_flush().is_ready() {
             self.error?;
@@ -445,6 +528,7 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
                     context: ConnectionTransmissionContext {
                         quic_version: self.event_context.quic_version,
                         timestamp,
+                        pacing_horizon: Duration::ZERO,
                         path_id,
                         path_manager,
                         local_id_registry: &mut self.local_id_registry,
@@ -452,6 +536,8 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
                         min_packet_len: None,
                         ecn,
                         transmission_mode,
+                        packet_coalescing: self.limits.packet_coalescing_enabled(),
+                        initial_padding: self.limits.initial_padding(),
                         publisher: &mut self.event_context.publisher(timestamp, subscriber),
                         packet_interceptor,
                     },
@@ -475,6 +561,7 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
         let meta = event::builder::ConnectionMeta {
             endpoint_type: Config::ENDPOINT_TYPE,
             id: self.event_context.internal_connection_id.into(),
+            correlation_id: self.event_context.correlation_id,
             timestamp,
         }
         .into_event();
@@ -518,6 +605,83 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
         Ok(())
     }
 
+    /// Publishes an event the first time the connection reaches a phase of the handshake
+    fn on_handshake_phase(
+        &mut self,
+        phase: HandshakePhase,
+        timestamp: Timestamp,
+        subscriber: &mut Config::EventSubscriber,
+    ) {
+        let handshake_timing = if let Some(handshake_timing) = self.handshake_timing.as_mut() {
+            handshake_timing
+        } else {
+            return;
+        };
+
+        let elapsed = timestamp.saturating_duration_since(self.created_at);
+        if handshake_timing.on_phase(&phase, elapsed) {
+            let mut publisher = self.event_context.publisher(timestamp, subscriber);
+            publisher.on_handshake_phase_reached(HandshakePhaseReached { phase, elapsed });
+        }
+    }
+
+    /// Records the phases of the handshake which follow from the state of the packet spaces
+    fn poll_handshake_phases(
+        &mut self,
+        timestamp: Timestamp,
+        subscriber: &mut Config::EventSubscriber,
+    ) {
+        // The Initial and Handshake spaces are discarded by the time the handshake is confirmed,
+        // so there is nothing left to record
+        if self
+            .handshake_timing
+            .map_or(true, |timing| timing.handshake_confirmed.is_some())
+        {
+            return;
+        }
+
+        if self
+            .space_manager
+            .has_sent_packet(PacketNumberSpace::Initial)
+        {
+            self.on_handshake_phase(HandshakePhase::InitialSent, timestamp, subscriber);
+        }
+
+        if self
+            .space_manager
+            .has_sent_packet(PacketNumberSpace::Handshake)
+        {
+            self.on_handshake_phase(HandshakePhase::HandshakeSent, timestamp, subscriber);
+        }
+
+        if self.space_manager.is_handshake_confirmed() {
+            self.on_handshake_phase(HandshakePhase::HandshakeConfirmed, timestamp, subscriber);
+        }
+    }
+
+    /// Reports the resources used by the connection to its tenant
+    fn update_tenant(&mut self, stream_bytes: usize) {
+        let tenant = if let Some(tenant) = self.tenant.as_mut() {
+            tenant
+        } else {
+            return;
+        };
+
+        if stream_bytes > 0 {
+            tenant.on_stream_bytes(stream_bytes as u64);
+        }
+
+        let open_streams = self
+            .space_manager
+            .application()
+            .map_or(0, |space| space.stream_manager.open_stream_count());
+
+        if open_streams != self.tenant_open_streams {
+            self.tenant_open_streams = open_streams;
+            tenant.on_open_streams(open_streams);
+        }
+    }
+
     /// Polls for the connection to flush all of the outstanding streams
     ///
     /// Once all of the streams are finished, `Poll::Ready` will be returned
@@ -544,6 +708,11 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
 
         Poll::Pending
     }
+
+    /// Returns `true` if the connection was exported to another endpoint
+    fn is_transferred(&self) -> bool {
+        matches!(self.error, Err(connection::Error::Transferred { .. }))
+    }
 }
 
 impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
@@ -559,12 +728,16 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         let mut event_context = EventContext {
             context: parameters.event_context,
             internal_connection_id: parameters.internal_connection_id,
+            correlation_id: parameters.correlation_id,
             quic_version: parameters.quic_version,
         };
 
         // The path manager always starts with a single path containing the known peer and local
         // connection ids.
-        let rtt_estimator = RttEstimator::default();
+        let rtt_estimator = RttEstimator::new_with_initial_rtt(
+            Duration::ZERO,
+            parameters.limits.initial_round_trip_time(),
+        );
         // Assume clients validate the server's address implicitly.
         let peer_validated = Self::Config::ENDPOINT_TYPE.is_server();
 
@@ -575,11 +748,23 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
             rtt_estimator,
             parameters.congestion_controller,
             peer_validated,
-            parameters.max_mtu,
+            path::mtu::Config::new(parameters.max_mtu, &parameters.limits)
+                .for_path(parameters.mtu_endpoint, &parameters.path_handle),
         );
 
         let path_manager = path::Manager::new(initial_path, parameters.peer_id_registry);
 
+        let protocol_violation_policy = parameters.protocol_violation_endpoint.new_policy(
+            &protocol_violation::ConnectionInfo::new(&parameters.path_handle.remote_address()),
+        );
+
+        let stream_scheduler =
+            parameters
+                .stream_scheduler_endpoint
+                .new_scheduler(&scheduler::ConnectionInfo::new(
+                    &parameters.path_handle.remote_address(),
+                ));
+
         let mut publisher =
             event_context.publisher(parameters.timestamp, parameters.event_subscriber);
 
@@ -609,17 +794,30 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
             state: ConnectionState::Handshaking,
             path_manager,
             limits: parameters.limits,
+            protocol_violation_policy,
+            stream_scheduler: Some(stream_scheduler),
+            extension_frame_handler: parameters.extension_frame_handler,
+            tenant: None,
+            tenant_open_streams: 0,
             error: Ok(()),
             close_sender: CloseSender::default(),
+            // servers only create connections in response to packets from the peer
+            peer_responded: Config::ENDPOINT_TYPE.is_server(),
             space_manager: parameters.space_manager,
             wakeup_handle,
             waker,
             event_context,
+            extensions: Extensions::new(),
+            created_at: parameters.timestamp,
+            handshake_duration: None,
+            handshake_timing: Some(HandshakeTiming::default()),
+            address_token: parameters.address_token,
         };
 
         if Config::ENDPOINT_TYPE.is_client() {
             if let Err(error) = connection.update_crypto_state(
                 parameters.timestamp,
+                parameters.random_generator,
                 parameters.event_subscriber,
                 parameters.datagram_endpoint,
             ) {
@@ -641,6 +839,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         let meta = event::builder::ConnectionMeta {
             endpoint_type: Config::ENDPOINT_TYPE,
             id: connection.internal_connection_id().into(),
+            correlation_id: connection.event_context.correlation_id,
             timestamp: parameters.timestamp,
         };
 
@@ -673,6 +872,11 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         self.event_context.quic_version
     }
 
+    /// Returns `true` if the connection advertised the `grease_quic_bit` transport parameter
+    fn accepts_cleared_quic_bit(&self) -> bool {
+        self.limits.grease_quic_bit_enabled()
+    }
+
     /// Initiates closing the connection as described in
     /// https://www.rfc-editor.org/rfc/rfc9000#section-10
     fn close(
@@ -721,6 +925,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                 &mut outcome,
                 active_path_id,
                 timestamp,
+                Duration::ZERO,
                 transmission::Mode::Normal,
                 subscriber,
                 packet_interceptor,
@@ -768,6 +973,12 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         if let Some((space, _)) = self.space_manager.application_mut() {
             space.datagram_manager.sender.on_connection_error(error);
             space.datagram_manager.receiver.on_connection_error(error);
+            space.extension_frame_manager.on_connection_error(error);
+        }
+
+        // Notify the extension frame handler if the application space wasn't created yet
+        if let Some(handler) = self.extension_frame_handler.as_mut() {
+            handler.on_connection_error(error);
         }
 
         //= https://www.rfc-editor.org/rfc/rfc9000#section-10.2.1
@@ -793,6 +1004,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         &mut self,
         connection_id_format: &mut Config::ConnectionIdFormat,
         stateless_reset_token_generator: &mut Config::StatelessResetTokenGenerator,
+        random_generator: &mut Config::RandomGenerator,
         timestamp: Timestamp,
     ) -> Result<(), LocalIdRegistrationError> {
         match self.local_id_registry.connection_id_interest() {
@@ -801,7 +1013,8 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                 let connection_info = ConnectionInfo::new(&remote_address);
 
                 while count > 0 {
-                    let id = connection_id_format.generate(&connection_info);
+                    let id = connection_id_format
+                        .generate_with_random(&connection_info, random_generator);
                     let expiration = connection_id_format
                         .lifetime()
                         .map(|duration| timestamp + duration);
@@ -835,6 +1048,10 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
             "connection should not express transmission interest if amplification limited"
         );
 
+        // Queues which pace the datagrams themselves accept packets ahead of their departure
+        // time, which is set as the delay of each datagram
+        let pacing_horizon = queue.pacing_horizon();
+
         match self.state {
             ConnectionState::Handshaking | ConnectionState::Active | ConnectionState::Flushing => {
                 let mut outcome = transmission::Outcome::default();
@@ -844,7 +1061,10 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                 // MTU probes are prioritized over other data so they are not blocked by the
                 // congestion controller, as they are critical to achieving maximum throughput.
                 if self.state == ConnectionState::Active
-                    && self.path_manager.active_path().can_transmit(timestamp)
+                    && self
+                        .path_manager
+                        .active_path()
+                        .can_transmit(timestamp + pacing_horizon)
                     && self
                         .path_manager
                         .active_path()
@@ -857,6 +1077,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                                 &mut outcome,
                                 path_id,
                                 timestamp,
+                                pacing_horizon,
                                 transmission::Mode::MtuProbing,
                                 subscriber,
                                 packet_interceptor,
@@ -869,7 +1090,10 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                 }
 
                 // Send all other data for the active path
-                while self.path_manager.active_path().can_transmit(timestamp)
+                while self
+                    .path_manager
+                    .active_path()
+                    .can_transmit(timestamp + pacing_horizon)
                     && queue
                         .push(ConnectionTransmission {
                             context: transmission_context!(
@@ -877,6 +1101,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                                 &mut outcome,
                                 path_id,
                                 timestamp,
+                                pacing_horizon,
                                 transmission::Mode::Normal,
                                 subscriber,
                                 packet_interceptor,
@@ -898,7 +1123,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                     .congestion_controller
                     .earliest_departure_time()
                 {
-                    if !edt.has_elapsed(timestamp) {
+                    if !edt.has_elapsed(timestamp + pacing_horizon) {
                         // We can't transmit more until a future time, so arm the pacing
                         // timer to pause transmission until the earliest departure time.
 
@@ -908,13 +1133,14 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
 
                         //= https://www.rfc-editor.org/rfc/rfc9002#section-7.7
                         //# Senders MUST either use pacing or limit such bursts.
-                        self.timers.pacing_timer.set(edt);
+                        self.timers.pacing_timer.set(edt - pacing_horizon);
                     }
                 }
 
                 let meta = event::builder::ConnectionMeta {
                     endpoint_type: Config::ENDPOINT_TYPE,
                     id: self.internal_connection_id().into(),
+                    correlation_id: self.event_context.correlation_id,
                     timestamp,
                 };
                 let path_id = self.path_manager.active_path_id().as_u8();
@@ -935,6 +1161,10 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                     packet_interceptor,
                 );
 
+                self.poll_handshake_phases(timestamp, subscriber);
+
+                self.update_tenant(outcome.bytes_progressed);
+
                 let mut publisher = self.event_context.publisher(timestamp, subscriber);
                 if outcome.bytes_progressed > 0 {
                     publisher.on_tx_stream_progress(TxStreamProgress {
@@ -1026,16 +1256,28 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
             debug_assert_eq!(ConnectionState::Handshaking, self.state);
             return Err(connection::Error::max_handshake_duration_exceeded(
                 self.limits.max_handshake_duration(),
+                self.peer_responded,
             ));
         }
 
+        if let Some(max_handshake_pto_count) = self.limits.max_handshake_pto_count() {
+            if self.state == ConnectionState::Handshaking
+                && self.path_manager.active_path().pto_count() > max_handshake_pto_count as u32
+            {
+                return Err(connection::Error::max_handshake_pto_count_exceeded(
+                    max_handshake_pto_count,
+                    self.peer_responded,
+                ));
+            }
+        }
+
         if self
             .timers
             .peer_idle_timer
             .poll_expiration(timestamp)
             .is_ready()
         {
-            return Err(connection::Error::idle_timer_expired());
+            return Err(connection::Error::idle_timer_expired(self.peer_responded));
         }
 
         if self
@@ -1047,6 +1289,15 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
             self.on_supervisor_timeout(timestamp, subscriber, supervisor_context)?;
         }
 
+        if self
+            .timers
+            .reclamation_timer
+            .poll_expiration(timestamp)
+            .is_ready()
+        {
+            self.space_manager.reclaim();
+        }
+
         // check to see if we're flushing the connection
         if self.poll_flush().is_ready() {
             return self.error;
@@ -1079,6 +1330,10 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
     fn on_wakeup(
         &mut self,
         timestamp: Timestamp,
+        congestion_controller_endpoint: &mut Config::CongestionControllerEndpoint,
+        mtu_endpoint: &mut Config::MtuEndpoint,
+        max_mtu: MaxMtu,
+        random_generator: &mut Config::RandomGenerator,
         subscriber: &mut Config::EventSubscriber,
         datagram: &mut Config::DatagramEndpoint,
     ) -> Result<(), connection::Error> {
@@ -1086,11 +1341,23 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         self.wakeup_handle.wakeup_handled();
 
         // check if crypto progress can be made
-        self.update_crypto_state(timestamp, subscriber, datagram)?;
+        self.update_crypto_state(timestamp, random_generator, subscriber, datagram)?;
 
         // return an error if the application set one
         self.error?;
 
+        // migrate to the local address requested by the application
+        if self.path_manager.has_pending_migration() {
+            let mut publisher = self.event_context.publisher(timestamp, subscriber);
+            self.path_manager.on_pending_migration(
+                congestion_controller_endpoint,
+                mtu_endpoint,
+                path::mtu::Config::new(max_mtu, &self.limits),
+                random_generator,
+                &mut publisher,
+            );
+        }
+
         Ok(())
     }
 
@@ -1101,6 +1368,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         datagram: &DatagramInfo,
         congestion_controller_endpoint: &mut Config::CongestionControllerEndpoint,
         path_migration: &mut Config::PathMigrationValidator,
+        mtu_endpoint: &mut Config::MtuEndpoint,
         max_mtu: MaxMtu,
         subscriber: &mut Config::EventSubscriber,
     ) -> Result<path::Id, DatagramDropReason> {
@@ -1125,7 +1393,8 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
             handshake_confirmed,
             congestion_controller_endpoint,
             path_migration,
-            max_mtu,
+            mtu_endpoint,
+            path::mtu::Config::new(max_mtu, &self.limits),
             &mut publisher,
         )?;
 
@@ -1159,6 +1428,57 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         Ok(id)
     }
 
+    fn check_unvalidated_bytes(
+        &mut self,
+        timestamp: Timestamp,
+        subscriber: &mut Config::EventSubscriber,
+    ) -> Result<(), connection::Error> {
+        // The limit only applies while the handshake is in progress. Established connections
+        // are subject to the regular anti-amplification limits when migrating.
+        if !matches!(self.state, ConnectionState::Handshaking) {
+            return Ok(());
+        }
+
+        let max_unvalidated_handshake_bytes =
+            if let Some(max) = self.limits.max_unvalidated_handshake_bytes() {
+                max
+            } else {
+                return Ok(());
+            };
+
+        let unvalidated_bytes = self.path_manager.unvalidated_bytes();
+
+        if unvalidated_bytes.received <= max_unvalidated_handshake_bytes {
+            return Ok(());
+        }
+
+        let mut publisher = self.event_context.publisher(timestamp, subscriber);
+        publisher.on_unvalidated_handshake_bytes_exceeded(
+            event::builder::UnvalidatedHandshakeBytesExceeded {
+                bytes_received: unvalidated_bytes.received,
+                bytes_sent: unvalidated_bytes.sent,
+                max_unvalidated_handshake_bytes,
+            },
+        );
+
+        Err(connection::Error::max_unvalidated_handshake_bytes_exceeded(
+            max_unvalidated_handshake_bytes,
+        ))
+    }
+
+    fn unvalidated_bytes(&self) -> path::UnvalidatedBytes {
+        self.path_manager.unvalidated_bytes()
+    }
+
+    fn on_tenant(&mut self, tenant: <Config::TenantClassifier as tenant::Classifier>::Tenant) {
+        debug_assert!(
+            self.tenant.is_none(),
+            "connections are only classified once"
+        );
+        self.tenant = Some(tenant);
+        self.update_tenant(0);
+    }
+
     /// Is called when a initial packet had been received
     fn handle_initial_packet(
         &mut self,
@@ -1201,6 +1521,12 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                 ),
             });
 
+            self.on_handshake_phase(
+                HandshakePhase::InitialReceived,
+                datagram.timestamp,
+                subscriber,
+            );
+
             self.handle_cleartext_initial_packet(
                 datagram,
                 path_id,
@@ -1257,10 +1583,16 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                 random_generator,
                 &mut publisher,
                 packet_interceptor,
+                &mut self.protocol_violation_policy,
             )?;
 
             // try to move the crypto state machine forward
-            self.update_crypto_state(datagram.timestamp, subscriber, datagram_endpoint)?;
+            self.update_crypto_state(
+                datagram.timestamp,
+                random_generator,
+                subscriber,
+                datagram_endpoint,
+            )?;
 
             // notify the connection a packet was processed
             self.on_processed_packet(&processed_packet, subscriber)?;
@@ -1322,6 +1654,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                 random_generator,
                 &mut publisher,
                 packet_interceptor,
+                &mut self.protocol_violation_policy,
             )?;
 
             if Self::Config::ENDPOINT_TYPE.is_server() {
@@ -1341,8 +1674,19 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
             //# have been validated.
             self.path_manager[path_id].on_handshake_packet();
 
+            self.on_handshake_phase(
+                HandshakePhase::HandshakeReceived,
+                datagram.timestamp,
+                subscriber,
+            );
+
             // try to move the crypto state machine forward
-            self.update_crypto_state(datagram.timestamp, subscriber, datagram_endpoint)?;
+            self.update_crypto_state(
+                datagram.timestamp,
+                random_generator,
+                subscriber,
+                datagram_endpoint,
+            )?;
 
             // notify the connection a packet was processed
             self.on_processed_packet(&processed_packet, subscriber)?;
@@ -1443,6 +1787,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                 random_generator,
                 &mut publisher,
                 packet_interceptor,
+                &mut self.protocol_violation_policy,
             )?;
 
             // notify the connection a packet was processed
@@ -1457,7 +1802,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         &mut self,
         datagram: &DatagramInfo,
         _path_id: path::Id,
-        _packet: ProtectedVersionNegotiation,
+        packet: ProtectedVersionNegotiation,
         subscriber: &mut Config::EventSubscriber,
         _packet_interceptor: &mut Config::PacketInterceptor,
     ) -> Result<(), ProcessingError> {
@@ -1466,31 +1811,36 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         publisher.on_packet_received(event::builder::PacketReceived {
             packet_header: event::builder::PacketHeader::VersionNegotiation {},
         });
-        //= https://www.rfc-editor.org/rfc/rfc9000#section-6.2
-        //= type=TODO
-        //= feature=Version negotiation handler
-        //= tracking-issue=349
-        //# A client that supports only this version of QUIC MUST abandon the
-        //# current connection attempt if it receives a Version Negotiation
-        //# packet, with the following two exceptions.
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-6.1
+        //# An endpoint MUST NOT send a Version Negotiation packet
+        //# in response to receiving a Version Negotiation packet.
+        if Config::ENDPOINT_TYPE.is_server() {
+            return Ok(());
+        }
 
         //= https://www.rfc-editor.org/rfc/rfc9000#section-6.2
-        //= type=TODO
-        //= feature=Version negotiation handler
-        //= tracking-issue=349
         //# A client MUST discard any
         //# Version Negotiation packet if it has received and successfully
         //# processed any other packet, including an earlier Version Negotiation
         //# packet.
+        if self.peer_responded {
+            return Ok(());
+        }
 
         //= https://www.rfc-editor.org/rfc/rfc9000#section-6.2
-        //= type=TODO
-        //= feature=Version negotiation handler
-        //= tracking-issue=349
         //# A client MUST discard a Version Negotiation packet that
         //# lists the QUIC version selected by the client.
+        let version = self.event_context.quic_version;
+        if packet.iter().any(|supported| supported == version) {
+            return Ok(());
+        }
 
-        Ok(())
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-6.2
+        //# A client that supports only this version of QUIC MUST abandon the
+        //# current connection attempt if it receives a Version Negotiation
+        //# packet, with the following two exceptions.
+        Err(connection::Error::no_compatible_version().into())
     }
 
     /// Is called when a zero rtt packet had been received
@@ -1662,6 +2012,9 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         }
 
         match self.state {
+            // The peer continues the connection with the endpoint which imported it, so nothing
+            // is sent until the connection is closed silently on the next wakeup.
+            ConnectionState::Active if self.is_transferred() => {}
             ConnectionState::Active | ConnectionState::Handshaking | ConnectionState::Flushing => {
                 let constraint = self.path_manager.transmission_constraint();
 
@@ -1702,6 +2055,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
             };
         } else {
             interests.timeout = self.next_expiration();
+            interests.idle_timeout_disabled = self.limits.max_idle_timeout().is_none();
         }
 
         interests
@@ -1781,6 +2135,109 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         self.wakeup_handle.wakeup();
     }
 
+    fn create_stream_group(
+        &mut self,
+        max_data: VarInt,
+    ) -> Result<stream::group::Id, connection::Error> {
+        self.error?;
+
+        let (space, _) = self
+            .space_manager
+            .application_mut()
+            .ok_or_else(connection::Error::unspecified)?;
+
+        space.stream_manager.create_group(max_data)
+    }
+
+    fn join_stream_group(
+        &mut self,
+        group: stream::group::Id,
+        stream_id: stream::StreamId,
+    ) -> Result<(), stream::StreamError> {
+        let (space, _) = self
+            .space_manager
+            .application_mut()
+            .ok_or_else(connection::Error::unspecified)?;
+
+        let mut api_context = ConnectionApiCallContext::from_wakeup_handle(&self.wakeup_handle);
+
+        space
+            .stream_manager
+            .join_group(group, stream_id, &mut api_context)
+    }
+
+    fn set_stream_group_max_data(
+        &mut self,
+        group: stream::group::Id,
+        max_data: VarInt,
+    ) -> Result<(), connection::Error> {
+        self.error?;
+
+        let (space, _) = self
+            .space_manager
+            .application_mut()
+            .ok_or_else(connection::Error::unspecified)?;
+
+        let mut api_context = ConnectionApiCallContext::from_wakeup_handle(&self.wakeup_handle);
+
+        space
+            .stream_manager
+            .set_group_max_data(group, max_data, &mut api_context);
+
+        Ok(())
+    }
+
+    fn set_stream_group_urgency(
+        &mut self,
+        group: stream::group::Id,
+        urgency: u8,
+    ) -> Result<(), connection::Error> {
+        self.error?;
+
+        let (space, _) = self
+            .space_manager
+            .application_mut()
+            .ok_or_else(connection::Error::unspecified)?;
+
+        space.stream_manager.set_group_urgency(group, urgency);
+
+        Ok(())
+    }
+
+    fn set_stream_incremental(
+        &mut self,
+        stream_id: stream::StreamId,
+        incremental: bool,
+    ) -> Result<(), stream::StreamError> {
+        let (space, _) = self
+            .space_manager
+            .application_mut()
+            .ok_or_else(connection::Error::unspecified)?;
+
+        space.stream_manager.set_incremental(stream_id, incremental)
+    }
+
+    fn reset_stream_group(
+        &mut self,
+        group: stream::group::Id,
+        error: application::Error,
+    ) -> Result<(), connection::Error> {
+        self.error?;
+
+        let (space, _) = self
+            .space_manager
+            .application_mut()
+            .ok_or_else(connection::Error::unspecified)?;
+
+        let mut api_context = ConnectionApiCallContext::from_wakeup_handle(&self.wakeup_handle);
+
+        space
+            .stream_manager
+            .reset_group(group, error, &mut api_context);
+
+        Ok(())
+    }
+
     fn server_name(&self) -> Option<ServerName> {
         self.space_manager.server_name.clone()
     }
@@ -1789,6 +2246,28 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         self.space_manager.application_protocol.clone()
     }
 
+    fn peer_certificates(&self) -> Vec<Bytes> {
+        self.space_manager.peer_certificates.clone()
+    }
+
+    fn peer_application_settings(&self) -> Option<Bytes> {
+        self.space_manager.peer_application_settings.clone()
+    }
+
+    fn handshake_info(&self) -> connection::HandshakeInfo {
+        let path = self.path_manager.active_path();
+
+        connection::HandshakeInfo::new(
+            self.space_manager.application_protocol.clone(),
+            self.space_manager.server_name.clone(),
+            *path.handle.remote_address(),
+            self.address_token.clone(),
+            self.space_manager.is_resumed(),
+            self.handshake_duration,
+            path.rtt_estimator.smoothed_rtt(),
+        )
+    }
+
     fn ping(&mut self) -> Result<(), connection::Error> {
         self.error?;
 
@@ -1825,6 +2304,114 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         Ok(())
     }
 
+    fn migrate(&mut self, local_address: SocketAddress) -> Result<(), migration::Error> {
+        self.error?;
+
+        let handshake_confirmed = self.space_manager.is_handshake_confirmed();
+        self.path_manager
+            .request_migration(local_address, handshake_confirmed)?;
+
+        self.wakeup_handle.wakeup();
+
+        Ok(())
+    }
+
+    fn export_state(&mut self) -> Result<transfer::State, transfer::Error> {
+        self.error?;
+
+        if Config::ENDPOINT_TYPE.is_client() {
+            return Err(transfer::Error::Unsupported);
+        }
+
+        match self.state {
+            ConnectionState::Active => {}
+            ConnectionState::Handshaking => return Err(transfer::Error::HandshakeNotConfirmed),
+            _ => return Err(transfer::Error::ConnectionClosed),
+        }
+
+        if !self.space_manager.is_handshake_confirmed() {
+            return Err(transfer::Error::HandshakeNotConfirmed);
+        }
+
+        // The importing endpoint routes packets to the connection by its connection IDs
+        if self.local_id_registry.is_zero_length() {
+            return Err(transfer::Error::Unsupported);
+        }
+
+        let space = self
+            .space_manager
+            .application()
+            .ok_or(transfer::Error::HandshakeNotConfirmed)?
+            .export_transfer()?;
+
+        let path = self.path_manager.active_path();
+        let peer_id_registry = &self.path_manager.peer_id_registry;
+
+        let state = transfer::State {
+            quic_version: self.quic_version(),
+            correlation_id: self.event_context.correlation_id,
+            local_address: *path.handle.local_address(),
+            remote_address: *path.handle.remote_address(),
+            server_name: self.space_manager.server_name.clone(),
+            application_protocol: self.space_manager.application_protocol.clone(),
+            local_connection_id: path.local_connection_id,
+            local_connection_ids: self.local_id_registry.export(),
+            next_local_sequence_number: self.local_id_registry.next_sequence_number(),
+            local_retire_prior_to: self.local_id_registry.retire_prior_to(),
+            peer_connection_id: path.peer_connection_id,
+            peer_connection_ids: peer_id_registry.export(),
+            peer_retire_prior_to: peer_id_registry.retire_prior_to(),
+            key: space.key,
+            header_key: space.header_key,
+            key_set: space.key_set,
+            next_packet_number: space.next_packet_number,
+            largest_received_packet_number: space.largest_received_packet_number,
+            streams: space.streams,
+            local_limits: self.limits.initial_flow_control_limits(),
+            peer_limits: space.peer_limits,
+            active_connection_id_limit: self.local_id_registry.active_connection_id_limit(),
+            max_idle_timeout: self.limits.max_idle_timeout(),
+            max_ack_delay: path.rtt_estimator.max_ack_delay(),
+            max_datagram_payload: space.max_datagram_payload,
+            grease_quic_bit: space.grease_quic_bit,
+        };
+
+        // The connection is closed silently by the endpoint once it handles the wakeup
+        self.error = Err(connection::Error::transferred());
+        self.wakeup_handle.wakeup();
+
+        Ok(state)
+    }
+
+    fn on_transferred(
+        &mut self,
+        state: &transfer::State,
+        timestamp: Timestamp,
+        random_generator: &mut Config::RandomGenerator,
+        subscriber: &mut Config::EventSubscriber,
+        datagram_endpoint: &mut Config::DatagramEndpoint,
+    ) -> Result<(), transfer::Error> {
+        self.space_manager.on_transferred(
+            state,
+            &mut self.path_manager,
+            &mut self.local_id_registry,
+            &mut self.limits,
+            timestamp,
+            random_generator,
+            datagram_endpoint,
+            &mut self.stream_scheduler,
+        )?;
+
+        // The handshake is already complete, which hands the connection over to the application
+        self.update_crypto_state(timestamp, random_generator, subscriber, datagram_endpoint)?;
+
+        // The handshake was completed by the exporting endpoint
+        self.handshake_duration = None;
+        self.handshake_timing = None;
+
+        Ok(())
+    }
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error> {
         Ok(*self.path_manager.active_path().handle.local_address())
     }
@@ -1833,6 +2420,44 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         Ok(*self.path_manager.active_path().handle.remote_address())
     }
 
+    fn arena_stats(&self) -> Result<connection::arena::Stats, connection::Error> {
+        Ok(self.space_manager.arena_stats())
+    }
+
+    fn memory_usage(&self) -> Result<connection::memory::Usage, connection::Error> {
+        Ok(self.space_manager.memory_usage())
+    }
+
+    fn handshake_timing(&self) -> Result<connection::HandshakeTiming, connection::Error> {
+        Ok(self.handshake_timing.unwrap_or_default())
+    }
+
+    fn correlation_id(&self) -> Result<connection::CorrelationId, connection::Error> {
+        Ok(self.event_context.correlation_id)
+    }
+
+    #[cfg(feature = "state-snapshot")]
+    fn snapshot(&self) -> Result<connection::snapshot::Snapshot, connection::Error> {
+        // the streams are discarded along with the application space once the connection closes
+        let (flow_control, streams) = self
+            .space_manager
+            .application()
+            .map(|space| {
+                (
+                    space.stream_manager.flow_control_snapshot(),
+                    space.stream_manager.stream_snapshots(),
+                )
+            })
+            .unwrap_or_default();
+
+        Ok(connection::snapshot::Snapshot {
+            state: self.state.into(),
+            spaces: self.space_manager.snapshot(),
+            flow_control,
+            streams,
+        })
+    }
+
     fn error(&self) -> Option<connection::Error> {
         self.error.err()
     }
@@ -1850,6 +2475,16 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         );
     }
 
+    #[inline]
+    fn query_extensions(&self, query: &mut dyn query::Query) {
+        query.execute(&self.extensions);
+    }
+
+    #[inline]
+    fn query_extensions_mut(&mut self, query: &mut dyn query::QueryMut) {
+        query.execute_mut(&mut self.extensions);
+    }
+
     #[inline]
     fn datagram_mut(&mut self, query: &mut dyn query::QueryMut) {
         if let Some((space, _)) = self.space_manager.application_mut() {
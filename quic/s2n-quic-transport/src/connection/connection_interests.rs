@@ -25,6 +25,9 @@ pub struct ConnectionInterests {
     pub ack: bool,
     /// Is `Some(Timestamp)` if the connection needs to be woken up at the specified time
     pub timeout: Option<Timestamp>,
+    /// Is `true` if the `Connection` has disabled its idle timeout and can therefore
+    /// remain active without being interested in any other interaction
+    pub idle_timeout_disabled: bool,
 }
 
 impl ConnectionInterests {
@@ -53,6 +56,7 @@ impl ConnectionInterests {
                 (None, Some(b)) => Some(b),
                 (None, None) => None,
             },
+            idle_timeout_disabled: self.idle_timeout_disabled || other.idle_timeout_disabled,
         }
     }
 }
@@ -89,6 +93,7 @@ mod tests {
             new_connection_id: false,
             ack: false,
             timeout: None,
+            idle_timeout_disabled: false,
         };
 
         let b_time = unsafe { Timestamp::from_duration(Duration::from_secs(123)) };
@@ -100,6 +105,7 @@ mod tests {
             new_connection_id: true,
             ack: true,
             timeout: Some(b_time),
+            idle_timeout_disabled: false,
         };
 
         let c_time = unsafe { Timestamp::from_duration(Duration::from_secs(456)) };
@@ -111,6 +117,7 @@ mod tests {
             new_connection_id: false,
             ack: false,
             timeout: Some(c_time),
+            idle_timeout_disabled: true,
         };
 
         assert_eq!(
@@ -122,6 +129,7 @@ mod tests {
                 new_connection_id: true,
                 ack: true,
                 timeout: Some(b_time),
+                idle_timeout_disabled: false,
             },
             a + b
         );
@@ -135,6 +143,7 @@ mod tests {
                 new_connection_id: false,
                 ack: false,
                 timeout: Some(c_time),
+                idle_timeout_disabled: true,
             },
             a + c
         );
@@ -148,6 +157,7 @@ mod tests {
                 new_connection_id: true,
                 ack: true,
                 timeout: Some(b_time),
+                idle_timeout_disabled: true,
             },
             b + c
         );
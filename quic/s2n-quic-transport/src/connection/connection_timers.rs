@@ -28,6 +28,8 @@ pub struct ConnectionTimers {
     pub max_handshake_duration_timer: Timer,
     /// The timer for calling the connection supervisor
     pub supervisor_timer: Timer,
+    /// The timer for reclaiming the resources of an idle connection
+    pub reclamation_timer: Timer,
 }
 
 impl ConnectionTimers {
@@ -38,6 +40,7 @@ impl ConnectionTimers {
         self.pacing_timer.cancel();
         self.max_handshake_duration_timer.cancel();
         self.supervisor_timer.cancel();
+        self.reclamation_timer.cancel();
     }
 }
 
@@ -50,6 +53,7 @@ impl timer::Provider for ConnectionTimers {
         self.pacing_timer.timers(query)?;
         self.max_handshake_duration_timer.timers(query)?;
         self.supervisor_timer.timers(query)?;
+        self.reclamation_timer.timers(query)?;
 
         Ok(())
     }
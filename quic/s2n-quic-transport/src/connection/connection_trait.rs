@@ -14,12 +14,15 @@ use crate::{
     path::{self, path_event},
     stream,
 };
+use alloc::vec::Vec;
 use bytes::Bytes;
 use core::task::{Context, Poll};
 use s2n_codec::DecoderBufferMut;
 use s2n_quic_core::{
     application,
     application::ServerName,
+    connection::transfer,
+    endpoint::tenant,
     event::{self, builder::DatagramDropReason, supervisor, ConnectionPublisher, IntoEvent},
     inet::{DatagramInfo, SocketAddress},
     io::tx,
@@ -32,9 +35,10 @@ use s2n_quic_core::{
         zero_rtt::ProtectedZeroRtt,
         ProtectedPacket,
     },
-    path::{Handle as _, MaxMtu},
+    path::{migration, Handle as _, MaxMtu},
     query,
     time::Timestamp,
+    varint::VarInt,
 };
 
 /// A trait which represents an internally used `Connection`
@@ -68,12 +72,13 @@ pub trait ConnectionTrait: 'static + Send + Sized {
     /// no longer be signalled.
     fn mark_as_accepted(&mut self);
 
-    /// Generates and registers new connection IDs using the given `ConnectionIdFormat` and
-    /// `StatelessResetTokenGenerator`
+    /// Generates and registers new connection IDs using the given `ConnectionIdFormat`,
+    /// `StatelessResetTokenGenerator` and `RandomGenerator`
     fn on_new_connection_id(
         &mut self,
         connection_id_format: &mut <Self::Config as endpoint::Config>::ConnectionIdFormat,
         stateless_reset_token_generator: &mut <Self::Config as endpoint::Config>::StatelessResetTokenGenerator,
+        random_generator: &mut <Self::Config as endpoint::Config>::RandomGenerator,
         timestamp: Timestamp,
     ) -> Result<(), LocalIdRegistrationError>;
 
@@ -101,9 +106,14 @@ pub trait ConnectionTrait: 'static + Send + Sized {
     ) -> Result<(), connection::Error>;
 
     /// Handles all external wakeups on the [`Connection`].
+    #[allow(clippy::too_many_arguments)]
     fn on_wakeup(
         &mut self,
         timestamp: Timestamp,
+        congestion_controller_endpoint: &mut <Self::Config as endpoint::Config>::CongestionControllerEndpoint,
+        mtu_endpoint: &mut <Self::Config as endpoint::Config>::MtuEndpoint,
+        max_mtu: MaxMtu,
+        random_generator: &mut <Self::Config as endpoint::Config>::RandomGenerator,
         subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
         datagram: &mut <Self::Config as endpoint::Config>::DatagramEndpoint,
     ) -> Result<(), connection::Error>;
@@ -198,16 +208,38 @@ pub trait ConnectionTrait: 'static + Send + Sized {
         datagram: &DatagramInfo,
         congestion_controller_endpoint: &mut <Self::Config as endpoint::Config>::CongestionControllerEndpoint,
         migration_validator: &mut <Self::Config as endpoint::Config>::PathMigrationValidator,
+        mtu_endpoint: &mut <Self::Config as endpoint::Config>::MtuEndpoint,
         max_mtu: MaxMtu,
         subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
     ) -> Result<path::Id, DatagramDropReason>;
 
+    /// Closes the connection if the peer sent more bytes during the handshake than the
+    /// configured limit before its address was validated
+    fn check_unvalidated_bytes(
+        &mut self,
+        timestamp: Timestamp,
+        subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
+    ) -> Result<(), connection::Error>;
+
+    /// Returns the number of bytes exchanged with the peer before its address was validated
+    fn unvalidated_bytes(&self) -> path::UnvalidatedBytes;
+
+    /// Assigns the connection to a tenant, which is notified of the resources used by the
+    /// connection until it is dropped
+    fn on_tenant(
+        &mut self,
+        tenant: <<Self::Config as endpoint::Config>::TenantClassifier as tenant::Classifier>::Tenant,
+    );
+
     /// Returns the Connections interests
     fn interests(&self) -> ConnectionInterests;
 
     /// Returns the QUIC version selected for the current connection
     fn quic_version(&self) -> u32;
 
+    /// Returns `true` if packets with the QUIC bit cleared are accepted
+    fn accepts_cleared_quic_bit(&self) -> bool;
+
     /// Handles reception of a single QUIC packet
     #[allow(clippy::too_many_arguments)]
     fn handle_packet(
@@ -220,6 +252,10 @@ pub trait ConnectionTrait: 'static + Send + Sized {
         packet_interceptor: &mut <Self::Config as endpoint::Config>::PacketInterceptor,
         datagram_endpoint: &mut <Self::Config as endpoint::Config>::DatagramEndpoint,
     ) -> Result<(), ProcessingError> {
+        // Every datagram attributed to the connection counts towards the limit, even if none of
+        // its packets can be processed.
+        self.check_unvalidated_bytes(datagram.timestamp, subscriber)?;
+
         //= https://www.rfc-editor.org/rfc/rfc9000#section-5.2.1
         //# If a client receives a packet that uses a different version than it
         //# initially selected, it MUST discard that packet.
@@ -242,6 +278,34 @@ pub trait ConnectionTrait: 'static + Send + Sized {
             }
         }
 
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2
+        //# Packets containing a zero
+        //# value for this bit are not valid packets in this version and MUST
+        //# be discarded.
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-17.3.1
+        //# Packets
+        //# containing a zero value for this bit are not valid packets in this
+        //# version and MUST be discarded.
+
+        // A connection which advertised the `grease_quic_bit` transport parameter from RFC 9287
+        // allows the peer to clear the bit, and must accept these packets.
+        if packet.quic_bit().is_zero() && !self.accepts_cleared_quic_bit() {
+            self.with_event_publisher(
+                datagram.timestamp,
+                Some(path_id),
+                subscriber,
+                |publisher, path| {
+                    publisher.on_packet_dropped(event::builder::PacketDropped {
+                        reason: event::builder::PacketDropReason::DecodingFailed {
+                            path: path_event!(path, path_id),
+                        },
+                    })
+                },
+            );
+            return Ok(());
+        }
+
         //= https://www.rfc-editor.org/rfc/rfc9001#section-4.1.4
         //# An endpoint SHOULD continue
         //# to respond to packets that can be processed during this time.
@@ -430,24 +494,98 @@ pub trait ConnectionTrait: 'static + Send + Sized {
 
     fn application_close(&mut self, error: Option<application::Error>);
 
+    fn create_stream_group(
+        &mut self,
+        max_data: VarInt,
+    ) -> Result<stream::group::Id, connection::Error>;
+
+    fn join_stream_group(
+        &mut self,
+        group: stream::group::Id,
+        stream_id: stream::StreamId,
+    ) -> Result<(), stream::StreamError>;
+
+    fn set_stream_group_max_data(
+        &mut self,
+        group: stream::group::Id,
+        max_data: VarInt,
+    ) -> Result<(), connection::Error>;
+
+    fn set_stream_group_urgency(
+        &mut self,
+        group: stream::group::Id,
+        urgency: u8,
+    ) -> Result<(), connection::Error>;
+
+    fn set_stream_incremental(
+        &mut self,
+        stream_id: stream::StreamId,
+        incremental: bool,
+    ) -> Result<(), stream::StreamError>;
+
+    fn reset_stream_group(
+        &mut self,
+        group: stream::group::Id,
+        error: application::Error,
+    ) -> Result<(), connection::Error>;
+
     fn server_name(&self) -> Option<ServerName>;
 
     fn application_protocol(&self) -> Bytes;
 
+    fn peer_certificates(&self) -> Vec<Bytes>;
+
+    fn peer_application_settings(&self) -> Option<Bytes>;
+
+    /// Returns the metadata about the handshake of the connection
+    fn handshake_info(&self) -> connection::HandshakeInfo;
+
     fn ping(&mut self) -> Result<(), connection::Error>;
 
     fn keep_alive(&mut self, enabled: bool) -> Result<(), connection::Error>;
 
+    fn migrate(&mut self, local_address: SocketAddress) -> Result<(), migration::Error>;
+
+    /// Exports the state of the connection so it can be imported by another endpoint
+    ///
+    /// The connection is closed silently once the state was exported.
+    fn export_state(&mut self) -> Result<transfer::State, transfer::Error>;
+
+    /// Restores the application space of a connection which was exported by another endpoint
+    fn on_transferred(
+        &mut self,
+        state: &transfer::State,
+        timestamp: Timestamp,
+        random_generator: &mut <Self::Config as endpoint::Config>::RandomGenerator,
+        subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
+        datagram_endpoint: &mut <Self::Config as endpoint::Config>::DatagramEndpoint,
+    ) -> Result<(), transfer::Error>;
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error>;
 
     fn remote_address(&self) -> Result<SocketAddress, connection::Error>;
 
+    fn arena_stats(&self) -> Result<connection::arena::Stats, connection::Error>;
+
+    fn memory_usage(&self) -> Result<connection::memory::Usage, connection::Error>;
+
+    fn handshake_timing(&self) -> Result<connection::HandshakeTiming, connection::Error>;
+
+    fn correlation_id(&self) -> Result<connection::CorrelationId, connection::Error>;
+
+    #[cfg(feature = "state-snapshot")]
+    fn snapshot(&self) -> Result<connection::snapshot::Snapshot, connection::Error>;
+
     fn error(&self) -> Option<connection::Error>;
 
     fn query_event_context(&self, query: &mut dyn query::Query);
 
     fn query_event_context_mut(&mut self, query: &mut dyn query::QueryMut);
 
+    fn query_extensions(&self, query: &mut dyn query::Query);
+
+    fn query_extensions_mut(&mut self, query: &mut dyn query::QueryMut);
+
     fn datagram_mut(&mut self, query: &mut dyn query::QueryMut);
 
     fn with_event_publisher<F>(
@@ -7,17 +7,20 @@ use crate::{
         InternalConnectionId,
     },
     contexts::WriteContext,
+    shared::Mutex,
     transmission,
 };
+use alloc::{sync::Arc, vec::Vec};
 use core::convert::TryInto;
 use s2n_quic_core::{
-    ack, connection, frame,
+    ack,
+    connection::{self, transfer},
+    frame, inet,
     packet::number::PacketNumber,
     stateless_reset,
     time::{timer, Duration, Timer, Timestamp},
 };
 use smallvec::SmallVec;
-use std::sync::{Arc, Mutex};
 
 /// The amount of ConnectionIds we can register without dynamic memory allocation
 const NR_STATIC_REGISTRABLE_IDS: usize = 5;
@@ -79,6 +82,9 @@ pub struct LocalIdRegistry {
     active_connection_id_limit: u8,
     /// Timer set to track retiring and expired connection IDs
     expiration_timer: Timer,
+    /// The remote address packets are routed by if the connection uses a zero-length
+    /// connection ID
+    remote_address: Option<inet::SocketAddress>,
 }
 
 #[derive(Debug)]
@@ -192,6 +198,10 @@ pub enum LocalIdRegistrationError {
     ConnectionIdInUse,
     /// An invalid sequence number was specified
     InvalidSequenceNumber,
+    /// The sequence number referred to the connection ID the retirement was received on
+    RetireCurrentConnectionId,
+    /// A connection ID was retired while a zero-length connection ID is in use
+    RetireZeroLengthConnectionId,
 }
 
 impl LocalIdRegistrationError {
@@ -199,6 +209,12 @@ impl LocalIdRegistrationError {
         match self {
             LocalIdRegistrationError::ConnectionIdInUse => "Connection ID already in use",
             LocalIdRegistrationError::InvalidSequenceNumber => "Invalid sequence number",
+            LocalIdRegistrationError::RetireCurrentConnectionId => {
+                "The connection ID the retirement was received on cannot be retired"
+            }
+            LocalIdRegistrationError::RetireZeroLengthConnectionId => {
+                "A zero-length connection ID cannot be retired"
+            }
         }
     }
 }
@@ -217,6 +233,10 @@ impl Drop for LocalIdRegistry {
 
         // Also clean up the initial ID if it had not already been removed
         guard.initial_id_map.remove(&self.internal_id);
+
+        if let Some(remote_address) = self.remote_address {
+            guard.remote_address_map.remove(&remote_address);
+        }
     }
 }
 
@@ -239,13 +259,28 @@ impl LocalIdRegistry {
             // from the peer transport parameters
             active_connection_id_limit: 1,
             expiration_timer: Timer::default(),
+            remote_address: None,
         };
 
-        let _ = registry.register_connection_id(
-            handshake_connection_id,
-            handshake_connection_id_expiration_time,
-            stateless_reset_token,
-        );
+        if handshake_connection_id.is_empty() {
+            // Zero-length connection IDs are shared by all of the connections using them, so
+            // the ID isn't inserted into the map. The connection is instead routed by its remote
+            // address once it's registered with `register_remote_address`.
+            registry.registered_ids.push(LocalIdInfo {
+                id: *handshake_connection_id,
+                sequence_number: 0,
+                retirement_time: None,
+                stateless_reset_token,
+                status: PendingIssuance,
+            });
+            registry.next_sequence_number = 1;
+        } else {
+            let _ = registry.register_connection_id(
+                handshake_connection_id,
+                handshake_connection_id_expiration_time,
+                stateless_reset_token,
+            );
+        }
 
         let handshake_connection_id_info = registry
             .registered_ids
@@ -264,11 +299,134 @@ impl LocalIdRegistry {
         registry
     }
 
+    /// Constructs a `LocalIdRegistry` with the connection IDs of a transferred connection
+    ///
+    /// This will return an error if one of the connection IDs is already used by a
+    /// different internal connection.
+    pub(crate) fn import(
+        internal_id: InternalConnectionId,
+        state: Arc<Mutex<ConnectionIdMapperState>>,
+        connection_ids: &[transfer::LocalConnectionId],
+        next_sequence_number: u32,
+        retire_prior_to: u32,
+    ) -> Result<Self, LocalIdRegistrationError> {
+        let mut registry = Self {
+            internal_id,
+            state,
+            registered_ids: SmallVec::new(),
+            next_sequence_number,
+            retire_prior_to,
+            active_connection_id_limit: 1,
+            expiration_timer: Timer::default(),
+            remote_address: None,
+        };
+
+        for connection_id in connection_ids {
+            // Only IDs which were inserted are tracked, so a failed import
+            // doesn't remove the IDs of other connections when it's dropped
+            registry
+                .state
+                .lock()
+                .expect("should succeed unless the lock is poisoned")
+                .local_id_map
+                .try_insert(&connection_id.id, internal_id)
+                .map_err(|_| LocalIdRegistrationError::ConnectionIdInUse)?;
+
+            let status = match connection_id.status {
+                transfer::LocalIdStatus::Active => Active,
+                transfer::LocalIdStatus::Pending => PendingIssuance,
+                transfer::LocalIdStatus::Retired => PendingRetirementConfirmation(None),
+            };
+
+            registry.registered_ids.push(LocalIdInfo {
+                id: connection_id.id,
+                sequence_number: connection_id.sequence_number,
+                retirement_time: None,
+                stateless_reset_token: connection_id.stateless_reset_token,
+                status,
+            });
+        }
+
+        Ok(registry)
+    }
+
+    /// Returns the registered connection IDs for transferring the connection to another
+    /// endpoint
+    ///
+    /// Connection IDs which are pending removal are not exported.
+    pub fn export(&self) -> Vec<transfer::LocalConnectionId> {
+        self.registered_ids
+            .iter()
+            .filter_map(|id_info| {
+                let status = match id_info.status {
+                    PendingIssuance | PendingReissue | PendingAcknowledgement(_) => {
+                        transfer::LocalIdStatus::Pending
+                    }
+                    Active => transfer::LocalIdStatus::Active,
+                    PendingRetirementConfirmation(_) => transfer::LocalIdStatus::Retired,
+                    PendingRemoval(_) => return None,
+                };
+
+                Some(transfer::LocalConnectionId {
+                    sequence_number: id_info.sequence_number,
+                    id: id_info.id,
+                    stateless_reset_token: id_info.stateless_reset_token,
+                    status,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the sequence number of the next connection ID which is issued
+    pub fn next_sequence_number(&self) -> u32 {
+        self.next_sequence_number
+    }
+
+    /// Returns the sequence number below which all connection IDs are retired
+    pub fn retire_prior_to(&self) -> u32 {
+        self.retire_prior_to
+    }
+
+    /// Returns the maximum number of connection IDs given to the peer
+    pub fn active_connection_id_limit(&self) -> u64 {
+        self.active_connection_id_limit as u64
+    }
+
     /// Returns the associated internal connection ID
     pub fn internal_connection_id(&self) -> InternalConnectionId {
         self.internal_id
     }
 
+    /// Returns true if the connection uses a zero-length connection ID
+    pub fn is_zero_length(&self) -> bool {
+        self.registered_ids
+            .first()
+            .map_or(false, |id_info| id_info.id.is_empty())
+    }
+
+    /// Routes packets with a zero-length destination connection ID from the given remote
+    /// address to this connection
+    ///
+    /// This will return an error if another connection using zero-length connection IDs
+    /// is already registered with the remote address.
+    pub fn register_remote_address(
+        &mut self,
+        remote_address: &inet::SocketAddress,
+    ) -> Result<(), LocalIdRegistrationError> {
+        debug_assert!(self.is_zero_length());
+        debug_assert!(self.remote_address.is_none());
+
+        self.state
+            .lock()
+            .expect("should succeed unless the lock is poisoned")
+            .remote_address_map
+            .try_insert(remote_address, self.internal_id)
+            .map_err(|_| LocalIdRegistrationError::ConnectionIdInUse)?;
+        self.remote_address = Some(*remote_address);
+
+        Ok(())
+    }
+
     /// Sets the active connection id limit
     pub fn set_active_connection_id_limit(&mut self, active_connection_id_limit: u64) {
         //= https://www.rfc-editor.org/rfc/rfc9000#section-5.1.1
@@ -388,6 +546,14 @@ impl LocalIdRegistry {
             return Err(LocalIdRegistrationError::InvalidSequenceNumber);
         }
 
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-19.16
+        //# An endpoint that provides a zero-
+        //# length connection ID MUST treat receipt of a RETIRE_CONNECTION_ID
+        //# frame as a connection error of type PROTOCOL_VIOLATION.
+        if self.is_zero_length() {
+            return Err(LocalIdRegistrationError::RetireZeroLengthConnectionId);
+        }
+
         let id_info = self
             .registered_ids
             .iter_mut()
@@ -402,11 +568,7 @@ impl LocalIdRegistry {
                 //# The sequence number specified in a RETIRE_CONNECTION_ID frame MUST
                 //# NOT refer to the Destination Connection ID field of the packet in
                 //# which the frame is contained.
-
-                //= https://www.rfc-editor.org/rfc/rfc9000#section-19.16
-                //# The peer MAY treat this as a
-                //# connection error of type PROTOCOL_VIOLATION.
-                return Err(LocalIdRegistrationError::InvalidSequenceNumber);
+                return Err(LocalIdRegistrationError::RetireCurrentConnectionId);
             }
 
             // Calculate a removal time based on RTT to give sufficient time for out of
@@ -422,6 +584,13 @@ impl LocalIdRegistry {
 
     /// Returns the mappers interest in new connection IDs
     pub fn connection_id_interest(&self) -> connection::id::Interest {
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-5.1.1
+        //# An endpoint that selects a zero-length connection ID during the
+        //# handshake cannot issue a new connection ID.
+        if self.is_zero_length() {
+            return connection::id::Interest::None;
+        }
+
         let active_connection_id_count = self
             .registered_ids
             .iter()
@@ -526,6 +695,11 @@ impl LocalIdRegistry {
 
     /// Requests the peer to retire the connection id used during the handshake
     pub fn retire_handshake_connection_id(&mut self) {
+        // A zero-length connection ID is the only one used for the connection
+        if self.is_zero_length() {
+            return;
+        }
+
         if let Some(handshake_id_info) = self
             .registered_ids
             .iter_mut()
@@ -273,7 +273,7 @@ fn on_retire_connection_id() {
     //# The peer MAY treat this as a
     //# connection error of type PROTOCOL_VIOLATION.
     assert_eq!(
-        Some(LocalIdRegistrationError::InvalidSequenceNumber),
+        Some(LocalIdRegistrationError::RetireCurrentConnectionId),
         reg1.on_retire_connection_id(1, &ext_id_2, Duration::default(), now)
             .err()
     );
@@ -903,3 +903,75 @@ fn retire_handshake_connection_id() {
         }
     }
 }
+
+#[test]
+fn zero_length_connection_id() {
+    let mut random_generator = random::testing::Generator(123);
+    let mut mapper = ConnectionIdMapper::new(&mut random_generator, endpoint::Type::Client);
+    let mut id_generator = InternalConnectionIdGenerator::new();
+    let empty_id = id(&[]);
+    let now = s2n_quic_platform::time::now();
+    let remote_address = s2n_quic_core::inet::SocketAddress::default();
+
+    let mut reg1 = mapper.create_local_id_registry(
+        id_generator.generate_id(),
+        &empty_id,
+        Some(now + MIN_LIFETIME),
+        TEST_TOKEN_1,
+    );
+    assert!(reg1.is_zero_length());
+
+    // zero-length connection IDs are routed by the remote address
+    assert_eq!(None, mapper.lookup_internal_connection_id(&empty_id));
+    assert_eq!(
+        None,
+        mapper.lookup_internal_connection_id_by_remote_address(&remote_address)
+    );
+    assert!(reg1.register_remote_address(&remote_address).is_ok());
+    assert_eq!(
+        Some(reg1.internal_connection_id()),
+        mapper.lookup_internal_connection_id_by_remote_address(&remote_address)
+    );
+
+    // a second connection can't use zero-length connection IDs with the same remote address
+    let mut reg2 =
+        mapper.create_local_id_registry(id_generator.generate_id(), &empty_id, None, TEST_TOKEN_2);
+    assert_eq!(
+        Err(LocalIdRegistrationError::ConnectionIdInUse),
+        reg2.register_remote_address(&remote_address)
+    );
+
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-5.1.1
+    //= type=test
+    //# An endpoint that selects a zero-length connection ID during the
+    //# handshake cannot issue a new connection ID.
+    reg1.set_active_connection_id_limit(3);
+    assert_eq!(
+        connection::id::Interest::None,
+        reg1.connection_id_interest()
+    );
+
+    // the handshake connection ID is never retired or expired
+    reg1.retire_handshake_connection_id();
+    reg1.on_timeout(now + MIN_LIFETIME);
+    assert!(!reg1.registered_ids[0].is_retired());
+    assert_eq!(None, reg1.next_status_change_time());
+
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-19.16
+    //= type=test
+    //# An endpoint that provides a zero-
+    //# length connection ID MUST treat receipt of a RETIRE_CONNECTION_ID
+    //# frame as a connection error of type PROTOCOL_VIOLATION.
+    assert_eq!(
+        Err(LocalIdRegistrationError::RetireZeroLengthConnectionId),
+        reg1.on_retire_connection_id(0, &id(b"id01"), Duration::from_millis(100), now)
+    );
+
+    // the remote address is available again once the connection is dropped
+    drop(reg1);
+    assert_eq!(
+        None,
+        mapper.lookup_internal_connection_id_by_remote_address(&remote_address)
+    );
+    assert!(reg2.register_remote_address(&remote_address).is_ok());
+}
@@ -7,7 +7,14 @@ use crate::{
     endpoint, path::MaxMtu, recovery::congestion_controller, space::PacketSpaceManager,
     wakeup_queue::WakeupHandle,
 };
-use s2n_quic_core::{connection, event, event::supervisor, time::Timestamp};
+use bytes::Bytes;
+use s2n_quic_core::{
+    connection,
+    event::{self, supervisor, IntoEvent as _},
+    extension_frame,
+    time::Timestamp,
+    transport,
+};
 
 mod api;
 mod api_provider;
@@ -49,6 +56,8 @@ pub use s2n_quic_core::connection::*;
 pub struct Parameters<'a, Cfg: endpoint::Config> {
     /// The [`Connection`]s internal identifier
     pub internal_connection_id: InternalConnectionId,
+    /// The ID which is attached to the events of the connection
+    pub correlation_id: connection::CorrelationId,
     /// The local ID registry which should be utilized by the connection
     pub local_id_registry: LocalIdRegistry,
     /// The peer ID registry which should be utilized by the connection
@@ -69,6 +78,8 @@ pub struct Parameters<'a, Cfg: endpoint::Config> {
     pub congestion_controller: <Cfg::CongestionControllerEndpoint as congestion_controller::Endpoint>::CongestionController,
     /// The time the connection is being created
     pub timestamp: Timestamp,
+    /// The address validation token the client sent in its first Initial packet
+    pub address_token: Option<Bytes>,
     /// The QUIC protocol version which is used for this particular connection
     pub quic_version: u32,
     /// The limits that were advertised to the peer
@@ -79,8 +90,42 @@ pub struct Parameters<'a, Cfg: endpoint::Config> {
     pub event_context: <Cfg::EventSubscriber as event::Subscriber>::ConnectionContext,
     /// The context passed to the connection supervisor
     pub supervisor_context: &'a supervisor::Context<'a>,
+    /// The random generator for the endpoint
+    pub random_generator: &'a mut Cfg::RandomGenerator,
     // The datagram provider for the endpoint
     pub datagram_endpoint: &'a mut Cfg::DatagramEndpoint,
+    /// The per-path MTU configuration for the endpoint
+    pub mtu_endpoint: &'a mut Cfg::MtuEndpoint,
+    /// The protocol violation policy for the endpoint
+    pub protocol_violation_endpoint: &'a mut Cfg::ProtocolViolationEndpoint,
+    /// The stream scheduler for the endpoint
+    pub stream_scheduler_endpoint: &'a mut Cfg::StreamSchedulerEndpoint,
+    /// The extension frame handler which was offered to the peer
+    pub extension_frame_handler:
+        Option<<Cfg::ExtensionFrameEndpoint as extension_frame::Endpoint>::Handler>,
     /// The event subscriber for the endpoint
     pub event_subscriber: &'a mut Cfg::EventSubscriber,
 }
+
+/// Consults the policy for a protocol violation by the peer
+///
+/// Returns an error if the connection should be closed. Otherwise the violation is reported
+/// and the caller ignores the frame that caused it.
+pub(crate) fn on_protocol_violation<
+    Policy: connection::protocol_violation::Policy,
+    Pub: event::ConnectionPublisher,
+>(
+    violation: connection::protocol_violation::Violation,
+    policy: &mut Policy,
+    publisher: &mut Pub,
+) -> Result<(), transport::Error> {
+    match policy.on_violation(&violation) {
+        connection::protocol_violation::Outcome::Close => Err(violation.error()),
+        connection::protocol_violation::Outcome::Ignore => {
+            publisher.on_protocol_violation_ignored(event::builder::ProtocolViolationIgnored {
+                violation: violation.into_event(),
+            });
+            Ok(())
+        }
+    }
+}
@@ -17,17 +17,20 @@ use crate::{
         InternalConnectionId,
     },
     path,
+    shared::Mutex,
     transmission::{self, WriteContext},
 };
+use alloc::{sync::Arc, vec::Vec};
 use s2n_quic_core::{
-    ack, connection, endpoint,
+    ack,
+    connection::{self, transfer},
+    endpoint,
     event::{self, IntoEvent},
     frame,
     packet::number::PacketNumber,
     stateless_reset, transport,
 };
 use smallvec::SmallVec;
-use std::sync::{Arc, Mutex};
 
 /// The amount of ConnectionIds we can register without dynamic memory allocation
 const NR_STATIC_REGISTRABLE_IDS: usize = 5;
@@ -254,6 +257,81 @@ impl PeerIdRegistry {
         }
     }
 
+    /// Constructs a `PeerIdRegistry` with the connection IDs of a transferred connection
+    pub(crate) fn import(
+        internal_id: InternalConnectionId,
+        state: Arc<Mutex<ConnectionIdMapperState>>,
+        connection_ids: &[transfer::PeerConnectionId],
+        retire_prior_to: u32,
+    ) -> Self {
+        let mut registry = Self::new(internal_id, state);
+        registry.retire_prior_to = retire_prior_to;
+
+        {
+            let mut guard = registry
+                .state
+                .lock()
+                .expect("should succeed unless the lock is poisoned");
+
+            for connection_id in connection_ids {
+                if let Some(token) = connection_id.stateless_reset_token {
+                    guard.stateless_reset_map.insert(token, internal_id);
+                }
+            }
+        }
+
+        for connection_id in connection_ids {
+            let status = match connection_id.status {
+                transfer::PeerIdStatus::New => New,
+                transfer::PeerIdStatus::InUse => InUse,
+                transfer::PeerIdStatus::InUsePendingNewConnectionId => InUsePendingNewConnectionId,
+                // The RETIRE_CONNECTION_ID frame is sent again by the importing endpoint
+                transfer::PeerIdStatus::Retired => PendingRetirement,
+            };
+
+            registry.registered_ids.push(PeerIdInfo {
+                id: connection_id.id,
+                sequence_number: connection_id.sequence_number,
+                stateless_reset_token: connection_id.stateless_reset_token,
+                status,
+            });
+        }
+
+        registry
+    }
+
+    /// Returns the registered connection IDs for transferring the connection to another
+    /// endpoint
+    pub fn export(&self) -> Vec<transfer::PeerConnectionId> {
+        self.registered_ids
+            .iter()
+            .map(|id_info| {
+                let status = match id_info.status {
+                    New => transfer::PeerIdStatus::New,
+                    InUse => transfer::PeerIdStatus::InUse,
+                    InUsePendingNewConnectionId => {
+                        transfer::PeerIdStatus::InUsePendingNewConnectionId
+                    }
+                    PendingRetirement
+                    | PendingRetirementRetransmission
+                    | PendingAcknowledgement(_) => transfer::PeerIdStatus::Retired,
+                };
+
+                transfer::PeerConnectionId {
+                    sequence_number: id_info.sequence_number,
+                    id: id_info.id,
+                    stateless_reset_token: id_info.stateless_reset_token,
+                    status,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the largest retire prior to value which was received from the peer
+    pub fn retire_prior_to(&self) -> u32 {
+        self.retire_prior_to
+    }
+
     /// Used to register the initial peer DestinationConnectionId.
     ///
     /// For a Server endpoint this happens immediately after creation of the
@@ -315,23 +393,27 @@ impl PeerIdRegistry {
         retire_prior_to: u32,
         stateless_reset_token: &stateless_reset::Token,
     ) -> Result<(), PeerIdRegistrationError> {
+        // Validate the new ID against all registered IDs before modifying any state, so an
+        // invalid NEW_CONNECTION_ID frame can be ignored without side effects
+        let mut is_duplicate = false;
+        for id_info in self.registered_ids.iter() {
+            is_duplicate |= id_info.validate_new_connection_id(
+                new_id,
+                stateless_reset_token,
+                sequence_number,
+            )?;
+        }
+
         //= https://www.rfc-editor.org/rfc/rfc9000#section-19.15
         //# A receiver MUST ignore any Retire Prior To fields that do not
         //# increase the largest received Retire Prior To value.
         self.retire_prior_to = self.retire_prior_to.max(retire_prior_to);
 
         let mut active_id_count = 0;
-        let mut is_duplicate = false;
         let mut id_pending_new_connection_id = None;
 
         // Iterate over all registered IDs, retiring any as necessary
         for id_info in self.registered_ids.iter_mut() {
-            is_duplicate |= id_info.validate_new_connection_id(
-                new_id,
-                stateless_reset_token,
-                sequence_number,
-            )?;
-
             if id_info.is_retire_ready(self.retire_prior_to) {
                 //= https://www.rfc-editor.org/rfc/rfc9000#section-5.1.2
                 //# Upon receipt of an increased Retire Prior To field, the peer MUST
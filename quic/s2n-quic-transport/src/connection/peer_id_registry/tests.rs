@@ -207,6 +207,26 @@ fn non_duplicate_new_id_same_token_or_sequence_number() {
     assert_eq!(Some(InvalidNewConnectionId), result.err());
 }
 
+// An invalid NEW_CONNECTION_ID frame may be ignored, so it must not modify the registry
+#[test]
+fn invalid_new_id_does_not_modify_registry() {
+    let id_1 = id(b"id01");
+    let mut reg = peer_registry(id_1, None);
+
+    let id_2 = id(b"id02");
+    let id_3 = id(b"id03");
+    assert!(reg.on_new_connection_id(&id_2, 1, 0, &TEST_TOKEN_1).is_ok());
+    assert_eq!(New, reg.registered_ids[1].status);
+
+    // Reuses the sequence number of id_2 while retiring all prior IDs
+    let result = reg.on_new_connection_id(&id_3, 1, 2, &TEST_TOKEN_2);
+    assert_eq!(Some(InvalidNewConnectionId), result.err());
+
+    assert_eq!(0, reg.retire_prior_to);
+    assert_eq!(2, reg.registered_ids.len());
+    assert_eq!(New, reg.registered_ids[1].status);
+}
+
 //= https://www.rfc-editor.org/rfc/rfc9000#section-19.15
 //= type=test
 //# A receiver MUST ignore any Retire Prior To fields that do not
@@ -8,6 +8,7 @@ use crate::{
 use core::time::Duration;
 use s2n_codec::{Encoder, EncoderBuffer};
 use s2n_quic_core::{
+    connection::limits::InitialPadding,
     event::{self, ConnectionPublisher as _},
     frame::ack_elicitation::AckElicitable,
     inet::ExplicitCongestionNotification,
@@ -21,6 +22,9 @@ use s2n_quic_core::{
 pub struct ConnectionTransmissionContext<'a, 'sub, Config: endpoint::Config> {
     pub quic_version: u32,
     pub timestamp: Timestamp,
+    /// How long before their departure time packets may be written, if the transmission queue
+    /// paces them
+    pub pacing_horizon: Duration,
     pub path_id: path::Id,
     pub path_manager: &'a mut path::Manager<Config>,
     pub local_id_registry: &'a mut connection::LocalIdRegistry,
@@ -28,6 +32,10 @@ pub struct ConnectionTransmissionContext<'a, 'sub, Config: endpoint::Config> {
     pub ecn: ExplicitCongestionNotification,
     pub min_packet_len: Option<usize>,
     pub transmission_mode: transmission::Mode,
+    /// Packets of different packet number spaces may be coalesced into a single datagram
+    pub packet_coalescing: bool,
+    /// How datagrams carrying Initial packets are padded
+    pub initial_padding: InitialPadding,
     pub publisher: &'a mut event::ConnectionPublisherSubscriber<'sub, Config::EventSubscriber>,
     pub packet_interceptor: &'a mut Config::PacketInterceptor,
 }
@@ -37,6 +45,23 @@ impl<'a, 'sub, Config: endpoint::Config> ConnectionTransmissionContext<'a, 'sub,
         &self.path_manager[self.path_id]
     }
 
+    /// Returns how long the packet needs to be held back to meet its earliest departure time
+    ///
+    /// Packets are only written ahead of their departure time if the transmission queue paces
+    /// them.
+    pub fn departure_delay(&self) -> Duration {
+        if self.pacing_horizon.is_zero() {
+            return Duration::ZERO;
+        }
+
+        self.path()
+            .congestion_controller
+            .earliest_departure_time()
+            .map_or(Duration::ZERO, |edt| {
+                edt.saturating_duration_since(self.timestamp)
+            })
+    }
+
     pub fn path_mut(&mut self) -> &mut Path<Config> {
         &mut self.path_manager[self.path_id]
     }
@@ -62,8 +87,7 @@ impl<'a, 'sub, Config: endpoint::Config> tx::Message for ConnectionTransmission<
 
     #[inline]
     fn delay(&mut self) -> Duration {
-        // TODO return delay from pacer
-        Default::default()
+        self.context.departure_delay()
     }
 
     #[inline]
@@ -86,6 +110,12 @@ impl<'a, 'sub, Config: endpoint::Config> tx::Message for ConnectionTransmission<
             }
         }
 
+        // Segments are sent along with the first packet of the datagram, so packets which are
+        // held back by the transmission queue need to start a new datagram
+        if !self.context.departure_delay().is_zero() {
+            return false;
+        }
+
         // If a packet can be GSO'd it means it's limited to the previously written packet
         // size. This becomes a problem for MTU probes where they will likely exceed that amount.
         // As such, if we're probing we want to let the IO layer know to not GSO the current
@@ -167,11 +197,14 @@ impl<'a, 'sub, Config: endpoint::Config> tx::Message for ConnectionTransmission<
             // if there will be an ApplicationData packet, since those packets come at the end of the
             // datagram. If there is no ApplicationData packet, the Handshake packet will come at the
             // end, so we check that next. Finally, if there is no ApplicationData or Handshake packet
-            // to transmit, the Initial packet itself will be padded.
+            // to transmit, or packets aren't coalesced, the Initial packet itself will be padded.
+            let coalesce = self.context.packet_coalescing;
             let mut pn_space_to_pad = {
                 if !has_transmission(space_manager.initial(), transmission_constraint) {
                     // There is no Initial packet, so no padding is needed
                     None
+                } else if !coalesce {
+                    Some(PacketNumberSpace::Initial)
                 } else if has_transmission(space_manager.application(), transmission_constraint) {
                     Some(PacketNumberSpace::ApplicationData)
                 } else if has_transmission(space_manager.handshake(), transmission_constraint) {
@@ -183,6 +216,14 @@ impl<'a, 'sub, Config: endpoint::Config> tx::Message for ConnectionTransmission<
                 }
             };
 
+            // With minimal padding, the packets which were already coalesced into the datagram
+            // count towards the 1200 bytes
+            let initial_padding = self.context.initial_padding;
+            let padded_len = |encoder: &EncoderBuffer| {
+                initial_padding
+                    .min_packet_len(initial_capacity - encoder.capacity(), encoder.capacity())
+            };
+
             //= https://www.rfc-editor.org/rfc/rfc9001#section-4
             //# When packets of different types need to be sent,
             //# endpoints SHOULD use coalesced packets to send them in the same UDP
@@ -191,6 +232,12 @@ impl<'a, 'sub, Config: endpoint::Config> tx::Message for ConnectionTransmission<
 
             let is_mtu_probing = self.context.transmission_mode.is_mtu_probing();
 
+            // When coalescing is disabled, the following spaces are only queried if the previous
+            // spaces didn't write a packet. The spaces return the remaining part of the buffer, so
+            // the capacity is compared rather than the length.
+            let can_coalesce =
+                |encoder: &EncoderBuffer| coalesce || encoder.capacity() == initial_capacity;
+
             let encoder = if let Some((space, handshake_status)) = space_manager
                 .initial_mut()
                 // MTU probes are only sent in the Application Space
@@ -198,7 +245,7 @@ impl<'a, 'sub, Config: endpoint::Config> tx::Message for ConnectionTransmission<
             {
                 self.context.min_packet_len = pn_space_to_pad
                     .filter(|pn_space| pn_space.is_initial())
-                    .map(|_| encoder.capacity());
+                    .map(|_| padded_len(&encoder));
 
                 match space.on_transmit(
                     &mut self.context,
@@ -246,11 +293,11 @@ impl<'a, 'sub, Config: endpoint::Config> tx::Message for ConnectionTransmission<
             let encoder = if let Some((space, handshake_status)) = space_manager
                 .handshake_mut()
                 // MTU probes are only sent in the Application Space
-                .filter(|_| !is_mtu_probing)
+                .filter(|_| !is_mtu_probing && can_coalesce(&encoder))
             {
                 self.context.min_packet_len = pn_space_to_pad
                     .filter(|pn_space| pn_space.is_handshake())
-                    .map(|_| encoder.capacity());
+                    .map(|_| padded_len(&encoder));
 
                 let encoder = match space.on_transmit(
                     &mut self.context,
@@ -319,10 +366,13 @@ impl<'a, 'sub, Config: endpoint::Config> tx::Message for ConnectionTransmission<
             // frames are only allowed in the ApplicationData space, which will always be the highest
             // current-available encryption level.
 
-            let encoder = if let Some((space, handshake_status)) = space_manager.application_mut() {
+            let encoder = if let Some((space, handshake_status)) = space_manager
+                .application_mut()
+                .filter(|_| can_coalesce(&encoder))
+            {
                 self.context.min_packet_len = pn_space_to_pad
                     .filter(|pn_space| pn_space.is_application_data())
-                    .map(|_| encoder.capacity());
+                    .map(|_| padded_len(&encoder));
 
                 // Pad the packet when sending path validation frames so that MTU is also validated.
                 let path = &self.context.path_manager[self.context.path_id];
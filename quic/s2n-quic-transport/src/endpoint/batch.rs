@@ -0,0 +1,119 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Groups received datagrams by the connection they are routed to
+//!
+//! Datagrams of a single connection usually arrive back to back, e.g. when the peer is
+//! transmitting a burst of packets or the socket coalesces them with GRO. Rather than looking
+//! up the connection, updating its interests and finalizing the container for every datagram,
+//! the endpoint collects runs of consecutive datagrams for the same connection into a
+//! [`Batch`] and processes all of them with a single access to the connection.
+
+use crate::connection::InternalConnectionId;
+use s2n_codec::DecoderBufferMut;
+use s2n_quic_core::{
+    inet::{datagram, DatagramInfo},
+    packet::ProtectedPacket,
+    stateless_reset,
+};
+use smallvec::SmallVec;
+
+/// The maximum number of datagrams which are processed with a single connection access
+///
+/// This bounds the time the connection is held before its interests are updated.
+pub const CAPACITY: usize = 16;
+
+/// A received datagram, of which the first packet has been decoded and routed to a connection
+#[derive(Debug)]
+pub struct Datagram<'a, Handle> {
+    pub header: datagram::Header<Handle>,
+    pub info: DatagramInfo,
+    pub packet: ProtectedPacket<'a>,
+    pub remaining: DecoderBufferMut<'a>,
+    /// The last bytes of the datagram, which are compared against the stateless reset tokens
+    /// if the first packet fails to decrypt
+    pub stateless_reset_token: Option<stateless_reset::Token>,
+}
+
+/// A run of consecutive entries for the same connection
+#[derive(Debug)]
+pub struct Batch<T> {
+    connection_id: Option<InternalConnectionId>,
+    entries: SmallVec<[T; CAPACITY]>,
+}
+
+impl<T> Default for Batch<T> {
+    fn default() -> Self {
+        Self {
+            connection_id: None,
+            entries: SmallVec::new(),
+        }
+    }
+}
+
+impl<T> Batch<T> {
+    /// Returns `true` if an entry for `connection_id` can be appended without taking the batch
+    #[inline]
+    pub fn can_push(&self, connection_id: InternalConnectionId) -> bool {
+        self.entries.is_empty()
+            || (self.connection_id == Some(connection_id) && self.entries.len() < CAPACITY)
+    }
+
+    /// Appends an entry for `connection_id`
+    #[inline]
+    pub fn push(&mut self, connection_id: InternalConnectionId, entry: T) {
+        debug_assert!(self.can_push(connection_id));
+        self.connection_id = Some(connection_id);
+        self.entries.push(entry);
+    }
+
+    /// Takes all of the entries out of the batch, along with the connection they belong to
+    #[inline]
+    pub fn take(&mut self) -> Option<(InternalConnectionId, smallvec::IntoIter<[T; CAPACITY]>)> {
+        let connection_id = self.connection_id.take()?;
+        let entries = core::mem::take(&mut self.entries);
+        Some((connection_id, entries.into_iter()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::InternalConnectionIdGenerator;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn batch_test() {
+        let mut generator = InternalConnectionIdGenerator::new();
+        let a = generator.generate_id();
+        let b = generator.generate_id();
+
+        let mut batch = Batch::default();
+        assert!(batch.take().is_none());
+        assert!(batch.can_push(a));
+        assert!(batch.can_push(b));
+
+        batch.push(a, 0);
+        batch.push(a, 1);
+
+        // entries for other connections need the batch to be taken first
+        assert!(batch.can_push(a));
+        assert!(!batch.can_push(b));
+
+        let (id, entries) = batch.take().unwrap();
+        assert_eq!(id, a);
+        assert_eq!(entries.collect::<Vec<_>>(), [0, 1]);
+        assert!(batch.take().is_none());
+        assert!(batch.can_push(b));
+
+        for i in 0..CAPACITY {
+            batch.push(b, i);
+        }
+
+        // the batch is limited to `CAPACITY` entries
+        assert!(!batch.can_push(b));
+        let (id, entries) = batch.take().unwrap();
+        assert_eq!(id, b);
+        assert_eq!(entries.len(), CAPACITY);
+    }
+}
@@ -2,12 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::handle::CloseReceiver;
-use crate::{connection, endpoint::handle::CloseSender};
-use alloc::sync::Arc;
-use core::{
-    sync::atomic::{AtomicBool, Ordering},
-    task::{Context, Poll, Waker},
+use crate::{
+    connection,
+    endpoint::handle::CloseSender,
+    shared::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
+use core::task::{Context, Poll, Waker};
 
 /// Held by library. Used to receive close attempts and track close state.
 #[derive(Debug)]
@@ -5,8 +5,8 @@
 
 use crate::{connection, stream};
 use s2n_quic_core::{
-    crypto::tls, datagram, endpoint, event, packet, path, random, recovery::congestion_controller,
-    stateless_reset,
+    ack, crypto::tls, datagram, endpoint, event, packet, path, random,
+    recovery::congestion_controller, stateless_reset,
 };
 
 /// Configuration parameters for a QUIC endpoint
@@ -40,10 +40,24 @@ pub trait Config: 'static + Send + Sized + core::fmt::Debug {
     type PathHandle: path::Handle;
     /// The path migration validator for the endpoint
     type PathMigrationValidator: path::migration::Validator;
+    /// The per-path MTU configuration for the endpoint
+    type MtuEndpoint: path::mtu::Endpoint;
+    /// The per-connection ACK strategy for the endpoint
+    type AckEndpoint: ack::strategy::Endpoint;
     /// The packet_interceptor implementation for the endpoint
     type PacketInterceptor: packet::interceptor::Interceptor;
     /// The datagram implementation for the endpoint
     type DatagramEndpoint: datagram::Endpoint;
+    /// The protocol violation policy for the endpoint
+    type ProtocolViolationEndpoint: connection::protocol_violation::Endpoint;
+    /// The per-connection stream scheduler for the endpoint
+    type StreamSchedulerEndpoint: s2n_quic_core::stream::scheduler::Endpoint;
+    /// The per-connection extension frame handler for the endpoint
+    type ExtensionFrameEndpoint: s2n_quic_core::extension_frame::Endpoint;
+    /// Assigns the connections of the endpoint to tenants
+    type TenantClassifier: endpoint::tenant::Classifier;
+    /// Forwards datagrams to back-ends if the endpoint is a stateless front-end
+    type RetryOffload: endpoint::retry_offload::Forwarder;
 
     /// The type of the local endpoint
     const ENDPOINT_TYPE: endpoint::Type;
@@ -84,7 +98,21 @@ pub struct Context<'a, Cfg: Config> {
 
     pub path_migration: &'a mut Cfg::PathMigrationValidator,
 
+    pub mtu: &'a mut Cfg::MtuEndpoint,
+
+    pub ack: &'a mut Cfg::AckEndpoint,
+
     pub packet_interceptor: &'a mut Cfg::PacketInterceptor,
 
     pub datagram: &'a mut Cfg::DatagramEndpoint,
+
+    pub protocol_violation: &'a mut Cfg::ProtocolViolationEndpoint,
+
+    pub stream_scheduler: &'a mut Cfg::StreamSchedulerEndpoint,
+
+    pub extension_frame: &'a mut Cfg::ExtensionFrameEndpoint,
+
+    pub tenant: &'a mut Cfg::TenantClassifier,
+
+    pub retry_offload: &'a mut Cfg::RetryOffload,
 }
@@ -5,6 +5,7 @@ use crate::{
     connection::{self, Connection},
     endpoint::handle::ConnectorSender,
 };
+use alloc::vec::Vec;
 use core::{
     fmt,
     future::Future,
@@ -12,7 +13,9 @@ use core::{
     task::{Context, Poll},
 };
 use futures_channel::oneshot;
-use s2n_quic_core::{application::ServerName, inet::SocketAddress, path::RemoteAddress};
+use s2n_quic_core::{
+    application::ServerName, connection::CorrelationId, inet::SocketAddress, path::RemoteAddress,
+};
 
 /// Held by connection Attempt future. Used to receive the actual connection.
 pub(crate) type ConnectionReceiver = oneshot::Receiver<Result<Connection, connection::Error>>;
@@ -25,6 +28,9 @@ pub(crate) type ConnectionSender = oneshot::Sender<Result<Connection, connection
 pub struct Connect {
     pub(crate) remote_address: RemoteAddress,
     pub(crate) server_name: Option<ServerName>,
+    /// Addresses which are raced against the `remote_address`
+    pub(crate) alternative_addresses: Vec<RemoteAddress>,
+    pub(crate) correlation_id: Option<CorrelationId>,
 }
 
 impl fmt::Display for Connect {
@@ -49,6 +55,8 @@ impl Connect {
         Self {
             remote_address: addr.into().into(),
             server_name: None,
+            alternative_addresses: Vec::new(),
+            correlation_id: None,
         }
     }
 
@@ -60,6 +68,56 @@ impl Connect {
             ..self
         }
     }
+
+    /// Specifies the correlation ID which is attached to the events of the connection
+    ///
+    /// This allows joining the events of the connection with the traces of the application, for
+    /// example by passing the trace-id of the current trace. A random correlation ID is
+    /// generated if none is specified. All of the connections which are raced with
+    /// [`Self::with_addresses`] use the same correlation ID.
+    #[must_use]
+    pub fn with_correlation_id<Id: Into<CorrelationId>>(self, correlation_id: Id) -> Self {
+        Self {
+            correlation_id: Some(correlation_id.into()),
+            ..self
+        }
+    }
+
+    /// Races the connection attempt across additional addresses of the same server
+    ///
+    /// A connection is opened to each of the addresses at the same time, for example to the
+    /// results of a DNS lookup or to multiple anycast points of presence. The first connection
+    /// to complete the handshake is returned and the other connections are closed. The attempt
+    /// only fails if all of the connections fail.
+    #[must_use]
+    pub fn with_addresses<I, Addr>(mut self, addresses: I) -> Self
+    where
+        I: IntoIterator<Item = Addr>,
+        Addr: Into<SocketAddress>,
+    {
+        let addresses = addresses
+            .into_iter()
+            .map(|addr| RemoteAddress::from(addr.into()));
+        self.alternative_addresses.extend(addresses);
+        self
+    }
+
+    /// Splits the connection attempt into an attempt for each of the addresses
+    fn split(mut self) -> impl Iterator<Item = Self> {
+        let alternative_addresses = core::mem::take(&mut self.alternative_addresses);
+        let server_name = self.server_name.clone();
+        let correlation_id = self.correlation_id;
+        core::iter::once(self).chain(
+            alternative_addresses
+                .into_iter()
+                .map(move |remote_address| Self {
+                    remote_address,
+                    server_name: server_name.clone(),
+                    alternative_addresses: Vec::new(),
+                    correlation_id,
+                }),
+        )
+    }
 }
 
 /// Make it easy for applications to create a connection attempt without importing the `Connect` struct
@@ -78,7 +136,8 @@ pub(crate) struct Request {
 
 #[must_use = "futures do nothing unless you `.await` or poll them"]
 pub struct Attempt {
-    state: AttemptState,
+    /// The attempts for each of the addresses which haven't failed yet
+    states: Vec<AttemptState>,
 }
 
 impl Attempt {
@@ -91,17 +150,26 @@ impl Attempt {
     /// * The attempt returns a `Self` while holding on to the oneshot receiver
     /// * The application polls the `Attempt` until either a successful `Connection` or `connection::Error` is
     ///   received over the oneshot receiver.
+    ///
+    /// If the `Connect` struct contains multiple addresses, a `Request` is made for each of them
+    /// and the first successful `Connection` is returned. Dropping the receivers of the remaining
+    /// requests notifies the endpoint to close their connections.
     pub(crate) fn new(opener: &ConnectorSender, connect: Connect) -> Self {
-        // open a oneshot channel to receive the connection or error after the endpoint attempted the handshake
-        let (response, receiver) = oneshot::channel();
-        // The request includes both the connection info and response onshot channel
-        let request = Request {
-            connect,
-            sender: response,
-        };
-        Self {
-            state: AttemptState::Connect(request, opener.clone(), receiver),
-        }
+        let states = connect
+            .split()
+            .map(|connect| {
+                // open a oneshot channel to receive the connection or error after the endpoint attempted the handshake
+                let (response, receiver) = oneshot::channel();
+                // The request includes both the connection info and response onshot channel
+                let request = Request {
+                    connect,
+                    sender: response,
+                };
+                AttemptState::Connect(request, opener.clone(), receiver)
+            })
+            .collect();
+
+        Self { states }
     }
 }
 
@@ -114,24 +182,22 @@ enum AttemptState {
     Unreachable,
 }
 
-impl Future for Attempt {
-    type Output = Result<Connection, connection::Error>;
-
-    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+impl AttemptState {
+    fn poll(&mut self, cx: &mut Context<'_>) -> Poll<Result<Connection, connection::Error>> {
         loop {
-            match core::mem::replace(&mut self.state, AttemptState::Unreachable) {
+            match core::mem::replace(self, AttemptState::Unreachable) {
                 AttemptState::Connect(request, mut opener, response) => {
                     match opener.poll_ready(cx) {
                         Poll::Ready(Ok(())) => {
                             match opener.try_send(request) {
                                 Ok(_) => {
                                     // transition to the waiting state
-                                    self.state = AttemptState::Waiting(response);
+                                    *self = AttemptState::Waiting(response);
                                     continue;
                                 }
                                 Err(err) if err.is_full() => {
                                     // reset to the original state
-                                    self.state =
+                                    *self =
                                         AttemptState::Connect(err.into_inner(), opener, response);
 
                                     // yield and wake up the task since the opener misreported its ready state
@@ -149,7 +215,7 @@ impl Future for Attempt {
                         }
                         Poll::Pending => {
                             // reset to the original state
-                            self.state = AttemptState::Connect(request, opener, response);
+                            *self = AttemptState::Connect(request, opener, response);
                         }
                     }
 
@@ -163,7 +229,7 @@ impl Future for Attempt {
                             Err(connection::Error::unspecified()).into()
                         }
                         Poll::Pending => {
-                            self.state = AttemptState::Waiting(response);
+                            *self = AttemptState::Waiting(response);
                             Poll::Pending
                         }
                     };
@@ -177,3 +243,33 @@ impl Future for Attempt {
         }
     }
 }
+
+impl Future for Attempt {
+    type Output = Result<Connection, connection::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut index = 0;
+
+        while let Some(state) = self.states.get_mut(index) {
+            match state.poll(cx) {
+                Poll::Ready(Ok(connection)) => {
+                    // the remaining attempts lost the race so they are closed by the endpoint once
+                    // their receivers are dropped
+                    self.states.clear();
+                    return Poll::Ready(Ok(connection));
+                }
+                Poll::Ready(Err(error)) => {
+                    self.states.swap_remove(index);
+
+                    // only fail once all of the addresses have failed
+                    if self.states.is_empty() {
+                        return Poll::Ready(Err(error));
+                    }
+                }
+                Poll::Pending => index += 1,
+            }
+        }
+
+        Poll::Pending
+    }
+}
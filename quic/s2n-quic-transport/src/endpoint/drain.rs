@@ -0,0 +1,243 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(any(loom, not(feature = "std")))]
+use crate::endpoint::mpsc;
+use crate::{connection, endpoint::close::Closer};
+use alloc::{vec, vec::Vec};
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+#[cfg(all(feature = "std", not(loom)))]
+use futures_channel::mpsc;
+use futures_core::Stream;
+use s2n_quic_core::{application, time::Timestamp};
+
+/// Held by library. Used to receive drain requests from the application.
+pub(crate) type DrainReceiver = mpsc::Receiver<Drain>;
+/// Held by the application. Used to submit drain requests to the library.
+pub(crate) type DrainSender = mpsc::Sender<Drain>;
+
+/// Configures how an endpoint drains its connections
+///
+/// While draining, the endpoint stops accepting new connections and waits for the open ones
+/// to close. If a deadline is configured, any connections which are still open once it
+/// expires are closed with the configured error code.
+#[derive(Clone, Copy, Debug)]
+pub struct Drain {
+    pub(crate) deadline: Option<Duration>,
+    pub(crate) error: application::Error,
+}
+
+impl Default for Drain {
+    fn default() -> Self {
+        Self {
+            deadline: None,
+            error: application::Error::UNKNOWN,
+        }
+    }
+}
+
+impl Drain {
+    /// Creates a drain request which waits for all of the connections to close
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the amount of time the open connections have to close before the endpoint
+    /// closes them
+    #[must_use]
+    pub fn with_deadline(self, deadline: Duration) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+
+    /// Sets the error code which is sent to peers of connections which are closed when the
+    /// deadline expires
+    #[must_use]
+    pub fn with_error(self, error: application::Error) -> Self {
+        Self { error, ..self }
+    }
+}
+
+/// Held by library. Used to receive drain requests and track the draining state.
+#[derive(Debug)]
+pub(crate) struct DrainHandle {
+    drain_receiver: DrainReceiver,
+    state: Option<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    deadline: Option<Timestamp>,
+    error: application::Error,
+    remaining_connections: usize,
+}
+
+impl DrainHandle {
+    pub fn new(drain_receiver: DrainReceiver) -> Self {
+        Self {
+            drain_receiver,
+            state: None,
+        }
+    }
+
+    /// Returns `true` if the application has requested the endpoint to drain
+    pub fn is_draining(&self) -> bool {
+        self.state.is_some()
+    }
+
+    /// Polls for a drain request from the application
+    ///
+    /// Returns the request if the endpoint is not already draining.
+    pub fn poll_request(&mut self, context: &mut Context) -> Poll<Drain> {
+        loop {
+            match Stream::poll_next(Pin::new(&mut self.drain_receiver), context) {
+                // the first request determines how the endpoint drains
+                Poll::Ready(Some(_)) if self.is_draining() => continue,
+                Poll::Ready(Some(drain)) => return Poll::Ready(drain),
+                Poll::Ready(None) | Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    /// Starts draining the endpoint
+    pub fn start(&mut self, drain: Drain, active_connections: usize, now: Timestamp) {
+        debug_assert!(!self.is_draining());
+
+        self.state = Some(State {
+            deadline: drain.deadline.map(|deadline| now + deadline),
+            error: drain.error,
+            remaining_connections: active_connections,
+        });
+    }
+
+    /// Updates the number of remaining connections
+    ///
+    /// Returns `true` if the number of connections decreased since the last update.
+    pub fn on_remaining_connections(&mut self, remaining_connections: usize) -> bool {
+        if let Some(state) = self.state.as_mut() {
+            if remaining_connections < state.remaining_connections {
+                state.remaining_connections = remaining_connections;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns the time at which the remaining connections will be closed
+    pub fn timeout(&self) -> Option<Timestamp> {
+        self.state.as_ref()?.deadline
+    }
+
+    /// Returns the error to close the remaining connections with if the deadline has expired
+    ///
+    /// The error is only returned once.
+    pub fn on_timeout(&mut self, now: Timestamp) -> Option<application::Error> {
+        let state = self.state.as_mut()?;
+        if !state.deadline?.has_elapsed(now) {
+            return None;
+        }
+        state.deadline = None;
+        Some(state.error)
+    }
+}
+
+/// Held by the application. Used to request the endpoint to drain.
+///
+/// When the connections are sharded across multiple endpoints, the request is sent to each
+/// endpoint and the drainer waits for all of them to close.
+#[derive(Clone, Debug)]
+pub struct Drainer {
+    endpoints: Vec<EndpointDrainer>,
+}
+
+impl Drainer {
+    pub(crate) fn new(drain_sender: DrainSender, closer: Closer) -> Self {
+        Self {
+            endpoints: vec![EndpointDrainer {
+                request_sent: false,
+                drain_sender,
+                closer,
+            }],
+        }
+    }
+
+    /// Combines the drainers of multiple endpoints into a single drainer
+    pub(crate) fn join<I: IntoIterator<Item = Self>>(drainers: I) -> Self {
+        let endpoints = drainers
+            .into_iter()
+            .flat_map(|drainer| drainer.endpoints)
+            .collect();
+        Self { endpoints }
+    }
+
+    /// Requests the endpoint to drain and polls for the endpoint to close
+    pub fn poll_drain(
+        &mut self,
+        context: &mut Context,
+        drain: Drain,
+    ) -> Poll<Result<(), connection::Error>> {
+        let mut is_ready = true;
+
+        for endpoint in &mut self.endpoints {
+            match endpoint.poll_drain(context, drain) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => is_ready = false,
+            }
+        }
+
+        if is_ready {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EndpointDrainer {
+    request_sent: bool,
+    drain_sender: DrainSender,
+    closer: Closer,
+}
+
+impl EndpointDrainer {
+    fn poll_drain(
+        &mut self,
+        context: &mut Context,
+        drain: Drain,
+    ) -> Poll<Result<(), connection::Error>> {
+        if !self.request_sent {
+            match self.drain_sender.poll_ready(context) {
+                Poll::Ready(Ok(())) => match self.drain_sender.try_send(drain) {
+                    Ok(_) => {
+                        self.request_sent = true;
+                    }
+                    Err(err) if err.is_full() => {
+                        // yield and wake up the task since the sender misreported its ready state
+                        context.waker().wake_by_ref();
+                        return Poll::Pending;
+                    }
+                    Err(_) => {
+                        // the endpoint is closed so return
+                        return Poll::Ready(Ok(()));
+                    }
+                },
+                Poll::Ready(Err(_)) => {
+                    // the endpoint is closed so return
+                    return Poll::Ready(Ok(()));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        self.closer.poll_close(context)
+    }
+}
@@ -0,0 +1,241 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Shares the transmission queue between the connections of an endpoint
+//!
+//! Each connection which wants to transmit takes a turn to write its packets into the queue.
+//! With [round robin](s2n_quic_core::endpoint::limits::TransmitFairness::RoundRobin) fairness,
+//! a [`Turn`] ends once the connection wrote its quantum of bytes. The connection is then
+//! appended to the transmission list again, and the endpoint starts another round over the list
+//! as long as the queue has capacity, so a bulk transfer can't starve the other connections.
+
+use core::time::Duration;
+use s2n_quic_core::io::tx;
+
+/// Limits the number of bytes a connection writes into the transmission queue in its turn
+#[derive(Debug)]
+pub struct Turn<'a, Q> {
+    queue: &'a mut Q,
+    /// The number of bytes the connection can still write, if limited
+    remaining: Option<usize>,
+}
+
+impl<'a, Q: tx::Queue> Turn<'a, Q> {
+    #[inline]
+    pub fn new(queue: &'a mut Q, quantum: Option<usize>) -> Self {
+        Self {
+            queue,
+            remaining: quantum,
+        }
+    }
+
+    /// Returns `true` if the connection wrote its quantum of bytes
+    #[inline]
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == Some(0)
+    }
+}
+
+impl<'a, Q: tx::Queue> tx::Queue for Turn<'a, Q> {
+    type Entry = Q::Entry;
+    type Handle = Q::Handle;
+
+    const SUPPORTS_ECN: bool = Q::SUPPORTS_ECN;
+    const SUPPORTS_PACING: bool = Q::SUPPORTS_PACING;
+    const SUPPORTS_FLOW_LABELS: bool = Q::SUPPORTS_FLOW_LABELS;
+
+    #[inline]
+    fn push<M: tx::Message<Handle = Self::Handle>>(
+        &mut self,
+        message: M,
+    ) -> Result<tx::Outcome, tx::Error> {
+        // the message is rejected before it's written, as if the queue was full
+        if self.is_exhausted() {
+            return Err(tx::Error::AtCapacity);
+        }
+
+        let outcome = self.queue.push(message)?;
+
+        // the last datagram of a turn may exceed the quantum
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining = remaining.saturating_sub(outcome.len);
+        }
+
+        Ok(outcome)
+    }
+
+    #[inline]
+    fn as_slice_mut(&mut self) -> &mut [Self::Entry] {
+        self.queue.as_slice_mut()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        if self.is_exhausted() {
+            0
+        } else {
+            self.queue.capacity()
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    #[inline]
+    fn pacing_horizon(&self) -> Duration {
+        self.queue.pacing_horizon()
+    }
+}
+
+/// Counts the turns the connections took while filling the transmission queue
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of rounds over the transmission list
+    pub rounds: usize,
+    /// The number of turns the connections took
+    pub turns: usize,
+    /// The number of turns which ended because the connection wrote its quantum
+    pub quantum_exhausted: usize,
+}
+
+impl Stats {
+    #[inline]
+    pub fn on_turn<Q: tx::Queue>(&mut self, turn: &Turn<Q>) {
+        self.turns += 1;
+        if turn.is_exhausted() {
+            self.quantum_exhausted += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_quic_core::{inet::SocketAddress, io::tx::Queue as _, path::RemoteAddress};
+
+    #[derive(Debug, Default)]
+    struct Entry(Vec<u8>);
+
+    impl tx::Entry for Entry {
+        type Handle = RemoteAddress;
+
+        fn set<M: tx::Message<Handle = Self::Handle>>(
+            &mut self,
+            mut message: M,
+        ) -> Result<usize, tx::Error> {
+            self.0.resize(1500, 0);
+            let len = message.write_payload(tx::PayloadBuffer::new(&mut self.0), 0)?;
+            self.0.truncate(len);
+            Ok(len)
+        }
+
+        fn payload(&self) -> &[u8] {
+            &self.0
+        }
+
+        fn payload_mut(&mut self) -> &mut [u8] {
+            &mut self.0
+        }
+    }
+
+    #[derive(Debug)]
+    struct Queue {
+        entries: Vec<Entry>,
+        capacity: usize,
+    }
+
+    impl tx::Queue for Queue {
+        type Entry = Entry;
+        type Handle = RemoteAddress;
+
+        fn push<M: tx::Message<Handle = Self::Handle>>(
+            &mut self,
+            message: M,
+        ) -> Result<tx::Outcome, tx::Error> {
+            use tx::Entry as _;
+
+            if !self.has_capacity() {
+                return Err(tx::Error::AtCapacity);
+            }
+
+            let mut entry = Entry::default();
+            let len = entry.set(message)?;
+            self.entries.push(entry);
+
+            Ok(tx::Outcome {
+                len,
+                index: self.entries.len() - 1,
+            })
+        }
+
+        fn as_slice_mut(&mut self) -> &mut [Self::Entry] {
+            &mut self.entries
+        }
+
+        fn capacity(&self) -> usize {
+            self.capacity - self.entries.len()
+        }
+
+        fn len(&self) -> usize {
+            self.entries.len()
+        }
+    }
+
+    fn message(len: usize) -> (RemoteAddress, Vec<u8>) {
+        (RemoteAddress::from(SocketAddress::default()), vec![1; len])
+    }
+
+    #[test]
+    fn quantum_test() {
+        let mut queue = Queue {
+            entries: vec![],
+            capacity: 10,
+        };
+
+        let mut turn = Turn::new(&mut queue, Some(2500));
+        assert!(turn.push(message(1000)).is_ok());
+        assert!(turn.push(message(1000)).is_ok());
+        assert!(!turn.is_exhausted());
+        assert_eq!(turn.capacity(), 8);
+
+        // the last datagram may exceed the quantum
+        assert!(turn.push(message(1000)).is_ok());
+        assert!(turn.is_exhausted());
+        assert_eq!(turn.capacity(), 0);
+        assert_eq!(turn.push(message(1000)).err(), Some(tx::Error::AtCapacity));
+        assert_eq!(turn.len(), 3);
+
+        let mut stats = Stats::default();
+        stats.on_turn(&turn);
+        assert_eq!(stats.turns, 1);
+        assert_eq!(stats.quantum_exhausted, 1);
+
+        // the next turn gets a new quantum
+        let mut turn = Turn::new(&mut queue, Some(2500));
+        assert!(turn.push(message(1000)).is_ok());
+        assert!(!turn.is_exhausted());
+        stats.on_turn(&turn);
+        assert_eq!(stats.turns, 2);
+        assert_eq!(stats.quantum_exhausted, 1);
+        assert_eq!(queue.len(), 4);
+    }
+
+    #[test]
+    fn unlimited_test() {
+        let mut queue = Queue {
+            entries: vec![],
+            capacity: 3,
+        };
+
+        let mut turn = Turn::new(&mut queue, None);
+        for _ in 0..3 {
+            assert!(turn.push(message(1000)).is_ok());
+        }
+        assert!(!turn.is_exhausted());
+
+        // the turn is only limited by the capacity of the queue
+        assert_eq!(turn.push(message(1000)).err(), Some(tx::Error::AtCapacity));
+    }
+}
@@ -3,17 +3,22 @@
 
 //! Allows to accept connections
 
+#[cfg(any(loom, not(feature = "std")))]
+use super::mpsc;
 use crate::{
     connection,
     connection::Connection,
-    endpoint::{close, close::CloseHandle, connect},
+    endpoint::{close, close::CloseHandle, connect, drain, drain::DrainHandle, transfer},
 };
+use alloc::{vec, vec::Vec};
 use core::{
     pin::Pin,
     task::{Context, Poll, Waker},
 };
+#[cfg(all(feature = "std", not(loom)))]
 use futures_channel::mpsc;
 use futures_core::Stream;
+use s2n_quic_core::connection::{id::shard, transfer::State as TransferState};
 
 /// Held by application. Used to accept new connections.
 pub(crate) type AcceptorReceiver = mpsc::UnboundedReceiver<Connection>;
@@ -41,17 +46,29 @@ impl Handle {
     /// Creates a new `Handle` with a limit opening connection limit.
     pub(crate) fn new(
         max_opening_connections: usize,
-    ) -> (Self, AcceptorSender, ConnectorReceiver, CloseHandle) {
+    ) -> (
+        Self,
+        AcceptorSender,
+        ConnectorReceiver,
+        CloseHandle,
+        DrainHandle,
+        transfer::ImportReceiver,
+    ) {
         let (acceptor_sender, acceptor_receiver) = mpsc::unbounded();
         let (connector_sender, connector_receiver) = mpsc::channel(max_opening_connections);
 
         let (close_sender, close_receiver) = mpsc::channel(max_opening_connections);
+        let (drain_sender, drain_receiver) = mpsc::channel(1);
+        let (import_sender, import_receiver) = mpsc::unbounded();
 
         let endpoint_state = close::EndpointState::default();
         let closer = close::Closer::new(close_sender, endpoint_state.clone());
         let handle = Self {
             acceptor: Acceptor {
-                acceptor: acceptor_receiver,
+                acceptors: vec![acceptor_receiver],
+                importers: vec![import_sender],
+                next: 0,
+                drainer: drain::Drainer::new(drain_sender, closer.clone()),
             },
             connector: Connector {
                 connector: connector_sender,
@@ -63,16 +80,44 @@ impl Handle {
             acceptor_sender,
             connector_receiver,
             CloseHandle::new(close_receiver, endpoint_state),
+            DrainHandle::new(drain_receiver),
+            import_receiver,
         )
     }
 }
 
 #[derive(Debug)]
 pub struct Acceptor {
-    acceptor: AcceptorReceiver,
+    /// The receivers for each endpoint which hasn't closed yet
+    acceptors: Vec<AcceptorReceiver>,
+    /// The import senders for each endpoint, in shard order
+    importers: Vec<transfer::ImportSender>,
+    /// The index of the receiver which is polled first
+    next: usize,
+    drainer: drain::Drainer,
 }
 
 impl Acceptor {
+    /// Combines the acceptors of multiple endpoints into a single acceptor
+    pub(crate) fn join<I: IntoIterator<Item = Self>>(acceptors: I) -> Self {
+        let mut receivers = Vec::new();
+        let mut importers = Vec::new();
+        let mut drainers = Vec::new();
+
+        for acceptor in acceptors {
+            receivers.extend(acceptor.acceptors);
+            importers.extend(acceptor.importers);
+            drainers.push(acceptor.drainer);
+        }
+
+        Self {
+            acceptors: receivers,
+            importers,
+            next: 0,
+            drainer: drain::Drainer::join(drainers),
+        }
+    }
+
     /// Polls for incoming connections and returns them.
     ///
     /// The method will return
@@ -86,11 +131,54 @@ impl Acceptor {
     ///   [`Context`] parameter, and notify it as soon as retrying
     ///   the method will yield a different result.
     pub fn poll_accept(&mut self, context: &mut Context) -> Poll<Option<Connection>> {
-        match Stream::poll_next(Pin::new(&mut self.acceptor), context) {
-            Poll::Ready(Some(connection)) => Poll::Ready(Some(connection)),
-            Poll::Ready(None) => Poll::Ready(None),
-            Poll::Pending => Poll::Pending,
+        let mut remaining = self.acceptors.len();
+
+        while remaining > 0 {
+            let index = self.next % self.acceptors.len();
+
+            match Stream::poll_next(Pin::new(&mut self.acceptors[index]), context) {
+                Poll::Ready(Some(connection)) => {
+                    // start with the next endpoint on the following call so each endpoint is
+                    // accepted from fairly
+                    self.next = index + 1;
+                    return Poll::Ready(Some(connection));
+                }
+                Poll::Ready(None) => {
+                    // the endpoint is closed so stop polling it
+                    self.acceptors.swap_remove(index);
+                }
+                Poll::Pending => {
+                    self.next = index + 1;
+                }
+            }
+
+            remaining -= 1;
         }
+
+        if self.acceptors.is_empty() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Imports a connection which was exported by another endpoint
+    ///
+    /// The connection is routed to the shard which owns its connection ID. Once imported, the
+    /// connection is returned from [`Self::poll_accept`].
+    pub fn import(&self, state: TransferState) -> transfer::Import {
+        let importer = if self.importers.is_empty() {
+            None
+        } else {
+            let index = shard::index(state.local_connection_id.as_bytes(), self.importers.len());
+            self.importers.get(index)
+        };
+        transfer::Import::new(importer, state)
+    }
+
+    /// Returns a handle which can be used to drain the endpoint
+    pub fn drainer(&self) -> drain::Drainer {
+        self.drainer.clone()
     }
 }
 
@@ -12,17 +12,25 @@ use crate::{
     recovery::congestion_controller::{self, Endpoint as _},
     space::PacketSpaceManager,
 };
+use bytes::Bytes;
 use core::convert::TryInto;
 use s2n_codec::DecoderBufferMut;
 use s2n_quic_core::{
+    ack::strategy::Endpoint as _,
+    connection::CorrelationId,
     crypto::{tls, tls::Endpoint as TLSEndpoint, CryptoSuite, InitialKey},
     datagram::{Endpoint, PreConnectionInfo},
+    endpoint::tenant::{self, Classifier as _},
     event::{self, supervisor, ConnectionPublisher, IntoEvent, Subscriber as _},
+    extension_frame::{self, Endpoint as _, Handler as _},
     inet::{datagram, DatagramInfo},
     packet::initial::ProtectedInitial,
     path::Handle as _,
     stateless_reset::token::Generator as _,
-    transport::{self, parameters::ServerTransportParameters},
+    transport::{
+        self,
+        parameters::{ReservedParameter, ServerTransportParameters},
+    },
 };
 
 impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
@@ -86,11 +94,17 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
             // The destination connection ID on the packet was randomly generated by the client
             // so we'll generate a new initial_connection_id.
             let connection_info = ConnectionInfo::new(&remote_address);
-            initial_connection_id = self
-                .config
-                .context()
+            let endpoint_context = self.config.context();
+            initial_connection_id = endpoint_context
                 .connection_id_format
-                .generate(&connection_info);
+                .generate_with_random(&connection_info, endpoint_context.random_generator);
+        }
+
+        // Servers route packets by connection ID, even after the client migrates
+        if initial_connection_id.is_empty() {
+            return Err(transport::Error::INTERNAL_ERROR
+                .with_reason("servers cannot use zero-length connection ids")
+                .into());
         }
 
         //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2
@@ -147,14 +161,35 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
 
         let mut transport_parameters = ServerTransportParameters::default();
 
-        let limits = self
-            .config
-            .context()
+        let limits_info = LimitsInfo::new(&remote_address);
+        let endpoint_context = self.config.context();
+        let mut limits = endpoint_context
             .connection_limits
-            .on_connection(&LimitsInfo::new(&remote_address));
+            .on_connection(&limits_info);
+        if let Some(strategy) = endpoint_context.ack.on_connection(&limits_info) {
+            limits.load_ack_strategy(&strategy);
+        }
 
         transport_parameters.load_limits(&limits);
 
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-18.2
+        //# A server that does not
+        //# send this transport parameter cannot use stateless reset
+        //# (Section 10.3) for the connection ID negotiated during the
+        //# handshake.
+        if Config::StatelessResetTokenGenerator::ENABLED {
+            transport_parameters.stateless_reset_token = Some(stateless_reset_token);
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-18.1
+        //# Transport parameters with an identifier of the form "31 * N + 27" for
+        //# integer values of N are reserved to exercise the requirement that
+        //# unknown transport parameters be ignored.
+        if limits.grease_enabled() {
+            transport_parameters.reserved_parameter =
+                Some(ReservedParameter::random(endpoint_context.random_generator));
+        }
+
         //= https://www.rfc-editor.org/rfc/rfc9000#section-7.3
         //# A server includes the Destination Connection ID field from the first
         //# Initial packet it received from the client in the
@@ -209,6 +244,15 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
             .try_into()
             .expect("Failed to convert max_datagram_frame_size");
 
+        // the extension isn't offered if its transport parameter can't be sent
+        let extension_frame_handler = endpoint_context
+            .extension_frame
+            .new_handler(&extension_frame::ConnectionInfo::new(&remote_address))
+            .filter(|handler| {
+                let (id, value) = handler.transport_parameter();
+                transport_parameters.add_custom_parameter(id, value).is_ok()
+            });
+
         let tls_session = endpoint_context
             .tls
             .new_server_session(&transport_parameters);
@@ -220,9 +264,12 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
 
         let quic_version = packet.version;
 
+        let correlation_id = CorrelationId::random(endpoint_context.random_generator);
+
         let meta = event::builder::ConnectionMeta {
             endpoint_type: Config::ENDPOINT_TYPE,
             id: internal_connection_id.into(),
+            correlation_id,
             timestamp: datagram.timestamp,
         };
 
@@ -250,13 +297,17 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
             tls_session,
             initial_key,
             initial_header_key,
+            transport_parameters.into(),
             datagram.timestamp,
+            &limits,
+            endpoint_context.random_generator,
             &mut publisher,
         );
 
         let max_mtu = self.max_mtu;
         let connection_parameters = connection::Parameters {
             internal_connection_id,
+            correlation_id,
             local_id_registry,
             peer_id_registry,
             space_manager,
@@ -269,17 +320,26 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
             path_handle: header.path,
             congestion_controller,
             timestamp: datagram.timestamp,
+            address_token: (!packet.token.is_empty()).then(|| Bytes::copy_from_slice(packet.token)),
             quic_version,
             limits,
             max_mtu,
             event_context,
             supervisor_context: &supervisor_context,
+            random_generator: endpoint_context.random_generator,
             event_subscriber: endpoint_context.event_subscriber,
             datagram_endpoint: endpoint_context.datagram,
+            mtu_endpoint: endpoint_context.mtu,
+            protocol_violation_endpoint: endpoint_context.protocol_violation,
+            stream_scheduler_endpoint: endpoint_context.stream_scheduler,
+            extension_frame_handler,
         };
 
         let mut connection = <Config as endpoint::Config>::Connection::new(connection_parameters)?;
 
+        // the token is needed to classify the connection after the packet has been handled
+        let token = packet.token;
+
         let endpoint_context = self.config.context();
         let handle_first_packet =
             move |connection: &mut <Config as endpoint::Config>::Connection| {
@@ -288,6 +348,7 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
                     datagram,
                     endpoint_context.congestion_controller,
                     endpoint_context.path_migration,
+                    endpoint_context.mtu,
                     max_mtu,
                     endpoint_context.event_subscriber,
                 );
@@ -379,6 +440,38 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
             return Err(error);
         }
 
+        // The server name is known once the ClientHello has been processed, so connections are
+        // assigned to tenants after handling the first packet
+        let endpoint_context = self.config.context();
+        let server_name = connection.server_name();
+        let info = tenant::Info::new(
+            server_name.as_ref(),
+            token,
+            &remote_address,
+            datagram.timestamp.into_event(),
+        );
+
+        match endpoint_context.tenant.on_connection(&info) {
+            tenant::Outcome::Exempt { .. } => {}
+            tenant::Outcome::Admit { tenant, .. } => connection.on_tenant(tenant),
+            _ => {
+                // The connection is still inserted so the peer is notified with a
+                // CONNECTION_CLOSE frame while it drains
+                let error = connection::Error::from(
+                    transport::Error::CONNECTION_REFUSED.with_reason("tenant quota exceeded"),
+                );
+
+                connection.close(
+                    error,
+                    endpoint_context.connection_close_formatter,
+                    &mut self.close_packet_buffer,
+                    datagram.timestamp,
+                    endpoint_context.event_subscriber,
+                    endpoint_context.packet_interceptor,
+                );
+            }
+        }
+
         //= https://www.rfc-editor.org/rfc/rfc9001#section-4.3
         //= type=TODO
         //= tracking-issue=299
@@ -390,13 +483,19 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
         //# [QUIC-TRANSPORT]) to only buffer partial ClientHello messages from
         //# clients with a validated address.
 
-        let result = self
-            .connection_id_mapper
-            .try_insert_initial_id(original_destination_connection_id, internal_connection_id);
+        // The handshake is tracked along with the initial id so the endpoint can drop the
+        // retransmissions of the first flight which use a new Destination Connection ID
+        let result = self.connection_id_mapper.try_insert_initial_id(
+            original_destination_connection_id,
+            &remote_address,
+            &source_connection_id,
+            token,
+            internal_connection_id,
+        );
 
         debug_assert!(
             result.is_ok(),
-            "Initial ID {:?} was already in the map",
+            "Initial ID {:?} or its handshake was already in the map",
             original_destination_connection_id
         );
 
@@ -11,48 +11,62 @@ use crate::{
         InternalConnectionId, InternalConnectionIdGenerator, ProcessingError, Trait as _,
     },
     endpoint,
-    endpoint::close::CloseHandle,
+    endpoint::{close::CloseHandle, drain::DrainHandle},
     recovery::congestion_controller::{self, Endpoint as _},
     space::PacketSpaceManager,
     wakeup_queue::WakeupQueue,
 };
-use alloc::collections::VecDeque;
+use alloc::{collections::VecDeque, vec::Vec};
 use core::{
     convert::TryInto,
     task::{self, Poll},
 };
 use s2n_codec::{DecoderBuffer, DecoderBufferMut};
 use s2n_quic_core::{
+    ack::strategy::Endpoint as _,
     connection::{
         id::{ConnectionInfo, Generator},
-        InitialId, LocalId, PeerId,
+        CorrelationId, InitialId, LocalId, PeerId,
     },
     crypto::{tls, tls::Endpoint as _, CryptoSuite, InitialKey},
     datagram::{Endpoint as DatagramEndpoint, PreConnectionInfo},
-    endpoint::{limits::Outcome, Limiter as _},
+    endpoint::{
+        limits::Outcome,
+        retry_offload::{self, Forwarder as _},
+        Limiter as _,
+    },
     event::{
         self, supervisor, ConnectionPublisher, EndpointPublisher as _, IntoEvent, Subscriber as _,
     },
+    extension_frame::{self, Endpoint as _, Handler as _},
     inet::{datagram, DatagramInfo},
     io::{rx, tx},
-    packet::{initial::ProtectedInitial, interceptor::Interceptor, ProtectedPacket},
+    packet::{initial::ProtectedInitial, interceptor::Interceptor, ProtectedPacket, QuicBit},
     path,
     path::{Handle as _, MaxMtu},
     random::Generator as _,
-    stateless_reset::token::{Generator as _, LEN as StatelessResetTokenLen},
+    stateless_reset::token::{
+        Generator as _, Token as StatelessResetToken, LEN as StatelessResetTokenLen,
+    },
     time::{Clock, Timestamp},
     token::{self, Format},
-    transport::parameters::ClientTransportParameters,
+    transport::parameters::{ClientTransportParameters, ReservedParameter},
 };
 
+mod batch;
 pub mod close;
 mod config;
 pub mod connect;
+pub mod drain;
+mod fairness;
 pub mod handle;
 mod initial;
+#[cfg(any(test, loom, not(feature = "std")))]
+mod mpsc;
 mod packet_buffer;
 mod retry;
 mod stateless_reset;
+pub mod transfer;
 mod version;
 
 // exports
@@ -62,6 +76,9 @@ pub use s2n_quic_core::endpoint::*;
 
 const DEFAULT_MAX_PEERS: usize = 1024;
 
+/// The datagrams which are queued to be dispatched to a connection
+type Batch<'a, Handle> = batch::Batch<batch::Datagram<'a, Handle>>;
+
 /// A QUIC `Endpoint`
 pub struct Endpoint<Cfg: Config> {
     /// Configuration parameters for the endpoint
@@ -77,6 +94,10 @@ pub struct Endpoint<Cfg: Config> {
     wakeup_queue: WakeupQueue<InternalConnectionId>,
     /// Used to receive close attempts and track close state.
     close_handle: CloseHandle,
+    /// Used to receive drain requests and track the draining state.
+    drain_handle: DrainHandle,
+    /// Used to receive connections which were exported by another endpoint
+    import_receiver: transfer::ImportReceiver,
     /// This queue contains wakeups we retrieved from the [`Self::wakeup_queue`] earlier.
     /// This is not a local variable in order to reuse the allocated queue capacity in between
     /// [`Endpoint`] interactions.
@@ -87,6 +108,15 @@ pub struct Endpoint<Cfg: Config> {
     close_packet_buffer: packet_buffer::Buffer,
     /// The largest maximum transmission unit (MTU) that can be sent on a path
     max_mtu: MaxMtu,
+    /// The number of received datagrams which are waiting to be processed
+    rx_backlog: usize,
+    /// The number of bytes which were left in the transmission queue by the IO provider
+    unsent_bytes: usize,
+    /// The latest time at which the endpoint processed datagrams, wakeups or timers
+    ///
+    /// Received datagrams are never backdated past this time, since packets may have been sent
+    /// or timers may have expired up until then.
+    latest_timestamp: Option<Timestamp>,
 }
 
 impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
@@ -105,8 +135,14 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
         let local_address = queue.local_address();
         let entries = queue.as_slice_mut();
         let mut now: Option<Timestamp> = None;
+        let mut batch = Batch::default();
+
+        let len = entries.len();
+
+        for (index, entry) in entries.iter_mut().enumerate() {
+            // the remaining datagrams indicate how far behind the endpoint is
+            self.rx_backlog = len - index;
 
-        for entry in entries.iter_mut() {
             let timestamp = match now {
                 Some(time) => time,
                 None => {
@@ -116,11 +152,18 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
             };
 
             if let Some((header, payload)) = entry.read(&local_address) {
-                self.receive_datagram(&header, payload, timestamp)
+                self.receive_datagram(&mut batch, &header, payload, timestamp)
             }
         }
 
-        let len = entries.len();
+        self.receive_batch(&mut batch);
+        // the batch borrows the entries, which are released by finishing the queue
+        drop(batch);
+
+        if now.is_some() {
+            self.latest_timestamp = now;
+        }
+        self.rx_backlog = 0;
         queue.finish(len);
     }
 
@@ -129,38 +172,81 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
         Tx: tx::Queue<Handle = Self::PathHandle>,
         C: Clock,
     {
+        use tx::Entry;
+
         self.on_timeout(clock.get_time());
 
+        // datagrams which are still queued weren't flushed by the IO provider since the last
+        // transmission
+        self.unsent_bytes = queue
+            .as_slice_mut()
+            .iter()
+            .map(|entry| entry.payload().len())
+            .sum();
+
         // Iterate over all connections which want to transmit data
         let mut transmit_result = Ok(());
         let endpoint_context = self.config.context();
 
         let timestamp = clock.get_time();
+        self.latest_timestamp = Some(timestamp);
+
+        // a connection which used its quantum takes another turn after the other connections
+        let quantum = endpoint_context
+            .endpoint_limits
+            .transmit_fairness()
+            .quantum();
+        let mut stats = fairness::Stats::default();
+
+        loop {
+            let exhausted_turns = stats.quantum_exhausted;
+            stats.rounds += 1;
+
+            self.connections.iterate_transmission_list(|connection| {
+                let mut turn = fairness::Turn::new(queue, quantum);
+                transmit_result = connection.on_transmit(
+                    &mut turn,
+                    timestamp,
+                    endpoint_context.event_subscriber,
+                    endpoint_context.packet_interceptor,
+                );
+                stats.on_turn(&turn);
 
-        self.connections.iterate_transmission_list(|connection| {
-            transmit_result = connection.on_transmit(
-                queue,
-                timestamp,
-                endpoint_context.event_subscriber,
-                endpoint_context.packet_interceptor,
-            );
-            if transmit_result.is_err() {
-                // If one connection fails, return
-                ConnectionContainerIterationResult::BreakAndInsertAtBack
-            } else {
-                ConnectionContainerIterationResult::Continue
+                if transmit_result.is_err() {
+                    // If one connection fails, return
+                    ConnectionContainerIterationResult::BreakAndInsertAtBack
+                } else {
+                    ConnectionContainerIterationResult::Continue
+                }
+            });
+
+            // another round is only needed if a connection was cut short by its quantum
+            if transmit_result.is_err()
+                || stats.quantum_exhausted == exhausted_turns
+                || !queue.has_capacity()
+            {
+                break;
             }
-        });
+        }
+
+        let mut publisher = event::EndpointPublisherSubscriber::new(
+            event::builder::EndpointMeta {
+                endpoint_type: Cfg::ENDPOINT_TYPE,
+                timestamp,
+            },
+            None,
+            endpoint_context.event_subscriber,
+        );
+
+        if stats.quantum_exhausted > 0 {
+            publisher.on_endpoint_transmit_scheduled(event::builder::EndpointTransmitScheduled {
+                rounds: stats.rounds,
+                turns: stats.turns,
+                quantum_exhausted: stats.quantum_exhausted,
+            });
+        }
 
         if transmit_result.is_ok() {
-            let mut publisher = event::EndpointPublisherSubscriber::new(
-                event::builder::EndpointMeta {
-                    endpoint_type: Cfg::ENDPOINT_TYPE,
-                    timestamp,
-                },
-                None,
-                endpoint_context.event_subscriber,
-            );
             self.version_negotiator.on_transmit(queue, &mut publisher);
             self.retry_dispatch.on_transmit(queue, &mut publisher);
             self.stateless_reset_dispatch
@@ -173,7 +259,23 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
         cx: &mut task::Context<'_>,
         clock: &C,
     ) -> Poll<Result<usize, s2n_quic_core::endpoint::CloseError>> {
-        if self.close_handle.poll_interest().is_ready() // poll for close interest
+        let mut wakeup_count = 0;
+
+        if let Poll::Ready(drain) = self.drain_handle.poll_request(cx) {
+            wakeup_count += 1;
+            self.on_drain_request(drain, clock.get_time());
+        } else if self
+            .drain_handle
+            .on_remaining_connections(self.connections.len())
+        {
+            self.publish_drain_progress(clock.get_time());
+        }
+
+        // poll for close interest
+        let close_interest =
+            self.close_handle.poll_interest().is_ready() || self.drain_handle.is_draining();
+
+        if close_interest
             && self.connections.is_empty() // wait for all connections to close gracefully
             && self.connections.is_open()
         {
@@ -193,8 +295,9 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
             .poll_pending_wakeups(&mut self.dequeued_wakeups, cx);
 
         let mut now: Option<Timestamp> = None;
-        let mut wakeup_count = self.dequeued_wakeups.len();
+        wakeup_count += self.dequeued_wakeups.len();
         let close_packet_buffer = &mut self.close_packet_buffer;
+        let max_mtu = self.max_mtu;
         let endpoint_context = self.config.context();
 
         for internal_id in self.dequeued_wakeups.drain(..) {
@@ -210,6 +313,10 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
 
                 if let Err(error) = conn.on_wakeup(
                     timestamp,
+                    endpoint_context.congestion_controller,
+                    endpoint_context.mtu,
+                    max_mtu,
+                    endpoint_context.random_generator,
                     endpoint_context.event_subscriber,
                     endpoint_context.datagram,
                 ) {
@@ -225,6 +332,10 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
             });
         }
 
+        if now.is_some() {
+            self.latest_timestamp = now;
+        }
+
         // try to open connection requests from the application
         if Cfg::ENDPOINT_TYPE.is_client() {
             loop {
@@ -234,10 +345,14 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
                         wakeup_count += 1;
 
                         let time = clock.get_time();
+                        self.latest_timestamp = Some(time);
                         if let Err(err) = self.create_client_connection(request, time) {
                             // TODO report that the connection was not successfully created
                             // TODO emit event
+                            #[cfg(feature = "std")]
                             dbg!(err);
+                            #[cfg(not(feature = "std"))]
+                            let _ = err;
                         }
                     }
                     Poll::Ready(None) => {
@@ -248,6 +363,19 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
             }
         }
 
+        // import the connections which were exported by other endpoints
+        if Cfg::ENDPOINT_TYPE.is_server() {
+            while let Poll::Ready(Some(request)) =
+                futures_core::Stream::poll_next(core::pin::Pin::new(&mut self.import_receiver), cx)
+            {
+                wakeup_count += 1;
+
+                let time = clock.get_time();
+                self.latest_timestamp = Some(time);
+                self.import_connection(request, time);
+            }
+        }
+
         if wakeup_count > 0 {
             Poll::Ready(Ok(wakeup_count))
         } else {
@@ -257,7 +385,11 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
 
     #[inline]
     fn timeout(&self) -> Option<Timestamp> {
-        self.connections.next_expiration()
+        let timeout = self.connections.next_expiration();
+        match (timeout, self.drain_handle.timeout()) {
+            (Some(timeout), Some(deadline)) => Some(timeout.min(deadline)),
+            (timeout, deadline) => timeout.or(deadline),
+        }
     }
 
     #[inline]
@@ -282,6 +414,20 @@ impl<Cfg: Config> Endpoint<Cfg> {
         (endpoint, handle.acceptor)
     }
 
+    /// Creates a QUIC server endpoint for each of the given configurations which share a single
+    /// acceptor
+    ///
+    /// Each endpoint owns the connections of a single shard. The IO provider is responsible for
+    /// routing each datagram to the endpoint which owns its connection.
+    pub fn new_server_shards<I: IntoIterator<Item = Cfg>>(
+        configs: I,
+    ) -> (Vec<Self>, handle::Acceptor) {
+        let (endpoints, acceptors): (Vec<_>, Vec<_>) =
+            configs.into_iter().map(Self::new_server).unzip();
+        assert!(!endpoints.is_empty(), "at least one shard is required");
+        (endpoints, handle::Acceptor::join(acceptors))
+    }
+
     /// Creates a new QUIC client endpoint using the given configuration
     pub fn new_client(config: Cfg) -> (Self, handle::Connector) {
         assert!(
@@ -295,8 +441,14 @@ impl<Cfg: Config> Endpoint<Cfg> {
     fn new(mut config: Cfg) -> (Self, handle::Handle) {
         // TODO make this limit configurable
         let max_opening_connections = 1000;
-        let (handle, acceptor_sender, connector_receiver, close_handle) =
-            handle::Handle::new(max_opening_connections);
+        let (
+            handle,
+            acceptor_sender,
+            connector_receiver,
+            close_handle,
+            drain_handle,
+            import_receiver,
+        ) = handle::Handle::new(max_opening_connections);
 
         let connection_id_mapper =
             ConnectionIdMapper::new(config.context().random_generator, Cfg::ENDPOINT_TYPE);
@@ -308,12 +460,17 @@ impl<Cfg: Config> Endpoint<Cfg> {
             connection_id_mapper,
             wakeup_queue: WakeupQueue::new(),
             close_handle,
+            drain_handle,
+            import_receiver,
             dequeued_wakeups: VecDeque::new(),
             version_negotiator: version::Negotiator::default(),
             retry_dispatch: retry::Dispatch::default(),
             stateless_reset_dispatch: stateless_reset::Dispatch::default(),
             close_packet_buffer: Default::default(),
             max_mtu: Default::default(),
+            rx_backlog: 0,
+            unsent_bytes: 0,
+            latest_timestamp: None,
         };
 
         (endpoint, handle)
@@ -331,17 +488,7 @@ impl<Cfg: Config> Endpoint<Cfg> {
             return None;
         }
 
-        let remote_address = header.path.remote_address();
-
-        let attempt = s2n_quic_core::endpoint::limits::ConnectionAttempt::new(
-            self.connections.handshake_connections(),
-            self.connections.len(),
-            &remote_address,
-            timestamp.into_event(),
-        );
-
         let context = self.config.context();
-        let outcome = context.endpoint_limits.on_connection_attempt(&attempt);
         let mut publisher = event::EndpointPublisherSubscriber::new(
             event::builder::EndpointMeta {
                 endpoint_type: Cfg::ENDPOINT_TYPE,
@@ -351,6 +498,27 @@ impl<Cfg: Config> Endpoint<Cfg> {
             context.event_subscriber,
         );
 
+        // the endpoint is waiting for the open connections to close so reject new ones
+        if self.drain_handle.is_draining() {
+            publisher.on_endpoint_datagram_dropped(event::builder::EndpointDatagramDropped {
+                len: payload_len as u16,
+                reason: event::builder::DatagramDropReason::RejectedConnectionAttempt,
+            });
+            return None;
+        }
+
+        let remote_address = header.path.remote_address();
+
+        let attempt = s2n_quic_core::endpoint::limits::ConnectionAttempt::new(
+            self.connections.handshake_connections(),
+            self.connections.len(),
+            &remote_address,
+            timestamp.into_event(),
+        )
+        .with_queue_depths(self.rx_backlog, self.unsent_bytes);
+
+        let outcome = context.endpoint_limits.on_connection_attempt(&attempt);
+
         match outcome {
             Outcome::Allow { .. } => Some(()),
             Outcome::Retry { .. } => {
@@ -363,9 +531,18 @@ impl<Cfg: Config> Endpoint<Cfg> {
                 //# it cooperates with, received the original Initial packet from the
                 //# client.
 
+                publisher.on_endpoint_connection_attempt_limited(
+                    event::builder::EndpointConnectionAttemptLimited {
+                        remote_address: remote_address.into_event(),
+                        outcome: event::builder::ConnectionAttemptOutcome::Retry,
+                    },
+                );
+
                 let connection_info = ConnectionInfo::new(&remote_address);
 
-                let local_connection_id = context.connection_id_format.generate(&connection_info);
+                let local_connection_id = context
+                    .connection_id_format
+                    .generate_with_random(&connection_info, context.random_generator);
 
                 self.retry_dispatch.queue::<
                     _,
@@ -388,6 +565,12 @@ impl<Cfg: Config> Endpoint<Cfg> {
                 //# Initial packet containing a CONNECTION_CLOSE frame with error code
                 //# CONNECTION_REFUSED.
 
+                publisher.on_endpoint_connection_attempt_limited(
+                    event::builder::EndpointConnectionAttemptLimited {
+                        remote_address: remote_address.into_event(),
+                        outcome: event::builder::ConnectionAttemptOutcome::Close,
+                    },
+                );
                 publisher.on_endpoint_datagram_dropped(event::builder::EndpointDatagramDropped {
                     len: payload_len as u16,
                     reason: event::builder::DatagramDropReason::RejectedConnectionAttempt,
@@ -396,6 +579,12 @@ impl<Cfg: Config> Endpoint<Cfg> {
                 None
             }
             Outcome::Drop { .. } => {
+                publisher.on_endpoint_connection_attempt_limited(
+                    event::builder::EndpointConnectionAttemptLimited {
+                        remote_address: remote_address.into_event(),
+                        outcome: event::builder::ConnectionAttemptOutcome::Drop,
+                    },
+                );
                 publisher.on_endpoint_datagram_dropped(event::builder::EndpointDatagramDropped {
                     len: payload_len as u16,
                     reason: event::builder::DatagramDropReason::RejectedConnectionAttempt,
@@ -403,6 +592,12 @@ impl<Cfg: Config> Endpoint<Cfg> {
                 None
             }
             _ => {
+                publisher.on_endpoint_connection_attempt_limited(
+                    event::builder::EndpointConnectionAttemptLimited {
+                        remote_address: remote_address.into_event(),
+                        outcome: event::builder::ConnectionAttemptOutcome::Drop,
+                    },
+                );
                 publisher.on_endpoint_datagram_dropped(event::builder::EndpointDatagramDropped {
                     len: payload_len as u16,
                     reason: event::builder::DatagramDropReason::RejectedConnectionAttempt,
@@ -413,17 +608,63 @@ impl<Cfg: Config> Endpoint<Cfg> {
         }
     }
 
+    /// Returns the time at which the datagram was received
+    ///
+    /// If the socket reported how long the datagram waited in the receive queue, that time is
+    /// excluded, which keeps the scheduling delays of the endpoint out of the RTT samples.
+    fn receive_time(
+        &self,
+        header: &datagram::Header<Cfg::PathHandle>,
+        timestamp: Timestamp,
+    ) -> Timestamp {
+        let received = header
+            .rx_timestamps
+            .queue_delay
+            .and_then(|queue_delay| timestamp.checked_sub(queue_delay))
+            .unwrap_or(timestamp);
+
+        match self.latest_timestamp {
+            Some(latest) => received.max(latest.min(timestamp)),
+            None => received,
+        }
+    }
+
     /// Ingests a single datagram
-    fn receive_datagram(
+    ///
+    /// Datagrams for existing connections are queued in the `batch`, which is dispatched once a
+    /// datagram for another connection or the endpoint itself is received.
+    fn receive_datagram<'a>(
         &mut self,
+        batch: &mut Batch<'a, Cfg::PathHandle>,
         header: &datagram::Header<Cfg::PathHandle>,
-        payload: &mut [u8],
+        payload: &'a mut [u8],
         timestamp: Timestamp,
     ) {
+        let timestamp = self.receive_time(header, timestamp);
         let endpoint_context = self.config.context();
 
         let remote_address = header.path.remote_address();
 
+        let rx_timestamps = header.rx_timestamps;
+        event::EndpointPublisherSubscriber::new(
+            event::builder::EndpointMeta {
+                endpoint_type: Cfg::ENDPOINT_TYPE,
+                timestamp,
+            },
+            None,
+            endpoint_context.event_subscriber,
+        )
+        .on_endpoint_datagram_received(event::builder::EndpointDatagramReceived {
+            len: payload.len() as u16,
+            software_timestamp: rx_timestamps.software,
+            hardware_timestamp: rx_timestamps.hardware,
+            queue_delay: rx_timestamps.queue_delay,
+        });
+
+        // The packets of the datagram borrow the payload until the datagram has been
+        // dispatched, so the potential stateless reset token is copied out beforehand
+        let stateless_reset_token = Self::stateless_reset_token(payload);
+
         // Try to decode the first packet in the datagram
         let payload_len = payload.len();
         let buffer = DecoderBufferMut::new(payload);
@@ -444,6 +685,12 @@ impl<Cfg: Config> Endpoint<Cfg> {
                 .intercept_rx_datagram(&subject, &datagram, buffer)
         };
 
+        if endpoint_context.retry_offload.is_enabled() {
+            // Front-ends don't hold any connections, so the datagram is never batched
+            self.offload_datagram(header, buffer.into_less_safe_slice(), timestamp);
+            return;
+        }
+
         let connection_info = ConnectionInfo::new(&remote_address);
         let (packet, remaining) = if let Ok((packet, remaining)) = ProtectedPacket::decode(
             buffer,
@@ -461,7 +708,12 @@ impl<Cfg: Config> Endpoint<Cfg> {
             //# versions might allow the use of a long header.
 
             // The packet may be a stateless reset, check before returning.
-            let internal_connection_id = self.close_on_matching_stateless_reset(payload, timestamp);
+            self.receive_batch(batch);
+            let internal_connection_id = self.close_on_matching_stateless_reset(
+                stateless_reset_token,
+                payload_len,
+                timestamp,
+            );
 
             if internal_connection_id.is_none() {
                 // The packet didn't contain a valid stateless token
@@ -508,8 +760,13 @@ impl<Cfg: Config> Endpoint<Cfg> {
 
         let destination_connection_id =
             match connection::LocalId::try_from_bytes(packet.destination_connection_id()) {
-                Some(connection_id) => connection_id,
-                None => {
+                // Only clients use zero-length connection IDs
+                Some(connection_id)
+                    if !connection_id.is_empty() || Cfg::ENDPOINT_TYPE.is_client() =>
+                {
+                    connection_id
+                }
+                _ => {
                     // Ignore the datagram
                     publisher.on_endpoint_datagram_dropped(
                         event::builder::EndpointDatagramDropped {
@@ -535,148 +792,70 @@ impl<Cfg: Config> Endpoint<Cfg> {
         };
 
         // TODO validate the connection ID before looking up the connection in the map
-        let close_packet_buffer = &mut self.close_packet_buffer;
-
-        // Try to lookup the internal connection ID and dispatch the packet
+        // Try to lookup the internal connection ID and queue the packet to be dispatched
         // to the Connection
-        if let Some(internal_id) = self
-            .connection_id_mapper
-            .lookup_internal_connection_id(&datagram.destination_connection_id)
-        {
-            let mut check_for_stateless_reset = false;
-            let max_mtu = self.max_mtu;
-
-            let _ = self.connections.with_connection(internal_id, |conn| {
-                // The path `Id` needs to be passed around instead of the path to get around `&mut self` and
-                // `&mut self.path_manager` being borrowed at the same time
-                let path_id = conn
-                    .on_datagram_received(
-                        &header.path,
-                        datagram,
-                        endpoint_context.congestion_controller,
-                        endpoint_context.path_migration,
-                        max_mtu,
-                        endpoint_context.event_subscriber,
-                    )
-                    .map_err(|datagram_drop_reason| {
-                        // An error received at this point was caused by a datagram that has not
-                        // been authenticated yet, and thus the connection should not be closed.
-                        conn.with_event_publisher(
-                            datagram.timestamp,
-                            None,
-                            endpoint_context.event_subscriber,
-                            |publisher, _path| {
-                                publisher.on_datagram_dropped(event::builder::DatagramDropped {
-                                    len: datagram.payload_len as u16,
-                                    reason: datagram_drop_reason,
-                                });
-                            },
-                        );
-                    })?;
+        let internal_id = if datagram.destination_connection_id.is_empty() {
+            // Connections using zero-length connection IDs are routed by the remote address
+            self.connection_id_mapper
+                .lookup_internal_connection_id_by_remote_address(&remote_address)
+        } else {
+            self.connection_id_mapper
+                .lookup_internal_connection_id(&datagram.destination_connection_id)
+        };
 
-                //= https://www.rfc-editor.org/rfc/rfc9000#section-10.2.1
-                //# An endpoint
-                //# that is closing is not required to process any received frame.
+        if let Some(internal_id) = internal_id {
+            if !batch.can_push(internal_id) {
+                self.receive_batch(batch);
+            }
 
-                if let Err(err) = conn.handle_packet(
-                    datagram,
-                    path_id,
+            batch.push(
+                internal_id,
+                batch::Datagram {
+                    header: *header,
+                    info: *datagram,
                     packet,
-                    endpoint_context.random_generator,
-                    endpoint_context.event_subscriber,
-                    endpoint_context.packet_interceptor,
-                    endpoint_context.datagram,
-                ) {
-                    match err {
-                        ProcessingError::DuplicatePacket => {
-                            // We discard duplicate packets
-                        }
-                        ProcessingError::NonEmptyRetryToken => {
-                            //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2.2
-                            //# Initial packets sent by the server MUST set the Token Length field
-                            //# to 0; clients that receive an Initial packet with a non-zero Token
-                            //# Length field MUST either discard the packet or generate a
-                            //# connection error of type PROTOCOL_VIOLATION.
-                            //
-                            // We discard server initials with non empty retry tokens instead of closing
-                            // the connection to prevent an attacker that can spoof initial packets
-                            // from gaining the ability to close a connection by setting a retry token.
-                        }
-                        ProcessingError::RetryScidEqualsDcid => {
-                            //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2.5.1
-                            //# A client MUST
-                            //# discard a Retry packet that contains a Source Connection ID field
-                            //# that is identical to the Destination Connection ID field of its
-                            //# Initial packet.
-                        }
-                        ProcessingError::ConnectionError(err) => {
-                            conn.close(
-                                err,
-                                endpoint_context.connection_close_formatter,
-                                close_packet_buffer,
-                                datagram.timestamp,
-                                endpoint_context.event_subscriber,
-                                endpoint_context.packet_interceptor,
-                            );
-                            return Err(());
-                        }
-                        ProcessingError::CryptoError(_) => {
-                            // CryptoErrors returned as a result of a packet failing decryption
-                            // will be silently discarded, but are a potential indication of a
-                            // stateless reset from the peer
-
-                            //= https://www.rfc-editor.org/rfc/rfc9000#section-5.2.1
-                            //# Due to packet reordering or loss, a client might receive packets for
-                            //# a connection that are encrypted with a key it has not yet computed.
-                            //# The client MAY drop these packets, or it MAY buffer them in
-                            //# anticipation of later packets that allow it to compute the key.
-                            //
-                            // Packets that fail decryption are discarded rather than buffered.
-
-                            //= https://www.rfc-editor.org/rfc/rfc9000#section-10.3.1
-                            //# Endpoints MAY skip this check if any packet from a datagram is
-                            //# successfully processed.  However, the comparison MUST be performed
-                            //# when the first packet in an incoming datagram either cannot be
-                            //# associated with a connection, or cannot be decrypted.
-                            check_for_stateless_reset = true;
-                        }
-                    }
-                }
-
-                if let Err(err) = conn.handle_remaining_packets(
-                    &header.path,
-                    datagram,
-                    path_id,
-                    endpoint_context.connection_id_format,
                     remaining,
-                    endpoint_context.random_generator,
-                    endpoint_context.event_subscriber,
-                    endpoint_context.packet_interceptor,
-                    endpoint_context.datagram,
-                ) {
-                    conn.close(
-                        err,
-                        endpoint_context.connection_close_formatter,
-                        close_packet_buffer,
-                        datagram.timestamp,
-                        endpoint_context.event_subscriber,
-                        endpoint_context.packet_interceptor,
-                    );
-                    return Err(());
-                }
-
-                Ok(())
-            });
-
-            if check_for_stateless_reset {
-                self.close_on_matching_stateless_reset(payload, timestamp);
-            }
+                    stateless_reset_token,
+                },
+            );
 
             return;
         }
 
+        // Dispatch the queued packets before the endpoint handles the datagram, so datagrams
+        // are observed in the order they were received
+        self.receive_batch(batch);
+
+        let endpoint_context = self.config.context();
+        let mut publisher = event::EndpointPublisherSubscriber::new(
+            event::builder::EndpointMeta {
+                endpoint_type: Cfg::ENDPOINT_TYPE,
+                timestamp,
+            },
+            packet.version(),
+            endpoint_context.event_subscriber,
+        );
+
         match (Cfg::ENDPOINT_TYPE, packet) {
             (s2n_quic_core::endpoint::Type::Server, ProtectedPacket::Initial(packet)) => {
+                //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2
+                //# Packets containing a zero
+                //# value for this bit are not valid packets in this version and MUST
+                //# be discarded.
+
+                // RFC 9287 only allows clients to clear the QUIC bit when establishing a new
+                // connection if they remember that the server previously advertised support
+                // for it, which isn't tracked in the tokens issued by this endpoint.
+                if QuicBit::from_tag(packet.payload.get_tag()).is_zero() {
+                    publisher.on_endpoint_datagram_dropped(
+                        event::builder::EndpointDatagramDropped {
+                            len: payload_len as u16,
+                            reason: event::builder::DatagramDropReason::DecodingFailed,
+                        },
+                    );
+                    return;
+                }
+
                 let source_connection_id =
                     match connection::PeerId::try_from_bytes(packet.source_connection_id()) {
                         Some(connection_id) => connection_id,
@@ -692,6 +871,24 @@ impl<Cfg: Config> Endpoint<Cfg> {
                         }
                     };
 
+                // Clients which retransmit their first flight with a new Destination Connection
+                // ID aren't routed to the handshake in progress by the initial id. Creating
+                // another connection for each retransmission would multiply the state which is
+                // held for a single client under lossy conditions, so the duplicates are dropped.
+                if self
+                    .connection_id_mapper
+                    .lookup_handshake(&remote_address, &source_connection_id, packet.token())
+                    .is_some()
+                {
+                    publisher.on_endpoint_datagram_dropped(
+                        event::builder::EndpointDatagramDropped {
+                            len: payload_len as u16,
+                            reason: event::builder::DatagramDropReason::DuplicateInitial,
+                        },
+                    );
+                    return;
+                }
+
                 //= https://www.rfc-editor.org/rfc/rfc9000#section-8.1
                 //= type=TODO
                 //= tracking-issue=140
@@ -802,7 +999,11 @@ impl<Cfg: Config> Endpoint<Cfg> {
                 //# valid stateless reset token as a Stateless Reset, as other QUIC
                 //# versions might allow the use of a long header.
                 let is_stateless_reset = self
-                    .close_on_matching_stateless_reset(payload, timestamp)
+                    .close_on_matching_stateless_reset(
+                        stateless_reset_token,
+                        datagram.payload_len,
+                        timestamp,
+                    )
                     .is_some();
 
                 //= https://www.rfc-editor.org/rfc/rfc9000#section-9.3.2
@@ -828,6 +1029,353 @@ impl<Cfg: Config> Endpoint<Cfg> {
         }
     }
 
+    /// Handles a datagram received by a stateless front-end
+    ///
+    /// Initial packets without a token are answered with a Retry packet and Initial packets with
+    /// an invalid token are dropped. All of the other datagrams are forwarded to the back-ends.
+    fn offload_datagram(
+        &mut self,
+        header: &datagram::Header<Cfg::PathHandle>,
+        payload: &mut [u8],
+        timestamp: Timestamp,
+    ) {
+        let payload_len = payload.len();
+        let remote_address = header.path.remote_address();
+        let local_address = header.path.local_address();
+        let connection_info = ConnectionInfo::new(&remote_address);
+        let endpoint_context = self.config.context();
+
+        let packet = if let Ok((packet, _remaining)) = ProtectedPacket::decode(
+            DecoderBufferMut::new(&mut *payload),
+            &connection_info,
+            endpoint_context.connection_id_format,
+        ) {
+            packet
+        } else {
+            let mut publisher = event::EndpointPublisherSubscriber::new(
+                event::builder::EndpointMeta {
+                    endpoint_type: Cfg::ENDPOINT_TYPE,
+                    timestamp,
+                },
+                None,
+                endpoint_context.event_subscriber,
+            );
+            publisher.on_endpoint_datagram_dropped(event::builder::EndpointDatagramDropped {
+                len: payload_len as u16,
+                reason: event::builder::DatagramDropReason::DecodingFailed,
+            });
+            return;
+        };
+
+        let mut publisher = event::EndpointPublisherSubscriber::new(
+            event::builder::EndpointMeta {
+                endpoint_type: Cfg::ENDPOINT_TYPE,
+                timestamp,
+            },
+            packet.version(),
+            endpoint_context.event_subscriber,
+        );
+
+        if self
+            .version_negotiator
+            .on_packet(&header.path, payload_len, &packet, &mut publisher)
+            .is_err()
+        {
+            publisher.on_endpoint_datagram_dropped(event::builder::EndpointDatagramDropped {
+                len: payload_len as u16,
+                reason: event::builder::DatagramDropReason::UnsupportedVersion,
+            });
+            return;
+        }
+
+        let original_destination_connection_id = match packet {
+            ProtectedPacket::Initial(packet) => {
+                //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2
+                //# Packets containing a zero
+                //# value for this bit are not valid packets in this version and MUST
+                //# be discarded.
+                if QuicBit::from_tag(packet.payload.get_tag()).is_zero() {
+                    publisher.on_endpoint_datagram_dropped(
+                        event::builder::EndpointDatagramDropped {
+                            len: payload_len as u16,
+                            reason: event::builder::DatagramDropReason::DecodingFailed,
+                        },
+                    );
+                    return;
+                }
+
+                let source_connection_id = if let Some(connection_id) =
+                    PeerId::try_from_bytes(packet.source_connection_id())
+                {
+                    connection_id
+                } else {
+                    publisher.on_endpoint_datagram_dropped(
+                        event::builder::EndpointDatagramDropped {
+                            len: payload_len as u16,
+                            reason: event::builder::DatagramDropReason::InvalidSourceConnectionId,
+                        },
+                    );
+                    return;
+                };
+
+                if packet.token().is_empty() {
+                    //= https://www.rfc-editor.org/rfc/rfc9000#section-8.1.2
+                    //# A server can also use a Retry packet to defer the state and
+                    //# processing costs of connection establishment.  Requiring the server
+                    //# to provide a different connection ID, along with the
+                    //# original_destination_connection_id transport parameter defined in
+                    //# Section 18.2, forces the server to demonstrate that it, or an entity
+                    //# it cooperates with, received the original Initial packet from the
+                    //# client.
+                    let local_connection_id = endpoint_context
+                        .connection_id_format
+                        .generate_with_random(&connection_info, endpoint_context.random_generator);
+
+                    self.retry_dispatch.queue::<
+                        _,
+                        <<<Cfg as Config>::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::RetryKey,
+                    >(
+                        header.path,
+                        &packet,
+                        local_connection_id,
+                        endpoint_context.random_generator,
+                        endpoint_context.token,
+                    );
+                    return;
+                }
+
+                let mut context = token::Context::new(
+                    &remote_address,
+                    &source_connection_id,
+                    endpoint_context.random_generator,
+                );
+
+                match endpoint_context
+                    .token
+                    .validate_token(&mut context, packet.token())
+                {
+                    Some(original_destination_connection_id) => {
+                        Some(original_destination_connection_id)
+                    }
+                    None => {
+                        //= https://www.rfc-editor.org/rfc/rfc9000#section-8.1.3
+                        //# Servers MAY
+                        //# discard any Initial packet that does not carry the expected token.
+                        publisher.on_endpoint_datagram_dropped(
+                            event::builder::EndpointDatagramDropped {
+                                len: payload_len as u16,
+                                reason: event::builder::DatagramDropReason::InvalidRetryToken,
+                            },
+                        );
+                        return;
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        let datagram = retry_offload::Datagram::new(
+            &remote_address,
+            &local_address,
+            payload,
+            original_destination_connection_id
+                .as_ref()
+                .map(|connection_id| connection_id.as_bytes()),
+            timestamp.into_event(),
+        );
+
+        endpoint_context.retry_offload.forward(&datagram);
+    }
+
+    /// Dispatches the queued datagrams to their connection
+    ///
+    /// All of the datagrams are processed with a single access to the connection, unless
+    /// processing one of them closes the connection, completes the handshake or requires checking
+    /// for a stateless reset.
+    fn receive_batch(&mut self, batch: &mut Batch<Cfg::PathHandle>) {
+        let (internal_id, mut datagrams) = if let Some(batch) = batch.take() {
+            batch
+        } else {
+            return;
+        };
+
+        while !datagrams.as_slice().is_empty() {
+            let endpoint_context = self.config.context();
+            let close_packet_buffer = &mut self.close_packet_buffer;
+            let max_mtu = self.max_mtu;
+            let mut stateless_reset = None;
+
+            let interrupted = self
+                .connections
+                .with_connection(internal_id, |conn| {
+                    for batch::Datagram {
+                        header,
+                        info: datagram,
+                        packet,
+                        remaining,
+                        stateless_reset_token,
+                    } in datagrams.by_ref()
+                    {
+                        let datagram = &datagram;
+                        let mut check_for_stateless_reset = false;
+                        let is_handshaking = conn.is_handshaking();
+
+                        // The path `Id` needs to be passed around instead of the path to get around `&mut self` and
+                        // `&mut self.path_manager` being borrowed at the same time
+                        let path_id = match conn.on_datagram_received(
+                            &header.path,
+                            datagram,
+                            endpoint_context.congestion_controller,
+                            endpoint_context.path_migration,
+                            endpoint_context.mtu,
+                            max_mtu,
+                            endpoint_context.event_subscriber,
+                        ) {
+                            Ok(path_id) => path_id,
+                            Err(datagram_drop_reason) => {
+                                // An error received at this point was caused by a datagram that has not
+                                // been authenticated yet, and thus the connection should not be closed.
+                                conn.with_event_publisher(
+                                    datagram.timestamp,
+                                    None,
+                                    endpoint_context.event_subscriber,
+                                    |publisher, _path| {
+                                        publisher.on_datagram_dropped(
+                                            event::builder::DatagramDropped {
+                                                len: datagram.payload_len as u16,
+                                                reason: datagram_drop_reason,
+                                            },
+                                        );
+                                    },
+                                );
+                                continue;
+                            }
+                        };
+
+                        //= https://www.rfc-editor.org/rfc/rfc9000#section-10.2.1
+                        //# An endpoint
+                        //# that is closing is not required to process any received frame.
+
+                        if let Err(err) = conn.handle_packet(
+                            datagram,
+                            path_id,
+                            packet,
+                            endpoint_context.random_generator,
+                            endpoint_context.event_subscriber,
+                            endpoint_context.packet_interceptor,
+                            endpoint_context.datagram,
+                        ) {
+                            match err {
+                                ProcessingError::DuplicatePacket => {
+                                    // We discard duplicate packets
+                                }
+                                ProcessingError::NonEmptyRetryToken => {
+                                    //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2.2
+                                    //# Initial packets sent by the server MUST set the Token Length field
+                                    //# to 0; clients that receive an Initial packet with a non-zero Token
+                                    //# Length field MUST either discard the packet or generate a
+                                    //# connection error of type PROTOCOL_VIOLATION.
+                                    //
+                                    // We discard server initials with non empty retry tokens instead of closing
+                                    // the connection to prevent an attacker that can spoof initial packets
+                                    // from gaining the ability to close a connection by setting a retry token.
+                                }
+                                ProcessingError::RetryScidEqualsDcid => {
+                                    //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2.5.1
+                                    //# A client MUST
+                                    //# discard a Retry packet that contains a Source Connection ID field
+                                    //# that is identical to the Destination Connection ID field of its
+                                    //# Initial packet.
+                                }
+                                ProcessingError::ConnectionError(err) => {
+                                    conn.close(
+                                        err,
+                                        endpoint_context.connection_close_formatter,
+                                        close_packet_buffer,
+                                        datagram.timestamp,
+                                        endpoint_context.event_subscriber,
+                                        endpoint_context.packet_interceptor,
+                                    );
+                                    return true;
+                                }
+                                ProcessingError::CryptoError(_) => {
+                                    // CryptoErrors returned as a result of a packet failing decryption
+                                    // will be silently discarded, but are a potential indication of a
+                                    // stateless reset from the peer
+
+                                    //= https://www.rfc-editor.org/rfc/rfc9000#section-5.2.1
+                                    //# Due to packet reordering or loss, a client might receive packets for
+                                    //# a connection that are encrypted with a key it has not yet computed.
+                                    //# The client MAY drop these packets, or it MAY buffer them in
+                                    //# anticipation of later packets that allow it to compute the key.
+                                    //
+                                    // Packets that fail decryption are discarded rather than buffered.
+
+                                    //= https://www.rfc-editor.org/rfc/rfc9000#section-10.3.1
+                                    //# Endpoints MAY skip this check if any packet from a datagram is
+                                    //# successfully processed.  However, the comparison MUST be performed
+                                    //# when the first packet in an incoming datagram either cannot be
+                                    //# associated with a connection, or cannot be decrypted.
+                                    check_for_stateless_reset = true;
+                                }
+                            }
+                        }
+
+                        if let Err(err) = conn.handle_remaining_packets(
+                            &header.path,
+                            datagram,
+                            path_id,
+                            endpoint_context.connection_id_format,
+                            remaining,
+                            endpoint_context.random_generator,
+                            endpoint_context.event_subscriber,
+                            endpoint_context.packet_interceptor,
+                            endpoint_context.datagram,
+                        ) {
+                            conn.close(
+                                err,
+                                endpoint_context.connection_close_formatter,
+                                close_packet_buffer,
+                                datagram.timestamp,
+                                endpoint_context.event_subscriber,
+                                endpoint_context.packet_interceptor,
+                            );
+                            return true;
+                        }
+
+                        if check_for_stateless_reset {
+                            // The stateless reset may close the connection, so the following
+                            // datagrams are dispatched after it has been checked
+                            stateless_reset = Some((
+                                stateless_reset_token,
+                                datagram.payload_len,
+                                datagram.timestamp,
+                            ));
+                            return true;
+                        }
+
+                        if is_handshaking && !conn.is_handshaking() {
+                            // The connection needs to be handed over to the application before
+                            // processing the following datagrams, as those could close it.
+                            return true;
+                        }
+                    }
+
+                    false
+                })
+                .map_or(false, |(interrupted, _interests)| interrupted);
+
+            if let Some((token, len, timestamp)) = stateless_reset {
+                self.close_on_matching_stateless_reset(token, len, timestamp);
+            }
+
+            if !interrupted {
+                // Either all of the datagrams were processed or the connection no longer exists
+                return;
+            }
+        }
+    }
+
     /// Enqueues sending a stateless reset to a peer.
     ///
     /// Sending the reset was caused through the passed `datagram`.
@@ -857,14 +1405,8 @@ impl<Cfg: Config> Endpoint<Cfg> {
         );
     }
 
-    /// Checks if the given payload contains a stateless reset token matching a known token.
-    /// If there is a match, the matching connection will be closed and the `InternalConnectionId`
-    /// will be returned.
-    fn close_on_matching_stateless_reset(
-        &mut self,
-        payload: &[u8],
-        timestamp: Timestamp,
-    ) -> Option<InternalConnectionId> {
+    /// Returns the bytes of a datagram which are compared against the stateless reset tokens
+    fn stateless_reset_token(payload: &[u8]) -> Option<StatelessResetToken> {
         let buffer = DecoderBuffer::new(payload);
 
         //= https://www.rfc-editor.org/rfc/rfc9000#section-10.3.1
@@ -876,10 +1418,23 @@ impl<Cfg: Config> Endpoint<Cfg> {
         let token_index = payload.len().checked_sub(StatelessResetTokenLen)?;
         let buffer = buffer.skip(token_index).ok()?;
         let (token, _) = buffer.decode().ok()?;
+        Some(token)
+    }
+
+    /// Checks if the given token matches a known stateless reset token.
+    /// If there is a match, the matching connection will be closed and the `InternalConnectionId`
+    /// will be returned.
+    fn close_on_matching_stateless_reset(
+        &mut self,
+        token: Option<StatelessResetToken>,
+        datagram_len: usize,
+        timestamp: Timestamp,
+    ) -> Option<InternalConnectionId> {
+        let token = token?;
         let endpoint_context = self.config.context();
         let internal_id = self
             .connection_id_mapper
-            .remove_internal_connection_id_by_stateless_reset_token(&token)?;
+            .remove_internal_connection_id_by_stateless_reset_token(&token);
 
         let mut publisher = event::EndpointPublisherSubscriber::new(
             event::builder::EndpointMeta {
@@ -890,6 +1445,17 @@ impl<Cfg: Config> Endpoint<Cfg> {
             endpoint_context.event_subscriber,
         );
 
+        let internal_id = if let Some(internal_id) = internal_id {
+            internal_id
+        } else {
+            publisher.on_endpoint_stateless_reset_token_mismatch(
+                event::builder::EndpointStatelessResetTokenMismatch {
+                    len: datagram_len as u16,
+                },
+            );
+            return None;
+        };
+
         publisher.on_endpoint_packet_received(event::builder::EndpointPacketReceived {
             packet_header: event::builder::PacketHeader::StatelessReset {},
         });
@@ -914,7 +1480,80 @@ impl<Cfg: Config> Endpoint<Cfg> {
         Some(internal_id)
     }
 
+    /// Starts draining the endpoint after the application requested it
+    fn on_drain_request(&mut self, drain: endpoint::drain::Drain, timestamp: Timestamp) {
+        let active_connections = self.connections.len();
+        self.drain_handle
+            .start(drain, active_connections, timestamp);
+
+        let mut publisher = event::EndpointPublisherSubscriber::new(
+            event::builder::EndpointMeta {
+                endpoint_type: Cfg::ENDPOINT_TYPE,
+                timestamp,
+            },
+            None,
+            self.config.context().event_subscriber,
+        );
+        publisher.on_endpoint_draining_started(event::builder::EndpointDrainingStarted {
+            active_connections,
+            deadline: drain.deadline,
+        });
+    }
+
+    fn publish_drain_progress(&mut self, timestamp: Timestamp) {
+        let mut publisher = event::EndpointPublisherSubscriber::new(
+            event::builder::EndpointMeta {
+                endpoint_type: Cfg::ENDPOINT_TYPE,
+                timestamp,
+            },
+            None,
+            self.config.context().event_subscriber,
+        );
+        publisher.on_endpoint_draining_progress(event::builder::EndpointDrainingProgress {
+            remaining_connections: self.connections.len(),
+        });
+    }
+
+    /// Closes the connections which are still open once the draining deadline expires
+    fn on_drain_deadline(
+        &mut self,
+        error: s2n_quic_core::application::Error,
+        timestamp: Timestamp,
+    ) {
+        let close_packet_buffer = &mut self.close_packet_buffer;
+        let endpoint_context = self.config.context();
+
+        let mut publisher = event::EndpointPublisherSubscriber::new(
+            event::builder::EndpointMeta {
+                endpoint_type: Cfg::ENDPOINT_TYPE,
+                timestamp,
+            },
+            None,
+            endpoint_context.event_subscriber,
+        );
+        publisher.on_endpoint_draining_deadline_exceeded(
+            event::builder::EndpointDrainingDeadlineExceeded {
+                remaining_connections: self.connections.len(),
+            },
+        );
+
+        self.connections.iterate_all(|conn| {
+            conn.close(
+                connection::Error::application(error),
+                endpoint_context.connection_close_formatter,
+                close_packet_buffer,
+                timestamp,
+                endpoint_context.event_subscriber,
+                endpoint_context.packet_interceptor,
+            );
+        });
+    }
+
     fn on_timeout(&mut self, timestamp: Timestamp) {
+        if let Some(error) = self.drain_handle.on_timeout(timestamp) {
+            self.on_drain_deadline(error, timestamp);
+        }
+
         let connection_id_mapper = &mut self.connection_id_mapper;
         let close_packet_buffer = &mut self.close_packet_buffer;
         let endpoint_context = self.config.context();
@@ -945,6 +1584,7 @@ impl<Cfg: Config> Endpoint<Cfg> {
                 let result = connection.on_new_connection_id(
                     endpoint_context.connection_id_format,
                     endpoint_context.stateless_reset_token_generator,
+                    endpoint_context.random_generator,
                     timestamp,
                 );
                 if result.is_ok() {
@@ -967,16 +1607,18 @@ impl<Cfg: Config> Endpoint<Cfg> {
                 endpoint::connect::Connect {
                     remote_address,
                     server_name: hostname,
+                    correlation_id,
+                    ..
                 },
             sender,
         } = request;
 
         let internal_connection_id = self.connection_id_generator.generate_id();
-        let local_connection_id = self
-            .config
-            .context()
-            .connection_id_format
-            .generate(&ConnectionInfo::new(&remote_address));
+        let endpoint_context = self.config.context();
+        let local_connection_id = endpoint_context.connection_id_format.generate_with_random(
+            &ConnectionInfo::new(&remote_address),
+            endpoint_context.random_generator,
+        );
 
         let local_connection_id_expiration_time = self
             .config
@@ -985,7 +1627,7 @@ impl<Cfg: Config> Endpoint<Cfg> {
             .lifetime()
             .map(|duration| timestamp + duration);
 
-        let local_id_registry = {
+        let mut local_id_registry = {
             // TODO: the client currently generates a random stateless_reset_token but doesnt
             // transmit it. Refactor `create_local_id_registry` to instead accept None for
             // stateless_reset_token.
@@ -1002,8 +1644,27 @@ impl<Cfg: Config> Endpoint<Cfg> {
             )
         };
 
+        if local_connection_id.is_empty() {
+            //= https://www.rfc-editor.org/rfc/rfc9000#section-5.1
+            //# An
+            //# endpoint MUST NOT use the same IP address and port for multiple
+            //# concurrent connections with zero-length connection IDs, unless it is
+            //# certain that those protocol features are not in use.
+            //
+            // Packets are routed by the address of the server, so only a single connection
+            // using zero-length connection IDs can be opened to each server address.
+            if let Err(error) = local_id_registry.register_remote_address(&remote_address) {
+                let error = connection::Error::immediate_close(error.message());
+                let _ = sender.send(Err(error));
+                return Err(error);
+            }
+        }
+
         let endpoint_context = self.config.context();
 
+        let correlation_id = correlation_id
+            .unwrap_or_else(|| CorrelationId::random(endpoint_context.random_generator));
+
         //= https://www.rfc-editor.org/rfc/rfc9000#section-7.2
         //# When an Initial packet is sent by a client that has not previously
         //# received an Initial or Retry packet from the server, the client
@@ -1044,6 +1705,7 @@ impl<Cfg: Config> Endpoint<Cfg> {
         let meta = event::builder::ConnectionMeta {
             endpoint_type: Cfg::ENDPOINT_TYPE,
             id: internal_connection_id.into(),
+            correlation_id,
             timestamp,
         };
         let supervisor_context = supervisor::Context::new(
@@ -1067,17 +1729,39 @@ impl<Cfg: Config> Endpoint<Cfg> {
             initial_source_connection_id: Some(local_connection_id.into()),
             ..Default::default()
         };
-        let limits = endpoint_context
+        let limits_info = LimitsInfo::new(&remote_address);
+        let mut limits = endpoint_context
             .connection_limits
-            .on_connection(&LimitsInfo::new(&remote_address));
+            .on_connection(&limits_info);
+        if let Some(strategy) = endpoint_context.ack.on_connection(&limits_info) {
+            limits.load_ack_strategy(&strategy);
+        }
         transport_parameters.load_limits(&limits);
 
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-18.1
+        //# Transport parameters with an identifier of the form "31 * N + 27" for
+        //# integer values of N are reserved to exercise the requirement that
+        //# unknown transport parameters be ignored.
+        if limits.grease_enabled() {
+            transport_parameters.reserved_parameter =
+                Some(ReservedParameter::random(endpoint_context.random_generator));
+        }
+
         transport_parameters.max_datagram_frame_size = endpoint_context
             .datagram
             .max_datagram_frame_size(&PreConnectionInfo::new())
             .try_into()
             .expect("Failed to convert max_datagram_frame_size");
 
+        // the extension isn't offered if its transport parameter can't be sent
+        let extension_frame_handler = endpoint_context
+            .extension_frame
+            .new_handler(&extension_frame::ConnectionInfo::new(&remote_address))
+            .filter(|handler| {
+                let (id, value) = handler.transport_parameter();
+                transport_parameters.add_custom_parameter(id, value).is_ok()
+            });
+
         transport_parameters.active_connection_id_limit = s2n_quic_core::varint::VarInt::from(
             connection::peer_id_registry::ACTIVE_CONNECTION_ID_LIMIT,
         )
@@ -1108,7 +1792,10 @@ impl<Cfg: Config> Endpoint<Cfg> {
             tls_session,
             initial_key,
             initial_header_key,
+            transport_parameters.into(),
             timestamp,
+            &limits,
+            endpoint_context.random_generator,
             &mut publisher,
         );
 
@@ -1123,6 +1810,7 @@ impl<Cfg: Config> Endpoint<Cfg> {
 
         let connection_parameters = connection::Parameters {
             internal_connection_id,
+            correlation_id,
             local_id_registry,
             peer_id_registry,
             space_manager,
@@ -1132,13 +1820,19 @@ impl<Cfg: Config> Endpoint<Cfg> {
             path_handle,
             congestion_controller,
             timestamp,
+            address_token: None,
             quic_version,
             limits,
             max_mtu: self.max_mtu,
             event_context,
             supervisor_context: &supervisor_context,
+            random_generator: endpoint_context.random_generator,
             event_subscriber: endpoint_context.event_subscriber,
             datagram_endpoint: endpoint_context.datagram,
+            mtu_endpoint: endpoint_context.mtu,
+            protocol_violation_endpoint: endpoint_context.protocol_violation,
+            stream_scheduler_endpoint: endpoint_context.stream_scheduler,
+            extension_frame_handler,
         };
         let connection = <Cfg as crate::endpoint::Config>::Connection::new(connection_parameters)?;
         self.connections
@@ -1172,8 +1866,16 @@ pub mod testing {
         type ConnectionCloseFormatter = s2n_quic_core::connection::close::Development;
         type EventSubscriber = Subscriber;
         type PathMigrationValidator = path::migration::default::Validator;
+        type MtuEndpoint = path::mtu::default::Endpoint;
+        type AckEndpoint = s2n_quic_core::ack::strategy::default::Endpoint;
         type PacketInterceptor = s2n_quic_core::packet::interceptor::Disabled;
         type DatagramEndpoint = s2n_quic_core::datagram::Disabled;
+        type ProtocolViolationEndpoint =
+            s2n_quic_core::connection::protocol_violation::default::Endpoint;
+        type StreamSchedulerEndpoint = s2n_quic_core::stream::scheduler::default::Endpoint;
+        type ExtensionFrameEndpoint = s2n_quic_core::extension_frame::Disabled;
+        type TenantClassifier = s2n_quic_core::endpoint::tenant::Disabled;
+        type RetryOffload = s2n_quic_core::endpoint::retry_offload::Disabled;
 
         fn context(&mut self) -> super::Context<Self> {
             todo!()
@@ -1182,14 +1884,17 @@ pub mod testing {
         const ENDPOINT_TYPE: endpoint::Type = endpoint::Type::Server;
     }
 
+    /// A client configuration
+    ///
+    /// The path handle can be overridden for tests which depend on the local address.
     #[derive(Debug)]
-    pub struct Client;
+    pub struct Client<PathHandle = path::RemoteAddress>(core::marker::PhantomData<PathHandle>);
 
-    impl Config for Client {
+    impl<PathHandle: path::Handle> Config for Client<PathHandle> {
         type CongestionControllerEndpoint =
             crate::recovery::congestion_controller::testing::mock::Endpoint;
         type TLSEndpoint = s2n_quic_core::crypto::tls::testing::Endpoint;
-        type PathHandle = path::RemoteAddress;
+        type PathHandle = PathHandle;
         type Connection = connection::Implementation<Self>;
         type ConnectionLock = std::sync::Mutex<Self::Connection>;
         type EndpointLimits = Limits;
@@ -1202,8 +1907,16 @@ pub mod testing {
         type ConnectionCloseFormatter = s2n_quic_core::connection::close::Development;
         type EventSubscriber = Subscriber;
         type PathMigrationValidator = path::migration::default::Validator;
+        type MtuEndpoint = path::mtu::default::Endpoint;
+        type AckEndpoint = s2n_quic_core::ack::strategy::default::Endpoint;
         type PacketInterceptor = s2n_quic_core::packet::interceptor::Disabled;
         type DatagramEndpoint = s2n_quic_core::datagram::Disabled;
+        type ProtocolViolationEndpoint =
+            s2n_quic_core::connection::protocol_violation::default::Endpoint;
+        type StreamSchedulerEndpoint = s2n_quic_core::stream::scheduler::default::Endpoint;
+        type ExtensionFrameEndpoint = s2n_quic_core::extension_frame::Disabled;
+        type TenantClassifier = s2n_quic_core::endpoint::tenant::Disabled;
+        type RetryOffload = s2n_quic_core::endpoint::retry_offload::Disabled;
 
         fn context(&mut self) -> super::Context<Self> {
             todo!()
@@ -0,0 +1,518 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A multi-producer, single-consumer channel for builds without `std`
+//!
+//! The channel mirrors the subset of the `futures_channel::mpsc` interface used by the endpoint
+//! handle, which is only available with `std`. Unlike the `futures_channel` implementation, a
+//! bounded channel does not reserve a slot for each sender, so `try_send` may report the
+//! channel as full after `poll_ready` returned `Ready`.
+//!
+//! The channel is also used in `loom` builds, since the model tests can't check the
+//! `futures_channel` implementation.
+
+use crate::shared::{Arc, Mutex};
+use alloc::{collections::VecDeque, vec::Vec};
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use futures_core::{FusedStream, Stream};
+
+struct State<T> {
+    queue: VecDeque<T>,
+    /// The maximum number of queued messages, if the channel is bounded
+    capacity: Option<usize>,
+    senders: usize,
+    is_closed: bool,
+    receiver_waker: Option<Waker>,
+    /// Senders waiting for capacity
+    sender_wakers: Vec<Waker>,
+}
+
+impl<T> State<T> {
+    fn is_full(&self) -> bool {
+        self.capacity
+            .map_or(false, |capacity| self.queue.len() >= capacity)
+    }
+
+    fn push(&mut self, value: T) -> Result<(), TrySendError<T>> {
+        if self.is_closed {
+            return Err(TrySendError {
+                is_full: false,
+                value,
+            });
+        }
+
+        if self.is_full() {
+            return Err(TrySendError {
+                is_full: true,
+                value,
+            });
+        }
+
+        self.queue.push_back(value);
+
+        if let Some(waker) = self.receiver_waker.take() {
+            waker.wake();
+        }
+
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let value = self.queue.pop_front()?;
+
+        for waker in self.sender_wakers.drain(..) {
+            waker.wake();
+        }
+
+        Some(value)
+    }
+
+    fn close(&mut self) {
+        self.is_closed = true;
+
+        if let Some(waker) = self.receiver_waker.take() {
+            waker.wake();
+        }
+
+        for waker in self.sender_wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+type Shared<T> = Arc<Mutex<State<T>>>;
+
+fn shared<T>(capacity: Option<usize>) -> Shared<T> {
+    Arc::new(Mutex::new(State {
+        queue: VecDeque::new(),
+        capacity,
+        senders: 1,
+        is_closed: false,
+        receiver_waker: None,
+        sender_wakers: Vec::new(),
+    }))
+}
+
+macro_rules! lock {
+    ($shared:expr) => {
+        $shared
+            .lock()
+            .expect("should succeed unless the lock is poisoned")
+    };
+}
+
+/// Creates a channel which holds at most `capacity` messages
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let shared = shared(Some(capacity.max(1)));
+    let sender = Sender(shared.clone());
+    let receiver = Receiver {
+        shared,
+        is_terminated: false,
+    };
+    (sender, receiver)
+}
+
+/// Creates a channel without a limit on the number of messages
+pub fn unbounded<T>() -> (UnboundedSender<T>, UnboundedReceiver<T>) {
+    let shared = shared(None);
+    let sender = UnboundedSender(Sender(shared.clone()));
+    let receiver = UnboundedReceiver(Receiver {
+        shared,
+        is_terminated: false,
+    });
+    (sender, receiver)
+}
+
+/// The error returned when the receiver has closed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SendError;
+
+/// The error returned when a message could not be sent
+pub struct TrySendError<T> {
+    is_full: bool,
+    value: T,
+}
+
+impl<T> TrySendError<T> {
+    /// Returns `true` if the channel is at capacity
+    pub fn is_full(&self) -> bool {
+        self.is_full
+    }
+
+    /// Returns the message that could not be sent
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TrySendError")
+            .field("is_full", &self.is_full)
+            .finish()
+    }
+}
+
+/// The error returned when the channel is empty, but still open
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryRecvError;
+
+pub struct Sender<T>(Shared<T>);
+
+impl<T> Sender<T> {
+    /// Polls the channel for capacity to send a message
+    pub fn poll_ready(&mut self, cx: &mut Context) -> Poll<Result<(), SendError>> {
+        let mut state = lock!(self.0);
+
+        if state.is_closed {
+            return Poll::Ready(Err(SendError));
+        }
+
+        if state.is_full() {
+            let waker = cx.waker();
+            if !state.sender_wakers.iter().any(|w| w.will_wake(waker)) {
+                state.sender_wakers.push(waker.clone());
+            }
+            return Poll::Pending;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    /// Attempts to send a message without waiting for capacity
+    pub fn try_send(&mut self, value: T) -> Result<(), TrySendError<T>> {
+        lock!(self.0).push(value)
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        lock!(self.0).senders += 1;
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = lock!(self.0);
+        state.senders -= 1;
+
+        if state.senders == 0 {
+            if let Some(waker) = state.receiver_waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+pub struct Receiver<T> {
+    shared: Shared<T>,
+    is_terminated: bool,
+}
+
+impl<T> Receiver<T> {
+    /// Closes the channel, while still allowing the queued messages to be received
+    pub fn close(&mut self) {
+        lock!(self.shared).close();
+    }
+
+    /// Receives the next message without waiting
+    ///
+    /// Returns `Ok(None)` once the channel is closed and all messages have been received.
+    pub fn try_next(&mut self) -> Result<Option<T>, TryRecvError> {
+        match self.poll(None) {
+            Poll::Ready(value) => Ok(value),
+            Poll::Pending => Err(TryRecvError),
+        }
+    }
+
+    fn poll(&mut self, waker: Option<&Waker>) -> Poll<Option<T>> {
+        if self.is_terminated {
+            return Poll::Ready(None);
+        }
+
+        let mut state = lock!(self.shared);
+
+        if let Some(value) = state.pop() {
+            return Poll::Ready(Some(value));
+        }
+
+        if state.is_closed || state.senders == 0 {
+            self.is_terminated = true;
+            return Poll::Ready(None);
+        }
+
+        if let Some(waker) = waker {
+            state.receiver_waker = Some(waker.clone());
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.poll(Some(cx.waker()))
+    }
+}
+
+impl<T> FusedStream for Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.is_terminated
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = lock!(self.shared);
+        state.close();
+        state.queue.clear();
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("is_terminated", &self.is_terminated)
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct UnboundedSender<T>(Sender<T>);
+
+impl<T> UnboundedSender<T> {
+    /// Sends a message
+    pub fn unbounded_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        lock!((self.0).0).push(value)
+    }
+
+    /// Returns `true` if the receiver has closed
+    pub fn is_closed(&self) -> bool {
+        lock!((self.0).0).is_closed
+    }
+
+    /// Closes the channel from the sender side
+    pub fn close_channel(&self) {
+        lock!((self.0).0).close();
+    }
+}
+
+impl<T> Clone for UnboundedSender<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+#[derive(Debug)]
+pub struct UnboundedReceiver<T>(Receiver<T>);
+
+impl<T> Stream for UnboundedReceiver<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        Pin::new(&mut self.0).poll_next(cx)
+    }
+}
+
+impl<T> FusedStream for UnboundedReceiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.0.is_terminated()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_test::task::new_count_waker;
+
+    #[test]
+    fn bounded_test() {
+        let (waker, wake_count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let (mut sender, mut receiver) = channel(2);
+
+        assert_eq!(receiver.try_next(), Err(TryRecvError));
+
+        for value in 0..2 {
+            assert_eq!(sender.poll_ready(&mut cx), Poll::Ready(Ok(())));
+            sender.try_send(value).unwrap();
+        }
+
+        // the channel is at capacity
+        assert_eq!(sender.poll_ready(&mut cx), Poll::Pending);
+        assert!(sender.try_send(2).unwrap_err().is_full());
+
+        // receiving a message wakes the sender
+        assert_eq!(receiver.try_next(), Ok(Some(0)));
+        assert_eq!(wake_count.get(), 1);
+        assert_eq!(sender.poll_ready(&mut cx), Poll::Ready(Ok(())));
+        sender.try_send(2).unwrap();
+
+        // queued messages are still received after closing
+        receiver.close();
+        assert_eq!(sender.poll_ready(&mut cx), Poll::Ready(Err(SendError)));
+        let err = sender.try_send(3).unwrap_err();
+        assert!(!err.is_full());
+        assert_eq!(err.into_inner(), 3);
+
+        assert_eq!(receiver.try_next(), Ok(Some(1)));
+        assert_eq!(receiver.try_next(), Ok(Some(2)));
+        assert!(!receiver.is_terminated());
+        assert_eq!(receiver.try_next(), Ok(None));
+        assert!(receiver.is_terminated());
+    }
+
+    #[test]
+    fn sender_drop_test() {
+        let (waker, wake_count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let (sender, mut receiver) = channel::<u8>(1);
+        let other = sender.clone();
+
+        assert_eq!(Pin::new(&mut receiver).poll_next(&mut cx), Poll::Pending);
+
+        drop(sender);
+        assert_eq!(wake_count.get(), 0);
+        assert_eq!(Pin::new(&mut receiver).poll_next(&mut cx), Poll::Pending);
+
+        // the channel ends once all of the senders are dropped
+        drop(other);
+        assert_eq!(wake_count.get(), 1);
+        assert_eq!(
+            Pin::new(&mut receiver).poll_next(&mut cx),
+            Poll::Ready(None)
+        );
+        assert!(receiver.is_terminated());
+    }
+
+    #[test]
+    fn unbounded_test() {
+        let (waker, wake_count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let (sender, mut receiver) = unbounded();
+
+        assert_eq!(Pin::new(&mut receiver).poll_next(&mut cx), Poll::Pending);
+
+        for value in 0..10 {
+            sender.unbounded_send(value).unwrap();
+        }
+        assert_eq!(wake_count.get(), 1);
+
+        for value in 0..10 {
+            assert_eq!(
+                Pin::new(&mut receiver).poll_next(&mut cx),
+                Poll::Ready(Some(value))
+            );
+        }
+
+        sender.close_channel();
+        assert!(sender.is_closed());
+        assert!(!sender.unbounded_send(10).unwrap_err().is_full());
+        assert_eq!(
+            Pin::new(&mut receiver).poll_next(&mut cx),
+            Poll::Ready(None)
+        );
+
+        // dropping the receiver closes the channel
+        let (sender, receiver) = unbounded::<u8>();
+        drop(receiver);
+        assert!(sender.is_closed());
+    }
+}
+
+#[cfg(loom)]
+mod loom_tests {
+    use super::*;
+    use core::future::Future;
+    use loom::thread;
+
+    struct Next<'a, S>(&'a mut S);
+
+    impl<S: Stream + Unpin> Future for Next<'_, S> {
+        type Output = Option<S::Item>;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            Pin::new(&mut *self.0).poll_next(cx)
+        }
+    }
+
+    fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+        loom::future::block_on(Next(stream))
+    }
+
+    #[test]
+    fn loom_accept_test() {
+        loom::model(|| {
+            let (sender, mut receiver) = unbounded();
+
+            let threads: Vec<_> = (0..2u32)
+                .map(|id| {
+                    let sender = sender.clone();
+                    thread::spawn(move || sender.unbounded_send(id).unwrap())
+                })
+                .collect();
+            drop(sender);
+
+            // every accepted value is received before the channel ends
+            let mut received = Vec::new();
+            while let Some(value) = next(&mut receiver) {
+                received.push(value);
+            }
+            received.sort_unstable();
+            assert_eq!(received, [0, 1]);
+
+            for thread in threads {
+                thread.join().unwrap();
+            }
+        });
+    }
+
+    struct Ready<'a, T>(&'a mut Sender<T>);
+
+    impl<T> Future for Ready<'_, T> {
+        type Output = Result<(), SendError>;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            self.0.poll_ready(cx)
+        }
+    }
+
+    #[test]
+    fn loom_bounded_test() {
+        loom::model(|| {
+            let (mut sender, mut receiver) = channel(1);
+
+            let thread = thread::spawn(move || {
+                for value in 0..3u32 {
+                    // waits until the receiver makes room for the next value
+                    loom::future::block_on(Ready(&mut sender)).unwrap();
+                    sender.try_send(value).unwrap();
+                }
+            });
+
+            // the values are received in order and the channel ends once the sender is dropped
+            for value in 0..3u32 {
+                assert_eq!(next(&mut receiver), Some(value));
+            }
+            assert_eq!(next(&mut receiver), None);
+
+            thread.join().unwrap();
+        });
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Allows to import connections which were exported by another endpoint
+
+#[cfg(any(loom, not(feature = "std")))]
+use super::mpsc;
+use crate::{
+    connection::{self, limits::ConnectionInfo as LimitsInfo, Trait as _},
+    endpoint,
+    recovery::congestion_controller::{self, Endpoint as _},
+    space::PacketSpaceManager,
+};
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+#[cfg(all(feature = "std", not(loom)))]
+use futures_channel::mpsc;
+use futures_channel::oneshot;
+use s2n_quic_core::{
+    ack::strategy::Endpoint as _,
+    connection::{limits::Limiter as _, transfer},
+    event::{self, supervisor, IntoEvent as _, Subscriber as _},
+    path::{self, Handle as _, LocalAddress, RemoteAddress},
+    time::Timestamp,
+};
+
+/// Held by the application. Used to submit transferred connections to the library.
+pub(crate) type ImportSender = mpsc::UnboundedSender<Request>;
+/// Held by the library. Used to receive transferred connections from the application.
+pub(crate) type ImportReceiver = mpsc::UnboundedReceiver<Request>;
+
+/// Held within the library. Used to notify the application about the outcome of the import.
+pub(crate) type ResultSender = oneshot::Sender<Result<(), transfer::Error>>;
+type ResultReceiver = oneshot::Receiver<Result<(), transfer::Error>>;
+
+#[derive(Debug)]
+pub(crate) struct Request {
+    pub state: transfer::State,
+    pub sender: ResultSender,
+}
+
+/// A future which resolves once the endpoint imported a transferred connection
+///
+/// On success, the connection is returned from the acceptor of the endpoint like any other
+/// accepted connection.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Import {
+    receiver: Option<ResultReceiver>,
+}
+
+impl Import {
+    pub(crate) fn new(importer: Option<&ImportSender>, state: transfer::State) -> Self {
+        let (sender, receiver) = oneshot::channel();
+        let request = Request { state, sender };
+
+        let receiver = importer
+            .and_then(|importer| importer.unbounded_send(request).ok())
+            .map(|_| receiver);
+
+        Self { receiver }
+    }
+}
+
+impl Future for Import {
+    type Output = Result<(), transfer::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let receiver = match self.receiver.as_mut() {
+            Some(receiver) => receiver,
+            None => return Poll::Ready(Err(transfer::Error::EndpointClosed)),
+        };
+
+        match Pin::new(receiver).poll(cx) {
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            // the endpoint was closed before processing the request
+            Poll::Ready(Err(_)) => Poll::Ready(Err(transfer::Error::EndpointClosed)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
+    /// Creates a connection from the state which was exported by another endpoint
+    ///
+    /// The connection completed the handshake on the exporting endpoint so it's handed to the
+    /// application through the acceptor once it's inserted.
+    pub(super) fn import_connection(&mut self, request: Request, timestamp: Timestamp) {
+        let Request { state, sender } = request;
+
+        let result = if self.drain_handle.is_draining() || !self.connections.can_accept() {
+            Err(transfer::Error::EndpointClosed)
+        } else {
+            self.create_transferred_connection(&state, timestamp)
+        };
+
+        // the application might have stopped waiting for the result
+        let _ = sender.send(result);
+    }
+
+    fn create_transferred_connection(
+        &mut self,
+        state: &transfer::State,
+        timestamp: Timestamp,
+    ) -> Result<(), transfer::Error> {
+        let internal_connection_id = self.connection_id_generator.generate_id();
+
+        let local_id_registry = self
+            .connection_id_mapper
+            .import_local_id_registry(internal_connection_id, state)
+            .map_err(|_| transfer::Error::ConnectionIdInUse)?;
+
+        let peer_id_registry = self
+            .connection_id_mapper
+            .import_peer_id_registry(internal_connection_id, state);
+
+        let wakeup_handle = self
+            .wakeup_queue
+            .create_wakeup_handle(internal_connection_id);
+
+        let remote_address = RemoteAddress::from(state.remote_address);
+        let mut path_handle =
+            <Config::PathHandle as path::Handle>::from_remote_address(remote_address);
+        path_handle.set_local_address(LocalAddress::from(state.local_address));
+
+        let endpoint_context = self.config.context();
+
+        let limits_info = LimitsInfo::new(&remote_address);
+        let mut limits = endpoint_context
+            .connection_limits
+            .on_connection(&limits_info);
+        if let Some(strategy) = endpoint_context.ack.on_connection(&limits_info) {
+            limits.load_ack_strategy(&strategy);
+        }
+
+        let path_info = congestion_controller::PathInfo::new(&remote_address);
+        let congestion_controller = endpoint_context
+            .congestion_controller
+            .new_congestion_controller(path_info);
+
+        let meta = event::builder::ConnectionMeta {
+            endpoint_type: Config::ENDPOINT_TYPE,
+            id: internal_connection_id.into(),
+            correlation_id: state.correlation_id,
+            timestamp,
+        };
+
+        let supervisor_context = supervisor::Context::new(
+            self.connections.handshake_connections(),
+            self.connections.len(),
+            &remote_address,
+            true,
+        );
+
+        let event_context = endpoint_context.event_subscriber.create_connection_context(
+            &meta.into_event(),
+            &event::builder::ConnectionInfo {}.into_event(),
+        );
+
+        let space_manager = PacketSpaceManager::new_transferred(
+            state.server_name.clone(),
+            state.application_protocol.clone(),
+        );
+
+        let connection_parameters = connection::Parameters {
+            internal_connection_id,
+            correlation_id: state.correlation_id,
+            local_id_registry,
+            peer_id_registry,
+            space_manager,
+            wakeup_handle,
+            peer_connection_id: state.peer_connection_id,
+            local_connection_id: state.local_connection_id,
+            path_handle,
+            congestion_controller,
+            timestamp,
+            address_token: None,
+            quic_version: state.quic_version,
+            limits,
+            max_mtu: self.max_mtu,
+            event_context,
+            supervisor_context: &supervisor_context,
+            random_generator: endpoint_context.random_generator,
+            event_subscriber: endpoint_context.event_subscriber,
+            datagram_endpoint: endpoint_context.datagram,
+            mtu_endpoint: endpoint_context.mtu,
+            protocol_violation_endpoint: endpoint_context.protocol_violation,
+            stream_scheduler_endpoint: endpoint_context.stream_scheduler,
+            extension_frame_handler: None,
+        };
+
+        let mut connection = <Config as endpoint::Config>::Connection::new(connection_parameters)?;
+
+        let endpoint_context = self.config.context();
+        connection.on_transferred(
+            state,
+            timestamp,
+            endpoint_context.random_generator,
+            endpoint_context.event_subscriber,
+            endpoint_context.datagram,
+        )?;
+
+        self.connections
+            .insert_server_connection(connection, internal_connection_id);
+
+        Ok(())
+    }
+}
@@ -494,6 +494,7 @@ mod tests {
             datagram_info.0,
             datagram_info.1.payload_len,
             Short {
+                quic_bit: Default::default(),
                 destination_connection_id: &[1u8, 2, 3][..],
                 key_phase: Default::default(),
                 spin_bit: Default::default(),
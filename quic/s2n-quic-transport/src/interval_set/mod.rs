@@ -235,6 +235,12 @@ impl<T> IntervalSet<T> {
         self.intervals.clear()
     }
 
+    /// Releases the capacity which isn't used by the contained intervals
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.intervals.shrink_to_fit()
+    }
+
     /// Removes the lowest `Interval` in the set, if any
     ///
     /// # Examples
@@ -616,6 +622,15 @@ impl<T: IntervalBound> IntervalSet<T> {
     /// interval comparison
     #[inline]
     fn index_for(&self, interval: &Interval<T>) -> usize {
+        // intervals are usually appended to the end of the set, e.g. when packets are received
+        // in order, so check the last interval first. None of the previous intervals can be
+        // affected by an interval starting after the last interval's start.
+        if let Some(last) = self.intervals.back() {
+            if interval.start >= last.start {
+                return self.intervals.len() - 1;
+            }
+        }
+
         // it's faster just to iterate through the set for smaller lengths
         if self.interval_len() < 16 {
             return 0;
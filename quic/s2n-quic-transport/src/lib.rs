@@ -3,15 +3,22 @@
 
 //! This module contains all main runtime components for receiving and sending
 //! data via the QUIC protocol.
+//!
+//! Disabling the default `std` feature builds the crate for `no_std` environments that provide
+//! a global allocator. The endpoint is then driven with an application-provided clock and IO,
+//! and a [`connection::Lock`] implementation must be provided for the connections.
 
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 #![deny(unused_must_use)]
 extern crate alloc;
 
 mod ack;
+mod arena;
 mod buffer;
 mod contexts;
 mod interval_set;
 mod processed_packet;
+mod shared;
 mod space;
 mod sync;
 mod transmission;
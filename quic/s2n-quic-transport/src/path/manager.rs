@@ -4,28 +4,25 @@
 //! This module contains the Manager implementation
 
 use crate::{
-    connection::PeerIdRegistry,
+    connection::{peer_id_registry::PeerIdRegistrationError, PeerIdRegistry},
     endpoint, path,
-    path::{challenge, Path},
+    path::{challenge, mtu, Path, UnvalidatedBytes},
     transmission,
 };
 use s2n_quic_core::{
     ack,
-    connection::{self, PeerId},
+    connection::{self, protocol_violation, PeerId},
     event::{self, builder::DatagramDropReason, IntoEvent},
     frame,
     frame::path_validation,
-    inet::DatagramInfo,
+    inet::{DatagramInfo, SocketAddress, Unspecified as _},
     packet::number::PacketNumberSpace,
     path::{
         migration::{self, Validator as _},
-        Handle as _, Id, MaxMtu,
+        Handle as _, Id, LocalAddress, MaxMtu,
     },
     random,
-    recovery::{
-        congestion_controller::{self, Endpoint as _},
-        RttEstimator,
-    },
+    recovery::congestion_controller::{self, Endpoint as _},
     stateless_reset,
     time::{timer, Timestamp},
     transport,
@@ -68,6 +65,22 @@ pub struct Manager<Config: endpoint::Config> {
     /// The `paths` data structure will need to be enhanced to include garbage collection
     /// of old paths to overcome this limitation.
     pending_packet_authentication: Option<u8>,
+
+    /// Set if the peer sent the `disable_active_migration` transport parameter
+    active_migration_disabled: bool,
+
+    /// A migration to a new local address that was requested by the application
+    pending_migration: Option<PendingMigration<Config::PathHandle>>,
+}
+
+/// A migration which is performed the next time the connection is woken up
+#[derive(Debug)]
+struct PendingMigration<Handle> {
+    handle: Handle,
+    /// The connection ID reserved for the new path
+    ///
+    /// This is `None` if the connection migrates back to a path it used before.
+    peer_connection_id: Option<PeerId>,
 }
 
 impl<Config: endpoint::Config> Manager<Config> {
@@ -78,6 +91,8 @@ impl<Config: endpoint::Config> Manager<Config> {
             active: 0,
             last_known_active_validated_path: None,
             pending_packet_authentication: None,
+            active_migration_disabled: false,
+            pending_migration: None,
         };
         manager.paths[0].activated = true;
         manager.paths[0].is_active = true;
@@ -145,6 +160,16 @@ impl<Config: endpoint::Config> Manager<Config> {
         &self.paths[self.active as usize]
     }
 
+    /// Returns the number of bytes exchanged on all paths before they were validated
+    #[inline]
+    pub fn unvalidated_bytes(&self) -> UnvalidatedBytes {
+        let mut bytes = UnvalidatedBytes::default();
+        for path in self.paths.iter() {
+            bytes += path.unvalidated_bytes();
+        }
+        bytes
+    }
+
     /// Return a mutable reference to the active path
     #[inline]
     pub fn active_path_mut(&mut self) -> &mut Path<Config> {
@@ -233,7 +258,8 @@ impl<Config: endpoint::Config> Manager<Config> {
         handshake_confirmed: bool,
         congestion_controller_endpoint: &mut Config::CongestionControllerEndpoint,
         migration_validator: &mut Config::PathMigrationValidator,
-        max_mtu: MaxMtu,
+        mtu_endpoint: &mut Config::MtuEndpoint,
+        mtu_config: mtu::Config,
         publisher: &mut Pub,
     ) -> Result<(Id, bool), DatagramDropReason> {
         let valid_initial_received = self.valid_initial_received();
@@ -258,6 +284,11 @@ impl<Config: endpoint::Config> Manager<Config> {
                 return Err(DatagramDropReason::InvalidSourceConnectionId);
             }
 
+            // Clients don't know the local address of the initial path until the server responds
+            if Config::ENDPOINT_TYPE.is_client() && path.local_address().ip().is_unspecified() {
+                path.handle.set_local_address(path_handle.local_address());
+            }
+
             let unblocked = path.on_bytes_received(datagram.payload_len);
             return Ok((id, unblocked));
         }
@@ -292,7 +323,8 @@ impl<Config: endpoint::Config> Manager<Config> {
             datagram,
             congestion_controller_endpoint,
             migration_validator,
-            max_mtu,
+            mtu_endpoint,
+            mtu_config,
             publisher,
         )
     }
@@ -304,7 +336,8 @@ impl<Config: endpoint::Config> Manager<Config> {
         datagram: &DatagramInfo,
         congestion_controller_endpoint: &mut Config::CongestionControllerEndpoint,
         migration_validator: &mut Config::PathMigrationValidator,
-        max_mtu: MaxMtu,
+        mtu_endpoint: &mut Config::MtuEndpoint,
+        mtu_config: mtu::Config,
         publisher: &mut Pub,
     ) -> Result<(Id, bool), DatagramDropReason> {
         //= https://www.rfc-editor.org/rfc/rfc9000#section-9
@@ -386,7 +419,7 @@ impl<Config: endpoint::Config> Manager<Config> {
         // estimator for the new path, and they are initialized with initial values,
         // we do not need to reset congestion controller and round-trip time estimator
         // again on confirming the peer's ownership of its new address.
-        let rtt = RttEstimator::new(self.active_path().rtt_estimator.max_ack_delay());
+        let rtt = self.active_path().rtt_estimator.for_new_path();
         let path_info = congestion_controller::PathInfo::new(&remote_address);
         let cc = congestion_controller_endpoint.new_congestion_controller(path_info);
 
@@ -430,7 +463,7 @@ impl<Config: endpoint::Config> Manager<Config> {
             rtt,
             cc,
             true,
-            max_mtu,
+            mtu_config.for_path(mtu_endpoint, path_handle),
         );
 
         let unblocked = path.on_bytes_received(datagram.payload_len);
@@ -628,7 +661,13 @@ impl<Config: endpoint::Config> Manager<Config> {
         //= https://www.rfc-editor.org/rfc/rfc9000#section-9.2
         //# An endpoint can migrate a connection to a new local address by
         //# sending packets containing non-probing frames from that address.
-        if !path_validation_probing.is_probing() && self.active_path_id() != path_id {
+        //
+        // Clients don't follow the peer to other paths, since the server address never changes.
+        // Instead, clients migrate with `request_migration`.
+        if Config::ENDPOINT_TYPE.is_server()
+            && !path_validation_probing.is_probing()
+            && self.active_path_id() != path_id
+        {
             self.update_active_path(path_id, random_generator, publisher)?;
 
             //= https://www.rfc-editor.org/rfc/rfc9000#section-9.3
@@ -651,6 +690,219 @@ impl<Config: endpoint::Config> Manager<Config> {
         Ok(())
     }
 
+    /// Called when the peer sends the `disable_active_migration` transport parameter
+    #[inline]
+    pub fn on_active_migration_disabled(&mut self) {
+        self.active_migration_disabled = true;
+    }
+
+    /// Requests a migration of the connection to a new local address
+    ///
+    /// Only the IP of the local address can change. If the port is `0`, the port of the active
+    /// path is used. The connection migrates the next time [`Self::on_pending_migration`] is
+    /// called.
+    pub fn request_migration(
+        &mut self,
+        local_address: SocketAddress,
+        handshake_confirmed: bool,
+    ) -> Result<(), migration::Error> {
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-9
+        //# Clients are responsible for initiating all migrations.
+        if Config::ENDPOINT_TYPE.is_server() {
+            return Err(migration::Error::NotClient);
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-9
+        //# An endpoint MUST NOT initiate
+        //# connection migration before the handshake is confirmed, as defined
+        //# in section 4.1.2 of [QUIC-TLS].
+        if !handshake_confirmed {
+            return Err(migration::Error::HandshakeNotConfirmed);
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-18.2
+        //# An endpoint that receives this transport
+        //# parameter MUST NOT use a new local address when sending to the
+        //# address that the peer used during the handshake.
+        if self.active_migration_disabled {
+            return Err(migration::Error::ActiveMigrationDisabled);
+        }
+
+        let mut handle = self.active_path().handle;
+        let active_port = handle.local_address().port();
+
+        let mut local_address = LocalAddress::from(local_address);
+        if local_address.port() == 0 {
+            local_address.set_port(active_port);
+        }
+
+        // the endpoint keeps sending from the same socket, so the port can't change
+        if local_address.port() != active_port {
+            return Err(migration::Error::UnsupportedLocalAddress);
+        }
+
+        handle.set_local_address(local_address);
+
+        // the IO provider doesn't support selecting the local address
+        if local_address.ip().is_unspecified() || handle.local_address() != local_address {
+            return Err(migration::Error::UnsupportedLocalAddress);
+        }
+
+        // a connection ID reserved by a previous request can be used for the new path, since it
+        // hasn't been used to send any packets yet
+        let reserved_id = self
+            .pending_migration
+            .take()
+            .and_then(|migration| migration.peer_connection_id);
+
+        let peer_connection_id = match self.path(&handle) {
+            Some((id, _)) if id == self.active_path_id() => {
+                // the connection already uses the local address
+                return Ok(());
+            }
+            Some(_) => {
+                // connection IDs are checked once the connection migrates back to the path
+                None
+            }
+            None => {
+                if self.paths.len() >= MAX_ALLOWED_PATHS {
+                    return Err(migration::Error::PathLimitExceeded);
+                }
+
+                //= https://www.rfc-editor.org/rfc/rfc9000#section-9.5
+                //# An endpoint MUST NOT reuse a connection ID when sending from more
+                //# than one local address -- for example, when initiating connection
+                //# migration as described in Section 9.2 or when probing a new network
+                //# path as described in Section 9.1.
+                let peer_connection_id = reserved_id
+                    .or_else(|| self.peer_id_registry.consume_new_id_for_new_path())
+                    .ok_or(migration::Error::InsufficientConnectionIds)?;
+
+                Some(peer_connection_id)
+            }
+        };
+
+        self.pending_migration = Some(PendingMigration {
+            handle,
+            peer_connection_id,
+        });
+
+        Ok(())
+    }
+
+    /// Returns `true` if the application requested a migration that hasn't been performed yet
+    #[inline]
+    pub fn has_pending_migration(&self) -> bool {
+        self.pending_migration.is_some()
+    }
+
+    /// Migrates the connection to the local address requested with [`Self::request_migration`]
+    pub fn on_pending_migration<Pub: event::ConnectionPublisher>(
+        &mut self,
+        congestion_controller_endpoint: &mut Config::CongestionControllerEndpoint,
+        mtu_endpoint: &mut Config::MtuEndpoint,
+        mtu_config: mtu::Config,
+        random_generator: &mut dyn random::Generator,
+        publisher: &mut Pub,
+    ) {
+        let migration = if let Some(migration) = self.pending_migration.take() {
+            migration
+        } else {
+            return;
+        };
+
+        let prev_path_id = self.active_path_id();
+
+        let new_path_id = if let Some((id, _)) = self.path(&migration.handle) {
+            if id == prev_path_id {
+                return;
+            }
+
+            // The path's connection id might have retired since we last used it
+            let peer_connection_id = self[id].peer_connection_id;
+            if !self.peer_id_registry.is_active(&peer_connection_id) {
+                let new_id = self.peer_id_registry.consume_new_id_for_existing_path(
+                    id,
+                    peer_connection_id,
+                    publisher,
+                );
+
+                match new_id {
+                    Some(new_id) => self[id].peer_connection_id = new_id,
+                    // stay on the active path until the peer issues more connection ids
+                    None => return,
+                }
+            }
+
+            id
+        } else {
+            let peer_connection_id = if let Some(id) = migration.peer_connection_id {
+                id
+            } else {
+                debug_assert!(false, "new paths should reserve a connection id");
+                return;
+            };
+
+            let new_path_id = path_id(self.paths.len() as u8);
+            let handle = &migration.handle;
+
+            //= https://www.rfc-editor.org/rfc/rfc9000#section-9.4
+            //# Changing paths can cause the available bandwidth to change, so the
+            //# endpoint that migrates resets its congestion controller.
+            let remote_address = handle.remote_address();
+            let rtt = self.active_path().rtt_estimator.for_new_path();
+            let path_info = congestion_controller::PathInfo::new(&remote_address);
+            let cc = congestion_controller_endpoint.new_congestion_controller(path_info);
+
+            let path = Path::new(
+                *handle,
+                peer_connection_id,
+                self.active_path().local_connection_id,
+                rtt,
+                cc,
+                false,
+                mtu_config.for_path(mtu_endpoint, handle),
+            );
+
+            let active_path = self.active_path();
+            publisher.on_path_created(event::builder::PathCreated {
+                active: path_event!(active_path, prev_path_id),
+                new: path_event!(path, new_path_id),
+            });
+
+            publisher.on_mtu_updated(event::builder::MtuUpdated {
+                path_id: new_path_id.into_event(),
+                mtu: path.mtu_controller.mtu() as u16,
+                cause: MtuUpdatedCause::NewPath,
+            });
+
+            self.paths.push(path);
+
+            new_path_id
+        };
+
+        if self.active_path().is_validated() {
+            self.last_known_active_validated_path = Some(self.active);
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-9.2
+        //# An endpoint can migrate a connection to a new local address by
+        //# sending packets containing non-probing frames from that address.
+        //
+        // The server validates the new client address once it receives packets on the new path.
+        // The client also validates the new path to confirm that it can reach the server.
+        if !self[new_path_id].is_challenge_pending() {
+            self.set_challenge(new_path_id, random_generator);
+        }
+
+        self.activate_path(publisher, prev_path_id, new_path_id);
+
+        // Restart ECN validation to check that the path still supports ECN
+        let path = self.active_path_mut();
+        path.ecn_controller
+            .restart(path_event!(path, new_path_id), publisher);
+    }
+
     #[inline]
     fn abandon_all_path_challenges<Pub: event::ConnectionPublisher>(
         &mut self,
@@ -671,21 +923,37 @@ impl<Config: endpoint::Config> Manager<Config> {
     }
 
     /// Called when a NEW_CONNECTION_ID frame is received from the peer
-    pub fn on_new_connection_id<Pub: event::ConnectionPublisher>(
+    pub fn on_new_connection_id<
+        Pub: event::ConnectionPublisher,
+        Policy: protocol_violation::Policy,
+    >(
         &mut self,
         connection_id: &connection::PeerId,
         sequence_number: u32,
         retire_prior_to: u32,
         stateless_reset_token: &stateless_reset::Token,
+        protocol_violation_policy: &mut Policy,
         publisher: &mut Pub,
     ) -> Result<(), transport::Error> {
         // Retire and register connection ID
-        self.peer_id_registry.on_new_connection_id(
+        match self.peer_id_registry.on_new_connection_id(
             connection_id,
             sequence_number,
             retire_prior_to,
             stateless_reset_token,
-        )?;
+        ) {
+            Ok(()) => {}
+            Err(PeerIdRegistrationError::InvalidNewConnectionId) => {
+                // The registry is left unchanged, so the frame can be ignored if the policy allows
+                let violation = protocol_violation::Violation::InconsistentNewConnectionId;
+                return crate::connection::on_protocol_violation(
+                    violation,
+                    protocol_violation_policy,
+                    publisher,
+                );
+            }
+            Err(err) => return Err(err.into()),
+        }
 
         //= https://www.rfc-editor.org/rfc/rfc9000#section-5.1.2
         //# Upon receipt of an increased Retire Prior To field, the peer MUST
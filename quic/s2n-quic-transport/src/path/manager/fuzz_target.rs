@@ -270,6 +270,7 @@ impl Model {
             source_connection_id: None,
         };
         let mut migration_validator = path::migration::default::Validator;
+        let mut mtu_endpoint = s2n_quic_core::path::mtu::default::Endpoint;
         let mut random_generator = Generator::default();
         let mut publisher = Publisher::no_snapshot();
 
@@ -284,7 +285,8 @@ impl Model {
             true,
             &mut Default::default(),
             &mut migration_validator,
-            MaxMtu::default(),
+            &mut mtu_endpoint,
+            MaxMtu::default().into(),
             &mut publisher,
         ) {
             Ok((id, _)) => {
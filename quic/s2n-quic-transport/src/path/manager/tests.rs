@@ -16,7 +16,7 @@ use core::time::Duration;
 use s2n_quic_core::{
     event::testing::Publisher,
     inet::{DatagramInfo, ExplicitCongestionNotification, SocketAddress},
-    path::{migration, RemoteAddress},
+    path::{migration, mtu, RemoteAddress},
     random::{self, Generator},
     recovery::RttEstimator,
     stateless_reset::token::testing::*,
@@ -59,7 +59,7 @@ fn get_path_by_address_test() {
         RttEstimator::default(),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
 
     let second_conn_id = connection::PeerId::try_from_bytes(&[5, 4, 3, 2, 1]).unwrap();
@@ -70,7 +70,7 @@ fn get_path_by_address_test() {
         RttEstimator::default(),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
 
     let mut manager = manager_server(first_path.clone());
@@ -101,7 +101,7 @@ fn test_invalid_path_fallback() {
         RttEstimator::default(),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     // simulate receiving a handshake packet to force path validation
     first_path.on_handshake_packet();
@@ -117,7 +117,7 @@ fn test_invalid_path_fallback() {
         RttEstimator::default(),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     second_path.set_challenge(challenge);
 
@@ -489,7 +489,7 @@ fn silently_return_when_there_is_no_valid_path() {
         RttEstimator::default(),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     first_path.set_challenge(challenge);
     let mut manager = manager_server(first_path);
@@ -692,7 +692,7 @@ fn test_adding_new_path() {
         RttEstimator::default(),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     let mut manager = manager_server(first_path);
 
@@ -719,7 +719,8 @@ fn test_adding_new_path() {
             true,
             &mut Default::default(),
             &mut migration::default::Validator::default(),
-            DEFAULT_MAX_MTU,
+            &mut mtu::default::Endpoint::default(),
+            DEFAULT_MAX_MTU.into(),
             &mut publisher,
         )
         .unwrap();
@@ -753,7 +754,7 @@ fn do_not_add_new_path_if_handshake_not_confirmed() {
         RttEstimator::default(),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     let mut manager = manager_server(first_path);
 
@@ -778,7 +779,8 @@ fn do_not_add_new_path_if_handshake_not_confirmed() {
         handshake_confirmed,
         &mut Default::default(),
         &mut migration::default::Validator::default(),
-        DEFAULT_MAX_MTU,
+        &mut mtu::default::Endpoint::default(),
+        DEFAULT_MAX_MTU.into(),
         &mut publisher,
     );
 
@@ -813,7 +815,7 @@ fn do_not_add_new_path_if_client() {
         RttEstimator::default(),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     let mut manager = manager_client(first_path);
     let mut publisher = Publisher::snapshot();
@@ -838,7 +840,8 @@ fn do_not_add_new_path_if_client() {
         true,
         &mut Default::default(),
         &mut migration::default::Validator::default(),
-        DEFAULT_MAX_MTU,
+        &mut mtu::default::Endpoint::default(),
+        DEFAULT_MAX_MTU.into(),
         &mut publisher,
     );
 
@@ -866,7 +869,7 @@ fn switch_destination_connection_id_after_first_server_response() {
         RttEstimator::default(),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     let mut manager = manager_client(zero_path);
     assert_eq!(manager[zero_path_id].peer_connection_id, initial_cid);
@@ -904,7 +907,7 @@ fn limit_number_of_connection_migrations() {
         RttEstimator::default(),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     let mut manager = manager_server(first_path);
     let mut total_paths = 1;
@@ -927,7 +930,8 @@ fn limit_number_of_connection_migrations() {
             &datagram,
             &mut Default::default(),
             &mut migration::default::Validator::default(),
-            DEFAULT_MAX_MTU,
+            &mut mtu::default::Endpoint::default(),
+            DEFAULT_MAX_MTU.into(),
             &mut publisher,
         );
         match res {
@@ -962,7 +966,7 @@ fn connection_migration_challenge_behavior() {
         RttEstimator::default(),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     let mut manager = manager_server(first_path);
 
@@ -984,7 +988,8 @@ fn connection_migration_challenge_behavior() {
             &datagram,
             &mut Default::default(),
             &mut migration::default::Validator::default(),
-            DEFAULT_MAX_MTU,
+            &mut mtu::default::Endpoint::default(),
+            DEFAULT_MAX_MTU.into(),
             &mut publisher,
         )
         .unwrap();
@@ -1055,7 +1060,7 @@ fn connection_migration_use_max_ack_delay_from_active_path() {
         RttEstimator::new(Duration::from_millis(30)),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     let mut manager = manager_server(first_path);
 
@@ -1078,7 +1083,8 @@ fn connection_migration_use_max_ack_delay_from_active_path() {
             &datagram,
             &mut Default::default(),
             &mut migration::default::Validator::default(),
-            DEFAULT_MAX_MTU,
+            &mut mtu::default::Endpoint::default(),
+            DEFAULT_MAX_MTU.into(),
             &mut publisher,
         )
         .unwrap();
@@ -1133,7 +1139,7 @@ fn connection_migration_new_path_abandon_timer() {
         RttEstimator::new(Duration::from_millis(30)),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     let mut manager = manager_server(first_path);
 
@@ -1155,7 +1161,8 @@ fn connection_migration_new_path_abandon_timer() {
             &datagram,
             &mut Default::default(),
             &mut migration::default::Validator::default(),
-            DEFAULT_MAX_MTU,
+            &mut mtu::default::Endpoint::default(),
+            DEFAULT_MAX_MTU.into(),
             &mut publisher,
         )
         .unwrap();
@@ -1253,18 +1260,302 @@ fn stop_using_a_retired_connection_id() {
         RttEstimator::default(),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     let mut manager = manager_server(first_path);
 
     let id_2 = connection::PeerId::try_from_bytes(b"id02").unwrap();
     assert!(manager
-        .on_new_connection_id(&id_2, 1, 1, &TEST_TOKEN_1, &mut publisher)
+        .on_new_connection_id(
+            &id_2,
+            1,
+            1,
+            &TEST_TOKEN_1,
+            &mut protocol_violation::default::Policy,
+            &mut publisher
+        )
         .is_ok());
 
     assert_eq!(id_2, manager.paths[0].peer_connection_id);
 }
 
+#[test]
+fn inconsistent_new_connection_id_policy() {
+    let mut publisher = Publisher::no_snapshot();
+    let id_1 = connection::PeerId::try_from_bytes(b"id01").unwrap();
+    let first_path = ServerPath::new(
+        Default::default(),
+        id_1,
+        connection::LocalId::TEST_ID,
+        RttEstimator::default(),
+        Default::default(),
+        false,
+        DEFAULT_MAX_MTU.into(),
+    );
+    let mut manager = manager_server(first_path);
+
+    let id_2 = connection::PeerId::try_from_bytes(b"id02").unwrap();
+    let id_3 = connection::PeerId::try_from_bytes(b"id03").unwrap();
+    assert!(manager
+        .on_new_connection_id(
+            &id_2,
+            1,
+            0,
+            &TEST_TOKEN_1,
+            &mut protocol_violation::default::Policy,
+            &mut publisher
+        )
+        .is_ok());
+    assert_eq!(id_2, manager.paths[0].peer_connection_id);
+
+    // The default policy closes the connection on a reused sequence number
+    let error = manager
+        .on_new_connection_id(
+            &id_3,
+            1,
+            1,
+            &TEST_TOKEN_2,
+            &mut protocol_violation::default::Policy,
+            &mut publisher,
+        )
+        .unwrap_err();
+    assert_eq!(transport::Error::PROTOCOL_VIOLATION.code, error.code);
+    assert_eq!(0, publisher.protocol_violation_ignored);
+
+    // Ignoring the violation leaves the active path unchanged
+    assert!(manager
+        .on_new_connection_id(
+            &id_3,
+            1,
+            1,
+            &TEST_TOKEN_2,
+            &mut protocol_violation::Outcome::Ignore,
+            &mut publisher
+        )
+        .is_ok());
+    assert_eq!(1, publisher.protocol_violation_ignored);
+    assert_eq!(id_2, manager.paths[0].peer_connection_id);
+}
+
+type MigrationManager = super::Manager<Client<path::Tuple>>;
+
+// Helper function to create a client PathManager with a confirmed handshake
+//
+// `new_id_count` connection ids are issued by the peer in addition to the id used by the active
+// path.
+fn helper_migration_manager(new_id_count: u32, publisher: &mut Publisher) -> MigrationManager {
+    let remote_address: SocketAddr = "127.0.0.1:443".parse().unwrap();
+    let local_address: SocketAddr = "192.168.0.1:4433".parse().unwrap();
+    let handle = path::Tuple {
+        remote_address: SocketAddress::from(remote_address).into(),
+        local_address: SocketAddress::from(local_address).into(),
+    };
+    let initial_id = connection::PeerId::try_from_bytes(b"id00").unwrap();
+    let first_path = super::Path::new(
+        handle,
+        initial_id,
+        connection::LocalId::TEST_ID,
+        RttEstimator::default(),
+        Default::default(),
+        false,
+        DEFAULT_MAX_MTU.into(),
+    );
+
+    let mut random_generator = random::testing::Generator(123);
+    let mut peer_id_registry =
+        ConnectionIdMapper::new(&mut random_generator, endpoint::Type::Client)
+            .create_client_peer_id_registry(InternalConnectionIdGenerator::new().generate_id());
+    peer_id_registry.register_initial_connection_id(initial_id);
+    let mut manager = MigrationManager::new(first_path, peer_id_registry);
+
+    let tokens = [TEST_TOKEN_1, TEST_TOKEN_2, TEST_TOKEN_3];
+    // the first id replaces the initial id on the active path
+    for sequence_number in 1..=(new_id_count + 1) {
+        let id = connection::PeerId::try_from_bytes(&[sequence_number as u8]).unwrap();
+        assert!(manager
+            .on_new_connection_id(
+                &id,
+                sequence_number,
+                0,
+                &tokens[sequence_number as usize - 1],
+                &mut protocol_violation::default::Policy,
+                publisher,
+            )
+            .is_ok());
+    }
+
+    manager
+}
+
+fn helper_migrate(manager: &mut MigrationManager, publisher: &mut Publisher) {
+    manager.on_pending_migration(
+        &mut Default::default(),
+        &mut mtu::default::Endpoint::default(),
+        DEFAULT_MAX_MTU.into(),
+        &mut random::testing::Generator(123),
+        publisher,
+    );
+}
+
+#[test]
+fn migrate_to_new_local_address() {
+    // Setup:
+    let mut publisher = Publisher::snapshot();
+    let mut manager = helper_migration_manager(1, &mut publisher);
+    let prev_path_id = manager.active_path_id();
+    let prev_peer_id = manager.active_path().peer_connection_id;
+    let new_local_address: SocketAddr = "10.0.0.1:0".parse().unwrap();
+
+    // Trigger:
+    assert_eq!(
+        manager.request_migration(new_local_address.into(), true),
+        Ok(())
+    );
+    assert!(manager.has_pending_migration());
+    // the connection only migrates once it's woken up
+    assert_eq!(manager.active_path_id(), prev_path_id);
+    helper_migrate(&mut manager, &mut publisher);
+
+    // Expectation:
+    assert!(!manager.has_pending_migration());
+    assert_eq!(manager.paths.len(), 2);
+    assert_ne!(manager.active_path_id(), prev_path_id);
+
+    let active_path = manager.active_path();
+    let expected_local_address: SocketAddr = "10.0.0.1:4433".parse().unwrap();
+    assert_eq!(
+        *active_path.handle.local_address,
+        SocketAddress::from(expected_local_address)
+    );
+    assert_eq!(
+        active_path.handle.remote_address,
+        manager[prev_path_id].handle.remote_address
+    );
+
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-9.5
+    //= type=test
+    //# An endpoint MUST NOT reuse a connection ID when sending from more
+    //# than one local address -- for example, when initiating connection
+    //# migration as described in Section 9.2 or when probing a new network
+    //# path as described in Section 9.1.
+    assert_ne!(active_path.peer_connection_id, prev_peer_id);
+    assert!(active_path.is_challenge_pending());
+    assert!(active_path.is_activated());
+    assert_eq!(
+        manager.last_known_active_validated_path,
+        Some(prev_path_id.as_u8())
+    );
+}
+
+#[test]
+fn migrate_back_to_previous_local_address() {
+    // Setup:
+    let mut publisher = Publisher::snapshot();
+    let mut manager = helper_migration_manager(1, &mut publisher);
+    let first_path_id = manager.active_path_id();
+    let first_local_address = *manager.active_path().handle.local_address;
+    let new_local_address: SocketAddr = "10.0.0.1:0".parse().unwrap();
+    assert!(manager
+        .request_migration(new_local_address.into(), true)
+        .is_ok());
+    helper_migrate(&mut manager, &mut publisher);
+    assert_ne!(manager.active_path_id(), first_path_id);
+
+    // Trigger:
+    assert!(manager.request_migration(first_local_address, true).is_ok());
+    helper_migrate(&mut manager, &mut publisher);
+
+    // Expectation:
+    // the previous path is reused instead of creating a new one
+    assert_eq!(manager.paths.len(), 2);
+    assert_eq!(manager.active_path_id(), first_path_id);
+}
+
+#[test]
+fn migrate_to_active_local_address() {
+    // Setup:
+    let mut publisher = Publisher::snapshot();
+    let mut manager = helper_migration_manager(1, &mut publisher);
+    let local_address = *manager.active_path().handle.local_address;
+
+    // Trigger:
+    assert_eq!(manager.request_migration(local_address, true), Ok(()));
+
+    // Expectation:
+    assert!(!manager.has_pending_migration());
+    assert_eq!(manager.paths.len(), 1);
+}
+
+#[test]
+fn migration_errors() {
+    let mut publisher = Publisher::no_snapshot();
+    let new_local_address: SocketAddr = "10.0.0.1:0".parse().unwrap();
+    let new_local_address = SocketAddress::from(new_local_address);
+
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-9
+    //= type=test
+    //# An endpoint MUST NOT initiate
+    //# connection migration before the handshake is confirmed, as defined
+    //# in section 4.1.2 of [QUIC-TLS].
+    let mut manager = helper_migration_manager(1, &mut publisher);
+    assert_eq!(
+        manager.request_migration(new_local_address, false),
+        Err(migration::Error::HandshakeNotConfirmed)
+    );
+
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-18.2
+    //= type=test
+    //# An endpoint that receives this transport
+    //# parameter MUST NOT use a new local address when sending to the
+    //# address that the peer used during the handshake.
+    let mut manager = helper_migration_manager(1, &mut publisher);
+    manager.on_active_migration_disabled();
+    assert_eq!(
+        manager.request_migration(new_local_address, true),
+        Err(migration::Error::ActiveMigrationDisabled)
+    );
+
+    let mut manager = helper_migration_manager(0, &mut publisher);
+    assert_eq!(
+        manager.request_migration(new_local_address, true),
+        Err(migration::Error::InsufficientConnectionIds)
+    );
+
+    // the endpoint can't send from a different port
+    let mut manager = helper_migration_manager(1, &mut publisher);
+    let other_port: SocketAddr = "10.0.0.1:1234".parse().unwrap();
+    assert_eq!(
+        manager.request_migration(other_port.into(), true),
+        Err(migration::Error::UnsupportedLocalAddress)
+    );
+    assert!(!manager.has_pending_migration());
+
+    // the remote address handle doesn't track the local address
+    let first_path = ClientPath::new(
+        Default::default(),
+        connection::PeerId::TEST_ID,
+        connection::LocalId::TEST_ID,
+        RttEstimator::default(),
+        Default::default(),
+        false,
+        DEFAULT_MAX_MTU.into(),
+    );
+    let mut manager = manager_client(first_path);
+    assert_eq!(
+        manager.request_migration(new_local_address, true),
+        Err(migration::Error::UnsupportedLocalAddress)
+    );
+
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-9
+    //= type=test
+    //# Clients are responsible for initiating all migrations.
+    let mut manager = manager_server(helper_path(connection::PeerId::TEST_ID));
+    assert_eq!(
+        manager.request_migration(new_local_address, true),
+        Err(migration::Error::NotClient)
+    );
+}
+
 #[test]
 fn amplification_limited_true_if_all_paths_amplificaiton_limited() {
     // Setup:
@@ -1347,7 +1638,7 @@ fn pending_paths_should_return_paths_pending_validation() {
         RttEstimator::default(),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     let expected_response_data = [0; 8];
     third_path.on_path_challenge(&expected_response_data);
@@ -1411,7 +1702,7 @@ fn temporary_until_authenticated() {
         RttEstimator::default(),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     let mut manager = manager_server(first_path);
 
@@ -1427,7 +1718,8 @@ fn temporary_until_authenticated() {
             true,
             &mut Default::default(),
             &mut migration::default::Validator::default(),
-            DEFAULT_MAX_MTU,
+            &mut mtu::default::Endpoint::default(),
+            DEFAULT_MAX_MTU.into(),
             &mut publisher,
         )
         .unwrap();
@@ -1449,7 +1741,8 @@ fn temporary_until_authenticated() {
             true,
             &mut Default::default(),
             &mut migration::default::Validator::default(),
-            DEFAULT_MAX_MTU,
+            &mut mtu::default::Endpoint::default(),
+            DEFAULT_MAX_MTU.into(),
             &mut publisher,
         )
         .unwrap();
@@ -1486,7 +1779,8 @@ fn temporary_until_authenticated() {
             true,
             &mut Default::default(),
             &mut migration::default::Validator::default(),
-            DEFAULT_MAX_MTU,
+            &mut mtu::default::Endpoint::default(),
+            DEFAULT_MAX_MTU.into(),
             &mut publisher,
         )
         .unwrap();
@@ -1790,7 +2084,7 @@ pub fn helper_path(peer_id: connection::PeerId) -> ServerPath {
         RttEstimator::new(Duration::from_millis(30)),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     )
 }
 
@@ -14,7 +14,9 @@ use crate::{
 use s2n_quic_core::{
     counter::{Counter, Saturating},
     event::{self, IntoEvent},
-    frame, packet, random,
+    frame,
+    inet::Unspecified as _,
+    packet, random,
     time::{timer, Timestamp},
 };
 
@@ -40,6 +42,21 @@ enum State {
     },
 }
 
+/// The number of bytes exchanged with a peer before its address was validated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnvalidatedBytes {
+    pub received: u64,
+    pub sent: u64,
+}
+
+impl core::ops::AddAssign for UnvalidatedBytes {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        self.received = self.received.saturating_add(rhs.received);
+        self.sent = self.sent.saturating_add(rhs.sent);
+    }
+}
+
 #[derive(Debug)]
 pub struct Path<Config: endpoint::Config> {
     /// The peer's socket address
@@ -56,6 +73,8 @@ pub struct Path<Config: endpoint::Config> {
     pub pto_backoff: u32,
     /// Tracks whether this path has passed Address or Path validation
     state: State,
+    /// The bytes exchanged on this path while it was amplification limited
+    unvalidated_bytes: UnvalidatedBytes,
     /// Controller for determining the maximum transmission unit of the path
     pub mtu_controller: mtu::Controller,
     /// Controller for determining the ECN capability of the path
@@ -92,6 +111,7 @@ impl<Config: endpoint::Config> Clone for Path<Config> {
             congestion_controller: self.congestion_controller.clone(),
             pto_backoff: self.pto_backoff,
             state: self.state,
+            unvalidated_bytes: self.unvalidated_bytes,
             mtu_controller: self.mtu_controller.clone(),
             ecn_controller: self.ecn_controller.clone(),
             peer_validated: self.peer_validated,
@@ -114,7 +134,7 @@ impl<Config: endpoint::Config> Path<Config> {
         rtt_estimator: RttEstimator,
         congestion_controller: <Config::CongestionControllerEndpoint as congestion_controller::Endpoint>::CongestionController,
         peer_validated: bool,
-        max_mtu: MaxMtu,
+        mtu_config: mtu::Config,
     ) -> Path<Config> {
         let state = match Config::ENDPOINT_TYPE {
             Type::Server => {
@@ -139,7 +159,8 @@ impl<Config: endpoint::Config> Path<Config> {
             congestion_controller,
             pto_backoff: INITIAL_PTO_BACKOFF,
             state,
-            mtu_controller: mtu::Controller::new(max_mtu, &peer_socket_address),
+            unvalidated_bytes: UnvalidatedBytes::default(),
+            mtu_controller: mtu::Controller::new(mtu_config, &peer_socket_address),
             ecn_controller: ecn::Controller::default(),
             peer_validated,
             challenge: Challenge::disabled(),
@@ -194,7 +215,8 @@ impl<Config: endpoint::Config> Path<Config> {
         );
 
         if let State::AmplificationLimited { tx_allowance, .. } = &mut self.state {
-            *tx_allowance -= bytes as u32
+            *tx_allowance -= bytes as u32;
+            self.unvalidated_bytes.sent = self.unvalidated_bytes.sent.saturating_add(bytes as u64);
         }
     }
 
@@ -218,6 +240,8 @@ impl<Config: endpoint::Config> Path<Config> {
         //
         if let State::AmplificationLimited { tx_allowance } = &mut self.state {
             *tx_allowance += bytes.saturating_mul(3) as u32;
+            self.unvalidated_bytes.received =
+                self.unvalidated_bytes.received.saturating_add(bytes as u64);
         }
 
         was_at_amplification_limit && !self.at_amplification_limit()
@@ -371,6 +395,12 @@ impl<Config: endpoint::Config> Path<Config> {
         self.state == State::Validated
     }
 
+    /// Returns the number of bytes exchanged on this path while it was amplification limited
+    #[inline]
+    pub fn unvalidated_bytes(&self) -> UnvalidatedBytes {
+        self.unvalidated_bytes
+    }
+
     /// The path received a non-path-validation-probing packet so mark it as activated.
     #[inline]
     pub fn on_activated(&mut self) {
@@ -512,6 +542,13 @@ impl<Config: endpoint::Config> Path<Config> {
         self.rtt_estimator.pto_period(self.pto_backoff, space)
     }
 
+    /// Returns the number of consecutive times the PTO timer expired
+    #[inline]
+    pub fn pto_count(&self) -> u32 {
+        // the backoff doubles on each PTO, so the count is its base-2 logarithm
+        31u32.saturating_sub(self.pto_backoff.leading_zeros())
+    }
+
     /// Resets the PTO backoff to the initial value
     #[inline]
     pub fn reset_pto_backoff(&mut self) {
@@ -559,9 +596,20 @@ impl<Config: endpoint::Config> Path<Config> {
     // to compare Paths.
     fn eq_by_handle(&self, handle: &Config::PathHandle) -> bool {
         if Config::ENDPOINT_TYPE.is_client() {
-            // TODO: https://github.com/aws/s2n-quic/issues/954
-            // Possibly research a strategy to populate the local_address for Client endpoint
-            s2n_quic_core::path::Handle::eq(&self.handle.remote_address(), &handle.remote_address())
+            // The local address of the initial path is unknown until the server responds, and
+            // some IO providers never populate it. The local IP is only compared if it's known on
+            // both sides. The port is ignored, since all of the packets use the same socket.
+            let local_ip = self.handle.local_address().ip().unmap();
+            let other_local_ip = handle.local_address().ip().unmap();
+            let local_ip_eq = local_ip.is_unspecified()
+                || other_local_ip.is_unspecified()
+                || local_ip == other_local_ip;
+
+            local_ip_eq
+                && s2n_quic_core::path::Handle::eq(
+                    &self.handle.remote_address(),
+                    &handle.remote_address(),
+                )
         } else {
             self.handle.eq(handle)
         }
@@ -618,7 +666,7 @@ pub mod testing {
             RttEstimator::new(Duration::from_millis(30)),
             Default::default(),
             true,
-            DEFAULT_MAX_MTU,
+            DEFAULT_MAX_MTU.into(),
         )
     }
 
@@ -630,7 +678,7 @@ pub mod testing {
             RttEstimator::new(Duration::from_millis(30)),
             Default::default(),
             false,
-            DEFAULT_MAX_MTU,
+            DEFAULT_MAX_MTU.into(),
         )
     }
 }
@@ -977,6 +1025,38 @@ mod tests {
         assert!(path.is_validated());
     }
 
+    #[test]
+    fn unvalidated_bytes_test() {
+        let mut path = testing::helper_path_server();
+
+        path.on_bytes_received(1200);
+        path.on_bytes_transmitted(1300);
+        assert_eq!(
+            path.unvalidated_bytes(),
+            UnvalidatedBytes {
+                received: 1200,
+                sent: 1300,
+            }
+        );
+
+        // bytes are no longer counted once the path is validated
+        path.on_validated();
+        path.on_bytes_received(1200);
+        path.on_bytes_transmitted(1200);
+        assert_eq!(
+            path.unvalidated_bytes(),
+            UnvalidatedBytes {
+                received: 1200,
+                sent: 1300,
+            }
+        );
+
+        // clients don't count any bytes since they are never amplification limited
+        let mut path = helper_path_client();
+        path.on_bytes_received(1200);
+        assert_eq!(path.unvalidated_bytes(), UnvalidatedBytes::default());
+    }
+
     #[test]
     fn amplification_limited_mtu_test() {
         //= https://www.rfc-editor.org/rfc/rfc9000#section-8.1
@@ -1130,7 +1210,7 @@ mod tests {
             RttEstimator::new(Duration::from_millis(30)),
             Default::default(),
             false,
-            DEFAULT_MAX_MTU,
+            DEFAULT_MAX_MTU.into(),
         );
         let now = NoopClock.get_time();
         let random = &mut random::testing::Generator::default();
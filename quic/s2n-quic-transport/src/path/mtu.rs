@@ -10,13 +10,17 @@ use crate::{
 use core::time::Duration;
 use s2n_codec::EncoderValue;
 use s2n_quic_core::{
+    connection::Limits,
     counter::{Counter, Saturating},
     event,
     event::{builder::MtuUpdatedCause, IntoEvent},
     frame,
     inet::SocketAddress,
     packet::number::PacketNumber,
-    path::{IPV4_MIN_HEADER_LEN, IPV6_MIN_HEADER_LEN, UDP_HEADER_LEN},
+    path::{
+        mtu::{Config as PathConfig, Endpoint, PathInfo},
+        Handle, IPV4_MIN_HEADER_LEN, IPV6_MIN_HEADER_LEN, UDP_HEADER_LEN,
+    },
     recovery::CongestionController,
     time::{timer, Timer, Timestamp},
 };
@@ -67,10 +71,10 @@ const ETHERNET_MTU: u16 = 1500;
 const PROBE_THRESHOLD: u16 = 20;
 
 /// When the black_hole_counter exceeds this threshold, on_black_hole_detected will be
-/// called to reduce the MTU to the BASE_PLPMTU. The black_hole_counter is incremented when
+/// called to reduce the MTU to the base_plpmtu. The black_hole_counter is incremented when
 /// a packet is lost that is:
 ///      1) not an MTU probe
-///      2) larger than the BASE_PLPMTU
+///      2) larger than the base_plpmtu
 ///      3) sent after the largest MTU-sized acknowledged packet number
 /// This is a possible indication that the path cannot support the MTU that was previously confirmed.
 const BLACK_HOLE_THRESHOLD: u8 = 3;
@@ -86,6 +90,55 @@ const BLACK_HOLE_COOL_OFF_DURATION: Duration = Duration::from_secs(60);
 //# seconds, as recommended by PLPMTUD [RFC4821].
 const PMTU_RAISE_TIMER_DURATION: Duration = Duration::from_secs(600);
 
+/// Configuration for the mtu::Controller
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Config {
+    /// The maximum size any packet can reach, including the IP and UDP headers
+    pub max_mtu: MaxMtu,
+    /// The UDP payload size used until a larger size has been confirmed and after a
+    /// black hole has been detected
+    pub base_udp_payload: u16,
+    //= https://www.rfc-editor.org/rfc/rfc8899#section-5.1.2
+    //# An application, or PL, MAY
+    //# choose a smaller MAX_PLPMTU when there is no need to send packets
+    //# larger than a specific size.
+    /// The maximum UDP payload size of any packet, including MTU probes
+    pub max_udp_payload: u16,
+    /// The MTU configuration for the specific path, as returned by the MTU provider
+    pub path: Option<PathConfig>,
+}
+
+impl Config {
+    /// Creates a new `Config` from the IO provider's `max_mtu` and the connection limits
+    pub fn new(max_mtu: MaxMtu, limits: &Limits) -> Self {
+        Self {
+            max_mtu,
+            base_udp_payload: limits.base_udp_payload(),
+            max_udp_payload: limits.max_udp_payload(),
+            path: None,
+        }
+    }
+
+    /// Applies the MTU configuration the `endpoint` provides for the path with the given `handle`
+    pub fn for_path<E: Endpoint, H: Handle>(mut self, endpoint: &mut E, handle: &H) -> Self {
+        let remote_address = handle.remote_address();
+        let local_address = handle.local_address();
+        self.path = endpoint.on_path(&PathInfo::new(&remote_address, &local_address));
+        self
+    }
+}
+
+impl From<MaxMtu> for Config {
+    fn from(max_mtu: MaxMtu) -> Self {
+        Self {
+            max_mtu,
+            base_udp_payload: BASE_PLPMTU,
+            max_udp_payload: u16::MAX,
+            path: None,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Controller {
     state: State,
@@ -93,6 +146,10 @@ pub struct Controller {
     //# The Packetization Layer PMTU is an estimate of the largest size
     //# of PL datagram that can be sent by a path, controlled by PLPMTUD
     plpmtu: u16,
+    //= https://www.rfc-editor.org/rfc/rfc8899#section-5.1.2
+    //# The BASE_PLPMTU is a configured size expected to work
+    //# for most paths.
+    base_plpmtu: u16,
     /// The maximum size any packet can reach
     max_mtu: MaxMtu,
     /// The maximum size the UDP payload can reach for any probe packet.
@@ -123,30 +180,55 @@ pub struct Controller {
 }
 
 impl Controller {
-    /// Construct a new mtu::Controller with the given `max_mtu` and `peer_socket_address`
+    /// Construct a new mtu::Controller with the given `config` and `peer_socket_address`
     ///
-    /// The UDP header length and IP header length will be subtracted from `max_mtu` to
+    /// The UDP header length and IP header length will be subtracted from the `max_mtu` to
     /// determine the max_udp_payload used for limiting the payload length of probe packets.
-    pub fn new(max_mtu: MaxMtu, peer_socket_address: &SocketAddress) -> Self {
+    /// If the configured `base_udp_payload` is equal to the resulting max_udp_payload, all
+    /// packets are sent with a fixed size and no probing is performed.
+    ///
+    /// If a path configuration is present, its MTUs further limit the max_udp_payload and
+    /// determine the base_plpmtu and the first size that is probed.
+    pub fn new(config: Config, peer_socket_address: &SocketAddress) -> Self {
+        let Config {
+            max_mtu,
+            base_udp_payload,
+            max_udp_payload,
+            path,
+        } = config;
         let min_ip_header_len = match peer_socket_address {
             SocketAddress::IpV4(_) => IPV4_MIN_HEADER_LEN,
             SocketAddress::IpV6(_) => IPV6_MIN_HEADER_LEN,
         };
-        let max_udp_payload = u16::from(max_mtu) - UDP_HEADER_LEN - min_ip_header_len;
+        let to_udp_payload = |mtu: u16| mtu.saturating_sub(UDP_HEADER_LEN + min_ip_header_len);
+
+        // The UDP payload size for the most likely MTU is based on standard Ethernet MTU minus
+        // the minimum length IP headers (without IPv4 options or IPv6 extensions) and UPD header
+        let (initial_mtu, base_mtu, path_max_mtu) = path
+            .map_or((ETHERNET_MTU, 0, u16::MAX), |path| {
+                (path.initial_mtu(), path.base_mtu(), path.max_mtu().into())
+            });
+
+        let max_udp_payload =
+            to_udp_payload(u16::from(max_mtu).min(path_max_mtu)).min(max_udp_payload);
         debug_assert!(
             max_udp_payload >= BASE_PLPMTU,
             "max_udp_payload must be at least {}",
             BASE_PLPMTU
         );
+        let base_plpmtu = base_udp_payload
+            .max(to_udp_payload(base_mtu))
+            .max(BASE_PLPMTU)
+            .min(max_udp_payload);
 
-        // The UDP payload size for the most likely MTU is based on standard Ethernet MTU minus
-        // the minimum length IP headers (without IPv4 options or IPv6 extensions) and UPD header
-        let initial_probed_size =
-            (ETHERNET_MTU - UDP_HEADER_LEN - min_ip_header_len).min(max_udp_payload);
+        let initial_probed_size = to_udp_payload(initial_mtu)
+            .min(max_udp_payload)
+            .max(base_plpmtu);
 
         Self {
             state: State::Disabled,
-            plpmtu: BASE_PLPMTU,
+            plpmtu: base_plpmtu,
+            base_plpmtu,
             probed_size: initial_probed_size,
             max_mtu,
             max_udp_payload,
@@ -266,12 +348,12 @@ impl Controller {
                 }
             }
             State::Searching(_, _) | State::SearchComplete | State::SearchRequested => {
-                if (BASE_PLPMTU + 1..=self.plpmtu).contains(&lost_bytes)
+                if (self.base_plpmtu + 1..=self.plpmtu).contains(&lost_bytes)
                     && self
                         .largest_acked_mtu_sized_packet
                         .map_or(true, |pn| packet_number > pn)
                 {
-                    // A non-probe packet larger than the BASE_PLPMTU that was sent after the last
+                    // A non-probe packet larger than the base_plpmtu that was sent after the last
                     // acknowledged MTU-sized packet has been lost
                     self.black_hole_counter += 1;
                 }
@@ -376,7 +458,7 @@ impl Controller {
         }
     }
 
-    /// Called when an excessive number of packets larger than the BASE_PLPMTU have been lost
+    /// Called when an excessive number of packets larger than the base_plpmtu have been lost
     fn on_black_hole_detected<CC: CongestionController, Pub: event::ConnectionPublisher>(
         &mut self,
         now: Timestamp,
@@ -386,9 +468,9 @@ impl Controller {
     ) {
         self.black_hole_counter = Default::default();
         self.largest_acked_mtu_sized_packet = None;
-        // Reset the plpmtu back to the BASE_PLPMTU and notify the congestion controller
-        self.plpmtu = BASE_PLPMTU;
-        congestion_controller.on_mtu_update(BASE_PLPMTU);
+        // Reset the plpmtu back to the base_plpmtu and notify the congestion controller
+        self.plpmtu = self.base_plpmtu;
+        congestion_controller.on_mtu_update(self.base_plpmtu);
         // Cancel any current probes
         self.state = State::SearchComplete;
         // Arm the PMTU raise timer to try a larger MTU again after a cooling off period
@@ -461,12 +543,12 @@ mod test {
         time::timer::Provider as _, varint::VarInt,
     };
     use s2n_quic_platform::time::now;
-    use std::{convert::TryInto, net::SocketAddr};
+    use std::net::SocketAddr;
 
     /// Creates a new mtu::Controller with an IPv4 address and the given `max_mtu`
     pub fn new_controller(max_mtu: u16) -> Controller {
         let addr: SocketAddr = "127.0.0.1:443".parse().unwrap();
-        Controller::new(max_mtu.try_into().unwrap(), &addr.into())
+        Controller::new(MaxMtu::try_from(max_mtu).unwrap().into(), &addr.into())
     }
 
     /// Creates an application space packet number with the given value
@@ -502,7 +584,7 @@ mod test {
     #[test]
     fn new_ipv4() {
         let addr: SocketAddr = "127.0.0.1:443".parse().unwrap();
-        let controller = Controller::new(1600.try_into().unwrap(), &addr.into());
+        let controller = Controller::new(MaxMtu::try_from(1600).unwrap().into(), &addr.into());
         assert_eq!(
             1600 - UDP_HEADER_LEN - IPV4_MIN_HEADER_LEN,
             controller.max_udp_payload
@@ -526,7 +608,7 @@ mod test {
         let addr: SocketAddr = "[2001:0db8:85a3:0001:0002:8a2e:0370:7334]:9000"
             .parse()
             .unwrap();
-        let controller = Controller::new(2000.try_into().unwrap(), &addr.into());
+        let controller = Controller::new(MaxMtu::try_from(2000).unwrap().into(), &addr.into());
         assert_eq!(
             2000 - UDP_HEADER_LEN - IPV6_MIN_HEADER_LEN,
             controller.max_udp_payload
@@ -564,6 +646,119 @@ mod test {
         assert_eq!(State::SearchRequested, controller.state);
     }
 
+    #[test]
+    fn new_with_max_udp_payload() {
+        let addr: SocketAddr = "127.0.0.1:443".parse().unwrap();
+        let limits = Limits::default().with_max_udp_payload(1300).unwrap();
+        let config = Config::new(MaxMtu::try_from(1500).unwrap(), &limits);
+        let mut controller = Controller::new(config, &addr.into());
+
+        assert_eq!(1300, controller.max_udp_payload);
+        assert_eq!(1300, controller.max_probe_size);
+        assert_eq!(1300, controller.probed_size);
+        assert_eq!(BASE_PLPMTU as usize, controller.mtu());
+
+        controller.enable();
+        assert_eq!(State::SearchRequested, controller.state);
+    }
+
+    #[test]
+    fn new_with_fixed_udp_payload() {
+        let addr: SocketAddr = "127.0.0.1:443".parse().unwrap();
+        let limits = Limits::default().with_fixed_udp_payload(1350).unwrap();
+        let config = Config::new(MaxMtu::try_from(1500).unwrap(), &limits);
+        let mut controller = Controller::new(config, &addr.into());
+
+        assert_eq!(1350, controller.mtu());
+        assert_eq!(1350, controller.max_udp_payload);
+        assert_eq!(1350, controller.probed_size);
+
+        // probing is not performed since the MTU can't increase
+        controller.enable();
+        assert_eq!(State::SearchComplete, controller.state);
+        assert!(!controller.pmtu_raise_timer.is_armed());
+    }
+
+    #[test]
+    fn new_with_fixed_udp_payload_larger_than_max_mtu() {
+        let addr: SocketAddr = "127.0.0.1:443".parse().unwrap();
+        let limits = Limits::default().with_fixed_udp_payload(1472).unwrap();
+        let config = Config::new(MaxMtu::try_from(1400).unwrap(), &limits);
+        let mut controller = Controller::new(config, &addr.into());
+
+        // the fixed payload is limited by the max MTU
+        let max_udp_payload = 1400 - UDP_HEADER_LEN - IPV4_MIN_HEADER_LEN;
+        assert_eq!(max_udp_payload as usize, controller.mtu());
+        assert_eq!(max_udp_payload, controller.max_udp_payload);
+
+        controller.enable();
+        assert_eq!(State::SearchComplete, controller.state);
+    }
+
+    #[test]
+    fn new_with_path_config() {
+        let addr: SocketAddr = "10.0.0.1:443".parse().unwrap();
+        let mut path_config = PathConfig::builder()
+            .with_max_mtu(9001)
+            .unwrap()
+            .with_initial_mtu(9001)
+            .unwrap()
+            .with_base_mtu(1500)
+            .unwrap()
+            .build()
+            .unwrap();
+        let config = Config::new(MaxMtu::try_from(9001).unwrap(), &Limits::default()).for_path(
+            &mut path_config,
+            &path::RemoteAddress::from(SocketAddress::from(addr)),
+        );
+        assert_eq!(Some(path_config), config.path);
+
+        let controller = Controller::new(config, &addr.into());
+        let headers = UDP_HEADER_LEN + IPV4_MIN_HEADER_LEN;
+
+        // packets are sent with the base MTU until the initial MTU has been confirmed
+        assert_eq!((1500 - headers) as usize, controller.mtu());
+        assert_eq!(9001 - headers, controller.probed_size);
+        assert_eq!(9001 - headers, controller.max_udp_payload);
+        // the max MTU of the IO provider is unchanged
+        assert_eq!(9001, u16::from(controller.max_mtu()));
+    }
+
+    #[test]
+    fn new_with_path_config_larger_than_max_mtu() {
+        let addr: SocketAddr = "10.0.0.1:443".parse().unwrap();
+        let path_config = PathConfig::builder()
+            .with_max_mtu(9001)
+            .unwrap()
+            .with_initial_mtu(9001)
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut config = Config::new(MaxMtu::try_from(1500).unwrap(), &Limits::default());
+        config.path = Some(path_config);
+        let controller = Controller::new(config, &addr.into());
+
+        // the path MTU is limited by the max MTU of the IO provider
+        let max_udp_payload = 1500 - UDP_HEADER_LEN - IPV4_MIN_HEADER_LEN;
+        assert_eq!(BASE_PLPMTU as usize, controller.mtu());
+        assert_eq!(max_udp_payload, controller.probed_size);
+        assert_eq!(max_udp_payload, controller.max_udp_payload);
+    }
+
+    #[test]
+    fn new_with_default_path_config() {
+        let addr: SocketAddr = "10.0.0.1:443".parse().unwrap();
+        let config = Config::new(MaxMtu::try_from(9001).unwrap(), &Limits::default()).for_path(
+            &mut s2n_quic_core::path::mtu::default::Endpoint,
+            &path::RemoteAddress::from(SocketAddress::from(addr)),
+        );
+        assert_eq!(None, config.path);
+        assert_eq!(
+            Config::new(MaxMtu::try_from(9001).unwrap(), &Limits::default()),
+            config
+        );
+    }
+
     //= https://www.rfc-editor.org/rfc/rfc8899#section-4.2
     //= type=test
     //# When
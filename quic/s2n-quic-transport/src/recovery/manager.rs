@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    arena,
     contexts::WriteContext,
     endpoint,
     path::{self, ecn::ValidationOutcome, path_event, Path},
@@ -10,18 +11,24 @@ use crate::{
     },
     transmission,
 };
+use alloc::vec::Vec;
 use core::{cmp::max, time::Duration};
 use s2n_quic_core::{
+    ack,
+    connection::memory,
     event::{
         self,
-        builder::{CongestionSource, SlowStartExitCause},
+        builder::{CongestionSource, SloMetric, SlowStartExitCause},
         IntoEvent,
     },
     frame,
     frame::ack::EcnCounts,
     inet::ExplicitCongestionNotification,
     packet::number::{PacketNumber, PacketNumberRange, PacketNumberSpace},
-    recovery::{congestion_controller, CongestionController, RttEstimator, K_GRANULARITY},
+    recovery::{
+        bandwidth::Bandwidth, congestion_controller, slo, CongestionController, RttEstimator,
+        K_GRANULARITY,
+    },
     time::{timer, Timer, Timestamp},
     transport,
 };
@@ -29,6 +36,25 @@ use smallvec::SmallVec;
 
 type PacketDetails<PacketInfo> = (PacketNumber, SentPacketInfo<PacketInfo>);
 
+/// The state and estimates of a path which are published when they change
+#[derive(Clone, Copy, Debug)]
+struct Snapshot {
+    state: congestion_controller::State,
+    bandwidth_estimate: Option<Bandwidth>,
+    min_rtt: Duration,
+}
+
+impl Snapshot {
+    #[inline]
+    fn new<Config: endpoint::Config>(path: &Path<Config>) -> Self {
+        Self {
+            state: path.congestion_controller.state(),
+            bandwidth_estimate: path.congestion_controller.bandwidth_estimate(),
+            min_rtt: path.rtt_estimator.min_rtt(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Manager<Config: endpoint::Config> {
     // The packet space for this recovery manager
@@ -64,6 +90,15 @@ pub struct Manager<Config: endpoint::Config> {
 
     // The total ecn counts for outstanding (unacknowledged) packets
     sent_packet_ecn_counts: EcnCounts,
+
+    // Recycled storage for the metadata of packets which are declared lost
+    lost_packets: Vec<PacketDetails<<<Config::CongestionControllerEndpoint as congestion_controller::Endpoint>::CongestionController as congestion_controller::CongestionController>::PacketInfo>>,
+
+    // The usage of `lost_packets`
+    lost_packets_usage: arena::Usage,
+
+    // Evaluates the RTT and loss rate thresholds of the connection
+    slo_monitor: slo::Monitor,
 }
 
 //= https://www.rfc-editor.org/rfc/rfc9002#section-6.1.1
@@ -79,6 +114,13 @@ const K_PACKET_THRESHOLD: u64 = 3;
 // TODO: Determine if there is a more appropriate default
 const ACKED_PACKETS_INITIAL_CAPACITY: usize = 32;
 
+/// The maximum number of ACK ranges accepted in a single ACK frame
+///
+/// Each range requires a lookup in the sent packets map so the amount of work a
+/// peer can cause with a single frame is bounded. Frames with more ranges are
+/// rejected and the connection is closed with a PROTOCOL_VIOLATION.
+const MAX_ACK_RANGES: usize = 256;
+
 macro_rules! recovery_event {
     ($path_id:ident, $path:ident) => {
         event::builder::RecoveryMetrics {
@@ -95,7 +137,7 @@ macro_rules! recovery_event {
             latest_rtt: $path.rtt_estimator.latest_rtt(),
             rtt_variance: $path.rtt_estimator.rttvar(),
             max_ack_delay: $path.rtt_estimator.max_ack_delay(),
-            pto_count: ($path.pto_backoff as f32).log2() as u32,
+            pto_count: $path.pto_count(),
             congestion_window: $path.congestion_controller.congestion_window(),
             bytes_in_flight: $path.congestion_controller.bytes_in_flight(),
             congestion_limited: $path.transmission_constraint().is_congestion_limited(),
@@ -128,9 +170,47 @@ impl<Config: endpoint::Config> Manager<Config> {
             time_of_last_ack_eliciting_packet: None,
             baseline_ecn_counts: EcnCounts::default(),
             sent_packet_ecn_counts: EcnCounts::default(),
+            lost_packets: Vec::new(),
+            lost_packets_usage: arena::Usage::default(),
+            slo_monitor: slo::Monitor::default(),
         }
     }
 
+    /// Sets the RTT and loss rate thresholds which are monitored by the recovery manager
+    pub fn with_slo_thresholds(mut self, thresholds: slo::Thresholds) -> Self {
+        self.slo_monitor = slo::Monitor::new(thresholds);
+        self
+    }
+
+    /// Sets the maximum number of probe packets sent when the PTO timer expires
+    pub fn with_max_pto_probes(mut self, max_probes: u8) -> Self {
+        debug_assert!(
+            (1..=MAX_PTO_PROBES).contains(&max_probes),
+            "an endpoint may send one or two probe packets"
+        );
+        self.pto.max_probes = max_probes;
+        self
+    }
+
+    /// Adds the usage of the storage for the metadata of lost packets to `stats`
+    pub fn arena_stats(&self, stats: &mut arena::Stats) {
+        stats.sent_packets += self.lost_packets_usage;
+    }
+
+    /// Adds the memory used for the metadata of sent packets to `usage`
+    pub fn memory_usage(&self, usage: &mut memory::Usage) {
+        usage.sent_packets += self.sent_packets.capacity()
+            * core::mem::size_of::<Option<SentPacketInfo<packet_info_type!()>>>()
+            + self.lost_packets.capacity()
+                * core::mem::size_of::<PacketDetails<packet_info_type!()>>();
+    }
+
+    /// Releases the storage for the metadata of sent packets which isn't in use
+    pub fn reclaim(&mut self) {
+        self.sent_packets.shrink();
+        self.lost_packets = Vec::new();
+    }
+
     /// Invoked when the Client processes a Retry packet.
     ///
     /// Reset congestion controller state by discarding sent bytes and replacing recovery
@@ -148,7 +228,7 @@ impl<Config: endpoint::Config> Manager<Config> {
         path.congestion_controller
             .on_packet_discarded(discarded_bytes);
 
-        *self = Self::new(self.space);
+        *self = Self::new(self.space).with_max_pto_probes(self.pto.max_probes);
     }
 
     pub fn on_timeout<Ctx: Context<Config>, Pub: event::ConnectionPublisher>(
@@ -223,12 +303,14 @@ impl<Config: endpoint::Config> Manager<Config> {
 
         let path_id = context.path_id();
         let path = context.path_mut();
+        let snapshot = Snapshot::new(path);
         let cc_packet_info = path.congestion_controller.on_packet_sent(
             time_sent,
             congestion_controlled_bytes,
             app_limited,
             &path.rtt_estimator,
         );
+        Self::on_congestion_controller_update(path, path_id, snapshot, publisher);
 
         self.sent_packets.insert(
             packet_number,
@@ -341,6 +423,25 @@ impl<Config: endpoint::Config> Manager<Config> {
         let space = self.space;
         let largest_acked_packet_number = space.new_packet_number(frame.largest_acknowledged());
 
+        let ack_ranges = frame.ack_ranges();
+        if ack_ranges.len() > MAX_ACK_RANGES {
+            // ranges are in descending order so the last range contains the smallest packet number
+            let smallest_acked = ack_ranges
+                .last()
+                .map_or(largest_acked_packet_number, |range| {
+                    space.new_packet_number(*range.start())
+                });
+            let error = ack::Error::RangeLimitExceeded;
+            Self::on_ack_range_rejected(
+                packet_number,
+                PacketNumberRange::new(smallest_acked, largest_acked_packet_number),
+                error.into_event(),
+                context,
+                publisher,
+            );
+            return Err(error.into());
+        }
+
         self.process_acks(
             timestamp,
             frame.ack_ranges().map(|ack_range| {
@@ -460,7 +561,17 @@ impl<Config: endpoint::Config> Manager<Config> {
                 ack_range: pn_range.into_event(),
             });
 
-            context.validate_packet_ack(timestamp, &pn_range)?;
+            if let Err(error) = context.validate_packet_ack(timestamp, &pn_range) {
+                Self::on_ack_range_rejected(
+                    packet_number,
+                    pn_range,
+                    error.into_event(),
+                    context,
+                    publisher,
+                );
+                return Err(error.into());
+            }
+
             // notify components of packets acked
             context.on_packet_ack(timestamp, &pn_range);
 
@@ -506,6 +617,26 @@ impl<Config: endpoint::Config> Manager<Config> {
         Ok((largest_newly_acked, includes_ack_eliciting))
     }
 
+    fn on_ack_range_rejected<Ctx: Context<Config>, Pub: event::ConnectionPublisher>(
+        packet_number: PacketNumber,
+        pn_range: PacketNumberRange,
+        reason: event::builder::AckRangeRejectReason,
+        context: &mut Ctx,
+        publisher: &mut Pub,
+    ) {
+        let rx_path_id = context.path_id();
+        let rx_path = context.path_mut();
+        publisher.on_ack_range_rejected(event::builder::AckRangeRejected {
+            packet_header: event::builder::PacketHeader::new(
+                packet_number,
+                publisher.quic_version(),
+            ),
+            path: path_event!(rx_path, rx_path_id),
+            ack_range: pn_range.into_event(),
+            reason,
+        });
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn update_congestion_control<Ctx: Context<Config>, Pub: event::ConnectionPublisher>(
         &mut self,
@@ -539,7 +670,9 @@ impl<Config: endpoint::Config> Manager<Config> {
 
         if should_update_rtt {
             let latest_rtt = timestamp - largest_newly_acked_info.time_sent;
+            let active_path_id = context.path_id();
             let path = context.path_mut_by_id(largest_newly_acked_info.path_id);
+            let snapshot = Snapshot::new(path);
             path.rtt_estimator.update_rtt(
                 ack_delay,
                 latest_rtt,
@@ -556,14 +689,22 @@ impl<Config: endpoint::Config> Manager<Config> {
                 timestamp,
                 &path.rtt_estimator,
             );
+            let path_id = largest_newly_acked_info.path_id;
             if slow_start && !path.congestion_controller.is_slow_start() {
-                let path_id = largest_newly_acked_info.path_id;
                 publisher.on_slow_start_exited(event::builder::SlowStartExited {
                     path: path_event!(path, path_id),
                     cause: SlowStartExitCause::Rtt,
                     congestion_window,
                 });
             }
+            Self::on_congestion_controller_update(path, path_id, snapshot, publisher);
+
+            if path_id == active_path_id {
+                let smoothed_rtt = path.rtt_estimator.smoothed_rtt();
+                if let Some(update) = self.slo_monitor.on_rtt_update(smoothed_rtt, timestamp) {
+                    Self::on_slo_update(path, path_id, update, publisher);
+                }
+            }
 
             // Notify components the RTT estimate was updated
             context.on_rtt_update();
@@ -605,6 +746,7 @@ impl<Config: endpoint::Config> Manager<Config> {
                 current_path_acked_bytes += sent_bytes;
             } else if sent_bytes > 0 {
                 let slow_start = path.congestion_controller.is_slow_start();
+                let snapshot = Snapshot::new(path);
                 let congestion_window = path.congestion_controller.congestion_window();
                 path.congestion_controller.on_ack(
                     acked_packet_info.time_sent,
@@ -614,14 +756,15 @@ impl<Config: endpoint::Config> Manager<Config> {
                     random_generator,
                     timestamp,
                 );
+                let path_id = acked_packet_info.path_id;
                 if slow_start && !path.congestion_controller.is_slow_start() {
-                    let path_id = acked_packet_info.path_id;
                     publisher.on_slow_start_exited(event::builder::SlowStartExited {
                         path: path_event!(path, path_id),
                         cause: SlowStartExitCause::Other,
                         congestion_window,
                     });
                 }
+                Self::on_congestion_controller_update(path, path_id, snapshot, publisher);
             }
 
             //= https://www.rfc-editor.org/rfc/rfc9002#section-6.2.1
@@ -668,6 +811,7 @@ impl<Config: endpoint::Config> Manager<Config> {
 
         if current_path_acked_bytes > 0 {
             let slow_start = path.congestion_controller.is_slow_start();
+            let snapshot = Snapshot::new(path);
             let congestion_window = path.congestion_controller.congestion_window();
             path.congestion_controller.on_ack(
                 largest_newly_acked.time_sent,
@@ -684,9 +828,22 @@ impl<Config: endpoint::Config> Manager<Config> {
                     congestion_window,
                 });
             }
+            Self::on_congestion_controller_update(path, current_path_id, snapshot, publisher);
 
             self.update_pto_timer(path, timestamp, is_handshake_confirmed);
         }
+
+        // MTU probes are excluded from the loss rate, since their loss is not caused by congestion
+        let acked_packets = newly_acked_packets
+            .iter()
+            .filter(|packet| !packet.transmission_mode.is_mtu_probing())
+            .count();
+        if let Some(update) = self
+            .slo_monitor
+            .on_packets(acked_packets as u64, 0, timestamp)
+        {
+            Self::on_slo_update(context.path(), current_path_id, update, publisher);
+        }
     }
 
     fn process_ecn<Ctx: Context<Config>, Pub: event::ConnectionPublisher>(
@@ -713,6 +870,7 @@ impl<Config: endpoint::Config> Manager<Config> {
 
         if let ValidationOutcome::CongestionExperienced(ce_count) = outcome {
             let slow_start = context.path().congestion_controller.is_slow_start();
+            let snapshot = Snapshot::new(context.path());
             let congestion_window = context.path().congestion_controller.congestion_window();
             //= https://www.rfc-editor.org/rfc/rfc9002#section-7.1
             //# If a path has been validated to support Explicit Congestion
@@ -732,6 +890,7 @@ impl<Config: endpoint::Config> Manager<Config> {
                 });
             }
             let path = context.path();
+            Self::on_congestion_controller_update(path, path_id, snapshot, publisher);
             publisher.on_congestion(event::builder::Congestion {
                 path: path_event!(path, path_id),
                 source: CongestionSource::Ecn,
@@ -742,6 +901,71 @@ impl<Config: endpoint::Config> Manager<Config> {
         self.sent_packet_ecn_counts -= newly_acked_ecn_counts;
     }
 
+    /// Publishes events for the changes of the congestion controller since the snapshot was taken
+    #[inline]
+    fn on_congestion_controller_update<Pub: event::ConnectionPublisher>(
+        path: &Path<Config>,
+        path_id: path::Id,
+        previous: Snapshot,
+        publisher: &mut Pub,
+    ) {
+        let current = Snapshot::new(path);
+
+        if current.state != previous.state {
+            publisher.on_congestion_state_updated(event::builder::CongestionStateUpdated {
+                path: path_event!(path, path_id),
+                previous_state: previous.state.into_event(),
+                state: current.state.into_event(),
+                congestion_window: path.congestion_controller.congestion_window(),
+            });
+        }
+
+        if current.bandwidth_estimate != previous.bandwidth_estimate
+            || current.min_rtt != previous.min_rtt
+        {
+            publisher.on_bandwidth_estimate_updated(event::builder::BandwidthEstimateUpdated {
+                path: path_event!(path, path_id),
+                bytes_per_second: current
+                    .bandwidth_estimate
+                    .map(|bandwidth| bandwidth * Duration::from_secs(1)),
+                min_rtt: current.min_rtt,
+            });
+        }
+    }
+
+    /// Publishes an event for an RTT or loss rate threshold which was breached or cleared
+    #[inline]
+    fn on_slo_update<Pub: event::ConnectionPublisher>(
+        path: &Path<Config>,
+        path_id: path::Id,
+        update: slo::Update,
+        publisher: &mut Pub,
+    ) {
+        let (metric, breached) = match update {
+            slo::Update::RoundTripTime {
+                breached,
+                smoothed_rtt,
+            } => (SloMetric::RoundTripTime { smoothed_rtt }, breached),
+            slo::Update::LossRate {
+                breached,
+                lost_packets,
+                total_packets,
+            } => (
+                SloMetric::LossRate {
+                    lost_packets,
+                    total_packets,
+                },
+                breached,
+            ),
+        };
+
+        publisher.on_slo_updated(event::builder::SloUpdated {
+            path: path_event!(path, path_id),
+            metric,
+            breached,
+        });
+    }
+
     /// Returns `true` if the recovery manager requires a probe packet to be sent.
     #[inline]
     pub fn requires_probe(&self) -> bool {
@@ -815,9 +1039,11 @@ impl<Config: endpoint::Config> Manager<Config> {
             .largest_acked_packet
             .expect("This function is only called after an ack has been received");
 
-        // TODO: Investigate a more efficient mechanism for managing sent packets to remove
-        //       See: https://github.com/aws/s2n-quic/issues/1075
-        let mut sent_packets_to_remove = Vec::new();
+        // The storage is reused across calls so it only needs to be allocated when more packets
+        // are lost than ever before
+        let mut sent_packets_to_remove = core::mem::take(&mut self.lost_packets);
+        debug_assert!(sent_packets_to_remove.is_empty());
+        let capacity = sent_packets_to_remove.capacity();
         let mut persistent_congestion_calculator = PersistentCongestionCalculator::new(
             context.path().rtt_estimator.first_rtt_sample(),
             context.path_id(),
@@ -894,6 +1120,12 @@ impl<Config: endpoint::Config> Manager<Config> {
             }
         }
 
+        self.lost_packets_usage.allocations += sent_packets_to_remove.len() as u64;
+        self.lost_packets_usage.on_heap_allocation(
+            (sent_packets_to_remove.capacity() - capacity)
+                * core::mem::size_of::<PacketDetails<packet_info_type!()>>(),
+        );
+
         (
             persistent_congestion_calculator.persistent_congestion_duration(),
             sent_packets_to_remove,
@@ -904,7 +1136,7 @@ impl<Config: endpoint::Config> Manager<Config> {
         &mut self,
         now: Timestamp,
         persistent_congestion_duration: Duration,
-        sent_packets_to_remove: Vec<PacketDetails<packet_info_type!()>>,
+        mut sent_packets_to_remove: Vec<PacketDetails<packet_info_type!()>>,
         random_generator: &mut Config::RandomGenerator,
         context: &mut Ctx,
         publisher: &mut Pub,
@@ -912,9 +1144,10 @@ impl<Config: endpoint::Config> Manager<Config> {
         let current_path_id = context.path_id();
         let mut is_congestion_event = false;
         let mut prev_lost_packet_number = None;
+        let mut lost_packets = 0;
 
         // Remove the lost packets and account for the bytes on the proper congestion controller
-        for (packet_number, sent_info) in sent_packets_to_remove {
+        for (packet_number, sent_info) in sent_packets_to_remove.drain(..) {
             let path = context.path_mut_by_id(sent_info.path_id);
             self.sent_packets.remove(packet_number);
 
@@ -947,6 +1180,7 @@ impl<Config: endpoint::Config> Manager<Config> {
                     .on_packet_discarded(sent_info.sent_bytes as usize);
             } else if sent_info.sent_bytes > 0 {
                 let slow_start = path.congestion_controller.is_slow_start();
+                let snapshot = Snapshot::new(path);
                 let congestion_window = path.congestion_controller.congestion_window();
                 path.congestion_controller.on_packet_lost(
                     sent_info.sent_bytes as u32,
@@ -956,17 +1190,22 @@ impl<Config: endpoint::Config> Manager<Config> {
                     random_generator,
                     now,
                 );
+                let path_id = sent_info.path_id;
                 if slow_start && !path.congestion_controller.is_slow_start() {
-                    let path_id = sent_info.path_id;
                     publisher.on_slow_start_exited(event::builder::SlowStartExited {
                         path: path_event!(path, path_id),
                         cause: SlowStartExitCause::PacketLoss,
                         congestion_window,
                     });
                 }
+                Self::on_congestion_controller_update(path, path_id, snapshot, publisher);
                 is_congestion_event = true;
             }
 
+            if !sent_info.transmission_mode.is_mtu_probing() {
+                lost_packets += 1;
+            }
+
             publisher.on_packet_lost(event::builder::PacketLost {
                 packet_header: event::builder::PacketHeader::new(
                     packet_number,
@@ -1016,6 +1255,13 @@ impl<Config: endpoint::Config> Manager<Config> {
                 source: CongestionSource::PacketLoss,
             })
         }
+
+        if let Some(update) = self.slo_monitor.on_packets(0, lost_packets, now) {
+            Self::on_slo_update(context.path(), current_path_id, update, publisher);
+        }
+
+        // Keep the storage around for the next time packets are declared lost
+        self.lost_packets = sent_packets_to_remove;
     }
 
     fn calculate_loss_time_threshold(rtt_estimator: &RttEstimator) -> Duration {
@@ -1077,7 +1323,7 @@ pub trait Context<Config: endpoint::Config> {
         &mut self,
         timestamp: Timestamp,
         packet_number_range: &PacketNumberRange,
-    ) -> Result<(), transport::Error>;
+    ) -> Result<(), ack::Error>;
 
     fn on_new_packet_ack<Pub: event::ConnectionPublisher>(
         &mut self,
@@ -1102,11 +1348,31 @@ impl<Config: endpoint::Config> transmission::interest::Provider for Manager<Conf
     }
 }
 
+//= https://www.rfc-editor.org/rfc/rfc9002#section-6.2.4
+//# An endpoint
+//# MAY send up to two full-sized datagrams containing ack-eliciting
+//# packets to avoid an expensive consecutive PTO expiration due to a
+//# single lost datagram or to transmit data from multiple packet number
+//# spaces.
+const MAX_PTO_PROBES: u8 = 2;
+
 /// Manages the probe time out calculation and probe packet transmission
-#[derive(Debug, Default)]
+#[derive(Debug)]
 struct Pto {
     timer: Timer,
     state: PtoState,
+    /// The number of probe packets sent when the timer expires with packets in flight
+    max_probes: u8,
+}
+
+impl Default for Pto {
+    fn default() -> Self {
+        Self {
+            timer: Timer::default(),
+            state: PtoState::default(),
+            max_probes: MAX_PTO_PROBES,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -1146,7 +1412,11 @@ impl Pto {
             //# Sending two packets on PTO
             //# expiration increases resilience to packet drops, thus reducing the
             //# probability of consecutive PTO events.
-            let transmission_count = if packets_in_flight { 2 } else { 1 };
+            let transmission_count = if packets_in_flight {
+                self.max_probes
+            } else {
+                1
+            };
 
             self.state = PtoState::RequiresTransmission(transmission_count);
             true
@@ -18,7 +18,7 @@ use s2n_quic_core::{
     frame::ack_elicitation::AckElicitation,
     inet::{DatagramInfo, ExplicitCongestionNotification, SocketAddress},
     packet::number::PacketNumberSpace,
-    path::{migration, RemoteAddress, DEFAULT_MAX_MTU, INITIAL_PTO_BACKOFF},
+    path::{migration, mtu, RemoteAddress, DEFAULT_MAX_MTU, INITIAL_PTO_BACKOFF},
     random,
     recovery::{
         congestion_controller::testing::mock::{
@@ -55,7 +55,7 @@ fn one_second_pto_when_no_previous_rtt_available() {
         RttEstimator::new(max_ack_delay),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
 
     manager
@@ -462,7 +462,7 @@ fn on_ack_frame() {
         context.path().rtt_estimator,
         MockCongestionController::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     context.path_mut().pto_backoff = 2;
     let ack_receive_time = ack_receive_time + Duration::from_millis(500);
@@ -2504,7 +2504,7 @@ fn update_pto_timer() {
         RttEstimator::new(Duration::from_millis(10)),
         MockCongestionController::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
     // simulate receiving a handshake packet to force path validation
     context.path_mut().on_handshake_packet();
@@ -2589,7 +2589,7 @@ fn pto_armed_if_handshake_not_confirmed() {
         RttEstimator::new(Duration::from_millis(10)),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
 
     // simulate receiving a handshake packet to force path validation
@@ -2617,7 +2617,7 @@ fn pto_must_be_at_least_k_granularity() {
         RttEstimator::new(max_ack_delay),
         Default::default(),
         false,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
 
     // Update RTT with the smallest possible sample
@@ -2758,6 +2758,37 @@ fn on_timeout() {
         .is_some());
 }
 
+#[test]
+fn on_timeout_max_pto_probes() {
+    let space = PacketNumberSpace::Initial;
+    let mut manager = Manager::new(space).with_max_pto_probes(1);
+    let now = s2n_quic_platform::time::now() + Duration::from_secs(10);
+    let mut path_manager = helper_generate_path_manager(Duration::from_millis(10));
+    let ecn = ExplicitCongestionNotification::default();
+    let mut context = MockContext::new(&mut path_manager);
+    let mut publisher = Publisher::no_snapshot();
+    let random = &mut random::testing::Generator::default();
+
+    manager.sent_packets.insert(
+        space.new_packet_number(VarInt::from_u8(1)),
+        SentPacketInfo::new(
+            true,
+            1,
+            now,
+            AckElicitation::Eliciting,
+            unsafe { path::Id::new(0) },
+            ecn,
+            transmission::Mode::Normal,
+            Default::default(),
+        ),
+    );
+    manager.pto.timer.set(now - Duration::from_secs(5));
+    manager.on_timeout(now, random, &mut context, &mut publisher);
+
+    // only a single probe is sent even though packets are in flight
+    assert_eq!(manager.pto.state, RequiresTransmission(1));
+}
+
 #[test]
 fn timers() {
     let space = PacketNumberSpace::ApplicationData;
@@ -2952,6 +2983,147 @@ fn ack_packets(
     )
 }
 
+#[test]
+fn on_ack_frame_rejected_range() {
+    let space = PacketNumberSpace::ApplicationData;
+    let mut manager = Manager::new(space);
+    let ecn = ExplicitCongestionNotification::default();
+    let mut path_manager = helper_generate_path_manager(Duration::from_millis(10));
+    let mut context = MockContext::new(&mut path_manager);
+    let mut publisher = Publisher::snapshot();
+    let random = &mut random::testing::Generator::default();
+    let time_sent = s2n_quic_platform::time::now() + Duration::from_secs(10);
+
+    // Send packets 1 to 10
+    for i in 1..=10 {
+        manager.on_packet_sent(
+            space.new_packet_number(VarInt::from_u8(i)),
+            transmission::Outcome {
+                ack_elicitation: AckElicitation::Eliciting,
+                is_congestion_controlled: true,
+                bytes_sent: 128,
+                bytes_progressed: 0,
+            },
+            time_sent,
+            ecn,
+            transmission::Mode::Normal,
+            None,
+            &mut context,
+            &mut publisher,
+        );
+    }
+
+    context.validate_packet_ack_error = Some(ack::Error::SkippedPacketNumber);
+
+    let acked_packets = PacketNumberRange::new(
+        space.new_packet_number(VarInt::from_u8(1)),
+        space.new_packet_number(VarInt::from_u8(3)),
+    );
+    let mut ack_ranges = AckRanges::new(1);
+    assert!(ack_ranges.insert_packet_number_range(acked_packets).is_ok());
+    let frame = frame::Ack {
+        ack_delay: VarInt::from_u8(10),
+        ack_ranges: (&ack_ranges),
+        ecn_counts: None,
+    };
+
+    let result = manager.on_ack_frame(
+        time_sent + Duration::from_millis(500),
+        frame,
+        acked_packets.start(),
+        random,
+        &mut context,
+        &mut publisher,
+    );
+
+    // The connection should be closed and none of the packets acknowledged
+    assert_eq!(
+        result,
+        Err(transport::Error::from(ack::Error::SkippedPacketNumber))
+    );
+    assert_eq!(context.validate_packet_ack_count, 1);
+    assert_eq!(context.on_packet_ack_count, 0);
+    assert_eq!(context.on_new_packet_ack_count, 0);
+    assert_eq!(manager.sent_packets.iter().count(), 10);
+}
+
+#[test]
+fn on_ack_frame_range_limit() {
+    let space = PacketNumberSpace::ApplicationData;
+    let ecn = ExplicitCongestionNotification::default();
+    let random = &mut random::testing::Generator::default();
+    let time_sent = s2n_quic_platform::time::now() + Duration::from_secs(10);
+
+    for range_count in [MAX_ACK_RANGES, MAX_ACK_RANGES + 1] {
+        let mut manager = Manager::new(space);
+        let mut path_manager = helper_generate_path_manager(Duration::from_millis(10));
+        let mut context = MockContext::new(&mut path_manager);
+        let mut publisher = Publisher::no_snapshot();
+        let mut ack_ranges = AckRanges::new(range_count);
+
+        // Send enough packets to ACK every other one
+        for i in 0..(range_count * 2) as u32 {
+            let packet_number = space.new_packet_number(VarInt::from_u32(i));
+            manager.on_packet_sent(
+                packet_number,
+                transmission::Outcome {
+                    ack_elicitation: AckElicitation::Eliciting,
+                    is_congestion_controlled: true,
+                    bytes_sent: 1,
+                    bytes_progressed: 0,
+                },
+                time_sent,
+                ecn,
+                transmission::Mode::Normal,
+                None,
+                &mut context,
+                &mut publisher,
+            );
+
+            if i % 2 == 0 {
+                assert!(ack_ranges.insert_packet_number(packet_number).is_ok());
+            }
+        }
+
+        let frame = frame::Ack {
+            ack_delay: VarInt::from_u8(10),
+            ack_ranges: (&ack_ranges),
+            ecn_counts: None,
+        };
+        assert_eq!(frame.ack_ranges().len(), range_count);
+        let largest_acked = frame.largest_acknowledged();
+
+        let result = manager.on_ack_frame(
+            time_sent + Duration::from_millis(500),
+            frame,
+            space.new_packet_number(largest_acked),
+            random,
+            &mut context,
+            &mut publisher,
+        );
+
+        if range_count > MAX_ACK_RANGES {
+            // The connection should be closed without processing any of the ranges
+            assert_eq!(
+                result,
+                Err(transport::Error::from(ack::Error::RangeLimitExceeded))
+            );
+            assert_eq!(context.validate_packet_ack_count, 0);
+            assert_eq!(context.on_packet_ack_count, 0);
+            assert_eq!(manager.sent_packets.iter().count(), range_count * 2);
+        } else {
+            assert!(result.is_ok());
+            assert_eq!(context.validate_packet_ack_count, range_count);
+            assert_eq!(context.on_new_packet_ack_count, range_count);
+            for i in 0..range_count as u32 {
+                let packet_number = space.new_packet_number(VarInt::from_u32(i * 2));
+                assert!(manager.sent_packets.get(packet_number).is_none());
+                assert!(!context.lost_packets.contains(&packet_number));
+            }
+        }
+    }
+}
+
 #[test]
 fn requires_probe() {
     let space = PacketNumberSpace::ApplicationData;
@@ -3151,7 +3323,8 @@ fn helper_generate_multi_path_manager(
                 true,
                 &mut Endpoint::default(),
                 &mut migration::default::Validator::default(),
-                DEFAULT_MAX_MTU,
+                &mut mtu::default::Endpoint::default(),
+                DEFAULT_MAX_MTU.into(),
                 publisher,
             )
             .unwrap();
@@ -3213,20 +3386,21 @@ fn helper_generate_path_manager_with_first_addr(
         RttEstimator::new(max_ack_delay),
         MockCongestionController::default(),
         true,
-        DEFAULT_MAX_MTU,
+        DEFAULT_MAX_MTU.into(),
     );
 
     path::Manager::new(path, registry)
 }
 
 struct MockContext<'a> {
-    validate_packet_ack_count: u8,
-    on_new_packet_ack_count: u8,
-    on_packet_ack_count: u8,
-    on_packet_loss_count: u8,
-    on_rtt_update_count: u8,
+    validate_packet_ack_count: usize,
+    on_new_packet_ack_count: usize,
+    on_packet_ack_count: usize,
+    on_packet_loss_count: usize,
+    on_rtt_update_count: usize,
     path_id: path::Id,
     lost_packets: HashSet<PacketNumber>,
+    validate_packet_ack_error: Option<ack::Error>,
     path_manager: &'a mut path::Manager<Config>,
 }
 
@@ -3240,6 +3414,7 @@ impl<'a> MockContext<'a> {
             on_rtt_update_count: 0,
             path_id: path_manager.active_path_id(),
             lost_packets: HashSet::default(),
+            validate_packet_ack_error: None,
             path_manager,
         }
     }
@@ -3280,9 +3455,12 @@ impl<'a> recovery::Context<Config> for MockContext<'a> {
         &mut self,
         _timestamp: Timestamp,
         _packet_number_range: &PacketNumberRange,
-    ) -> Result<(), transport::Error> {
+    ) -> Result<(), ack::Error> {
         self.validate_packet_ack_count += 1;
-        Ok(())
+        match self.validate_packet_ack_error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
     }
 
     fn on_new_packet_ack<Pub: event::ConnectionPublisher>(
@@ -0,0 +1,46 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Synchronization primitives for state that is shared between the endpoint and its connections
+//!
+//! `std::sync::Mutex` is used when the `std` feature is enabled. Otherwise a spin lock is used,
+//! which exposes the same interface but can never be poisoned.
+//!
+//! When compiled with `--cfg loom`, the primitives are replaced with the `loom` implementations
+//! so the model tests can check all of the possible interleavings of the threads. Values which
+//! use these primitives can only be created inside of `loom::model` in that configuration.
+
+#[cfg(loom)]
+pub use loom::sync::{atomic, Arc, Mutex};
+
+#[cfg(not(loom))]
+pub use alloc::sync::Arc;
+
+#[cfg(not(loom))]
+pub use core::sync::atomic;
+
+#[cfg(all(not(loom), feature = "std"))]
+pub use std::sync::Mutex;
+
+#[cfg(all(not(loom), not(feature = "std")))]
+pub use spin_lock::Mutex;
+
+#[cfg(all(not(loom), not(feature = "std")))]
+mod spin_lock {
+    use core::convert::Infallible;
+
+    #[derive(Debug)]
+    pub struct Mutex<T>(spin::Mutex<T>);
+
+    impl<T> Mutex<T> {
+        #[inline]
+        pub fn new(value: T) -> Self {
+            Self(spin::Mutex::new(value))
+        }
+
+        #[inline]
+        pub fn lock(&self) -> Result<spin::MutexGuard<'_, T>, Infallible> {
+            Ok(self.0.lock())
+        }
+    }
+}
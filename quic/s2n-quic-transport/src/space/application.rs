@@ -3,38 +3,59 @@
 
 use crate::{
     ack::AckManager,
-    connection::{self, ConnectionTransmissionContext, ProcessingError},
+    arena,
+    connection::{
+        self, local_id_registry::LocalIdRegistrationError, protocol_violation,
+        ConnectionTransmissionContext, ProcessingError,
+    },
     endpoint, path,
     path::{path_event, Path},
     processed_packet::ProcessedPacket,
     recovery,
-    space::{datagram, keep_alive::KeepAlive, HandshakeStatus, PacketSpace, TxPacketNumbers},
+    space::{
+        datagram, extension_frame, keep_alive::KeepAlive, HandshakeStatus, PacketSpace,
+        TxPacketNumbers,
+    },
     stream::AbstractStreamManager,
     sync::flag,
     transmission,
     transmission::interest::Provider,
 };
 use core::{convert::TryInto, fmt, marker::PhantomData};
+#[cfg(feature = "std")]
 use once_cell::sync::OnceCell;
 use s2n_codec::EncoderBuffer;
 use s2n_quic_core::{
-    crypto::{application::KeySet, limited, tls, CryptoSuite},
+    ack,
+    connection::{memory, transfer},
+    crypto::{
+        application::{KeySet, KeySetState},
+        limited,
+        one_rtt::{ExportedHeaderKey, ExportedKey},
+        tls, CryptoSuite, OneRttHeaderKey,
+    },
     event::{self, ConnectionPublisher as _, IntoEvent},
+    extension_frame::FrameType,
     frame::{
-        ack::AckRanges, crypto::CryptoRef, datagram::DatagramRef, stream::StreamRef, Ack,
-        ConnectionClose, DataBlocked, HandshakeDone, MaxData, MaxStreamData, MaxStreams,
-        NewConnectionId, NewToken, PathChallenge, PathResponse, ResetStream, RetireConnectionId,
-        StopSending, StreamDataBlocked, StreamsBlocked,
+        ack::AckRanges, crypto::CryptoRef, datagram::DatagramRef, extension::ExtensionRef,
+        stream::StreamRef, Ack, ConnectionClose, DataBlocked, HandshakeDone, MaxData,
+        MaxStreamData, MaxStreams, NewConnectionId, NewToken, PathChallenge, PathResponse,
+        ResetStream, RetireConnectionId, StopSending, StreamDataBlocked, StreamsBlocked,
     },
     inet::DatagramInfo,
     packet::{
         encoding::{PacketEncoder, PacketEncodingError},
         number::{PacketNumber, PacketNumberRange, PacketNumberSpace, SlidingWindow},
+        quic_bit::{self, QuicBit},
         short::{CleartextShort, ProtectedShort, Short, SpinBit},
     },
     path::MaxMtu,
     time::{timer, Timestamp},
-    transport,
+    transport::{
+        self,
+        parameters::{GreaseQuicBit, InitialFlowControlLimits},
+    },
+    varint::VarInt,
 };
 
 pub struct ApplicationSpace<Config: endpoint::Config> {
@@ -47,6 +68,9 @@ pub struct ApplicationSpace<Config: endpoint::Config> {
     /// The current state of the Spin bit
     /// TODO: Spin me
     pub spin_bit: SpinBit,
+    /// Chooses the QUIC bit of sent packets, if the peer advertised the `grease_quic_bit`
+    /// transport parameter
+    quic_bit_grease: Option<quic_bit::Grease>,
     /// The crypto suite for application data
     /// TODO: What about ZeroRtt?
     //= https://www.rfc-editor.org/rfc/rfc9001#section-6.3
@@ -64,6 +88,21 @@ pub struct ApplicationSpace<Config: endpoint::Config> {
     processed_packet_numbers: SlidingWindow,
     recovery_manager: recovery::Manager<Config>,
     pub datagram_manager: datagram::Manager<Config>,
+    pub extension_frame_manager: extension_frame::Manager<Config>,
+}
+
+/// The state of the application space which is transferred to another endpoint
+#[derive(Debug)]
+pub struct ExportedSpace {
+    pub key: ExportedKey,
+    pub header_key: ExportedHeaderKey,
+    pub key_set: KeySetState,
+    pub next_packet_number: VarInt,
+    pub largest_received_packet_number: Option<VarInt>,
+    pub streams: transfer::Streams,
+    pub peer_limits: InitialFlowControlLimits,
+    pub max_datagram_payload: u64,
+    pub grease_quic_bit: bool,
 }
 
 impl<Config: endpoint::Config> fmt::Debug for ApplicationSpace<Config> {
@@ -90,22 +129,169 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
         keep_alive: KeepAlive,
         max_mtu: MaxMtu,
         datagram_manager: datagram::Manager<Config>,
+        extension_frame_manager: extension_frame::Manager<Config>,
+        grease_quic_bit: GreaseQuicBit,
+        recovery_manager: recovery::Manager<Config>,
+        random_generator: &mut Config::RandomGenerator,
     ) -> Self {
         let key_set = KeySet::new(key, Self::key_limits(max_mtu));
 
+        Self::with_key_set(
+            key_set,
+            header_key,
+            now,
+            stream_manager,
+            ack_manager,
+            keep_alive,
+            datagram_manager,
+            extension_frame_manager,
+            grease_quic_bit,
+            recovery_manager,
+            random_generator,
+        )
+    }
+
+    /// Creates the application space of a connection which was transferred from another
+    /// endpoint
+    #[allow(clippy::too_many_arguments)]
+    pub fn import(
+        state: &transfer::State,
+        now: Timestamp,
+        mut stream_manager: AbstractStreamManager<Config::Stream>,
+        mut ack_manager: AckManager,
+        keep_alive: KeepAlive,
+        max_mtu: MaxMtu,
+        datagram_manager: datagram::Manager<Config>,
+        extension_frame_manager: extension_frame::Manager<Config>,
+        recovery_manager: recovery::Manager<Config>,
+        random_generator: &mut Config::RandomGenerator,
+    ) -> Result<Self, transfer::Error> {
+        let key_set = KeySet::import(&state.key, state.key_set, Self::key_limits(max_mtu))
+            .ok_or(transfer::Error::KeysNotExportable)?;
+        let header_key: <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::OneRttHeaderKey =
+            OneRttHeaderKey::import(&state.header_key)
+                .ok_or(transfer::Error::KeysNotExportable)?;
+
+        stream_manager.on_transferred(&state.streams);
+
+        let grease_quic_bit = if state.grease_quic_bit {
+            GreaseQuicBit::Enabled
+        } else {
+            GreaseQuicBit::Disabled
+        };
+
+        let mut processed_packet_numbers = SlidingWindow::default();
+        if let Some(largest) = state.largest_received_packet_number {
+            let largest = PacketNumberSpace::ApplicationData.new_packet_number(largest);
+            ack_manager.on_transferred(largest);
+            // Packets up to the largest received packet number might have been processed by
+            // the exporting endpoint, so older packets are rejected as duplicates.
+            processed_packet_numbers
+                .insert(largest)
+                .expect("the sliding window is empty");
+        }
+
+        let mut space = Self::with_key_set(
+            key_set,
+            header_key,
+            now,
+            stream_manager,
+            ack_manager,
+            keep_alive,
+            datagram_manager,
+            extension_frame_manager,
+            grease_quic_bit,
+            recovery_manager,
+            random_generator,
+        );
+        space.tx_packet_numbers = TxPacketNumbers::import(
+            PacketNumberSpace::ApplicationData,
+            state.next_packet_number,
+            now,
+            random_generator,
+        );
+        space.processed_packet_numbers = processed_packet_numbers;
+
+        Ok(space)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn with_key_set(
+        key_set: KeySet<
+            <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::OneRttKey,
+        >,
+        header_key: <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::OneRttHeaderKey,
+        now: Timestamp,
+        stream_manager: AbstractStreamManager<Config::Stream>,
+        ack_manager: AckManager,
+        keep_alive: KeepAlive,
+        datagram_manager: datagram::Manager<Config>,
+        extension_frame_manager: extension_frame::Manager<Config>,
+        grease_quic_bit: GreaseQuicBit,
+        recovery_manager: recovery::Manager<Config>,
+        random_generator: &mut Config::RandomGenerator,
+    ) -> Self {
+        // RFC 9287 Section 3.1: Endpoints that receive the grease_quic_bit transport parameter
+        // from a peer SHOULD set the QUIC Bit to an unpredictable value unless another extension
+        // assigns specific meaning to the value of the bit.
+        let quic_bit_grease = if grease_quic_bit.is_enabled() {
+            Some(quic_bit::Grease::new(random_generator))
+        } else {
+            None
+        };
+
         Self {
-            tx_packet_numbers: TxPacketNumbers::new(PacketNumberSpace::ApplicationData, now),
+            tx_packet_numbers: TxPacketNumbers::new(
+                PacketNumberSpace::ApplicationData,
+                now,
+                random_generator,
+            ),
             ack_manager,
             spin_bit: SpinBit::Zero,
+            quic_bit_grease,
             stream_manager,
             key_set,
             header_key,
             ping: flag::Ping::default(),
             keep_alive,
             processed_packet_numbers: SlidingWindow::default(),
-            recovery_manager: recovery::Manager::new(PacketNumberSpace::ApplicationData),
+            recovery_manager,
             datagram_manager,
+            extension_frame_manager,
+        }
+    }
+
+    /// Exports the keys, packet numbers and stream state for transferring the connection to
+    /// another endpoint
+    pub fn export_transfer(&self) -> Result<ExportedSpace, transfer::Error> {
+        if self.key_set.key_update_in_progress() {
+            return Err(transfer::Error::KeyUpdateInProgress);
         }
+
+        let streams = self.stream_manager.export_transfer()?;
+        let (key, key_set) = self
+            .key_set
+            .export()
+            .ok_or(transfer::Error::KeysNotExportable)?;
+        let header_key = self
+            .header_key
+            .export()
+            .ok_or(transfer::Error::KeysNotExportable)?;
+
+        Ok(ExportedSpace {
+            key,
+            header_key,
+            key_set,
+            next_packet_number: PacketNumber::as_varint(self.tx_packet_numbers.next()),
+            largest_received_packet_number: self
+                .ack_manager
+                .largest_received_packet_number()
+                .map(PacketNumber::as_varint),
+            streams,
+            peer_limits: self.stream_manager.initial_peer_limits(),
+            max_datagram_payload: self.datagram_manager.max_datagram_payload(),
+            grease_quic_bit: self.quic_bit_grease.is_some(),
+        })
     }
 
     /// Returns true if the packet number has already been processed
@@ -161,6 +347,7 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
         let transmission_mode = context.transmission_mode;
         let min_packet_len = context.min_packet_len;
         let bytes_progressed = self.stream_manager.outgoing_bytes_progressed();
+        let quic_bit = self.next_quic_bit();
 
         let payload = transmission::Transmission {
             config: <PhantomData<Config>>::default(),
@@ -177,6 +364,7 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
                 &mut self.stream_manager,
                 &mut self.recovery_manager,
                 &mut self.datagram_manager,
+                &mut self.extension_frame_manager,
             ),
             timestamp,
             transmission_constraint,
@@ -193,6 +381,7 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
             self.key_set
                 .encrypt_packet(buffer, |buffer, key, key_phase| {
                     let packet = Short {
+                        quic_bit,
                         spin_bit,
                         key_phase,
                         destination_connection_id,
@@ -259,6 +448,7 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
 
         let mut outcome = transmission::Outcome::default();
         let destination_connection_id = context.path().peer_connection_id;
+        let quic_bit = self.next_quic_bit();
 
         let payload = transmission::Transmission {
             config: <PhantomData<Config>>::default(),
@@ -284,6 +474,7 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
             self.key_set
                 .encrypt_packet(buffer, |buffer, key, key_phase| {
                     let packet = Short {
+                        quic_bit,
                         spin_bit,
                         key_phase,
                         destination_connection_id,
@@ -311,6 +502,14 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
         Ok((outcome, buffer))
     }
 
+    /// Returns the QUIC bit for the next packet
+    #[inline]
+    fn next_quic_bit(&mut self) -> QuicBit {
+        self.quic_bit_grease
+            .as_mut()
+            .map_or(QuicBit::One, quic_bit::Grease::next_bit)
+    }
+
     /// Signals the connection was previously blocked by anti-amplification limits
     /// but is now no longer limited.
     pub fn on_amplification_unblocked(
@@ -394,6 +593,26 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
         }
     }
 
+    /// Adds the arena usage of the packet number space to `stats`
+    pub fn arena_stats(&self, stats: &mut arena::Stats) {
+        self.recovery_manager.arena_stats(stats);
+        self.stream_manager.arena_stats(stats);
+    }
+
+    /// Adds the memory used by the packet number space to `usage`
+    pub fn memory_usage(&self, usage: &mut memory::Usage) {
+        self.recovery_manager.memory_usage(usage);
+        self.stream_manager.memory_usage(usage);
+        usage.crypto +=
+            core::mem::size_of_val(&self.key_set) + core::mem::size_of_val(&self.header_key);
+    }
+
+    /// Releases the memory which is retained for reuse by the packet number space
+    pub fn reclaim(&mut self) {
+        self.recovery_manager.reclaim();
+        self.stream_manager.reclaim();
+    }
+
     /// Returns `true` if the recovery manager for this packet space requires a probe
     /// packet to be sent.
     pub fn requires_probe(&self) -> bool {
@@ -430,6 +649,7 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
                 handshake_status,
                 ping: &mut self.ping,
                 stream_manager: &mut self.stream_manager,
+                extension_frame_manager: &mut self.extension_frame_manager,
                 local_id_registry,
                 path_id,
                 path_manager,
@@ -533,27 +753,37 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
         limits.max_mtu = max_mtu;
 
         // AEAD optimizations are currently in the testing phase so make them opt-in at runtime
-        limits.sealer_optimization_threshold = {
-            static THRESHOLD: OnceCell<u64> = OnceCell::new();
-
-            *THRESHOLD.get_or_init(|| {
-                std::env::var("S2N_UNSTABLE_CRYPTO_OPT_TX")
-                    .ok()
-                    .and_then(|v| v.parse().ok())
-                    .unwrap_or(u64::MAX)
-            })
-        };
-
-        limits.opener_optimization_threshold = {
-            static THRESHOLD: OnceCell<u64> = OnceCell::new();
+        #[cfg(feature = "std")]
+        {
+            limits.sealer_optimization_threshold = {
+                static THRESHOLD: OnceCell<u64> = OnceCell::new();
+
+                *THRESHOLD.get_or_init(|| {
+                    std::env::var("S2N_UNSTABLE_CRYPTO_OPT_TX")
+                        .ok()
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(u64::MAX)
+                })
+            };
+
+            limits.opener_optimization_threshold = {
+                static THRESHOLD: OnceCell<u64> = OnceCell::new();
+
+                *THRESHOLD.get_or_init(|| {
+                    std::env::var("S2N_UNSTABLE_CRYPTO_OPT_RX")
+                        .ok()
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(u64::MAX)
+                })
+            };
+        }
 
-            *THRESHOLD.get_or_init(|| {
-                std::env::var("S2N_UNSTABLE_CRYPTO_OPT_RX")
-                    .ok()
-                    .and_then(|v| v.parse().ok())
-                    .unwrap_or(u64::MAX)
-            })
-        };
+        // the environment can't be read without `std` so the optimizations stay disabled
+        #[cfg(not(feature = "std"))]
+        {
+            limits.sealer_optimization_threshold = u64::MAX;
+            limits.opener_optimization_threshold = u64::MAX;
+        }
 
         limits
     }
@@ -583,6 +813,7 @@ impl<Config: endpoint::Config> transmission::interest::Provider for ApplicationS
         self.recovery_manager.transmission_interest(query)?;
         self.stream_manager.transmission_interest(query)?;
         self.datagram_manager.transmission_interest(query)?;
+        self.extension_frame_manager.transmission_interest(query)?;
         Ok(())
     }
 }
@@ -598,6 +829,7 @@ struct RecoveryContext<'a, Config: endpoint::Config> {
     handshake_status: &'a mut HandshakeStatus,
     ping: &'a mut flag::Ping,
     stream_manager: &'a mut AbstractStreamManager<Config::Stream>,
+    extension_frame_manager: &'a mut extension_frame::Manager<Config>,
     local_id_registry: &'a mut connection::LocalIdRegistry,
     path_id: path::Id,
     path_manager: &'a mut path::Manager<Config>,
@@ -635,7 +867,7 @@ impl<'a, Config: endpoint::Config> recovery::Context<Config> for RecoveryContext
         &mut self,
         timestamp: Timestamp,
         packet_number_range: &PacketNumberRange,
-    ) -> Result<(), transport::Error> {
+    ) -> Result<(), ack::Error> {
         self.tx_packet_numbers
             .on_packet_ack(timestamp, packet_number_range)
     }
@@ -648,7 +880,10 @@ impl<'a, Config: endpoint::Config> recovery::Context<Config> for RecoveryContext
         self.handshake_status
             .on_packet_ack(packet_number_range, publisher);
         self.ping.on_packet_ack(packet_number_range);
-        self.stream_manager.on_packet_ack(packet_number_range);
+        self.stream_manager
+            .on_packet_ack(packet_number_range, publisher);
+        self.extension_frame_manager
+            .on_packet_ack(packet_number_range);
         self.local_id_registry.on_packet_ack(packet_number_range);
         self.path_manager.on_packet_ack(packet_number_range);
     }
@@ -667,7 +902,10 @@ impl<'a, Config: endpoint::Config> recovery::Context<Config> for RecoveryContext
         self.handshake_status
             .on_packet_loss(packet_number_range, publisher);
         self.ping.on_packet_loss(packet_number_range);
-        self.stream_manager.on_packet_loss(packet_number_range);
+        self.stream_manager
+            .on_packet_loss(packet_number_range, publisher);
+        self.extension_frame_manager
+            .on_packet_loss(packet_number_range);
         self.local_id_registry.on_packet_loss(packet_number_range);
         self.path_manager.on_packet_loss(packet_number_range);
     }
@@ -701,7 +939,11 @@ impl<Config: endpoint::Config> PacketSpace<Config> for ApplicationSpace<Config>
         Ok(())
     }
 
-    fn handle_ack_frame<A: AckRanges, Pub: event::ConnectionPublisher>(
+    fn handle_ack_frame<
+        A: AckRanges,
+        Pub: event::ConnectionPublisher,
+        Policy: protocol_violation::Policy,
+    >(
         &mut self,
         frame: Ack<A>,
         timestamp: Timestamp,
@@ -711,8 +953,17 @@ impl<Config: endpoint::Config> PacketSpace<Config> for ApplicationSpace<Config>
         handshake_status: &mut HandshakeStatus,
         local_id_registry: &mut connection::LocalIdRegistry,
         random_generator: &mut Config::RandomGenerator,
+        protocol_violation_policy: &mut Policy,
         publisher: &mut Pub,
     ) -> Result<(), transport::Error> {
+        if !self.tx_packet_numbers.validate_ack_frame(
+            &frame,
+            protocol_violation_policy,
+            publisher,
+        )? {
+            return Ok(());
+        }
+
         let path = &mut path_manager[path_id];
         path.on_peer_validated();
         let (recovery_manager, mut context) =
@@ -725,7 +976,11 @@ impl<Config: endpoint::Config> PacketSpace<Config> for ApplicationSpace<Config>
             random_generator,
             &mut context,
             publisher,
-        )
+        )?;
+
+        self.tx_packet_numbers.on_ack_frame(random_generator);
+
+        Ok(())
     }
 
     fn handle_connection_close_frame(
@@ -737,10 +992,11 @@ impl<Config: endpoint::Config> PacketSpace<Config> for ApplicationSpace<Config>
         Ok(())
     }
 
-    fn handle_stream_frame(
+    fn handle_stream_frame<Pub: event::ConnectionPublisher>(
         &mut self,
         frame: StreamRef,
         packet: &mut ProcessedPacket,
+        publisher: &mut Pub,
     ) -> Result<(), transport::Error> {
         let bytes_progressed = self.stream_manager.incoming_bytes_progressed();
 
@@ -749,6 +1005,21 @@ impl<Config: endpoint::Config> PacketSpace<Config> for ApplicationSpace<Config>
         packet.bytes_progressed +=
             (self.stream_manager.incoming_bytes_progressed() - bytes_progressed).as_u64() as usize;
 
+        // only expose the plaintext to subscribers that explicitly asked for it
+        let data = if publisher.stream_data_access() {
+            Some(frame.data)
+        } else {
+            None
+        };
+
+        publisher.on_stream_data_received(event::builder::StreamDataReceived {
+            stream_id: frame.stream_id.as_u64(),
+            offset: frame.offset.as_u64(),
+            len: frame.data.len(),
+            is_fin: frame.is_fin,
+            data,
+        });
+
         Ok(())
     }
 
@@ -757,6 +1028,14 @@ impl<Config: endpoint::Config> PacketSpace<Config> for ApplicationSpace<Config>
         Ok(())
     }
 
+    fn extension_frame_type(&self, frame_type: VarInt) -> Option<FrameType> {
+        self.extension_frame_manager.frame_type(frame_type)
+    }
+
+    fn handle_extension_frame(&mut self, frame: ExtensionRef) -> Result<(), transport::Error> {
+        self.extension_frame_manager.on_frame(frame)
+    }
+
     fn handle_data_blocked_frame(&mut self, frame: DataBlocked) -> Result<(), transport::Error> {
         self.stream_manager.on_data_blocked(frame)
     }
@@ -809,15 +1088,22 @@ impl<Config: endpoint::Config> PacketSpace<Config> for ApplicationSpace<Config>
                 .with_frame_type(frame.tag().into()));
         }
         // TODO
+        #[cfg(feature = "std")]
         eprintln!("UNIMPLEMENTED APPLICATION FRAME {:?}", frame);
+        #[cfg(not(feature = "std"))]
+        let _ = frame;
         Ok(())
     }
 
-    fn handle_new_connection_id_frame<Pub: event::ConnectionPublisher>(
+    fn handle_new_connection_id_frame<
+        Pub: event::ConnectionPublisher,
+        Policy: protocol_violation::Policy,
+    >(
         &mut self,
         frame: NewConnectionId,
         _datagram: &DatagramInfo,
         path_manager: &mut path::Manager<Config>,
+        protocol_violation_policy: &mut Policy,
         publisher: &mut Pub,
     ) -> Result<(), transport::Error> {
         if path_manager.active_path().peer_connection_id.is_empty() {
@@ -847,16 +1133,22 @@ impl<Config: endpoint::Config> PacketSpace<Config> for ApplicationSpace<Config>
             sequence_number,
             retire_prior_to,
             &stateless_reset_token,
+            protocol_violation_policy,
             publisher,
         )
     }
 
-    fn handle_retire_connection_id_frame(
+    fn handle_retire_connection_id_frame<
+        Pub: event::ConnectionPublisher,
+        Policy: protocol_violation::Policy,
+    >(
         &mut self,
         frame: RetireConnectionId,
         datagram: &DatagramInfo,
         path: &mut Path<Config>,
         local_id_registry: &mut connection::LocalIdRegistry,
+        protocol_violation_policy: &mut Policy,
+        publisher: &mut Pub,
     ) -> Result<(), transport::Error> {
         let sequence_number = frame
             .sequence_number
@@ -869,22 +1161,26 @@ impl<Config: endpoint::Config> PacketSpace<Config> for ApplicationSpace<Config>
         //# NOT refer to the Destination Connection ID field of the packet in
         //# which the frame is contained.
 
-        //= https://www.rfc-editor.org/rfc/rfc9000#section-19.16
-        //# The peer MAY treat this as a
-        //# connection error of type PROTOCOL_VIOLATION.
-
         //= https://www.rfc-editor.org/rfc/rfc9000#section-19.16
         //# Receipt of a RETIRE_CONNECTION_ID frame containing a sequence number
         //# greater than any previously sent to the peer MUST be treated as a
         //# connection error of type PROTOCOL_VIOLATION.
-        local_id_registry
-            .on_retire_connection_id(
-                sequence_number,
-                &datagram.destination_connection_id,
-                path.rtt_estimator.smoothed_rtt(),
-                datagram.timestamp,
-            )
-            .map_err(|err| transport::Error::PROTOCOL_VIOLATION.with_reason(err.message()))
+        match local_id_registry.on_retire_connection_id(
+            sequence_number,
+            &datagram.destination_connection_id,
+            path.rtt_estimator.smoothed_rtt(),
+            datagram.timestamp,
+        ) {
+            Ok(()) => Ok(()),
+            Err(LocalIdRegistrationError::RetireCurrentConnectionId) => {
+                //= https://www.rfc-editor.org/rfc/rfc9000#section-19.16
+                //# The peer MAY treat this as a
+                //# connection error of type PROTOCOL_VIOLATION.
+                let violation = protocol_violation::Violation::RetireCurrentConnectionId;
+                connection::on_protocol_violation(violation, protocol_violation_policy, publisher)
+            }
+            Err(err) => Err(transport::Error::PROTOCOL_VIOLATION.with_reason(err.message())),
+        }
     }
 
     fn handle_path_challenge_frame(
@@ -6,7 +6,9 @@ use crate::{
     sync::data_sender::{self, DataSender, OutgoingDataFlowController},
     transmission,
 };
-use s2n_quic_core::{ack, frame::crypto::CryptoRef, transport, varint::VarInt};
+use s2n_quic_core::{
+    ack, connection::limits::Limits, frame::crypto::CryptoRef, transport, varint::VarInt,
+};
 
 pub type TxCryptoStream = DataSender<CryptoFlowController, data_sender::writer::Crypto>;
 
@@ -34,25 +36,36 @@ pub struct CryptoStream {
     pub tx: TxCryptoStream,
     pub rx: StreamReceiveBuffer,
     is_finished: bool,
+    /// The maximum number of bytes that can be buffered past the consumed offset
+    max_buffer_size: u64,
+    /// The end offset of the received CRYPTO data
+    max_received_offset: u64,
 }
 
 const TX_MAX_BUFFER_CAPACITY: u32 = 4096;
 
 impl Default for CryptoStream {
     fn default() -> Self {
-        Self::new()
+        Self::new(&Limits::default())
     }
 }
 
 impl CryptoStream {
-    pub fn new() -> Self {
+    pub fn new(limits: &Limits) -> Self {
         Self {
             tx: TxCryptoStream::new(Default::default(), TX_MAX_BUFFER_CAPACITY),
             rx: StreamReceiveBuffer::default(),
             is_finished: false,
+            max_buffer_size: limits.max_crypto_buffer_size() as u64,
+            max_received_offset: 0,
         }
     }
 
+    /// Returns the number of bytes used by the buffered CRYPTO data
+    pub fn memory_usage(&self) -> usize {
+        self.tx.memory_usage() + self.rx.memory_usage()
+    }
+
     pub fn can_send(&self) -> bool {
         !self.is_finished && self.tx.available_buffer_space() > 0
     }
@@ -73,7 +86,16 @@ impl CryptoStream {
         }
     }
 
+    /// Returns the number of received bytes past the offset consumed by the TLS provider
+    pub fn rx_buffered_len(&self) -> u64 {
+        self.max_received_offset
+            .saturating_sub(self.rx.consumed_len())
+    }
+
+    /// Buffers the data in a received CRYPTO frame
     pub fn on_crypto_frame(&mut self, frame: CryptoRef) -> Result<(), transport::Error> {
+        let end_offset = frame.offset.as_u64() + frame.data.len() as u64;
+
         //= https://www.rfc-editor.org/rfc/rfc9001#section-4.1.3
         //# *  If the packet is from a previously installed encryption level, it
         //# MUST NOT contain data that extends past the end of previously
@@ -81,34 +103,25 @@ impl CryptoStream {
         //# violations of this requirement as a connection error of type
         //# PROTOCOL_VIOLATION.
 
-        if self.is_finished && frame.offset + frame.data.len() > self.rx.total_received_len() {
+        if self.is_finished && end_offset > self.rx.total_received_len() {
             return Err(transport::Error::PROTOCOL_VIOLATION);
         }
 
         //= https://www.rfc-editor.org/rfc/rfc9000#section-7.5
-        //= type=TODO
-        //= tracking-issue=356
-        //= feature=Crypto buffer limits
-        //# Implementations MUST support buffering at least 4096 bytes of data
-        //# received in out-of-order CRYPTO frames.
-
-        //= https://www.rfc-editor.org/rfc/rfc9000#section-7.5
-        //= type=TODO
-        //= tracking-issue=356
-        //= feature=Crypto buffer limits
-        //# Endpoints MAY choose to
-        //# allow more data to be buffered during the handshake.
-
-        //TODO we need to limit the buffer size here
+        //# If an endpoint does not expand its buffer, it MUST close
+        //# the connection with a CRYPTO_BUFFER_EXCEEDED error code.
+        if end_offset > self.rx.consumed_len() + self.max_buffer_size {
+            return Err(transport::Error::CRYPTO_BUFFER_EXCEEDED
+                .with_reason("crypto frame exceeded the buffer limit"));
+        }
 
         self.rx.write_at(frame.offset, frame.data).map_err(|_| {
-            //= https://www.rfc-editor.org/rfc/rfc9000#section-7.5
-            //# If an endpoint does not expand its buffer, it MUST close
-            //# the connection with a CRYPTO_BUFFER_EXCEEDED error code.
-
+            // the frame extended past the maximum offset of the stream
             transport::Error::CRYPTO_BUFFER_EXCEEDED
         })?;
 
+        self.max_received_offset = self.max_received_offset.max(end_offset);
+
         Ok(())
     }
 
@@ -128,6 +141,28 @@ impl CryptoStream {
     }
 }
 
+/// Ensures the CRYPTO data buffered across the given streams is within the limit for a peer
+/// which hasn't validated its address
+///
+/// The limit is shared by the Initial and Handshake spaces so an unvalidated peer can't
+/// double the amount of memory it's able to hold by spreading the data across spaces.
+pub fn check_unvalidated_buffer_size<'a, I: IntoIterator<Item = &'a CryptoStream>>(
+    streams: I,
+    limits: &Limits,
+) -> Result<(), transport::Error> {
+    let buffered_len: u64 = streams.into_iter().map(CryptoStream::rx_buffered_len).sum();
+
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-7.5
+    //# If an endpoint does not expand its buffer, it MUST close
+    //# the connection with a CRYPTO_BUFFER_EXCEEDED error code.
+    if buffered_len > limits.max_unvalidated_crypto_buffer_size() as u64 {
+        return Err(transport::Error::CRYPTO_BUFFER_EXCEEDED
+            .with_reason("crypto data from an unvalidated peer exceeded the buffer limit"));
+    }
+
+    Ok(())
+}
+
 impl transmission::interest::Provider for CryptoStream {
     #[inline]
     fn transmission_interest<Q: transmission::interest::Query>(
@@ -137,3 +172,77 @@ impl transmission::interest::Provider for CryptoStream {
         self.tx.transmission_interest(query)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(offset: u64, data: &[u8]) -> CryptoRef<'_> {
+        CryptoRef {
+            offset: VarInt::new(offset).unwrap(),
+            data,
+        }
+    }
+
+    #[test]
+    fn buffer_limit_test() {
+        let limits = Limits::default();
+        let max = limits.max_crypto_buffer_size() as u64;
+        let mut stream = CryptoStream::new(&limits);
+
+        // out-of-order data up to the limit is buffered
+        assert!(stream.on_crypto_frame(frame(max - 1, &[1])).is_ok());
+
+        // data past the limit is rejected
+        let error = stream.on_crypto_frame(frame(max, &[1])).unwrap_err();
+        assert_eq!(error.code, transport::Error::CRYPTO_BUFFER_EXCEEDED.code);
+
+        // consuming data allows the peer to send more
+        assert!(stream.on_crypto_frame(frame(0, &[1; 16])).is_ok());
+        assert!(stream.rx.pop().is_some());
+        assert!(stream.on_crypto_frame(frame(max, &[1])).is_ok());
+    }
+
+    #[test]
+    fn unvalidated_buffer_limit_test() {
+        let limits = Limits::default();
+        let max = limits.max_unvalidated_crypto_buffer_size() as u64;
+        let mut initial = CryptoStream::new(&limits);
+        let mut handshake = CryptoStream::new(&limits);
+
+        // out-of-order data in each space is counted up to the end of the received data
+        let len = max / 2;
+        assert!(initial.on_crypto_frame(frame(len - 1, &[1])).is_ok());
+        assert_eq!(initial.rx_buffered_len(), len);
+        assert!(handshake.on_crypto_frame(frame(len - 1, &[1])).is_ok());
+        assert!(check_unvalidated_buffer_size([&initial, &handshake], &limits).is_ok());
+
+        // the limit is shared by both of the spaces
+        assert!(handshake.on_crypto_frame(frame(max - len, &[1])).is_ok());
+        let error = check_unvalidated_buffer_size([&initial, &handshake], &limits).unwrap_err();
+        assert_eq!(error.code, transport::Error::CRYPTO_BUFFER_EXCEEDED.code);
+        assert!(check_unvalidated_buffer_size([&handshake], &limits).is_ok());
+
+        // consumed data no longer counts towards the limit
+        assert!(initial.on_crypto_frame(frame(0, &[1; 16])).is_ok());
+        assert!(initial.rx.pop().is_some());
+        assert_eq!(initial.rx_buffered_len(), len - 16);
+        assert!(check_unvalidated_buffer_size([&initial, &handshake], &limits).is_ok());
+    }
+
+    #[test]
+    fn min_buffer_size_test() {
+        assert!(Limits::default().with_max_crypto_buffer_size(4095).is_err());
+        assert!(Limits::default()
+            .with_max_unvalidated_crypto_buffer_size(4095)
+            .is_err());
+
+        let limits = Limits::default()
+            .with_max_crypto_buffer_size(4096)
+            .unwrap()
+            .with_max_unvalidated_crypto_buffer_size(4096)
+            .unwrap();
+        assert_eq!(limits.max_crypto_buffer_size(), 4096);
+        assert_eq!(limits.max_unvalidated_crypto_buffer_size(), 4096);
+    }
+}
@@ -41,6 +41,11 @@ impl<Config: endpoint::Config> Manager<Config> {
         }
     }
 
+    /// Returns the largest datagram payload the peer accepts
+    pub fn max_datagram_payload(&self) -> u64 {
+        self.max_datagram_payload
+    }
+
     /// A callback that allows users to write datagrams directly to the packet.
     pub fn on_transmit<S: Stream, W: WriteContext>(
         &mut self,
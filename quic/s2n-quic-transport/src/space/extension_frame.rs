@@ -0,0 +1,244 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    endpoint,
+    transmission::{self, WriteContext},
+};
+use alloc::{collections::VecDeque, vec::Vec};
+use s2n_codec::EncoderValue;
+use s2n_quic_core::{
+    connection,
+    extension_frame::{Endpoint, FrameType, Handler, WriteError, Writer},
+    frame::extension::{Extension, ExtensionRef},
+    packet::number::{PacketNumber, PacketNumberRange},
+    transport,
+    varint::VarInt,
+};
+
+/// The extension frame handler type for the endpoint configuration
+pub type HandlerType<Config> =
+    <<Config as endpoint::Config>::ExtensionFrameEndpoint as Endpoint>::Handler;
+
+// Contains the extension frame handler of the connection, if the extension was negotiated.
+//
+// Keeps track of the ack-eliciting frames in flight so lost frames can be retransmitted or
+// reported to the handler.
+pub struct Manager<Config: endpoint::Config> {
+    handler: Option<HandlerType<Config>>,
+    frame_types: Vec<FrameType>,
+    in_flight: VecDeque<SentFrame>,
+    lost: VecDeque<LostFrame>,
+}
+
+struct SentFrame {
+    packet_number: PacketNumber,
+    frame_type: FrameType,
+    /// The payload of the frame, which is only retained if the frame is retransmitted
+    payload: Vec<u8>,
+}
+
+struct LostFrame {
+    frame_type: FrameType,
+    payload: Vec<u8>,
+}
+
+impl<Config: endpoint::Config> Manager<Config> {
+    pub fn new(handler: Option<HandlerType<Config>>) -> Self {
+        let frame_types = handler
+            .as_ref()
+            .map(|handler| handler.frame_types().to_vec())
+            .unwrap_or_default();
+
+        Self {
+            handler,
+            frame_types,
+            in_flight: VecDeque::new(),
+            lost: VecDeque::new(),
+        }
+    }
+
+    /// Returns the registration of the frame type, if the extension was negotiated
+    #[inline]
+    pub fn frame_type(&self, frame_type: VarInt) -> Option<FrameType> {
+        self.frame_types
+            .iter()
+            .find(|registered| registered.id() == frame_type)
+            .copied()
+    }
+
+    /// Passes a received frame to the handler
+    pub fn on_frame(&mut self, frame: ExtensionRef) -> Result<(), transport::Error> {
+        match self.handler.as_mut() {
+            Some(handler) => handler.on_frame(frame.frame_type, frame.payload),
+            None => {
+                debug_assert!(false, "frames are only decoded for registered frame types");
+                Ok(())
+            }
+        }
+    }
+
+    /// Retransmits lost frames and allows the handler to write new frames into the packet
+    pub fn on_transmit<W: WriteContext>(&mut self, context: &mut W) {
+        let handler = if let Some(handler) = self.handler.as_mut() {
+            handler
+        } else {
+            return;
+        };
+
+        let constraint = context.transmission_constraint();
+
+        if constraint.can_retransmit() {
+            while let Some(frame) = self.lost.front() {
+                let packet_number = if let Some(packet_number) =
+                    write_frame(context, frame.frame_type, &frame.payload)
+                {
+                    packet_number
+                } else {
+                    break;
+                };
+
+                let frame = self.lost.pop_front().expect("frame should be present");
+                self.in_flight.push_back(SentFrame {
+                    packet_number,
+                    frame_type: frame.frame_type,
+                    payload: frame.payload,
+                });
+            }
+        }
+
+        if constraint.can_transmit() && handler.has_transmission_interest() {
+            let mut writer = PacketWriter {
+                context,
+                frame_types: &self.frame_types,
+                in_flight: &mut self.in_flight,
+            };
+            handler.on_transmit(&mut writer);
+        }
+    }
+
+    pub fn on_packet_ack(&mut self, packet_number_range: &PacketNumberRange) {
+        self.in_flight
+            .retain(|frame| !packet_number_range.contains(frame.packet_number));
+    }
+
+    pub fn on_packet_loss(&mut self, packet_number_range: &PacketNumberRange) {
+        let mut index = 0;
+        while index < self.in_flight.len() {
+            if !packet_number_range.contains(self.in_flight[index].packet_number) {
+                index += 1;
+                continue;
+            }
+
+            let frame = self
+                .in_flight
+                .remove(index)
+                .expect("index is within bounds");
+
+            if frame.frame_type.is_retransmitted() {
+                self.lost.push_back(LostFrame {
+                    frame_type: frame.frame_type,
+                    payload: frame.payload,
+                });
+            } else if let Some(handler) = self.handler.as_mut() {
+                handler.on_frame_lost(frame.frame_type.id());
+            }
+        }
+    }
+
+    pub fn on_connection_error(&mut self, error: connection::Error) {
+        if let Some(handler) = self.handler.as_mut() {
+            handler.on_connection_error(error);
+        }
+    }
+}
+
+impl<Config: endpoint::Config> transmission::interest::Provider for Manager<Config> {
+    #[inline]
+    fn transmission_interest<Q: transmission::interest::Query>(
+        &self,
+        query: &mut Q,
+    ) -> transmission::interest::Result {
+        if !self.lost.is_empty() {
+            query.on_lost_data()?;
+        }
+
+        if self
+            .handler
+            .as_ref()
+            .map_or(false, |handler| handler.has_transmission_interest())
+        {
+            query.on_new_data()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the frame into the packet and returns the packet number it was written to
+#[inline]
+fn write_frame<W: WriteContext>(
+    context: &mut W,
+    frame_type: FrameType,
+    payload: &[u8],
+) -> Option<PacketNumber> {
+    let frame = Extension {
+        frame_type: frame_type.id(),
+        ack_eliciting: frame_type.is_ack_eliciting(),
+        payload,
+    };
+    context.write_frame(&frame)
+}
+
+struct PacketWriter<'a, C: WriteContext> {
+    context: &'a mut C,
+    frame_types: &'a [FrameType],
+    in_flight: &'a mut VecDeque<SentFrame>,
+}
+
+impl<'a, C: WriteContext> Writer for PacketWriter<'a, C> {
+    fn remaining_capacity(&self) -> usize {
+        let space = self.context.remaining_capacity();
+        let frame_type_len = self
+            .frame_types
+            .iter()
+            .map(|frame_type| frame_type.id().encoding_size())
+            .max()
+            .unwrap_or_default();
+        // Remove the largest frame type length and the maximum length value
+        space.saturating_sub(frame_type_len).saturating_sub(
+            VarInt::new(space as u64)
+                .unwrap_or(VarInt::MAX)
+                .encoding_size(),
+        )
+    }
+
+    fn write_frame(&mut self, frame_type: VarInt, payload: &[u8]) -> Result<(), WriteError> {
+        let frame_type = self
+            .frame_types
+            .iter()
+            .find(|registered| registered.id() == frame_type)
+            .copied()
+            .ok_or(WriteError::UnregisteredFrameType)?;
+
+        let packet_number = write_frame(self.context, frame_type, payload)
+            .ok_or(WriteError::ExceedsPacketCapacity)?;
+
+        // the loss of frames which don't elicit ACKs can't be detected
+        if frame_type.is_ack_eliciting() {
+            let payload = if frame_type.is_retransmitted() {
+                payload.to_vec()
+            } else {
+                Vec::new()
+            };
+
+            self.in_flight.push_back(SentFrame {
+                packet_number,
+                frame_type,
+                payload,
+            });
+        }
+
+        Ok(())
+    }
+}
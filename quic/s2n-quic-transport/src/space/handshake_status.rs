@@ -131,6 +131,17 @@ impl HandshakeStatus {
         }
     }
 
+    /// This method is called when a server connection was transferred from another endpoint
+    pub fn on_transferred(&mut self) {
+        debug_assert!(matches!(self, Self::InProgress));
+
+        // The peer might not have received the HANDSHAKE_DONE frame of the exporting
+        // endpoint, so it's sent again.
+        let mut flag = Flag::default();
+        flag.send();
+        *self = HandshakeStatus::ServerCompleteConfirmed(flag);
+    }
+
     /// Used for tracking when the HANDSHAKE_DONE frame has been delivered
     /// to the peer.
     pub fn on_packet_ack<A: ack::Set, Pub: event::ConnectionPublisher>(
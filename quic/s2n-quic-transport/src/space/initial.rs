@@ -3,7 +3,8 @@
 
 use crate::{
     ack::AckManager,
-    connection::{self, ConnectionTransmissionContext, ProcessingError},
+    arena,
+    connection::{self, protocol_violation, ConnectionTransmissionContext, ProcessingError},
     endpoint, path,
     path::{path_event, Path},
     processed_packet::ProcessedPacket,
@@ -11,10 +12,12 @@ use crate::{
     space::{CryptoStream, HandshakeStatus, PacketSpace, TxPacketNumbers},
     transmission,
 };
+use alloc::vec::Vec;
 use core::{fmt, marker::PhantomData};
 use s2n_codec::EncoderBuffer;
 use s2n_quic_core::{
-    connection::PeerId,
+    ack,
+    connection::{limits::Limits, memory, PeerId},
     crypto::{tls, CryptoSuite, InitialKey},
     event::{self, ConnectionPublisher as _, IntoEvent},
     frame::{ack::AckRanges, crypto::CryptoRef, Ack, ConnectionClose},
@@ -69,17 +72,25 @@ impl<Config: endpoint::Config> InitialSpace<Config> {
         header_key: <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::InitialHeaderKey,
         now: Timestamp,
         ack_manager: AckManager,
+        crypto_stream: CryptoStream,
+        limits: &Limits,
+        random_generator: &mut Config::RandomGenerator,
     ) -> Self {
         Self {
             ack_manager,
             key,
             header_key,
-            crypto_stream: CryptoStream::new(),
-            tx_packet_numbers: TxPacketNumbers::new(PacketNumberSpace::Initial, now),
+            crypto_stream,
+            tx_packet_numbers: TxPacketNumbers::new(
+                PacketNumberSpace::Initial,
+                now,
+                random_generator,
+            ),
             received_hello_message: false,
             retry_token: Vec::new(),
             processed_packet_numbers: SlidingWindow::default(),
-            recovery_manager: recovery::Manager::new(PacketNumberSpace::Initial),
+            recovery_manager: recovery::Manager::new(PacketNumberSpace::Initial)
+                .with_max_pto_probes(limits.max_handshake_probes()),
         }
     }
 
@@ -341,6 +352,19 @@ impl<Config: endpoint::Config> InitialSpace<Config> {
             .on_packet_number_space_discarded(path, path_id, publisher);
     }
 
+    /// Adds the arena usage of the packet number space to `stats`
+    pub fn arena_stats(&self, stats: &mut arena::Stats) {
+        self.recovery_manager.arena_stats(stats);
+    }
+
+    /// Adds the memory used by the packet number space to `usage`
+    pub fn memory_usage(&self, usage: &mut memory::Usage) {
+        self.recovery_manager.memory_usage(usage);
+        usage.crypto += core::mem::size_of_val(&self.key)
+            + core::mem::size_of_val(&self.header_key)
+            + self.crypto_stream.memory_usage();
+    }
+
     pub fn requires_probe(&self) -> bool {
         self.recovery_manager.requires_probe()
     }
@@ -583,7 +607,7 @@ impl<'a, Config: endpoint::Config> recovery::Context<Config> for RecoveryContext
         &mut self,
         timestamp: Timestamp,
         packet_number_range: &PacketNumberRange,
-    ) -> Result<(), transport::Error> {
+    ) -> Result<(), ack::Error> {
         self.tx_packet_numbers
             .on_packet_ack(timestamp, packet_number_range)
     }
@@ -643,7 +667,11 @@ impl<Config: endpoint::Config> PacketSpace<Config> for InitialSpace<Config> {
         Ok(())
     }
 
-    fn handle_ack_frame<A: AckRanges, Pub: event::ConnectionPublisher>(
+    fn handle_ack_frame<
+        A: AckRanges,
+        Pub: event::ConnectionPublisher,
+        Policy: protocol_violation::Policy,
+    >(
         &mut self,
         frame: Ack<A>,
         timestamp: Timestamp,
@@ -653,8 +681,17 @@ impl<Config: endpoint::Config> PacketSpace<Config> for InitialSpace<Config> {
         handshake_status: &mut HandshakeStatus,
         _local_id_registry: &mut connection::LocalIdRegistry,
         random_generator: &mut Config::RandomGenerator,
+        protocol_violation_policy: &mut Policy,
         publisher: &mut Pub,
     ) -> Result<(), transport::Error> {
+        if !self.tx_packet_numbers.validate_ack_frame(
+            &frame,
+            protocol_violation_policy,
+            publisher,
+        )? {
+            return Ok(());
+        }
+
         let (recovery_manager, mut context) =
             self.recovery(handshake_status, path_id, path_manager);
         recovery_manager.on_ack_frame(
@@ -664,7 +701,11 @@ impl<Config: endpoint::Config> PacketSpace<Config> for InitialSpace<Config> {
             random_generator,
             &mut context,
             publisher,
-        )
+        )?;
+
+        self.tx_packet_numbers.on_ack_frame(random_generator);
+
+        Ok(())
     }
 
     fn handle_connection_close_frame(
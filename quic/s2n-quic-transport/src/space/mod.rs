@@ -4,37 +4,51 @@
 use crate::{
     ack,
     ack::AckManager,
-    connection, endpoint, path,
+    arena, connection, endpoint, path,
     path::{path_event, Path},
     processed_packet::ProcessedPacket,
+    recovery,
+    stream::AbstractStreamManager,
     transmission,
 };
+use alloc::{boxed::Box, vec::Vec};
 use bytes::Bytes;
 use core::{
     fmt,
     task::{Poll, Waker},
 };
 use s2n_codec::DecoderBufferMut;
+#[cfg(feature = "state-snapshot")]
+use s2n_quic_core::connection::snapshot;
 use s2n_quic_core::{
     application::ServerName,
-    connection::{limits::Limits, InitialId, PeerId},
+    connection::{limits::Limits, memory, protocol_violation, transfer, InitialId, PeerId},
     crypto::{tls, tls::Session, CryptoSuite, Key},
+    datagram::{ConnectionInfo, Endpoint as _},
     event::{self, IntoEvent},
+    extension_frame::FrameType,
     frame::{
-        ack::AckRanges, crypto::CryptoRef, datagram::DatagramRef, stream::StreamRef, Ack,
-        ConnectionClose, DataBlocked, HandshakeDone, MaxData, MaxStreamData, MaxStreams,
+        ack::AckRanges,
+        crypto::CryptoRef,
+        datagram::DatagramRef,
+        extension::{Extension, ExtensionRef},
+        stream::StreamRef,
+        Ack, ConnectionClose, DataBlocked, HandshakeDone, MaxData, MaxStreamData, MaxStreams,
         NewConnectionId, NewToken, PathChallenge, PathResponse, ResetStream, RetireConnectionId,
         StopSending, StreamDataBlocked, StreamsBlocked,
     },
     inet::DatagramInfo,
     packet::number::{PacketNumber, PacketNumberSpace},
+    stream::scheduler,
     time::{timer, Timestamp},
-    transport,
+    transport::{self, parameters::MaxAckDelay},
+    varint::VarInt,
 };
 
 mod application;
 mod crypto_stream;
 pub(crate) mod datagram;
+pub(crate) mod extension_frame;
 mod handshake;
 mod handshake_status;
 mod initial;
@@ -47,12 +61,15 @@ pub(crate) use crypto_stream::CryptoStream;
 pub(crate) use handshake::HandshakeSpace;
 pub(crate) use handshake_status::HandshakeStatus;
 pub(crate) use initial::InitialSpace;
-pub(crate) use session_context::SessionContext;
+pub(crate) use session_context::{SessionContext, TransportParameterExchange};
 pub(crate) use tx_packet_numbers::TxPacketNumbers;
 
+use keep_alive::KeepAlive;
+
 struct SessionInfo<Config: endpoint::Config> {
     session: <Config::TLSEndpoint as tls::Endpoint>::Session,
     initial_cid: InitialId,
+    transport_parameters: Box<TransportParameterExchange>,
 }
 
 pub struct PacketSpaceManager<Config: endpoint::Config> {
@@ -64,6 +81,10 @@ pub struct PacketSpaceManager<Config: endpoint::Config> {
     zero_rtt_crypto:
         Option<Box<<<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::ZeroRttKey>>,
     handshake_status: HandshakeStatus,
+    /// Set once 0-RTT keys were installed for a resumed session
+    resumed: bool,
+    /// The arena usage of the packet number spaces which have been discarded
+    discarded_arena_stats: arena::Stats,
     /// Server Name Indication
     pub server_name: Option<ServerName>,
     //= https://www.rfc-editor.org/rfc/rfc9000#section-7
@@ -74,6 +95,10 @@ pub struct PacketSpaceManager<Config: endpoint::Config> {
     //# another mechanism is used for agreeing on an application protocol,
     //# endpoints MUST use ALPN for this purpose.
     pub application_protocol: Bytes,
+    /// The DER-encoded certificate chain presented by the peer
+    pub peer_certificates: Vec<Bytes>,
+    /// The application settings the peer sent for the negotiated application protocol
+    pub peer_application_settings: Option<Bytes>,
 }
 
 impl<Config: endpoint::Config> fmt::Debug for PacketSpaceManager<Config> {
@@ -117,6 +142,7 @@ macro_rules! packet_space_api {
                 //# a now discarded packet number space.
                 path.reset_pto_backoff();
                 if let Some(mut space) = self.$field.take() {
+                    space.arena_stats(&mut self.discarded_arena_stats);
                     space.on_discard(path, path_id, publisher);
                 }
 
@@ -136,12 +162,16 @@ macro_rules! packet_space_api {
 }
 
 impl<Config: endpoint::Config> PacketSpaceManager<Config> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new<Pub: event::ConnectionPublisher>(
         initial_cid: InitialId,
         session: <Config::TLSEndpoint as tls::Endpoint>::Session,
         initial_key: <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::InitialKey,
         header_key: <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::InitialHeaderKey,
+        local_transport_parameters: TransportParameterExchange,
         now: Timestamp,
+        limits: &Limits,
+        random_generator: &mut Config::RandomGenerator,
         publisher: &mut Pub,
     ) -> Self {
         let ack_manager = AckManager::new(PacketNumberSpace::Initial, ack::Settings::EARLY);
@@ -154,6 +184,7 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
             session_info: Some(SessionInfo {
                 session,
                 initial_cid,
+                transport_parameters: Box::new(local_transport_parameters),
             }),
             retry_cid: None,
             initial: Some(Box::new(InitialSpace::new(
@@ -161,16 +192,126 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
                 header_key,
                 now,
                 ack_manager,
+                CryptoStream::new(limits),
+                limits,
+                random_generator,
             ))),
             handshake: None,
             application: None,
             zero_rtt_crypto: None,
             handshake_status: HandshakeStatus::default(),
+            resumed: false,
+            discarded_arena_stats: arena::Stats::default(),
             server_name: None,
             application_protocol: Bytes::new(),
+            peer_certificates: Vec::new(),
+            peer_application_settings: None,
+        }
+    }
+
+    /// Creates the space manager of a connection which was transferred from another endpoint
+    ///
+    /// The handshake was completed by the exporting endpoint, so only the application space is
+    /// installed with [`Self::on_transferred`].
+    pub fn new_transferred(server_name: Option<ServerName>, application_protocol: Bytes) -> Self {
+        Self {
+            session_info: None,
+            retry_cid: None,
+            initial: None,
+            handshake: None,
+            application: None,
+            zero_rtt_crypto: None,
+            handshake_status: HandshakeStatus::default(),
+            resumed: false,
+            discarded_arena_stats: arena::Stats::default(),
+            server_name,
+            application_protocol,
+            peer_certificates: Vec::new(),
+            peer_application_settings: None,
         }
     }
 
+    /// Restores the application space of a transferred connection
+    ///
+    /// This mirrors the creation of the application space when the 1-RTT keys become available
+    /// during the handshake, but uses the keys and limits which were negotiated by the
+    /// exporting endpoint.
+    #[allow(clippy::too_many_arguments)]
+    pub fn on_transferred(
+        &mut self,
+        state: &transfer::State,
+        path_manager: &mut path::Manager<Config>,
+        local_id_registry: &mut connection::LocalIdRegistry,
+        limits: &mut Limits,
+        now: Timestamp,
+        random_generator: &mut Config::RandomGenerator,
+        datagram_endpoint: &mut Config::DatagramEndpoint,
+        stream_scheduler: &mut Option<
+            <Config::StreamSchedulerEndpoint as scheduler::Endpoint>::Scheduler,
+        >,
+    ) -> Result<(), transfer::Error> {
+        debug_assert!(self.application.is_none());
+
+        local_id_registry.set_active_connection_id_limit(state.active_connection_id_limit);
+        limits.load_transferred_idle_timeout(state.max_idle_timeout);
+
+        let stream_manager = AbstractStreamManager::new(
+            limits,
+            Config::ENDPOINT_TYPE,
+            state.local_limits,
+            state.peer_limits,
+            Box::new(
+                stream_scheduler
+                    .take()
+                    .expect("the stream manager is only created once"),
+            ),
+        );
+
+        let ack_manager =
+            AckManager::new(PacketNumberSpace::ApplicationData, limits.ack_settings());
+
+        let keep_alive = KeepAlive::new(limits.max_idle_timeout(), limits.max_keep_alive_period());
+
+        let conn_info = ConnectionInfo::new(state.max_datagram_payload);
+        let (datagram_sender, datagram_receiver) = datagram_endpoint.create_connection(&conn_info);
+        let datagram_manager = datagram::Manager::new(
+            datagram_sender,
+            datagram_receiver,
+            state.max_datagram_payload,
+        );
+
+        // extension frames are negotiated per connection and aren't transferred
+        let extension_frame_manager = extension_frame::Manager::new(None);
+
+        let max_ack_delay =
+            MaxAckDelay::try_from(state.max_ack_delay).unwrap_or(MaxAckDelay::RECOMMENDED);
+        path_manager
+            .active_path_mut()
+            .rtt_estimator
+            .on_max_ack_delay(max_ack_delay);
+
+        let recovery_manager = recovery::Manager::new(PacketNumberSpace::ApplicationData)
+            .with_slo_thresholds(limits.slo_thresholds());
+
+        let application = ApplicationSpace::import(
+            state,
+            now,
+            stream_manager,
+            ack_manager,
+            keep_alive,
+            path_manager.max_mtu(),
+            datagram_manager,
+            extension_frame_manager,
+            recovery_manager,
+            random_generator,
+        )?;
+
+        self.application = Some(Box::new(application));
+        self.handshake_status.on_transferred();
+
+        Ok(())
+    }
+
     packet_space_api!(InitialSpace<Config>, initial, initial_mut, discard_initial);
 
     packet_space_api!(
@@ -202,14 +343,30 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
         limits: &mut Limits,
         now: Timestamp,
         waker: &Waker,
+        random_generator: &mut Config::RandomGenerator,
         publisher: &mut Pub,
         datagram: &mut Config::DatagramEndpoint,
+        stream_scheduler: &mut Option<
+            <Config::StreamSchedulerEndpoint as scheduler::Endpoint>::Scheduler,
+        >,
+        extension_frame_handler: &mut Option<extension_frame::HandlerType<Config>>,
     ) -> Poll<Result<(), transport::Error>> {
+        if !path_manager.active_path().is_validated() {
+            // the limit for unvalidated peers is shared by the Initial and Handshake spaces
+            let initial = self.initial.as_ref().map(|space| &space.crypto_stream);
+            let handshake = self.handshake.as_ref().map(|space| &space.crypto_stream);
+            crypto_stream::check_unvalidated_buffer_size(
+                initial.into_iter().chain(handshake),
+                limits,
+            )?;
+        }
+
         if let Some(session_info) = self.session_info.as_mut() {
             let mut context: SessionContext<Config, Pub> = SessionContext {
                 now,
                 initial_cid: &session_info.initial_cid,
                 retry_cid: self.retry_cid.as_deref(),
+                transport_parameters: &mut session_info.transport_parameters,
                 initial: &mut self.initial,
                 handshake: &mut self.handshake,
                 application: &mut self.application,
@@ -220,12 +377,23 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
                 limits,
                 server_name: &mut self.server_name,
                 application_protocol: &mut self.application_protocol,
+                peer_certificates: &mut self.peer_certificates,
+                peer_application_settings: &mut self.peer_application_settings,
                 waker,
+                random_generator,
                 publisher,
                 datagram,
+                stream_scheduler,
+                extension_frame_handler,
             };
 
-            match session_info.session.poll(&mut context)? {
+            let result = session_info.session.poll(&mut context);
+
+            // The 0-RTT keys are discarded after the handshake, so the resumption is recorded as
+            // soon as they are installed
+            self.resumed |= self.zero_rtt_crypto.is_some();
+
+            match result? {
                 Poll::Ready(_success) => {
                     // The TLS session and retry_cid is no longer needed
                     self.session_info = None;
@@ -304,6 +472,105 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
         }
     }
 
+    /// Releases the resources which are retained by an idle connection
+    ///
+    /// This is only done after the handshake is confirmed, since the resources are in use
+    /// until then.
+    pub fn reclaim(&mut self) {
+        if !self.is_handshake_confirmed() {
+            return;
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9001#section-4.9.3
+        //# Servers MAY temporarily retain 0-RTT keys to allow decrypting
+        //# reordered packets without requiring their contents to be
+        //# retransmitted with 1-RTT keys.
+        // The reordered 0-RTT packets have long arrived once the connection is idle
+        self.discard_zero_rtt_crypto();
+
+        if let Some((space, _handshake_status)) = self.application_mut() {
+            space.reclaim();
+        }
+    }
+
+    /// Returns the memory used by all of the packet number spaces
+    pub fn memory_usage(&self) -> memory::Usage {
+        let mut usage = memory::Usage::default();
+
+        if let Some(space) = self.initial.as_ref() {
+            space.memory_usage(&mut usage);
+        }
+        if let Some(space) = self.handshake.as_ref() {
+            space.memory_usage(&mut usage);
+        }
+        if let Some(space) = self.application.as_ref() {
+            space.memory_usage(&mut usage);
+        }
+
+        if let Some(session_info) = self.session_info.as_ref() {
+            usage.crypto += core::mem::size_of_val(session_info)
+                + core::mem::size_of_val(&*session_info.transport_parameters);
+        }
+        if let Some(key) = self.zero_rtt_crypto.as_ref() {
+            usage.crypto += core::mem::size_of_val(&**key);
+        }
+        usage.crypto += self
+            .peer_certificates
+            .iter()
+            .map(|certificate| certificate.len())
+            .sum::<usize>();
+
+        usage
+    }
+
+    /// Returns the packet numbers of the packet number spaces which weren't discarded
+    #[cfg(feature = "state-snapshot")]
+    pub fn snapshot(&self) -> snapshot::Spaces {
+        let snapshot_space =
+            |tx_packet_numbers: &TxPacketNumbers, ack_manager: &AckManager| snapshot::Space {
+                next_packet_number: tx_packet_numbers.next().as_u64(),
+                largest_acked_packet_number: tx_packet_numbers
+                    .largest_sent_packet_number_acked()
+                    .as_u64(),
+                largest_received_packet_number: ack_manager
+                    .largest_received_packet_number()
+                    .map(PacketNumber::as_u64),
+            };
+
+        snapshot::Spaces {
+            initial: self
+                .initial
+                .as_ref()
+                .map(|space| snapshot_space(&space.tx_packet_numbers, &space.ack_manager)),
+            handshake: self
+                .handshake
+                .as_ref()
+                .map(|space| snapshot_space(&space.tx_packet_numbers, &space.ack_manager)),
+            application: self
+                .application
+                .as_ref()
+                .map(|space| snapshot_space(&space.tx_packet_numbers, &space.ack_manager)),
+            handshake_confirmed: self.is_handshake_confirmed(),
+        }
+    }
+
+    /// Returns the combined arena usage of all of the packet number spaces
+    pub fn arena_stats(&self) -> arena::Stats {
+        let mut stats = self.discarded_arena_stats;
+
+        if let Some(space) = self.initial.as_ref() {
+            space.arena_stats(&mut stats);
+        }
+        if let Some(space) = self.handshake.as_ref() {
+            space.arena_stats(&mut stats);
+        }
+        if let Some(space) = self.application.as_ref() {
+            space.arena_stats(&mut stats);
+        }
+
+        stats
+    }
+
     pub fn requires_probe(&self) -> bool {
         core::iter::empty()
             .chain(self.initial.iter().map(|space| space.requires_probe()))
@@ -312,6 +579,29 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
             .any(|requires_probe| requires_probe)
     }
 
+    /// Returns `true` if a packet was sent in the packet number space
+    ///
+    /// Packet number spaces which were discarded always return `false`.
+    pub fn has_sent_packet(&self, space: PacketNumberSpace) -> bool {
+        let tx_packet_numbers = match space {
+            PacketNumberSpace::Initial => {
+                self.initial.as_ref().map(|space| &space.tx_packet_numbers)
+            }
+            PacketNumberSpace::Handshake => self
+                .handshake
+                .as_ref()
+                .map(|space| &space.tx_packet_numbers),
+            PacketNumberSpace::ApplicationData => self
+                .application
+                .as_ref()
+                .map(|space| &space.tx_packet_numbers),
+        };
+
+        tx_packet_numbers.map_or(false, |tx_packet_numbers| {
+            tx_packet_numbers.next().as_u64() > 0
+        })
+    }
+
     pub fn is_handshake_confirmed(&self) -> bool {
         self.handshake_status.is_confirmed()
     }
@@ -320,6 +610,11 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
         self.handshake_status.is_complete()
     }
 
+    /// Returns `true` if the TLS session was resumed with 0-RTT keys
+    pub fn is_resumed(&self) -> bool {
+        self.resumed
+    }
+
     pub(crate) fn on_transmit_close(
         &mut self,
         early_connection_close: &ConnectionClose,
@@ -533,7 +828,11 @@ pub trait PacketSpace<Config: endpoint::Config> {
     ) -> Result<(), transport::Error>;
 
     #[allow(clippy::too_many_arguments)]
-    fn handle_ack_frame<A: AckRanges, Pub: event::ConnectionPublisher>(
+    fn handle_ack_frame<
+        A: AckRanges,
+        Pub: event::ConnectionPublisher,
+        Policy: protocol_violation::Policy,
+    >(
         &mut self,
         frame: Ack<A>,
         timestamp: Timestamp,
@@ -543,6 +842,7 @@ pub trait PacketSpace<Config: endpoint::Config> {
         handshake_status: &mut HandshakeStatus,
         local_id_registry: &mut connection::LocalIdRegistry,
         random_generator: &mut Config::RandomGenerator,
+        protocol_violation_policy: &mut Policy,
         publisher: &mut Pub,
     ) -> Result<(), transport::Error>;
 
@@ -567,23 +867,32 @@ pub trait PacketSpace<Config: endpoint::Config> {
             .with_frame_type(frame.tag().into()))
     }
 
-    fn handle_retire_connection_id_frame(
+    fn handle_retire_connection_id_frame<
+        Pub: event::ConnectionPublisher,
+        Policy: protocol_violation::Policy,
+    >(
         &mut self,
         frame: RetireConnectionId,
         _datagram: &DatagramInfo,
         _path: &mut Path<Config>,
         _local_id_registry: &mut connection::LocalIdRegistry,
+        _protocol_violation_policy: &mut Policy,
+        _publisher: &mut Pub,
     ) -> Result<(), transport::Error> {
         Err(transport::Error::PROTOCOL_VIOLATION
             .with_reason(Self::INVALID_FRAME_ERROR)
             .with_frame_type(frame.tag().into()))
     }
 
-    fn handle_new_connection_id_frame<Pub: event::ConnectionPublisher>(
+    fn handle_new_connection_id_frame<
+        Pub: event::ConnectionPublisher,
+        Policy: protocol_violation::Policy,
+    >(
         &mut self,
         frame: NewConnectionId,
         _datagram: &DatagramInfo,
         _path_manager: &mut path::Manager<Config>,
+        _protocol_violation_policy: &mut Policy,
         _publisher: &mut Pub,
     ) -> Result<(), transport::Error> {
         Err(transport::Error::PROTOCOL_VIOLATION
@@ -613,10 +922,11 @@ pub trait PacketSpace<Config: endpoint::Config> {
             .with_frame_type(frame.tag().into()))
     }
 
-    fn handle_stream_frame(
+    fn handle_stream_frame<Pub: event::ConnectionPublisher>(
         &mut self,
         frame: StreamRef,
         _packet: &mut ProcessedPacket,
+        _publisher: &mut Pub,
     ) -> Result<(), transport::Error> {
         Err(transport::Error::PROTOCOL_VIOLATION
             .with_reason(Self::INVALID_FRAME_ERROR)
@@ -629,6 +939,19 @@ pub trait PacketSpace<Config: endpoint::Config> {
             .with_frame_type(frame.tag().into()))
     }
 
+    /// Returns the registration of the extension frame type, if the extension was negotiated
+    /// and its frames are accepted in this space
+    fn extension_frame_type(&self, frame_type: VarInt) -> Option<FrameType> {
+        let _ = frame_type;
+        None
+    }
+
+    fn handle_extension_frame(&mut self, frame: ExtensionRef) -> Result<(), transport::Error> {
+        Err(transport::Error::PROTOCOL_VIOLATION
+            .with_reason(Self::INVALID_FRAME_ERROR)
+            .with_frame_type(frame.frame_type))
+    }
+
     default_frame_handler!(handle_data_blocked_frame, DataBlocked);
     default_frame_handler!(handle_max_data_frame, MaxData);
     default_frame_handler!(handle_max_stream_data_frame, MaxStreamData);
@@ -649,7 +972,11 @@ pub trait PacketSpace<Config: endpoint::Config> {
 
     // TODO: Reduce arguments, https://github.com/aws/s2n-quic/issues/312
     #[allow(clippy::too_many_arguments)]
-    fn handle_cleartext_payload<'a, Pub: event::ConnectionPublisher>(
+    fn handle_cleartext_payload<
+        'a,
+        Pub: event::ConnectionPublisher,
+        Policy: protocol_violation::Policy,
+    >(
         &mut self,
         packet_number: PacketNumber,
         payload: DecoderBufferMut<'a>,
@@ -661,11 +988,9 @@ pub trait PacketSpace<Config: endpoint::Config> {
         random_generator: &mut Config::RandomGenerator,
         publisher: &mut Pub,
         packet_interceptor: &mut Config::PacketInterceptor,
+        protocol_violation_policy: &mut Policy,
     ) -> Result<ProcessedPacket<'a>, connection::Error> {
-        use s2n_quic_core::{
-            frame::{Frame, FrameMut},
-            varint::VarInt,
-        };
+        use s2n_quic_core::frame::{Frame, FrameMut};
 
         let mut payload = {
             use s2n_quic_core::packet::interceptor::{Interceptor, Packet};
@@ -692,6 +1017,38 @@ pub trait PacketSpace<Config: endpoint::Config> {
         }
 
         while !payload.is_empty() {
+            // extension frames are decoded before the frames of the transport, since their
+            // types are only known to the negotiated extension
+            let extension_frame_type = payload
+                .peek()
+                .decode::<VarInt>()
+                .ok()
+                .and_then(|(frame_type, _)| self.extension_frame_type(frame_type));
+
+            if let Some(frame_type) = extension_frame_type {
+                let (frame, remaining) = Extension::decode(payload, frame_type.is_ack_eliciting())
+                    .map_err(transport::Error::from)?;
+                let frame = ExtensionRef::from(frame);
+
+                let path = &path_manager[path_id];
+                publisher.on_frame_received(event::builder::FrameReceived {
+                    packet_header: event::builder::PacketHeader::new(
+                        packet_number,
+                        publisher.quic_version(),
+                    ),
+                    path: path_event!(path, path_id),
+                    frame: (&frame).into_event(),
+                });
+
+                processed_packet.on_processed_frame(&frame);
+                let frame_type = frame.frame_type;
+                self.handle_extension_frame(frame)
+                    .map_err(|err| err.with_frame_type(frame_type))?;
+
+                payload = remaining;
+                continue;
+            }
+
             let (frame, remaining) = payload
                 .decode::<FrameMut>()
                 .map_err(transport::Error::from)?;
@@ -749,6 +1106,7 @@ pub trait PacketSpace<Config: endpoint::Config> {
                         handshake_status,
                         local_id_registry,
                         random_generator,
+                        protocol_violation_policy,
                         publisher,
                     )
                     .map_err(on_error)?;
@@ -767,7 +1125,7 @@ pub trait PacketSpace<Config: endpoint::Config> {
                 }
                 Frame::Stream(frame) => {
                     let on_error = on_frame_processed!(frame);
-                    self.handle_stream_frame(frame.into(), &mut processed_packet)
+                    self.handle_stream_frame(frame.into(), &mut processed_packet, publisher)
                         .map_err(on_error)?;
                 }
                 Frame::Datagram(frame) => {
@@ -813,8 +1171,14 @@ pub trait PacketSpace<Config: endpoint::Config> {
                 }
                 Frame::NewConnectionId(frame) => {
                     let on_error = on_frame_processed!(frame);
-                    self.handle_new_connection_id_frame(frame, datagram, path_manager, publisher)
-                        .map_err(on_error)?;
+                    self.handle_new_connection_id_frame(
+                        frame,
+                        datagram,
+                        path_manager,
+                        protocol_violation_policy,
+                        publisher,
+                    )
+                    .map_err(on_error)?;
                 }
                 Frame::RetireConnectionId(frame) => {
                     let on_error = on_frame_processed!(frame);
@@ -823,6 +1187,8 @@ pub trait PacketSpace<Config: endpoint::Config> {
                         datagram,
                         &mut path_manager[path_id],
                         local_id_registry,
+                        protocol_violation_policy,
+                        publisher,
                     )
                     .map_err(on_error)?;
                 }
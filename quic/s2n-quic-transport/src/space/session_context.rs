@@ -4,13 +4,14 @@
 use crate::{
     ack::AckManager,
     connection::{self, limits::Limits},
-    endpoint, path,
+    endpoint, path, recovery,
     space::{
-        datagram, keep_alive::KeepAlive, ApplicationSpace, HandshakeSpace, HandshakeStatus,
-        InitialSpace,
+        datagram, extension_frame, keep_alive::KeepAlive, ApplicationSpace, CryptoStream,
+        HandshakeSpace, HandshakeStatus, InitialSpace,
     },
     stream::AbstractStreamManager,
 };
+use alloc::{boxed::Box, vec::Vec};
 use bytes::Bytes;
 use core::{ops::Not, task::Waker};
 use s2n_codec::{DecoderBuffer, DecoderValue};
@@ -24,22 +25,53 @@ use s2n_quic_core::{
     datagram::{ConnectionInfo, Endpoint},
     event,
     event::IntoEvent,
+    extension_frame::Handler as _,
     packet::number::PacketNumberSpace,
+    stream::scheduler,
     time::Timestamp,
     transport::{
         self,
         parameters::{
-            ActiveConnectionIdLimit, ClientTransportParameters, DatagramLimits,
-            InitialFlowControlLimits, InitialSourceConnectionId, MaxAckDelay,
-            ServerTransportParameters,
+            ActiveConnectionIdLimit, ClientTransportParameters, CustomParameters, DatagramLimits,
+            GreaseQuicBit, InitialFlowControlLimits, InitialSourceConnectionId, MaxAckDelay,
+            MigrationSupport, ServerTransportParameters,
         },
     },
 };
 
+/// The transport parameters sent by each endpoint during the handshake
+///
+/// The parameters are retained until the handshake completes so the negotiated values can be
+/// published to the event subscriber.
+#[derive(Debug, Default)]
+pub struct TransportParameterExchange {
+    pub client: Option<ClientTransportParameters>,
+    pub server: Option<ServerTransportParameters>,
+}
+
+impl From<ClientTransportParameters> for TransportParameterExchange {
+    fn from(parameters: ClientTransportParameters) -> Self {
+        Self {
+            client: Some(parameters),
+            server: None,
+        }
+    }
+}
+
+impl From<ServerTransportParameters> for TransportParameterExchange {
+    fn from(parameters: ServerTransportParameters) -> Self {
+        Self {
+            client: None,
+            server: Some(parameters),
+        }
+    }
+}
+
 pub struct SessionContext<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher> {
     pub now: Timestamp,
     pub initial_cid: &'a InitialId,
     pub retry_cid: Option<&'a PeerId>,
+    pub transport_parameters: &'a mut TransportParameterExchange,
     pub path_manager: &'a mut path::Manager<Config>,
     pub initial: &'a mut Option<Box<InitialSpace<Config>>>,
     pub handshake: &'a mut Option<Box<HandshakeSpace<Config>>>,
@@ -52,9 +84,15 @@ pub struct SessionContext<'a, Config: endpoint::Config, Pub: event::ConnectionPu
     pub limits: &'a mut Limits,
     pub server_name: &'a mut Option<ServerName>,
     pub application_protocol: &'a mut Bytes,
+    pub peer_certificates: &'a mut Vec<Bytes>,
+    pub peer_application_settings: &'a mut Option<Bytes>,
     pub waker: &'a Waker,
+    pub random_generator: &'a mut Config::RandomGenerator,
     pub publisher: &'a mut Pub,
     pub datagram: &'a mut Config::DatagramEndpoint,
+    pub stream_scheduler:
+        &'a mut Option<<Config::StreamSchedulerEndpoint as scheduler::Endpoint>::Scheduler>,
+    pub extension_frame_handler: &'a mut Option<extension_frame::HandlerType<Config>>,
 }
 
 impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
@@ -70,6 +108,8 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             ActiveConnectionIdLimit,
             DatagramLimits,
             MaxAckDelay,
+            GreaseQuicBit,
+            CustomParameters,
         ),
         transport::Error,
     > {
@@ -176,18 +216,30 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
                 .register_initial_stateless_reset_token(stateless_reset_token);
         }
 
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-18.2
+        //# The disable active migration
+        //# transport parameter is included if the endpoint does not support
+        //# active connection migration (Section 9) on the address being used
+        //# during the handshake.
+        if let MigrationSupport::Disabled = peer_parameters.migration_support {
+            self.path_manager.on_active_migration_disabled();
+        }
+
         // Load the peer's transport parameters into the connection's limits
         self.limits.load_peer(&peer_parameters);
 
         let initial_flow_control_limits = peer_parameters.flow_control_limits();
         let active_connection_id_limit = peer_parameters.active_connection_id_limit;
         let datagram_limits = peer_parameters.datagram_limits();
+        self.transport_parameters.server = Some(peer_parameters);
 
         Ok((
             initial_flow_control_limits,
             active_connection_id_limit,
             datagram_limits,
             peer_parameters.max_ack_delay,
+            peer_parameters.grease_quic_bit,
+            peer_parameters.custom_parameters,
         ))
     }
 
@@ -201,6 +253,8 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             ActiveConnectionIdLimit,
             DatagramLimits,
             MaxAckDelay,
+            GreaseQuicBit,
+            CustomParameters,
         ),
         transport::Error,
     > {
@@ -240,12 +294,15 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
         let initial_flow_control_limits = peer_parameters.flow_control_limits();
         let active_connection_id_limit = peer_parameters.active_connection_id_limit;
         let datagram_limits = peer_parameters.datagram_limits();
+        self.transport_parameters.client = Some(peer_parameters);
 
         Ok((
             initial_flow_control_limits,
             active_connection_id_limit,
             datagram_limits,
             peer_parameters.max_ack_delay,
+            peer_parameters.grease_quic_bit,
+            peer_parameters.custom_parameters,
         ))
     }
 
@@ -321,6 +378,9 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             header_key,
             self.now,
             ack_manager,
+            CryptoStream::new(self.limits),
+            self.limits,
+            self.random_generator,
         )));
         self.publisher.on_key_update(event::builder::KeyUpdate {
             key_type: event::builder::KeyType::Handshake,
@@ -373,11 +433,17 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
 
         // Parse transport parameters
         let param_decoder = DecoderBuffer::new(application_parameters.transport_parameters);
-        let (peer_flow_control_limits, active_connection_id_limit, datagram_limits, max_ack_delay) =
-            match Config::ENDPOINT_TYPE {
-                endpoint::Type::Client => self.on_server_params(param_decoder)?,
-                endpoint::Type::Server => self.on_client_params(param_decoder)?,
-            };
+        let (
+            peer_flow_control_limits,
+            active_connection_id_limit,
+            datagram_limits,
+            max_ack_delay,
+            grease_quic_bit,
+            peer_custom_parameters,
+        ) = match Config::ENDPOINT_TYPE {
+            endpoint::Type::Client => self.on_server_params(param_decoder)?,
+            endpoint::Type::Server => self.on_client_params(param_decoder)?,
+        };
 
         self.local_id_registry
             .set_active_connection_id_limit(active_connection_id_limit.as_u64());
@@ -387,6 +453,11 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             Config::ENDPOINT_TYPE,
             self.limits.initial_flow_control_limits(),
             peer_flow_control_limits,
+            Box::new(
+                self.stream_scheduler
+                    .take()
+                    .expect("the stream manager is only created once"),
+            ),
         );
 
         let ack_manager = AckManager::new(
@@ -407,11 +478,29 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             datagram_limits.max_datagram_payload,
         );
 
+        // the extension is only enabled if the peer sent the same transport parameter
+        let extension_frame_handler = match self.extension_frame_handler.take() {
+            Some(mut handler) => {
+                let (id, _) = handler.transport_parameter();
+                if let Some(peer_value) = peer_custom_parameters.get(id) {
+                    handler.on_negotiated(peer_value)?;
+                    Some(handler)
+                } else {
+                    None
+                }
+            }
+            None => None,
+        };
+        let extension_frame_manager = extension_frame::Manager::new(extension_frame_handler);
+
         self.path_manager
             .active_path_mut()
             .rtt_estimator
             .on_max_ack_delay(max_ack_delay);
 
+        let recovery_manager = recovery::Manager::new(PacketNumberSpace::ApplicationData)
+            .with_slo_thresholds(self.limits.slo_thresholds());
+
         let cipher_suite = key.cipher_suite().into_event();
         let max_mtu = self.path_manager.max_mtu();
         *self.application = Some(Box::new(ApplicationSpace::new(
@@ -423,6 +512,10 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             keep_alive,
             max_mtu,
             datagram_manager,
+            extension_frame_manager,
+            grease_quic_bit,
+            recovery_manager,
+            self.random_generator,
         )));
         self.publisher.on_key_update(event::builder::KeyUpdate {
             key_type: event::builder::KeyType::OneRtt { generation: 0 },
@@ -456,6 +549,18 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
         Ok(())
     }
 
+    fn on_peer_certificates(&mut self, certificates: Vec<Bytes>) -> Result<(), transport::Error> {
+        *self.peer_certificates = certificates;
+
+        Ok(())
+    }
+
+    fn on_application_settings(&mut self, settings: Bytes) -> Result<(), transport::Error> {
+        *self.peer_application_settings = Some(settings);
+
+        Ok(())
+    }
+
     fn on_handshake_complete(&mut self) -> Result<(), transport::Error> {
         // After the handshake is complete, the handshake crypto stream should be completely
         // finished
@@ -480,6 +585,26 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             return Err(err);
         }
 
+        if let TransportParameterExchange {
+            client: Some(client),
+            server: Some(server),
+        } = &*self.transport_parameters
+        {
+            let (local_transport_parameters, peer_transport_parameters) =
+                if Config::ENDPOINT_TYPE.is_server() {
+                    (server.into_event(), client.into_event())
+                } else {
+                    (client.into_event(), server.into_event())
+                };
+
+            self.publisher.on_transport_parameters_negotiated(
+                event::builder::TransportParametersNegotiated {
+                    local_transport_parameters,
+                    peer_transport_parameters,
+                },
+            );
+        }
+
         self.handshake_status
             .on_handshake_complete(Config::ENDPOINT_TYPE, self.publisher);
 
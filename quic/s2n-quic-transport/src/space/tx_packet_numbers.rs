@@ -1,28 +1,93 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::connection::{self, protocol_violation};
+use core::ops::RangeInclusive;
 use s2n_quic_core::{
-    ack,
+    ack, event, frame,
     packet::number::{PacketNumber, PacketNumberSpace},
+    random,
     time::Timestamp,
     transport,
     varint::VarInt,
 };
 
+/// The range of values from which the first packet number in each space is selected
+///
+/// The range is kept small so the first packets can still be encoded with a short
+/// truncated packet number.
+const INITIAL_PACKET_NUMBER_RANGE: RangeInclusive<usize> = 0..=255;
+
+/// The range of packets sent between each intentionally skipped packet number
+const SKIP_INTERVAL: RangeInclusive<usize> = 64..=512;
+
 /// Context for tracking transmission of packet numbers
 #[derive(Debug)]
 pub struct TxPacketNumbers {
     largest_sent_acked: (PacketNumber, Timestamp),
+    /// The first packet number of the space, below which no packets were sent
+    first: PacketNumber,
     next: PacketNumber,
+    /// The packet number at which the next skip should occur
+    next_skip: Option<PacketNumber>,
+    /// The most recently skipped packet number, which the peer should never acknowledge
+    skipped: Option<PacketNumber>,
 }
 
 impl TxPacketNumbers {
-    pub fn new(packet_space: PacketNumberSpace, now: Timestamp) -> Self {
-        let initial_packet_number = packet_space.new_packet_number(VarInt::from_u8(0));
-        Self {
-            largest_sent_acked: (initial_packet_number, now),
-            next: initial_packet_number,
-        }
+    pub fn new(
+        packet_space: PacketNumberSpace,
+        now: Timestamp,
+        random_generator: &mut dyn random::Generator,
+    ) -> Self {
+        let largest_sent_acked = packet_space.new_packet_number(VarInt::from_u8(0));
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-21.4
+        //# An endpoint MAY skip packet numbers when sending
+        //# packets to detect this behavior.
+
+        // Rather than always starting at 0, a random amount of packet numbers are skipped
+        // at the beginning of each space. This makes the packet numbers harder for an
+        // off-path attacker to predict, and ACKs for any of the skipped packet numbers are
+        // rejected as optimistic.
+        let offset = random::gen_range_biased(random_generator, INITIAL_PACKET_NUMBER_RANGE);
+        let next = packet_space.new_packet_number(VarInt::from_u32(offset as u32));
+
+        let mut tx_packet_numbers = Self {
+            largest_sent_acked: (largest_sent_acked, now),
+            first: next,
+            next,
+            next_skip: None,
+            skipped: None,
+        };
+
+        tx_packet_numbers.schedule_skip(random_generator);
+
+        tx_packet_numbers
+    }
+
+    /// Creates the packet numbers of a transferred connection, which continue at `next`
+    ///
+    /// The largest acknowledged packet number is unknown, so packet numbers are encoded
+    /// with the full length until the peer acknowledges a packet.
+    pub fn import(
+        packet_space: PacketNumberSpace,
+        next: VarInt,
+        now: Timestamp,
+        random_generator: &mut dyn random::Generator,
+    ) -> Self {
+        let mut tx_packet_numbers = Self {
+            largest_sent_acked: (packet_space.new_packet_number(VarInt::from_u8(0)), now),
+            // the packet numbers sent by the previous endpoint are unknown
+            first: packet_space.new_packet_number(VarInt::from_u8(0)),
+            next: packet_space.new_packet_number(next),
+            next_skip: None,
+            skipped: None,
+        };
+
+        tx_packet_numbers.schedule_skip(random_generator);
+
+        tx_packet_numbers
     }
 
     /// This method gets called when a packet delivery got acknowledged
@@ -30,7 +95,7 @@ impl TxPacketNumbers {
         &mut self,
         timestamp: Timestamp,
         ack_set: &A,
-    ) -> Result<(), transport::Error> {
+    ) -> Result<(), ack::Error> {
         let largest = ack_set.largest();
 
         //= https://www.rfc-editor.org/rfc/rfc9000#section-13.1
@@ -38,9 +103,18 @@ impl TxPacketNumbers {
         //# did not send as a connection error of type PROTOCOL_VIOLATION, if it
         //# is able to detect the condition.
 
-        if largest >= self.next {
-            return Err(transport::Error::PROTOCOL_VIOLATION
-                .with_reason("received an ACK for a packet that was not sent"));
+        if largest >= self.next || ack_set.smallest() < self.first {
+            return Err(ack::Error::PacketNotSent);
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-21.4
+        //# An endpoint can then immediately
+        //# close the connection with a connection error of type
+        //# PROTOCOL_VIOLATION; see Section 10.2.
+        if let Some(skipped) = self.skipped {
+            if ack_set.contains(skipped) {
+                return Err(ack::Error::SkippedPacketNumber);
+            }
         }
 
         // record the largest packet acked
@@ -51,6 +125,57 @@ impl TxPacketNumbers {
         Ok(())
     }
 
+    /// Checks that the packet numbers acknowledged by an ACK frame were sent
+    ///
+    /// Returns `false` if the policy ignored the violation, in which case the ACK frame should
+    /// be discarded.
+    pub fn validate_ack_frame<
+        A: frame::ack::AckRanges,
+        Policy: protocol_violation::Policy,
+        Pub: event::ConnectionPublisher,
+    >(
+        &self,
+        frame: &frame::Ack<A>,
+        protocol_violation_policy: &mut Policy,
+        publisher: &mut Pub,
+    ) -> Result<bool, transport::Error> {
+        let space = self.next.space();
+        let largest_acknowledged = space.new_packet_number(frame.largest_acknowledged());
+        let smallest_acknowledged = frame
+            .ack_ranges()
+            .last()
+            .map_or(largest_acknowledged, |range| {
+                space.new_packet_number(*range.start())
+            });
+
+        if largest_acknowledged < self.next && smallest_acknowledged >= self.first {
+            return Ok(true);
+        }
+
+        let violation = protocol_violation::Violation::AckForUnsentPacket;
+        connection::on_protocol_violation(violation, protocol_violation_policy, publisher)?;
+
+        Ok(false)
+    }
+
+    /// Called after an ACK frame has been processed
+    ///
+    /// Once the peer has acknowledged packets past the previously skipped packet number,
+    /// a new packet number is scheduled to be skipped.
+    pub fn on_ack_frame(&mut self, random_generator: &mut dyn random::Generator) {
+        if self.next_skip.is_some() {
+            return;
+        }
+
+        if let Some(skipped) = self.skipped {
+            if self.largest_sent_acked.0 <= skipped {
+                return;
+            }
+        }
+
+        self.schedule_skip(random_generator);
+    }
+
     /// Called after a packet is transmitted with a given packet number
     pub fn on_transmit(&mut self, packet_number: PacketNumber) {
         //= https://www.rfc-editor.org/rfc/rfc9000#section-12.3
@@ -66,6 +191,14 @@ impl TxPacketNumbers {
         // It's probably OK to just panic as there will be other things to worry about at that point.
 
         self.next = packet_number.next().expect("packet number overflowed");
+
+        if let Some(next_skip) = self.next_skip {
+            if self.next >= next_skip {
+                self.next_skip = None;
+                self.skipped = Some(self.next);
+                self.next = self.next.next().expect("packet number overflowed");
+            }
+        }
     }
 
     /// Returns the next packet number in the sequence
@@ -78,4 +211,213 @@ impl TxPacketNumbers {
     pub fn largest_sent_packet_number_acked(&self) -> PacketNumber {
         self.largest_sent_acked.0
     }
+
+    fn schedule_skip(&mut self, random_generator: &mut dyn random::Generator) {
+        let interval = random::gen_range_biased(random_generator, SKIP_INTERVAL);
+        self.next_skip = self
+            .next
+            .as_u64()
+            .checked_add(interval as u64)
+            .and_then(|value| VarInt::new(value).ok())
+            .map(|value| self.next.space().new_packet_number(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_quic_platform::time;
+
+    fn transmit_until_skip(tx_packet_numbers: &mut TxPacketNumbers) -> PacketNumber {
+        for _ in 0..=*SKIP_INTERVAL.end() {
+            let packet_number = tx_packet_numbers.next();
+            tx_packet_numbers.on_transmit(packet_number);
+
+            if tx_packet_numbers.next() != packet_number.next().unwrap() {
+                return packet_number.next().unwrap();
+            }
+        }
+
+        panic!("packet number was never skipped");
+    }
+
+    #[test]
+    fn initial_packet_number_test() {
+        let mut random = random::testing::Generator::default();
+
+        for _ in 0..100 {
+            let tx_packet_numbers =
+                TxPacketNumbers::new(PacketNumberSpace::ApplicationData, time::now(), &mut random);
+
+            let offset = tx_packet_numbers.next().as_u64() as usize;
+            assert!(INITIAL_PACKET_NUMBER_RANGE.contains(&offset));
+            assert_eq!(
+                tx_packet_numbers
+                    .largest_sent_packet_number_acked()
+                    .as_u64(),
+                0
+            );
+        }
+    }
+
+    #[test]
+    fn skipped_packet_number_ack_test() {
+        let mut random = random::testing::Generator::default();
+        let now = time::now();
+        let mut tx_packet_numbers =
+            TxPacketNumbers::new(PacketNumberSpace::ApplicationData, now, &mut random);
+
+        let skipped = transmit_until_skip(&mut tx_packet_numbers);
+
+        // acknowledging packets around the skipped packet number is allowed
+        let prev = skipped.prev().unwrap();
+        assert!(tx_packet_numbers.on_packet_ack(now, &prev).is_ok());
+
+        // transmit one more packet so the skipped packet number is in the sent range
+        let next = tx_packet_numbers.next();
+        tx_packet_numbers.on_transmit(next);
+        assert!(tx_packet_numbers.on_packet_ack(now, &next).is_ok());
+
+        // acknowledging the skipped packet should fail
+        assert_eq!(
+            tx_packet_numbers.on_packet_ack(now, &skipped),
+            Err(ack::Error::SkippedPacketNumber)
+        );
+        assert_eq!(
+            tx_packet_numbers.on_packet_ack(now, &(prev..=next)),
+            Err(ack::Error::SkippedPacketNumber)
+        );
+    }
+
+    #[test]
+    fn skip_rescheduling_test() {
+        let mut random = random::testing::Generator::default();
+        let now = time::now();
+        let mut tx_packet_numbers =
+            TxPacketNumbers::new(PacketNumberSpace::ApplicationData, now, &mut random);
+
+        let skipped = transmit_until_skip(&mut tx_packet_numbers);
+        assert!(tx_packet_numbers.next_skip.is_none());
+
+        // the skip is not rescheduled until the peer ACKs past the skipped packet number
+        tx_packet_numbers.on_ack_frame(&mut random);
+        assert!(tx_packet_numbers.next_skip.is_none());
+
+        let next = tx_packet_numbers.next();
+        tx_packet_numbers.on_transmit(next);
+        tx_packet_numbers.on_packet_ack(now, &next).unwrap();
+        tx_packet_numbers.on_ack_frame(&mut random);
+        assert!(tx_packet_numbers.next_skip.is_some());
+
+        let next_skipped = transmit_until_skip(&mut tx_packet_numbers);
+        assert!(next_skipped > skipped);
+    }
+
+    #[test]
+    fn unsent_packet_ack_test() {
+        let mut random = random::testing::Generator::default();
+        let now = time::now();
+        let mut tx_packet_numbers =
+            TxPacketNumbers::new(PacketNumberSpace::ApplicationData, now, &mut random);
+
+        let next = tx_packet_numbers.next();
+        assert_eq!(
+            tx_packet_numbers.on_packet_ack(now, &next),
+            Err(ack::Error::PacketNotSent)
+        );
+    }
+
+    #[test]
+    fn unsent_initial_offset_ack_test() {
+        let mut random = random::testing::Generator::default();
+        let now = time::now();
+
+        // find a space which doesn't start at 0
+        let mut tx_packet_numbers = loop {
+            let tx_packet_numbers =
+                TxPacketNumbers::new(PacketNumberSpace::ApplicationData, now, &mut random);
+            if tx_packet_numbers.next().as_u64() > 0 {
+                break tx_packet_numbers;
+            }
+        };
+
+        let first = tx_packet_numbers.next();
+        tx_packet_numbers.on_transmit(first);
+        let below = first.prev().unwrap();
+
+        // the packet numbers below the initial offset were never sent
+        assert_eq!(
+            tx_packet_numbers.on_packet_ack(now, &below),
+            Err(ack::Error::PacketNotSent)
+        );
+        assert_eq!(
+            tx_packet_numbers.on_packet_ack(now, &(below..=first)),
+            Err(ack::Error::PacketNotSent)
+        );
+        assert!(tx_packet_numbers.on_packet_ack(now, &first).is_ok());
+    }
+
+    #[test]
+    fn unsent_ack_frame_test() {
+        use crate::ack::ack_ranges::AckRanges;
+        use s2n_quic_core::connection::protocol_violation::Outcome;
+
+        let mut random = random::testing::Generator::default();
+        let mut publisher = event::testing::Publisher::no_snapshot();
+        let now = time::now();
+
+        // find a space which doesn't start at 0
+        let mut tx_packet_numbers = loop {
+            let tx_packet_numbers =
+                TxPacketNumbers::new(PacketNumberSpace::ApplicationData, now, &mut random);
+            if tx_packet_numbers.next().as_u64() > 0 {
+                break tx_packet_numbers;
+            }
+        };
+
+        let sent = tx_packet_numbers.next();
+        tx_packet_numbers.on_transmit(sent);
+        let unsent = tx_packet_numbers.next();
+        let below = sent.prev().unwrap();
+
+        let frame = |packet_number: PacketNumber| {
+            let mut ack_ranges = AckRanges::default();
+            ack_ranges.insert_packet_number(packet_number).unwrap();
+            ack_ranges
+        };
+
+        let sent = frame(sent);
+        let sent = frame::Ack {
+            ack_delay: VarInt::from_u8(0),
+            ack_ranges: &sent,
+            ecn_counts: None,
+        };
+
+        for mut policy in [Outcome::Close, Outcome::Ignore] {
+            assert_eq!(
+                tx_packet_numbers.validate_ack_frame(&sent, &mut policy, &mut publisher),
+                Ok(true)
+            );
+        }
+
+        for unsent in [unsent, below] {
+            let unsent = frame(unsent);
+            let unsent = frame::Ack {
+                ack_delay: VarInt::from_u8(0),
+                ack_ranges: &unsent,
+                ecn_counts: None,
+            };
+
+            assert_eq!(
+                tx_packet_numbers.validate_ack_frame(&unsent, &mut Outcome::Close, &mut publisher),
+                Err(ack::Error::PacketNotSent.into())
+            );
+
+            // ignoring the violation discards the ACK frame
+            assert_eq!(
+                tx_packet_numbers.validate_ack_frame(&unsent, &mut Outcome::Ignore, &mut publisher),
+                Ok(false)
+            );
+        }
+    }
 }
@@ -144,6 +144,36 @@ macro_rules! tx_stream_apis {
             Ok(response.tx().expect("invalid response").chunks.consumed).into()
         }
 
+        /// Enqueues a slice of chunks of data for sending it towards the peer and marks the
+        /// stream as finished once all of the chunks have been enqueued.
+        ///
+        /// Since the data and the FIN are part of the same request, the final chunk and the
+        /// FIN bit can be transmitted in the same `STREAM` frame.
+        ///
+        /// The method will return:
+        /// - `Poll::Ready(Ok(count))` if part of the slice was enqueued for sending. Any of the
+        ///   consumed `Bytes` will be replaced with an empty `Bytes`, in order to reduce needless
+        ///   ref count increases. The stream is only finished if `count` equals the total number
+        ///   of chunks. Otherwise the stream will store the waker and wake the task once more
+        ///   capacity is available.
+        /// - `Poll::Ready(Err(stream_error))` if the data could not be sent, because the stream
+        ///   had previously entered an error state.
+        /// - `Poll::Pending` if the send buffer capacity is currently exhausted. In this case, the
+        ///   caller should retry sending after the `Waker` on the provided `Context` is notified.
+        pub fn poll_send_vectored_and_finish(
+            &mut self,
+            chunks: &mut [Bytes],
+            cx: &mut Context,
+        ) -> Poll<Result<usize, StreamError>> {
+            let response = self.tx_request()?.send(chunks).finish().poll(Some(cx))?;
+
+            if !chunks.is_empty() && response.chunks.consumed == 0 {
+                return Poll::Pending;
+            }
+
+            Ok(response.tx().expect("invalid response").chunks.consumed).into()
+        }
+
         /// Polls send readiness for the given stream.
         ///
         /// The method will return:
@@ -217,6 +247,26 @@ macro_rules! tx_stream_apis {
             self.tx_request()?.finish().flush().poll(Some(cx))?.into()
         }
 
+        /// Resets the stream with the `error_code` once any of its data is lost, instead of
+        /// retransmitting the lost data.
+        pub fn reset_on_loss(&mut self, error_code: application::Error) -> Result<(), StreamError> {
+            self.tx_request()?.reset_on_loss(error_code).poll(None)?;
+            Ok(())
+        }
+
+        /// Notifies the `delivery` once the peer has acknowledged all of the data which is currently
+        /// enqueued on the stream.
+        ///
+        /// The method will return:
+        /// - `Ok(())` if the notification was registered. If the stream is reset before all of the
+        ///   data is acknowledged, the `delivery` is notified with the error.
+        /// - `Err(stream_error)` if the notification could not be registered, because the stream
+        ///   had previously entered an error state.
+        pub fn notify_delivery(&mut self, delivery: ops::tx::Delivery) -> Result<(), StreamError> {
+            self.tx_request()?.notify_delivery(delivery).poll(None)?;
+            Ok(())
+        }
+
         /// Initiates a `RESET` on the stream.
         ///
         /// This will close the stream and notify the peer of the provided `error_code`.
@@ -288,6 +338,55 @@ macro_rules! rx_stream_apis {
             Poll::Ready(Ok((consumed, is_open)))
         }
 
+        /// Polls if the stream has data available for receiving.
+        ///
+        /// Polling readiness doesn't consume any data, which allows the caller to stop polling at
+        /// any time without losing data.
+        ///
+        /// The method will return:
+        /// - `Poll::Ready(Ok(available_bytes))` if the stream has data available, where
+        ///   `available_bytes` is how many bytes can currently be received. If the stream was
+        ///   finished and all of the data was consumed, `0` is returned.
+        /// - `Poll::Ready(Err(stream_error))` if the stream could not be read, because the stream
+        ///   had previously entered an error state.
+        /// - `Poll::Pending` if the stream is waiting to receive data from the peer. In this case, the
+        ///   caller should retry receiving after the `Waker` on the provided `Context` is notified.
+        pub fn poll_receive_ready(&mut self, cx: &mut Context) -> Poll<Result<usize, StreamError>> {
+            let response = self.rx_request()?.with_low_watermark(1).poll(Some(cx))?;
+
+            // the end of the stream is ready, even without any data
+            if response.status.is_finished() {
+                return Ok(0).into();
+            }
+
+            let response = ready!(response.into_poll());
+            Ok(response.rx().expect("invalid response").bytes.available).into()
+        }
+
+        /// Receives a chunk of data from the stream without waiting for the peer.
+        ///
+        /// This method should only be called after calling `poll_receive_ready` first, as the
+        /// stream may not have any data available.
+        ///
+        /// The method will return:
+        /// - `Ok(Some(chunk))` if data was available
+        /// - `Ok(None)` if the stream was finished and all of the data was consumed
+        /// - `Err(stream_error)` if the stream could not be read, because the stream
+        ///   had previously entered an error state, or the stream did not have any data available.
+        pub fn receive_data(&mut self) -> Result<Option<Bytes>, StreamError> {
+            let mut chunk = Bytes::new();
+            let response = self
+                .rx_request()?
+                .receive(core::slice::from_mut(&mut chunk))
+                .poll(None)?;
+
+            match response.rx().expect("invalid response") {
+                rx if rx.chunks.consumed > 0 => Ok(Some(chunk)),
+                rx if rx.status.is_finished() => Ok(None),
+                _ => Err(StreamError::receiving_blocked()),
+            }
+        }
+
         /// Sends a `STOP_SENDING` message to the peer. This requests the peer to
         /// finish the `Stream` as soon as possible by issuing a `RESET` with the
         /// provided `error_code`.
@@ -494,6 +593,16 @@ macro_rules! tx_request_apis {
             self.request.flush();
             self
         }
+
+        pub fn reset_on_loss(&mut self, error_code: application::Error) -> &mut Self {
+            self.request.reset_on_loss(error_code);
+            self
+        }
+
+        pub fn notify_delivery(&mut self, delivery: ops::tx::Delivery) -> &mut Self {
+            self.request.notify_delivery(delivery);
+            self
+        }
     };
 }
 
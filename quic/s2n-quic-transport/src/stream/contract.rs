@@ -1,6 +1,7 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
+use alloc::vec::Vec;
 use bytes::Bytes;
 use core::task::Context;
 use s2n_quic_core::{
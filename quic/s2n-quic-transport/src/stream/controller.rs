@@ -17,7 +17,9 @@ use core::{
 };
 use futures_core::ready;
 use s2n_quic_core::{
-    ack, endpoint,
+    ack,
+    connection::transfer,
+    endpoint,
     frame::MaxStreams,
     stream::{self, iter::StreamIter, StreamId, StreamType},
     time::{timer, Timestamp},
@@ -201,6 +203,44 @@ impl Controller {
         }
     }
 
+    /// Returns the number of streams currently open, regardless of which side opened them
+    pub fn open_stream_count(&self) -> u64 {
+        (self.local_bidi_controller.open_stream_count()
+            + self.remote_bidi_controller.open_stream_count()
+            + self.local_uni_controller.open_stream_count()
+            + self.remote_uni_controller.open_stream_count())
+        .as_u64()
+    }
+
+    /// Returns the number of streams which were opened by each endpoint and the stream limits
+    /// of the peer, for transferring the connection to another endpoint
+    pub fn export_transfer(&self, streams: &mut transfer::Streams) {
+        streams.local_bidirectional_opened = self.local_bidi_controller.total_open_stream_count();
+        streams.local_unidirectional_opened = self.local_uni_controller.total_open_stream_count();
+        streams.remote_bidirectional_opened = self.remote_bidi_controller.total_open_stream_count();
+        streams.remote_unidirectional_opened = self.remote_uni_controller.total_open_stream_count();
+        streams.peer_max_bidirectional_streams =
+            self.local_bidi_controller.peer_cumulative_stream_limit();
+        streams.peer_max_unidirectional_streams =
+            self.local_uni_controller.peer_cumulative_stream_limit();
+    }
+
+    /// Restores the stream counts of a transferred connection
+    pub fn on_transferred(&mut self, streams: &transfer::Streams) {
+        self.local_bidi_controller.on_transferred(
+            streams.local_bidirectional_opened,
+            streams.peer_max_bidirectional_streams,
+        );
+        self.local_uni_controller.on_transferred(
+            streams.local_unidirectional_opened,
+            streams.peer_max_unidirectional_streams,
+        );
+        self.remote_bidi_controller
+            .on_transferred(streams.remote_bidirectional_opened);
+        self.remote_uni_controller
+            .on_transferred(streams.remote_unidirectional_opened);
+    }
+
     /// This method is called when the stream manager is closed. All wakers will be woken
     /// to unblock waiting tasks.
     pub fn close(&mut self) {
@@ -197,6 +197,21 @@ impl<L: LocalLimits, OpenNotify: OpenNotifyBehavior> LocalInitiated<L, OpenNotif
         self.opened_streams
     }
 
+    /// Returns the cumulative stream limit of the peer
+    #[inline]
+    pub fn peer_cumulative_stream_limit(&self) -> VarInt {
+        self.peer_cumulative_stream_limit
+    }
+
+    /// Restores the state of a transferred connection, which has no open streams
+    pub fn on_transferred(&mut self, opened_streams: VarInt, peer_cumulative_stream_limit: VarInt) {
+        self.opened_streams = opened_streams;
+        self.closed_streams = opened_streams;
+        self.peer_cumulative_stream_limit = peer_cumulative_stream_limit.max(opened_streams);
+
+        self.check_integrity();
+    }
+
     #[inline]
     pub fn on_timeout(&mut self, now: Timestamp) {
         self.streams_blocked_sync.on_timeout(now);
@@ -115,6 +115,27 @@ impl RemoteInitiated {
         self.opened_streams
     }
 
+    /// Restores the state of a transferred connection, which has no open streams
+    ///
+    /// The updated limit is always advertised again, since the importing endpoint doesn't
+    /// know which `MAX_STREAMS` frames the peer received.
+    pub fn on_transferred(&mut self, opened_streams: VarInt) {
+        self.opened_streams = opened_streams;
+        self.closed_streams = opened_streams;
+
+        let max_streams = opened_streams
+            .saturating_add(self.max_local_limit)
+            .min(MAX_STREAMS_MAX_VALUE);
+        // the initial limit is the only value known to be acknowledged by the peer
+        self.max_streams_sync = IncrementalValueSync::new(
+            max_streams,
+            self.max_local_limit.min(max_streams),
+            self.max_local_limit / MAX_STREAMS_SYNC_FRACTION,
+        );
+
+        self.check_integrity();
+    }
+
     #[inline]
     pub fn on_packet_ack<A: ack::Set>(&mut self, ack_set: &A) {
         self.max_streams_sync.on_packet_ack(ack_set)
@@ -0,0 +1,344 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Stream groups allow applications to multiplex several logical sessions on one connection
+//!
+//! Streams which joined a group share a send window in addition to the connection flow control
+//! window, which limits the amount of data the local endpoint sends on all of the group's streams.
+//! The application grants additional window to the group, in the same way a peer grants
+//! additional connection window with `MAX_DATA` frames. A group can also be reset, which resets
+//! all of its streams with the same error code. Each group is scheduled as its own class, with
+//! a weight that is derived from the urgency of the group.
+
+use alloc::{collections::BTreeMap, rc::Rc, vec::Vec};
+use core::cell::RefCell;
+use s2n_quic_core::{
+    application,
+    stream::{scheduler, StreamId},
+    varint::VarInt,
+};
+
+/// Identifies a group of streams on a connection
+///
+/// Identifiers are only unique to the connection which created the group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Id(u64);
+
+impl Id {
+    /// Returns the value of the identifier
+    #[inline]
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+#[derive(Debug)]
+struct WindowState {
+    /// The total amount of data the streams of the group are allowed to send
+    max_data: VarInt,
+    /// The amount of window which was handed out to the streams of the group
+    acquired: VarInt,
+}
+
+/// The send window which is shared by all streams of a group
+#[derive(Clone, Debug)]
+pub struct Window {
+    state: Rc<RefCell<WindowState>>,
+}
+
+impl Window {
+    fn new(max_data: VarInt) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(WindowState {
+                max_data,
+                acquired: VarInt::from_u32(0),
+            })),
+        }
+    }
+
+    /// Returns the window which can still be handed out to the streams of the group
+    pub fn available_window(&self) -> VarInt {
+        let state = self.state.borrow();
+        state.max_data - state.acquired
+    }
+
+    /// Returns the window which was handed out to the streams of the group
+    pub fn acquired_window(&self) -> VarInt {
+        self.state.borrow().acquired
+    }
+
+    /// Records that a stream acquired `window` bytes of the group's window
+    ///
+    /// The window must not exceed the available window.
+    pub fn on_acquired(&self, window: VarInt) {
+        let mut state = self.state.borrow_mut();
+        debug_assert!(state.acquired + window <= state.max_data);
+        state.acquired += window;
+    }
+
+    /// Increases the total window of the group
+    ///
+    /// Returns `true` if the window was increased. Decreasing the window is not supported,
+    /// since it may already have been handed out to streams.
+    fn set_max_data(&self, max_data: VarInt) -> bool {
+        let mut state = self.state.borrow_mut();
+        if max_data <= state.max_data {
+            return false;
+        }
+        state.max_data = max_data;
+        true
+    }
+}
+
+#[derive(Debug)]
+enum Group {
+    Open {
+        window: Window,
+        streams: Vec<StreamId>,
+        urgency: u8,
+    },
+    /// The group was reset by the application
+    ///
+    /// Streams which join the group afterwards are reset with the same error.
+    Reset(application::Error),
+}
+
+/// The result of joining a group
+#[derive(Debug)]
+pub enum Membership {
+    /// The stream joined the group and shares its window
+    Joined(Window),
+    /// The group was reset and the stream needs to be reset as well
+    Reset(application::Error),
+}
+
+/// Tracks all groups of a connection
+#[derive(Debug, Default)]
+pub struct Groups {
+    next_id: u64,
+    groups: BTreeMap<Id, Group>,
+    /// The group of each stream which is a member of an open group
+    members: BTreeMap<StreamId, Id>,
+}
+
+impl Groups {
+    /// Creates a new group with an initial send window of `max_data` bytes
+    pub fn create(&mut self, max_data: VarInt) -> Id {
+        let id = Id(self.next_id);
+        self.next_id += 1;
+
+        self.groups.insert(
+            id,
+            Group::Open {
+                window: Window::new(max_data),
+                streams: Vec::new(),
+                urgency: scheduler::DEFAULT_URGENCY,
+            },
+        );
+
+        id
+    }
+
+    /// Adds the stream to the group
+    ///
+    /// A stream is a member of at most one group, so it is removed from any group it joined
+    /// before. `is_open` is used to forget about streams which were finalized in the meantime.
+    ///
+    /// Returns `None` if the group doesn't exist.
+    pub fn join<F: Fn(StreamId) -> bool>(
+        &mut self,
+        id: Id,
+        stream_id: StreamId,
+        is_open: F,
+    ) -> Option<Membership> {
+        if !self.groups.contains_key(&id) {
+            return None;
+        }
+
+        for group in self.groups.values_mut() {
+            if let Group::Open { streams, .. } = group {
+                streams.retain(|member| *member != stream_id && is_open(*member));
+            }
+        }
+        self.members
+            .retain(|member, _| *member != stream_id && is_open(*member));
+
+        match self.groups.get_mut(&id)? {
+            Group::Open {
+                window, streams, ..
+            } => {
+                streams.push(stream_id);
+                self.members.insert(stream_id, id);
+                Some(Membership::Joined(window.clone()))
+            }
+            Group::Reset(error) => Some(Membership::Reset(*error)),
+        }
+    }
+
+    /// Returns `true` if no groups were created
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Returns the open group the stream is a member of
+    pub fn group_of(&self, stream_id: StreamId) -> Option<Id> {
+        self.members.get(&stream_id).copied()
+    }
+
+    /// Returns the scheduling class of the given group, or of streams which didn't join a group
+    pub fn class(&self, id: Option<Id>) -> scheduler::Class {
+        let urgency = match id.and_then(|id| self.groups.get(&id)) {
+            Some(Group::Open { urgency, .. }) => *urgency,
+            _ => scheduler::DEFAULT_URGENCY,
+        };
+        scheduler::Class::new(urgency, id.map(Id::as_u64))
+    }
+
+    /// Sets the urgency of the group, which is used to derive its scheduling weight
+    ///
+    /// Returns `false` if the group doesn't exist or was reset.
+    pub fn set_urgency(&mut self, id: Id, urgency: u8) -> bool {
+        match self.groups.get_mut(&id) {
+            Some(Group::Open {
+                urgency: current, ..
+            }) => {
+                *current = urgency.min(scheduler::MAX_URGENCY);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Increases the send window of the group
+    ///
+    /// Returns `true` if the window was increased.
+    pub fn set_max_data(&mut self, id: Id, max_data: VarInt) -> bool {
+        match self.groups.get(&id) {
+            Some(Group::Open { window, .. }) => window.set_max_data(max_data),
+            _ => false,
+        }
+    }
+
+    /// Resets the group and returns the streams which need to be reset
+    pub fn reset(&mut self, id: Id, error: application::Error) -> Vec<StreamId> {
+        let group = match self.groups.get_mut(&id) {
+            Some(group) => group,
+            None => return Vec::new(),
+        };
+
+        match core::mem::replace(group, Group::Reset(error)) {
+            Group::Open { streams, .. } => {
+                self.members.retain(|_, member| *member != id);
+                streams
+            }
+            Group::Reset(previous) => {
+                // keep the error of the first reset
+                *group = Group::Reset(previous);
+                Vec::new()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream_id(id: u32) -> StreamId {
+        StreamId::from_varint(VarInt::from_u32(id))
+    }
+
+    #[test]
+    fn window_test() {
+        let mut groups = Groups::default();
+        let id = groups.create(VarInt::from_u32(100));
+
+        let window = match groups.join(id, stream_id(0), |_| true) {
+            Some(Membership::Joined(window)) => window,
+            other => panic!("unexpected membership {:?}", other),
+        };
+
+        window.on_acquired(VarInt::from_u32(60));
+        assert_eq!(window.available_window(), VarInt::from_u32(40));
+        assert_eq!(window.acquired_window(), VarInt::from_u32(60));
+
+        // the window is shared by all streams of the group
+        let other = match groups.join(id, stream_id(4), |_| true) {
+            Some(Membership::Joined(window)) => window,
+            other => panic!("unexpected membership {:?}", other),
+        };
+        assert_eq!(other.available_window(), VarInt::from_u32(40));
+
+        // the window can only be increased
+        assert!(!groups.set_max_data(id, VarInt::from_u32(50)));
+        assert!(groups.set_max_data(id, VarInt::from_u32(200)));
+        assert_eq!(window.available_window(), VarInt::from_u32(140));
+    }
+
+    #[test]
+    fn class_test() {
+        let mut groups = Groups::default();
+        assert!(groups.is_empty());
+        let id = groups.create(VarInt::from_u32(100));
+        assert!(!groups.is_empty());
+
+        groups.join(id, stream_id(0), |_| true).unwrap();
+        assert_eq!(groups.group_of(stream_id(0)), Some(id));
+        assert_eq!(groups.group_of(stream_id(4)), None);
+
+        let class = groups.class(Some(id));
+        assert_eq!(class.urgency, scheduler::DEFAULT_URGENCY);
+        assert_eq!(class.group, Some(id.as_u64()));
+
+        // urgencies are capped to the least important urgency
+        assert!(groups.set_urgency(id, u8::MAX));
+        assert_eq!(groups.class(Some(id)).urgency, scheduler::MAX_URGENCY);
+
+        // streams which didn't join a group use the default urgency
+        let class = groups.class(None);
+        assert_eq!(class.urgency, scheduler::DEFAULT_URGENCY);
+        assert_eq!(class.group, None);
+
+        assert!(!groups.set_urgency(Id(100), 0));
+    }
+
+    #[test]
+    fn reset_test() {
+        let mut groups = Groups::default();
+        let first = groups.create(VarInt::from_u32(100));
+        let second = groups.create(VarInt::from_u32(100));
+        assert_ne!(first, second);
+
+        for id in [0, 4, 8] {
+            groups.join(first, stream_id(id), |_| true).unwrap();
+        }
+
+        // joining another group moves the stream
+        groups.join(second, stream_id(4), |_| true).unwrap();
+        // finalized streams are forgotten
+        groups
+            .join(second, stream_id(12), |id| id != stream_id(8))
+            .unwrap();
+
+        let error = application::Error::new(7).unwrap();
+        assert_eq!(groups.reset(first, error), [stream_id(0)]);
+        assert!(groups.reset(first, application::Error::UNKNOWN).is_empty());
+
+        // streams which join after the reset are reset as well
+        assert!(matches!(
+            groups.join(first, stream_id(16), |_| true),
+            Some(Membership::Reset(e)) if e == error
+        ));
+        assert!(!groups.set_max_data(first, VarInt::from_u32(1000)));
+
+        assert_eq!(groups.reset(second, error), [stream_id(4), stream_id(12)]);
+
+        // reset groups no longer have members
+        assert_eq!(groups.group_of(stream_id(0)), None);
+
+        // unknown groups are ignored
+        let unknown = Id(100);
+        assert!(groups.join(unknown, stream_id(0), |_| true).is_none());
+        assert!(groups.reset(unknown, error).is_empty());
+    }
+}
@@ -11,7 +11,14 @@ use crate::{
 use alloc::rc::Rc;
 use core::cell::RefCell;
 use s2n_quic_core::{
-    ack, frame::max_data::MaxData, packet::number::PacketNumber, stream::StreamId, transport,
+    ack,
+    frame::max_data::MaxData,
+    packet::number::PacketNumber,
+    stream::{
+        limits::{ReassemblyLimit, ReassemblyUsage},
+        StreamId,
+    },
+    transport,
     varint::VarInt,
 };
 
@@ -46,10 +53,18 @@ struct IncomingConnectionFlowControllerImpl {
     /// The amount of flow control credits which had been acquired and where the
     /// data had already been consumed by the application
     pub(super) consumed_window: VarInt,
+    /// Limits the out-of-order data buffered across all Streams
+    reassembly_limit: ReassemblyLimit,
+    /// The out-of-order data buffered across all Streams
+    reassembly_usage: ReassemblyUsage,
 }
 
 impl IncomingConnectionFlowControllerImpl {
-    pub fn new(initial_window_size: VarInt, desired_flow_control_window: u32) -> Self {
+    pub fn new(
+        initial_window_size: VarInt,
+        desired_flow_control_window: u32,
+        reassembly_limit: ReassemblyLimit,
+    ) -> Self {
         Self {
             read_window_sync: IncrementalValueSync::new(
                 VarInt::from_u32(desired_flow_control_window),
@@ -59,6 +74,8 @@ impl IncomingConnectionFlowControllerImpl {
             desired_flow_control_window,
             acquired_window: VarInt::from_u32(0),
             consumed_window: VarInt::from_u32(0),
+            reassembly_limit,
+            reassembly_usage: ReassemblyUsage::default(),
         }
     }
 
@@ -71,6 +88,22 @@ impl IncomingConnectionFlowControllerImpl {
         self.read_window_sync.latest_value()
     }
 
+    pub fn on_transferred(&mut self, acquired_window: VarInt, consumed_window: VarInt) {
+        let initial_window = self.read_window_sync.latest_value();
+        let latest_value = consumed_window
+            .saturating_add(VarInt::from_u32(self.desired_flow_control_window))
+            .max(initial_window);
+
+        self.acquired_window = acquired_window;
+        self.consumed_window = consumed_window;
+        // the initial window is the only value known to be acknowledged by the peer
+        self.read_window_sync = IncrementalValueSync::new(
+            latest_value,
+            initial_window,
+            VarInt::from_u32(self.desired_flow_control_window / 10),
+        );
+    }
+
     pub fn release_window(&mut self, amount: VarInt) {
         self.consumed_window += amount;
         debug_assert!(
@@ -97,6 +130,24 @@ impl IncomingConnectionFlowControllerImpl {
         Ok(())
     }
 
+    pub fn on_reassembly_usage(
+        &mut self,
+        previous: ReassemblyUsage,
+        current: ReassemblyUsage,
+    ) -> Result<(), transport::Error> {
+        self.reassembly_usage = self
+            .reassembly_usage
+            .saturating_sub(previous)
+            .saturating_add(current);
+
+        if self.reassembly_limit.is_exceeded(self.reassembly_usage) {
+            return Err(transport::Error::PROTOCOL_VIOLATION
+                .with_reason("connection reassembly limit exceeded"));
+        }
+
+        Ok(())
+    }
+
     pub fn on_packet_ack<A: ack::Set>(&mut self, ack_set: &A) {
         self.read_window_sync.on_packet_ack(ack_set)
     }
@@ -134,11 +185,19 @@ impl IncomingConnectionFlowController {
     /// `desired_flow_control_window`. This means if the window which is indicated
     /// to the peer is lower than this value the new value will be communicated
     /// to the peer.
-    pub fn new(initial_window_size: VarInt, desired_flow_control_window: u32) -> Self {
+    ///
+    /// The out-of-order data buffered across all Streams is limited to
+    /// `reassembly_limit`.
+    pub fn new(
+        initial_window_size: VarInt,
+        desired_flow_control_window: u32,
+        reassembly_limit: ReassemblyLimit,
+    ) -> Self {
         Self {
             inner: Rc::new(RefCell::new(IncomingConnectionFlowControllerImpl::new(
                 initial_window_size,
                 desired_flow_control_window,
+                reassembly_limit,
             ))),
         }
     }
@@ -157,6 +216,25 @@ impl IncomingConnectionFlowController {
         self.inner.borrow_mut().release_window(amount)
     }
 
+    /// Returns `true` if the out-of-order data across all Streams is limited
+    pub fn is_reassembly_limited(&self) -> bool {
+        self.inner.borrow().reassembly_limit.is_limited()
+    }
+
+    /// Replaces the `previous` out-of-order data usage of a Stream with the
+    /// `current` usage.
+    ///
+    /// Returns an error if the usage across all Streams exceeds the limit.
+    pub fn on_reassembly_usage(
+        &mut self,
+        previous: ReassemblyUsage,
+        current: ReassemblyUsage,
+    ) -> Result<(), transport::Error> {
+        self.inner
+            .borrow_mut()
+            .on_reassembly_usage(previous, current)
+    }
+
     /// This method gets called when a packet delivery got acknowledged
     pub fn on_packet_ack<A: ack::Set>(&mut self, ack_set: &A) {
         self.inner.borrow_mut().on_packet_ack(ack_set)
@@ -177,6 +255,18 @@ impl IncomingConnectionFlowController {
         self.inner.borrow().acquired_window
     }
 
+    /// Returns the amount of acquired window which was consumed by the application
+    pub fn consumed_window(&self) -> VarInt {
+        self.inner.borrow().consumed_window
+    }
+
+    /// Restores the flow control offsets of a transferred connection
+    pub fn on_transferred(&mut self, acquired_window: VarInt, consumed_window: VarInt) {
+        self.inner
+            .borrow_mut()
+            .on_transferred(acquired_window, consumed_window)
+    }
+
     #[cfg(test)]
     pub fn remaining_window(&self) -> VarInt {
         self.inner.borrow_mut().remaining_window()
@@ -4,13 +4,14 @@
 //! `StreamManager` manages the lifecycle of all `Stream`s inside a `Connection`
 
 use crate::{
-    connection,
+    arena, connection,
     contexts::{ConnectionApiCallContext, OnTransmitError, WriteContext},
     recovery::RttEstimator,
     stream::{
-        self,
+        self, group,
         incoming_connection_flow_controller::IncomingConnectionFlowController,
         outgoing_connection_flow_controller::OutgoingConnectionFlowController,
+        scheduler::Scheduler,
         stream_container::{StreamContainer, StreamContainerIterationResult},
         stream_events::StreamEvents,
         stream_impl::StreamConfig,
@@ -18,19 +19,24 @@ use crate::{
     },
     transmission::{self, interest::Provider as _},
 };
+use alloc::{boxed::Box, vec::Vec};
 use core::{
     task::{Context, Poll, Waker},
     time::Duration,
 };
 use futures_core::ready;
+#[cfg(feature = "state-snapshot")]
+use s2n_quic_core::connection::snapshot;
 use s2n_quic_core::{
-    ack, endpoint,
+    ack, application,
+    connection::{memory, transfer},
+    endpoint, event,
     frame::{
         stream::StreamRef, DataBlocked, MaxData, MaxStreamData, MaxStreams, ResetStream,
         StopSending, StreamDataBlocked, StreamsBlocked,
     },
     packet::number::PacketNumberSpace,
-    stream::{iter::StreamIter, ops, StreamId, StreamType},
+    stream::{iter::StreamIter, limits::ReassemblyLimit, ops, scheduler, StreamId, StreamType},
     time::{timer, Timestamp},
     transport::{self, parameters::InitialFlowControlLimits},
     varint::VarInt,
@@ -163,6 +169,8 @@ pub struct StreamManagerState<S> {
     pub(super) incoming_connection_flow_controller: IncomingConnectionFlowController,
     /// Flow control credit manager for sending data
     pub(super) outgoing_connection_flow_controller: OutgoingConnectionFlowController,
+    /// The arena which the buffers for received stream data are allocated from
+    frame_arena: arena::Frames,
     /// Controller for managing streams concurrency limits
     stream_controller: stream::Controller,
     /// A container which contains all Streams
@@ -186,6 +194,12 @@ pub struct StreamManagerState<S> {
     /// Limits for the Stream manager. Since only Stream limits are utilized at
     /// the moment we only store those
     stream_limits: stream::Limits,
+    /// Limits the out-of-order data which is buffered by each Stream
+    stream_reassembly_limit: ReassemblyLimit,
+    /// The stream groups which were created by the application
+    groups: group::Groups,
+    /// Shares the transmission capacity between the stream groups
+    scheduler: Scheduler,
 }
 
 impl<S: StreamTrait> StreamManagerState<S> {
@@ -239,6 +253,8 @@ impl<S: StreamTrait> StreamManagerState<S> {
             desired_flow_control_window: initial_receive_window.as_u64() as u32,
             initial_send_window,
             max_send_buffer_size: self.stream_limits.max_send_buffer_size.as_u32(),
+            frame_arena: self.frame_arena.clone(),
+            reassembly_limit: self.stream_reassembly_limit,
         }));
     }
 
@@ -403,6 +419,85 @@ impl<S: StreamTrait> StreamManagerState<S> {
             Poll::Ready(())
         }
     }
+
+    /// Transmits new data on the streams, weighted by the class of each stream
+    ///
+    /// Streams of classes which ran out of credit are skipped and stay in the transmission
+    /// list in their current order. The list is iterated again as long as streams make
+    /// progress, and the skipped classes are replenished once none of the classes could
+    /// transmit.
+    fn on_scheduled_transmit<W: WriteContext>(
+        &mut self,
+        context: &mut W,
+    ) -> Result<(), OnTransmitError> {
+        let groups = &self.groups;
+        let scheduler = &mut self.scheduler;
+        let mut transmit_result = Ok(());
+
+        loop {
+            let mut is_skipped = false;
+            let mut has_transmitted = false;
+            // the classes which are held by a non-incremental stream in this pass
+            let mut claimed = Vec::new();
+
+            self.streams.iterate_transmission_list(
+                &mut self.stream_controller,
+                |stream: &mut S| {
+                    let class = groups.group_of(stream.stream_id());
+                    if claimed.contains(&class) {
+                        return StreamContainerIterationResult::Continue;
+                    }
+
+                    // non-incremental streams keep their position until they are done, which
+                    // holds back the streams of their class which were queued later
+                    let is_incremental = scheduler.is_incremental(stream.stream_id());
+                    let result = if is_incremental {
+                        StreamContainerIterationResult::Continue
+                    } else {
+                        StreamContainerIterationResult::ContinueAndInsertAtFront
+                    };
+
+                    if !scheduler.on_stream(class) {
+                        is_skipped = true;
+                        if !is_incremental {
+                            claimed.push(class);
+                        }
+                        return result;
+                    }
+
+                    let capacity = context.remaining_capacity();
+                    transmit_result = stream.on_transmit(context);
+                    let len = capacity.saturating_sub(context.remaining_capacity());
+                    scheduler.on_transmit(class, len);
+                    has_transmitted |= len > 0;
+
+                    if transmit_result.is_err() {
+                        return StreamContainerIterationResult::BreakAndInsertAtBack;
+                    }
+
+                    if !is_incremental
+                        && matches!(
+                            stream.get_stream_interests().transmission,
+                            transmission::Interest::NewData
+                        )
+                    {
+                        claimed.push(class);
+                    }
+
+                    result
+                },
+            );
+
+            if transmit_result.is_err() || !is_skipped {
+                return transmit_result;
+            }
+
+            // the classes with remaining credit get another chance before the round ends
+            if !has_transmitted && !scheduler.replenish(groups) {
+                return transmit_result;
+            }
+        }
+    }
 }
 
 /// Manages all active `Stream`s inside a connection.
@@ -424,6 +519,7 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
         local_endpoint_type: endpoint::Type,
         initial_local_limits: InitialFlowControlLimits,
         initial_peer_limits: InitialFlowControlLimits,
+        scheduler: Box<dyn scheduler::Scheduler>,
     ) -> Self {
         debug_assert!(
             initial_local_limits.max_data <= VarInt::from_u32(core::u32::MAX),
@@ -435,10 +531,12 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
                 incoming_connection_flow_controller: IncomingConnectionFlowController::new(
                     initial_local_limits.max_data,
                     initial_local_limits.max_data.as_u64() as u32,
+                    connection_limits.connection_reassembly_limit(),
                 ),
                 outgoing_connection_flow_controller: OutgoingConnectionFlowController::new(
                     initial_peer_limits.max_data,
                 ),
+                frame_arena: arena::Frames::default(),
                 stream_controller: stream::Controller::new(
                     local_endpoint_type,
                     initial_peer_limits,
@@ -453,10 +551,137 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
                 close_reason: None,
                 accept_state: AcceptState::new(local_endpoint_type),
                 stream_limits: connection_limits.stream_limits(),
+                stream_reassembly_limit: connection_limits.stream_reassembly_limit(),
+                groups: group::Groups::default(),
+                scheduler: Scheduler::new(scheduler),
             },
         }
     }
 
+    /// Adds the usage of the arenas for received frame data and stream state to `stats`
+    pub fn arena_stats(&self, stats: &mut arena::Stats) {
+        stats.frames += self.inner.frame_arena.usage();
+        stats.streams += self.inner.streams.arena_usage();
+    }
+
+    /// Adds the memory used by the buffers of all of the streams to `usage`
+    pub fn memory_usage(&self, usage: &mut memory::Usage) {
+        self.inner
+            .streams
+            .for_each(|stream| stream.memory_usage(usage));
+    }
+
+    /// Returns the connection flow control offsets
+    #[cfg(feature = "state-snapshot")]
+    pub fn flow_control_snapshot(&self) -> snapshot::FlowControl {
+        let incoming = &self.inner.incoming_connection_flow_controller;
+        let outgoing = &self.inner.outgoing_connection_flow_controller;
+
+        snapshot::FlowControl {
+            received_data: incoming.acquired_window().as_u64(),
+            consumed_data: incoming.consumed_window().as_u64(),
+            sent_data: outgoing.acquired_window().as_u64(),
+            peer_max_data: outgoing.total_window().as_u64(),
+        }
+    }
+
+    /// Returns the states of all of the streams, ordered by their stream ID
+    #[cfg(feature = "state-snapshot")]
+    pub fn stream_snapshots(&self) -> Vec<snapshot::Stream> {
+        let mut streams = Vec::new();
+        self.inner
+            .streams
+            .for_each(|stream| streams.push(stream.snapshot()));
+        streams
+    }
+
+    /// Releases the memory which the streams retain for reuse
+    ///
+    /// This is called once the connection was idle for a while, so the memory can be used
+    /// by other connections.
+    pub fn reclaim(&mut self) {
+        self.inner.frame_arena.reclaim();
+        self.inner.streams.reclaim_arena();
+        self.inner
+            .streams
+            .iterate_streams(&mut self.inner.stream_controller, |stream| stream.reclaim());
+    }
+
+    /// Returns the number of streams currently open, regardless of which side opened them
+    pub fn open_stream_count(&self) -> u64 {
+        self.inner.stream_controller.open_stream_count()
+    }
+
+    /// Exports the stream counts and flow control offsets for transferring the connection to
+    /// another endpoint
+    ///
+    /// Connections with open streams can't be transferred.
+    pub fn export_transfer(&self) -> Result<transfer::Streams, transfer::Error> {
+        if self.open_stream_count() > 0 {
+            return Err(transfer::Error::StreamsOpen);
+        }
+
+        let mut streams = transfer::Streams::default();
+        self.inner.stream_controller.export_transfer(&mut streams);
+
+        let incoming = &self.inner.incoming_connection_flow_controller;
+        streams.received_data = incoming.acquired_window();
+        streams.consumed_data = incoming.consumed_window();
+
+        let outgoing = &self.inner.outgoing_connection_flow_controller;
+        streams.peer_max_data = outgoing.total_window();
+        streams.sent_data = outgoing.acquired_window();
+
+        Ok(streams)
+    }
+
+    /// Returns the initial flow control limits which were received from the peer
+    pub fn initial_peer_limits(&self) -> InitialFlowControlLimits {
+        self.inner.initial_peer_limits
+    }
+
+    /// Restores the stream counts and flow control offsets of a transferred connection
+    pub fn on_transferred(&mut self, streams: &transfer::Streams) {
+        let local_endpoint_type = self.inner.local_endpoint_type;
+        let peer_endpoint_type = local_endpoint_type.peer_type();
+
+        self.inner.stream_controller.on_transferred(streams);
+
+        for (stream_type, local_opened, remote_opened) in [
+            (
+                StreamType::Bidirectional,
+                streams.local_bidirectional_opened,
+                streams.remote_bidirectional_opened,
+            ),
+            (
+                StreamType::Unidirectional,
+                streams.local_unidirectional_opened,
+                streams.remote_unidirectional_opened,
+            ),
+        ] {
+            *self
+                .inner
+                .next_stream_ids
+                .get_mut(local_endpoint_type, stream_type) =
+                StreamId::nth(local_endpoint_type, stream_type, local_opened.as_u64());
+
+            let next_remote_stream_id =
+                StreamId::nth(peer_endpoint_type, stream_type, remote_opened.as_u64());
+            *self
+                .inner
+                .next_stream_ids
+                .get_mut(peer_endpoint_type, stream_type) = next_remote_stream_id;
+            *self.inner.accept_state.next_stream_mut(stream_type) = next_remote_stream_id;
+        }
+
+        self.inner
+            .incoming_connection_flow_controller
+            .on_transferred(streams.received_data, streams.consumed_data);
+        self.inner
+            .outgoing_connection_flow_controller
+            .on_transferred(streams.peer_max_data, streams.sent_data);
+    }
+
     /// The number of bytes of forward progress the peer has made on incoming streams
     pub fn incoming_bytes_progressed(&self) -> VarInt {
         self.inner
@@ -581,7 +806,11 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
     }
 
     /// This method gets called when a packet delivery got acknowledged
-    pub fn on_packet_ack<A: ack::Set>(&mut self, ack_set: &A) {
+    pub fn on_packet_ack<A: ack::Set, Pub: event::ConnectionPublisher>(
+        &mut self,
+        ack_set: &A,
+        publisher: &mut Pub,
+    ) {
         self.inner
             .incoming_connection_flow_controller
             .on_packet_ack(ack_set);
@@ -596,14 +825,18 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
                 // We have to wake inside the lock, since `StreamEvent`s has no capacity
                 // to carry wakers in another iteration
                 let mut events = StreamEvents::new();
-                stream.on_packet_ack(ack_set, &mut events);
+                stream.on_packet_ack(ack_set, &mut events, publisher);
                 events.wake_all();
             },
         );
     }
 
     /// This method gets called when a packet loss is reported
-    pub fn on_packet_loss<A: ack::Set>(&mut self, ack_set: &A) {
+    pub fn on_packet_loss<A: ack::Set, Pub: event::ConnectionPublisher>(
+        &mut self,
+        ack_set: &A,
+        publisher: &mut Pub,
+    ) {
         self.inner
             .incoming_connection_flow_controller
             .on_packet_loss(ack_set);
@@ -618,7 +851,7 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
                 // We have to wake inside the lock, since `StreamEvent`s has no capacity
                 // to carry wakers in another iteration
                 let mut events = StreamEvents::new();
-                stream.on_packet_loss(ack_set, &mut events);
+                stream.on_packet_loss(ack_set, &mut events, publisher);
                 events.wake_all();
             },
         );
@@ -722,17 +955,21 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
         }
 
         if context.transmission_constraint().can_transmit() {
-            self.inner.streams.iterate_transmission_list(
-                &mut self.inner.stream_controller,
-                |stream: &mut S| {
-                    transmit_result = stream.on_transmit(context);
-                    if transmit_result.is_err() {
-                        StreamContainerIterationResult::BreakAndInsertAtBack
-                    } else {
-                        StreamContainerIterationResult::Continue
-                    }
-                },
-            );
+            if self.inner.groups.is_empty() && !self.inner.scheduler.has_sequential_streams() {
+                self.inner.streams.iterate_transmission_list(
+                    &mut self.inner.stream_controller,
+                    |stream: &mut S| {
+                        transmit_result = stream.on_transmit(context);
+                        if transmit_result.is_err() {
+                            StreamContainerIterationResult::BreakAndInsertAtBack
+                        } else {
+                            StreamContainerIterationResult::Continue
+                        }
+                    },
+                );
+            } else {
+                transmit_result = self.inner.on_scheduled_transmit(context);
+            }
         }
 
         // There is no `finalize_done_streams` here, since we do not expect to
@@ -859,13 +1096,20 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
             .outgoing_connection_flow_controller
             .on_max_data(frame);
 
+        self.on_connection_window_available();
+
+        Ok(())
+    }
+
+    /// Allows streams which are blocked on the connection window to acquire window
+    fn on_connection_window_available(&mut self) {
         if self
             .inner
             .outgoing_connection_flow_controller
             .available_window()
             == VarInt::from_u32(0)
         {
-            return Ok(());
+            return;
         }
 
         // Iterate over streams and allow them to grab credits from the
@@ -885,8 +1129,6 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
                 }
             },
         );
-
-        Ok(())
     }
 
     /// This is called when a `STREAMS_BLOCKED` frame had been received
@@ -923,16 +1165,31 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
     ) -> R
     where
         F: FnOnce(&mut S) -> R,
+    {
+        self.perform_transmitting_call(api_call_context, |manager| {
+            manager
+                .inner
+                .streams
+                .with_stream(stream_id, &mut manager.inner.stream_controller, |stream| {
+                    func(stream)
+                })
+                .unwrap_or(unknown_stream_result)
+        })
+    }
+
+    /// Executes an application API call and notifies the QUIC connection thread if the call
+    /// requires transmission of data
+    fn perform_transmitting_call<F, R>(
+        &mut self,
+        api_call_context: &mut ConnectionApiCallContext,
+        func: F,
+    ) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
     {
         let had_transmission_interest = self.inner.streams.has_transmission_interest();
 
-        let result = self
-            .inner
-            .streams
-            .with_stream(stream_id, &mut self.inner.stream_controller, |stream| {
-                func(stream)
-            })
-            .unwrap_or(unknown_stream_result);
+        let result = func(self);
 
         // A wakeup is only triggered if the the transmission list is
         // now empty, but was previously not. The edge triggered behavior
@@ -964,6 +1221,119 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
         )
     }
 
+    /// Creates a new stream group with a send window of `max_data` bytes
+    pub fn create_group(&mut self, max_data: VarInt) -> Result<group::Id, connection::Error> {
+        if let Some(error) = self.inner.close_reason {
+            return Err(error);
+        }
+
+        Ok(self.inner.groups.create(max_data))
+    }
+
+    /// Adds the stream to the group
+    ///
+    /// If the group was already reset, the stream is reset with the same error.
+    pub fn join_group(
+        &mut self,
+        group_id: group::Id,
+        stream_id: StreamId,
+        api_call_context: &mut ConnectionApiCallContext,
+    ) -> Result<(), StreamError> {
+        let state = &mut self.inner;
+        if !state.streams.contains(stream_id) {
+            return Err(StreamError::invalid_stream());
+        }
+
+        let streams = &state.streams;
+        let membership = state
+            .groups
+            .join(group_id, stream_id, |id| streams.contains(id))
+            .ok_or_else(StreamError::invalid_stream)?;
+
+        self.perform_api_call(
+            stream_id,
+            Err(StreamError::invalid_stream()),
+            api_call_context,
+            |stream| match membership {
+                group::Membership::Joined(window) => {
+                    stream.join_group(window);
+                    Ok(())
+                }
+                group::Membership::Reset(error) => {
+                    let mut events = StreamEvents::new();
+                    stream.on_group_reset(error, &mut events);
+                    events.wake_all();
+                    Err(StreamError::stream_reset(error))
+                }
+            },
+        )
+    }
+
+    /// Increases the send window of the group to `max_data` bytes
+    pub fn set_group_max_data(
+        &mut self,
+        group_id: group::Id,
+        max_data: VarInt,
+        api_call_context: &mut ConnectionApiCallContext,
+    ) {
+        if !self.inner.groups.set_max_data(group_id, max_data) {
+            return;
+        }
+
+        // Streams of the group might be blocked on the window of the group
+        self.perform_transmitting_call(api_call_context, |manager| {
+            manager.on_connection_window_available()
+        });
+    }
+
+    /// Sets the urgency of the group, which determines its share of the transmission capacity
+    pub fn set_group_urgency(&mut self, group_id: group::Id, urgency: u8) {
+        self.inner.groups.set_urgency(group_id, urgency);
+    }
+
+    /// Sets whether the stream shares the capacity of its class in round robin order with the
+    /// other streams of the class, or is sent sequentially
+    pub fn set_incremental(
+        &mut self,
+        stream_id: StreamId,
+        incremental: bool,
+    ) -> Result<(), StreamError> {
+        let state = &mut self.inner;
+        if !state.streams.contains(stream_id) {
+            return Err(StreamError::invalid_stream());
+        }
+
+        let streams = &state.streams;
+        state
+            .scheduler
+            .set_incremental(stream_id, incremental, |id| streams.contains(id));
+        Ok(())
+    }
+
+    /// Resets all streams of the group with the given error
+    pub fn reset_group(
+        &mut self,
+        group_id: group::Id,
+        error: application::Error,
+        api_call_context: &mut ConnectionApiCallContext,
+    ) {
+        let stream_ids = self.inner.groups.reset(group_id, error);
+
+        self.perform_transmitting_call(api_call_context, |manager| {
+            for stream_id in stream_ids {
+                // We have to wake inside the lock, since `StreamEvent`s has no capacity
+                // to carry wakers in another iteration
+                let mut events = StreamEvents::new();
+                manager.inner.streams.with_stream(
+                    stream_id,
+                    &mut manager.inner.stream_controller,
+                    |stream| stream.on_group_reset(error, &mut events),
+                );
+                events.wake_all();
+            }
+        });
+    }
+
     /// Returns whether or not streams have data to send
     pub fn has_pending_streams(&self) -> bool {
         self.inner.streams.has_pending_streams()
@@ -14,6 +14,7 @@ use crate::{
     recovery::RttEstimator,
     stream::{
         controller::MAX_STREAMS_SYNC_FRACTION,
+        group,
         stream_impl::StreamConfig,
         stream_interests::{StreamInterestProvider, StreamInterests},
         testing::*,
@@ -24,7 +25,7 @@ use crate::{
     transmission::interest::Provider as TransmissionInterestProvider,
     wakeup_queue::{WakeupHandle, WakeupQueue},
 };
-use alloc::{collections::VecDeque, sync::Arc};
+use alloc::{boxed::Box, collections::VecDeque, sync::Arc};
 use bytes::Bytes;
 use core::{
     task::{Context, Poll, Waker},
@@ -39,7 +40,7 @@ use s2n_quic_core::{
         StopSending, Stream as StreamFrame, StreamDataBlocked, StreamsBlocked,
     },
     packet::number::{PacketNumberRange, PacketNumberSpace},
-    stream::{ops, StreamId, StreamType},
+    stream::{ops, scheduler, StreamId, StreamType},
     time::{
         timer::{self, Provider as _},
         Timestamp,
@@ -68,6 +69,8 @@ struct MockStream {
     update_blocked_sync_period_count: usize,
     on_timeout_count: usize,
     on_internal_reset_count: usize,
+    group_window: Option<group::Window>,
+    on_group_reset_count: usize,
     on_transmit_try_write_frames: usize,
     on_transmit_count: usize,
     on_transmit_limit: Option<usize>,
@@ -139,6 +142,8 @@ impl StreamTrait for MockStream {
             update_blocked_sync_period_count: 0,
             on_timeout_count: 0,
             on_internal_reset_count: 0,
+            group_window: None,
+            on_group_reset_count: 0,
             on_data_count: 0,
             on_reset_count: 0,
             on_stream_data_blocked_count: 0,
@@ -240,12 +245,22 @@ impl StreamTrait for MockStream {
         Ok(())
     }
 
-    fn on_packet_ack<A: AckSet>(&mut self, _ack_set: &A, events: &mut StreamEvents) {
+    fn on_packet_ack<A: AckSet, Pub: event::ConnectionPublisher>(
+        &mut self,
+        _ack_set: &A,
+        events: &mut StreamEvents,
+        _publisher: &mut Pub,
+    ) {
         self.on_packet_ack_count += 1;
         self.store_wakers(events);
     }
 
-    fn on_packet_loss<A: AckSet>(&mut self, _ack_set: &A, events: &mut StreamEvents) {
+    fn on_packet_loss<A: AckSet, Pub: event::ConnectionPublisher>(
+        &mut self,
+        _ack_set: &A,
+        events: &mut StreamEvents,
+        _publisher: &mut Pub,
+    ) {
         self.on_packet_loss_count += 1;
         self.store_wakers(events);
     }
@@ -258,6 +273,19 @@ impl StreamTrait for MockStream {
         self.on_timeout_count += 1;
     }
 
+    fn reclaim(&mut self) {}
+
+    fn memory_usage(&self, _usage: &mut s2n_quic_core::connection::memory::Usage) {}
+
+    #[cfg(feature = "state-snapshot")]
+    fn snapshot(&self) -> s2n_quic_core::connection::snapshot::Stream {
+        s2n_quic_core::connection::snapshot::Stream {
+            id: self.config.stream_id.into(),
+            send: None,
+            receive: None,
+        }
+    }
+
     fn on_internal_reset(&mut self, _error: StreamError, events: &mut StreamEvents) {
         self.on_internal_reset_count += 1;
         if self.set_finalize_on_internal_reset {
@@ -301,6 +329,15 @@ impl StreamTrait for MockStream {
         self.on_connection_window_available_retrieve_window -= Into::<u64>::into(acquired_window);
     }
 
+    fn join_group(&mut self, group_window: group::Window) {
+        self.group_window = Some(group_window);
+    }
+
+    fn on_group_reset(&mut self, _error: ApplicationErrorCode, events: &mut StreamEvents) {
+        self.on_group_reset_count += 1;
+        self.store_wakers(events);
+    }
+
     fn poll_request(
         &mut self,
         request: &mut ops::Request,
@@ -402,6 +439,7 @@ fn create_stream_manager(local_ep_type: endpoint::Type) -> AbstractStreamManager
         local_ep_type,
         initial_local_limits,
         initial_peer_limits,
+        Box::new(scheduler::default::Scheduler),
     )
 }
 
@@ -785,7 +823,10 @@ fn peer_closing_streams_transmits_max_streams() {
             manager.get_transmission_interest()
         );
 
-        manager.on_packet_loss(&PacketNumberRange::new(packet_number, packet_number));
+        manager.on_packet_loss(
+            &PacketNumberRange::new(packet_number, packet_number),
+            &mut event::testing::Publisher::no_snapshot(),
+        );
 
         assert_eq!(
             transmission::Interest::LostData,
@@ -795,7 +836,10 @@ fn peer_closing_streams_transmits_max_streams() {
         let packet_number = write_context.packet_number();
         assert!(manager.on_transmit(&mut write_context).is_ok());
 
-        manager.on_packet_ack(&PacketNumberRange::new(packet_number, packet_number));
+        manager.on_packet_ack(
+            &PacketNumberRange::new(packet_number, packet_number),
+            &mut event::testing::Publisher::no_snapshot(),
+        );
 
         assert_eq!(
             transmission::Interest::None,
@@ -866,7 +910,10 @@ fn send_streams_blocked_frame_when_blocked_by_peer() {
             manager.get_transmission_interest()
         );
 
-        manager.on_packet_loss(&PacketNumberRange::new(packet_number, packet_number));
+        manager.on_packet_loss(
+            &PacketNumberRange::new(packet_number, packet_number),
+            &mut event::testing::Publisher::no_snapshot(),
+        );
 
         assert_eq!(
             transmission::Interest::LostData,
@@ -876,7 +923,10 @@ fn send_streams_blocked_frame_when_blocked_by_peer() {
         let packet_number = write_context.packet_number();
         assert!(manager.on_transmit(&mut write_context).is_ok());
 
-        manager.on_packet_ack(&PacketNumberRange::new(packet_number, packet_number));
+        manager.on_packet_ack(
+            &PacketNumberRange::new(packet_number, packet_number),
+            &mut event::testing::Publisher::no_snapshot(),
+        );
 
         assert_eq!(
             transmission::Interest::None,
@@ -1012,7 +1062,10 @@ where
 
     let rtt_estimator = RttEstimator::new(Duration::from_millis(100));
     manager.on_rtt_update(&rtt_estimator);
-    manager.on_packet_ack(&PacketNumberRange::new(packet_number, packet_number));
+    manager.on_packet_ack(
+        &PacketNumberRange::new(packet_number, packet_number),
+        &mut event::testing::Publisher::no_snapshot(),
+    );
 
     let expected_transmission_backoff = 2;
 
@@ -1093,7 +1146,10 @@ fn send_data_blocked_frame_when_blocked_by_connection_flow_limits() {
         manager.get_transmission_interest()
     );
 
-    manager.on_packet_loss(&PacketNumberRange::new(packet_number, packet_number));
+    manager.on_packet_loss(
+        &PacketNumberRange::new(packet_number, packet_number),
+        &mut event::testing::Publisher::no_snapshot(),
+    );
 
     assert_eq!(
         transmission::Interest::LostData,
@@ -1104,7 +1160,10 @@ fn send_data_blocked_frame_when_blocked_by_connection_flow_limits() {
     assert!(manager.on_transmit(&mut write_context).is_ok());
     write_context.frame_buffer.clear();
 
-    manager.on_packet_ack(&PacketNumberRange::new(packet_number, packet_number));
+    manager.on_packet_ack(
+        &PacketNumberRange::new(packet_number, packet_number),
+        &mut event::testing::Publisher::no_snapshot(),
+    );
 
     assert_eq!(
         transmission::Interest::None,
@@ -1337,6 +1396,7 @@ fn asymmetric_stream_limits_remote_initiated() {
                     endpoint::Type::Server,
                     initial_local_limits,
                     initial_peer_limits,
+                    Box::new(scheduler::default::Scheduler),
                 );
 
                 // The peer opens streams up to the limit we have given them
@@ -1415,6 +1475,7 @@ fn asymmetric_stream_limits_local_initiated() {
                     endpoint::Type::Server,
                     initial_local_limits,
                     initial_peer_limits,
+                    Box::new(scheduler::default::Scheduler),
                 );
 
                 // Local endpoint opens streams up to the limit
@@ -2092,9 +2153,9 @@ fn on_packet_ack_and_loss_is_forwarded_to_interested_streams() {
         *manager.streams_waiting_for_delivery_notifications()
     );
 
-    manager.on_packet_ack(&pn(1));
-    manager.on_packet_loss(&pn(2));
-    manager.on_packet_loss(&pn(3));
+    manager.on_packet_ack(&pn(1), &mut event::testing::Publisher::no_snapshot());
+    manager.on_packet_loss(&pn(2), &mut event::testing::Publisher::no_snapshot());
+    manager.on_packet_loss(&pn(3), &mut event::testing::Publisher::no_snapshot());
 
     assert_eq!(read_wake_counter, 3 * 3);
     assert_eq!(write_wake_counter, 3 * 3);
@@ -2123,9 +2184,9 @@ fn on_packet_ack_and_loss_is_forwarded_to_interested_streams() {
         stream.interests.delivery_notifications = false;
     });
 
-    manager.on_packet_ack(&pn(4));
-    manager.on_packet_ack(&pn(5));
-    manager.on_packet_loss(&pn(6));
+    manager.on_packet_ack(&pn(4), &mut event::testing::Publisher::no_snapshot());
+    manager.on_packet_ack(&pn(5), &mut event::testing::Publisher::no_snapshot());
+    manager.on_packet_loss(&pn(6), &mut event::testing::Publisher::no_snapshot());
 
     for stream_id in &[stream_2, stream_1, stream_4] {
         manager.with_asserted_stream(*stream_id, |stream| {
@@ -3107,3 +3168,280 @@ fn stream_transmission_fairness_test() {
         }
     }
 }
+
+#[test]
+fn stream_group_scheduling_test() {
+    let (_wakeup_queue, wakeup_handle) = create_wakeup_queue_and_handle();
+    let mut manager = create_stream_manager(endpoint::Type::Server);
+
+    let important = manager.create_group(VarInt::MAX).unwrap();
+    let background = manager.create_group(VarInt::MAX).unwrap();
+    manager.set_group_urgency(background, scheduler::DEFAULT_URGENCY + 1);
+
+    let important_stream = try_open(&mut manager, StreamType::Bidirectional).unwrap();
+    let background_stream = try_open(&mut manager, StreamType::Bidirectional).unwrap();
+    let ungrouped_stream = try_open(&mut manager, StreamType::Bidirectional).unwrap();
+
+    for (group, stream_id) in [
+        (important, important_stream),
+        (background, background_stream),
+    ] {
+        manager
+            .join_group(
+                group,
+                stream_id,
+                &mut ConnectionApiCallContext::from_wakeup_handle(&wakeup_handle),
+            )
+            .unwrap();
+    }
+
+    const FRAMES: usize = 100_000;
+    let streams = [important_stream, background_stream, ungrouped_stream];
+    for stream_id in streams {
+        manager.with_asserted_stream(stream_id, |stream| {
+            stream.on_transmit_try_write_frames = FRAMES;
+        });
+    }
+
+    let mut frame_buffer = OutgoingFrameBuffer::new();
+    frame_buffer.set_max_packet_size(Some(1200));
+    let mut write_context = MockWriteContext::new(
+        s2n_quic_platform::time::now(),
+        &mut frame_buffer,
+        transmission::Constraint::None,
+        transmission::Mode::Normal,
+        endpoint::Type::Server,
+    );
+
+    // fill 500 packets with up to 500 frames each
+    for _ in 0..500 {
+        write_context
+            .frame_buffer
+            .set_error_write_after_n_frames(500);
+        let _ = manager.on_transmit(&mut write_context);
+        write_context.frame_buffer.flush();
+        write_context.frame_buffer.clear();
+    }
+
+    let transmitted = streams.map(|stream_id| {
+        let mut remaining = 0;
+        manager.with_asserted_stream(stream_id, |stream| {
+            remaining = stream.on_transmit_try_write_frames
+        });
+        (FRAMES - remaining) as f64
+    });
+
+    // the background group has half the weight of the other classes
+    let important_ratio = transmitted[0] / transmitted[1];
+    assert!(
+        (important_ratio - 2.0).abs() < 0.2,
+        "unexpected ratio {}",
+        important_ratio
+    );
+    // streams which didn't join a group are scheduled with the default urgency
+    let ungrouped_ratio = transmitted[2] / transmitted[1];
+    assert!(
+        (ungrouped_ratio - 2.0).abs() < 0.2,
+        "unexpected ratio {}",
+        ungrouped_ratio
+    );
+}
+
+#[test]
+fn sequential_stream_test() {
+    let mut manager = create_stream_manager(endpoint::Type::Server);
+
+    let first = try_open(&mut manager, StreamType::Bidirectional).unwrap();
+    let second = try_open(&mut manager, StreamType::Bidirectional).unwrap();
+    let incremental = try_open(&mut manager, StreamType::Bidirectional).unwrap();
+    let streams = [first, second, incremental];
+
+    // the second stream is queued first, but the first stream became non-incremental first
+    for stream_id in [second, first, incremental] {
+        manager.with_asserted_stream(stream_id, |stream| {
+            stream.on_transmit_try_write_frames = 3;
+            stream.on_transmit_limit = Some(1);
+        });
+    }
+    for stream_id in [first, second] {
+        manager.set_incremental(stream_id, false).unwrap();
+    }
+
+    let mut frame_buffer = OutgoingFrameBuffer::new();
+    let mut write_context = MockWriteContext::new(
+        s2n_quic_platform::time::now(),
+        &mut frame_buffer,
+        transmission::Constraint::None,
+        transmission::Mode::Normal,
+        endpoint::Type::Server,
+    );
+
+    let remaining = |manager: &mut AbstractStreamManager<MockStream>| {
+        streams.map(|stream_id| {
+            let mut remaining = 0;
+            manager.with_asserted_stream(stream_id, |stream| {
+                remaining = stream.on_transmit_try_write_frames
+            });
+            remaining
+        })
+    };
+
+    // the non-incremental streams are sent one after the other, before the incremental stream
+    let mut order = Vec::new();
+    for _ in 0..20 {
+        let before = remaining(&mut manager);
+        // write a single frame per call to record the order of the streams
+        write_context.frame_buffer.set_error_write_after_n_frames(1);
+        let _ = manager.on_transmit(&mut write_context);
+        write_context.frame_buffer.flush();
+        write_context.frame_buffer.clear();
+        let after = remaining(&mut manager);
+
+        for (index, (before, after)) in before.iter().zip(after.iter()).enumerate() {
+            for _ in *after..*before {
+                order.push(streams[index]);
+            }
+        }
+    }
+
+    assert_eq!(
+        order,
+        [
+            second,
+            second,
+            second,
+            first,
+            first,
+            first,
+            incremental,
+            incremental,
+            incremental
+        ]
+    );
+
+    // unknown streams can't be changed
+    let unknown = StreamId::initial(endpoint::Type::Client, StreamType::Unidirectional);
+    assert!(matches!(
+        manager.set_incremental(unknown, false),
+        Err(StreamError::InvalidStream { .. })
+    ));
+}
+
+#[test]
+fn stream_group_test() {
+    let (mut wakeup_queue, wakeup_handle) = create_wakeup_queue_and_handle();
+    let mut manager = create_stream_manager(endpoint::Type::Server);
+    let (read_waker, read_wake_counter) = new_count_waker();
+
+    let group_1 = manager.create_group(VarInt::from_u32(1000)).unwrap();
+    let group_2 = manager.create_group(VarInt::from_u32(1000)).unwrap();
+    let stream_1 = try_open(&mut manager, StreamType::Bidirectional).unwrap();
+    let stream_2 = try_open(&mut manager, StreamType::Unidirectional).unwrap();
+    let stream_3 = try_open(&mut manager, StreamType::Bidirectional).unwrap();
+
+    for (group, stream_id) in [
+        (group_1, stream_1),
+        (group_1, stream_2),
+        (group_2, stream_3),
+    ] {
+        assert!(manager
+            .join_group(
+                group,
+                stream_id,
+                &mut ConnectionApiCallContext::from_wakeup_handle(&wakeup_handle),
+            )
+            .is_ok());
+    }
+
+    // streams of a group share the same window
+    manager.with_asserted_stream(stream_1, |stream| {
+        let window = stream.group_window.as_ref().unwrap();
+        window.on_acquired(VarInt::from_u32(400));
+        stream.read_waker_to_return = Some(read_waker.clone());
+    });
+    manager.with_asserted_stream(stream_2, |stream| {
+        let window = stream.group_window.as_ref().unwrap();
+        assert_eq!(window.available_window(), VarInt::from_u32(600));
+    });
+    manager.with_asserted_stream(stream_3, |stream| {
+        let window = stream.group_window.as_ref().unwrap();
+        assert_eq!(window.available_window(), VarInt::from_u32(1000));
+    });
+
+    manager.set_group_max_data(
+        group_1,
+        VarInt::from_u32(2000),
+        &mut ConnectionApiCallContext::from_wakeup_handle(&wakeup_handle),
+    );
+    manager.with_asserted_stream(stream_2, |stream| {
+        let window = stream.group_window.as_ref().unwrap();
+        assert_eq!(window.available_window(), VarInt::from_u32(1600));
+    });
+
+    // resetting a group resets all of its streams
+    manager.reset_group(
+        group_1,
+        ApplicationErrorCode::new(1).unwrap(),
+        &mut ConnectionApiCallContext::from_wakeup_handle(&wakeup_handle),
+    );
+    for (stream_id, expected) in [(stream_1, 1), (stream_2, 1), (stream_3, 0)] {
+        manager.with_asserted_stream(stream_id, |stream| {
+            assert_eq!(stream.on_group_reset_count, expected);
+        });
+    }
+    assert_eq!(read_wake_counter, 1);
+    assert_wakeups(&mut wakeup_queue, 0);
+
+    // streams which join a reset group are reset as well
+    assert_matches!(
+        manager.join_group(
+            group_1,
+            stream_3,
+            &mut ConnectionApiCallContext::from_wakeup_handle(&wakeup_handle),
+        ),
+        Err(StreamError::StreamReset { .. }),
+    );
+    manager.with_asserted_stream(stream_3, |stream| {
+        assert_eq!(stream.on_group_reset_count, 1);
+    });
+
+    // unknown streams can't join a group
+    assert_matches!(
+        manager.join_group(
+            group_2,
+            invalid_stream_id(endpoint::Type::Server),
+            &mut ConnectionApiCallContext::from_wakeup_handle(&wakeup_handle),
+        ),
+        Err(StreamError::InvalidStream { .. }),
+    );
+
+    // groups can't be created after the manager is closed
+    manager.close(connection::Error::unspecified());
+    assert!(manager.create_group(VarInt::from_u32(1000)).is_err());
+}
+
+#[test]
+fn transfer_streams_test() {
+    let mut manager = create_stream_manager(endpoint::Type::Server);
+    try_open(&mut manager, StreamType::Bidirectional).unwrap();
+    assert_eq!(manager.export_transfer(), Err(transfer::Error::StreamsOpen));
+
+    let mut streams = create_stream_manager(endpoint::Type::Server)
+        .export_transfer()
+        .unwrap();
+    streams.local_bidirectional_opened = VarInt::from_u8(3);
+    streams.remote_unidirectional_opened = VarInt::from_u8(2);
+    streams.received_data = VarInt::from_u16(1000);
+    streams.consumed_data = VarInt::from_u16(500);
+    streams.sent_data = VarInt::from_u16(200);
+
+    let mut manager = create_stream_manager(endpoint::Type::Server);
+    manager.on_transferred(&streams);
+    assert_eq!(manager.export_transfer(), Ok(streams));
+
+    // locally initiated streams continue after the transferred streams
+    assert_eq!(
+        try_open(&mut manager, StreamType::Bidirectional).unwrap(),
+        StreamId::nth(endpoint::Type::Server, StreamType::Bidirectional, 3).unwrap()
+    );
+}
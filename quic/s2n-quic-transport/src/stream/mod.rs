@@ -5,10 +5,12 @@
 
 mod api;
 mod controller;
+pub mod group;
 mod incoming_connection_flow_controller;
 mod manager;
 mod outgoing_connection_flow_controller;
 mod receive_stream;
+mod scheduler;
 mod send_stream;
 mod stream_container;
 mod stream_events;
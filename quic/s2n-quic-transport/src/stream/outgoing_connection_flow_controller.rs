@@ -135,6 +135,13 @@ impl OutgoingConnectionFlowController {
         inner.total_available_window - inner.available_window
     }
 
+    /// Restores the flow control offsets of a transferred connection
+    pub fn on_transferred(&mut self, total_window: VarInt, acquired_window: VarInt) {
+        let mut inner = self.inner.borrow_mut();
+        inner.total_available_window = total_window.max(acquired_window);
+        inner.available_window = inner.total_available_window - acquired_window;
+    }
+
     /// Acquires a part of the window from the `ConnectionFlowController` in
     /// order to be able to use it for sending data. `desired` is the window
     /// size that is intended to be borrowed. The returned window size might
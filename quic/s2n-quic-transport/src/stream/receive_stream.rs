@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    arena,
     buffer::{StreamReceiveBuffer, StreamReceiveBufferError},
     contexts::{OnTransmitError, WriteContext},
     stream::{
@@ -17,11 +18,16 @@ use core::{
     convert::TryFrom,
     task::{Context, Poll, Waker},
 };
+#[cfg(feature = "state-snapshot")]
+use s2n_quic_core::connection::snapshot;
 use s2n_quic_core::{
     ack, application,
     frame::{stream::StreamRef, MaxStreamData, ResetStream, StopSending, StreamDataBlocked},
     packet::number::PacketNumber,
-    stream::{ops, StreamId},
+    stream::{
+        limits::{ReassemblyLimit, ReassemblyUsage},
+        ops, StreamId,
+    },
     transport,
     varint::VarInt,
 };
@@ -347,6 +353,10 @@ pub struct ReceiveStream {
     final_state_observed: bool,
     /// Marks the stream as detached from the application
     detached: bool,
+    /// Limits the data which is buffered out of order
+    reassembly_limit: ReassemblyLimit,
+    /// The out-of-order data usage which was last reported to the connection
+    reassembly_usage: ReassemblyUsage,
 }
 
 impl ReceiveStream {
@@ -355,6 +365,8 @@ impl ReceiveStream {
         connection_flow_controller: IncomingConnectionFlowController,
         initial_window: VarInt,
         desired_flow_control_window: u32,
+        frame_arena: arena::Frames,
+        reassembly_limit: ReassemblyLimit,
     ) -> ReceiveStream {
         // If the stream is created in closed state directly move into the
         // terminal state.
@@ -366,7 +378,7 @@ impl ReceiveStream {
 
         let mut result = ReceiveStream {
             state,
-            receive_buffer: StreamReceiveBuffer::new(),
+            receive_buffer: StreamReceiveBuffer::with_arena(frame_arena),
             flow_controller: ReceiveStreamFlowController::new(
                 connection_flow_controller,
                 initial_window,
@@ -376,6 +388,8 @@ impl ReceiveStream {
             read_waiter: None,
             final_state_observed: is_closed,
             detached: is_closed,
+            reassembly_limit,
+            reassembly_usage: ReassemblyUsage::default(),
         };
 
         if is_closed {
@@ -467,6 +481,9 @@ impl ReceiveStream {
                         .with_frame_type(frame.tag().into())
                     })?;
 
+                self.update_reassembly_usage()
+                    .map_err(|error| error.with_frame_type(frame.tag().into()))?;
+
                 // wake the waiter if the buffer has data and the len has crossed the watermark
                 let mut should_wake = self
                     .read_waiter
@@ -535,7 +552,7 @@ impl ReceiveStream {
                     // buffered data might already have been consumed. In this
                     // case we directly go into [`ReceiveStreamState::DataRead`]
                     if frame.is_fin && self.receive_buffer.consumed_len() == total_size {
-                        self.receive_buffer.reset();
+                        self.reset_receive_buffer();
                         self.state = ReceiveStreamState::DataRead;
                     }
                 }
@@ -561,6 +578,76 @@ impl ReceiveStream {
         Ok(())
     }
 
+    /// Reports the data which is buffered out of order to the connection and
+    /// checks it against the limits
+    fn update_reassembly_usage(&mut self) -> Result<(), transport::Error> {
+        let connection_flow_controller = &mut self.flow_controller.connection_flow_controller;
+
+        // avoid walking the buffer if nothing is limited
+        if !self.reassembly_limit.is_limited()
+            && !connection_flow_controller.is_reassembly_limited()
+        {
+            return Ok(());
+        }
+
+        let usage = self.receive_buffer.reassembly_usage();
+        let previous = core::mem::replace(&mut self.reassembly_usage, usage);
+        connection_flow_controller.on_reassembly_usage(previous, usage)?;
+
+        if self.reassembly_limit.is_exceeded(usage) {
+            return Err(transport::Error::PROTOCOL_VIOLATION
+                .with_reason("stream reassembly limit exceeded"));
+        }
+
+        Ok(())
+    }
+
+    /// Drops all of the received data and releases the out-of-order data
+    /// usage of the stream
+    fn reset_receive_buffer(&mut self) {
+        self.receive_buffer.reset();
+
+        let previous = core::mem::take(&mut self.reassembly_usage);
+        if previous != ReassemblyUsage::default() {
+            // releasing buffered data can't exceed the limit
+            let _ = self
+                .flow_controller
+                .connection_flow_controller
+                .on_reassembly_usage(previous, ReassemblyUsage::default());
+        }
+    }
+
+    /// Returns the number of bytes used for buffering received data
+    pub fn memory_usage(&self) -> usize {
+        self.receive_buffer.memory_usage()
+    }
+
+    /// Releases the memory which is retained for receiving data
+    pub fn reclaim(&mut self) {
+        self.receive_buffer.shrink_to_fit();
+    }
+
+    /// Returns the state of the stream, as it is defined in RFC 9000
+    #[cfg(feature = "state-snapshot")]
+    pub fn snapshot(&self) -> snapshot::ReceiveState {
+        match self.state {
+            ReceiveStreamState::Receiving(None) => snapshot::ReceiveState::Recv,
+            ReceiveStreamState::Receiving(Some(total_size))
+                if self.receive_buffer.total_received_len() == total_size =>
+            {
+                snapshot::ReceiveState::DataRecvd
+            }
+            ReceiveStreamState::Receiving(Some(_)) => snapshot::ReceiveState::SizeKnown,
+            ReceiveStreamState::DataRead => snapshot::ReceiveState::DataRead,
+            // the peer keeps sending data until it receives the STOP_SENDING frame
+            ReceiveStreamState::Stopping { .. } => snapshot::ReceiveState::Recv,
+            ReceiveStreamState::Reset(_) if self.final_state_observed => {
+                snapshot::ReceiveState::ResetRead
+            }
+            ReceiveStreamState::Reset(_) => snapshot::ReceiveState::ResetRecvd,
+        }
+    }
+
     /// This method gets called when a stream gets reset due to a reason that is
     /// not related to a frame. E.g. due to a connection failure.
     pub fn on_internal_reset(&mut self, error: StreamError, events: &mut StreamEvents) {
@@ -665,7 +752,7 @@ impl ReceiveStream {
         self.flow_controller.stop_sync();
 
         // Reset the stream receive buffer
-        self.receive_buffer.reset();
+        self.reset_receive_buffer();
 
         // The data which was inside the receive buffer had actually not been
         // consumed. And if the peer signaled us a bigger final size than what
@@ -796,7 +883,7 @@ impl ReceiveStream {
 
             // We clear the receive buffer, to free up any buffer
             // space which had been allocated but not used
-            self.receive_buffer.reset();
+            self.reset_receive_buffer();
 
             // Mark the stream as reset. Note that the request doesn't have a flush so there's
             // currently no way to wait for the reset to be acknowledged.
@@ -899,7 +986,7 @@ impl ReceiveStream {
 
                 // We clear the receive buffer, to free up any buffer
                 // space which had been allocated but not used
-                self.receive_buffer.reset();
+                self.reset_receive_buffer();
 
                 // clear the waiter
                 self.read_waiter = None;
@@ -11,9 +11,9 @@ use bytes::Bytes;
 use core::{convert::TryFrom, task::Poll};
 use s2n_quic_core::{
     application::Error as ApplicationErrorCode,
-    connection, endpoint,
+    connection, endpoint, event,
     frame::{Frame, MaxData, MaxStreamData, ResetStream, StopSending},
-    stream::{ops, StreamError, StreamType},
+    stream::{limits::ReassemblyLimit, ops, StreamError, StreamType},
     transport::Error as TransportError,
     varint::VarInt,
 };
@@ -702,6 +702,106 @@ fn exceed_connection_flow_control_window() {
     test_env.assert_pop_error();
 }
 
+/// Returns a test environment configuration with a stream window which allows
+/// for sending data far ahead of the missing data
+fn reassembly_test_env_config() -> TestEnvironmentConfig {
+    let mut test_env_config: TestEnvironmentConfig = Default::default();
+    test_env_config.stream_id = StreamId::initial(
+        test_env_config.local_endpoint_type.peer_type(),
+        StreamType::Unidirectional,
+    );
+    test_env_config.initial_receive_window = 64 * 1024;
+    test_env_config.desired_flow_control_window = 64 * 1024;
+    test_env_config
+}
+
+#[test]
+fn exceed_stream_reassembly_limit() {
+    let mut test_env_config = reassembly_test_env_config();
+    test_env_config.stream_reassembly_limit = ReassemblyLimit {
+        max_bytes: None,
+        max_gaps: Some(1),
+    };
+    let mut test_env = setup_stream_test_env_with_config(test_env_config);
+
+    // a single gap is allowed
+    test_env.feed_data(VarInt::from_u32(100), 10);
+    // filling the gap releases it
+    test_env.feed_data(VarInt::from_u32(0), 100);
+    test_env.feed_data(VarInt::from_u32(10_000), 10);
+
+    // a second gap exceeds the limit
+    let mut events = StreamEvents::new();
+    assert_is_transport_error(
+        test_env.stream.on_data(
+            &stream_data(
+                test_env.stream.stream_id,
+                VarInt::from_u32(20_000),
+                &[1],
+                false,
+            ),
+            &mut events,
+        ),
+        TransportError::PROTOCOL_VIOLATION,
+    );
+}
+
+#[test]
+fn exceed_connection_reassembly_limit() {
+    let mut test_env_config = reassembly_test_env_config();
+    test_env_config.connection_reassembly_limit = ReassemblyLimit {
+        max_bytes: None,
+        max_gaps: Some(1),
+    };
+    let mut test_env = setup_stream_test_env_with_config(test_env_config);
+
+    // another stream of the same connection
+    let mut other = ReceiveStream::new(
+        false,
+        test_env.rx_connection_flow_controller.clone(),
+        VarInt::from_u32(100_000),
+        100_000,
+        Default::default(),
+        ReassemblyLimit::UNLIMITED,
+    );
+    let mut events = StreamEvents::new();
+
+    // the gap of the first stream is released before the other stream creates a gap
+    test_env.feed_data(VarInt::from_u32(100), 10);
+    test_env.feed_data(VarInt::from_u32(0), 100);
+    assert_eq!(
+        Ok(()),
+        other.on_data(
+            &stream_data(
+                test_env.stream.stream_id,
+                VarInt::from_u32(100),
+                &[1],
+                false
+            ),
+            &mut events,
+        )
+    );
+
+    // the gaps of both streams exceed the connection limit
+    assert_is_transport_error(
+        test_env.stream.on_data(
+            &stream_data(
+                test_env.stream.stream_id,
+                VarInt::from_u32(10_000),
+                &[1],
+                false,
+            ),
+            &mut events,
+        ),
+        TransportError::PROTOCOL_VIOLATION,
+    );
+
+    // resetting the other stream releases its gap, which makes room for the gap of the first
+    // stream
+    other.reset_receive_buffer();
+    test_env.feed_data(VarInt::from_u32(10_000), 1);
+}
+
 #[test]
 fn receiving_data_will_lead_to_a_stream_flow_control_window_update() {
     let mut test_env = setup_receive_only_test_env();
@@ -1802,7 +1902,11 @@ fn stop_sending_will_trigger_a_stop_sending_frame() {
 
             // Mark the frame as acknowledged
             let mut events = StreamEvents::new();
-            test_env.stream.on_packet_ack(&packet_nr, &mut events);
+            test_env.stream.on_packet_ack(
+                &packet_nr,
+                &mut events,
+                &mut event::testing::Publisher::no_snapshot(),
+            );
 
             // Nothing new to write; the stream should be finished
             assert_eq!(
@@ -1857,9 +1961,11 @@ fn do_not_retransmit_stop_sending_if_requested_twice() {
             if *ack_packet {
                 // Mark the frame as acknowledged
                 let mut events = StreamEvents::new();
-                test_env
-                    .stream
-                    .on_packet_ack(&sent_frame.packet_nr, &mut events);
+                test_env.stream.on_packet_ack(
+                    &sent_frame.packet_nr,
+                    &mut events,
+                    &mut event::testing::Publisher::no_snapshot(),
+                );
 
                 assert_eq!(
                     stream_interests(&[]),
@@ -2203,7 +2309,11 @@ fn stop_sending_frames_are_retransmitted_on_loss() {
 
     // Mark the frame as lost
     let mut events = StreamEvents::new();
-    test_env.stream.on_packet_loss(&packet_nr, &mut events);
+    test_env.stream.on_packet_loss(
+        &packet_nr,
+        &mut events,
+        &mut event::testing::Publisher::no_snapshot(),
+    );
 
     // Expect a retransmission of StopSending
     assert_eq!(
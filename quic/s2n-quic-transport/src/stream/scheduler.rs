@@ -0,0 +1,224 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Shares the transmission capacity between the classes of streams with deficit round robin
+//!
+//! Each class has a deficit counter, which is charged with the bytes its streams write. Streams
+//! of classes without remaining credit are skipped until the round ends, which happens once all
+//! classes with pending data ran out of credit. The classes are then replenished with an amount
+//! of credit that is proportional to their weight and the next round starts.
+//!
+//! Streams are incremental by default, which means that the streams of a class share its
+//! credit in round robin order. Non-incremental streams are sent sequentially instead: the other
+//! streams of the class wait until the non-incremental stream which was queued first has no
+//! more data to send.
+
+use super::group::{self, Groups};
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+};
+use s2n_quic_core::stream::{scheduler, StreamId};
+
+/// The credit which a class receives per round for each unit of weight
+const QUANTUM: i64 = 128;
+
+#[derive(Debug, Default)]
+struct Deficit {
+    /// The number of bytes the class is allowed to write before it is skipped
+    credit: i64,
+    /// Set if a stream of the class was skipped in the current round
+    is_backlogged: bool,
+}
+
+/// Tracks the credit of each class of streams on a connection
+#[derive(Debug)]
+pub struct Scheduler {
+    scheduler: Box<dyn scheduler::Scheduler>,
+    /// The deficit of each group, and of streams which didn't join a group
+    deficits: BTreeMap<Option<group::Id>, Deficit>,
+    /// The streams which are sent sequentially within their class
+    sequential: BTreeSet<StreamId>,
+}
+
+impl Scheduler {
+    pub fn new(scheduler: Box<dyn scheduler::Scheduler>) -> Self {
+        Self {
+            scheduler,
+            deficits: BTreeMap::new(),
+            sequential: BTreeSet::new(),
+        }
+    }
+
+    /// Returns `true` if the stream shares the credit of its class in round robin order
+    pub fn is_incremental(&self, stream_id: StreamId) -> bool {
+        !self.sequential.contains(&stream_id)
+    }
+
+    /// Sets whether the stream is sent incrementally or sequentially
+    ///
+    /// `is_open` is used to forget about streams which were finalized in the meantime.
+    pub fn set_incremental<F: Fn(StreamId) -> bool>(
+        &mut self,
+        stream_id: StreamId,
+        incremental: bool,
+        is_open: F,
+    ) {
+        self.sequential.retain(|id| is_open(*id));
+        if incremental {
+            self.sequential.remove(&stream_id);
+        } else {
+            self.sequential.insert(stream_id);
+        }
+    }
+
+    /// Returns `true` if any stream is sent sequentially
+    pub fn has_sequential_streams(&self) -> bool {
+        !self.sequential.is_empty()
+    }
+
+    /// Returns `true` if streams of the class may transmit in the current round
+    ///
+    /// Otherwise the class is marked as backlogged, and is replenished before the next round.
+    pub fn on_stream(&mut self, class: Option<group::Id>) -> bool {
+        let deficit = self.deficits.entry(class).or_default();
+        if deficit.credit > 0 {
+            return true;
+        }
+        deficit.is_backlogged = true;
+        false
+    }
+
+    /// Charges the class for the bytes its stream wrote
+    pub fn on_transmit(&mut self, class: Option<group::Id>, len: usize) {
+        if let Some(deficit) = self.deficits.get_mut(&class) {
+            deficit.credit -= len as i64;
+        }
+    }
+
+    /// Starts a new round by replenishing the classes which were skipped
+    ///
+    /// This must only be called once none of the classes with pending data have credit left.
+    /// Classes which weren't skipped have no pending data, and lose the credit they didn't use.
+    /// Rounds in which none of the classes would have regained credit are skipped.
+    ///
+    /// Returns `false` if no class was skipped, in which case another round would not
+    /// transmit any more data.
+    pub fn replenish(&mut self, groups: &Groups) -> bool {
+        let scheduler = &mut self.scheduler;
+        let mut rounds = None;
+
+        for (class, deficit) in self.deficits.iter_mut() {
+            if !deficit.is_backlogged {
+                continue;
+            }
+
+            let quantum = quantum(scheduler.as_mut(), groups, *class);
+            // the number of rounds until the class has credit again
+            let required = (1 - deficit.credit + quantum - 1) / quantum;
+            rounds = Some(rounds.map_or(required, |rounds: i64| rounds.min(required)));
+        }
+
+        let rounds = match rounds {
+            Some(rounds) => rounds,
+            None => return false,
+        };
+
+        for (class, deficit) in self.deficits.iter_mut() {
+            if core::mem::take(&mut deficit.is_backlogged) {
+                deficit.credit += rounds * quantum(scheduler.as_mut(), groups, *class);
+            } else {
+                deficit.credit = deficit.credit.min(0);
+            }
+        }
+
+        true
+    }
+}
+
+/// Returns the credit the class receives in each round
+fn quantum(
+    scheduler: &mut dyn scheduler::Scheduler,
+    groups: &Groups,
+    class: Option<group::Id>,
+) -> i64 {
+    // a weight of 0 would starve the class
+    let weight = scheduler.weight(&groups.class(class)).max(1);
+    weight as i64 * QUANTUM
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_quic_core::{stream::scheduler::default, varint::VarInt};
+
+    #[test]
+    fn replenish_test() {
+        let mut groups = Groups::default();
+        let first = groups.create(VarInt::MAX);
+        let second = groups.create(VarInt::MAX);
+        groups.set_urgency(second, 4);
+        let (first, second) = (Some(first), Some(second));
+
+        let mut scheduler = Scheduler::new(Box::new(default::Scheduler));
+        assert!(!scheduler.replenish(&groups));
+
+        // classes start without credit
+        assert!(!scheduler.on_stream(first));
+        assert!(!scheduler.on_stream(second));
+        assert!(scheduler.replenish(&groups));
+
+        // the weight of the first group is twice the weight of the second group
+        let mut transmitted = [0; 2];
+        for _ in 0..1000 {
+            let mut has_transmitted = false;
+            for (index, class) in [first, second].iter().enumerate() {
+                if scheduler.on_stream(*class) {
+                    scheduler.on_transmit(*class, 1000);
+                    transmitted[index] += 1000;
+                    has_transmitted = true;
+                }
+            }
+            if !has_transmitted {
+                assert!(scheduler.replenish(&groups));
+            }
+        }
+        let ratio = transmitted[0] as f64 / transmitted[1] as f64;
+        assert!((ratio - 2.0).abs() < 0.1, "unexpected ratio {}", ratio);
+
+        // a class with a weight of 0 still makes progress
+        #[derive(Debug)]
+        struct Zero;
+
+        impl scheduler::Scheduler for Zero {
+            fn weight(&mut self, _class: &scheduler::Class) -> u32 {
+                0
+            }
+        }
+
+        let mut scheduler = Scheduler::new(Box::new(Zero));
+        assert!(!scheduler.on_stream(None));
+        assert!(scheduler.replenish(&groups));
+        assert!(scheduler.on_stream(None));
+    }
+
+    #[test]
+    fn incremental_test() {
+        let stream_id = |id: u32| StreamId::from_varint(VarInt::from_u32(id));
+        let mut scheduler = Scheduler::new(Box::new(default::Scheduler));
+        assert!(scheduler.is_incremental(stream_id(0)));
+        assert!(!scheduler.has_sequential_streams());
+
+        scheduler.set_incremental(stream_id(0), false, |_| true);
+        scheduler.set_incremental(stream_id(4), false, |_| true);
+        assert!(!scheduler.is_incremental(stream_id(0)));
+        assert!(scheduler.has_sequential_streams());
+
+        scheduler.set_incremental(stream_id(0), true, |_| true);
+        assert!(scheduler.is_incremental(stream_id(0)));
+
+        // finalized streams are forgotten
+        scheduler.set_incremental(stream_id(8), true, |id| id != stream_id(4));
+        assert!(!scheduler.has_sequential_streams());
+    }
+}
@@ -4,6 +4,7 @@
 use crate::{
     contexts::{OnTransmitError, WriteContext},
     stream::{
+        group,
         outgoing_connection_flow_controller::OutgoingConnectionFlowController,
         stream_events::StreamEvents,
         stream_interests::{StreamInterestProvider, StreamInterests},
@@ -16,14 +17,17 @@ use crate::{
     transmission,
     transmission::interest::Provider as _,
 };
+use alloc::collections::VecDeque;
 use bytes::Bytes;
 use core::{
     convert::TryFrom,
     task::{Context, Waker},
     time::Duration,
 };
+#[cfg(feature = "state-snapshot")]
+use s2n_quic_core::connection::snapshot;
 use s2n_quic_core::{
-    ack, application,
+    ack, application, event,
     frame::{MaxStreamData, ResetStream, StopSending, StreamDataBlocked},
     packet::number::PacketNumber,
     stream::{ops, StreamId},
@@ -132,6 +136,9 @@ enum ResetSource {
     /// The reset had been initiated as an internal reset. Likely caused by a
     /// connection error or termination.
     InternalReset,
+    /// The reset had been initiated because data was lost on a stream which the
+    /// application configured to not retransmit any data.
+    LossPolicy,
 }
 
 impl ResetSource {
@@ -188,6 +195,9 @@ pub(super) struct StreamFlowController {
     connection_flow_controller: OutgoingConnectionFlowController,
     /// The flow control window acquired from the connection
     acquired_connection_flow_controller_window: VarInt,
+    /// The send window of the group the stream joined, which limits the window acquired from
+    /// the connection
+    group_window: Option<group::Window>,
     /// The highest offset which was ever tried to be acquired via
     /// the `acquire_flow_control_window()` method
     highest_requested_connection_flow_control_window: VarInt,
@@ -208,6 +218,7 @@ impl StreamFlowController {
         Self {
             connection_flow_controller,
             acquired_connection_flow_controller_window: VarInt::from_u32(0),
+            group_window: None,
             highest_requested_connection_flow_control_window: VarInt::from_u32(0),
             max_stream_data: initial_window,
             state: StreamFlowControllerState::Ready,
@@ -241,10 +252,16 @@ impl StreamFlowController {
             return;
         }
 
-        let missing_connection_window = self
+        let mut missing_connection_window = self
             .highest_requested_connection_flow_control_window
             .saturating_sub(self.acquired_connection_flow_controller_window);
 
+        // Streams in a group can't acquire more than the remaining window of the group
+        if let Some(group_window) = self.group_window.as_ref() {
+            missing_connection_window =
+                missing_connection_window.min(group_window.available_window());
+        }
+
         if missing_connection_window > VarInt::from_u32(0) {
             // Acquire as much window from the connection as possible to satisfy
             // the full range. We might get any amount of window back from it.
@@ -253,6 +270,9 @@ impl StreamFlowController {
                 .connection_flow_controller
                 .acquire_window(missing_connection_window);
             self.acquired_connection_flow_controller_window += acquired;
+            if let Some(group_window) = self.group_window.as_ref() {
+                group_window.on_acquired(acquired);
+            }
             if acquired > VarInt::from_u32(0)
                 && self.state == StreamFlowControllerState::BlockedOnConnectionWindow
             {
@@ -261,6 +281,13 @@ impl StreamFlowController {
         }
     }
 
+    /// Sets the window of the group the stream joined
+    ///
+    /// Only connection window which is acquired afterwards is accounted to the group.
+    pub fn set_group_window(&mut self, group_window: group::Window) {
+        self.group_window = Some(group_window);
+    }
+
     /// Returns the window/offset up to which data can be written
     fn available_window(&self) -> VarInt {
         core::cmp::min(
@@ -453,6 +480,14 @@ pub struct SendStream {
     /// If the second value in the tuple is set to true, the stream should be flushed before waking
     /// the waiter.
     pub(super) write_waiter: Option<(Waker, bool)>,
+    /// Delivery notifications which are waiting for the data up to the offset to be acknowledged
+    ///
+    /// The notifications are ordered by their offset, since they are registered as data is
+    /// enqueued.
+    deliveries: VecDeque<(VarInt, ops::tx::Delivery)>,
+    /// If set, the stream is reset with the error code once any data is lost, instead of
+    /// retransmitting the data
+    reset_on_loss: Option<application::Error>,
     /// Whether the final state had already been observed by the application
     final_state_observed: bool,
     /// Marks the stream as detached from the application
@@ -483,6 +518,8 @@ impl SendStream {
             data_sender,
             reset_sync: OnceSync::new(),
             write_waiter: None,
+            deliveries: VecDeque::new(),
+            reset_on_loss: None,
             final_state_observed: is_closed,
             detached: is_closed,
         };
@@ -578,8 +615,21 @@ impl SendStream {
     }
 
     /// This method gets called when a packet delivery got acknowledged
-    pub fn on_packet_ack<A: ack::Set>(&mut self, ack_set: &A, events: &mut StreamEvents) {
-        self.data_sender.on_packet_ack(ack_set);
+    pub fn on_packet_ack<A: ack::Set, Pub: event::ConnectionPublisher>(
+        &mut self,
+        stream_id: StreamId,
+        ack_set: &A,
+        events: &mut StreamEvents,
+        publisher: &mut Pub,
+    ) {
+        self.data_sender.on_packet_ack_with(ack_set, |range| {
+            publisher.on_stream_data_acked(event::builder::StreamDataAcked {
+                stream_id: stream_id.as_varint().as_u64(),
+                offset: range.start_inclusive().as_u64(),
+                len: range.len() as u64,
+            });
+        });
+        self.notify_deliveries();
         self.data_sender
             .flow_controller_mut()
             .on_packet_ack(ack_set);
@@ -638,12 +688,36 @@ impl SendStream {
     }
 
     /// This method gets called when a packet loss is reported
-    pub fn on_packet_loss<A: ack::Set>(&mut self, ack_set: &A) {
-        self.data_sender.on_packet_loss(ack_set);
+    pub fn on_packet_loss<A: ack::Set, Pub: event::ConnectionPublisher>(
+        &mut self,
+        stream_id: StreamId,
+        ack_set: &A,
+        events: &mut StreamEvents,
+        publisher: &mut Pub,
+    ) {
+        let mut is_data_lost = false;
+        self.data_sender.on_packet_loss_with(ack_set, |range| {
+            is_data_lost = true;
+            publisher.on_stream_data_lost(event::builder::StreamDataLost {
+                stream_id: stream_id.as_varint().as_u64(),
+                offset: range.start_inclusive().as_u64(),
+                len: range.len() as u64,
+            });
+        });
         self.data_sender
             .flow_controller_mut()
             .on_packet_loss(ack_set);
         self.reset_sync.on_packet_loss(ack_set);
+
+        // The application prefers skipping the rest of the stream over waiting for
+        // retransmissions of the lost data
+        if let Some(error_code) = self.reset_on_loss.filter(|_| is_data_lost) {
+            let error = StreamError::stream_reset(error_code);
+            if self.init_reset(ResetSource::LossPolicy, error) == InitResetResult::ResetInitiated {
+                // notify blocked writers that the stream is reset
+                self.wake(events);
+            }
+        }
     }
 
     /// Queries the component for any outgoing frames that need to get sent
@@ -672,6 +746,34 @@ impl SendStream {
         self.data_sender.flow_controller_mut().on_timeout(now)
     }
 
+    /// Returns the number of bytes used for buffering data which wasn't acknowledged yet
+    pub fn memory_usage(&self) -> usize {
+        self.data_sender.memory_usage()
+    }
+
+    /// Releases the memory which is retained for sending data
+    pub fn reclaim(&mut self) {
+        self.data_sender.shrink_to_fit();
+    }
+
+    /// Returns the state of the stream, as it is defined in RFC 9000
+    #[cfg(feature = "state-snapshot")]
+    pub fn snapshot(&self) -> snapshot::SendState {
+        match self.state {
+            SendStreamState::Sending => match self.data_sender.state() {
+                data_sender::State::Sending
+                | data_sender::State::Finishing(data_sender::FinState::Pending) => {
+                    snapshot::SendState::Send
+                }
+                data_sender::State::Finishing(_) => snapshot::SendState::DataSent,
+                data_sender::State::Finished => snapshot::SendState::DataRecvd,
+                data_sender::State::Cancelled(_) => snapshot::SendState::ResetSent,
+            },
+            SendStreamState::ResetSent(_) => snapshot::SendState::ResetSent,
+            SendStreamState::ResetAcknowledged(_) => snapshot::SendState::ResetRecvd,
+        }
+    }
+
     /// A reset that is triggered without having received a `RESET` frame.
     pub fn on_internal_reset(&mut self, error: StreamError, events: &mut StreamEvents) {
         let _ = self.init_reset(
@@ -708,6 +810,13 @@ impl SendStream {
         }
     }
 
+    /// Shares the send window of a group with the stream
+    pub fn join_group(&mut self, group_window: group::Window) {
+        self.data_sender
+            .flow_controller_mut()
+            .set_group_window(group_window);
+    }
+
     /// Wakes up the application on progress updates
     ///
     /// If there is not a registered waker and the stream is in a terminal state,
@@ -724,8 +833,38 @@ impl SendStream {
         }
     }
 
+    /// Notifies the deliveries of all of the data which has been acknowledged by the peer
+    fn notify_deliveries(&mut self) {
+        let acknowledged = self.data_sender.total_acknowledged_len();
+
+        while let Some((offset, _)) = self.deliveries.front() {
+            if *offset > acknowledged {
+                break;
+            }
+
+            if let Some((_, delivery)) = self.deliveries.pop_front() {
+                delivery.notify(Ok(()));
+            }
+        }
+    }
+
     // These functions are called from the client API
 
+    /// Registers a notification for the delivery of all of the data which is currently enqueued
+    fn register_delivery(&mut self, delivery: ops::tx::Delivery) {
+        match self.state {
+            SendStreamState::ResetSent(error) | SendStreamState::ResetAcknowledged(error) => {
+                delivery.notify(Err(error));
+            }
+            SendStreamState::Sending => {
+                let offset = self.data_sender.total_enqueued_len();
+                self.deliveries.push_back((offset, delivery));
+                // the data may have already been acknowledged
+                self.notify_deliveries();
+            }
+        }
+    }
+
     /// Tries to enqueue data for transmission on the `Stream`.
     ///
     /// The method will succeed as long as buffering space is available for the data,
@@ -749,12 +888,23 @@ impl SendStream {
         request: &mut ops::tx::Request,
         context: Option<&Context>,
     ) -> Result<ops::tx::Response, StreamError> {
+        if let Some(delivery) = request.delivery.take() {
+            // register the notification after the data in the request has been enqueued
+            let result = self.poll_request(request, context);
+            self.register_delivery(delivery);
+            return result;
+        }
+
         let mut response = ops::tx::Response::default();
 
         if request.detached {
             self.detach();
         }
 
+        if let Some(error_code) = request.reset_on_loss {
+            self.reset_on_loss = Some(error_code);
+        }
+
         macro_rules! store_waker {
             ($should_flush:expr) => {
                 // Store the waker, in order to be able to wakeup the caller
@@ -985,6 +1135,11 @@ impl SendStream {
         // to send or resend the remaining data.
         self.data_sender.stop_sending(error);
 
+        // The data which isn't acknowledged yet will never be delivered
+        for (_, delivery) in self.deliveries.drain(..) {
+            delivery.notify(Err(error));
+        }
+
         // For an internal reset (which provides no error_code) we do not need
         // to transmit the reset frame
         match (reason.is_internal(), error) {
@@ -2854,3 +2854,106 @@ fn can_query_stream_readiness() {
         }
     }
 }
+
+#[test]
+fn delivery_notifications_test() {
+    use std::sync::{Arc, Mutex};
+
+    let mut test_env = setup_send_only_test_env();
+    let notified = Arc::new(Mutex::new(Vec::new()));
+
+    let delivery = |id: u8| {
+        let notified = notified.clone();
+        ops::tx::Delivery::new(move |result| notified.lock().unwrap().push((id, result)))
+    };
+    let notified = || core::mem::take(&mut *notified.lock().unwrap());
+
+    // nothing has been enqueued, so the delivery is notified immediately
+    test_env
+        .run_request(ops::Request::default().notify_delivery(delivery(0)), false)
+        .unwrap();
+    assert_eq!(notified(), [(0, Ok(()))]);
+
+    let mut packets = vec![];
+    for id in 1..=2 {
+        test_env
+            .run_request(
+                ops::Request::default()
+                    .send(&mut [Bytes::from_static(b"123")])
+                    .notify_delivery(delivery(id)),
+                true,
+            )
+            .unwrap();
+        packets.push(test_env.transmit().expect("data is transmitted").packet_nr);
+    }
+
+    // the data of the second delivery is acknowledged first, which leaves a gap
+    test_env.ack_packet(packets[1], ExpectWakeup(None));
+    assert!(notified().is_empty());
+
+    // the deliveries are notified in order once the gap is acknowledged
+    test_env.ack_packet(packets[0], ExpectWakeup(None));
+    assert_eq!(notified(), [(1, Ok(())), (2, Ok(()))]);
+
+    // the deliveries which are still waiting are notified when the stream is reset
+    test_env
+        .run_request(
+            ops::Request::default()
+                .send(&mut [Bytes::from_static(b"456")])
+                .notify_delivery(delivery(3)),
+            true,
+        )
+        .unwrap();
+    assert!(notified().is_empty());
+
+    let error_code = ApplicationErrorCode::new(1).unwrap();
+    test_env.reset(error_code).unwrap();
+    assert!(matches!(
+        &notified()[..],
+        [(3, Err(StreamError::StreamReset { error, .. }))] if *error == error_code
+    ));
+
+    // deliveries registered after the reset are notified with the error
+    assert!(test_env
+        .run_request(ops::Request::default().notify_delivery(delivery(4)), false)
+        .is_err());
+    assert_matches!(&notified()[..], [(4, Err(StreamError::StreamReset { .. }))]);
+}
+
+#[test]
+fn reset_on_loss_test() {
+    for is_lost in [false, true] {
+        let mut test_env = setup_send_only_test_env();
+        let error_code = ApplicationErrorCode::new(7).unwrap();
+
+        test_env
+            .run_request(
+                ops::Request::default()
+                    .reset_on_loss(error_code)
+                    .send(&mut [Bytes::from_static(b"1234")]),
+                true,
+            )
+            .unwrap();
+        test_env.assert_write_frames(1);
+        assert_eq!(pn(0), test_env.sent_frames.pop_front().unwrap().packet_nr);
+
+        if is_lost {
+            test_env.nack_packet(pn(0));
+
+            // the lost data isn't retransmitted and the peer is notified of the reset instead
+            test_env.assert_write_reset_frame(error_code, pn(1), VarInt::from_u32(4));
+            assert_matches!(
+                test_env.poll_push(Bytes::from_static(b"5678")),
+                Poll::Ready(Err(StreamError::StreamReset { .. })),
+            );
+        } else {
+            // the stream isn't affected as long as data isn't lost
+            test_env.ack_packet(pn(0), ExpectWakeup(None));
+            assert_eq!(
+                test_env.poll_push(Bytes::from_static(b"5678")),
+                Poll::Ready(Ok(()))
+            );
+            test_env.assert_write_frames(1);
+        }
+    }
+}
@@ -8,7 +8,7 @@
 #![allow(unknown_lints, clippy::non_send_fields_in_send_ty)]
 
 use crate::{
-    stream,
+    arena, stream,
     stream::{stream_impl::StreamTrait, stream_interests::StreamInterests},
     transmission,
 };
@@ -95,6 +95,14 @@ impl<S> StreamNode<S> {
             waiting_for_stream_flow_control_credits_link: LinkedListLink::new(),
         }
     }
+
+    /// Replaces the Stream implementation of a `StreamNode` which was released
+    /// by the container, in order to reuse its allocation.
+    fn reuse(&mut self, stream_impl: S) {
+        debug_assert!(!self.tree_link.is_linked());
+        debug_assert!(!self.done_streams_link.is_linked());
+        *self.inner.get_mut() = stream_impl;
+    }
 }
 
 // This is required to build an intrusive `RBTree` of `StreamNode`s which
@@ -240,6 +248,9 @@ impl<S: StreamTrait> InterestLists<S> {
     }
 }
 
+/// The maximum number of nodes of finalized Streams which are retained for reuse
+const MAX_FREE_NODES: usize = 8;
+
 /// A collection of all intrusive lists Streams are part of.
 ///
 /// The container will automatically update the membership of a `Stream` in a
@@ -259,6 +270,8 @@ pub struct StreamContainer<S> {
     nr_active_streams: usize,
     /// Additional interest lists in which Streams will be placed dynamically
     interest_lists: InterestLists<S>,
+    /// Recycles the nodes of finalized Streams for newly inserted Streams
+    nodes: arena::Slab<Rc<StreamNode<S>>>,
 }
 
 impl<S> core::fmt::Debug for StreamContainer<S> {
@@ -319,7 +332,8 @@ macro_rules! iterate_interruptible {
                         .splice_after(extracted_list);
                     break;
                 }
-                StreamContainerIterationResult::Continue => {}
+                StreamContainerIterationResult::Continue
+                | StreamContainerIterationResult::ContinueAndInsertAtFront => {}
             }
         }
 
@@ -336,6 +350,7 @@ impl<S: StreamTrait> StreamContainer<S> {
             stream_map: RBTree::new(StreamTreeAdapter::new()),
             nr_active_streams: 0,
             interest_lists: InterestLists::new(),
+            nodes: arena::Slab::new(MAX_FREE_NODES),
         }
     }
 
@@ -345,7 +360,15 @@ impl<S: StreamTrait> StreamContainer<S> {
         // would be better to avoid future bugs
         let interests = stream.get_stream_interests();
 
-        let new_stream = Rc::new(StreamNode::new(stream));
+        let new_stream = self.nodes.alloc(
+            stream,
+            |stream| Rc::new(StreamNode::new(stream)),
+            |node, stream| {
+                Rc::get_mut(node)
+                    .expect("released nodes are not referenced")
+                    .reuse(stream)
+            },
+        );
 
         self.interest_lists.update_interests(
             &new_stream,
@@ -357,6 +380,26 @@ impl<S: StreamTrait> StreamContainer<S> {
         self.nr_active_streams += 1;
     }
 
+    /// Returns the usage of the arena for Stream nodes
+    pub fn arena_usage(&self) -> arena::Usage {
+        self.nodes.usage()
+    }
+
+    /// Calls `func` with each Stream which is tracked by the `StreamContainer`
+    pub fn for_each<F>(&self, mut func: F)
+    where
+        F: FnMut(&S),
+    {
+        for stream in self.stream_map.iter() {
+            func(&stream.inner.borrow());
+        }
+    }
+
+    /// Releases the nodes which are retained for reuse
+    pub fn reclaim_arena(&mut self) {
+        self.nodes.reclaim();
+    }
+
     /// Returns the amount of streams which are tracked by the `StreamContainer`
     pub fn nr_active_streams(&self) -> usize {
         self.nr_active_streams
@@ -414,11 +457,16 @@ impl<S: StreamTrait> StreamContainer<S> {
 
         // Update the interest lists after the interactions and then remove
         // all finalized streams
-        if self.interest_lists.update_interests(
+        let is_done = self.interest_lists.update_interests(
             &node_ptr,
             interests,
             StreamContainerIterationResult::Continue,
-        ) {
+        );
+
+        // Release the reference before finalizing so the node can be recycled
+        drop(node_ptr);
+
+        if is_done {
             self.finalize_done_streams(controller);
         }
 
@@ -430,11 +478,12 @@ impl<S: StreamTrait> StreamContainer<S> {
     /// The `stream::Controller` will be notified of streams that have been
     /// closed to allow for further streams to be opened.
     pub fn finalize_done_streams(&mut self, controller: &mut stream::Controller) {
-        for stream in self.interest_lists.done_streams.take() {
+        for mut stream in self.interest_lists.done_streams.take() {
             // Remove the Stream from `stream_map`
             let mut cursor = self.stream_map.find_mut(&stream.inner.borrow().stream_id());
             let remove_result = cursor.remove();
             debug_assert!(remove_result.is_some());
+            drop(remove_result);
             self.nr_active_streams -= 1;
 
             // And remove the Stream from all other interest lists it might be
@@ -471,6 +520,11 @@ impl<S: StreamTrait> StreamContainer<S> {
             );
 
             controller.on_close_stream(stream.inner.borrow().stream_id());
+
+            // Recycle the node once it isn't referenced anymore
+            if Rc::get_mut(&mut stream).is_some() {
+                self.nodes.free(stream);
+            }
         }
     }
 
@@ -659,6 +713,10 @@ impl<S: StreamTrait> transmission::interest::Provider for StreamContainer<S> {
 pub enum StreamContainerIterationResult {
     /// Continue iteration over the list
     Continue,
+    /// Continue iteration over the list and insert the current item at the
+    /// front instead of the back of the list, ahead of the items which were
+    /// already iterated
+    ContinueAndInsertAtFront,
     /// Aborts the iteration over a list and add the remaining items at the
     /// back of the list
     BreakAndInsertAtBack,
@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    arena,
     contexts::{OnTransmitError, WriteContext},
     stream::{
+        group,
         incoming_connection_flow_controller::IncomingConnectionFlowController,
         outgoing_connection_flow_controller::OutgoingConnectionFlowController,
         receive_stream::ReceiveStream,
@@ -14,10 +16,14 @@ use crate::{
     },
 };
 use core::{task::Context, time::Duration};
+#[cfg(feature = "state-snapshot")]
+use s2n_quic_core::connection::snapshot;
 use s2n_quic_core::{
-    ack, endpoint,
+    ack, application,
+    connection::memory,
+    endpoint, event,
     frame::{stream::StreamRef, MaxStreamData, ResetStream, StopSending, StreamDataBlocked},
-    stream::{ops, StreamId},
+    stream::{limits::ReassemblyLimit, ops, StreamId},
     time::{timer, Timestamp},
     transport,
     varint::VarInt,
@@ -42,6 +48,10 @@ pub struct StreamConfig {
     pub initial_send_window: VarInt,
     /// The maximum buffered amount of data on the sending side
     pub max_send_buffer_size: u32,
+    /// The connection-wide arena for received frame data
+    pub frame_arena: arena::Frames,
+    /// Limits the data which is buffered out of order on the receiving side
+    pub reassembly_limit: ReassemblyLimit,
 }
 
 /// A trait which represents an internally used `Stream`
@@ -95,10 +105,20 @@ pub trait StreamTrait: StreamInterestProvider + timer::Provider + core::fmt::Deb
     ) -> Result<(), transport::Error>;
 
     /// This method gets called when a packet delivery got acknowledged
-    fn on_packet_ack<A: ack::Set>(&mut self, ack_set: &A, events: &mut StreamEvents);
+    fn on_packet_ack<A: ack::Set, Pub: event::ConnectionPublisher>(
+        &mut self,
+        ack_set: &A,
+        events: &mut StreamEvents,
+        publisher: &mut Pub,
+    );
 
     /// This method gets called when a packet loss is reported
-    fn on_packet_loss<A: ack::Set>(&mut self, ack_set: &A, events: &mut StreamEvents);
+    fn on_packet_loss<A: ack::Set, Pub: event::ConnectionPublisher>(
+        &mut self,
+        ack_set: &A,
+        events: &mut StreamEvents,
+        publisher: &mut Pub,
+    );
 
     /// Updates the period at which `STREAM_DATA_BLOCKED` frames are sent to the peer
     /// if the application is blocked by peer limits.
@@ -107,6 +127,17 @@ pub trait StreamTrait: StreamInterestProvider + timer::Provider + core::fmt::Deb
     /// Called when the connection timer expires
     fn on_timeout(&mut self, now: Timestamp);
 
+    /// Called when the connection was idle long enough to release the memory which is
+    /// retained for reuse
+    fn reclaim(&mut self);
+
+    /// Adds the memory used by the buffers of the stream to `usage`
+    fn memory_usage(&self, usage: &mut memory::Usage);
+
+    /// Returns the states of the sending and receiving parts of the stream
+    #[cfg(feature = "state-snapshot")]
+    fn snapshot(&self) -> snapshot::Stream;
+
     /// This method gets called when a stream gets reset due to a reason that is
     /// not related to a frame. E.g. due to a connection failure.
     fn on_internal_reset(&mut self, error: StreamError, events: &mut StreamEvents);
@@ -122,6 +153,15 @@ pub trait StreamTrait: StreamInterestProvider + timer::Provider + core::fmt::Deb
     /// This method is called when a connection window is available
     fn on_connection_window_available(&mut self);
 
+    /// This method is called when the stream joins a group, which shares its send window with
+    /// the stream
+    fn join_group(&mut self, group_window: group::Window);
+
+    /// This method is called when the group of the stream gets reset by the application
+    ///
+    /// Both halves of the stream are reset with the error, independent of which task owns them.
+    fn on_group_reset(&mut self, error: application::Error, events: &mut StreamEvents);
+
     // These functions are called from the client API
 
     fn poll_request(
@@ -137,6 +177,8 @@ pub trait StreamTrait: StreamInterestProvider + timer::Provider + core::fmt::Deb
 pub struct StreamImpl {
     /// The stream ID
     pub(super) stream_id: StreamId,
+    /// Set to `true` when this stream has a receiving side
+    has_receive: bool,
     /// Manages the receiving side of the stream
     pub(super) receive_stream: ReceiveStream,
     /// Set to `true` when this stream has a sending side
@@ -199,11 +241,14 @@ impl StreamTrait for StreamImpl {
 
         StreamImpl {
             stream_id: config.stream_id,
+            has_receive: !receive_is_closed,
             receive_stream: ReceiveStream::new(
                 receive_is_closed,
                 config.incoming_connection_flow_controller,
                 config.initial_receive_window,
                 config.desired_flow_control_window,
+                config.frame_arena,
+                config.reassembly_limit,
             ),
             has_send: !send_is_closed,
             send_stream: SendStream::new(
@@ -277,15 +322,27 @@ impl StreamTrait for StreamImpl {
     }
 
     #[inline]
-    fn on_packet_ack<A: ack::Set>(&mut self, ack_set: &A, events: &mut StreamEvents) {
+    fn on_packet_ack<A: ack::Set, Pub: event::ConnectionPublisher>(
+        &mut self,
+        ack_set: &A,
+        events: &mut StreamEvents,
+        publisher: &mut Pub,
+    ) {
         self.receive_stream.on_packet_ack(ack_set);
-        self.send_stream.on_packet_ack(ack_set, events);
+        self.send_stream
+            .on_packet_ack(self.stream_id, ack_set, events, publisher);
     }
 
     #[inline]
-    fn on_packet_loss<A: ack::Set>(&mut self, ack_set: &A, _events: &mut StreamEvents) {
+    fn on_packet_loss<A: ack::Set, Pub: event::ConnectionPublisher>(
+        &mut self,
+        ack_set: &A,
+        events: &mut StreamEvents,
+        publisher: &mut Pub,
+    ) {
         self.receive_stream.on_packet_loss(ack_set);
-        self.send_stream.on_packet_loss(ack_set);
+        self.send_stream
+            .on_packet_loss(self.stream_id, ack_set, events, publisher);
     }
 
     #[inline]
@@ -299,6 +356,36 @@ impl StreamTrait for StreamImpl {
         self.send_stream.on_timeout(now)
     }
 
+    #[inline]
+    fn reclaim(&mut self) {
+        self.receive_stream.reclaim();
+        self.send_stream.reclaim();
+    }
+
+    #[inline]
+    fn memory_usage(&self, usage: &mut memory::Usage) {
+        usage.receive_buffers += self.receive_stream.memory_usage();
+        usage.send_buffers += self.send_stream.memory_usage();
+    }
+
+    #[cfg(feature = "state-snapshot")]
+    #[inline]
+    fn snapshot(&self) -> snapshot::Stream {
+        snapshot::Stream {
+            id: self.stream_id.into(),
+            send: if self.has_send {
+                Some(self.send_stream.snapshot())
+            } else {
+                None
+            },
+            receive: if self.has_receive {
+                Some(self.receive_stream.snapshot())
+            } else {
+                None
+            },
+        }
+    }
+
     #[inline]
     fn on_internal_reset(&mut self, error: StreamError, events: &mut StreamEvents) {
         self.receive_stream.on_internal_reset(error, events);
@@ -327,6 +414,32 @@ impl StreamTrait for StreamImpl {
         self.send_stream.on_connection_window_available()
     }
 
+    #[inline]
+    fn join_group(&mut self, group_window: group::Window) {
+        self.send_stream.join_group(group_window)
+    }
+
+    fn on_group_reset(&mut self, error: application::Error, events: &mut StreamEvents) {
+        // The requests below clear the wakers of the stream without waking them, since they
+        // usually come from the task which is blocked on the stream. Group resets come from
+        // other tasks, so the blocked tasks are woken to observe the reset.
+        if let Some((waker, _low_watermark)) = self.receive_stream.read_waiter.take() {
+            events.store_read_waker(waker);
+        }
+        if let Some((waker, _should_flush)) = self.send_stream.write_waiter.take() {
+            events.store_write_waker(waker);
+        }
+
+        let mut request = ops::Request::default();
+        request.stop_sending(error);
+        if self.has_send {
+            request.reset(error);
+        }
+
+        // resetting is a best effort operation so ignore the result
+        let _ = self.poll_request_impl(&mut request, None);
+    }
+
     // These functions are called from the client API
 
     fn poll_request(
@@ -16,10 +16,10 @@ use core::task::{Context, Poll, Waker};
 use futures_test::task::{new_count_waker, AwokenCount};
 use s2n_quic_core::{
     application::Error as ApplicationErrorCode,
-    endpoint,
+    endpoint, event,
     frame::{stream::Stream as StreamFrame, Frame, ResetStream, StreamDataBlocked},
     packet::number::{PacketNumber, PacketNumberSpace},
-    stream::{ops, StreamError, StreamId, StreamType},
+    stream::{limits::ReassemblyLimit, ops, StreamError, StreamId, StreamType},
     time::Timestamp,
     transport,
     varint::VarInt,
@@ -385,7 +385,11 @@ impl TestEnvironment {
         self.rx_connection_flow_controller
             .on_packet_ack(&packet_number);
         let mut events = StreamEvents::new();
-        self.stream.on_packet_ack(&packet_number, &mut events);
+        self.stream.on_packet_ack(
+            &packet_number,
+            &mut events,
+            &mut event::testing::Publisher::no_snapshot(),
+        );
         events.wake_all();
         let new_wake_count = self.wake_counter.get();
         let was_woken = new_wake_count > old_wake_count;
@@ -399,7 +403,11 @@ impl TestEnvironment {
         self.rx_connection_flow_controller
             .on_packet_loss(&packet_number);
         let mut events = StreamEvents::new();
-        self.stream.on_packet_loss(&packet_number, &mut events);
+        self.stream.on_packet_loss(
+            &packet_number,
+            &mut events,
+            &mut event::testing::Publisher::no_snapshot(),
+        );
     }
 
     pub fn run_request(
@@ -487,6 +495,8 @@ pub struct TestEnvironmentConfig {
     pub transmission_constraint: transmission::Constraint,
     pub local_endpoint_type: endpoint::Type,
     pub max_packet_size: Option<usize>,
+    pub stream_reassembly_limit: ReassemblyLimit,
+    pub connection_reassembly_limit: ReassemblyLimit,
 }
 
 impl Default for TestEnvironmentConfig {
@@ -515,6 +525,8 @@ impl TestEnvironmentConfig {
             max_send_buffer_size: TestEnvironment::DEFAULT_MAX_SEND_BUFFER_SIZE,
             transmission_constraint: transmission::Constraint::None,
             max_packet_size: None,
+            stream_reassembly_limit: ReassemblyLimit::UNLIMITED,
+            connection_reassembly_limit: ReassemblyLimit::UNLIMITED,
         }
     }
 }
@@ -530,6 +542,7 @@ pub fn setup_stream_test_env_with_config(config: TestEnvironmentConfig) -> TestE
     let rx_connection_flow_controller = IncomingConnectionFlowController::new(
         VarInt::new(config.initial_connection_receive_window_size).unwrap(),
         config.desired_connection_flow_control_window,
+        config.connection_reassembly_limit,
     );
 
     let tx_connection_flow_controller = OutgoingConnectionFlowController::new(
@@ -545,6 +558,8 @@ pub fn setup_stream_test_env_with_config(config: TestEnvironmentConfig) -> TestE
         desired_flow_control_window: config.desired_flow_control_window,
         initial_send_window: VarInt::new(config.initial_send_window).unwrap(),
         max_send_buffer_size: config.max_send_buffer_size as u32,
+        frame_arena: Default::default(),
+        reassembly_limit: config.stream_reassembly_limit,
     });
 
     let (waker, wake_counter) = new_count_waker();
@@ -3,7 +3,7 @@
 
 use crate::{
     contexts::{OnTransmitError, WriteContext},
-    interval_set::IntervalSet,
+    interval_set::{Interval, IntervalSet},
     transmission,
 };
 use bytes::Bytes;
@@ -197,11 +197,29 @@ impl<FlowController: OutgoingDataFlowController, Writer: FrameWriter>
         self.buffer.total_len()
     }
 
+    /// Returns the amount of bytes at the start of the Stream which have been acknowledged by the
+    /// peer without any gaps.
+    pub fn total_acknowledged_len(&self) -> VarInt {
+        self.buffer.head()
+    }
+
     /// Returns true if the data sender doesn't have any data enqueued for sending
     pub fn is_empty(&self) -> bool {
         self.buffer.is_empty()
     }
 
+    /// Returns the number of bytes used by the enqueued data
+    pub fn memory_usage(&self) -> usize {
+        self.buffer.memory_usage()
+    }
+
+    /// Releases the capacity which isn't used by the enqueued data
+    pub fn shrink_to_fit(&mut self) {
+        self.buffer.shrink_to_fit();
+        self.pending.shrink_to_fit();
+        self.lost.shrink_to_fit();
+    }
+
     /// Returns the state of the sender
     pub fn state(&self) -> State {
         self.state
@@ -286,12 +304,24 @@ impl<FlowController: OutgoingDataFlowController, Writer: FrameWriter>
 
     /// This method gets called when a packet delivery got acknowledged
     pub fn on_packet_ack<A: ack::Set>(&mut self, ack_set: &A) {
+        self.on_packet_ack_with(ack_set, |_range| {})
+    }
+
+    /// This method gets called when a packet delivery got acknowledged
+    ///
+    /// `on_range` is called with each range of data which was carried by the acknowledged packets.
+    pub fn on_packet_ack_with<A: ack::Set, F: FnMut(Interval<VarInt>)>(
+        &mut self,
+        ack_set: &A,
+        mut on_range: F,
+    ) {
         // If we do not get acknowledgements for any in flight data don't try
         // to release buffer chunks
 
         let pending = &mut self.pending;
 
         let any_acked = self.transmissions.on_ack_signal(ack_set, |range| {
+            on_range(range);
             pending
                 .remove(range)
                 .expect("output should not have a limit");
@@ -335,9 +365,21 @@ impl<FlowController: OutgoingDataFlowController, Writer: FrameWriter>
 
     /// This method gets called when a packet loss is reported
     pub fn on_packet_loss<A: ack::Set>(&mut self, ack_set: &A) {
+        self.on_packet_loss_with(ack_set, |_range| {})
+    }
+
+    /// This method gets called when a packet loss is reported
+    ///
+    /// `on_range` is called with each range of data which was carried by the lost packets.
+    pub fn on_packet_loss_with<A: ack::Set, F: FnMut(Interval<VarInt>)>(
+        &mut self,
+        ack_set: &A,
+        mut on_range: F,
+    ) {
         let lost = &mut self.lost;
 
         let mut any_lost = self.transmissions.on_ack_signal(ack_set, |range| {
+            on_range(range);
             lost.insert(range).expect("output should not have a limit");
         });
 
@@ -43,6 +43,17 @@ impl Buffer {
         VarInt::MAX - self.total_len()
     }
 
+    /// Returns the number of bytes used by the buffered chunks
+    pub fn memory_usage(&self) -> usize {
+        let data: usize = self.chunks.iter().map(|chunk| chunk.data.len()).sum();
+        data + self.chunks.capacity() * core::mem::size_of::<Chunk>()
+    }
+
+    /// Releases the capacity which isn't used by the buffered chunks
+    pub fn shrink_to_fit(&mut self) {
+        self.chunks.shrink_to_fit();
+    }
+
     /// Clears and resets the buffer
     pub fn clear(&mut self) {
         self.chunks.clear();
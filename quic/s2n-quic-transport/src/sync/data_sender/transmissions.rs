@@ -9,6 +9,7 @@ use crate::{
     contexts::{OnTransmitError, WriteContext},
     interval_set::{Interval, IntervalSet},
 };
+use alloc::vec::Vec;
 use core::{convert::TryInto, num::NonZeroU16};
 use s2n_quic_core::{
     ack,
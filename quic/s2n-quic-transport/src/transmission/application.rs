@@ -8,7 +8,7 @@ use crate::{
     endpoint, path,
     path::mtu,
     recovery,
-    space::{datagram, HandshakeStatus},
+    space::{datagram, extension_frame, HandshakeStatus},
     stream::{AbstractStreamManager, StreamTrait as Stream},
     sync::{flag, flag::Ping},
     transmission::{self, Mode},
@@ -38,6 +38,7 @@ impl<'a, Config: endpoint::Config> Payload<'a, Config> {
         stream_manager: &'a mut AbstractStreamManager<Config::Stream>,
         recovery_manager: &'a mut recovery::Manager<Config>,
         datagram_manager: &'a mut datagram::Manager<Config>,
+        extension_frame_manager: &'a mut extension_frame::Manager<Config>,
     ) -> Self {
         if transmission_mode != Mode::PathValidationOnly {
             debug_assert_eq!(path_id, path_manager.active_path_id());
@@ -54,6 +55,7 @@ impl<'a, Config: endpoint::Config> Payload<'a, Config> {
                     path_manager,
                     recovery_manager,
                     datagram_manager,
+                    extension_frame_manager,
                     prioritize_datagrams: false,
                 })
             }
@@ -110,6 +112,7 @@ pub struct Normal<'a, S: Stream, Config: endpoint::Config> {
     path_manager: &'a mut path::Manager<Config>,
     recovery_manager: &'a mut recovery::Manager<Config>,
     datagram_manager: &'a mut datagram::Manager<Config>,
+    extension_frame_manager: &'a mut extension_frame::Manager<Config>,
     prioritize_datagrams: bool,
 }
 
@@ -138,6 +141,9 @@ impl<'a, S: Stream, Config: endpoint::Config> Normal<'a, S, Config> {
         if can_transmit {
             self.transmit_control_data(context);
 
+            // extension frames are sent after the control frames of the transport
+            self.extension_frame_manager.on_transmit(context);
+
             // If we did not prioritize datagrams in this packet, we send them just
             // before we send stream data.
             if !self.prioritize_datagrams {
@@ -197,6 +203,7 @@ impl<'a, S: Stream, Config: endpoint::Config> transmission::interest::Provider
         self.handshake_status.transmission_interest(query)?;
         self.stream_manager.transmission_interest(query)?;
         self.datagram_manager.transmission_interest(query)?;
+        self.extension_frame_manager.transmission_interest(query)?;
         self.local_id_registry.transmission_interest(query)?;
         self.path_manager.transmission_interest(query)?;
         self.recovery_manager.transmission_interest(query)?;
@@ -59,6 +59,40 @@ impl<'a, 'b, 'sub, Config: endpoint::Config> Context<'a, 'b, 'sub, Config> {
             transmission::Constraint::None => {}
         }
     }
+
+    /// Encodes a frame with the precomputed encoding size of `len`
+    ///
+    /// The space for the frame is reserved in the buffer in a single pass, rather than
+    /// checking the capacity for each field of the frame.
+    #[inline]
+    fn encode_frame<Frame>(&mut self, frame: &Frame, len: usize) -> PacketNumber
+    where
+        Frame: EncoderValue + FrameTrait,
+        for<'frame> &'frame Frame: IntoEvent<event::builder::Frame>,
+    {
+        self.buffer.write_sized(len, |buffer| {
+            let mut buffer = EncoderBuffer::new(buffer);
+            buffer.encode(frame);
+            debug_assert_eq!(
+                buffer.len(),
+                len,
+                "the frame should fill the reserved space"
+            );
+        });
+
+        self.outcome.ack_elicitation |= frame.ack_elicitation();
+        self.outcome.is_congestion_controlled |= frame.is_congestion_controlled();
+
+        self.publisher.on_frame_sent(event::builder::FrameSent {
+            packet_header: event::builder::PacketHeader::new(
+                self.packet_number,
+                self.publisher.quic_version(),
+            ),
+            path_id: self.path_id.into_event(),
+            frame: frame.into_event(),
+        });
+        self.packet_number
+    }
 }
 
 impl<'a, 'b, 'sub, Config: endpoint::Config> WriteContext for Context<'a, 'b, 'sub, Config> {
@@ -98,21 +132,11 @@ impl<'a, 'b, 'sub, Config: endpoint::Config> WriteContext for Context<'a, 'b, 's
         for<'frame> &'frame Frame: IntoEvent<event::builder::Frame>,
     {
         self.check_frame_constraint(frame);
-        debug_assert!(frame.encoding_size() <= self.buffer.remaining_capacity());
 
-        self.buffer.encode(frame);
-        self.outcome.ack_elicitation |= frame.ack_elicitation();
-        self.outcome.is_congestion_controlled |= frame.is_congestion_controlled();
+        let len = frame.encoding_size();
+        debug_assert!(len <= self.buffer.remaining_capacity());
 
-        self.publisher.on_frame_sent(event::builder::FrameSent {
-            packet_header: event::builder::PacketHeader::new(
-                self.packet_number,
-                self.publisher.quic_version(),
-            ),
-            path_id: self.path_id.into_event(),
-            frame: frame.into_event(),
-        });
-        self.packet_number
+        self.encode_frame(frame, len)
     }
 
     fn write_frame_forced<Frame>(&mut self, frame: &Frame) -> Option<PacketNumber>
@@ -120,23 +144,13 @@ impl<'a, 'b, 'sub, Config: endpoint::Config> WriteContext for Context<'a, 'b, 's
         Frame: EncoderValue + FrameTrait,
         for<'frame> &'frame Frame: IntoEvent<event::builder::Frame>,
     {
-        if frame.encoding_size() > self.buffer.remaining_capacity() {
+        let len = frame.encoding_size();
+
+        if len > self.buffer.remaining_capacity() {
             return None;
         }
 
-        self.buffer.encode(frame);
-        self.outcome.ack_elicitation |= frame.ack_elicitation();
-        self.outcome.is_congestion_controlled |= frame.is_congestion_controlled();
-
-        self.publisher.on_frame_sent(event::builder::FrameSent {
-            packet_header: event::builder::PacketHeader::new(
-                self.packet_number,
-                self.publisher.quic_version(),
-            ),
-            path_id: self.path_id.into_event(),
-            frame: frame.into_event(),
-        });
-        Some(self.packet_number)
+        Some(self.encode_frame(frame, len))
     }
 
     #[inline]
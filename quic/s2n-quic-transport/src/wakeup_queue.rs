@@ -5,12 +5,12 @@
 //! reception or timers. This queue is used in case connections inside the endpoint
 //! change their readiness state (e.g. they get ready to write).
 
-use alloc::{collections::VecDeque, sync::Arc};
-use core::{
-    sync::atomic::{AtomicBool, Ordering},
-    task::{Context, Waker},
+use crate::shared::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
 };
-use std::{sync::Mutex, task::Wake};
+use alloc::{collections::VecDeque, task::Wake};
+use core::task::{Context, Waker};
 
 /// The shared state of the [`WakeupQueue`].
 #[derive(Debug)]
@@ -181,12 +181,13 @@ impl<T: Copy> WakeupHandle<T> {
     }
 }
 
+// `Waker`s can only be created from the `Arc` of the standard library, even in `loom` builds
 impl<T: Copy> Wake for WakeupHandle<T> {
-    fn wake(self: Arc<Self>) {
+    fn wake(self: alloc::sync::Arc<Self>) {
         self.wakeup()
     }
 
-    fn wake_by_ref(self: &Arc<Self>) {
+    fn wake_by_ref(self: &alloc::sync::Arc<Self>) {
         self.wakeup()
     }
 }
@@ -194,6 +195,7 @@ impl<T: Copy> Wake for WakeupHandle<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::sync::Arc;
     use futures_test::task::new_count_waker;
 
     macro_rules! vec_deque {
@@ -328,3 +330,65 @@ mod tests {
         pending.clear();
     }
 }
+
+#[cfg(loom)]
+mod loom_tests {
+    use super::*;
+    use futures_test::task::new_count_waker;
+    use loom::thread;
+
+    #[test]
+    fn loom_concurrent_wakeups() {
+        loom::model(|| {
+            let (waker, counter) = new_count_waker();
+            let mut queue = WakeupQueue::new();
+            let mut pending = VecDeque::new();
+
+            // store the waker of the endpoint
+            queue.poll_pending_wakeups(&mut pending, &Context::from_waker(&waker));
+            assert!(pending.is_empty());
+
+            let threads: Vec<_> = (0..2u32)
+                .map(|id| {
+                    let handle = queue.create_wakeup_handle(id);
+                    thread::spawn(move || handle.wakeup())
+                })
+                .collect();
+
+            for thread in threads {
+                thread.join().unwrap();
+            }
+
+            // the endpoint is only woken once for both of the handles
+            assert_eq!(counter.get(), 1);
+
+            queue.poll_pending_wakeups(&mut pending, &Context::from_waker(&waker));
+            let mut woken: Vec<_> = pending.drain(..).collect();
+            woken.sort_unstable();
+            assert_eq!(woken, [0, 1]);
+        });
+    }
+
+    #[test]
+    fn loom_wakeup_while_polling() {
+        loom::model(|| {
+            let (waker, counter) = new_count_waker();
+            let mut queue = WakeupQueue::new();
+            let mut pending = VecDeque::new();
+
+            let handle = queue.create_wakeup_handle(1u32);
+            let thread = thread::spawn(move || handle.wakeup());
+
+            queue.poll_pending_wakeups(&mut pending, &Context::from_waker(&waker));
+            thread.join().unwrap();
+
+            if pending.is_empty() {
+                // the wakeup happened after the waker was stored, so it must not get lost
+                assert_eq!(counter.get(), 1);
+                queue.poll_pending_wakeups(&mut pending, &Context::from_waker(&waker));
+            }
+
+            assert_eq!(pending, [1]);
+        });
+    }
+}
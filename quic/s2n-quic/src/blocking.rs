@@ -0,0 +1,393 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A blocking client for applications which don't use an async runtime
+//!
+//! The [`Client`] runs the endpoint on a background runtime, which is shared with the
+//! connections and streams it creates, so the connections keep making progress between calls.
+//! The runtime is shut down once the client and all of its connections and streams are dropped.
+//!
+//! All of the methods block the calling thread and return [`std::io::Error`]s, similar to the
+//! types in [`std::net`]. Blocking operations fail with [`ErrorKind::TimedOut`] if a timeout
+//! is configured and elapses.
+//!
+//! The methods must not be called from within an async runtime, since they would block one of
+//! its threads.
+//!
+//! ```rust,no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use s2n_quic::{blocking, client::Connect, Client};
+//! use std::{
+//!     io::{Read, Write},
+//!     net::SocketAddr,
+//!     path::Path,
+//!     time::Duration,
+//! };
+//!
+//! let client = blocking::Client::start(|| {
+//!     Client::builder()
+//!         .with_tls(Path::new("./certs/cert.pem"))?
+//!         .with_io("0.0.0.0:0")?
+//!         .start()
+//!         .map_err(Box::<dyn std::error::Error>::from)
+//! })?
+//! .with_timeout(Duration::from_secs(5));
+//!
+//! let addr: SocketAddr = "127.0.0.1:443".parse()?;
+//! let mut connection = client.connect(Connect::new(addr).with_server_name("localhost"))?;
+//!
+//! let mut stream = connection.open_bidirectional_stream()?;
+//! stream.write_all(b"hello")?;
+//! stream.finish()?;
+//!
+//! let mut response = vec![];
+//! stream.read_to_end(&mut response)?;
+//! #
+//! #   Ok(())
+//! # }
+//! ```
+
+use crate::{client::Connect, provider::StartError, stream::BidirectionalStream};
+use core::{future::Future, time::Duration};
+use std::{
+    io::{self, ErrorKind},
+    net::SocketAddr,
+    sync::Arc,
+};
+use tokio::runtime::Runtime;
+
+/// A QUIC client endpoint with a blocking API
+#[derive(Debug)]
+pub struct Client {
+    client: crate::Client,
+    runtime: Arc<Runtime>,
+    timeout: Option<Duration>,
+}
+
+impl Client {
+    /// Starts a client which listens on the provided socket
+    ///
+    /// See [`crate::Client::bind`].
+    pub fn bind<T>(socket: T) -> Result<Self, StartError>
+    where
+        T: crate::provider::io::TryInto,
+    {
+        Self::start(|| crate::Client::bind(socket))
+    }
+
+    /// Starts the client returned by `start` on a background runtime
+    ///
+    /// The closure is called within the runtime, which allows it to configure the client with
+    /// the [`Builder`](crate::client::Builder) like an async application would.
+    pub fn start<F, E>(start: F) -> Result<Self, E>
+    where
+        F: FnOnce() -> Result<crate::Client, E>,
+        E: From<StartError>,
+    {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("s2n-quic-blocking")
+            .enable_all()
+            .build()
+            .map_err(StartError::new)?;
+
+        let client = {
+            let _guard = runtime.enter();
+            start()?
+        };
+
+        Ok(Self {
+            client,
+            runtime: Arc::new(runtime),
+            timeout: None,
+        })
+    }
+
+    /// Sets the timeout of establishing connections
+    ///
+    /// The connections and streams created by the client start with the same timeout for
+    /// their operations. By default, there is no timeout.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Establishes a connection to the specified endpoint
+    pub fn connect(&self, connect: Connect) -> io::Result<Connection> {
+        let attempt = self.client.connect(connect);
+        let connection = block_on(
+            &self.runtime,
+            self.timeout,
+            async move { Ok(attempt.await?) },
+        )?;
+
+        Ok(Connection {
+            connection,
+            runtime: self.runtime.clone(),
+            timeout: self.timeout,
+        })
+    }
+
+    /// Waits for the client endpoint to finish handling all outstanding connections
+    ///
+    /// See [`crate::Client::wait_idle`]. This call is not limited by the timeout.
+    pub fn wait_idle(&mut self) -> io::Result<()> {
+        let client = &mut self.client;
+        block_on(
+            &self.runtime,
+            None,
+            async move { Ok(client.wait_idle().await?) },
+        )
+    }
+
+    /// Returns the local address that this client is bound to
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.client.local_addr()
+    }
+}
+
+/// A QUIC connection with a blocking API
+#[derive(Debug)]
+pub struct Connection {
+    connection: crate::Connection,
+    runtime: Arc<Runtime>,
+    timeout: Option<Duration>,
+}
+
+impl Connection {
+    /// Returns the connection's unique identifier within the endpoint
+    pub fn id(&self) -> u64 {
+        self.connection.id()
+    }
+
+    /// Returns the address of the peer
+    pub fn remote_addr(&self) -> io::Result<SocketAddr> {
+        Ok(self.connection.remote_addr()?)
+    }
+
+    /// Returns the local address of the connection
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        Ok(self.connection.local_addr()?)
+    }
+
+    /// Sets the timeout of opening and accepting streams, and the initial timeouts of the
+    /// streams created from now on
+    ///
+    /// A `None` value disables the timeout.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Opens a new bidirectional stream
+    ///
+    /// Blocks until the peer allows opening another stream.
+    pub fn open_bidirectional_stream(&mut self) -> io::Result<Stream> {
+        let connection = &mut self.connection;
+        let stream = block_on(&self.runtime, self.timeout, async move {
+            Ok(connection.open_bidirectional_stream().await?)
+        })?;
+
+        Ok(self.stream(stream))
+    }
+
+    /// Accepts a bidirectional stream opened by the peer
+    ///
+    /// Returns `None` once the connection was closed without an error.
+    pub fn accept_bidirectional_stream(&mut self) -> io::Result<Option<Stream>> {
+        let connection = &mut self.connection;
+        let stream = block_on(&self.runtime, self.timeout, async move {
+            Ok(connection.accept_bidirectional_stream().await?)
+        })?;
+
+        Ok(stream.map(|stream| self.stream(stream)))
+    }
+
+    /// Closes the connection immediately with the given error code
+    pub fn close(&self, error_code: crate::application::Error) {
+        self.connection.close(error_code)
+    }
+
+    fn stream(&self, stream: BidirectionalStream) -> Stream {
+        Stream {
+            stream,
+            runtime: self.runtime.clone(),
+            read_timeout: self.timeout,
+            write_timeout: self.timeout,
+        }
+    }
+}
+
+/// A bidirectional QUIC stream with a blocking API
+///
+/// The stream implements [`std::io::Read`] and [`std::io::Write`].
+#[derive(Debug)]
+pub struct Stream {
+    stream: BidirectionalStream,
+    runtime: Arc<Runtime>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+}
+
+impl Stream {
+    /// Returns the stream's identifier
+    pub fn id(&self) -> u64 {
+        self.stream.id()
+    }
+
+    /// Sets the timeout of reading from the stream
+    ///
+    /// A `None` value disables the timeout.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.read_timeout = timeout;
+    }
+
+    /// Sets the timeout of writing to, flushing and closing the stream
+    ///
+    /// A `None` value disables the timeout.
+    pub fn set_write_timeout(&mut self, timeout: Option<Duration>) {
+        self.write_timeout = timeout;
+    }
+
+    /// Marks the sending side of the stream as finished without waiting for the peer
+    ///
+    /// The data which was written is still delivered to the peer.
+    pub fn finish(&mut self) -> io::Result<()> {
+        Ok(self.stream.finish()?)
+    }
+
+    /// Finishes the sending side of the stream and waits until the peer acknowledged all of
+    /// the data
+    pub fn close(&mut self) -> io::Result<()> {
+        let stream = &mut self.stream;
+        block_on(&self.runtime, self.write_timeout, async move {
+            Ok(stream.close().await?)
+        })
+    }
+}
+
+impl io::Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let stream = &mut self.stream;
+        block_on(
+            &self.runtime,
+            self.read_timeout,
+            futures::io::AsyncReadExt::read(stream, buf),
+        )
+    }
+}
+
+impl io::Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let stream = &mut self.stream;
+        block_on(
+            &self.runtime,
+            self.write_timeout,
+            futures::io::AsyncWriteExt::write(stream, buf),
+        )
+    }
+
+    /// Waits until the peer acknowledged all of the data which was written
+    fn flush(&mut self) -> io::Result<()> {
+        let stream = &mut self.stream;
+        block_on(
+            &self.runtime,
+            self.write_timeout,
+            futures::io::AsyncWriteExt::flush(stream),
+        )
+    }
+}
+
+/// Runs the future on the runtime and blocks the thread until it completes or the timeout
+/// elapses
+fn block_on<F, T>(runtime: &Runtime, timeout: Option<Duration>, future: F) -> io::Result<T>
+where
+    F: Future<Output = io::Result<T>>,
+{
+    let timeout = if let Some(timeout) = timeout {
+        timeout
+    } else {
+        return runtime.block_on(future);
+    };
+
+    runtime
+        .block_on(async move { tokio::time::timeout(timeout, future).await })
+        .unwrap_or_else(|_| Err(ErrorKind::TimedOut.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Server;
+    use s2n_quic_core::crypto::tls::testing::certificates;
+    use std::io::{Read, Write};
+
+    #[test]
+    fn echo_test() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut server = {
+            let _guard = runtime.enter();
+            Server::builder()
+                .with_tls((certificates::CERT_PEM, certificates::KEY_PEM))
+                .unwrap()
+                .with_io("127.0.0.1:0")
+                .unwrap()
+                .start()
+                .unwrap()
+        };
+        let server_addr = server.local_addr().unwrap();
+
+        // echo the data of each stream
+        runtime.spawn(async move {
+            while let Some(mut connection) = server.accept().await {
+                tokio::spawn(async move {
+                    while let Ok(Some(mut stream)) = connection.accept_bidirectional_stream().await
+                    {
+                        tokio::spawn(async move {
+                            while let Ok(Some(data)) = stream.receive().await {
+                                let _ = stream.send(data).await;
+                            }
+                            let _ = stream.close().await;
+                        });
+                    }
+                });
+            }
+        });
+
+        let client = Client::start(|| {
+            crate::Client::builder()
+                .with_tls(certificates::CERT_PEM)
+                .map_err(StartError::new)?
+                .with_io("127.0.0.1:0")
+                .map_err(StartError::new)?
+                .start()
+        })
+        .unwrap()
+        .with_timeout(Duration::from_secs(10));
+
+        let connect = Connect::new(server_addr).with_server_name("localhost");
+        let mut connection = client.connect(connect).unwrap();
+        assert_eq!(connection.remote_addr().unwrap(), server_addr);
+
+        let mut stream = connection.open_bidirectional_stream().unwrap();
+        stream.write_all(b"hello").unwrap();
+        stream.flush().unwrap();
+
+        let mut response = [0; 5];
+        stream.read_exact(&mut response).unwrap();
+        assert_eq!(&response, b"hello");
+
+        // the server doesn't send anything else
+        stream.set_read_timeout(Some(Duration::from_millis(100)));
+        let error = stream.read(&mut response).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::TimedOut);
+
+        // the stream remains usable after a timeout
+        stream.write_all(b"world").unwrap();
+        stream.finish().unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(10)));
+        let mut response = vec![];
+        stream.read_to_end(&mut response).unwrap();
+        assert_eq!(response, b"world");
+    }
+}
@@ -13,7 +13,9 @@ use core::{
 };
 use s2n_quic_transport::endpoint::{connect, handle::Connector};
 
+pub mod alt_svc;
 mod builder;
+pub mod probe;
 mod providers;
 
 pub use builder::*;
@@ -80,7 +82,7 @@ impl Client {
     /// #    Ok(())
     /// # }
     /// ```
-    pub fn builder() -> Builder<impl ClientProviders> {
+    pub fn builder() -> Builder<DefaultProviders> {
         Builder::default()
     }
 
@@ -110,6 +112,45 @@ impl Client {
         ConnectionAttempt(attempt)
     }
 
+    /// Performs a handshake with the specified endpoint and reports what was negotiated
+    ///
+    /// The connection is closed once the handshake has finished, according to the probe's
+    /// [`Mode`](probe::Mode). The client must be configured with a [`probe::Subscriber`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use std::error::Error;
+    /// use s2n_quic::{
+    ///     client::{probe, Connect},
+    ///     Client,
+    /// };
+    /// use std::{net::SocketAddr, path::Path};
+    ///
+    /// # async fn probe() -> Result<(), Box<dyn Error>> {
+    /// let client = Client::builder()
+    ///     .with_tls(Path::new("./certs/cert.pem"))?
+    ///     .with_io("0.0.0.0:0")?
+    ///     .with_event(probe::Subscriber::default())?
+    ///     .start()?;
+    ///
+    /// let addr: SocketAddr = "127.0.0.1:443".parse()?;
+    /// let connect = Connect::new(addr).with_server_name("localhost");
+    /// let probe = probe::Probe::new(connect).with_mode(probe::Mode::Abort);
+    /// let report = client.probe(probe).await?;
+    ///
+    /// println!("{:?}", report.transport_parameters);
+    /// #
+    /// #    Ok(())
+    /// # }
+    /// ```
+    pub async fn probe<P: Into<probe::Probe>>(
+        &self,
+        probe: P,
+    ) -> Result<probe::Report, probe::Error> {
+        probe::run(self, probe.into()).await
+    }
+
     /// Wait for the client endpoint to finish handling all outstanding connections
     ///
     /// Notifies the endpoint of application interest in closing the endpoint. The
@@ -0,0 +1,393 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers for discovering HTTP/3 endpoints with HTTP Alternative Services
+//!
+//! Servers advertise that an origin is also reachable over HTTP/3 with the `Alt-Svc` header of
+//! responses sent over HTTP/1.1 or HTTP/2 (see [RFC 7838](https://www.rfc-editor.org/rfc/rfc7838)
+//! and [RFC 9114 Section 3.1.1](https://www.rfc-editor.org/rfc/rfc9114#section-3.1.1)).
+//! Applications typically connect over TCP first, parse the header of the response and use
+//! QUIC for following requests to the origin. If the QUIC connection attempt fails with an
+//! error for which [`connection::Error::quic_blocked`] returns a reason, applications should
+//! keep using TCP instead.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use std::error::Error;
+//! use s2n_quic::client::alt_svc;
+//!
+//! # fn alt_svc() -> Result<(), Box<dyn Error>> {
+//! let alternatives = alt_svc::parse(r#"h3=":443"; ma=3600, h2=":443""#)?;
+//!
+//! if let Some(alternative) = alternatives.iter().find(|alt| alt.is_http3()) {
+//!     let connect = alternative.connect("example.com")?;
+//!     // open the connection with a client configured with the "h3" application protocol
+//! }
+//! #
+//! #    Ok(())
+//! # }
+//! ```
+//!
+//! [`connection::Error::quic_blocked`]: crate::connection::Error::quic_blocked
+
+use crate::client::Connect;
+use core::{fmt, time::Duration};
+use std::{io, net::ToSocketAddrs};
+
+/// The ALPN protocol ID of HTTP/3
+pub const HTTP3: &str = "h3";
+
+/// The freshness lifetime of alternatives without a `ma` parameter
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// An alternative service advertised by an origin
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alternative {
+    protocol: String,
+    host: Option<String>,
+    port: u16,
+    max_age: Duration,
+    persist: bool,
+}
+
+impl Alternative {
+    /// Returns the ALPN protocol ID of the alternative, e.g. `h3`
+    pub fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    /// Returns the host of the alternative
+    ///
+    /// If `None` is returned, the alternative is on the same host as the origin.
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// Returns the port of the alternative
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns the amount of time for which the alternative can be used after it was received
+    pub fn max_age(&self) -> Duration {
+        self.max_age
+    }
+
+    /// Returns `true` if the alternative should be kept across changes of the network
+    pub fn persist(&self) -> bool {
+        self.persist
+    }
+
+    /// Returns `true` if the alternative uses HTTP/3 over QUIC version 1
+    pub fn is_http3(&self) -> bool {
+        self.protocol == HTTP3
+    }
+
+    /// Returns the host and port of the alternative for the origin with the given host
+    pub fn authority<'a>(&'a self, origin_host: &'a str) -> (&'a str, u16) {
+        (self.host().unwrap_or(origin_host), self.port)
+    }
+
+    /// Creates a connection attempt to the alternative of the origin with the given host
+    ///
+    /// The host of the alternative is resolved with a blocking DNS lookup and the attempt is
+    /// raced across all of the returned addresses. The server name is set to the host of the
+    /// origin, since the alternative has to present a certificate which is valid for the
+    /// origin.
+    pub fn connect(&self, origin_host: &str) -> io::Result<Connect> {
+        let mut addresses = self.authority(origin_host).to_socket_addrs()?;
+
+        let address = addresses.next().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "the host of the alternative did not resolve to any addresses",
+            )
+        })?;
+
+        Ok(Connect::new(address)
+            .with_addresses(addresses)
+            .with_server_name(origin_host))
+    }
+}
+
+/// An error which occurred while parsing an `Alt-Svc` header value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    reason: &'static str,
+}
+
+impl ParseError {
+    const fn new(reason: &'static str) -> Self {
+        Self { reason }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid Alt-Svc header: {}", self.reason)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses the value of an `Alt-Svc` header
+///
+/// The alternatives are returned in the order of preference of the server. An empty list is
+/// returned for the `clear` value, which indicates that all of the alternatives previously
+/// advertised by the origin are no longer valid.
+///
+/// Unknown parameters are ignored, as required by RFC 7838.
+pub fn parse(value: &str) -> Result<Vec<Alternative>, ParseError> {
+    let mut parser = Parser(value.as_bytes());
+    let mut alternatives = Vec::new();
+
+    parser.skip_whitespace();
+    if parser.0 == b"clear" {
+        return Ok(alternatives);
+    }
+
+    loop {
+        alternatives.push(parser.alternative()?);
+
+        parser.skip_whitespace();
+        if parser.0.is_empty() {
+            return Ok(alternatives);
+        }
+        parser.expect(b',', "expected a comma between alternatives")?;
+        parser.skip_whitespace();
+    }
+}
+
+struct Parser<'a>(&'a [u8]);
+
+impl<'a> Parser<'a> {
+    fn alternative(&mut self) -> Result<Alternative, ParseError> {
+        let protocol = percent_decode(self.token()?)?;
+        self.expect(b'=', "expected an alternative authority")?;
+        let authority = self.quoted_string()?;
+        let (host, port) = parse_authority(&authority)?;
+
+        let mut alternative = Alternative {
+            protocol,
+            host,
+            port,
+            max_age: DEFAULT_MAX_AGE,
+            persist: false,
+        };
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b';') {
+                return Ok(alternative);
+            }
+            self.0 = &self.0[1..];
+            self.skip_whitespace();
+
+            let name = self.token()?;
+            self.expect(b'=', "expected a parameter value")?;
+            let value = if self.peek() == Some(b'"') {
+                self.quoted_string()?
+            } else {
+                String::from_utf8_lossy(self.token()?).into_owned()
+            };
+
+            match name.to_ascii_lowercase().as_slice() {
+                b"ma" => {
+                    let secs = value
+                        .parse()
+                        .map_err(|_| ParseError::new("invalid max age"))?;
+                    alternative.max_age = Duration::from_secs(secs);
+                }
+                b"persist" => alternative.persist = value == "1",
+                _ => {}
+            }
+        }
+    }
+
+    fn token(&mut self) -> Result<&'a [u8], ParseError> {
+        let len = self
+            .0
+            .iter()
+            .position(|byte| !is_tchar(*byte))
+            .unwrap_or(self.0.len());
+
+        if len == 0 {
+            return Err(ParseError::new("expected a token"));
+        }
+
+        let (token, remaining) = self.0.split_at(len);
+        self.0 = remaining;
+        Ok(token)
+    }
+
+    fn quoted_string(&mut self) -> Result<String, ParseError> {
+        self.expect(b'"', "expected a quoted string")?;
+
+        let mut value = Vec::new();
+        loop {
+            match self.0 {
+                [b'"', remaining @ ..] => {
+                    self.0 = remaining;
+                    return String::from_utf8(value)
+                        .map_err(|_| ParseError::new("invalid quoted string"));
+                }
+                [b'\\', byte, remaining @ ..] | [byte, remaining @ ..] => {
+                    value.push(*byte);
+                    self.0 = remaining;
+                }
+                [] => return Err(ParseError::new("unterminated quoted string")),
+            }
+        }
+    }
+
+    fn expect(&mut self, byte: u8, reason: &'static str) -> Result<(), ParseError> {
+        if self.peek() != Some(byte) {
+            return Err(ParseError::new(reason));
+        }
+        self.0 = &self.0[1..];
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.0.first().copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t') = self.peek() {
+            self.0 = &self.0[1..];
+        }
+    }
+}
+
+/// Returns `true` if the byte can be part of a token, as defined in RFC 7230 Section 3.2.6
+fn is_tchar(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+/// Decodes the percent-encoded octets of a protocol ID, as defined in RFC 7838 Section 3
+fn percent_decode(token: &[u8]) -> Result<String, ParseError> {
+    let invalid = || ParseError::new("invalid protocol ID");
+
+    let mut protocol = Vec::with_capacity(token.len());
+    let mut bytes = token.iter();
+    while let Some(byte) = bytes.next() {
+        if *byte == b'%' {
+            let hex = [
+                *bytes.next().ok_or_else(invalid)?,
+                *bytes.next().ok_or_else(invalid)?,
+            ];
+            let hex = core::str::from_utf8(&hex).map_err(|_| invalid())?;
+            protocol.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+        } else {
+            protocol.push(*byte);
+        }
+    }
+
+    String::from_utf8(protocol).map_err(|_| invalid())
+}
+
+/// Splits an alternative authority into its optional host and port
+fn parse_authority(authority: &str) -> Result<(Option<String>, u16), ParseError> {
+    let (host, port) = authority
+        .rsplit_once(':')
+        .ok_or_else(|| ParseError::new("expected a port in the alternative authority"))?;
+    let port = port
+        .parse()
+        .map_err(|_| ParseError::new("invalid port in the alternative authority"))?;
+
+    // IPv6 addresses are enclosed in brackets
+    let host = host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host);
+
+    let host = if host.is_empty() {
+        None
+    } else {
+        Some(host.to_string())
+    };
+
+    Ok((host, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alternative(protocol: &str, host: Option<&str>, port: u16) -> Alternative {
+        Alternative {
+            protocol: protocol.to_string(),
+            host: host.map(String::from),
+            port,
+            max_age: DEFAULT_MAX_AGE,
+            persist: false,
+        }
+    }
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(
+            parse(r#"h3=":443""#).unwrap(),
+            vec![alternative("h3", None, 443)]
+        );
+
+        let alternatives =
+            parse(r#"h3="alt.example.com:8443"; ma=60; persist=1, h2=":443";ma=3600"#).unwrap();
+        assert_eq!(alternatives.len(), 2);
+        assert!(alternatives[0].is_http3());
+        assert_eq!(alternatives[0].host(), Some("alt.example.com"));
+        assert_eq!(alternatives[0].port(), 8443);
+        assert_eq!(alternatives[0].max_age(), Duration::from_secs(60));
+        assert!(alternatives[0].persist());
+        assert!(!alternatives[1].is_http3());
+        assert_eq!(alternatives[1].max_age(), Duration::from_secs(3600));
+        assert!(!alternatives[1].persist());
+
+        // unknown parameters and quoted parameter values are accepted
+        let alternatives = parse(r#"h3="[::1]:443"; foo="bar, baz"; ma="10""#).unwrap();
+        assert_eq!(alternatives[0].host(), Some("::1"));
+        assert_eq!(alternatives[0].max_age(), Duration::from_secs(10));
+
+        // protocol IDs are percent-decoded
+        assert_eq!(parse(r#"w%3Dx%3Ay=":443""#).unwrap()[0].protocol(), "w=x:y");
+
+        assert!(parse(" clear").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_error_test() {
+        for value in [
+            "",
+            "h3",
+            "h3=:443",
+            r#"h3="""#,
+            r#"h3="example.com""#,
+            r#"h3=":443"#,
+            r#"h3=":99999""#,
+            r#"h3=":443" h2=":443""#,
+            r#"h3=":443"; ma=soon"#,
+            r#"h3=":443","#,
+            r#"h%3=":443""#,
+        ] {
+            assert!(parse(value).is_err(), "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn authority_test() {
+        let same_host = alternative("h3", None, 443);
+        assert_eq!(same_host.authority("example.com"), ("example.com", 443));
+
+        let other_host = alternative("h3", Some("alt.example.com"), 8443);
+        assert_eq!(
+            other_host.authority("example.com"),
+            ("alt.example.com", 8443)
+        );
+
+        let connect = alternative("h3", Some("127.0.0.1"), 4433)
+            .connect("example.com")
+            .unwrap();
+        assert_eq!(format!("{:#}", connect), "example.com at 127.0.0.1:4433");
+    }
+}
@@ -126,6 +126,192 @@ impl<Providers: ClientProviders> Builder<Providers> {
         ClientProviders
     );
 
+    impl_provider_method!(
+        /// Sets the MTU provider for the [`Client`]
+        ///
+        /// # Examples
+        ///
+        /// Probes for jumbo frames on every path
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{Client, provider::mtu};
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let mtu = mtu::Config::builder()
+        ///     .with_initial_mtu(9001)?
+        ///     .with_max_mtu(9001)?
+        ///     .build()?;
+        ///
+        /// let client = Client::builder()
+        ///     .with_mtu(mtu)?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_mtu,
+        mtu,
+        ClientProviders
+    );
+
+    impl_provider_method!(
+        /// Sets the ACK strategy provider for the [`Client`]
+        ///
+        /// # Examples
+        ///
+        /// Reduces the number of ACK frames sent on high-throughput paths
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{Client, provider::ack};
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let client = Client::builder()
+        ///     .with_ack(ack::Strategy::HIGH_THROUGHPUT)?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_ack,
+        ack,
+        ClientProviders
+    );
+
+    impl_provider_method!(
+        /// Sets the protocol violation provider for the [`Client`]
+        ///
+        /// # Examples
+        ///
+        /// Ignores all protocol violations that the specification permits ignoring
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{Client, provider::protocol_violation};
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let client = Client::builder()
+        ///     .with_protocol_violation(protocol_violation::Outcome::Ignore)?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_protocol_violation,
+        protocol_violation,
+        ClientProviders
+    );
+
+    impl_provider_method!(
+        /// Sets the stream scheduler provider for the [`Client`]
+        ///
+        /// # Examples
+        ///
+        /// Shares the transmission capacity equally between all stream groups
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{Client, provider::stream_scheduler};
+        ///
+        /// #[derive(Debug)]
+        /// struct Equal;
+        ///
+        /// impl stream_scheduler::Endpoint for Equal {
+        ///     type Scheduler = Self;
+        ///
+        ///     fn new_scheduler(&mut self, _info: &stream_scheduler::ConnectionInfo) -> Self {
+        ///         Equal
+        ///     }
+        /// }
+        ///
+        /// impl stream_scheduler::Scheduler for Equal {
+        ///     fn weight(&mut self, _class: &stream_scheduler::Class) -> u32 {
+        ///         1
+        ///     }
+        /// }
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let client = Client::builder()
+        ///     .with_stream_scheduler(Equal)?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_stream_scheduler,
+        stream_scheduler,
+        ClientProviders
+    );
+
+    impl_provider_method!(
+        /// Sets the extension frame provider for the [`Client`]
+        ///
+        /// # Examples
+        ///
+        /// Offers an extension which exchanges a single frame type
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{
+        ///     provider::extension_frame::{self, FrameType},
+        ///     Client,
+        /// };
+        /// use s2n_quic_core::{transport, varint::VarInt};
+        ///
+        /// const TRANSPORT_PARAMETER: VarInt = VarInt::from_u32(0xff0a_0001);
+        ///
+        /// #[derive(Debug)]
+        /// struct MyExtension;
+        ///
+        /// impl extension_frame::Endpoint for MyExtension {
+        ///     type Handler = MyHandler;
+        ///
+        ///     fn new_handler(&mut self, _info: &extension_frame::ConnectionInfo) -> Option<MyHandler> {
+        ///         Some(MyHandler {
+        ///             frame_types: [FrameType::new(VarInt::from_u16(0x3a11)).unwrap()],
+        ///         })
+        ///     }
+        /// }
+        ///
+        /// #[derive(Debug)]
+        /// struct MyHandler {
+        ///     frame_types: [FrameType; 1],
+        /// }
+        ///
+        /// impl extension_frame::Handler for MyHandler {
+        ///     fn transport_parameter(&self) -> (VarInt, &[u8]) {
+        ///         (TRANSPORT_PARAMETER, &[])
+        ///     }
+        ///
+        ///     fn frame_types(&self) -> &[FrameType] {
+        ///         &self.frame_types
+        ///     }
+        ///
+        ///     fn on_frame(&mut self, _frame_type: VarInt, payload: &[u8]) -> Result<(), transport::Error> {
+        ///         println!("received {:?}", payload);
+        ///         Ok(())
+        ///     }
+        /// }
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let client = Client::builder()
+        ///     .with_extension_frame(MyExtension)?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_extension_frame,
+        extension_frame,
+        ClientProviders
+    );
+
     impl_provider_method!(
         /// Sets the event provider for the [`Client`]
         ///
@@ -264,7 +450,7 @@ impl<Providers: ClientProviders> Builder<Providers> {
         ClientProviders
     );
 
-    #[cfg(all(not(docdiff), feature = "unstable-provider-datagram"))]
+    #[cfg(any(test, all(not(docdiff), feature = "unstable-provider-datagram")))]
     impl_provider_method!(
         /// Sets the datagram provider for the [`Client`]
         with_datagram,
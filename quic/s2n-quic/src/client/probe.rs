@@ -0,0 +1,420 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A handshake-only client mode for reachability and monitoring probes
+//!
+//! A probe establishes a connection with a peer, records what was negotiated during the
+//! handshake and then closes the connection without opening any streams. This requires the
+//! client to be configured with a [`Subscriber`], which records the [`Report`] for each
+//! connection.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use std::error::Error;
+//! use s2n_quic::{
+//!     client::{probe, Connect},
+//!     Client,
+//! };
+//! use std::{net::SocketAddr, path::Path};
+//!
+//! # async fn probe() -> Result<(), Box<dyn Error>> {
+//! let client = Client::builder()
+//!     .with_tls(Path::new("./certs/cert.pem"))?
+//!     .with_io("0.0.0.0:0")?
+//!     .with_event(probe::Subscriber::default())?
+//!     .start()?;
+//!
+//! let addr: SocketAddr = "127.0.0.1:443".parse()?;
+//! let connect = Connect::new(addr).with_server_name("localhost");
+//! let report = client.probe(connect).await?;
+//!
+//! println!("handshake completed in {:?}", report.handshake_complete);
+//! #
+//! #    Ok(())
+//! # }
+//! ```
+
+use crate::{
+    application,
+    client::{Client, Connect},
+    connection,
+    provider::event::{self, events, query, ConnectionInfo, ConnectionMeta, Timestamp},
+};
+use bytes::Bytes;
+use core::{
+    fmt,
+    task::{self, Poll, Waker},
+    time::Duration,
+};
+use s2n_quic_core::application::ServerName;
+use std::net::SocketAddr;
+
+/// Determines how far a probe proceeds before closing the connection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Mode {
+    /// Waits for the server to confirm the handshake with a HANDSHAKE_DONE frame before
+    /// closing the connection
+    Complete,
+
+    /// Closes the connection as soon as the client completes the handshake, without waiting
+    /// for the server to confirm it
+    Abort,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Self::Complete
+    }
+}
+
+/// The parameters of a single probe
+#[derive(Debug)]
+pub struct Probe {
+    connect: Connect,
+    mode: Mode,
+}
+
+impl Probe {
+    /// Creates a probe which completes the handshake with the specified endpoint
+    pub fn new(connect: Connect) -> Self {
+        Self {
+            connect,
+            mode: Mode::default(),
+        }
+    }
+
+    /// Sets how far the probe proceeds before closing the connection
+    #[must_use]
+    pub fn with_mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+impl From<Connect> for Probe {
+    fn from(connect: Connect) -> Self {
+        Self::new(connect)
+    }
+}
+
+/// The timings and parameters that were observed while probing a peer
+///
+/// All of the timings are relative to the creation of the connection.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct Report {
+    /// The time at which the first packet was received from the peer
+    pub first_packet_received: Option<Duration>,
+    /// The time at which the handshake completed
+    pub handshake_complete: Option<Duration>,
+    /// The time at which the handshake was confirmed
+    pub handshake_confirmed: Option<Duration>,
+    /// The minimum RTT observed on the path
+    pub min_rtt: Option<Duration>,
+    /// The most recent smoothed RTT of the path
+    pub smoothed_rtt: Option<Duration>,
+    /// The cipher suite which was negotiated for 1-RTT packets
+    pub cipher_suite: Option<events::CipherSuite>,
+    /// The application protocol which was negotiated with ALPN
+    pub application_protocol: Option<Bytes>,
+    /// The server name which was sent to the peer
+    pub server_name: Option<ServerName>,
+    /// The transport parameters which were sent by the peer
+    pub transport_parameters: Option<TransportParameters>,
+}
+
+/// An owned copy of the transport parameters which were sent by the peer
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct TransportParameters {
+    pub original_destination_connection_id: Option<Bytes>,
+    pub initial_source_connection_id: Option<Bytes>,
+    pub retry_source_connection_id: Option<Bytes>,
+    pub stateless_reset_token: Option<Bytes>,
+    pub preferred_address: Option<PreferredAddress>,
+    pub migration_support: bool,
+    pub max_idle_timeout: Duration,
+    pub ack_delay_exponent: u8,
+    pub max_ack_delay: Duration,
+    pub max_udp_payload_size: u64,
+    pub active_connection_id_limit: u64,
+    pub initial_max_stream_data_bidi_local: u64,
+    pub initial_max_stream_data_bidi_remote: u64,
+    pub initial_max_stream_data_uni: u64,
+    pub initial_max_streams_bidi: u64,
+    pub initial_max_streams_uni: u64,
+    pub max_datagram_frame_size: u64,
+}
+
+impl<'a> From<&events::TransportParameters<'a>> for TransportParameters {
+    fn from(params: &events::TransportParameters<'a>) -> Self {
+        let connection_id = |id: &Option<events::ConnectionId>| {
+            id.as_ref().map(|id| Bytes::copy_from_slice(id.bytes))
+        };
+
+        Self {
+            original_destination_connection_id: connection_id(
+                &params.original_destination_connection_id,
+            ),
+            initial_source_connection_id: connection_id(&params.initial_source_connection_id),
+            retry_source_connection_id: connection_id(&params.retry_source_connection_id),
+            stateless_reset_token: params.stateless_reset_token.map(Bytes::copy_from_slice),
+            preferred_address: params
+                .preferred_address
+                .as_ref()
+                .map(PreferredAddress::from),
+            migration_support: params.migration_support,
+            max_idle_timeout: params.max_idle_timeout,
+            ack_delay_exponent: params.ack_delay_exponent,
+            max_ack_delay: params.max_ack_delay,
+            max_udp_payload_size: params.max_udp_payload_size,
+            active_connection_id_limit: params.active_connection_id_limit,
+            initial_max_stream_data_bidi_local: params.initial_max_stream_data_bidi_local,
+            initial_max_stream_data_bidi_remote: params.initial_max_stream_data_bidi_remote,
+            initial_max_stream_data_uni: params.initial_max_stream_data_uni,
+            initial_max_streams_bidi: params.initial_max_streams_bidi,
+            initial_max_streams_uni: params.initial_max_streams_uni,
+            max_datagram_frame_size: params.max_datagram_frame_size,
+        }
+    }
+}
+
+/// An owned copy of the preferred address which was sent by the peer
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct PreferredAddress {
+    pub ipv4_address: Option<SocketAddr>,
+    pub ipv6_address: Option<SocketAddr>,
+    pub connection_id: Bytes,
+    pub stateless_reset_token: Bytes,
+}
+
+impl<'a> From<&events::PreferredAddress<'a>> for PreferredAddress {
+    fn from(address: &events::PreferredAddress<'a>) -> Self {
+        Self {
+            ipv4_address: address.ipv4_address.as_ref().and_then(socket_addr),
+            ipv6_address: address.ipv6_address.as_ref().and_then(socket_addr),
+            connection_id: Bytes::copy_from_slice(address.connection_id.bytes),
+            stateless_reset_token: Bytes::copy_from_slice(address.stateless_reset_token),
+        }
+    }
+}
+
+fn socket_addr(address: &events::SocketAddress) -> Option<SocketAddr> {
+    match address {
+        events::SocketAddress::IpV4 { ip, port, .. } => Some((**ip, *port).into()),
+        events::SocketAddress::IpV6 { ip, port, .. } => Some((**ip, *port).into()),
+        _ => None,
+    }
+}
+
+/// An error which occurred while probing a peer
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The connection failed or was closed before the probe finished
+    Connection(connection::Error),
+    /// The report could not be queried, usually because the client was not configured with a
+    /// probe [`Subscriber`]
+    Query(query::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Connection(error) => write!(f, "probe connection failed: {}", error),
+            Self::Query(error) => write!(f, "probe report unavailable: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<connection::Error> for Error {
+    fn from(error: connection::Error) -> Self {
+        Self::Connection(error)
+    }
+}
+
+impl From<query::Error> for Error {
+    fn from(error: query::Error) -> Self {
+        Self::Query(error)
+    }
+}
+
+/// Records a [`Report`] for each connection
+///
+/// The subscriber can be composed with other subscribers in a tuple.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct Subscriber {}
+
+/// The connection context of the probe [`Subscriber`]
+#[derive(Debug)]
+pub struct Context {
+    start: Timestamp,
+    report: Report,
+    error: Option<connection::Error>,
+    waker: Option<Waker>,
+}
+
+impl Context {
+    /// Returns the report which has been recorded so far
+    pub fn report(&self) -> &Report {
+        &self.report
+    }
+
+    fn elapsed(&self, meta: &ConnectionMeta) -> Option<Duration> {
+        Some(meta.timestamp.saturating_duration_since(self.start))
+    }
+
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn poll_confirmed(&mut self, cx: &mut task::Context) -> Poll<Result<(), connection::Error>> {
+        if self.report.handshake_confirmed.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+
+        if let Some(error) = self.error {
+            return Poll::Ready(Err(error));
+        }
+
+        self.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl event::Subscriber for Subscriber {
+    type ConnectionContext = Context;
+
+    fn create_connection_context(
+        &mut self,
+        meta: &ConnectionMeta,
+        _info: &ConnectionInfo,
+    ) -> Self::ConnectionContext {
+        Context {
+            start: meta.timestamp,
+            report: Report::default(),
+            error: None,
+            waker: None,
+        }
+    }
+
+    fn on_packet_received(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        meta: &ConnectionMeta,
+        _event: &events::PacketReceived,
+    ) {
+        if context.report.first_packet_received.is_none() {
+            context.report.first_packet_received = context.elapsed(meta);
+        }
+    }
+
+    fn on_handshake_status_updated(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        meta: &ConnectionMeta,
+        event: &events::HandshakeStatusUpdated,
+    ) {
+        match event.status {
+            events::HandshakeStatus::Complete { .. } => {
+                context.report.handshake_complete = context.elapsed(meta);
+            }
+            events::HandshakeStatus::Confirmed { .. } => {
+                context.report.handshake_confirmed = context.elapsed(meta);
+                context.wake();
+            }
+            _ => {}
+        }
+    }
+
+    fn on_recovery_metrics(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &ConnectionMeta,
+        event: &events::RecoveryMetrics,
+    ) {
+        context.report.min_rtt = Some(event.min_rtt);
+        context.report.smoothed_rtt = Some(event.smoothed_rtt);
+    }
+
+    fn on_key_update(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &ConnectionMeta,
+        event: &events::KeyUpdate,
+    ) {
+        if let events::KeyType::OneRtt { .. } = event.key_type {
+            context.report.cipher_suite = Some(event.cipher_suite.clone());
+        }
+    }
+
+    fn on_application_protocol_information(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &ConnectionMeta,
+        event: &events::ApplicationProtocolInformation,
+    ) {
+        context.report.application_protocol =
+            Some(Bytes::copy_from_slice(event.chosen_application_protocol));
+    }
+
+    fn on_server_name_information(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &ConnectionMeta,
+        event: &events::ServerNameInformation,
+    ) {
+        context.report.server_name = Some(event.chosen_server_name.into());
+    }
+
+    fn on_transport_parameters_received(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &ConnectionMeta,
+        event: &events::TransportParametersReceived,
+    ) {
+        context.report.transport_parameters = Some((&event.transport_parameters).into());
+    }
+
+    fn on_connection_closed(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &ConnectionMeta,
+        event: &events::ConnectionClosed,
+    ) {
+        context.error = Some(event.error);
+        context.wake();
+    }
+}
+
+pub(super) async fn run(client: &Client, probe: Probe) -> Result<Report, Error> {
+    let Probe { connect, mode } = probe;
+    let mut connection = client.connect(connect).await?;
+
+    if mode == Mode::Complete {
+        futures::future::poll_fn(|cx| {
+            match connection
+                .query_event_context_mut(|context: &mut Context| context.poll_confirmed(cx))
+            {
+                Ok(Poll::Ready(result)) => Poll::Ready(result.map_err(Error::from)),
+                Ok(Poll::Pending) => Poll::Pending,
+                Err(error) => Poll::Ready(Err(error.into())),
+            }
+        })
+        .await?;
+    }
+
+    let report = connection.query_event_context(|context: &Context| context.report.clone())?;
+
+    connection.close(application::Error::UNKNOWN);
+
+    Ok(report)
+}
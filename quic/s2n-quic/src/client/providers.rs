@@ -18,6 +18,11 @@ impl_providers_state! {
         event: Event,
         limits: Limits,
         io: IO,
+        mtu: Mtu,
+        ack: Ack,
+        protocol_violation: ProtocolViolation,
+        stream_scheduler: StreamScheduler,
+        extension_frame: ExtensionFrame,
         sync: Sync,
         tls: Tls,
         datagram: Datagram,
@@ -37,6 +42,11 @@ impl<
         Event: event::Provider,
         Limits: limits::Provider,
         IO: io::Provider,
+        Mtu: mtu::Provider,
+        Ack: ack::Provider,
+        ProtocolViolation: protocol_violation::Provider,
+        StreamScheduler: stream_scheduler::Provider,
+        ExtensionFrame: extension_frame::Provider,
         Sync: sync::Provider,
         Tls: tls::Provider,
         Datagram: datagram::Provider,
@@ -51,6 +61,11 @@ impl<
         Event,
         Limits,
         IO,
+        Mtu,
+        Ack,
+        ProtocolViolation,
+        StreamScheduler,
+        ExtensionFrame,
         Sync,
         Tls,
         Datagram,
@@ -66,6 +81,11 @@ impl<
             random,
             event,
             limits,
+            mtu,
+            ack,
+            protocol_violation,
+            stream_scheduler,
+            extension_frame,
             io,
             sync,
             tls,
@@ -82,6 +102,11 @@ impl<
         let random = random.start().map_err(StartError::new)?;
         let endpoint_limits = EndpointLimits;
         let limits = limits.start().map_err(StartError::new)?;
+        let mtu = mtu.start().map_err(StartError::new)?;
+        let ack = ack.start().map_err(StartError::new)?;
+        let protocol_violation = protocol_violation.start().map_err(StartError::new)?;
+        let stream_scheduler = stream_scheduler.start().map_err(StartError::new)?;
+        let extension_frame = extension_frame.start().map_err(StartError::new)?;
         let event = event.start().map_err(StartError::new)?;
         let token = Token;
         let sync = sync.start().map_err(StartError::new)?;
@@ -116,6 +141,13 @@ impl<
             token,
             path_handle: PhantomData,
             path_migration,
+            mtu,
+            ack,
+            protocol_violation,
+            stream_scheduler,
+            extension_frame,
+            tenant: tenant::Disabled,
+            retry_offload: retry_offload::Disabled,
             datagram,
         };
 
@@ -199,6 +231,11 @@ struct EndpointConfig<
     Random,
     Event,
     Limits,
+    Mtu,
+    Ack,
+    ProtocolViolation,
+    StreamScheduler,
+    ExtensionFrame,
     Sync,
     Tls,
     Datagram,
@@ -212,6 +249,13 @@ struct EndpointConfig<
     endpoint_limits: EndpointLimits,
     event: Event,
     limits: Limits,
+    mtu: Mtu,
+    ack: Ack,
+    protocol_violation: ProtocolViolation,
+    stream_scheduler: StreamScheduler,
+    extension_frame: ExtensionFrame,
+    tenant: tenant::Disabled,
+    retry_offload: retry_offload::Disabled,
     sync: Sync,
     tls: Tls,
     token: Token,
@@ -230,6 +274,11 @@ impl<
         Random: s2n_quic_core::random::Generator,
         Event: s2n_quic_core::event::Subscriber,
         Limits: s2n_quic_core::connection::limits::Limiter,
+        Mtu: mtu::Endpoint,
+        Ack: ack::Endpoint,
+        ProtocolViolation: protocol_violation::Endpoint,
+        StreamScheduler: stream_scheduler::Endpoint,
+        ExtensionFrame: extension_frame::Endpoint,
         Sync,
         Tls: crypto::tls::Endpoint,
         Datagram: s2n_quic_core::datagram::Endpoint,
@@ -244,6 +293,11 @@ impl<
         Random,
         Event,
         Limits,
+        Mtu,
+        Ack,
+        ProtocolViolation,
+        StreamScheduler,
+        ExtensionFrame,
         Sync,
         Tls,
         Datagram,
@@ -264,6 +318,11 @@ impl<
         Random: s2n_quic_core::random::Generator,
         Event: s2n_quic_core::event::Subscriber,
         Limits: s2n_quic_core::connection::limits::Limiter,
+        Mtu: mtu::Endpoint,
+        Ack: ack::Endpoint,
+        ProtocolViolation: protocol_violation::Endpoint,
+        StreamScheduler: stream_scheduler::Endpoint,
+        ExtensionFrame: extension_frame::Endpoint,
         Sync: 'static + Send,
         Tls: crypto::tls::Endpoint,
         Datagram: s2n_quic_core::datagram::Endpoint,
@@ -278,6 +337,11 @@ impl<
         Random,
         Event,
         Limits,
+        Mtu,
+        Ack,
+        ProtocolViolation,
+        StreamScheduler,
+        ExtensionFrame,
         Sync,
         Tls,
         Datagram,
@@ -299,6 +363,13 @@ impl<
     type ConnectionLimits = Limits;
     type Stream = stream::StreamImpl;
     type PathMigrationValidator = PathMigration;
+    type MtuEndpoint = Mtu;
+    type AckEndpoint = Ack;
+    type ProtocolViolationEndpoint = ProtocolViolation;
+    type StreamSchedulerEndpoint = StreamScheduler;
+    type ExtensionFrameEndpoint = ExtensionFrame;
+    type TenantClassifier = tenant::Disabled;
+    type RetryOffload = retry_offload::Disabled;
     type PacketInterceptor = PacketInterceptor;
     type DatagramEndpoint = Datagram;
 
@@ -318,6 +389,13 @@ impl<
             connection_limits: &mut self.limits,
             event_subscriber: &mut self.event,
             path_migration: &mut self.path_migration,
+            mtu: &mut self.mtu,
+            ack: &mut self.ack,
+            protocol_violation: &mut self.protocol_violation,
+            stream_scheduler: &mut self.stream_scheduler,
+            extension_frame: &mut self.extension_frame,
+            tenant: &mut self.tenant,
+            retry_offload: &mut self.retry_offload,
             datagram: &mut self.datagram,
         }
     }
@@ -11,10 +11,33 @@ mod handle;
 
 pub use acceptor::*;
 pub use handle::*;
-pub use s2n_quic_core::connection::Error;
+pub use s2n_quic_core::connection::{
+    CorrelationId, Error, Extensions, HandshakeInfo, HandshakeTiming,
+};
 
 pub mod error {
-    pub use s2n_quic_core::transport::error::Code;
+    pub use s2n_quic_core::{connection::error::Blocked, transport::error::Code};
+}
+
+pub mod migration {
+    pub use s2n_quic_core::path::migration::Error;
+}
+
+pub mod arena {
+    pub use s2n_quic_core::connection::arena::{Stats, Usage};
+}
+
+pub mod memory {
+    pub use s2n_quic_core::connection::memory::Usage;
+}
+
+#[cfg(any(test, all(not(docdiff), feature = "unstable-state-snapshot")))]
+pub mod snapshot {
+    pub use s2n_quic_core::connection::snapshot::*;
+}
+
+pub mod transfer {
+    pub use s2n_quic_core::connection::transfer::{Error, State};
 }
 
 pub type Result<T, E = Error> = core::result::Result<T, E>;
@@ -15,6 +15,11 @@ macro_rules! impl_accept_api {
         /// - `Ok(None)` if the connection was closed without an error
         /// - `Err(stream_error)` if no stream could be accepted due to an error
         ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. A stream is only taken from the connection when the future
+        /// completes, so dropping the future never loses an accepted stream.
+        ///
         /// # Examples
         ///
         /// ```rust,no_run
@@ -65,6 +70,84 @@ macro_rules! impl_accept_api {
             .into()
         }
 
+        /// Accepts all of the currently available incoming [`PeerStream`](`crate::stream::PeerStream`)s
+        ///
+        /// Accepted streams are appended to `streams`. The method will return
+        /// - `Ok(Some(count))` if `count` streams were accepted
+        /// - `Ok(None)` if the connection was closed without an error
+        /// - `Err(stream_error)` if no stream could be accepted due to an error
+        ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. The streams are only appended to `streams` when the future
+        /// completes, so dropping the future never loses an accepted stream.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # async fn test() -> s2n_quic::stream::Result<()> {
+        /// #   let mut acceptor: s2n_quic::connection::StreamAcceptor = todo!();
+        /// #
+        /// let mut streams = Vec::new();
+        /// while let Some(count) = acceptor.accept_many(&mut streams).await? {
+        ///     println!("Accepted {} streams", count);
+        ///     streams.clear();
+        /// }
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub async fn accept_many(
+            &mut self,
+            streams: &mut Vec<crate::stream::PeerStream>,
+        ) -> crate::connection::Result<Option<usize>> {
+            futures::future::poll_fn(|cx| self.poll_accept_many(streams, cx)).await
+        }
+
+        /// Poll for accepting all of the currently available incoming
+        /// [`PeerStream`](`crate::stream::PeerStream`)s
+        ///
+        /// This drains all of the streams that are ready to be accepted in a single call, which
+        /// avoids waking the task for each stream when the peer opens many streams at once.
+        /// Accepted streams are appended to `streams`.
+        ///
+        /// The method will return
+        /// - `Poll::Ready(Ok(Some(count)))` if `count` streams were accepted. If the connection
+        ///   was closed after accepting at least one stream, the close is returned on the next call.
+        /// - `Poll::Ready(Ok(None))` if the connection was closed without an error
+        /// - `Poll::Ready(Err(stream_error))` if no stream could be accepted due to an error
+        /// - `Poll::Pending` if no new [`PeerStream`](`crate::stream::PeerStream`) was accepted by the connection yet.
+        ///   In this case the caller must retry calling [`Self::poll_accept_many`].
+        ///   For this purpose the method will save the [`core::task::Waker`]
+        ///   which is provided as part of the [`core::task::Context`] parameter, and notify it
+        ///   as soon as retrying the method will yield a different result.
+        #[inline]
+        pub fn poll_accept_many(
+            &mut self,
+            streams: &mut Vec<crate::stream::PeerStream>,
+            cx: &mut core::task::Context,
+        ) -> core::task::Poll<crate::connection::Result<Option<usize>>> {
+            use core::task::Poll;
+
+            let mut count = 0;
+
+            loop {
+                match self.poll_accept(cx) {
+                    Poll::Ready(Ok(Some(stream))) => {
+                        streams.push(stream);
+                        count += 1;
+                    }
+                    // return the streams that were accepted before reporting the close
+                    Poll::Ready(_) | Poll::Pending if count > 0 => break,
+                    Poll::Ready(result) => return Poll::Ready(result.map(|_| None)),
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+
+            Poll::Ready(Ok(Some(count)))
+        }
+
         impl_accept_bidirectional_api!();
         impl_accept_receive_api!();
     };
@@ -79,6 +162,11 @@ macro_rules! impl_accept_bidirectional_api {
         /// - `Ok(None)` if the connection was closed without an error
         /// - `Err(stream_error)` if no stream could be accepted due to an error
         ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. A stream is only taken from the connection when the future
+        /// completes, so dropping the future never loses an accepted stream.
+        ///
         /// # Examples
         ///
         /// ```rust,no_run
@@ -134,6 +222,11 @@ macro_rules! impl_accept_receive_api {
         /// - `Ok(None)` if the connection was closed without an error
         /// - `Err(stream_error)` if no stream could be accepted due to an error
         ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. A stream is only taken from the connection when the future
+        /// completes, so dropping the future never loses an accepted stream.
+        ///
         /// # Examples
         ///
         /// ```rust,no_run
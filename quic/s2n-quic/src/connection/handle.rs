@@ -9,6 +9,11 @@ macro_rules! impl_handle_api {
         ///  - `Ok(stream)` if a stream of the requested type was opened
         ///  - `Err(stream_error)` if the stream could not be opened due to an error
         ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. A stream is only opened when the future completes, so
+        /// dropping the future doesn't consume any of the peer's stream limits.
+        ///
         /// # Examples
         ///
         /// ```rust,no_run
@@ -63,6 +68,10 @@ macro_rules! impl_handle_api {
         ///  - `Ok(stream)` if a bidirectional stream was opened
         ///  - `Err(stream_error)` if the stream could not be opened due to an error
         ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe, like [`open_stream`](Self::open_stream).
+        ///
         /// # Examples
         ///
         /// ```rust,no_run
@@ -104,6 +113,10 @@ macro_rules! impl_handle_api {
 
         /// Opens a [`SendStream`](`crate::stream::SendStream`)
         ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe, like [`open_stream`](Self::open_stream).
+        ///
         /// # Examples
         ///
         /// ```rust,no_run
@@ -137,6 +150,96 @@ macro_rules! impl_handle_api {
             Ok(SendStream::new(stream.into())).into()
         }
 
+        /// Opens a [`SendStream`](`crate::stream::SendStream`), enqueues `data` and finishes the
+        /// stream
+        ///
+        /// The data and the FIN are enqueued in a single operation, which allows small messages
+        /// to be transmitted in a single packet. The returned stream can be used to
+        /// [`close`](crate::stream::SendStream::close) the stream and wait for the peer to
+        /// acknowledge the data.
+        ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. If the future is dropped after the stream was opened but
+        /// before all of the data was enqueued, the stream is reset, so the peer never receives a
+        /// truncated message as a finished stream.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # async fn test() -> s2n_quic::stream::Result<()> {
+        /// #   let mut connection: s2n_quic::connection::Handle = todo!();
+        /// #
+        /// let data = bytes::Bytes::from_static(b"hello");
+        /// let stream = connection.open_send_stream_with(data).await?;
+        /// println!("Send stream opened with id: {}", stream.id());
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub async fn open_send_stream_with(
+            &mut self,
+            data: bytes::Bytes,
+        ) -> $crate::stream::Result<$crate::stream::SendStream> {
+            let stream = self.open_send_stream().await?;
+            // reset the stream if the future is dropped, instead of finishing it without the data
+            let mut stream =
+                $crate::stream::ResetGuard::new(stream, $crate::stream::SendStream::reset);
+            stream
+                .get_mut()
+                .send_vectored_and_finish(&mut [data])
+                .await?;
+            Ok(stream.into_inner())
+        }
+
+        /// Opens a [`BidirectionalStream`](`crate::stream::BidirectionalStream`), enqueues
+        /// `request` and finishes the sending side of the stream
+        ///
+        /// This is intended for request/response patterns, where the entire request is known
+        /// upfront. The request and the FIN are enqueued in a single operation, which allows
+        /// small requests to be transmitted in a single packet. The returned stream can be used
+        /// to receive the response.
+        ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. If the future is dropped after the stream was opened but
+        /// before all of the request was enqueued, the stream is reset, so the peer never receives
+        /// a truncated request as a finished stream.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # async fn test() -> s2n_quic::stream::Result<()> {
+        /// #   let mut connection: s2n_quic::connection::Handle = todo!();
+        /// #
+        /// let request = bytes::Bytes::from_static(b"GET /");
+        /// let mut stream = connection.send_request(request).await?;
+        ///
+        /// while let Some(chunk) = stream.receive().await? {
+        ///     println!("Received {} bytes", chunk.len());
+        /// }
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub async fn send_request(
+            &mut self,
+            request: bytes::Bytes,
+        ) -> $crate::stream::Result<$crate::stream::BidirectionalStream> {
+            let stream = self.open_bidirectional_stream().await?;
+            // reset the stream if the future is dropped, instead of finishing it without the
+            // request
+            let mut stream =
+                $crate::stream::ResetGuard::new(stream, $crate::stream::BidirectionalStream::reset);
+            stream
+                .get_mut()
+                .send_vectored_and_finish(&mut [request])
+                .await?;
+            Ok(stream.into_inner())
+        }
+
         /// Returns the local address that this connection is bound to.
         #[inline]
         pub fn local_addr(&self) -> $crate::connection::Result<std::net::SocketAddr> {
@@ -149,6 +252,144 @@ macro_rules! impl_handle_api {
             self.0.remote_address().map(std::net::SocketAddr::from)
         }
 
+        /// Returns the usage of the connection's arenas
+        ///
+        /// Each connection allocates the buffers for received stream data, the state of its
+        /// streams, and the metadata of lost packets from arenas to reduce the number of
+        /// allocations made from the global allocator.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # fn test() -> s2n_quic::connection::Result<()> {
+        /// #   let connection: s2n_quic::connection::Handle = todo!();
+        /// #
+        /// let stats = connection.arena_stats()?;
+        /// let total = stats.total();
+        /// println!(
+        ///     "{} of {} allocations were served without the global allocator",
+        ///     total.allocations - total.heap_allocations,
+        ///     total.allocations,
+        /// );
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub fn arena_stats(&self) -> $crate::connection::Result<$crate::connection::arena::Stats> {
+            self.0.arena_stats()
+        }
+
+        /// Returns the estimated memory usage of the connection
+        ///
+        /// The usage is broken down into the stream send and receive buffers, the metadata of
+        /// sent packets, and the crypto state. It can be used for finding connections which
+        /// hold on to an unexpected amount of memory, or for accounting the memory used by
+        /// each tenant of a server.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # fn test() -> s2n_quic::connection::Result<()> {
+        /// #   let connection: s2n_quic::connection::Handle = todo!();
+        /// #
+        /// let usage = connection.memory_usage()?;
+        /// println!(
+        ///     "the connection uses {} bytes, {} of which are receive buffers",
+        ///     usage.total(),
+        ///     usage.receive_buffers,
+        /// );
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub fn memory_usage(
+            &self,
+        ) -> $crate::connection::Result<$crate::connection::memory::Usage> {
+            self.0.memory_usage()
+        }
+
+        /// Returns the times at which the connection reached each phase of the handshake
+        ///
+        /// Each phase is recorded as the time elapsed since the connection was created. The same
+        /// values are emitted with the `HandshakePhaseReached` event as each phase is reached.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # fn test() -> s2n_quic::connection::Result<()> {
+        /// #   let connection: s2n_quic::connection::Handle = todo!();
+        /// #
+        /// let timing = connection.handshake_timing()?;
+        /// if let Some(confirmed) = timing.handshake_confirmed {
+        ///     println!("the handshake was confirmed after {:?}", confirmed);
+        /// }
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub fn handshake_timing(
+            &self,
+        ) -> $crate::connection::Result<$crate::connection::HandshakeTiming> {
+            self.0.handshake_timing()
+        }
+
+        /// Returns the correlation ID which is attached to all of the events of the connection
+        ///
+        /// Clients can provide the correlation ID with `Connect::with_correlation_id`. Otherwise
+        /// a random ID is generated when the connection is created. The same ID is reported in
+        /// `ConnectionMeta::correlation_id` and on the `conn` span of the tracing subscriber.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # fn test() -> s2n_quic::connection::Result<()> {
+        /// #   let connection: s2n_quic::connection::Handle = todo!();
+        /// #
+        /// let correlation_id = connection.correlation_id()?;
+        /// println!("serving the request for connection {}", correlation_id);
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub fn correlation_id(
+            &self,
+        ) -> $crate::connection::Result<$crate::connection::CorrelationId> {
+            self.0.correlation_id()
+        }
+
+        /// Returns a snapshot of the state machines of the connection
+        ///
+        /// The snapshot contains the state of the connection, the packet numbers of each packet
+        /// number space, the connection flow control offsets and the states of the streams, as
+        /// they are defined in RFC 9000. Snapshots can be serialized and compared to the states
+        /// of other QUIC implementations or of a model checker in differential tests.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # fn test() -> s2n_quic::connection::Result<()> {
+        /// #   let connection: s2n_quic::connection::Handle = todo!();
+        /// #
+        /// use s2n_quic::connection::snapshot::State;
+        ///
+        /// let snapshot = connection.snapshot()?;
+        /// assert_eq!(snapshot.state, State::Active);
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[cfg(any(test, all(not(docdiff), feature = "unstable-state-snapshot")))]
+        #[inline]
+        pub fn snapshot(
+            &self,
+        ) -> $crate::connection::Result<$crate::connection::snapshot::Snapshot> {
+            self.0.snapshot()
+        }
+
         /// Returns the negotiated server name the connection is using.
         #[inline]
         pub fn server_name(&self) -> $crate::connection::Result<Option<$crate::server::Name>> {
@@ -161,6 +402,39 @@ macro_rules! impl_handle_api {
             self.0.application_protocol()
         }
 
+        /// Returns the DER-encoded certificate chain presented by the peer
+        ///
+        /// The chain starts with the peer's end-entity certificate. It is empty if the peer
+        /// didn't present any certificates, e.g. a client without client authentication, or if
+        /// the TLS provider doesn't expose them.
+        #[inline]
+        pub fn peer_certificates(&self) -> $crate::connection::Result<Vec<::bytes::Bytes>> {
+            self.0.peer_certificates()
+        }
+
+        /// Returns the application settings the peer sent for the negotiated application protocol
+        ///
+        /// The settings are configured on the TLS provider, e.g. with `with_application_settings`,
+        /// and are exchanged during the handshake. This returns `None` if the peer didn't send
+        /// any settings for the application protocol.
+        #[inline]
+        pub fn peer_application_settings(
+            &self,
+        ) -> $crate::connection::Result<Option<::bytes::Bytes>> {
+            self.0.peer_application_settings()
+        }
+
+        /// Returns the metadata about the handshake of the connection
+        ///
+        /// This includes the negotiated application protocol and server name, the address of
+        /// the peer, the address validation token, and the duration of the handshake.
+        #[inline]
+        pub fn handshake_info(
+            &self,
+        ) -> $crate::connection::Result<$crate::connection::HandshakeInfo> {
+            self.0.handshake_info()
+        }
+
         /// Returns the internal identifier for the [`Connection`](`crate::Connection`)
         ///
         /// Note: This internal identifier is not the same as the connection ID included in packet
@@ -186,6 +460,212 @@ macro_rules! impl_handle_api {
             self.0.keep_alive(enabled)
         }
 
+        /// Migrates the connection to a new local IP address
+        ///
+        /// This is intended for clients that move to a new network, for example, when switching
+        /// from Wi-Fi to a cellular network. The connection starts sending from `local_address`
+        /// and validates the new path with the server. If the validation fails, the connection
+        /// falls back to the previous path.
+        ///
+        /// The endpoint keeps sending from the same socket, so only the IP of the local address
+        /// can change. A port of `0` uses the port of the current path. The IO provider must
+        /// support selecting the local address of each packet, for example, with `IP_PKTINFO`.
+        ///
+        /// Only client connections can migrate, once the handshake is confirmed.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # fn test() -> Result<(), s2n_quic::connection::migration::Error> {
+        /// #   let mut connection: s2n_quic::connection::Handle = todo!();
+        /// #
+        /// connection.migrate("192.168.1.10:0".parse().unwrap())?;
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub fn migrate(
+            &mut self,
+            local_address: std::net::SocketAddr,
+        ) -> Result<(), $crate::connection::migration::Error> {
+            self.0.migrate(local_address.into())
+        }
+
+        /// Exports the state of the connection so it can be continued by another server
+        ///
+        /// The state is imported with [`Server::import`](crate::Server::import), for example,
+        /// after passing it to a new process during a binary upgrade. Only server connections
+        /// which confirmed the handshake, have no open streams, and aren't updating their keys
+        /// can be exported. The connection is closed silently on this endpoint once the state
+        /// was exported.
+        ///
+        /// Until the socket is handed over, this endpoint might still receive packets for the
+        /// connection, which it answers with stateless resets if enabled.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # fn test() -> Result<(), s2n_quic::connection::transfer::Error> {
+        /// #   let mut connection: s2n_quic::connection::Handle = todo!();
+        /// #
+        /// let state = connection.export_state()?;
+        /// let bytes = state.encode_to_vec();
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub fn export_state(
+            &mut self,
+        ) -> Result<$crate::connection::transfer::State, $crate::connection::transfer::Error> {
+            self.0.export_state()
+        }
+
+        /// Creates a new group of streams with a shared send window of `max_data` bytes
+        ///
+        /// Stream groups allow multiplexing several logical sessions on one connection. The
+        /// streams of a group can't send more than the window of the group in total, in addition
+        /// to the flow control limits of the peer, and can be reset as a unit with
+        /// [`Self::reset_stream_group`].
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # async fn test() -> Result<(), Box<dyn std::error::Error>> {
+        /// #   let mut connection: s2n_quic::connection::Connection = todo!();
+        /// #
+        /// let session = connection.create_stream_group(1_000_000)?;
+        ///
+        /// let stream = connection.open_bidirectional_stream().await?;
+        /// connection.join_stream_group(session, stream.id())?;
+        ///
+        /// // grant the session more window once the application is ready to send more data
+        /// connection.set_stream_group_max_data(session, 2_000_000)?;
+        ///
+        /// // send the data of the session before the data of other streams
+        /// connection.set_stream_group_urgency(session, 0)?;
+        ///
+        /// // close all streams of the session
+        /// connection.reset_stream_group(session, 1u32.into())?;
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub fn create_stream_group(
+            &mut self,
+            max_data: u64,
+        ) -> $crate::connection::Result<$crate::stream::GroupId> {
+            use s2n_quic_core::varint::VarInt;
+
+            let max_data = VarInt::new(max_data).unwrap_or(VarInt::MAX);
+            self.0.create_stream_group(max_data)
+        }
+
+        /// Adds the stream with the given id to a group
+        ///
+        /// Only the data which is sent after joining the group is accounted to the group's
+        /// window. A stream is a member of at most one group, so it leaves any group it joined
+        /// before. If the group was already reset, the stream is reset with the same error.
+        #[inline]
+        pub fn join_stream_group(
+            &mut self,
+            group: $crate::stream::GroupId,
+            stream_id: u64,
+        ) -> $crate::stream::Result<()> {
+            use s2n_quic_core::{stream::StreamId, varint::VarInt};
+
+            let stream_id = VarInt::new(stream_id)
+                .map(StreamId::from_varint)
+                .map_err(|_| $crate::stream::Error::invalid_stream())?;
+            self.0.join_stream_group(group, stream_id)
+        }
+
+        /// Increases the shared send window of a group to `max_data` bytes
+        ///
+        /// Values which are smaller than the current window are ignored.
+        #[inline]
+        pub fn set_stream_group_max_data(
+            &mut self,
+            group: $crate::stream::GroupId,
+            max_data: u64,
+        ) -> $crate::connection::Result<()> {
+            use s2n_quic_core::varint::VarInt;
+
+            let max_data = VarInt::new(max_data).unwrap_or(VarInt::MAX);
+            self.0.set_stream_group_max_data(group, max_data)
+        }
+
+        /// Sets the urgency of a group, from 0 (most important) to 7
+        ///
+        /// Once groups are created, each group receives a share of the transmission capacity
+        /// that is derived from its urgency by the
+        /// [stream scheduler provider](crate::provider::stream_scheduler). Streams which didn't
+        /// join a group share the capacity of the default urgency of 3. Urgencies above 7 are
+        /// treated as 7.
+        #[inline]
+        pub fn set_stream_group_urgency(
+            &mut self,
+            group: $crate::stream::GroupId,
+            urgency: u8,
+        ) -> $crate::connection::Result<()> {
+            self.0.set_stream_group_urgency(group, urgency)
+        }
+
+        /// Sets whether the stream with the given id is sent incrementally or sequentially
+        ///
+        /// Incremental streams share the transmission capacity of their group, or of the streams
+        /// which didn't join a group, in round robin order. This is the default, and suits
+        /// streams which are consumed as they arrive. Non-incremental streams are sent one at a
+        /// time in the order they were queued, and hold back the other streams of their group
+        /// until they have no more data to send. This suits streams which are only useful once
+        /// they are complete, similar to the `incremental` parameter of
+        /// [RFC 9218](https://www.rfc-editor.org/rfc/rfc9218#section-4.2).
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # async fn test() -> Result<(), Box<dyn std::error::Error>> {
+        /// #   let mut connection: s2n_quic::connection::Connection = todo!();
+        /// #
+        /// let first = connection.open_send_stream().await?;
+        /// let second = connection.open_send_stream().await?;
+        ///
+        /// // send the whole first stream before the second stream
+        /// connection.set_stream_incremental(first.id(), false)?;
+        /// connection.set_stream_incremental(second.id(), false)?;
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub fn set_stream_incremental(
+            &mut self,
+            stream_id: u64,
+            incremental: bool,
+        ) -> $crate::stream::Result<()> {
+            use s2n_quic_core::{stream::StreamId, varint::VarInt};
+
+            let stream_id = VarInt::new(stream_id)
+                .map(StreamId::from_varint)
+                .map_err(|_| $crate::stream::Error::invalid_stream())?;
+            self.0.set_stream_incremental(stream_id, incremental)
+        }
+
+        /// Resets all streams of a group with the provided error code
+        ///
+        /// Both directions of each stream are reset, which wakes up any tasks blocked on them.
+        /// Streams which join the group afterwards are reset as well.
+        #[inline]
+        pub fn reset_stream_group(
+            &mut self,
+            group: $crate::stream::GroupId,
+            error_code: $crate::application::Error,
+        ) -> $crate::connection::Result<()> {
+            self.0.reset_stream_group(group, error_code)
+        }
+
         /// Closes the Connection with the provided error code
         ///
         /// This will immediately terminate all outstanding streams.
@@ -378,6 +858,58 @@ macro_rules! impl_handle_api {
             query.into()
         }
 
+        /// API for reading the values attached to the connection
+        ///
+        /// The [`Extensions`](crate::connection::Extensions) are shared by all of the handles to
+        /// the connection, so values inserted by the acceptor of the connection can be read by the
+        /// tasks which serve its streams.
+        ///
+        /// ```ignore
+        /// let tenant = connection
+        ///     .extensions(|extensions| extensions.get::<TenantId>().copied())?;
+        /// ```
+        pub fn extensions<Query, Outcome>(
+            &self,
+            query: Query,
+        ) -> core::result::Result<Outcome, s2n_quic_core::query::Error>
+        where
+            Query: FnOnce(&s2n_quic_core::connection::Extensions) -> Outcome,
+        {
+            use s2n_quic_core::query;
+            let mut query = query::Once::new(query);
+
+            self.0
+                .query_extensions(&mut query)
+                .map_err(|_| query::Error::ConnectionLockPoisoned)?;
+
+            query.into()
+        }
+
+        /// API for modifying the values attached to the connection
+        ///
+        /// Similar to [`Self::extensions`] but provides mutable access to the
+        /// [`Extensions`](crate::connection::Extensions).
+        ///
+        /// ```ignore
+        /// connection.extensions_mut(|extensions| extensions.insert(TenantId(1)))?;
+        /// ```
+        pub fn extensions_mut<Query, Outcome>(
+            &mut self,
+            query: Query,
+        ) -> core::result::Result<Outcome, s2n_quic_core::query::Error>
+        where
+            Query: FnOnce(&mut s2n_quic_core::connection::Extensions) -> Outcome,
+        {
+            use s2n_quic_core::query;
+            let mut query = query::Once::new_mut(query);
+
+            self.0
+                .query_extensions_mut(&mut query)
+                .map_err(|_| query::Error::ConnectionLockPoisoned)?;
+
+            query.into()
+        }
+
         /// API for querying the connection's datagram endpoint.
         ///
         ///  Provides mutable access to `Sender` or `Receiver`.
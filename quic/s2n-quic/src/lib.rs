@@ -50,19 +50,42 @@
 //!
 //! **NOTE**: this will override the platform detection and always use [`s2n-tls`][s2n-tls] by default.
 //!
+//! ## Pure Rust builds
+//!
+//! The [`s2n-tls`][s2n-tls] provider links against a C library, which requires a C toolchain at
+//! build time. Applications which can't build C code can disable the default features and only
+//! enable the [`rustls`][rustls] provider:
+//!
+//! ```toml
+//! [dependencies]
+//! s2n-quic = { version = "1", default-features = false, features = ["provider-address-token-default", "provider-tls-rustls"] }
+//! ```
+//!
+//! This results in a fully functional endpoint which only relies on [`rustls`][rustls] and
+//! [`ring`](https://crates.io/crates/ring) for cryptography. [`provider::tls::default`] will
+//! use the [`rustls`][rustls] provider.
+//!
 //! [s2n-tls]: https://github.com/aws/s2n-tls
 //! [rustls]: https://github.com/rustls/rustls
 
 #[macro_use]
 pub mod provider;
 
+#[cfg(any(test, all(not(docdiff), feature = "unstable-blocking")))]
+pub mod blocking;
 pub mod client;
 pub mod connection;
+#[cfg(any(test, all(not(docdiff), feature = "unstable-multicast")))]
+pub mod multicast;
 pub mod server;
 pub mod stream;
 
 pub mod application {
     pub use s2n_quic_core::application::Error;
+
+    pub mod error {
+        pub use s2n_quic_core::application::error::Range;
+    }
 }
 
 pub use client::Client;
@@ -78,11 +101,22 @@ mod tests;
         // add new unstable features to this list
         any(
             feature = "unstable_client_hello",
+            feature = "unstable-blocking",
+            feature = "unstable-drain-signal",
+            feature = "unstable-hot-upgrade",
+            feature = "unstable-multicast",
             feature = "unstable-provider-datagram",
+            feature = "unstable-provider-io-async-io",
+            feature = "unstable-provider-io-fault",
+            feature = "unstable-provider-io-replay",
             feature = "unstable-provider-io-testing",
             feature = "unstable-provider-packet-interceptor",
             feature = "unstable-provider-random",
+            feature = "unstable-provider-tls-dangerous",
+            feature = "unstable-provider-tls-replay",
             feature = "unstable-provider-congestion-controller",
+            feature = "unstable-state-snapshot",
+            feature = "unstable-stream-compression",
         ),
         // any unstable features requires at least one of the following conditions
         not(any(
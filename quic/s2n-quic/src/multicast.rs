@@ -0,0 +1,330 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sends the same payload to many connections
+//!
+//! A [`Multicaster`] holds the send streams, and optionally the connections, of the subscribers
+//! of a publisher. Each payload is passed to every subscriber as a reference to the same
+//! [`Bytes`] buffer, so fanning out a payload doesn't copy it for each connection.
+//!
+//! Subscribers which fail are removed from the multicaster and returned along with the error,
+//! which allows the application to clean up its state without tracking each send.
+//!
+//! Sending datagrams requires the `unstable-provider-datagram` feature, and the connections need
+//! to use the default datagram provider.
+//!
+//! ```rust,no_run
+//! # async fn test() -> s2n_quic::connection::Result<()> {
+//! #   let mut server: s2n_quic::Server = todo!();
+//! #
+//! use s2n_quic::multicast::Multicaster;
+//!
+//! let mut multicaster = Multicaster::new();
+//!
+//! while let Some(mut connection) = server.accept().await {
+//!     let stream = connection.open_send_stream().await?;
+//!     multicaster.insert_stream(connection.id(), stream);
+//!
+//!     let data = bytes::Bytes::from_static(b"a new subscriber joined");
+//!     for (id, error) in multicaster.send(data).await {
+//!         println!("removed subscriber {}: {}", id, error);
+//!     }
+//! }
+//! #
+//! #   Ok(())
+//! # }
+//! ```
+
+use crate::stream::{Error as StreamError, SendStream};
+#[cfg(any(test, all(not(docdiff), feature = "unstable-provider-datagram")))]
+use crate::{connection::Handle, provider::datagram::default::DatagramError};
+use bytes::Bytes;
+use core::{
+    fmt,
+    task::{Context, Poll},
+};
+#[cfg(any(test, all(not(docdiff), feature = "unstable-provider-datagram")))]
+use s2n_quic_core::query;
+
+/// An error which occurred while sending a payload to a subscriber
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The stream of the subscriber encountered an error
+    Stream(StreamError),
+    /// The datagram couldn't be sent to the subscriber
+    #[cfg(any(test, all(not(docdiff), feature = "unstable-provider-datagram")))]
+    Datagram(DatagramError),
+    /// The datagram sender of the connection couldn't be queried
+    #[cfg(any(test, all(not(docdiff), feature = "unstable-provider-datagram")))]
+    Query(query::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Stream(error) => fmt::Display::fmt(error, f),
+            #[cfg(any(test, all(not(docdiff), feature = "unstable-provider-datagram")))]
+            Self::Datagram(error) => fmt::Display::fmt(error, f),
+            #[cfg(any(test, all(not(docdiff), feature = "unstable-provider-datagram")))]
+            Self::Query(error) => write!(f, "query error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Stream(error) => Some(error),
+            #[cfg(any(test, all(not(docdiff), feature = "unstable-provider-datagram")))]
+            Self::Datagram(_) => None,
+            #[cfg(any(test, all(not(docdiff), feature = "unstable-provider-datagram")))]
+            Self::Query(error) => Some(error),
+        }
+    }
+}
+
+impl From<StreamError> for Error {
+    #[inline]
+    fn from(error: StreamError) -> Self {
+        Self::Stream(error)
+    }
+}
+
+/// The send stream of a subscriber
+#[derive(Debug)]
+struct Subscriber<K> {
+    key: K,
+    stream: SendStream,
+    /// The part of the current payload which wasn't accepted by the stream yet
+    pending: Bytes,
+}
+
+/// Sends the same payload to the streams or connections of many subscribers
+///
+/// Each subscriber is identified by a key which is chosen by the application, such as the
+/// [connection ID](crate::Connection::id), and returned when the subscriber fails.
+#[derive(Debug)]
+pub struct Multicaster<K> {
+    streams: Vec<Subscriber<K>>,
+    /// Subscribers which failed since the last call returned the failures
+    failed: Vec<(K, Error)>,
+    #[cfg(any(test, all(not(docdiff), feature = "unstable-provider-datagram")))]
+    connections: Vec<(K, Handle)>,
+}
+
+impl<K> Default for Multicaster<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Multicaster<K> {
+    /// Creates a multicaster without any subscribers
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            streams: Vec::new(),
+            failed: Vec::new(),
+            #[cfg(any(test, all(not(docdiff), feature = "unstable-provider-datagram")))]
+            connections: Vec::new(),
+        }
+    }
+
+    /// Returns the number of streams which receive the payloads
+    #[inline]
+    pub fn stream_count(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Adds a stream which receives the payloads sent from now on
+    ///
+    /// If a payload is still being sent, the stream only receives the payloads of later calls.
+    #[inline]
+    pub fn insert_stream(&mut self, key: K, stream: SendStream) {
+        self.streams.push(Subscriber {
+            key,
+            stream,
+            pending: Bytes::new(),
+        });
+    }
+
+    /// Removes the stream of a subscriber and returns it
+    ///
+    /// Any part of a payload which the stream didn't accept yet is discarded.
+    pub fn remove_stream(&mut self, key: &K) -> Option<SendStream>
+    where
+        K: PartialEq,
+    {
+        let index = self.streams.iter().position(|s| s.key == *key)?;
+        Some(self.streams.swap_remove(index).stream)
+    }
+
+    /// Sends a payload on all of the streams
+    ///
+    /// The payload is sent concurrently, so a slow subscriber delays the completion of the call
+    /// but not the delivery to the other subscribers.
+    ///
+    /// # Return value
+    ///
+    /// Returns the subscribers whose stream failed, which are removed from the multicaster.
+    ///
+    /// # Cancel safety
+    ///
+    /// If the future is dropped, the streams which didn't accept the payload yet keep it and
+    /// accept it before the payload of the next call, which preserves the order of the payloads
+    /// on every stream.
+    pub async fn send(&mut self, data: Bytes) -> Vec<(K, Error)> {
+        // finish the payload of a cancelled call first
+        futures::future::poll_fn(|cx| self.poll_pending(cx)).await;
+
+        for subscriber in &mut self.streams {
+            subscriber.pending = data.clone();
+        }
+
+        futures::future::poll_fn(|cx| self.poll_pending(cx)).await;
+
+        core::mem::take(&mut self.failed)
+    }
+
+    /// Finishes sending the pending payloads and closes all of the streams
+    ///
+    /// All of the streams are removed from the multicaster. Returns the subscribers whose stream
+    /// failed.
+    pub async fn close(&mut self) -> Vec<(K, Error)> {
+        futures::future::poll_fn(|cx| self.poll_pending(cx)).await;
+
+        futures::future::poll_fn(|cx| {
+            let mut is_pending = false;
+            let mut index = 0;
+
+            while let Some(subscriber) = self.streams.get_mut(index) {
+                match subscriber.stream.poll_close(cx) {
+                    Poll::Ready(Ok(())) => {
+                        self.streams.swap_remove(index);
+                        continue;
+                    }
+                    Poll::Ready(Err(error)) => {
+                        let subscriber = self.streams.swap_remove(index);
+                        self.failed.push((subscriber.key, error.into()));
+                        continue;
+                    }
+                    Poll::Pending => is_pending = true,
+                }
+                index += 1;
+            }
+
+            if is_pending {
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+        .await;
+
+        core::mem::take(&mut self.failed)
+    }
+
+    /// Passes the pending payloads to the streams
+    ///
+    /// Returns `Ready` once every stream accepted its payload or failed.
+    fn poll_pending(&mut self, cx: &mut Context) -> Poll<()> {
+        let mut is_pending = false;
+        let mut index = 0;
+
+        while let Some(subscriber) = self.streams.get_mut(index) {
+            match subscriber.stream.poll_send(&mut subscriber.pending, cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(error)) => {
+                    let subscriber = self.streams.swap_remove(index);
+                    self.failed.push((subscriber.key, error.into()));
+                    continue;
+                }
+                Poll::Pending => is_pending = true,
+            }
+            index += 1;
+        }
+
+        if is_pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    }
+}
+
+#[cfg(any(test, all(not(docdiff), feature = "unstable-provider-datagram")))]
+impl<K> Multicaster<K> {
+    /// Returns the number of connections which receive the datagrams
+    #[inline]
+    pub fn connection_count(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Adds a connection which receives the datagrams sent from now on
+    ///
+    /// The connection needs to use the default datagram provider.
+    #[inline]
+    pub fn insert_connection(&mut self, key: K, handle: Handle) {
+        self.connections.push((key, handle));
+    }
+
+    /// Removes the connection of a subscriber and returns its handle
+    pub fn remove_connection(&mut self, key: &K) -> Option<Handle>
+    where
+        K: PartialEq,
+    {
+        let index = self.connections.iter().position(|(k, _)| k == key)?;
+        Some(self.connections.swap_remove(index).1)
+    }
+
+    /// Enqueues a datagram for sending it on all of the connections
+    ///
+    /// Datagrams are unreliable, so a connection whose send queue is full or whose peer doesn't
+    /// accept datagrams of this size only misses this datagram and is kept.
+    ///
+    /// # Return value
+    ///
+    /// Returns the subscribers which didn't receive the datagram. Connections which were
+    /// closed or can't be queried for the datagram sender are removed from the multicaster.
+    pub fn send_datagram(&mut self, data: Bytes) -> Vec<(K, Error)>
+    where
+        K: Clone,
+    {
+        use crate::provider::datagram::default::Sender;
+
+        let mut failed = Vec::new();
+        let mut index = 0;
+
+        while let Some((key, handle)) = self.connections.get_mut(index) {
+            let error = match handle
+                .datagram_mut(|sender: &mut Sender| sender.send_datagram(data.clone()))
+            {
+                Ok(Ok(())) => {
+                    index += 1;
+                    continue;
+                }
+                Ok(Err(error @ DatagramError::ConnectionError { .. })) => {
+                    Err(Error::Datagram(error))
+                }
+                Ok(Err(error)) => Ok(Error::Datagram(error)),
+                Err(error) => Err(Error::Query(error)),
+            };
+
+            match error {
+                // the connection only missed this datagram
+                Ok(error) => {
+                    failed.push((key.clone(), error));
+                    index += 1;
+                }
+                // the connection can't receive any datagrams
+                Err(error) => {
+                    let (key, _) = self.connections.swap_remove(index);
+                    failed.push((key, error));
+                }
+            }
+        }
+
+        failed
+    }
+}
@@ -7,13 +7,20 @@ use core::fmt;
 #[macro_use]
 mod macros;
 
+pub mod ack;
 pub mod address_token;
 pub mod connection_id;
 pub mod endpoint_limits;
 pub mod event;
+pub mod extension_frame;
 pub mod io;
 pub mod limits;
+pub mod mtu;
+pub mod protocol_violation;
+pub mod retry_offload;
 pub mod stateless_reset_token;
+pub mod stream_scheduler;
+pub mod tenant;
 pub mod tls;
 
 // These providers are not currently exposed to applications
@@ -0,0 +1,32 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides the strategy used to acknowledge packets on each connection
+//!
+//! By default, every connection uses the `max_ack_delay` configured on the connection limits
+//! and sends an ACK frame after at most 10 packets. A [`Strategy`] applies the same policy
+//! to every connection, e.g. [`Strategy::HIGH_THROUGHPUT`] to reduce the ACK overhead or
+//! [`Strategy::LOW_LATENCY`] to acknowledge every packet immediately, while a custom
+//! [`Endpoint`] can choose a strategy for each connection.
+
+pub use s2n_quic_core::ack::strategy::{default, Builder, ConnectionInfo, Endpoint, Strategy};
+
+pub trait Provider {
+    type Endpoint: 'static + Send + Endpoint;
+    type Error: 'static + core::fmt::Display;
+
+    fn start(self) -> Result<Self::Endpoint, Self::Error>;
+}
+
+impl_provider_utils!();
+
+pub type Default = default::Endpoint;
+
+impl<T: 'static + Send + Endpoint> Provider for T {
+    type Endpoint = T;
+    type Error = core::convert::Infallible;
+
+    fn start(self) -> Result<Self::Endpoint, Self::Error> {
+        Ok(self)
+    }
+}
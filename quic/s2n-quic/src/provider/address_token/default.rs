@@ -6,6 +6,11 @@
 //! The default provider will randomly generate a 256 bit key. This key will be used to sign and
 //! verify tokens. The key can be rotated at a duration set by the user.
 //!
+//! Servers which need to accept the tokens issued by each other, e.g. the front-ends and
+//! back-ends of a [retry offload](crate::provider::retry_offload) deployment, can instead derive
+//! the keys from a shared secret with [`Provider::with_shared_secret`]. The keys are then rotated
+//! at fixed intervals of the system time, so the clocks of the servers need to be synchronized.
+//!
 //! The default provider does not support tokens delivered in a NEW_TOKEN frame.
 
 use core::{mem::size_of, time::Duration};
@@ -15,7 +20,10 @@ use s2n_codec::{DecoderBuffer, DecoderBufferMut};
 use s2n_quic_core::{
     connection, event::api::SocketAddress, random, time::Timestamp, token::Source,
 };
-use std::hash::{Hash, Hasher};
+use std::{
+    hash::{Hash, Hasher},
+    time::{SystemTime, UNIX_EPOCH},
+};
 use zerocopy::{AsBytes, FromBytes, Unaligned};
 use zeroize::Zeroizing;
 
@@ -25,6 +33,9 @@ struct BaseKey {
     // HMAC key for signing and verifying
     key: Option<(Timestamp, hmac::Key)>,
 
+    // The epoch of the key, if it was derived from a shared secret
+    epoch: Option<u64>,
+
     //= https://www.rfc-editor.org/rfc/rfc9000#section-8.1.4
     //# To protect against such attacks, servers MUST ensure that
     //# replay of tokens is prevented or limited.
@@ -36,6 +47,7 @@ impl BaseKey {
         Self {
             active_duration,
             key: None,
+            epoch: None,
             duplicate_filter: cuckoofilter::CuckooFilter::with_capacity(
                 cuckoofilter::DEFAULT_CAPACITY,
             ),
@@ -77,11 +89,30 @@ impl BaseKey {
 
         self.key.as_ref().map(|key| key.1.clone())
     }
+
+    /// Returns a hasher with the key which is derived from the secret for the epoch
+    pub fn shared_hasher(&mut self, secret: &hmac::Key, epoch: u64) -> Option<hmac::Context> {
+        if self.epoch != Some(epoch) {
+            let now = s2n_quic_platform::time::now();
+            let key_material = hmac::sign(secret, &epoch.to_be_bytes());
+            let key = hmac::Key::new(hmac::HMAC_SHA256, key_material.as_ref());
+
+            // tokens from previous epochs can't be validated anymore
+            self.duplicate_filter =
+                cuckoofilter::CuckooFilter::with_capacity(cuckoofilter::DEFAULT_CAPACITY);
+
+            self.key = Some((now, key));
+            self.epoch = Some(epoch);
+        }
+
+        let (_, key) = self.key.as_ref()?;
+        Some(hmac::Context::with_key(key))
+    }
 }
 
 const DEFAULT_KEY_ROTATION_PERIOD: Duration = Duration::from_millis(1000);
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Provider {
     //= https://www.rfc-editor.org/rfc/rfc9000#section-8.1.3
     //# Thus, a token SHOULD have an
@@ -91,16 +122,43 @@ pub struct Provider {
     /// To fulfill this SHOULD, we rotate the key periodically. This allows
     /// customers to control the token lifetime without adding bytes to the token itself.
     key_rotation_period: Duration,
+
+    /// The secret from which the keys are derived, if they are shared with other servers
+    shared_secret: Option<hmac::Key>,
+
+    replay_detection: bool,
 }
 
 impl Default for Provider {
     fn default() -> Self {
         Self {
             key_rotation_period: DEFAULT_KEY_ROTATION_PERIOD,
+            shared_secret: None,
+            replay_detection: true,
         }
     }
 }
 
+impl Provider {
+    /// Derives the keys from a secret which is shared with other servers
+    ///
+    /// Every server configured with the same secret accepts the tokens issued by the others.
+    pub fn with_shared_secret(mut self, secret: &[u8]) -> Self {
+        self.shared_secret = Some(hmac::Key::new(hmac::HMAC_SHA256, secret));
+        self
+    }
+
+    /// Sets whether tokens are only accepted once, which is enabled by default
+    ///
+    /// The front-ends of a retry offload deployment validate each Initial packet which carries
+    /// a token, including the retransmissions of the client, so they need to disable the
+    /// detection. Replayed tokens are still detected by the back-ends.
+    pub fn with_replay_detection(mut self, enabled: bool) -> Self {
+        self.replay_detection = enabled;
+        self
+    }
+}
+
 impl super::Provider for Provider {
     type Format = Format;
     type Error = core::convert::Infallible;
@@ -116,6 +174,8 @@ impl super::Provider for Provider {
                 BaseKey::new(self.key_rotation_period * 2),
                 BaseKey::new(self.key_rotation_period * 2),
             ],
+            shared_secret: self.shared_secret,
+            replay_detection: self.replay_detection,
         };
 
         Ok(format)
@@ -143,10 +203,20 @@ pub struct Format {
 
     /// Key used to sign keys
     keys: [BaseKey; 2],
+
+    /// The secret from which the keys are derived, if they are shared with other servers
+    shared_secret: Option<hmac::Key>,
+
+    /// Whether tokens are only accepted once
+    replay_detection: bool,
 }
 
 impl Format {
     fn current_key(&mut self) -> u8 {
+        if self.shared_secret.is_some() {
+            return (self.shared_epoch() & 1) as u8;
+        }
+
         let now = s2n_quic_platform::time::now();
         if now > self.current_key_rotates_at {
             self.current_key ^= 1;
@@ -158,6 +228,32 @@ impl Format {
         self.current_key
     }
 
+    /// Returns the number of rotation periods since the UNIX epoch
+    ///
+    /// All of the servers sharing a secret agree on the epoch as long as their clocks are
+    /// synchronized.
+    fn shared_epoch(&self) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        (now.as_nanos() / self.key_rotation_period.as_nanos().max(1)) as u64
+    }
+
+    fn hasher(&mut self, key_id: u8, random: &mut dyn random::Generator) -> Option<hmac::Context> {
+        if let Some(secret) = self.shared_secret.as_ref() {
+            let epoch = self.shared_epoch();
+            // the key was either used in the current epoch or in the previous one
+            let epoch = if epoch & 1 == key_id as u64 {
+                epoch
+            } else {
+                epoch.checked_sub(1)?
+            };
+            return self.keys[key_id as usize].shared_hasher(secret, epoch);
+        }
+
+        self.keys[key_id as usize].hasher(random)
+    }
+
     // Retry Tokens need to include the original destination connection id from the transport
     // parameters. This OCID is included in the tag.
     fn tag_retry_token(
@@ -165,7 +261,7 @@ impl Format {
         token: &Token,
         context: &mut super::Context<'_>,
     ) -> Option<hmac::Tag> {
-        let mut ctx = self.keys[token.header.key_id() as usize].hasher(context.random)?;
+        let mut ctx = self.hasher(token.header.key_id(), context.random)?;
 
         //= https://www.rfc-editor.org/rfc/rfc9000#section-8.1.4
         //# Tokens
@@ -199,9 +295,10 @@ impl Format {
         context: &mut super::Context<'_>,
         token: &Token,
     ) -> Option<connection::InitialId> {
-        if self.keys[token.header.key_id() as usize]
-            .duplicate_filter
-            .contains(token)
+        if self.replay_detection
+            && self.keys[token.header.key_id() as usize]
+                .duplicate_filter
+                .contains(token)
         {
             return None;
         }
@@ -214,9 +311,11 @@ impl Format {
 
             // Ignore the outcome of adding a token to the filter because we always want to
             // continue the connection if the filter fails.
-            let _ = self.keys[token.header.key_id() as usize]
-                .duplicate_filter
-                .add(token);
+            if self.replay_detection {
+                let _ = self.keys[token.header.key_id() as usize]
+                    .duplicate_filter
+                    .add(token);
+            }
 
             return token.original_destination_connection_id();
         }
@@ -481,6 +580,8 @@ mod tests {
             ],
             current_key_rotates_at: time::now(),
             current_key: 0,
+            shared_secret: None,
+            replay_detection: true,
         }
     }
 
@@ -758,4 +859,42 @@ mod tests {
                 assert!(format.validate_token(&mut context, token).is_none())
             });
     }
+
+    #[test]
+    fn test_shared_secret() {
+        use crate::provider::address_token::Provider as _;
+
+        let provider = Provider::default().with_shared_secret(b"secret");
+        let mut front_end = provider
+            .clone()
+            .with_replay_detection(false)
+            .start()
+            .unwrap();
+        let mut back_end = provider.start().unwrap();
+        let mut other = Provider::default()
+            .with_shared_secret(b"other secret")
+            .start()
+            .unwrap();
+
+        let conn_id = connection::PeerId::TEST_ID;
+        let odcid = connection::InitialId::try_from_bytes(&[0, 1, 2, 3, 4, 5, 6, 7]).unwrap();
+        let addr = SocketAddress::default();
+        let mut buf = [0; Format::TOKEN_LEN];
+        let mut random = random::testing::Generator(5);
+        let mut context = Context::new(&addr, &conn_id, &mut random);
+        front_end
+            .generate_retry_token(&mut context, &odcid, &mut buf)
+            .unwrap();
+
+        // The front-end accepts the retransmissions of the client
+        assert_eq!(front_end.validate_token(&mut context, &buf), Some(odcid));
+        assert_eq!(front_end.validate_token(&mut context, &buf), Some(odcid));
+
+        // Servers with a different secret don't accept the token
+        assert!(other.validate_token(&mut context, &buf).is_none());
+
+        // The back-end accepts the token once
+        assert_eq!(back_end.validate_token(&mut context, &buf), Some(odcid));
+        assert!(back_end.validate_token(&mut context, &buf).is_none());
+    }
 }
@@ -31,12 +31,15 @@ pub mod default {
         time::Duration,
     };
     use rand::prelude::*;
-    use s2n_quic_core::connection::{
-        self,
-        id::{ConnectionInfo, Generator, Validator},
+    use s2n_quic_core::{
+        connection::{
+            self,
+            id::{ConnectionInfo, Generator, Validator},
+        },
+        random,
     };
 
-    #[derive(Debug, Default)]
+    #[derive(Clone, Debug, Default)]
     pub struct Provider(Format);
 
     impl super::Provider for Provider {
@@ -51,12 +54,18 @@ pub mod default {
     /// 16 bytes should be big enough for a randomly generated Id
     const DEFAULT_LEN: usize = 16;
 
+    /// The minimum number of random bytes in each non-empty connection Id
+    pub const MIN_RANDOM_LEN: usize = connection::id::MIN_NON_EMPTY_LOCAL_LEN;
+
     /// Randomly generated connection Id format.
     ///
-    /// By default, connection Ids of length 16 bytes are generated.
-    #[derive(Debug)]
+    /// By default, connection Ids of length 16 bytes are generated. The random bytes are drawn
+    /// from the random provider of the endpoint, so a seeded random provider also makes the
+    /// connection Ids deterministic.
+    #[derive(Clone, Debug)]
     pub struct Format {
         len: usize,
+        prefix: Vec<u8>,
         lifetime: Option<Duration>,
     }
 
@@ -64,6 +73,7 @@ pub mod default {
         fn default() -> Self {
             Self {
                 len: DEFAULT_LEN,
+                prefix: Vec::new(),
                 lifetime: None,
             }
         }
@@ -80,6 +90,7 @@ pub mod default {
     #[derive(Debug)]
     pub struct Builder {
         len: usize,
+        prefix: Vec<u8>,
         lifetime: Option<Duration>,
     }
 
@@ -87,6 +98,7 @@ pub mod default {
         fn default() -> Self {
             Self {
                 len: DEFAULT_LEN,
+                prefix: Vec::new(),
                 lifetime: None,
             }
         }
@@ -94,14 +106,47 @@ pub mod default {
 
     impl Builder {
         /// Sets the length of the generated connection Id
+        ///
+        /// The length must be between [`connection::id::MIN_NON_EMPTY_LOCAL_LEN`] and
+        /// [`connection::id::MAX_LEN`], and leave room for at least [`MIN_RANDOM_LEN`] bytes
+        /// after the prefix.
+        ///
+        /// A length of 0 configures zero-length connection Ids, which are only supported by
+        /// clients. Packets for these connections are routed by the address of the server, so
+        /// zero-length connection Ids should only be used if the local address of the client
+        /// is stable, and only a single connection can be opened to each server address.
         pub fn with_len(mut self, len: usize) -> Result<Self, connection::id::Error> {
-            if !(connection::LocalId::MIN_LEN..=connection::id::MAX_LEN).contains(&len) {
+            let is_valid = if len == 0 {
+                self.prefix.is_empty()
+            } else {
+                (connection::id::MIN_NON_EMPTY_LOCAL_LEN..=connection::id::MAX_LEN).contains(&len)
+                    && len >= self.prefix.len() + MIN_RANDOM_LEN
+            };
+            if !is_valid {
                 return Err(connection::id::Error::InvalidLength);
             }
             self.len = len;
             Ok(self)
         }
 
+        /// Sets a fixed prefix for each generated connection Id
+        ///
+        /// The prefix can be used by load balancers to route packets to the endpoint which issued
+        /// the connection Id. The remaining bytes of each connection Id are random, of which there
+        /// must be at least [`MIN_RANDOM_LEN`], so the length should be set before the prefix.
+        ///
+        /// **NOTE**: The prefix can be used by observers to link connections to the same endpoint.
+        /// Sharded servers also route packets by the first
+        /// [`connection::id::shard::KEY_LEN`] bytes of the connection Id, which therefore
+        /// can't all be part of the prefix.
+        pub fn with_prefix(mut self, prefix: &[u8]) -> Result<Self, connection::id::Error> {
+            if self.len == 0 || prefix.len() + MIN_RANDOM_LEN > self.len {
+                return Err(connection::id::Error::InvalidLength);
+            }
+            self.prefix = prefix.to_vec();
+            Ok(self)
+        }
+
         /// Sets the lifetime of each generated connection Id
         pub fn with_lifetime(mut self, lifetime: Duration) -> Result<Self, connection::id::Error> {
             if !(connection::id::MIN_LIFETIME..=connection::id::MAX_LIFETIME).contains(&lifetime) {
@@ -115,18 +160,35 @@ pub mod default {
         pub fn build(self) -> Result<Format, core::convert::Infallible> {
             Ok(Format {
                 len: self.len,
+                prefix: self.prefix,
                 lifetime: self.lifetime,
             })
         }
     }
 
-    impl Generator for Format {
-        fn generate(&mut self, _connection_info: &ConnectionInfo) -> connection::LocalId {
+    impl Format {
+        fn generate_with(&self, fill: impl FnOnce(&mut [u8])) -> connection::LocalId {
             let mut id = [0u8; connection::id::MAX_LEN];
             let id = &mut id[..self.len];
-            rand::thread_rng().fill_bytes(id);
+            let (prefix, random) = id.split_at_mut(self.prefix.len());
+            prefix.copy_from_slice(&self.prefix);
+            fill(random);
             (&*id).try_into().expect("length already checked")
         }
+    }
+
+    impl Generator for Format {
+        fn generate(&mut self, _connection_info: &ConnectionInfo) -> connection::LocalId {
+            self.generate_with(|random| rand::thread_rng().fill_bytes(random))
+        }
+
+        fn generate_with_random(
+            &mut self,
+            _connection_info: &ConnectionInfo,
+            random_generator: &mut dyn random::Generator,
+        ) -> connection::LocalId {
+            self.generate_with(|random| random_generator.public_random_fill(random))
+        }
 
         fn lifetime(&self) -> Option<Duration> {
             self.lifetime
@@ -152,7 +214,7 @@ pub mod default {
             let remote_address = &s2n_quic_core::inet::SocketAddress::default();
             let connection_info = ConnectionInfo::new(remote_address);
 
-            for len in connection::LocalId::MIN_LEN..connection::id::MAX_LEN {
+            for len in connection::id::MIN_NON_EMPTY_LOCAL_LEN..connection::id::MAX_LEN {
                 let mut format = Format::builder().with_len(len).unwrap().build().unwrap();
 
                 let id = format.generate(&connection_info);
@@ -178,10 +240,19 @@ pub mod default {
             assert_eq!(
                 Some(connection::id::Error::InvalidLength),
                 Format::builder()
-                    .with_len(connection::LocalId::MIN_LEN - 1)
+                    .with_len(connection::id::MIN_NON_EMPTY_LOCAL_LEN - 1)
                     .err()
             );
 
+            //= https://www.rfc-editor.org/rfc/rfc9000#section-5.1
+            //= type=test
+            //# A zero-length connection ID can be used when a connection ID is not
+            //# needed to route to the correct endpoint.
+            let mut format = Format::builder().with_len(0).unwrap().build().unwrap();
+            let id = format.generate(&connection_info);
+            assert!(id.is_empty());
+            assert_eq!(format.validate(&connection_info, &[1, 2, 3]), Some(0));
+
             let lifetime = Duration::from_secs(1000);
             let format = Format::builder()
                 .with_lifetime(lifetime)
@@ -204,5 +275,88 @@ pub mod default {
                     .err()
             );
         }
+
+        #[test]
+        fn prefix_test() {
+            let remote_address = &s2n_quic_core::inet::SocketAddress::default();
+            let connection_info = ConnectionInfo::new(remote_address);
+
+            let mut format = Format::builder()
+                .with_len(8)
+                .unwrap()
+                .with_prefix(&[1, 2, 3, 4])
+                .unwrap()
+                .build()
+                .unwrap();
+
+            let a = format.generate(&connection_info);
+            let b = format.generate(&connection_info);
+            assert_eq!(a.len(), 8);
+            assert_eq!(&a.as_bytes()[..4], &[1, 2, 3, 4]);
+            assert_eq!(&b.as_bytes()[..4], &[1, 2, 3, 4]);
+            assert_ne!(a, b);
+            assert_eq!(format.validate(&connection_info, a.as_ref()), Some(8));
+
+            // the prefix must leave room for the random bytes
+            assert_eq!(
+                Some(connection::id::Error::InvalidLength),
+                Format::builder()
+                    .with_len(8)
+                    .unwrap()
+                    .with_prefix(&[1, 2, 3, 4, 5])
+                    .err()
+            );
+            assert_eq!(
+                Some(connection::id::Error::InvalidLength),
+                Format::builder()
+                    .with_prefix(&[1, 2, 3, 4])
+                    .unwrap()
+                    .with_len(7)
+                    .err()
+            );
+
+            // zero-length connection Ids can't have a prefix
+            assert_eq!(
+                Some(connection::id::Error::InvalidLength),
+                Format::builder()
+                    .with_len(0)
+                    .unwrap()
+                    .with_prefix(&[1])
+                    .err()
+            );
+            assert_eq!(
+                Some(connection::id::Error::InvalidLength),
+                Format::builder()
+                    .with_prefix(&[1])
+                    .unwrap()
+                    .with_len(0)
+                    .err()
+            );
+        }
+
+        #[test]
+        fn random_generator_test() {
+            use crate::provider::random::seeded::Generator as Seeded;
+
+            let remote_address = &s2n_quic_core::inet::SocketAddress::default();
+            let connection_info = ConnectionInfo::new(remote_address);
+            let mut format = Format::default();
+
+            let mut a = Seeded::new(123);
+            let mut b = Seeded::new(123);
+
+            for _ in 0..10 {
+                assert_eq!(
+                    format.generate_with_random(&connection_info, &mut a),
+                    format.generate_with_random(&connection_info, &mut b)
+                );
+            }
+
+            let mut c = Seeded::new(456);
+            assert_ne!(
+                format.generate_with_random(&connection_info, &mut a),
+                format.generate_with_random(&connection_info, &mut c)
+            );
+        }
     }
 }
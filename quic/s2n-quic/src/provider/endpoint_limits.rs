@@ -4,7 +4,7 @@
 //! Allows applications to limit peer's ability to open new connections
 
 pub use s2n_quic_core::endpoint::{
-    limits::{ConnectionAttempt, Outcome},
+    limits::{ConnectionAttempt, Outcome, TransmitFairness},
     Limiter,
 };
 use s2n_quic_core::{event::Timestamp, path::THROTTLED_PORTS_LEN};
@@ -180,6 +180,9 @@ pub mod default {
     #[derive(Default)]
     pub struct Builder {
         max_inflight_handshake_limit: Option<usize>,
+        rx_backlog_limit: Option<usize>,
+        unsent_bytes_limit: Option<usize>,
+        transmit_fairness: TransmitFairness,
     }
 
     impl Builder {
@@ -189,10 +192,51 @@ pub mod default {
             Ok(self)
         }
 
+        /// Sets the limit on received datagrams waiting to be processed, after which new
+        /// connection attempts are dropped
+        pub fn with_rx_backlog_limit(mut self, limit: usize) -> Result<Self, Infallible> {
+            self.rx_backlog_limit = Some(limit);
+            Ok(self)
+        }
+
+        /// Sets the limit on bytes waiting to be sent by the IO provider, after which new
+        /// connection attempts are deferred with Retry packets
+        pub fn with_unsent_bytes_limit(mut self, limit: usize) -> Result<Self, Infallible> {
+            self.unsent_bytes_limit = Some(limit);
+            Ok(self)
+        }
+
+        /// Sets how the transmission queue is shared between the connections
+        ///
+        /// By default, each connection transmits as much as it can in its turn. Round robin
+        /// fairness prevents a bulk transfer from delaying the packets of the other connections.
+        ///
+        /// ```rust
+        /// use s2n_quic::provider::endpoint_limits::{self, TransmitFairness};
+        /// # use std::error::Error;
+        /// # fn main() -> Result<(), Box<dyn Error>> {
+        /// let limits = endpoint_limits::Default::builder()
+        ///     .with_transmit_fairness(TransmitFairness::round_robin(16 * 1024))?
+        ///     .build()?;
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        pub fn with_transmit_fairness(
+            mut self,
+            fairness: TransmitFairness,
+        ) -> Result<Self, Infallible> {
+            self.transmit_fairness = fairness;
+            Ok(self)
+        }
+
         /// Build the limits
         pub fn build(self) -> Result<Limits, Infallible> {
             Ok(Limits {
                 max_inflight_handshake_limit: self.max_inflight_handshake_limit,
+                rx_backlog_limit: self.rx_backlog_limit,
+                unsent_bytes_limit: self.unsent_bytes_limit,
+                transmit_fairness: self.transmit_fairness,
                 rate_limiter: [BasicRateLimiter::default(); THROTTLED_PORTS_LEN],
             })
         }
@@ -202,6 +246,13 @@ pub mod default {
     pub struct Limits {
         /// Maximum number of handshakes to allow before Retry packets are queued
         max_inflight_handshake_limit: Option<usize>,
+        /// Maximum number of received datagrams waiting to be processed before connection
+        /// attempts are dropped
+        rx_backlog_limit: Option<usize>,
+        /// Maximum number of bytes waiting to be sent before Retry packets are queued
+        unsent_bytes_limit: Option<usize>,
+        /// How the transmission queue is shared between the connections
+        transmit_fairness: TransmitFairness,
         rate_limiter: [BasicRateLimiter; THROTTLED_PORTS_LEN],
     }
 
@@ -227,14 +278,34 @@ pub mod default {
                 }
             }
 
+            // the endpoint is falling behind on processing datagrams, so shed new connections
+            // as cheaply as possible
+            if let Some(limit) = self.rx_backlog_limit {
+                if info.rx_backlog > limit {
+                    return Outcome::drop();
+                }
+            }
+
             if let Some(limit) = self.max_inflight_handshake_limit {
                 if info.inflight_handshakes >= limit {
                     return Outcome::retry();
                 }
             }
 
+            // a Retry packet is much smaller than the server's handshake flight
+            if let Some(limit) = self.unsent_bytes_limit {
+                if info.unsent_bytes > limit {
+                    return Outcome::retry();
+                }
+            }
+
             Outcome::allow()
         }
+
+        #[inline]
+        fn transmit_fairness(&self) -> TransmitFairness {
+            self.transmit_fairness
+        }
     }
 
     /// Default limit values are as non-intrusive as possible
@@ -242,6 +313,9 @@ pub mod default {
         fn default() -> Self {
             Self {
                 max_inflight_handshake_limit: None,
+                rx_backlog_limit: None,
+                unsent_bytes_limit: None,
+                transmit_fairness: TransmitFairness::disabled(),
                 rate_limiter: [BasicRateLimiter::default(); THROTTLED_PORTS_LEN],
             }
         }
@@ -257,6 +331,39 @@ pub mod default {
         assert_eq!(elp.max_inflight_handshake_limit, Some(100));
     }
 
+    #[test]
+    fn load_shedding_test() {
+        use s2n_quic_core::{
+            event::IntoEvent,
+            inet::SocketAddress,
+            time::{testing::Clock as MockClock, Clock},
+        };
+
+        let mut remote_address = SocketAddress::default();
+        // port 0 is blocked
+        remote_address.set_port(443);
+        let mock_clock = MockClock::default();
+        let mut limits = Limits::builder()
+            .with_rx_backlog_limit(10)
+            .unwrap()
+            .with_unsent_bytes_limit(1000)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut attempt = |rx_backlog, unsent_bytes| {
+            let info =
+                ConnectionAttempt::new(0, 0, &remote_address, mock_clock.get_time().into_event())
+                    .with_queue_depths(rx_backlog, unsent_bytes);
+            limits.on_connection_attempt(&info)
+        };
+
+        assert_eq!(attempt(10, 1000), Outcome::allow());
+        assert_eq!(attempt(10, 1001), Outcome::retry());
+        assert_eq!(attempt(11, 0), Outcome::drop());
+        assert_eq!(attempt(11, 1001), Outcome::drop());
+    }
+
     #[test]
     fn blocked_port_connection_attempt() {
         use s2n_quic_core::{
@@ -3,7 +3,7 @@
 
 use crate::provider::event::{ConnectionInfo, ConnectionMeta};
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Provider;
 
 impl super::Provider for Provider {
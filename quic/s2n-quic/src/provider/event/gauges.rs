@@ -0,0 +1,285 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Gauges of the connections which are currently open on an endpoint
+//!
+//! The [`Subscriber`] counts the open connections, labeled by their negotiated application
+//! protocol and the server name requested by the client, along with the number of handshakes in
+//! flight. Applications can periodically export a [`Snapshot`] to their metrics system, e.g. for
+//! capacity planning.
+//!
+//! Clones of the subscriber share the same gauges, so a single subscriber can be used for each
+//! shard of a server. The subscriber can be combined with other subscribers with a tuple, e.g.
+//! `(gauges, tracing)`.
+//!
+//! ```rust,no_run
+//! # use std::error::Error;
+//! use s2n_quic::{provider::event, Server};
+//! #
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn Error>> {
+//! let gauges = event::gauges::Subscriber::default();
+//!
+//! let server = Server::builder()
+//!     .with_event(gauges.clone())?
+//!     .start()?;
+//!
+//! let snapshot = gauges.snapshot();
+//! println!("open connections: {}", snapshot.connections);
+//! # let _ = server;
+//! #    Ok(())
+//! # }
+//! ```
+
+use super::{events, ConnectionInfo, ConnectionMeta};
+use bytes::Bytes;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+/// The default number of server names which are tracked individually
+const DEFAULT_MAX_SERVER_NAMES: usize = 64;
+
+/// The state of the gauges at a point in time
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    /// The number of open connections
+    pub connections: u64,
+
+    /// The number of connections which haven't completed the handshake yet
+    pub handshakes: u64,
+
+    /// The number of open connections for each negotiated application protocol
+    pub application_protocols: BTreeMap<Bytes, u64>,
+
+    /// The number of open connections for each server name bucket
+    pub server_names: BTreeMap<String, u64>,
+
+    /// The number of open connections for server names which didn't fit in
+    /// [`Subscriber::with_max_server_names`]
+    pub other_server_names: u64,
+}
+
+/// Counts the connections which are currently open on an endpoint
+#[derive(Clone)]
+pub struct Subscriber {
+    gauges: Arc<Mutex<Snapshot>>,
+    server_name_bucket: fn(&str) -> &str,
+    max_server_names: usize,
+}
+
+impl Default for Subscriber {
+    fn default() -> Self {
+        Self {
+            gauges: Default::default(),
+            server_name_bucket: |server_name| server_name,
+            max_server_names: DEFAULT_MAX_SERVER_NAMES,
+        }
+    }
+}
+
+impl core::fmt::Debug for Subscriber {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Subscriber")
+            .field("gauges", &self.gauges)
+            .field("max_server_names", &self.max_server_names)
+            .finish()
+    }
+}
+
+impl Subscriber {
+    /// Sets the function which maps a server name to the bucket it is counted in
+    ///
+    /// Server names are counted individually by default. Grouping them bounds the number of
+    /// labels exported to the metrics system.
+    ///
+    /// ```rust
+    /// use s2n_quic::provider::event::gauges::Subscriber;
+    ///
+    /// // count `a.example.com` and `b.example.com` as `example.com`
+    /// let gauges = Subscriber::default().with_server_name_bucket(|server_name| {
+    ///     server_name
+    ///         .split_once('.')
+    ///         .map_or(server_name, |(_, parent)| parent)
+    /// });
+    /// # let _ = gauges;
+    /// ```
+    pub fn with_server_name_bucket(mut self, bucket: fn(&str) -> &str) -> Self {
+        self.server_name_bucket = bucket;
+        self
+    }
+
+    /// Sets the maximum number of server name buckets which are counted individually
+    ///
+    /// Connections for any additional buckets are counted in [`Snapshot::other_server_names`].
+    /// The default is 64.
+    pub fn with_max_server_names(mut self, max: usize) -> Self {
+        self.max_server_names = max;
+        self
+    }
+
+    /// Returns the current state of the gauges
+    pub fn snapshot(&self) -> Snapshot {
+        self.gauges.lock().unwrap().clone()
+    }
+}
+
+/// The labels a connection was counted with
+#[derive(Debug)]
+pub struct Connection {
+    gauges: Arc<Mutex<Snapshot>>,
+    is_open: bool,
+    is_handshaking: bool,
+    application_protocol: Option<Bytes>,
+    server_name: Option<ServerName>,
+}
+
+#[derive(Debug)]
+enum ServerName {
+    Bucket(String),
+    Other,
+}
+
+impl Connection {
+    fn on_handshake_complete(&mut self) {
+        if core::mem::take(&mut self.is_handshaking) {
+            self.gauges.lock().unwrap().handshakes -= 1;
+        }
+    }
+
+    /// Removes the connection from the gauges
+    fn release(&mut self) {
+        if !core::mem::take(&mut self.is_open) {
+            return;
+        }
+
+        let mut gauges = self.gauges.lock().unwrap();
+        let gauges = &mut *gauges;
+
+        gauges.connections -= 1;
+
+        if core::mem::take(&mut self.is_handshaking) {
+            gauges.handshakes -= 1;
+        }
+
+        if let Some(application_protocol) = self.application_protocol.take() {
+            decrement(&mut gauges.application_protocols, application_protocol);
+        }
+
+        match self.server_name.take() {
+            Some(ServerName::Bucket(bucket)) => decrement(&mut gauges.server_names, bucket),
+            Some(ServerName::Other) => gauges.other_server_names -= 1,
+            None => {}
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Decrements the count of the label, removing it once no connections are left
+fn decrement<K: Ord>(counts: &mut BTreeMap<K, u64>, label: K) {
+    if let Some(count) = counts.get_mut(&label) {
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(&label);
+        }
+    }
+}
+
+impl super::Subscriber for Subscriber {
+    type ConnectionContext = Connection;
+
+    fn create_connection_context(
+        &mut self,
+        _meta: &ConnectionMeta,
+        _info: &ConnectionInfo,
+    ) -> Self::ConnectionContext {
+        let mut gauges = self.gauges.lock().unwrap();
+        gauges.connections += 1;
+        gauges.handshakes += 1;
+
+        Connection {
+            gauges: self.gauges.clone(),
+            is_open: true,
+            is_handshaking: true,
+            application_protocol: None,
+            server_name: None,
+        }
+    }
+
+    fn on_application_protocol_information(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &ConnectionMeta,
+        event: &events::ApplicationProtocolInformation,
+    ) {
+        if !context.is_open || context.application_protocol.is_some() {
+            return;
+        }
+
+        let application_protocol = Bytes::copy_from_slice(event.chosen_application_protocol);
+        *self
+            .gauges
+            .lock()
+            .unwrap()
+            .application_protocols
+            .entry(application_protocol.clone())
+            .or_default() += 1;
+        context.application_protocol = Some(application_protocol);
+    }
+
+    fn on_server_name_information(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &ConnectionMeta,
+        event: &events::ServerNameInformation,
+    ) {
+        if !context.is_open || context.server_name.is_some() {
+            return;
+        }
+
+        let bucket = (self.server_name_bucket)(event.chosen_server_name);
+        let mut gauges = self.gauges.lock().unwrap();
+        let gauges = &mut *gauges;
+
+        let server_name = if let Some(count) = gauges.server_names.get_mut(bucket) {
+            *count += 1;
+            ServerName::Bucket(bucket.to_string())
+        } else if gauges.server_names.len() < self.max_server_names {
+            gauges.server_names.insert(bucket.to_string(), 1);
+            ServerName::Bucket(bucket.to_string())
+        } else {
+            gauges.other_server_names += 1;
+            ServerName::Other
+        };
+
+        context.server_name = Some(server_name);
+    }
+
+    fn on_handshake_status_updated(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &ConnectionMeta,
+        event: &events::HandshakeStatusUpdated,
+    ) {
+        if matches!(event.status, events::HandshakeStatus::Complete { .. }) {
+            context.on_handshake_complete();
+        }
+    }
+
+    fn on_connection_closed(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &ConnectionMeta,
+        _event: &events::ConnectionClosed,
+    ) {
+        // closed connections may linger while draining, so they're removed right away
+        context.release();
+    }
+}
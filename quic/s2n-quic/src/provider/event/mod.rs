@@ -25,6 +25,10 @@ pub trait Provider {
 /// Provides an implementation to disable all events
 pub mod disabled;
 
+pub mod gauges;
+
+pub mod recorder;
+
 /// This module contains event integration with [`tracing`](https://docs.rs/tracing)
 #[cfg(any(feature = "provider-event-tracing", test))]
 pub mod tracing;
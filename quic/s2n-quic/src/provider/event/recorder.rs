@@ -0,0 +1,405 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A "black box" recorder of the most recent events on each connection
+//!
+//! The [`Subscriber`] keeps a bounded ring buffer of the latest events for each open connection.
+//! When a connection is closed with a transport error, e.g. a protocol violation, its events are
+//! collected into a [`Bundle`] and passed to the configured handler for post-mortem debugging.
+//! The recorder can also dump all of the open connections when the process panics with
+//! [`Subscriber::install_panic_hook`].
+//!
+//! Events are formatted as they are recorded, which adds overhead to every event. Events don't
+//! carry the raw datagrams, so bundles describe the packets which were sent and received rather
+//! than containing them.
+//!
+//! ```rust,no_run
+//! # use std::error::Error;
+//! use s2n_quic::{provider::event::recorder, Server};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn Error>> {
+//! let recorder = recorder::Subscriber::builder()
+//!     .with_capacity(1000)
+//!     .with_handler(|bundle| eprintln!("{}", bundle))
+//!     .build();
+//!
+//! recorder.install_panic_hook();
+//!
+//! let server = Server::builder()
+//!     .with_event(recorder)?
+//!     .start()?;
+//! # let _ = server;
+//! #    Ok(())
+//! # }
+//! ```
+
+use super::{events, ConnectionInfo, ConnectionMeta, Event, Timestamp};
+use crate::connection;
+use core::fmt;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, Weak,
+    },
+};
+
+/// The default number of events which are kept for each connection
+const DEFAULT_CAPACITY: usize = 256;
+
+type Handler = dyn Fn(&Bundle) + Send + Sync;
+
+/// An event which was recorded on a connection
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct Record {
+    pub timestamp: Timestamp,
+    pub name: &'static str,
+    /// The `Debug` representation of the event
+    pub event: String,
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:?} {} {}",
+            self.timestamp.duration_since_start(),
+            self.name,
+            self.event
+        )
+    }
+}
+
+/// The reason a bundle was recorded
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug)]
+pub enum Reason {
+    /// The connection was closed with an error matching [`Builder::with_trigger`]
+    Closed { error: connection::Error },
+    /// The process panicked while the connection was open
+    Panic,
+    /// The application requested a dump with [`Subscriber::dump`]
+    Requested,
+}
+
+/// The most recent events of a connection
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct Bundle {
+    pub endpoint_type: events::EndpointType,
+    /// The internal identifier of the connection, as reported in [`ConnectionMeta::id`]
+    pub connection_id: u64,
+    /// The correlation ID of the connection, as reported in [`ConnectionMeta::correlation_id`]
+    pub correlation_id: connection::CorrelationId,
+    pub reason: Reason,
+    /// The number of events which were discarded to make room for newer events
+    pub discarded: u64,
+    pub records: Vec<Record>,
+}
+
+impl fmt::Display for Bundle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "connection {} [{}] ({:?}): {:?}",
+            self.connection_id, self.correlation_id, self.endpoint_type, self.reason
+        )?;
+
+        if self.discarded > 0 {
+            writeln!(f, "... {} earlier events discarded", self.discarded)?;
+        }
+
+        for record in &self.records {
+            writeln!(f, "{}", record)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds a [`Subscriber`]
+pub struct Builder {
+    capacity: usize,
+    trigger: fn(&connection::Error) -> bool,
+    handler: Arc<Handler>,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_CAPACITY,
+            trigger: |error| matches!(error, connection::Error::Transport { .. }),
+            handler: Arc::new(|bundle| eprintln!("{}", bundle)),
+        }
+    }
+}
+
+impl fmt::Debug for Builder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Builder")
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+impl Builder {
+    /// Sets the number of events which are kept for each connection
+    ///
+    /// The default is 256.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Sets the function which decides if closing a connection with the given error records a
+    /// bundle
+    ///
+    /// By default, bundles are recorded for connections which are closed with a transport error,
+    /// regardless of which endpoint closed the connection.
+    pub fn with_trigger(mut self, trigger: fn(&connection::Error) -> bool) -> Self {
+        self.trigger = trigger;
+        self
+    }
+
+    /// Sets the function which is called with each bundle
+    ///
+    /// By default, bundles are written to stderr.
+    pub fn with_handler<F: 'static + Fn(&Bundle) + Send + Sync>(mut self, handler: F) -> Self {
+        self.handler = Arc::new(handler);
+        self
+    }
+
+    pub fn build(self) -> Subscriber {
+        Subscriber {
+            state: Arc::new(State {
+                capacity: self.capacity,
+                trigger: self.trigger,
+                handler: self.handler,
+                next_id: AtomicU64::new(0),
+                connections: Default::default(),
+            }),
+        }
+    }
+}
+
+/// Records the most recent events of each connection
+///
+/// Clones of the subscriber share the same configuration and connections.
+#[derive(Clone)]
+pub struct Subscriber {
+    state: Arc<State>,
+}
+
+impl Default for Subscriber {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl fmt::Debug for Subscriber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Subscriber")
+            .field("capacity", &self.state.capacity)
+            .finish()
+    }
+}
+
+struct State {
+    capacity: usize,
+    trigger: fn(&connection::Error) -> bool,
+    handler: Arc<Handler>,
+    next_id: AtomicU64,
+    /// The rings of the open connections, which are dumped on panic
+    connections: Mutex<BTreeMap<u64, Weak<Mutex<Ring>>>>,
+}
+
+impl Subscriber {
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Passes a bundle for each open connection to the handler
+    pub fn dump(&self) {
+        self.state.dump(Reason::Requested, false);
+    }
+
+    /// Dumps the open connections when the process panics
+    ///
+    /// The hook is installed in addition to the current hook, which is called afterwards.
+    pub fn install_panic_hook(&self) {
+        let state = Arc::downgrade(&self.state);
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if let Some(state) = state.upgrade() {
+                state.dump(Reason::Panic, true);
+            }
+            hook(info)
+        }));
+    }
+}
+
+impl State {
+    fn dump(&self, reason: Reason, is_panicking: bool) {
+        let rings: Vec<_> = {
+            let connections = if is_panicking {
+                // the panicking thread may be holding the lock
+                match self.connections.try_lock() {
+                    Ok(connections) => connections,
+                    Err(std::sync::TryLockError::Poisoned(err)) => err.into_inner(),
+                    Err(std::sync::TryLockError::WouldBlock) => return,
+                }
+            } else {
+                self.connections
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner())
+            };
+            connections.values().filter_map(Weak::upgrade).collect()
+        };
+
+        for ring in rings {
+            let bundle = if is_panicking {
+                match ring.try_lock() {
+                    Ok(ring) => ring.bundle(reason),
+                    Err(std::sync::TryLockError::Poisoned(err)) => err.into_inner().bundle(reason),
+                    Err(std::sync::TryLockError::WouldBlock) => continue,
+                }
+            } else {
+                ring.lock()
+                    .unwrap_or_else(|err| err.into_inner())
+                    .bundle(reason)
+            };
+
+            (self.handler)(&bundle);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Ring {
+    endpoint_type: events::EndpointType,
+    connection_id: u64,
+    correlation_id: connection::CorrelationId,
+    capacity: usize,
+    discarded: u64,
+    records: VecDeque<Record>,
+}
+
+impl Ring {
+    fn push(&mut self, record: Record) {
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+            self.discarded += 1;
+        }
+        self.records.push_back(record);
+    }
+
+    fn bundle(&self, reason: Reason) -> Bundle {
+        Bundle {
+            endpoint_type: self.endpoint_type.clone(),
+            connection_id: self.connection_id,
+            correlation_id: self.correlation_id,
+            reason,
+            discarded: self.discarded,
+            records: self.records.iter().cloned().collect(),
+        }
+    }
+}
+
+/// The recorded events of a connection
+pub struct Connection {
+    key: u64,
+    ring: Arc<Mutex<Ring>>,
+    state: Arc<State>,
+}
+
+impl fmt::Debug for Connection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Connection")
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        if let Ok(mut connections) = self.state.connections.lock() {
+            connections.remove(&self.key);
+        }
+    }
+}
+
+impl super::Subscriber for Subscriber {
+    type ConnectionContext = Connection;
+
+    fn create_connection_context(
+        &mut self,
+        meta: &ConnectionMeta,
+        _info: &ConnectionInfo,
+    ) -> Self::ConnectionContext {
+        let ring = Arc::new(Mutex::new(Ring {
+            endpoint_type: meta.endpoint_type.clone(),
+            connection_id: meta.id,
+            correlation_id: meta.correlation_id,
+            capacity: self.state.capacity,
+            discarded: 0,
+            records: VecDeque::with_capacity(self.state.capacity.min(DEFAULT_CAPACITY)),
+        }));
+
+        // connection ids are only unique to each endpoint, so the recorder assigns its own keys
+        let key = self.state.next_id.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut connections) = self.state.connections.lock() {
+            connections.insert(key, Arc::downgrade(&ring));
+        }
+
+        Connection {
+            key,
+            ring,
+            state: self.state.clone(),
+        }
+    }
+
+    fn on_connection_event<E: Event>(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        meta: &ConnectionMeta,
+        event: &E,
+    ) {
+        let record = Record {
+            timestamp: meta.timestamp,
+            name: E::NAME,
+            event: format!("{:?}", event),
+        };
+
+        if let Ok(mut ring) = context.ring.lock() {
+            ring.push(record);
+        }
+    }
+
+    fn on_connection_closed(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        meta: &ConnectionMeta,
+        event: &events::ConnectionClosed,
+    ) {
+        if !(self.state.trigger)(&event.error) {
+            return;
+        }
+
+        let mut bundle = context
+            .ring
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .bundle(Reason::Closed { error: event.error });
+
+        // `on_connection_event` is only called for the close event after this method
+        bundle.records.push(Record {
+            timestamp: meta.timestamp,
+            name: events::ConnectionClosed::NAME,
+            event: format!("{:?}", event),
+        });
+
+        (self.state.handler)(&bundle);
+    }
+}
@@ -3,7 +3,7 @@
 
 pub use s2n_quic_core::event::tracing::Subscriber;
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Provider(());
 
 impl super::Provider for Provider {
@@ -0,0 +1,34 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides handlers for the frames of private or experimental extensions
+//!
+//! An [`Endpoint`] creates a [`Handler`] for each connection, which registers the frame types of
+//! the extension and the custom transport parameter that negotiates it. The extension is only
+//! enabled on a connection if the peer sends a transport parameter with the same ID. Each
+//! [`FrameType`] decides if its frames elicit ACKs and if they are retransmitted when lost. By
+//! default, no extensions are offered.
+
+pub use s2n_quic_core::extension_frame::{
+    ConnectionInfo, Disabled, Endpoint, FrameType, Handler, WriteError, Writer,
+};
+
+pub trait Provider {
+    type Endpoint: 'static + Send + Endpoint;
+    type Error: 'static + core::fmt::Display;
+
+    fn start(self) -> Result<Self::Endpoint, Self::Error>;
+}
+
+impl_provider_utils!();
+
+pub type Default = Disabled;
+
+impl<T: 'static + Send + Endpoint> Provider for T {
+    type Endpoint = T;
+    type Error = core::convert::Infallible;
+
+    fn start(self) -> Result<Self::Endpoint, Self::Error> {
+        Ok(self)
+    }
+}
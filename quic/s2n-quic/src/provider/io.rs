@@ -16,6 +16,26 @@ pub trait Provider: 'static {
     ) -> Result<SocketAddress, Self::Error>;
 }
 
+/// An IO provider which is able to start multiple endpoints that share its sockets
+///
+/// The provider routes each received datagram to the endpoint which owns the connection, based on
+/// [`shard::index_for_datagram`](s2n_quic_core::connection::id::shard::index_for_datagram).
+pub trait ShardedProvider: Provider {
+    fn start_sharded<E: Endpoint<PathHandle = Self::PathHandle>>(
+        self,
+        endpoints: Vec<E>,
+    ) -> Result<SocketAddress, Self::Error>;
+}
+
+#[cfg(any(test, all(not(docdiff), feature = "unstable-provider-io-async-io")))]
+pub mod async_io;
+
+#[cfg(any(test, all(not(docdiff), feature = "unstable-provider-io-fault")))]
+pub mod fault;
+
+#[cfg(any(test, all(not(docdiff), feature = "unstable-provider-io-replay")))]
+pub mod replay;
+
 #[cfg(any(test, all(not(docdiff), feature = "unstable-provider-io-testing")))]
 pub mod testing;
 
@@ -0,0 +1,52 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides an implementation of the [`io::Provider`](crate::provider::io::Provider)
+//! using the [`async-io`](https://docs.rs/async-io) reactor
+//!
+//! The reactor is shared by the [`smol`](https://docs.rs/smol) and
+//! [`async-std`](https://docs.rs/async-std) runtimes, which allows applications using them to
+//! run an endpoint without a Tokio runtime. The provider doesn't spawn any tasks. Instead, the
+//! endpoint is handed to a [`Driver`] when it's started, which the application spawns on its
+//! executor, e.g. with `smol::spawn` or `async_std::task::spawn`.
+//!
+//! ```rust,no_run
+//! # use std::error::Error;
+//! # fn spawn<F: core::future::Future + Send + 'static>(_: F) {}
+//! use s2n_quic::{provider::io::async_io::Builder as IoBuilder, Server};
+//! use std::net::ToSocketAddrs;
+//!
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! let addr = "127.0.0.1:443".to_socket_addrs()?.next().unwrap();
+//!
+//! let (io, driver) = IoBuilder::default()
+//!     .with_receive_address(addr)?
+//!     .build()?;
+//!
+//! let server = Server::builder().with_io(io)?.start()?;
+//!
+//! // the endpoint makes no progress until the driver is spawned on the executor
+//! spawn(driver);
+//! # let _ = server;
+//! #
+//! #    Ok(())
+//! # }
+//! ```
+
+use s2n_quic_core::{endpoint::Endpoint, inet::SocketAddress};
+use s2n_quic_platform::io::async_io;
+use std::io;
+
+pub use self::async_io::{Builder, Driver, Io as Provider};
+
+impl super::Provider for Provider {
+    type PathHandle = async_io::PathHandle;
+    type Error = io::Error;
+
+    fn start<E: Endpoint<PathHandle = Self::PathHandle>>(
+        self,
+        endpoint: E,
+    ) -> Result<SocketAddress, Self::Error> {
+        Provider::start(self, endpoint)
+    }
+}
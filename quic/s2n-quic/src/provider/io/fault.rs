@@ -0,0 +1,490 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides an IO decorator which injects faults into received datagrams
+//!
+//! The [`Provider`] wraps another IO provider and consults a [`Schedule`] for each datagram
+//! received by the endpoint. The schedule decides if the datagram is delivered, dropped,
+//! duplicated, reordered, corrupted or delayed. This makes it possible to exercise loss recovery
+//! without network access or to run chaos tests against an application.
+//!
+//! Faults are only applied to received datagrams. Wrapping the IO providers of both peers injects
+//! faults in both directions.
+//!
+//! ```rust,no_run
+//! # use std::{error::Error, time::Duration};
+//! use s2n_quic::{
+//!     provider::io::fault::{Fault, Provider, Script},
+//!     Server,
+//! };
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn Error>> {
+//! let script = Script::new()
+//!     // drop the first datagram
+//!     .at(0, Fault::Drop)
+//!     // corrupt every 10th datagram
+//!     .every(10, Fault::Corrupt)
+//!     // delay all of the other datagrams
+//!     .with_latency(Duration::from_millis(50));
+//!
+//! let server = Server::builder()
+//!     .with_io(Provider::new("127.0.0.1:4433", script)?)?
+//!     .start()?;
+//! #
+//! #    Ok(())
+//! # }
+//! ```
+
+use crate::provider::io;
+use core::{ops::Range, time::Duration};
+use s2n_quic_core::{
+    endpoint::{self, CloseError},
+    inet::{datagram, SocketAddress},
+    io::{
+        rx::{self, Entry as _},
+        tx,
+    },
+    path::{self, Handle as _, LocalAddress, MaxMtu},
+    time::{Clock, Timestamp},
+};
+use std::task::{Context, Poll};
+
+/// A fault which is applied to a received datagram
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Fault {
+    /// The datagram is delivered to the endpoint
+    Pass,
+    /// The datagram is discarded
+    Drop,
+    /// The datagram is delivered to the endpoint twice
+    Duplicate,
+    /// The datagram is delivered after the next datagram
+    ///
+    /// The datagram is held until another datagram is delivered to the endpoint.
+    Reorder,
+    /// The last byte of the datagram is inverted before it is delivered
+    ///
+    /// This invalidates the authentication tag of the last packet in the datagram.
+    Corrupt,
+    /// The datagram is delivered after the given amount of time
+    Delay(Duration),
+}
+
+impl Default for Fault {
+    fn default() -> Self {
+        Self::Pass
+    }
+}
+
+/// Information about a received datagram which is passed to a [`Schedule`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Datagram<'a> {
+    /// The number of datagrams received by the endpoint before this one
+    pub index: u64,
+    /// The time at which the datagram was received
+    pub timestamp: Timestamp,
+    /// The address of the peer which sent the datagram
+    pub remote_address: SocketAddress,
+    /// The payload of the datagram
+    pub payload: &'a [u8],
+}
+
+/// Decides which fault is applied to each received datagram
+pub trait Schedule: 'static + Send {
+    /// Returns the fault for the given datagram
+    fn on_datagram(&mut self, datagram: &Datagram) -> Fault;
+}
+
+impl<F: 'static + Send + FnMut(&Datagram) -> Fault> Schedule for F {
+    #[inline]
+    fn on_datagram(&mut self, datagram: &Datagram) -> Fault {
+        (self)(datagram)
+    }
+}
+
+/// A [`Schedule`] which applies faults based on the index of each datagram
+///
+/// Rules are checked in the order they were added and the first matching rule is applied.
+/// Datagrams which don't match any rule are delivered after the configured latency.
+#[derive(Clone, Debug, Default)]
+pub struct Script {
+    rules: Vec<(Rule, Fault)>,
+    latency: Duration,
+}
+
+#[derive(Clone, Debug)]
+enum Rule {
+    Range(Range<u64>),
+    Every(u64),
+}
+
+impl Rule {
+    #[inline]
+    fn matches(&self, index: u64) -> bool {
+        match self {
+            Self::Range(range) => range.contains(&index),
+            Self::Every(interval) => *interval != 0 && (index + 1) % interval == 0,
+        }
+    }
+}
+
+impl Script {
+    /// Creates a script which delivers all datagrams without any faults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies the fault to the datagram at `index`, starting at `0`
+    #[must_use]
+    pub fn at(self, index: u64, fault: Fault) -> Self {
+        self.range(index..index.saturating_add(1), fault)
+    }
+
+    /// Applies the fault to all of the datagrams with an index in `range`
+    #[must_use]
+    pub fn range(mut self, range: Range<u64>, fault: Fault) -> Self {
+        self.rules.push((Rule::Range(range), fault));
+        self
+    }
+
+    /// Applies the fault to every `interval`th datagram
+    ///
+    /// An interval of `0` never matches.
+    #[must_use]
+    pub fn every(mut self, interval: u64, fault: Fault) -> Self {
+        self.rules.push((Rule::Every(interval), fault));
+        self
+    }
+
+    /// Delays all of the datagrams which don't match a rule
+    #[must_use]
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+}
+
+impl Schedule for Script {
+    #[inline]
+    fn on_datagram(&mut self, datagram: &Datagram) -> Fault {
+        for (rule, fault) in &self.rules {
+            if rule.matches(datagram.index) {
+                return *fault;
+            }
+        }
+
+        if self.latency.is_zero() {
+            Fault::Pass
+        } else {
+            Fault::Delay(self.latency)
+        }
+    }
+}
+
+/// An IO provider which injects faults into the datagrams received from the wrapped provider
+#[derive(Debug, Default)]
+pub struct Provider<P, S> {
+    io: P,
+    schedule: S,
+}
+
+impl<P: io::Provider, S: Schedule> Provider<P, S> {
+    /// Injects faults into the given IO provider according to the schedule
+    pub fn new<T: io::TryInto<Provider = P>>(io: T, schedule: S) -> Result<Self, T::Error> {
+        Ok(Self {
+            io: io.try_into()?,
+            schedule,
+        })
+    }
+}
+
+impl<P: io::Provider, S: Schedule> io::Provider for Provider<P, S> {
+    type PathHandle = P::PathHandle;
+    type Error = P::Error;
+
+    fn start<E: endpoint::Endpoint<PathHandle = Self::PathHandle>>(
+        self,
+        endpoint: E,
+    ) -> Result<SocketAddress, Self::Error> {
+        self.io.start(Endpoint {
+            endpoint,
+            schedule: self.schedule,
+            index: 0,
+            local_address: Default::default(),
+            ready: Vec::new(),
+            reordered: Vec::new(),
+            delayed: Vec::new(),
+        })
+    }
+}
+
+/// Wraps an endpoint and applies the schedule to each datagram before passing it along
+struct Endpoint<E: endpoint::Endpoint, S> {
+    endpoint: E,
+    schedule: S,
+    /// The number of datagrams received so far
+    index: u64,
+    local_address: LocalAddress,
+    /// Datagrams which will be delivered on the next call to the endpoint
+    ready: Vec<Entry<E::PathHandle>>,
+    /// Datagrams which are waiting for the next delivered datagram
+    reordered: Vec<Entry<E::PathHandle>>,
+    /// Datagrams which are waiting for their delay to expire, sorted by delivery time
+    delayed: Vec<(Timestamp, Entry<E::PathHandle>)>,
+}
+
+impl<E: endpoint::Endpoint, S: Schedule> Endpoint<E, S> {
+    fn on_datagram(
+        &mut self,
+        timestamp: Timestamp,
+        header: datagram::Header<E::PathHandle>,
+        payload: &[u8],
+    ) {
+        let datagram = Datagram {
+            index: self.index,
+            timestamp,
+            remote_address: *header.path.remote_address(),
+            payload,
+        };
+        self.index += 1;
+
+        let fault = self.schedule.on_datagram(&datagram);
+
+        let mut entry = Entry {
+            header,
+            payload: payload.to_vec(),
+        };
+
+        match fault {
+            Fault::Pass => self.push(entry),
+            Fault::Drop => {}
+            Fault::Duplicate => {
+                self.push(entry.clone());
+                self.push(entry);
+            }
+            Fault::Reorder => self.reordered.push(entry),
+            Fault::Corrupt => {
+                if let Some(byte) = entry.payload.last_mut() {
+                    *byte = !*byte;
+                }
+                self.push(entry);
+            }
+            Fault::Delay(delay) => {
+                let deadline = timestamp + delay;
+                // keep datagrams with the same deadline in the order they were received
+                let index = self
+                    .delayed
+                    .partition_point(|(other, _)| *other <= deadline);
+                self.delayed.insert(index, (deadline, entry));
+            }
+        }
+    }
+
+    /// Queues the datagram for delivery, followed by any datagrams waiting to be reordered
+    #[inline]
+    fn push(&mut self, entry: Entry<E::PathHandle>) {
+        self.ready.push(entry);
+        self.ready.append(&mut self.reordered);
+    }
+
+    /// Queues all of the delayed datagrams with an expired deadline
+    fn on_timeout(&mut self, now: Timestamp) {
+        let expired = self
+            .delayed
+            .partition_point(|(deadline, _)| *deadline <= now);
+
+        if expired == 0 {
+            return;
+        }
+
+        let pending = self.delayed.split_off(expired);
+        for (_, entry) in core::mem::replace(&mut self.delayed, pending) {
+            self.push(entry);
+        }
+    }
+
+    /// Passes all of the queued datagrams to the wrapped endpoint
+    fn deliver<C: Clock>(&mut self, clock: &C) {
+        if self.ready.is_empty() {
+            return;
+        }
+
+        let mut queue = Queue {
+            local_address: self.local_address,
+            entries: &mut self.ready,
+        };
+        self.endpoint.receive(&mut queue, clock);
+
+        // the endpoint should have consumed all of the entries
+        self.ready.clear();
+    }
+}
+
+impl<E: endpoint::Endpoint, S: Schedule> endpoint::Endpoint for Endpoint<E, S> {
+    type PathHandle = E::PathHandle;
+    type Subscriber = E::Subscriber;
+
+    const ENDPOINT_TYPE: endpoint::Type = E::ENDPOINT_TYPE;
+
+    fn receive<Rx, C>(&mut self, rx: &mut Rx, clock: &C)
+    where
+        Rx: rx::Queue<Handle = Self::PathHandle>,
+        C: Clock,
+    {
+        let now = clock.get_time();
+        self.on_timeout(now);
+
+        let local_address = rx.local_address();
+        self.local_address = local_address;
+
+        let entries = rx.as_slice_mut();
+        let len = entries.len();
+        for entry in entries {
+            if let Some((header, payload)) = entry.read(&local_address) {
+                self.on_datagram(now, header, payload);
+            }
+        }
+        rx.finish(len);
+
+        self.deliver(clock);
+    }
+
+    fn transmit<Tx, C>(&mut self, tx: &mut Tx, clock: &C)
+    where
+        Tx: tx::Queue<Handle = Self::PathHandle>,
+        C: Clock,
+    {
+        // the IO providers call `transmit` after the timeout expires so deliver any delayed
+        // datagrams before transmitting
+        self.on_timeout(clock.get_time());
+        self.deliver(clock);
+
+        self.endpoint.transmit(tx, clock)
+    }
+
+    fn poll_wakeups<C: Clock>(
+        &mut self,
+        cx: &mut Context<'_>,
+        clock: &C,
+    ) -> Poll<Result<usize, CloseError>> {
+        self.endpoint.poll_wakeups(cx, clock)
+    }
+
+    fn timeout(&self) -> Option<Timestamp> {
+        let timeout = self.endpoint.timeout();
+        let delayed = self.delayed.first().map(|(deadline, _)| *deadline);
+
+        match (timeout, delayed) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    fn set_max_mtu(&mut self, max_mtu: MaxMtu) {
+        self.endpoint.set_max_mtu(max_mtu)
+    }
+
+    fn subscriber(&mut self) -> &mut Self::Subscriber {
+        self.endpoint.subscriber()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Entry<Handle> {
+    header: datagram::Header<Handle>,
+    payload: Vec<u8>,
+}
+
+impl<Handle: path::Handle> rx::Entry for Entry<Handle> {
+    type Handle = Handle;
+
+    #[inline]
+    fn read(
+        &mut self,
+        _local_address: &LocalAddress,
+    ) -> Option<(datagram::Header<Self::Handle>, &mut [u8])> {
+        Some((self.header, &mut self.payload))
+    }
+}
+
+struct Queue<'a, Handle> {
+    local_address: LocalAddress,
+    entries: &'a mut Vec<Entry<Handle>>,
+}
+
+impl<'a, Handle: path::Handle> rx::Queue for Queue<'a, Handle> {
+    type Entry = Entry<Handle>;
+    type Handle = Handle;
+
+    #[inline]
+    fn local_address(&self) -> LocalAddress {
+        self.local_address
+    }
+
+    #[inline]
+    fn as_slice_mut(&mut self) -> &mut [Self::Entry] {
+        self.entries
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    fn finish(&mut self, count: usize) {
+        self.entries.drain(..count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datagram(index: u64) -> Datagram<'static> {
+        Datagram {
+            index,
+            timestamp: unsafe { Timestamp::from_duration(Duration::from_secs(1)) },
+            remote_address: Default::default(),
+            payload: &[],
+        }
+    }
+
+    #[test]
+    fn script_test() {
+        let latency = Duration::from_millis(10);
+        let mut script = Script::new()
+            .at(1, Fault::Drop)
+            .range(1..3, Fault::Duplicate)
+            .every(4, Fault::Corrupt)
+            .every(0, Fault::Reorder)
+            .with_latency(latency);
+
+        let faults: Vec<_> = (0..8)
+            .map(|index| script.on_datagram(&datagram(index)))
+            .collect();
+
+        assert_eq!(
+            faults,
+            [
+                Fault::Delay(latency),
+                Fault::Drop,
+                Fault::Duplicate,
+                Fault::Corrupt,
+                Fault::Delay(latency),
+                Fault::Delay(latency),
+                Fault::Delay(latency),
+                Fault::Corrupt,
+            ]
+        );
+    }
+
+    #[test]
+    fn script_default_test() {
+        let mut script = Script::default();
+        assert_eq!(script.on_datagram(&datagram(0)), Fault::Pass);
+    }
+}
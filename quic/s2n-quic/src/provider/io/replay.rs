@@ -0,0 +1,672 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides an IO implementation which replays captured traffic into a server
+//!
+//! The [`Io`] provider delivers the datagrams that a server received in a [`Capture`] on a
+//! virtual clock. The clock jumps to the timestamp of each datagram and to each timer of the
+//! endpoint that expires in between, so a replay does not depend on the speed of the host and
+//! produces the same sequence of events every time. This makes it possible to reproduce protocol
+//! bugs reported from production in a debugger or under the tracing event provider.
+//!
+//! Captured packets are protected with keys that the original server negotiated. The key log
+//! TLS provider in `provider::tls::replay` imports these keys from an `SSLKEYLOGFILE`. The
+//! server also needs to use the connection IDs that the client addressed in the capture, which
+//! are provided by [`Io::connection_ids`].
+//!
+//! ```rust,no_run
+//! # use std::{error::Error, fs::File, io::BufReader};
+//! use s2n_quic::{
+//!     provider::{
+//!         io::replay::{Capture, Io},
+//!         protocol_violation::Outcome,
+//!         tls::replay::KeyLog,
+//!     },
+//!     Server,
+//! };
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn Error>> {
+//! let capture = Capture::from_pcap(File::open("server.pcap")?)?;
+//! let io = Io::builder().with_capture(capture).build()?;
+//! let key_log = KeyLog::read(BufReader::new(File::open("keys.log")?))?;
+//!
+//! let mut server = Server::builder()
+//!     .with_connection_id(io.connection_ids())?
+//!     .with_io(io)?
+//!     .with_tls(key_log)?
+//!     // acknowledgements of packets that only the original server sent are ignored
+//!     .with_protocol_violation(Outcome::Ignore)?
+//!     .start()?;
+//!
+//! while let Some(connection) = server.accept().await {
+//!     // handle the replayed connection
+//! }
+//! #
+//! #    Ok(())
+//! # }
+//! ```
+//!
+//! Only the datagrams received by the server are replayed. The packets that the replayed server
+//! transmits are discarded, since the recorded client can't respond to them. As a result, the
+//! replayed server observes acknowledgements for packets that it never sent and should be
+//! configured to ignore the `AckForUnsentPacket` protocol violation. Once the capture is
+//! exhausted, the clock stops and the endpoint only continues to serve the application.
+
+use crate::provider::{
+    connection_id::{self, Generator as _, Validator as _},
+    io,
+};
+use core::{task::Poll, time::Duration};
+use s2n_quic_core::{
+    connection::{self, id::ConnectionInfo},
+    endpoint::{self, CloseError},
+    event::api::SocketAddress as EventAddress,
+    inet::{datagram, SocketAddress},
+    io::{rx, tx},
+    path::{self, LocalAddress},
+    random,
+    time::{Clock, Timestamp},
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    io::Read,
+    net::SocketAddr,
+};
+use tokio::runtime::Handle;
+
+mod pcap;
+
+/// The maximum number of datagrams the endpoint can transmit for each wakeup
+const TX_CAPACITY: usize = 64;
+
+/// A UDP datagram in a capture
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Datagram {
+    /// The time at which the datagram was captured
+    pub timestamp: Duration,
+    /// The address of the peer which sent the datagram
+    pub source: SocketAddr,
+    /// The address to which the datagram was sent
+    pub destination: SocketAddr,
+    /// The payload of the datagram
+    pub payload: Vec<u8>,
+}
+
+/// A sequence of captured UDP datagrams
+#[derive(Clone, Debug, Default)]
+pub struct Capture {
+    datagrams: Vec<Datagram>,
+}
+
+impl Capture {
+    /// Reads the UDP datagrams of a capture in the pcap format
+    ///
+    /// Datagrams sent over IPv4 and IPv6 are read from Ethernet, raw IP, Linux cooked and
+    /// loopback captures. Other packets, fragmented IP packets and packets which were truncated
+    /// by the snapshot length are skipped. Captures in the pcapng format can be converted
+    /// with `editcap -F pcap`.
+    pub fn from_pcap<R: Read>(reader: R) -> std::io::Result<Self> {
+        let datagrams = pcap::read(reader)?;
+        Ok(Self { datagrams })
+    }
+
+    /// Returns the datagrams in the capture
+    pub fn datagrams(&self) -> &[Datagram] {
+        &self.datagrams
+    }
+}
+
+impl From<Vec<Datagram>> for Capture {
+    fn from(datagrams: Vec<Datagram>) -> Self {
+        Self { datagrams }
+    }
+}
+
+impl FromIterator<Datagram> for Capture {
+    fn from_iter<T: IntoIterator<Item = Datagram>>(iter: T) -> Self {
+        Self {
+            datagrams: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// An IO provider which replays the datagrams received by a server in a [`Capture`]
+#[derive(Debug)]
+pub struct Io {
+    datagrams: Vec<Datagram>,
+    local_address: SocketAddr,
+    handle: Option<Handle>,
+}
+
+impl Io {
+    /// Creates a builder for the replay IO provider
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Returns a connection ID format which reissues the connection IDs used in the capture
+    ///
+    /// For each client, the generated connection IDs are the destination connection IDs of its
+    /// packets in the order they first appear in the capture, excluding the one the client chose
+    /// for its first Initial packet. Once these are exhausted, connection IDs of the same length
+    /// are generated from a fixed seed.
+    pub fn connection_ids(&self) -> ConnectionIds {
+        ConnectionIds::new(&self.datagrams)
+    }
+}
+
+/// A builder for the replay IO provider
+#[derive(Debug, Default)]
+pub struct Builder {
+    capture: Capture,
+    local_address: Option<SocketAddr>,
+    handle: Option<Handle>,
+}
+
+impl Builder {
+    /// Sets the capture which is replayed
+    pub fn with_capture(mut self, capture: Capture) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    /// Sets the address of the server in the capture
+    ///
+    /// Only the datagrams sent to this address are replayed. By default, the destination of the
+    /// first datagram in the capture is used.
+    pub fn with_local_address(mut self, local_address: SocketAddr) -> Self {
+        self.local_address = Some(local_address);
+        self
+    }
+
+    /// Sets the tokio runtime handle which is used to spawn the replay
+    ///
+    /// By default, the runtime of the caller of `start` is used.
+    pub fn with_handle(mut self, handle: Handle) -> Self {
+        self.handle = Some(handle);
+        self
+    }
+
+    /// Builds the replay IO provider
+    pub fn build(self) -> std::io::Result<Io> {
+        let Self {
+            capture,
+            local_address,
+            handle,
+        } = self;
+
+        let local_address = local_address
+            .or_else(|| capture.datagrams.first().map(|d| d.destination))
+            .ok_or_else(|| invalid_input("the capture does not contain any datagrams"))?;
+
+        let datagrams: Vec<_> = capture
+            .datagrams
+            .into_iter()
+            .filter(|datagram| datagram.destination == local_address)
+            .collect();
+
+        if datagrams.is_empty() {
+            return Err(invalid_input(format!(
+                "the capture does not contain any datagrams sent to {}",
+                local_address
+            )));
+        }
+
+        Ok(Io {
+            datagrams,
+            local_address,
+            handle,
+        })
+    }
+}
+
+impl io::Provider for Io {
+    type PathHandle = path::Tuple;
+    type Error = std::io::Error;
+
+    fn start<E: endpoint::Endpoint<PathHandle = Self::PathHandle>>(
+        self,
+        endpoint: E,
+    ) -> Result<SocketAddress, Self::Error> {
+        let handle = match self.handle {
+            Some(handle) => handle,
+            None => Handle::try_current()
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err.to_string()))?,
+        };
+
+        let local_address = self.local_address.into();
+
+        let instance = Instance {
+            endpoint,
+            datagrams: self.datagrams,
+            local_address,
+        };
+
+        handle.spawn(instance.event_loop());
+
+        Ok(local_address)
+    }
+}
+
+fn invalid_input<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, error)
+}
+
+/// A clock which only advances when the replay moves it forward
+struct VirtualClock(Timestamp);
+
+impl Clock for VirtualClock {
+    fn get_time(&self) -> Timestamp {
+        self.0
+    }
+}
+
+struct Instance<E> {
+    endpoint: E,
+    datagrams: Vec<Datagram>,
+    local_address: SocketAddress,
+}
+
+impl<E: endpoint::Endpoint<PathHandle = path::Tuple>> Instance<E> {
+    async fn event_loop(self) {
+        let Self {
+            mut endpoint,
+            datagrams,
+            local_address,
+        } = self;
+
+        // the virtual clock starts at the epoch when the first datagram is received
+        let start = datagrams
+            .first()
+            .map(|datagram| datagram.timestamp)
+            .unwrap_or_default();
+        let mut clock = VirtualClock(unsafe {
+            // Safety: the replay is the time source of the endpoint
+            Timestamp::from_duration(Duration::ZERO)
+        });
+        let mut tx = Sink::default();
+
+        for datagram in datagrams {
+            let now = unsafe {
+                // Safety: the replay is the time source of the endpoint
+                Timestamp::from_duration(datagram.timestamp.saturating_sub(start))
+            };
+
+            // fire each of the timers which expire before the datagram is received
+            let mut previous = None;
+            while let Some(timeout) = endpoint.timeout() {
+                // stop if the endpoint didn't make progress on the previous timeout
+                if timeout > now || previous == Some(timeout) {
+                    break;
+                }
+                previous = Some(timeout);
+                clock.0 = clock.0.max(timeout);
+
+                if wakeup(&mut endpoint, &clock, &mut tx).await.is_err() {
+                    return;
+                }
+            }
+
+            clock.0 = clock.0.max(now);
+
+            let mut entries = vec![Entry {
+                header: datagram::Header {
+                    path: path::Tuple {
+                        remote_address: SocketAddress::from(datagram.source).into(),
+                        local_address: local_address.into(),
+                    },
+                    ecn: Default::default(),
+                    rx_timestamps: Default::default(),
+                },
+                payload: datagram.payload,
+            }];
+            endpoint.receive(
+                &mut Queue {
+                    local_address: local_address.into(),
+                    entries: &mut entries,
+                },
+                &clock,
+            );
+
+            if wakeup(&mut endpoint, &clock, &mut tx).await.is_err() {
+                return;
+            }
+        }
+
+        // the capture is exhausted so only serve the application until the endpoint closes
+        while endpoint.wakeups(&clock).await.is_ok() {
+            tx.count = 0;
+            endpoint.transmit(&mut tx, &clock);
+        }
+    }
+}
+
+/// Lets the application react to the previous step and then wakes up the endpoint
+async fn wakeup<E: endpoint::Endpoint<PathHandle = path::Tuple>>(
+    endpoint: &mut E,
+    clock: &VirtualClock,
+    tx: &mut Sink,
+) -> Result<(), CloseError> {
+    tokio::task::yield_now().await;
+
+    let wakeups = futures::future::poll_fn(|cx| Poll::Ready(endpoint.poll_wakeups(cx, clock)));
+    if let Poll::Ready(Err(err)) = wakeups.await {
+        return Err(err);
+    }
+
+    tx.count = 0;
+    endpoint.transmit(tx, clock);
+
+    Ok(())
+}
+
+#[derive(Debug)]
+struct Entry {
+    header: datagram::Header<path::Tuple>,
+    payload: Vec<u8>,
+}
+
+impl rx::Entry for Entry {
+    type Handle = path::Tuple;
+
+    #[inline]
+    fn read(
+        &mut self,
+        _local_address: &LocalAddress,
+    ) -> Option<(datagram::Header<Self::Handle>, &mut [u8])> {
+        Some((self.header, &mut self.payload))
+    }
+}
+
+struct Queue<'a> {
+    local_address: LocalAddress,
+    entries: &'a mut Vec<Entry>,
+}
+
+impl<'a> rx::Queue for Queue<'a> {
+    type Entry = Entry;
+    type Handle = path::Tuple;
+
+    #[inline]
+    fn local_address(&self) -> LocalAddress {
+        self.local_address
+    }
+
+    #[inline]
+    fn as_slice_mut(&mut self) -> &mut [Self::Entry] {
+        self.entries
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    fn finish(&mut self, count: usize) {
+        self.entries.drain(..count);
+    }
+}
+
+/// A transmission queue which discards every datagram
+///
+/// The capacity is bounded so the endpoint has to wait for the next wakeup to continue
+/// transmitting, as it would with a socket.
+struct Sink {
+    entry: SinkEntry,
+    count: usize,
+}
+
+impl Default for Sink {
+    fn default() -> Self {
+        Self {
+            entry: SinkEntry {
+                payload: vec![0; u16::MAX as usize],
+            },
+            count: 0,
+        }
+    }
+}
+
+impl tx::Queue for Sink {
+    type Entry = SinkEntry;
+    type Handle = path::Tuple;
+
+    fn push<M: tx::Message<Handle = Self::Handle>>(
+        &mut self,
+        message: M,
+    ) -> Result<tx::Outcome, tx::Error> {
+        if self.count >= TX_CAPACITY {
+            return Err(tx::Error::AtCapacity);
+        }
+
+        let len = tx::Entry::set(&mut self.entry, message)?;
+        self.count += 1;
+
+        Ok(tx::Outcome { len, index: 0 })
+    }
+
+    fn as_slice_mut(&mut self) -> &mut [Self::Entry] {
+        core::slice::from_mut(&mut self.entry)
+    }
+
+    fn capacity(&self) -> usize {
+        TX_CAPACITY - self.count
+    }
+
+    fn len(&self) -> usize {
+        // all of the datagrams are written to the same entry
+        self.count.min(1)
+    }
+}
+
+struct SinkEntry {
+    payload: Vec<u8>,
+}
+
+impl tx::Entry for SinkEntry {
+    type Handle = path::Tuple;
+
+    fn set<M: tx::Message<Handle = Self::Handle>>(
+        &mut self,
+        mut message: M,
+    ) -> Result<usize, tx::Error> {
+        message.write_payload(tx::PayloadBuffer::new(&mut self.payload), 0)
+    }
+
+    fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    fn payload_mut(&mut self) -> &mut [u8] {
+        &mut self.payload
+    }
+}
+
+/// A connection ID format which reissues the connection IDs that clients addressed in a capture
+///
+/// Any other connection IDs are generated with the random provider of the server, which can be
+/// seeded to make them deterministic as well.
+#[derive(Debug)]
+pub struct ConnectionIds {
+    peers: HashMap<SocketAddr, VecDeque<connection::LocalId>>,
+    len: Option<usize>,
+    fallback: connection_id::default::Format,
+}
+
+impl ConnectionIds {
+    fn new(datagrams: &[Datagram]) -> Self {
+        let mut peers: HashMap<SocketAddr, (Vec<u8>, VecDeque<connection::LocalId>)> =
+            HashMap::new();
+        let mut len = None;
+
+        for datagram in datagrams {
+            let payload = &datagram.payload[..];
+            let first = match payload.first() {
+                Some(first) => *first,
+                None => continue,
+            };
+
+            let id = if first & 0x80 == 0x80 {
+                // long header packets encode the length of the destination connection ID
+                let id_len = match payload.get(5) {
+                    Some(id_len) => *id_len as usize,
+                    None => continue,
+                };
+                payload.get(6..6 + id_len)
+            } else if let Some(len) = len {
+                // short header packets use the length of the IDs from the long header packets
+                payload.get(1..1 + len)
+            } else {
+                None
+            };
+
+            let id = match id {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let (original, ids) = peers
+                .entry(datagram.source)
+                .or_insert_with(|| (id.to_vec(), VecDeque::new()));
+
+            // the client chose the first ID itself, before it received any from the server
+            if original == id {
+                continue;
+            }
+
+            if let Some(id) = connection::LocalId::try_from_bytes(id) {
+                if !ids.contains(&id) {
+                    len.get_or_insert(id.as_bytes().len());
+                    ids.push_back(id);
+                }
+            }
+        }
+
+        let fallback = connection_id::default::Format::builder();
+        let fallback = if let Some(len) = len {
+            fallback
+                .with_len(len)
+                .expect("captured connection IDs have a valid length")
+        } else {
+            fallback
+        };
+        let fallback = fallback.build().expect("infallible");
+
+        Self {
+            peers: peers
+                .into_iter()
+                .map(|(address, (_original, ids))| (address, ids))
+                .collect(),
+            len,
+            fallback,
+        }
+    }
+}
+
+impl ConnectionIds {
+    /// Returns the next captured connection ID for the peer of the connection
+    fn next_captured(&mut self, connection_info: &ConnectionInfo) -> Option<connection::LocalId> {
+        let address = match &connection_info.remote_address {
+            EventAddress::IpV4 { ip, port, .. } => SocketAddr::from((**ip, *port)),
+            EventAddress::IpV6 { ip, port, .. } => SocketAddr::from((**ip, *port)),
+            _ => return None,
+        };
+
+        self.peers.get_mut(&address)?.pop_front()
+    }
+}
+
+impl connection_id::Generator for ConnectionIds {
+    fn generate(&mut self, connection_info: &ConnectionInfo) -> connection::LocalId {
+        self.next_captured(connection_info)
+            .unwrap_or_else(|| self.fallback.generate(connection_info))
+    }
+
+    fn generate_with_random(
+        &mut self,
+        connection_info: &ConnectionInfo,
+        random_generator: &mut dyn random::Generator,
+    ) -> connection::LocalId {
+        self.next_captured(connection_info).unwrap_or_else(|| {
+            self.fallback
+                .generate_with_random(connection_info, random_generator)
+        })
+    }
+}
+
+impl connection_id::Validator for ConnectionIds {
+    fn validate(&self, connection_info: &ConnectionInfo, buffer: &[u8]) -> Option<usize> {
+        match self.len {
+            Some(len) => len.validate(connection_info, buffer),
+            None => self.fallback.validate(connection_info, buffer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datagram(source: &str, payload: &[u8]) -> Datagram {
+        Datagram {
+            timestamp: Duration::ZERO,
+            source: source.parse().unwrap(),
+            destination: "192.0.2.1:443".parse().unwrap(),
+            payload: payload.to_vec(),
+        }
+    }
+
+    #[test]
+    fn build_test() {
+        let capture = Capture::from(vec![
+            datagram("192.0.2.2:1234", &[]),
+            Datagram {
+                destination: "192.0.2.2:1234".parse().unwrap(),
+                ..datagram("192.0.2.1:443", &[])
+            },
+        ]);
+
+        let io = Io::builder().with_capture(capture.clone()).build().unwrap();
+        assert_eq!(io.local_address, "192.0.2.1:443".parse().unwrap());
+        assert_eq!(io.datagrams.len(), 1);
+
+        assert!(Io::builder()
+            .with_capture(capture)
+            .with_local_address("192.0.2.3:443".parse().unwrap())
+            .build()
+            .is_err());
+        assert!(Io::builder().build().is_err());
+    }
+
+    #[test]
+    fn connection_ids_test() {
+        let capture = Capture::from(vec![
+            // Initial packet to the ID chosen by the client
+            datagram(
+                "192.0.2.2:1234",
+                &[0xc0, 0, 0, 0, 1, 8, 1, 1, 1, 1, 1, 1, 1, 1],
+            ),
+            // Handshake packet to the ID chosen by the server
+            datagram("192.0.2.2:1234", &[0xe0, 0, 0, 0, 1, 4, 2, 2, 2, 2]),
+            // short header packets to the same ID and to a new ID
+            datagram("192.0.2.2:1234", &[0x40, 2, 2, 2, 2]),
+            datagram("192.0.2.2:1234", &[0x40, 3, 3, 3, 3]),
+        ]);
+        let io = Io::builder().with_capture(capture).build().unwrap();
+        let mut ids = io.connection_ids();
+
+        let remote_address: SocketAddress = "192.0.2.2:1234".parse::<SocketAddr>().unwrap().into();
+        let info = ConnectionInfo::new(&remote_address);
+
+        for expected in [[2u8; 4], [3; 4]] {
+            let id = ids.generate(&info);
+            assert_eq!(id.as_bytes(), &expected[..]);
+        }
+
+        // new IDs have the same length as the captured IDs
+        assert_eq!(ids.generate(&info).as_bytes().len(), 4);
+        assert_eq!(ids.validate(&info, &[0; 16]), Some(4));
+    }
+}
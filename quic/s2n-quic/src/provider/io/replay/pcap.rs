@@ -0,0 +1,343 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reads UDP datagrams from captures in the pcap format
+//!
+//! See <https://datatracker.ietf.org/doc/draft-ietf-opsawg-pcap/> for the format.
+
+use super::Datagram;
+use core::time::Duration;
+use std::{
+    io::{self, Read},
+    net::{IpAddr, SocketAddr},
+};
+
+const MAGIC_MICROS: u32 = 0xa1b2_c3d4;
+const MAGIC_NANOS: u32 = 0xa1b2_3c4d;
+const MAGIC_PCAPNG: u32 = 0x0a0d_0d0a;
+
+// See <https://www.tcpdump.org/linktypes.html>
+const LINKTYPE_NULL: u32 = 0;
+const LINKTYPE_ETHERNET: u32 = 1;
+// Some platforms use a different value for raw IP captures
+const LINKTYPE_RAW_BSD: [u32; 2] = [12, 14];
+const LINKTYPE_RAW: u32 = 101;
+const LINKTYPE_LOOP: u32 = 108;
+const LINKTYPE_LINUX_SLL: u32 = 113;
+const LINKTYPE_IPV4: u32 = 228;
+const LINKTYPE_IPV6: u32 = 229;
+const LINKTYPE_LINUX_SLL2: u32 = 276;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+const ETHERTYPE_VLAN: [u16; 2] = [0x8100, 0x88a8];
+
+const PROTOCOL_UDP: u8 = 17;
+
+/// The maximum length of a record in the capture
+///
+/// This prevents corrupted captures from allocating arbitrary amounts of memory.
+const MAX_RECORD_LEN: usize = 256 * 1024;
+
+/// Reads all of the UDP datagrams in a pcap capture
+pub fn read<R: Read>(mut reader: R) -> io::Result<Vec<Datagram>> {
+    let mut header = [0u8; 24];
+    reader.read_exact(&mut header)?;
+
+    let magic = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    let (is_big_endian, is_nanos) = match magic {
+        MAGIC_MICROS => (false, false),
+        MAGIC_NANOS => (false, true),
+        _ if magic.swap_bytes() == MAGIC_MICROS => (true, false),
+        _ if magic.swap_bytes() == MAGIC_NANOS => (true, true),
+        MAGIC_PCAPNG => {
+            return Err(invalid_data(
+                "pcapng captures are not supported; convert the capture with `editcap -F pcap`",
+            ))
+        }
+        _ => return Err(invalid_data("the capture is not in the pcap format")),
+    };
+
+    let read_u32 = |bytes: &[u8]| {
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        if is_big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        }
+    };
+
+    // the upper bits of the link type field contain the FCS length, which isn't needed
+    let link_type = read_u32(&header[20..24]) & 0xffff;
+    match link_type {
+        LINKTYPE_NULL | LINKTYPE_ETHERNET | LINKTYPE_RAW | LINKTYPE_LOOP | LINKTYPE_LINUX_SLL
+        | LINKTYPE_IPV4 | LINKTYPE_IPV6 | LINKTYPE_LINUX_SLL2 => {}
+        _ if LINKTYPE_RAW_BSD.contains(&link_type) => {}
+        _ => {
+            return Err(invalid_data(format!(
+                "captures with link type {} are not supported",
+                link_type
+            )))
+        }
+    }
+
+    let mut datagrams = vec![];
+    let mut record = [0u8; 16];
+    let mut data = vec![];
+
+    loop {
+        // a capture which was interrupted while writing a record ends early
+        match reader.read_exact(&mut record) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err),
+        }
+
+        let seconds = read_u32(&record[0..4]);
+        let fraction = read_u32(&record[4..8]);
+        let captured_len = read_u32(&record[8..12]) as usize;
+        let original_len = read_u32(&record[12..16]) as usize;
+
+        if captured_len > MAX_RECORD_LEN {
+            return Err(invalid_data("the capture contains an oversized record"));
+        }
+
+        data.resize(captured_len, 0);
+        match reader.read_exact(&mut data) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err),
+        }
+
+        // skip packets which were truncated by the snapshot length
+        if captured_len < original_len {
+            continue;
+        }
+
+        let fraction = if is_nanos {
+            Duration::from_nanos(fraction as _)
+        } else {
+            Duration::from_micros(fraction as _)
+        };
+        let timestamp = Duration::from_secs(seconds as _) + fraction;
+
+        if let Some((source, destination, payload)) = link(link_type, &data) {
+            datagrams.push(Datagram {
+                timestamp,
+                source,
+                destination,
+                payload: payload.to_vec(),
+            });
+        }
+    }
+
+    Ok(datagrams)
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let bytes = bytes.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Removes the link layer header of the packet
+fn link(link_type: u32, data: &[u8]) -> Option<(SocketAddr, SocketAddr, &[u8])> {
+    let packet = match link_type {
+        // the address family in the header is redundant with the IP version
+        LINKTYPE_NULL | LINKTYPE_LOOP => data.get(4..)?,
+        LINKTYPE_ETHERNET => {
+            let mut offset = 12;
+            let mut ethertype = read_u16(data, offset)?;
+            while ETHERTYPE_VLAN.contains(&ethertype) {
+                offset += 4;
+                ethertype = read_u16(data, offset)?;
+            }
+            ethernet(ethertype, data.get(offset + 2..)?)?
+        }
+        LINKTYPE_LINUX_SLL => ethernet(read_u16(data, 14)?, data.get(16..)?)?,
+        LINKTYPE_LINUX_SLL2 => ethernet(read_u16(data, 0)?, data.get(20..)?)?,
+        _ => data,
+    };
+
+    ip(packet)
+}
+
+fn ethernet(ethertype: u16, packet: &[u8]) -> Option<&[u8]> {
+    if ethertype == ETHERTYPE_IPV4 || ethertype == ETHERTYPE_IPV6 {
+        Some(packet)
+    } else {
+        None
+    }
+}
+
+/// Removes the IP and UDP headers of the packet
+fn ip(packet: &[u8]) -> Option<(SocketAddr, SocketAddr, &[u8])> {
+    let (source, destination, segment) = match packet.first()? >> 4 {
+        4 => {
+            let header_len = (packet[0] & 0x0f) as usize * 4;
+            let total_len = read_u16(packet, 2)? as usize;
+            let fragment = read_u16(packet, 6)?;
+
+            // fragmented packets are not reassembled
+            let more_fragments = fragment & 0x2000 != 0;
+            let fragment_offset = fragment & 0x1fff;
+            if more_fragments || fragment_offset != 0 || *packet.get(9)? != PROTOCOL_UDP {
+                return None;
+            }
+
+            let source: [u8; 4] = packet.get(12..16)?.try_into().ok()?;
+            let destination: [u8; 4] = packet.get(16..20)?.try_into().ok()?;
+            let segment = packet.get(header_len..total_len)?;
+            (IpAddr::from(source), IpAddr::from(destination), segment)
+        }
+        6 => {
+            let payload_len = read_u16(packet, 4)? as usize;
+
+            // extension headers are not supported
+            if *packet.get(6)? != PROTOCOL_UDP {
+                return None;
+            }
+
+            let source: [u8; 16] = packet.get(8..24)?.try_into().ok()?;
+            let destination: [u8; 16] = packet.get(24..40)?.try_into().ok()?;
+            let segment = packet.get(40..40 + payload_len)?;
+            (IpAddr::from(source), IpAddr::from(destination), segment)
+        }
+        _ => return None,
+    };
+
+    let source_port = read_u16(segment, 0)?;
+    let destination_port = read_u16(segment, 2)?;
+    let len = read_u16(segment, 4)? as usize;
+    let payload = segment.get(8..len)?;
+
+    Some((
+        SocketAddr::new(source, source_port),
+        SocketAddr::new(destination, destination_port),
+        payload,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture(link_type: u32, packets: &[Vec<u8>]) -> Vec<u8> {
+        let mut capture = vec![];
+        capture.extend_from_slice(&MAGIC_MICROS.to_le_bytes());
+        capture.extend_from_slice(&2u16.to_le_bytes());
+        capture.extend_from_slice(&4u16.to_le_bytes());
+        capture.extend_from_slice(&[0; 8]);
+        capture.extend_from_slice(&65535u32.to_le_bytes());
+        capture.extend_from_slice(&link_type.to_le_bytes());
+
+        for (index, packet) in packets.iter().enumerate() {
+            capture.extend_from_slice(&(index as u32).to_le_bytes());
+            capture.extend_from_slice(&500u32.to_le_bytes());
+            capture.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+            capture.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+            capture.extend_from_slice(packet);
+        }
+
+        capture
+    }
+
+    fn udp(payload: &[u8]) -> Vec<u8> {
+        let mut segment = vec![];
+        segment.extend_from_slice(&1234u16.to_be_bytes());
+        segment.extend_from_slice(&443u16.to_be_bytes());
+        segment.extend_from_slice(&(8 + payload.len() as u16).to_be_bytes());
+        segment.extend_from_slice(&[0; 2]);
+        segment.extend_from_slice(payload);
+        segment
+    }
+
+    fn ipv4(protocol: u8, segment: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0x45, 0];
+        packet.extend_from_slice(&(20 + segment.len() as u16).to_be_bytes());
+        packet.extend_from_slice(&[0, 0, 0x40, 0, 64, protocol, 0, 0]);
+        packet.extend_from_slice(&[192, 0, 2, 2]);
+        packet.extend_from_slice(&[192, 0, 2, 1]);
+        packet.extend_from_slice(segment);
+        packet
+    }
+
+    fn ipv6(segment: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0x60, 0, 0, 0];
+        packet.extend_from_slice(&(segment.len() as u16).to_be_bytes());
+        packet.extend_from_slice(&[PROTOCOL_UDP, 64]);
+        packet.extend_from_slice(&[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2]);
+        packet.extend_from_slice(&[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        packet.extend_from_slice(segment);
+        packet
+    }
+
+    fn ethernet(ethertype: u16, packet: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0; 12];
+        // the frame is tagged with a VLAN
+        frame.extend_from_slice(&0x8100u16.to_be_bytes());
+        frame.extend_from_slice(&[0; 2]);
+        frame.extend_from_slice(&ethertype.to_be_bytes());
+        frame.extend_from_slice(packet);
+        frame
+    }
+
+    #[test]
+    fn ethernet_test() {
+        let packets = [
+            ethernet(ETHERTYPE_IPV4, &ipv4(PROTOCOL_UDP, &udp(b"hello"))),
+            // TCP packets are skipped
+            ethernet(ETHERTYPE_IPV4, &ipv4(6, &udp(b"skipped"))),
+            ethernet(ETHERTYPE_IPV6, &ipv6(&udp(b"world"))),
+        ];
+        let datagrams = read(&capture(LINKTYPE_ETHERNET, &packets)[..]).unwrap();
+
+        assert_eq!(
+            datagrams,
+            vec![
+                Datagram {
+                    timestamp: Duration::from_micros(500),
+                    source: "192.0.2.2:1234".parse().unwrap(),
+                    destination: "192.0.2.1:443".parse().unwrap(),
+                    payload: b"hello".to_vec(),
+                },
+                Datagram {
+                    timestamp: Duration::from_secs(2) + Duration::from_micros(500),
+                    source: "[2001:db8::2]:1234".parse().unwrap(),
+                    destination: "[2001:db8::1]:443".parse().unwrap(),
+                    payload: b"world".to_vec(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn raw_test() {
+        let packets = [ipv6(&udp(b"hello")), ipv4(PROTOCOL_UDP, &udp(b"world"))];
+        let mut capture = capture(LINKTYPE_RAW, &packets);
+
+        // a partially written record is ignored
+        capture.extend_from_slice(&[0; 20]);
+
+        let datagrams = read(&capture[..]).unwrap();
+        assert_eq!(datagrams.len(), 2);
+        assert_eq!(datagrams[0].payload, b"hello");
+        assert_eq!(datagrams[1].payload, b"world");
+    }
+
+    #[test]
+    fn invalid_test() {
+        let mut pcapng = capture(LINKTYPE_RAW, &[]);
+        pcapng[..4].copy_from_slice(&MAGIC_PCAPNG.to_le_bytes());
+        assert!(read(&pcapng[..]).is_err());
+
+        let unsupported_link_type = capture(147, &[]);
+        assert!(read(&unsupported_link_type[..]).is_err());
+
+        // missing header
+        assert!(read(&[0u8; 4][..]).is_err());
+    }
+}
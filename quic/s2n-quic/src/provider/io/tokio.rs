@@ -3,12 +3,81 @@
 
 //! Provides an implementation of the [`io::Provider`](crate::provider::io::Provider)
 //! using the [`Tokio runtime`](https://docs.rs/tokio/latest/tokio/runtime/index.html)
+//!
+//! By default, the endpoint is spawned as a task on the current runtime. Alternatively, the
+//! provider can be built with [`Builder::build_no_spawn`] to hand the endpoint to a [`Driver`],
+//! which the application polls on a task or thread of its choosing.
+//!
+//! ```rust,no_run
+//! # use std::error::Error;
+//! use s2n_quic::{provider::io::tokio::Builder as IoBuilder, Server};
+//! use std::net::ToSocketAddrs;
+//!
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! let addr = "127.0.0.1:443".to_socket_addrs()?.next().unwrap();
+//!
+//! let (io, driver) = IoBuilder::default()
+//!     .with_receive_address(addr)?
+//!     .build_no_spawn()?;
+//!
+//! // no runtime is required to start the server in this mode
+//! let mut server = Server::builder().with_io(io)?.start()?;
+//!
+//! // drive the endpoint and the application on a single thread
+//! let runtime = tokio::runtime::Builder::new_current_thread()
+//!     .enable_all()
+//!     .build()?;
+//!
+//! let (result, _) = runtime.block_on(async move {
+//!     let application = async move {
+//!         while let Some(connection) = server.accept().await {
+//!             // handle the connection
+//!             let _ = connection;
+//!         }
+//!     };
+//!
+//!     tokio::join!(driver, application)
+//! });
+//!
+//! result?;
+//! #
+//! #    Ok(())
+//! # }
+//! ```
+//!
+//! The [`Features`] handle reports whether the socket features, e.g. Generic Segmentation Offload
+//! (GSO) and ECN, are active once the endpoint is started. The features can be disabled while the
+//! endpoint is running, e.g. when a kernel bug is suspected, without re-creating the endpoint.
+//!
+//! ```rust,no_run
+//! # use std::error::Error;
+//! use s2n_quic::{provider::io::tokio::Builder as IoBuilder, Server};
+//! use std::net::ToSocketAddrs;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn Error>> {
+//! let addr = "127.0.0.1:443".to_socket_addrs()?.next().unwrap();
+//!
+//! let io = IoBuilder::default().with_receive_address(addr)?.build()?;
+//! let features = io.features();
+//!
+//! let server = Server::builder().with_io(io)?.start()?;
+//!
+//! println!("GSO segments: {}", features.gso_max_segments());
+//!
+//! // the feature is disabled the next time the endpoint wakes up
+//! features.disable_gso();
+//! # let _ = server;
+//! #
+//! #    Ok(())
+//! # }
+//! ```
 
 use s2n_quic_core::{endpoint::Endpoint, inet::SocketAddress};
 use s2n_quic_platform::io::tokio;
 use std::io;
 
-pub use self::tokio::{Builder, Io as Provider};
+pub use self::tokio::{Builder, Driver, Features, Io as Provider};
 
 impl super::Provider for Provider {
     type PathHandle = tokio::PathHandle;
@@ -22,3 +91,13 @@ impl super::Provider for Provider {
         Ok(local_addr)
     }
 }
+
+impl super::ShardedProvider for Provider {
+    fn start_sharded<E: Endpoint<PathHandle = Self::PathHandle>>(
+        self,
+        endpoints: Vec<E>,
+    ) -> Result<SocketAddress, Self::Error> {
+        let (_join_handles, local_addr) = Provider::start_sharded(self, endpoints)?;
+        Ok(local_addr)
+    }
+}
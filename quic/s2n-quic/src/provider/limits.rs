@@ -3,7 +3,7 @@
 
 //! Provides limits support for a connection
 
-pub use s2n_quic_core::connection::limits::{ConnectionInfo, Limiter, Limits};
+pub use s2n_quic_core::connection::limits::{ConnectionInfo, InitialPadding, Limiter, Limits};
 
 pub trait Provider {
     type Limits: 'static + Send + Limiter;
@@ -26,7 +26,7 @@ impl<T: 'static + Send + Limiter> Provider for T {
 }
 
 pub mod default {
-    #[derive(Debug, Default)]
+    #[derive(Clone, Debug, Default)]
     pub struct Provider(());
 
     impl super::Provider for Provider {
@@ -0,0 +1,32 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides the MTU configuration for each path
+//!
+//! By default, every path uses the MTU configured on the IO provider and the connection
+//! limits. A [`Config`] applies the same initial, base and max MTU to every path, while a
+//! custom [`Endpoint`] can configure each path individually, e.g. to use jumbo frames for
+//! peers within a datacenter subnet. Packets on the path, including GSO segments, are sized
+//! according to the resulting path MTU.
+
+pub use s2n_quic_core::path::mtu::{default, Builder, Config, Endpoint, PathInfo};
+
+pub trait Provider {
+    type Endpoint: 'static + Send + Endpoint;
+    type Error: 'static + core::fmt::Display;
+
+    fn start(self) -> Result<Self::Endpoint, Self::Error>;
+}
+
+impl_provider_utils!();
+
+pub type Default = default::Endpoint;
+
+impl<T: 'static + Send + Endpoint> Provider for T {
+    type Endpoint = T;
+    type Error = core::convert::Infallible;
+
+    fn start(self) -> Result<Self::Endpoint, Self::Error> {
+        Ok(self)
+    }
+}
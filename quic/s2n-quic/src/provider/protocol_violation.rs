@@ -0,0 +1,34 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides the policy for responding to protocol violations by the peer
+//!
+//! Some protocol violations may be treated as a connection error, without the specification
+//! requiring it. By default, the connection is closed on all of them. An [`Outcome`] applies
+//! the same response to every [`Violation`] on every connection, while a custom [`Endpoint`]
+//! can create a [`Policy`] for each connection. Ignored violations are reported with the
+//! `ProtocolViolationIgnored` event.
+
+pub use s2n_quic_core::connection::protocol_violation::{
+    default, ConnectionInfo, Endpoint, Outcome, Policy, Violation,
+};
+
+pub trait Provider {
+    type Endpoint: 'static + Send + Endpoint;
+    type Error: 'static + core::fmt::Display;
+
+    fn start(self) -> Result<Self::Endpoint, Self::Error>;
+}
+
+impl_provider_utils!();
+
+pub type Default = default::Endpoint;
+
+impl<T: 'static + Send + Endpoint> Provider for T {
+    type Endpoint = T;
+    type Error = core::convert::Infallible;
+
+    fn start(self) -> Result<Self::Endpoint, Self::Error> {
+        Ok(self)
+    }
+}
@@ -15,6 +15,9 @@ pub use self::rand::Provider as Default;
 
 impl_provider_utils!();
 
+#[cfg(any(test, feature = "unstable-provider-random"))]
+pub mod seeded;
+
 mod rand {
     use core::convert::Infallible;
     use rand::{
@@ -31,6 +34,14 @@ mod rand {
     #[derive(Debug, Default)]
     pub struct Provider(Generator);
 
+    /// Cloning the provider creates an independently seeded generator, so endpoints started
+    /// from the clones don't produce the same random values
+    impl Clone for Provider {
+        fn clone(&self) -> Self {
+            Self::default()
+        }
+    }
+
     impl super::Provider for Provider {
         type Generator = Generator;
         type Error = Infallible;
@@ -0,0 +1,117 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A deterministic random provider, seeded from a fixed value
+//!
+//! **NOTE**: The generated values are entirely predictable given the seed. This provider
+//! should only be used for testing and debugging; never in production deployments.
+
+use core::convert::Infallible;
+use rand::prelude::*;
+use rand_chacha::ChaCha20Rng;
+use s2n_quic_core::random;
+
+#[derive(Debug)]
+pub struct Provider(Generator);
+
+impl Provider {
+    /// Creates a provider with the given `seed`
+    pub fn new(seed: u64) -> Self {
+        Self(Generator::new(seed))
+    }
+}
+
+impl super::Provider for Provider {
+    type Generator = Generator;
+    type Error = Infallible;
+
+    fn start(self) -> Result<Self::Generator, Self::Error> {
+        Ok(self.0)
+    }
+}
+
+impl super::TryInto for Generator {
+    type Provider = Provider;
+    type Error = Infallible;
+
+    fn try_into(self) -> Result<Self::Provider, Self::Error> {
+        Ok(Provider(self))
+    }
+}
+
+/// Deterministically generated bits.
+///
+/// Two generators constructed with the same seed will produce identical sequences.
+#[derive(Debug)]
+pub struct Generator {
+    public: ChaCha20Rng,
+    private: ChaCha20Rng,
+}
+
+impl Generator {
+    /// Creates a generator with the given `seed`
+    pub fn new(seed: u64) -> Self {
+        let mut public = ChaCha20Rng::seed_from_u64(seed);
+        let mut private = ChaCha20Rng::seed_from_u64(seed);
+
+        // keep the public and private sequences independent of each other
+        public.set_stream(0);
+        private.set_stream(1);
+
+        Self { public, private }
+    }
+}
+
+impl random::Generator for Generator {
+    fn public_random_fill(&mut self, dest: &mut [u8]) {
+        self.public.fill_bytes(dest)
+    }
+
+    fn private_random_fill(&mut self, dest: &mut [u8]) {
+        self.private.fill_bytes(dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Generator;
+    use s2n_quic_core::random::Generator as _;
+
+    #[test]
+    fn deterministic_test() {
+        let mut a = Generator::new(123);
+        let mut b = Generator::new(123);
+
+        let mut dest_a = [0; 20];
+        let mut dest_b = [0; 20];
+
+        for _ in 0..10 {
+            a.public_random_fill(&mut dest_a);
+            b.public_random_fill(&mut dest_b);
+            assert_eq!(dest_a, dest_b);
+
+            a.private_random_fill(&mut dest_a);
+            b.private_random_fill(&mut dest_b);
+            assert_eq!(dest_a, dest_b);
+        }
+    }
+
+    #[test]
+    fn independent_streams_test() {
+        let mut generator = Generator::new(123);
+
+        let mut public = [0; 20];
+        let mut private = [0; 20];
+
+        generator.public_random_fill(&mut public);
+        generator.private_random_fill(&mut private);
+
+        assert_ne!(public, private);
+
+        let mut other = Generator::new(456);
+        let mut other_public = [0; 20];
+        other.public_random_fill(&mut other_public);
+
+        assert_ne!(public, other_public);
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Allows servers to run as stateless front-ends which validate the addresses of clients and
+//! forward the datagrams of validated clients to back-end servers
+//!
+//! By default, servers handle all of the connections themselves. A server configured with a
+//! [`Forwarder`] answers every Initial packet without an address validation token with a Retry
+//! packet and hands all of the other datagrams to the forwarder, without creating any
+//! connections. The front-ends and back-ends must share the keys of the address validation
+//! tokens, e.g. with
+//! [`address_token::Default::with_shared_secret`](crate::provider::address_token::Default::with_shared_secret),
+//! so the back-ends can verify that the forwarded Initial packets were validated by a front-end.
+
+pub use s2n_quic_core::endpoint::retry_offload::{Datagram, Disabled, Forwarder};
+
+pub trait Provider: 'static {
+    type Forwarder: 'static + Send + Forwarder;
+    type Error: 'static + core::fmt::Display;
+
+    /// Starts the forwarder
+    fn start(self) -> Result<Self::Forwarder, Self::Error>;
+}
+
+impl_provider_utils!();
+
+pub type Default = Disabled;
+
+impl<T: 'static + Send + Forwarder> Provider for T {
+    type Forwarder = T;
+    type Error = core::convert::Infallible;
+
+    fn start(self) -> Result<Self::Forwarder, Self::Error> {
+        Ok(self)
+    }
+}
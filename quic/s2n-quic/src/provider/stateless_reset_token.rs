@@ -16,12 +16,14 @@ pub use random::Provider as Default;
 
 impl_provider_utils!();
 
+pub mod hmac;
+
 mod random {
     use core::convert::Infallible;
     use rand::prelude::*;
     use s2n_quic_core::{frame::new_connection_id::STATELESS_RESET_TOKEN_LEN, stateless_reset};
 
-    #[derive(Debug, Default)]
+    #[derive(Clone, Debug, Default)]
     pub struct Provider(Generator);
 
     impl super::Provider for Provider {
@@ -43,7 +45,7 @@ mod random {
     }
 
     /// Randomly generated stateless reset token.
-    #[derive(Debug, Default)]
+    #[derive(Clone, Debug, Default)]
     pub struct Generator {}
 
     impl stateless_reset::token::Generator for Generator {
@@ -0,0 +1,155 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Stateless reset tokens derived from a static key
+//!
+//! Each token is computed as `HMAC-SHA256(key, local_connection_id)`, truncated to 16 bytes.
+//! Since the token only depends on the key and the connection ID, it doesn't need to be stored
+//! and can be recomputed after the endpoint lost the state of a connection, which allows it to
+//! send a stateless reset the peer will recognize. Every new connection ID issued to the peer is
+//! associated with a different token.
+//!
+//! All of the endpoints which can receive packets for a connection ID, e.g. the instances of a
+//! server behind a load balancer or a server that restarts, must be configured with the same
+//! key. The key must be kept secret, as anyone who knows it can reset the connections of the
+//! endpoint.
+
+use core::{convert::Infallible, fmt};
+use ring::hmac;
+use s2n_quic_core::{frame::new_connection_id::STATELESS_RESET_TOKEN_LEN, stateless_reset};
+
+/// The minimum length of the key, in bytes
+pub const MIN_KEY_LEN: usize = STATELESS_RESET_TOKEN_LEN;
+
+#[derive(Clone, Debug)]
+pub struct Provider(Generator);
+
+impl Provider {
+    /// Creates a provider which derives stateless reset tokens from the given key
+    ///
+    /// The key must be at least [`MIN_KEY_LEN`] bytes long and should be generated with a
+    /// cryptographically secure random number generator.
+    pub fn new(key: &[u8]) -> Result<Self, Error> {
+        Ok(Self(Generator::new(key)?))
+    }
+}
+
+impl super::Provider for Provider {
+    type Generator = Generator;
+    type Error = Infallible;
+
+    fn start(self) -> Result<Self::Generator, Self::Error> {
+        Ok(self.0)
+    }
+}
+
+impl super::TryInto for Generator {
+    type Provider = Provider;
+    type Error = Infallible;
+
+    fn try_into(self) -> Result<Self::Provider, Self::Error> {
+        Ok(Provider(self))
+    }
+}
+
+/// Generates stateless reset tokens from a static key
+#[derive(Clone, Debug)]
+pub struct Generator {
+    key: hmac::Key,
+}
+
+impl Generator {
+    /// Creates a generator which derives stateless reset tokens from the given key
+    ///
+    /// The key must be at least [`MIN_KEY_LEN`] bytes long and should be generated with a
+    /// cryptographically secure random number generator.
+    pub fn new(key: &[u8]) -> Result<Self, Error> {
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-10.3.2
+        //# The stateless reset token MUST be difficult to guess.
+        if key.len() < MIN_KEY_LEN {
+            return Err(Error::KeyTooShort);
+        }
+
+        Ok(Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, key),
+        })
+    }
+}
+
+impl stateless_reset::token::Generator for Generator {
+    fn generate(&mut self, local_connection_id: &[u8]) -> stateless_reset::Token {
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-10.3.2
+        //# An endpoint could use HMAC [RFC2104] (for
+        //# example, HMAC(static_key, connection_id)) or the HMAC-based Key
+        //# Derivation Function (HKDF) [RFC5869] (for example, using the static
+        //# key as input keying material, with the connection ID as salt).  The
+        //# output of this function is truncated to 16 bytes to produce the
+        //# stateless reset token for that connection.
+        let tag = hmac::sign(&self.key, local_connection_id);
+
+        let mut token = [0u8; STATELESS_RESET_TOKEN_LEN];
+        token.copy_from_slice(&tag.as_ref()[..STATELESS_RESET_TOKEN_LEN]);
+        token.into()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The key is shorter than [`MIN_KEY_LEN`]
+    KeyTooShort,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::KeyTooShort => write!(
+                f,
+                "the stateless reset key must be at least {} bytes long",
+                MIN_KEY_LEN
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_quic_core::{connection, stateless_reset::token::Generator as _};
+
+    #[test]
+    fn stateless_reset_token_test() {
+        let mut generator = Generator::new(&[1; 32]).unwrap();
+        let id_1 = connection::LocalId::try_from_bytes(b"id01").unwrap();
+        let id_2 = connection::LocalId::try_from_bytes(b"id02").unwrap();
+
+        // the same token is derived for a connection ID without storing it
+        let token_1 = generator.generate(id_1.as_bytes());
+        assert_eq!(token_1, generator.generate(id_1.as_bytes()));
+        assert_eq!(
+            token_1,
+            Generator::new(&[1; 32]).unwrap().generate(id_1.as_bytes())
+        );
+
+        // each connection ID is associated with a different token
+        assert_ne!(token_1, generator.generate(id_2.as_bytes()));
+
+        // the token depends on the key
+        assert_ne!(
+            token_1,
+            Generator::new(&[2; 32]).unwrap().generate(id_1.as_bytes())
+        );
+    }
+
+    #[test]
+    fn key_len_test() {
+        assert_eq!(
+            Generator::new(&[1; MIN_KEY_LEN - 1]).unwrap_err(),
+            Error::KeyTooShort
+        );
+        assert!(Generator::new(&[1; MIN_KEY_LEN]).is_ok());
+        assert!(Provider::new(&[]).is_err());
+    }
+}
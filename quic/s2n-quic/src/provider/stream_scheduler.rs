@@ -0,0 +1,36 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides the weights for sharing the transmission capacity of a connection between its
+//! stream groups
+//!
+//! Once an application creates stream groups on a connection, each group and the class of
+//! streams which didn't join a group receive a share of the transmission capacity which is
+//! proportional to their weight. Every class with pending data makes progress, regardless of its
+//! weight. By default, the weight is derived from the urgency of each group, which is set with
+//! `Connection::set_stream_group_urgency`. A custom [`Endpoint`] can create a [`Scheduler`] for
+//! each connection.
+
+pub use s2n_quic_core::stream::scheduler::{
+    default, Class, ConnectionInfo, Endpoint, Scheduler, DEFAULT_URGENCY, MAX_URGENCY, MIN_URGENCY,
+};
+
+pub trait Provider {
+    type Endpoint: 'static + Send + Endpoint;
+    type Error: 'static + core::fmt::Display;
+
+    fn start(self) -> Result<Self::Endpoint, Self::Error>;
+}
+
+impl_provider_utils!();
+
+pub type Default = default::Endpoint;
+
+impl<T: 'static + Send + Endpoint> Provider for T {
+    type Endpoint = T;
+    type Error = core::convert::Infallible;
+
+    fn start(self) -> Result<Self::Endpoint, Self::Error> {
+        Ok(self)
+    }
+}
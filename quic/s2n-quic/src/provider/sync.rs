@@ -14,7 +14,7 @@ pub use default::Provider as Default;
 impl_provider_utils!();
 
 pub mod default {
-    #[derive(Debug, Default)]
+    #[derive(Clone, Debug, Default)]
     pub struct Provider;
 
     impl super::Provider for Provider {
@@ -0,0 +1,444 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Allows servers to assign connections to tenants and limit the resources each tenant may use
+//!
+//! By default, connections don't belong to any tenant. [`Quotas`] groups connections with a
+//! classification function, e.g. by the requested server name or the address token, and refuses
+//! new connections for tenants which exceed their [`Quota`]. Refused connections are closed with
+//! a `CONNECTION_REFUSED` error.
+
+pub use s2n_quic_core::endpoint::tenant::{Classifier, Disabled, Info, Outcome, Tenant};
+use s2n_quic_core::event::Timestamp;
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+pub trait Provider: 'static {
+    type Classifier: 'static + Send + Classifier;
+    type Error: 'static + core::fmt::Display;
+
+    /// Starts the tenant classifier
+    fn start(self) -> Result<Self::Classifier, Self::Error>;
+}
+
+impl_provider_utils!();
+
+pub type Default = Disabled;
+
+impl<T: 'static + Send + Classifier> Provider for T {
+    type Classifier = T;
+    type Error = core::convert::Infallible;
+
+    fn start(self) -> Result<Self::Classifier, Self::Error> {
+        Ok(self)
+    }
+}
+
+/// The interval over which the bandwidth of a tenant is measured
+const RATE_INTERVAL: core::time::Duration = core::time::Duration::from_secs(1);
+
+/// The resources a tenant may use
+///
+/// Quotas govern the admission of new connections: once a tenant reaches any of its limits,
+/// new connections are refused until enough of its existing connections close or become idle.
+/// Limits which aren't set are unbounded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Quota {
+    max_connections: Option<u32>,
+    max_open_streams: Option<u64>,
+    max_bytes_per_second: Option<u64>,
+}
+
+impl Quota {
+    /// Creates a quota without any limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of concurrent connections of the tenant
+    pub fn with_max_connections(mut self, value: u32) -> Self {
+        self.max_connections = Some(value);
+        self
+    }
+
+    /// Sets the maximum number of streams open across all connections of the tenant
+    pub fn with_max_open_streams(mut self, value: u64) -> Self {
+        self.max_open_streams = Some(value);
+        self
+    }
+
+    /// Sets the maximum rate of stream bytes sent and received across all connections of
+    /// the tenant
+    pub fn with_max_bytes_per_second(mut self, value: u64) -> Self {
+        self.max_bytes_per_second = Some(value);
+        self
+    }
+}
+
+/// Enforces a [`Quota`] for each tenant
+///
+/// The classification function returns the key of the tenant a connection belongs to along with
+/// its quota, or `None` if the connection isn't subject to quotas. Usage is shared between all
+/// clones of the classifier, which includes the classifiers of each shard of a server.
+///
+/// ```rust
+/// use s2n_quic::provider::tenant::{Quota, Quotas};
+///
+/// // allow up to 100 connections for each server name
+/// let quotas = Quotas::new(|info: &s2n_quic::provider::tenant::Info| {
+///     let server_name = info.server_name?.to_string();
+///     Some((server_name, Quota::new().with_max_connections(100)))
+/// });
+/// # let _ = quotas;
+/// ```
+pub struct Quotas<F, K> {
+    classify: F,
+    tenants: Arc<Mutex<HashMap<K, Arc<Usage>>>>,
+}
+
+impl<F, K> Quotas<F, K>
+where
+    F: FnMut(&Info) -> Option<(K, Quota)>,
+    K: Eq + Hash,
+{
+    /// Creates a classifier which groups connections with the given function
+    pub fn new(classify: F) -> Self {
+        Self {
+            classify,
+            tenants: Arc::default(),
+        }
+    }
+}
+
+impl<F: Clone, K> Clone for Quotas<F, K> {
+    fn clone(&self) -> Self {
+        Self {
+            classify: self.classify.clone(),
+            tenants: self.tenants.clone(),
+        }
+    }
+}
+
+impl<F, K> Classifier for Quotas<F, K>
+where
+    F: 'static + Send + FnMut(&Info) -> Option<(K, Quota)>,
+    K: 'static + Send + Clone + Eq + Hash,
+{
+    type Tenant = Connection<K>;
+
+    fn on_connection(&mut self, info: &Info) -> Outcome<Self::Tenant> {
+        let (key, quota) = match (self.classify)(info) {
+            Some(tenant) => tenant,
+            None => return Outcome::exempt(),
+        };
+
+        let usage = self
+            .tenants
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_default()
+            .clone();
+
+        // create the handle before checking the quota so the tenant is released if the
+        // connection is refused
+        let connection = Connection {
+            key,
+            usage,
+            tenants: self.tenants.clone(),
+            open_streams: 0,
+            is_admitted: false,
+        };
+
+        connection.admit(&quota, info.timestamp)
+    }
+}
+
+/// Tracks the resources used by all of the connections of a tenant
+#[derive(Debug, Default)]
+struct Usage {
+    connections: AtomicU32,
+    open_streams: AtomicU64,
+    bytes: AtomicU64,
+    rate: Mutex<Rate>,
+}
+
+#[derive(Debug, Default)]
+struct Rate {
+    start: Option<Timestamp>,
+    bytes: u64,
+    bytes_per_second: u64,
+}
+
+impl Usage {
+    /// Returns the rate of stream bytes measured over the last completed interval
+    fn bytes_per_second(&self, now: Timestamp) -> u64 {
+        let mut rate = self.rate.lock().unwrap();
+        let bytes = self.bytes.load(Ordering::Relaxed);
+
+        let start = match rate.start {
+            Some(start) => start,
+            None => {
+                rate.start = Some(now);
+                rate.bytes = bytes;
+                return 0;
+            }
+        };
+
+        let elapsed = now.saturating_duration_since(start);
+        if elapsed >= RATE_INTERVAL {
+            let transferred = bytes.saturating_sub(rate.bytes) as u128;
+            let bytes_per_second = transferred * 1000 / elapsed.as_millis();
+            rate.bytes_per_second = bytes_per_second.min(u64::MAX as u128) as u64;
+            rate.start = Some(now);
+            rate.bytes = bytes;
+        }
+
+        rate.bytes_per_second
+    }
+}
+
+/// Reports the resources used by a single connection to its tenant
+///
+/// The resources are released when the connection is dropped.
+pub struct Connection<K: Eq + Hash> {
+    key: K,
+    usage: Arc<Usage>,
+    tenants: Arc<Mutex<HashMap<K, Arc<Usage>>>>,
+    open_streams: u64,
+    is_admitted: bool,
+}
+
+impl<K: Eq + Hash> core::fmt::Debug for Connection<K> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Connection")
+            .field("open_streams", &self.open_streams)
+            .field("is_admitted", &self.is_admitted)
+            .finish()
+    }
+}
+
+impl<K: Eq + Hash> Connection<K> {
+    fn admit(mut self, quota: &Quota, now: Timestamp) -> Outcome<Self> {
+        let usage = &self.usage;
+
+        if let Some(max) = quota.max_open_streams {
+            if usage.open_streams.load(Ordering::Relaxed) >= max {
+                return Outcome::refuse();
+            }
+        }
+
+        if let Some(max) = quota.max_bytes_per_second {
+            if usage.bytes_per_second(now) > max {
+                return Outcome::refuse();
+            }
+        }
+
+        let admitted = usage
+            .connections
+            .fetch_update(
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+                |connections| match quota.max_connections {
+                    Some(max) if connections >= max => None,
+                    _ => Some(connections + 1),
+                },
+            )
+            .is_ok();
+
+        if !admitted {
+            return Outcome::refuse();
+        }
+
+        self.is_admitted = true;
+        Outcome::admit(self)
+    }
+}
+
+impl<K: 'static + Send + Eq + Hash> Tenant for Connection<K> {
+    #[inline]
+    fn on_open_streams(&mut self, count: u64) {
+        let open_streams = &self.usage.open_streams;
+
+        if count > self.open_streams {
+            open_streams.fetch_add(count - self.open_streams, Ordering::Relaxed);
+        } else {
+            open_streams.fetch_sub(self.open_streams - count, Ordering::Relaxed);
+        }
+
+        self.open_streams = count;
+    }
+
+    #[inline]
+    fn on_stream_bytes(&mut self, bytes: u64) {
+        self.usage.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+}
+
+impl<K: Eq + Hash> Drop for Connection<K> {
+    fn drop(&mut self) {
+        if self.is_admitted {
+            self.usage.connections.fetch_sub(1, Ordering::Relaxed);
+        }
+        self.usage
+            .open_streams
+            .fetch_sub(self.open_streams, Ordering::Relaxed);
+
+        // Usage is only cloned while holding the lock, so the tenant can be removed once the
+        // last handle is dropped
+        if let Ok(mut tenants) = self.tenants.lock() {
+            if Arc::strong_count(&self.usage) == 2 {
+                tenants.remove(&self.key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_quic_core::{
+        application::ServerName,
+        event::IntoEvent,
+        inet::SocketAddress,
+        time::{testing::Clock as MockClock, Clock},
+    };
+
+    fn quotas(quota: Quota) -> impl Classifier<Tenant = Connection<String>> + Clone {
+        Quotas::new(move |info: &Info| Some((info.server_name?.to_string(), quota)))
+    }
+
+    fn classify<C: Classifier>(
+        classifier: &mut C,
+        server_name: &str,
+        clock: &MockClock,
+    ) -> Option<C::Tenant> {
+        let server_name = ServerName::from(server_name);
+        let remote_address = SocketAddress::default();
+        let info = Info::new(
+            Some(&server_name),
+            &[],
+            &remote_address,
+            clock.get_time().into_event(),
+        );
+
+        match classifier.on_connection(&info) {
+            Outcome::Admit { tenant, .. } => Some(tenant),
+            Outcome::Refuse { .. } => None,
+            _ => panic!("connection should be classified"),
+        }
+    }
+
+    #[test]
+    fn max_connections_test() {
+        let clock = MockClock::default();
+        let mut classifier = quotas(Quota::new().with_max_connections(2));
+
+        let first = classify(&mut classifier, "a.example.com", &clock).unwrap();
+        let second = classify(&mut classifier, "a.example.com", &clock).unwrap();
+        assert!(classify(&mut classifier, "a.example.com", &clock).is_none());
+
+        // other tenants aren't affected
+        assert!(classify(&mut classifier, "b.example.com", &clock).is_some());
+
+        // closing a connection releases its slot
+        drop(first);
+        let _third = classify(&mut classifier, "a.example.com", &clock).unwrap();
+        assert!(classify(&mut classifier, "a.example.com", &clock).is_none());
+
+        drop(second);
+    }
+
+    #[test]
+    fn shared_between_clones_test() {
+        let clock = MockClock::default();
+        let mut classifier = quotas(Quota::new().with_max_connections(1));
+        let mut shard = classifier.clone();
+
+        let _connection = classify(&mut classifier, "example.com", &clock).unwrap();
+        assert!(classify(&mut shard, "example.com", &clock).is_none());
+    }
+
+    #[test]
+    fn max_open_streams_test() {
+        let clock = MockClock::default();
+        let mut classifier = quotas(Quota::new().with_max_open_streams(10));
+
+        let mut connection = classify(&mut classifier, "example.com", &clock).unwrap();
+        connection.on_open_streams(10);
+        assert!(classify(&mut classifier, "example.com", &clock).is_none());
+
+        connection.on_open_streams(9);
+        let other = classify(&mut classifier, "example.com", &clock).unwrap();
+
+        // dropping a connection releases its streams
+        connection.on_open_streams(10);
+        drop(connection);
+        drop(classify(&mut classifier, "example.com", &clock).unwrap());
+        drop(other);
+    }
+
+    #[test]
+    fn max_bytes_per_second_test() {
+        let mut clock = MockClock::default();
+        let mut classifier = quotas(Quota::new().with_max_bytes_per_second(1000));
+
+        let mut connection = classify(&mut classifier, "example.com", &clock).unwrap();
+        connection.on_stream_bytes(5000);
+
+        // the rate is only measured once the interval elapsed
+        assert!(classify(&mut classifier, "example.com", &clock).is_some());
+
+        clock.inc_by(RATE_INTERVAL * 2);
+        assert!(classify(&mut classifier, "example.com", &clock).is_none());
+
+        // the tenant is admitted again once it becomes idle
+        clock.inc_by(RATE_INTERVAL);
+        assert!(classify(&mut classifier, "example.com", &clock).is_some());
+
+        drop(connection);
+    }
+
+    #[test]
+    fn exempt_test() {
+        let clock = MockClock::default();
+        let mut classifier = Quotas::new(|_: &Info| None::<(String, Quota)>);
+        let server_name = ServerName::from("example.com");
+        let remote_address = SocketAddress::default();
+        let info = Info::new(
+            Some(&server_name),
+            &[],
+            &remote_address,
+            clock.get_time().into_event(),
+        );
+
+        assert!(matches!(
+            classifier.on_connection(&info),
+            Outcome::Exempt { .. }
+        ));
+    }
+
+    #[test]
+    fn tenant_removed_test() {
+        let clock = MockClock::default();
+        let quotas = Quotas::new(|info: &Info| {
+            Some((
+                info.server_name?.to_string(),
+                Quota::new().with_max_connections(1),
+            ))
+        });
+        let mut classifier = quotas.clone();
+
+        let connection = classify(&mut classifier, "example.com", &clock).unwrap();
+        assert_eq!(quotas.tenants.lock().unwrap().len(), 1);
+
+        drop(connection);
+        assert!(quotas.tenants.lock().unwrap().is_empty());
+    }
+}
@@ -20,6 +20,12 @@ pub trait Provider {
 
 impl_provider_utils!();
 
+#[cfg(any(test, all(not(docdiff), feature = "unstable-provider-tls-dangerous")))]
+pub mod dangerous;
+
+#[cfg(any(test, all(not(docdiff), feature = "unstable-provider-tls-replay")))]
+pub mod replay;
+
 cfg_if! {
     if #[cfg(feature = "provider-tls-default")] {
         pub mod default {
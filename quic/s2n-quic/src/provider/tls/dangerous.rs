@@ -0,0 +1,389 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides TLS implementations which disable packet protection
+//!
+//! **WARNING**: these providers remove all of the confidentiality and integrity guarantees of
+//! QUIC and must never be used outside of testing and debugging.
+//!
+//! [`NullCipher`] wraps another TLS provider. The TLS handshake is still performed, so peers
+//! are authenticated and negotiate transport parameters as usual. The keys derived from the
+//! handshake are replaced with a null cipher, which leaves packet payloads and headers in
+//! plaintext. This avoids the overhead of encryption when fuzzing the protocol and makes packet
+//! captures readable without exporting the session keys. Both peers must use the null cipher to
+//! establish a connection.
+//!
+//! ```rust,no_run
+//! # fn test() -> Result<(), Box<dyn std::error::Error>> {
+//! #   let (cert_pem, key_pem) = ("", "");
+//! use s2n_quic::{provider::tls::dangerous::NullCipher, Server};
+//!
+//! let server = Server::builder()
+//!     .with_tls(NullCipher::new((cert_pem, key_pem)))?
+//!     .with_io("127.0.0.1:4433")?
+//!     .start()?;
+//! #
+//! #   Ok(())
+//! # }
+//! ```
+
+use crate::provider::tls;
+use core::task::Poll;
+use s2n_codec::EncoderValue;
+use s2n_quic_core::{
+    application::ServerName,
+    crypto::{
+        self,
+        retry::{IntegrityTag, INTEGRITY_TAG_LEN},
+        tls::{ApplicationParameters, Bytes, CipherSuite},
+        CryptoError, CryptoSuite, HeaderProtectionMask,
+    },
+    transport,
+};
+
+/// The length of the tag appended to each packet
+///
+/// Packets are the same size as with AES-GCM, so transmissions are the same as for a real cipher.
+const TAG_LEN: usize = 16;
+
+/// The length of the header protection sample, which matches AES-based header protection
+const SAMPLE_LEN: usize = 16;
+
+/// Wraps a TLS provider to disable packet protection
+#[derive(Debug, Default)]
+pub struct NullCipher<P>(P);
+
+impl<P: tls::Provider> NullCipher<P> {
+    /// Disables packet protection for the given TLS provider
+    pub fn new(provider: P) -> Self {
+        Self(provider)
+    }
+}
+
+impl<P: tls::Provider> tls::Provider for NullCipher<P> {
+    type Server = Endpoint<P::Server>;
+    type Client = Endpoint<P::Client>;
+    type Error = P::Error;
+
+    fn start_server(self) -> Result<Self::Server, Self::Error> {
+        Ok(Endpoint(self.0.start_server()?))
+    }
+
+    fn start_client(self) -> Result<Self::Client, Self::Error> {
+        Ok(Endpoint(self.0.start_client()?))
+    }
+}
+
+/// A TLS endpoint which creates sessions with a null cipher
+#[derive(Debug)]
+pub struct Endpoint<E>(E);
+
+impl<E: crypto::tls::Endpoint> crypto::tls::Endpoint for Endpoint<E> {
+    type Session = Session<E::Session>;
+
+    fn new_server_session<Params: EncoderValue>(
+        &mut self,
+        transport_parameters: &Params,
+    ) -> Self::Session {
+        Session(self.0.new_server_session(transport_parameters))
+    }
+
+    fn new_client_session<Params: EncoderValue>(
+        &mut self,
+        transport_parameters: &Params,
+        server_name: ServerName,
+    ) -> Self::Session {
+        Session(self.0.new_client_session(transport_parameters, server_name))
+    }
+
+    fn max_tag_length(&self) -> usize {
+        TAG_LEN
+    }
+}
+
+/// A TLS session which replaces the negotiated keys with a null cipher
+#[derive(Debug)]
+pub struct Session<S>(S);
+
+impl<S: crypto::tls::Session> CryptoSuite for Session<S> {
+    type HandshakeKey = Key;
+    type HandshakeHeaderKey = HeaderKey;
+    type InitialKey = Key;
+    type InitialHeaderKey = HeaderKey;
+    type ZeroRttKey = Key;
+    type ZeroRttHeaderKey = HeaderKey;
+    type OneRttKey = Key;
+    type OneRttHeaderKey = HeaderKey;
+    type RetryKey = Key;
+}
+
+impl<S: crypto::tls::Session> crypto::tls::Session for Session<S> {
+    fn poll<C: crypto::tls::Context<Self>>(
+        &mut self,
+        context: &mut C,
+    ) -> Poll<Result<(), transport::Error>> {
+        self.0.poll(&mut Context(context))
+    }
+}
+
+/// Forwards the calls of the wrapped session and replaces any keys
+struct Context<'a, C>(&'a mut C);
+
+impl<'a, S, C> crypto::tls::Context<S> for Context<'a, C>
+where
+    S: crypto::tls::Session,
+    C: crypto::tls::Context<Session<S>>,
+{
+    fn on_handshake_keys(
+        &mut self,
+        key: S::HandshakeKey,
+        _header_key: S::HandshakeHeaderKey,
+    ) -> Result<(), transport::Error> {
+        self.0.on_handshake_keys(Key::new(&key), HeaderKey)
+    }
+
+    fn on_zero_rtt_keys(
+        &mut self,
+        key: S::ZeroRttKey,
+        _header_key: S::ZeroRttHeaderKey,
+        application_parameters: ApplicationParameters,
+    ) -> Result<(), transport::Error> {
+        self.0
+            .on_zero_rtt_keys(Key::new(&key), HeaderKey, application_parameters)
+    }
+
+    fn on_one_rtt_keys(
+        &mut self,
+        key: S::OneRttKey,
+        _header_key: S::OneRttHeaderKey,
+        application_parameters: ApplicationParameters,
+    ) -> Result<(), transport::Error> {
+        self.0
+            .on_one_rtt_keys(Key::new(&key), HeaderKey, application_parameters)
+    }
+
+    fn on_server_name(&mut self, server_name: ServerName) -> Result<(), transport::Error> {
+        self.0.on_server_name(server_name)
+    }
+
+    fn on_application_protocol(
+        &mut self,
+        application_protocol: Bytes,
+    ) -> Result<(), transport::Error> {
+        self.0.on_application_protocol(application_protocol)
+    }
+
+    fn on_peer_certificates(&mut self, certificates: Vec<Bytes>) -> Result<(), transport::Error> {
+        self.0.on_peer_certificates(certificates)
+    }
+
+    fn on_application_settings(&mut self, settings: Bytes) -> Result<(), transport::Error> {
+        self.0.on_application_settings(settings)
+    }
+
+    fn on_handshake_complete(&mut self) -> Result<(), transport::Error> {
+        self.0.on_handshake_complete()
+    }
+
+    fn receive_initial(&mut self, max_len: Option<usize>) -> Option<Bytes> {
+        self.0.receive_initial(max_len)
+    }
+
+    fn receive_handshake(&mut self, max_len: Option<usize>) -> Option<Bytes> {
+        self.0.receive_handshake(max_len)
+    }
+
+    fn receive_application(&mut self, max_len: Option<usize>) -> Option<Bytes> {
+        self.0.receive_application(max_len)
+    }
+
+    fn can_send_initial(&self) -> bool {
+        self.0.can_send_initial()
+    }
+
+    fn send_initial(&mut self, transmission: Bytes) {
+        self.0.send_initial(transmission)
+    }
+
+    fn can_send_handshake(&self) -> bool {
+        self.0.can_send_handshake()
+    }
+
+    fn send_handshake(&mut self, transmission: Bytes) {
+        self.0.send_handshake(transmission)
+    }
+
+    fn can_send_application(&self) -> bool {
+        self.0.can_send_application()
+    }
+
+    fn send_application(&mut self, transmission: Bytes) {
+        self.0.send_application(transmission)
+    }
+
+    fn waker(&self) -> &core::task::Waker {
+        self.0.waker()
+    }
+}
+
+/// A packet protection key which leaves the payload in plaintext
+///
+/// An all-zero tag is appended to each packet. Packets with any other tag are rejected, which
+/// causes packets from peers that don't use the null cipher to be dropped.
+#[derive(Debug)]
+pub struct Key {
+    confidentiality_limit: u64,
+    integrity_limit: u64,
+    cipher_suite: CipherSuite,
+}
+
+impl Key {
+    /// Creates a null key with the same limits as the negotiated key
+    ///
+    /// This keeps the key update behavior the same as with the negotiated cipher.
+    fn new<K: crypto::Key>(key: &K) -> Self {
+        Self {
+            confidentiality_limit: key.aead_confidentiality_limit(),
+            integrity_limit: key.aead_integrity_limit(),
+            cipher_suite: key.cipher_suite(),
+        }
+    }
+
+    /// Creates a null key for the initial packet space
+    ///
+    /// Initial packets always use AES-128-GCM, so the limits of that cipher are used.
+    fn initial() -> Self {
+        Self {
+            // See https://www.rfc-editor.org/rfc/rfc9001#section-6.6
+            confidentiality_limit: 2u64.pow(23),
+            integrity_limit: 2u64.pow(52),
+            cipher_suite: CipherSuite::TLS_AES_128_GCM_SHA256,
+        }
+    }
+}
+
+impl crypto::Key for Key {
+    fn decrypt(
+        &self,
+        _packet_number: u64,
+        _header: &[u8],
+        payload: &mut [u8],
+    ) -> Result<(), CryptoError> {
+        let payload_len = payload
+            .len()
+            .checked_sub(TAG_LEN)
+            .ok_or(CryptoError::DECRYPT_ERROR)?;
+
+        if payload[payload_len..].iter().any(|byte| *byte != 0) {
+            return Err(CryptoError::DECRYPT_ERROR);
+        }
+
+        Ok(())
+    }
+
+    fn encrypt(
+        &self,
+        _packet_number: u64,
+        _header: &[u8],
+        payload: &mut [u8],
+    ) -> Result<(), CryptoError> {
+        let payload_len = payload
+            .len()
+            .checked_sub(TAG_LEN)
+            .ok_or(CryptoError::INTERNAL_ERROR)?;
+
+        payload[payload_len..].fill(0);
+
+        Ok(())
+    }
+
+    fn tag_len(&self) -> usize {
+        TAG_LEN
+    }
+
+    fn aead_confidentiality_limit(&self) -> u64 {
+        self.confidentiality_limit
+    }
+
+    fn aead_integrity_limit(&self) -> u64 {
+        self.integrity_limit
+    }
+
+    fn cipher_suite(&self) -> CipherSuite {
+        self.cipher_suite
+    }
+}
+
+impl crypto::InitialKey for Key {
+    type HeaderKey = HeaderKey;
+
+    fn new_server(_connection_id: &[u8]) -> (Self, Self::HeaderKey) {
+        (Self::initial(), HeaderKey)
+    }
+
+    fn new_client(_connection_id: &[u8]) -> (Self, Self::HeaderKey) {
+        (Self::initial(), HeaderKey)
+    }
+}
+
+impl crypto::HandshakeKey for Key {}
+
+impl crypto::ZeroRttKey for Key {}
+
+impl crypto::OneRttKey for Key {
+    fn derive_next_key(&self) -> Self {
+        Self {
+            confidentiality_limit: self.confidentiality_limit,
+            integrity_limit: self.integrity_limit,
+            cipher_suite: self.cipher_suite,
+        }
+    }
+
+    fn update_sealer_pmtu(&mut self, _pmtu: u16) {}
+
+    fn update_opener_pmtu(&mut self, _pmtu: u16) {}
+}
+
+impl crypto::RetryKey for Key {
+    fn generate_tag(_payload: &[u8]) -> IntegrityTag {
+        [0; INTEGRITY_TAG_LEN]
+    }
+
+    fn validate(_payload: &[u8], tag: IntegrityTag) -> Result<(), CryptoError> {
+        if tag != [0; INTEGRITY_TAG_LEN] {
+            return Err(CryptoError::DECRYPT_ERROR);
+        }
+
+        Ok(())
+    }
+}
+
+/// A header protection key which leaves the packet header in plaintext
+#[derive(Debug, Default)]
+pub struct HeaderKey;
+
+impl crypto::HeaderKey for HeaderKey {
+    fn opening_header_protection_mask(&self, _sample: &[u8]) -> HeaderProtectionMask {
+        [0; 5]
+    }
+
+    fn opening_sample_len(&self) -> usize {
+        SAMPLE_LEN
+    }
+
+    fn sealing_header_protection_mask(&self, _sample: &[u8]) -> HeaderProtectionMask {
+        [0; 5]
+    }
+
+    fn sealing_sample_len(&self) -> usize {
+        SAMPLE_LEN
+    }
+}
+
+impl crypto::InitialHeaderKey for HeaderKey {}
+
+impl crypto::HandshakeHeaderKey for HeaderKey {}
+
+impl crypto::ZeroRttHeaderKey for HeaderKey {}
+
+impl crypto::OneRttHeaderKey for HeaderKey {}
@@ -0,0 +1,549 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides a TLS implementation which imports the keys of captured connections
+//!
+//! [`KeyLog`] reads the secrets that a TLS library logged in the `SSLKEYLOGFILE` format while
+//! the connections in a capture were established. Instead of performing a handshake, a server
+//! session looks up the secrets of the client random in the ClientHello and installs the
+//! derived packet protection keys. This allows a server to decrypt the packets in a capture
+//! which is replayed with the replay IO provider in `provider::io::replay`.
+//!
+//! The server name, application protocol and transport parameters of each connection are read
+//! from the ClientHello. The handshake completes once the client sends its Finished message. The
+//! server doesn't send any handshake messages, so the provider can only be used for replays.
+//!
+//! ```rust,no_run
+//! # use std::{error::Error, fs::File, io::BufReader};
+//! use s2n_quic::{provider::tls::replay::KeyLog, Server};
+//!
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! let key_log = KeyLog::read(BufReader::new(File::open("keys.log")?))?;
+//!
+//! let server = Server::builder()
+//!     .with_tls(key_log)?
+//!     .with_io("127.0.0.1:4433")?
+//!     .start()?;
+//! #
+//! #    Ok(())
+//! # }
+//! ```
+//!
+//! Key logs don't record the cipher suite that was negotiated. By default, the first cipher
+//! suite offered by the client which matches the length of the logged secrets is used. A
+//! different cipher suite can be selected with [`KeyLog::with_cipher_suite`]. 0-RTT keys are
+//! not imported, so early data is discarded.
+
+use crate::provider::tls;
+use bytes::Bytes;
+use core::{fmt, task::Poll};
+use s2n_codec::{DecoderBuffer, EncoderValue};
+use s2n_quic_core::{
+    application::ServerName,
+    crypto::{self, tls::ApplicationParameters},
+    endpoint, transport,
+};
+use s2n_quic_crypto::{
+    handshake::HandshakeKey,
+    one_rtt::OneRttKey,
+    ring::{aead, hkdf},
+    Prk, SecretPair,
+};
+use std::{collections::HashMap, io::BufRead, sync::Arc};
+
+pub use crypto::tls::CipherSuite;
+
+/// The `quic_transport_parameters` TLS extension
+///
+/// See <https://www.rfc-editor.org/rfc/rfc9001#section-8.2>
+const EXTENSION_TRANSPORT_PARAMETERS: u16 = 0x39;
+const EXTENSION_SERVER_NAME: u16 = 0;
+const EXTENSION_APPLICATION_PROTOCOL: u16 = 16;
+
+const CLIENT_HELLO: u8 = 1;
+const FINISHED: u8 = 20;
+
+/// The length of the authentication tag of each of the supported ciphers
+const TAG_LEN: usize = 16;
+
+/// The secrets of a single connection
+#[derive(Clone, Default)]
+struct Secrets {
+    client_handshake: Option<Vec<u8>>,
+    server_handshake: Option<Vec<u8>>,
+    client_application: Option<Vec<u8>>,
+    server_application: Option<Vec<u8>>,
+}
+
+/// The secrets of the connections in a capture, indexed by the client random
+#[derive(Clone, Default)]
+pub struct KeyLog {
+    connections: Arc<HashMap<[u8; 32], Secrets>>,
+    cipher_suite: Option<CipherSuite>,
+}
+
+impl fmt::Debug for KeyLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("KeyLog")
+            .field("connections", &self.connections.len())
+            .field("cipher_suite", &self.cipher_suite)
+            .finish()
+    }
+}
+
+impl KeyLog {
+    /// Reads the secrets in a key log
+    ///
+    /// Comments and the secrets of other labels, such as early traffic and exporter secrets,
+    /// are ignored.
+    pub fn read<R: BufRead>(reader: R) -> std::io::Result<Self> {
+        let mut connections: HashMap<[u8; 32], Secrets> = HashMap::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("line {} of the key log is invalid", index + 1),
+                )
+            };
+
+            let mut fields = line.split_whitespace();
+            let (label, client_random, secret) =
+                match (fields.next(), fields.next(), fields.next(), fields.next()) {
+                    (Some(label), Some(client_random), Some(secret), None) => {
+                        (label, client_random, secret)
+                    }
+                    _ => return Err(invalid()),
+                };
+
+            let client_random = decode_hex(client_random)
+                .and_then(|client_random| client_random.try_into().ok())
+                .ok_or_else(invalid)?;
+            let secret = decode_hex(secret).ok_or_else(invalid)?;
+
+            let secrets = connections.entry(client_random).or_default();
+            let slot = match label {
+                "CLIENT_HANDSHAKE_TRAFFIC_SECRET" => &mut secrets.client_handshake,
+                "SERVER_HANDSHAKE_TRAFFIC_SECRET" => &mut secrets.server_handshake,
+                "CLIENT_TRAFFIC_SECRET_0" => &mut secrets.client_application,
+                "SERVER_TRAFFIC_SECRET_0" => &mut secrets.server_application,
+                _ => continue,
+            };
+            *slot = Some(secret);
+        }
+
+        Ok(Self {
+            connections: Arc::new(connections),
+            cipher_suite: None,
+        })
+    }
+
+    /// Sets the cipher suite that was negotiated for each of the connections
+    pub fn with_cipher_suite(mut self, cipher_suite: CipherSuite) -> Self {
+        self.cipher_suite = Some(cipher_suite);
+        self
+    }
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if value.len() % 2 != 0 {
+        return None;
+    }
+
+    (0..value.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(value.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+impl tls::Provider for KeyLog {
+    type Server = Endpoint;
+    type Client = Endpoint;
+    type Error = Box<dyn std::error::Error>;
+
+    fn start_server(self) -> Result<Self::Server, Self::Error> {
+        Ok(Endpoint(self))
+    }
+
+    fn start_client(self) -> Result<Self::Client, Self::Error> {
+        Err("key logs can only be imported by servers".into())
+    }
+}
+
+/// A TLS endpoint which creates sessions from a key log
+#[derive(Debug)]
+pub struct Endpoint(KeyLog);
+
+impl crypto::tls::Endpoint for Endpoint {
+    type Session = Session;
+
+    fn new_server_session<Params: EncoderValue>(
+        &mut self,
+        _transport_parameters: &Params,
+    ) -> Self::Session {
+        Session {
+            key_log: self.0.clone(),
+            state: State::ClientHello(vec![]),
+        }
+    }
+
+    fn new_client_session<Params: EncoderValue>(
+        &mut self,
+        _transport_parameters: &Params,
+        _server_name: ServerName,
+    ) -> Self::Session {
+        Session {
+            key_log: self.0.clone(),
+            state: State::Client,
+        }
+    }
+
+    fn max_tag_length(&self) -> usize {
+        TAG_LEN
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    /// Waiting for the ClientHello in the Initial space
+    ClientHello(Vec<u8>),
+    /// Waiting for the client Finished message in the Handshake space
+    Finished(Vec<u8>),
+    Complete,
+    /// Client sessions can't be imported
+    Client,
+}
+
+/// A TLS session which installs the keys of the connection in the key log
+pub struct Session {
+    key_log: KeyLog,
+    state: State,
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+impl crypto::CryptoSuite for Session {
+    type HandshakeKey = <s2n_quic_crypto::Suite as crypto::CryptoSuite>::HandshakeKey;
+    type HandshakeHeaderKey = <s2n_quic_crypto::Suite as crypto::CryptoSuite>::HandshakeHeaderKey;
+    type InitialKey = <s2n_quic_crypto::Suite as crypto::CryptoSuite>::InitialKey;
+    type InitialHeaderKey = <s2n_quic_crypto::Suite as crypto::CryptoSuite>::InitialHeaderKey;
+    type ZeroRttKey = <s2n_quic_crypto::Suite as crypto::CryptoSuite>::ZeroRttKey;
+    type ZeroRttHeaderKey = <s2n_quic_crypto::Suite as crypto::CryptoSuite>::ZeroRttHeaderKey;
+    type OneRttKey = <s2n_quic_crypto::Suite as crypto::CryptoSuite>::OneRttKey;
+    type OneRttHeaderKey = <s2n_quic_crypto::Suite as crypto::CryptoSuite>::OneRttHeaderKey;
+    type RetryKey = <s2n_quic_crypto::Suite as crypto::CryptoSuite>::RetryKey;
+}
+
+impl crypto::tls::Session for Session {
+    fn poll<C: crypto::tls::Context<Self>>(
+        &mut self,
+        context: &mut C,
+    ) -> Poll<Result<(), transport::Error>> {
+        loop {
+            match &mut self.state {
+                State::ClientHello(buffer) => {
+                    while let Some(bytes) = context.receive_initial(None) {
+                        buffer.extend_from_slice(&bytes);
+                    }
+
+                    let message = match message(buffer, CLIENT_HELLO)? {
+                        Some(message) => message,
+                        None => return Poll::Pending,
+                    };
+
+                    let client_hello = ClientHello::decode(message)?;
+                    on_client_hello(&self.key_log, context, client_hello)?;
+                    self.state = State::Finished(vec![]);
+                }
+                State::Finished(buffer) => {
+                    while let Some(bytes) = context.receive_handshake(None) {
+                        buffer.extend_from_slice(&bytes);
+                    }
+
+                    if message(buffer, FINISHED)?.is_none() {
+                        return Poll::Pending;
+                    }
+
+                    context.on_handshake_complete()?;
+                    self.state = State::Complete;
+                }
+                State::Complete => return Poll::Ready(Ok(())),
+                State::Client => {
+                    return Poll::Ready(Err(transport::Error::INTERNAL_ERROR
+                        .with_reason("key logs can only be imported by servers")))
+                }
+            }
+        }
+    }
+}
+
+/// Installs the keys for the connection of the ClientHello
+fn on_client_hello<C: crypto::tls::Context<Session>>(
+    key_log: &KeyLog,
+    context: &mut C,
+    client_hello: ClientHello,
+) -> Result<(), transport::Error> {
+    let secrets = key_log
+        .connections
+        .get(client_hello.random)
+        .ok_or_else(|| {
+            crypto::CryptoError::HANDSHAKE_FAILURE
+                .with_reason("the client random is missing from the key log")
+        })?;
+
+    let missing = || {
+        crypto::CryptoError::HANDSHAKE_FAILURE
+            .with_reason("the key log is missing secrets for the connection")
+    };
+    let client_handshake = secrets.client_handshake.as_deref().ok_or_else(missing)?;
+    let server_handshake = secrets.server_handshake.as_deref().ok_or_else(missing)?;
+    let client_application = secrets.client_application.as_deref().ok_or_else(missing)?;
+    let server_application = secrets.server_application.as_deref().ok_or_else(missing)?;
+
+    let cipher_suite = key_log
+        .cipher_suite
+        .filter(|cipher_suite| !matches!(cipher_suite, CipherSuite::Unknown))
+        .or_else(|| client_hello.cipher_suite(client_handshake.len()))
+        .ok_or_else(|| {
+            crypto::CryptoError::HANDSHAKE_FAILURE
+                .with_reason("the client didn't offer a cipher suite for the secrets")
+        })?;
+    let (prk_algorithm, aead_algorithm) = algorithms(cipher_suite);
+
+    let secrets = |client: &[u8], server: &[u8]| SecretPair {
+        client: Prk::new_less_safe(prk_algorithm, client),
+        server: Prk::new_less_safe(prk_algorithm, server),
+    };
+    let invalid_secret = || {
+        crypto::CryptoError::HANDSHAKE_FAILURE.with_reason("the key log contains an invalid secret")
+    };
+
+    if let Some(server_name) = client_hello.server_name {
+        context.on_server_name(server_name)?;
+    }
+
+    // the server's preference isn't known so the first protocol offered by the client is used
+    if let Some(application_protocol) = client_hello.application_protocol {
+        context.on_application_protocol(application_protocol)?;
+    }
+
+    let (key, header_key) = HandshakeKey::new(
+        endpoint::Type::Server,
+        aead_algorithm,
+        secrets(client_handshake, server_handshake),
+    )
+    .ok_or_else(invalid_secret)?;
+    context.on_handshake_keys(key, header_key)?;
+
+    let (key, header_key) = OneRttKey::new(
+        endpoint::Type::Server,
+        aead_algorithm,
+        secrets(client_application, server_application),
+    )
+    .ok_or_else(invalid_secret)?;
+    context.on_one_rtt_keys(
+        key,
+        header_key,
+        ApplicationParameters {
+            transport_parameters: client_hello.transport_parameters,
+        },
+    )?;
+
+    Ok(())
+}
+
+//= https://www.rfc-editor.org/rfc/rfc8446#appendix-B.4
+//# This specification defines the following cipher suites for use with
+//# TLS 1.3.
+//#
+//#              +------------------------------+-------------+
+//#              | Description                  | Value       |
+//#              +------------------------------+-------------+
+//#              | TLS_AES_128_GCM_SHA256       | {0x13,0x01} |
+//#              |                              |             |
+//#              | TLS_AES_256_GCM_SHA384       | {0x13,0x02} |
+//#              |                              |             |
+//#              | TLS_CHACHA20_POLY1305_SHA256 | {0x13,0x03} |
+//#              |                              |             |
+//#              | TLS_AES_128_CCM_SHA256       | {0x13,0x04} |
+//#              |                              |             |
+//#              | TLS_AES_128_CCM_8_SHA256     | {0x13,0x05} |
+//#              +------------------------------+-------------+
+const TLS_AES_128_GCM_SHA256: [u8; 2] = [0x13, 0x01];
+const TLS_AES_256_GCM_SHA384: [u8; 2] = [0x13, 0x02];
+const TLS_CHACHA20_POLY1305_SHA256: [u8; 2] = [0x13, 0x03];
+
+fn algorithms(cipher_suite: CipherSuite) -> (hkdf::Algorithm, &'static aead::Algorithm) {
+    match cipher_suite {
+        CipherSuite::TLS_AES_256_GCM_SHA384 => (hkdf::HKDF_SHA384, &aead::AES_256_GCM),
+        CipherSuite::TLS_CHACHA20_POLY1305_SHA256 => (hkdf::HKDF_SHA256, &aead::CHACHA20_POLY1305),
+        CipherSuite::TLS_AES_128_GCM_SHA256 | CipherSuite::Unknown => {
+            (hkdf::HKDF_SHA256, &aead::AES_128_GCM)
+        }
+    }
+}
+
+/// Returns the body of the handshake message in the buffer, if it has been fully received
+fn message(buffer: &[u8], msg_type: u8) -> Result<Option<&[u8]>, transport::Error> {
+    let header = match buffer.get(..4) {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+
+    if header[0] != msg_type {
+        return Err(crypto::CryptoError::UNEXPECTED_MESSAGE.into());
+    }
+
+    let len = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+    Ok(buffer.get(4..4 + len))
+}
+
+/// The fields of a ClientHello which are needed to import the keys of the connection
+struct ClientHello<'a> {
+    random: &'a [u8; 32],
+    cipher_suites: &'a [u8],
+    server_name: Option<ServerName>,
+    application_protocol: Option<Bytes>,
+    transport_parameters: &'a [u8],
+}
+
+impl<'a> ClientHello<'a> {
+    /// Decodes the body of a ClientHello message
+    ///
+    /// See <https://www.rfc-editor.org/rfc/rfc8446#section-4.1.2>
+    fn decode(message: &'a [u8]) -> Result<Self, transport::Error> {
+        Self::decode_buffer(DecoderBuffer::new(message))
+            .map_err(|_| crypto::CryptoError::DECODE_ERROR.into())
+    }
+
+    fn decode_buffer(buffer: DecoderBuffer<'a>) -> Result<Self, s2n_codec::DecoderError> {
+        let buffer = buffer.skip(2)?;
+        let (random, buffer) = buffer.decode_slice(32)?;
+        let random = random
+            .into_less_safe_slice()
+            .try_into()
+            .expect("slice is 32 bytes");
+        let (_session_id, buffer) = buffer.decode_slice_with_len_prefix::<u8>()?;
+        let (cipher_suites, buffer) = buffer.decode_slice_with_len_prefix::<u16>()?;
+        let (_compression_methods, buffer) = buffer.decode_slice_with_len_prefix::<u8>()?;
+        let (mut extensions, _) = buffer.decode_slice_with_len_prefix::<u16>()?;
+
+        let mut client_hello = Self {
+            random,
+            cipher_suites: cipher_suites.into_less_safe_slice(),
+            server_name: None,
+            application_protocol: None,
+            transport_parameters: &[],
+        };
+
+        while !extensions.is_empty() {
+            let (extension_type, buffer) = extensions.decode::<u16>()?;
+            let (data, buffer) = buffer.decode_slice_with_len_prefix::<u16>()?;
+            extensions = buffer;
+
+            match extension_type {
+                EXTENSION_SERVER_NAME => {
+                    let (names, _) = data.decode_slice_with_len_prefix::<u16>()?;
+                    let (_name_type, names) = names.decode::<u8>()?;
+                    let (name, _) = names.decode_slice_with_len_prefix::<u16>()?;
+                    let name = core::str::from_utf8(name.into_less_safe_slice())
+                        .map_err(|_| s2n_codec::DecoderError::InvariantViolation("invalid name"))?;
+                    client_hello.server_name = Some(name.into());
+                }
+                EXTENSION_APPLICATION_PROTOCOL => {
+                    let (protocols, _) = data.decode_slice_with_len_prefix::<u16>()?;
+                    let (protocol, _) = protocols.decode_slice_with_len_prefix::<u8>()?;
+                    client_hello.application_protocol =
+                        Some(Bytes::copy_from_slice(protocol.into_less_safe_slice()));
+                }
+                EXTENSION_TRANSPORT_PARAMETERS => {
+                    client_hello.transport_parameters = data.into_less_safe_slice();
+                }
+                _ => {}
+            }
+        }
+
+        Ok(client_hello)
+    }
+
+    /// Returns the first cipher suite offered by the client with secrets of the given length
+    fn cipher_suite(&self, secret_len: usize) -> Option<CipherSuite> {
+        self.cipher_suites.chunks_exact(2).find_map(|value| {
+            match ([value[0], value[1]], secret_len) {
+                (TLS_AES_128_GCM_SHA256, 32) => Some(CipherSuite::TLS_AES_128_GCM_SHA256),
+                (TLS_AES_256_GCM_SHA384, 48) => Some(CipherSuite::TLS_AES_256_GCM_SHA384),
+                (TLS_CHACHA20_POLY1305_SHA256, 32) => {
+                    Some(CipherSuite::TLS_CHACHA20_POLY1305_SHA256)
+                }
+                _ => None,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_test() {
+        let client_random = "01".repeat(32);
+        let key_log = format!(
+            "# comment\n\
+             CLIENT_HANDSHAKE_TRAFFIC_SECRET {random} {secret}\n\
+             SERVER_HANDSHAKE_TRAFFIC_SECRET {random} {secret}\n\
+             CLIENT_TRAFFIC_SECRET_0 {random} {secret}\n\
+             SERVER_TRAFFIC_SECRET_0 {random} {secret}\n\
+             EXPORTER_SECRET {random} {secret}\n",
+            random = client_random,
+            secret = "ab".repeat(48),
+        );
+
+        let key_log = KeyLog::read(key_log.as_bytes()).unwrap();
+        let secrets = &key_log.connections[&[1; 32]];
+        assert_eq!(secrets.client_handshake.as_deref(), Some(&[0xab; 48][..]));
+        assert_eq!(secrets.server_application.as_deref(), Some(&[0xab; 48][..]));
+
+        for invalid in [
+            "CLIENT_TRAFFIC_SECRET_0 0101",
+            "CLIENT_TRAFFIC_SECRET_0 0101 abab",
+            "CLIENT_TRAFFIC_SECRET_0 zz abab extra",
+        ] {
+            assert!(KeyLog::read(invalid.as_bytes()).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn cipher_suite_test() {
+        let client_hello = ClientHello {
+            random: &[0; 32],
+            cipher_suites: &[0x13, 0x03, 0x13, 0x02, 0x13, 0x01],
+            server_name: None,
+            application_protocol: None,
+            transport_parameters: &[],
+        };
+
+        assert!(matches!(
+            client_hello.cipher_suite(32),
+            Some(CipherSuite::TLS_CHACHA20_POLY1305_SHA256)
+        ));
+        assert!(matches!(
+            client_hello.cipher_suite(48),
+            Some(CipherSuite::TLS_AES_256_GCM_SHA384)
+        ));
+        assert!(client_hello.cipher_suite(64).is_none());
+    }
+}
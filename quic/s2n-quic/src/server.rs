@@ -1,17 +1,25 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{connection::Connection, provider::*};
+use crate::{
+    connection::{Connection, HandshakeInfo},
+    provider::*,
+};
 use core::{
     fmt,
+    pin::Pin,
     task::{Context, Poll},
 };
 use s2n_quic_transport::endpoint::handle::Acceptor;
 
 mod builder;
+mod drain;
 mod providers;
+#[cfg(all(unix, any(test, all(not(docdiff), feature = "unstable-hot-upgrade"))))]
+pub mod upgrade;
 
 pub use builder::*;
+pub use drain::*;
 pub use providers::*;
 pub use s2n_quic_core::application::ServerName as Name;
 
@@ -47,7 +55,7 @@ impl Server {
     /// #    Ok(())
     /// # }
     /// ```
-    pub fn builder() -> Builder<impl ServerProviders> {
+    pub fn builder() -> Builder<DefaultProviders> {
         Builder::default()
     }
 
@@ -56,6 +64,11 @@ impl Server {
     /// This function will yield once a new QUIC connection is established. When established,
     /// the corresponding [`Connection`] will be returned.
     ///
+    /// # Cancel safety
+    ///
+    /// This method is cancel safe. A connection is only taken from the server when the future
+    /// completes, so dropping the future never loses an established connection.
+    ///
     /// # Examples
     ///
     /// ```rust,no_run
@@ -101,6 +114,124 @@ impl Server {
         }
     }
 
+    /// Accepts a new incoming [`Connection`] along with the metadata about its handshake
+    ///
+    /// The [`HandshakeInfo`] contains the negotiated application protocol and server name, the
+    /// address of the client, the address validation token, whether the session was resumed,
+    /// and the duration of the handshake.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use std::{error::Error, path::Path};
+    /// # use s2n_quic::Server;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut server = Server::builder()
+    ///     .with_tls((Path::new("./certs/cert.pem"), Path::new("./certs/key.pem")))?
+    ///     .with_io("127.0.0.1:443")?
+    ///     .start()?;
+    ///
+    /// while let Some((connection, info)) = server.accept_with_info().await {
+    ///     println!(
+    ///         "new connection: {:?} {:?} {:?}",
+    ///         info.remote_address(),
+    ///         info.server_name(),
+    ///         info.handshake_duration()
+    ///     );
+    /// }
+    /// #    Ok(())
+    /// # }
+    /// ```
+    pub async fn accept_with_info(&mut self) -> Option<(Connection, HandshakeInfo)> {
+        futures::future::poll_fn(|cx| self.poll_accept_with_info(cx)).await
+    }
+
+    /// Attempts to accept a new incoming [`Connection`] along with the metadata about its
+    /// handshake
+    ///
+    /// This behaves like [`Self::poll_accept`]. Connections which are finalized before their
+    /// metadata is captured are skipped.
+    pub fn poll_accept_with_info(
+        &mut self,
+        cx: &mut Context,
+    ) -> Poll<Option<(Connection, HandshakeInfo)>> {
+        loop {
+            let connection = match self.acceptor.poll_accept(cx) {
+                Poll::Ready(Some(connection)) => connection,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            if let Ok(info) = connection.handshake_info() {
+                return Poll::Ready(Some((Connection::new(connection), info)));
+            }
+        }
+    }
+
+    /// Returns a stream of the accepted connections along with the metadata about their
+    /// handshakes
+    ///
+    /// See [`Self::accept_with_info`].
+    pub fn with_info(&mut self) -> WithInfo<'_> {
+        WithInfo(self)
+    }
+
+    /// Returns a [`Drainer`] which is able to gracefully shut down the [`Server`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use std::{error::Error, path::Path};
+    /// # use s2n_quic::{server::Drain, Server};
+    /// #
+    /// # async fn drain() -> Result<(), Box<dyn Error>> {
+    /// let server = Server::builder()
+    ///     .with_tls((Path::new("./certs/cert.pem"), Path::new("./certs/key.pem")))?
+    ///     .with_io("127.0.0.1:443")?
+    ///     .start()?;
+    ///
+    /// server.drainer().drain(Drain::new()).await?;
+    /// #
+    /// #    Ok(())
+    /// # }
+    /// ```
+    pub fn drainer(&self) -> Drainer {
+        Drainer::new(self.acceptor.drainer())
+    }
+
+    /// Imports a connection which was exported by another server
+    ///
+    /// The state is created with
+    /// [`connection::Handle::export_state`](crate::connection::Handle::export_state). Once
+    /// imported, the connection is returned by [`Self::accept`] like any other connection.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use std::{error::Error, path::Path};
+    /// # use s2n_quic::{connection::transfer::State, Server};
+    /// #
+    /// # async fn import(bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+    /// let mut server = Server::builder()
+    ///     .with_tls((Path::new("./certs/cert.pem"), Path::new("./certs/key.pem")))?
+    ///     .with_io("127.0.0.1:443")?
+    ///     .start()?;
+    ///
+    /// server.import(State::decode(bytes)?).await?;
+    /// let connection = server.accept().await;
+    /// #
+    /// #    Ok(())
+    /// # }
+    /// ```
+    pub async fn import(
+        &self,
+        state: crate::connection::transfer::State,
+    ) -> Result<(), crate::connection::transfer::Error> {
+        self.acceptor.import(state).await
+    }
+
     /// Returns the local address that this listener is bound to.
     ///
     /// This can be useful, for example, when binding to port `0` to figure out which
@@ -138,3 +269,17 @@ impl futures::stream::Stream for Server {
         self.poll_accept(cx)
     }
 }
+
+/// A stream of accepted connections along with the metadata about their handshakes
+///
+/// Created with [`Server::with_info`].
+#[derive(Debug)]
+pub struct WithInfo<'a>(&'a mut Server);
+
+impl futures::stream::Stream for WithInfo<'_> {
+    type Item = (Connection, HandshakeInfo);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.poll_accept_with_info(cx)
+    }
+}
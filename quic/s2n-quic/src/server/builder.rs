@@ -3,7 +3,7 @@
 
 use crate::{
     provider::*,
-    server::{DefaultProviders, Server, ServerProviders},
+    server::{DefaultProviders, Server, ServerProviders, ShardedServerProviders},
 };
 
 /// A builder for configuring [`Server`] providers
@@ -126,6 +126,276 @@ impl<Providers: ServerProviders> Builder<Providers> {
         ServerProviders
     );
 
+    impl_provider_method!(
+        /// Sets the MTU provider for the [`Server`]
+        ///
+        /// # Examples
+        ///
+        /// Probes for jumbo frames on every path
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{Server, provider::mtu};
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let mtu = mtu::Config::builder()
+        ///     .with_initial_mtu(9001)?
+        ///     .with_max_mtu(9001)?
+        ///     .build()?;
+        ///
+        /// let server = Server::builder()
+        ///     .with_mtu(mtu)?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_mtu,
+        mtu,
+        ServerProviders
+    );
+
+    impl_provider_method!(
+        /// Sets the ACK strategy provider for the [`Server`]
+        ///
+        /// # Examples
+        ///
+        /// Reduces the number of ACK frames sent on high-throughput paths
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{Server, provider::ack};
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let server = Server::builder()
+        ///     .with_ack(ack::Strategy::HIGH_THROUGHPUT)?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_ack,
+        ack,
+        ServerProviders
+    );
+
+    impl_provider_method!(
+        /// Sets the protocol violation provider for the [`Server`]
+        ///
+        /// # Examples
+        ///
+        /// Ignores all protocol violations that the specification permits ignoring
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{Server, provider::protocol_violation};
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let server = Server::builder()
+        ///     .with_protocol_violation(protocol_violation::Outcome::Ignore)?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_protocol_violation,
+        protocol_violation,
+        ServerProviders
+    );
+
+    impl_provider_method!(
+        /// Sets the stream scheduler provider for the [`Server`]
+        ///
+        /// # Examples
+        ///
+        /// Shares the transmission capacity equally between all stream groups
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{Server, provider::stream_scheduler};
+        ///
+        /// #[derive(Debug)]
+        /// struct Equal;
+        ///
+        /// impl stream_scheduler::Endpoint for Equal {
+        ///     type Scheduler = Self;
+        ///
+        ///     fn new_scheduler(&mut self, _info: &stream_scheduler::ConnectionInfo) -> Self {
+        ///         Equal
+        ///     }
+        /// }
+        ///
+        /// impl stream_scheduler::Scheduler for Equal {
+        ///     fn weight(&mut self, _class: &stream_scheduler::Class) -> u32 {
+        ///         1
+        ///     }
+        /// }
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let server = Server::builder()
+        ///     .with_stream_scheduler(Equal)?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_stream_scheduler,
+        stream_scheduler,
+        ServerProviders
+    );
+
+    impl_provider_method!(
+        /// Sets the extension frame provider for the [`Server`]
+        ///
+        /// # Examples
+        ///
+        /// Offers an extension which exchanges a single frame type
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{
+        ///     provider::extension_frame::{self, FrameType},
+        ///     Server,
+        /// };
+        /// use s2n_quic_core::{transport, varint::VarInt};
+        ///
+        /// const TRANSPORT_PARAMETER: VarInt = VarInt::from_u32(0xff0a_0001);
+        ///
+        /// #[derive(Debug)]
+        /// struct MyExtension;
+        ///
+        /// impl extension_frame::Endpoint for MyExtension {
+        ///     type Handler = MyHandler;
+        ///
+        ///     fn new_handler(&mut self, _info: &extension_frame::ConnectionInfo) -> Option<MyHandler> {
+        ///         Some(MyHandler {
+        ///             frame_types: [FrameType::new(VarInt::from_u16(0x3a11)).unwrap()],
+        ///         })
+        ///     }
+        /// }
+        ///
+        /// #[derive(Debug)]
+        /// struct MyHandler {
+        ///     frame_types: [FrameType; 1],
+        /// }
+        ///
+        /// impl extension_frame::Handler for MyHandler {
+        ///     fn transport_parameter(&self) -> (VarInt, &[u8]) {
+        ///         (TRANSPORT_PARAMETER, &[])
+        ///     }
+        ///
+        ///     fn frame_types(&self) -> &[FrameType] {
+        ///         &self.frame_types
+        ///     }
+        ///
+        ///     fn on_frame(&mut self, _frame_type: VarInt, payload: &[u8]) -> Result<(), transport::Error> {
+        ///         println!("received {:?}", payload);
+        ///         Ok(())
+        ///     }
+        /// }
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let server = Server::builder()
+        ///     .with_extension_frame(MyExtension)?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_extension_frame,
+        extension_frame,
+        ServerProviders
+    );
+
+    impl_provider_method!(
+        /// Sets the tenant provider for the [`Server`]
+        ///
+        /// # Examples
+        ///
+        /// Limits the number of concurrent connections for each requested server name
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{Server, provider::tenant};
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let quotas = tenant::Quotas::new(|info: &tenant::Info| {
+        ///     let server_name = info.server_name?.to_string();
+        ///     Some((server_name, tenant::Quota::new().with_max_connections(1000)))
+        /// });
+        ///
+        /// let server = Server::builder()
+        ///     .with_tenant(quotas)?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_tenant,
+        tenant,
+        ServerProviders
+    );
+
+    impl_provider_method!(
+        /// Sets the retry offload provider for the [`Server`]
+        ///
+        /// # Examples
+        ///
+        /// Runs the server as a stateless front-end, which forwards the datagrams of validated
+        /// clients to a back-end along with the address of the client
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{Server, provider::{address_token, retry_offload}};
+        /// use std::net::{SocketAddr, UdpSocket};
+        ///
+        /// struct Encapsulate {
+        ///     socket: UdpSocket,
+        ///     back_end: SocketAddr,
+        /// }
+        ///
+        /// impl retry_offload::Forwarder for Encapsulate {
+        ///     fn forward(&mut self, datagram: &retry_offload::Datagram) {
+        ///         let address = &datagram.remote_address;
+        ///         let mut message = vec![address.ip().len() as u8];
+        ///         message.extend_from_slice(address.ip());
+        ///         message.extend_from_slice(&address.port().to_be_bytes());
+        ///         message.extend_from_slice(datagram.payload);
+        ///         let _ = self.socket.send_to(&message, self.back_end);
+        ///     }
+        /// }
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let forwarder = Encapsulate {
+        ///     socket: UdpSocket::bind("0.0.0.0:0")?,
+        ///     back_end: "192.0.2.1:4433".parse()?,
+        /// };
+        ///
+        /// // the back-ends use the same secret and keep detecting replayed tokens
+        /// let address_token = address_token::Default::default()
+        ///     .with_shared_secret(b"secret")
+        ///     .with_replay_detection(false);
+        ///
+        /// let server = Server::builder()
+        ///     .with_address_token(address_token)?
+        ///     .with_retry_offload(forwarder)?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_retry_offload,
+        retry_offload,
+        ServerProviders
+    );
+
     impl_provider_method!(
         /// Sets the endpoint limits provider for the [`Server`]
         ///
@@ -314,7 +584,7 @@ impl<Providers: ServerProviders> Builder<Providers> {
         ServerProviders
     );
 
-    #[cfg(all(not(docdiff), feature = "unstable-provider-datagram"))]
+    #[cfg(any(test, all(not(docdiff), feature = "unstable-provider-datagram")))]
     impl_provider_method!(
         /// Sets the datagram provider for the [`Server`]
         with_datagram,
@@ -354,4 +624,39 @@ impl<Providers: ServerProviders> Builder<Providers> {
     pub fn start(self) -> Result<Server, StartError> {
         self.0.build().start()
     }
+
+    /// Starts the [`Server`] with the configured providers, partitioning its connections across
+    /// `shards` endpoints
+    ///
+    /// Each endpoint processes its connections in its own task, which allows a single [`Server`]
+    /// to scale across multiple cores. Datagrams are routed to the endpoints by hashing their
+    /// destination connection ID, and each endpoint only issues connection IDs that are routed to
+    /// itself. The endpoints are started with their own copies of the providers, so all of the
+    /// providers except for the IO provider must implement `Clone`.
+    ///
+    /// On Linux, the kernel can route the datagrams to the endpoints instead, which is enabled
+    /// with [`Builder::with_steering`](crate::provider::io::tokio::Builder::with_steering).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use std::{error::Error, path::Path};
+    /// # use s2n_quic::Server;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn Error>> {
+    /// let server = Server::builder()
+    ///     .with_tls((Path::new("./certs/cert.pem"), Path::new("./certs/key.pem")))?
+    ///     .with_io("127.0.0.1:443")?
+    ///     .start_sharded(4)?;
+    /// #
+    /// #    Ok(())
+    /// # }
+    /// ```
+    pub fn start_sharded(self, shards: usize) -> Result<Server, StartError>
+    where
+        Providers: ShardedServerProviders,
+    {
+        self.0.start_sharded(shards)
+    }
 }
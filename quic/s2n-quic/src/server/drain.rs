@@ -0,0 +1,116 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::connection;
+use s2n_quic_transport::endpoint::drain;
+
+pub use drain::Drain;
+
+/// A handle which drains a [`Server`](crate::Server) endpoint
+///
+/// The handle can be moved to another task so the server can keep accepting connections until
+/// draining starts. Once the server is drained, [`Server::accept`](crate::Server::accept)
+/// returns `None`.
+///
+/// Progress is reported to the event subscriber with the `on_endpoint_draining_*` events.
+#[derive(Clone, Debug)]
+pub struct Drainer(drain::Drainer);
+
+impl Drainer {
+    pub(crate) fn new(drainer: drain::Drainer) -> Self {
+        Self(drainer)
+    }
+
+    /// Stops accepting new connections and waits for the open connections to close
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use std::{error::Error, path::Path, time::Duration};
+    /// use s2n_quic::{server::Drain, Server};
+    ///
+    /// # async fn drain() -> Result<(), Box<dyn Error>> {
+    /// let mut server = Server::builder()
+    ///     .with_tls((Path::new("./certs/cert.pem"), Path::new("./certs/key.pem")))?
+    ///     .with_io("127.0.0.1:443")?
+    ///     .start()?;
+    ///
+    /// let mut drainer = server.drainer();
+    /// tokio::spawn(async move {
+    ///     // ...
+    ///     let drain = Drain::new().with_deadline(Duration::from_secs(30));
+    ///     drainer.drain(drain).await
+    /// });
+    ///
+    /// while let Some(connection) = server.accept().await {
+    ///     // ...
+    /// }
+    /// #
+    /// #    Ok(())
+    /// # }
+    /// ```
+    pub async fn drain(&mut self, drain: Drain) -> Result<(), connection::Error> {
+        futures::future::poll_fn(|cx| self.0.poll_drain(cx, drain)).await
+    }
+
+    /// Waits for the process to receive a termination signal and drains the server
+    ///
+    /// On unix platforms, both `SIGTERM` and `SIGINT` start draining the server. On other
+    /// platforms, only `CTRL-C` is handled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use std::{error::Error, path::Path, time::Duration};
+    /// use s2n_quic::{server::Drain, Server};
+    ///
+    /// # async fn drain() -> Result<(), Box<dyn Error>> {
+    /// let mut server = Server::builder()
+    ///     .with_tls((Path::new("./certs/cert.pem"), Path::new("./certs/key.pem")))?
+    ///     .with_io("127.0.0.1:443")?
+    ///     .start()?;
+    ///
+    /// let mut drainer = server.drainer();
+    /// tokio::spawn(async move {
+    ///     let drain = Drain::new().with_deadline(Duration::from_secs(30));
+    ///     drainer.drain_on_signal(drain).await
+    /// });
+    ///
+    /// while let Some(connection) = server.accept().await {
+    ///     // ...
+    /// }
+    /// #
+    /// #    Ok(())
+    /// # }
+    /// ```
+    #[cfg(any(test, all(not(docdiff), feature = "unstable-drain-signal")))]
+    pub async fn drain_on_signal(&mut self, drain: Drain) -> std::io::Result<()> {
+        termination_signal().await?;
+        self.drain(drain).await?;
+        Ok(())
+    }
+}
+
+#[cfg(any(test, all(not(docdiff), feature = "unstable-drain-signal")))]
+async fn termination_signal() -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use futures::future::{select, Either};
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        let interrupt = tokio::signal::ctrl_c();
+        let terminate = terminate.recv();
+        futures::pin_mut!(interrupt, terminate);
+
+        match select(interrupt, terminate).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => Ok(()),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await
+    }
+}
@@ -19,6 +19,13 @@ impl_providers_state! {
         event: Event,
         limits: Limits,
         io: IO,
+        mtu: Mtu,
+        ack: Ack,
+        protocol_violation: ProtocolViolation,
+        stream_scheduler: StreamScheduler,
+        extension_frame: ExtensionFrame,
+        tenant: Tenant,
+        retry_offload: RetryOffload,
         path_migration: PathMigration,
         sync: Sync,
         tls: Tls,
@@ -30,6 +37,46 @@ impl_providers_state! {
     trait ServerProviders {}
 }
 
+/// Opaque trait for the configured providers which can be started for multiple shards
+///
+/// This is implemented when all of the providers, except for the IO provider, implement `Clone`
+/// and the IO provider supports sharding.
+pub trait ShardedServerProviders: ServerProviders {
+    #[doc(hidden)]
+    fn start_sharded(self, shards: usize) -> Result<Server, StartError>;
+}
+
+impl<P> ShardedServerProviders for P
+where
+    P: ServerProviders,
+    P::CongestionController: Clone,
+    P::ConnectionCloseFormatter: Clone,
+    P::ConnectionID: Clone,
+    P::PacketInterceptor: Clone,
+    P::StatelessResetToken: Clone,
+    P::Random: Clone,
+    P::EndpointLimits: Clone,
+    P::Event: Clone,
+    P::Limits: Clone,
+    P::Mtu: Clone,
+    P::Ack: Clone,
+    P::ProtocolViolation: Clone,
+    P::StreamScheduler: Clone,
+    P::ExtensionFrame: Clone,
+    P::Tenant: Clone,
+    P::RetryOffload: Clone,
+    P::PathMigration: Clone,
+    P::Sync: Clone,
+    P::Tls: Clone,
+    P::AddressToken: Clone,
+    P::Datagram: Clone,
+    P::IO: io::ShardedProvider,
+{
+    fn start_sharded(self, shards: usize) -> Result<Server, StartError> {
+        self.build().start_sharded(shards)
+    }
+}
+
 impl<
         CongestionController: congestion_controller::Provider,
         ConnectionCloseFormatter: connection_close_formatter::Provider,
@@ -41,6 +88,13 @@ impl<
         Event: event::Provider,
         Limits: limits::Provider,
         IO: io::Provider,
+        Mtu: mtu::Provider,
+        Ack: ack::Provider,
+        ProtocolViolation: protocol_violation::Provider,
+        StreamScheduler: stream_scheduler::Provider,
+        ExtensionFrame: extension_frame::Provider,
+        Tenant: tenant::Provider,
+        RetryOffload: retry_offload::Provider,
         PathMigration: path_migration::Provider,
         Sync: sync::Provider,
         Tls: tls::Provider,
@@ -58,6 +112,13 @@ impl<
         Event,
         Limits,
         IO,
+        Mtu,
+        Ack,
+        ProtocolViolation,
+        StreamScheduler,
+        ExtensionFrame,
+        Tenant,
+        RetryOffload,
         PathMigration,
         Sync,
         Tls,
@@ -76,6 +137,13 @@ impl<
             endpoint_limits,
             event,
             limits,
+            mtu,
+            ack,
+            protocol_violation,
+            stream_scheduler,
+            extension_frame,
+            tenant,
+            retry_offload,
             address_token,
             io,
             path_migration,
@@ -94,6 +162,13 @@ impl<
         let random = random.start().map_err(StartError::new)?;
         let endpoint_limits = endpoint_limits.start().map_err(StartError::new)?;
         let limits = limits.start().map_err(StartError::new)?;
+        let mtu = mtu.start().map_err(StartError::new)?;
+        let ack = ack.start().map_err(StartError::new)?;
+        let protocol_violation = protocol_violation.start().map_err(StartError::new)?;
+        let stream_scheduler = stream_scheduler.start().map_err(StartError::new)?;
+        let extension_frame = extension_frame.start().map_err(StartError::new)?;
+        let tenant = tenant.start().map_err(StartError::new)?;
+        let retry_offload = retry_offload.start().map_err(StartError::new)?;
         let event = event.start().map_err(StartError::new)?;
         let address_token = address_token.start().map_err(StartError::new)?;
         let sync = sync.start().map_err(StartError::new)?;
@@ -128,6 +203,13 @@ impl<
             address_token,
             path_handle: PhantomData,
             path_migration,
+            mtu,
+            ack,
+            protocol_violation,
+            stream_scheduler,
+            extension_frame,
+            tenant,
+            retry_offload,
             datagram,
         };
 
@@ -143,6 +225,198 @@ impl<
     }
 }
 
+impl<
+        CongestionController: congestion_controller::Provider + Clone,
+        ConnectionCloseFormatter: connection_close_formatter::Provider + Clone,
+        ConnectionID: connection_id::Provider + Clone,
+        PacketInterceptor: packet_interceptor::Provider + Clone,
+        StatelessResetToken: stateless_reset_token::Provider + Clone,
+        Random: random::Provider + Clone,
+        EndpointLimits: endpoint_limits::Provider + Clone,
+        Event: event::Provider + Clone,
+        Limits: limits::Provider + Clone,
+        IO: io::ShardedProvider,
+        Mtu: mtu::Provider + Clone,
+        Ack: ack::Provider + Clone,
+        ProtocolViolation: protocol_violation::Provider + Clone,
+        StreamScheduler: stream_scheduler::Provider + Clone,
+        ExtensionFrame: extension_frame::Provider + Clone,
+        Tenant: tenant::Provider + Clone,
+        RetryOffload: retry_offload::Provider + Clone,
+        PathMigration: path_migration::Provider + Clone,
+        Sync: sync::Provider + Clone,
+        Tls: tls::Provider + Clone,
+        AddressToken: address_token::Provider + Clone,
+        Datagram: datagram::Provider + Clone,
+    >
+    Providers<
+        CongestionController,
+        ConnectionCloseFormatter,
+        ConnectionID,
+        PacketInterceptor,
+        StatelessResetToken,
+        Random,
+        EndpointLimits,
+        Event,
+        Limits,
+        IO,
+        Mtu,
+        Ack,
+        ProtocolViolation,
+        StreamScheduler,
+        ExtensionFrame,
+        Tenant,
+        RetryOffload,
+        PathMigration,
+        Sync,
+        Tls,
+        AddressToken,
+        Datagram,
+    >
+{
+    /// Starts an endpoint for each of the `shards`, which share a single IO provider
+    ///
+    /// Each endpoint is started with its own copy of the providers.
+    pub fn start_sharded(self, shards: usize) -> Result<Server, StartError> {
+        let Self {
+            congestion_controller,
+            connection_close_formatter,
+            connection_id,
+            packet_interceptor,
+            stateless_reset_token,
+            random,
+            endpoint_limits,
+            event,
+            limits,
+            mtu,
+            ack,
+            protocol_violation,
+            stream_scheduler,
+            extension_frame,
+            tenant,
+            retry_offload,
+            address_token,
+            io,
+            path_migration,
+            sync,
+            tls,
+            datagram,
+        } = self;
+
+        if !(1..=connection::id::shard::MAX_SHARDS).contains(&shards) {
+            return Err(StartError::new(ShardError));
+        }
+
+        // Servers route packets by connection ID, so ensure the connection IDs are routable to
+        // each shard before any of the endpoints are started
+        let mut format = connection_id.clone().start().map_err(StartError::new)?;
+        connection::id::shard::validate(&mut format, shards).map_err(StartError::new)?;
+
+        let mut configs = Vec::with_capacity(shards);
+
+        for shard in 0..shards {
+            let congestion_controller = congestion_controller
+                .clone()
+                .start()
+                .map_err(StartError::new)?;
+            let connection_close_formatter = connection_close_formatter
+                .clone()
+                .start()
+                .map_err(StartError::new)?;
+            let connection_id = connection_id.clone().start().map_err(StartError::new)?;
+            let packet_interceptor = packet_interceptor
+                .clone()
+                .start()
+                .map_err(StartError::new)?;
+            let stateless_reset_token = stateless_reset_token
+                .clone()
+                .start()
+                .map_err(StartError::new)?;
+            let random = random.clone().start().map_err(StartError::new)?;
+            let endpoint_limits = endpoint_limits.clone().start().map_err(StartError::new)?;
+            let limits = limits.clone().start().map_err(StartError::new)?;
+            let mtu = mtu.clone().start().map_err(StartError::new)?;
+            let ack = ack.clone().start().map_err(StartError::new)?;
+            let protocol_violation = protocol_violation
+                .clone()
+                .start()
+                .map_err(StartError::new)?;
+            let stream_scheduler = stream_scheduler.clone().start().map_err(StartError::new)?;
+            let extension_frame = extension_frame.clone().start().map_err(StartError::new)?;
+            let tenant = tenant.clone().start().map_err(StartError::new)?;
+            let retry_offload = retry_offload.clone().start().map_err(StartError::new)?;
+            let event = event.clone().start().map_err(StartError::new)?;
+            let address_token = address_token.clone().start().map_err(StartError::new)?;
+            let sync = sync.clone().start().map_err(StartError::new)?;
+            let path_migration = path_migration.clone().start().map_err(StartError::new)?;
+            let tls = tls.clone().start_server().map_err(StartError::new)?;
+            let datagram = datagram.clone().start().map_err(StartError::new)?;
+
+            let valid_lifetime = |lifetime| {
+                (connection::id::MIN_LIFETIME..=connection::id::MAX_LIFETIME).contains(&lifetime)
+            };
+            if connection_id
+                .lifetime()
+                .map_or(false, |lifetime| !valid_lifetime(lifetime))
+            {
+                return Err(StartError::new(connection::id::Error::InvalidLifetime));
+            };
+
+            // only issue connection IDs which are routed to this shard
+            let connection_id = connection::id::shard::Format::new(connection_id, shard, shards);
+
+            configs.push(EndpointConfig {
+                congestion_controller,
+                connection_close_formatter,
+                connection_id,
+                packet_interceptor,
+                stateless_reset_token,
+                random,
+                endpoint_limits,
+                event,
+                limits,
+                sync,
+                tls,
+                address_token,
+                path_handle: PhantomData,
+                path_migration,
+                mtu,
+                ack,
+                protocol_violation,
+                stream_scheduler,
+                extension_frame,
+                tenant,
+                retry_offload,
+                datagram,
+            });
+        }
+
+        let (endpoints, acceptor) = endpoint::Endpoint::new_server_shards(configs);
+
+        // Start the IO last
+        let local_addr = io.start_sharded(endpoints).map_err(StartError::new)?;
+
+        Ok(Server {
+            acceptor,
+            local_addr,
+        })
+    }
+}
+
+/// The number of shards is outside of the supported range
+#[derive(Debug)]
+struct ShardError;
+
+impl fmt::Display for ShardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the number of shards must be between 1 and {}",
+            connection::id::shard::MAX_SHARDS
+        )
+    }
+}
+
 #[allow(dead_code)] // don't warn on unused providers for now
 struct EndpointConfig<
     CongestionController,
@@ -156,6 +430,13 @@ struct EndpointConfig<
     EndpointLimits,
     Event,
     Limits,
+    Mtu,
+    Ack,
+    ProtocolViolation,
+    StreamScheduler,
+    ExtensionFrame,
+    Tenant,
+    RetryOffload,
     Sync,
     Tls,
     AddressToken,
@@ -170,6 +451,13 @@ struct EndpointConfig<
     endpoint_limits: EndpointLimits,
     event: Event,
     limits: Limits,
+    mtu: Mtu,
+    ack: Ack,
+    protocol_violation: ProtocolViolation,
+    stream_scheduler: StreamScheduler,
+    extension_frame: ExtensionFrame,
+    tenant: Tenant,
+    retry_offload: RetryOffload,
     sync: Sync,
     tls: Tls,
     address_token: AddressToken,
@@ -190,6 +478,13 @@ impl<
         EndpointLimits: s2n_quic_core::endpoint::Limiter,
         Event: s2n_quic_core::event::Subscriber,
         Limits: s2n_quic_core::connection::limits::Limiter,
+        Mtu: mtu::Endpoint,
+        Ack: ack::Endpoint,
+        ProtocolViolation: protocol_violation::Endpoint,
+        StreamScheduler: stream_scheduler::Endpoint,
+        ExtensionFrame: extension_frame::Endpoint,
+        Tenant: tenant::Classifier,
+        RetryOffload: retry_offload::Forwarder,
         Sync,
         Tls: crypto::tls::Endpoint,
         AddressToken: address_token::Format,
@@ -207,6 +502,13 @@ impl<
         EndpointLimits,
         Event,
         Limits,
+        Mtu,
+        Ack,
+        ProtocolViolation,
+        StreamScheduler,
+        ExtensionFrame,
+        Tenant,
+        RetryOffload,
         Sync,
         Tls,
         AddressToken,
@@ -230,6 +532,13 @@ impl<
         EndpointLimits: s2n_quic_core::endpoint::Limiter,
         Event: s2n_quic_core::event::Subscriber,
         Limits: s2n_quic_core::connection::limits::Limiter,
+        Mtu: mtu::Endpoint,
+        Ack: ack::Endpoint,
+        ProtocolViolation: protocol_violation::Endpoint,
+        StreamScheduler: stream_scheduler::Endpoint,
+        ExtensionFrame: extension_frame::Endpoint,
+        Tenant: tenant::Classifier,
+        RetryOffload: retry_offload::Forwarder,
         Sync: 'static + Send,
         Tls: crypto::tls::Endpoint,
         AddressToken: address_token::Format,
@@ -247,6 +556,13 @@ impl<
         EndpointLimits,
         Event,
         Limits,
+        Mtu,
+        Ack,
+        ProtocolViolation,
+        StreamScheduler,
+        ExtensionFrame,
+        Tenant,
+        RetryOffload,
         Sync,
         Tls,
         AddressToken,
@@ -269,6 +585,13 @@ impl<
     type ConnectionLimits = Limits;
     type Stream = stream::StreamImpl;
     type PathMigrationValidator = PathMigration;
+    type MtuEndpoint = Mtu;
+    type AckEndpoint = Ack;
+    type ProtocolViolationEndpoint = ProtocolViolation;
+    type StreamSchedulerEndpoint = StreamScheduler;
+    type ExtensionFrameEndpoint = ExtensionFrame;
+    type TenantClassifier = Tenant;
+    type RetryOffload = RetryOffload;
     type PacketInterceptor = PacketInterceptor;
     type DatagramEndpoint = Datagram;
 
@@ -288,6 +611,13 @@ impl<
             connection_limits: &mut self.limits,
             event_subscriber: &mut self.event,
             path_migration: &mut self.path_migration,
+            mtu: &mut self.mtu,
+            ack: &mut self.ack,
+            protocol_violation: &mut self.protocol_violation,
+            stream_scheduler: &mut self.stream_scheduler,
+            extension_frame: &mut self.extension_frame,
+            tenant: &mut self.tenant,
+            retry_offload: &mut self.retry_offload,
             datagram: &mut self.datagram,
         }
     }
@@ -0,0 +1,267 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Upgrades the binary of a running server without closing its sockets
+//!
+//! The upgrade is coordinated over a unix domain socket, called the control channel:
+//!
+//! 1. The running process binds a [`Listener`] to the control channel and calls
+//!    [`Listener::handoff`] with copies of its UDP sockets and the [`Drainer`] of its server.
+//! 2. The new process is started and calls [`Upgrade::connect`], which receives the UDP sockets.
+//!    The sockets are passed to the IO provider of the new server, for example with
+//!    [`Builder::with_rx_socket`](crate::provider::io::tokio::Builder::with_rx_socket).
+//! 3. Once the new server is started, it calls [`Upgrade::ready`].
+//! 4. The running process stops accepting new connections and drains the open connections.
+//!
+//! Since both processes share the sockets, the datagrams which are queued on the sockets are
+//! never dropped. If the new process fails before it is ready, the running process continues to
+//! serve connections without draining.
+//!
+//! Alternatively, the new process can bind its own sockets to the same address with
+//! [`Builder::with_reuse_port`](crate::provider::io::tokio::Builder::with_reuse_port) and drop the
+//! received sockets, in which case the control channel only coordinates the switchover.
+//!
+//! In both cases, the kernel might deliver the datagrams of connections which are still open on
+//! the running process to the new process. The new process doesn't know these connections and
+//! answers them with stateless resets, if enabled. Long-lived connections can be moved to the new
+//! process with [`connection::Handle::export_state`](crate::connection::Handle::export_state)
+//! and [`Server::import`](crate::Server::import) instead of being drained.
+
+use super::{Drain, Drainer};
+use s2n_quic_platform::socket::passing;
+use std::{
+    io::{self, Read, Write},
+    net::UdpSocket,
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The message which is sent by the new process once its server is started
+const READY: u8 = 1;
+
+/// Listens on the control channel of the running process
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use std::{error::Error, net::UdpSocket, path::Path};
+/// use s2n_quic::{
+///     provider::io::tokio::Builder as IoBuilder,
+///     server::{upgrade::Listener, Drain},
+///     Server,
+/// };
+///
+/// # async fn run() -> Result<(), Box<dyn Error>> {
+/// let socket = UdpSocket::bind("127.0.0.1:443")?;
+/// let handoff_socket = socket.try_clone()?;
+///
+/// let io = IoBuilder::default().with_rx_socket(socket)?.build()?;
+/// let mut server = Server::builder()
+///     .with_tls((Path::new("./certs/cert.pem"), Path::new("./certs/key.pem")))?
+///     .with_io(io)?
+///     .start()?;
+///
+/// let listener = Listener::bind("/run/my-server/upgrade.sock")?;
+/// let drainer = server.drainer();
+/// tokio::spawn(async move {
+///     // hands the socket to the new process and drains this server once it is ready
+///     listener.handoff(vec![handoff_socket], drainer, Drain::new()).await
+/// });
+///
+/// while let Some(connection) = server.accept().await {
+///     // ...
+/// }
+/// #
+/// #    Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Listener {
+    listener: UnixListener,
+    path: PathBuf,
+    ready_timeout: Option<Duration>,
+}
+
+impl Listener {
+    /// Binds the control channel to the given path
+    ///
+    /// A file which was left behind at the path by a previous process is removed.
+    pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+
+        let listener = UnixListener::bind(&path)?;
+
+        Ok(Self {
+            listener,
+            path,
+            ready_timeout: None,
+        })
+    }
+
+    /// Sets the maximum amount of time the new process has to start its server
+    ///
+    /// If the new process isn't ready in time, the handoff fails and the server is not drained.
+    #[must_use]
+    pub fn with_ready_timeout(mut self, timeout: Duration) -> Self {
+        self.ready_timeout = Some(timeout);
+        self
+    }
+
+    /// Waits for a new process, hands the sockets to it, and drains the server once the new
+    /// process is ready
+    pub async fn handoff(
+        self,
+        sockets: Vec<UdpSocket>,
+        mut drainer: Drainer,
+        drain: Drain,
+    ) -> io::Result<()> {
+        tokio::task::spawn_blocking(move || self.handoff_blocking(&sockets))
+            .await
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))??;
+
+        drainer
+            .drain(drain)
+            .await
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))
+    }
+
+    fn handoff_blocking(&self, sockets: &[UdpSocket]) -> io::Result<()> {
+        let (mut stream, _) = self.listener.accept()?;
+        stream.set_read_timeout(self.ready_timeout)?;
+
+        passing::send(&stream, sockets)?;
+
+        let mut message = [0u8; 1];
+        stream.read_exact(&mut message)?;
+
+        if message[0] != READY {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unexpected message on the control channel",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Takes over the sockets of the running process in the new process
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use std::{error::Error, path::Path};
+/// use s2n_quic::{provider::io::tokio::Builder as IoBuilder, server::upgrade::Upgrade, Server};
+///
+/// # async fn run() -> Result<(), Box<dyn Error>> {
+/// let mut upgrade = Upgrade::connect("/run/my-server/upgrade.sock")?;
+/// let socket = upgrade.take_sockets().pop().expect("the socket is passed");
+///
+/// let io = IoBuilder::default().with_rx_socket(socket)?.build()?;
+/// let mut server = Server::builder()
+///     .with_tls((Path::new("./certs/cert.pem"), Path::new("./certs/key.pem")))?
+///     .with_io(io)?
+///     .start()?;
+///
+/// // the previous process starts draining its server
+/// upgrade.ready()?;
+///
+/// while let Some(connection) = server.accept().await {
+///     // ...
+/// }
+/// #
+/// #    Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Upgrade {
+    stream: UnixStream,
+    sockets: Vec<UdpSocket>,
+}
+
+impl Upgrade {
+    /// Connects to the control channel of the running process and receives its sockets
+    pub fn connect<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let stream = UnixStream::connect(path)?;
+        let sockets = passing::receive(&stream)?;
+        Ok(Self { stream, sockets })
+    }
+
+    /// Takes the sockets which were received from the running process
+    ///
+    /// The sockets are in the same order as they were passed to [`Listener::handoff`].
+    pub fn take_sockets(&mut self) -> Vec<UdpSocket> {
+        core::mem::take(&mut self.sockets)
+    }
+
+    /// Notifies the running process that the server of the new process was started
+    pub fn ready(mut self) -> io::Result<()> {
+        self.stream.write_all(&[READY])?;
+        self.stream.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn control_channel_test() {
+        let dir = std::env::temp_dir().join(format!("s2n-quic-upgrade-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("upgrade.sock");
+
+        let listener = Listener::bind(&path).unwrap();
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+
+        let child = std::thread::spawn(move || {
+            let mut upgrade = Upgrade::connect(&path).unwrap();
+            let sockets = upgrade.take_sockets();
+            assert_eq!(sockets.len(), 1);
+            assert_eq!(sockets[0].local_addr().unwrap(), addr);
+            upgrade.ready().unwrap();
+        });
+
+        listener.handoff_blocking(&[socket]).unwrap();
+        child.join().unwrap();
+
+        drop(listener);
+        let _ = std::fs::remove_dir(&dir);
+    }
+
+    #[test]
+    fn not_ready_test() {
+        let dir = std::env::temp_dir().join(format!("s2n-quic-not-ready-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("upgrade.sock");
+
+        let listener = Listener::bind(&path).unwrap();
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        let child = std::thread::spawn(move || {
+            // the new process exits before its server is ready
+            let upgrade = Upgrade::connect(&path).unwrap();
+            drop(upgrade);
+        });
+
+        assert!(listener.handoff_blocking(&[socket]).is_err());
+        child.join().unwrap();
+
+        drop(listener);
+        let _ = std::fs::remove_dir(&dir);
+    }
+}
@@ -15,7 +15,11 @@ mod bidirectional;
 mod local;
 mod peer;
 
+#[cfg(any(test, feature = "unstable-stream-compression"))]
+pub mod compression;
+
 pub use s2n_quic_core::stream::{StreamError as Error, StreamType as Type};
+pub use s2n_quic_transport::stream::group::Id as GroupId;
 
 pub use bidirectional::*;
 pub use local::*;
@@ -0,0 +1,529 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compresses the data which is sent on a stream with [zstd](https://facebook.github.io/zstd/)
+//!
+//! Any stream can be wrapped with `compressed(level)`, which compresses the data on send and
+//! decompresses the data on receive. The compression isn't negotiated with the peer, so the
+//! application protocol needs to make sure both endpoints wrap the stream. The wrapper is
+//! generic over the [`Sink`] and [`Stream`] implementations of the stream, which allows
+//! composing it with other adapters.
+//!
+//! Compressed data is buffered until it fills a block, so [`Compressed::flush`] should be called
+//! when the peer needs to receive the data which was sent so far. [`Compressed::close`] ends the
+//! compressed frame before finishing the stream.
+//!
+//! ```rust,no_run
+//! # async fn test() -> Result<(), s2n_quic::stream::compression::Error> {
+//! #   let mut connection: s2n_quic::connection::Connection = todo!();
+//! #
+//! let mut stream = connection.open_bidirectional_stream().await?.compressed(3);
+//! stream.send(bytes::Bytes::from_static(&[0; 10_000])).await?;
+//! stream.close().await?;
+//!
+//! while let Some(chunk) = stream.receive().await? {
+//!     println!("received {} bytes", chunk.len());
+//! }
+//! #
+//! #   Ok(())
+//! # }
+//! ```
+
+use crate::stream::{
+    BidirectionalStream, Error as StreamError, LocalStream, PeerStream, ReceiveStream, SendStream,
+    Stream as QuicStream,
+};
+use bytes::{Buf, Bytes, BytesMut};
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures::{ready, Sink, Stream};
+use std::io;
+use zstd::stream::raw::{Decoder, Encoder, Operation, OutBuffer};
+
+/// The amount of output which is produced at a time by the compressor and decompressor
+const CHUNK_LEN: usize = 16 * 1024;
+
+/// An error which occurred on a compressed stream
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The underlying stream encountered an error
+    Stream(StreamError),
+    /// The data couldn't be compressed or decompressed
+    Compression(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Stream(error) => fmt::Display::fmt(error, f),
+            Self::Compression(error) => write!(f, "compression error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Stream(error) => Some(error),
+            Self::Compression(error) => Some(error),
+        }
+    }
+}
+
+impl From<StreamError> for Error {
+    #[inline]
+    fn from(error: StreamError) -> Self {
+        Self::Stream(error)
+    }
+}
+
+impl From<io::Error> for Error {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        Self::Compression(error)
+    }
+}
+
+impl From<Error> for io::Error {
+    #[inline]
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Stream(error) => error.into(),
+            Error::Compression(error) => error,
+        }
+    }
+}
+
+/// A stream which compresses the data it sends and decompresses the data it receives
+pub struct Compressed<S> {
+    stream: S,
+    level: i32,
+    /// The compressor, which is created once data is sent
+    encoder: Option<Encoder<'static>>,
+    /// Compressed data which wasn't passed to the stream yet
+    pending: BytesMut,
+    /// Set once the compressed frame was ended
+    finished: bool,
+    /// The decompressor, which is created once data is received
+    decoder: Option<Decoder<'static>>,
+    /// Received data which wasn't decompressed yet
+    received: Bytes,
+    /// Set if the decompressor filled the output and may hold more data
+    decoder_full: bool,
+    /// Set if the received data ends on a frame boundary
+    frame_complete: bool,
+}
+
+impl<S: fmt::Debug> fmt::Debug for Compressed<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Compressed")
+            .field("stream", &self.stream)
+            .field("level", &self.level)
+            .field("pending", &self.pending.len())
+            .field("finished", &self.finished)
+            .field("received", &self.received.len())
+            .finish()
+    }
+}
+
+impl<S> Compressed<S> {
+    /// Wraps a stream, compressing the sent data with the zstd compression `level`
+    ///
+    /// The level is only used for sending data and follows the zstd conventions: `0` selects the
+    /// default level, and higher levels trade speed for better compression.
+    #[inline]
+    pub fn new(stream: S, level: i32) -> Self {
+        Self {
+            stream,
+            level,
+            encoder: None,
+            pending: BytesMut::new(),
+            finished: false,
+            decoder: None,
+            received: Bytes::new(),
+            decoder_full: false,
+            frame_complete: true,
+        }
+    }
+
+    /// Returns a reference to the wrapped stream
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Returns a mutable reference to the wrapped stream
+    ///
+    /// Data which is sent or received on the wrapped stream directly bypasses the compression,
+    /// which corrupts the compressed data.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Returns the wrapped stream
+    ///
+    /// Compressed data which wasn't flushed to the stream yet is discarded.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    /// Compresses the data into the pending buffer
+    fn compress(&mut self, mut data: &[u8]) -> Result<(), Error> {
+        if self.finished {
+            return Err(StreamError::send_after_finish().into());
+        }
+
+        let encoder = match &mut self.encoder {
+            Some(encoder) => encoder,
+            encoder => encoder.insert(Encoder::new(self.level)?),
+        };
+
+        while !data.is_empty() {
+            let len = self.pending.len();
+            self.pending.resize(len + CHUNK_LEN, 0);
+            let status = encoder.run_on_buffers(data, &mut self.pending[len..])?;
+            self.pending.truncate(len + status.bytes_written);
+            data = &data[status.bytes_read..];
+        }
+
+        Ok(())
+    }
+
+    /// Moves the data which is buffered by the compressor into the pending buffer
+    ///
+    /// If `finish` is set, the compressed frame is ended.
+    fn drain_encoder(&mut self, finish: bool) -> Result<(), Error> {
+        if finish {
+            self.finished = true;
+        }
+
+        let encoder = match self.encoder.as_mut() {
+            Some(encoder) => encoder,
+            None => return Ok(()),
+        };
+
+        loop {
+            let len = self.pending.len();
+            self.pending.resize(len + CHUNK_LEN, 0);
+            let mut output = OutBuffer::around(&mut self.pending[len..]);
+            let remaining = if finish {
+                encoder.finish(&mut output, true)
+            } else {
+                encoder.flush(&mut output)
+            };
+            let written = output.pos();
+            self.pending.truncate(len + written);
+
+            if remaining? == 0 {
+                break;
+            }
+        }
+
+        if finish {
+            self.encoder = None;
+        }
+
+        Ok(())
+    }
+
+    /// Decompresses the received data into a chunk of up to `CHUNK_LEN` bytes
+    fn decompress(&mut self) -> Result<Option<Bytes>, Error> {
+        if self.received.is_empty() && !self.decoder_full {
+            return Ok(None);
+        }
+
+        let decoder = match &mut self.decoder {
+            Some(decoder) => decoder,
+            decoder => decoder.insert(Decoder::new()?),
+        };
+
+        let mut output = vec![0; CHUNK_LEN];
+        let mut written = 0;
+
+        loop {
+            let status = decoder.run_on_buffers(&self.received, &mut output[written..])?;
+            self.received.advance(status.bytes_read);
+            written += status.bytes_written;
+
+            if status.bytes_read > 0 || status.bytes_written > 0 {
+                // the decompressor returns 0 once a frame is decoded and flushed
+                self.frame_complete = status.remaining == 0;
+            }
+
+            if written == output.len() || (self.received.is_empty() && status.bytes_written == 0) {
+                break;
+            }
+        }
+
+        self.decoder_full = written == output.len();
+
+        if written == 0 {
+            return Ok(None);
+        }
+
+        output.truncate(written);
+        Ok(Some(output.into()))
+    }
+}
+
+impl<S: Sink<Bytes, Error = StreamError> + Unpin> Compressed<S> {
+    /// Passes the pending compressed data to the stream
+    fn poll_send_pending(&mut self, cx: &mut Context) -> Poll<Result<(), Error>> {
+        while !self.pending.is_empty() {
+            ready!(Pin::new(&mut self.stream).poll_ready(cx))?;
+            let chunk = self.pending.split().freeze();
+            Pin::new(&mut self.stream).start_send(chunk)?;
+        }
+
+        Ok(()).into()
+    }
+
+    /// Compresses and sends a chunk of data
+    ///
+    /// The data is buffered by the compressor until [`Self::flush`] or [`Self::close`] is called.
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancel safe. The data is compressed when the future is first polled, and
+    /// the future then only waits for the stream to accept the compressed data, which is kept by
+    /// the adapter and passed to the stream by the next call if the future is dropped.
+    pub async fn send(&mut self, data: Bytes) -> Result<(), Error> {
+        self.compress(&data)?;
+        futures::future::poll_fn(|cx| self.poll_send_pending(cx)).await
+    }
+
+    /// Sends all of the data which is buffered by the compressor and flushes the stream
+    pub async fn flush(&mut self) -> Result<(), Error> {
+        futures::SinkExt::flush(self).await
+    }
+
+    /// Ends the compressed data and closes the sending side of the stream
+    pub async fn close(&mut self) -> Result<(), Error> {
+        futures::SinkExt::close(self).await
+    }
+}
+
+impl<S: Stream<Item = Result<Bytes, StreamError>> + Unpin> Compressed<S> {
+    /// Receives and decompresses the next chunk of data
+    ///
+    /// Returns `None` once the peer closed the stream. Returns an error if the stream ended
+    /// in the middle of a compressed frame.
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancel safe. Received data which wasn't decompressed yet is kept by the
+    /// adapter, so dropping the future doesn't lose any data.
+    pub async fn receive(&mut self) -> Result<Option<Bytes>, Error> {
+        futures::StreamExt::next(self).await.transpose()
+    }
+}
+
+impl<S: Sink<Bytes, Error = StreamError> + Unpin> Sink<Bytes> for Compressed<S> {
+    type Error = Error;
+
+    #[inline]
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Error>> {
+        self.poll_send_pending(cx)
+    }
+
+    #[inline]
+    fn start_send(mut self: Pin<&mut Self>, data: Bytes) -> Result<(), Error> {
+        self.compress(&data)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Error>> {
+        self.drain_encoder(false)?;
+        ready!(self.poll_send_pending(cx))?;
+        Pin::new(&mut self.stream)
+            .poll_flush(cx)
+            .map_err(Error::from)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Error>> {
+        if !self.finished {
+            self.drain_encoder(true)?;
+        }
+        ready!(self.poll_send_pending(cx))?;
+        Pin::new(&mut self.stream)
+            .poll_close(cx)
+            .map_err(Error::from)
+    }
+}
+
+impl<S: Stream<Item = Result<Bytes, StreamError>> + Unpin> Stream for Compressed<S> {
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(chunk) = self.decompress()? {
+                return Some(Ok(chunk)).into();
+            }
+
+            match ready!(Pin::new(&mut self.stream).poll_next(cx)) {
+                Some(Ok(chunk)) => self.received = chunk,
+                Some(Err(error)) => return Some(Err(error.into())).into(),
+                None if self.frame_complete => return None.into(),
+                None => {
+                    let error = io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "the stream ended in the middle of a compressed frame",
+                    );
+                    return Some(Err(error.into())).into();
+                }
+            }
+        }
+    }
+}
+
+macro_rules! impl_compressed {
+    ($($name:ident),* $(,)?) => {
+        $(
+            impl $name {
+                /// Wraps the stream with an adapter which compresses the sent data with zstd and
+                /// decompresses the received data
+                ///
+                /// The `level` is only used for sending data. See the
+                /// [`compression`](crate::stream::compression) module for more details.
+                #[inline]
+                pub fn compressed(self, level: i32) -> Compressed<Self> {
+                    Compressed::new(self, level)
+                }
+            }
+        )*
+    };
+}
+
+impl_compressed!(
+    BidirectionalStream,
+    SendStream,
+    ReceiveStream,
+    LocalStream,
+    PeerStream,
+    QuicStream,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{SinkExt, StreamExt};
+    use std::collections::VecDeque;
+
+    /// Records the sent chunks, which can be received again
+    #[derive(Default)]
+    struct Loopback {
+        chunks: VecDeque<Bytes>,
+        closed: bool,
+    }
+
+    impl Sink<Bytes> for Loopback {
+        type Error = StreamError;
+
+        fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), StreamError>> {
+            Ok(()).into()
+        }
+
+        fn start_send(mut self: Pin<&mut Self>, chunk: Bytes) -> Result<(), StreamError> {
+            self.chunks.push_back(chunk);
+            Ok(())
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), StreamError>> {
+            Ok(()).into()
+        }
+
+        fn poll_close(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context,
+        ) -> Poll<Result<(), StreamError>> {
+            self.closed = true;
+            Ok(()).into()
+        }
+    }
+
+    impl Stream for Loopback {
+        type Item = Result<Bytes, StreamError>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Option<Self::Item>> {
+            match self.chunks.pop_front() {
+                Some(chunk) => Some(Ok(chunk)).into(),
+                None if self.closed => None.into(),
+                None => Poll::Pending,
+            }
+        }
+    }
+
+    async fn receive_all(stream: &mut Compressed<Loopback>) -> Vec<u8> {
+        let mut received = vec![];
+        while let Some(chunk) = stream.next().await {
+            received.extend_from_slice(&chunk.unwrap());
+        }
+        received
+    }
+
+    #[tokio::test]
+    async fn round_trip_test() {
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i % 7) as u8).collect();
+
+        let mut stream = Compressed::new(Loopback::default(), 3);
+        for chunk in data.chunks(1000) {
+            stream.send(Bytes::copy_from_slice(chunk)).await.unwrap();
+        }
+        stream.close().await.unwrap();
+
+        // the repetitive data compresses well
+        let compressed_len: usize = stream
+            .get_ref()
+            .chunks
+            .iter()
+            .map(|chunk| chunk.len())
+            .sum();
+        assert!(compressed_len < data.len() / 10);
+
+        assert_eq!(receive_all(&mut stream).await, data);
+        assert!(stream.send(Bytes::from_static(b"late")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn flush_test() {
+        let mut stream = Compressed::new(Loopback::default(), 0);
+        stream.send(Bytes::from_static(b"hello")).await.unwrap();
+        stream.flush().await.unwrap();
+
+        // the peer can decompress the data before the stream is closed
+        let chunk = stream.receive().await.unwrap().unwrap();
+        assert_eq!(chunk, Bytes::from_static(b"hello"));
+    }
+
+    #[tokio::test]
+    async fn truncated_test() {
+        let mut stream = Compressed::new(Loopback::default(), 0);
+        stream.send(Bytes::from_static(b"hello")).await.unwrap();
+        stream.flush().await.unwrap();
+        // the stream is closed without ending the compressed frame
+        stream.get_mut().close().await.unwrap();
+
+        assert_eq!(
+            stream.receive().await.unwrap().unwrap(),
+            Bytes::from_static(b"hello")
+        );
+        assert!(matches!(stream.receive().await, Err(Error::Compression(_))));
+    }
+
+    #[tokio::test]
+    async fn corrupted_test() {
+        let mut stream = Compressed::new(Loopback::default(), 0);
+        stream
+            .get_mut()
+            .send(Bytes::from_static(b"not compressed"))
+            .await
+            .unwrap();
+
+        assert!(matches!(stream.receive().await, Err(Error::Compression(_))));
+    }
+}
@@ -19,6 +19,11 @@ macro_rules! impl_receive_stream_api {
         /// - `Ok(None)` if the stream was finished and all of the data was consumed.
         /// - `Err(e)` if the stream encountered a [`stream::Error`](crate::stream::Error).
         ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. A chunk is only removed from the stream when the future
+        /// completes, so if the future is dropped, no data is lost.
+        ///
         /// # Examples
         ///
         /// ```rust,no_run
@@ -85,6 +90,11 @@ macro_rules! impl_receive_stream_api {
         ///   `Ok((0, false))`.
         /// - `Err(e)` if the stream encountered a [`stream::Error`](crate::stream::Error).
         ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. The chunks are only filled when the future completes, so
+        /// if the future is dropped, no data is lost and the slice is left untouched.
+        ///
         /// # Examples
         ///
         /// ```rust,no_run
@@ -153,6 +163,118 @@ macro_rules! impl_receive_stream_api {
             $dispatch_body
         }
 
+        /// Waits until the stream has data available for receiving.
+        ///
+        /// This allows receiving data in two phases: the readiness is awaited without consuming
+        /// any data, and the data is then taken from the stream with
+        /// [`receive_data`](Self::receive_data), which never waits.
+        ///
+        /// # Return value
+        ///
+        /// The function returns:
+        ///
+        /// - `Ok(available_bytes)` if the stream has data available, where `available_bytes` is
+        ///   how many bytes can currently be received. If the stream was finished and all of the
+        ///   data was consumed, `0` is returned.
+        /// - `Err(e)` if the stream encountered a [`stream::Error`](crate::stream::Error).
+        ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. It never consumes any data, so it can be used in a
+        /// `select!` loop without any state being held by the future.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # async fn test() -> s2n_quic::stream::Result<()> {
+        /// #   let mut stream: s2n_quic::stream::ReceiveStream = todo!();
+        /// #   let mut shutdown: tokio::sync::oneshot::Receiver<()> = todo!();
+        /// #
+        /// loop {
+        ///     tokio::select! {
+        ///         ready = stream.receive_ready() => {
+        ///             ready?;
+        ///         }
+        ///         _ = &mut shutdown => {
+        ///             // any pending data is still buffered in the stream
+        ///             break;
+        ///         }
+        ///     }
+        ///
+        ///     match stream.receive_data()? {
+        ///         Some(chunk) => println!("received: {:?}", chunk),
+        ///         None => break,
+        ///     }
+        /// }
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub async fn receive_ready(&mut self) -> $crate::stream::Result<usize> {
+            ::futures::future::poll_fn(|cx| self.poll_receive_ready(cx)).await
+        }
+
+        /// Polls if the stream has data available for receiving.
+        ///
+        /// # Return value
+        ///
+        /// The function returns:
+        ///
+        /// - `Poll::Pending` if the stream is waiting to receive data from the peer. In this case,
+        ///   the caller should retry receiving after the [`Waker`](core::task::Waker) on the provided
+        ///   [`Context`](core::task::Context) is notified.
+        /// - `Poll::Ready(Ok(available_bytes))` if the stream has data available, where
+        ///   `available_bytes` is how many bytes can currently be received. If the stream was
+        ///   finished and all of the data was consumed, `0` is returned.
+        /// - `Poll::Ready(Err(e))` if the stream encountered a [`stream::Error`](crate::stream::Error).
+        #[inline]
+        pub fn poll_receive_ready(
+            &mut self,
+            cx: &mut core::task::Context,
+        ) -> core::task::Poll<$crate::stream::Result<usize>> {
+            macro_rules! $dispatch {
+                () => {
+                    Err($crate::stream::Error::non_readable()).into()
+                };
+                ($variant: expr) => {
+                    $variant.poll_receive_ready(cx)
+                };
+            }
+
+            let $stream = self;
+            $dispatch_body
+        }
+
+        /// Receives a chunk of data from the stream without blocking the task.
+        ///
+        /// [`receive_ready`](Self::receive_ready) or
+        /// [`poll_receive_ready`](Self::poll_receive_ready) _must_ be called before calling this
+        /// method.
+        ///
+        /// # Return value
+        ///
+        /// The function returns:
+        ///
+        /// - `Ok(Some(chunk))` if data was available.
+        /// - `Ok(None)` if the stream was finished and all of the data was consumed.
+        /// - `Err(ReceivingBlocked)` if the stream did not have any data available.
+        /// - `Err(e)` if the stream encountered a [`stream::Error`](crate::stream::Error).
+        #[inline]
+        pub fn receive_data(&mut self) -> $crate::stream::Result<Option<bytes::Bytes>> {
+            macro_rules! $dispatch {
+                () => {
+                    Err($crate::stream::Error::non_readable())
+                };
+                ($variant: expr) => {
+                    $variant.receive_data()
+                };
+            }
+
+            let $stream = self;
+            $dispatch_body
+        }
+
         /// Notifies the peer to stop sending data on the stream.
         ///
         /// This requests the peer to finish the stream as soon as possible
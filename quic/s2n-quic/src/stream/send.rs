@@ -1,12 +1,102 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures::channel::oneshot;
 use s2n_quic_transport::stream;
 
 /// A QUIC stream that is only allowed to send data.
 #[derive(Debug)]
 pub struct SendStream(stream::SendStream);
 
+/// A future which resolves once the peer has acknowledged the data sent on a stream
+///
+/// The future doesn't borrow the stream, so more data can be sent while waiting for the
+/// acknowledgement. It resolves with an error if the stream is reset before all of the data is
+/// acknowledged.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Delivery(oneshot::Receiver<crate::stream::Result<()>>);
+
+impl Delivery {
+    #[inline]
+    pub(crate) fn register<
+        F: FnOnce(s2n_quic_core::stream::ops::tx::Delivery) -> crate::stream::Result<()>,
+    >(
+        register: F,
+    ) -> crate::stream::Result<Self> {
+        let (sender, receiver) = oneshot::channel();
+        let delivery = s2n_quic_core::stream::ops::tx::Delivery::new(move |result| {
+            // the application may not be interested in the outcome anymore
+            let _ = sender.send(result);
+        });
+        register(delivery)?;
+        Ok(Self(receiver))
+    }
+}
+
+impl Future for Delivery {
+    type Output = crate::stream::Result<()>;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        match futures::ready!(Pin::new(&mut self.0).poll(cx)) {
+            Ok(result) => result.into(),
+            // the stream was dropped without notifying the delivery
+            Err(_) => Err(crate::stream::Error::invalid_stream()).into(),
+        }
+    }
+}
+
+/// Resets a stream if it's dropped before [`into_inner`](Self::into_inner) is called
+///
+/// Dropping a stream finishes it, which makes the peer consider a partially sent message as
+/// complete. Futures which open a stream and send a message on it hold the stream in a guard
+/// so cancelling them resets the stream instead.
+#[derive(Debug)]
+pub(crate) struct ResetGuard<S> {
+    stream: Option<S>,
+    reset: fn(&mut S, crate::application::Error) -> crate::stream::Result<()>,
+}
+
+impl<S> ResetGuard<S> {
+    #[inline]
+    pub(crate) fn new(
+        stream: S,
+        reset: fn(&mut S, crate::application::Error) -> crate::stream::Result<()>,
+    ) -> Self {
+        Self {
+            stream: Some(stream),
+            reset,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn get_mut(&mut self) -> &mut S {
+        self.stream.as_mut().expect("stream is only taken on drop")
+    }
+
+    /// Returns the stream without resetting it
+    #[inline]
+    pub(crate) fn into_inner(mut self) -> S {
+        self.stream.take().expect("stream is only taken on drop")
+    }
+}
+
+impl<S> Drop for ResetGuard<S> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(stream) = self.stream.as_mut() {
+            // the stream may have already been reset by the peer or closed with the connection
+            let _ = (self.reset)(stream, crate::application::Error::UNKNOWN);
+        }
+    }
+}
+
 macro_rules! impl_send_stream_api {
     (| $stream:ident, $dispatch:ident | $dispatch_body:expr) => {
         /// Enqueues a chunk of data for sending it towards the peer.
@@ -18,6 +108,14 @@ macro_rules! impl_send_stream_api {
         /// - `Ok(())` if the data was enqueued for sending.
         /// - `Err(e)` if the stream encountered a [`stream::Error`](crate::stream::Error).
         ///
+        /// # Cancel safety
+        ///
+        /// This method is not cancel safe. If the future is dropped before the stream accepted
+        /// the `data`, the `data` is dropped with it and is never sent. Applications which poll
+        /// the stream in a `select!` loop should wait for [`send_ready`](Self::send_ready) and
+        /// enqueue the data with [`send_data`](Self::send_data) instead, or keep the chunks in
+        /// a slice which is passed to [`send_vectored`](Self::send_vectored).
+        ///
         /// # Examples
         ///
         /// ```rust,no_run
@@ -76,6 +174,12 @@ macro_rules! impl_send_stream_api {
         ///   consumed [`Bytes`](bytes::Bytes) will be replaced with an empty [`Bytes`](bytes::Bytes).
         /// - `Err(e)` if the stream encountered a [`stream::Error`](crate::stream::Error).
         ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. If the future is dropped, the chunks which were enqueued
+        /// are empty and the rest of the data remains in the slice, so calling this method again
+        /// with the same slice resumes sending without losing or duplicating any data.
+        ///
         /// # Examples
         ///
         /// ```rust,no_run
@@ -142,6 +246,141 @@ macro_rules! impl_send_stream_api {
             $dispatch_body
         }
 
+        /// Enqueues a slice of chunks of data for sending it towards the peer and marks the
+        /// stream as finished.
+        ///
+        /// This method is equivalent to calling [`send_vectored`](Self::send_vectored) and
+        /// [`finish`](Self::finish), but the FIN is enqueued along with the final chunk, which
+        /// allows both to be transmitted in the same packet.
+        ///
+        /// # Return value
+        ///
+        /// The function returns:
+        ///
+        /// - `Ok(())` if all of the chunks of data were enqueued for sending and the stream was
+        ///   finished. Each of the consumed [`Bytes`](bytes::Bytes) will be replaced with an empty
+        ///   [`Bytes`](bytes::Bytes).
+        /// - `Err(e)` if the stream encountered a [`stream::Error`](crate::stream::Error).
+        ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. The stream is only finished once all of the chunks were
+        /// enqueued, so if the future is dropped, calling this method again with the same slice
+        /// resumes sending the remaining chunks.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # async fn test() -> s2n_quic::stream::Result<()> {
+        /// #   let stream: s2n_quic::stream::SendStream = todo!();
+        /// #
+        /// let mut chunks = [
+        ///     bytes::Bytes::from_static(&[1, 2, 3]),
+        ///     bytes::Bytes::from_static(&[4, 5, 6]),
+        /// ];
+        /// stream.send_vectored_and_finish(&mut chunks).await?;
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub async fn send_vectored_and_finish(
+            &mut self,
+            chunks: &mut [bytes::Bytes],
+        ) -> $crate::stream::Result<()> {
+            let mut sent_chunks = 0;
+
+            ::futures::future::poll_fn(|cx| {
+                sent_chunks += ::futures::ready!(
+                    self.poll_send_vectored_and_finish(&mut chunks[sent_chunks..], cx)
+                )?;
+                if sent_chunks == chunks.len() {
+                    return Ok(()).into();
+                }
+                core::task::Poll::Pending
+            })
+            .await
+        }
+
+        /// Polls enqueueing a slice of chunks of data for sending it towards the peer and marking
+        /// the stream as finished.
+        ///
+        /// # Return value
+        ///
+        /// The function returns:
+        ///
+        /// - `Poll::Pending` if the stream's send buffer capacity is currently exhausted. In this case,
+        ///   the caller should retry sending after the [`Waker`](core::task::Waker) on the provided
+        ///   [`Context`](core::task::Context) is notified.
+        /// - `Poll::Ready(Ok(count))` if one or more chunks of data were enqueued for sending. Any of the
+        ///   consumed [`Bytes`](bytes::Bytes) will be replaced with an empty [`Bytes`](bytes::Bytes).
+        ///   The stream is only finished once `count` equals the total number of chunks. Otherwise,
+        ///   the stream will store the [Waker](core::task::Waker) and notify the task once more
+        ///   capacity is available.
+        /// - `Poll::Ready(Err(e))` if the stream encountered a [`stream::Error`](crate::stream::Error).
+        #[inline]
+        pub fn poll_send_vectored_and_finish(
+            &mut self,
+            chunks: &mut [bytes::Bytes],
+            cx: &mut core::task::Context,
+        ) -> core::task::Poll<$crate::stream::Result<usize>> {
+            macro_rules! $dispatch {
+                () => {
+                    Err($crate::stream::Error::non_writable()).into()
+                };
+                ($variant: expr) => {
+                    $variant.poll_send_vectored_and_finish(chunks, cx)
+                };
+            }
+
+            let $stream = self;
+            $dispatch_body
+        }
+
+        /// Waits until the stream is ready to send data.
+        ///
+        /// This method, or [`poll_send_ready`](Self::poll_send_ready), _must_ be called before
+        /// calling [`send_data`](Self::send_data).
+        ///
+        /// # Return value
+        ///
+        /// The function returns:
+        /// - `Ok(available_bytes)` if the stream is ready to send data, where `available_bytes` is
+        ///   how many bytes the stream can currently accept.
+        /// - `Err(e)` if the stream encountered a [`stream::Error`](crate::stream::Error).
+        ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. It doesn't take ownership of any data, so it can be used in
+        /// a `select!` loop, followed by [`send_data`](Self::send_data) once it returns.
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # async fn test() -> s2n_quic::stream::Result<()> {
+        /// #   let mut stream: s2n_quic::stream::SendStream = todo!();
+        /// #   let mut shutdown: tokio::sync::oneshot::Receiver<()> = todo!();
+        /// #
+        /// let data = bytes::Bytes::from_static(&[1, 2, 3, 4]);
+        ///
+        /// tokio::select! {
+        ///     ready = stream.send_ready() => {
+        ///         ready?;
+        ///         stream.send_data(data)?;
+        ///     }
+        ///     _ = &mut shutdown => {
+        ///         // `data` is still owned by the application
+        ///     }
+        /// }
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub async fn send_ready(&mut self) -> $crate::stream::Result<usize> {
+            ::futures::future::poll_fn(|cx| self.poll_send_ready(cx)).await
+        }
+
         /// Polls send readiness for the given stream.
         ///
         /// This method _must_ be called before calling [`send_data`](Self::send_data).
@@ -175,7 +414,8 @@ macro_rules! impl_send_stream_api {
 
         /// Sends data on the stream without blocking the task.
         ///
-        /// [`poll_send_ready`](Self::poll_send_ready) _must_ be called before calling this method.
+        /// [`send_ready`](Self::send_ready) or [`poll_send_ready`](Self::poll_send_ready) _must_ be
+        /// called before calling this method.
         ///
         /// # Return value
         ///
@@ -208,6 +448,11 @@ macro_rules! impl_send_stream_api {
         ///   the peer.
         /// - `Err(e)` if the stream encountered a [`stream::Error`](crate::stream::Error).
         ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. Dropping the future only stops waiting for the
+        /// acknowledgements, and the enqueued data is still sent.
+        ///
         /// # Examples
         ///
         /// ```rust,no_run
@@ -256,6 +501,80 @@ macro_rules! impl_send_stream_api {
             $dispatch_body
         }
 
+        /// Enqueues a chunk of data for sending it towards the peer and returns a [`Delivery`]
+        /// which resolves once the peer has acknowledged it.
+        ///
+        /// Since stream data is delivered in order, the [`Delivery`] also covers all of the data
+        /// which was sent before `data`.
+        ///
+        /// # Return value
+        ///
+        /// The function returns:
+        /// - `Ok(delivery)` if the data was enqueued for sending.
+        /// - `Err(e)` if the stream encountered a [`stream::Error`](crate::stream::Error).
+        ///
+        /// # Cancel safety
+        ///
+        /// This method is not cancel safe, for the same reasons as [`send`](Self::send). A
+        /// cancel safe alternative is to enqueue the data with [`send_data`](Self::send_data) and
+        /// call [`track_delivery`](Self::track_delivery).
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # async fn test() -> s2n_quic::stream::Result<()> {
+        /// #   let mut stream: s2n_quic::stream::SendStream = todo!();
+        /// #
+        /// let delivery = stream.send_with_ack(bytes::Bytes::from_static(&[1, 2, 3])).await?;
+        /// stream.send(bytes::Bytes::from_static(&[4, 5, 6])).await?;
+        /// delivery.await?;
+        /// // at this point, the peer has received `[1, 2, 3]`
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub async fn send_with_ack(
+            &mut self,
+            data: bytes::Bytes,
+        ) -> $crate::stream::Result<$crate::stream::Delivery> {
+            self.send(data).await?;
+            self.track_delivery()
+        }
+
+        /// Returns a [`Delivery`](crate::stream::Delivery) which resolves once the peer has
+        /// acknowledged all of the data which is currently enqueued on the stream.
+        ///
+        /// # Return value
+        ///
+        /// The function returns:
+        /// - `Ok(delivery)` if the delivery is being tracked.
+        /// - `Err(e)` if the stream encountered a [`stream::Error`](crate::stream::Error).
+        #[inline]
+        pub fn track_delivery(&mut self) -> $crate::stream::Result<$crate::stream::Delivery> {
+            $crate::stream::Delivery::register(|delivery| self.notify_delivery(delivery))
+        }
+
+        // Registers the `delivery` with the underlying stream for `track_delivery`
+        #[inline]
+        #[doc(hidden)]
+        pub fn notify_delivery(
+            &mut self,
+            delivery: s2n_quic_core::stream::ops::tx::Delivery,
+        ) -> $crate::stream::Result<()> {
+            macro_rules! $dispatch {
+                () => {
+                    Err($crate::stream::Error::non_writable())
+                };
+                ($variant: expr) => {
+                    $variant.notify_delivery(delivery)
+                };
+            }
+
+            let $stream = self;
+            $dispatch_body
+        }
+
         /// Marks the stream as finished.
         ///
         /// This method returns immediately without notifying the caller that all of the outstanding
@@ -296,6 +615,11 @@ macro_rules! impl_send_stream_api {
         ///   the peer.
         /// - `Err(e)` if the stream encountered a [`stream::Error`](crate::stream::Error).
         ///
+        /// # Cancel safety
+        ///
+        /// This method is cancel safe. The stream is finished on the first poll, so dropping the
+        /// future only stops waiting for the acknowledgements.
+        ///
         /// # Examples
         ///
         /// ```rust,no_run
@@ -376,6 +700,55 @@ macro_rules! impl_send_stream_api {
             let $stream = self;
             $dispatch_body
         }
+
+        /// Resets the stream with the `error_code` once any of its data is lost, instead of
+        /// retransmitting the lost data.
+        ///
+        /// This is useful for real-time data, such as media frames, which is useless if it
+        /// arrives late. Since QUIC streams are delivered reliably and in order, the rest of the
+        /// stream is skipped: the peer is notified with a `RESET_STREAM` frame and the application
+        /// should continue on a new stream. Sending data on a stream which was reset returns an
+        /// error containing the `error_code`.
+        ///
+        /// # Return value
+        ///
+        /// The function returns:
+        /// - `Ok(())` if the policy was applied to the stream.
+        /// - `Err(e)` if the stream encountered a [`stream::Error`](crate::stream::Error).
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # async fn test() -> s2n_quic::stream::Result<()> {
+        /// #   let mut connection: s2n_quic::connection::Connection = todo!();
+        /// #   let frame = bytes::Bytes::new();
+        /// #
+        /// // send each frame on its own stream so lost frames don't delay the following ones
+        /// let mut stream = connection.open_send_stream().await?;
+        /// stream.reset_on_loss(1u8.into())?;
+        /// stream.send(frame).await?;
+        /// stream.finish()?;
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub fn reset_on_loss(
+            &mut self,
+            error_code: $crate::application::Error,
+        ) -> $crate::stream::Result<()> {
+            macro_rules! $dispatch {
+                () => {
+                    Err($crate::stream::Error::non_writable())
+                };
+                ($variant: expr) => {
+                    $variant.reset_on_loss(error_code)
+                };
+            }
+
+            let $stream = self;
+            $dispatch_body
+        }
     };
 }
 
@@ -8,12 +8,17 @@ use crate::{
         io::testing::{rand, spawn, test, time::delay, Model},
         packet_interceptor::Loss,
     },
-    Server,
+    Client, Server,
 };
 use std::time::Duration;
 
+mod extension_frame;
 mod setup;
+
+#[cfg(feature = "provider-tls-rustls")]
+mod rustls;
 use bytes::Bytes;
+use s2n_quic_core::crypto::tls::testing::certificates;
 use s2n_quic_platform::io::testing::primary;
 use setup::*;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -41,6 +46,66 @@ fn blackhole(model: Model, blackhole_duration: Duration) {
     .unwrap();
 }
 
+/// Closes the client connection as soon as the handshake completes, which causes the server to
+/// receive the end of the client's handshake and the close in the same run of datagrams
+#[test]
+fn close_after_handshake_test() {
+    test(Model::default(), |handle| {
+        let mut server = build_server(handle)?;
+        let addr = server.local_addr()?;
+
+        primary::spawn(async move {
+            let connection = server.accept().await;
+            assert!(connection.is_some());
+        });
+
+        let client = build_client(handle)?;
+        primary::spawn(async move {
+            let connect = Connect::new(addr).with_server_name("localhost");
+            let connection = client.connect(connect).await.unwrap();
+            drop(connection);
+        });
+
+        Ok(addr)
+    })
+    .unwrap();
+}
+
+#[test]
+fn accept_with_info_test() {
+    test(Model::default(), |handle| {
+        let mut server = build_server(handle)?;
+        let addr = server.local_addr()?;
+
+        primary::spawn(async move {
+            let (connection, info) = server.accept_with_info().await.unwrap();
+
+            assert_eq!(info.server_name().map(|name| &**name), Some("localhost"));
+            assert_eq!(
+                info.application_protocol(),
+                &connection.application_protocol().unwrap()
+            );
+            let remote_addr: std::net::SocketAddr = info.remote_address().into();
+            assert_eq!(remote_addr, connection.remote_addr().unwrap());
+            assert!(info.token().is_none());
+            assert!(!info.is_resumed());
+            // the handshake takes at least a round trip on the simulated network
+            assert!(info.handshake_duration().unwrap() > Duration::ZERO);
+        });
+
+        let client = build_client(handle)?;
+        primary::spawn(async move {
+            let connect = Connect::new(addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            // keep the connection open until the server accepted it
+            connection.accept().await.ok();
+        });
+
+        Ok(addr)
+    })
+    .unwrap();
+}
+
 #[test]
 fn blackhole_success_test() {
     let model = Model::default();
@@ -291,3 +356,3905 @@ fn local_stream_open_notify_test() {
     })
     .unwrap();
 }
+
+/// Runs an exchange between endpoints with seeded random providers and returns the traces of the
+/// server and the client
+fn seeded_random(server_seed: u64, client_seed: u64) -> (Vec<String>, Vec<String>) {
+    use provider::{
+        event::{
+            events::{ConnectionIdUpdated, ConnectionStarted, PacketSent},
+            ConnectionInfo, ConnectionMeta, Subscriber,
+        },
+        random::{self, seeded::Generator as Seeded},
+    };
+    use std::sync::{Arc, Mutex};
+
+    /// Records the connection IDs, packet numbers and random values of an endpoint
+    #[derive(Clone, Default)]
+    struct Trace(Arc<Mutex<Vec<String>>>);
+
+    impl Trace {
+        fn push(&self, entry: String) {
+            self.0.lock().unwrap().push(entry);
+        }
+
+        fn take(&self) -> Vec<String> {
+            core::mem::take(&mut *self.0.lock().unwrap())
+        }
+    }
+
+    impl Subscriber for Trace {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+
+        fn on_connection_started(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            event: &ConnectionStarted,
+        ) {
+            self.push(format!(
+                "started local_cid={:?} remote_cid={:?}",
+                event.path.local_cid.bytes, event.path.remote_cid.bytes
+            ));
+        }
+
+        fn on_connection_id_updated(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            event: &ConnectionIdUpdated,
+        ) {
+            self.push(format!(
+                "cid_updated {:?} {:?}",
+                event.cid_consumer, event.current.bytes
+            ));
+        }
+
+        fn on_packet_sent(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            event: &PacketSent,
+        ) {
+            self.push(format!("packet_sent {:?}", event.packet_header));
+        }
+    }
+
+    /// A seeded random provider which records the values it generates
+    struct TracedRandom {
+        generator: Seeded,
+        trace: Trace,
+    }
+
+    impl random::Provider for TracedRandom {
+        type Generator = Self;
+        type Error = core::convert::Infallible;
+
+        fn start(self) -> Result<Self::Generator, Self::Error> {
+            Ok(self)
+        }
+    }
+
+    impl random::Generator for TracedRandom {
+        fn public_random_fill(&mut self, dest: &mut [u8]) {
+            self.generator.public_random_fill(dest);
+            self.trace.push(format!("public_random {:?}", dest));
+        }
+
+        fn private_random_fill(&mut self, dest: &mut [u8]) {
+            self.generator.private_random_fill(dest);
+            self.trace.push(format!("private_random {:?}", dest));
+        }
+    }
+
+    let server_trace = Trace::default();
+    let client_trace = Trace::default();
+
+    let model = Model::default();
+    test(model, |handle| {
+        let server = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(server_trace.clone())?
+                .with_random(TracedRandom {
+                    generator: Seeded::new(server_seed),
+                    trace: server_trace.clone(),
+                })?
+                .start()?)
+        })?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(client_trace.clone())?
+            .with_random(TracedRandom {
+                generator: Seeded::new(client_seed),
+                trace: client_trace.clone(),
+            })?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+
+            stream.send(Bytes::from_static(&[42; 100])).await.unwrap();
+            stream.finish().unwrap();
+
+            let mut recv_len = 0;
+            while let Some(chunk) = stream.receive().await.unwrap() {
+                recv_len += chunk.len();
+            }
+            assert_eq!(recv_len, 100);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+
+    (server_trace.take(), client_trace.take())
+}
+
+/// Ensures endpoints configured with seeded random providers are able to communicate and
+/// reproduce the same connection IDs, packet numbers and random values for the same seeds
+#[test]
+fn seeded_random_test() {
+    let (server, client) = seeded_random(123, 456);
+
+    for trace in [&server, &client] {
+        assert!(trace.iter().any(|entry| entry.starts_with("started")));
+        assert!(trace.iter().any(|entry| entry.starts_with("packet_sent")));
+        assert!(trace.iter().any(|entry| entry.starts_with("public_random")));
+    }
+
+    let (server_replay, client_replay) = seeded_random(123, 456);
+    assert_eq!(server, server_replay);
+    assert_eq!(client, client_replay);
+
+    let (other_server, _) = seeded_random(789, 456);
+    assert_ne!(server, other_server);
+}
+
+/// Ensures the application is notified when a connection is closed by the idle timer
+#[test]
+fn idle_timeout_test() {
+    let model = Model::default();
+    test(model, |handle| {
+        let server = server(handle)?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(events())?
+            .with_limits(
+                provider::limits::Limits::default()
+                    .with_max_idle_timeout(Duration::from_secs(5))
+                    .unwrap(),
+            )?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            // the keep alive is disabled by default so the connection goes idle
+            let error = connection.accept_bidirectional_stream().await.unwrap_err();
+            assert!(
+                matches!(error, crate::connection::Error::IdleTimerExpired { .. }),
+                "{:?}",
+                error
+            );
+            // the peer responded so QUIC isn't blocked
+            assert_eq!(error.quic_blocked(), None);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures connections with the idle timeout disabled remain open while idle
+#[test]
+fn idle_timeout_disabled_test() {
+    let model = Model::default();
+    test(model, |handle| {
+        let limits = provider::limits::Limits::default()
+            .with_max_idle_timeout(Duration::from_secs(5))
+            .unwrap()
+            .with_idle_timeout_enabled(false)
+            .unwrap();
+
+        let server = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(events())?
+                .with_limits(limits)?
+                .start()?)
+        })?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(events())?
+            .with_limits(limits)?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+
+            // wait well past the configured idle timeout
+            delay(Duration::from_secs(60)).await;
+
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            stream.send(Bytes::from_static(&[42; 100])).await.unwrap();
+            stream.finish().unwrap();
+
+            let mut recv_len = 0;
+            while let Some(chunk) = stream.receive().await.unwrap() {
+                recv_len += chunk.len();
+            }
+            assert_eq!(recv_len, 100);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures the idle timeout can be disabled for individual connections
+#[test]
+fn idle_timeout_per_connection_test() {
+    /// Disables the idle timeout for the first connection only
+    #[derive(Default)]
+    struct Limiter {
+        connections: usize,
+    }
+
+    impl provider::limits::Limiter for Limiter {
+        fn on_connection(
+            &mut self,
+            _info: &provider::limits::ConnectionInfo,
+        ) -> provider::limits::Limits {
+            self.connections += 1;
+            provider::limits::Limits::default()
+                .with_max_idle_timeout(Duration::from_secs(5))
+                .unwrap()
+                .with_idle_timeout_enabled(self.connections > 1)
+                .unwrap()
+        }
+    }
+
+    let model = Model::default();
+    test(model, |handle| {
+        let server = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(events())?
+                .with_limits(Limiter::default())?
+                .start()?)
+        })?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(events())?
+            .with_limits(Limiter::default())?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut pinned = client.connect(connect.clone()).await.unwrap();
+            let mut idle = client.connect(connect).await.unwrap();
+
+            // wait well past the configured idle timeout
+            delay(Duration::from_secs(60)).await;
+
+            let error = idle.accept_bidirectional_stream().await.unwrap_err();
+            assert!(
+                matches!(error, crate::connection::Error::IdleTimerExpired { .. }),
+                "{:?}",
+                error
+            );
+
+            let mut stream = pinned.open_bidirectional_stream().await.unwrap();
+            stream.send(Bytes::from_static(&[42; 100])).await.unwrap();
+            stream.finish().unwrap();
+
+            let mut recv_len = 0;
+            while let Some(chunk) = stream.receive().await.unwrap() {
+                recv_len += chunk.len();
+            }
+            assert_eq!(recv_len, 100);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures streams opened with data are delivered and finished
+#[test]
+fn open_stream_with_data_test() {
+    let model = Model::default();
+    test(model, |handle| {
+        let server = server(handle)?;
+        let client = build_client(handle)?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+
+            let mut stream = connection
+                .open_send_stream_with(Bytes::from_static(&[42; 100]))
+                .await
+                .unwrap();
+            // the stream has already been finished
+            assert!(stream.send(Bytes::from_static(&[42])).await.is_err());
+
+            // the server echoes the request back and finishes the stream
+            let mut stream = connection
+                .send_request(Bytes::from_static(&[42; 100]))
+                .await
+                .unwrap();
+
+            let mut recv_len = 0;
+            while let Some(chunk) = stream.receive().await.unwrap() {
+                recv_len += chunk.len();
+            }
+            assert_eq!(recv_len, 100);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures all of the available streams are accepted in a single call
+#[test]
+fn accept_many_test() {
+    let model = Model::default();
+    test(model, |handle| {
+        const STREAMS: usize = 10;
+        const LEN: usize = 100;
+
+        let mut server = build_server(handle)?;
+        let server_addr = server.local_addr()?;
+
+        spawn(async move {
+            let mut connection = server.accept().await.unwrap();
+            let mut streams = vec![];
+            let mut batches = 0;
+
+            while streams.len() < STREAMS {
+                let count = connection.accept_many(&mut streams).await.unwrap().unwrap();
+                assert!(count > 0);
+                batches += 1;
+            }
+
+            assert_eq!(streams.len(), STREAMS);
+            // the client opens all of the streams at once so they should be accepted in fewer
+            // calls than there are streams
+            assert!(batches < STREAMS);
+
+            for mut stream in streams {
+                let mut recv_len = 0;
+                while let Some(chunk) = stream.receive().await.unwrap() {
+                    recv_len += chunk.len();
+                }
+                assert_eq!(recv_len, LEN);
+            }
+        });
+
+        let client = build_client(handle)?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server_addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+
+            let mut streams = vec![];
+            for _ in 0..STREAMS {
+                let stream = connection
+                    .open_send_stream_with(Bytes::from_static(&[42; LEN]))
+                    .await
+                    .unwrap();
+                streams.push(stream);
+            }
+
+            for mut stream in streams {
+                stream.close().await.unwrap();
+            }
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures no data is lost when the readiness futures of a stream are dropped by a `select`
+#[test]
+fn cancelled_readiness_test() {
+    use futures::future::{select, Either};
+
+    let model = Model::default();
+    test(model, |handle| {
+        const LEN: usize = 100_000;
+        const CHUNK_LEN: usize = 1000;
+
+        let data: Vec<u8> = (0..LEN).map(|i| i as u8).collect();
+        let expected = data.clone();
+
+        let mut server = build_server(handle)?;
+        let server_addr = server.local_addr()?;
+
+        spawn(async move {
+            let mut connection = server.accept().await.unwrap();
+            let mut stream = connection.open_send_stream().await.unwrap();
+
+            for chunk in data.chunks(CHUNK_LEN) {
+                // the chunk is owned by the task until the stream is ready to accept it
+                loop {
+                    let ready = stream.send_ready();
+                    let timeout = delay(Duration::from_micros(100));
+                    futures::pin_mut!(ready, timeout);
+
+                    if let Either::Left((ready, _)) = select(ready, timeout).await {
+                        ready.unwrap();
+                        break;
+                    }
+                }
+                stream.send_data(Bytes::copy_from_slice(chunk)).unwrap();
+            }
+
+            stream.close().await.unwrap();
+        });
+
+        let client = build_client(handle)?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server_addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection.accept_receive_stream().await.unwrap().unwrap();
+
+            let mut received = vec![];
+            let mut cancelled = 0;
+
+            'receive: loop {
+                {
+                    let ready = stream.receive_ready();
+                    let timeout = delay(Duration::from_micros(100));
+                    futures::pin_mut!(ready, timeout);
+
+                    match select(ready, timeout).await {
+                        Either::Left((ready, _)) => {
+                            ready.unwrap();
+                        }
+                        Either::Right(_) => {
+                            cancelled += 1;
+                            continue;
+                        }
+                    }
+                }
+
+                // drain all of the data which is available without waiting
+                loop {
+                    match stream.receive_data() {
+                        Ok(Some(chunk)) => received.extend_from_slice(&chunk),
+                        Ok(None) => break 'receive,
+                        Err(crate::stream::Error::ReceivingBlocked { .. }) => break,
+                        Err(error) => panic!("unexpected error: {}", error),
+                    }
+                }
+            }
+
+            assert!(cancelled > 0);
+            assert_eq!(received, expected);
+
+            // the stream keeps reporting the end of the data
+            assert_eq!(stream.receive_ready().await.unwrap(), 0);
+            assert!(stream.receive_data().unwrap().is_none());
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures endpoints configured with a fixed UDP payload size are able to communicate on a
+/// network that drops larger datagrams
+#[test]
+fn fixed_udp_payload_test() {
+    let model = Model::default();
+    model.set_max_udp_payload(1350);
+    test(model, |handle| {
+        let limits = provider::limits::Limits::default()
+            .with_fixed_udp_payload(1350)
+            .unwrap();
+
+        let server = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(events())?
+                .with_limits(limits)?
+                .start()?)
+        })?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(events())?
+            .with_limits(limits)?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection
+                .send_request(Bytes::from_static(&[42; 10_000]))
+                .await
+                .unwrap();
+
+            let mut recv_len = 0;
+            while let Some(chunk) = stream.receive().await.unwrap() {
+                recv_len += chunk.len();
+            }
+            assert_eq!(recv_len, 10_000);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn mtu_provider_test() {
+    use provider::mtu::{Config, Endpoint, PathInfo};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(Clone)]
+    struct Subnet {
+        config: Config,
+        paths: Arc<AtomicUsize>,
+    }
+
+    impl Endpoint for Subnet {
+        fn on_path(&mut self, _info: &PathInfo) -> Option<Config> {
+            self.paths.fetch_add(1, Ordering::Relaxed);
+            Some(self.config)
+        }
+    }
+
+    let model = Model::default();
+    model.set_max_udp_payload(1350);
+
+    // fix the MTU to the largest size the model allows over IPv4
+    let config = Config::builder()
+        .with_max_mtu(1378)
+        .unwrap()
+        .with_initial_mtu(1378)
+        .unwrap()
+        .with_base_mtu(1378)
+        .unwrap()
+        .build()
+        .unwrap();
+    let mtu = Subnet {
+        config,
+        paths: Default::default(),
+    };
+    let paths = mtu.paths.clone();
+
+    test(model, |handle| {
+        let server = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(events())?
+                .with_mtu(mtu.clone())?
+                .start()?)
+        })?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(events())?
+            .with_mtu(mtu)?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection
+                .send_request(Bytes::from_static(&[42; 10_000]))
+                .await
+                .unwrap();
+
+            let mut recv_len = 0;
+            while let Some(chunk) = stream.receive().await.unwrap() {
+                recv_len += chunk.len();
+            }
+            assert_eq!(recv_len, 10_000);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+
+    // one path for each endpoint
+    assert_eq!(paths.load(Ordering::Relaxed), 2);
+}
+
+/// Ensures stream data is only exposed to subscribers that request access
+#[test]
+fn stream_data_access_test() {
+    use provider::event::{events::StreamDataReceived, ConnectionInfo, ConnectionMeta, Subscriber};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    const LEN: usize = 10_000;
+
+    #[derive(Clone, Default)]
+    struct Scanner {
+        access: bool,
+        received: Arc<AtomicUsize>,
+        scanned: Arc<AtomicUsize>,
+    }
+
+    impl Subscriber for Scanner {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+
+        fn stream_data_access(&self) -> bool {
+            self.access
+        }
+
+        fn on_stream_data_received(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            event: &StreamDataReceived,
+        ) {
+            self.received.fetch_add(event.len, Ordering::Relaxed);
+
+            if let Some(data) = event.data {
+                assert_eq!(data.len(), event.len);
+                assert!(data.iter().all(|byte| *byte == 42));
+                self.scanned.fetch_add(data.len(), Ordering::Relaxed);
+            }
+        }
+    }
+
+    let server_scanner = Scanner {
+        access: true,
+        ..Default::default()
+    };
+    let client_scanner = Scanner::default();
+
+    let model = Model::default();
+    test(model, |handle| {
+        let server = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(server_scanner.clone())?
+                .start()?)
+        })?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(client_scanner.clone())?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection
+                .send_request(Bytes::from_static(&[42; LEN]))
+                .await
+                .unwrap();
+
+            let mut recv_len = 0;
+            while let Some(chunk) = stream.receive().await.unwrap() {
+                recv_len += chunk.len();
+            }
+            assert_eq!(recv_len, LEN);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+
+    // the server scanned everything it received
+    let received = server_scanner.received.load(Ordering::Relaxed);
+    assert!(received >= LEN);
+    assert_eq!(server_scanner.scanned.load(Ordering::Relaxed), received);
+
+    // the client observed the boundaries without the data
+    assert!(client_scanner.received.load(Ordering::Relaxed) >= LEN);
+    assert_eq!(client_scanner.scanned.load(Ordering::Relaxed), 0);
+}
+
+fn null_cipher_client(
+    handle: &provider::io::testing::Handle,
+    server: std::net::SocketAddr,
+) -> provider::io::testing::Result {
+    use provider::tls::dangerous::NullCipher;
+
+    let client = Client::builder()
+        .with_io(handle.builder().build()?)?
+        .with_tls(NullCipher::new(certificates::CERT_PEM))?
+        .with_event(events())?
+        .start()?;
+
+    primary::spawn(async move {
+        let connect = Connect::new(server).with_server_name("localhost");
+        let mut connection = client.connect(connect).await.unwrap();
+        let mut stream = connection
+            .send_request(Bytes::from_static(&[42; 10_000]))
+            .await
+            .unwrap();
+
+        let mut recv_len = 0;
+        while let Some(chunk) = stream.receive().await.unwrap() {
+            recv_len += chunk.len();
+        }
+        assert_eq!(recv_len, 10_000);
+    });
+
+    Ok(())
+}
+
+#[test]
+fn null_cipher_test() {
+    use provider::tls::dangerous::NullCipher;
+
+    let model = Model::default();
+    test(model, |handle| {
+        let server = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(NullCipher::new(SERVER_CERTS))?
+                .with_event(events())?
+                .start()?)
+        })?;
+
+        null_cipher_client(handle, server)
+    })
+    .unwrap();
+}
+
+/// Ensures a null cipher endpoint can't connect to a peer with packet protection
+#[test]
+#[should_panic]
+fn null_cipher_mismatch_test() {
+    let model = Model::default();
+    test(model, |handle| {
+        let server = server(handle)?;
+        null_cipher_client(handle, server)
+    })
+    .unwrap();
+}
+
+/// Runs a client against a server which injects faults into the datagrams it receives
+///
+/// Returns the total runtime of the test
+fn fault_injection<S: provider::io::fault::Schedule>(schedule: S) -> Duration {
+    use provider::io::fault;
+
+    let model = Model::default();
+    test(model, |handle| {
+        let server = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(fault::Provider::new(io, schedule)?)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(events())?
+                .start()?)
+        })?;
+
+        client(handle, server)
+    })
+    .unwrap()
+}
+
+#[test]
+fn fault_script_test() {
+    use provider::io::fault::{Fault, Script};
+
+    fault_injection(
+        Script::new()
+            // drop the first client initial and its retransmission
+            .range(0..2, Fault::Drop)
+            .at(2, Fault::Duplicate)
+            .at(3, Fault::Reorder)
+            .every(5, Fault::Corrupt)
+            .with_latency(Duration::from_millis(10)),
+    );
+}
+
+#[test]
+fn fault_latency_test() {
+    use provider::io::fault::Script;
+
+    let latency = Duration::from_millis(100);
+    let baseline = fault_injection(Script::new());
+    let delayed = fault_injection(Script::new().with_latency(latency));
+
+    // the handshake takes at least a round trip so the latency is added at least once
+    assert!(
+        delayed >= baseline + latency,
+        "{:?} >= {:?}",
+        delayed,
+        baseline + latency
+    );
+}
+
+#[test]
+#[should_panic]
+fn fault_blackhole_test() {
+    use provider::io::fault::{Datagram, Fault};
+
+    fault_injection(|_datagram: &Datagram| Fault::Drop);
+}
+
+/// Records the datagrams and secrets of a connection and replays them into a new server
+#[cfg(unix)]
+#[test]
+fn replay_test() {
+    use provider::{
+        io::{
+            fault::{Datagram, Fault},
+            replay,
+        },
+        protocol_violation::Outcome,
+        tls::{self, replay::KeyLog},
+    };
+    use s2n_quic_core::stream::testing::Data;
+    use std::{
+        fs::File,
+        io::BufReader,
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    };
+
+    let key_log_path =
+        std::env::temp_dir().join(format!("s2n-quic-replay-test-{}.log", std::process::id()));
+    let _ = std::fs::remove_file(&key_log_path);
+
+    let server_address: SocketAddr = "192.0.2.1:443".parse().unwrap();
+    let recorded = Arc::new(Mutex::new(vec![]));
+
+    test(Model::default(), |handle| {
+        let recorded = recorded.clone();
+        let mut start = None;
+
+        // the fault injection provider observes each datagram received by the server
+        let record = move |datagram: &Datagram| {
+            let start = *start.get_or_insert(datagram.timestamp);
+            recorded.lock().unwrap().push(replay::Datagram {
+                timestamp: datagram.timestamp.saturating_duration_since(start),
+                source: datagram.remote_address.into(),
+                destination: server_address,
+                payload: datagram.payload.to_vec(),
+            });
+            Fault::Pass
+        };
+
+        let server = server_with(handle, |io| {
+            std::env::set_var("SSLKEYLOGFILE", &key_log_path);
+            let tls = tls::default::Server::builder()
+                .with_certificate(SERVER_CERTS.0, SERVER_CERTS.1)?
+                .with_key_logging()?
+                .build();
+            std::env::remove_var("SSLKEYLOGFILE");
+
+            Ok(Server::builder()
+                .with_io(provider::io::fault::Provider::new(io, record)?)?
+                .with_tls(tls?)?
+                .with_event(events())?
+                .start()?)
+        })?;
+
+        client(handle, server)
+    })
+    .unwrap();
+
+    let capture: replay::Capture = recorded.lock().unwrap().drain(..).collect();
+    let key_log = KeyLog::read(BufReader::new(File::open(&key_log_path).unwrap())).unwrap();
+    let _ = std::fs::remove_file(&key_log_path);
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async move {
+        let io = replay::Io::builder().with_capture(capture).build().unwrap();
+        let mut server = Server::builder()
+            .with_connection_id(io.connection_ids())
+            .unwrap()
+            .with_io(io)
+            .unwrap()
+            .with_tls(key_log)
+            .unwrap()
+            .with_protocol_violation(Outcome::Ignore)
+            .unwrap()
+            .with_event(events())
+            .unwrap()
+            .start()
+            .unwrap();
+
+        // the replayed server receives the same stream data as the recorded server
+        let replay = async move {
+            let mut connection = server.accept().await.unwrap();
+            let mut stream = connection
+                .accept_bidirectional_stream()
+                .await
+                .unwrap()
+                .unwrap();
+
+            let mut data = Data::new(10_000);
+            while let Some(chunk) = stream.receive().await.unwrap() {
+                data.receive(&[chunk]);
+            }
+            assert!(data.is_finished());
+        };
+
+        tokio::time::timeout(Duration::from_secs(30), replay)
+            .await
+            .expect("the replay should complete");
+    });
+}
+
+#[test]
+fn probe_test() {
+    use crate::client::probe::{self, Mode, Probe};
+
+    let model = Model::default();
+    test(model, |handle| {
+        let server = server(handle)?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(probe::Subscriber::default())?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let report = client.probe(connect.clone()).await.unwrap();
+
+            let complete = report.handshake_complete.unwrap();
+            let confirmed = report.handshake_confirmed.unwrap();
+            assert!(report.first_packet_received.unwrap() <= complete);
+            assert!(complete <= confirmed);
+            assert!(report.smoothed_rtt.is_some());
+            assert!(report.cipher_suite.is_some());
+            assert!(report.application_protocol.is_some());
+            assert_eq!(report.server_name.as_deref(), Some("localhost"));
+            let transport_parameters = report.transport_parameters.unwrap();
+            assert!(transport_parameters.initial_source_connection_id.is_some());
+            assert!(transport_parameters
+                .original_destination_connection_id
+                .is_some());
+
+            // aborting after the handshake completes doesn't wait for HANDSHAKE_DONE
+            let probe = Probe::new(connect).with_mode(Mode::Abort);
+            let report = client.probe(probe).await.unwrap();
+            assert!(report.handshake_complete.is_some());
+            assert!(report.handshake_confirmed.is_none());
+            assert!(report.transport_parameters.is_some());
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures connection attempts raced across multiple addresses complete with the first
+/// reachable address and close the connections to the other addresses
+#[test]
+fn connect_race_test() {
+    use provider::event::{events::ConnectionClosed, ConnectionInfo, ConnectionMeta, Subscriber};
+    use std::{
+        net::SocketAddr,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    #[derive(Clone, Default)]
+    struct Closed(Arc<AtomicUsize>);
+
+    impl Subscriber for Closed {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+
+        fn on_connection_closed(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            _event: &ConnectionClosed,
+        ) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let closed = Closed::default();
+
+    let model = Model::default();
+    test(model, |handle| {
+        let server = server(handle)?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(closed.clone())?
+            .start()?;
+
+        primary::spawn(async move {
+            // nothing is listening on this address
+            let unreachable: SocketAddr = "1.0.0.99:4433".parse().unwrap();
+            let connect = Connect::new(unreachable)
+                .with_addresses([server])
+                .with_server_name("localhost");
+
+            let mut connection = client.connect(connect).await.unwrap();
+            assert_eq!(connection.remote_addr().unwrap(), server);
+
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            stream.send(Bytes::from_static(b"hello")).await.unwrap();
+            assert_eq!(
+                stream.receive().await.unwrap().unwrap(),
+                Bytes::from_static(b"hello")
+            );
+
+            // the abandoned attempt is closed well before the handshake would time out
+            delay(Duration::from_secs(5)).await;
+            assert_eq!(closed.0.load(Ordering::Relaxed), 1);
+
+            // the attempt only fails if all of the addresses fail
+            let connect = Connect::new(unreachable)
+                .with_addresses([unreachable])
+                .with_server_name("localhost");
+            assert!(client.connect(connect).await.is_err());
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures the ACK strategy is applied to connections and advertised to the peer
+#[test]
+fn ack_strategy_test() {
+    use crate::client::probe;
+    use provider::ack::Strategy;
+
+    let model = Model::default();
+    test(model, |handle| {
+        let strategy = Strategy::builder()
+            .with_max_ack_delay(Duration::from_millis(5))?
+            .with_ack_eliciting_threshold(2)?
+            .build();
+
+        let server = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(events())?
+                .with_ack(strategy)?
+                .start()?)
+        })?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(probe::Subscriber::default())?
+            .with_ack(Strategy::HIGH_THROUGHPUT)?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let report = client.probe(connect.clone()).await.unwrap();
+            let transport_parameters = report.transport_parameters.unwrap();
+            assert_eq!(transport_parameters.max_ack_delay, Duration::from_millis(5));
+
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection
+                .send_request(Bytes::from_static(&[42; 100_000]))
+                .await
+                .unwrap();
+
+            let mut recv_len = 0;
+            while let Some(chunk) = stream.receive().await.unwrap() {
+                recv_len += chunk.len();
+            }
+            assert_eq!(recv_len, 100_000);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures probes fail when the client isn't configured with a probe subscriber
+#[test]
+fn probe_without_subscriber_test() {
+    use crate::client::probe;
+
+    let model = Model::default();
+    test(model, |handle| {
+        let server = server(handle)?;
+        let client = build_client(handle)?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let error = client.probe(connect).await.unwrap_err();
+            assert!(matches!(error, probe::Error::Query(_)));
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures a draining server rejects new connections and closes the remaining ones once the
+/// deadline expires
+#[test]
+fn drain_test() {
+    use crate::{application, server::Drain};
+    use provider::event::{events, ConnectionInfo, ConnectionMeta, Subscriber};
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    };
+
+    #[derive(Clone, Default)]
+    struct Progress(Arc<Mutex<Vec<(&'static str, usize)>>>);
+
+    impl Progress {
+        fn push(&self, event: &'static str, connections: usize) {
+            self.0.lock().unwrap().push((event, connections));
+        }
+    }
+
+    impl Subscriber for Progress {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+
+        fn on_endpoint_draining_started(
+            &mut self,
+            _meta: &events::EndpointMeta,
+            event: &events::EndpointDrainingStarted,
+        ) {
+            self.push("started", event.active_connections);
+        }
+
+        fn on_endpoint_draining_progress(
+            &mut self,
+            _meta: &events::EndpointMeta,
+            event: &events::EndpointDrainingProgress,
+        ) {
+            self.push("progress", event.remaining_connections);
+        }
+
+        fn on_endpoint_draining_deadline_exceeded(
+            &mut self,
+            _meta: &events::EndpointMeta,
+            event: &events::EndpointDrainingDeadlineExceeded,
+        ) {
+            self.push("deadline", event.remaining_connections);
+        }
+    }
+
+    let progress = Progress::default();
+    let drained = Arc::new(AtomicBool::new(false));
+    let error = application::Error::new(42).unwrap();
+
+    let model = Model::default();
+    test(model, |handle| {
+        let mut server = Server::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(SERVER_CERTS)?
+            .with_event(progress.clone())?
+            .start()?;
+        let server_addr = server.local_addr()?;
+        let mut drainer = server.drainer();
+
+        let accepted = drained.clone();
+        spawn(async move {
+            let mut connections = vec![];
+            while let Some(connection) = server.accept().await {
+                connections.push(connection);
+            }
+            // the server stops accepting once it's drained
+            accepted.store(true, Ordering::Relaxed);
+        });
+
+        let client = build_client(handle)?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server_addr).with_server_name("localhost");
+            let closed = client.connect(connect.clone()).await.unwrap();
+            let mut open = client.connect(connect.clone()).await.unwrap();
+
+            primary::spawn(async move {
+                let drain = Drain::new()
+                    .with_deadline(Duration::from_secs(5))
+                    .with_error(error);
+                drainer.drain(drain).await.unwrap();
+            });
+
+            delay(Duration::from_secs(1)).await;
+            closed.close(application::Error::UNKNOWN);
+
+            // new connections are rejected while draining
+            primary::spawn(async move {
+                assert!(client.connect(connect).await.is_err());
+            });
+
+            // the remaining connection is closed by the server at the deadline
+            let err = open.accept_bidirectional_stream().await.unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    crate::connection::Error::Application { error: code, .. } if code == error
+                ),
+                "{:?}",
+                err
+            );
+        });
+
+        Ok(())
+    })
+    .unwrap();
+
+    assert!(drained.load(Ordering::Relaxed));
+    assert_eq!(
+        *progress.0.lock().unwrap(),
+        [
+            ("started", 2),
+            ("progress", 1),
+            ("deadline", 1),
+            ("progress", 0)
+        ]
+    );
+}
+
+/// Ensures the connection arenas reuse their allocations across streams
+#[test]
+fn arena_stats_test() {
+    let model = Model::default();
+    test(model, |handle| {
+        const STREAMS: u64 = 5;
+        const LEN: usize = 10_000;
+
+        let server = server(handle)?;
+        let client = build_client(handle)?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+
+            for _ in 0..STREAMS {
+                let mut stream = connection
+                    .send_request(Bytes::from_static(&[42; LEN]))
+                    .await
+                    .unwrap();
+
+                let mut recv_len = 0;
+                while let Some(chunk) = stream.receive().await.unwrap() {
+                    recv_len += chunk.len();
+                }
+                assert_eq!(recv_len, LEN);
+
+                // give the stream time to be finalized
+                drop(stream);
+                delay(Duration::from_millis(100)).await;
+            }
+
+            let stats = connection.arena_stats().unwrap();
+
+            // the received data is allocated from shared chunks
+            assert!(stats.frames.allocations > 0);
+            assert!(stats.frames.heap_allocations < stats.frames.allocations);
+
+            // the streams reuse the state of the previously finalized streams
+            assert_eq!(stats.streams.allocations, STREAMS);
+            assert!(stats.streams.heap_allocations < STREAMS);
+
+            let total = stats.total();
+            assert!(total.heap_bytes > 0);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures the memory usage of a connection reflects the data it buffers
+#[test]
+fn memory_usage_test() {
+    let model = Model::default();
+    test(model, |handle| {
+        const LEN: usize = 10_000;
+
+        let server = server(handle)?;
+        let client = build_client(handle)?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+
+            let usage = connection.memory_usage().unwrap();
+            assert!(usage.crypto > 0);
+            assert!(usage.sent_packets > 0);
+            assert_eq!(usage.receive_buffers, 0);
+
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            stream.send(Bytes::from_static(&[42; LEN])).await.unwrap();
+            stream.finish().unwrap();
+
+            // the echoed data is buffered until the application reads it
+            delay(Duration::from_millis(500)).await;
+            let buffered = connection.memory_usage().unwrap();
+            assert!(buffered.receive_buffers >= LEN);
+            assert_eq!(
+                buffered.total(),
+                buffered.send_buffers
+                    + buffered.receive_buffers
+                    + buffered.sent_packets
+                    + buffered.crypto
+            );
+
+            let mut recv_len = 0;
+            while let Some(chunk) = stream.receive().await.unwrap() {
+                recv_len += chunk.len();
+            }
+            assert_eq!(recv_len, LEN);
+
+            let usage = connection.memory_usage().unwrap();
+            assert!(usage.receive_buffers < buffered.receive_buffers);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures the phases of the handshake are recorded in the order they are reached
+#[test]
+fn handshake_timing_test() {
+    let model = Model::default();
+    model.set_delay(Duration::from_millis(50));
+    test(model, |handle| {
+        let server = server(handle)?;
+        let client = build_client(handle)?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            stream.send(Bytes::from_static(&[42; 100])).await.unwrap();
+            stream.finish().unwrap();
+            while stream.receive().await.unwrap().is_some() {}
+
+            let timing = connection.handshake_timing().unwrap();
+            let initial_sent = timing.initial_sent.unwrap();
+            let initial_received = timing.initial_received.unwrap();
+            let handshake_received = timing.handshake_received.unwrap();
+            let handshake_sent = timing.handshake_sent.unwrap();
+            let handshake_confirmed = timing.handshake_confirmed.unwrap();
+            let application_data_received = timing.application_data_received.unwrap();
+
+            // a round trip takes 100ms, less the rounding of the simulated clock
+            let round_trip = Duration::from_millis(99);
+
+            // the server responds after a round trip
+            assert!(initial_received >= initial_sent + round_trip);
+            assert!(handshake_received >= initial_received);
+            assert!(handshake_sent >= handshake_received);
+            // the server confirms the handshake with a HANDSHAKE_DONE frame
+            assert!(handshake_confirmed >= handshake_sent + round_trip);
+            assert!(application_data_received >= handshake_sent);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures the correlation ID of a connection is attached to all of its events
+#[test]
+fn correlation_id_test() {
+    use crate::connection::CorrelationId;
+    use provider::event::{ConnectionInfo, ConnectionMeta, Event, Subscriber};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Correlated(Arc<Mutex<Vec<CorrelationId>>>);
+
+    impl Subscriber for Correlated {
+        type ConnectionContext = CorrelationId;
+
+        fn create_connection_context(
+            &mut self,
+            meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+            self.0.lock().unwrap().push(meta.correlation_id);
+            meta.correlation_id
+        }
+
+        fn on_connection_event<E: Event>(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            _event: &E,
+        ) {
+            assert_eq!(meta.correlation_id, *context);
+        }
+    }
+
+    let client_ids = Correlated::default();
+    let server_ids = Correlated::default();
+    let correlation_id = CorrelationId::from(0x4bf92f3577b34da6a3ce929d0e0e4736u128);
+
+    let model = Model::default();
+    test(model, |handle| {
+        let mut server = Server::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(SERVER_CERTS)?
+            .with_event(server_ids.clone())?
+            .start()?;
+        let server_addr = server.local_addr()?;
+
+        let server_id = server_ids.clone();
+        primary::spawn(async move {
+            let mut connection = server.accept().await.unwrap();
+            let id = connection.correlation_id().unwrap();
+            assert_eq!(*server_id.0.lock().unwrap(), [id]);
+            // the server generates its own random ID
+            assert_ne!(id, correlation_id);
+
+            // keep the connection open until the client closes it
+            let _ = connection.accept_bidirectional_stream().await;
+        });
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(client_ids.clone())?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server_addr)
+                .with_server_name("localhost")
+                .with_correlation_id(correlation_id);
+            let connection = client.connect(connect).await.unwrap();
+            assert_eq!(connection.correlation_id().unwrap(), correlation_id);
+            assert_eq!(*client_ids.0.lock().unwrap(), [correlation_id]);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures data sent on compressed streams is decompressed by the peer
+#[test]
+fn compressed_stream_test() {
+    let data: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+    let expected = Bytes::from(data.clone());
+
+    let model = Model::default();
+    test(model, |handle| {
+        let mut server = build_server(handle)?;
+        let server_addr = server.local_addr()?;
+
+        spawn(async move {
+            let mut connection = server.accept().await.unwrap();
+            let stream = connection
+                .accept_bidirectional_stream()
+                .await
+                .unwrap()
+                .unwrap();
+            let mut stream = stream.compressed(1);
+
+            // echo the decompressed data back once the client closes the stream
+            let mut received = vec![];
+            while let Some(chunk) = stream.receive().await.unwrap() {
+                received.extend_from_slice(&chunk);
+            }
+            stream.send(Bytes::from(received)).await.unwrap();
+            stream.close().await.unwrap();
+        });
+
+        let client = build_client(handle)?;
+        primary::spawn(async move {
+            let connect = Connect::new(server_addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let stream = connection.open_bidirectional_stream().await.unwrap();
+            let mut stream = stream.compressed(3);
+
+            for chunk in data.chunks(1000) {
+                stream.send(Bytes::copy_from_slice(chunk)).await.unwrap();
+            }
+            stream.close().await.unwrap();
+
+            let mut received = vec![];
+            while let Some(chunk) = stream.receive().await.unwrap() {
+                received.extend_from_slice(&chunk);
+            }
+            assert_eq!(received, expected);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures the application settings configured on the TLS providers are exchanged
+#[test]
+fn application_settings_test() {
+    use provider::tls;
+
+    let model = Model::default();
+    test(model, |handle| {
+        let mut server = Server::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(
+                tls::default::Server::builder()
+                    .with_certificate(SERVER_CERTS.0, SERVER_CERTS.1)?
+                    .with_application_settings(b"h3", b"server settings")?
+                    .build()?,
+            )?
+            .with_event(events())?
+            .start()?;
+        let server_addr = server.local_addr()?;
+
+        primary::spawn(async move {
+            let connection = server.accept().await.unwrap();
+            assert_eq!(
+                connection.peer_application_settings().unwrap().as_deref(),
+                Some(&b"client settings"[..])
+            );
+        });
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(
+                tls::default::Client::builder()
+                    .with_certificate(certificates::CERT_PEM)?
+                    .with_application_settings(b"h3", b"client settings")?
+                    .build()?,
+            )?
+            .with_event(events())?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server_addr).with_server_name("localhost");
+            let connection = client.connect(connect).await.unwrap();
+            assert_eq!(
+                connection.peer_application_settings().unwrap().as_deref(),
+                Some(&b"server settings"[..])
+            );
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures the state snapshots of a connection follow the stream state machines
+#[test]
+fn snapshot_test() {
+    use crate::connection::snapshot::{ReceiveState, SendState, State};
+
+    let model = Model::default();
+    test(model, |handle| {
+        const LEN: usize = 10_000;
+
+        let server = server(handle)?;
+        let client = build_client(handle)?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            let stream_id = stream.id();
+            stream.send(Bytes::from_static(&[42; LEN])).await.unwrap();
+
+            let snapshot = connection.snapshot().unwrap();
+            assert_eq!(snapshot.state, State::Active);
+            assert_eq!(snapshot.streams.len(), 1);
+            assert_eq!(snapshot.streams[0].id, stream_id);
+            assert_eq!(snapshot.streams[0].send, Some(SendState::Send));
+
+            stream.finish().unwrap();
+
+            // wait for the peer to acknowledge the data and to echo it back
+            delay(Duration::from_millis(500)).await;
+            let snapshot = connection.snapshot().unwrap();
+
+            // the Initial and Handshake spaces are discarded once the handshake is confirmed
+            assert!(snapshot.spaces.handshake_confirmed);
+            assert!(snapshot.spaces.initial.is_none());
+            assert!(snapshot.spaces.handshake.is_none());
+            let application = snapshot.spaces.application.unwrap();
+            assert!(application.next_packet_number > application.largest_acked_packet_number);
+            assert!(application.largest_received_packet_number.is_some());
+
+            assert!(snapshot.flow_control.sent_data >= LEN as u64);
+            assert!(snapshot.flow_control.received_data >= LEN as u64);
+
+            assert_eq!(snapshot.streams[0].send, Some(SendState::DataRecvd));
+            assert_eq!(snapshot.streams[0].receive, Some(ReceiveState::DataRecvd));
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Returns the number of datagrams sent by the server which carried more than one packet
+fn coalesced_datagrams(packet_coalescing_enabled: bool) -> usize {
+    use provider::event::{
+        events::{DatagramSent, PacketSent},
+        ConnectionInfo, ConnectionMeta, Subscriber,
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(Clone, Default)]
+    struct Coalescing {
+        coalesced: Arc<AtomicUsize>,
+    }
+
+    impl Subscriber for Coalescing {
+        /// The number of packets written to the current datagram
+        type ConnectionContext = usize;
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+            0
+        }
+
+        fn on_packet_sent(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            _event: &PacketSent,
+        ) {
+            *context += 1;
+        }
+
+        fn on_datagram_sent(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            _event: &DatagramSent,
+        ) {
+            if *context > 1 {
+                self.coalesced.fetch_add(1, Ordering::Relaxed);
+            }
+            *context = 0;
+        }
+    }
+
+    let subscriber = Coalescing::default();
+
+    let model = Model::default();
+    test(model, |handle| {
+        let limits = provider::limits::Limits::default()
+            .with_packet_coalescing_enabled(packet_coalescing_enabled)
+            .unwrap();
+
+        let server = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(subscriber.clone())?
+                .with_limits(limits)?
+                .start()?)
+        })?;
+
+        let client = build_client(handle)?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection
+                .send_request(Bytes::from_static(&[42; 10_000]))
+                .await
+                .unwrap();
+
+            let mut recv_len = 0;
+            while let Some(chunk) = stream.receive().await.unwrap() {
+                recv_len += chunk.len();
+            }
+            assert_eq!(recv_len, 10_000);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+
+    subscriber.coalesced.load(Ordering::Relaxed)
+}
+
+/// Ensures the server coalesces the packets of its handshake flights, unless disabled
+#[test]
+fn packet_coalescing_test() {
+    assert!(coalesced_datagrams(true) > 0);
+    assert_eq!(coalesced_datagrams(false), 0);
+}
+
+/// Returns the lengths of the datagrams carrying Initial packets which are sent by the client
+fn initial_datagram_lens(padding: provider::limits::InitialPadding) -> Vec<u16> {
+    use provider::{
+        event::{
+            events::{DatagramSent, PacketHeader, PacketSent},
+            ConnectionInfo, ConnectionMeta, Subscriber,
+        },
+        mtu::Config,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct InitialDatagrams {
+        lens: Arc<Mutex<Vec<u16>>>,
+    }
+
+    impl Subscriber for InitialDatagrams {
+        /// Set if an Initial packet was written to the current datagram
+        type ConnectionContext = bool;
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+            false
+        }
+
+        fn on_packet_sent(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            event: &PacketSent,
+        ) {
+            *context |= matches!(event.packet_header, PacketHeader::Initial { .. });
+        }
+
+        fn on_datagram_sent(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            event: &DatagramSent,
+        ) {
+            if core::mem::take(context) {
+                self.lens.lock().unwrap().push(event.len);
+            }
+        }
+    }
+
+    let subscriber = InitialDatagrams::default();
+
+    let model = Model::default();
+    model.set_max_udp_payload(1350);
+
+    // fix the MTU to the largest size the model allows over IPv4
+    let mtu = Config::builder()
+        .with_max_mtu(1378)
+        .unwrap()
+        .with_initial_mtu(1378)
+        .unwrap()
+        .with_base_mtu(1378)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    test(model, |handle| {
+        let server = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(events())?
+                .start()?)
+        })?;
+
+        let limits = provider::limits::Limits::default()
+            .with_initial_padding(padding)
+            .unwrap();
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(subscriber.clone())?
+            .with_mtu(mtu)?
+            .with_limits(limits)?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection
+                .send_request(Bytes::from_static(&[42; 100]))
+                .await
+                .unwrap();
+
+            while stream.receive().await.unwrap().is_some() {}
+        });
+
+        Ok(())
+    })
+    .unwrap();
+
+    let lens = subscriber.lens.lock().unwrap().clone();
+    assert!(!lens.is_empty());
+    lens
+}
+
+/// Ensures the client pads its Initial datagrams according to the configured strategy
+#[test]
+fn initial_padding_test() {
+    use provider::limits::InitialPadding;
+
+    for len in initial_datagram_lens(InitialPadding::Mtu) {
+        assert_eq!(len, 1350);
+    }
+
+    for len in initial_datagram_lens(InitialPadding::Minimum) {
+        assert_eq!(len, 1200);
+    }
+}
+
+/// Ensures connection attempts which fail because QUIC is blocked are classified as such
+#[test]
+fn quic_blocked_test() {
+    use crate::connection::error::Blocked;
+    use s2n_codec::{DecoderBufferMut, EncoderBuffer};
+    use s2n_quic_core::{
+        event::api::Subject,
+        packet::interceptor::{Datagram, Interceptor},
+    };
+    use std::net::SocketAddr;
+
+    /// Pretends that the client and server don't share a QUIC version
+    struct Unsupported;
+
+    impl Interceptor for Unsupported {
+        fn intercept_rx_datagram<'a>(
+            &mut self,
+            _subject: &Subject,
+            _datagram: &Datagram,
+            payload: DecoderBufferMut<'a>,
+        ) -> DecoderBufferMut<'a> {
+            let bytes = payload.into_less_safe_slice();
+
+            // replace the versions listed in Version Negotiation packets with draft 29
+            if bytes[0] & 0x80 != 0 && bytes[1..5] == [0; 4] {
+                let destination_len = bytes[5] as usize;
+                let source_len = bytes[6 + destination_len] as usize;
+                for version in bytes[7 + destination_len + source_len..].chunks_exact_mut(4) {
+                    version.copy_from_slice(&0xff00_001du32.to_be_bytes());
+                }
+            }
+
+            DecoderBufferMut::new(bytes)
+        }
+
+        fn intercept_tx_datagram<'a>(
+            &mut self,
+            _subject: &Subject,
+            _datagram: &Datagram,
+            payload: &mut EncoderBuffer<'a>,
+        ) {
+            // use a reserved version so the server responds with a Version Negotiation packet
+            let bytes = payload.as_mut_slice();
+            if bytes[0] & 0x80 != 0 && bytes[1..5] == 1u32.to_be_bytes() {
+                bytes[1..5].copy_from_slice(&0x0a0a_0a0au32.to_be_bytes());
+            }
+        }
+    }
+
+    let model = Model::default();
+    test(model, |handle| {
+        let server = server(handle)?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(events())?
+            .start()?;
+
+        let unsupported = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(events())?
+            .with_packet_interceptor(Unsupported)?
+            .start()?;
+
+        primary::spawn(async move {
+            // nothing is listening on this address
+            let unreachable: SocketAddr = "1.0.0.99:4433".parse().unwrap();
+            let connect = Connect::new(unreachable).with_server_name("localhost");
+            let error = client.connect(connect).await.unwrap_err();
+            assert_eq!(
+                error.quic_blocked(),
+                Some(Blocked::NoResponse),
+                "{:?}",
+                error
+            );
+
+            let connect = Connect::new(server).with_server_name("localhost");
+            let error = unsupported.connect(connect).await.unwrap_err();
+            assert!(
+                matches!(error, crate::connection::Error::NoCompatibleVersion { .. }),
+                "{:?}",
+                error
+            );
+            assert_eq!(error.quic_blocked(), Some(Blocked::NoCompatibleVersion));
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures clients can use zero-length connection IDs, which are routed by the server address
+#[test]
+fn zero_length_connection_id_test() {
+    use s2n_codec::{DecoderBufferMut, EncoderBuffer};
+    use s2n_quic_core::{
+        event::api::Subject,
+        packet::interceptor::{Datagram, Interceptor},
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    /// Counts the long header packets addressed to zero-length connection IDs
+    #[derive(Clone, Default)]
+    struct ZeroLength(Arc<AtomicUsize>);
+
+    impl Interceptor for ZeroLength {
+        fn intercept_rx_datagram<'a>(
+            &mut self,
+            _subject: &Subject,
+            _datagram: &Datagram,
+            payload: DecoderBufferMut<'a>,
+        ) -> DecoderBufferMut<'a> {
+            let bytes = payload.into_less_safe_slice();
+            if bytes[0] & 0x80 != 0 {
+                assert_eq!(bytes[5], 0, "the server should use the empty connection ID");
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+            DecoderBufferMut::new(bytes)
+        }
+
+        fn intercept_tx_datagram<'a>(
+            &mut self,
+            _subject: &Subject,
+            _datagram: &Datagram,
+            _payload: &mut EncoderBuffer<'a>,
+        ) {
+        }
+    }
+
+    // servers route packets by connection ID, so they can't use zero-length connection IDs
+    let format = || {
+        provider::connection_id::default::Format::builder()
+            .with_len(0)
+            .unwrap()
+            .build()
+            .unwrap()
+    };
+    let result = Server::builder()
+        .with_tls(SERVER_CERTS)
+        .unwrap()
+        .with_connection_id(format())
+        .unwrap()
+        .start_sharded(1);
+    let error = match result {
+        Ok(_) => panic!("servers can't use zero-length connection ids"),
+        Err(error) => error,
+    };
+    assert!(error.to_string().contains("length"), "{}", error);
+
+    let received = ZeroLength::default();
+
+    let model = Model::default();
+    test(model, |handle| {
+        let server = server(handle)?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(events())?
+            .with_connection_id(format())?
+            .with_packet_interceptor(received.clone())?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect.clone()).await.unwrap();
+
+            // only a single connection with zero-length connection IDs can be opened to a server
+            assert!(client.connect(connect).await.is_err());
+
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            stream.send(Bytes::from_static(b"hello")).await.unwrap();
+            assert_eq!(
+                stream.receive().await.unwrap().unwrap(),
+                Bytes::from_static(b"hello")
+            );
+        });
+
+        Ok(())
+    })
+    .unwrap();
+
+    assert!(received.0.load(Ordering::Relaxed) > 0);
+}
+
+/// Ensures values attached to a connection are visible to all of its handles
+#[test]
+fn extensions_test() {
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct TenantId(u8);
+
+    let model = Model::default();
+    test(model, |handle| {
+        let mut server = build_server(handle)?;
+        let server_addr = server.local_addr()?;
+        let client = build_client(handle)?;
+
+        spawn(async move {
+            let mut tenant = 0;
+            while let Some(mut connection) = server.accept().await {
+                tenant += 1;
+                // attach the state when the connection is accepted
+                let previous = connection
+                    .extensions_mut(|extensions| extensions.insert(TenantId(tenant)))
+                    .unwrap();
+                assert_eq!(previous, None);
+
+                let (handle, mut acceptor) = connection.split();
+                spawn(async move {
+                    while let Ok(Some(mut stream)) = acceptor.accept_bidirectional_stream().await {
+                        // read the state from the task serving the stream
+                        let tenant = handle
+                            .extensions(|extensions| extensions.get::<TenantId>().copied())
+                            .unwrap()
+                            .unwrap();
+                        stream.send(Bytes::from(vec![tenant.0])).await.unwrap();
+                    }
+                });
+            }
+        });
+
+        primary::spawn(async move {
+            for tenant in 1..=2 {
+                let connect = Connect::new(server_addr).with_server_name("localhost");
+                let mut connection = client.connect(connect).await.unwrap();
+
+                let extensions = connection.extensions(|extensions| extensions.len());
+                assert_eq!(extensions.unwrap(), 0);
+
+                let mut stream = connection.open_bidirectional_stream().await.unwrap();
+                stream.finish().unwrap();
+                assert_eq!(
+                    stream.receive().await.unwrap().unwrap(),
+                    Bytes::from(vec![tenant])
+                );
+            }
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures deliveries resolve once the peer has acknowledged the data, even with packet loss
+#[test]
+fn delivery_test() {
+    let model = Model::default();
+    model.set_drop_rate(0.1);
+    test(model, |handle| {
+        const CHUNKS: usize = 10;
+        const LEN: usize = 5_000;
+
+        let server = server(handle)?;
+        let client = build_client(handle)?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let stream = connection.open_bidirectional_stream().await.unwrap();
+            let (mut recv, mut send) = stream.split();
+
+            // keep sending while the previous chunks are in flight
+            let mut deliveries = vec![];
+            for _ in 0..CHUNKS {
+                let delivery = send
+                    .send_with_ack(Bytes::from_static(&[42; LEN]))
+                    .await
+                    .unwrap();
+                deliveries.push(delivery);
+            }
+
+            for delivery in deliveries {
+                delivery.await.unwrap();
+            }
+
+            // all of the data was acknowledged, so nothing new needs to be tracked
+            send.track_delivery().unwrap().await.unwrap();
+
+            let mut recv_len = 0;
+            while recv_len < CHUNKS * LEN {
+                recv_len += recv.receive().await.unwrap().unwrap().len();
+            }
+
+            // deliveries which are waiting when the stream is reset fail
+            let delivery = send
+                .send_with_ack(Bytes::from_static(&[42; LEN]))
+                .await
+                .unwrap();
+            send.reset(1u8.into()).unwrap();
+            assert!(delivery.await.is_err());
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures the acknowledged and lost stream ranges are reported to subscribers
+#[test]
+fn stream_data_acked_and_lost_test() {
+    use provider::event::{
+        events::{StreamDataAcked, StreamDataLost},
+        ConnectionInfo, ConnectionMeta, Subscriber,
+    };
+    use std::sync::{Arc, Mutex};
+
+    const LEN: u64 = 50_000;
+
+    #[derive(Clone, Default)]
+    struct Ranges {
+        acked: Arc<Mutex<Vec<(u64, u64)>>>,
+        lost: Arc<Mutex<Vec<(u64, u64)>>>,
+    }
+
+    impl Subscriber for Ranges {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+
+        fn on_stream_data_acked(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            event: &StreamDataAcked,
+        ) {
+            assert_eq!(event.stream_id, 0);
+            let range = (event.offset, event.offset + event.len);
+            self.acked.lock().unwrap().push(range);
+        }
+
+        fn on_stream_data_lost(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            event: &StreamDataLost,
+        ) {
+            assert_eq!(event.stream_id, 0);
+            let range = (event.offset, event.offset + event.len);
+            self.lost.lock().unwrap().push(range);
+        }
+    }
+
+    let ranges = Ranges::default();
+
+    let model = Model::default();
+    model.set_drop_rate(0.1);
+    test(model, |handle| {
+        let server = server(handle)?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(ranges.clone())?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            stream
+                .send(Bytes::from_static(&[42; LEN as usize]))
+                .await
+                .unwrap();
+            stream.flush().await.unwrap();
+        });
+
+        Ok(())
+    })
+    .unwrap();
+
+    // the acknowledged ranges cover all of the sent data
+    let mut acked = ranges.acked.lock().unwrap().clone();
+    acked.sort_unstable();
+    let mut acked_len = 0;
+    for (start, end) in acked {
+        assert!(start <= acked_len, "gap in the acknowledged ranges");
+        acked_len = acked_len.max(end);
+    }
+    assert_eq!(acked_len, LEN);
+
+    // some of the data was lost and retransmitted
+    let lost = ranges.lost.lock().unwrap();
+    assert!(!lost.is_empty());
+    assert!(lost.iter().all(|(start, end)| start < end && *end <= LEN));
+}
+
+/// Ensures streams which are reset on loss skip the lost data instead of retransmitting it
+#[test]
+fn reset_on_loss_test() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    const STREAMS: usize = 20;
+    const LEN: usize = 20_000;
+    const ERROR_CODE: u8 = 1;
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let reset = Arc::new(AtomicUsize::new(0));
+
+    let model = Model::default();
+    model.set_drop_rate(0.05);
+    test(model, |handle| {
+        let mut server = build_server(handle)?;
+        let server_addr = server.local_addr()?;
+        let client = build_client(handle)?;
+
+        let counts = (received.clone(), reset.clone());
+        primary::spawn(async move {
+            let (received, reset) = counts;
+            let mut connection = server.accept().await.unwrap();
+
+            for _ in 0..STREAMS {
+                let mut stream = connection.accept_receive_stream().await.unwrap().unwrap();
+
+                let mut len = 0;
+                loop {
+                    match stream.receive().await {
+                        Ok(Some(chunk)) => len += chunk.len(),
+                        Ok(None) => {
+                            assert_eq!(len, LEN);
+                            received.fetch_add(1, Ordering::Relaxed);
+                            break;
+                        }
+                        Err(crate::stream::Error::StreamReset { error, .. }) => {
+                            assert_eq!(error, ERROR_CODE.into());
+                            reset.fetch_add(1, Ordering::Relaxed);
+                            break;
+                        }
+                        Err(error) => panic!("unexpected error {}", error),
+                    }
+                }
+            }
+        });
+
+        primary::spawn(async move {
+            let connect = Connect::new(server_addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+
+            for _ in 0..STREAMS {
+                let mut stream = connection.open_send_stream().await.unwrap();
+                stream.reset_on_loss(ERROR_CODE.into()).unwrap();
+
+                // the stream may have been reset while sending
+                if stream.send(Bytes::from_static(&[42; LEN])).await.is_ok() {
+                    let _ = stream.finish();
+                }
+            }
+
+            // keep the connection open until the server has seen all of the streams
+            connection.keep_alive(true).unwrap();
+            delay(Duration::from_secs(5)).await;
+        });
+
+        Ok(())
+    })
+    .unwrap();
+
+    let received = received.load(Ordering::Relaxed);
+    let reset = reset.load(Ordering::Relaxed);
+    assert_eq!(received + reset, STREAMS);
+    assert!(received > 0);
+    assert!(reset > 0);
+}
+
+/// Ensures the QUIC bit is only greased when the receiver advertised support for it
+#[test]
+fn grease_quic_bit_test() {
+    use s2n_codec::{DecoderBufferMut, EncoderBuffer};
+    use s2n_quic_core::{
+        event::api::Subject,
+        packet::interceptor::{Datagram, Interceptor},
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    /// Counts the datagrams received with the QUIC bit cleared
+    #[derive(Clone, Default)]
+    struct ClearedQuicBit(Arc<AtomicUsize>);
+
+    impl Interceptor for ClearedQuicBit {
+        fn intercept_rx_datagram<'a>(
+            &mut self,
+            _subject: &Subject,
+            _datagram: &Datagram,
+            payload: DecoderBufferMut<'a>,
+        ) -> DecoderBufferMut<'a> {
+            if payload.peek_byte(0).unwrap() & 0x40 == 0 {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+            payload
+        }
+
+        fn intercept_tx_datagram<'a>(
+            &mut self,
+            _subject: &Subject,
+            _datagram: &Datagram,
+            _payload: &mut EncoderBuffer<'a>,
+        ) {
+        }
+    }
+
+    let run = |server_enabled: bool, client_enabled: bool| {
+        let cleared = ClearedQuicBit::default();
+
+        let limits = |enabled| {
+            provider::limits::Limits::default()
+                .with_grease_quic_bit_enabled(enabled)
+                .unwrap()
+        };
+
+        let model = Model::default();
+        test(model, |handle| {
+            let server = server_with(handle, |io| {
+                Ok(Server::builder()
+                    .with_io(io)?
+                    .with_tls(SERVER_CERTS)?
+                    .with_event(events())?
+                    .with_limits(limits(server_enabled))?
+                    .start()?)
+            })?;
+
+            let client = Client::builder()
+                .with_io(handle.builder().build()?)?
+                .with_tls(certificates::CERT_PEM)?
+                .with_event(events())?
+                .with_limits(limits(client_enabled))?
+                .with_packet_interceptor(cleared.clone())?
+                .start()?;
+
+            primary::spawn(async move {
+                let connect = Connect::new(server).with_server_name("localhost");
+                let mut connection = client.connect(connect).await.unwrap();
+
+                let mut stream = connection.open_bidirectional_stream().await.unwrap();
+                for _ in 0..20 {
+                    stream.send(Bytes::from_static(b"hello")).await.unwrap();
+                    assert_eq!(
+                        stream.receive().await.unwrap().unwrap(),
+                        Bytes::from_static(b"hello")
+                    );
+                }
+            });
+
+            Ok(())
+        })
+        .unwrap();
+
+        cleared.0.load(Ordering::Relaxed)
+    };
+
+    // the client accepts the packets the server sent with the QUIC bit cleared
+    assert!(run(true, true) > 0);
+
+    // the server greases the bit if the client advertised support for it, even if it didn't
+    assert!(run(false, true) > 0);
+
+    // the server keeps the bit set if the client doesn't support greasing it
+    assert_eq!(run(true, false), 0);
+}
+
+/// Returns the error the client connection was closed with after the server lost its state
+/// and the number of datagrams the server checked for a stateless reset without a match
+fn stateless_reset<P: provider::stateless_reset_token::Provider>(
+    stateless_reset_token: P,
+) -> (crate::connection::Error, usize) {
+    use provider::event::{events, ConnectionInfo, ConnectionMeta, Subscriber};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    #[derive(Clone, Default)]
+    struct Mismatches(Arc<AtomicUsize>);
+
+    impl Subscriber for Mismatches {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+
+        fn on_endpoint_stateless_reset_token_mismatch(
+            &mut self,
+            _meta: &events::EndpointMeta,
+            _event: &events::EndpointStatelessResetTokenMismatch,
+        ) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let mismatches = Mismatches::default();
+    let error = Arc::new(Mutex::new(None));
+
+    let model = Model::default();
+    test(model.clone(), |handle| {
+        let mut server = Server::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(SERVER_CERTS)?
+            .with_event(mismatches.clone())?
+            .with_stateless_reset_token(stateless_reset_token)?
+            .start()?;
+        let server_addr = server.local_addr()?;
+
+        spawn(async move {
+            let mut connection = server.accept().await.unwrap();
+            let mut stream = connection
+                .accept_bidirectional_stream()
+                .await
+                .unwrap()
+                .unwrap();
+            let chunk = stream.receive().await.unwrap().unwrap();
+            stream.send(chunk).await.unwrap();
+            stream.flush().await.unwrap();
+
+            // the client doesn't find out the connection was closed
+            delay(Duration::from_secs(1)).await;
+            model.set_drop_rate(1.0);
+            connection.close(crate::application::Error::UNKNOWN);
+
+            // the server discards the connection once it finishes draining and the application
+            // releases its handles
+            drop(stream);
+            drop(connection);
+            delay(Duration::from_secs(5)).await;
+            model.set_drop_rate(0.0);
+
+            // keep the endpoint open to respond to the client
+            while server.accept().await.is_some() {}
+        });
+
+        let client = build_client(handle)?;
+        let error = error.clone();
+
+        primary::spawn(async move {
+            let connect = Connect::new(server_addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+
+            stream.send(Bytes::from_static(b"hello")).await.unwrap();
+            assert!(stream.receive().await.unwrap().is_some());
+
+            // keep sending packets with the connection IDs the server has forgotten about
+            let err = loop {
+                if let Err(err) = stream.send(Bytes::from_static(b"hello")).await {
+                    break err;
+                }
+                delay(Duration::from_millis(100)).await;
+            };
+
+            *error.lock().unwrap() = Some(err);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+
+    let error = error.lock().unwrap().take().unwrap();
+    let error = match error {
+        crate::stream::Error::ConnectionError { error, .. } => error,
+        error => panic!("unexpected error {:?}", error),
+    };
+
+    (error, mismatches.0.load(Ordering::Relaxed))
+}
+
+#[test]
+fn stateless_reset_test() {
+    // the server recomputes the token for the connection ID from the key, so the client
+    // recognizes the stateless reset
+    let provider = provider::stateless_reset_token::hmac::Provider::new(&[42; 32]).unwrap();
+    let (error, mismatches) = stateless_reset(provider);
+    assert!(
+        matches!(error, crate::connection::Error::StatelessReset { .. }),
+        "{:?}",
+        error
+    );
+    assert!(mismatches > 0);
+
+    // random tokens can't be recomputed, so the server doesn't send stateless resets and the
+    // client has to wait for the idle timer
+    let (error, mismatches) = stateless_reset(provider::stateless_reset_token::Default::default());
+    assert!(
+        matches!(error, crate::connection::Error::IdleTimerExpired { .. }),
+        "{:?}",
+        error
+    );
+    assert!(mismatches > 0);
+}
+
+/// Returns the error a client connecting to an unreachable address fails with, along with the
+/// time it took and the number of datagrams it sent
+fn unreachable_handshake(
+    limits: provider::limits::Limits,
+) -> (crate::connection::Error, Duration, usize) {
+    use provider::event::{events::DatagramSent, ConnectionInfo, ConnectionMeta, Subscriber};
+    use std::{
+        net::SocketAddr,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    #[derive(Clone, Default)]
+    struct Datagrams(Arc<AtomicUsize>);
+
+    impl Subscriber for Datagrams {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+
+        fn on_datagram_sent(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            _event: &DatagramSent,
+        ) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let datagrams = Datagrams::default();
+    let result = Arc::new(Mutex::new(None));
+
+    let model = Model::default();
+    test(model, |handle| {
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(datagrams.clone())?
+            .with_limits(limits)?
+            .start()?;
+        let result = result.clone();
+
+        primary::spawn(async move {
+            // nothing is listening on this address
+            let unreachable: SocketAddr = "1.0.0.99:4433".parse().unwrap();
+            let connect = Connect::new(unreachable).with_server_name("localhost");
+
+            let start = s2n_quic_platform::io::testing::now();
+            let error = client.connect(connect).await.unwrap_err();
+            let elapsed = s2n_quic_platform::io::testing::now() - start;
+
+            *result.lock().unwrap() = Some((error, elapsed));
+        });
+
+        Ok(())
+    })
+    .unwrap();
+
+    let (error, elapsed) = result.lock().unwrap().take().unwrap();
+    (error, elapsed, datagrams.0.load(Ordering::Relaxed))
+}
+
+#[test]
+fn handshake_retransmission_test() {
+    use crate::connection::error::Blocked;
+
+    let limits = provider::limits::Limits::default()
+        .with_initial_round_trip_time(Duration::from_millis(100))
+        .unwrap()
+        .with_max_handshake_pto_count(2)
+        .unwrap();
+
+    // the PTO starts at 300ms and doubles on every expiration, so the connection attempt is
+    // abandoned after the third expiration at 300ms + 600ms + 1200ms
+    let (error, elapsed, datagrams) = unreachable_handshake(limits);
+    assert!(
+        matches!(
+            error,
+            crate::connection::Error::MaxHandshakePtoCountExceeded {
+                max_handshake_pto_count: 2,
+                ..
+            }
+        ),
+        "{:?}",
+        error
+    );
+    assert_eq!(error.quic_blocked(), Some(Blocked::NoResponse));
+    assert!(
+        (Duration::from_secs(2)..Duration::from_millis(2200)).contains(&elapsed),
+        "{:?}",
+        elapsed
+    );
+
+    // sending a single probe on each expiration results in fewer retransmitted datagrams
+    let (_, single_probe_elapsed, single_probe_datagrams) =
+        unreachable_handshake(limits.with_max_handshake_probes(1).unwrap());
+    assert_eq!(single_probe_elapsed, elapsed);
+    assert!(
+        single_probe_datagrams < datagrams,
+        "{} < {}",
+        single_probe_datagrams,
+        datagrams
+    );
+
+    // without the limit, the connection attempt is only bounded by the max handshake duration
+    let (error, elapsed, _) = unreachable_handshake(provider::limits::Limits::default());
+    assert!(
+        matches!(
+            error,
+            crate::connection::Error::MaxHandshakeDurationExceeded { .. }
+        ),
+        "{:?}",
+        error
+    );
+    assert!(elapsed >= Duration::from_secs(10), "{:?}", elapsed);
+
+    assert!(provider::limits::Limits::default()
+        .with_initial_round_trip_time(Duration::ZERO)
+        .is_err());
+    assert!(provider::limits::Limits::default()
+        .with_max_handshake_pto_count(0)
+        .is_err());
+    assert!(provider::limits::Limits::default()
+        .with_max_handshake_probes(3)
+        .is_err());
+}
+
+#[test]
+fn max_unvalidated_handshake_bytes_test() {
+    use provider::event::{
+        events::UnvalidatedHandshakeBytesExceeded, ConnectionInfo, ConnectionMeta, Subscriber,
+    };
+    use std::sync::{Arc, Mutex};
+
+    /// Records the bytes received from the peer when the limit was exceeded
+    #[derive(Clone, Default)]
+    struct Exceeded(Arc<Mutex<Vec<(u64, u64)>>>);
+
+    impl Subscriber for Exceeded {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+
+        fn on_unvalidated_handshake_bytes_exceeded(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            event: &UnvalidatedHandshakeBytesExceeded,
+        ) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((event.bytes_received, event.max_unvalidated_handshake_bytes));
+        }
+    }
+
+    let run = |max_unvalidated_handshake_bytes: u64| {
+        let exceeded = Exceeded::default();
+        let result = Arc::new(Mutex::new(None));
+
+        test(Model::default(), |handle| {
+            let limits = provider::limits::Limits::default()
+                .with_max_unvalidated_handshake_bytes(max_unvalidated_handshake_bytes)?;
+            let addr = server_with(handle, |io| {
+                Ok(Server::builder()
+                    .with_io(io)?
+                    .with_tls(SERVER_CERTS)?
+                    .with_event(exceeded.clone())?
+                    .with_limits(limits)?
+                    .start()?)
+            })?;
+
+            let client = build_client(handle)?;
+            let result = result.clone();
+            primary::spawn(async move {
+                let connect = Connect::new(addr).with_server_name("localhost");
+                // the client considers the handshake complete before the server receives its
+                // second flight, so the handshake only fails once data is exchanged
+                let echo = async move {
+                    let mut connection = client.connect(connect).await?;
+                    let mut stream = connection.open_bidirectional_stream().await?;
+                    stream.send(Bytes::from_static(b"hello")).await?;
+                    stream.finish()?;
+                    stream.receive().await
+                };
+                *result.lock().unwrap() = Some(echo.await.map(|_| ()));
+            });
+
+            Ok(addr)
+        })
+        .unwrap();
+
+        let result = result.lock().unwrap().take().unwrap();
+        let exceeded = exceeded.0.lock().unwrap().clone();
+        (result, exceeded)
+    };
+
+    // the client's second flight is sent before the server can validate its address, which
+    // exceeds a limit of a single datagram
+    let (result, exceeded) = run(1200);
+    assert!(result.is_err());
+    assert_eq!(exceeded.len(), 1);
+    let (bytes_received, max_unvalidated_handshake_bytes) = exceeded[0];
+    assert!(bytes_received > 1200, "{}", bytes_received);
+    assert_eq!(max_unvalidated_handshake_bytes, 1200);
+
+    // the limit is not reached by a regular handshake
+    let (result, exceeded) = run(100_000);
+    assert!(result.is_ok());
+    assert!(exceeded.is_empty());
+
+    assert!(provider::limits::Limits::default()
+        .with_max_unvalidated_handshake_bytes(1199)
+        .is_err());
+}
+
+#[test]
+fn connection_attempt_limited_test() {
+    use provider::{
+        endpoint_limits::{ConnectionAttempt, Limiter, Outcome},
+        event::{
+            events::{ConnectionAttemptOutcome, EndpointConnectionAttemptLimited, EndpointMeta},
+            ConnectionInfo, ConnectionMeta, Subscriber,
+        },
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    /// Asks every client to validate its address
+    struct AlwaysRetry;
+
+    impl Limiter for AlwaysRetry {
+        fn on_connection_attempt(&mut self, _info: &ConnectionAttempt) -> Outcome {
+            Outcome::retry()
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct Retries(Arc<AtomicUsize>);
+
+    impl Subscriber for Retries {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+
+        fn on_endpoint_connection_attempt_limited(
+            &mut self,
+            _meta: &EndpointMeta,
+            event: &EndpointConnectionAttemptLimited,
+        ) {
+            assert!(matches!(
+                event.outcome,
+                ConnectionAttemptOutcome::Retry { .. }
+            ));
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let retries = Retries::default();
+
+    test(Model::default(), |handle| {
+        let addr = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(retries.clone())?
+                .with_endpoint_limits(AlwaysRetry)?
+                .start()?)
+        })?;
+        // clients presenting a retry token bypass the limiter, so the connection succeeds
+        client(handle, addr)?;
+        Ok(addr)
+    })
+    .unwrap();
+
+    assert_eq!(retries.0.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn tenant_quota_test() {
+    use provider::tenant::{Info, Quota, Quotas};
+    use s2n_quic_core::transport;
+    use std::sync::{Arc, Mutex};
+
+    let results = Arc::new(Mutex::new(vec![]));
+
+    test(Model::default(), |handle| {
+        let quotas = Quotas::new(|info: &Info| {
+            let server_name = info.server_name?.to_string();
+            Some((server_name, Quota::new().with_max_connections(1)))
+        });
+        let addr = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_tenant(quotas)?
+                .start()?)
+        })?;
+
+        let client = build_client(handle)?;
+        let results = results.clone();
+        primary::spawn(async move {
+            let connect = Connect::new(addr).with_server_name("localhost");
+            let first = client.connect(connect.clone()).await.unwrap();
+
+            // the tenant already has a connection open
+            let mut refused = client.connect(connect.clone()).await;
+            if let Ok(connection) = refused.as_mut() {
+                // the client may consider the handshake complete before learning about the error
+                let error = connection.accept_bidirectional_stream().await.unwrap_err();
+                refused = Err(error);
+            }
+            results.lock().unwrap().push(refused.map(|_| ()));
+
+            // closing the connection releases the quota
+            drop(first);
+            // wait for the server to discard the connection after closing it
+            delay(Duration::from_secs(1)).await;
+            let admitted = client.connect(connect).await.map(|_| ());
+            results.lock().unwrap().push(admitted);
+        });
+
+        Ok(addr)
+    })
+    .unwrap();
+
+    let results = results.lock().unwrap();
+    assert_eq!(results.len(), 2);
+    let error = results[0].unwrap_err();
+    assert!(
+        matches!(
+            error,
+            crate::connection::Error::Transport { code, .. }
+                if code == transport::Error::CONNECTION_REFUSED.code
+        ),
+        "{:?}",
+        error
+    );
+    assert!(results[1].is_ok(), "{:?}", results[1]);
+}
+
+#[test]
+fn connection_gauges_test() {
+    use provider::event::gauges::{Snapshot, Subscriber};
+    use std::sync::{Arc, Mutex};
+
+    let gauges = Subscriber::default();
+    let snapshots = Arc::new(Mutex::new(vec![]));
+
+    test(Model::default(), |handle| {
+        let addr = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(gauges.clone())?
+                .start()?)
+        })?;
+
+        let client = build_client(handle)?;
+        let gauges = gauges.clone();
+        let snapshots = snapshots.clone();
+        primary::spawn(async move {
+            let connect = Connect::new(addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            // wait for the server to complete the handshake
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            stream.send(Bytes::from_static(b"hello")).await.unwrap();
+            stream.finish().unwrap();
+            stream.receive().await.unwrap();
+            snapshots.lock().unwrap().push(gauges.snapshot());
+
+            connection.close(0u8.into());
+            delay(Duration::from_millis(100)).await;
+            snapshots.lock().unwrap().push(gauges.snapshot());
+        });
+
+        Ok(addr)
+    })
+    .unwrap();
+
+    let snapshots = snapshots.lock().unwrap();
+    assert_eq!(snapshots.len(), 2);
+
+    let open = &snapshots[0];
+    assert_eq!(open.connections, 1);
+    assert_eq!(open.handshakes, 0);
+    assert_eq!(
+        open.application_protocols.get(&b"h3"[..]).copied(),
+        Some(1),
+        "{:?}",
+        open
+    );
+    assert_eq!(open.server_names.get("localhost").copied(), Some(1));
+    assert_eq!(open.other_server_names, 0);
+
+    // the connection is removed once it's closed
+    assert_eq!(snapshots[1], Snapshot::default());
+}
+
+#[test]
+fn application_error_range_test() {
+    use crate::application::{error::Range, Error};
+    use s2n_quic_core::application::error::TryInto as _;
+    use std::sync::{Arc, Mutex};
+
+    const RANGE: Range = Range::new(Error::from_u16(0x0100), Error::from_u16(0x0110));
+
+    let reset_error = Arc::new(Mutex::new(None));
+    let close_error = Arc::new(Mutex::new(None));
+
+    test(Model::default(), |handle| {
+        let mut server = build_server(handle)?;
+        let addr = server.local_addr()?;
+
+        let reset_error = reset_error.clone();
+        spawn(async move {
+            let mut connection = server.accept().await.unwrap();
+            let mut stream = connection.accept_receive_stream().await.unwrap().unwrap();
+
+            // the error code of the reset is reported to the peer as-is
+            let error = loop {
+                match stream.receive().await {
+                    Ok(Some(_)) => continue,
+                    Ok(None) => panic!("the stream should be reset"),
+                    Err(error) => break error,
+                }
+            };
+            *reset_error.lock().unwrap() = error.application_error();
+
+            connection.close(RANGE.get(1).unwrap());
+        });
+
+        let client = build_client(handle)?;
+        let close_error = close_error.clone();
+        primary::spawn(async move {
+            let connect = Connect::new(addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+
+            let mut stream = connection.open_send_stream().await.unwrap();
+            stream.send(Bytes::from_static(b"hello")).await.unwrap();
+            stream.flush().await.unwrap();
+            stream.reset(RANGE.get(2).unwrap()).unwrap();
+
+            let error = connection.accept().await.unwrap_err();
+            *close_error.lock().unwrap() = error.application_error();
+        });
+
+        Ok(addr)
+    })
+    .unwrap();
+
+    let reset_error = reset_error.lock().unwrap().unwrap();
+    assert_eq!(RANGE.offset(reset_error), Some(2));
+
+    let close_error = close_error.lock().unwrap().unwrap();
+    assert_eq!(RANGE.offset(close_error), Some(1));
+}
+
+#[test]
+fn event_recorder_test() {
+    use crate::connection;
+    use provider::event::recorder::{Bundle, Reason, Subscriber};
+    use std::sync::{Arc, Mutex};
+
+    let bundles: Arc<Mutex<Vec<Bundle>>> = Default::default();
+
+    let recorder = Subscriber::builder()
+        .with_capacity(8)
+        .with_trigger(|error| matches!(error, connection::Error::Application { .. }))
+        .with_handler({
+            let bundles = bundles.clone();
+            move |bundle| bundles.lock().unwrap().push(bundle.clone())
+        })
+        .build();
+
+    test(Model::default(), |handle| {
+        let addr = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(recorder.clone())?
+                .start()?)
+        })?;
+
+        let client = build_client(handle)?;
+        let recorder = recorder.clone();
+        primary::spawn(async move {
+            let connect = Connect::new(addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            stream.send(Bytes::from_static(b"hello")).await.unwrap();
+            stream.finish().unwrap();
+            stream.receive().await.unwrap();
+
+            // dump the open server connection
+            recorder.dump();
+
+            connection.close(0u8.into());
+            delay(Duration::from_millis(100)).await;
+        });
+
+        Ok(addr)
+    })
+    .unwrap();
+
+    let bundles = bundles.lock().unwrap();
+    assert_eq!(bundles.len(), 2, "{:?}", bundles);
+
+    let requested = &bundles[0];
+    assert!(matches!(requested.reason, Reason::Requested));
+    assert_eq!(requested.records.len(), 8);
+    assert!(requested.discarded > 0);
+
+    let closed = &bundles[1];
+    assert!(matches!(
+        closed.reason,
+        Reason::Closed {
+            error: connection::Error::Application { .. }
+        }
+    ));
+    // the close event is included in addition to the recorded events
+    assert_eq!(closed.records.len(), 9);
+    assert_eq!(
+        closed.records.last().unwrap().name,
+        "connectivity:connection_closed"
+    );
+    assert_eq!(closed.connection_id, requested.connection_id);
+}
+
+#[test]
+fn connection_attempt_queue_depths_test() {
+    use provider::endpoint_limits::{ConnectionAttempt, Limiter, Outcome};
+    use std::sync::{Arc, Mutex};
+
+    /// Records the rx backlog of each connection attempt
+    #[derive(Clone, Default)]
+    struct Backlog(Arc<Mutex<Vec<usize>>>);
+
+    impl Limiter for Backlog {
+        fn on_connection_attempt(&mut self, info: &ConnectionAttempt) -> Outcome {
+            self.0.lock().unwrap().push(info.rx_backlog);
+            Outcome::allow()
+        }
+    }
+
+    let backlog = Backlog::default();
+
+    test(Model::default(), |handle| {
+        let addr = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_endpoint_limits(backlog.clone())?
+                .start()?)
+        })?;
+        client(handle, addr)?;
+        Ok(addr)
+    })
+    .unwrap();
+
+    let backlog = backlog.0.lock().unwrap();
+    assert_eq!(backlog.len(), 1);
+    // the datagram of the connection attempt is still waiting to be processed
+    assert!(backlog[0] >= 1);
+}
+
+#[test]
+fn transport_parameters_negotiated_test() {
+    use provider::event::{
+        events::{EndpointType, TransportParametersNegotiated},
+        ConnectionInfo, ConnectionMeta, Subscriber,
+    };
+    use std::sync::{Arc, Mutex};
+
+    /// Records the local and peer idle timeouts of each negotiation
+    #[derive(Clone, Default)]
+    struct Negotiated(Arc<Mutex<Vec<(Duration, Duration)>>>);
+
+    impl Subscriber for Negotiated {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+
+        fn on_transport_parameters_negotiated(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &TransportParametersNegotiated,
+        ) {
+            // the server's parameters are the only ones containing the original connection ID
+            assert_eq!(
+                event
+                    .local_transport_parameters
+                    .original_destination_connection_id
+                    .is_some(),
+                matches!(meta.endpoint_type, EndpointType::Server { .. })
+            );
+
+            self.0.lock().unwrap().push((
+                event.local_transport_parameters.max_idle_timeout,
+                event.peer_transport_parameters.max_idle_timeout,
+            ));
+        }
+    }
+
+    let limits = |max_idle_timeout| {
+        provider::limits::Limits::default()
+            .with_max_idle_timeout(max_idle_timeout)
+            .unwrap()
+    };
+    let server_idle_timeout = Duration::from_secs(20);
+    let client_idle_timeout = Duration::from_secs(30);
+
+    let server_negotiated = Negotiated::default();
+    let client_negotiated = Negotiated::default();
+
+    let model = Model::default();
+    test(model, |handle| {
+        let server = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(server_negotiated.clone())?
+                .with_limits(limits(server_idle_timeout))?
+                .start()?)
+        })?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(client_negotiated.clone())?
+            .with_limits(limits(client_idle_timeout))?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            stream.send(Bytes::from_static(b"hello")).await.unwrap();
+            stream.receive().await.unwrap();
+        });
+
+        Ok(())
+    })
+    .unwrap();
+
+    // each endpoint publishes the parameters once with its own parameters as the local ones
+    assert_eq!(
+        *server_negotiated.0.lock().unwrap(),
+        [(server_idle_timeout, client_idle_timeout)]
+    );
+    assert_eq!(
+        *client_negotiated.0.lock().unwrap(),
+        [(client_idle_timeout, server_idle_timeout)]
+    );
+}
+
+#[test]
+fn stream_group_test() {
+    use crate::application::Error;
+    use s2n_quic_core::application::error::TryInto as _;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    const RESET_ERROR: u32 = 42;
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let resets: Arc<Mutex<Vec<Option<Error>>>> = Default::default();
+    let local_reset = Arc::new(Mutex::new(None));
+
+    test(Model::default(), |handle| {
+        let mut server = build_server(handle)?;
+        let addr = server.local_addr()?;
+
+        let server_received = received.clone();
+        let server_resets = resets.clone();
+        spawn(async move {
+            let mut connection = server.accept().await.unwrap();
+            while let Ok(Some(mut stream)) = connection.accept_receive_stream().await {
+                let received = server_received.clone();
+                let resets = server_resets.clone();
+                spawn(async move {
+                    let error = loop {
+                        match stream.receive().await {
+                            Ok(Some(chunk)) => {
+                                received.fetch_add(chunk.len(), Ordering::Relaxed);
+                            }
+                            Ok(None) => panic!("the stream should be reset"),
+                            Err(error) => break error,
+                        }
+                    };
+                    resets.lock().unwrap().push(error.application_error());
+                });
+            }
+        });
+
+        let client = build_client(handle)?;
+        let received = received.clone();
+        let local_reset = local_reset.clone();
+        primary::spawn(async move {
+            let connect = Connect::new(addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let group = connection.create_stream_group(1000).unwrap();
+
+            let mut streams = vec![];
+            for _ in 0..2 {
+                let mut stream = connection.open_send_stream().await.unwrap();
+                connection.join_stream_group(group, stream.id()).unwrap();
+                stream.send(Bytes::from(vec![0; 800])).await.unwrap();
+                streams.push(stream);
+            }
+
+            // a task which is blocked on a stream is woken up by the group reset
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            connection.join_stream_group(group, stream.id()).unwrap();
+            let blocked = primary::spawn(async move {
+                let error = stream.receive().await.unwrap_err();
+                *local_reset.lock().unwrap() = error.application_error();
+            });
+
+            // the streams share the window of the group
+            delay(Duration::from_secs(1)).await;
+            assert_eq!(received.load(Ordering::Relaxed), 1000);
+
+            connection.set_stream_group_max_data(group, 1600).unwrap();
+            delay(Duration::from_secs(1)).await;
+            assert_eq!(received.load(Ordering::Relaxed), 1600);
+
+            connection
+                .reset_stream_group(group, RESET_ERROR.into())
+                .unwrap();
+            blocked.await;
+
+            // streams which join the group after the reset are reset as well
+            let mut stream = connection.open_send_stream().await.unwrap();
+            assert!(connection.join_stream_group(group, stream.id()).is_err());
+            assert!(stream.send(Bytes::from_static(b"hello")).await.is_err());
+
+            delay(Duration::from_secs(1)).await;
+            drop(streams);
+        });
+
+        Ok(addr)
+    })
+    .unwrap();
+
+    let expected = Some(Error::from(RESET_ERROR));
+    assert_eq!(*local_reset.lock().unwrap(), expected);
+    // the peer observes the resets of the group's send streams, including the late joiner
+    assert_eq!(*resets.lock().unwrap(), [expected; 3]);
+}
+
+#[test]
+fn stream_group_scheduling_test() {
+    use provider::stream_scheduler::{self, Class, ConnectionInfo};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    const LEN: usize = 500_000;
+
+    /// Gives the most urgent class a hundred times the weight of the other classes
+    #[derive(Clone, Debug, Default)]
+    struct Scheduler {
+        weight_calls: Arc<AtomicUsize>,
+    }
+
+    impl stream_scheduler::Endpoint for Scheduler {
+        type Scheduler = Self;
+
+        fn new_scheduler(&mut self, _info: &ConnectionInfo) -> Self {
+            self.clone()
+        }
+    }
+
+    impl stream_scheduler::Scheduler for Scheduler {
+        fn weight(&mut self, class: &Class) -> u32 {
+            self.weight_calls.fetch_add(1, Ordering::Relaxed);
+            if class.urgency == stream_scheduler::MIN_URGENCY {
+                100
+            } else {
+                1
+            }
+        }
+    }
+
+    let scheduler = Scheduler::default();
+    let completed: Arc<Mutex<Vec<u64>>> = Default::default();
+    let expected: Arc<Mutex<Vec<u64>>> = Default::default();
+
+    test(Model::default(), |handle| {
+        let mut server = build_server(handle)?;
+        let addr = server.local_addr()?;
+
+        let server_completed = completed.clone();
+        spawn(async move {
+            let mut connection = server.accept().await.unwrap();
+            while let Ok(Some(mut stream)) = connection.accept_receive_stream().await {
+                let completed = server_completed.clone();
+                spawn(async move {
+                    let mut len = 0;
+                    while let Some(chunk) = stream.receive().await.unwrap() {
+                        len += chunk.len();
+                    }
+                    assert_eq!(len, LEN);
+                    completed.lock().unwrap().push(stream.id());
+                });
+            }
+        });
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(events())?
+            .with_stream_scheduler(scheduler.clone())?
+            .start()?;
+
+        let expected = expected.clone();
+        primary::spawn(async move {
+            let connect = Connect::new(addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+
+            let background = connection.create_stream_group(u64::MAX).unwrap();
+            connection
+                .set_stream_group_urgency(background, stream_scheduler::MAX_URGENCY)
+                .unwrap();
+            let urgent = connection.create_stream_group(u64::MAX).unwrap();
+            connection
+                .set_stream_group_urgency(urgent, stream_scheduler::MIN_URGENCY)
+                .unwrap();
+
+            // the background stream starts sending first
+            let mut senders = vec![];
+            for group in [background, urgent] {
+                let mut stream = connection.open_send_stream().await.unwrap();
+                connection.join_stream_group(group, stream.id()).unwrap();
+                expected.lock().unwrap().push(stream.id());
+                senders.push(primary::spawn(async move {
+                    stream.send(Bytes::from(vec![0; LEN])).await.unwrap();
+                    stream.finish().unwrap();
+                    delay(Duration::from_secs(5)).await;
+                }));
+            }
+
+            for sender in senders {
+                sender.await;
+            }
+        });
+
+        Ok(addr)
+    })
+    .unwrap();
+
+    // the urgent stream completes first
+    let mut expected = expected.lock().unwrap().clone();
+    expected.reverse();
+    assert_eq!(*completed.lock().unwrap(), expected);
+    assert!(scheduler.weight_calls.load(Ordering::Relaxed) > 0);
+}
+
+#[test]
+fn sequential_stream_test() {
+    use std::sync::{Arc, Mutex};
+
+    const LEN: usize = 200_000;
+
+    let completed: Arc<Mutex<Vec<u64>>> = Default::default();
+    let expected: Arc<Mutex<Vec<u64>>> = Default::default();
+
+    test(Model::default(), |handle| {
+        let mut server = build_server(handle)?;
+        let addr = server.local_addr()?;
+
+        let server_completed = completed.clone();
+        spawn(async move {
+            let mut connection = server.accept().await.unwrap();
+            while let Ok(Some(mut stream)) = connection.accept_receive_stream().await {
+                let completed = server_completed.clone();
+                spawn(async move {
+                    while let Some(_chunk) = stream.receive().await.unwrap() {}
+                    completed.lock().unwrap().push(stream.id());
+                });
+            }
+        });
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(events())?
+            .start()?;
+
+        let expected = expected.clone();
+        primary::spawn(async move {
+            let connect = Connect::new(addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+
+            // the first stream sends twice as much data, which would complete last if the
+            // streams were sent in round robin order
+            let mut senders = vec![];
+            for len in [LEN * 2, LEN] {
+                let mut stream = connection.open_send_stream().await.unwrap();
+                connection
+                    .set_stream_incremental(stream.id(), false)
+                    .unwrap();
+                expected.lock().unwrap().push(stream.id());
+                senders.push(primary::spawn(async move {
+                    stream.send(Bytes::from(vec![0; len])).await.unwrap();
+                    stream.finish().unwrap();
+                    delay(Duration::from_secs(5)).await;
+                }));
+            }
+
+            for sender in senders {
+                sender.await;
+            }
+        });
+
+        Ok(addr)
+    })
+    .unwrap();
+
+    assert_eq!(*completed.lock().unwrap(), *expected.lock().unwrap());
+}
+
+#[test]
+fn disabled_pacing_test() {
+    use provider::congestion_controller::{pacing, Bbr};
+
+    test(Model::default(), |handle| {
+        let addr = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(events())?
+                .with_congestion_controller(pacing::Paced::new(Bbr::default(), pacing::Disabled))?
+                .start()?)
+        })?;
+        client(handle, addr)?;
+        Ok(addr)
+    })
+    .unwrap();
+}
+
+#[test]
+fn bandwidth_estimate_test() {
+    use provider::{
+        congestion_controller::Bbr,
+        event::{events::BandwidthEstimateUpdated, ConnectionInfo, ConnectionMeta, Subscriber},
+    };
+    use std::sync::{Arc, Mutex};
+
+    /// The bandwidth in bytes per second and the minimum round trip time
+    type Estimate = (Option<u64>, Duration);
+
+    #[derive(Clone, Default)]
+    struct Estimates(Arc<Mutex<Vec<Estimate>>>);
+
+    impl Subscriber for Estimates {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+
+        fn on_bandwidth_estimate_updated(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            event: &BandwidthEstimateUpdated,
+        ) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((event.bytes_per_second, event.min_rtt));
+        }
+    }
+
+    let estimates = Estimates::default();
+
+    test(Model::default(), |handle| {
+        let addr = server(handle)?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(estimates.clone())?
+            .with_congestion_controller(Bbr::default())?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            stream.send(Bytes::from(vec![42; 100_000])).await.unwrap();
+            stream.finish().unwrap();
+            while stream.receive().await.unwrap().is_some() {}
+        });
+
+        Ok(addr)
+    })
+    .unwrap();
+
+    let estimates = estimates.0.lock().unwrap();
+    assert!(!estimates.is_empty());
+    // the estimates are only published when they change
+    assert!(estimates.windows(2).all(|pair| pair[0] != pair[1]));
+    let (bytes_per_second, min_rtt) = *estimates.last().unwrap();
+    assert!(bytes_per_second.unwrap() > 0);
+    assert!(min_rtt > Duration::ZERO);
+}
+
+#[test]
+fn slo_threshold_test() {
+    use provider::{
+        event::{
+            events::{SloMetric, SloUpdated},
+            ConnectionInfo, ConnectionMeta, Subscriber,
+        },
+        limits::Limits,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Breaches {
+        rtt: Arc<Mutex<Vec<bool>>>,
+        loss: Arc<Mutex<Vec<bool>>>,
+    }
+
+    impl Subscriber for Breaches {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+
+        fn on_slo_updated(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            event: &SloUpdated,
+        ) {
+            match event.metric {
+                SloMetric::RoundTripTime { smoothed_rtt, .. } => {
+                    assert_eq!(event.breached, smoothed_rtt > Duration::from_millis(300));
+                    self.rtt.lock().unwrap().push(event.breached);
+                }
+                SloMetric::LossRate {
+                    lost_packets,
+                    total_packets,
+                    ..
+                } => {
+                    assert!(lost_packets <= total_packets);
+                    self.loss.lock().unwrap().push(event.breached);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let breaches = Breaches::default();
+
+    let model = Model::default();
+    model.set_delay(Duration::from_millis(200));
+    model.set_drop_rate(0.1);
+
+    test(model, |handle| {
+        let addr = server(handle)?;
+
+        let limits = Limits::default()
+            .with_rtt_threshold(Duration::from_millis(300), Duration::from_secs(1))?
+            .with_loss_threshold(0.02, Duration::from_millis(500))?;
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(breaches.clone())?
+            .with_limits(limits)?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            stream.send(Bytes::from(vec![42; 500_000])).await.unwrap();
+            stream.finish().unwrap();
+            while stream.receive().await.unwrap().is_some() {}
+        });
+
+        Ok(addr)
+    })
+    .unwrap();
+
+    // breaches and clears alternate, starting with a breach
+    for events in [&breaches.rtt, &breaches.loss] {
+        let events = events.lock().unwrap();
+        assert!(!events.is_empty());
+        for (index, breached) in events.iter().enumerate() {
+            assert_eq!(*breached, index % 2 == 0);
+        }
+    }
+}
+
+/// Ensures the multicaster delivers each payload to all of the subscribers
+#[test]
+fn multicast_test() {
+    use crate::{multicast::Multicaster, provider::datagram::default};
+
+    const SUBSCRIBERS: usize = 3;
+    const PAYLOADS: usize = 10;
+
+    fn datagram_endpoint() -> default::Endpoint {
+        default::Endpoint::builder()
+            .with_send_capacity(PAYLOADS)
+            .unwrap()
+            .with_recv_capacity(PAYLOADS)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    let model = Model::default();
+    test(model, |handle| {
+        let mut server = Server::builder()
+            .with_io(handle.builder().build().unwrap())?
+            .with_tls(SERVER_CERTS)?
+            .with_event(events())?
+            .with_datagram(datagram_endpoint())?
+            .start()?;
+        let server_addr = server.local_addr()?;
+
+        spawn(async move {
+            let mut multicaster = Multicaster::new();
+            let mut connections = vec![];
+
+            for _ in 0..SUBSCRIBERS {
+                let mut connection = server.accept().await.unwrap();
+                let stream = connection.open_send_stream().await.unwrap();
+                multicaster.insert_stream(connection.id(), stream);
+                multicaster.insert_connection(connection.id(), connection.handle());
+                connections.push(connection);
+            }
+
+            assert_eq!(multicaster.stream_count(), SUBSCRIBERS);
+            assert_eq!(multicaster.connection_count(), SUBSCRIBERS);
+
+            for index in 0..PAYLOADS {
+                let payload = Bytes::from(vec![index as u8; 1000]);
+                assert!(multicaster.send_datagram(payload.slice(..100)).is_empty());
+                assert!(multicaster.send(payload).await.is_empty());
+            }
+
+            assert!(multicaster.close().await.is_empty());
+            assert_eq!(multicaster.stream_count(), 0);
+
+            // keep the connections open until the clients received everything
+            delay(Duration::from_secs(1)).await;
+            drop(connections);
+        });
+
+        for _ in 0..SUBSCRIBERS {
+            let client = Client::builder()
+                .with_io(handle.builder().build().unwrap())?
+                .with_tls(certificates::CERT_PEM)?
+                .with_event(events())?
+                .with_datagram(datagram_endpoint())?
+                .start()?;
+
+            primary::spawn(async move {
+                let connect = Connect::new(server_addr).with_server_name("localhost");
+                let mut connection = client.connect(connect).await.unwrap();
+                let mut stream = connection.accept_receive_stream().await.unwrap().unwrap();
+
+                let mut received = vec![];
+                while let Some(chunk) = stream.receive().await.unwrap() {
+                    received.extend_from_slice(&chunk);
+                }
+                let expected: Vec<u8> = (0..PAYLOADS)
+                    .flat_map(|index| vec![index as u8; 1000])
+                    .collect();
+                assert_eq!(received, expected);
+
+                for index in 0..PAYLOADS {
+                    let datagram = futures::future::poll_fn(|cx| {
+                        connection
+                            .datagram_mut(|receiver: &mut default::Receiver| {
+                                receiver.poll_recv_datagram(cx)
+                            })
+                            .unwrap()
+                    })
+                    .await
+                    .unwrap();
+                    assert_eq!(datagram, Bytes::from(vec![index as u8; 100]));
+                }
+            });
+        }
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Ensures the connections take turns to transmit when round robin fairness is enabled
+#[test]
+fn transmit_fairness_test() {
+    use provider::{
+        endpoint_limits::{self, TransmitFairness},
+        event::{events, ConnectionInfo, ConnectionMeta, Subscriber},
+    };
+    use std::sync::{Arc, Mutex};
+
+    /// Records the stats of each scheduled transmission
+    #[derive(Clone, Default)]
+    struct Scheduled(Arc<Mutex<Vec<(usize, usize, usize)>>>);
+
+    impl Subscriber for Scheduled {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+
+        fn on_endpoint_transmit_scheduled(
+            &mut self,
+            _meta: &events::EndpointMeta,
+            event: &events::EndpointTransmitScheduled,
+        ) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((event.rounds, event.turns, event.quantum_exhausted));
+        }
+    }
+
+    let scheduled = Scheduled::default();
+
+    test(Model::default(), |handle| {
+        let addr = server_with(handle, |io| {
+            // each connection writes a single datagram in its turn
+            let limits = endpoint_limits::Default::builder()
+                .with_transmit_fairness(TransmitFairness::round_robin(1))?
+                .build()?;
+
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(scheduled.clone())?
+                .with_endpoint_limits(limits)?
+                .start()?)
+        })?;
+
+        // the clients check the data which is echoed back
+        client(handle, addr)?;
+        client(handle, addr)?;
+        Ok(addr)
+    })
+    .unwrap();
+
+    let scheduled = scheduled.0.lock().unwrap();
+    assert!(!scheduled.is_empty());
+    for (_rounds, turns, quantum_exhausted) in scheduled.iter().copied() {
+        assert!(quantum_exhausted > 0);
+        assert!(turns >= quantum_exhausted);
+    }
+    // the connections took several rounds to fill the queue at least once
+    assert!(scheduled.iter().any(|(rounds, _, _)| *rounds > 1));
+}
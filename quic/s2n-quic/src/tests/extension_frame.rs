@@ -0,0 +1,163 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for extension frames which are negotiated with a custom transport parameter
+
+use super::*;
+use provider::extension_frame::{self, ConnectionInfo, FrameType, Writer};
+use s2n_quic_core::{transport, varint::VarInt};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+const TRANSPORT_PARAMETER: VarInt = VarInt::from_u32(0xff0a_0001);
+const FRAME_TYPE: VarInt = VarInt::from_u16(0x3a11);
+const FRAMES: u8 = 200;
+const FRAME_LEN: usize = 100;
+
+/// Sends a sequence of numbered frames on each connection and records the received frames
+#[derive(Clone, Debug, Default)]
+struct Extension {
+    negotiated: Arc<AtomicBool>,
+    received: Arc<Mutex<Vec<u8>>>,
+}
+
+impl extension_frame::Endpoint for Extension {
+    type Handler = Handler;
+
+    fn new_handler(&mut self, _info: &ConnectionInfo) -> Option<Handler> {
+        Some(Handler {
+            frame_types: [FrameType::new(FRAME_TYPE).unwrap()],
+            next: 0,
+            extension: self.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Handler {
+    frame_types: [FrameType; 1],
+    next: u8,
+    extension: Extension,
+}
+
+impl extension_frame::Handler for Handler {
+    fn transport_parameter(&self) -> (VarInt, &[u8]) {
+        (TRANSPORT_PARAMETER, b"v1")
+    }
+
+    fn frame_types(&self) -> &[FrameType] {
+        &self.frame_types
+    }
+
+    fn on_negotiated(&mut self, peer_value: &[u8]) -> Result<(), transport::Error> {
+        assert_eq!(peer_value, b"v1");
+        self.extension.negotiated.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn on_frame(&mut self, frame_type: VarInt, payload: &[u8]) -> Result<(), transport::Error> {
+        assert_eq!(frame_type, FRAME_TYPE);
+        assert_eq!(payload.len(), FRAME_LEN);
+        self.extension.received.lock().unwrap().push(payload[0]);
+        Ok(())
+    }
+
+    fn has_transmission_interest(&self) -> bool {
+        self.next < FRAMES
+    }
+
+    fn on_transmit<W: Writer>(&mut self, writer: &mut W) {
+        while self.has_transmission_interest() && writer.remaining_capacity() > 0 {
+            if writer
+                .write_frame(FRAME_TYPE, &[self.next; FRAME_LEN])
+                .is_err()
+            {
+                break;
+            }
+            self.next += 1;
+        }
+    }
+}
+
+fn run(model: Model, server_extension: Option<Extension>, client_extension: Extension) {
+    test(model, |handle| {
+        let server = Server::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(SERVER_CERTS)?
+            .with_event(events())?;
+        let mut server = match server_extension {
+            Some(extension) => server.with_extension_frame(extension)?.start()?,
+            None => server.start()?,
+        };
+        let addr = server.local_addr()?;
+
+        spawn(async move {
+            let mut connection = server.accept().await.unwrap();
+            // keep the connection open until the client closes it
+            while let Ok(Some(_stream)) = connection.accept_receive_stream().await {}
+        });
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(events())?
+            .with_extension_frame(client_extension)?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(addr).with_server_name("localhost");
+            let _connection = client.connect(connect).await.unwrap();
+            delay(Duration::from_secs(5)).await;
+        });
+
+        Ok(addr)
+    })
+    .unwrap();
+}
+
+fn assert_received_all(extension: &Extension) {
+    assert!(extension.negotiated.load(Ordering::Relaxed));
+
+    // frames which were spuriously declared lost may be received twice
+    let mut received = extension.received.lock().unwrap().clone();
+    received.sort_unstable();
+    received.dedup();
+    assert_eq!(received, (0..FRAMES).collect::<Vec<_>>());
+}
+
+#[test]
+fn exchange_test() {
+    let server = Extension::default();
+    let client = Extension::default();
+
+    run(Model::default(), Some(server.clone()), client.clone());
+
+    assert_received_all(&server);
+    assert_received_all(&client);
+}
+
+#[test]
+fn retransmission_test() {
+    let model = Model::default();
+    model.set_drop_rate(0.1);
+
+    let server = Extension::default();
+    let client = Extension::default();
+
+    run(model, Some(server.clone()), client.clone());
+
+    assert_received_all(&server);
+    assert_received_all(&client);
+}
+
+#[test]
+fn not_negotiated_test() {
+    let client = Extension::default();
+
+    run(Model::default(), None, client.clone());
+
+    assert!(!client.negotiated.load(Ordering::Relaxed));
+    assert!(client.received.lock().unwrap().is_empty());
+}
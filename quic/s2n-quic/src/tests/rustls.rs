@@ -0,0 +1,149 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for endpoints using the rustls provider, which doesn't depend on any C code
+
+use super::*;
+use crate::provider::{
+    io::testing::{Handle, Result},
+    tls::rustls,
+};
+use std::net::SocketAddr;
+
+fn rustls_server(handle: &Handle, tls: rustls::Server) -> Result<SocketAddr> {
+    let mut server = Server::builder()
+        .with_io(handle.builder().build().unwrap())?
+        .with_tls(tls)?
+        .with_event(events())?
+        .start()?;
+    let server_addr = server.local_addr()?;
+
+    spawn(async move {
+        while let Some(mut connection) = server.accept().await {
+            spawn(async move {
+                while let Ok(Some(mut stream)) = connection.accept_bidirectional_stream().await {
+                    spawn(async move {
+                        while let Ok(Some(chunk)) = stream.receive().await {
+                            let _ = stream.send(chunk).await;
+                        }
+                    });
+                }
+            });
+        }
+    });
+
+    Ok(server_addr)
+}
+
+fn rustls_client(
+    handle: &Handle,
+    server_addr: SocketAddr,
+    tls: rustls::Client,
+    on_connect: impl 'static + Send + FnOnce(&crate::Connection),
+) -> Result {
+    let client = Client::builder()
+        .with_io(handle.builder().build().unwrap())?
+        .with_tls(tls)?
+        .with_event(events())?
+        .start()?;
+
+    primary::spawn(async move {
+        let connect = Connect::new(server_addr).with_server_name("localhost");
+        let mut connection = client.connect(connect).await.unwrap();
+
+        on_connect(&connection);
+
+        let mut stream = connection.open_bidirectional_stream().await.unwrap();
+        stream.send(Bytes::from_static(b"hello")).await.unwrap();
+        stream.finish().unwrap();
+
+        let mut response = vec![];
+        stream.read_to_end(&mut response).await.unwrap();
+        assert_eq!(response, b"hello");
+    });
+
+    Ok(())
+}
+
+#[test]
+fn rustls_client_server_test() {
+    test(Model::default(), |handle| {
+        let server = rustls::Server::builder()
+            .with_certificate(certificates::CERT_PEM, certificates::KEY_PEM)?
+            .build()?;
+        let addr = rustls_server(handle, server)?;
+
+        let client = rustls::Client::builder()
+            .with_certificate(certificates::CERT_PEM)?
+            .build()?;
+        rustls_client(handle, addr, client, |connection| {
+            // the client exposes the certificate presented by the server
+            assert_eq!(connection.peer_certificates().unwrap().len(), 1);
+        })?;
+
+        Ok(addr)
+    })
+    .unwrap();
+}
+
+#[test]
+fn rustls_client_authentication_test() {
+    test(Model::default(), |handle| {
+        let server = rustls::Server::builder()
+            .with_certificate(certificates::CERT_PEM, certificates::KEY_PEM)?
+            .with_trusted_certificate(certificates::CERT_PEM)?
+            .with_client_authentication()?
+            .build()?;
+
+        let mut server = Server::builder()
+            .with_io(handle.builder().build().unwrap())?
+            .with_tls(server)?
+            .with_event(events())?
+            .start()?;
+        let addr = server.local_addr()?;
+
+        primary::spawn(async move {
+            let connection = server.accept().await.unwrap();
+            // the server exposes the certificate presented by the client
+            assert_eq!(connection.peer_certificates().unwrap().len(), 1);
+        });
+
+        let client = rustls::Client::builder()
+            .with_certificate(certificates::CERT_PEM)?
+            .with_client_identity(certificates::CERT_PEM, certificates::KEY_PEM)?
+            .build()?;
+        let client = Client::builder()
+            .with_io(handle.builder().build().unwrap())?
+            .with_tls(client)?
+            .with_event(events())?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(addr).with_server_name("localhost");
+            let connection = client.connect(connect).await.unwrap();
+            assert_eq!(connection.peer_certificates().unwrap().len(), 1);
+        });
+
+        Ok(addr)
+    })
+    .unwrap();
+}
+
+/// Without the default TLS features, the rustls provider is used by default
+#[test]
+#[cfg(not(any(feature = "provider-tls-default", feature = "provider-tls-s2n")))]
+fn rustls_default_provider_test() {
+    use core::any::TypeId;
+
+    assert_eq!(
+        TypeId::of::<provider::tls::default::Server>(),
+        TypeId::of::<rustls::Server>()
+    );
+    assert_eq!(
+        TypeId::of::<provider::tls::default::Client>(),
+        TypeId::of::<rustls::Client>()
+    );
+
+    // the certificate providers use the default TLS provider
+    test(Model::default(), client_server).unwrap();
+}